@@ -2,8 +2,14 @@ use chrono::{DateTime, Utc};
 use gloo_net::http::Request;
 use serde::{Deserialize, Serialize};
 use shared_types::{
-    AppDefinition, ConductorExecuteRequest, ConductorExecuteResponse, ConductorOutputMode,
-    ConductorRunState, ConductorRunStatusResponse, DesktopState, ViewerRevision, WindowState,
+    ApiError, AppDefinition, BuildVersion, ConductorExecuteRequest, ConductorExecuteResponse,
+    ConductorOutputMode, ConductorRefineRequest, ConductorRefineResponse, ConductorRunState,
+    ConductorRunStatusResponse, ContextTracesResponse, DesktopState, DesktopTemplate, ErrorCode,
+    EventPollResponse, EventSubscriptionRequest, EventSubscriptionResponse,
+    LearnedPreferencesResponse, ObjectiveContract, ResearchRerunRequest, ResearchRerunResponse,
+    ResearchSendToWriterRequest, ResearchSendToWriterResponse, ResearchTaskDetail,
+    ResearchTaskSummary, SearchHitKind, SearchResponse, ServerTimeResponse, ViewerDescriptor,
+    ViewerRevision, WindowState, WriterLockStrictness,
 };
 use std::sync::OnceLock;
 
@@ -42,6 +48,17 @@ async fn describe_http_error(response: gloo_net::http::Response) -> String {
     describe_http_error_from_body(status, &body)
 }
 
+/// The typed `{ "error": ApiError }` body of a failed request, if it has one.
+pub fn api_error_from_body(body: &str) -> Option<ApiError> {
+    #[derive(Deserialize)]
+    struct ApiErrorBody {
+        error: ApiError,
+    }
+    serde_json::from_str::<ApiErrorBody>(body)
+        .ok()
+        .map(|body| body.error)
+}
+
 fn describe_http_error_from_body(status: u16, body: &str) -> String {
     if body.trim().is_empty() {
         return format!("HTTP error: {status}");
@@ -79,7 +96,8 @@ fn response_looks_like_html(body: &str) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use super::describe_http_error_from_body;
+    use super::{api_error_from_body, describe_http_error_from_body};
+    use shared_types::ErrorCode;
 
     #[test]
     fn describe_http_error_uses_nested_error_message_and_code() {
@@ -91,6 +109,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn api_error_from_body_parses_typed_code() {
+        let body = r#"{"error":{"code":"NOT_FOUND","message":"run not found: r1"}}"#;
+        let error = api_error_from_body(body).expect("typed error");
+        assert_eq!(error.code, ErrorCode::NotFound);
+        assert_eq!(error.message, "run not found: r1");
+        assert!(api_error_from_body(r#"{"error":"plain"}"#).is_none());
+    }
+
     #[test]
     fn describe_http_error_falls_back_to_top_level_message() {
         let body = r#"{"message":"Something failed"}"#;
@@ -136,6 +163,85 @@ pub async fn fetch_latest_log_seq() -> Result<i64, String> {
     Ok(data.latest_seq.max(0))
 }
 
+pub async fn fetch_server_time() -> Result<ServerTimeResponse, String> {
+    let url = format!("{}/api/time", api_base());
+    let response = Request::get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))?;
+    if !response.ok() {
+        return Err(describe_http_error(response).await);
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse JSON: {e}"))
+}
+
+pub async fn fetch_server_version() -> Result<BuildVersion, String> {
+    let url = format!("{}/api/version", api_base());
+    let response = Request::get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))?;
+    if !response.ok() {
+        return Err(describe_http_error(response).await);
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse JSON: {e}"))
+}
+
+/// Start a long-poll session, or add a desktop to one when `request.cursor`
+/// names it.
+pub async fn subscribe_desktop_events(
+    request: &EventSubscriptionRequest,
+) -> Result<EventSubscriptionResponse, String> {
+    let url = format!("{}/api/events/subscriptions", api_base());
+    let response = Request::post(&url)
+        .json(request)
+        .map_err(|e| format!("Failed to serialize request: {e}"))?
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))?;
+    if !response.ok() {
+        return Err(describe_http_error(response).await);
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse JSON: {e}"))
+}
+
+/// Wait up to `timeout_ms` for desktop messages after `cursor`. `Ok(None)`
+/// means the session expired or the cursor can no longer be replayed, so
+/// the caller must subscribe again.
+pub async fn poll_desktop_events(
+    cursor: &str,
+    timeout_ms: u64,
+) -> Result<Option<EventPollResponse>, String> {
+    let url = format!(
+        "{}/api/events/poll?cursor={cursor}&timeout_ms={timeout_ms}",
+        api_base()
+    );
+    let response = Request::get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))?;
+    if matches!(response.status(), 404 | 409) {
+        return Ok(None);
+    }
+    if !response.ok() {
+        return Err(describe_http_error(response).await);
+    }
+    response
+        .json()
+        .await
+        .map(Some)
+        .map_err(|e| format!("Failed to parse JSON: {e}"))
+}
+
 pub async fn fetch_logs_events(
     since_seq: i64,
     limit: i64,
@@ -202,7 +308,6 @@ pub struct OpenWindowRequest {
 pub struct OpenWindowResponse {
     pub success: bool,
     pub window: Option<WindowState>,
-    pub error: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -257,6 +362,62 @@ pub async fn fetch_desktop_state(desktop_id: &str) -> Result<DesktopState, Strin
     Ok(data.desktop)
 }
 
+/// Built-in and saved desktop templates.
+pub async fn fetch_desktop_templates() -> Result<Vec<DesktopTemplate>, String> {
+    let url = format!("{}/desktop-templates", api_base());
+    let response = Request::get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))?;
+    if !response.ok() {
+        return Err(describe_http_error(response).await);
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Response {
+        templates: Vec<DesktopTemplate>,
+    }
+
+    let data: Response = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse JSON: {e}"))?;
+    Ok(data.templates)
+}
+
+/// Create a desktop, registering `apps` and applying `template_id` if given.
+/// Safe to retry; the server opens a template's windows once per desktop.
+pub async fn create_desktop(
+    desktop_id: &str,
+    template_id: Option<&str>,
+    apps: &[AppDefinition],
+) -> Result<DesktopState, String> {
+    let url = format!("{}/desktop/{}", api_base(), desktop_id);
+    let body = serde_json::json!({
+        "template_id": template_id,
+        "apps": apps,
+    });
+
+    let response = Request::post(&url)
+        .json(&body)
+        .map_err(|e| format!("Failed to serialize request: {e}"))?
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))?;
+    if !response.ok() {
+        return Err(describe_http_error(response).await);
+    }
+
+    let data: GetDesktopStateResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse JSON: {e}"))?;
+    if !data.success {
+        return Err("API returned success=false".to_string());
+    }
+    Ok(data.desktop)
+}
+
 pub async fn fetch_windows(desktop_id: &str) -> Result<Vec<WindowState>, String> {
     let url = format!("{}/desktop/{}/windows", api_base(), desktop_id);
 
@@ -266,7 +427,7 @@ pub async fn fetch_windows(desktop_id: &str) -> Result<Vec<WindowState>, String>
         .map_err(|e| format!("Request failed: {e}"))?;
 
     if !response.ok() {
-        return Err(format!("HTTP error: {}", response.status()));
+        return Err(describe_http_error(response).await);
     }
 
     let data: GetWindowsResponse = response
@@ -303,7 +464,7 @@ pub async fn open_window(
         .map_err(|e| format!("Request failed: {e}"))?;
 
     if !response.ok() {
-        return Err(format!("HTTP error: {}", response.status()));
+        return Err(describe_http_error(response).await);
     }
 
     let data: OpenWindowResponse = response
@@ -312,7 +473,7 @@ pub async fn open_window(
         .map_err(|e| format!("Failed to parse JSON: {e}"))?;
 
     if !data.success {
-        return Err(data.error.unwrap_or_else(|| "Unknown error".to_string()));
+        return Err("API returned success=false".to_string());
     }
 
     data.window.ok_or_else(|| "Window not returned".to_string())
@@ -327,7 +488,7 @@ pub async fn fetch_user_theme_preference(user_id: &str) -> Result<String, String
         .map_err(|e| format!("Request failed: {e}"))?;
 
     if !response.ok() {
-        return Err(format!("HTTP error: {}", response.status()));
+        return Err(describe_http_error(response).await);
     }
 
     let data: UserPreferencesResponse = response
@@ -354,7 +515,7 @@ pub async fn update_user_theme_preference(user_id: &str, theme: &str) -> Result<
         .map_err(|e| format!("Request failed: {e}"))?;
 
     if !response.ok() {
-        return Err(format!("HTTP error: {}", response.status()));
+        return Err(describe_http_error(response).await);
     }
 
     let data: UserPreferencesResponse = response
@@ -369,6 +530,60 @@ pub async fn update_user_theme_preference(user_id: &str, theme: &str) -> Result<
     Ok(data.theme)
 }
 
+// ── Learned preferences ─────────────────────────────────────────────────────
+
+pub async fn fetch_learned_preferences() -> Result<LearnedPreferencesResponse, String> {
+    let url = format!("{}/api/preferences", api_base());
+    let response = Request::get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))?;
+    if !response.ok() {
+        return Err(describe_http_error(response).await);
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse JSON: {e}"))
+}
+
+pub async fn update_preference_learning(
+    learning_enabled: bool,
+) -> Result<LearnedPreferencesResponse, String> {
+    let url = format!("{}/api/preferences", api_base());
+    let request = shared_types::UpdatePreferenceLearningRequest { learning_enabled };
+    let response = Request::patch(&url)
+        .json(&request)
+        .map_err(|e| format!("Failed to serialize request: {e}"))?
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))?;
+    if !response.ok() {
+        return Err(describe_http_error(response).await);
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse JSON: {e}"))
+}
+
+pub async fn delete_learned_preference(
+    preference_id: &str,
+) -> Result<LearnedPreferencesResponse, String> {
+    let url = format!("{}/api/preferences/{}", api_base(), preference_id);
+    let response = Request::delete(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))?;
+    if !response.ok() {
+        return Err(describe_http_error(response).await);
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse JSON: {e}"))
+}
+
 // ── Model catalog + config ──────────────────────────────────────────────────
 
 #[derive(Debug, Clone, Deserialize)]
@@ -397,7 +612,7 @@ pub async fn fetch_model_catalog() -> Result<ModelCatalogResponse, String> {
         .await
         .map_err(|e| format!("Request failed: {e}"))?;
     if !response.ok() {
-        return Err(format!("HTTP error: {}", response.status()));
+        return Err(describe_http_error(response).await);
     }
     response
         .json()
@@ -412,7 +627,7 @@ pub async fn fetch_model_config(user_id: &str) -> Result<ModelConfigResponse, St
         .await
         .map_err(|e| format!("Request failed: {e}"))?;
     if !response.ok() {
-        return Err(format!("HTTP error: {}", response.status()));
+        return Err(describe_http_error(response).await);
     }
     response
         .json()
@@ -433,7 +648,7 @@ pub async fn update_model_config(
         .await
         .map_err(|e| format!("Request failed: {e}"))?;
     if !response.ok() {
-        return Err(format!("HTTP error: {}", response.status()));
+        return Err(describe_http_error(response).await);
     }
     response
         .json()
@@ -461,7 +676,6 @@ pub async fn close_window(desktop_id: &str, window_id: &str) -> Result<(), Strin
     #[derive(Debug, Deserialize)]
     struct Response {
         success: bool,
-        error: Option<String>,
     }
 
     let data: Response = response
@@ -470,7 +684,7 @@ pub async fn close_window(desktop_id: &str, window_id: &str) -> Result<(), Strin
         .map_err(|e| format!("Failed to parse JSON: {e}"))?;
 
     if !data.success {
-        return Err(data.error.unwrap_or_else(|| "Unknown error".to_string()));
+        return Err("API returned success=false".to_string());
     }
 
     Ok(())
@@ -496,7 +710,6 @@ pub async fn focus_window(desktop_id: &str, window_id: &str) -> Result<(), Strin
     #[derive(Debug, Deserialize)]
     struct Response {
         success: bool,
-        error: Option<String>,
     }
 
     let data: Response = response
@@ -505,7 +718,7 @@ pub async fn focus_window(desktop_id: &str, window_id: &str) -> Result<(), Strin
         .map_err(|e| format!("Failed to parse JSON: {e}"))?;
 
     if !data.success {
-        return Err(data.error.unwrap_or_else(|| "Unknown error".to_string()));
+        return Err("API returned success=false".to_string());
     }
 
     Ok(())
@@ -525,13 +738,12 @@ pub async fn minimize_window(desktop_id: &str, window_id: &str) -> Result<(), St
         .map_err(|e| format!("Request failed: {e}"))?;
 
     if !response.ok() {
-        return Err(format!("HTTP error: {}", response.status()));
+        return Err(describe_http_error(response).await);
     }
 
     #[derive(Debug, Deserialize)]
     struct Response {
         success: bool,
-        error: Option<String>,
     }
 
     let data: Response = response
@@ -540,7 +752,7 @@ pub async fn minimize_window(desktop_id: &str, window_id: &str) -> Result<(), St
         .map_err(|e| format!("Failed to parse JSON: {e}"))?;
 
     if !data.success {
-        return Err(data.error.unwrap_or_else(|| "Unknown error".to_string()));
+        return Err("API returned success=false".to_string());
     }
 
     Ok(())
@@ -580,13 +792,12 @@ pub async fn maximize_window(
     };
 
     if !response.ok() {
-        return Err(format!("HTTP error: {}", response.status()));
+        return Err(describe_http_error(response).await);
     }
 
     #[derive(Debug, Deserialize)]
     struct Response {
         success: bool,
-        error: Option<String>,
     }
 
     let data: Response = response
@@ -595,7 +806,7 @@ pub async fn maximize_window(
         .map_err(|e| format!("Failed to parse JSON: {e}"))?;
 
     if !data.success {
-        return Err(data.error.unwrap_or_else(|| "Unknown error".to_string()));
+        return Err("API returned success=false".to_string());
     }
 
     Ok(())
@@ -615,13 +826,12 @@ pub async fn restore_window(desktop_id: &str, window_id: &str) -> Result<(), Str
         .map_err(|e| format!("Request failed: {e}"))?;
 
     if !response.ok() {
-        return Err(format!("HTTP error: {}", response.status()));
+        return Err(describe_http_error(response).await);
     }
 
     #[derive(Debug, Deserialize)]
     struct Response {
         success: bool,
-        error: Option<String>,
     }
 
     let data: Response = response
@@ -630,7 +840,7 @@ pub async fn restore_window(desktop_id: &str, window_id: &str) -> Result<(), Str
         .map_err(|e| format!("Failed to parse JSON: {e}"))?;
 
     if !data.success {
-        return Err(data.error.unwrap_or_else(|| "Unknown error".to_string()));
+        return Err("API returned success=false".to_string());
     }
 
     Ok(())
@@ -660,13 +870,12 @@ pub async fn move_window(desktop_id: &str, window_id: &str, x: i32, y: i32) -> R
         .map_err(|e| format!("Request failed: {e}"))?;
 
     if !response.ok() {
-        return Err(format!("HTTP error: {}", response.status()));
+        return Err(describe_http_error(response).await);
     }
 
     #[derive(Debug, Deserialize)]
     struct Response {
         success: bool,
-        error: Option<String>,
     }
 
     let data: Response = response
@@ -675,7 +884,7 @@ pub async fn move_window(desktop_id: &str, window_id: &str, x: i32, y: i32) -> R
         .map_err(|e| format!("Failed to parse JSON: {e}"))?;
 
     if !data.success {
-        return Err(data.error.unwrap_or_else(|| "Unknown error".to_string()));
+        return Err("API returned success=false".to_string());
     }
 
     Ok(())
@@ -710,13 +919,12 @@ pub async fn resize_window(
         .map_err(|e| format!("Request failed: {e}"))?;
 
     if !response.ok() {
-        return Err(format!("HTTP error: {}", response.status()));
+        return Err(describe_http_error(response).await);
     }
 
     #[derive(Debug, Deserialize)]
     struct Response {
         success: bool,
-        error: Option<String>,
     }
 
     let data: Response = response
@@ -725,7 +933,28 @@ pub async fn resize_window(
         .map_err(|e| format!("Failed to parse JSON: {e}"))?;
 
     if !data.success {
-        return Err(data.error.unwrap_or_else(|| "Unknown error".to_string()));
+        return Err("API returned success=false".to_string());
+    }
+
+    Ok(())
+}
+
+/// Report the area the server keeps window title bars inside.
+pub async fn set_desktop_bounds(
+    desktop_id: &str,
+    bounds: MaximizeWindowRequest,
+) -> Result<(), String> {
+    let url = format!("{}/desktop/{}/bounds", api_base(), desktop_id);
+
+    let response = Request::patch(&url)
+        .json(&bounds)
+        .map_err(|e| format!("Failed to serialize request: {e}"))?
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))?;
+
+    if !response.ok() {
+        return Err(describe_http_error(response).await);
     }
 
     Ok(())
@@ -740,7 +969,7 @@ pub async fn fetch_apps(desktop_id: &str) -> Result<Vec<AppDefinition>, String>
         .map_err(|e| format!("Request failed: {e}"))?;
 
     if !response.ok() {
-        return Err(format!("HTTP error: {}", response.status()));
+        return Err(describe_http_error(response).await);
     }
 
     let data: GetAppsResponse = response
@@ -766,13 +995,12 @@ pub async fn register_app(desktop_id: &str, app: &AppDefinition) -> Result<(), S
         .map_err(|e| format!("Request failed: {e}"))?;
 
     if !response.ok() {
-        return Err(format!("HTTP error: {}", response.status()));
+        return Err(describe_http_error(response).await);
     }
 
     #[derive(Debug, Deserialize)]
     struct Response {
         success: bool,
-        error: Option<String>,
     }
 
     let data: Response = response
@@ -781,7 +1009,7 @@ pub async fn register_app(desktop_id: &str, app: &AppDefinition) -> Result<(), S
         .map_err(|e| format!("Failed to parse JSON: {e}"))?;
 
     if !data.success {
-        return Err(data.error.unwrap_or_else(|| "Unknown error".to_string()));
+        return Err("API returned success=false".to_string());
     }
 
     Ok(())
@@ -796,6 +1024,8 @@ pub struct ViewerContentResponse {
     pub rendered_html: Option<String>,
     pub revision: ViewerRevision,
     pub readonly: bool,
+    #[serde(default)]
+    pub orientation: Option<u8>,
 }
 
 #[derive(Debug, Serialize)]
@@ -811,8 +1041,6 @@ pub struct PatchViewerContentRequest {
 pub struct PatchViewerContentResponse {
     pub success: bool,
     pub revision: Option<ViewerRevision>,
-    pub error: Option<String>,
-    pub latest: Option<PatchViewerContentLatest>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -837,7 +1065,7 @@ pub async fn fetch_viewer_content(uri: &str) -> Result<ViewerContentResponse, St
         .await
         .map_err(|e| format!("request failed: {e}"))?;
     if !response.ok() {
-        return Err(format!("HTTP error: {}", response.status()));
+        return Err(describe_http_error(response).await);
     }
     let data: ViewerContentResponse = response
         .json()
@@ -849,6 +1077,51 @@ pub async fn fetch_viewer_content(uri: &str) -> Result<ViewerContentResponse, St
     Ok(data)
 }
 
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SkippedViewerResource {
+    pub uri: String,
+    pub mime: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RunImageSetResponse {
+    pub success: bool,
+    pub run_id: String,
+    pub viewer: Option<ViewerDescriptor>,
+    #[serde(default)]
+    pub skipped: Vec<SkippedViewerResource>,
+}
+
+/// GET /viewer/image-set — every image artifact of a run as one viewer set.
+pub async fn fetch_run_image_set(run_id: &str) -> Result<RunImageSetResponse, String> {
+    let url = format!(
+        "{}/viewer/image-set?run_id={}",
+        api_base(),
+        encode_uri_component(run_id)
+    );
+    let response = Request::get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("request failed: {e}"))?;
+    if !response.ok() {
+        return Err(describe_http_error(response).await);
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| format!("failed to parse JSON: {e}"))
+}
+
+/// URL of the backend-generated thumbnail for an image resource.
+pub fn viewer_thumbnail_url(uri: &str) -> String {
+    format!(
+        "{}/viewer/thumbnail?uri={}",
+        api_base(),
+        encode_uri_component(uri)
+    )
+}
+
 pub async fn patch_viewer_content(
     uri: &str,
     base_rev: i64,
@@ -871,28 +1144,41 @@ pub async fn patch_viewer_content(
         .await
         .map_err(|e| PatchViewerContentError::Message(format!("request failed: {e}")))?;
 
-    let status = response.status();
-    let data: PatchViewerContentResponse = response
-        .json()
-        .await
-        .map_err(|e| PatchViewerContentError::Message(format!("failed to parse JSON: {e}")))?;
-
-    if status == 409 || data.error.as_deref() == Some("revision_conflict") {
-        if let Some(latest) = data.latest {
-            return Err(PatchViewerContentError::Conflict {
-                latest_content: latest.content,
-                latest_revision: latest.revision,
-            });
+    if !response.ok() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        if let Some(error) = api_error_from_body(&body) {
+            if error.code == ErrorCode::DocumentConflict {
+                let latest = error
+                    .details
+                    .and_then(|details| details.get("latest").cloned())
+                    .and_then(|latest| {
+                        serde_json::from_value::<PatchViewerContentLatest>(latest).ok()
+                    });
+                return Err(match latest {
+                    Some(latest) => PatchViewerContentError::Conflict {
+                        latest_content: latest.content,
+                        latest_revision: latest.revision,
+                    },
+                    None => PatchViewerContentError::Message(
+                        "DOCUMENT_CONFLICT without latest payload".to_string(),
+                    ),
+                });
+            }
         }
         return Err(PatchViewerContentError::Message(
-            "revision_conflict without latest payload".to_string(),
+            describe_http_error_from_body(status, &body),
         ));
     }
 
+    let data: PatchViewerContentResponse = response
+        .json()
+        .await
+        .map_err(|e| PatchViewerContentError::Message(format!("failed to parse JSON: {e}")))?;
+
     if !data.success {
         return Err(PatchViewerContentError::Message(
-            data.error
-                .unwrap_or_else(|| "unknown viewer save error".to_string()),
+            "unknown viewer save error".to_string(),
         ));
     }
 
@@ -1000,17 +1286,6 @@ pub mod files_api {
     }
 
     /// Error response
-    #[derive(Debug, Deserialize)]
-    struct ErrorResponse {
-        pub error: ErrorDetail,
-    }
-
-    #[derive(Debug, Deserialize)]
-    struct ErrorDetail {
-        pub code: String,
-        pub message: String,
-    }
-
     /// List directory contents
     pub async fn list_directory(path: &str) -> Result<ListDirectoryResponse, String> {
         let encoded_path = js_sys::encode_uri_component(path)
@@ -1026,8 +1301,8 @@ pub mod files_api {
         if !response.ok() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
-            if let Ok(err) = serde_json::from_str::<ErrorResponse>(&body) {
-                return Err(format!("{}: {}", err.error.code, err.error.message));
+            if let Some(err) = api_error_from_body(&body) {
+                return Err(format!("{}: {}", err.code, err.message));
             }
             return Err(format!("HTTP error: {status}"));
         }
@@ -1053,8 +1328,8 @@ pub mod files_api {
         if !response.ok() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
-            if let Ok(err) = serde_json::from_str::<ErrorResponse>(&body) {
-                return Err(format!("{}: {}", err.error.code, err.error.message));
+            if let Some(err) = api_error_from_body(&body) {
+                return Err(format!("{}: {}", err.code, err.message));
             }
             return Err(format!("HTTP error: {status}"));
         }
@@ -1087,8 +1362,8 @@ pub mod files_api {
         if !response.ok() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
-            if let Ok(err) = serde_json::from_str::<ErrorResponse>(&body) {
-                return Err(format!("{}: {}", err.error.code, err.error.message));
+            if let Some(err) = api_error_from_body(&body) {
+                return Err(format!("{}: {}", err.code, err.message));
             }
             return Err(format!("HTTP error: {status}"));
         }
@@ -1117,8 +1392,8 @@ pub mod files_api {
         if !response.ok() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
-            if let Ok(err) = serde_json::from_str::<ErrorResponse>(&body) {
-                return Err(format!("{}: {}", err.error.code, err.error.message));
+            if let Some(err) = api_error_from_body(&body) {
+                return Err(format!("{}: {}", err.code, err.message));
             }
             return Err(format!("HTTP error: {status}"));
         }
@@ -1148,8 +1423,8 @@ pub mod files_api {
         if !response.ok() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
-            if let Ok(err) = serde_json::from_str::<ErrorResponse>(&body) {
-                return Err(format!("{}: {}", err.error.code, err.error.message));
+            if let Some(err) = api_error_from_body(&body) {
+                return Err(format!("{}: {}", err.code, err.message));
             }
             return Err(format!("HTTP error: {status}"));
         }
@@ -1178,8 +1453,8 @@ pub mod files_api {
         if !response.ok() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
-            if let Ok(err) = serde_json::from_str::<ErrorResponse>(&body) {
-                return Err(format!("{}: {}", err.error.code, err.error.message));
+            if let Some(err) = api_error_from_body(&body) {
+                return Err(format!("{}: {}", err.code, err.message));
             }
             return Err(format!("HTTP error: {status}"));
         }
@@ -1217,6 +1492,13 @@ pub struct SaveDocumentResponse {
     pub saved: bool,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct RevertPatchResponse {
+    pub run_id: String,
+    pub version: WriterVersion,
+    pub undo_of: String,
+}
+
 /// Preview response
 #[derive(Debug, Clone, Deserialize)]
 pub struct PreviewResponse {
@@ -1283,23 +1565,58 @@ pub struct DismissOverlayResponse {
     pub dismissed: bool,
 }
 
-/// Writer error detail
+/// Proposal overlay with the content accepting it would produce
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct WriterProposal {
+    pub overlay_id: String,
+    pub base_version_id: u64,
+    pub author: String,
+    pub status: String,
+    /// Based on an older version than the head; needs a rebase or re-proposal
+    pub stale: bool,
+    pub proposed_content: String,
+}
+
 #[derive(Debug, Clone, Deserialize)]
-pub struct WriterErrorDetail {
-    pub code: String,
-    pub message: String,
+pub struct ListProposalsResponse {
+    pub run_id: String,
+    pub review_mode: bool,
+    #[serde(default)]
+    pub lock_strictness: WriterLockStrictness,
+    pub head_version_id: u64,
+    pub proposals: Vec<WriterProposal>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AcceptProposalResponse {
+    pub run_id: String,
+    pub overlay_id: String,
+    pub version: WriterVersion,
+    pub rebased: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReproposeProposalResponse {
+    pub run_id: String,
+    pub proposal: WriterProposal,
 }
 
-/// Writer error response
 #[derive(Debug, Clone, Deserialize)]
-pub struct WriterErrorResponse {
-    pub error: WriterErrorDetail,
+pub struct ReviewModeResponse {
+    pub run_id: String,
+    pub review_mode: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LockStrictnessResponse {
+    pub run_id: String,
+    pub lock_strictness: WriterLockStrictness,
 }
 
 /// Conflict response from server
 #[derive(Debug, Clone, Deserialize)]
 pub struct ConflictResponse {
-    pub error: WriterErrorDetail,
+    pub error: ApiError,
     pub path: String,
     pub current_revision: u64,
     pub current_content: String,
@@ -1320,8 +1637,8 @@ pub async fn writer_open(path: &str) -> Result<OpenDocumentResponse, String> {
     if !response.ok() {
         let status = response.status();
         let body = response.text().await.unwrap_or_default();
-        if let Ok(err) = serde_json::from_str::<WriterErrorResponse>(&body) {
-            return Err(format!("{}: {}", err.error.code, err.error.message));
+        if let Some(err) = api_error_from_body(&body) {
+            return Err(format!("{}: {}", err.code, err.message));
         }
         return Err(format!("HTTP error: {status}"));
     }
@@ -1368,8 +1685,8 @@ pub async fn writer_save(
 
     if !response.ok() {
         let body = response.text().await.unwrap_or_default();
-        if let Ok(err) = serde_json::from_str::<WriterErrorResponse>(&body) {
-            return Err(format!("{}: {}", err.error.code, err.error.message));
+        if let Some(err) = api_error_from_body(&body) {
+            return Err(format!("{}: {}", err.code, err.message));
         }
         return Err(format!("HTTP error: {status}"));
     }
@@ -1401,8 +1718,8 @@ pub async fn writer_preview(
     if !response.ok() {
         let status = response.status();
         let body = response.text().await.unwrap_or_default();
-        if let Ok(err) = serde_json::from_str::<WriterErrorResponse>(&body) {
-            return Err(format!("{}: {}", err.error.code, err.error.message));
+        if let Some(err) = api_error_from_body(&body) {
+            return Err(format!("{}: {}", err.code, err.message));
         }
         return Err(format!("HTTP error: {status}"));
     }
@@ -1436,8 +1753,8 @@ pub async fn writer_prompt(
     if !response.ok() {
         let status = response.status();
         let body = response.text().await.unwrap_or_default();
-        if let Ok(err) = serde_json::from_str::<WriterErrorResponse>(&body) {
-            return Err(format!("{}: {}", err.error.code, err.error.message));
+        if let Some(err) = api_error_from_body(&body) {
+            return Err(format!("{}: {}", err.code, err.message));
         }
         return Err(format!("HTTP error: {status}"));
     }
@@ -1460,8 +1777,8 @@ pub async fn writer_versions(path: &str) -> Result<ListVersionsResponse, String>
     if !response.ok() {
         let status = response.status();
         let body = response.text().await.unwrap_or_default();
-        if let Ok(err) = serde_json::from_str::<WriterErrorResponse>(&body) {
-            return Err(format!("{}: {}", err.error.code, err.error.message));
+        if let Some(err) = api_error_from_body(&body) {
+            return Err(format!("{}: {}", err.code, err.message));
         }
         return Err(format!("HTTP error: {status}"));
     }
@@ -1488,8 +1805,8 @@ pub async fn writer_version(path: &str, version_id: u64) -> Result<GetVersionRes
     if !response.ok() {
         let status = response.status();
         let body = response.text().await.unwrap_or_default();
-        if let Ok(err) = serde_json::from_str::<WriterErrorResponse>(&body) {
-            return Err(format!("{}: {}", err.error.code, err.error.message));
+        if let Some(err) = api_error_from_body(&body) {
+            return Err(format!("{}: {}", err.code, err.message));
         }
         return Err(format!("HTTP error: {status}"));
     }
@@ -1519,8 +1836,8 @@ pub async fn writer_save_version(
     if !response.ok() {
         let status = response.status();
         let body = response.text().await.unwrap_or_default();
-        if let Ok(err) = serde_json::from_str::<WriterErrorResponse>(&body) {
-            return Err(format!("{}: {}", err.error.code, err.error.message));
+        if let Some(err) = api_error_from_body(&body) {
+            return Err(format!("{}: {}", err.code, err.message));
         }
         return Err(format!("HTTP error: {status}"));
     }
@@ -1530,6 +1847,39 @@ pub async fn writer_save_version(
         .map_err(|e| format!("Failed to parse JSON: {e}"))
 }
 
+/// Undo the most recent user edit of a run document.
+pub async fn writer_undo(path: &str) -> Result<RevertPatchResponse, String> {
+    writer_revert(path, "undo").await
+}
+
+/// Redo the most recently undone user edit of a run document.
+pub async fn writer_redo(path: &str) -> Result<RevertPatchResponse, String> {
+    writer_revert(path, "redo").await
+}
+
+async fn writer_revert(path: &str, action: &str) -> Result<RevertPatchResponse, String> {
+    let encoded = js_sys::encode_uri_component(path)
+        .as_string()
+        .unwrap_or_else(|| path.to_string());
+    let url = format!("{}/api/writer/documents/{}/{}", api_base(), encoded, action);
+    let response = Request::post(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))?;
+    if !response.ok() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        if let Some(err) = api_error_from_body(&body) {
+            return Err(format!("{}: {}", err.code, err.message));
+        }
+        return Err(format!("HTTP error: {status}"));
+    }
+    response
+        .json::<RevertPatchResponse>()
+        .await
+        .map_err(|e| format!("Failed to parse JSON: {e}"))
+}
+
 pub async fn writer_dismiss_overlay(
     path: &str,
     overlay_id: &str,
@@ -1548,8 +1898,8 @@ pub async fn writer_dismiss_overlay(
     if !response.ok() {
         let status = response.status();
         let body = response.text().await.unwrap_or_default();
-        if let Ok(err) = serde_json::from_str::<WriterErrorResponse>(&body) {
-            return Err(format!("{}: {}", err.error.code, err.error.message));
+        if let Some(err) = api_error_from_body(&body) {
+            return Err(format!("{}: {}", err.code, err.message));
         }
         return Err(format!("HTTP error: {status}"));
     }
@@ -1559,6 +1909,128 @@ pub async fn writer_dismiss_overlay(
         .map_err(|e| format!("Failed to parse JSON: {e}"))
 }
 
+fn writer_document_url(path: &str, suffix: &str) -> String {
+    let encoded = js_sys::encode_uri_component(path)
+        .as_string()
+        .unwrap_or_else(|| path.to_string());
+    format!("{}/api/writer/documents/{}/{}", api_base(), encoded, suffix)
+}
+
+async fn writer_error_message(response: gloo_net::http::Response) -> String {
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    if let Some(err) = api_error_from_body(&body) {
+        return format!("{}: {}", err.code, err.message);
+    }
+    format!("HTTP error: {status}")
+}
+
+/// List proposals awaiting review on a run document.
+pub async fn writer_list_proposals(path: &str) -> Result<ListProposalsResponse, String> {
+    let response = Request::get(&writer_document_url(path, "proposals"))
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))?;
+    if !response.ok() {
+        return Err(writer_error_message(response).await);
+    }
+    response
+        .json::<ListProposalsResponse>()
+        .await
+        .map_err(|e| format!("Failed to parse JSON: {e}"))
+}
+
+/// Accept a proposal; the server rebases it when its base is stale.
+pub async fn writer_accept_proposal(
+    path: &str,
+    overlay_id: &str,
+) -> Result<AcceptProposalResponse, String> {
+    let url = writer_document_url(path, &format!("proposals/{overlay_id}/accept"));
+    let response = Request::post(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))?;
+    if !response.ok() {
+        return Err(writer_error_message(response).await);
+    }
+    response
+        .json::<AcceptProposalResponse>()
+        .await
+        .map_err(|e| format!("Failed to parse JSON: {e}"))
+}
+
+/// Reject a proposal without applying it.
+pub async fn writer_reject_proposal(path: &str, overlay_id: &str) -> Result<(), String> {
+    let url = writer_document_url(path, &format!("proposals/{overlay_id}/reject"));
+    let response = Request::post(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))?;
+    if !response.ok() {
+        return Err(writer_error_message(response).await);
+    }
+    Ok(())
+}
+
+/// Re-propose a stale proposal against the current head version.
+pub async fn writer_repropose_proposal(
+    path: &str,
+    overlay_id: &str,
+) -> Result<ReproposeProposalResponse, String> {
+    let url = writer_document_url(path, &format!("proposals/{overlay_id}/repropose"));
+    let response = Request::post(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))?;
+    if !response.ok() {
+        return Err(writer_error_message(response).await);
+    }
+    response
+        .json::<ReproposeProposalResponse>()
+        .await
+        .map_err(|e| format!("Failed to parse JSON: {e}"))
+}
+
+/// Turn review mode on or off for a run document.
+pub async fn writer_set_review_mode(path: &str, enabled: bool) -> Result<bool, String> {
+    let response = Request::patch(&writer_document_url(path, "review-mode"))
+        .json(&serde_json::json!({ "enabled": enabled }))
+        .map_err(|e| format!("Failed to serialize request: {e}"))?
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))?;
+    if !response.ok() {
+        return Err(writer_error_message(response).await);
+    }
+    response
+        .json::<ReviewModeResponse>()
+        .await
+        .map(|body| body.review_mode)
+        .map_err(|e| format!("Failed to parse JSON: {e}"))
+}
+
+/// Choose whether edits to blocks an agent is editing are warned about or
+/// refused for a run document.
+pub async fn writer_set_lock_strictness(
+    path: &str,
+    strictness: WriterLockStrictness,
+) -> Result<WriterLockStrictness, String> {
+    let response = Request::patch(&writer_document_url(path, "lock-strictness"))
+        .json(&serde_json::json!({ "strictness": strictness }))
+        .map_err(|e| format!("Failed to serialize request: {e}"))?
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))?;
+    if !response.ok() {
+        return Err(writer_error_message(response).await);
+    }
+    response
+        .json::<LockStrictnessResponse>()
+        .await
+        .map(|body| body.lock_strictness)
+        .map_err(|e| format!("Failed to parse JSON: {e}"))
+}
+
 // ============================================================================
 // Conductor API Functions
 // ============================================================================
@@ -1611,12 +2083,65 @@ pub async fn conductor_get_run_state(run_id: &str) -> Result<ConductorRunState,
         .map_err(|e| format!("Failed to parse JSON: {e}"))
 }
 
+/// GET /api/context-traces?run_id= — prompt composition per LLM call of a run.
+pub async fn fetch_run_context_traces(run_id: &str) -> Result<ContextTracesResponse, String> {
+    let url = format!(
+        "{}/api/context-traces?run_id={}",
+        api_base(),
+        encode_uri_component(run_id)
+    );
+    let response = Request::get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))?;
+    if !response.ok() {
+        return Err(describe_http_error(response).await);
+    }
+    response
+        .json::<ContextTracesResponse>()
+        .await
+        .map_err(|e| format!("Failed to parse JSON: {e}"))
+}
+
+/// POST /api/conductor/refine
+/// Returns clarifying questions and a proposed contract for the objective
+pub async fn conductor_refine(
+    objective: &str,
+    desktop_id: &str,
+    desktop_context: Vec<String>,
+) -> Result<ConductorRefineResponse, String> {
+    let url = format!("{}/api/conductor/refine", api_base());
+
+    let request = ConductorRefineRequest {
+        objective: objective.to_string(),
+        desktop_id: desktop_id.to_string(),
+        desktop_context,
+    };
+
+    let response = Request::post(&url)
+        .json(&request)
+        .map_err(|e| format!("Failed to serialize request: {e}"))?
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))?;
+
+    if !response.ok() {
+        return Err(describe_http_error(response).await);
+    }
+
+    response
+        .json::<ConductorRefineResponse>()
+        .await
+        .map_err(|e| format!("Failed to parse JSON: {e}"))
+}
+
 /// POST /conductor/execute
 /// Returns a run_id that can be used to poll for run status
 pub async fn execute_conductor(
     objective: &str,
     desktop_id: &str,
     output_mode: ConductorOutputMode,
+    contract: Option<ObjectiveContract>,
 ) -> Result<ConductorExecuteResponse, String> {
     let url = format!("{}/conductor/execute", api_base());
 
@@ -1625,6 +2150,7 @@ pub async fn execute_conductor(
         desktop_id: desktop_id.to_string(),
         output_mode,
         hints: None,
+        contract,
     };
 
     let response = Request::post(&url)
@@ -1655,3 +2181,151 @@ pub async fn execute_conductor(
         format!("Failed to parse JSON: {e} (body preview: {preview})")
     })
 }
+
+// ============================================================================
+// Search API Functions
+// ============================================================================
+
+/// GET /api/search — full-text search over chat, documents and artifacts.
+/// An empty `kinds` slice searches every kind.
+pub async fn search_content(
+    query: &str,
+    kinds: &[SearchHitKind],
+    limit: u32,
+) -> Result<SearchResponse, String> {
+    let encoded = js_sys::encode_uri_component(query)
+        .as_string()
+        .unwrap_or_else(|| query.to_string());
+    let mut url = format!("{}/api/search?q={}&limit={}", api_base(), encoded, limit);
+    if !kinds.is_empty() {
+        let kinds = kinds
+            .iter()
+            .map(|kind| kind.as_str())
+            .collect::<Vec<_>>()
+            .join(",");
+        url.push_str(&format!("&kinds={kinds}"));
+    }
+    let response = Request::get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))?;
+    if !response.ok() {
+        return Err(describe_http_error(response).await);
+    }
+    response
+        .json::<SearchResponse>()
+        .await
+        .map_err(|e| format!("Failed to parse JSON: {e}"))
+}
+
+// ============================================================================
+// Research API Functions
+// ============================================================================
+
+fn encode_uri_component(value: &str) -> String {
+    js_sys::encode_uri_component(value)
+        .as_string()
+        .unwrap_or_else(|| value.to_string())
+}
+
+/// GET /api/research/tasks — a desktop's recent research tasks, newest first.
+pub async fn fetch_research_tasks(
+    desktop_id: &str,
+    limit: u32,
+) -> Result<Vec<ResearchTaskSummary>, String> {
+    let url = format!(
+        "{}/api/research/tasks?limit={}&desktop_id={}",
+        api_base(),
+        limit,
+        encode_uri_component(desktop_id)
+    );
+    let response = Request::get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))?;
+    if !response.ok() {
+        return Err(describe_http_error(response).await);
+    }
+    response
+        .json::<Vec<ResearchTaskSummary>>()
+        .await
+        .map_err(|e| format!("Failed to parse JSON: {e}"))
+}
+
+/// GET /api/research/tasks/{task_id} — result, citations and progress.
+pub async fn fetch_research_task(task_id: &str) -> Result<ResearchTaskDetail, String> {
+    let url = format!(
+        "{}/api/research/tasks/{}",
+        api_base(),
+        encode_uri_component(task_id)
+    );
+    let response = Request::get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))?;
+    if !response.ok() {
+        return Err(describe_http_error(response).await);
+    }
+    response
+        .json::<ResearchTaskDetail>()
+        .await
+        .map_err(|e| format!("Failed to parse JSON: {e}"))
+}
+
+/// POST /api/research/tasks/{task_id}/rerun — returns the new task id.
+pub async fn rerun_research_task(
+    desktop_id: &str,
+    task_id: &str,
+    objective: Option<String>,
+) -> Result<ResearchRerunResponse, String> {
+    let url = format!(
+        "{}/api/research/tasks/{}/rerun",
+        api_base(),
+        encode_uri_component(task_id)
+    );
+    let request = ResearchRerunRequest {
+        objective,
+        max_rounds: None,
+        desktop_id: Some(desktop_id.to_string()),
+    };
+    let response = Request::post(&url)
+        .json(&request)
+        .map_err(|e| format!("Failed to serialize request: {e}"))?
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))?;
+    if !response.ok() {
+        return Err(describe_http_error(response).await);
+    }
+    response
+        .json::<ResearchRerunResponse>()
+        .await
+        .map_err(|e| format!("Failed to parse JSON: {e}"))
+}
+
+/// POST /api/research/tasks/{task_id}/send-to-writer — append the result to a
+/// run document; `path` defaults to the task's own run document.
+pub async fn send_research_to_writer(
+    task_id: &str,
+    path: Option<String>,
+) -> Result<ResearchSendToWriterResponse, String> {
+    let url = format!(
+        "{}/api/research/tasks/{}/send-to-writer",
+        api_base(),
+        encode_uri_component(task_id)
+    );
+    let request = ResearchSendToWriterRequest { path };
+    let response = Request::post(&url)
+        .json(&request)
+        .map_err(|e| format!("Failed to serialize request: {e}"))?
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))?;
+    if !response.ok() {
+        return Err(describe_http_error(response).await);
+    }
+    response
+        .json::<ResearchSendToWriterResponse>()
+        .await
+        .map_err(|e| format!("Failed to parse JSON: {e}"))
+}
@@ -1,12 +1,16 @@
+pub mod error_notice;
 pub mod files;
 pub mod logs;
+pub mod research;
 pub mod settings;
 pub mod styles;
 pub mod trace;
 pub mod writer;
 
+pub use error_notice::ErrorNotice;
 pub use files::{load_files_path, FilesView};
 pub use logs::LogsView;
+pub use research::ResearchView;
 pub use settings::SettingsView;
 pub use trace::TraceView;
 pub use writer::WriterView;
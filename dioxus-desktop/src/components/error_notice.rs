@@ -0,0 +1,117 @@
+//! Error notice shared by app surfaces
+//!
+//! Failed requests carry the backend's catalog code inside their message
+//! (`HTTP error: 507 (QUOTA_EXCEEDED: ...)`). The notice leads with what the
+//! user can do about that code and keeps the raw message behind a toggle.
+
+use dioxus::prelude::*;
+use shared_types::ErrorCode;
+
+/// The first catalog code named in an error message, if any.
+pub fn error_code_in(message: &str) -> Option<ErrorCode> {
+    message
+        .split(|c: char| !(c.is_ascii_uppercase() || c == '_'))
+        .find_map(ErrorCode::parse)
+}
+
+/// What the user can do about a failure with this code.
+pub fn guidance_for(code: Option<ErrorCode>) -> &'static str {
+    let Some(code) = code else {
+        return "Something went wrong.";
+    };
+    match code {
+        ErrorCode::InvalidRequest => "The request was rejected. Check the input and try again.",
+        ErrorCode::PathTraversal => "That path is outside the workspace.",
+        ErrorCode::NotAFile => "That path is a folder, not a file.",
+        ErrorCode::NotADirectory => "That path is a file, not a folder.",
+        ErrorCode::IsDirectory => "That path is a folder. Open a document inside it instead.",
+        ErrorCode::InvalidContent => "The content could not be read or is not valid.",
+        ErrorCode::InvalidRevision => "That revision does not exist. Reload for the latest one.",
+        ErrorCode::PayloadTooLarge => "This is larger than the size limit.",
+        ErrorCode::UnsupportedMediaType => "This file type is not supported here.",
+        ErrorCode::UnsupportedSchema => "This was made by an incompatible version of ChoirOS.",
+        ErrorCode::NotFound => "It no longer exists. It may have been moved or deleted.",
+        ErrorCode::RunNotFound => "That run no longer exists.",
+        ErrorCode::RunFailed => "The run failed. Check its trace, then retry.",
+        ErrorCode::RunBlocked => "The run stopped and needs your input to continue.",
+        ErrorCode::AlreadyExists => "Something with that name already exists. Pick another name.",
+        ErrorCode::Conflict => "This conflicts with the current state. Reload and try again.",
+        ErrorCode::DocumentConflict => {
+            "The document changed elsewhere. Reload it, then reapply your edit."
+        }
+        ErrorCode::PermissionDenied => "That is not allowed in the workspace.",
+        ErrorCode::PolicyBlocked => "A workspace policy blocked this action.",
+        ErrorCode::QuotaExceeded => {
+            "The workspace is out of disk space. Delete files to free some."
+        }
+        ErrorCode::SupervisorUnavailable => "The backend is still starting. Retry in a moment.",
+        ErrorCode::ActorSpawnFailed => {
+            "The backend could not start this service. Retry, or restart the sandbox."
+        }
+        ErrorCode::ActorUnavailable | ErrorCode::ServiceUnavailable => {
+            "A backend service is not responding. Retry in a moment."
+        }
+        ErrorCode::ProviderUnavailable => {
+            "The model provider is unreachable. Check the provider settings or retry later."
+        }
+        ErrorCode::InternalError => "Something went wrong on the server.",
+    }
+}
+
+/// Guidance for the failure's code, with the raw message behind a toggle.
+/// `source` labels where the error came from, e.g. "Log stream".
+#[component]
+pub fn ErrorNotice(
+    message: String,
+    #[props(default)] source: String,
+    #[props(default)] style: String,
+) -> Element {
+    let guidance = guidance_for(error_code_in(&message));
+    let heading = if source.is_empty() {
+        guidance.to_string()
+    } else {
+        format!("{source}: {guidance}")
+    };
+
+    rsx! {
+        div {
+            class: "error-notice",
+            style: "{style}",
+            role: "alert",
+            div { "{heading}" }
+            details {
+                style: "margin-top: 0.25rem; font-size: 0.75rem; opacity: 0.85;",
+                summary { style: "cursor: pointer;", "Details" }
+                div {
+                    style: "margin-top: 0.25rem; white-space: pre-wrap; word-break: break-word; font-family: monospace;",
+                    "{message}"
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_code_in_http_error_message() {
+        assert_eq!(
+            error_code_in("HTTP error: 507 (QUOTA_EXCEEDED: disk full)"),
+            Some(ErrorCode::QuotaExceeded)
+        );
+        assert_eq!(
+            error_code_in("NOT_A_FILE: Path is a directory"),
+            Some(ErrorCode::NotAFile)
+        );
+        assert_eq!(error_code_in("Request failed: network down"), None);
+    }
+
+    #[test]
+    fn every_code_has_guidance() {
+        for code in ErrorCode::ALL {
+            assert_ne!(guidance_for(Some(code)), guidance_for(None));
+        }
+    }
+}
@@ -4,6 +4,8 @@ use crate::api::files_api::{
     create_directory, create_file, delete_file, list_directory, rename_file, DirectoryEntry,
 };
 use crate::api::open_window;
+use crate::components::ErrorNotice;
+use crate::time::format_date_str;
 
 /// Dialog state
 #[derive(Debug, Clone, PartialEq)]
@@ -473,9 +475,9 @@ pub fn FilesView(
 
             // Error banner
             if let Some(err) = error() {
-                div {
+                ErrorNotice {
+                    message: err,
                     style: "padding: 0.75rem 1rem; background: #7f1d1d; color: #fecaca; font-size: 0.875rem; border-bottom: 1px solid #991b1b;",
-                    "Error: {err}"
                 }
             }
 
@@ -522,7 +524,7 @@ pub fn FilesView(
                                 if entry.is_dir { "—" } else { "{format_size(entry.size)}" }
                             }
                             div { style: "color: var(--text-secondary, #94a3b8); font-size: 0.75rem;",
-                                {format_timestamp(&entry.modified_at)}
+                                {format_date_str(&entry.modified_at)}
                             }
                         }
                     }
@@ -720,16 +722,6 @@ fn ConfirmDialog(
     }
 }
 
-/// Format ISO timestamp to readable format
-fn format_timestamp(iso: &str) -> String {
-    // Try to parse and format the timestamp
-    if let Some(date_part) = iso.split('T').next() {
-        date_part.to_string()
-    } else {
-        iso.to_string()
-    }
-}
-
 /// Persist the current files path to window props
 async fn persist_files_path(desktop_id: &str, window_id: &str, path: &str) -> Result<(), String> {
     // For now, use localStorage as a simple persistence mechanism
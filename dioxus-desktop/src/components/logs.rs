@@ -7,9 +7,13 @@ use wasm_bindgen::closure::Closure;
 use wasm_bindgen::prelude::*;
 use web_sys::{CloseEvent, ErrorEvent, Event, MessageEvent, WebSocket};
 
-use crate::api::{fetch_latest_log_seq, fetch_logs_events, open_window, LogsEvent};
+use crate::api::{
+    fetch_latest_log_seq, fetch_logs_events, fetch_run_image_set, open_window, LogsEvent,
+};
+use crate::components::ErrorNotice;
 
 use super::styles::CHAT_STYLES;
+use crate::time::{format_number, format_timestamp_str, relative_time_str};
 
 enum LogsWsEvent {
     Connected,
@@ -49,6 +53,7 @@ pub fn LogsView(desktop_id: String, window_id: String) -> Element {
     let mut entries = use_signal(Vec::<LogFeedEntry>::new);
     let mut since_seq = use_signal(load_logs_cursor);
     let mut selected_run_id = use_signal(|| None::<String>);
+    let mut run_images_notice = use_signal(|| None::<String>);
     let mut connected = use_signal(|| false);
     let mut error = use_signal(|| None::<String>);
     let mut ws_runtime = use_signal(|| None::<LogsRuntime>);
@@ -332,7 +337,7 @@ pub fn LogsView(desktop_id: String, window_id: String) -> Element {
             if let Some(message) = error() {
                 div {
                     class: "message-bubble system-bubble",
-                    "Log stream error: {message}"
+                    ErrorNotice { message, source: "Log stream" }
                 }
             }
             if runs.is_empty() {
@@ -363,10 +368,13 @@ pub fn LogsView(desktop_id: String, window_id: String) -> Element {
                                     },
                                     onclick: {
                                         let run_id = run.run_id.clone();
-                                        move |_| selected_run_id.set(Some(run_id.clone()))
+                                        move |_| {
+                                            selected_run_id.set(Some(run_id.clone()));
+                                            run_images_notice.set(None);
+                                        }
                                     },
                                     div { class: "thread-title", "{run.headline}" }
-                                    div { class: "thread-preview", "#{run.last_seq} {run.status} {run.event_count} events" }
+                                    div { class: "thread-preview", "#{run.last_seq} {run.status} {format_number(run.event_count as f64)} events" }
                                 }
                             }
                         }
@@ -395,9 +403,36 @@ pub fn LogsView(desktop_id: String, window_id: String) -> Element {
                                 },
                                 "Open Run Markdown"
                             }
+                            button {
+                                class: "thread-run-button",
+                                onclick: {
+                                    let desktop_id = desktop_id.clone();
+                                    let run = run.clone();
+                                    move |_| {
+                                        let desktop_id = desktop_id.clone();
+                                        let run = run.clone();
+                                        spawn(async move {
+                                            match open_run_images_from_logs(desktop_id, run).await {
+                                                Ok(notice) => run_images_notice.set(notice),
+                                                Err(e) => {
+                                                    dioxus_logger::tracing::error!(
+                                                        "Failed to open run images from logs: {}",
+                                                        e
+                                                    );
+                                                    run_images_notice.set(Some(e));
+                                                }
+                                            }
+                                        });
+                                    }
+                                },
+                                "Open Run Images"
+                            }
+                            if let Some(notice) = run_images_notice() {
+                                div { class: "tool-meta", "{notice}" }
+                            }
                             div {
                                 class: "message-bubble system-bubble",
-                                "Run {run.run_id} | actor {run.actor_id} | status {run.status} | events {run.event_count} | start {run.started_at} | last {run.updated_at}"
+                                "Run {run.run_id} | actor {run.actor_id} | status {run.status} | events {format_number(run.event_count as f64)} | start {format_timestamp_str(&run.started_at)} | last {relative_time_str(&run.updated_at)}"
                             }
                             if filtered.is_empty() {
                                 div {
@@ -422,7 +457,7 @@ pub fn LogsView(desktop_id: String, window_id: String) -> Element {
                                                 if let Some(emitter_actor) = event_emitter_label(&entry.event) {
                                                     p { class: "tool-meta", "Emitter: {emitter_actor}" }
                                                 }
-                                                p { class: "tool-meta", "Time: {entry.event.timestamp}" }
+                                                p { class: "tool-meta", "Time: {format_timestamp_str(&entry.event.timestamp)}" }
                                                 pre {
                                                     class: "tool-pre",
                                                     "{serde_json::to_string_pretty(&entry.event.payload).unwrap_or_else(|_| entry.event.payload.to_string())}"
@@ -576,6 +611,35 @@ async fn open_run_markdown_from_logs(desktop_id: String, run: RunListEntry) -> R
         .map(|_| ())
 }
 
+/// Opens every image artifact of the run in one image-set viewer.
+///
+/// Returns a notice for the logs view when artifacts were skipped or the run
+/// has no images at all.
+async fn open_run_images_from_logs(
+    desktop_id: String,
+    run: RunListEntry,
+) -> Result<Option<String>, String> {
+    let run_id = run
+        .run_id
+        .strip_prefix("run:")
+        .or_else(|| run.run_id.strip_prefix("corr:"))
+        .unwrap_or(&run.run_id);
+    let set = fetch_run_image_set(run_id).await?;
+    let notice = (!set.skipped.is_empty())
+        .then(|| format!("Skipped {} non-image artifact(s).", set.skipped.len()));
+    let Some(viewer) = set.viewer else {
+        let empty = "No image artifacts in this run.".to_string();
+        return Ok(Some(match notice {
+            Some(notice) => format!("{empty} {notice}"),
+            None => empty,
+        }));
+    };
+    let props = serde_json::json!({ "viewer": viewer });
+    open_window(&desktop_id, "writer", "Run Images", Some(props))
+        .await
+        .map(|_| notice)
+}
+
 fn url_encode(value: &str) -> String {
     js_sys::encode_uri_component(value)
         .as_string()
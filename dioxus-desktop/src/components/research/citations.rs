@@ -0,0 +1,53 @@
+/// Piece of a research summary; `Marker(n)` is an inline `[n]` reference to
+/// the n-th citation (1-based).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SummarySegment {
+    Text(String),
+    Marker(usize),
+}
+
+pub fn split_citation_markers(summary: &str) -> Vec<SummarySegment> {
+    let mut segments = Vec::new();
+    let mut text = String::new();
+    let mut rest = summary;
+
+    while let Some(open) = rest.find('[') {
+        let after = &rest[open + 1..];
+        let marker = after
+            .find(']')
+            .map(|close| &after[..close])
+            .filter(|digits| !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()))
+            .and_then(|digits| digits.parse::<usize>().ok().map(|n| (n, digits.len())));
+        match marker {
+            Some((n, len)) if n > 0 => {
+                text.push_str(&rest[..open]);
+                if !text.is_empty() {
+                    segments.push(SummarySegment::Text(std::mem::take(&mut text)));
+                }
+                segments.push(SummarySegment::Marker(n));
+                rest = &after[len + 1..];
+            }
+            _ => {
+                text.push_str(&rest[..=open]);
+                rest = after;
+            }
+        }
+    }
+    text.push_str(rest);
+    if !text.is_empty() {
+        segments.push(SummarySegment::Text(text));
+    }
+    segments
+}
+
+/// Host of a citation URL without `www.`, for display and favicons.
+pub fn citation_domain(url: &str) -> Option<String> {
+    let (_, rest) = url.split_once("://")?;
+    let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = host.strip_prefix("www.").unwrap_or(host);
+    (!host.is_empty()).then(|| host.to_string())
+}
+
+pub fn favicon_url(domain: &str) -> String {
+    format!("https://{domain}/favicon.ico")
+}
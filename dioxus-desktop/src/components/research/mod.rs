@@ -0,0 +1,39 @@
+pub mod citations;
+pub mod styles;
+pub mod view;
+
+pub use view::ResearchView;
+
+#[cfg(test)]
+mod tests {
+    use super::citations::{citation_domain, split_citation_markers, SummarySegment};
+
+    #[test]
+    fn test_summary_citation_markers_are_split_out() {
+        let segments = split_citation_markers("Axum [1] and Actix [2][3]. Not [a] or [].");
+        assert_eq!(
+            segments,
+            vec![
+                SummarySegment::Text("Axum ".to_string()),
+                SummarySegment::Marker(1),
+                SummarySegment::Text(" and Actix ".to_string()),
+                SummarySegment::Marker(2),
+                SummarySegment::Marker(3),
+                SummarySegment::Text(". Not [a] or [].".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_citation_domain_strips_scheme_www_and_path() {
+        assert_eq!(
+            citation_domain("https://www.docs.rs/axum/latest?x=1"),
+            Some("docs.rs".to_string())
+        );
+        assert_eq!(
+            citation_domain("http://example.com:8080"),
+            Some("example.com:8080".to_string())
+        );
+        assert_eq!(citation_domain("not a url"), None);
+    }
+}
@@ -0,0 +1,140 @@
+pub const RESEARCH_VIEW_STYLES: &str = r#"
+.research-detail {
+    display: flex;
+    flex-direction: column;
+    gap: 0.8rem;
+    padding: 0.75rem 1rem;
+}
+
+.research-objective {
+    font-weight: 600;
+    color: var(--text-primary, #f8fafc);
+}
+
+.research-meta {
+    font-size: 0.75rem;
+    color: var(--text-secondary, #94a3b8);
+}
+
+.research-status {
+    font-size: 0.7rem;
+    text-transform: uppercase;
+    letter-spacing: 0.04em;
+}
+
+.research-status.running {
+    color: var(--warning-bg, #f59e0b);
+}
+
+.research-status.completed {
+    color: var(--success-bg, #10b981);
+}
+
+.research-status.failed {
+    color: #ef4444;
+}
+
+.research-card {
+    border: 1px solid var(--border-color, #334155);
+    border-radius: 10px;
+    background: var(--bg-secondary, #1e293b);
+    padding: 0.7rem 0.85rem;
+    color: var(--text-primary, #f8fafc);
+}
+
+.research-card h4 {
+    margin: 0 0 0.5rem;
+    font-size: 0.8rem;
+    color: var(--text-secondary, #94a3b8);
+}
+
+.research-summary {
+    line-height: 1.5;
+    white-space: pre-wrap;
+}
+
+.research-cite-marker {
+    font-size: 0.7em;
+    vertical-align: super;
+    color: var(--accent-bg, #3b82f6);
+    text-decoration: none;
+    margin: 0 0.05rem;
+}
+
+.research-citation {
+    display: flex;
+    gap: 0.55rem;
+    padding: 0.4rem 0;
+    border-top: 1px solid var(--border-color, #334155);
+}
+
+.research-citation:first-of-type {
+    border-top: none;
+}
+
+.research-favicon {
+    width: 16px;
+    height: 16px;
+    flex-shrink: 0;
+    margin-top: 0.15rem;
+}
+
+.research-citation a {
+    color: var(--text-primary, #f8fafc);
+}
+
+.research-citation-snippet {
+    font-size: 0.75rem;
+    color: var(--text-muted, #64748b);
+}
+
+.research-provider-table {
+    width: 100%;
+    border-collapse: collapse;
+    font-size: 0.75rem;
+}
+
+.research-provider-table th,
+.research-provider-table td {
+    text-align: left;
+    padding: 0.25rem 0.4rem;
+    border-bottom: 1px solid var(--border-color, #334155);
+}
+
+.research-progress-line {
+    font-size: 0.75rem;
+    color: var(--text-secondary, #94a3b8);
+    font-family: monospace;
+}
+
+.research-actions {
+    display: flex;
+    flex-direction: column;
+    gap: 0.45rem;
+}
+
+.research-actions textarea,
+.research-actions input {
+    background: var(--input-bg, #0f172a);
+    color: var(--text-primary, #f8fafc);
+    border: 1px solid var(--border-color, #334155);
+    border-radius: 0.45rem;
+    padding: 0.4rem 0.55rem;
+    font: inherit;
+}
+
+.research-actions button {
+    align-self: flex-start;
+    background: var(--accent-bg, #3b82f6);
+    color: white;
+    border: none;
+    border-radius: 0.45rem;
+    padding: 0.35rem 0.75rem;
+    cursor: pointer;
+}
+
+.research-actions button:disabled {
+    opacity: 0.5;
+    cursor: default;
+}
+"#;
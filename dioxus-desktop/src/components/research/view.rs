@@ -0,0 +1,734 @@
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
+
+use dioxus::prelude::*;
+use gloo_timers::future::TimeoutFuture;
+use shared_types::{
+    ResearchCitation, ResearchProviderCall, ResearchTaskDetail, ResearchTaskProgress,
+    ResearchTaskStatus, ResearchTaskSummary,
+};
+use wasm_bindgen::prelude::*;
+use web_sys::WebSocket;
+
+use crate::api::{
+    fetch_latest_log_seq, fetch_research_task, fetch_research_tasks, open_window,
+    rerun_research_task, send_research_to_writer,
+};
+use crate::components::trace::ws::{http_to_ws_url, TraceRuntime, TraceWsEvent};
+use crate::components::ErrorNotice;
+use crate::time::{format_date_str, format_timestamp};
+
+use super::citations::{citation_domain, favicon_url, split_citation_markers, SummarySegment};
+use super::styles::RESEARCH_VIEW_STYLES;
+
+use super::super::styles::CHAT_STYLES;
+
+const RESEARCH_TASK_LIMIT: u32 = 50;
+
+fn status_label(status: ResearchTaskStatus) -> &'static str {
+    match status {
+        ResearchTaskStatus::Running => "running",
+        ResearchTaskStatus::Completed => "completed",
+        ResearchTaskStatus::Failed => "failed",
+    }
+}
+
+fn provider_call_status(call: &ResearchProviderCall) -> String {
+    if call.succeeded {
+        "ok".to_string()
+    } else {
+        call.error.clone().unwrap_or_else(|| "error".to_string())
+    }
+}
+
+fn run_document_path(run_id: Option<&str>) -> String {
+    run_id
+        .map(|run_id| format!("conductor/runs/{run_id}/draft.md"))
+        .unwrap_or_default()
+}
+
+/// Progress from the detail response merged with lines streamed since.
+fn merged_progress(
+    detail: &[ResearchTaskProgress],
+    live: Option<&Vec<ResearchTaskProgress>>,
+) -> Vec<ResearchTaskProgress> {
+    let mut lines = detail.to_vec();
+    if let Some(live) = live {
+        lines.extend(live.iter().cloned());
+    }
+    lines.sort_by_key(|line| line.seq);
+    lines.dedup_by_key(|line| line.seq);
+    lines
+}
+
+fn build_research_ws_url(since_seq: i64) -> String {
+    let ws_base = http_to_ws_url(crate::api::api_base());
+    format!(
+        "{}/ws/logs/events?since_seq={}&limit=300&poll_ms=200",
+        ws_base,
+        since_seq.max(0)
+    )
+}
+
+#[component]
+pub fn ResearchView(desktop_id: String, window_id: String) -> Element {
+    let mut tasks = use_signal(Vec::<ResearchTaskSummary>::new);
+    let mut selected_task_id = use_signal(|| None::<String>);
+    let mut detail = use_signal(|| None::<ResearchTaskDetail>);
+    let mut live_progress = use_signal(HashMap::<String, Vec<ResearchTaskProgress>>::new);
+    let mut refresh_tick = use_signal(|| 0u64);
+    let mut rerun_objective = use_signal(String::new);
+    let mut writer_path = use_signal(String::new);
+    let mut action_pending = use_signal(|| false);
+    let mut notice = use_signal(|| None::<String>);
+    let mut since_seq = use_signal(|| 0i64);
+    let mut connected = use_signal(|| false);
+    let mut error = use_signal(|| None::<String>);
+    let mut ws_runtime = use_signal(|| None::<TraceRuntime>);
+    let mut preload_started = use_signal(|| false);
+    let mut preload_ready = use_signal(|| false);
+    let ws_event_queue = use_hook(|| Rc::new(RefCell::new(VecDeque::<TraceWsEvent>::new())));
+    let mut ws_event_pump_started = use_signal(|| false);
+    let ws_event_pump_alive = use_hook(|| Rc::new(Cell::new(true)));
+
+    {
+        let ws_event_pump_alive = ws_event_pump_alive.clone();
+        use_drop(move || {
+            ws_event_pump_alive.set(false);
+            if let Some(runtime) = ws_runtime.write().take() {
+                runtime.closing.set(true);
+                let _ = runtime.ws.close();
+            }
+        });
+    }
+
+    // Initial task list; the stream resumes from the oldest running task so
+    // its earlier progress is replayed.
+    {
+        let desktop_id = desktop_id.clone();
+        use_effect(move || {
+            if preload_started() {
+                return;
+            }
+            preload_started.set(true);
+
+            let desktop_id = desktop_id.clone();
+            spawn(async move {
+                let latest_seq = fetch_latest_log_seq().await.unwrap_or(0);
+                match fetch_research_tasks(&desktop_id, RESEARCH_TASK_LIMIT).await {
+                    Ok(list) => {
+                        let resume_seq = list
+                            .iter()
+                            .filter(|task| task.status == ResearchTaskStatus::Running)
+                            .map(|task| task.started_seq)
+                            .min()
+                            .unwrap_or(latest_seq);
+                        since_seq.set(resume_seq);
+                        tasks.set(list);
+                    }
+                    Err(e) => {
+                        since_seq.set(latest_seq);
+                        error.set(Some(format!("Failed to load research tasks: {e}")));
+                    }
+                }
+                preload_ready.set(true);
+            });
+        });
+    }
+
+    // Refresh on research lifecycle events.
+    {
+        let desktop_id = desktop_id.clone();
+        use_effect(move || {
+            let tick = refresh_tick();
+            if tick == 0 {
+                return;
+            }
+            let desktop_id = desktop_id.clone();
+            spawn(async move {
+                if let Ok(list) = fetch_research_tasks(&desktop_id, RESEARCH_TASK_LIMIT).await {
+                    tasks.set(list);
+                }
+            });
+        });
+    }
+
+    // Load the selected task's detail.
+    use_effect(move || {
+        let _ = refresh_tick();
+        let Some(task_id) = selected_task_id() else {
+            detail.set(None);
+            return;
+        };
+        spawn(async move {
+            match fetch_research_task(&task_id).await {
+                Ok(loaded) => {
+                    if selected_task_id().as_deref() != Some(loaded.task.task_id.as_str()) {
+                        return;
+                    }
+                    let same_task = detail
+                        .read()
+                        .as_ref()
+                        .is_some_and(|current| current.task.task_id == loaded.task.task_id);
+                    if !same_task {
+                        rerun_objective.set(loaded.task.objective.clone());
+                        writer_path.set(run_document_path(loaded.task.run_id.as_deref()));
+                    }
+                    detail.set(Some(loaded));
+                }
+                Err(e) => error.set(Some(format!("Failed to load research task: {e}"))),
+            }
+        });
+    });
+
+    {
+        let ws_event_queue = ws_event_queue.clone();
+        let ws_event_pump_alive = ws_event_pump_alive.clone();
+        use_effect(move || {
+            if ws_event_pump_started() {
+                return;
+            }
+            ws_event_pump_started.set(true);
+
+            let ws_event_queue = ws_event_queue.clone();
+            let ws_event_pump_alive = ws_event_pump_alive.clone();
+            spawn(async move {
+                while ws_event_pump_alive.get() {
+                    let drained: Vec<TraceWsEvent> =
+                        ws_event_queue.borrow_mut().drain(..).collect();
+                    let mut needs_refresh = false;
+
+                    for event in drained {
+                        match event {
+                            TraceWsEvent::Connected => {
+                                connected.set(true);
+                                error.set(None);
+                            }
+                            TraceWsEvent::Error(message) => {
+                                connected.set(false);
+                                error.set(Some(message));
+                                ws_runtime.set(None);
+                            }
+                            TraceWsEvent::Closed => {
+                                connected.set(false);
+                                ws_runtime.set(None);
+                            }
+                            TraceWsEvent::Message(text) => {
+                                let Ok(json) = serde_json::from_str::<serde_json::Value>(&text)
+                                else {
+                                    continue;
+                                };
+                                if json.get("type").and_then(|v| v.as_str()) != Some("event") {
+                                    continue;
+                                }
+                                let seq = json.get("seq").and_then(|v| v.as_i64()).unwrap_or(0);
+                                if seq > since_seq() {
+                                    since_seq.set(seq);
+                                }
+                                let event_type = json
+                                    .get("event_type")
+                                    .and_then(|v| v.as_str())
+                                    .unwrap_or_default();
+                                let payload = json
+                                    .get("payload")
+                                    .cloned()
+                                    .unwrap_or(serde_json::Value::Null);
+                                let task_id = payload
+                                    .get("task_id")
+                                    .and_then(|v| v.as_str())
+                                    .unwrap_or_default()
+                                    .to_string();
+
+                                if event_type.starts_with("research.task.") {
+                                    needs_refresh = true;
+                                } else if event_type == "worker.task.progress" {
+                                    let is_research_task =
+                                        tasks.read().iter().any(|task| task.task_id == task_id);
+                                    if !is_research_task {
+                                        continue;
+                                    }
+                                    let field = |key: &str| {
+                                        payload
+                                            .get(key)
+                                            .and_then(|v| v.as_str())
+                                            .map(ToString::to_string)
+                                    };
+                                    let line = ResearchTaskProgress {
+                                        seq,
+                                        phase: field("phase").unwrap_or_default(),
+                                        message: field("message").unwrap_or_default(),
+                                        provider: field("provider"),
+                                        timestamp: field("timestamp").unwrap_or_default(),
+                                    };
+                                    live_progress.write().entry(task_id).or_default().push(line);
+                                }
+                            }
+                        }
+                    }
+
+                    if needs_refresh {
+                        refresh_tick.set(refresh_tick() + 1);
+                    }
+                    TimeoutFuture::new(16).await;
+                }
+            });
+        });
+    }
+
+    {
+        let ws_event_queue = ws_event_queue.clone();
+        use_effect(move || {
+            if !preload_ready() {
+                return;
+            }
+            if ws_runtime.read().is_some() {
+                return;
+            }
+
+            let ws_url = build_research_ws_url(since_seq());
+            let ws = match WebSocket::new(&ws_url) {
+                Ok(ws) => ws,
+                Err(err) => {
+                    error.set(Some(format!("research websocket open failed: {err:?}")));
+                    return;
+                }
+            };
+            let closing = Rc::new(Cell::new(false));
+
+            let queue_open = ws_event_queue.clone();
+            let on_open =
+                wasm_bindgen::closure::Closure::wrap(Box::new(move |_e: web_sys::Event| {
+                    queue_open.borrow_mut().push_back(TraceWsEvent::Connected);
+                })
+                    as Box<dyn FnMut(web_sys::Event)>);
+            ws.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+
+            let queue_message = ws_event_queue.clone();
+            let on_message =
+                wasm_bindgen::closure::Closure::wrap(Box::new(move |e: web_sys::MessageEvent| {
+                    let Ok(text) = e.data().dyn_into::<js_sys::JsString>() else {
+                        return;
+                    };
+                    let text_string = text.as_string().unwrap_or_default();
+                    queue_message
+                        .borrow_mut()
+                        .push_back(TraceWsEvent::Message(text_string));
+                })
+                    as Box<dyn FnMut(web_sys::MessageEvent)>);
+            ws.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+
+            let queue_error = ws_event_queue.clone();
+            let on_error =
+                wasm_bindgen::closure::Closure::wrap(Box::new(move |e: web_sys::ErrorEvent| {
+                    queue_error
+                        .borrow_mut()
+                        .push_back(TraceWsEvent::Error(e.message()));
+                })
+                    as Box<dyn FnMut(web_sys::ErrorEvent)>);
+            ws.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+
+            let queue_close = ws_event_queue.clone();
+            let closing_for_close = closing.clone();
+            let on_close =
+                wasm_bindgen::closure::Closure::wrap(Box::new(move |_e: web_sys::CloseEvent| {
+                    if closing_for_close.get() {
+                        return;
+                    }
+                    queue_close.borrow_mut().push_back(TraceWsEvent::Closed);
+                })
+                    as Box<dyn FnMut(web_sys::CloseEvent)>);
+            ws.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+
+            ws_runtime.set(Some(TraceRuntime {
+                ws,
+                closing,
+                _on_open: on_open,
+                _on_message: on_message,
+                _on_error: on_error,
+                _on_close: on_close,
+            }));
+        });
+    }
+
+    let on_rerun = {
+        let desktop_id = desktop_id.clone();
+        move |_| {
+            let Some(task_id) = selected_task_id() else {
+                return;
+            };
+            let objective = rerun_objective().trim().to_string();
+            let desktop_id = desktop_id.clone();
+            action_pending.set(true);
+            spawn(async move {
+                let objective = (!objective.is_empty()).then_some(objective);
+                match rerun_research_task(&desktop_id, &task_id, objective).await {
+                    Ok(started) => {
+                        notice.set(Some("Re-run started".to_string()));
+                        selected_task_id.set(Some(started.task_id));
+                        refresh_tick.set(refresh_tick() + 1);
+                    }
+                    Err(e) => notice.set(Some(format!("Re-run failed: {e}"))),
+                }
+                action_pending.set(false);
+            });
+        }
+    };
+
+    let on_send_to_writer = {
+        let desktop_id = desktop_id.clone();
+        move |_| {
+            let Some(task_id) = selected_task_id() else {
+                return;
+            };
+            let path = writer_path().trim().to_string();
+            let desktop_id = desktop_id.clone();
+            action_pending.set(true);
+            spawn(async move {
+                match send_research_to_writer(&task_id, (!path.is_empty()).then_some(path)).await {
+                    Ok(sent) => {
+                        notice.set(Some(format!("Added section to {}", sent.path)));
+                        let props = serde_json::json!({ "path": sent.path });
+                        if let Err(e) =
+                            open_window(&desktop_id, "writer", "Writer", Some(props)).await
+                        {
+                            dioxus_logger::tracing::error!(
+                                "Failed to open writer for research section: {}",
+                                e
+                            );
+                        }
+                    }
+                    Err(e) => notice.set(Some(format!("Send to Writer failed: {e}"))),
+                }
+                action_pending.set(false);
+            });
+        }
+    };
+
+    let status_label_text = if connected() { "Live" } else { "Reconnecting" };
+    let task_list = tasks.read().clone();
+    let detail_snapshot = detail.read().clone();
+    let active_task_id = selected_task_id();
+
+    rsx! {
+        style { {CHAT_STYLES} }
+        style { {RESEARCH_VIEW_STYLES} }
+        div {
+            class: "panel-container",
+            div {
+                class: "panel-header",
+                h3 { "Research" }
+                span {
+                    class: "panel-status",
+                    style: if connected() {
+                        "color: #16a34a;"
+                    } else {
+                        "color: #f59e0b;"
+                    },
+                    "{status_label_text}"
+                }
+            }
+            if let Some(message) = error() {
+                div {
+                    class: "message-bubble system-bubble",
+                    ErrorNotice { message, source: "Research stream" }
+                }
+            }
+            if task_list.is_empty() {
+                div {
+                    class: "empty-state",
+                    div { class: "empty-icon", "🔬" }
+                    p { "No research tasks yet" }
+                    span { "Delegated research shows up here as it runs" }
+                }
+            } else {
+                div {
+                    class: "panel-body",
+                    aside {
+                        class: "thread-sidebar",
+                        div {
+                            class: "thread-sidebar-header",
+                            span { "Tasks" }
+                            span { "{task_list.len()}" }
+                        }
+                        div {
+                            class: "thread-list",
+                            for task in task_list {
+                                button {
+                                    key: "{task.task_id}",
+                                    class: if active_task_id.as_deref() == Some(task.task_id.as_str()) {
+                                        "thread-item active"
+                                    } else {
+                                        "thread-item"
+                                    },
+                                    onclick: {
+                                        let task_id = task.task_id.clone();
+                                        move |_| {
+                                            notice.set(None);
+                                            selected_task_id.set(Some(task_id.clone()));
+                                        }
+                                    },
+                                    div { class: "thread-title", "{task.objective}" }
+                                    div {
+                                        class: "thread-preview",
+                                        span {
+                                            class: "research-status {status_label(task.status)}",
+                                            "{status_label(task.status)}"
+                                        }
+                                        " · {task.citation_count} sources"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    div {
+                        class: "messages-scroll-area",
+                        if let Some(task_detail) = detail_snapshot {
+                            ResearchDetail {
+                                detail: task_detail.clone(),
+                                progress: merged_progress(
+                                    &task_detail.progress,
+                                    live_progress.read().get(&task_detail.task.task_id),
+                                ),
+                                rerun_objective: rerun_objective(),
+                                writer_path: writer_path(),
+                                action_pending: action_pending(),
+                                notice: notice(),
+                                on_rerun_objective: move |value: String| rerun_objective.set(value),
+                                on_writer_path: move |value: String| writer_path.set(value),
+                                on_rerun,
+                                on_send_to_writer,
+                            }
+                        } else {
+                            div {
+                                class: "empty-state",
+                                div { class: "empty-icon", "🧭" }
+                                p { "Select a research task from the sidebar" }
+                            }
+                        }
+                    }
+                }
+            }
+            div {
+                class: "input-hint",
+                "Desktop: {desktop_id} | Window: {window_id}"
+            }
+        }
+    }
+}
+
+#[component]
+fn ResearchDetail(
+    detail: ResearchTaskDetail,
+    progress: Vec<ResearchTaskProgress>,
+    rerun_objective: String,
+    writer_path: String,
+    action_pending: bool,
+    notice: Option<String>,
+    on_rerun_objective: EventHandler<String>,
+    on_writer_path: EventHandler<String>,
+    on_rerun: EventHandler<()>,
+    on_send_to_writer: EventHandler<()>,
+) -> Element {
+    let task = detail.task.clone();
+    let status = status_label(task.status);
+    let can_send = task.status == ResearchTaskStatus::Completed && detail.summary.is_some();
+    let started_at = format_timestamp(&task.started_at);
+
+    rsx! {
+        div {
+            class: "research-detail",
+            div { class: "research-objective", "{task.objective}" }
+            div {
+                class: "research-meta",
+                span { class: "research-status {status}", "{status}" }
+                " · started {started_at}"
+                if let Some(provider) = task.provider_used.as_ref() {
+                    " · via {provider}"
+                }
+                if let Some(model) = detail.model_used.as_ref() {
+                    " · {model}"
+                }
+                if let Some(rerun_of) = task.rerun_of.as_ref() {
+                    " · re-run of {rerun_of}"
+                }
+            }
+
+            if let Some(message) = notice {
+                div { class: "message-bubble system-bubble", "{message}" }
+            }
+            if let Some(error) = detail.error.as_ref() {
+                div { class: "message-bubble system-bubble", "Research failed: {error}" }
+            }
+
+            if let Some(summary) = detail.summary.as_ref() {
+                div {
+                    class: "research-card",
+                    h4 { "Summary" }
+                    div {
+                        class: "research-summary",
+                        for segment in split_citation_markers(summary) {
+                            match segment {
+                                SummarySegment::Text(text) => rsx! { "{text}" },
+                                SummarySegment::Marker(n) => match detail.citations.get(n - 1) {
+                                    Some(citation) => rsx! {
+                                        a {
+                                            class: "research-cite-marker",
+                                            href: "{citation.url}",
+                                            target: "_blank",
+                                            rel: "noopener noreferrer",
+                                            title: "{citation.title}",
+                                            "[{n}]"
+                                        }
+                                    },
+                                    None => rsx! { "[{n}]" },
+                                },
+                            }
+                        }
+                    }
+                }
+            }
+
+            if !detail.citations.is_empty() {
+                div {
+                    class: "research-card",
+                    h4 { "Sources ({detail.citations.len()})" }
+                    for (index, citation) in detail.citations.iter().enumerate() {
+                        CitationRow { index: index + 1, citation: citation.clone() }
+                    }
+                }
+            }
+
+            if !detail.provider_calls.is_empty() {
+                ProviderCalls { calls: detail.provider_calls.clone() }
+            }
+
+            if task.status == ResearchTaskStatus::Running || !progress.is_empty() {
+                details {
+                    class: "research-card",
+                    open: task.status == ResearchTaskStatus::Running,
+                    summary { "Progress ({progress.len()})" }
+                    for line in progress {
+                        div {
+                            key: "{line.seq}",
+                            class: "research-progress-line",
+                            "{line.phase}"
+                            if let Some(provider) = line.provider.as_ref() {
+                                " [{provider}]"
+                            }
+                            " {line.message}"
+                        }
+                    }
+                }
+            }
+
+            div {
+                class: "research-card research-actions",
+                h4 { "Re-run with changes" }
+                textarea {
+                    rows: "3",
+                    value: "{rerun_objective}",
+                    oninput: move |e: FormEvent| on_rerun_objective.call(e.value()),
+                }
+                button {
+                    disabled: action_pending,
+                    onclick: move |_| on_rerun.call(()),
+                    "Re-run"
+                }
+                h4 { "Send to Writer as a new section" }
+                input {
+                    r#type: "text",
+                    placeholder: "conductor/runs/<run_id>/draft.md",
+                    value: "{writer_path}",
+                    oninput: move |e: FormEvent| on_writer_path.call(e.value()),
+                }
+                button {
+                    disabled: action_pending || !can_send,
+                    onclick: move |_| on_send_to_writer.call(()),
+                    "Send to Writer"
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn CitationRow(index: usize, citation: ResearchCitation) -> Element {
+    let domain = citation_domain(&citation.url);
+    let title = if citation.title.trim().is_empty() {
+        citation.url.clone()
+    } else {
+        citation.title.clone()
+    };
+
+    rsx! {
+        div {
+            class: "research-citation",
+            if let Some(domain) = domain.as_ref() {
+                img {
+                    class: "research-favicon",
+                    src: "{favicon_url(domain)}",
+                    alt: "",
+                    loading: "lazy",
+                }
+            }
+            div {
+                div {
+                    "[{index}] "
+                    a {
+                        href: "{citation.url}",
+                        target: "_blank",
+                        rel: "noopener noreferrer",
+                        "{title}"
+                    }
+                }
+                div {
+                    class: "research-meta",
+                    if let Some(domain) = domain.as_ref() {
+                        "{domain} · "
+                    }
+                    "{citation.provider}"
+                    if let Some(published_at) = citation.published_at.as_ref() {
+                        " · {format_date_str(published_at)}"
+                    }
+                }
+                if !citation.snippet.trim().is_empty() {
+                    div { class: "research-citation-snippet", "{citation.snippet}" }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn ProviderCalls(calls: Vec<ResearchProviderCall>) -> Element {
+    let total_ms: u64 = calls.iter().map(|call| call.latency_ms).sum();
+
+    rsx! {
+        div {
+            class: "research-card",
+            h4 { "Provider calls ({calls.len()}, {total_ms} ms total)" }
+            table {
+                class: "research-provider-table",
+                thead {
+                    tr {
+                        th { "Provider" }
+                        th { "Latency" }
+                        th { "Results" }
+                        th { "Status" }
+                    }
+                }
+                tbody {
+                    for call in calls {
+                        tr {
+                            td { "{call.provider}" }
+                            td { "{call.latency_ms} ms" }
+                            td { "{call.result_count}" }
+                            td { "{provider_call_status(&call)}" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
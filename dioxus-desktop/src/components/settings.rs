@@ -2,7 +2,13 @@ use std::collections::HashMap;
 
 use dioxus::prelude::*;
 
-use crate::api::{fetch_model_catalog, fetch_model_config, update_model_config, ModelInfo};
+use shared_types::LearnedPreferencesResponse;
+
+use crate::api::{
+    delete_learned_preference, fetch_learned_preferences, fetch_model_catalog, fetch_model_config,
+    update_model_config, update_preference_learning, ModelInfo,
+};
+use crate::time::format_date;
 
 use super::styles::CHAT_STYLES;
 
@@ -25,12 +31,21 @@ pub fn SettingsView(desktop_id: String, window_id: String) -> Element {
                     onclick: move |_| active_tab.set("model_config".to_string()),
                     "Model Config"
                 }
+                button {
+                    class: "tool-summary",
+                    style: if active_tab() == "preferences" { "text-align:left; width:100%; font-weight:700;" } else { "text-align:left; width:100%;" },
+                    onclick: move |_| active_tab.set("preferences".to_string()),
+                    "Preferences"
+                }
             }
             div {
                 style: "flex: 1; padding: 0.75rem; overflow: auto; display:flex; flex-direction:column; gap:0.5rem;",
                 if active_tab() == "model_config" {
                     ModelConfigPanel {}
                 }
+                if active_tab() == "preferences" {
+                    LearnedPreferencesPanel {}
+                }
             }
         }
     }
@@ -139,3 +154,102 @@ fn ModelConfigPanel() -> Element {
         }
     }
 }
+
+/// Preferences learned from prompts, with delete and a learning switch.
+#[component]
+fn LearnedPreferencesPanel() -> Element {
+    let mut data = use_signal(|| None::<LearnedPreferencesResponse>);
+    let mut status_msg = use_signal(|| None::<String>);
+
+    use_effect(move || {
+        spawn(async move {
+            match fetch_learned_preferences().await {
+                Ok(response) => data.set(Some(response)),
+                Err(e) => status_msg.set(Some(format!("Failed to load preferences: {e}"))),
+            }
+        });
+    });
+
+    let on_toggle = move |_| {
+        let Some(enabled) = data().map(|d| d.learning_enabled) else {
+            return;
+        };
+        spawn(async move {
+            match update_preference_learning(!enabled).await {
+                Ok(response) => {
+                    data.set(Some(response));
+                    status_msg.set(None);
+                }
+                Err(e) => status_msg.set(Some(format!("Error: {e}"))),
+            }
+        });
+    };
+
+    rsx! {
+        div { class: "panel-header",
+            h3 { "Learned Preferences" }
+            if let Some(msg) = status_msg() {
+                span { class: "panel-status", "{msg}" }
+            }
+        }
+        p {
+            style: "margin: 0; color: var(--text-muted, #94a3b8);",
+            "Durable preferences picked up from your prompts are shared with the conductor on later runs. Secrets are never stored."
+        }
+        match data() {
+            None => rsx! { p { "Loading preferences..." } },
+            Some(prefs) => rsx! {
+                label {
+                    style: "display: flex; align-items: center; gap: 0.5rem;",
+                    input {
+                        r#type: "checkbox",
+                        checked: prefs.learning_enabled,
+                        onchange: on_toggle,
+                    }
+                    "Learn preferences from my prompts"
+                }
+                if prefs.preferences.is_empty() {
+                    p { "No preferences learned yet." }
+                }
+                for pref in prefs.preferences {
+                    {
+                        let preference_id = pref.preference_id.clone();
+                        let learned = format_date(&pref.learned_at);
+                        rsx! {
+                            div {
+                                key: "{pref.preference_id}",
+                                style: "display: flex; align-items: center; gap: 0.75rem; padding: 0.4rem 0; border-bottom: 1px solid var(--border-color, #334155);",
+                                div {
+                                    style: "flex: 1; display: flex; flex-direction: column; gap: 0.15rem;",
+                                    span { style: "font-weight: 600;", "{pref.topic}" }
+                                    span { "{pref.statement}" }
+                                    span {
+                                        style: "font-size: 0.8rem; color: var(--text-muted, #94a3b8);",
+                                        "Learned {learned}"
+                                    }
+                                }
+                                button {
+                                    class: "tool-summary",
+                                    style: "width: auto; padding: 0.25rem 0.75rem;",
+                                    onclick: move |_| {
+                                        let preference_id = preference_id.clone();
+                                        spawn(async move {
+                                            match delete_learned_preference(&preference_id).await {
+                                                Ok(response) => {
+                                                    data.set(Some(response));
+                                                    status_msg.set(None);
+                                                }
+                                                Err(e) => status_msg.set(Some(format!("Error: {e}"))),
+                                            }
+                                        });
+                                    },
+                                    "Delete"
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use dioxus::prelude::*;
+use shared_types::{ContextTraceCall, ContextTraceItem, ContextTraceItemKind, ContextTraceStatus};
+
+use crate::api::fetch_run_context_traces;
+use crate::components::ErrorNotice;
+use crate::time::format_timestamp;
+
+use super::view::format_tokens_short;
+
+fn item_color(item: &ContextTraceItem) -> &'static str {
+    match (item.kind, item.role.as_deref()) {
+        (ContextTraceItemKind::System, _) => "#6366f1",
+        (ContextTraceItemKind::Tools, _) => "#0ea5e9",
+        (ContextTraceItemKind::Message, Some("assistant")) => "#f59e0b",
+        (ContextTraceItemKind::Message, _) => "#22c55e",
+    }
+}
+
+/// One stacked-bar segment: width in percent of the bar, color, tooltip.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextBarSegment {
+    pub width_pct: f64,
+    pub color: &'static str,
+    pub trimmed: bool,
+    pub title: String,
+}
+
+/// Segments for a call's bar. The bar spans the model's context window when
+/// known, otherwise the call's own total.
+pub fn context_bar_segments(call: &ContextTraceCall) -> Vec<ContextBarSegment> {
+    let span = call
+        .context_window
+        .unwrap_or(call.total_est_tokens)
+        .max(call.total_est_tokens)
+        .max(1) as f64;
+    call.items
+        .iter()
+        .filter(|item| item.est_tokens > 0)
+        .map(|item| {
+            let mut title = format!("{} · ~{} tokens", item.label, item.est_tokens);
+            if let Some(reason) = &item.trim_reason {
+                title.push_str(&format!(" · trimmed: {reason}"));
+            }
+            ContextBarSegment {
+                width_pct: f64::from(item.est_tokens) / span * 100.0,
+                color: item_color(item),
+                trimmed: item.trimmed,
+                title,
+            }
+        })
+        .collect()
+}
+
+fn usage_label(call: &ContextTraceCall) -> String {
+    let total = format_tokens_short(i64::from(call.total_est_tokens));
+    match call.context_window {
+        Some(window) => format!(
+            "~{total} / {} ({:.0}%)",
+            format_tokens_short(i64::from(window)),
+            f64::from(call.total_est_tokens) / f64::from(window.max(1)) * 100.0
+        ),
+        None => format!("~{total}"),
+    }
+}
+
+/// Context composition of each LLM call in a run, as stacked bars.
+#[component]
+pub fn ContextTracePanel(run_id: String, on_close: EventHandler<()>) -> Element {
+    let mut calls = use_signal(|| None::<Vec<ContextTraceCall>>);
+    let mut error = use_signal(|| None::<String>);
+
+    use_effect(use_reactive!(|run_id| {
+        calls.set(None);
+        error.set(None);
+        spawn(async move {
+            match fetch_run_context_traces(&run_id).await {
+                Ok(response) => calls.set(Some(response.calls)),
+                Err(e) => error.set(Some(format!("Failed to load context traces: {e}"))),
+            }
+        });
+    }));
+
+    rsx! {
+        div {
+            class: "trace-context-panel",
+            div {
+                class: "trace-node-panel-head",
+                h4 { class: "trace-node-title", "Context composition" }
+                button {
+                    class: "trace-run-toggle",
+                    onclick: move |_| on_close.call(()),
+                    "Close"
+                }
+            }
+            if let Some(message) = error() {
+                ErrorNotice { message, source: "Context traces" }
+            }
+            match calls() {
+                None => rsx! {
+                    div { class: "trace-context-empty", "Loading…" }
+                },
+                Some(calls) if calls.is_empty() => rsx! {
+                    div { class: "trace-context-empty", "No LLM calls recorded for this run." }
+                },
+                Some(calls) => rsx! {
+                    for call in calls {
+                        div {
+                            key: "{call.trace_id}",
+                            class: "trace-context-row",
+                            div {
+                                class: "trace-context-meta",
+                                span { class: "trace-context-fn", "{call.function_name}" }
+                                span { "{call.model_used}" }
+                                span { "{format_timestamp(&call.started_at)}" }
+                                if call.status == ContextTraceStatus::Recorded {
+                                    span { class: "trace-context-usage", "{usage_label(&call)}" }
+                                }
+                            }
+                            if call.status == ContextTraceStatus::Pruned {
+                                div { class: "trace-context-pruned", "trace pruned" }
+                            } else {
+                                div {
+                                    class: "trace-context-bar",
+                                    for (index, segment) in context_bar_segments(&call).into_iter().enumerate() {
+                                        div {
+                                            key: "{index}",
+                                            class: if segment.trimmed {
+                                                "trace-context-seg trimmed"
+                                            } else {
+                                                "trace-context-seg"
+                                            },
+                                            style: "width: {segment.width_pct:.2}%; background-color: {segment.color};",
+                                            title: "{segment.title}",
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(kind: ContextTraceItemKind, est_tokens: u32, trimmed: bool) -> ContextTraceItem {
+        ContextTraceItem {
+            kind,
+            role: None,
+            label: "item".to_string(),
+            id: None,
+            est_tokens,
+            trimmed,
+            trim_reason: trimmed.then(|| "cut".to_string()),
+        }
+    }
+
+    fn call(items: Vec<ContextTraceItem>, context_window: Option<u32>) -> ContextTraceCall {
+        ContextTraceCall {
+            trace_id: "trace-1".to_string(),
+            function_name: "Decide".to_string(),
+            model_used: "test-model".to_string(),
+            started_at: chrono::Utc::now(),
+            status: ContextTraceStatus::Recorded,
+            total_est_tokens: items.iter().map(|item| item.est_tokens).sum(),
+            items,
+            context_window,
+            document_context: None,
+        }
+    }
+
+    #[test]
+    fn test_bar_segments_scale_to_context_window() {
+        let call = call(
+            vec![
+                item(ContextTraceItemKind::System, 250, false),
+                item(ContextTraceItemKind::Message, 0, false),
+                item(ContextTraceItemKind::Message, 750, true),
+            ],
+            Some(2_000),
+        );
+        let segments = context_bar_segments(&call);
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].width_pct, 12.5);
+        assert_eq!(segments[1].width_pct, 37.5);
+        assert!(segments[1].trimmed);
+        assert!(segments[1].title.contains("trimmed: cut"));
+    }
+
+    #[test]
+    fn test_bar_segments_fill_bar_without_context_window() {
+        let call = call(
+            vec![
+                item(ContextTraceItemKind::System, 1, false),
+                item(ContextTraceItemKind::Tools, 3, false),
+            ],
+            None,
+        );
+        let total: f64 = context_bar_segments(&call)
+            .iter()
+            .map(|segment| segment.width_pct)
+            .sum();
+        assert_eq!(total, 100.0);
+    }
+}
@@ -0,0 +1,134 @@
+use dioxus::prelude::*;
+use shared_types::ObjectiveContract;
+
+use crate::api::conductor_get_run_state;
+use crate::components::ErrorNotice;
+
+/// One-line summary of the budget a contract holds the run to.
+pub fn contract_budget_label(contract: &ObjectiveContract) -> String {
+    let constraints = &contract.constraints;
+    let mut label = format!(
+        "{} tool calls · {}s · {} attempt{}",
+        constraints.max_tool_calls,
+        constraints.timeout_ms / 1000,
+        contract.attempts_budget,
+        if contract.attempts_budget == 1 {
+            ""
+        } else {
+            "s"
+        }
+    );
+    if !constraints.allowed_capabilities.is_empty() {
+        label.push_str(&format!(
+            " · only {}",
+            constraints.allowed_capabilities.join(", ")
+        ));
+    }
+    label
+}
+
+/// Contract agreed in the prompt bar's refinement dialog for a run.
+#[component]
+pub fn RunContractPanel(run_id: String, on_close: EventHandler<()>) -> Element {
+    let mut contract = use_signal(|| None::<Option<ObjectiveContract>>);
+    let mut error = use_signal(|| None::<String>);
+
+    use_effect(use_reactive!(|run_id| {
+        contract.set(None);
+        error.set(None);
+        spawn(async move {
+            match conductor_get_run_state(&run_id).await {
+                Ok(run) => contract.set(Some(run.contract)),
+                Err(e) => error.set(Some(format!("Failed to load run state: {e}"))),
+            }
+        });
+    }));
+
+    rsx! {
+        div {
+            class: "trace-context-panel",
+            div {
+                class: "trace-node-panel-head",
+                h4 { class: "trace-node-title", "Objective contract" }
+                button {
+                    class: "trace-run-toggle",
+                    onclick: move |_| on_close.call(()),
+                    "Close"
+                }
+            }
+            if let Some(message) = error() {
+                ErrorNotice { message, source: "Run state" }
+            }
+            match contract() {
+                None => rsx! {
+                    div { class: "trace-context-empty", "Loading…" }
+                },
+                Some(None) => rsx! {
+                    div { class: "trace-context-empty", "This run was started without refinement." }
+                },
+                Some(Some(contract)) => {
+                    let sources = contract.evidence_requirements.required_source_types.join(", ");
+                    rsx! {
+                        div { class: "trace-contract-objective", "{contract.primary_objective}" }
+                        div { class: "trace-context-meta", "{contract_budget_label(&contract)}" }
+                        ul {
+                            class: "trace-contract-criteria",
+                            for (index, criterion) in contract.success_criteria.iter().enumerate() {
+                                li { key: "{index}", "{criterion}" }
+                            }
+                        }
+                        if contract.evidence_requirements.requires_citations {
+                            div {
+                                class: "trace-context-meta",
+                                if sources.is_empty() {
+                                    "Citations required"
+                                } else {
+                                    "Citations required: {sources}"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared_types::{EvidenceRequirements, ObjectiveConstraints};
+
+    fn contract(attempts_budget: u8, allowed_capabilities: Vec<String>) -> ObjectiveContract {
+        ObjectiveContract {
+            objective_id: "obj-1".to_string(),
+            parent_objective_id: None,
+            primary_objective: "Summarize the Q3 incidents".to_string(),
+            success_criteria: vec!["Every incident is listed".to_string()],
+            constraints: ObjectiveConstraints {
+                max_tool_calls: 30,
+                timeout_ms: 180_000,
+                max_subframe_depth: 2,
+                allowed_capabilities,
+            },
+            attempts_budget,
+            evidence_requirements: EvidenceRequirements {
+                requires_citations: false,
+                min_confidence: 0.6,
+                required_source_types: Vec::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_budget_label_lists_limits_and_whitelist() {
+        assert_eq!(
+            contract_budget_label(&contract(2, Vec::new())),
+            "30 tool calls · 180s · 2 attempts"
+        );
+        assert_eq!(
+            contract_budget_label(&contract(1, vec!["writer".to_string()])),
+            "30 tool calls · 180s · 1 attempt · only writer"
+        );
+    }
+}
@@ -1,3 +1,5 @@
+pub mod context;
+pub mod contract;
 pub mod graph;
 pub mod parsers;
 pub mod styles;
@@ -119,6 +119,81 @@ pub const TRACE_VIEW_STYLES: &str = r#"
     margin-bottom: 0.4rem;
 }
 
+.trace-context-panel {
+    border-bottom: 1px solid var(--border-color, #334155);
+    background: color-mix(in srgb, var(--bg-secondary, #111827) 88%, #020617 12%);
+    padding: 0.6rem;
+    max-height: 40%;
+    overflow: auto;
+}
+
+.trace-context-row {
+    display: flex;
+    flex-direction: column;
+    gap: 0.25rem;
+    padding: 0.35rem 0;
+}
+
+.trace-context-meta {
+    display: flex;
+    flex-wrap: wrap;
+    gap: 0.5rem;
+    font-size: 0.72rem;
+    color: var(--text-secondary, #9ca3af);
+}
+
+.trace-context-fn {
+    color: var(--text-primary, #f8fafc);
+    font-weight: 600;
+}
+
+.trace-context-usage {
+    margin-left: auto;
+}
+
+.trace-context-bar {
+    display: flex;
+    height: 0.7rem;
+    border-radius: 0.25rem;
+    overflow: hidden;
+    background: color-mix(in srgb, var(--bg-primary) 80%, var(--border-color) 20%);
+}
+
+.trace-context-seg {
+    height: 100%;
+    min-width: 1px;
+}
+
+.trace-context-seg.trimmed {
+    background-image: repeating-linear-gradient(
+        45deg,
+        transparent 0 3px,
+        rgba(0, 0, 0, 0.35) 3px 6px
+    );
+}
+
+.trace-contract-objective {
+    color: var(--text-primary, #f8fafc);
+    font-size: 0.8rem;
+    font-weight: 600;
+    white-space: pre-wrap;
+    margin-bottom: 0.3rem;
+}
+
+.trace-contract-criteria {
+    margin: 0.3rem 0;
+    padding-left: 1.1rem;
+    font-size: 0.72rem;
+    color: var(--text-secondary, #9ca3af);
+}
+
+.trace-context-pruned,
+.trace-context-empty {
+    font-size: 0.72rem;
+    font-style: italic;
+    color: var(--text-secondary, #9ca3af);
+}
+
 .trace-node-title {
     margin: 0;
     color: var(--text-primary, #f8fafc);
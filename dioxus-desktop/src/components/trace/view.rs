@@ -8,7 +8,10 @@ use wasm_bindgen::prelude::*;
 use web_sys::WebSocket;
 
 use crate::api::{fetch_latest_log_seq, fetch_logs_events, LogsEvent};
+use crate::components::ErrorNotice;
 
+use super::context::ContextTracePanel;
+use super::contract::RunContractPanel;
 use super::graph::{
     build_graph_edges, build_graph_layout, build_graph_nodes_for_run, build_run_graph_summaries,
     display_actor_label, graph_node_color, graph_status_color, run_status_class,
@@ -33,24 +36,10 @@ use super::types::{
 use super::ws::{build_trace_ws_url, TraceRuntime, TraceWsEvent};
 
 use super::super::styles::CHAT_STYLES;
+use crate::time::{format_timestamp_str, relative_time_str};
 
 // ── Formatting helpers ───────────────────────────────────────────────────────
 
-pub fn format_relative_time(timestamp: &str) -> String {
-    let Ok(dt) = chrono::DateTime::parse_from_rfc3339(timestamp) else {
-        return timestamp.to_string();
-    };
-    let now = js_sys::Date::now() as i64;
-    let then_ms = dt.timestamp_millis();
-    let diff_secs = ((now - then_ms) / 1000).max(0);
-    match diff_secs {
-        0..=59 => "just now".to_string(),
-        60..=3599 => format!("{}m ago", diff_secs / 60),
-        3600..=86399 => format!("{}h ago", diff_secs / 3600),
-        _ => format!("{}d ago", diff_secs / 86400),
-    }
-}
-
 pub fn format_duration_short(ms: i64) -> String {
     if ms >= 1_000 {
         format!("{:.1}s", ms as f64 / 1_000.0)
@@ -200,6 +189,8 @@ pub fn TraceView(desktop_id: String, window_id: String) -> Element {
     let mut trajectory_mode = use_signal(|| TrajectoryMode::Status);
     let mut run_sidebar_open = use_signal(|| true);
     let mut node_sheet_open = use_signal(|| false);
+    let mut context_panel_open = use_signal(|| false);
+    let mut contract_panel_open = use_signal(|| false);
     let mut view_mode = use_signal(|| TraceViewMode::Overview);
     let mut connected = use_signal(|| false);
     let mut error = use_signal(|| None::<String>);
@@ -798,6 +789,22 @@ pub fn TraceView(desktop_id: String, window_id: String) -> Element {
                                 "List"
                             }
                         }
+                        button {
+                            class: "trace-run-toggle",
+                            onclick: {
+                                let next = !context_panel_open();
+                                move |_| context_panel_open.set(next)
+                            },
+                            "Context"
+                        }
+                        button {
+                            class: "trace-run-toggle",
+                            onclick: {
+                                let next = !contract_panel_open();
+                                move |_| contract_panel_open.set(next)
+                            },
+                            "Contract"
+                        }
                     } else {
                         {
                             let run_count_label = if run_summaries.len() == 1 {
@@ -828,7 +835,23 @@ pub fn TraceView(desktop_id: String, window_id: String) -> Element {
                 div {
                     class: "message-bubble system-bubble",
                     style: "margin: 0.6rem;",
-                    "Trace stream error: {message}"
+                    ErrorNotice { message, source: "Trace stream" }
+                }
+            }
+            if !is_overview && context_panel_open() {
+                if let Some(run_id) = active_run_id.clone() {
+                    ContextTracePanel {
+                        run_id,
+                        on_close: move |_| context_panel_open.set(false),
+                    }
+                }
+            }
+            if !is_overview && contract_panel_open() {
+                if let Some(run_id) = active_run_id.clone() {
+                    RunContractPanel {
+                        run_id,
+                        on_close: move |_| contract_panel_open.set(false),
+                    }
                 }
             }
             if run_summaries.is_empty() {
@@ -912,7 +935,7 @@ pub fn TraceView(desktop_id: String, window_id: String) -> Element {
                                         }
                                         span {
                                             class: "trace-run-card-time",
-                                            "{format_relative_time(&timestamp)}"
+                                            "{relative_time_str(&timestamp)}"
                                         }
                                     }
                                 }
@@ -1508,7 +1531,7 @@ pub fn TraceView(desktop_id: String, window_id: String) -> Element {
 
                                                             div {
                                                                 style: "font-size:0.7rem;color:var(--text-muted,#64748b);margin-bottom:0.35rem;",
-                                                                "trace_id: {trace.trace_id} | actor: {trace.actor_id()} | {trace.timestamp()}"
+                                                                "trace_id: {trace.trace_id} | actor: {trace.actor_id()} | {format_timestamp_str(&trace.timestamp())}"
                                                             }
 
                                                             if let Some(started) = &trace.started {
@@ -89,6 +89,34 @@ pub fn overlay_note_lines(overlay: &WriterOverlay) -> Vec<String> {
     lines
 }
 
+/// One run of text in an inline tracked-changes rendering.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrackedChange {
+    Same(String),
+    Added(String),
+    Removed(String),
+}
+
+/// Word-level tracked changes turning `base` into `proposed`, with
+/// consecutive runs of the same kind merged.
+pub fn tracked_changes(base: &str, proposed: &str) -> Vec<TrackedChange> {
+    use similar::{ChangeTag, TextDiff};
+
+    let mut runs: Vec<TrackedChange> = Vec::new();
+    for change in TextDiff::from_words(base, proposed).iter_all_changes() {
+        let text = change.value();
+        match (change.tag(), runs.last_mut()) {
+            (ChangeTag::Equal, Some(TrackedChange::Same(run)))
+            | (ChangeTag::Insert, Some(TrackedChange::Added(run)))
+            | (ChangeTag::Delete, Some(TrackedChange::Removed(run))) => run.push_str(text),
+            (ChangeTag::Equal, _) => runs.push(TrackedChange::Same(text.to_string())),
+            (ChangeTag::Insert, _) => runs.push(TrackedChange::Added(text.to_string())),
+            (ChangeTag::Delete, _) => runs.push(TrackedChange::Removed(text.to_string())),
+        }
+    }
+    runs
+}
+
 pub fn normalize_version_ids(mut ids: Vec<u64>) -> Vec<u64> {
     ids.sort_unstable();
     ids.dedup();
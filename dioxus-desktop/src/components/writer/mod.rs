@@ -1,5 +1,6 @@
 pub mod dialogs;
 pub mod logic;
+pub mod proposals;
 pub mod styles;
 pub mod types;
 pub mod view;
@@ -0,0 +1,102 @@
+//! Inline tracked-changes review of agent proposals
+
+use dioxus::prelude::*;
+
+use crate::api::WriterProposal;
+
+use super::logic::{tracked_changes, TrackedChange};
+
+/// Steps through open proposals one at a time, rendering each against the
+/// head version as inline insertions and deletions.
+#[component]
+pub fn ProposalReview(
+    proposals: Vec<WriterProposal>,
+    head_content: String,
+    on_accept: EventHandler<String>,
+    on_reject: EventHandler<String>,
+    on_repropose: EventHandler<String>,
+) -> Element {
+    let mut selected = use_signal(|| 0usize);
+    let total = proposals.len();
+    let index = selected().min(total.saturating_sub(1));
+    let Some(proposal) = proposals.get(index).cloned() else {
+        return rsx! {};
+    };
+    // A stale proposal is relative to an older base, so diffing it against
+    // the head would show the newer edits as its own deletions.
+    let changes = if proposal.stale {
+        Vec::new()
+    } else {
+        tracked_changes(&head_content, &proposal.proposed_content)
+    };
+    let overlay_id = proposal.overlay_id.clone();
+
+    rsx! {
+        div { class: "writer-proposal-review",
+            div { class: "writer-proposal-header",
+                button {
+                    class: "writer-margin-card-btn",
+                    disabled: index == 0,
+                    onclick: move |_| selected.set(index.saturating_sub(1)),
+                    "<"
+                }
+                span { "Suggestion {index + 1} of {total} · {proposal.author}" }
+                button {
+                    class: "writer-margin-card-btn",
+                    disabled: index + 1 >= total,
+                    onclick: move |_| selected.set(index + 1),
+                    ">"
+                }
+                if proposal.stale {
+                    span { class: "writer-proposal-stale",
+                        "Stale: based on v{proposal.base_version_id}"
+                    }
+                }
+                div { class: "writer-toolbar-spacer" }
+                button {
+                    class: "writer-margin-card-btn",
+                    onclick: {
+                        let overlay_id = overlay_id.clone();
+                        move |_| on_accept.call(overlay_id.clone())
+                    },
+                    if proposal.stale { "Rebase & Accept" } else { "Accept" }
+                }
+                if proposal.stale {
+                    button {
+                        class: "writer-margin-card-btn",
+                        onclick: {
+                            let overlay_id = overlay_id.clone();
+                            move |_| on_repropose.call(overlay_id.clone())
+                        },
+                        "Re-propose"
+                    }
+                }
+                button {
+                    class: "writer-margin-card-btn",
+                    onclick: {
+                        let overlay_id = overlay_id.clone();
+                        move |_| on_reject.call(overlay_id.clone())
+                    },
+                    "Reject"
+                }
+            }
+            if proposal.stale {
+                div { class: "writer-proposal-body writer-proposal-body--stale",
+                    "The document changed since this suggestion was made. Accept to rebase it onto the latest version, or re-propose it to review it against the latest version."
+                }
+            } else {
+                div { class: "writer-proposal-body",
+                    for (i, change) in changes.into_iter().enumerate() {
+                        {
+                            match change {
+                                TrackedChange::Same(text) => rsx! { span { key: "{i}", "{text}" } },
+                                TrackedChange::Added(text) => rsx! { ins { key: "{i}", class: "writer-tracked-added", "{text}" } },
+                                TrackedChange::Removed(text) => rsx! { del { key: "{i}", class: "writer-tracked-removed", "{text}" } },
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -29,40 +29,49 @@
       return "<p><br></p>";
     }
     const blocks = markdown.replace(/\r/g, "").split(/\n\s*\n/);
-    const htmlBlocks = blocks.map(function (block) {
-      const trimmed = block.trim();
-      if (!trimmed) {
-        return "<p><br></p>";
-      }
-      const lines = block.replace(/\r/g, "").split("\n");
-      const first = (lines[0] || "").trim();
-      const rest = lines.slice(1).join("\n").trim();
-
-      if (/^###\s+/.test(first)) {
-        const heading = "<h3>" + inlineMarkdownToHtml(first.replace(/^###\s+/, "")) + "</h3>";
-        if (!rest) return heading;
-        return heading + "<p>" + inlineMarkdownToHtml(rest).replace(/\n/g, "<br>") + "</p>";
-      }
-      if (/^##\s+/.test(first)) {
-        const heading = "<h2>" + inlineMarkdownToHtml(first.replace(/^##\s+/, "")) + "</h2>";
-        if (!rest) return heading;
-        return heading + "<p>" + inlineMarkdownToHtml(rest).replace(/\n/g, "<br>") + "</p>";
-      }
-      if (/^#\s+/.test(first)) {
-        const heading = "<h1>" + inlineMarkdownToHtml(first.replace(/^#\s+/, "")) + "</h1>";
-        if (!rest) return heading;
-        return heading + "<p>" + inlineMarkdownToHtml(rest).replace(/\n/g, "<br>") + "</p>";
-      }
-      return (
-        "<p>" +
-        inlineMarkdownToHtml(block)
-          .replace(/\n/g, "<br>") +
-        "</p>"
+    const htmlBlocks = blocks.map(function (block, index) {
+      // Tag every element with its block index so lock highlights can find
+      // the elements a markdown block rendered to.
+      return blockToHtml(block).replace(
+        /<(h1|h2|h3|p)>/g,
+        '<$1 data-block="' + index + '">',
       );
     });
     return htmlBlocks.join("");
   }
 
+  function blockToHtml(block) {
+    const trimmed = block.trim();
+    if (!trimmed) {
+      return "<p><br></p>";
+    }
+    const lines = block.replace(/\r/g, "").split("\n");
+    const first = (lines[0] || "").trim();
+    const rest = lines.slice(1).join("\n").trim();
+
+    if (/^###\s+/.test(first)) {
+      const heading = "<h3>" + inlineMarkdownToHtml(first.replace(/^###\s+/, "")) + "</h3>";
+      if (!rest) return heading;
+      return heading + "<p>" + inlineMarkdownToHtml(rest).replace(/\n/g, "<br>") + "</p>";
+    }
+    if (/^##\s+/.test(first)) {
+      const heading = "<h2>" + inlineMarkdownToHtml(first.replace(/^##\s+/, "")) + "</h2>";
+      if (!rest) return heading;
+      return heading + "<p>" + inlineMarkdownToHtml(rest).replace(/\n/g, "<br>") + "</p>";
+    }
+    if (/^#\s+/.test(first)) {
+      const heading = "<h1>" + inlineMarkdownToHtml(first.replace(/^#\s+/, "")) + "</h1>";
+      if (!rest) return heading;
+      return heading + "<p>" + inlineMarkdownToHtml(rest).replace(/\n/g, "<br>") + "</p>";
+    }
+    return (
+      "<p>" +
+      inlineMarkdownToHtml(block)
+        .replace(/\n/g, "<br>") +
+      "</p>"
+    );
+  }
+
   function saveSelection(root) {
     const sel = window.getSelection();
     if (!sel || sel.rangeCount === 0) {
@@ -271,10 +280,22 @@
     return tops;
   }
 
+  function markLockedBlocks(id, indices) {
+    const el = document.getElementById(id);
+    if (!el) {
+      return;
+    }
+    const locked = new Set((indices || []).map(String));
+    el.querySelectorAll("[data-block]").forEach(function (node) {
+      node.classList.toggle("writer-block--locked", locked.has(node.dataset.block));
+    });
+  }
+
   window.__writerProseInterop = {
     setMarkdown: setMarkdown,
     getMarkdown: getMarkdown,
     applyShortcuts: applyShortcuts,
     computeBubbleTops: computeBubbleTops,
+    markLockedBlocks: markLockedBlocks,
   };
 })();
@@ -317,6 +317,50 @@ pub const WRITER_STYLES: &str = r#"
     color: var(--text-primary);
 }
 
+.writer-proposal-review {
+    border-bottom: 1px solid var(--border-color);
+    background: var(--titlebar-bg);
+    font-size: 0.8rem;
+    flex-shrink: 0;
+}
+
+.writer-proposal-header {
+    display: flex;
+    align-items: center;
+    gap: 0.4rem;
+    padding: 0.3rem 0.75rem;
+    color: var(--text-secondary);
+}
+
+.writer-proposal-stale {
+    color: var(--warning-bg);
+    font-weight: 600;
+}
+
+.writer-proposal-body {
+    max-height: 30vh;
+    overflow-y: auto;
+    padding: 0.4rem 0.75rem 0.6rem;
+    white-space: pre-wrap;
+    line-height: 1.45;
+    color: var(--text-primary);
+}
+
+.writer-proposal-body--stale {
+    color: var(--text-secondary);
+    white-space: normal;
+}
+
+.writer-tracked-added {
+    background: rgba(34, 197, 94, 0.18);
+    text-decoration: underline;
+}
+
+.writer-tracked-removed {
+    background: rgba(239, 68, 68, 0.18);
+    text-decoration: line-through;
+}
+
 .writer-prose-column {
     display: flex;
     flex-direction: column;
@@ -377,6 +421,36 @@ pub const WRITER_STYLES: &str = r#"
     margin: 0.35rem 0 0.8rem;
 }
 
+/* Blocks an agent run has locked */
+.writer-prose-body .writer-block--locked {
+    position: relative;
+    border-radius: 4px;
+    background: linear-gradient(
+        100deg,
+        transparent 30%,
+        color-mix(in srgb, var(--accent-bg) 18%, transparent) 50%,
+        transparent 70%
+    );
+    background-size: 250% 100%;
+    animation: writer-block-shimmer 1.8s linear infinite;
+}
+
+.writer-prose-body .writer-block--locked::after {
+    content: "agent editing";
+    position: absolute;
+    top: 0;
+    right: 0;
+    font-size: 0.65rem;
+    color: var(--text-secondary);
+    pointer-events: none;
+    user-select: none;
+}
+
+@keyframes writer-block-shimmer {
+    from { background-position: 100% 0; }
+    to { background-position: -150% 0; }
+}
+
 .writer-note-toggle {
     position: absolute;
     top: 0.8rem;
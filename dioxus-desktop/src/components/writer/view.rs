@@ -6,18 +6,22 @@ use std::cell::Cell;
 
 use crate::api::files_api::list_directory;
 use crate::api::{
-    conductor_get_run_status, conductor_list_runs, writer_dismiss_overlay, writer_open,
-    writer_prompt, writer_save, writer_save_version, writer_version, writer_versions,
-    WriterOverlay,
+    conductor_get_run_status, conductor_list_runs, writer_accept_proposal, writer_dismiss_overlay,
+    writer_list_proposals, writer_open, writer_prompt, writer_redo, writer_repropose_proposal,
+    writer_save, writer_save_version, writer_set_lock_strictness, writer_set_review_mode,
+    writer_undo, writer_version, writer_versions, WriterOverlay, WriterProposal,
 };
+use crate::components::ErrorNotice;
 use crate::desktop::state::{ActiveWriterRun, ACTIVE_WRITER_RUNS};
+use crate::time::format_timestamp;
 use shared_types::{
     ChangesetImpact, ConductorRunStatus, ConductorRunStatusResponse, PatchOp, PatchSource,
-    WriterRunStatusKind,
+    WriterLockStrictness, WriterRunStatusKind,
 };
 
 use super::dialogs::render_dialog;
 use super::logic::*;
+use super::proposals::ProposalReview;
 use super::styles::*;
 use super::types::*;
 
@@ -41,7 +45,9 @@ fn map_conductor_to_writer_status(status: ConductorRunStatus) -> WriterRunStatus
     match status {
         ConductorRunStatus::Initializing => WriterRunStatusKind::Initializing,
         ConductorRunStatus::Running => WriterRunStatusKind::Running,
-        ConductorRunStatus::WaitingForCalls => WriterRunStatusKind::WaitingForWorker,
+        ConductorRunStatus::WaitingForCalls | ConductorRunStatus::WaitingForProvider => {
+            WriterRunStatusKind::WaitingForWorker
+        }
         ConductorRunStatus::Completing => WriterRunStatusKind::Completing,
         ConductorRunStatus::Completed => WriterRunStatusKind::Completed,
         ConductorRunStatus::Failed => WriterRunStatusKind::Failed,
@@ -108,6 +114,16 @@ fn prose_apply_shortcuts(editor_id: &str) {
     let _ = js_sys::eval(&script);
 }
 
+fn prose_mark_locked_blocks(editor_id: &str, indices: &[usize]) {
+    ensure_prose_interop_loaded();
+    let id = serde_json::to_string(editor_id).unwrap_or_else(|_| "\"\"".to_string());
+    let indices = serde_json::to_string(indices).unwrap_or_else(|_| "[]".to_string());
+    let script = format!(
+        "window.__writerProseInterop && window.__writerProseInterop.markLockedBlocks({id}, {indices});"
+    );
+    let _ = js_sys::eval(&script);
+}
+
 fn prose_compute_bubble_tops(editor_id: &str, count: usize) -> Vec<i32> {
     if count == 0 {
         return Vec::new();
@@ -158,6 +174,7 @@ async fn reconcile_run_state_on_open(
                 pending_patches: Vec::new(),
                 last_applied_revision: revision,
                 recent_changesets: Vec::new(),
+                block_locks: Vec::new(),
             },
         );
     } else if let Some(existing) = runs.get_mut(opened_path) {
@@ -226,6 +243,9 @@ pub fn WriterView(desktop_id: String, window_id: String, initial_path: String) -
     let mut selected_version_source = use_signal(String::new);
     let mut selected_version_source_refs = use_signal(Vec::<String>::new);
     let mut selected_overlays = use_signal(|| Vec::<WriterOverlay>::new());
+    let mut proposals = use_signal(Vec::<WriterProposal>::new);
+    let mut review_mode = use_signal(|| false);
+    let mut lock_strictness = use_signal(WriterLockStrictness::default);
     let mut typing_locked = use_signal(|| false);
     let mut new_version_available = use_signal(|| false);
     let mut right_margin_open = use_signal(|| false);
@@ -236,6 +256,25 @@ pub fn WriterView(desktop_id: String, window_id: String, initial_path: String) -
     let mut last_applied_revision = use_signal(|| 0u64);
     let mut run_status = use_signal(|| None::<WriterRunStatusKind>);
 
+    // Proposals carry server-computed stale flags, so they are re-fetched
+    // whenever a patch lands rather than patched locally.
+    let refresh_proposals = use_callback(move |_: ()| {
+        let current_path = path();
+        if extract_run_id_from_document_path(&current_path).is_none() {
+            proposals.set(Vec::new());
+            review_mode.set(false);
+            lock_strictness.set(WriterLockStrictness::default());
+            return;
+        }
+        spawn(async move {
+            if let Ok(response) = writer_list_proposals(&current_path).await {
+                proposals.set(response.proposals);
+                review_mode.set(response.review_mode);
+                lock_strictness.set(response.lock_strictness);
+            }
+        });
+    });
+
     use_effect(move || {
         let path_str = path();
         if path_str.is_empty() {
@@ -355,6 +394,7 @@ pub fn WriterView(desktop_id: String, window_id: String, initial_path: String) -
                                         }
                                         selected_version_source_refs.set(refs);
                                         selected_overlays.set(version_response.overlays);
+                                        refresh_proposals.call(());
                                     }
                                     Err(_) => {
                                         selected_overlays.set(Vec::new());
@@ -528,6 +568,7 @@ pub fn WriterView(desktop_id: String, window_id: String, initial_path: String) -
                             selected_overlays_for_effect.set(merged_overlays);
                         }
                         new_version_available_for_effect.set(false);
+                        refresh_proposals.call(());
 
                         if let Some(run) = ACTIVE_WRITER_RUNS.write().get_mut(&current_path) {
                             run.last_applied_revision = highest_revision;
@@ -579,6 +620,38 @@ pub fn WriterView(desktop_id: String, window_id: String, initial_path: String) -
         });
     }
 
+    // Shimmer the blocks an agent run has locked, waking at the next expiry
+    // so lapsed locks clear without another event.
+    {
+        let prose_editor_id = prose_editor_id.clone();
+        let mut lock_tick = use_signal(|| 0u64);
+        use_effect(move || {
+            let _ = lock_tick();
+            if writer_view_mode() != WriterViewMode::Editor || loading() {
+                return;
+            }
+            let now = chrono::Utc::now();
+            let (locked, next_expiry) = ACTIVE_WRITER_RUNS
+                .read()
+                .get(&path())
+                .map(|run| (run.locked_block_ids(now), run.next_lock_expiry(now)))
+                .unwrap_or_default();
+            let indices: Vec<usize> = shared_types::writer_blocks(&content())
+                .into_iter()
+                .filter(|block| locked.contains(&block.block_id))
+                .map(|block| block.index)
+                .collect();
+            prose_mark_locked_blocks(&prose_editor_id, &indices);
+            if let Some(expiry) = next_expiry {
+                let wait_ms = (expiry - now).num_milliseconds().clamp(0, u32::MAX as i64) as u32;
+                spawn(async move {
+                    TimeoutFuture::new(wait_ms).await;
+                    lock_tick.with_mut(|tick| *tick += 1);
+                });
+            }
+        });
+    }
+
     let handle_save = use_callback(move |_| {
         if readonly() || matches!(save_state(), SaveState::Saving) {
             return;
@@ -709,11 +782,64 @@ pub fn WriterView(desktop_id: String, window_id: String, initial_path: String) -
         });
     }
 
+    // Undo/redo of saved user edits is server-side for run documents so the
+    // history stays in the event log. With unsaved edits (or a save in
+    // flight) Ctrl+Z stays with the editor's own undo.
+    let handle_revert = use_callback(move |redo: bool| {
+        let current_path = path();
+        if readonly()
+            || typing_locked()
+            || !matches!(save_state(), SaveState::Clean)
+            || extract_run_id_from_document_path(&current_path).is_none()
+        {
+            return;
+        }
+        spawn(async move {
+            let result = if redo {
+                writer_redo(&current_path).await
+            } else {
+                writer_undo(&current_path).await
+            };
+            match result {
+                Ok(reverted) => {
+                    let version = reverted.version;
+                    content.set(version.content.clone());
+                    selected_version_id.set(Some(version.version_id));
+                    selected_version_content.set(version.content.clone());
+                    prompt_base_content.set(version.content);
+                    selected_version_source.set(version.source);
+                    let mut ids = version_ids();
+                    if !ids.contains(&version.version_id) {
+                        ids.push(version.version_id);
+                        ids.sort_unstable();
+                        version_ids.set(ids);
+                    }
+                    revision.set(revision().saturating_add(1));
+                }
+                Err(e) if e.starts_with("CONFLICT:") => {}
+                Err(e) => {
+                    let action = if redo { "Redo" } else { "Undo" };
+                    save_state.set(SaveState::Error(format!("{action} failed: {e}")));
+                }
+            }
+        });
+    });
+
     let on_keydown = use_callback(move |e: KeyboardEvent| {
         if e.key() == Key::Character("s".to_string()) && e.modifiers().ctrl() {
             e.prevent_default();
             handle_save.call(());
         }
+        let is_z = matches!(e.key(), Key::Character(ref c) if c.eq_ignore_ascii_case("z"));
+        if is_z
+            && e.modifiers().ctrl()
+            && !typing_locked()
+            && matches!(save_state(), SaveState::Clean)
+            && extract_run_id_from_document_path(&path()).is_some()
+        {
+            e.prevent_default();
+            handle_revert.call(e.modifiers().shift());
+        }
     });
 
     let handle_reload_latest = use_callback(move |(new_content, new_revision): (String, u64)| {
@@ -911,60 +1037,41 @@ pub fn WriterView(desktop_id: String, window_id: String, initial_path: String) -
         });
     });
 
+    // Accepting goes through the server so the change stays attributed to
+    // the proposing agent and stale proposals are rebased onto the head.
     let handle_accept_overlay = use_callback(move |overlay_id: String| {
-        let overlays = selected_overlays();
-        let Some(overlay) = overlays
-            .iter()
-            .find(|item| item.overlay_id == overlay_id)
-            .cloned()
-        else {
-            return;
-        };
-
-        if selected_version_id() != Some(overlay.base_version_id) {
-            save_state.set(SaveState::Error(
-                "Cannot accept suggestion on a different base version".to_string(),
-            ));
-            return;
-        }
-
-        let merged_content = apply_patch_ops(&selected_version_content(), &overlay.diff_ops);
         let current_path = path();
         save_state.set(SaveState::Saving);
 
         spawn(async move {
-            match writer_save_version(
-                &current_path,
-                &merged_content,
-                Some(overlay.base_version_id),
-            )
-            .await
-            {
-                Ok(saved) => {
-                    content.set(saved.version.content.clone());
-                    selected_version_content.set(saved.version.content.clone());
-                    prompt_base_content.set(saved.version.content.clone());
-                    selected_version_source.set(saved.version.source.clone());
-                    let mut refs = saved.version.selected_source_refs.clone();
-                    for value in saved.version.observed_source_refs.clone() {
+            match writer_accept_proposal(&current_path, &overlay_id).await {
+                Ok(accepted) => {
+                    let version = accepted.version;
+                    content.set(version.content.clone());
+                    selected_version_content.set(version.content.clone());
+                    prompt_base_content.set(version.content.clone());
+                    selected_version_source.set(version.source.clone());
+                    let mut refs = version.selected_source_refs.clone();
+                    for value in version.observed_source_refs.clone() {
                         if !refs.iter().any(|existing| existing == &value) {
                             refs.push(value);
                         }
                     }
                     selected_version_source_refs.set(refs);
-                    selected_version_id.set(Some(saved.version.version_id));
+                    selected_version_id.set(Some(version.version_id));
                     let mut ids = version_ids();
-                    if !ids.contains(&saved.version.version_id) {
-                        ids.push(saved.version.version_id);
+                    if !ids.contains(&version.version_id) {
+                        ids.push(version.version_id);
                         ids.sort_unstable();
                         version_ids.set(ids);
                     }
                     selected_overlays.set(
                         selected_overlays()
                             .into_iter()
-                            .filter(|item| item.overlay_id != overlay.overlay_id)
+                            .filter(|item| item.overlay_id != overlay_id)
                             .collect(),
                     );
+                    refresh_proposals.call(());
                     typing_locked.set(false);
                     save_state.set(SaveState::Saved);
                     spawn(async move {
@@ -977,6 +1084,41 @@ pub fn WriterView(desktop_id: String, window_id: String, initial_path: String) -
         });
     });
 
+    let handle_repropose_overlay = use_callback(move |overlay_id: String| {
+        let current_path = path();
+        spawn(async move {
+            match writer_repropose_proposal(&current_path, &overlay_id).await {
+                Ok(_response) => refresh_proposals.call(()),
+                Err(e) => save_state.set(SaveState::Error(format!("Re-propose failed: {e}"))),
+            }
+        });
+    });
+
+    let handle_toggle_review_mode = use_callback(move |_| {
+        let current_path = path();
+        let enabled = !review_mode();
+        spawn(async move {
+            match writer_set_review_mode(&current_path, enabled).await {
+                Ok(review_mode_now) => review_mode.set(review_mode_now),
+                Err(e) => save_state.set(SaveState::Error(format!("Review mode failed: {e}"))),
+            }
+        });
+    });
+
+    let handle_toggle_lock_strictness = use_callback(move |_| {
+        let current_path = path();
+        let strictness = match lock_strictness() {
+            WriterLockStrictness::Warn => WriterLockStrictness::Reject,
+            WriterLockStrictness::Reject => WriterLockStrictness::Warn,
+        };
+        spawn(async move {
+            match writer_set_lock_strictness(&current_path, strictness).await {
+                Ok(strictness_now) => lock_strictness.set(strictness_now),
+                Err(e) => save_state.set(SaveState::Error(format!("Lock setting failed: {e}"))),
+            }
+        });
+    });
+
     let handle_dismiss_overlay = use_callback(move |overlay_id: String| {
         let current_path = path();
         spawn(async move {
@@ -988,6 +1130,7 @@ pub fn WriterView(desktop_id: String, window_id: String, initial_path: String) -
                             .filter(|item| item.overlay_id != overlay_id)
                             .collect(),
                     );
+                    refresh_proposals.call(());
                 }
                 Err(e) => save_state.set(SaveState::Error(format!("Dismiss failed: {e}"))),
             }
@@ -1019,6 +1162,10 @@ pub fn WriterView(desktop_id: String, window_id: String, initial_path: String) -
     let can_go_next =
         current_selected_version_index.is_some_and(|idx| idx + 1 < current_total_versions);
     let current_selected_overlays = selected_overlays();
+    let current_proposals = proposals();
+    let current_review_mode = review_mode();
+    let current_lock_strictness = lock_strictness();
+    let is_run_document = extract_run_id_from_document_path(&current_path).is_some();
     let current_new_version_available = new_version_available();
 
     let current_run_state = {
@@ -1204,6 +1351,7 @@ pub fn WriterView(desktop_id: String, window_id: String, initial_path: String) -
                                                     ConductorRunStatus::Initializing => ("init", "writer-status--initializing"),
                                                     ConductorRunStatus::Running => ("running", "writer-status--running"),
                                                     ConductorRunStatus::WaitingForCalls => ("waiting", "writer-status--waiting"),
+                                                    ConductorRunStatus::WaitingForProvider => ("queued", "writer-status--waiting"),
                                                     ConductorRunStatus::Completing => ("completing", "writer-status--completing"),
                                                     ConductorRunStatus::Completed => ("done", "writer-status--completed"),
                                                     ConductorRunStatus::Failed => ("failed", "writer-status--failed"),
@@ -1213,7 +1361,7 @@ pub fn WriterView(desktop_id: String, window_id: String, initial_path: String) -
                                             }
                                         };
 
-                                        let date_display = run.created_at.format("%Y-%m-%d %H:%M").to_string();
+                                        let date_display = format_timestamp(&run.created_at);
                                         let title = if run.objective.len() > 80 {
                                             format!("{}...", &run.objective[..80])
                                         } else {
@@ -1364,6 +1512,20 @@ pub fn WriterView(desktop_id: String, window_id: String, initial_path: String) -
                             onclick: move |_| show_save_as_dialog.call(()),
                             "Save As..."
                         }
+                        if is_run_document {
+                            button {
+                                class: if current_review_mode { "writer-toolbar-btn-accent" } else { "writer-toolbar-btn" },
+                                title: "Park agent edits as suggestions to review",
+                                onclick: move |_| handle_toggle_review_mode.call(()),
+                                if current_review_mode { "Review: On" } else { "Review: Off" }
+                            }
+                            button {
+                                class: "writer-toolbar-btn",
+                                title: "Whether edits to blocks the agent is editing are allowed with a warning or refused",
+                                onclick: move |_| handle_toggle_lock_strictness.call(()),
+                                if current_lock_strictness == WriterLockStrictness::Reject { "Agent blocks: Locked" } else { "Agent blocks: Warn" }
+                            }
+                        }
                         {render_save_status(&current_save_state, dismiss_saved.clone())}
                     }
 
@@ -1408,7 +1570,7 @@ pub fn WriterView(desktop_id: String, window_id: String, initial_path: String) -
                 if let SaveState::Error(ref msg) = current_save_state {
                     div {
                         style: "padding: 0.75rem 1rem; background: var(--danger-bg); color: var(--danger-text); font-size: 0.875rem; border-bottom: 1px solid var(--danger-bg); display: flex; justify-content: space-between; align-items: center;",
-                        div { "Error: {msg}" }
+                        ErrorNotice { message: msg.clone() }
                         button {
                             style: "background: transparent; border: 1px solid var(--danger-text); color: var(--danger-text); cursor: pointer; padding: 0.25rem 0.5rem; border-radius: 0.25rem; font-size: 0.75rem;",
                             onclick: move |_| clear_error.call(()),
@@ -1441,6 +1603,16 @@ pub fn WriterView(desktop_id: String, window_id: String, initial_path: String) -
                     }
                 }
 
+                if !current_proposals.is_empty() {
+                    ProposalReview {
+                        proposals: current_proposals.clone(),
+                        head_content: selected_version_content(),
+                        on_accept: move |overlay_id| handle_accept_overlay.call(overlay_id),
+                        on_reject: move |overlay_id| handle_dismiss_overlay.call(overlay_id),
+                        on_repropose: move |overlay_id| handle_repropose_overlay.call(overlay_id),
+                    }
+                }
+
                 div {
                     class: "writer-layout",
 
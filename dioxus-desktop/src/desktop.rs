@@ -5,7 +5,9 @@ use dioxus::prelude::*;
 mod actions;
 mod apps;
 mod components;
+mod connection;
 mod effects;
+mod long_poll;
 mod shell;
 pub mod state;
 mod theme;
@@ -13,6 +15,31 @@ mod ws;
 
 pub use shell::DesktopShell;
 
+/// Desktop shown when the URL names none.
+pub const DEFAULT_DESKTOP_ID: &str = "default-desktop";
+
+/// The desktop named by the `desktop` query parameter, if any.
+pub fn desktop_id_from_url() -> Option<String> {
+    let search = web_sys::window()?.location().search().ok()?;
+    search
+        .trim_start_matches('?')
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("desktop="))
+        .and_then(|value| js_sys::decode_uri_component(value).ok()?.as_string())
+        .filter(|id| !id.is_empty())
+}
+
+/// Navigate to another desktop.
+pub fn open_desktop(desktop_id: &str) {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    let encoded = js_sys::encode_uri_component(desktop_id)
+        .as_string()
+        .unwrap_or_else(|| desktop_id.to_string());
+    let _ = window.location().set_search(&format!("desktop={encoded}"));
+}
+
 #[component]
 pub fn Desktop(desktop_id: String) -> Element {
     rsx! {
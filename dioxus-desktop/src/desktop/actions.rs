@@ -3,7 +3,7 @@ use shared_types::{AppDefinition, DesktopState};
 
 use crate::api::{
     close_window, focus_window, maximize_window, minimize_window, move_window, open_window,
-    resize_window, restore_window, MaximizeWindowRequest,
+    resize_window, restore_window, set_desktop_bounds, MaximizeWindowRequest,
 };
 use crate::desktop::state::{
     focus_window_and_raise_z, push_window_and_activate, remove_window_and_reselect_active,
@@ -147,6 +147,16 @@ pub async fn resize_window_action(desktop_id: String, window_id: String, width:
     }
 }
 
+/// Keep the server's desktop bounds in step with the window canvas.
+pub async fn sync_desktop_bounds_action(desktop_id: String) {
+    let Some(bounds) = maximize_work_area_request() else {
+        return;
+    };
+    if let Err(e) = set_desktop_bounds(&desktop_id, bounds).await {
+        dioxus_logger::tracing::error!("Failed to set desktop bounds: {}", e);
+    }
+}
+
 pub async fn minimize_window_action(desktop_id: String, window_id: String) {
     if let Err(e) = minimize_window(&desktop_id, &window_id).await {
         dioxus_logger::tracing::error!("Failed to minimize window: {}", e);
@@ -42,6 +42,14 @@ pub fn core_apps() -> Vec<AppDefinition> {
             default_width: 900,
             default_height: 600,
         },
+        AppDefinition {
+            id: "research".to_string(),
+            name: "Research".to_string(),
+            icon: "🔬".to_string(),
+            component_code: "ResearchApp".to_string(),
+            default_width: 920,
+            default_height: 640,
+        },
         AppDefinition {
             id: "settings".to_string(),
             name: "Settings".to_string(),
@@ -60,6 +68,7 @@ pub fn get_app_icon(app_id: &str) -> &'static str {
         "files" => "📁",
         "logs" => "📡",
         "trace" => "🔍",
+        "research" => "🔬",
         "settings" => "⚙️",
         _ => "📱",
     }
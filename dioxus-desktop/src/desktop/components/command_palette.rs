@@ -0,0 +1,254 @@
+use dioxus::prelude::*;
+use gloo_timers::future::TimeoutFuture;
+use shared_types::{SearchHit, SearchHitKind, SearchTarget, WriterWindowProps};
+
+use crate::api::{open_window, search_content};
+use crate::time::format_timestamp;
+
+/// Typing this prefix in the prompt bar switches it into content search.
+pub const SEARCH_PREFIX: char = '?';
+
+const SEARCH_LIMIT: u32 = 20;
+const SEARCH_DEBOUNCE_MS: u32 = 150;
+
+/// Search text when the prompt input is in command palette mode.
+pub fn palette_query(input: &str) -> Option<&str> {
+    input
+        .trim_start()
+        .strip_prefix(SEARCH_PREFIX)
+        .map(str::trim)
+}
+
+fn kind_label(kind: SearchHitKind) -> &'static str {
+    match kind {
+        SearchHitKind::Chat => "Chat",
+        SearchHitKind::Document => "Doc",
+        SearchHitKind::Artifact => "Artifact",
+    }
+}
+
+fn hit_subtitle(hit: &SearchHit) -> String {
+    let when = format_timestamp(&hit.timestamp);
+    match &hit.target {
+        SearchTarget::Chat { thread_id, .. } if !thread_id.is_empty() => {
+            format!("{when} · thread {thread_id}")
+        }
+        SearchTarget::Document { revision, .. } => format!("{when} · v{revision}"),
+        SearchTarget::Artifact {
+            artifact_kind,
+            run_id: Some(run_id),
+            ..
+        } => format!("{when} · {artifact_kind} · {run_id}"),
+        _ => when.to_string(),
+    }
+}
+
+/// Writer window that a hit deep-links to, if it has one.
+fn writer_props_for_hit(hit: &SearchHit) -> Option<WriterWindowProps> {
+    let (path, preview_mode, run_id) = match &hit.target {
+        SearchTarget::Chat { document_path, .. } => (document_path.clone()?, false, None),
+        SearchTarget::Document { path, run_id, .. } => (path.clone(), false, run_id.clone()),
+        SearchTarget::Artifact { path, run_id, .. } => (path.clone()?, true, run_id.clone()),
+    };
+    Some(WriterWindowProps {
+        x: 100,
+        y: 100,
+        width: 900,
+        height: 680,
+        path,
+        preview_mode,
+        run_id,
+    })
+}
+
+#[derive(Props, Clone, PartialEq)]
+pub struct CommandPaletteProps {
+    /// Raw prompt input; searched while it starts with `SEARCH_PREFIX`.
+    pub input: Signal<String>,
+    pub desktop_id: String,
+    pub on_close: Callback<()>,
+}
+
+#[component]
+pub fn CommandPalette(props: CommandPaletteProps) -> Element {
+    let input = props.input;
+    let on_close = props.on_close;
+    let desktop_id_signal = use_signal(|| props.desktop_id.clone());
+    let mut hits = use_signal(Vec::<SearchHit>::new);
+    let mut loading = use_signal(|| false);
+    let mut error = use_signal(|| None::<String>);
+    let mut kind_filter = use_signal(|| None::<SearchHitKind>);
+    let mut generation = use_signal(|| 0_u64);
+
+    use_effect(move || {
+        let query = palette_query(&input()).unwrap_or_default().to_string();
+        let kinds: Vec<SearchHitKind> = kind_filter().into_iter().collect();
+        let current = generation.peek().wrapping_add(1);
+        generation.set(current);
+
+        if query.is_empty() {
+            hits.set(Vec::new());
+            loading.set(false);
+            error.set(None);
+            return;
+        }
+
+        spawn(async move {
+            TimeoutFuture::new(SEARCH_DEBOUNCE_MS).await;
+            // A newer keystroke superseded this query.
+            if *generation.peek() != current {
+                return;
+            }
+            loading.set(true);
+            let result = search_content(&query, &kinds, SEARCH_LIMIT).await;
+            if *generation.peek() != current {
+                return;
+            }
+            loading.set(false);
+            match result {
+                Ok(response) => {
+                    error.set(None);
+                    hits.set(response.hits);
+                }
+                Err(e) => error.set(Some(e)),
+            }
+        });
+    });
+
+    let open_hit = use_callback(move |hit: SearchHit| {
+        let desktop_id = desktop_id_signal.read().clone();
+        on_close.call(());
+        let Some(props) = writer_props_for_hit(&hit) else {
+            return;
+        };
+        spawn(async move {
+            let props_value = match serde_json::to_value(&props) {
+                Ok(value) => value,
+                Err(e) => {
+                    dioxus_logger::tracing::error!("Failed to serialize writer props: {}", e);
+                    return;
+                }
+            };
+            if let Err(e) = open_window(&desktop_id, "writer", "Writer", Some(props_value)).await {
+                dioxus_logger::tracing::error!("Failed to open search result: {}", e);
+            }
+        });
+    });
+
+    let filters: [(Option<SearchHitKind>, &str); 4] = [
+        (None, "All"),
+        (Some(SearchHitKind::Chat), "Chat"),
+        (Some(SearchHitKind::Document), "Docs"),
+        (Some(SearchHitKind::Artifact), "Artifacts"),
+    ];
+    let has_query = palette_query(&input()).is_some_and(|q| !q.is_empty());
+
+    rsx! {
+        div {
+            class: "command-palette",
+            style: "position: absolute; left: 0; right: 0; bottom: calc(100% + 0.5rem); max-height: 60vh; display: flex; flex-direction: column; background: var(--window-bg, #1f2937); border: 1px solid var(--border-color, #374151); border-radius: var(--radius-lg, 12px); box-shadow: var(--shadow-lg, 0 10px 40px rgba(0,0,0,0.5)); overflow: hidden; z-index: 2100;",
+
+            div {
+                class: "command-palette-filters",
+                style: "display: flex; align-items: center; gap: 0.35rem; padding: 0.5rem 0.75rem; border-bottom: 1px solid var(--border-color, #374151);",
+
+                for (kind, label) in filters {
+                    button {
+                        key: "{label}",
+                        style: if kind_filter() == kind {
+                            "padding: 0.15rem 0.6rem; border-radius: 999px; border: 1px solid var(--accent-bg, #3b82f6); background: var(--accent-bg, #3b82f6); color: var(--accent-text, white); font-size: 0.75rem; cursor: pointer;"
+                        } else {
+                            "padding: 0.15rem 0.6rem; border-radius: 999px; border: 1px solid var(--border-color, #374151); background: transparent; color: var(--text-secondary, #9ca3af); font-size: 0.75rem; cursor: pointer;"
+                        },
+                        onclick: move |_| kind_filter.set(kind),
+                        "{label}"
+                    }
+                }
+
+                if loading() {
+                    div {
+                        style: "margin-left: auto; width: 12px; height: 12px; border: 2px solid var(--border-color, #374151); border-top-color: var(--accent-bg, #3b82f6); border-radius: 50%; animation: spin 1s linear infinite;",
+                    }
+                }
+            }
+
+            div {
+                class: "command-palette-results",
+                style: "overflow-y: auto;",
+
+                if let Some(message) = error() {
+                    div {
+                        style: "padding: 0.75rem; color: var(--danger-text, #ef4444); font-size: 0.8rem;",
+                        "{message}"
+                    }
+                } else if !has_query {
+                    div {
+                        style: "padding: 0.75rem; color: var(--text-muted, #6b7280); font-size: 0.8rem;",
+                        "Search chats, documents and reports"
+                    }
+                } else if hits.read().is_empty() && !loading() {
+                    div {
+                        style: "padding: 0.75rem; color: var(--text-muted, #6b7280); font-size: 0.8rem;",
+                        "No matches"
+                    }
+                }
+
+                for hit in hits() {
+                    button {
+                        key: "{hit.seq}",
+                        class: "command-palette-hit",
+                        style: "display: flex; flex-direction: column; gap: 0.2rem; width: 100%; padding: 0.6rem 0.75rem; background: transparent; border: none; border-bottom: 1px solid var(--border-color, #374151); color: var(--text-primary, white); text-align: left; cursor: pointer;",
+                        onclick: {
+                            let hit = hit.clone();
+                            move |_| open_hit.call(hit.clone())
+                        },
+
+                        div {
+                            style: "display: flex; align-items: center; gap: 0.5rem; min-width: 0;",
+                            span {
+                                style: "flex-shrink: 0; padding: 0 0.4rem; border-radius: var(--radius-sm, 4px); background: var(--hover-bg, rgba(255,255,255,0.1)); color: var(--text-secondary, #9ca3af); font-size: 0.7rem;",
+                                "{kind_label(hit.kind)}"
+                            }
+                            span {
+                                style: "font-size: 0.8rem; font-weight: 600; overflow: hidden; text-overflow: ellipsis; white-space: nowrap;",
+                                "{hit.title}"
+                            }
+                            span {
+                                style: "margin-left: auto; flex-shrink: 0; color: var(--text-muted, #6b7280); font-size: 0.7rem;",
+                                "{hit_subtitle(&hit)}"
+                            }
+                        }
+
+                        div {
+                            style: "font-size: 0.8rem; color: var(--text-secondary, #9ca3af); line-height: 1.4;",
+                            for (idx, segment) in hit.excerpt.iter().enumerate() {
+                                if segment.highlight {
+                                    mark {
+                                        key: "{idx}",
+                                        style: "background: var(--warning-bg, #f59e0b); color: var(--bg-primary, #0f172a); border-radius: 2px; padding: 0 1px;",
+                                        "{segment.text}"
+                                    }
+                                } else {
+                                    span { key: "{idx}", "{segment.text}" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::palette_query;
+
+    #[test]
+    fn palette_query_requires_search_prefix() {
+        assert_eq!(palette_query("? pricing model"), Some("pricing model"));
+        assert_eq!(palette_query("  ?docs"), Some("docs"));
+        assert_eq!(palette_query("?"), Some(""));
+        assert_eq!(palette_query("what is the pricing?"), None);
+    }
+}
@@ -1,4 +1,6 @@
+pub mod command_palette;
 pub mod desktop_icons;
+pub mod new_desktop_dialog;
 pub mod prompt_bar;
 pub mod status_views;
 pub mod workspace_canvas;
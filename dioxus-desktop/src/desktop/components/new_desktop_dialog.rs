@@ -0,0 +1,141 @@
+use dioxus::prelude::*;
+use shared_types::DesktopTemplate;
+
+use crate::api::{create_desktop, fetch_desktop_templates};
+use crate::desktop::apps::core_apps;
+use crate::desktop::open_desktop;
+
+/// Desktop id for a new desktop: the name as a slug plus a time suffix, so
+/// two desktops with the same name never collide.
+fn new_desktop_id(name: &str) -> String {
+    let slug = name
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect::<String>()
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    let slug = if slug.is_empty() { "desktop" } else { &slug };
+    format!("{slug}-{:x}", js_sys::Date::now() as u64)
+}
+
+#[component]
+pub fn NewDesktopDialog(on_close: Callback<()>) -> Element {
+    let mut templates = use_signal(Vec::<DesktopTemplate>::new);
+    let mut selected = use_signal(|| None::<String>);
+    let mut name = use_signal(String::new);
+    let mut creating = use_signal(|| false);
+    let mut error = use_signal(|| None::<String>);
+
+    use_future(move || async move {
+        match fetch_desktop_templates().await {
+            Ok(loaded) => templates.set(loaded),
+            Err(e) => error.set(Some(format!("Failed to load templates: {e}"))),
+        }
+    });
+
+    let create = move |_| {
+        if creating() {
+            return;
+        }
+        creating.set(true);
+        error.set(None);
+        let desktop_id = new_desktop_id(&name());
+        let template_id = selected();
+        spawn(async move {
+            match create_desktop(&desktop_id, template_id.as_deref(), &core_apps()).await {
+                Ok(_) => open_desktop(&desktop_id),
+                Err(e) => {
+                    error.set(Some(format!("Failed to create desktop: {e}")));
+                    creating.set(false);
+                }
+            }
+        });
+    };
+
+    let option_style = |active: bool| {
+        if active {
+            "display: flex; flex-direction: column; gap: 0.25rem; padding: 0.625rem 0.75rem; text-align: left; background: var(--accent-bg, #3b82f6); color: white; border: 1px solid var(--accent-bg, #3b82f6); border-radius: var(--radius-md, 8px); cursor: pointer;"
+        } else {
+            "display: flex; flex-direction: column; gap: 0.25rem; padding: 0.625rem 0.75rem; text-align: left; background: var(--window-bg, #1f2937); color: var(--text-primary, white); border: 1px solid var(--border-color, #374151); border-radius: var(--radius-md, 8px); cursor: pointer;"
+        }
+    };
+
+    rsx! {
+        div {
+            class: "new-desktop-backdrop",
+            style: "position: fixed; inset: 0; z-index: 9000; display: flex; align-items: center; justify-content: center; background: rgba(0, 0, 0, 0.5);",
+            onclick: move |_| on_close.call(()),
+
+            div {
+                class: "new-desktop-dialog",
+                role: "dialog",
+                aria_label: "New desktop",
+                style: "width: min(28rem, 92vw); display: flex; flex-direction: column; gap: 0.75rem; padding: 1rem; background: var(--window-bg, #1f2937); color: var(--text-primary, white); border: 1px solid var(--border-color, #374151); border-radius: var(--radius-lg, 12px); box-shadow: var(--shadow-lg, 0 10px 40px rgba(0,0,0,0.5));",
+                onclick: move |e| e.stop_propagation(),
+
+                div { style: "font-weight: 600;", "New desktop" }
+
+                input {
+                    style: "padding: 0.5rem 0.75rem; background: var(--input-bg, #1f2937); color: var(--text-primary, white); border: 1px solid var(--border-color, #374151); border-radius: var(--radius-md, 8px); font-size: 0.875rem; outline: none;",
+                    placeholder: "Name",
+                    value: "{name}",
+                    oninput: move |e| name.set(e.value()),
+                }
+
+                div {
+                    class: "new-desktop-templates",
+                    style: "display: flex; flex-direction: column; gap: 0.5rem; max-height: 50vh; overflow-y: auto;",
+
+                    button {
+                        style: option_style(selected().is_none()),
+                        onclick: move |_| selected.set(None),
+                        span { style: "font-weight: 600; font-size: 0.875rem;", "Empty" }
+                        span { style: "font-size: 0.75rem; opacity: 0.8;", "No windows or files." }
+                    }
+
+                    for template in templates() {
+                        button {
+                            key: "{template.id}",
+                            style: option_style(selected().as_deref() == Some(template.id.as_str())),
+                            onclick: {
+                                let id = template.id.clone();
+                                move |_| selected.set(Some(id.clone()))
+                            },
+                            span { style: "font-weight: 600; font-size: 0.875rem;", "{template.name}" }
+                            if !template.description.is_empty() {
+                                span { style: "font-size: 0.75rem; opacity: 0.8;", "{template.description}" }
+                            }
+                        }
+                    }
+                }
+
+                if let Some(message) = error() {
+                    div {
+                        role: "alert",
+                        style: "font-size: 0.75rem; color: var(--danger-bg, #f87171);",
+                        "{message}"
+                    }
+                }
+
+                div {
+                    style: "display: flex; justify-content: flex-end; gap: 0.5rem;",
+                    button {
+                        style: "padding: 0.375rem 0.75rem; background: transparent; color: var(--text-secondary, #9ca3af); border: 1px solid var(--border-color, #374151); border-radius: var(--radius-md, 8px); cursor: pointer;",
+                        onclick: move |_| on_close.call(()),
+                        "Cancel"
+                    }
+                    button {
+                        style: "padding: 0.375rem 0.75rem; background: var(--accent-bg, #3b82f6); color: white; border: none; border-radius: var(--radius-md, 8px); cursor: pointer;",
+                        disabled: creating(),
+                        onclick: create,
+                        if creating() { "Creating…" } else { "Create" }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,13 +1,18 @@
 use dioxus::prelude::*;
 use shared_types::{
-    ConductorError, ConductorExecuteResponse, ConductorOutputMode, ConductorRunStatus,
-    ConductorToastPayload, EventImportance, WindowState, WriterWindowProps,
+    ConductorError, ConductorExecuteResponse, ConductorOutputMode, ConductorRefineResponse,
+    ConductorRunStatus, ConductorToastPayload, ErrorCode, EventImportance, ObjectiveContract,
+    WindowState, WriterWindowProps,
 };
 
 use crate::api::{
-    conductor_get_run_state, conductor_get_run_status, execute_conductor, open_window,
+    conductor_get_run_state, conductor_get_run_status, conductor_refine, execute_conductor,
+    open_window,
 };
+use crate::components::ErrorNotice;
 use crate::desktop::apps::get_app_icon;
+use crate::desktop::components::command_palette::{palette_query, CommandPalette};
+use crate::desktop::state::{follow_conductor_run, unfollow_conductor_run, CONDUCTOR_RUNS};
 
 // ============================================================================
 // Phase F: Live Telemetry Stream (Star Wars Style Rising Lines)
@@ -250,6 +255,8 @@ pub fn format_telemetry_message(event_type: &str, data: &serde_json::Value) -> S
 #[derive(Clone, Debug, PartialEq)]
 pub enum ConductorSubmissionState {
     Idle,
+    Refining,
+    AwaitingAnswers,
     Submitting,
     OpeningWriter {
         run_id: String,
@@ -271,6 +278,8 @@ impl ConductorSubmissionState {
     pub fn display_text(&self) -> Option<&'static str> {
         match self {
             ConductorSubmissionState::Idle => None,
+            ConductorSubmissionState::Refining => Some("Refining..."),
+            ConductorSubmissionState::AwaitingAnswers => None,
             ConductorSubmissionState::Submitting => Some("Submitting..."),
             ConductorSubmissionState::OpeningWriter { .. } => Some("Opening Writer..."),
             ConductorSubmissionState::Success { .. } => None,
@@ -313,6 +322,37 @@ impl ConductorSubmissionState {
     }
 }
 
+/// Objective waiting on the user's answers to the refinement questions.
+#[derive(Clone, Debug, PartialEq)]
+struct PendingRefinement {
+    objective: String,
+    refinement: ConductorRefineResponse,
+    answers: Vec<String>,
+}
+
+/// Fold the user's answers into the proposed contract. Unanswered questions
+/// are dropped; the conductor sees the answers as part of the objective.
+fn contract_with_answers(
+    mut contract: ObjectiveContract,
+    questions: &[String],
+    answers: &[String],
+) -> ObjectiveContract {
+    let clarifications: Vec<String> = questions
+        .iter()
+        .zip(answers)
+        .filter(|(_, answer)| !answer.trim().is_empty())
+        .map(|(question, answer)| format!("- {} {}", question.trim(), answer.trim()))
+        .collect();
+    if !clarifications.is_empty() {
+        contract.primary_objective = format!(
+            "{}\n\nClarifications:\n{}",
+            contract.primary_objective.trim(),
+            clarifications.join("\n")
+        );
+    }
+    contract
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum TaskLifecycleDecision {
     Running,
@@ -325,6 +365,7 @@ fn classify_run_status(status: ConductorRunStatus) -> TaskLifecycleDecision {
         ConductorRunStatus::Initializing
         | ConductorRunStatus::Running
         | ConductorRunStatus::WaitingForCalls
+        | ConductorRunStatus::WaitingForProvider
         | ConductorRunStatus::Completing => TaskLifecycleDecision::Running,
         ConductorRunStatus::Completed => TaskLifecycleDecision::Completed,
         ConductorRunStatus::Failed | ConductorRunStatus::Blocked => TaskLifecycleDecision::Failed,
@@ -332,12 +373,24 @@ fn classify_run_status(status: ConductorRunStatus) -> TaskLifecycleDecision {
 }
 
 fn failure_from_error(error: Option<ConductorError>) -> (String, String) {
-    error.map(|e| (e.code, e.message)).unwrap_or_else(|| {
-        (
-            "RUN_FAILED".to_string(),
-            "Run failed without error details".to_string(),
-        )
-    })
+    error
+        .map(|e| (e.code.as_str().to_string(), e.message))
+        .unwrap_or_else(|| {
+            (
+                ErrorCode::RunFailed.as_str().to_string(),
+                "Run failed without error details".to_string(),
+            )
+        })
+}
+
+/// Unfollows a run when the prompt bar stops waiting on it, however the wait
+/// ends, so finished runs do not pile up in `CONDUCTOR_RUNS`.
+struct FollowedRun(String);
+
+impl Drop for FollowedRun {
+    fn drop(&mut self) {
+        unfollow_conductor_run(&self.0);
+    }
 }
 
 fn run_state_requires_writer(run: &shared_types::ConductorRunState) -> bool {
@@ -407,6 +460,7 @@ pub struct PromptBarProps {
     pub desktop_id: String,
     pub on_focus_window: Callback<String>,
     pub on_show_desktop: Callback<()>,
+    pub on_new_desktop: Callback<()>,
     pub current_theme: String,
     pub on_toggle_theme: Callback<()>,
     pub telemetry_state: Signal<TelemetryStreamState>,
@@ -421,6 +475,7 @@ pub fn PromptBar(props: PromptBarProps) -> Element {
     let desktop_id = props.desktop_id;
     let on_focus_window = props.on_focus_window;
     let on_show_desktop = props.on_show_desktop;
+    let on_new_desktop = props.on_new_desktop;
     let current_theme = props.current_theme;
     let on_toggle_theme = props.on_toggle_theme;
     let telemetry_state = props.telemetry_state;
@@ -428,6 +483,39 @@ pub fn PromptBar(props: PromptBarProps) -> Element {
     let mut prompt_expanded = use_signal(|| true);
     let mut conductor_state = use_signal(|| ConductorSubmissionState::Idle);
     let desktop_id_signal = use_signal(|| desktop_id.clone());
+    // Off by default: refinement costs a model call before every run.
+    let mut refine_enabled = use_signal(|| false);
+    let mut pending_refinement = use_signal(|| None::<PendingRefinement>);
+    let window_titles: Vec<String> = windows.iter().map(|window| window.title.clone()).collect();
+
+    let submit_objective = use_callback(
+        move |(objective, contract): (String, Option<ObjectiveContract>)| {
+            let desktop_id = desktop_id_signal.read().clone();
+            let mut state = conductor_state.clone();
+            state.set(ConductorSubmissionState::Submitting);
+
+            spawn(async move {
+                match execute_conductor(
+                    &objective,
+                    &desktop_id,
+                    ConductorOutputMode::Auto,
+                    contract,
+                )
+                .await
+                {
+                    Ok(response) => {
+                        handle_conductor_response(response, state, desktop_id).await;
+                    }
+                    Err(e) => {
+                        state.set(ConductorSubmissionState::Failed {
+                            code: "EXECUTE_FAILED".to_string(),
+                            message: e,
+                        });
+                    }
+                }
+            });
+        },
+    );
 
     // Conductor submission handler
     let handle_conductor_submit = use_callback(move |objective: String| {
@@ -438,19 +526,31 @@ pub fn PromptBar(props: PromptBarProps) -> Element {
         if state().is_active() {
             return;
         }
-        state.set(ConductorSubmissionState::Submitting);
+        if !refine_enabled() {
+            submit_objective.call((objective, None));
+            return;
+        }
+        state.set(ConductorSubmissionState::Refining);
 
+        let desktop_context = window_titles.clone();
         spawn(async move {
-            // Execute conductor task
-            match execute_conductor(&objective, &desktop_id, ConductorOutputMode::Auto).await {
-                Ok(response) => {
-                    handle_conductor_response(response, state, desktop_id).await;
+            match conductor_refine(&objective, &desktop_id, desktop_context).await {
+                Ok(refinement) if refinement.questions.is_empty() => {
+                    submit_objective.call((objective, Some(refinement.contract)));
+                }
+                Ok(refinement) => {
+                    let answers = vec![String::new(); refinement.questions.len()];
+                    pending_refinement.set(Some(PendingRefinement {
+                        objective,
+                        refinement,
+                        answers,
+                    }));
+                    state.set(ConductorSubmissionState::AwaitingAnswers);
                 }
                 Err(e) => {
-                    state.set(ConductorSubmissionState::Failed {
-                        code: "EXECUTE_FAILED".to_string(),
-                        message: e,
-                    });
+                    // Refinement is an optional step; run the raw objective.
+                    dioxus_logger::tracing::warn!("Objective refinement failed: {}", e);
+                    submit_objective.call((objective, None));
                 }
             }
         });
@@ -485,83 +585,95 @@ pub fn PromptBar(props: PromptBarProps) -> Element {
                     let run_id = response.run_id.clone();
                     let mut opened_writer = false;
 
-                    for _ in 0..80 {
-                        match conductor_get_run_status(&run_id).await {
-                            Ok(run_status) => match classify_run_status(run_status.status) {
-                                TaskLifecycleDecision::Completed => {
-                                    if let Some(toast) = run_status.toast {
-                                        state.set(ConductorSubmissionState::ToastReady {
-                                            run_id: run_id.clone(),
-                                            toast,
-                                        });
-                                        return;
-                                    }
+                    // Follow the run through its `conductor.run.state_delta`
+                    // stream; only the initial state, any refetch after a
+                    // missed delta and the final status come over HTTP.
+                    if let Ok(run_state) = conductor_get_run_state(&run_id).await {
+                        follow_conductor_run(run_state);
+                    }
+                    let _following = FollowedRun(run_id.clone());
 
-                                    let writer_props = run_status
-                                        .report_path
-                                        .as_deref()
-                                        .map(|path| writer_props_for_report(path, &run_id))
-                                        .or_else(|| {
-                                            (!run_status.document_path.trim().is_empty()).then(
-                                                || {
-                                                    writer_props_for_run_document(
-                                                        &run_status.document_path,
-                                                        &run_id,
-                                                    )
-                                                },
-                                            )
-                                        });
+                    for _ in 0..80 {
+                        let followed = CONDUCTOR_RUNS.read().get(&run_id).cloned();
+                        let Some(run_state) = followed else {
+                            gloo_timers::future::TimeoutFuture::new(250).await;
+                            continue;
+                        };
+
+                        if classify_run_status(run_state.status) != TaskLifecycleDecision::Running {
+                            match conductor_get_run_status(&run_id).await {
+                                Ok(run_status) => match classify_run_status(run_status.status) {
+                                    TaskLifecycleDecision::Completed => {
+                                        if let Some(toast) = run_status.toast {
+                                            state.set(ConductorSubmissionState::ToastReady {
+                                                run_id: run_id.clone(),
+                                                toast,
+                                            });
+                                            return;
+                                        }
 
-                                    state.set(ConductorSubmissionState::OpeningWriter {
-                                        run_id: run_id.clone(),
-                                    });
-                                    if let Err(e) =
-                                        open_writer_window(&desktop_id, writer_props).await
-                                    {
-                                        state.set(ConductorSubmissionState::Failed {
-                                            code: "WINDOW_OPEN_FAILED".to_string(),
-                                            message: e,
-                                        });
-                                    } else {
-                                        state.set(ConductorSubmissionState::Success {
+                                        let writer_props = run_status
+                                            .report_path
+                                            .as_deref()
+                                            .map(|path| writer_props_for_report(path, &run_id))
+                                            .or_else(|| {
+                                                (!run_status.document_path.trim().is_empty()).then(
+                                                    || {
+                                                        writer_props_for_run_document(
+                                                            &run_status.document_path,
+                                                            &run_id,
+                                                        )
+                                                    },
+                                                )
+                                            });
+
+                                        state.set(ConductorSubmissionState::OpeningWriter {
                                             run_id: run_id.clone(),
                                         });
+                                        if let Err(e) =
+                                            open_writer_window(&desktop_id, writer_props).await
+                                        {
+                                            state.set(ConductorSubmissionState::Failed {
+                                                code: "WINDOW_OPEN_FAILED".to_string(),
+                                                message: e,
+                                            });
+                                        } else {
+                                            state.set(ConductorSubmissionState::Success {
+                                                run_id: run_id.clone(),
+                                            });
+                                        }
+                                        return;
                                     }
-                                    return;
-                                }
-                                TaskLifecycleDecision::Failed => {
-                                    let (code, message) = failure_from_error(run_status.error);
-                                    state.set(ConductorSubmissionState::Failed { code, message });
-                                    return;
-                                }
-                                TaskLifecycleDecision::Running => {}
-                            },
-                            Err(_) => {}
-                        }
-
-                        if !opened_writer {
-                            match conductor_get_run_state(&run_id).await {
-                                Ok(run_state) if run_state_requires_writer(&run_state) => {
-                                    state.set(ConductorSubmissionState::OpeningWriter {
-                                        run_id: run_id.clone(),
-                                    });
-                                    let props = Some(writer_props_for_run_document(
-                                        &run_state.document_path,
-                                        &run_id,
-                                    ));
-                                    if let Err(e) = open_writer_window(&desktop_id, props).await {
+                                    TaskLifecycleDecision::Failed => {
+                                        let (code, message) = failure_from_error(run_status.error);
                                         state.set(ConductorSubmissionState::Failed {
-                                            code: "WINDOW_OPEN_FAILED".to_string(),
-                                            message: e,
-                                        });
-                                    } else {
-                                        state.set(ConductorSubmissionState::Success {
-                                            run_id: run_id.clone(),
+                                            code,
+                                            message,
                                         });
-                                        opened_writer = true;
+                                        return;
                                     }
-                                }
-                                _ => {}
+                                    TaskLifecycleDecision::Running => {}
+                                },
+                                Err(_) => {}
+                            }
+                        } else if !opened_writer && run_state_requires_writer(&run_state) {
+                            state.set(ConductorSubmissionState::OpeningWriter {
+                                run_id: run_id.clone(),
+                            });
+                            let props = Some(writer_props_for_run_document(
+                                &run_state.document_path,
+                                &run_id,
+                            ));
+                            if let Err(e) = open_writer_window(&desktop_id, props).await {
+                                state.set(ConductorSubmissionState::Failed {
+                                    code: "WINDOW_OPEN_FAILED".to_string(),
+                                    message: e,
+                                });
+                            } else {
+                                state.set(ConductorSubmissionState::Success {
+                                    run_id: run_id.clone(),
+                                });
+                                opened_writer = true;
                             }
                         }
 
@@ -657,6 +769,73 @@ pub fn PromptBar(props: PromptBarProps) -> Element {
             is_active: conductor_state().is_active(),
         }
 
+        if let Some(pending) = pending_refinement() {
+            div {
+                class: "refine-dialog",
+                style: "position: fixed; left: 50%; bottom: 4.5rem; transform: translateX(-50%); width: min(32rem, calc(100vw - 1rem)); max-height: 60vh; overflow-y: auto; display: flex; flex-direction: column; gap: 0.75rem; padding: 1rem; background: var(--window-bg, #1f2937); color: var(--text-primary, white); border: 1px solid var(--border-color, #374151); border-radius: var(--radius-md, 8px); box-shadow: 0 10px 30px rgba(0,0,0,0.4); z-index: 2100;",
+
+                div {
+                    style: "font-size: 0.875rem; font-weight: 600;",
+                    "{pending.refinement.contract.primary_objective}"
+                }
+
+                ul {
+                    class: "refine-criteria",
+                    style: "margin: 0; padding-left: 1.25rem; font-size: 0.75rem; color: var(--text-secondary, #9ca3af);",
+                    for criterion in pending.refinement.contract.success_criteria.iter() {
+                        li { "{criterion}" }
+                    }
+                }
+
+                for (index, question) in pending.refinement.questions.iter().enumerate() {
+                    label {
+                        key: "refine-question-{index}",
+                        style: "display: flex; flex-direction: column; gap: 0.25rem; font-size: 0.8125rem;",
+                        span { "{question}" }
+                        input {
+                            class: "refine-answer",
+                            style: "padding: 0.375rem 0.625rem; background: var(--input-bg, #111827); color: var(--text-primary, white); border: 1px solid var(--border-color, #374151); border-radius: var(--radius-sm, 4px); font-size: 0.8125rem; outline: none;",
+                            value: pending.answers.get(index).cloned().unwrap_or_default(),
+                            oninput: move |e| {
+                                if let Some(pending) = pending_refinement.write().as_mut() {
+                                    pending.answers[index] = e.value();
+                                }
+                            },
+                        }
+                    }
+                }
+
+                div {
+                    style: "display: flex; justify-content: flex-end; gap: 0.5rem;",
+                    button {
+                        class: "refine-skip",
+                        style: "padding: 0.375rem 0.75rem; background: transparent; color: var(--text-secondary, #9ca3af); border: 1px solid var(--border-color, #374151); border-radius: var(--radius-sm, 4px); font-size: 0.75rem; cursor: pointer;",
+                        onclick: move |_| {
+                            if let Some(pending) = pending_refinement.take() {
+                                submit_objective.call((pending.objective, None));
+                            }
+                        },
+                        "Skip"
+                    }
+                    button {
+                        class: "refine-submit",
+                        style: "padding: 0.375rem 0.75rem; background: var(--accent-bg, #3b82f6); color: white; border: none; border-radius: var(--radius-sm, 4px); font-size: 0.75rem; font-weight: 600; cursor: pointer;",
+                        onclick: move |_| {
+                            if let Some(pending) = pending_refinement.take() {
+                                let contract = contract_with_answers(
+                                    pending.refinement.contract,
+                                    &pending.refinement.questions,
+                                    &pending.answers,
+                                );
+                                submit_objective.call((pending.objective, Some(contract)));
+                            }
+                        },
+                        "Submit"
+                    }
+                }
+            }
+        }
+
         div {
             class: "prompt-bar",
             style: if is_mobile {
@@ -713,9 +892,12 @@ pub fn PromptBar(props: PromptBarProps) -> Element {
                                     }
                                 },
                                 onkeydown: move |e| {
-                                    if e.key() == Key::Enter {
+                                    if e.key() == Key::Escape && palette_query(&input_value()).is_some() {
+                                        input_value.set(String::new());
+                                    } else if e.key() == Key::Enter {
                                         let text = input_value.to_string();
-                                        if !text.trim().is_empty() {
+                                        // Search mode: results are opened from the palette.
+                                        if !text.trim().is_empty() && palette_query(&text).is_none() {
                                             handle_conductor_submit.call(text);
                                             input_value.set(String::new());
                                         }
@@ -723,6 +905,14 @@ pub fn PromptBar(props: PromptBarProps) -> Element {
                                 }
                             }
 
+                            if palette_query(&input_value()).is_some() {
+                                CommandPalette {
+                                    input: input_value,
+                                    desktop_id: desktop_id_signal.read().clone(),
+                                    on_close: move |_| input_value.set(String::new()),
+                                }
+                            }
+
                             // Conductor state indicator overlay
                             if let Some(display_text) = conductor_state().display_text() {
                                 div {
@@ -746,7 +936,8 @@ pub fn PromptBar(props: PromptBarProps) -> Element {
                                     title: "{error_msg}",
 
                                     span { "⚠" }
-                                    span { "{error_msg}" }
+
+                                    ErrorNotice { message: error_msg.clone() }
                                 }
                             }
 
@@ -828,6 +1019,18 @@ pub fn PromptBar(props: PromptBarProps) -> Element {
                     }
                 }
 
+                button {
+                    class: "prompt-refine-toggle",
+                    style: if refine_enabled() {
+                        "height: 32px; padding: 0 0.625rem; display: flex; align-items: center; background: var(--accent-bg, #3b82f6); color: white; border: 1px solid var(--accent-bg, #3b82f6); border-radius: var(--radius-md, 8px); font-size: 0.75rem; cursor: pointer; flex-shrink: 0;"
+                    } else {
+                        "height: 32px; padding: 0 0.625rem; display: flex; align-items: center; background: var(--window-bg, #1f2937); color: var(--text-secondary, #9ca3af); border: 1px solid var(--border-color, #374151); border-radius: var(--radius-md, 8px); font-size: 0.75rem; cursor: pointer; flex-shrink: 0;"
+                    },
+                    title: "Ask clarifying questions before running",
+                    onclick: move |_| refine_enabled.set(!refine_enabled()),
+                    "Refine"
+                }
+
                 div {
                     class: "prompt-input-container",
                     style: "flex: 1; min-width: 0; position: relative; display: flex; align-items: center;",
@@ -849,9 +1052,12 @@ pub fn PromptBar(props: PromptBarProps) -> Element {
                             }
                         },
                         onkeydown: move |e| {
-                            if e.key() == Key::Enter {
+                            if e.key() == Key::Escape && palette_query(&input_value()).is_some() {
+                                input_value.set(String::new());
+                            } else if e.key() == Key::Enter {
                                 let text = input_value.to_string();
-                                if !text.trim().is_empty() {
+                                // Search mode: results are opened from the palette.
+                                if !text.trim().is_empty() && palette_query(&text).is_none() {
                                     handle_conductor_submit.call(text);
                                     input_value.set(String::new());
                                 }
@@ -859,6 +1065,14 @@ pub fn PromptBar(props: PromptBarProps) -> Element {
                         }
                     }
 
+                    if palette_query(&input_value()).is_some() {
+                        CommandPalette {
+                            input: input_value,
+                            desktop_id: desktop_id_signal.read().clone(),
+                            on_close: move |_| input_value.set(String::new()),
+                        }
+                    }
+
                     // Conductor state indicator overlay
                     if let Some(display_text) = conductor_state().display_text() {
                         div {
@@ -882,7 +1096,8 @@ pub fn PromptBar(props: PromptBarProps) -> Element {
                             title: "{error_msg}",
 
                             span { "⚠" }
-                            span { "{error_msg}" }
+
+                            ErrorNotice { message: error_msg.clone() }
                         }
                     }
 
@@ -942,6 +1157,10 @@ pub fn PromptBar(props: PromptBarProps) -> Element {
                         on_show_desktop,
                     }
 
+                    NewDesktopIndicator {
+                        on_new_desktop,
+                    }
+
                     for window in windows.iter() {
                         RunningAppIndicator {
                             key: "{window.id}",
@@ -1028,6 +1247,10 @@ mod tests {
             classify_run_status(ConductorRunStatus::WaitingForCalls),
             TaskLifecycleDecision::Running
         );
+        assert_eq!(
+            classify_run_status(ConductorRunStatus::WaitingForProvider),
+            TaskLifecycleDecision::Running
+        );
         assert_eq!(
             classify_run_status(ConductorRunStatus::Completing),
             TaskLifecycleDecision::Running
@@ -1045,14 +1268,50 @@ mod tests {
     #[test]
     fn failure_from_error_uses_typed_backend_error() {
         let (code, message) = failure_from_error(Some(ConductorError {
-            code: "EXEC_FAIL".to_string(),
+            code: ErrorCode::ProviderUnavailable,
             message: "Typed failure".to_string(),
             failure_kind: None,
         }));
-        assert_eq!(code, "EXEC_FAIL");
+        assert_eq!(code, "PROVIDER_UNAVAILABLE");
         assert_eq!(message, "Typed failure");
     }
 
+    #[test]
+    fn contract_with_answers_folds_answered_questions_into_objective() {
+        let contract = ObjectiveContract {
+            objective_id: "obj-1".to_string(),
+            parent_objective_id: None,
+            primary_objective: "Improve the report".to_string(),
+            success_criteria: vec!["Summary is shorter".to_string()],
+            constraints: shared_types::ObjectiveConstraints {
+                max_tool_calls: 30,
+                timeout_ms: 180_000,
+                max_subframe_depth: 2,
+                allowed_capabilities: Vec::new(),
+            },
+            attempts_budget: 2,
+            evidence_requirements: shared_types::EvidenceRequirements {
+                requires_citations: false,
+                min_confidence: 0.6,
+                required_source_types: Vec::new(),
+            },
+        };
+        let questions = vec!["Which report?".to_string(), "Audience?".to_string()];
+
+        let answered = contract_with_answers(
+            contract.clone(),
+            &questions,
+            &["Q3 incidents".to_string(), "  ".to_string()],
+        );
+        assert_eq!(
+            answered.primary_objective,
+            "Improve the report\n\nClarifications:\n- Which report? Q3 incidents"
+        );
+
+        let skipped = contract_with_answers(contract.clone(), &questions, &[]);
+        assert_eq!(skipped, contract);
+    }
+
     #[test]
     fn run_state_requires_writer_is_false_for_immediate_only_agenda() {
         let now = chrono::Utc::now();
@@ -1080,6 +1339,8 @@ mod tests {
             document_path: "conductor/runs/run-1/draft.md".to_string(),
             output_mode: ConductorOutputMode::Auto,
             desktop_id: "desktop-1".to_string(),
+            revision: 0,
+            contract: None,
         };
 
         assert!(!run_state_requires_writer(&run));
@@ -1112,6 +1373,8 @@ mod tests {
             document_path: "conductor/runs/run-2/draft.md".to_string(),
             output_mode: ConductorOutputMode::Auto,
             desktop_id: "desktop-1".to_string(),
+            revision: 0,
+            contract: None,
         };
 
         assert!(run_state_requires_writer(&run));
@@ -1145,6 +1408,8 @@ mod tests {
             document_path: "conductor/runs/run-3/draft.md".to_string(),
             output_mode: ConductorOutputMode::Auto,
             desktop_id: "desktop-1".to_string(),
+            revision: 0,
+            contract: None,
         };
 
         assert!(run_state_requires_writer(&run));
@@ -1178,6 +1443,8 @@ mod tests {
             document_path: "conductor/runs/run-4/draft.md".to_string(),
             output_mode: ConductorOutputMode::Auto,
             desktop_id: "desktop-1".to_string(),
+            revision: 0,
+            contract: None,
         };
 
         assert!(!run_state_requires_writer(&run));
@@ -1197,6 +1464,19 @@ pub fn ShowDesktopIndicator(on_show_desktop: Callback<()>) -> Element {
     }
 }
 
+#[component]
+pub fn NewDesktopIndicator(on_new_desktop: Callback<()>) -> Element {
+    rsx! {
+        button {
+            class: "new-desktop",
+            style: "width: 32px; height: 32px; display: flex; align-items: center; justify-content: center; background: var(--window-bg, #1f2937); color: var(--text-secondary, #9ca3af); border: 1px solid var(--border-color, #374151); border-radius: var(--radius-md, 8px); cursor: pointer; font-size: 1rem;",
+            onclick: move |_| on_new_desktop.call(()),
+            title: "New desktop",
+            "+"
+        }
+    }
+}
+
 #[component]
 pub fn RunningAppIndicator(
     window: WindowState,
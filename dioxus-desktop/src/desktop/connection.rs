@@ -0,0 +1,206 @@
+//! Desktop event connection: a WebSocket, with long-poll as the fallback.
+//!
+//! A WebSocket that closes is reopened with backoff. Once
+//! [`WS_FAILURES_BEFORE_LONG_POLL`] attempts in a row fail, events arrive by
+//! long-poll instead and the WebSocket is retried every
+//! [`WS_UPGRADE_RETRY_MS`]; when one opens, long-poll stops and the socket,
+//! which starts with a fresh `desktop_state`, takes over.
+//!
+//! [`DesktopConnection::switch_desktop`] moves an open socket to another
+//! desktop without reconnecting; later attempts subscribe to the new one.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use gloo_timers::future::TimeoutFuture;
+
+use crate::desktop::long_poll::{connect_long_poll, DesktopLongPollRuntime};
+use crate::desktop::ws::{connect_websocket, DesktopWsRuntime, WsEvent};
+
+/// WebSocket attempts that fail in a row before falling back to long-poll.
+pub const WS_FAILURES_BEFORE_LONG_POLL: u32 = 3;
+
+/// How often a WebSocket is retried while long-poll is in use.
+pub const WS_UPGRADE_RETRY_MS: u32 = 30_000;
+
+/// A socket still connecting after this long counts as failed; some proxies
+/// hold an upgrade request open instead of refusing it.
+const WS_CONNECT_TIMEOUT_MS: u32 = 10_000;
+
+const WS_RETRY_BASE_MS: u32 = 1_000;
+const WS_RETRY_MAX_MS: u32 = 10_000;
+const STATUS_CHECK_MS: u32 = 250;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SocketStatus {
+    Connecting,
+    Open,
+    Closed,
+}
+
+type EventHandler = Rc<RefCell<dyn FnMut(WsEvent)>>;
+
+/// Closes the socket and stops long-poll when dropped.
+pub struct DesktopConnection {
+    alive: Rc<Cell<bool>>,
+    desktop_id: Rc<RefCell<String>>,
+    socket: Rc<RefCell<Option<DesktopWsRuntime>>>,
+    long_poll: Rc<RefCell<Option<DesktopLongPollRuntime>>>,
+    on_event: EventHandler,
+}
+
+impl DesktopConnection {
+    /// Follow `desktop_id` instead of the current desktop.
+    pub fn switch_desktop(&self, desktop_id: &str) {
+        let previous = self.desktop_id.replace(desktop_id.to_string());
+        if previous == desktop_id {
+            return;
+        }
+        if let Some(socket) = self.socket.borrow().as_ref() {
+            socket.switch_desktop(desktop_id);
+        }
+        // Long-poll is bound to one desktop; restart it on the new one.
+        let mut long_poll = self.long_poll.borrow_mut();
+        if long_poll.is_some() {
+            let on_event = self.on_event.clone();
+            *long_poll = Some(connect_long_poll(desktop_id, move |event| {
+                on_event.borrow_mut()(event);
+            }));
+        }
+    }
+}
+
+impl Drop for DesktopConnection {
+    fn drop(&mut self) {
+        self.alive.set(false);
+        self.socket.borrow_mut().take();
+        self.long_poll.borrow_mut().take();
+    }
+}
+
+pub fn connect_desktop_events<F>(desktop_id: &str, on_event: F) -> DesktopConnection
+where
+    F: FnMut(WsEvent) + 'static,
+{
+    let connection = DesktopConnection {
+        alive: Rc::new(Cell::new(true)),
+        desktop_id: Rc::new(RefCell::new(desktop_id.to_string())),
+        socket: Rc::new(RefCell::new(None)),
+        long_poll: Rc::new(RefCell::new(None)),
+        on_event: Rc::new(RefCell::new(on_event)),
+    };
+    wasm_bindgen_futures::spawn_local(run_connection(
+        connection.desktop_id.clone(),
+        connection.on_event.clone(),
+        connection.alive.clone(),
+        connection.socket.clone(),
+        connection.long_poll.clone(),
+    ));
+    connection
+}
+
+/// Delay before the next WebSocket attempt after `failures` failed in a row.
+fn retry_delay_ms(failures: u32, long_polling: bool) -> u32 {
+    if long_polling {
+        WS_UPGRADE_RETRY_MS
+    } else {
+        (WS_RETRY_BASE_MS << failures.min(4)).min(WS_RETRY_MAX_MS)
+    }
+}
+
+async fn run_connection(
+    desktop_id: Rc<RefCell<String>>,
+    on_event: EventHandler,
+    alive: Rc<Cell<bool>>,
+    socket_slot: Rc<RefCell<Option<DesktopWsRuntime>>>,
+    long_poll: Rc<RefCell<Option<DesktopLongPollRuntime>>>,
+) {
+    let mut failures = 0u32;
+
+    while alive.get() {
+        let status = Rc::new(Cell::new(SocketStatus::Connecting));
+        let current_desktop = desktop_id.borrow().clone();
+        let socket = {
+            let status = status.clone();
+            let alive = alive.clone();
+            let long_poll = long_poll.clone();
+            let on_event = on_event.clone();
+            connect_websocket(&current_desktop, move |event| {
+                if !alive.get() {
+                    return;
+                }
+                match event {
+                    WsEvent::Connected => {
+                        status.set(SocketStatus::Open);
+                        if long_poll.borrow_mut().take().is_some() {
+                            dioxus_logger::tracing::info!(
+                                "WebSocket reachable again, leaving long-poll"
+                            );
+                        }
+                    }
+                    WsEvent::Disconnected => {
+                        status.set(SocketStatus::Closed);
+                        // Long-poll still carries events; the UI stays connected.
+                        if long_poll.borrow().is_some() {
+                            return;
+                        }
+                    }
+                    _ => {}
+                }
+                on_event.borrow_mut()(event);
+            })
+        };
+        match socket {
+            Ok(socket) => *socket_slot.borrow_mut() = Some(socket),
+            Err(e) => {
+                dioxus_logger::tracing::warn!("WebSocket attempt failed: {}", e);
+                status.set(SocketStatus::Closed);
+            }
+        }
+
+        let mut connecting_ms = 0;
+        while alive.get() && status.get() != SocketStatus::Closed {
+            match status.get() {
+                SocketStatus::Open => failures = 0,
+                SocketStatus::Connecting if connecting_ms >= WS_CONNECT_TIMEOUT_MS => {
+                    dioxus_logger::tracing::warn!("WebSocket connect timed out");
+                    break;
+                }
+                _ => connecting_ms += STATUS_CHECK_MS,
+            }
+            TimeoutFuture::new(STATUS_CHECK_MS).await;
+        }
+        socket_slot.borrow_mut().take();
+        if !alive.get() {
+            break;
+        }
+
+        failures += 1;
+        if failures >= WS_FAILURES_BEFORE_LONG_POLL && long_poll.borrow().is_none() {
+            let on_event = on_event.clone();
+            let runtime = connect_long_poll(&desktop_id.borrow(), move |event| {
+                on_event.borrow_mut()(event);
+            });
+            *long_poll.borrow_mut() = Some(runtime);
+        }
+
+        let mut waited_ms = 0;
+        let delay_ms = retry_delay_ms(failures, long_poll.borrow().is_some());
+        while alive.get() && waited_ms < delay_ms {
+            TimeoutFuture::new(STATUS_CHECK_MS).await;
+            waited_ms += STATUS_CHECK_MS;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_backs_off_then_waits_for_upgrade() {
+        let delays: Vec<u32> = (1..=6).map(|n| retry_delay_ms(n, false)).collect();
+        assert_eq!(delays, [2_000, 4_000, 8_000, 10_000, 10_000, 10_000]);
+        assert_eq!(retry_delay_ms(1, true), WS_UPGRADE_RETRY_MS);
+    }
+}
@@ -1,16 +1,17 @@
 use dioxus::prelude::{Signal, WritableExt};
-use shared_types::{AppDefinition, DesktopState};
+use shared_types::{AppDefinition, BuildVersion, DesktopState};
 use wasm_bindgen::closure::Closure;
 use wasm_bindgen::JsCast;
 
 use crate::api::{
-    fetch_desktop_state, fetch_user_theme_preference, register_app, update_user_theme_preference,
+    fetch_desktop_state, fetch_server_version, fetch_user_theme_preference, register_app,
+    update_user_theme_preference,
 };
+use crate::desktop::connection::{connect_desktop_events, DesktopConnection};
 use crate::desktop::theme::{
     apply_theme_to_document, get_cached_theme_preference, set_cached_theme_preference,
     DEFAULT_THEME,
 };
-use crate::desktop::ws::{connect_websocket, DesktopWsRuntime};
 
 pub async fn track_viewport(mut viewport: Signal<(u32, u32)>) {
     if let Some((w, h)) = current_viewport_size() {
@@ -113,11 +114,11 @@ pub async fn load_initial_desktop_state(
     loading.set(false);
 }
 
-pub fn bootstrap_websocket<F>(desktop_id: String, on_event: F) -> Result<DesktopWsRuntime, String>
+pub fn bootstrap_desktop_events<F>(desktop_id: String, on_event: F) -> DesktopConnection
 where
     F: FnMut(crate::desktop::ws::WsEvent) + 'static,
 {
-    connect_websocket(&desktop_id, on_event)
+    connect_desktop_events(&desktop_id, on_event)
 }
 
 /// Send periodic heartbeats to keep the sandbox alive.
@@ -132,6 +133,45 @@ pub async fn run_heartbeat_loop() {
     }
 }
 
+/// Compare the sandbox's build with this frontend's. Sets `reload_required`
+/// to the sandbox build when their schema versions differ; a failed request
+/// leaves it alone.
+pub async fn check_server_version(mut reload_required: Signal<Option<BuildVersion>>) {
+    match fetch_server_version().await {
+        Ok(server) => {
+            let compatible = BuildVersion::current().is_compatible_with(&server);
+            if !compatible {
+                dioxus_logger::tracing::warn!(
+                    "Frontend schema v{} does not match sandbox schema v{} ({})",
+                    shared_types::SCHEMA_VERSION,
+                    server.schema_version,
+                    server.git_hash
+                );
+            }
+            reload_required.set((!compatible).then_some(server));
+        }
+        Err(e) => dioxus_logger::tracing::warn!("Version check failed: {e}"),
+    }
+}
+
+/// Reload the page under a fresh URL so cached frontend assets are refetched.
+pub fn reload_bypassing_cache(server: &BuildVersion) {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    let location = window.location();
+    let path = location.pathname().unwrap_or_else(|_| "/".to_string());
+    let desktop = crate::desktop::desktop_id_from_url()
+        .and_then(|id| js_sys::encode_uri_component(&id).as_string())
+        .map(|id| format!("desktop={id}&"))
+        .unwrap_or_default();
+    let _ = location.replace(&format!(
+        "{path}?{desktop}v={}-{}",
+        server.git_hash,
+        js_sys::Date::now() as u64
+    ));
+}
+
 pub async fn register_core_apps_once(
     desktop_id: String,
     apps: Vec<AppDefinition>,
@@ -0,0 +1,115 @@
+//! Long-poll transport for desktop events, used when WebSockets are blocked.
+//!
+//! Subscribes through `POST /api/events/subscriptions` and polls
+//! `GET /api/events/poll` in a loop, handing every message to the same
+//! [`WsEvent`] callback the WebSocket uses. A failed poll is retried with the
+//! same cursor, so nothing is lost; an expired session is subscribed again,
+//! which starts over from a fresh `desktop_state`.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use gloo_timers::future::TimeoutFuture;
+use shared_types::EventSubscriptionRequest;
+
+use crate::api::{poll_desktop_events, subscribe_desktop_events};
+use crate::desktop::ws::{ws_event_from_message, WsEvent};
+
+/// How long each poll asks the server to wait for messages.
+const POLL_TIMEOUT_MS: u64 = 25_000;
+
+/// Delay before retrying a failed subscribe or poll.
+const RETRY_DELAY_MS: u32 = 2_000;
+
+/// Stops polling when dropped.
+pub struct DesktopLongPollRuntime {
+    alive: Rc<Cell<bool>>,
+}
+
+impl Drop for DesktopLongPollRuntime {
+    fn drop(&mut self) {
+        self.alive.set(false);
+    }
+}
+
+pub fn connect_long_poll<F>(desktop_id: &str, on_event: F) -> DesktopLongPollRuntime
+where
+    F: FnMut(WsEvent) + 'static,
+{
+    dioxus_logger::tracing::info!("Falling back to long-poll for desktop events");
+    let alive = Rc::new(Cell::new(true));
+    wasm_bindgen_futures::spawn_local(run_long_poll(
+        desktop_id.to_string(),
+        on_event,
+        alive.clone(),
+    ));
+    DesktopLongPollRuntime { alive }
+}
+
+async fn run_long_poll<F>(desktop_id: String, mut on_event: F, alive: Rc<Cell<bool>>)
+where
+    F: FnMut(WsEvent),
+{
+    let request = EventSubscriptionRequest {
+        cursor: None,
+        desktop_id,
+        patch_granularity: None,
+        topics: vec![shared_types::EVENT_TOPIC_CONDUCTOR_RUN_STATE_DELTA.to_string()],
+    };
+    let mut connected = false;
+
+    while alive.get() {
+        let subscribed = subscribe_desktop_events(&request).await;
+        if !alive.get() {
+            return;
+        }
+        let mut cursor = match subscribed {
+            Ok(response) => response.cursor,
+            Err(e) => {
+                dioxus_logger::tracing::warn!("Long-poll subscribe failed: {}", e);
+                if connected {
+                    connected = false;
+                    on_event(WsEvent::Disconnected);
+                }
+                TimeoutFuture::new(RETRY_DELAY_MS).await;
+                continue;
+            }
+        };
+
+        loop {
+            let polled = poll_desktop_events(&cursor, POLL_TIMEOUT_MS).await;
+            if !alive.get() {
+                return;
+            }
+            match polled {
+                Ok(Some(response)) => {
+                    if !connected {
+                        connected = true;
+                        on_event(WsEvent::Connected);
+                    }
+                    cursor = response.next_cursor;
+                    for message in response.messages {
+                        if let Some(event) = ws_event_from_message(message) {
+                            on_event(event);
+                        }
+                    }
+                }
+                Ok(None) => {
+                    dioxus_logger::tracing::info!("Long-poll session expired, subscribing again");
+                    break;
+                }
+                Err(e) => {
+                    dioxus_logger::tracing::warn!("Long-poll request failed: {}", e);
+                    if connected {
+                        connected = false;
+                        on_event(WsEvent::Disconnected);
+                    }
+                    TimeoutFuture::new(RETRY_DELAY_MS).await;
+                    if !alive.get() {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
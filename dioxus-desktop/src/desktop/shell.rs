@@ -4,20 +4,24 @@ use std::rc::Rc;
 
 use dioxus::prelude::*;
 use gloo_timers::future::TimeoutFuture;
-use shared_types::{AppDefinition, DesktopState};
+use shared_types::{AppDefinition, BuildVersion, DesktopState};
 
 use crate::auth::{probe_session, AuthModal, AuthState};
 use crate::desktop::actions;
 use crate::desktop::actions::ShowDesktopSnapshot;
 use crate::desktop::apps::core_apps;
+use crate::desktop::components::new_desktop_dialog::NewDesktopDialog;
 use crate::desktop::components::prompt_bar::{PromptBar, TelemetryStreamState};
 use crate::desktop::components::workspace_canvas::WorkspaceCanvas;
+use crate::desktop::connection::DesktopConnection;
 use crate::desktop::effects;
-use crate::desktop::state::{apply_ws_event, update_writer_runs_from_event};
+use crate::desktop::state::{
+    apply_ws_event, update_conductor_runs_from_delta, update_writer_runs_from_event,
+};
 use crate::desktop::theme::{
     apply_theme_to_document, next_theme, set_cached_theme_preference, DEFAULT_THEME,
 };
-use crate::desktop::ws::{DesktopWsRuntime, WsEvent};
+use crate::desktop::ws::WsEvent;
 use crate::interop::get_viewport_size;
 
 #[component]
@@ -30,7 +34,7 @@ pub fn DesktopShell(desktop_id: String) -> Element {
     let mut loading = use_signal(|| true);
     let mut error = use_signal(|| None::<String>);
     let mut ws_connected = use_signal(|| false);
-    let mut desktop_ws_runtime = use_signal(|| None::<DesktopWsRuntime>);
+    let mut desktop_connection = use_signal(|| None::<DesktopConnection>);
     let ws_event_queue = use_hook(|| Rc::new(RefCell::new(VecDeque::<WsEvent>::new())));
     let mut ws_event_pump_started = use_signal(|| false);
     let ws_event_pump_alive = use_hook(|| Rc::new(Cell::new(true)));
@@ -41,6 +45,11 @@ pub fn DesktopShell(desktop_id: String) -> Element {
     let mut current_theme = use_signal(|| DEFAULT_THEME.to_string());
     let show_desktop_snapshot = use_signal(|| None::<ShowDesktopSnapshot>);
     let mut telemetry_state = use_signal(|| TelemetryStreamState::new(10)); // Max 10 telemetry lines
+    // Set while the provider gateway is unreachable; cleared on recovery.
+    let mut provider_outage = use_signal(|| None::<String>);
+    // Set to the sandbox build when its schema no longer matches this frontend.
+    let reload_required = use_signal(|| None::<BuildVersion>);
+    let mut new_desktop_open = use_signal(|| false);
 
     {
         let ws_event_pump_alive = ws_event_pump_alive.clone();
@@ -55,6 +64,22 @@ pub fn DesktopShell(desktop_id: String) -> Element {
         });
     });
 
+    // Relative times are measured against the server clock, not the client's.
+    use_effect(move || {
+        spawn(async move {
+            crate::time::sync_server_clock().await;
+        });
+    });
+
+    // Re-sent whenever the viewport changes so windows stay reachable.
+    use_effect(move || {
+        let _ = *viewport.read();
+        let desktop_id = desktop_id_signal.read().clone();
+        spawn(async move {
+            actions::sync_desktop_bounds_action(desktop_id).await;
+        });
+    });
+
     // Probe /auth/me once on load so other components know session state.
     use_effect(move || {
         spawn(async move {
@@ -156,6 +181,9 @@ pub fn DesktopShell(desktop_id: String) -> Element {
             heartbeat_started.set(true);
             // Dismiss BIOS boot screen (may already be dismissed by auth modal)
             let _ = js_sys::eval("window.__biosComplete && window.__biosComplete()");
+            spawn(async move {
+                effects::check_server_version(reload_required).await;
+            });
             spawn(async move {
                 effects::run_heartbeat_loop().await;
             });
@@ -202,6 +230,11 @@ pub fn DesktopShell(desktop_id: String) -> Element {
                     }
 
                     for event in drained {
+                        // A reconnect may land on a sandbox that was redeployed.
+                        if matches!(event, WsEvent::Connected) {
+                            spawn(effects::check_server_version(reload_required));
+                        }
+
                         // Handle telemetry events separately
                         if let WsEvent::Telemetry {
                             event_type,
@@ -227,6 +260,16 @@ pub fn DesktopShell(desktop_id: String) -> Element {
                                 phase.clone(),
                                 importance_enum,
                             );
+                        } else if let WsEvent::ProviderGatewayStatus { reachable, message } =
+                            &event
+                        {
+                            provider_outage.set((!reachable).then(|| {
+                                message.clone().unwrap_or_else(|| {
+                                    "The provider gateway is unreachable.".to_string()
+                                })
+                            }));
+                        } else if let WsEvent::ConductorRunStateDelta(delta) = &event {
+                            update_conductor_runs_from_delta(delta);
                         } else if matches!(
                             event,
                             WsEvent::WriterRunStarted { .. }
@@ -235,6 +278,7 @@ pub fn DesktopShell(desktop_id: String) -> Element {
                                 | WsEvent::WriterRunChangeset { .. }
                                 | WsEvent::WriterRunStatus { .. }
                                 | WsEvent::WriterRunFailed { .. }
+                                | WsEvent::WriterRunLock { .. }
                         ) {
                             update_writer_runs_from_event(&event);
                         } else {
@@ -253,30 +297,24 @@ pub fn DesktopShell(desktop_id: String) -> Element {
         use_effect(move || {
             let auth_state = auth.read().clone();
             if !matches!(auth_state, AuthState::Authenticated(_)) {
-                if desktop_ws_runtime.read().is_some() {
-                    desktop_ws_runtime.set(None);
+                if desktop_connection.read().is_some() {
+                    desktop_connection.set(None);
                 }
                 ws_connected.set(false);
                 return;
             }
 
             let desktop_id = desktop_id_signal.read().clone();
-            if desktop_ws_runtime.read().is_some() {
+            if let Some(connection) = desktop_connection.read().as_ref() {
+                connection.switch_desktop(&desktop_id);
                 return;
             }
 
             let ws_event_queue_for_cb = ws_event_queue.clone();
-            match effects::bootstrap_websocket(desktop_id, move |event| {
+            let connection = effects::bootstrap_desktop_events(desktop_id, move |event| {
                 ws_event_queue_for_cb.borrow_mut().push_back(event);
-            }) {
-                Ok(runtime) => {
-                    desktop_ws_runtime.set(Some(runtime));
-                }
-                Err(e) => {
-                    ws_connected.set(false);
-                    error.set(Some(format!("Failed to connect desktop websocket: {e}")));
-                }
-            }
+            });
+            desktop_connection.set(Some(connection));
         });
     }
 
@@ -385,6 +423,29 @@ pub fn DesktopShell(desktop_id: String) -> Element {
             class: "desktop-shell",
             style: "width: 100vw; height: 100dvh; min-height: 100dvh; max-height: 100dvh; display: flex; flex-direction: column; overflow: hidden;",
 
+            if let Some(server) = reload_required() {
+                div {
+                    class: "reload-required-banner",
+                    role: "alert",
+                    style: "flex-shrink: 0; display: flex; align-items: center; justify-content: center; gap: 0.75rem; padding: 0.5rem 1rem; background: var(--danger-bg, #dc2626); color: #fff; font-size: 0.875rem; border-bottom: 1px solid var(--border-color);",
+                    span { "ChoirOS was updated ({server.git_hash}). Reload to keep working; this page may misbehave until you do." }
+                    button {
+                        style: "padding: 0.25rem 0.75rem; border: 1px solid #fff; border-radius: 4px; background: transparent; color: inherit; cursor: pointer;",
+                        onclick: move |_| effects::reload_bypassing_cache(&server),
+                        "Reload"
+                    }
+                }
+            }
+
+            if let Some(message) = provider_outage() {
+                div {
+                    class: "provider-outage-banner",
+                    role: "status",
+                    style: "flex-shrink: 0; padding: 0.5rem 1rem; background: var(--warning-bg, #f59e0b); color: #1c1917; font-size: 0.875rem; text-align: center; border-bottom: 1px solid var(--border-color);",
+                    "{message} Runs you start now are queued and will resume automatically."
+                }
+            }
+
             WorkspaceCanvas {
                 desktop_id: desktop_id_signal.read().clone(),
                 apps: workspace_apps,
@@ -411,12 +472,19 @@ pub fn DesktopShell(desktop_id: String) -> Element {
                 desktop_id: desktop_id_signal.read().clone(),
                 on_focus_window: focus_window_cb,
                 on_show_desktop: show_desktop_cb,
+                on_new_desktop: move |_| new_desktop_open.set(true),
                 current_theme: current_theme(),
                 on_toggle_theme: toggle_theme,
                 telemetry_state,
             }
         }
 
+        if new_desktop_open() {
+            NewDesktopDialog {
+                on_close: move |_| new_desktop_open.set(false),
+            }
+        }
+
         // Auth modal — renders as fixed overlay when AuthState::Required
         AuthModal {}
     }
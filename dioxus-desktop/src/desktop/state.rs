@@ -1,7 +1,7 @@
 use dioxus::prelude::{Signal, WritableExt};
 use shared_types::{
-    AppDefinition, ChangesetImpact, DesktopState, PatchOp, PatchSource, WindowState,
-    WriterRunStatusKind,
+    AppDefinition, ChangesetImpact, ConductorRunState, ConductorRunStateDelta, DesktopState,
+    PatchOp, PatchSource, RunDeltaOutcome, WindowState, WriterRunLockPayload, WriterRunStatusKind,
 };
 
 use crate::desktop::ws::WsEvent;
@@ -46,6 +46,34 @@ pub struct ActiveWriterRun {
     pub last_applied_revision: u64,
     /// Recent changeset summaries from writer.run.changeset events (capped at 20)
     pub recent_changesets: Vec<LiveChangeset>,
+    /// Block locks from writer.run.lock events, including lapsed ones
+    pub block_locks: Vec<WriterRunLockPayload>,
+}
+
+impl ActiveWriterRun {
+    /// Ids of the blocks an agent holds a lock on at `now`.
+    pub fn locked_block_ids(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> std::collections::HashSet<String> {
+        self.block_locks
+            .iter()
+            .filter(|lock| lock.is_active(now))
+            .flat_map(|lock| lock.block_ids.iter().cloned())
+            .collect()
+    }
+
+    /// Earliest expiry among the locks still held at `now`.
+    pub fn next_lock_expiry(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.block_locks
+            .iter()
+            .filter(|lock| lock.is_active(now))
+            .map(|lock| lock.expires_at)
+            .min()
+    }
 }
 
 impl Default for ActiveWriterRun {
@@ -64,6 +92,7 @@ impl Default for ActiveWriterRun {
             pending_patches: Vec::new(),
             last_applied_revision: 0,
             recent_changesets: Vec::new(),
+            block_locks: Vec::new(),
         }
     }
 }
@@ -73,6 +102,62 @@ pub static ACTIVE_WRITER_RUNS: dioxus::signals::GlobalSignal<
     std::collections::HashMap<String, ActiveWriterRun>,
 > = dioxus::signals::GlobalSignal::new(std::collections::HashMap::new);
 
+/// Global signal for conductor runs followed through state deltas, keyed by
+/// run_id. A run is held only while someone follows it.
+pub static CONDUCTOR_RUNS: dioxus::signals::GlobalSignal<
+    std::collections::HashMap<String, ConductorRunState>,
+> = dioxus::signals::GlobalSignal::new(std::collections::HashMap::new);
+
+/// Start following a run from a fetched state, replacing any copy held.
+pub fn follow_conductor_run(run: ConductorRunState) {
+    CONDUCTOR_RUNS.write().insert(run.run_id.clone(), run);
+}
+
+/// Stop following a run and drop its state.
+pub fn unfollow_conductor_run(run_id: &str) {
+    CONDUCTOR_RUNS.write().remove(run_id);
+}
+
+/// Apply a state delta to the followed runs. Deltas of runs nobody follows
+/// and redelivered deltas are ignored; a gap means the run has to be
+/// refetched.
+pub fn apply_conductor_run_delta(
+    runs: &mut std::collections::HashMap<String, ConductorRunState>,
+    delta: &ConductorRunStateDelta,
+) -> RunDeltaOutcome {
+    match runs.get_mut(&delta.run_id) {
+        Some(run) => run.apply_delta(delta),
+        None => RunDeltaOutcome::Ignored,
+    }
+}
+
+/// Replace a followed run with a refetched state, unless it was unfollowed
+/// meanwhile or deltas already carried it past the fetched revision.
+pub fn replace_followed_conductor_run(
+    runs: &mut std::collections::HashMap<String, ConductorRunState>,
+    run: ConductorRunState,
+) {
+    if let Some(followed) = runs.get_mut(&run.run_id) {
+        if run.revision > followed.revision {
+            *followed = run;
+        }
+    }
+}
+
+/// Update the followed runs from a state delta, refetching on a revision gap
+pub fn update_conductor_runs_from_delta(delta: &ConductorRunStateDelta) {
+    let outcome = apply_conductor_run_delta(&mut CONDUCTOR_RUNS.write(), delta);
+    if outcome != RunDeltaOutcome::Gap {
+        return;
+    }
+    let run_id = delta.run_id.clone();
+    dioxus::prelude::spawn(async move {
+        if let Ok(run) = crate::api::conductor_get_run_state(&run_id).await {
+            replace_followed_conductor_run(&mut CONDUCTOR_RUNS.write(), run);
+        }
+    });
+}
+
 fn merge_source_refs(run: &mut ActiveWriterRun, incoming: &[String], prioritize: bool) {
     if incoming.is_empty() {
         return;
@@ -153,6 +238,7 @@ pub fn apply_writer_runs_event(
                     pending_patches: vec![patch],
                     last_applied_revision: 0,
                     recent_changesets: Vec::new(),
+                    block_locks: Vec::new(),
                 };
                 runs.insert(base.document_path.clone(), run);
             }
@@ -172,6 +258,7 @@ pub fn apply_writer_runs_event(
                 pending_patches: Vec::new(),
                 last_applied_revision: 0,
                 recent_changesets: Vec::new(),
+                block_locks: Vec::new(),
             };
             runs.insert(base.document_path.clone(), run);
         }
@@ -214,6 +301,7 @@ pub fn apply_writer_runs_event(
                         pending_patches: Vec::new(),
                         last_applied_revision: 0,
                         recent_changesets: Vec::new(),
+                        block_locks: Vec::new(),
                     },
                 );
             }
@@ -229,6 +317,12 @@ pub fn apply_writer_runs_event(
                 if let Some(msg) = message {
                     run.message = Some(msg.clone());
                 }
+                if matches!(
+                    status,
+                    WriterRunStatusKind::Completed | WriterRunStatusKind::Failed
+                ) {
+                    run.block_locks.clear();
+                }
             } else {
                 runs.insert(
                     base.document_path.clone(),
@@ -246,6 +340,7 @@ pub fn apply_writer_runs_event(
                         pending_patches: Vec::new(),
                         last_applied_revision: 0,
                         recent_changesets: Vec::new(),
+                        block_locks: Vec::new(),
                     },
                 );
             }
@@ -260,6 +355,7 @@ pub fn apply_writer_runs_event(
                 run.revision = base.revision;
                 run.status = WriterRunStatusKind::Failed;
                 run.message = Some(error_message.clone());
+                run.block_locks.clear();
             } else {
                 runs.insert(
                     base.document_path.clone(),
@@ -277,6 +373,7 @@ pub fn apply_writer_runs_event(
                         pending_patches: Vec::new(),
                         last_applied_revision: 0,
                         recent_changesets: Vec::new(),
+                        block_locks: Vec::new(),
                     },
                 );
             }
@@ -325,6 +422,22 @@ pub fn apply_writer_runs_event(
                 runs.insert(key, new_run);
             }
         }
+        WsEvent::WriterRunLock { base, payload } => {
+            let run = runs
+                .entry(base.document_path.clone())
+                .or_insert_with(|| ActiveWriterRun {
+                    run_id: base.run_id.clone(),
+                    document_path: base.document_path.clone(),
+                    revision: base.revision,
+                    status: WriterRunStatusKind::Running,
+                    ..Default::default()
+                });
+            run.block_locks
+                .retain(|lock| lock.lock_id != payload.lock_id);
+            if !payload.released {
+                run.block_locks.push(payload.clone());
+            }
+        }
         _ => {}
     }
 }
@@ -472,7 +585,10 @@ pub fn apply_desktop_state_event(
         | WsEvent::WriterRunPatch { .. }
         | WsEvent::WriterRunStatus { .. }
         | WsEvent::WriterRunFailed { .. }
-        | WsEvent::WriterRunChangeset { .. } => state,
+        | WsEvent::WriterRunChangeset { .. }
+        | WsEvent::WriterRunLock { .. }
+        | WsEvent::ConductorRunStateDelta(_)
+        | WsEvent::ProviderGatewayStatus { .. } => state,
     }
 }
 
@@ -510,12 +626,78 @@ pub fn focus_window_and_raise_z(state: &mut DesktopState, window_id: &str) {
 
 #[cfg(test)]
 mod ws_state_tests {
-    use super::apply_writer_runs_event;
+    use super::{
+        apply_conductor_run_delta, apply_writer_runs_event, replace_followed_conductor_run,
+    };
     use crate::desktop::ws::WsEvent;
     use chrono::{TimeZone, Utc};
-    use shared_types::{ChangesetImpact, WriterRunEventBase};
+    use shared_types::{
+        ChangesetImpact, ConductorOutputMode, ConductorRunState, ConductorRunStateDelta,
+        ConductorRunStatus, RunDeltaOutcome, WriterRunEventBase, WriterRunLockPayload,
+        WriterRunStatusKind,
+    };
     use std::collections::HashMap;
 
+    #[test]
+    fn conductor_run_deltas_apply_to_followed_runs_and_report_gaps() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 13, 22, 0, 0).unwrap();
+        let run = ConductorRunState {
+            run_id: "run-1".to_string(),
+            objective: "Draft the answer".to_string(),
+            status: ConductorRunStatus::Running,
+            created_at: now,
+            updated_at: now,
+            completed_at: None,
+            agenda: Vec::new(),
+            active_calls: Vec::new(),
+            artifacts: Vec::new(),
+            decision_log: Vec::new(),
+            document_path: "conductor/runs/run-1/draft.md".to_string(),
+            output_mode: ConductorOutputMode::Auto,
+            desktop_id: "desktop-1".to_string(),
+            revision: 2,
+            contract: None,
+        };
+        let mut completed = ConductorRunStateDelta::next(&run, now);
+        completed.status = Some(ConductorRunStatus::Completed);
+        completed.completed_at = Some(now);
+
+        let mut runs = HashMap::new();
+        assert_eq!(
+            apply_conductor_run_delta(&mut runs, &completed),
+            RunDeltaOutcome::Ignored
+        );
+        assert!(runs.is_empty(), "unfollowed runs are not tracked");
+
+        runs.insert(run.run_id.clone(), run.clone());
+        let mut skipped = completed.clone();
+        skipped.revision = 5;
+        assert_eq!(
+            apply_conductor_run_delta(&mut runs, &skipped),
+            RunDeltaOutcome::Gap
+        );
+        assert_eq!(
+            apply_conductor_run_delta(&mut runs, &completed),
+            RunDeltaOutcome::Applied
+        );
+        assert_eq!(
+            apply_conductor_run_delta(&mut runs, &completed),
+            RunDeltaOutcome::Ignored,
+            "a redelivered delta does not trigger a refetch"
+        );
+        assert_eq!(runs["run-1"].revision, 3);
+        assert_eq!(runs["run-1"].status, ConductorRunStatus::Completed);
+        assert_eq!(runs["run-1"].completed_at, Some(now));
+
+        // A refetch that lands after newer deltas, or after the run was
+        // unfollowed, changes nothing.
+        replace_followed_conductor_run(&mut runs, run.clone());
+        assert_eq!(runs["run-1"].revision, 3);
+        runs.clear();
+        replace_followed_conductor_run(&mut runs, run);
+        assert!(runs.is_empty());
+    }
+
     #[test]
     fn changeset_events_attach_to_existing_writer_run_by_document_path() {
         let mut runs = HashMap::new();
@@ -560,6 +742,80 @@ mod ws_state_tests {
             "Tightened the opening section."
         );
     }
+
+    #[test]
+    fn lock_events_track_locked_blocks_until_release_expiry_or_completion() {
+        let mut runs = HashMap::new();
+        let now = Utc.with_ymd_and_hms(2026, 3, 13, 22, 0, 0).unwrap();
+        let base = WriterRunEventBase {
+            desktop_id: "desktop-1".to_string(),
+            session_id: "session-1".to_string(),
+            thread_id: "thread-1".to_string(),
+            run_id: "run-1".to_string(),
+            document_path: "conductor/runs/run-1/draft.md".to_string(),
+            revision: 4,
+            head_version_id: None,
+            timestamp: now,
+        };
+        let lock = |lock_id: &str, block_id: &str, secs: i64| WriterRunLockPayload {
+            lock_id: lock_id.to_string(),
+            block_ids: vec![block_id.to_string()],
+            ttl_ms: (secs * 1000) as u64,
+            expires_at: now + chrono::Duration::seconds(secs),
+            released: false,
+            reason: None,
+        };
+        let path = base.document_path.clone();
+
+        for payload in [lock("lock-1", "blk-a", 60), lock("lock-2", "blk-b", 5)] {
+            apply_writer_runs_event(
+                &mut runs,
+                &WsEvent::WriterRunLock {
+                    base: base.clone(),
+                    payload,
+                },
+            );
+        }
+        let run = &runs[&path];
+        assert_eq!(run.locked_block_ids(now).len(), 2);
+        assert_eq!(
+            run.next_lock_expiry(now),
+            Some(now + chrono::Duration::seconds(5))
+        );
+        let later = now + chrono::Duration::seconds(10);
+        assert_eq!(
+            run.locked_block_ids(later).into_iter().collect::<Vec<_>>(),
+            vec!["blk-a".to_string()]
+        );
+
+        let mut released = lock("lock-1", "blk-a", 60);
+        released.released = true;
+        apply_writer_runs_event(
+            &mut runs,
+            &WsEvent::WriterRunLock {
+                base: base.clone(),
+                payload: released,
+            },
+        );
+        assert!(runs[&path].locked_block_ids(later).is_empty());
+
+        apply_writer_runs_event(
+            &mut runs,
+            &WsEvent::WriterRunLock {
+                base: base.clone(),
+                payload: lock("lock-3", "blk-c", 60),
+            },
+        );
+        apply_writer_runs_event(
+            &mut runs,
+            &WsEvent::WriterRunStatus {
+                base,
+                status: WriterRunStatusKind::Completed,
+                message: None,
+            },
+        );
+        assert!(runs[&path].locked_block_ids(now).is_empty());
+    }
 }
 
 #[cfg(test)]
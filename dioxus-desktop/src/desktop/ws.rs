@@ -3,8 +3,9 @@ use std::cell::RefCell;
 use std::rc::Rc;
 
 use shared_types::{
-    AppDefinition, ChangesetImpact, DesktopState, DesktopWsMessage, EventImportance,
-    FailureKind, WindowState, WriterRunEventBase, WriterRunPatchPayload, WriterRunStatusKind,
+    AppDefinition, ChangesetImpact, ConductorRunStateDelta, DesktopState, DesktopWsMessage,
+    EventImportance, FailureKind, WindowState, WriterRunEventBase, WriterRunLockPayload,
+    WriterRunPatchPayload, WriterRunStatusKind,
 };
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
@@ -12,6 +13,8 @@ use web_sys::{MessageEvent, WebSocket};
 
 pub struct DesktopWsRuntime {
     ws: WebSocket,
+    /// Desktop the socket subscribes to once open.
+    desktop_id: Rc<RefCell<String>>,
     closing: Rc<Cell<bool>>,
     _on_open: Closure<dyn FnMut(wasm_bindgen::JsValue)>,
     _on_message: Closure<dyn FnMut(MessageEvent)>,
@@ -72,6 +75,8 @@ pub enum WsEvent {
         content_excerpt: String,
         timestamp: String,
     },
+    /// Changed fields of a conductor run, applied to the followed run state
+    ConductorRunStateDelta(ConductorRunStateDelta),
     /// Writer run started event
     WriterRunStarted {
         base: WriterRunEventBase,
@@ -112,6 +117,16 @@ pub enum WsEvent {
         impact: ChangesetImpact,
         op_taxonomy: Vec<String>,
     },
+    /// Writer block locks declared or released; drives the "agent editing" shimmer
+    WriterRunLock {
+        base: WriterRunEventBase,
+        payload: WriterRunLockPayload,
+    },
+    /// Provider gateway reachability changed; drives the outage banner
+    ProviderGatewayStatus {
+        reachable: bool,
+        message: Option<String>,
+    },
     Pong,
     Error(String),
 }
@@ -141,7 +156,12 @@ pub fn http_to_ws_url(http_url: &str) -> String {
 
 pub fn parse_ws_message(payload: &str) -> Option<WsEvent> {
     let message: DesktopWsMessage = serde_json::from_str(payload).ok()?;
+    ws_event_from_message(message)
+}
 
+/// The UI event for a desktop protocol message, whichever transport carried
+/// it; client-to-server messages have none.
+pub fn ws_event_from_message(message: DesktopWsMessage) -> Option<WsEvent> {
     match message {
         DesktopWsMessage::Pong => Some(WsEvent::Pong),
         DesktopWsMessage::DesktopState { desktop } => Some(WsEvent::DesktopStateUpdate(desktop)),
@@ -206,6 +226,9 @@ pub fn parse_ws_message(payload: &str) -> Option<WsEvent> {
             content_excerpt: payload.content_excerpt,
             timestamp: payload.timestamp,
         }),
+        DesktopWsMessage::ConductorRunStateDelta { delta } => {
+            Some(WsEvent::ConductorRunStateDelta(delta))
+        }
         DesktopWsMessage::WriterRunStarted { base, objective } => {
             Some(WsEvent::WriterRunStarted { base, objective })
         }
@@ -255,8 +278,17 @@ pub fn parse_ws_message(payload: &str) -> Option<WsEvent> {
                 op_taxonomy: payload.op_taxonomy,
             })
         }
-        DesktopWsMessage::Error { message } => Some(WsEvent::Error(message)),
-        DesktopWsMessage::Subscribe { .. } | DesktopWsMessage::Ping => None,
+        DesktopWsMessage::WriterRunLock { base, payload } => {
+            Some(WsEvent::WriterRunLock { base, payload })
+        }
+        DesktopWsMessage::ProviderGatewayStatus { reachable, message } => {
+            Some(WsEvent::ProviderGatewayStatus { reachable, message })
+        }
+        DesktopWsMessage::Error { message, .. } => Some(WsEvent::Error(message)),
+        DesktopWsMessage::Subscribe { .. }
+        | DesktopWsMessage::Unsubscribe { .. }
+        | DesktopWsMessage::UpdateSubscription { .. }
+        | DesktopWsMessage::Ping => None,
     }
 }
 
@@ -291,18 +323,15 @@ where
     let on_event_open = on_event_rc.clone();
     let on_event_close = on_event_rc.clone();
     let closing_for_close = closing.clone();
-    let desktop_id_clone = desktop_id.to_string();
+    let desktop_id = Rc::new(RefCell::new(desktop_id.to_string()));
+    let desktop_id_clone = desktop_id.clone();
     let ws_clone = ws.clone();
 
     let onopen_callback = Closure::wrap(Box::new(move |_e: wasm_bindgen::JsValue| {
         dioxus_logger::tracing::info!("WebSocket connected");
         on_event_open.borrow_mut()(WsEvent::Connected);
 
-        if let Ok(subscribe_msg) =
-            serde_json::to_string(&DesktopWsMessage::Subscribe { desktop_id: desktop_id_clone.clone() })
-        {
-            let _ = ws_clone.send_with_str(&subscribe_msg);
-        }
+        send_message(&ws_clone, &subscribe_message(&desktop_id_clone.borrow()));
     }) as Box<dyn FnMut(wasm_bindgen::JsValue)>);
     ws.set_onopen(Some(onopen_callback.as_ref().unchecked_ref()));
 
@@ -343,6 +372,7 @@ where
 
     Ok(DesktopWsRuntime {
         ws,
+        desktop_id,
         closing,
         _on_open: onopen_callback,
         _on_message: onmessage_callback,
@@ -351,6 +381,40 @@ where
     })
 }
 
+impl DesktopWsRuntime {
+    /// Follow `desktop_id` instead of the current desktop. An open socket
+    /// unsubscribes from the old desktop first, so it stops counting against
+    /// the session's subscriptions; one still connecting subscribes to the
+    /// new desktop when it opens.
+    pub fn switch_desktop(&self, desktop_id: &str) {
+        let previous = self.desktop_id.replace(desktop_id.to_string());
+        if previous == desktop_id || self.ws.ready_state() != WebSocket::OPEN {
+            return;
+        }
+        send_message(
+            &self.ws,
+            &DesktopWsMessage::Unsubscribe {
+                desktop_id: previous,
+            },
+        );
+        send_message(&self.ws, &subscribe_message(desktop_id));
+    }
+}
+
+fn subscribe_message(desktop_id: &str) -> DesktopWsMessage {
+    DesktopWsMessage::Subscribe {
+        desktop_id: desktop_id.to_string(),
+        patch_granularity: None,
+        topics: vec![shared_types::EVENT_TOPIC_CONDUCTOR_RUN_STATE_DELTA.to_string()],
+    }
+}
+
+fn send_message(ws: &WebSocket, message: &DesktopWsMessage) {
+    if let Ok(text) = serde_json::to_string(message) {
+        let _ = ws.send_with_str(&text);
+    }
+}
+
 impl Drop for DesktopWsRuntime {
     fn drop(&mut self) {
         self.closing.set(true);
@@ -6,7 +6,7 @@ use wasm_bindgen::closure::Closure;
 use wasm_bindgen::JsCast;
 
 use crate::components::{
-    load_files_path, FilesView, LogsView, SettingsView, TraceView, WriterView,
+    load_files_path, FilesView, LogsView, ResearchView, SettingsView, TraceView, WriterView,
 };
 use crate::terminal::TerminalView;
 use crate::viewers::{parse_viewer_window_props, ViewerShell};
@@ -1146,6 +1146,13 @@ pub fn FloatingWindow(
                             window_id: window.id.clone(),
                         }
                     },
+                    "research" => rsx! {
+                        ResearchView {
+                            key: "{window.id}",
+                            desktop_id: desktop_id.clone(),
+                            window_id: window.id.clone(),
+                        }
+                    },
                     "trace" => rsx! {
                         TraceView {
                             key: "{window.id}",
@@ -1322,6 +1329,7 @@ fn get_app_icon(app_id: &str) -> &'static str {
         "files" => "📁",
         "logs" => "📡",
         "trace" => "🔍",
+        "research" => "🔬",
         "settings" => "⚙️",
         _ => "📱",
     }
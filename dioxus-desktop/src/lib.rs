@@ -5,6 +5,7 @@ pub mod desktop;
 pub mod desktop_window;
 pub mod interop;
 pub mod terminal;
+pub mod time;
 pub mod viewers;
 
 pub use api::*;
@@ -21,7 +22,9 @@ use dioxus::prelude::*;
 #[component]
 fn App() -> Element {
     rsx! {
-        Desktop { desktop_id: "default-desktop".to_string() }
+        Desktop {
+            desktop_id: desktop_id_from_url().unwrap_or_else(|| DEFAULT_DESKTOP_ID.to_string()),
+        }
     }
 }
 
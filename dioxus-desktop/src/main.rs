@@ -2,7 +2,7 @@ use dioxus::launch;
 use dioxus::prelude::*;
 use dioxus_logger::tracing::Level;
 
-use dioxus_desktop::Desktop;
+use dioxus_desktop::{desktop_id_from_url, Desktop, DEFAULT_DESKTOP_ID};
 
 fn main() {
     // Initialize logging for WASM
@@ -15,6 +15,8 @@ fn main() {
 #[component]
 fn App() -> Element {
     rsx! {
-        Desktop { desktop_id: "default-desktop".to_string() }
+        Desktop {
+            desktop_id: desktop_id_from_url().unwrap_or_else(|| DEFAULT_DESKTOP_ID.to_string()),
+        }
     }
 }
@@ -0,0 +1,252 @@
+//! Timestamp and number formatting for the UI.
+//!
+//! Absolute times and numbers render in the browser locale. Relative times
+//! ("3m ago") are measured against an estimate of the server clock, because
+//! event timestamps are stamped by the server and the client clock can be
+//! minutes off. The estimate comes from `GET /api/time`, sampled by
+//! [`sync_server_clock`] for the lifetime of the desktop.
+
+use std::cell::{Cell, RefCell};
+
+use chrono::{DateTime, Utc};
+use gloo_timers::future::TimeoutFuture;
+use js_sys::{Array, Function, Intl, Object, Reflect};
+use wasm_bindgen::JsValue;
+
+use crate::api::fetch_server_time;
+
+/// How often the server clock is re-sampled.
+const SERVER_CLOCK_REFRESH_MS: u32 = 60_000;
+
+/// A sample older than this on the server's monotonic clock is replaced even
+/// by one with a slower round trip, so drift does not accumulate.
+const MAX_SAMPLE_AGE_MS: u64 = 10 * 60_000;
+
+/// One `GET /api/time` exchange, reduced to the server-minus-client offset.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClockSample {
+    pub offset_ms: f64,
+    pub round_trip_ms: f64,
+    pub monotonic_ms: u64,
+}
+
+impl ClockSample {
+    /// Sample from a request sent at `sent_ms` and answered at `received_ms`
+    /// on the client clock, carrying the server time `server_ms`. The server
+    /// is assumed to have read its clock halfway through the round trip.
+    pub fn from_exchange(
+        sent_ms: f64,
+        received_ms: f64,
+        server_ms: f64,
+        monotonic_ms: u64,
+    ) -> Self {
+        let round_trip_ms = (received_ms - sent_ms).max(0.0);
+        Self {
+            offset_ms: server_ms - (sent_ms + round_trip_ms / 2.0),
+            round_trip_ms,
+            monotonic_ms,
+        }
+    }
+}
+
+/// The sample to keep after `next` arrives. A faster round trip bounds the
+/// offset error more tightly, so it wins; a server restart or an aged current
+/// sample always gives way to the new one.
+pub fn preferred_sample(current: Option<ClockSample>, next: ClockSample) -> ClockSample {
+    let Some(current) = current else {
+        return next;
+    };
+    let restarted = next.monotonic_ms < current.monotonic_ms;
+    let aged = next.monotonic_ms.saturating_sub(current.monotonic_ms) > MAX_SAMPLE_AGE_MS;
+    if restarted || aged || next.round_trip_ms <= current.round_trip_ms {
+        next
+    } else {
+        current
+    }
+}
+
+/// Server time for a client clock reading, corrected by `sample` if any.
+pub fn corrected_now_ms(client_now_ms: f64, sample: Option<ClockSample>) -> f64 {
+    client_now_ms + sample.map(|sample| sample.offset_ms).unwrap_or(0.0)
+}
+
+/// "just now", "5m ago", "3h ago" or "2d ago" for `then_ms` seen at `now_ms`.
+/// Times slightly in the future (residual skew) read as "just now".
+pub fn relative_between(then_ms: i64, now_ms: i64) -> String {
+    let diff_secs = ((now_ms - then_ms) / 1000).max(0);
+    match diff_secs {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{}m ago", diff_secs / 60),
+        3600..=86399 => format!("{}h ago", diff_secs / 3600),
+        _ => format!("{}d ago", diff_secs / 86400),
+    }
+}
+
+thread_local! {
+    static SERVER_CLOCK: Cell<Option<ClockSample>> = const { Cell::new(None) };
+    static DATE_TIME_FORMAT: RefCell<Option<Function>> = const { RefCell::new(None) };
+    static DATE_FORMAT: RefCell<Option<Function>> = const { RefCell::new(None) };
+    static NUMBER_FORMAT: RefCell<Option<Function>> = const { RefCell::new(None) };
+}
+
+/// Best current estimate of the server clock, in epoch milliseconds.
+pub fn server_now_ms() -> f64 {
+    corrected_now_ms(js_sys::Date::now(), SERVER_CLOCK.with(Cell::get))
+}
+
+/// Sample the server clock now and then every [`SERVER_CLOCK_REFRESH_MS`].
+pub async fn sync_server_clock() {
+    loop {
+        let sent_ms = js_sys::Date::now();
+        match fetch_server_time().await {
+            Ok(time) => {
+                let sample = ClockSample::from_exchange(
+                    sent_ms,
+                    js_sys::Date::now(),
+                    time.now.timestamp_millis() as f64,
+                    time.monotonic_ms,
+                );
+                SERVER_CLOCK.with(|clock| clock.set(Some(preferred_sample(clock.get(), sample))));
+            }
+            Err(e) => dioxus_logger::tracing::warn!("server clock sample failed: {e}"),
+        }
+        TimeoutFuture::new(SERVER_CLOCK_REFRESH_MS).await;
+    }
+}
+
+/// Relative time of `timestamp` against the server clock.
+pub fn relative_time(timestamp: &DateTime<Utc>) -> String {
+    relative_between(timestamp.timestamp_millis(), server_now_ms() as i64)
+}
+
+/// [`relative_time`] for an RFC 3339 string; unparseable input is returned as is.
+pub fn relative_time_str(timestamp: &str) -> String {
+    parse_rfc3339(timestamp)
+        .map(|dt| relative_time(&dt))
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+/// Date and time in the browser locale, e.g. "Mar 13, 2026, 10:00 PM".
+pub fn format_timestamp(timestamp: &DateTime<Utc>) -> String {
+    let date = js_sys::Date::new(&JsValue::from_f64(timestamp.timestamp_millis() as f64));
+    format_with(&DATE_TIME_FORMAT, || date_time_format(true), &date.into())
+        .unwrap_or_else(|| timestamp.format("%Y-%m-%d %H:%M").to_string())
+}
+
+/// [`format_timestamp`] for an RFC 3339 string; unparseable input is returned as is.
+pub fn format_timestamp_str(timestamp: &str) -> String {
+    parse_rfc3339(timestamp)
+        .map(|dt| format_timestamp(&dt))
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+/// Date only in the browser locale, e.g. "Mar 13, 2026".
+pub fn format_date(timestamp: &DateTime<Utc>) -> String {
+    let date = js_sys::Date::new(&JsValue::from_f64(timestamp.timestamp_millis() as f64));
+    format_with(&DATE_FORMAT, || date_time_format(false), &date.into())
+        .unwrap_or_else(|| timestamp.format("%Y-%m-%d").to_string())
+}
+
+/// [`format_date`] for an RFC 3339 string; unparseable input is returned as is.
+pub fn format_date_str(timestamp: &str) -> String {
+    parse_rfc3339(timestamp)
+        .map(|dt| format_date(&dt))
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+/// A number with the browser locale's grouping and decimal separators.
+pub fn format_number(value: f64) -> String {
+    format_with(
+        &NUMBER_FORMAT,
+        || Intl::NumberFormat::new(&Array::new(), &Object::new()).format(),
+        &JsValue::from_f64(value),
+    )
+    .unwrap_or_else(|| value.to_string())
+}
+
+fn parse_rfc3339(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+fn date_time_format(with_time: bool) -> Function {
+    let options = Object::new();
+    let _ = Reflect::set(&options, &"dateStyle".into(), &"medium".into());
+    if with_time {
+        let _ = Reflect::set(&options, &"timeStyle".into(), &"short".into());
+    }
+    Intl::DateTimeFormat::new(&Array::new(), &options).format()
+}
+
+/// Format `value` with a lazily built, cached `Intl` formatter.
+fn format_with(
+    cache: &'static std::thread::LocalKey<RefCell<Option<Function>>>,
+    build: impl FnOnce() -> Function,
+    value: &JsValue,
+) -> Option<String> {
+    cache.with(|cell| {
+        let mut cell = cell.borrow_mut();
+        let format = cell.get_or_insert_with(build);
+        format.call1(&JsValue::UNDEFINED, value).ok()?.as_string()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offset_assumes_the_server_read_its_clock_mid_flight() {
+        // Client clock runs 5s behind; 200ms round trip.
+        let sample = ClockSample::from_exchange(1_000.0, 1_200.0, 6_100.0, 42);
+        assert_eq!(sample.offset_ms, 5_000.0);
+        assert_eq!(sample.round_trip_ms, 200.0);
+        assert_eq!(corrected_now_ms(2_000.0, Some(sample)), 7_000.0);
+        assert_eq!(corrected_now_ms(2_000.0, None), 2_000.0);
+    }
+
+    #[test]
+    fn faster_restarted_or_aged_samples_replace_the_current_one() {
+        let current = ClockSample {
+            offset_ms: 5_000.0,
+            round_trip_ms: 100.0,
+            monotonic_ms: 60_000,
+        };
+        let slower = ClockSample {
+            offset_ms: 5_400.0,
+            round_trip_ms: 900.0,
+            monotonic_ms: 120_000,
+        };
+        assert_eq!(preferred_sample(None, slower), slower);
+        assert_eq!(preferred_sample(Some(current), slower), current);
+
+        let faster = ClockSample {
+            round_trip_ms: 50.0,
+            ..slower
+        };
+        assert_eq!(preferred_sample(Some(current), faster), faster);
+
+        let restarted = ClockSample {
+            monotonic_ms: 1_000,
+            ..slower
+        };
+        assert_eq!(preferred_sample(Some(current), restarted), restarted);
+
+        let aged = ClockSample {
+            monotonic_ms: current.monotonic_ms + MAX_SAMPLE_AGE_MS + 1,
+            ..slower
+        };
+        assert_eq!(preferred_sample(Some(current), aged), aged);
+    }
+
+    #[test]
+    fn relative_times_bucket_by_magnitude() {
+        let now = 10 * 86_400_000;
+        assert_eq!(relative_between(now - 30_000, now), "just now");
+        assert_eq!(relative_between(now + 5_000, now), "just now");
+        assert_eq!(relative_between(now - 3 * 60_000, now), "3m ago");
+        assert_eq!(relative_between(now - 2 * 3_600_000, now), "2h ago");
+        assert_eq!(relative_between(now - 3 * 86_400_000, now), "3d ago");
+    }
+}
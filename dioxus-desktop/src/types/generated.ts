@@ -1,5 +1,28 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
+/**
+ * Body of `GET /api/activity`. The hypervisor idle watchdog reads this to
+ * decide when the sandbox may hibernate.
+ */
+export type ActivityResponse = { 
+/**
+ * Latest activity across all sessions; None before any activity.
+ */
+last_activity_at: string | null, 
+/**
+ * Seconds since `last_activity_at`, measured by the sandbox clock.
+ */
+idle_secs: bigint | null, 
+/**
+ * Most recently active first.
+ */
+sessions: Array<SessionActivity>, };
+
+/**
+ * What counted as user activity in a session.
+ */
+export type ActivitySource = "web_socket" | "long_poll" | "delegation";
+
 /**
  * Unique identifier for actors
  */
@@ -10,15 +33,30 @@ export type ActorId = string;
  */
 export type AgendaItemStatus = "pending" | "ready" | "running" | "completed" | "failed" | "blocked";
 
+/**
+ * Typed error body, returned as `{ "error": ApiError }`.
+ */
+export type ApiError = { code: ErrorCode, message: string, 
+/**
+ * Endpoint-specific context, e.g. the offending field or limit.
+ */
+details?: unknown, failure_kind?: FailureKind | null, };
+
 /**
  * App definition for dynamic app registration
  */
 export type AppDefinition = { id: string, name: string, icon: string, component_code: string, default_width: number, default_height: number, };
 
 /**
- * Request to append an event
+ * Request to append an event. The event is recorded for the caller's
+ * session user.
  */
-export type AppendEvent = { event_type: string, payload: unknown, actor_id: ActorId, user_id: string, };
+export type AppendEvent = { event_type: string, payload: unknown, actor_id: ActorId, 
+/**
+ * Retrying an append with the same key returns the event stored by the
+ * first attempt instead of appending a duplicate.
+ */
+idempotency_key: string | null, };
 
 /**
  * Kinds of artifacts that can be produced
@@ -58,12 +96,16 @@ export type ConductorDocumentUpdatePayload = { run_id: string, document_path: st
 /**
  * Typed error for Conductor task failures
  */
-export type ConductorError = { code: string, message: string, failure_kind: FailureKind | null, };
+export type ConductorError = { code: ErrorCode, message: string, failure_kind: FailureKind | null, };
 
 /**
  * Request to execute a Conductor run.
  */
-export type ConductorExecuteRequest = { objective: string, desktop_id: string, output_mode: ConductorOutputMode, hints: unknown, };
+export type ConductorExecuteRequest = { objective: string, desktop_id: string, output_mode: ConductorOutputMode, hints: unknown, 
+/**
+ * Refined contract the run is held to; `None` when refinement was skipped
+ */
+contract: ObjectiveContract | null, };
 
 /**
  * Response from Conductor task execution
@@ -75,6 +117,20 @@ export type ConductorExecuteResponse = { run_id: string, status: ConductorRunSta
  */
 export type ConductorOutputMode = "auto" | "markdown_report_to_writer" | "toast_with_report_link";
 
+/**
+ * Request to refine an objective into an [`ObjectiveContract`] before execution.
+ */
+export type ConductorRefineRequest = { objective: string, desktop_id: string, 
+/**
+ * What the user has open, e.g. window titles, to ground the questions
+ */
+desktop_context: Array<string>, };
+
+/**
+ * Proposed contract for an objective plus questions to put to the user.
+ */
+export type ConductorRefineResponse = { questions: Array<string>, contract: ObjectiveContract, };
+
 /**
  * Full runtime state for a conductor run
  */
@@ -106,17 +162,61 @@ output_mode: ConductorOutputMode,
 /**
  * Desktop ID for UI coordination
  */
-desktop_id: string, };
+desktop_id: string, 
+/**
+ * Bumped by every change published as a [`ConductorRunStateDelta`]
+ */
+revision: bigint, 
+/**
+ * Contract agreed in the refinement dialog, if the user refined the objective
+ */
+contract?: ObjectiveContract | null, };
+
+/**
+ * Changed fields of a conductor run, published as
+ * `conductor.run.state_delta` on every agenda, call, artifact, decision or
+ * status transition. Applied in revision order, the stream reproduces the
+ * run state.
+ */
+export type ConductorRunStateDelta = { run_id: string, desktop_id: string, 
+/**
+ * Run revision after this change
+ */
+revision: bigint, updated_at: string, status?: ConductorRunStatus | null, completed_at?: string | null, output_mode?: ConductorOutputMode | null, 
+/**
+ * Added or changed agenda items, replacing those with the same id
+ */
+agenda?: Array<ConductorAgendaItem>, 
+/**
+ * Started or updated capability calls, replacing those with the same id
+ */
+calls?: Array<ConductorCapabilityCall>, 
+/**
+ * Appended artifacts
+ */
+artifacts?: Array<ConductorArtifact>, 
+/**
+ * Appended decisions
+ */
+decisions?: Array<ConductorDecision>, };
 
 /**
  * Status of a conductor run
  */
-export type ConductorRunStatus = "initializing" | "running" | "waiting_for_calls" | "completing" | "completed" | "failed" | "blocked";
+export type ConductorRunStatus = "initializing" | "running" | "waiting_for_calls" | "waiting_for_provider" | "completing" | "completed" | "failed" | "blocked";
 
 /**
  * State tracking for a Conductor run via API
  */
-export type ConductorRunStatusResponse = { run_id: string, status: ConductorRunStatus, objective: string, desktop_id: string, output_mode: ConductorOutputMode, created_at: string, updated_at: string, completed_at: string | null, document_path: string, report_path: string | null, toast: ConductorToastPayload | null, error: ConductorError | null, };
+export type ConductorRunStatusResponse = { run_id: string, status: ConductorRunStatus, objective: string, desktop_id: string, output_mode: ConductorOutputMode, created_at: string, updated_at: string, completed_at: string | null, document_path: string, report_path: string | null, 
+/**
+ * Priority-weighted share of completed agenda items; 100 once completed.
+ */
+progress_pct: number, toast: ConductorToastPayload | null, error: ConductorError | null, 
+/**
+ * Run revision; `conductor.run.state_delta` events continue from it
+ */
+revision: bigint, };
 
 /**
  * Payload for `conductor.task.completed`.
@@ -136,7 +236,7 @@ export type ConductorTaskProgressPayload = { run_id: string, status: string, pha
 /**
  * Payload for `conductor.task.started`.
  */
-export type ConductorTaskStartedPayload = { run_id: string, objective: string, desktop_id: string, status: string, phase: string, timestamp: string, };
+export type ConductorTaskStartedPayload = { run_id: string, objective: string, desktop_id: string, status: string, phase: string, timestamp: string, contract?: ObjectiveContract | null, };
 
 /**
  * Typed prompt-bar toast payload for Conductor completion.
@@ -158,6 +258,38 @@ export type ConductorWorkerCallPayload = { run_id: string, worker_type: string,
  */
 export type ConductorWorkerResultPayload = { run_id: string, worker_type: string, success: boolean, result_summary: string, timestamp: string, };
 
+/**
+ * Context composition of one LLM call, as served by
+ * `GET /api/context-traces`.
+ */
+export type ContextTraceCall = { trace_id: string, function_name: string, model_used: string, started_at: string, status: ContextTraceStatus, items: Array<ContextTraceItem>, total_est_tokens: number, context_window?: number | null, document_context?: DocumentContextMode | null, };
+
+/**
+ * One piece of an LLM call's prompt.
+ */
+export type ContextTraceItem = { kind: ContextTraceItemKind, role?: string | null, label: string, 
+/**
+ * Stable id of the item within its prompt: `system`, `tools`, or
+ * `message:<index>`. Absent in traces recorded before ids existed.
+ */
+id?: string | null, 
+/**
+ * Rough estimate: one token per four characters.
+ */
+est_tokens: number, trimmed: boolean, trim_reason?: string | null, };
+
+/**
+ * What a piece of an LLM call's prompt is.
+ */
+export type ContextTraceItemKind = "system" | "tools" | "message";
+
+/**
+ * Whether an LLM call's context composition is still available.
+ */
+export type ContextTraceStatus = "recorded" | "pruned";
+
+export type ContextTracesResponse = { calls: Array<ContextTraceCall>, };
+
 /**
  * Types of decisions the conductor can make
  */
@@ -173,10 +305,66 @@ export type DesktopState = { windows: Array<WindowState>, active_window: string
  */
 export type DesktopTelemetryEvent = { event_type: string, capability: string, phase: string, importance: EventImportance, data: unknown, };
 
+/**
+ * Starting layout for a new desktop: windows to open and files to seed.
+ */
+export type DesktopTemplate = { id: string, name: string, description: string, windows: Array<DesktopTemplateWindow>, 
+/**
+ * Written into the workspace only where no file exists yet.
+ */
+seed_files: Array<DesktopTemplateFile>, };
+
+/**
+ * A file a desktop template seeds, relative to the workspace root.
+ */
+export type DesktopTemplateFile = { path: string, content: string, };
+
+/**
+ * A window a desktop template opens.
+ */
+export type DesktopTemplateWindow = { app_id: string, title: string, props: unknown, };
+
 /**
  * Canonical desktop WebSocket protocol shared by sandbox and UI.
  */
-export type DesktopWsMessage = { "type": "subscribe", desktop_id: string, } | { "type": "ping" } | { "type": "pong" } | { "type": "desktop_state", desktop: DesktopState, } | { "type": "window_opened", window: WindowState, } | { "type": "window_closed", window_id: string, } | { "type": "window_moved", window_id: string, x: number, y: number, } | { "type": "window_resized", window_id: string, width: number, height: number, } | { "type": "window_focused", window_id: string, z_index: number, } | { "type": "window_minimized", window_id: string, } | { "type": "window_maximized", window_id: string, x: number, y: number, width: number, height: number, } | { "type": "window_restored", window_id: string, x: number, y: number, width: number, height: number, from: string, maximized: boolean, } | { "type": "app_registered", app: AppDefinition, } | { "type": "telemetry", event_type: string, capability: string, phase: string, importance: EventImportance, data: unknown, } | { "type": "conductor.run.document_update", run_id: string, document_path: string, content_excerpt: string, timestamp: string, } | { "type": "writer.run.started", objective: string, desktop_id: string, session_id: string, thread_id: string, run_id: string, document_path: string, revision: bigint, head_version_id: bigint | null, timestamp: string, } | { "type": "writer.run.progress", phase: string, message: string, progress_pct: number | null, source_refs: Array<string>, desktop_id: string, session_id: string, thread_id: string, run_id: string, document_path: string, revision: bigint, head_version_id: bigint | null, timestamp: string, } | { "type": "writer.run.patch", desktop_id: string, session_id: string, thread_id: string, run_id: string, document_path: string, revision: bigint, head_version_id: bigint | null, timestamp: string, patch_id: string, source: PatchSource, source_actor: string | null, section_id: string | null, ops: Array<PatchOp>, proposal: string | null, base_version_id: bigint | null, target_version_id: bigint | null, overlay_id: string | null, } | { "type": "writer.run.status", status: WriterRunStatusKind, message: string | null, desktop_id: string, session_id: string, thread_id: string, run_id: string, document_path: string, revision: bigint, head_version_id: bigint | null, timestamp: string, } | { "type": "writer.run.failed", error_code: string, error_message: string, failure_kind: FailureKind | null, desktop_id: string, session_id: string, thread_id: string, run_id: string, document_path: string, revision: bigint, head_version_id: bigint | null, timestamp: string, } | { "type": "writer.run.changeset", desktop_id: string, session_id: string, thread_id: string, run_id: string, document_path: string, revision: bigint, head_version_id: bigint | null, timestamp: string, 
+export type DesktopWsMessage = { "type": "subscribe", desktop_id: string, 
+/**
+ * Defaults to the session's current setting (`all` for a new session).
+ */
+patch_granularity?: PatchGranularity | null, 
+/**
+ * Opt-in event topics, such as `conductor.run.state_delta`, that
+ * are only forwarded to sessions naming them.
+ */
+topics?: Array<string>, } | { "type": "unsubscribe", desktop_id: string, } | { "type": "update_subscription", patch_granularity: PatchGranularity, } | { "type": "ping" } | { "type": "pong" } | { "type": "desktop_state", desktop: DesktopState, } | { "type": "window_opened", window: WindowState, } | { "type": "window_closed", window_id: string, } | { "type": "window_moved", window_id: string, x: number, y: number, } | { "type": "window_resized", window_id: string, width: number, height: number, } | { "type": "window_focused", window_id: string, z_index: number, } | { "type": "window_minimized", window_id: string, } | { "type": "window_maximized", window_id: string, x: number, y: number, width: number, height: number, } | { "type": "window_restored", window_id: string, x: number, y: number, width: number, height: number, from: string, maximized: boolean, } | { "type": "app_registered", app: AppDefinition, } | { "type": "telemetry", event_type: string, capability: string, phase: string, importance: EventImportance, data: unknown, } | { "type": "conductor.run.document_update", run_id: string, document_path: string, content_excerpt: string, timestamp: string, } | { "type": "conductor.run.state_delta", run_id: string, desktop_id: string, 
+/**
+ * Run revision after this change
+ */
+revision: bigint, updated_at: string, status?: ConductorRunStatus | null, completed_at?: string | null, output_mode?: ConductorOutputMode | null, 
+/**
+ * Added or changed agenda items, replacing those with the same id
+ */
+agenda?: Array<ConductorAgendaItem>, 
+/**
+ * Started or updated capability calls, replacing those with the same id
+ */
+calls?: Array<ConductorCapabilityCall>, 
+/**
+ * Appended artifacts
+ */
+artifacts?: Array<ConductorArtifact>, 
+/**
+ * Appended decisions
+ */
+decisions?: Array<ConductorDecision>, } | { "type": "writer.run.started", objective: string, desktop_id: string, session_id: string, thread_id: string, run_id: string, document_path: string, revision: bigint, head_version_id: bigint | null, timestamp: string, } | { "type": "writer.run.progress", phase: string, message: string, progress_pct: number | null, source_refs: Array<string>, desktop_id: string, session_id: string, thread_id: string, run_id: string, document_path: string, revision: bigint, head_version_id: bigint | null, timestamp: string, } | { "type": "writer.run.patch", desktop_id: string, session_id: string, thread_id: string, run_id: string, document_path: string, revision: bigint, head_version_id: bigint | null, timestamp: string, patch_id: string, source: PatchSource, source_actor: string | null, section_id: string | null, ops: Array<PatchOp>, proposal: string | null, base_version_id: bigint | null, target_version_id: bigint | null, overlay_id: string | null, 
+/**
+ * Patch this one reverts (an undo, or a redo when the target is an undo).
+ */
+undo_of: string | null, 
+/**
+ * Who confirmed an accepted proposal; `source` stays the proposing agent.
+ */
+confirmed_by: string | null, } | { "type": "writer.run.status", status: WriterRunStatusKind, message: string | null, desktop_id: string, session_id: string, thread_id: string, run_id: string, document_path: string, revision: bigint, head_version_id: bigint | null, timestamp: string, } | { "type": "writer.run.failed", error_code: string, error_message: string, failure_kind: FailureKind | null, desktop_id: string, session_id: string, thread_id: string, run_id: string, document_path: string, revision: bigint, head_version_id: bigint | null, timestamp: string, } | { "type": "writer.run.changeset", desktop_id: string, session_id: string, thread_id: string, run_id: string, document_path: string, revision: bigint, head_version_id: bigint | null, timestamp: string, 
 /**
  * Correlates to the patch_id from the preceding writer.run.patch event
  */
@@ -204,7 +392,63 @@ impact: ChangesetImpact,
 /**
  * List of change categories present (e.g. "insert", "structural_rewrite")
  */
-op_taxonomy: Array<string>, } | { "type": "error", message: string, };
+op_taxonomy: Array<string>, 
+/**
+ * Who confirmed the change when it was an accepted proposal
+ */
+confirmed_by: string | null, } | { "type": "writer.run.lock", desktop_id: string, session_id: string, thread_id: string, run_id: string, document_path: string, revision: bigint, head_version_id: bigint | null, timestamp: string, lock_id: string, 
+/**
+ * Ids from [`writer_blocks`] of the version the loop started from.
+ */
+block_ids: Array<string>, ttl_ms: bigint, expires_at: string, 
+/**
+ * Set on the event that ends the lock before its TTL.
+ */
+released: boolean, 
+/**
+ * Why the lock was released: "completed" or "failed".
+ */
+reason: string | null, } | { "type": "provider.gateway.status", reachable: boolean, message: string | null, } | { "type": "error", message: string, error_code?: WsErrorCode | null, 
+/**
+ * Build of the sandbox that sent the error.
+ */
+version?: BuildVersion | null, };
+
+export type DiskCategoryUsage = { category: DiskUsageCategory, bytes: bigint, };
+
+/**
+ * What a byte of workspace disk usage belongs to, by top-level location.
+ */
+export type DiskUsageCategory = "runs" | "reports" | "revisions" | "files";
+
+/**
+ * Body of `GET /api/usage/disk`.
+ */
+export type DiskUsageResponse = { used_bytes: bigint, 
+/**
+ * None when no quota is configured.
+ */
+quota_bytes: bigint | null, used_pct: number | null, 
+/**
+ * Largest category first, the order cleanup should work through.
+ */
+categories: Array<DiskCategoryUsage>, 
+/**
+ * When the last full scan ran; writes since then are counted incrementally.
+ */
+scanned_at: string, };
+
+/**
+ * How a writer loop's prompt carried the document.
+ */
+export type DocumentContextMode = "full_text" | "sectioned";
+
+/**
+ * Catalog of user-facing failure codes, shared by every sandbox endpoint and
+ * the UI. The sandbox maps each code to one HTTP status; the UI branches on
+ * the code for guidance and keeps the raw message as detail.
+ */
+export type ErrorCode = "INVALID_REQUEST" | "PATH_TRAVERSAL" | "NOT_A_FILE" | "NOT_A_DIRECTORY" | "IS_DIRECTORY" | "INVALID_CONTENT" | "INVALID_REVISION" | "PAYLOAD_TOO_LARGE" | "UNSUPPORTED_MEDIA_TYPE" | "UNSUPPORTED_SCHEMA" | "NOT_FOUND" | "RUN_NOT_FOUND" | "RUN_FAILED" | "RUN_BLOCKED" | "ALREADY_EXISTS" | "CONFLICT" | "DOCUMENT_CONFLICT" | "PERMISSION_DENIED" | "POLICY_BLOCKED" | "QUOTA_EXCEEDED" | "SUPERVISOR_UNAVAILABLE" | "ACTOR_SPAWN_FAILED" | "ACTOR_UNAVAILABLE" | "PROVIDER_UNAVAILABLE" | "SERVICE_UNAVAILABLE" | "INTERNAL_ERROR";
 
 /**
  * Event - append-only log entry
@@ -220,13 +464,31 @@ seq: bigint,
  */
 event_id: string, 
 /**
- * When the event occurred
+ * When the event store recorded the event. Same value as `stored_at`,
+ * kept for existing consumers.
  */
 timestamp: string, 
+/**
+ * Authoritative append time, stamped by the event store and never
+ * decreasing along `seq`. Events serialized before this field existed
+ * read back with the Unix epoch.
+ */
+stored_at: string, 
+/**
+ * The producer's own clock reading (the payload's `timestamp`), if any.
+ * Producer clocks can skew, so this is informational and never used for
+ * ordering.
+ */
+produced_at: string | null, 
 /**
  * Which actor produced this event
  */
 actor_id: ActorId, 
+/**
+ * Position among `actor_id`'s events, starting at 1. A gap between
+ * consecutive events of one actor means events in between were missed.
+ */
+actor_seq: bigint, 
 /**
  * Event type (e.g., "conductor.run_started", "file.write")
  */
@@ -279,10 +541,329 @@ capability: string | null,
  */
 phase: string | null, };
 
+/**
+ * Response of `GET /api/events/poll`.
+ */
+export type EventPollResponse = { 
+/**
+ * Messages after the polled cursor, in the order a WebSocket session
+ * would have received them.
+ */
+messages: Array<DesktopWsMessage>, 
+/**
+ * Cursor for the next poll; the polled cursor when nothing arrived.
+ */
+next_cursor: string, };
+
+/**
+ * Session/thread scope of an event, carried under `scope` in its payload.
+ */
+export type EventScope = { session_id?: string | null, thread_id?: string | null, };
+
+/**
+ * Body of `POST /api/events/subscriptions`: the long-poll counterpart of a
+ * WebSocket `subscribe` message.
+ */
+export type EventSubscriptionRequest = { 
+/**
+ * Cursor of an existing long-poll session to add the desktop to; a new
+ * session is started when absent.
+ */
+cursor?: string | null, desktop_id: string, 
+/**
+ * Defaults to the session's current setting (`all` for a new session).
+ */
+patch_granularity?: PatchGranularity | null, topics?: Array<string>, };
+
+/**
+ * Response of `POST /api/events/subscriptions`.
+ */
+export type EventSubscriptionResponse = { 
+/**
+ * Cursor for the next `GET /api/events/poll`.
+ */
+cursor: string, 
+/**
+ * The session is dropped after this long without a poll or subscribe.
+ */
+expires_in_ms: bigint, };
+
+export type EvidenceRequirements = { requires_citations: boolean, min_confidence: number, required_source_types: Array<string>, };
+
+/**
+ * A durable preference learned from what the user said in conversation.
+ */
+export type LearnedPreference = { preference_id: string, 
+/**
+ * Short snake_case subject, e.g. `package_manager`. A newer preference on
+ * the same topic replaces the older one.
+ */
+topic: string, 
+/**
+ * The preference in the user's terms, e.g. "Uses pnpm, not npm".
+ */
+statement: string, confidence: number, 
+/**
+ * Run whose prompt the preference was learned from.
+ */
+source_run_id: string | null, learned_at: string, };
+
+/**
+ * Body of `GET /api/preferences`.
+ */
+export type LearnedPreferencesResponse = { 
+/**
+ * Whether new preferences are learned from conversations.
+ */
+learning_enabled: boolean, 
+/**
+ * Newest first.
+ */
+preferences: Array<LearnedPreference>, };
+
+/**
+ * Payload for `model.context.trace`: what went into the prompt of the LLM
+ * call whose `llm.call.started` event carries the same `trace_id`.
+ */
+export type ModelContextTracePayload = { trace_id: string, run_id?: string | null, thread_id?: string | null, model_used: string, items: Array<ContextTraceItem>, total_est_tokens: number, 
+/**
+ * Whether any item was cut down to fit the prompt.
+ */
+trimmed: boolean, context_window?: number | null, 
+/**
+ * Set for writer loops: how the document was put into the prompt.
+ */
+document_context?: DocumentContextMode | null, };
+
+export type ObjectiveConstraints = { max_tool_calls: number, timeout_ms: bigint, max_subframe_depth: number, allowed_capabilities: Array<string>, };
+
+/**
+ * Contract defining an objective for parent-child delegation
+ */
+export type ObjectiveContract = { objective_id: string, parent_objective_id: string | null, primary_objective: string, success_criteria: Array<string>, constraints: ObjectiveConstraints, attempts_budget: number, evidence_requirements: EvidenceRequirements, };
+
+/**
+ * Which `writer.run.*` events a desktop WebSocket session is sent.
+ * Status and failure events are delivered at every level.
+ */
+export type PatchGranularity = "all" | "changesets_only" | "status_only";
+
 /**
  * Query events for an actor
  */
-export type QueryEvents = { actor_id: ActorId, since_seq: bigint, };
+export type QueryEvents = { actor_id: ActorId, since_seq: bigint, 
+/**
+ * Only events in this lane. Events without lane metadata count as
+ * telemetry.
+ */
+lane: EventLane | null, };
+
+/**
+ * Body of `GET /api/research/batch/{batch_id}`; tasks in submission order.
+ */
+export type ResearchBatchDetail = { batch_id: string, tasks: Array<ResearchTaskSummary>, };
+
+/**
+ * Body of `POST /api/research/batch`.
+ */
+export type ResearchBatchRequest = { queries: Array<string>, 
+/**
+ * Round budget shared by the whole batch, split evenly across queries.
+ */
+max_rounds: number | null, 
+/**
+ * Desktop to list the batch's tasks on.
+ */
+desktop_id: string | null, };
+
+/**
+ * Response of `POST /api/research/batch`. `task_ids` follow the order of
+ * the submitted queries.
+ */
+export type ResearchBatchResponse = { batch_id: string, task_ids: Array<string>, };
+
+/**
+ * Source gathered by a research task.
+ */
+export type ResearchCitation = { id: string, provider: string, title: string, url: string, snippet: string, published_at: string | null, score: number | null, 
+/**
+ * Days since `published_at`, when the date could be read.
+ */
+age_days: number | null, };
+
+/**
+ * One search provider request made by a research task.
+ */
+export type ResearchProviderCall = { provider: string, latency_ms: bigint, result_count: number, succeeded: boolean, error: string | null, };
+
+/**
+ * Body of `POST /api/research/tasks/{task_id}/rerun`.
+ */
+export type ResearchRerunRequest = { 
+/**
+ * Replacement objective; the original one is reused when absent.
+ */
+objective: string | null, max_rounds: number | null, 
+/**
+ * Desktop to list the new task on; the original task's when absent.
+ */
+desktop_id: string | null, };
+
+/**
+ * Response of `POST /api/research/tasks/{task_id}/rerun`.
+ */
+export type ResearchRerunResponse = { task_id: string, };
+
+/**
+ * Body of `POST /api/research/tasks/{task_id}/send-to-writer`.
+ */
+export type ResearchSendToWriterRequest = { 
+/**
+ * Run document to append to; defaults to the task's own run document.
+ */
+path: string | null, };
+
+/**
+ * Response of `POST /api/research/tasks/{task_id}/send-to-writer`.
+ */
+export type ResearchSendToWriterResponse = { path: string, section_id: string, revision: bigint, };
+
+/**
+ * Payload for `research.task.completed`.
+ */
+export type ResearchTaskCompletedPayload = { task_id: string, researcher_id: string, run_id: string | null, success: boolean, 
+/**
+ * Synthesized answer; citation markers like `[1]` index into `citations`.
+ */
+summary: string, provider_used: string | null, model_used: string | null, citations: Array<ResearchCitation>, provider_calls: Array<ResearchProviderCall>, timestamp: string, };
+
+/**
+ * Body of `GET /api/research/tasks/{task_id}`.
+ */
+export type ResearchTaskDetail = { task: ResearchTaskSummary, summary: string | null, model_used: string | null, citations: Array<ResearchCitation>, provider_calls: Array<ResearchProviderCall>, error: string | null, progress: Array<ResearchTaskProgress>, };
+
+/**
+ * Payload for `research.task.failed`.
+ */
+export type ResearchTaskFailedPayload = { task_id: string, researcher_id: string, run_id: string | null, error: string, timestamp: string, };
+
+/**
+ * Progress line from a task's `worker.task.progress` events.
+ */
+export type ResearchTaskProgress = { seq: bigint, phase: string, message: string, provider: string | null, timestamp: string, };
+
+/**
+ * Payload for `research.task.started`. `task_id` is also the `task_id` of
+ * the task's `worker.task.progress` events.
+ */
+export type ResearchTaskStartedPayload = { task_id: string, researcher_id: string, objective: string, run_id: string | null, call_id: string | null, 
+/**
+ * Task this one re-runs, when started from the Research app.
+ */
+rerun_of: string | null, 
+/**
+ * Batch this task was submitted in, see `POST /api/research/batch`.
+ */
+batch_id: string | null, 
+/**
+ * Desktop the task was started from in the Research app. Tasks of a
+ * conductor run belong to the run's desktop instead.
+ */
+desktop_id: string | null, timestamp: string, };
+
+export type ResearchTaskStatus = "running" | "completed" | "failed";
+
+/**
+ * Row of `GET /api/research/tasks`, newest first.
+ */
+export type ResearchTaskSummary = { task_id: string, objective: string, status: ResearchTaskStatus, run_id: string | null, rerun_of: string | null, batch_id: string | null, 
+/**
+ * Desktop the task is listed on, when known.
+ */
+desktop_id: string | null, 
+/**
+ * Seq of the `research.task.started` event; live progress follows it.
+ */
+started_seq: bigint, started_at: string, finished_at: string | null, citation_count: number, provider_used: string | null, };
+
+/**
+ * Piece of a search excerpt; `highlight` marks text that matched the query.
+ */
+export type SearchExcerptSegment = { text: string, highlight: boolean, };
+
+/**
+ * Single result from `GET /api/search`.
+ */
+export type SearchHit = { kind: SearchHitKind, 
+/**
+ * Seq of the event the hit was indexed from.
+ */
+seq: bigint, title: string, excerpt: Array<SearchExcerptSegment>, timestamp: string, 
+/**
+ * Relevance, higher is better.
+ */
+score: number, target: SearchTarget, };
+
+/**
+ * Source of a search hit.
+ */
+export type SearchHitKind = "chat" | "document" | "artifact";
+
+/**
+ * Response body for `GET /api/search`.
+ */
+export type SearchResponse = { query: string, hits: Array<SearchHit>, };
+
+/**
+ * Where a search hit deep-links to.
+ */
+export type SearchTarget = { "type": "chat", actor_id: string, thread_id: string, session_id: string, 
+/**
+ * Document the message was typed against, for writer prompts.
+ */
+document_path: string | null, } | { "type": "document", path: string, run_id: string | null, revision: bigint, } | { "type": "artifact", artifact_id: string | null, artifact_kind: string, run_id: string | null, 
+/**
+ * Sandbox-relative path when the artifact is a file.
+ */
+path: string | null, };
+
+/**
+ * Server clock reading served by `GET /api/time`.
+ */
+export type ServerTimeResponse = { 
+/**
+ * Server wall-clock time when the request was handled.
+ */
+now: string, 
+/**
+ * Milliseconds on the server's monotonic clock. It only goes backwards
+ * across a server restart, which makes earlier samples stale.
+ */
+monotonic_ms: bigint, };
+
+/**
+ * Which build produced a frontend or backend. Served by `GET /api/version`
+ * and attached to error bodies so bug reports name the build.
+ */
+export type BuildVersion = { 
+/**
+ * Short git hash of the build, or "unknown".
+ */
+git_hash: string, 
+/**
+ * [`SCHEMA_VERSION`] the build was compiled with.
+ */
+schema_version: number, };
+
+/**
+ * Payload for `session.activity`: the last activity seen in a session.
+ */
+export type SessionActivity = { 
+/**
+ * The desktop id, or the connection id of a WebSocket that has not
+ * subscribed to a desktop yet.
+ */
+session_id: string, source: ActivitySource, at: string, };
 
 /**
  * Tool call from LLM
@@ -294,11 +875,47 @@ export type ToolCall = { id: string, tool: string, args: unknown, };
  */
 export type ToolDef = { name: string, description: string, parameters: unknown, };
 
+/**
+ * Payload for `viewer.content_conflict`: a save whose `base_rev` no longer
+ * matched the stored revision.
+ */
+export type ViewerConflictPayload = { 
+/**
+ * Viewer URI of the document.
+ */
+path: string, 
+/**
+ * Revision the stale save was based on.
+ */
+base_rev: bigint, 
+/**
+ * Revision stored when the save arrived.
+ */
+current_rev: bigint, attempted_content: string, window_id: string, user_id: string, timestamp: string, };
+
+/**
+ * Body of `PATCH /api/preferences`.
+ */
+export type UpdatePreferenceLearningRequest = { learning_enabled: boolean, };
+
+/**
+ * Surface that received a user input.
+ */
+export type UserInputSurface = "conductor" | "writer" | "prompt_bar";
+
 export type ViewerCapabilities = { readonly: boolean, };
 
-export type ViewerDescriptor = { kind: ViewerKind, resource: ViewerResource, capabilities: ViewerCapabilities, };
+export type ViewerDescriptor = { kind: ViewerKind, 
+/**
+ * The resource shown first; for an image set, its first entry.
+ */
+resource: ViewerResource, capabilities: ViewerCapabilities, 
+/**
+ * Every resource of an image set, in display order. Empty otherwise.
+ */
+resources: Array<ViewerResource>, };
 
-export type ViewerKind = "text" | "image";
+export type ViewerKind = "text" | "image" | "image_set";
 
 export type ViewerResource = { uri: string, mime: string, };
 
@@ -445,6 +1062,48 @@ export type WorkerTurnReportIngestResult = { accepted_findings: number, accepted
 
 export type WorkerTurnStatus = "running" | "completed" | "failed" | "blocked";
 
+/**
+ * Payload for `workspace.quota.warning`, emitted once per threshold crossing.
+ */
+export type WorkspaceQuotaWarningPayload = { 
+/**
+ * The crossed threshold: 80 or 95.
+ */
+threshold_pct: number, used_bytes: bigint, quota_bytes: bigint, largest_category: DiskUsageCategory | null, timestamp: string, };
+
+/**
+ * What happens to a user edit that touches a block an agent has locked.
+ */
+export type WriterLockStrictness = "warn" | "reject";
+
+/**
+ * Payload for writer.run.lock events.
+ *
+ * A writer loop takes a lock when it starts and declares the blocks it is
+ * about to rewrite, so user edits to them can be warned about or rejected.
+ * The lock is re-sent under the same `lock_id` each time it grows. Locks are
+ * soft: each one lapses at `expires_at` even if its release event never
+ * arrives.
+ */
+export type WriterRunLockPayload = { lock_id: string, 
+/**
+ * Ids from [`writer_blocks`] of the version the loop started from.
+ */
+block_ids: Array<string>, ttl_ms: bigint, expires_at: string, 
+/**
+ * Set on the event that ends the lock before its TTL.
+ */
+released: boolean, 
+/**
+ * Why the lock was released: "completed" or "failed".
+ */
+reason: string | null, };
+
+/**
+ * Machine-readable reason attached to a desktop WebSocket error.
+ */
+export type WsErrorCode = "too_many_subscriptions" | "message_too_large";
+
 /**
  * WebSocket message protocol
  */
@@ -1,7 +1,25 @@
 use dioxus::prelude::*;
 
+/// CSS transform that undoes an EXIF orientation (1-8).
+pub fn orientation_transform(orientation: Option<u8>) -> &'static str {
+    match orientation {
+        Some(2) => "scaleX(-1)",
+        Some(3) => "rotate(180deg)",
+        Some(4) => "scaleY(-1)",
+        Some(5) => "rotate(90deg) scaleX(-1)",
+        Some(6) => "rotate(90deg)",
+        Some(7) => "rotate(270deg) scaleX(-1)",
+        Some(8) => "rotate(270deg)",
+        _ => "none",
+    }
+}
+
 #[component]
-pub fn ImageViewer(content: String, fallback_uri: String) -> Element {
+pub fn ImageViewer(
+    content: String,
+    fallback_uri: String,
+    #[props(default)] orientation: Option<u8>,
+) -> Element {
     let mut scale = use_signal(|| 1.0f64);
     let mut offset_x = use_signal(|| 0.0f64);
     let mut offset_y = use_signal(|| 0.0f64);
@@ -14,6 +32,9 @@ pub fn ImageViewer(content: String, fallback_uri: String) -> Element {
         fallback_uri
     };
 
+    // The backend reports orientation, so the browser must not apply it again.
+    let orient = orientation_transform(orientation);
+
     let on_reset = move |_| {
         scale.set(1.0);
         offset_x.set(0.0);
@@ -56,9 +77,23 @@ pub fn ImageViewer(content: String, fallback_uri: String) -> Element {
                 },
                 img {
                     src: "{src}",
-                    style: "max-width: none; max-height: none; user-select: none; transform: translate({offset_x}px, {offset_y}px) scale({scale}); transform-origin: center center; position: absolute; top: 50%; left: 50%;",
+                    style: "max-width: none; max-height: none; user-select: none; image-orientation: none; transform: translate({offset_x}px, {offset_y}px) scale({scale}) {orient}; transform-origin: center center; position: absolute; top: 50%; left: 50%;",
                 }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orientation_transform_covers_exif_values() {
+        assert_eq!(orientation_transform(None), "none");
+        assert_eq!(orientation_transform(Some(1)), "none");
+        assert_eq!(orientation_transform(Some(6)), "rotate(90deg)");
+        assert_eq!(orientation_transform(Some(8)), "rotate(270deg)");
+        assert_eq!(orientation_transform(Some(9)), "none");
+    }
+}
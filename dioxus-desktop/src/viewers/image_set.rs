@@ -0,0 +1,135 @@
+use dioxus::prelude::*;
+use shared_types::ViewerResource;
+
+use crate::api::{fetch_viewer_content, viewer_thumbnail_url};
+use crate::viewers::image::ImageViewer;
+
+/// Splits a set into its image resources and the number of others skipped.
+pub fn partition_image_resources(resources: &[ViewerResource]) -> (Vec<ViewerResource>, usize) {
+    let images: Vec<ViewerResource> = resources
+        .iter()
+        .filter(|resource| resource.mime.starts_with("image/"))
+        .cloned()
+        .collect();
+    let skipped = resources.len() - images.len();
+    (images, skipped)
+}
+
+#[component]
+pub fn ImageSetViewer(resources: Vec<ViewerResource>) -> Element {
+    let (images, skipped) = partition_image_resources(&resources);
+    let mut selected = use_signal(|| 0usize);
+    let mut content = use_signal(String::new);
+    let mut orientation = use_signal(|| None::<u8>);
+    let mut error = use_signal(|| None::<String>);
+
+    let images_for_effect = images.clone();
+    use_effect(move || {
+        let Some(resource) = images_for_effect.get(selected()).cloned() else {
+            return;
+        };
+        spawn(async move {
+            error.set(None);
+            match fetch_viewer_content(&resource.uri).await {
+                Ok(resp) => {
+                    content.set(resp.content);
+                    orientation.set(resp.orientation);
+                }
+                Err(e) => error.set(Some(e)),
+            }
+        });
+    });
+
+    let total = images.len();
+    let index = selected().min(total.saturating_sub(1));
+    let current = images.get(index).cloned();
+
+    rsx! {
+        div {
+            style: "height: 100%; display: flex; flex-direction: column; background: #0b1220;",
+            if skipped > 0 {
+                div {
+                    style: "padding: 6px 12px; font-size: 0.75rem; color: #fbbf24; border-bottom: 1px solid #1f2937;",
+                    "Skipped {skipped} non-image resource(s)."
+                }
+            }
+            if let Some(resource) = current {
+                div {
+                    style: "display: flex; align-items: center; gap: 8px; padding: 6px 12px; font-size: 0.75rem; color: #94a3b8;",
+                    button {
+                        disabled: index == 0,
+                        onclick: move |_| selected.set(index.saturating_sub(1)),
+                        "Prev"
+                    }
+                    span { "{index + 1} / {total}" }
+                    button {
+                        disabled: index + 1 >= total,
+                        onclick: move |_| selected.set(index + 1),
+                        "Next"
+                    }
+                    span { style: "overflow: hidden; text-overflow: ellipsis; white-space: nowrap;", "{resource.uri}" }
+                }
+                div {
+                    style: "flex: 1; min-height: 0;",
+                    ImageViewer {
+                        key: "{resource.uri}",
+                        content: content(),
+                        fallback_uri: resource.uri.clone(),
+                        orientation: orientation(),
+                    }
+                }
+                div {
+                    style: "display: flex; gap: 6px; padding: 6px; overflow-x: auto; border-top: 1px solid #1f2937;",
+                    for (i, thumb) in images.iter().enumerate() {
+                        img {
+                            key: "{thumb.uri}",
+                            src: viewer_thumbnail_url(&thumb.uri),
+                            alt: "{thumb.uri}",
+                            title: "{thumb.uri}",
+                            style: if i == index {
+                                "height: 64px; width: 64px; object-fit: cover; cursor: pointer; border: 2px solid #60a5fa; background: #111827;"
+                            } else {
+                                "height: 64px; width: 64px; object-fit: cover; cursor: pointer; border: 2px solid transparent; background: #111827;"
+                            },
+                            onclick: move |_| selected.set(i),
+                        }
+                    }
+                }
+            } else {
+                div { style: "padding: 12px; color: #94a3b8;", "No images in this set." }
+            }
+            if let Some(message) = error() {
+                div {
+                    style: "padding: 6px 12px; font-size: 0.75rem; color: #fca5a5;",
+                    "{message}"
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resource(uri: &str, mime: &str) -> ViewerResource {
+        ViewerResource {
+            uri: uri.to_string(),
+            mime: mime.to_string(),
+        }
+    }
+
+    #[test]
+    fn partition_keeps_images_in_order_and_counts_the_rest() {
+        let (images, skipped) = partition_image_resources(&[
+            resource("sandbox://a.png", "image/png"),
+            resource("sandbox://notes.md", "text/markdown"),
+            resource("sandbox://b.jpg", "image/jpeg"),
+        ]);
+        assert_eq!(
+            images.iter().map(|r| r.uri.as_str()).collect::<Vec<_>>(),
+            vec!["sandbox://a.png", "sandbox://b.jpg"]
+        );
+        assert_eq!(skipped, 1);
+    }
+}
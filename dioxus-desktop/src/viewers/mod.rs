@@ -1,10 +1,12 @@
 pub mod image;
+pub mod image_set;
 pub mod markdown;
 pub mod shell;
 pub mod text;
 pub mod types;
 
 pub use image::*;
+pub use image_set::*;
 pub use markdown::*;
 pub use shell::*;
 pub use text::*;
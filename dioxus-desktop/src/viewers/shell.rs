@@ -1,8 +1,10 @@
 use dioxus::prelude::*;
 use shared_types::{ViewerDescriptor, ViewerKind, ViewerRevision};
 
+use crate::components::ErrorNotice;
 use crate::api::{fetch_viewer_content, patch_viewer_content, PatchViewerContentError};
 use crate::viewers::image::ImageViewer;
+use crate::viewers::image_set::ImageSetViewer;
 use crate::viewers::markdown::MarkdownViewer;
 use crate::viewers::text::TextViewer;
 
@@ -27,16 +29,23 @@ pub fn ViewerShell(window_id: String, desktop_id: String, descriptor: ViewerDesc
     });
     let mut error = use_signal(|| None::<String>);
     let mut markdown_preview = use_signal(|| true);
+    let mut orientation = use_signal(|| None::<u8>);
 
     let uri = descriptor.resource.uri.clone();
     let mime = descriptor.resource.mime.clone();
     let readonly = descriptor.capabilities.readonly;
     let text_mode = matches!(descriptor.kind, ViewerKind::Text);
     let markdown_mode = text_mode && mime == "text/markdown";
+    // Image sets load each resource themselves as it is selected.
+    let image_set_mode = matches!(descriptor.kind, ViewerKind::ImageSet);
     let effective_readonly = readonly;
 
     let uri_for_initial = uri.clone();
     use_effect(move || {
+        if image_set_mode {
+            shell_state.set(ViewerShellState::Ready);
+            return;
+        }
         let uri_inner = uri_for_initial.clone();
         spawn(async move {
             shell_state.set(ViewerShellState::Loading);
@@ -45,6 +54,7 @@ pub fn ViewerShell(window_id: String, desktop_id: String, descriptor: ViewerDesc
                 Ok(resp) => {
                     content.set(resp.content.clone());
                     rendered_html.set(resp.rendered_html.clone());
+                    orientation.set(resp.orientation);
                     saved_content.set(resp.content);
                     revision.set(resp.revision);
                     if markdown_mode {
@@ -176,28 +186,31 @@ pub fn ViewerShell(window_id: String, desktop_id: String, descriptor: ViewerDesc
                             "Copy all"
                         }
                     }
-                    button {
-                        onclick: move |_| {
-                            let uri_inner = uri_for_reload.clone();
-                            spawn(async move {
-                                shell_state.set(ViewerShellState::Loading);
-                                error.set(None);
-                                match fetch_viewer_content(&uri_inner).await {
-                                    Ok(resp) => {
-                                        content.set(resp.content.clone());
-                                        rendered_html.set(resp.rendered_html.clone());
-                                        saved_content.set(resp.content);
-                                        revision.set(resp.revision);
-                                        shell_state.set(ViewerShellState::Ready);
-                                    }
-                                    Err(e) => {
-                                        error.set(Some(e));
-                                        shell_state.set(ViewerShellState::Error);
+                    if !image_set_mode {
+                        button {
+                            onclick: move |_| {
+                                let uri_inner = uri_for_reload.clone();
+                                spawn(async move {
+                                    shell_state.set(ViewerShellState::Loading);
+                                    error.set(None);
+                                    match fetch_viewer_content(&uri_inner).await {
+                                        Ok(resp) => {
+                                            content.set(resp.content.clone());
+                                            rendered_html.set(resp.rendered_html.clone());
+                                            orientation.set(resp.orientation);
+                                            saved_content.set(resp.content);
+                                            revision.set(resp.revision);
+                                            shell_state.set(ViewerShellState::Ready);
+                                        }
+                                        Err(e) => {
+                                            error.set(Some(e));
+                                            shell_state.set(ViewerShellState::Error);
+                                        }
                                     }
-                                }
-                            });
-                        },
-                        "Reload"
+                                });
+                            },
+                            "Reload"
+                        }
                     }
                     if !effective_readonly {
                         button {
@@ -247,6 +260,12 @@ pub fn ViewerShell(window_id: String, desktop_id: String, descriptor: ViewerDesc
                             ImageViewer {
                                 content: content(),
                                 fallback_uri: uri.clone(),
+                                orientation: orientation(),
+                            }
+                        },
+                        ViewerKind::ImageSet => rsx! {
+                            ImageSetViewer {
+                                resources: descriptor.resources.clone(),
                             }
                         },
                     }
@@ -259,9 +278,9 @@ pub fn ViewerShell(window_id: String, desktop_id: String, descriptor: ViewerDesc
                 span { "rev {revision().rev} ({mime}) · {desktop_id}" }
             }
             if let Some(message) = error() {
-                div {
+                ErrorNotice {
+                    message,
                     style: "padding: 8px 12px; font-size: 0.75rem; color: #fca5a5;",
-                    "{message}"
                 }
             }
         }
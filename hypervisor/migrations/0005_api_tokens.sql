@@ -0,0 +1,15 @@
+-- Personal access tokens for programmatic access (CLI, scripts).
+-- The secret is shown once at creation; only its SHA-256 digest is stored.
+CREATE TABLE IF NOT EXISTS api_tokens (
+    id           TEXT PRIMARY KEY,         -- ULID, also embedded in the token string
+    user_id      TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name         TEXT NOT NULL,
+    scope        TEXT NOT NULL,            -- "read_only" | "full"
+    secret_hash  TEXT NOT NULL,            -- hex SHA-256 of the secret part
+    created_at   INTEGER NOT NULL,
+    expires_at   INTEGER NOT NULL,
+    last_used_at INTEGER,
+    revoked_at   INTEGER                   -- NULL = active
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_tokens_user ON api_tokens(user_id);
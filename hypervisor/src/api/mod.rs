@@ -32,10 +32,26 @@ pub async fn heartbeat(State(state): State<Arc<AppState>>, session: Session) ->
     StatusCode::OK.into_response()
 }
 
-/// GET /admin/sandboxes — list all sandbox statuses
+/// GET /admin/sandboxes — list all sandbox statuses plus warm pool metrics
+///
+/// Running sandboxes are asked for their build, so a fleet running mixed
+/// versions shows up next to the hypervisor's own.
 pub async fn list_sandboxes(State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    let snapshots = state.sandbox_registry.snapshot().await;
-    Json(snapshots)
+    let registry = &state.sandbox_registry;
+    let snapshots = futures_util::future::join_all(registry.snapshot().await.into_iter().map(
+        |mut snapshot| async move {
+            if snapshot.status == crate::sandbox::SandboxStatus::Running {
+                snapshot.version = registry.fetch_reported_version(snapshot.port).await;
+            }
+            snapshot
+        },
+    ))
+    .await;
+    Json(serde_json::json!({
+        "sandboxes": snapshots,
+        "warm_pool": registry.warm_pool_snapshot(),
+        "hypervisor_version": shared_types::BuildVersion::current(),
+    }))
 }
 
 /// GET /admin/stats — host-level resource stats for stress testing
@@ -52,9 +68,46 @@ pub async fn host_stats(State(state): State<Arc<AppState>>) -> impl IntoResponse
         "memory_available_mb": crate::sandbox::read_available_memory_mb(),
         "vms_running": running,
         "vms_total": total,
+        "vms_warm": state.sandbox_registry.warm_pool_snapshot().ready,
     }))
 }
 
+async fn fleet_state(state: &AppState) -> crate::metrics::FleetState {
+    let now = chrono::Utc::now().timestamp();
+    let active_sessions: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM sessions WHERE expiry_date > ?")
+            .bind(now)
+            .fetch_one(&state.db)
+            .await
+            .unwrap_or_else(|e| {
+                error!("count active sessions: {e}");
+                0
+            });
+    crate::metrics::FleetState {
+        sandboxes: state.sandbox_registry.snapshot().await,
+        restarts: state.sandbox_registry.restart_counts(),
+        active_sessions: active_sessions.max(0) as u64,
+    }
+}
+
+/// GET /admin/metrics — fleet counters in the Prometheus text format
+pub async fn metrics(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let fleet = fleet_state(&state).await;
+    (
+        [(
+            axum::http::header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        state.metrics.render_prometheus(&fleet),
+    )
+}
+
+/// GET /admin/dashboard — fleet summary for the admin UI
+pub async fn dashboard(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let fleet = fleet_state(&state).await;
+    Json(state.metrics.dashboard(fleet))
+}
+
 #[derive(serde::Deserialize)]
 pub struct SandboxActionPath {
     pub user_id: String,
@@ -14,7 +14,9 @@ use tracing::{error, info, warn};
 use uuid::Uuid;
 use webauthn_rs::prelude::*;
 
+use crate::auth::tokens::{self, TokenScope, DEFAULT_TOKEN_TTL_DAYS, MAX_TOKEN_TTL_DAYS};
 use crate::auth::{generate_recovery_codes, session as sess, verify_recovery_code};
+use crate::metrics::AuthFailure;
 use crate::AppState;
 
 // ── Registration ─────────────────────────────────────────────────────────────
@@ -282,6 +284,7 @@ pub async fn login_finish(
         Ok(r) => r,
         Err(e) => {
             warn!(username, "finish_passkey_authentication failed: {e}");
+            state.metrics.record_auth_failure(AuthFailure::Passkey);
             return (
                 StatusCode::UNAUTHORIZED,
                 format!("authentication failed: {e}"),
@@ -382,6 +385,7 @@ pub async fn recovery(
                 let _ = Argon2::default().hash_password(b"dummy", &salt);
             }
             warn!(username = %body.username, "recovery attempt for unknown user");
+            state.metrics.record_auth_failure(AuthFailure::RecoveryCode);
             return (StatusCode::UNAUTHORIZED, "invalid username or code").into_response();
         }
     };
@@ -404,6 +408,7 @@ pub async fn recovery(
 
     if rows.is_empty() {
         warn!(username = %body.username, "no unused recovery codes");
+        state.metrics.record_auth_failure(AuthFailure::RecoveryCode);
         return (StatusCode::UNAUTHORIZED, "invalid username or code").into_response();
     }
 
@@ -414,6 +419,7 @@ pub async fn recovery(
     match matched {
         None => {
             warn!(username = %body.username, "recovery code mismatch");
+            state.metrics.record_auth_failure(AuthFailure::RecoveryCode);
             (StatusCode::UNAUTHORIZED, "invalid username or code").into_response()
         }
         Some(row) => {
@@ -888,7 +894,7 @@ async fn update_passkey_counter(
     Ok(())
 }
 
-async fn audit(
+pub(crate) async fn audit(
     pool: &SqlitePool,
     user_id: Option<&str>,
     event: &str,
@@ -908,6 +914,114 @@ async fn audit(
     .await;
 }
 
+// ── API tokens ───────────────────────────────────────────────────────────────
+
+#[derive(Deserialize)]
+pub struct CreateTokenBody {
+    pub name: String,
+    pub scope: TokenScope,
+    /// Days until the token expires (default 90, max 365).
+    pub expires_in_days: Option<i64>,
+}
+
+#[derive(Serialize)]
+pub struct CreateTokenResponse {
+    #[serde(flatten)]
+    pub token: tokens::ApiToken,
+    /// Plaintext token. Returned only here; it cannot be retrieved later.
+    pub secret: String,
+}
+
+/// POST /auth/tokens — create a personal access token for the session user.
+pub async fn create_token(
+    State(state): State<Arc<AppState>>,
+    session: Session,
+    Json(body): Json<CreateTokenBody>,
+) -> Response {
+    let Some(user_id) = sess::get_user_id(&session).await else {
+        return StatusCode::UNAUTHORIZED.into_response();
+    };
+    let name = body.name.trim();
+    if name.is_empty() || name.len() > 100 {
+        return (StatusCode::BAD_REQUEST, "name must be 1-100 characters").into_response();
+    }
+    let ttl_days = body.expires_in_days.unwrap_or(DEFAULT_TOKEN_TTL_DAYS);
+    if !(1..=MAX_TOKEN_TTL_DAYS).contains(&ttl_days) {
+        return (
+            StatusCode::BAD_REQUEST,
+            format!("expires_in_days must be between 1 and {MAX_TOKEN_TTL_DAYS}"),
+        )
+            .into_response();
+    }
+
+    match tokens::create_token(&state.db, &user_id, name, body.scope, ttl_days).await {
+        Ok((token, secret)) => {
+            info!(user_id, token_id = token.id, "api token created");
+            audit(
+                &state.db,
+                Some(&user_id),
+                "token_created",
+                Some(&token.id),
+                None,
+            )
+            .await;
+            (
+                StatusCode::CREATED,
+                Json(CreateTokenResponse { token, secret }),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            error!(user_id, "create api token: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// GET /auth/tokens — list the session user's tokens (without secrets).
+pub async fn list_tokens(State(state): State<Arc<AppState>>, session: Session) -> Response {
+    let Some(user_id) = sess::get_user_id(&session).await else {
+        return StatusCode::UNAUTHORIZED.into_response();
+    };
+    match tokens::list_tokens(&state.db, &user_id).await {
+        Ok(tokens) => Json(serde_json::json!({ "tokens": tokens })).into_response(),
+        Err(e) => {
+            error!(user_id, "list api tokens: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// DELETE /auth/tokens/{token_id} — revoke a token. Takes effect on the next request.
+pub async fn revoke_token(
+    State(state): State<Arc<AppState>>,
+    session: Session,
+    axum::extract::Path(token_id): axum::extract::Path<String>,
+) -> Response {
+    let Some(user_id) = sess::get_user_id(&session).await else {
+        return StatusCode::UNAUTHORIZED.into_response();
+    };
+    match tokens::revoke_token(&state.db, &user_id, &token_id).await {
+        Ok(true) => {
+            info!(user_id, token_id, "api token revoked");
+            audit(
+                &state.db,
+                Some(&user_id),
+                "token_revoked",
+                Some(&token_id),
+                None,
+            )
+            .await;
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Ok(false) => (StatusCode::NOT_FOUND, "token not found").into_response(),
+        Err(e) => {
+            error!(user_id, "revoke api token: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
 // ── Machine class profile ────────────────────────────────────────────────────
 
 /// Load the user's machine_class preference from DB into the sandbox registry.
@@ -7,6 +7,7 @@ use crate::config::Config;
 
 pub mod handlers;
 pub mod session;
+pub mod tokens;
 
 /// Shared WebAuthn instance (cheap to clone — Arc-wrapped internally).
 pub fn build_webauthn(config: &Config) -> anyhow::Result<Arc<Webauthn>> {
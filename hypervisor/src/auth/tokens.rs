@@ -0,0 +1,251 @@
+//! Personal access tokens for programmatic access (CLI, scripts).
+//!
+//! A token string is `choir_pat_<id>_<secret>`. The id locates the row; only
+//! a SHA-256 digest of the secret is stored, so the plaintext is returned once
+//! at creation and cannot be recovered. Every authenticated request re-reads
+//! the row, which makes revocation take effect immediately.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{Row, SqlitePool};
+
+pub const TOKEN_PREFIX: &str = "choir_pat_";
+
+/// Lifetime used when the caller does not ask for one.
+pub const DEFAULT_TOKEN_TTL_DAYS: i64 = 90;
+/// Longest lifetime a token may be issued with.
+pub const MAX_TOKEN_TTL_DAYS: i64 = 365;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenScope {
+    /// Safe methods only (GET, HEAD, OPTIONS), no WebSocket upgrades.
+    ReadOnly,
+    Full,
+}
+
+impl TokenScope {
+    fn as_str(self) -> &'static str {
+        match self {
+            TokenScope::ReadOnly => "read_only",
+            TokenScope::Full => "full",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "read_only" => Some(TokenScope::ReadOnly),
+            "full" => Some(TokenScope::Full),
+            _ => None,
+        }
+    }
+}
+
+/// Stored token metadata. Never includes the secret.
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiToken {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    pub scope: TokenScope,
+    pub created_at: i64,
+    pub expires_at: i64,
+    pub last_used_at: Option<i64>,
+    pub revoked_at: Option<i64>,
+}
+
+/// Request identity established from a bearer token by the auth middleware.
+/// Inserted as a request extension.
+#[derive(Debug, Clone)]
+pub struct TokenPrincipal {
+    pub user_id: String,
+    pub token_id: String,
+    pub scope: TokenScope,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TokenAuthError {
+    #[error("invalid token")]
+    Invalid,
+    #[error("token expired")]
+    Expired,
+    #[error("token revoked")]
+    Revoked,
+    #[error("token lookup failed: {0}")]
+    Storage(#[from] sqlx::Error),
+}
+
+fn hash_secret(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
+
+/// Split a token string into `(id, secret)`.
+fn parse_token(token: &str) -> Option<(&str, &str)> {
+    let (id, secret) = token.strip_prefix(TOKEN_PREFIX)?.split_once('_')?;
+    (!id.is_empty() && !secret.is_empty()).then_some((id, secret))
+}
+
+/// Whether a bearer credential looks like a personal access token.
+pub fn is_personal_token(token: &str) -> bool {
+    token.starts_with(TOKEN_PREFIX)
+}
+
+fn row_to_token(row: &sqlx::sqlite::SqliteRow) -> anyhow::Result<ApiToken> {
+    let scope: String = row.get("scope");
+    Ok(ApiToken {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        name: row.get("name"),
+        scope: TokenScope::parse(&scope)
+            .ok_or_else(|| anyhow::anyhow!("unknown token scope: {scope}"))?,
+        created_at: row.get("created_at"),
+        expires_at: row.get("expires_at"),
+        last_used_at: row.get("last_used_at"),
+        revoked_at: row.get("revoked_at"),
+    })
+}
+
+/// Create a token and return its metadata with the plaintext token string.
+pub async fn create_token(
+    pool: &SqlitePool,
+    user_id: &str,
+    name: &str,
+    scope: TokenScope,
+    ttl_days: i64,
+) -> anyhow::Result<(ApiToken, String)> {
+    use rand::RngCore;
+
+    let id = ulid::Ulid::new().to_string().to_lowercase();
+    let mut raw = [0u8; 32];
+    rand::rng().fill_bytes(&mut raw);
+    let secret = hex::encode(raw);
+    let now = Utc::now().timestamp();
+    let expires_at = now + ttl_days * 86_400;
+
+    sqlx::query(
+        r#"
+        INSERT INTO api_tokens (id, user_id, name, scope, secret_hash, created_at, expires_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&id)
+    .bind(user_id)
+    .bind(name)
+    .bind(scope.as_str())
+    .bind(hash_secret(&secret))
+    .bind(now)
+    .bind(expires_at)
+    .execute(pool)
+    .await?;
+
+    let token = ApiToken {
+        id: id.clone(),
+        user_id: user_id.to_string(),
+        name: name.to_string(),
+        scope,
+        created_at: now,
+        expires_at,
+        last_used_at: None,
+        revoked_at: None,
+    };
+    Ok((token, format!("{TOKEN_PREFIX}{id}_{secret}")))
+}
+
+/// All tokens of a user, newest first, including revoked and expired ones.
+pub async fn list_tokens(pool: &SqlitePool, user_id: &str) -> anyhow::Result<Vec<ApiToken>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id, user_id, name, scope, created_at, expires_at, last_used_at, revoked_at
+        FROM api_tokens WHERE user_id = ? ORDER BY created_at DESC, id DESC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+    rows.iter().map(row_to_token).collect()
+}
+
+/// Revoke one of the user's tokens. Returns false if no active token matched.
+pub async fn revoke_token(
+    pool: &SqlitePool,
+    user_id: &str,
+    token_id: &str,
+) -> anyhow::Result<bool> {
+    let result = sqlx::query(
+        "UPDATE api_tokens SET revoked_at = ? WHERE id = ? AND user_id = ? AND revoked_at IS NULL",
+    )
+    .bind(Utc::now().timestamp())
+    .bind(token_id)
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Resolve a bearer token to its principal and record the use.
+pub async fn authenticate(
+    pool: &SqlitePool,
+    token: &str,
+) -> Result<TokenPrincipal, TokenAuthError> {
+    let Some((id, secret)) = parse_token(token) else {
+        return Err(TokenAuthError::Invalid);
+    };
+    let Some(row) = sqlx::query(
+        "SELECT user_id, scope, secret_hash, expires_at, revoked_at FROM api_tokens WHERE id = ?",
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await?
+    else {
+        return Err(TokenAuthError::Invalid);
+    };
+
+    let stored_hash: String = row.get("secret_hash");
+    if !constant_time_eq(hash_secret(secret).as_bytes(), stored_hash.as_bytes()) {
+        return Err(TokenAuthError::Invalid);
+    }
+    if row.get::<Option<i64>, _>("revoked_at").is_some() {
+        return Err(TokenAuthError::Revoked);
+    }
+    let now = Utc::now().timestamp();
+    if row.get::<i64, _>("expires_at") <= now {
+        return Err(TokenAuthError::Expired);
+    }
+    let scope: String = row.get("scope");
+    let Some(scope) = TokenScope::parse(&scope) else {
+        return Err(TokenAuthError::Invalid);
+    };
+
+    sqlx::query("UPDATE api_tokens SET last_used_at = ? WHERE id = ?")
+        .bind(now)
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    Ok(TokenPrincipal {
+        user_id: row.get("user_id"),
+        token_id: id.to_string(),
+        scope,
+    })
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_token;
+
+    #[test]
+    fn parse_token_splits_id_and_secret() {
+        assert_eq!(
+            parse_token("choir_pat_01hx_abc123"),
+            Some(("01hx", "abc123"))
+        );
+        assert_eq!(parse_token("choir_pat_01hx_"), None);
+        assert_eq!(parse_token("choir_pat_nosecret"), None);
+        assert_eq!(parse_token("admin-token"), None);
+    }
+}
@@ -75,6 +75,49 @@ fn config_class_names(config: &MachineClassesConfig) -> String {
     names.join(", ")
 }
 
+// ── Warm sandbox pool ──────────────────────────────────────────────────────
+
+/// When the registry tops the warm pool back up after an adoption.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WarmPoolRefill {
+    /// Start a replacement as soon as a standby is adopted.
+    #[default]
+    Immediate,
+    /// Leave refills to the idle watchdog tick, spreading boot load out.
+    Watchdog,
+}
+
+impl std::str::FromStr for WarmPoolRefill {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "immediate" => Ok(Self::Immediate),
+            "watchdog" => Ok(Self::Watchdog),
+            other => Err(format!(
+                "unknown refill policy '{other}' (expected immediate|watchdog)"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for WarmPoolRefill {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Immediate => write!(f, "immediate"),
+            Self::Watchdog => write!(f, "watchdog"),
+        }
+    }
+}
+
+/// Pre-started generic sandboxes adopted on login instead of cold-booting.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WarmPoolConfig {
+    /// Number of standbys to keep running; 0 disables the pool.
+    pub size: usize,
+    pub refill: WarmPoolRefill,
+}
+
 #[derive(Debug, Clone)]
 pub struct Config {
     /// Port the hypervisor listens on
@@ -103,12 +146,18 @@ pub struct Config {
     pub provider_gateway_token: Option<String>,
     /// Base URL sandboxes use to reach the hypervisor provider gateway.
     pub provider_gateway_base_url: Option<String>,
+    /// Master secret each sandbox's event payload encryption key is derived from.
+    pub event_encryption_secret: Option<String>,
     /// Allowed upstream provider base URLs for the gateway.
     pub provider_gateway_allowed_upstreams: Vec<String>,
-    /// Per-sandbox request budget over a rolling 60s window.
+    /// Per-user, per-provider request budget over a rolling 60s window.
     pub provider_gateway_rate_limit_per_minute: usize,
+    /// Provider-specific budgets that replace the default one.
+    pub provider_gateway_rate_limit_overrides: HashMap<String, usize>,
     /// Machine classes config (ADR-0014 Phase 6).
     pub machine_classes: MachineClassesConfig,
+    /// Warm standby sandboxes adopted on first login.
+    pub sandbox_warm_pool: WarmPoolConfig,
 }
 
 impl Config {
@@ -152,6 +201,10 @@ impl Config {
             webauthn_rp_name: env_str("WEBAUTHN_RP_NAME", "ChoirOS"),
             provider_gateway_token,
             provider_gateway_base_url,
+            event_encryption_secret: env_or_credential(
+                "CHOIR_EVENT_ENCRYPTION_SECRET",
+                "event_encryption_secret",
+            ),
             provider_gateway_allowed_upstreams: env_csv(
                 "CHOIR_PROVIDER_GATEWAY_ALLOWED_UPSTREAMS",
                 &[
@@ -169,10 +222,17 @@ impl Config {
                 "CHOIR_PROVIDER_GATEWAY_RATE_LIMIT_PER_MINUTE",
                 120,
             )?,
+            provider_gateway_rate_limit_overrides: env_rate_limit_overrides(
+                "CHOIR_PROVIDER_GATEWAY_PROVIDER_RATE_LIMITS",
+            )?,
             machine_classes: MachineClassesConfig::load(&env_str(
                 "CHOIR_MACHINE_CLASSES_PATH",
                 "/etc/choiros/machine-classes.toml",
             )),
+            sandbox_warm_pool: WarmPoolConfig {
+                size: env_parse("SANDBOX_WARM_POOL_SIZE", 0)?,
+                refill: env_parse("SANDBOX_WARM_POOL_REFILL", WarmPoolRefill::Immediate)?,
+            },
         };
 
         if cfg.sandbox_branch_port_start > cfg.sandbox_branch_port_end {
@@ -247,6 +307,36 @@ fn env_csv(key: &str, default: &[&str]) -> Vec<String> {
     }
 }
 
+/// Read `provider=limit` pairs, comma separated, e.g. `openai=60,aws-bedrock=300`.
+fn env_rate_limit_overrides(key: &str) -> anyhow::Result<HashMap<String, usize>> {
+    match std::env::var(key) {
+        Ok(raw) => parse_rate_limit_overrides(&raw)
+            .map_err(|e| anyhow::anyhow!("Failed to parse env var {key}={raw}: {e}")),
+        Err(_) => Ok(HashMap::new()),
+    }
+}
+
+fn parse_rate_limit_overrides(raw: &str) -> Result<HashMap<String, usize>, String> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (provider, limit) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("expected provider=limit, got '{pair}'"))?;
+            let provider = provider.trim();
+            if provider.is_empty() {
+                return Err(format!("missing provider in '{pair}'"));
+            }
+            let limit = limit
+                .trim()
+                .parse::<usize>()
+                .map_err(|e| format!("invalid limit in '{pair}': {e}"))?;
+            Ok((provider.to_string(), limit))
+        })
+        .collect()
+}
+
 /// Resolve the Dioxus frontend dist directory for unauth auth-page bootstrap.
 ///
 /// If `FRONTEND_DIST` is set, that value is used as-is.
@@ -280,7 +370,7 @@ pub fn frontend_dist_from_env() -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::read_credential_from_dir;
+    use super::{parse_rate_limit_overrides, read_credential_from_dir, WarmPoolRefill};
 
     #[test]
     fn reads_trimmed_credential_value() {
@@ -297,4 +387,30 @@ mod tests {
 
         let _ = std::fs::remove_dir_all(dir);
     }
+
+    #[test]
+    fn parses_warm_pool_refill_policy() {
+        assert_eq!(
+            "immediate".parse::<WarmPoolRefill>(),
+            Ok(WarmPoolRefill::Immediate)
+        );
+        assert_eq!(
+            "watchdog".parse::<WarmPoolRefill>(),
+            Ok(WarmPoolRefill::Watchdog)
+        );
+        assert!("lazy".parse::<WarmPoolRefill>().is_err());
+    }
+
+    #[test]
+    fn parses_provider_rate_limit_overrides() {
+        let overrides = parse_rate_limit_overrides(" openai=60, aws-bedrock = 0 ,").unwrap();
+        assert_eq!(overrides.len(), 2);
+        assert_eq!(overrides["openai"], 60);
+        assert_eq!(overrides["aws-bedrock"], 0);
+
+        assert!(parse_rate_limit_overrides("").unwrap().is_empty());
+        assert!(parse_rate_limit_overrides("openai").is_err());
+        assert!(parse_rate_limit_overrides("=5").is_err());
+        assert!(parse_rate_limit_overrides("openai=lots").is_err());
+    }
 }
@@ -43,6 +43,7 @@ mod tests {
             "branch_runtimes",
             "route_pointers",
             "runtime_events",
+            "api_tokens",
         ];
 
         for table in expected_tables {
@@ -3,6 +3,7 @@ mod auth;
 mod config;
 mod db;
 mod jobs;
+mod metrics;
 mod middleware;
 mod provider_gateway;
 mod proxy;
@@ -71,14 +72,21 @@ async fn main() -> anyhow::Result<()> {
         config.sandbox_idle_timeout,
         config.provider_gateway_base_url.clone(),
         config.provider_gateway_token.clone(),
+        config.event_encryption_secret.clone(),
+        Some(config.webauthn_rp_origin.clone()),
         config.machine_classes.clone(),
+        config.sandbox_warm_pool,
     );
 
     // Boot live sandbox in background so the HTTP server starts immediately.
     // The VM takes ~90s to boot; requests will get 503 until it's ready.
+    // Warm standbys boot after it so they don't compete for the first VM.
     {
         let reg = Arc::clone(&sandbox_registry);
-        tokio::spawn(async move { reg.boot_live_sandbox().await });
+        tokio::spawn(async move {
+            reg.boot_live_sandbox().await;
+            reg.fill_warm_pool();
+        });
     }
 
     // Spawn idle watchdog
@@ -99,11 +107,19 @@ async fn main() -> anyhow::Result<()> {
                 .timeout(std::time::Duration::from_secs(120))
                 .build()?,
             rate_limit_per_minute: config.provider_gateway_rate_limit_per_minute,
+            rate_limit_overrides: config.provider_gateway_rate_limit_overrides.clone(),
             rate_limit_state: Arc::new(dashmap::DashMap::new()),
+            inflight_requests: Arc::new(dashmap::DashMap::new()),
         },
         proxy_client: proxy::new_pooled_client(),
+        metrics: metrics::Metrics::default(),
     });
 
+    // Evict idle provider gateway rate limit buckets.
+    tokio::spawn(provider_gateway::run_rate_limit_bucket_eviction(
+        Arc::clone(&state.provider_gateway.rate_limit_state),
+    ));
+
     let app = Router::new()
         // Auth API endpoints
         .route("/auth/register/start", post(auth::handlers::register_start))
@@ -116,12 +132,21 @@ async fn main() -> anyhow::Result<()> {
         .route("/auth/logout", post(auth::handlers::logout))
         .route("/auth/recovery", post(auth::handlers::recovery))
         .route("/auth/me", get(auth::handlers::me))
+        .route(
+            "/auth/tokens",
+            post(auth::handlers::create_token).get(auth::handlers::list_tokens),
+        )
+        .route(
+            "/auth/tokens/{token_id}",
+            delete(auth::handlers::revoke_token),
+        )
         // Root shell page (Dioxus SPA bootstrap). Runtime APIs still proxy via fallback.
         .route("/", get(auth::handlers::login_page))
         // Public auth shell pages.
         .route("/login", get(auth::handlers::login_page))
         .route("/register", get(auth::handlers::register_page))
         .route("/recovery", get(auth::handlers::recovery_page))
+        .route("/provider/v1/health", get(provider_gateway::health))
         .route(
             "/provider/v1/{provider}/{*rest}",
             any(provider_gateway::forward_provider_request),
@@ -140,6 +165,8 @@ async fn main() -> anyhow::Result<()> {
         // Admin sandbox management
         .route("/admin/stats", get(api::host_stats))
         .route("/admin/sandboxes", get(api::list_sandboxes))
+        .route("/admin/metrics", get(api::metrics))
+        .route("/admin/dashboard", get(api::dashboard))
         .route(
             "/admin/sandboxes/{user_id}/{role}/start",
             post(api::start_sandbox),
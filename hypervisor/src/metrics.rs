@@ -0,0 +1,605 @@
+//! Fleet metrics for operators.
+//!
+//! Counters are plain atomics behind a DashMap keyed by label, so recording
+//! on the proxy hot path is a shard read lock and a few relaxed increments;
+//! a label's counters are only allocated the first time it is seen.
+//! `/admin/metrics` renders them in the Prometheus text format and
+//! `/admin/dashboard` summarizes them as JSON for the admin UI.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use axum::http::StatusCode;
+use dashmap::DashMap;
+use serde::Serialize;
+
+use crate::sandbox::{SandboxSnapshot, SandboxStatus};
+
+/// Upper bounds, in seconds, of the proxy latency histogram buckets.
+const LATENCY_BUCKETS_SECS: [f64; 10] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 10.0];
+
+/// Label for one sandbox runtime: `{user_id}/live`, `{user_id}/dev` or
+/// `{user_id}/branch:{name}`.
+pub fn sandbox_label(user_id: &str, runtime: &str) -> String {
+    format!("{user_id}/{runtime}")
+}
+
+/// Why a request failed authentication.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AuthFailure {
+    /// Passkey login assertion was rejected.
+    Passkey,
+    /// Recovery code did not match.
+    RecoveryCode,
+    /// Personal access token was unknown, expired or revoked.
+    ApiToken,
+    /// Personal access token lacked the scope for the request.
+    TokenScope,
+    /// A sandbox presented the wrong provider gateway token.
+    GatewayToken,
+}
+
+impl AuthFailure {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Passkey => "passkey",
+            Self::RecoveryCode => "recovery_code",
+            Self::ApiToken => "api_token",
+            Self::TokenScope => "token_scope",
+            Self::GatewayToken => "gateway_token",
+        }
+    }
+}
+
+#[derive(Default)]
+struct ProxyCounters {
+    requests: AtomicU64,
+    /// Responses with a 5xx status.
+    errors: AtomicU64,
+    latency_micros: AtomicU64,
+    /// Cumulative counts per `LATENCY_BUCKETS_SECS` bound.
+    buckets: [AtomicU64; LATENCY_BUCKETS_SECS.len()],
+}
+
+#[derive(Default)]
+struct GatewayCounters {
+    calls: AtomicU64,
+    /// Upstream failures and non-2xx upstream responses.
+    errors: AtomicU64,
+    input_tokens: AtomicU64,
+    output_tokens: AtomicU64,
+}
+
+/// Proxy, gateway and auth counters for the whole hypervisor.
+pub struct Metrics {
+    started_at: Instant,
+    proxy: DashMap<String, ProxyCounters>,
+    gateway: DashMap<String, GatewayCounters>,
+    auth_failures: DashMap<AuthFailure, AtomicU64>,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self {
+            started_at: Instant::now(),
+            proxy: DashMap::new(),
+            gateway: DashMap::new(),
+            auth_failures: DashMap::new(),
+        }
+    }
+}
+
+/// Proxy counters for one sandbox.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ProxyStats {
+    pub requests: u64,
+    pub errors: u64,
+    pub avg_latency_ms: f64,
+}
+
+/// Provider gateway counters for one user.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GatewayStats {
+    pub user_id: String,
+    pub calls: u64,
+    pub errors: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+/// One sandbox row of the dashboard.
+#[derive(Debug, Serialize)]
+pub struct SandboxSummary {
+    pub label: String,
+    #[serde(flatten)]
+    pub snapshot: SandboxSnapshot,
+    pub restarts: u64,
+    #[serde(flatten)]
+    pub proxy: ProxyStats,
+}
+
+/// Body of `GET /admin/dashboard`.
+#[derive(Debug, Serialize)]
+pub struct Dashboard {
+    pub uptime_secs: u64,
+    pub sandboxes_running: usize,
+    pub sandboxes_total: usize,
+    pub active_sessions: u64,
+    /// Busiest first.
+    pub sandboxes: Vec<SandboxSummary>,
+    /// Most tokens first.
+    pub gateway: Vec<GatewayStats>,
+    pub auth_failures: BTreeMap<&'static str, u64>,
+}
+
+/// State outside the counters needed to render a report.
+pub struct FleetState {
+    pub sandboxes: Vec<SandboxSnapshot>,
+    /// Restarts per `sandbox_label`.
+    pub restarts: Vec<(String, u64)>,
+    pub active_sessions: u64,
+}
+
+impl Metrics {
+    /// Count one request proxied to the sandbox `label`.
+    pub fn record_proxy(&self, label: &str, status: StatusCode, latency: Duration) {
+        if let Some(counters) = self.proxy.get(label) {
+            counters.record(status, latency);
+            return;
+        }
+        self.proxy
+            .entry(label.to_string())
+            .or_default()
+            .record(status, latency);
+    }
+
+    /// Count one provider gateway call made on behalf of `user_id`.
+    pub fn record_gateway_call(
+        &self,
+        user_id: &str,
+        success: bool,
+        input_tokens: u64,
+        output_tokens: u64,
+    ) {
+        let record = |counters: &GatewayCounters| {
+            counters.calls.fetch_add(1, Ordering::Relaxed);
+            if !success {
+                counters.errors.fetch_add(1, Ordering::Relaxed);
+            }
+            counters
+                .input_tokens
+                .fetch_add(input_tokens, Ordering::Relaxed);
+            counters
+                .output_tokens
+                .fetch_add(output_tokens, Ordering::Relaxed);
+        };
+        if let Some(counters) = self.gateway.get(user_id) {
+            record(&counters);
+            return;
+        }
+        record(&self.gateway.entry(user_id.to_string()).or_default());
+    }
+
+    pub fn record_auth_failure(&self, reason: AuthFailure) {
+        self.auth_failures
+            .entry(reason)
+            .or_default()
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn proxy_stats(&self, label: &str) -> ProxyStats {
+        self.proxy
+            .get(label)
+            .map(|counters| counters.stats())
+            .unwrap_or_default()
+    }
+
+    pub fn gateway_stats(&self) -> Vec<GatewayStats> {
+        let mut stats: Vec<GatewayStats> = self
+            .gateway
+            .iter()
+            .map(|entry| GatewayStats {
+                user_id: entry.key().clone(),
+                calls: entry.calls.load(Ordering::Relaxed),
+                errors: entry.errors.load(Ordering::Relaxed),
+                input_tokens: entry.input_tokens.load(Ordering::Relaxed),
+                output_tokens: entry.output_tokens.load(Ordering::Relaxed),
+            })
+            .collect();
+        stats.sort_by_key(|s| std::cmp::Reverse(s.input_tokens + s.output_tokens));
+        stats
+    }
+
+    fn auth_failure_counts(&self) -> BTreeMap<&'static str, u64> {
+        self.auth_failures
+            .iter()
+            .map(|entry| (entry.key().as_str(), entry.load(Ordering::Relaxed)))
+            .collect()
+    }
+
+    pub fn dashboard(&self, fleet: FleetState) -> Dashboard {
+        let restarts: HashMap<String, u64> = fleet.restarts.into_iter().collect();
+        let sandboxes_total = fleet.sandboxes.len();
+        let sandboxes_running = fleet
+            .sandboxes
+            .iter()
+            .filter(|s| s.status == SandboxStatus::Running)
+            .count();
+        let mut sandboxes: Vec<SandboxSummary> = fleet
+            .sandboxes
+            .into_iter()
+            .map(|snapshot| {
+                let label = snapshot_label(&snapshot);
+                SandboxSummary {
+                    restarts: restarts.get(&label).copied().unwrap_or(0),
+                    proxy: self.proxy_stats(&label),
+                    label,
+                    snapshot,
+                }
+            })
+            .collect();
+        sandboxes.sort_by_key(|s| std::cmp::Reverse(s.proxy.requests));
+        Dashboard {
+            uptime_secs: self.started_at.elapsed().as_secs(),
+            sandboxes_running,
+            sandboxes_total,
+            active_sessions: fleet.active_sessions,
+            sandboxes,
+            gateway: self.gateway_stats(),
+            auth_failures: self.auth_failure_counts(),
+        }
+    }
+
+    /// All metrics in the Prometheus text exposition format.
+    pub fn render_prometheus(&self, fleet: &FleetState) -> String {
+        let mut out = String::new();
+
+        header(
+            &mut out,
+            "choiros_uptime_seconds",
+            "gauge",
+            "Hypervisor uptime.",
+        );
+        let _ = writeln!(
+            out,
+            "choiros_uptime_seconds {}",
+            self.started_at.elapsed().as_secs()
+        );
+
+        header(
+            &mut out,
+            "choiros_sandboxes",
+            "gauge",
+            "Known sandboxes by status.",
+        );
+        for status in ["running", "starting", "hibernated", "stopped", "failed"] {
+            let count = fleet
+                .sandboxes
+                .iter()
+                .filter(|s| status_name(&s.status) == status)
+                .count();
+            let _ = writeln!(out, "choiros_sandboxes{{status=\"{status}\"}} {count}");
+        }
+
+        header(
+            &mut out,
+            "choiros_sandbox_restarts_total",
+            "counter",
+            "Sandbox starts after a stop, hibernation or failure.",
+        );
+        for (label, count) in &fleet.restarts {
+            let _ = writeln!(
+                out,
+                "choiros_sandbox_restarts_total{{sandbox=\"{}\"}} {count}",
+                escape(label)
+            );
+        }
+
+        header(
+            &mut out,
+            "choiros_sessions_active",
+            "gauge",
+            "Unexpired login sessions.",
+        );
+        let _ = writeln!(out, "choiros_sessions_active {}", fleet.active_sessions);
+
+        let mut proxy: Vec<(String, ProxySnapshot)> = self
+            .proxy
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.snapshot()))
+            .collect();
+        proxy.sort_by(|a, b| a.0.cmp(&b.0));
+        header(
+            &mut out,
+            "choiros_proxy_requests_total",
+            "counter",
+            "Requests proxied to a sandbox.",
+        );
+        for (label, snapshot) in &proxy {
+            let _ = writeln!(
+                out,
+                "choiros_proxy_requests_total{{sandbox=\"{}\"}} {}",
+                escape(label),
+                snapshot.requests
+            );
+        }
+        header(
+            &mut out,
+            "choiros_proxy_errors_total",
+            "counter",
+            "Proxied requests answered with a 5xx status.",
+        );
+        for (label, snapshot) in &proxy {
+            let _ = writeln!(
+                out,
+                "choiros_proxy_errors_total{{sandbox=\"{}\"}} {}",
+                escape(label),
+                snapshot.errors
+            );
+        }
+        header(
+            &mut out,
+            "choiros_proxy_request_duration_seconds",
+            "histogram",
+            "Proxy latency up to response headers.",
+        );
+        for (label, snapshot) in &proxy {
+            let label = escape(label);
+            for (bound, count) in LATENCY_BUCKETS_SECS.iter().zip(snapshot.buckets) {
+                let _ = writeln!(
+                    out,
+                    "choiros_proxy_request_duration_seconds_bucket{{sandbox=\"{label}\",le=\"{bound}\"}} {count}"
+                );
+            }
+            let _ = writeln!(
+                out,
+                "choiros_proxy_request_duration_seconds_bucket{{sandbox=\"{label}\",le=\"+Inf\"}} {}",
+                snapshot.requests
+            );
+            let _ = writeln!(
+                out,
+                "choiros_proxy_request_duration_seconds_sum{{sandbox=\"{label}\"}} {}",
+                snapshot.latency_micros as f64 / 1_000_000.0
+            );
+            let _ = writeln!(
+                out,
+                "choiros_proxy_request_duration_seconds_count{{sandbox=\"{label}\"}} {}",
+                snapshot.requests
+            );
+        }
+
+        let mut gateway = self.gateway_stats();
+        gateway.sort_by(|a, b| a.user_id.cmp(&b.user_id));
+        header(
+            &mut out,
+            "choiros_gateway_calls_total",
+            "counter",
+            "Provider gateway calls per user.",
+        );
+        for stats in &gateway {
+            let _ = writeln!(
+                out,
+                "choiros_gateway_calls_total{{user=\"{}\"}} {}",
+                escape(&stats.user_id),
+                stats.calls
+            );
+        }
+        header(
+            &mut out,
+            "choiros_gateway_errors_total",
+            "counter",
+            "Provider gateway calls that failed or got a non-2xx answer.",
+        );
+        for stats in &gateway {
+            let _ = writeln!(
+                out,
+                "choiros_gateway_errors_total{{user=\"{}\"}} {}",
+                escape(&stats.user_id),
+                stats.errors
+            );
+        }
+        header(
+            &mut out,
+            "choiros_gateway_tokens_total",
+            "counter",
+            "Model tokens billed through the provider gateway.",
+        );
+        for stats in &gateway {
+            let user = escape(&stats.user_id);
+            let _ = writeln!(
+                out,
+                "choiros_gateway_tokens_total{{user=\"{user}\",direction=\"input\"}} {}",
+                stats.input_tokens
+            );
+            let _ = writeln!(
+                out,
+                "choiros_gateway_tokens_total{{user=\"{user}\",direction=\"output\"}} {}",
+                stats.output_tokens
+            );
+        }
+
+        header(
+            &mut out,
+            "choiros_auth_failures_total",
+            "counter",
+            "Rejected authentication attempts by reason.",
+        );
+        for (reason, count) in self.auth_failure_counts() {
+            let _ = writeln!(
+                out,
+                "choiros_auth_failures_total{{reason=\"{}\"}} {count}",
+                reason
+            );
+        }
+        out
+    }
+}
+
+struct ProxySnapshot {
+    requests: u64,
+    errors: u64,
+    latency_micros: u64,
+    buckets: [u64; LATENCY_BUCKETS_SECS.len()],
+}
+
+impl ProxyCounters {
+    fn record(&self, status: StatusCode, latency: Duration) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        if status.is_server_error() {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+        self.latency_micros
+            .fetch_add(latency.as_micros() as u64, Ordering::Relaxed);
+        let secs = latency.as_secs_f64();
+        for (bound, bucket) in LATENCY_BUCKETS_SECS.iter().zip(&self.buckets) {
+            if secs <= *bound {
+                bucket.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    fn snapshot(&self) -> ProxySnapshot {
+        ProxySnapshot {
+            requests: self.requests.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+            latency_micros: self.latency_micros.load(Ordering::Relaxed),
+            buckets: std::array::from_fn(|i| self.buckets[i].load(Ordering::Relaxed)),
+        }
+    }
+
+    fn stats(&self) -> ProxyStats {
+        let snapshot = self.snapshot();
+        let avg_latency_ms = if snapshot.requests == 0 {
+            0.0
+        } else {
+            snapshot.latency_micros as f64 / snapshot.requests as f64 / 1000.0
+        };
+        ProxyStats {
+            requests: snapshot.requests,
+            errors: snapshot.errors,
+            avg_latency_ms,
+        }
+    }
+}
+
+/// Input and output token counts from a provider response body. Understands
+/// the Anthropic (`input_tokens`/`output_tokens`) and OpenAI
+/// (`prompt_tokens`/`completion_tokens`) usage shapes; anything else,
+/// including streamed responses, counts as zero.
+pub fn token_usage(body: &[u8]) -> (u64, u64) {
+    let Ok(json) = serde_json::from_slice::<serde_json::Value>(body) else {
+        return (0, 0);
+    };
+    let Some(usage) = json.get("usage") else {
+        return (0, 0);
+    };
+    let count = |keys: [&str; 2]| {
+        keys.iter()
+            .find_map(|key| usage.get(*key).and_then(|v| v.as_u64()))
+            .unwrap_or(0)
+    };
+    (
+        count(["input_tokens", "prompt_tokens"]),
+        count(["output_tokens", "completion_tokens"]),
+    )
+}
+
+fn snapshot_label(snapshot: &SandboxSnapshot) -> String {
+    let runtime = match (&snapshot.branch, snapshot.role) {
+        (Some(branch), _) => format!("branch:{branch}"),
+        (None, Some(role)) => role.to_string(),
+        (None, None) => "unknown".to_string(),
+    };
+    sandbox_label(&snapshot.user_id, &runtime)
+}
+
+fn status_name(status: &SandboxStatus) -> &'static str {
+    match status {
+        SandboxStatus::Running => "running",
+        SandboxStatus::Starting(_) => "starting",
+        SandboxStatus::Hibernated => "hibernated",
+        SandboxStatus::Stopped => "stopped",
+        SandboxStatus::Failed => "failed",
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+/// Escape a Prometheus label value.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_fleet() -> FleetState {
+        FleetState {
+            sandboxes: Vec::new(),
+            restarts: Vec::new(),
+            active_sessions: 0,
+        }
+    }
+
+    #[test]
+    fn proxy_latency_lands_in_cumulative_buckets() {
+        let metrics = Metrics::default();
+        metrics.record_proxy("u1/live", StatusCode::OK, Duration::from_millis(20));
+        metrics.record_proxy(
+            "u1/live",
+            StatusCode::BAD_GATEWAY,
+            Duration::from_millis(300),
+        );
+
+        let stats = metrics.proxy_stats("u1/live");
+        assert_eq!(stats.requests, 2);
+        assert_eq!(stats.errors, 1);
+        assert_eq!(stats.avg_latency_ms, 160.0);
+
+        let text = metrics.render_prometheus(&empty_fleet());
+        assert!(text.contains(
+            "choiros_proxy_request_duration_seconds_bucket{sandbox=\"u1/live\",le=\"0.025\"} 1"
+        ));
+        assert!(text.contains(
+            "choiros_proxy_request_duration_seconds_bucket{sandbox=\"u1/live\",le=\"0.5\"} 2"
+        ));
+        assert!(text.contains("choiros_proxy_errors_total{sandbox=\"u1/live\"} 1"));
+    }
+
+    #[test]
+    fn gateway_calls_accumulate_tokens_per_user() {
+        let metrics = Metrics::default();
+        let (input, output) =
+            token_usage(br#"{"usage": {"input_tokens": 12, "output_tokens": 30}}"#);
+        metrics.record_gateway_call("u1", true, input, output);
+        let (input, output) =
+            token_usage(br#"{"usage": {"prompt_tokens": 5, "completion_tokens": 7}}"#);
+        metrics.record_gateway_call("u1", true, input, output);
+        metrics.record_gateway_call("u2", false, 0, 0);
+
+        let stats = metrics.gateway_stats();
+        assert_eq!(stats[0].user_id, "u1");
+        assert_eq!((stats[0].input_tokens, stats[0].output_tokens), (17, 37));
+        assert_eq!((stats[1].calls, stats[1].errors), (1, 1));
+        assert_eq!(token_usage(b"data: {}\n\n"), (0, 0));
+
+        metrics.record_auth_failure(AuthFailure::GatewayToken);
+        let text = metrics.render_prometheus(&empty_fleet());
+        assert!(text.contains("choiros_gateway_tokens_total{user=\"u1\",direction=\"output\"} 37"));
+        assert!(text.contains("choiros_auth_failures_total{reason=\"gateway_token\"} 1"));
+    }
+
+    #[test]
+    fn label_values_are_escaped() {
+        assert_eq!(escape("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+}
@@ -1,16 +1,22 @@
 use std::sync::{Arc, OnceLock};
+use std::time::Instant;
 
 use axum::{
     body::Body,
     extract::{Request, State},
-    http::{header, HeaderValue, StatusCode},
+    http::{header, HeaderValue, Method, StatusCode},
     middleware::Next,
     response::{IntoResponse, Redirect, Response},
 };
 use tower_sessions::Session;
 
 use crate::{
-    auth::session as sess,
+    auth::{
+        handlers::audit,
+        session as sess,
+        tokens::{self, TokenAuthError, TokenPrincipal, TokenScope},
+    },
+    metrics::{sandbox_label, AuthFailure},
     runtime_registry::{self, PointerTarget},
     sandbox::SandboxRole,
     AppState,
@@ -169,13 +175,87 @@ fn strip_path_prefix(path_and_query: &str, prefix: &str) -> String {
     }
 }
 
-/// Middleware: require an authenticated session.
+fn bearer_token(req: &Request) -> Option<&str> {
+    req.headers()
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+}
+
+fn is_websocket_upgrade(req: &Request) -> bool {
+    req.headers()
+        .get("upgrade")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.eq_ignore_ascii_case("websocket"))
+        .unwrap_or(false)
+}
+
+/// Authenticate a personal access token and check its scope for this request.
+/// Granted and scope-denied uses are recorded in the audit log.
+async fn authorize_personal_token(
+    state: &AppState,
+    method: &Method,
+    path: &str,
+    is_ws: bool,
+    token: &str,
+) -> Result<TokenPrincipal, Response> {
+    let principal = match tokens::authenticate(&state.db, token).await {
+        Ok(principal) => principal,
+        Err(TokenAuthError::Storage(e)) => {
+            tracing::error!("api token lookup: {e}");
+            return Err(StatusCode::SERVICE_UNAVAILABLE.into_response());
+        }
+        Err(e) => {
+            state.metrics.record_auth_failure(AuthFailure::ApiToken);
+            return Err((StatusCode::UNAUTHORIZED, e.to_string()).into_response());
+        }
+    };
+
+    let detail = format!("{} {method} {path}", principal.token_id);
+    let denied = if path.starts_with("/admin/") {
+        Some("api tokens cannot access admin endpoints")
+    } else if principal.scope == TokenScope::ReadOnly
+        && (!matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) || is_ws)
+    {
+        Some("read-only token cannot make mutating requests")
+    } else {
+        None
+    };
+
+    if let Some(reason) = denied {
+        state.metrics.record_auth_failure(AuthFailure::TokenScope);
+        audit(
+            &state.db,
+            Some(&principal.user_id),
+            "token_denied",
+            Some(&detail),
+            None,
+        )
+        .await;
+        return Err((StatusCode::FORBIDDEN, reason).into_response());
+    }
+
+    audit(
+        &state.db,
+        Some(&principal.user_id),
+        "token_used",
+        Some(&detail),
+        None,
+    )
+    .await;
+    Ok(principal)
+}
+
+/// Middleware: require an authenticated session or personal access token.
 /// Unauthenticated requests to non-auth paths are redirected to /login.
 /// Admin endpoints accept a bearer token from /run/choiros/admin.token (ADR-0020 Phase 0).
+/// `Authorization: Bearer choir_pat_...` requests are attributed to the token
+/// owner via a [`TokenPrincipal`] request extension.
 pub async fn require_auth(
-    State(_state): State<Arc<AppState>>,
+    State(state): State<Arc<AppState>>,
     session: Session,
-    req: Request,
+    mut req: Request,
     next: Next,
 ) -> Response {
     let path = req.uri().path();
@@ -208,6 +288,22 @@ pub async fn require_auth(
         return next.run(req).await;
     }
 
+    if let Some(token) = bearer_token(&req)
+        .filter(|token| tokens::is_personal_token(token))
+        .map(str::to_string)
+    {
+        let method = req.method().clone();
+        let path = path.to_string();
+        let is_ws = is_websocket_upgrade(&req);
+        return match authorize_personal_token(&state, &method, &path, is_ws, &token).await {
+            Ok(principal) => {
+                req.extensions_mut().insert(principal);
+                next.run(req).await
+            }
+            Err(resp) => resp,
+        };
+    }
+
     if sess::get_user_id(&session).await.is_none() {
         return Redirect::to("/login").into_response();
     }
@@ -233,7 +329,15 @@ pub async fn proxy_to_sandbox(
         }
     }
 
-    let (user_id, authenticated) = match sess::get_user_id(&session).await {
+    let token_user_id = req
+        .extensions()
+        .get::<TokenPrincipal>()
+        .map(|principal| principal.user_id.clone());
+    let session_user_id = match token_user_id {
+        Some(id) => Some(id),
+        None => sess::get_user_id(&session).await,
+    };
+    let (user_id, authenticated) = match session_user_id {
         Some(id) => (id, true),
         None if is_public_bootstrap => ("public".to_string(), false),
         None => return (StatusCode::UNAUTHORIZED, "not authenticated").into_response(),
@@ -252,10 +356,40 @@ pub async fn proxy_to_sandbox(
             Err(resp) => return resp,
         };
 
+    let label = sandbox_label(&user_id, &runtime_label(&resolved_target));
+    let started_at = Instant::now();
+    let response = forward_to_sandbox(
+        &state,
+        req,
+        &user_id,
+        &resolved_target,
+        pointer_name.as_deref(),
+        &resolution,
+        authenticated,
+    )
+    .await;
+    state
+        .metrics
+        .record_proxy(&label, response.status(), started_at.elapsed());
+    response
+}
+
+/// Start the target runtime if needed and proxy `req` to it.
+async fn forward_to_sandbox(
+    state: &Arc<AppState>,
+    req: Request,
+    user_id: &str,
+    resolved_target: &SandboxRouteTarget,
+    pointer_name: Option<&str>,
+    resolution: &RouteResolution,
+    authenticated: bool,
+) -> Response {
+    let path = req.uri().path().to_string();
+
     // Ensure the target runtime is running (auto-starts if needed).
-    let port = match &resolved_target {
+    let port = match resolved_target {
         SandboxRouteTarget::Role(role) => {
-            match state.sandbox_registry.ensure_running(&user_id, *role).await {
+            match state.sandbox_registry.ensure_running(user_id, *role).await {
                 Ok(p) => p,
                 Err(e) => {
                     let msg = format!("sandbox unavailable: {e}");
@@ -271,7 +405,7 @@ pub async fn proxy_to_sandbox(
         SandboxRouteTarget::Branch(branch) => {
             match state
                 .sandbox_registry
-                .ensure_branch_running(&user_id, branch)
+                .ensure_branch_running(user_id, branch)
                 .await
             {
                 Ok(p) => p,
@@ -288,19 +422,23 @@ pub async fn proxy_to_sandbox(
         }
     };
 
-    // Detect WebSocket upgrade.
-    let is_ws = req
-        .headers()
-        .get("upgrade")
-        .and_then(|v| v.to_str().ok())
-        .map(|v| v.eq_ignore_ascii_case("websocket"))
-        .unwrap_or(false);
+    // The first request after a start waits briefly for the sandbox to report ready.
+    if let Err(resp) = state
+        .sandbox_registry
+        .readiness()
+        .ensure_ready(&state.proxy_client, port)
+        .await
+    {
+        return resp;
+    }
+
+    let is_ws = is_websocket_upgrade(&req);
 
     let req = sanitize_and_tag_proxy_request(
         req,
-        Some(&user_id),
-        &resolved_target,
-        pointer_name.as_deref(),
+        Some(user_id),
+        resolved_target,
+        pointer_name,
         authenticated,
     );
 
@@ -337,6 +475,14 @@ pub async fn proxy_to_sandbox(
     crate::proxy::proxy_http(&state.proxy_client, req, port).await
 }
 
+/// `live`, `dev` or `branch:{name}`.
+fn runtime_label(target: &SandboxRouteTarget) -> String {
+    match target {
+        SandboxRouteTarget::Role(role) => role.to_string(),
+        SandboxRouteTarget::Branch(branch) => format!("branch:{branch}"),
+    }
+}
+
 fn sanitize_and_tag_proxy_request(
     req: Request,
     user_id: Option<&str>,
@@ -363,12 +509,10 @@ fn sanitize_and_tag_proxy_request(
         }
     }
 
-    let (role_value, branch_value, runtime_label) = match target {
-        SandboxRouteTarget::Role(SandboxRole::Live) => ("live", None, "live".to_string()),
-        SandboxRouteTarget::Role(SandboxRole::Dev) => ("dev", None, "dev".to_string()),
-        SandboxRouteTarget::Branch(branch) => {
-            ("branch", Some(branch.as_str()), format!("branch:{branch}"))
-        }
+    let (role_value, branch_value) = match target {
+        SandboxRouteTarget::Role(SandboxRole::Live) => ("live", None),
+        SandboxRouteTarget::Role(SandboxRole::Dev) => ("dev", None),
+        SandboxRouteTarget::Branch(branch) => ("branch", Some(branch.as_str())),
     };
 
     parts.headers.insert(
@@ -382,7 +526,7 @@ fn sanitize_and_tag_proxy_request(
         }
     }
 
-    if let Ok(v) = HeaderValue::from_str(&runtime_label) {
+    if let Ok(v) = HeaderValue::from_str(&runtime_label(target)) {
         parts.headers.insert("x-choiros-sandbox-runtime", v);
     }
 
@@ -475,8 +619,261 @@ fn verify_admin_token(token: &str) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use super::{resolve_route, strip_path_prefix, RouteTarget};
-    use axum::http::StatusCode;
+    use super::{proxy_to_sandbox, require_auth, resolve_route, strip_path_prefix, RouteTarget};
+    use crate::auth::tokens::{self, TokenPrincipal, TokenScope};
+    use crate::AppState;
+    use axum::body::Body;
+    use axum::extract::Request;
+    use axum::http::{header, StatusCode};
+    use axum::{middleware::from_fn_with_state, routing::get, Extension, Router};
+    use http_body_util::BodyExt;
+    use std::sync::Arc;
+    use tower::ServiceExt;
+    use tower_sessions::SessionManagerLayer;
+
+    async fn token_test_app() -> (Router, Arc<AppState>, std::path::PathBuf) {
+        let registry = crate::sandbox::SandboxRegistry::new(
+            "true".to_string(),
+            1,
+            2,
+            3,
+            4,
+            std::time::Duration::from_secs(60),
+            None,
+            None,
+            None,
+            None,
+            Default::default(),
+            Default::default(),
+        );
+        let (state, session_store, db_path) = test_state(registry).await;
+
+        async fn whoami(principal: Option<Extension<TokenPrincipal>>) -> String {
+            principal.map(|Extension(p)| p.user_id).unwrap_or_default()
+        }
+        let app = Router::new()
+            .route("/api/whoami", get(whoami).post(whoami))
+            .layer(from_fn_with_state(Arc::clone(&state), require_auth))
+            .layer(SessionManagerLayer::new(session_store));
+        (app, state, db_path)
+    }
+
+    async fn test_state(
+        sandbox_registry: Arc<crate::sandbox::SandboxRegistry>,
+    ) -> (
+        Arc<AppState>,
+        crate::session_store::SqliteSessionStore,
+        std::path::PathBuf,
+    ) {
+        let db_path =
+            std::env::temp_dir().join(format!("hypervisor-tokens-{}.db", uuid::Uuid::new_v4()));
+        let db = crate::db::connect(&format!("sqlite:{}", db_path.display()))
+            .await
+            .expect("db should connect");
+        sqlx::query(
+            "INSERT INTO users (id, username, display_name, created_at) VALUES ('u1', 'alice', 'Alice', 0)",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        let session_store = crate::session_store::SqliteSessionStore::new(db.clone());
+        session_store.migrate().await.unwrap();
+
+        let origin = url::Url::parse("http://localhost").unwrap();
+        let webauthn = webauthn_rs::prelude::WebauthnBuilder::new("localhost", &origin)
+            .unwrap()
+            .build()
+            .unwrap();
+        let state = Arc::new(AppState {
+            db,
+            webauthn: Arc::new(webauthn),
+            sandbox_registry,
+            provider_gateway: crate::state::ProviderGatewayState {
+                token: None,
+                base_url: None,
+                allowed_upstreams: Vec::new(),
+                client: reqwest::Client::new(),
+                rate_limit_per_minute: 60,
+                rate_limit_overrides: Default::default(),
+                rate_limit_state: Arc::new(dashmap::DashMap::new()),
+                inflight_requests: Arc::new(dashmap::DashMap::new()),
+            },
+            proxy_client: crate::proxy::new_pooled_client(),
+            metrics: Default::default(),
+        });
+        (state, session_store, db_path)
+    }
+
+    async fn send(app: &Router, method: &str, token: &str) -> (StatusCode, String) {
+        let req = Request::builder()
+            .method(method)
+            .uri("/api/whoami")
+            .header(header::AUTHORIZATION, format!("Bearer {token}"))
+            .body(Body::empty())
+            .unwrap();
+        let resp = app.clone().oneshot(req).await.unwrap();
+        let status = resp.status();
+        let body = resp.into_body().collect().await.unwrap().to_bytes();
+        (status, String::from_utf8_lossy(&body).to_string())
+    }
+
+    async fn audit_events(state: &AppState) -> Vec<String> {
+        sqlx::query_scalar("SELECT event FROM audit_log ORDER BY id")
+            .fetch_all(&state.db)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn bearer_token_is_attributed_and_scope_limits_mutations() {
+        let (app, state, db_path) = token_test_app().await;
+        let (_, full) = tokens::create_token(&state.db, "u1", "cli", TokenScope::Full, 30)
+            .await
+            .unwrap();
+        let (_, read_only) =
+            tokens::create_token(&state.db, "u1", "dash", TokenScope::ReadOnly, 30)
+                .await
+                .unwrap();
+
+        assert_eq!(
+            send(&app, "GET", &full).await,
+            (StatusCode::OK, "u1".into())
+        );
+        assert_eq!(send(&app, "POST", &full).await.0, StatusCode::OK);
+        assert_eq!(send(&app, "GET", &read_only).await.0, StatusCode::OK);
+        assert_eq!(
+            send(&app, "POST", &read_only).await.0,
+            StatusCode::FORBIDDEN
+        );
+
+        let last = if full.ends_with('0') { '1' } else { '0' };
+        let tampered = format!("{}{last}", &full[..full.len() - 1]);
+        assert_eq!(
+            send(&app, "GET", &tampered).await.0,
+            StatusCode::UNAUTHORIZED
+        );
+
+        assert_eq!(
+            audit_events(&state).await,
+            vec!["token_used", "token_used", "token_used", "token_denied"]
+        );
+        let listed = tokens::list_tokens(&state.db, "u1").await.unwrap();
+        assert!(listed.iter().all(|t| t.last_used_at.is_some()));
+
+        state.db.close().await;
+        let _ = tokio::fs::remove_file(&db_path).await;
+    }
+
+    #[tokio::test]
+    async fn expired_token_is_rejected() {
+        let (app, state, db_path) = token_test_app().await;
+        let (token, secret) = tokens::create_token(&state.db, "u1", "cli", TokenScope::Full, 30)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE api_tokens SET expires_at = ? WHERE id = ?")
+            .bind(chrono::Utc::now().timestamp() - 1)
+            .bind(&token.id)
+            .execute(&state.db)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            send(&app, "GET", &secret).await,
+            (StatusCode::UNAUTHORIZED, "token expired".into())
+        );
+        assert!(audit_events(&state).await.is_empty());
+
+        state.db.close().await;
+        let _ = tokio::fs::remove_file(&db_path).await;
+    }
+
+    #[tokio::test]
+    async fn revoked_token_is_rejected_on_the_next_request() {
+        let (app, state, db_path) = token_test_app().await;
+        let (token, secret) = tokens::create_token(&state.db, "u1", "cli", TokenScope::Full, 30)
+            .await
+            .unwrap();
+        assert_eq!(send(&app, "GET", &secret).await.0, StatusCode::OK);
+
+        assert!(tokens::revoke_token(&state.db, "u1", &token.id)
+            .await
+            .unwrap());
+        assert_eq!(
+            send(&app, "GET", &secret).await,
+            (StatusCode::UNAUTHORIZED, "token revoked".into())
+        );
+        // Revoking an already revoked token is a no-op.
+        assert!(!tokens::revoke_token(&state.db, "u1", &token.id)
+            .await
+            .unwrap());
+
+        state.db.close().await;
+        let _ = tokio::fs::remove_file(&db_path).await;
+    }
+
+    #[tokio::test]
+    async fn proxied_request_is_counted_against_its_sandbox() {
+        // A stand-in sandbox; `true` stands in for runtime-ctl.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind listener");
+        let port = listener.local_addr().expect("local addr").port();
+        let sandbox = Router::new()
+            .route("/health/ready", get(|| async { "ok" }))
+            .route("/api/ping", get(|| async { "pong" }));
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, sandbox).await;
+        });
+
+        let registry = crate::sandbox::SandboxRegistry::new(
+            "true".to_string(),
+            1,
+            2,
+            port,
+            port,
+            std::time::Duration::from_secs(60),
+            None,
+            None,
+            None,
+            None,
+            Default::default(),
+            Default::default(),
+        );
+        let (state, session_store, db_path) = test_state(registry).await;
+        let app = Router::new()
+            .fallback(proxy_to_sandbox)
+            .layer(from_fn_with_state(Arc::clone(&state), require_auth))
+            .layer(SessionManagerLayer::new(session_store))
+            .with_state(Arc::clone(&state));
+        let (_, secret) = tokens::create_token(&state.db, "u1", "cli", TokenScope::Full, 30)
+            .await
+            .unwrap();
+
+        let req = Request::builder()
+            .uri("/api/ping")
+            .header(header::AUTHORIZATION, format!("Bearer {secret}"))
+            .body(Body::empty())
+            .unwrap();
+        let resp = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = resp.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(&body[..], b"pong");
+
+        assert_eq!(state.metrics.proxy_stats("u1/live").requests, 1);
+        assert_eq!(state.metrics.proxy_stats("u1/dev").requests, 0);
+        let text = state
+            .metrics
+            .render_prometheus(&crate::metrics::FleetState {
+                sandboxes: state.sandbox_registry.snapshot().await,
+                restarts: state.sandbox_registry.restart_counts(),
+                active_sessions: 0,
+            });
+        assert!(text.contains("choiros_proxy_requests_total{sandbox=\"u1/live\"} 1"));
+        assert!(text.contains("choiros_sandboxes{status=\"running\"} 1"));
+
+        state.db.close().await;
+        let _ = tokio::fs::remove_file(&db_path).await;
+    }
 
     #[test]
     fn resolve_route_defaults_to_main_pointer() {
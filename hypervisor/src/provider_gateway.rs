@@ -1,16 +1,19 @@
 use std::{
     fs,
+    future::Future,
     sync::Arc,
     time::{Duration, Instant},
 };
 
 use axum::{
-    body::Body,
+    body::{Body, Bytes},
     extract::{Path, Request, State},
     http::{header, HeaderMap, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
 };
+use dashmap::DashMap;
 use http_body_util::BodyExt;
+use tokio::sync::OnceCell;
 use tracing::{error, info, warn};
 
 use crate::{state::ProviderGatewayState, AppState};
@@ -28,6 +31,35 @@ enum UpstreamAuthMode {
     Header(&'static str),
 }
 
+/// A fully read upstream response, cloned out to every coalesced caller.
+#[derive(Debug, Clone)]
+pub struct UpstreamReply {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+/// Upstream outcome; the error is the message returned as 502.
+type UpstreamResult = Result<UpstreamReply, &'static str>;
+
+/// In-flight coalescable requests by [`coalescing_key`].
+pub type InflightRequests = DashMap<String, Arc<OnceCell<UpstreamResult>>>;
+
+/// Request timestamps within the rate limit window by `(user_id, provider)`.
+pub type RateLimitBuckets = DashMap<(String, String), Vec<Instant>>;
+
+/// Unauthenticated liveness check sandboxes poll to detect gateway outages.
+pub async fn health(State(state): State<Arc<AppState>>) -> Response {
+    if state.provider_gateway.token.is_none() {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "provider gateway not configured",
+        )
+            .into_response();
+    }
+    (StatusCode::OK, "ok").into_response()
+}
+
 pub async fn forward_provider_request(
     State(state): State<Arc<AppState>>,
     Path((provider, _rest)): Path<(String, String)>,
@@ -60,18 +92,20 @@ pub async fn forward_provider_request(
             expected_prefix = %expected_prefix,
             "invalid provider gateway token"
         );
+        state
+            .metrics
+            .record_auth_failure(crate::metrics::AuthFailure::GatewayToken);
         return (StatusCode::UNAUTHORIZED, "invalid provider gateway token").into_response();
     }
 
     let context = caller_context_from_headers(req.headers());
 
-    if let Err(response) =
-        enforce_per_sandbox_rate_limit(&state.provider_gateway, &context.sandbox_id).await
-    {
-        return response;
-    }
-
     if provider == "aws-bedrock" {
+        if let Err(response) =
+            enforce_provider_rate_limit(&state.provider_gateway, &context.user_id, &provider).await
+        {
+            return response;
+        }
         return forward_bedrock_request(state, provider, req, context, started_at).await;
     }
 
@@ -102,7 +136,19 @@ pub async fn forward_provider_request(
             .into_response();
     }
 
-    let (provider_api_key, auth_mode) = match provider_key_for_upstream(upstream_base_url) {
+    // Budgets follow the upstream actually called; the `{provider}` path
+    // segment is only a label the caller picks.
+    let Some(upstream_provider) = upstream_provider(upstream_base_url) else {
+        return (StatusCode::FORBIDDEN, "unsupported provider upstream").into_response();
+    };
+    if let Err(response) =
+        enforce_provider_rate_limit(&state.provider_gateway, &context.user_id, upstream_provider)
+            .await
+    {
+        return response;
+    }
+
+    let (provider_api_key, auth_mode) = match provider_key_for_upstream(upstream_provider) {
         Ok(v) => v,
         Err((status, msg)) => return (status, msg).into_response(),
     };
@@ -134,34 +180,36 @@ pub async fn forward_provider_request(
 
     let method = reqwest::Method::from_bytes(parts.method.as_str().as_bytes())
         .unwrap_or(reqwest::Method::POST);
-    let mut upstream_req = state
-        .provider_gateway
-        .client
-        .request(method, &upstream_url)
-        .body(body_bytes.clone());
-    upstream_req = match auth_mode {
-        UpstreamAuthMode::Bearer => upstream_req.bearer_auth(provider_api_key),
-        UpstreamAuthMode::Header(header_name) => upstream_req.header(header_name, provider_api_key),
+    let send = || {
+        send_upstream(
+            &state.provider_gateway.client,
+            &provider,
+            method.clone(),
+            &upstream_url,
+            body_bytes.clone(),
+            &provider_api_key,
+            auth_mode,
+            &parts.headers,
+        )
     };
-    upstream_req = copy_request_headers(upstream_req, &parts.headers);
-
-    let upstream_res = match upstream_req.send().await {
-        Ok(res) => res,
-        Err(e) => {
-            error!(provider = %provider, upstream_url = %upstream_url, error = %e, "provider gateway upstream request failed");
-            return (StatusCode::BAD_GATEWAY, "provider upstream request failed").into_response();
-        }
+    let result = match coalescing_key(&method, &upstream_url, &parts.headers, &body_bytes) {
+        Some(key) => coalesce(&state.provider_gateway.inflight_requests, key, send).await,
+        None => send().await,
     };
-
-    let status = upstream_res.status();
-    let headers = upstream_res.headers().clone();
-    let bytes = match upstream_res.bytes().await {
-        Ok(b) => b,
-        Err(e) => {
-            error!(provider = %provider, upstream_url = %upstream_url, error = %e, "provider gateway failed to read upstream response body");
-            return (StatusCode::BAD_GATEWAY, "invalid upstream response").into_response();
+    let UpstreamReply {
+        status,
+        headers,
+        body: bytes,
+    } = match result {
+        Ok(reply) => reply,
+        Err(message) => {
+            state
+                .metrics
+                .record_gateway_call(&context.user_id, false, 0, 0);
+            return (StatusCode::BAD_GATEWAY, message).into_response();
         }
     };
+    record_gateway_reply(&state, &context, status, &bytes);
 
     if status == StatusCode::UNAUTHORIZED {
         dump_401_debug_artifact(
@@ -192,6 +240,100 @@ pub async fn forward_provider_request(
     response
 }
 
+/// Count an upstream reply, with its token usage, against the caller.
+fn record_gateway_reply(
+    state: &AppState,
+    context: &GatewayCallerContext,
+    status: StatusCode,
+    body: &[u8],
+) {
+    let (input_tokens, output_tokens) = crate::metrics::token_usage(body);
+    state.metrics.record_gateway_call(
+        &context.user_id,
+        status.is_success(),
+        input_tokens,
+        output_tokens,
+    );
+}
+
+/// Send one request upstream and read the whole response.
+#[allow(clippy::too_many_arguments)]
+async fn send_upstream(
+    client: &reqwest::Client,
+    provider: &str,
+    method: reqwest::Method,
+    upstream_url: &str,
+    body: Bytes,
+    provider_api_key: &str,
+    auth_mode: UpstreamAuthMode,
+    headers: &HeaderMap,
+) -> UpstreamResult {
+    let mut upstream_req = client.request(method, upstream_url).body(body);
+    upstream_req = match auth_mode {
+        UpstreamAuthMode::Bearer => upstream_req.bearer_auth(provider_api_key),
+        UpstreamAuthMode::Header(header_name) => upstream_req.header(header_name, provider_api_key),
+    };
+    upstream_req = copy_request_headers(upstream_req, headers);
+
+    let upstream_res = match upstream_req.send().await {
+        Ok(res) => res,
+        Err(e) => {
+            error!(provider = %provider, upstream_url = %upstream_url, error = %e, "provider gateway upstream request failed");
+            return Err("provider upstream request failed");
+        }
+    };
+
+    let status = upstream_res.status();
+    let headers = upstream_res.headers().clone();
+    match upstream_res.bytes().await {
+        Ok(body) => Ok(UpstreamReply {
+            status,
+            headers,
+            body,
+        }),
+        Err(e) => {
+            error!(provider = %provider, upstream_url = %upstream_url, error = %e, "provider gateway failed to read upstream response body");
+            Err("invalid upstream response")
+        }
+    }
+}
+
+/// Key under which concurrent requests may share one upstream call, or
+/// `None` when the request must go upstream on its own. Only bodiless
+/// `GET`/`HEAD` requests qualify; the key covers the forwarded headers so
+/// requests that could get different answers are never merged.
+fn coalescing_key(
+    method: &reqwest::Method,
+    upstream_url: &str,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Option<String> {
+    if !matches!(*method, reqwest::Method::GET | reqwest::Method::HEAD) || !body.is_empty() {
+        return None;
+    }
+    let mut forwarded: Vec<String> = headers
+        .iter()
+        .filter(|(name, _)| is_forwarded_request_header(name))
+        .map(|(name, value)| format!("{}:{}", name, String::from_utf8_lossy(value.as_bytes())))
+        .collect();
+    forwarded.sort();
+    Some(format!("{method} {upstream_url}\n{}", forwarded.join("\n")))
+}
+
+/// Run `fetch` once for all concurrent callers with the same `key`. The
+/// entry is dropped as soon as the call resolves, so nothing is cached
+/// beyond the requests that were already waiting.
+async fn coalesce<F, Fut>(inflight: &InflightRequests, key: String, fetch: F) -> UpstreamResult
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = UpstreamResult>,
+{
+    let cell = inflight.entry(key.clone()).or_default().clone();
+    let result = cell.get_or_init(fetch).await.clone();
+    inflight.remove_if(&key, |_, current| Arc::ptr_eq(current, &cell));
+    result
+}
+
 /// Forward a request to AWS Bedrock. Auth uses a bearer identity token
 /// (`AWS_BEARER_TOKEN_BEDROCK`) rather than SigV4 signing. The AWS SDK
 /// supports bearer tokens natively; we use a plain HTTP forward here to
@@ -265,6 +407,9 @@ async fn forward_bedrock_request(
         Ok(res) => res,
         Err(e) => {
             error!(provider = %provider, upstream_url = %upstream_url, error = %e, "provider gateway upstream request failed");
+            state
+                .metrics
+                .record_gateway_call(&context.user_id, false, 0, 0);
             return (StatusCode::BAD_GATEWAY, "provider upstream request failed").into_response();
         }
     };
@@ -275,9 +420,13 @@ async fn forward_bedrock_request(
         Ok(b) => b,
         Err(e) => {
             error!(provider = %provider, upstream_url = %upstream_url, error = %e, "provider gateway failed to read upstream response body");
+            state
+                .metrics
+                .record_gateway_call(&context.user_id, false, 0, 0);
             return (StatusCode::BAD_GATEWAY, "invalid upstream response").into_response();
         }
     };
+    record_gateway_reply(&state, &context, status, &bytes);
 
     if status == StatusCode::UNAUTHORIZED {
         dump_401_debug_artifact(
@@ -374,32 +523,49 @@ fn extract_provided_token(headers: &HeaderMap) -> &str {
         .unwrap_or_default()
 }
 
-async fn enforce_per_sandbox_rate_limit(
+/// Rolling window the gateway rate limits are counted over.
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Count a request against its `(user_id, provider)` bucket, or refuse it
+/// with 429 and a `Retry-After` of the seconds until the bucket has room.
+async fn enforce_provider_rate_limit(
     state: &ProviderGatewayState,
-    sandbox_id: &str,
+    user_id: &str,
+    provider: &str,
 ) -> Result<(), Response> {
-    let limit = state.rate_limit_per_minute;
+    let limit = state
+        .rate_limit_overrides
+        .get(provider)
+        .copied()
+        .unwrap_or(state.rate_limit_per_minute);
     if limit == 0 {
         return Ok(());
     }
 
-    let window = Duration::from_secs(60);
     let now = Instant::now();
-    // ADR-0022: DashMap entry() for per-sandbox concurrency.
+    // ADR-0022: DashMap entry() for per-bucket concurrency.
     let mut bucket = state
         .rate_limit_state
-        .entry(sandbox_id.to_string())
+        .entry((user_id.to_string(), provider.to_string()))
         .or_default();
 
-    bucket.retain(|stamp| now.duration_since(*stamp) < window);
+    bucket.retain(|stamp| now.duration_since(*stamp) < RATE_LIMIT_WINDOW);
     if bucket.len() >= limit {
+        // Room opens when the request that brings the bucket under the
+        // limit leaves the window.
+        let freeing = bucket[bucket.len() - limit];
+        let wait = RATE_LIMIT_WINDOW.saturating_sub(now.duration_since(freeing));
+        let retry_after_secs = (wait.as_millis().div_ceil(1000) as u64).max(1);
         warn!(
-            sandbox_id = %sandbox_id,
+            user_id = %user_id,
+            provider = %provider,
             limit_per_minute = limit,
+            retry_after_secs,
             "provider gateway rate limit exceeded"
         );
         return Err((
             StatusCode::TOO_MANY_REQUESTS,
+            [(header::RETRY_AFTER, retry_after_secs.to_string())],
             "provider gateway rate limit exceeded",
         )
             .into_response());
@@ -409,6 +575,27 @@ async fn enforce_per_sandbox_rate_limit(
     Ok(())
 }
 
+/// Drop buckets whose requests have all left the window, so callers that
+/// went quiet do not keep an entry forever.
+fn evict_idle_rate_limit_buckets(buckets: &RateLimitBuckets) {
+    let now = Instant::now();
+    buckets.retain(|_, stamps| {
+        stamps
+            .last()
+            .is_some_and(|stamp| now.duration_since(*stamp) < RATE_LIMIT_WINDOW)
+    });
+}
+
+/// Background task: evict idle rate limit buckets once per window.
+pub async fn run_rate_limit_bucket_eviction(buckets: Arc<RateLimitBuckets>) {
+    let mut interval = tokio::time::interval(RATE_LIMIT_WINDOW);
+    interval.tick().await; // first tick is immediate; skip it
+    loop {
+        interval.tick().await;
+        evict_idle_rate_limit_buckets(&buckets);
+    }
+}
+
 fn caller_context_from_headers(headers: &HeaderMap) -> GatewayCallerContext {
     let sandbox_id = headers
         .get("x-choiros-sandbox-id")
@@ -441,43 +628,53 @@ fn caller_context_from_headers(headers: &HeaderMap) -> GatewayCallerContext {
     }
 }
 
+/// Provider name of an upstream base URL, as rate limits and overrides
+/// know it, or `None` for an upstream the gateway holds no key for.
+fn upstream_provider(upstream_base_url: &str) -> Option<&'static str> {
+    [
+        ("api.z.ai", "zai"),
+        ("api.kimi.com", "kimi"),
+        ("api.openai.com", "openai"),
+        ("api.inceptionlabs.ai", "inception"),
+        ("openrouter.ai", "openrouter"),
+        ("api.tavily.com", "tavily"),
+        ("api.search.brave.com", "brave"),
+        ("api.exa.ai", "exa"),
+    ]
+    .into_iter()
+    .find(|(host, _)| upstream_base_url.contains(host))
+    .map(|(_, provider)| provider)
+}
+
 fn provider_key_for_upstream(
-    upstream_base_url: &str,
+    upstream_provider: &str,
 ) -> Result<(String, UpstreamAuthMode), (StatusCode, &'static str)> {
-    let (key_env, credential_name, auth_mode) = if upstream_base_url.contains("api.z.ai") {
-        ("ZAI_API_KEY", "zai_api_key", UpstreamAuthMode::Bearer)
-    } else if upstream_base_url.contains("api.kimi.com") {
-        ("KIMI_API_KEY", "kimi_api_key", UpstreamAuthMode::Bearer)
-    } else if upstream_base_url.contains("api.openai.com") {
-        ("OPENAI_API_KEY", "openai_api_key", UpstreamAuthMode::Bearer)
-    } else if upstream_base_url.contains("api.inceptionlabs.ai") {
-        (
+    let (key_env, credential_name, auth_mode) = match upstream_provider {
+        "zai" => ("ZAI_API_KEY", "zai_api_key", UpstreamAuthMode::Bearer),
+        "kimi" => ("KIMI_API_KEY", "kimi_api_key", UpstreamAuthMode::Bearer),
+        "openai" => ("OPENAI_API_KEY", "openai_api_key", UpstreamAuthMode::Bearer),
+        "inception" => (
             "INCEPTION_API_KEY",
             "inception_api_key",
             UpstreamAuthMode::Bearer,
-        )
-    } else if upstream_base_url.contains("openrouter.ai") {
-        (
+        ),
+        "openrouter" => (
             "OPENROUTER_API_KEY",
             "openrouter_api_key",
             UpstreamAuthMode::Bearer,
-        )
-    } else if upstream_base_url.contains("api.tavily.com") {
-        ("TAVILY_API_KEY", "tavily_api_key", UpstreamAuthMode::Bearer)
-    } else if upstream_base_url.contains("api.search.brave.com") {
-        (
+        ),
+        "tavily" => ("TAVILY_API_KEY", "tavily_api_key", UpstreamAuthMode::Bearer),
+        "brave" => (
             "BRAVE_API_KEY",
             "brave_api_key",
             UpstreamAuthMode::Header("X-Subscription-Token"),
-        )
-    } else if upstream_base_url.contains("api.exa.ai") {
-        (
+        ),
+        "exa" => (
             "EXA_API_KEY",
             "exa_api_key",
             UpstreamAuthMode::Header("x-api-key"),
-        )
-    } else {
-        return Err((StatusCode::FORBIDDEN, "unsupported provider upstream"));
+        ),
+        _ => return Err((StatusCode::FORBIDDEN, "unsupported provider upstream")),
     };
 
     read_secret_env_or_credential(key_env, credential_name)
@@ -514,21 +711,7 @@ fn copy_request_headers(
     headers: &HeaderMap,
 ) -> reqwest::RequestBuilder {
     for (name, value) in headers {
-        if name == header::HOST
-            || name == header::CONTENT_LENGTH
-            || name == header::AUTHORIZATION
-            || name.as_str().eq_ignore_ascii_case("x-api-key")
-            || name.as_str().eq_ignore_ascii_case("api-key")
-            || name == "x-choiros-upstream-base-url"
-            || name.as_str().starts_with("x-choiros-")
-            || name == header::CONNECTION
-            || name.as_str().eq_ignore_ascii_case("proxy-connection")
-            || name.as_str().eq_ignore_ascii_case("keep-alive")
-            || name == header::TE
-            || name == header::TRAILER
-            || name == header::TRANSFER_ENCODING
-            || name == header::UPGRADE
-        {
+        if !is_forwarded_request_header(name) {
             continue;
         }
         request = request.header(name, value);
@@ -536,6 +719,25 @@ fn copy_request_headers(
     request
 }
 
+/// Whether a caller header is passed through upstream. Gateway credentials,
+/// `x-choiros-*` routing headers and hop-by-hop headers are not.
+fn is_forwarded_request_header(name: &header::HeaderName) -> bool {
+    !(name == header::HOST
+        || name == header::CONTENT_LENGTH
+        || name == header::AUTHORIZATION
+        || name.as_str().eq_ignore_ascii_case("x-api-key")
+        || name.as_str().eq_ignore_ascii_case("api-key")
+        || name == "x-choiros-upstream-base-url"
+        || name.as_str().starts_with("x-choiros-")
+        || name == header::CONNECTION
+        || name.as_str().eq_ignore_ascii_case("proxy-connection")
+        || name.as_str().eq_ignore_ascii_case("keep-alive")
+        || name == header::TE
+        || name == header::TRAILER
+        || name == header::TRANSFER_ENCODING
+        || name == header::UPGRADE)
+}
+
 fn copy_response_headers(dest: &mut HeaderMap, src: &HeaderMap) {
     for (name, value) in src {
         if name == header::CONNECTION
@@ -649,28 +851,101 @@ mod tests {
         assert_eq!(result.model, "unknown");
     }
 
-    #[tokio::test]
-    async fn rate_limit_blocks_after_budget() {
-        let state = ProviderGatewayState {
+    fn rate_limited_state(
+        rate_limit_per_minute: usize,
+        overrides: &[(&str, usize)],
+    ) -> ProviderGatewayState {
+        ProviderGatewayState {
             token: None,
             base_url: None,
             allowed_upstreams: Vec::new(),
             client: reqwest::Client::new(),
-            rate_limit_per_minute: 2,
+            rate_limit_per_minute,
+            rate_limit_overrides: overrides
+                .iter()
+                .map(|(provider, limit)| (provider.to_string(), *limit))
+                .collect(),
             rate_limit_state: Arc::new(DashMap::new()),
-        };
+            inflight_requests: Arc::new(DashMap::new()),
+        }
+    }
+
+    #[tokio::test]
+    async fn rate_limit_blocks_after_budget() {
+        let state = rate_limited_state(2, &[]);
 
-        assert!(enforce_per_sandbox_rate_limit(&state, "u1:live")
+        assert!(enforce_provider_rate_limit(&state, "u1", "forward")
             .await
             .is_ok());
-        assert!(enforce_per_sandbox_rate_limit(&state, "u1:live")
+        assert!(enforce_provider_rate_limit(&state, "u1", "forward")
             .await
             .is_ok());
 
-        let third = enforce_per_sandbox_rate_limit(&state, "u1:live").await;
+        let third = enforce_provider_rate_limit(&state, "u1", "forward").await;
         assert!(third.is_err());
         let response = third.expect_err("third call should be rate limited");
         assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        let retry_after: u64 = response.headers()[header::RETRY_AFTER]
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!((1..=60).contains(&retry_after));
+    }
+
+    #[tokio::test]
+    async fn rate_limit_buckets_are_per_provider_and_user() {
+        let state = rate_limited_state(2, &[("search", 1), ("aws-bedrock", 0)]);
+
+        assert!(enforce_provider_rate_limit(&state, "u1", "search")
+            .await
+            .is_ok());
+        let throttled = enforce_provider_rate_limit(&state, "u1", "search")
+            .await
+            .expect_err("search override allows one request");
+        assert_eq!(throttled.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        // Another provider, and the same provider for another user, keep
+        // their own budgets.
+        for _ in 0..2 {
+            assert!(enforce_provider_rate_limit(&state, "u1", "forward")
+                .await
+                .is_ok());
+        }
+        assert!(enforce_provider_rate_limit(&state, "u2", "search")
+            .await
+            .is_ok());
+
+        // An override of 0 lifts the limit for that provider.
+        for _ in 0..5 {
+            assert!(enforce_provider_rate_limit(&state, "u1", "aws-bedrock")
+                .await
+                .is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn idle_rate_limit_buckets_are_evicted() {
+        let state = rate_limited_state(2, &[]);
+        assert!(enforce_provider_rate_limit(&state, "u1", "openai")
+            .await
+            .is_ok());
+        state.rate_limit_state.insert(
+            ("u2".to_string(), "openai".to_string()),
+            vec![Instant::now() - RATE_LIMIT_WINDOW],
+        );
+        state
+            .rate_limit_state
+            .insert(("u3".to_string(), "openai".to_string()), Vec::new());
+
+        evict_idle_rate_limit_buckets(&state.rate_limit_state);
+
+        let remaining: Vec<_> = state
+            .rate_limit_state
+            .iter()
+            .map(|bucket| bucket.key().0.clone())
+            .collect();
+        assert_eq!(remaining, vec!["u1".to_string()]);
     }
 
     #[test]
@@ -713,34 +988,141 @@ mod tests {
         std::env::set_var("INCEPTION_API_KEY", "i-key");
 
         let (tavily, tavily_mode) =
-            provider_key_for_upstream("https://api.tavily.com").expect("tavily key");
+            provider_key_for_upstream(upstream_provider("https://api.tavily.com").unwrap())
+                .expect("tavily key");
         assert_eq!(tavily, "t-key");
         assert_eq!(tavily_mode, UpstreamAuthMode::Bearer);
 
         let (brave, brave_mode) =
-            provider_key_for_upstream("https://api.search.brave.com").expect("brave key");
+            provider_key_for_upstream(upstream_provider("https://api.search.brave.com").unwrap())
+                .expect("brave key");
         assert_eq!(brave, "b-key");
         assert_eq!(brave_mode, UpstreamAuthMode::Header("X-Subscription-Token"));
 
-        let (exa, exa_mode) = provider_key_for_upstream("https://api.exa.ai").expect("exa key");
+        let (exa, exa_mode) =
+            provider_key_for_upstream(upstream_provider("https://api.exa.ai").unwrap())
+                .expect("exa key");
         assert_eq!(exa, "e-key");
         assert_eq!(exa_mode, UpstreamAuthMode::Header("x-api-key"));
 
-        let (inception, inception_mode) =
-            provider_key_for_upstream("https://api.inceptionlabs.ai/v1").expect("inception key");
+        let (inception, inception_mode) = provider_key_for_upstream(
+            upstream_provider("https://api.inceptionlabs.ai/v1").unwrap(),
+        )
+        .expect("inception key");
         assert_eq!(inception, "i-key");
         assert_eq!(inception_mode, UpstreamAuthMode::Bearer);
 
         std::env::set_var("OPENROUTER_API_KEY", "or-key");
         let (openrouter, openrouter_mode) =
-            provider_key_for_upstream("https://openrouter.ai/api/v1").expect("openrouter key");
+            provider_key_for_upstream(upstream_provider("https://openrouter.ai/api/v1").unwrap())
+                .expect("openrouter key");
         assert_eq!(openrouter, "or-key");
         assert_eq!(openrouter_mode, UpstreamAuthMode::Bearer);
 
+        assert_eq!(
+            upstream_provider("https://api.z.ai/api/anthropic"),
+            Some("zai")
+        );
+        assert_eq!(upstream_provider("https://attacker.example"), None);
+
         std::env::remove_var("TAVILY_API_KEY");
         std::env::remove_var("BRAVE_API_KEY");
         std::env::remove_var("EXA_API_KEY");
         std::env::remove_var("INCEPTION_API_KEY");
         std::env::remove_var("OPENROUTER_API_KEY");
     }
+
+    /// Local upstream counting hits; each answer takes long enough for
+    /// concurrent callers to overlap.
+    async fn counting_upstream() -> (String, Arc<std::sync::atomic::AtomicUsize>) {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        let app = axum::Router::new().route(
+            "/v1/models",
+            axum::routing::get(move || {
+                let counter = counter.clone();
+                async move {
+                    let n = counter.fetch_add(1, Ordering::SeqCst) + 1;
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    format!("models #{n}")
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        (format!("http://{addr}/v1/models"), hits)
+    }
+
+    #[tokio::test]
+    async fn concurrent_identical_gets_share_one_upstream_call() {
+        use std::sync::atomic::Ordering;
+
+        let (url, hits) = counting_upstream().await;
+        let client = reqwest::Client::new();
+        let inflight = InflightRequests::new();
+        let headers = HeaderMap::new();
+        let key = coalescing_key(&reqwest::Method::GET, &url, &headers, &[]).expect("coalescable");
+
+        let request = || {
+            coalesce(&inflight, key.clone(), || {
+                send_upstream(
+                    &client,
+                    "test",
+                    reqwest::Method::GET,
+                    &url,
+                    Bytes::new(),
+                    "key",
+                    UpstreamAuthMode::Bearer,
+                    &headers,
+                )
+            })
+        };
+        let replies = futures_util::future::join_all((0..10).map(|_| request())).await;
+
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+        for reply in replies {
+            let reply = reply.expect("upstream reply");
+            assert_eq!(reply.status, StatusCode::OK);
+            assert_eq!(reply.body, "models #1");
+        }
+        assert!(inflight.is_empty());
+
+        // Nothing is cached once the shared call has resolved.
+        let reply = request().await.expect("upstream reply");
+        assert_eq!(reply.body, "models #2");
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn only_bodiless_gets_with_matching_headers_coalesce() {
+        let url = "https://api.openai.com/v1/models";
+        let headers = HeaderMap::new();
+        assert!(coalescing_key(&reqwest::Method::GET, url, &headers, &[]).is_some());
+        assert!(coalescing_key(&reqwest::Method::HEAD, url, &headers, &[]).is_some());
+        assert!(coalescing_key(&reqwest::Method::POST, url, &headers, &[]).is_none());
+        assert!(coalescing_key(&reqwest::Method::GET, url, &headers, b"{}").is_none());
+
+        // Gateway routing headers do not split keys; forwarded headers do.
+        let mut routed = HeaderMap::new();
+        routed.insert("x-choiros-sandbox-id", HeaderValue::from_static("u1:live"));
+        routed.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer tok"),
+        );
+        assert_eq!(
+            coalescing_key(&reqwest::Method::GET, url, &headers, &[]),
+            coalescing_key(&reqwest::Method::GET, url, &routed, &[])
+        );
+        let mut versioned = HeaderMap::new();
+        versioned.insert("anthropic-version", HeaderValue::from_static("2023-06-01"));
+        assert_ne!(
+            coalescing_key(&reqwest::Method::GET, url, &headers, &[]),
+            coalescing_key(&reqwest::Method::GET, url, &versioned, &[])
+        );
+    }
 }
@@ -4,9 +4,11 @@ use axum::{
     http::{header, HeaderValue, StatusCode, Uri},
     response::{IntoResponse, Response},
 };
+use dashmap::DashSet;
 use http_body_util::BodyExt;
 use hyper_util::{client::legacy::Client, rt::TokioExecutor};
-use tracing::{debug, error};
+use std::time::Duration;
+use tracing::{debug, error, info};
 
 /// Shared HTTP client with connection pooling for sandbox proxy requests.
 /// Reuses TCP connections + HTTP/1.1 keep-alive across requests to the same port.
@@ -21,6 +23,114 @@ pub fn new_pooled_client() -> PooledClient {
         .build_http()
 }
 
+/// Sandbox endpoint that answers 200 once the runtime can serve requests.
+pub const SANDBOX_READY_PATH: &str = "/health/ready";
+
+/// Tracks which sandbox ports have passed a readiness probe since their last start.
+///
+/// The first request proxied to a freshly started sandbox probes
+/// `SANDBOX_READY_PATH` for a short window; once it answers, the port is
+/// trusted until the registry resets it on the next start, stop or failure.
+pub struct ReadinessGate {
+    ready: DashSet<u16>,
+    window: Duration,
+    interval: Duration,
+}
+
+impl Default for ReadinessGate {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(3), Duration::from_millis(250))
+    }
+}
+
+impl ReadinessGate {
+    pub fn new(window: Duration, interval: Duration) -> Self {
+        Self {
+            ready: DashSet::new(),
+            window,
+            interval,
+        }
+    }
+
+    /// Forget a port's readiness so the next request probes it again.
+    pub fn reset(&self, port: u16) {
+        self.ready.remove(&port);
+    }
+
+    pub fn is_ready(&self, port: u16) -> bool {
+        self.ready.contains(&port)
+    }
+
+    /// Probe `port` until it reports ready or the window closes.
+    /// Returns the "sandbox starting" page if it never became ready.
+    pub async fn ensure_ready(&self, client: &PooledClient, port: u16) -> Result<(), Response> {
+        if self.is_ready(port) {
+            return Ok(());
+        }
+
+        let deadline = tokio::time::Instant::now() + self.window;
+        loop {
+            if probe_ready(client, port, self.interval.max(Duration::from_millis(500))).await {
+                self.ready.insert(port);
+                info!(port, "sandbox passed readiness probe");
+                return Ok(());
+            }
+            if tokio::time::Instant::now() + self.interval >= deadline {
+                debug!(port, "sandbox not ready yet, serving starting page");
+                return Err(sandbox_starting_response());
+            }
+            tokio::time::sleep(self.interval).await;
+        }
+    }
+}
+
+async fn probe_ready(client: &PooledClient, port: u16, timeout: Duration) -> bool {
+    let req = match hyper::Request::get(format!("http://127.0.0.1:{port}{SANDBOX_READY_PATH}"))
+        .body(Body::empty())
+    {
+        Ok(req) => req,
+        Err(_) => return false,
+    };
+    matches!(
+        tokio::time::timeout(timeout, client.request(req)).await,
+        Ok(Ok(resp)) if resp.status().is_success()
+    )
+}
+
+/// 503 page shown while a sandbox is still starting; reloads itself shortly.
+pub fn sandbox_starting_response() -> Response {
+    const PAGE: &str = r#"<!doctype html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta http-equiv="refresh" content="2">
+<title>Starting your sandbox…</title>
+<style>
+body { margin: 0; min-height: 100vh; display: flex; align-items: center; justify-content: center;
+  background: #0f172a; color: #e5e7eb; font-family: system-ui, sans-serif; }
+p { color: #9ca3af; }
+</style>
+</head>
+<body>
+<main>
+<h1>Starting your sandbox…</h1>
+<p>This page will refresh automatically in a moment.</p>
+</main>
+</body>
+</html>
+"#;
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        [
+            (header::CONTENT_TYPE, "text/html; charset=utf-8"),
+            (header::RETRY_AFTER, "2"),
+            (header::CACHE_CONTROL, "no-store"),
+        ],
+        PAGE,
+    )
+        .into_response()
+}
+
 /// Forward an HTTP request to `target_port`, rewriting the URI.
 /// Uses a connection-pooled client to reuse TCP connections across requests.
 pub async fn proxy_http(client: &PooledClient, req: Request, target_port: u16) -> Response {
@@ -206,3 +316,73 @@ pub async fn proxy_ws_raw(req: Request, target_port: u16, path: String) -> Respo
         Err(_) => StatusCode::BAD_REQUEST.into_response(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::get, Router};
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    async fn spawn_stub_sandbox(ready: Arc<AtomicBool>) -> u16 {
+        let app = Router::new()
+            .route(
+                SANDBOX_READY_PATH,
+                get(move || {
+                    let ready = ready.clone();
+                    async move {
+                        if ready.load(Ordering::SeqCst) {
+                            StatusCode::OK
+                        } else {
+                            StatusCode::SERVICE_UNAVAILABLE
+                        }
+                    }
+                }),
+            )
+            .route("/hello", get(|| async { "hello from sandbox" }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        port
+    }
+
+    async fn body_text(resp: Response) -> String {
+        let bytes = resp.into_body().collect().await.unwrap().to_bytes();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn proxies_only_after_sandbox_reports_ready() {
+        let ready = Arc::new(AtomicBool::new(false));
+        let port = spawn_stub_sandbox(ready.clone()).await;
+        let client = new_pooled_client();
+        let gate = ReadinessGate::new(Duration::from_millis(300), Duration::from_millis(50));
+
+        let resp = gate.ensure_ready(&client, port).await.unwrap_err();
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(resp.headers()[header::RETRY_AFTER], "2");
+        assert!(body_text(resp).await.contains("Starting your sandbox"));
+        assert!(!gate.is_ready(port));
+
+        ready.store(true, Ordering::SeqCst);
+        gate.ensure_ready(&client, port).await.unwrap();
+        assert!(gate.is_ready(port));
+
+        let req = Request::builder()
+            .uri("/hello")
+            .body(Body::empty())
+            .unwrap();
+        let resp = proxy_http(&client, req, port).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(body_text(resp).await, "hello from sandbox");
+
+        // A restart makes the next request probe again.
+        ready.store(false, Ordering::SeqCst);
+        gate.reset(port);
+        assert!(gate.ensure_ready(&client, port).await.is_err());
+    }
+}
@@ -1,4 +1,5 @@
 pub mod systemd;
+pub mod warm_pool;
 
 use std::{
     collections::HashMap,
@@ -10,9 +11,11 @@ use dashmap::{DashMap, DashSet};
 use tokio::{net::TcpStream, process::Command, sync::watch, time::sleep};
 use tracing::{error, info, warn};
 
-use crate::config::MachineClassesConfig;
+use crate::config::{MachineClassesConfig, WarmPoolConfig, WarmPoolRefill};
+use crate::proxy::ReadinessGate;
 
 use self::systemd::SystemdLifecycle;
+use self::warm_pool::{WarmPool, WarmPoolSnapshot, WarmSandbox};
 
 // ── Memory pressure helpers (ADR-0018) ──────────────────────────────────────
 
@@ -100,6 +103,19 @@ fn validate_branch_name(branch: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Secret a user's sandboxes derive their event payload encryption key from:
+/// HMAC-SHA256 of the user id under the hypervisor's master secret, so one
+/// sandbox's secret opens no other user's events.
+fn sandbox_event_encryption_key(master_secret: &str, user_id: &str) -> String {
+    use hmac::Mac;
+
+    let mut mac = hmac::Hmac::<sha2::Sha256>::new_from_slice(master_secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(b"choiros-sandbox-event-key:");
+    mac.update(user_id.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
 /// The role of a sandbox instance.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum SandboxRole {
@@ -154,6 +170,8 @@ pub struct SandboxEntry {
     pub handle: Option<SandboxHandle>,
     /// ADR-0014 Phase 6: machine class this VM was booted with.
     pub machine_class: Option<String>,
+    /// Time to adopt the warm standby this runtime came from, if any.
+    pub adoption_ms: Option<u64>,
 }
 
 pub enum SandboxHandle {
@@ -207,6 +225,9 @@ impl PortAllocator {
     }
 }
 
+/// How long the idle watchdog waits for a sandbox to report its activity.
+const ACTIVITY_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
 /// Per-user sandbox registry (ADR-0022: DashMap for per-user concurrency).
 pub struct SandboxRegistry {
     runtime_ctl: String,
@@ -219,6 +240,11 @@ pub struct SandboxRegistry {
     port_allocator: PortAllocator,
     provider_gateway_base_url: Option<String>,
     provider_gateway_token: Option<String>,
+    /// Master secret per-sandbox event encryption keys are derived from.
+    event_encryption_secret: Option<String>,
+    /// Public origin of the hypervisor; the only CORS origin a spawned
+    /// sandbox allows unless it is configured otherwise.
+    hypervisor_origin: Option<String>,
     /// Configurable hard ceiling for concurrent VMs (ADR-0022).
     max_concurrent_vms: usize,
     /// When set, use systemd unit templates instead of bash runtime-ctl (ADR-0017).
@@ -227,6 +253,14 @@ pub struct SandboxRegistry {
     machine_classes: MachineClassesConfig,
     /// Per-user machine class overrides. user_id -> class name.
     user_class_overrides: DashMap<String, String>,
+    /// Ports that answered the readiness probe since their last (re)start.
+    readiness: ReadinessGate,
+    /// Pre-started standbys adopted on login in place of a cold start.
+    warm_pool: WarmPool,
+    /// Polls each running sandbox's `GET /api/activity`.
+    activity_client: reqwest::Client,
+    /// Starts after a stop, hibernation or failure, per `metrics::sandbox_label`.
+    restarts: DashMap<String, u64>,
 }
 
 impl SandboxRegistry {
@@ -240,7 +274,10 @@ impl SandboxRegistry {
         idle_timeout: Duration,
         provider_gateway_base_url: Option<String>,
         provider_gateway_token: Option<String>,
+        event_encryption_secret: Option<String>,
+        hypervisor_origin: Option<String>,
         machine_classes: MachineClassesConfig,
+        warm_pool: WarmPoolConfig,
     ) -> Arc<Self> {
         let systemd_lifecycle = SystemdLifecycle::from_env();
         if systemd_lifecycle.is_some() {
@@ -278,13 +315,27 @@ impl SandboxRegistry {
             port_allocator,
             provider_gateway_base_url,
             provider_gateway_token,
+            event_encryption_secret,
+            hypervisor_origin,
             max_concurrent_vms,
             systemd_lifecycle,
             machine_classes,
             user_class_overrides: DashMap::new(),
+            readiness: ReadinessGate::default(),
+            warm_pool: WarmPool::new(warm_pool),
+            activity_client: reqwest::Client::builder()
+                .timeout(ACTIVITY_PROBE_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            restarts: DashMap::new(),
         })
     }
 
+    /// Readiness gate the proxy consults before forwarding to a sandbox port.
+    pub fn readiness(&self) -> &ReadinessGate {
+        &self.readiness
+    }
+
     /// Ensure the live sandbox is running at startup.
     /// Called once after hypervisor boot so the VM is ready before the first request.
     pub async fn boot_live_sandbox(self: &Arc<Self>) {
@@ -341,7 +392,6 @@ impl SandboxRegistry {
                     // ADR-0022 Phase 2: trust Running status, no readiness probe.
                     // If the sandbox crashed, proxy returns 502 and marks it Failed.
                     SandboxStatus::Running => {
-                        entry.last_activity = Instant::now();
                         return Ok(entry.port);
                     }
                     // ADR-0022 Phase 1: join existing boot instead of spawning again.
@@ -352,15 +402,25 @@ impl SandboxRegistry {
                     }
                     SandboxStatus::Hibernated => {
                         info!(user_id, %role, "sandbox hibernated; will restore from snapshot");
+                        self.record_restart(user_id, &role.to_string());
                     }
                     SandboxStatus::Stopped | SandboxStatus::Failed => {
                         info!(user_id, %role, status = ?entry.status, "sandbox not running; will re-spawn");
+                        self.record_restart(user_id, &role.to_string());
                     }
                 }
             }
         }
         // DashMap guard dropped — spawn_instance can take a long time.
 
+        // Adopt a warm standby when there is no VM snapshot to restore. The
+        // standby already counts against capacity, so the gate below is skipped.
+        if self.can_adopt(user_id, role) {
+            if let Some(warm) = self.warm_pool.take() {
+                return self.adopt_warm(user_id, role, warm).await;
+            }
+        }
+
         // ADR-0022: capacity gate with dynamic cap.
         let is_existing = self
             .entries
@@ -368,7 +428,7 @@ impl SandboxRegistry {
             .and_then(|u| u.roles.get(&role).map(|_| ()))
             .is_some();
         if !is_existing {
-            let running = count_running_vms(&self.entries);
+            let running = count_running_vms(&self.entries) + self.warm_pool.len();
             let effective_max = self.effective_max_vms();
             if running >= effective_max {
                 return Err(anyhow::anyhow!(
@@ -432,6 +492,7 @@ impl SandboxRegistry {
                             last_activity: Instant::now(),
                             handle: None,
                             machine_class: None,
+                            adoption_ms: None,
                         },
                     );
                 } else {
@@ -445,6 +506,7 @@ impl SandboxRegistry {
                             last_activity: Instant::now(),
                             handle: None,
                             machine_class: None,
+                            adoption_ms: None,
                         },
                     );
                 }
@@ -459,17 +521,14 @@ impl SandboxRegistry {
                         last_activity: Instant::now(),
                         handle: None,
                         machine_class: None,
+                        adoption_ms: None,
                     },
                 );
             }
         }
         // DashMap guard dropped — now spawn (can take seconds).
 
-        let runtime_name = if user_id == "default" || user_id == "public" {
-            role.to_string()
-        } else {
-            format!("u-{}", &user_id[..8.min(user_id.len())])
-        };
+        let runtime_name = Self::role_runtime_name(user_id, role);
         self.spawn_role_boot_task(user_id.to_string(), role, runtime_name, port, tx);
         let mut rx = rx;
         Self::wait_for_boot_result(&mut rx).await
@@ -489,7 +548,6 @@ impl SandboxRegistry {
             if let Some(entry) = user_map.branches.get_mut(branch) {
                 match &entry.status {
                     SandboxStatus::Running => {
-                        entry.last_activity = Instant::now();
                         return Ok(entry.port);
                     }
                     SandboxStatus::Starting(rx) => {
@@ -502,9 +560,11 @@ impl SandboxRegistry {
                             user_id,
                             branch, "branch sandbox hibernated; will restore from snapshot"
                         );
+                        self.record_restart(user_id, &format!("branch:{branch}"));
                     }
                     SandboxStatus::Stopped | SandboxStatus::Failed => {
                         info!(user_id, branch, status = ?entry.status, "branch sandbox not running; will re-spawn");
+                        self.record_restart(user_id, &format!("branch:{branch}"));
                     }
                 }
             }
@@ -549,6 +609,7 @@ impl SandboxRegistry {
                     last_activity: Instant::now(),
                     handle: None,
                     machine_class: None,
+                    adoption_ms: None,
                 },
             );
         }
@@ -580,6 +641,7 @@ impl SandboxRegistry {
         if handle.is_some() {
             self.stop_handle(user_id, None, &mut handle).await;
             if let Some(p) = port {
+                self.readiness.reset(p);
                 self.port_allocator.release(p);
             }
             info!(user_id, %role, "sandbox stopped");
@@ -606,6 +668,7 @@ impl SandboxRegistry {
                 return Err(anyhow::anyhow!("sandbox is not running"));
             }
             entry.status = SandboxStatus::Hibernated;
+            self.readiness.reset(entry.port);
             entry.handle.take()
         };
         // DashMap guard dropped — do async hibernate work.
@@ -631,6 +694,7 @@ impl SandboxRegistry {
         if handle.is_some() {
             self.stop_handle(user_id, Some(branch), &mut handle).await;
             if let Some(p) = port {
+                self.readiness.reset(p);
                 self.port_allocator.release(p);
             }
             info!(user_id, branch, "branch sandbox stopped");
@@ -658,7 +722,8 @@ impl SandboxRegistry {
         Ok(())
     }
 
-    /// Touch the activity timestamp for a running sandbox.
+    /// Touch the activity timestamp for a running sandbox. Used by the
+    /// explicit `/heartbeat`; the sandbox reports everything else itself.
     pub async fn touch_activity(&self, user_id: &str, role: SandboxRole) {
         if let Some(mut user_map) = self.entries.get_mut(user_id) {
             if let Some(entry) = user_map.roles.get_mut(&role) {
@@ -671,10 +736,9 @@ impl SandboxRegistry {
 
     /// Return the port for a running sandbox role, if any.
     pub async fn port_of(&self, user_id: &str, role: SandboxRole) -> Option<u16> {
-        let mut user_map = self.entries.get_mut(user_id)?;
-        let entry = user_map.roles.get_mut(&role)?;
+        let user_map = self.entries.get(user_id)?;
+        let entry = user_map.roles.get(&role)?;
         if matches!(entry.status, SandboxStatus::Running) {
-            entry.last_activity = Instant::now();
             Some(entry.port)
         } else {
             None
@@ -683,10 +747,9 @@ impl SandboxRegistry {
 
     /// Return the port for a running branch sandbox, if any.
     pub async fn branch_port_of(&self, user_id: &str, branch: &str) -> Option<u16> {
-        let mut user_map = self.entries.get_mut(user_id)?;
-        let entry = user_map.branches.get_mut(branch)?;
+        let user_map = self.entries.get(user_id)?;
+        let entry = user_map.branches.get(branch)?;
         if matches!(entry.status, SandboxStatus::Running) {
-            entry.last_activity = Instant::now();
             Some(entry.port)
         } else {
             None
@@ -700,6 +763,7 @@ impl SandboxRegistry {
                 if matches!(entry.status, SandboxStatus::Running) {
                     warn!(user_id, %role, "marking sandbox as failed (proxy 502)");
                     entry.status = SandboxStatus::Failed;
+                    self.readiness.reset(entry.port);
                 }
             }
         }
@@ -720,6 +784,8 @@ impl SandboxRegistry {
                     status: e.status.clone(),
                     idle_secs: e.last_activity.elapsed().as_secs(),
                     machine_class: e.machine_class.clone(),
+                    adoption_ms: e.adoption_ms,
+                    version: None,
                 });
             }
             for e in user_map.branches.values() {
@@ -731,12 +797,37 @@ impl SandboxRegistry {
                     status: e.status.clone(),
                     idle_secs: e.last_activity.elapsed().as_secs(),
                     machine_class: e.machine_class.clone(),
+                    adoption_ms: e.adoption_ms,
+                    version: None,
                 });
             }
         }
         out
     }
 
+    fn record_restart(&self, user_id: &str, runtime: &str) {
+        *self
+            .restarts
+            .entry(crate::metrics::sandbox_label(user_id, runtime))
+            .or_default() += 1;
+    }
+
+    /// Restarts per sandbox label, for the fleet metrics.
+    pub fn restart_counts(&self) -> Vec<(String, u64)> {
+        let mut counts: Vec<(String, u64)> = self
+            .restarts
+            .iter()
+            .map(|entry| (entry.key().clone(), *entry.value()))
+            .collect();
+        counts.sort();
+        counts
+    }
+
+    /// Warm pool occupancy and adoption latency for the admin sandbox list.
+    pub fn warm_pool_snapshot(&self) -> WarmPoolSnapshot {
+        self.warm_pool.snapshot()
+    }
+
     /// ADR-0018: Compute effective idle timeout based on memory pressure.
     /// More aggressive hibernation as available memory shrinks.
     fn effective_idle_timeout(&self) -> Duration {
@@ -765,31 +856,22 @@ impl SandboxRegistry {
                 );
             }
 
-            // Phase 1: Collect candidates under brief per-shard read locks.
-            // (user_id, is_branch, branch_name_or_empty, idle_duration)
-            let mut candidates: Vec<(String, bool, String, Duration)> = Vec::new();
-
-            for entry in self.entries.iter() {
-                let user_id = entry.key().clone();
-                let user_map = entry.value();
-                for (role, sandbox) in &user_map.roles {
-                    if matches!(sandbox.status, SandboxStatus::Running) {
-                        let idle = sandbox.last_activity.elapsed();
-                        if mem_pct < 15 || idle >= timeout {
-                            candidates.push((user_id.clone(), false, role.to_string(), idle));
-                        }
-                    }
-                }
-                for (branch, sandbox) in &user_map.branches {
-                    if matches!(sandbox.status, SandboxStatus::Running) {
-                        let idle = sandbox.last_activity.elapsed();
-                        if mem_pct < 15 || idle >= timeout {
-                            candidates.push((user_id.clone(), true, branch.clone(), idle));
-                        }
-                    }
-                }
+            // Top the warm pool up, unless memory is already being reclaimed.
+            if mem_pct > 30 {
+                self.fill_warm_pool();
             }
-            // All DashMap guards dropped here.
+
+            // Phase 0: Before judging a sandbox idle, pull its own
+            // event-sourced activity (`session.activity`).
+            let probe_after = if mem_pct < 15 {
+                Duration::ZERO
+            } else {
+                timeout
+            };
+            self.sync_reported_activity(probe_after).await;
+
+            // Phase 1: Collect candidates under brief per-shard read locks.
+            let mut candidates = self.idle_candidates(timeout, mem_pct);
 
             if candidates.is_empty() {
                 continue;
@@ -847,6 +929,149 @@ impl SandboxRegistry {
         }
     }
 
+    /// Running sandboxes idle locally for at least `timeout`, as
+    /// (user_id, is_branch, role_or_branch, port).
+    fn idle_sandbox_ports(&self, timeout: Duration) -> Vec<(String, bool, String, u16)> {
+        let mut idle = Vec::new();
+        for entry in self.entries.iter() {
+            let user_id = entry.key();
+            let user_map = entry.value();
+            let roles = user_map
+                .roles
+                .iter()
+                .map(|(role, sandbox)| (false, role.to_string(), sandbox));
+            let branches = user_map
+                .branches
+                .iter()
+                .map(|(branch, sandbox)| (true, branch.clone(), sandbox));
+            for (is_branch, key, sandbox) in roles.chain(branches) {
+                if matches!(sandbox.status, SandboxStatus::Running)
+                    && sandbox.last_activity.elapsed() >= timeout
+                {
+                    idle.push((user_id.clone(), is_branch, key, sandbox.port));
+                }
+            }
+        }
+        idle
+    }
+
+    /// Sandboxes to hibernate as (user_id, is_branch, role_or_branch, idle).
+    /// At critical memory pressure (< 15% available) every running sandbox
+    /// is a candidate.
+    fn idle_candidates(
+        &self,
+        timeout: Duration,
+        mem_pct: u64,
+    ) -> Vec<(String, bool, String, Duration)> {
+        let mut candidates = Vec::new();
+        for entry in self.entries.iter() {
+            let user_id = entry.key();
+            let user_map = entry.value();
+            let roles = user_map
+                .roles
+                .iter()
+                .map(|(role, sandbox)| (false, role.to_string(), sandbox));
+            let branches = user_map
+                .branches
+                .iter()
+                .map(|(branch, sandbox)| (true, branch.clone(), sandbox));
+            for (is_branch, key, sandbox) in roles.chain(branches) {
+                if matches!(sandbox.status, SandboxStatus::Running) {
+                    let idle = sandbox.last_activity.elapsed();
+                    if mem_pct < 15 || idle >= timeout {
+                        candidates.push((user_id.clone(), is_branch, key, idle));
+                    }
+                }
+            }
+        }
+        candidates
+    }
+
+    /// Ask each sandbox that looks idle after `timeout` how long it has
+    /// really been idle and record the answers. Sandboxes that do not answer
+    /// keep their local timestamp.
+    async fn sync_reported_activity(&self, timeout: Duration) {
+        let idle = self.idle_sandbox_ports(timeout);
+        let reports = futures_util::future::join_all(idle.into_iter().map(
+            |(user_id, is_branch, key, port)| async move {
+                let idle = self.fetch_reported_idle(port).await;
+                (user_id, is_branch, key, idle)
+            },
+        ))
+        .await;
+        for (user_id, is_branch, key, idle) in reports {
+            if let Some(idle) = idle {
+                self.record_reported_activity(&user_id, is_branch, &key, idle);
+            }
+        }
+    }
+
+    async fn fetch_reported_idle(&self, port: u16) -> Option<Duration> {
+        let response = self
+            .activity_client
+            .get(format!("http://127.0.0.1:{port}/api/activity"))
+            .send()
+            .await
+            .ok()?;
+        if !response.status().is_success() {
+            return None;
+        }
+        let body = response.bytes().await.ok()?;
+        let activity: shared_types::ActivityResponse = serde_json::from_slice(&body).ok()?;
+        activity.idle_secs.map(Duration::from_secs)
+    }
+
+    /// Build a running sandbox reports, if it answers in time.
+    pub async fn fetch_reported_version(&self, port: u16) -> Option<shared_types::BuildVersion> {
+        let response = self
+            .activity_client
+            .get(format!("http://127.0.0.1:{port}/api/version"))
+            .send()
+            .await
+            .ok()?;
+        if !response.status().is_success() {
+            return None;
+        }
+        let body = response.bytes().await.ok()?;
+        serde_json::from_slice(&body).ok()
+    }
+
+    /// Move a running sandbox's activity timestamp up to the activity it
+    /// reported `idle` ago. Older reports never push the timestamp back, so
+    /// a fresh boot or heartbeat still counts.
+    pub fn record_reported_activity(
+        &self,
+        user_id: &str,
+        is_branch: bool,
+        key: &str,
+        idle: Duration,
+    ) {
+        let Some(mut user_map) = self.entries.get_mut(user_id) else {
+            return;
+        };
+        let entry = if is_branch {
+            user_map.branches.get_mut(key)
+        } else {
+            let role = if key == "live" {
+                SandboxRole::Live
+            } else {
+                SandboxRole::Dev
+            };
+            user_map.roles.get_mut(&role)
+        };
+        let Some(entry) = entry else {
+            return;
+        };
+        if !matches!(entry.status, SandboxStatus::Running) {
+            return;
+        }
+        if let Some(reported) = Instant::now().checked_sub(idle) {
+            if reported > entry.last_activity {
+                entry.last_activity = reported;
+            }
+        }
+    }
+
     async fn spawn_instance(
         &self,
         user_id: &str,
@@ -884,6 +1109,7 @@ impl SandboxRegistry {
                 .await
             {
                 Ok(handle) => {
+                    registry.readiness.reset(port);
                     {
                         let mut user_map = registry.entries.entry(user_id.clone()).or_default();
                         user_map.roles.insert(
@@ -896,6 +1122,7 @@ impl SandboxRegistry {
                                 last_activity: Instant::now(),
                                 handle: Some(handle),
                                 machine_class: None,
+                                adoption_ms: None,
                             },
                         );
                     }
@@ -935,6 +1162,7 @@ impl SandboxRegistry {
                 .await
             {
                 Ok(handle) => {
+                    registry.readiness.reset(port);
                     {
                         let mut user_map = registry.entries.entry(user_id.clone()).or_default();
                         user_map.branches.insert(
@@ -947,6 +1175,7 @@ impl SandboxRegistry {
                                 last_activity: Instant::now(),
                                 handle: Some(handle),
                                 machine_class: None,
+                                adoption_ms: None,
                             },
                         );
                     }
@@ -969,6 +1198,242 @@ impl SandboxRegistry {
         });
     }
 
+    fn role_runtime_name(user_id: &str, role: SandboxRole) -> String {
+        if user_id == "default" || user_id == "public" {
+            role.to_string()
+        } else {
+            format!("u-{}", &user_id[..8.min(user_id.len())])
+        }
+    }
+
+    // ── Warm pool ─────────────────────────────────────────────────────────
+
+    /// Boot standbys until the warm pool reaches its target size.
+    /// Each standby runs under a placeholder user with empty data.
+    pub fn fill_warm_pool(self: &Arc<Self>) {
+        if self.runtime_ctl.trim().is_empty() && self.systemd_lifecycle.is_none() {
+            return;
+        }
+        while let Some(runtime_name) = self.warm_pool.begin_fill() {
+            // The slot just reserved is already part of `warm_pool.len()`.
+            let running = count_running_vms(&self.entries) + self.warm_pool.len();
+            if running > self.effective_max_vms() {
+                self.warm_pool.finish_fill(None);
+                break;
+            }
+            let Some(port) = self.port_allocator.reserve() else {
+                self.warm_pool.finish_fill(None);
+                break;
+            };
+
+            let registry = Arc::clone(self);
+            tokio::spawn(async move {
+                match registry
+                    .spawn_instance(&runtime_name, &runtime_name, None, None, port)
+                    .await
+                {
+                    Ok(SandboxHandle::RuntimeCtl(handle)) => {
+                        info!(runtime = runtime_name, port, "warm standby ready");
+                        registry.warm_pool.finish_fill(Some(WarmSandbox {
+                            handle,
+                            warmed_at: Instant::now(),
+                        }));
+                    }
+                    Err(e) => {
+                        registry.port_allocator.release(port);
+                        registry.warm_pool.finish_fill(None);
+                        warn!(
+                            runtime = runtime_name,
+                            port, "warm standby failed to start: {e}"
+                        );
+                    }
+                }
+            });
+        }
+    }
+
+    /// Only users with nothing to restore and no machine class override take
+    /// a standby; standbys boot with the host default class.
+    fn can_adopt(&self, user_id: &str, role: SandboxRole) -> bool {
+        if role != SandboxRole::Live || user_id == "default" || user_id == "public" {
+            return false;
+        }
+        if self.user_class_overrides.contains_key(user_id) {
+            return false;
+        }
+        !self
+            .entries
+            .get(user_id)
+            .and_then(|u| {
+                u.roles
+                    .get(&role)
+                    .map(|e| matches!(e.status, SandboxStatus::Hibernated))
+            })
+            .unwrap_or(false)
+    }
+
+    /// Point a standby at the user's data and register it under their role.
+    /// Falls back to a cold start on the user's own runtime if adoption fails.
+    async fn adopt_warm(
+        self: &Arc<Self>,
+        user_id: &str,
+        role: SandboxRole,
+        warm: WarmSandbox,
+    ) -> anyhow::Result<u16> {
+        let port = warm.handle.port;
+        let (tx, rx) = watch::channel(Err("booting".to_string()));
+        let previous = {
+            let mut user_map = self.entries.entry(user_id.to_string()).or_default();
+            user_map.roles.insert(
+                role,
+                SandboxEntry {
+                    role: Some(role),
+                    branch: None,
+                    port,
+                    status: SandboxStatus::Starting(rx.clone()),
+                    last_activity: Instant::now(),
+                    handle: None,
+                    machine_class: None,
+                    adoption_ms: None,
+                },
+            )
+        };
+        // A runtime the proxy marked failed still holds its handle and port.
+        if let Some(mut previous) = previous {
+            if previous.handle.is_some() {
+                self.stop_handle(user_id, None, &mut previous.handle).await;
+                self.readiness.reset(previous.port);
+                if previous.port != port {
+                    self.port_allocator.release(previous.port);
+                }
+            }
+        }
+        if self.warm_pool.refill_policy() == WarmPoolRefill::Immediate {
+            self.fill_warm_pool();
+        }
+
+        let registry = Arc::clone(self);
+        let user_id = user_id.to_string();
+        tokio::spawn(async move {
+            let started = Instant::now();
+            let runtime_name = warm.handle.runtime_name.clone();
+            match registry.adopt_runtime(&user_id, role, &warm.handle).await {
+                Ok(()) => {
+                    let elapsed = started.elapsed();
+                    registry.warm_pool.record_adoption(elapsed);
+                    // Traffic waits on the readiness probe as after any start.
+                    registry.readiness.reset(port);
+                    {
+                        let mut user_map = registry.entries.entry(user_id.clone()).or_default();
+                        user_map.roles.insert(
+                            role,
+                            SandboxEntry {
+                                role: Some(role),
+                                branch: None,
+                                port,
+                                status: SandboxStatus::Running,
+                                last_activity: Instant::now(),
+                                handle: Some(SandboxHandle::RuntimeCtl(RuntimeCtlHandle {
+                                    user_id: user_id.clone(),
+                                    runtime_name: runtime_name.clone(),
+                                    role: Some(role),
+                                    branch: None,
+                                    port,
+                                })),
+                                machine_class: None,
+                                adoption_ms: Some(elapsed.as_millis() as u64),
+                            },
+                        );
+                    }
+                    let _ = tx.send(Ok(port));
+                    info!(
+                        user_id,
+                        %role,
+                        port,
+                        runtime = runtime_name,
+                        warmed_secs = warm.warmed_at.elapsed().as_secs(),
+                        adoption_ms = elapsed.as_millis() as u64,
+                        "warm standby adopted"
+                    );
+                }
+                Err(e) => {
+                    warn!(user_id, %role, runtime = runtime_name, "warm adoption failed, cold starting: {e}");
+                    registry.warm_pool.record_adoption_failure();
+                    let placeholder = warm.handle.user_id.clone();
+                    registry
+                        .stop_handle(
+                            &placeholder,
+                            None,
+                            &mut Some(SandboxHandle::RuntimeCtl(warm.handle)),
+                        )
+                        .await;
+                    registry.readiness.reset(port);
+                    registry.port_allocator.release(port);
+
+                    let Some(cold_port) = registry.port_allocator.reserve() else {
+                        let message = "no available ports for cold start".to_string();
+                        if let Some(mut user_map) = registry.entries.get_mut(&user_id) {
+                            if let Some(entry) = user_map.roles.get_mut(&role) {
+                                entry.status = SandboxStatus::Failed;
+                            }
+                        }
+                        let _ = tx.send(Err(message));
+                        return;
+                    };
+                    if let Some(mut user_map) = registry.entries.get_mut(&user_id) {
+                        if let Some(entry) = user_map.roles.get_mut(&role) {
+                            entry.port = cold_port;
+                        }
+                    }
+                    let runtime_name = Self::role_runtime_name(&user_id, role);
+                    registry.spawn_role_boot_task(user_id, role, runtime_name, cold_port, tx);
+                }
+            }
+        });
+        let mut rx = rx;
+        Self::wait_for_boot_result(&mut rx).await
+    }
+
+    /// Restart a standby under the user's identity. The runtime name and port
+    /// are kept so the runner binary and its page cache are reused.
+    async fn adopt_runtime(
+        &self,
+        user_id: &str,
+        role: SandboxRole,
+        warm: &RuntimeCtlHandle,
+    ) -> anyhow::Result<()> {
+        if let Some(lifecycle) = &self.systemd_lifecycle {
+            lifecycle
+                .adopt(
+                    &warm.runtime_name,
+                    &warm.user_id,
+                    user_id,
+                    self.provider_gateway_token.as_deref(),
+                )
+                .await?;
+        } else {
+            self.run_runtime_ctl(
+                "stop",
+                &warm.user_id,
+                &warm.runtime_name,
+                None,
+                None,
+                warm.port,
+            )
+            .await?;
+            self.run_runtime_ctl(
+                "ensure",
+                user_id,
+                &warm.runtime_name,
+                Some(role),
+                None,
+                warm.port,
+            )
+            .await?;
+        }
+        Self::wait_for_runtime_port(&warm.runtime_name, warm.port).await
+    }
+
     fn runtime_ctl_args(
         action: &str,
         user_id: &str,
@@ -1034,6 +1499,15 @@ impl SandboxRegistry {
         if let Some(token) = self.provider_gateway_token.as_ref() {
             cmd.env("CHOIR_PROVIDER_GATEWAY_TOKEN", token);
         }
+        if let Some(secret) = self.event_encryption_secret.as_deref() {
+            cmd.env(
+                "CHOIR_EVENT_ENCRYPTION_KEY",
+                sandbox_event_encryption_key(secret, user_id),
+            );
+        }
+        if let Some(origin) = self.hypervisor_origin.as_ref() {
+            cmd.env("CHOIR_HYPERVISOR_ORIGIN", origin);
+        }
         if let Ok(frontend_dist) = std::env::var("FRONTEND_DIST") {
             cmd.env("FRONTEND_DIST", frontend_dist);
         }
@@ -1152,7 +1626,19 @@ impl SandboxRegistry {
                 .await?;
         }
 
-        // Poll for host-facing runtime readiness after runtime control succeeds.
+        Self::wait_for_runtime_port(runtime_name, port).await?;
+
+        Ok(SandboxHandle::RuntimeCtl(RuntimeCtlHandle {
+            user_id: user_id.to_string(),
+            runtime_name: runtime_name.to_string(),
+            role,
+            branch: branch.map(ToString::to_string),
+            port,
+        }))
+    }
+
+    /// Poll for host-facing runtime readiness after runtime control succeeds.
+    async fn wait_for_runtime_port(runtime_name: &str, port: u16) -> anyhow::Result<()> {
         let deadline = tokio::time::Instant::now() + Duration::from_secs(45);
         loop {
             if tokio::time::Instant::now() >= deadline {
@@ -1169,18 +1655,10 @@ impl SandboxRegistry {
                     runtime = runtime_name,
                     port, "sandbox runtime port is ready"
                 );
-                break;
+                return Ok(());
             }
             sleep(Duration::from_millis(200)).await;
         }
-
-        Ok(SandboxHandle::RuntimeCtl(RuntimeCtlHandle {
-            user_id: user_id.to_string(),
-            runtime_name: runtime_name.to_string(),
-            role,
-            branch: branch.map(ToString::to_string),
-            port,
-        }))
     }
 
     async fn stop_handle(
@@ -1275,7 +1753,161 @@ impl SandboxRegistry {
 
 #[cfg(test)]
 mod tests {
-    use super::{validate_branch_name, SandboxRegistry, SandboxRole};
+    use std::time::{Duration, Instant};
+
+    use super::{
+        sandbox_event_encryption_key, validate_branch_name, SandboxEntry, SandboxRegistry,
+        SandboxRole, SandboxStatus,
+    };
+    use crate::config::{WarmPoolConfig, WarmPoolRefill};
+
+    fn registry_on_port(port: u16, warm_pool: WarmPoolConfig) -> std::sync::Arc<SandboxRegistry> {
+        SandboxRegistry::new(
+            "true".to_string(),
+            1,
+            2,
+            port,
+            port,
+            Duration::from_secs(60),
+            None,
+            None,
+            None,
+            None,
+            Default::default(),
+            warm_pool,
+        )
+    }
+
+    #[tokio::test]
+    async fn login_adopts_warm_standby_and_records_latency() {
+        // `true` stands in for runtime-ctl; the listener answers the port probe.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind listener");
+        let port = listener.local_addr().expect("local addr").port();
+        let registry = registry_on_port(
+            port,
+            WarmPoolConfig {
+                size: 1,
+                refill: WarmPoolRefill::Watchdog,
+            },
+        );
+
+        registry.fill_warm_pool();
+        for _ in 0..50 {
+            if registry.warm_pool_snapshot().ready == 1 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(registry.warm_pool_snapshot().ports, vec![port]);
+
+        let adopted = registry
+            .ensure_running("user-1234abcd", SandboxRole::Live)
+            .await
+            .expect("adoption succeeds");
+        assert_eq!(adopted, port);
+        assert!(!registry.readiness().is_ready(port));
+
+        let snapshots = registry.snapshot().await;
+        let entry = snapshots
+            .iter()
+            .find(|s| s.user_id == "user-1234abcd")
+            .expect("adopted entry");
+        assert_eq!(entry.status, SandboxStatus::Running);
+        assert!(entry.adoption_ms.is_some());
+
+        let pool = registry.warm_pool_snapshot();
+        assert_eq!(pool.ready, 0);
+        assert_eq!(pool.adoptions, 1);
+        assert_eq!(pool.cold_starts, 0);
+        assert!(pool.last_adoption_ms.is_some());
+    }
+
+    #[tokio::test]
+    async fn empty_warm_pool_falls_back_to_cold_start() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind listener");
+        let port = listener.local_addr().expect("local addr").port();
+        let registry = registry_on_port(
+            port,
+            WarmPoolConfig {
+                size: 1,
+                refill: WarmPoolRefill::Watchdog,
+            },
+        );
+
+        let started = registry
+            .ensure_running("user-5678efgh", SandboxRole::Live)
+            .await
+            .expect("cold start succeeds");
+        assert_eq!(started, port);
+
+        let snapshots = registry.snapshot().await;
+        assert_eq!(snapshots[0].adoption_ms, None);
+        let pool = registry.warm_pool_snapshot();
+        assert_eq!(pool.adoptions, 0);
+        assert_eq!(pool.cold_starts, 1);
+    }
+
+    #[tokio::test]
+    async fn idle_watchdog_respects_reported_activity() {
+        let registry = registry_on_port(
+            3,
+            WarmPoolConfig {
+                size: 0,
+                refill: WarmPoolRefill::Watchdog,
+            },
+        );
+        let timeout = Duration::from_secs(60);
+        let Some(stale) = Instant::now().checked_sub(Duration::from_secs(120)) else {
+            return;
+        };
+        registry
+            .entries
+            .entry("user-a".to_string())
+            .or_default()
+            .roles
+            .insert(
+                SandboxRole::Live,
+                SandboxEntry {
+                    role: Some(SandboxRole::Live),
+                    branch: None,
+                    port: 3,
+                    status: SandboxStatus::Running,
+                    last_activity: stale,
+                    handle: None,
+                    machine_class: None,
+                    adoption_ms: None,
+                },
+            );
+
+        let candidates = registry.idle_candidates(timeout, 100);
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].2, "live");
+
+        // Activity older than the local timestamp changes nothing.
+        registry.record_reported_activity("user-a", false, "live", Duration::from_secs(600));
+        assert_eq!(registry.idle_candidates(timeout, 100).len(), 1);
+
+        registry.record_reported_activity("user-a", false, "live", Duration::from_secs(5));
+        assert!(registry.idle_candidates(timeout, 100).is_empty());
+        let snapshots = registry.snapshot().await;
+        assert!(snapshots[0].idle_secs < 60);
+
+        // Critical memory pressure hibernates regardless of activity.
+        assert_eq!(registry.idle_candidates(timeout, 10).len(), 1);
+    }
+
+    #[test]
+    fn event_encryption_keys_are_stable_per_user() {
+        let key = sandbox_event_encryption_key("master", "user-a");
+        assert_eq!(key, sandbox_event_encryption_key("master", "user-a"));
+        assert_eq!(key.len(), 64);
+        assert_ne!(key, sandbox_event_encryption_key("master", "user-b"));
+        assert_ne!(key, sandbox_event_encryption_key("other", "user-a"));
+    }
 
     #[test]
     fn validates_branch_names() {
@@ -1344,6 +1976,10 @@ pub struct SandboxSnapshot {
     pub status: SandboxStatus,
     pub idle_secs: u64,
     pub machine_class: Option<String>,
+    pub adoption_ms: Option<u64>,
+    /// Build the sandbox reports from `GET /api/version`; filled in by the
+    /// admin list for running sandboxes that answer.
+    pub version: Option<shared_types::BuildVersion>,
 }
 
 impl serde::Serialize for SandboxRole {
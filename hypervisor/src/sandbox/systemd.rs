@@ -510,6 +510,48 @@ impl SystemdLifecycle {
         Ok(())
     }
 
+    /// Hand a warm standby to a user: relink its data image to the user's
+    /// subvolume and restart the VM in place, keeping the instance, port and
+    /// the host's warm page cache for the runner.
+    pub async fn adopt(
+        &self,
+        instance: &str,
+        placeholder_user: &str,
+        user_id: &str,
+        gateway_token: Option<&str>,
+    ) -> anyhow::Result<()> {
+        self.link_user_data_img(user_id, instance).await?;
+        if let Some(token) = gateway_token {
+            tokio::fs::write(
+                self.instance_state_dir(instance).join("gateway-token"),
+                token,
+            )
+            .await?;
+        }
+        // A standby never hibernates, but never restore someone else's memory image.
+        let snap = self.vm_snapshot_dir(instance);
+        if snap.exists() {
+            let _ = tokio::fs::remove_dir_all(&snap).await;
+        }
+        tokio::fs::write(self.boot_mode_path(instance), "cold").await?;
+
+        let template = self.read_instance_template(instance).await;
+        systemctl_restart(&format!("{template}@{instance}")).await?;
+
+        // The placeholder subvolume only ever held an empty data.img.
+        let placeholder_dir = self.user_data_dir.join(placeholder_user);
+        if placeholder_dir.exists() {
+            let _ = Command::new("btrfs")
+                .args(["subvolume", "delete"])
+                .arg(&placeholder_dir)
+                .status()
+                .await;
+        }
+
+        info!(instance, user_id, template, "warm standby adopted");
+        Ok(())
+    }
+
     /// Check if the VM is currently running.
     #[allow(dead_code)]
     pub async fn is_active(&self, instance: &str) -> bool {
@@ -568,6 +610,20 @@ async fn systemctl_start(unit: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
+async fn systemctl_restart(unit: &str) -> anyhow::Result<()> {
+    info!(unit, "systemctl restart");
+    let output = Command::new("systemctl")
+        .args(["restart", unit])
+        .output()
+        .await?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        error!(unit, stderr = %stderr, "systemctl restart failed");
+        anyhow::bail!("systemctl restart {unit} failed: {stderr}");
+    }
+    Ok(())
+}
+
 async fn systemctl_stop(unit: &str) {
     info!(unit, "systemctl stop");
     let result = Command::new("systemctl")
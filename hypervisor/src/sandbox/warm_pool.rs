@@ -0,0 +1,235 @@
+//! Warm standby sandboxes.
+//!
+//! The registry keeps up to `size` generic runtimes booted against empty
+//! placeholder data. A login with no running sandbox adopts one, pointing it
+//! at the user's data and restarting it in place, which skips most of the
+//! cold-start cost; an empty pool falls back to a normal cold start.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::config::{WarmPoolConfig, WarmPoolRefill};
+
+use super::RuntimeCtlHandle;
+
+/// Prefix shared by standby runtime names and their placeholder user ids.
+pub const WARM_RUNTIME_PREFIX: &str = "warm-";
+
+/// A booted standby waiting to be adopted.
+pub struct WarmSandbox {
+    pub handle: RuntimeCtlHandle,
+    pub warmed_at: Instant,
+}
+
+/// Standby queue plus adoption counters surfaced on the admin sandbox list.
+pub struct WarmPool {
+    config: WarmPoolConfig,
+    ready: Mutex<VecDeque<WarmSandbox>>,
+    /// Standbys currently booting.
+    filling: AtomicUsize,
+    next_id: AtomicU64,
+    adoptions: AtomicU64,
+    cold_starts: AtomicU64,
+    adoption_ms_total: AtomicU64,
+    adoption_ms_last: AtomicU64,
+    adoption_ms_max: AtomicU64,
+}
+
+impl WarmPool {
+    pub fn new(config: WarmPoolConfig) -> Self {
+        Self {
+            config,
+            ready: Mutex::new(VecDeque::new()),
+            filling: AtomicUsize::new(0),
+            next_id: AtomicU64::new(0),
+            adoptions: AtomicU64::new(0),
+            cold_starts: AtomicU64::new(0),
+            adoption_ms_total: AtomicU64::new(0),
+            adoption_ms_last: AtomicU64::new(0),
+            adoption_ms_max: AtomicU64::new(0),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.config.size > 0
+    }
+
+    pub fn refill_policy(&self) -> WarmPoolRefill {
+        self.config.refill
+    }
+
+    /// Standbys that are booted or booting; both count against VM capacity.
+    pub fn len(&self) -> usize {
+        self.ready_len() + self.filling.load(Ordering::SeqCst)
+    }
+
+    fn ready_len(&self) -> usize {
+        self.ready.lock().expect("warm pool lock poisoned").len()
+    }
+
+    /// Reserve a boot slot if the pool is below its target size.
+    /// Returns a fresh runtime name; the caller must `finish_fill` it.
+    pub fn begin_fill(&self) -> Option<String> {
+        let ready = self.ready_len();
+        self.filling
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |filling| {
+                (ready + filling < self.config.size).then_some(filling + 1)
+            })
+            .ok()?;
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        Some(format!("{WARM_RUNTIME_PREFIX}{id}"))
+    }
+
+    /// Release a boot slot, queueing the standby if it came up.
+    pub fn finish_fill(&self, warmed: Option<WarmSandbox>) {
+        if let Some(warm) = warmed {
+            self.ready
+                .lock()
+                .expect("warm pool lock poisoned")
+                .push_back(warm);
+        }
+        self.filling.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    /// Take the longest-warmed standby, counting a cold start when empty.
+    pub fn take(&self) -> Option<WarmSandbox> {
+        if !self.enabled() {
+            return None;
+        }
+        let warm = self
+            .ready
+            .lock()
+            .expect("warm pool lock poisoned")
+            .pop_front();
+        if warm.is_none() {
+            self.cold_starts.fetch_add(1, Ordering::SeqCst);
+        }
+        warm
+    }
+
+    /// Record how long an adoption took, from take to port ready.
+    pub fn record_adoption(&self, elapsed: Duration) {
+        let ms = elapsed.as_millis() as u64;
+        self.adoptions.fetch_add(1, Ordering::SeqCst);
+        self.adoption_ms_total.fetch_add(ms, Ordering::SeqCst);
+        self.adoption_ms_last.store(ms, Ordering::SeqCst);
+        self.adoption_ms_max.fetch_max(ms, Ordering::SeqCst);
+    }
+
+    /// Count a failed adoption as the cold start that replaces it.
+    pub fn record_adoption_failure(&self) {
+        self.cold_starts.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn snapshot(&self) -> WarmPoolSnapshot {
+        let (ready, ports) = {
+            let ready = self.ready.lock().expect("warm pool lock poisoned");
+            (ready.len(), ready.iter().map(|w| w.handle.port).collect())
+        };
+        let adoptions = self.adoptions.load(Ordering::SeqCst);
+        let recorded = |ms: u64| (adoptions > 0).then_some(ms);
+        WarmPoolSnapshot {
+            size: self.config.size,
+            refill: self.config.refill.to_string(),
+            ready,
+            filling: self.filling.load(Ordering::SeqCst),
+            ports,
+            adoptions,
+            cold_starts: self.cold_starts.load(Ordering::SeqCst),
+            last_adoption_ms: recorded(self.adoption_ms_last.load(Ordering::SeqCst)),
+            avg_adoption_ms: recorded(
+                self.adoption_ms_total.load(Ordering::SeqCst) / adoptions.max(1),
+            ),
+            max_adoption_ms: recorded(self.adoption_ms_max.load(Ordering::SeqCst)),
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct WarmPoolSnapshot {
+    pub size: usize,
+    pub refill: String,
+    pub ready: usize,
+    pub filling: usize,
+    pub ports: Vec<u16>,
+    pub adoptions: u64,
+    /// Logins that found the pool empty or whose adoption failed.
+    pub cold_starts: u64,
+    pub last_adoption_ms: Option<u64>,
+    pub avg_adoption_ms: Option<u64>,
+    pub max_adoption_ms: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{WarmPool, WarmSandbox};
+    use crate::config::{WarmPoolConfig, WarmPoolRefill};
+    use crate::sandbox::RuntimeCtlHandle;
+
+    fn warm(runtime_name: String, port: u16) -> WarmSandbox {
+        WarmSandbox {
+            handle: RuntimeCtlHandle {
+                user_id: runtime_name.clone(),
+                runtime_name,
+                role: None,
+                branch: None,
+                port,
+            },
+            warmed_at: Instant::now(),
+        }
+    }
+
+    #[test]
+    fn fill_slots_stop_at_target_size() {
+        let pool = WarmPool::new(WarmPoolConfig {
+            size: 2,
+            refill: WarmPoolRefill::Immediate,
+        });
+        let first = pool.begin_fill().expect("first slot");
+        let second = pool.begin_fill().expect("second slot");
+        assert_ne!(first, second);
+        assert!(pool.begin_fill().is_none());
+
+        pool.finish_fill(Some(warm(first, 12000)));
+        pool.finish_fill(None);
+        assert_eq!(pool.len(), 1);
+        assert!(pool.begin_fill().is_some());
+    }
+
+    #[test]
+    fn take_counts_cold_starts_and_adoption_latency() {
+        let pool = WarmPool::new(WarmPoolConfig {
+            size: 1,
+            refill: WarmPoolRefill::Watchdog,
+        });
+        assert!(pool.take().is_none());
+
+        let name = pool.begin_fill().expect("slot");
+        pool.finish_fill(Some(warm(name, 12000)));
+        let adopted = pool.take().expect("standby");
+        assert_eq!(adopted.handle.port, 12000);
+        pool.record_adoption(Duration::from_millis(40));
+        pool.record_adoption(Duration::from_millis(20));
+
+        let snapshot = pool.snapshot();
+        assert_eq!(snapshot.cold_starts, 1);
+        assert_eq!(snapshot.adoptions, 2);
+        assert_eq!(snapshot.last_adoption_ms, Some(20));
+        assert_eq!(snapshot.avg_adoption_ms, Some(30));
+        assert_eq!(snapshot.max_adoption_ms, Some(40));
+        assert_eq!(snapshot.refill, "watchdog");
+    }
+
+    #[test]
+    fn disabled_pool_never_hands_out_standbys() {
+        let pool = WarmPool::new(WarmPoolConfig::default());
+        assert!(!pool.enabled());
+        assert!(pool.begin_fill().is_none());
+        assert!(pool.take().is_none());
+        assert_eq!(pool.snapshot().cold_starts, 0);
+    }
+}
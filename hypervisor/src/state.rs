@@ -1,7 +1,6 @@
+use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Instant;
 
-use dashmap::DashMap;
 use sqlx::SqlitePool;
 use webauthn_rs::prelude::Webauthn;
 
@@ -13,9 +12,18 @@ pub struct ProviderGatewayState {
     pub base_url: Option<String>,
     pub allowed_upstreams: Vec<String>,
     pub client: reqwest::Client,
+    /// Requests each `(user_id, provider)` bucket may make per rolling
+    /// minute; 0 disables the limit.
     pub rate_limit_per_minute: usize,
-    /// ADR-0022: DashMap for per-sandbox rate limit concurrency.
-    pub rate_limit_state: Arc<DashMap<String, Vec<Instant>>>,
+    /// Budgets that replace `rate_limit_per_minute` for a provider, keyed by
+    /// the upstream provider name (`openai`, `tavily`, `aws-bedrock`, ...).
+    pub rate_limit_overrides: HashMap<String, usize>,
+    /// ADR-0022: DashMap for per-bucket rate limit concurrency, keyed by
+    /// `(user_id, provider)`.
+    pub rate_limit_state: Arc<crate::provider_gateway::RateLimitBuckets>,
+    /// In-flight idempotent upstream requests, so identical concurrent
+    /// requests share one upstream call.
+    pub inflight_requests: Arc<crate::provider_gateway::InflightRequests>,
 }
 
 pub struct AppState {
@@ -25,4 +33,6 @@ pub struct AppState {
     pub provider_gateway: ProviderGatewayState,
     /// ADR-0022 Phase 5: connection-pooled HTTP client for sandbox proxy.
     pub proxy_client: crate::proxy::PooledClient,
+    /// Fleet counters served at `/admin/metrics` and `/admin/dashboard`.
+    pub metrics: crate::metrics::Metrics,
 }
@@ -0,0 +1,11 @@
+-- Per-projection cursor tracking for the ProjectionManager.
+-- cursor_seq is the highest event seq the projection has applied.
+-- status is 'active' during normal catch-up and 'rebuilding' while a
+-- rebuild is in flight, so an interrupted rebuild can resume from cursor_seq.
+
+CREATE TABLE IF NOT EXISTS projection_cursors (
+    name TEXT PRIMARY KEY NOT NULL,
+    cursor_seq INTEGER NOT NULL DEFAULT 0,
+    status TEXT NOT NULL DEFAULT 'active',
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
@@ -0,0 +1,13 @@
+-- Full-text search read model over chat messages, document versions and
+-- report artifacts. Maintained by the "search" projection; rowid is the seq
+-- of the source event, so replays overwrite instead of duplicating and a
+-- redaction removes exactly the entry its event produced.
+
+CREATE VIRTUAL TABLE IF NOT EXISTS search_index USING fts5(
+    title,
+    content,
+    kind UNINDEXED,
+    target UNINDEXED,
+    created_at UNINDEXED,
+    tokenize = 'porter unicode61'
+);
@@ -0,0 +1,8 @@
+-- Field-level payload encryption (see actors/event_encryption.rs).
+-- NULL for plaintext rows; otherwise the id of the key the row's sealed
+-- fields were encrypted with, so key rotation can find rows to re-seal.
+
+ALTER TABLE events ADD COLUMN encryption_key_id TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_events_encryption_key ON events(encryption_key_id)
+    WHERE encryption_key_id IS NOT NULL;
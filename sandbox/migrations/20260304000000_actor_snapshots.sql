@@ -0,0 +1,10 @@
+-- Latest state snapshot per actor, written best-effort while the actor runs.
+-- seq is the highest event seq reflected in the state, so a respawned actor
+-- loads the snapshot and replays only events after it.
+
+CREATE TABLE IF NOT EXISTS actor_snapshots (
+    actor_id TEXT PRIMARY KEY NOT NULL,
+    seq INTEGER NOT NULL,
+    state TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
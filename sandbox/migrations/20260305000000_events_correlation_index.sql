@@ -0,0 +1,12 @@
+-- Index the payload fields a causal chain is looked up by (see
+-- EventStoreMsg::QueryByCorrelation). Supervisor events carry both
+-- `correlation_id` and `trace_id`; some producers set only one. The indexed
+-- expressions must match the query's text exactly for SQLite to use them.
+
+CREATE INDEX IF NOT EXISTS idx_events_correlation_id
+    ON events(json_extract(payload, '$.correlation_id'))
+    WHERE json_extract(payload, '$.correlation_id') IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_events_trace_id
+    ON events(json_extract(payload, '$.trace_id'))
+    WHERE json_extract(payload, '$.trace_id') IS NOT NULL;
@@ -0,0 +1,8 @@
+-- Client-supplied idempotency key (see AppendEvent::idempotency_key). A
+-- retried append carrying a key already stored returns the original event
+-- instead of writing a duplicate. NULL for appends without a key.
+
+ALTER TABLE events ADD COLUMN idempotency_key TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_events_idempotency_key ON events(idempotency_key)
+    WHERE idempotency_key IS NOT NULL;
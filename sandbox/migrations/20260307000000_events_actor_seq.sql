@@ -0,0 +1,17 @@
+-- Per-actor sequence number, assigned at append time alongside the global
+-- seq. An actor's events are numbered 1, 2, 3, ..., so a consumer following
+-- one actor can tell from a gap that it missed events.
+--
+-- Existing rows are numbered from insertion order.
+
+ALTER TABLE events ADD COLUMN actor_seq INTEGER NOT NULL DEFAULT 0;
+
+UPDATE events
+SET actor_seq = numbered.actor_seq
+FROM (
+    SELECT seq, ROW_NUMBER() OVER (PARTITION BY actor_id ORDER BY seq) AS actor_seq
+    FROM events
+) AS numbered
+WHERE events.seq = numbered.seq;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_events_actor_id_actor_seq ON events(actor_id, actor_seq);
@@ -0,0 +1,12 @@
+-- Full-text index over every event, for debugging runs without reading the
+-- database by hand. rowid is the event's seq. Written by the event store on
+-- append (see EventStoreMsg::Search); stores spawned without event search
+-- leave it untouched, and existing events are indexed on the next start with
+-- it enabled.
+
+CREATE VIRTUAL TABLE IF NOT EXISTS event_search USING fts5(
+    event_type,
+    actor_id,
+    content,
+    tokenize = 'unicode61'
+);
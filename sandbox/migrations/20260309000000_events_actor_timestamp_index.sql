@@ -0,0 +1,4 @@
+-- Time-range queries for one actor (see EventStoreMsg::QueryByTime). Ranges
+-- across all actors use idx_events_timestamp.
+
+CREATE INDEX IF NOT EXISTS idx_events_actor_id_timestamp ON events(actor_id, timestamp);
@@ -0,0 +1,15 @@
+-- High-water actor_seq per actor. Appends take the next number from here
+-- rather than from the rows still stored, so compaction removing an actor's
+-- newest events never lets a number be handed out twice.
+--
+-- compacted_through is the highest actor_seq compaction has removed for the
+-- actor. A gap at or below it is retention, not a lost event.
+
+CREATE TABLE IF NOT EXISTS actor_seq_counters (
+    actor_id TEXT PRIMARY KEY NOT NULL,
+    last_actor_seq INTEGER NOT NULL,
+    compacted_through INTEGER NOT NULL DEFAULT 0
+);
+
+INSERT INTO actor_seq_counters (actor_id, last_actor_seq)
+SELECT actor_id, MAX(actor_seq) FROM events GROUP BY actor_id;
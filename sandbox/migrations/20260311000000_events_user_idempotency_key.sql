@@ -0,0 +1,8 @@
+-- Idempotency keys are chosen by clients, so they are only unique per user:
+-- one user's key must never return another user's event.
+
+DROP INDEX IF EXISTS idx_events_idempotency_key;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_events_user_idempotency_key
+    ON events(user_id, idempotency_key)
+    WHERE idempotency_key IS NOT NULL;
@@ -0,0 +1,209 @@
+//! Per-session activity tracking.
+//!
+//! Inbound WebSocket messages and conductor delegations count as activity.
+//! The latest activity per session is kept in memory and recorded as
+//! `session.activity` events, at most once per `PERSIST_INTERVAL` per
+//! session, so a restarted sandbox rebuilds the same view from its log.
+//! `GET /api/activity` serves it to the hypervisor idle watchdog, which
+//! hibernates the sandbox once every session has been idle long enough.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Duration, Utc};
+use shared_types::{ActivityResponse, ActivitySource, SessionActivity};
+
+/// Actor id `session.activity` events are recorded under.
+pub const ACTIVITY_ACTOR_ID: &str = "session_activity";
+
+/// Minimum gap between two persisted activity events for one session.
+pub const PERSIST_INTERVAL: Duration = Duration::seconds(60);
+
+/// How far back the log is replayed on startup. Older activity cannot keep a
+/// sandbox awake under any idle timeout the hypervisor uses.
+pub const RESTORE_WINDOW: Duration = Duration::hours(24);
+
+struct SessionEntry {
+    activity: SessionActivity,
+    persisted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Default)]
+pub struct ActivityTracker {
+    sessions: Mutex<HashMap<String, SessionEntry>>,
+}
+
+impl ActivityTracker {
+    /// Replay `session.activity` events in seq order. Activity already seen
+    /// is only replaced by newer activity.
+    pub fn replay(&self, events: &[shared_types::Event]) {
+        let mut sessions = self.sessions.lock().expect("activity lock poisoned");
+        for event in events {
+            if event.event_type != shared_types::EVENT_TOPIC_SESSION_ACTIVITY {
+                continue;
+            }
+            let Ok(activity) = serde_json::from_value::<SessionActivity>(event.payload.clone())
+            else {
+                continue;
+            };
+            let newer = sessions
+                .get(&activity.session_id)
+                .map_or(true, |entry| entry.activity.at < activity.at);
+            if newer {
+                sessions.insert(
+                    activity.session_id.clone(),
+                    SessionEntry {
+                        persisted_at: Some(activity.at),
+                        activity,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Note activity in `session_id` at `at`. Returns the activity to persist
+    /// when the session's last persisted event is older than
+    /// `PERSIST_INTERVAL`.
+    pub fn record(
+        &self,
+        session_id: &str,
+        source: ActivitySource,
+        at: DateTime<Utc>,
+    ) -> Option<SessionActivity> {
+        let mut sessions = self.sessions.lock().expect("activity lock poisoned");
+        let entry = sessions
+            .entry(session_id.to_string())
+            .or_insert_with(|| SessionEntry {
+                activity: SessionActivity {
+                    session_id: session_id.to_string(),
+                    source,
+                    at,
+                },
+                persisted_at: None,
+            });
+        if at >= entry.activity.at {
+            entry.activity.source = source;
+            entry.activity.at = at;
+        }
+        let due = entry
+            .persisted_at
+            .map_or(true, |persisted_at| at - persisted_at >= PERSIST_INTERVAL);
+        if due {
+            entry.persisted_at = Some(at);
+            Some(entry.activity.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Latest activity across all sessions.
+    pub fn last_activity_at(&self) -> Option<DateTime<Utc>> {
+        let sessions = self.sessions.lock().expect("activity lock poisoned");
+        sessions.values().map(|entry| entry.activity.at).max()
+    }
+
+    pub fn snapshot(&self, now: DateTime<Utc>) -> ActivityResponse {
+        let mut sessions: Vec<SessionActivity> = self
+            .sessions
+            .lock()
+            .expect("activity lock poisoned")
+            .values()
+            .map(|entry| entry.activity.clone())
+            .collect();
+        sessions.sort_by_key(|activity| std::cmp::Reverse(activity.at));
+        let last_activity_at = sessions.first().map(|activity| activity.at);
+        ActivityResponse {
+            last_activity_at,
+            idle_secs: last_activity_at.map(|at| (now - at).num_seconds().max(0) as u64),
+            sessions,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activity_event(seq: i64, activity: &SessionActivity) -> shared_types::Event {
+        shared_types::Event {
+            seq,
+            event_id: format!("evt-{seq}"),
+            timestamp: activity.at,
+            stored_at: activity.at,
+            produced_at: None,
+            event_type: shared_types::EVENT_TOPIC_SESSION_ACTIVITY.to_string(),
+            payload: serde_json::to_value(activity).unwrap(),
+            actor_id: shared_types::ActorId(ACTIVITY_ACTOR_ID.to_string()),
+            actor_seq: seq,
+            user_id: "system".to_string(),
+        }
+    }
+
+    #[test]
+    fn activity_updates_timestamp_and_throttles_persistence() {
+        let tracker = ActivityTracker::default();
+        let start = Utc::now() - Duration::minutes(10);
+        assert_eq!(tracker.last_activity_at(), None);
+
+        let persisted = tracker.record("desktop-1", ActivitySource::WebSocket, start);
+        assert_eq!(persisted.map(|a| a.at), Some(start));
+
+        let soon = start + Duration::seconds(5);
+        assert!(tracker
+            .record("desktop-1", ActivitySource::Delegation, soon)
+            .is_none());
+        assert_eq!(tracker.last_activity_at(), Some(soon));
+
+        let later = start + PERSIST_INTERVAL;
+        let persisted = tracker
+            .record("desktop-1", ActivitySource::WebSocket, later)
+            .expect("persisted after the interval");
+        assert_eq!(persisted.at, later);
+
+        let snapshot = tracker.snapshot(later + Duration::seconds(30));
+        assert_eq!(snapshot.last_activity_at, Some(later));
+        assert_eq!(snapshot.idle_secs, Some(30));
+    }
+
+    #[test]
+    fn replay_keeps_latest_activity_per_session() {
+        let at = Utc::now() - Duration::minutes(5);
+        let events = [
+            SessionActivity {
+                session_id: "desktop-1".to_string(),
+                source: ActivitySource::WebSocket,
+                at,
+            },
+            SessionActivity {
+                session_id: "desktop-2".to_string(),
+                source: ActivitySource::Delegation,
+                at: at + Duration::minutes(2),
+            },
+            SessionActivity {
+                session_id: "desktop-1".to_string(),
+                source: ActivitySource::WebSocket,
+                at: at - Duration::minutes(1),
+            },
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, activity)| activity_event(i as i64 + 1, activity))
+        .collect::<Vec<_>>();
+
+        let tracker = ActivityTracker::default();
+        tracker.replay(&events);
+        let snapshot = tracker.snapshot(at + Duration::minutes(3));
+        assert_eq!(snapshot.sessions.len(), 2);
+        assert_eq!(snapshot.sessions[0].session_id, "desktop-2");
+        assert_eq!(snapshot.sessions[1].at, at);
+        assert_eq!(snapshot.idle_secs, Some(60));
+        // Replayed activity is already persisted.
+        assert!(tracker
+            .record(
+                "desktop-2",
+                ActivitySource::WebSocket,
+                at + Duration::minutes(2) + Duration::seconds(1)
+            )
+            .is_none());
+    }
+}
@@ -38,6 +38,7 @@ use chrono::{Duration, Utc};
 use regex::Regex;
 use tracing::{debug, info, warn};
 
+use super::ObjectiveStatus;
 use crate::actors::model_config::ModelRegistry;
 use crate::baml_client::types::{
     AlmTurn, AlmTurnContext, ContextSourceKind, DagStep, NextActionKind, StepOp,
@@ -50,6 +51,9 @@ use crate::baml_client::{new_collector, ClientRegistry, B};
 #[derive(Debug, Clone)]
 pub struct AlmRunResult {
     pub final_working_memory: String,
+    /// `Complete` only when the model completed; blocks and exhausted
+    /// budgets are `Blocked`.
+    pub objective_status: ObjectiveStatus,
     pub completion_reason: String,
     pub turns_taken: usize,
     pub tool_executions: Vec<AlmToolExecution>,
@@ -104,6 +108,8 @@ pub struct AlmTurnLog {
 /// Configuration for the ALM harness.
 #[derive(Debug, Clone)]
 pub struct AlmConfig {
+    /// Turn budget. A run still going after this many turns is checkpointed
+    /// and finalized as `Blocked` with [`TURN_BUDGET_EXHAUSTED`].
     pub max_turns: usize,
     pub max_recurse_depth: usize,
     pub timeout_budget_ms: u64,
@@ -122,6 +128,9 @@ impl Default for AlmConfig {
     }
 }
 
+/// Completion reason of a run finalized by the turn budget.
+pub const TURN_BUDGET_EXHAUSTED: &str = "turn budget exhausted";
+
 /// Result of executing a single LLM call within a DAG.
 #[derive(Debug, Clone)]
 pub struct LlmCallResult {
@@ -217,6 +226,16 @@ pub trait AlmPort: Send + Sync {
     ///
     /// Implementations must be non-blocking — spawn the actor and return.
     async fn spawn_harness(&self, objective: &str, context: serde_json::Value, corr_id: &str);
+
+    /// Answer a turn without calling `AlmCompose`. Ports that script the
+    /// model (evals, tests) return `Some`; the default defers to the model.
+    async fn compose_turn(
+        &self,
+        _turn_ctx: &AlmTurnContext,
+        _capabilities: &str,
+    ) -> Option<Result<AlmTurn, String>> {
+        None
+    }
 }
 
 // ─── DAG Executor ────────────────────────────────────────────────────────────
@@ -653,11 +672,8 @@ impl<P: AlmPort> AlmHarness<P> {
     }
 
     async fn run_inner(&self, objective: String) -> Result<AlmRunResult, String> {
-        let model_id = self.port.model_id();
-        let client_registry = self
-            .model_registry
-            .create_runtime_client_registry_for_model(model_id)
-            .map_err(|e| format!("model registry: {e}"))?;
+        // Built on the first turn the port does not answer itself.
+        let mut client_registry: Option<ClientRegistry> = None;
 
         let capabilities = self.port.capabilities_description();
         let mut working_memory: Option<String> = None;
@@ -671,6 +687,7 @@ impl<P: AlmPort> AlmHarness<P> {
         let mut pending_replies: Vec<shared_types::PendingReply> = Vec::new();
         let mut pending_corr_ids: Vec<String> = Vec::new();
         let mut turn_summaries: Vec<shared_types::TurnSummary> = Vec::new();
+        let mut last_checkpoint_turn: Option<usize> = None;
         // Recurse/FanOut depth tracking — counts subharness dispatches fired.
         // Once this reaches max_recurse_depth no further recursive dispatches
         // are allowed; the action is converted to a Block result instead.
@@ -691,6 +708,7 @@ impl<P: AlmPort> AlmHarness<P> {
                 );
                 return Ok(AlmRunResult {
                     final_working_memory: working_memory.unwrap_or_default(),
+                    objective_status: ObjectiveStatus::Blocked,
                     completion_reason: format!(
                         "timeout: exceeded {}ms budget after {} turns",
                         self.config.timeout_budget_ms,
@@ -727,9 +745,21 @@ impl<P: AlmPort> AlmHarness<P> {
                 turn_history_summary: history_summary,
             };
 
-            let rlm_turn = self
-                .call_compose(&client_registry, &turn_ctx, &capabilities)
-                .await?;
+            let rlm_turn = match self.port.compose_turn(&turn_ctx, &capabilities).await {
+                Some(turn) => turn?,
+                None => {
+                    if client_registry.is_none() {
+                        client_registry = Some(
+                            self.model_registry
+                                .create_runtime_client_registry_for_model(self.port.model_id())
+                                .map_err(|e| format!("model registry: {e}"))?,
+                        );
+                    }
+                    let registry = client_registry.as_ref().expect("registry initialized");
+                    self.call_compose(registry, &turn_ctx, &capabilities)
+                        .await?
+                }
+            };
 
             // 2. Record working memory
             working_memory = Some(rlm_turn.working_memory.clone());
@@ -778,6 +808,7 @@ impl<P: AlmPort> AlmHarness<P> {
 
                     return Ok(AlmRunResult {
                         final_working_memory: rlm_turn.working_memory,
+                        objective_status: ObjectiveStatus::Complete,
                         completion_reason: reason,
                         turns_taken: turn,
                         tool_executions: all_tool_executions,
@@ -803,6 +834,7 @@ impl<P: AlmPort> AlmHarness<P> {
 
                     return Ok(AlmRunResult {
                         final_working_memory: rlm_turn.working_memory,
+                        objective_status: ObjectiveStatus::Blocked,
                         completion_reason: format!("BLOCKED: {reason}"),
                         turns_taken: turn,
                         tool_executions: all_tool_executions,
@@ -1007,6 +1039,7 @@ impl<P: AlmPort> AlmHarness<P> {
                     checkpointed_at: Utc::now(),
                 };
                 self.port.write_checkpoint(&checkpoint).await;
+                last_checkpoint_turn = Some(turn);
             }
 
             turn_log.push(AlmTurnLog {
@@ -1023,10 +1056,31 @@ impl<P: AlmPort> AlmHarness<P> {
             });
         }
 
-        // Budget exhausted
+        // Turn budget exhausted: checkpoint the final state so recovery sees
+        // where the run stopped, then finalize instead of looping on.
+        let final_working_memory = working_memory.unwrap_or_default();
+        if last_checkpoint_turn != Some(self.config.max_turns) {
+            let checkpoint = shared_types::HarnessCheckpoint {
+                run_id: self.port.run_id().to_string(),
+                actor_id: self.port.actor_id().to_string(),
+                turn_number: self.config.max_turns,
+                working_memory: final_working_memory.clone(),
+                objective: objective.clone(),
+                pending_replies,
+                turn_summaries,
+                checkpointed_at: Utc::now(),
+            };
+            self.port.write_checkpoint(&checkpoint).await;
+        }
+        tracing::warn!(
+            run_id = %self.port.run_id(),
+            max_turns = self.config.max_turns,
+            "ALM harness turn budget exhausted"
+        );
         Ok(AlmRunResult {
-            final_working_memory: working_memory.unwrap_or_default(),
-            completion_reason: format!("budget exhausted after {} turns", self.config.max_turns),
+            final_working_memory,
+            objective_status: ObjectiveStatus::Blocked,
+            completion_reason: TURN_BUDGET_EXHAUSTED.to_string(),
             turns_taken: self.config.max_turns,
             tool_executions: all_tool_executions,
             turn_log,
@@ -1295,4 +1349,127 @@ mod tests {
             .unwrap_err()
             .contains("unknown step"));
     }
+
+    /// A port whose scripted model never completes, recording checkpoints.
+    #[derive(Default)]
+    struct EndlessPort {
+        turns_composed: std::sync::Mutex<usize>,
+        checkpoints: std::sync::Mutex<Vec<shared_types::HarnessCheckpoint>>,
+    }
+
+    #[async_trait::async_trait]
+    impl AlmPort for EndlessPort {
+        fn capabilities_description(&self) -> String {
+            String::new()
+        }
+        fn model_id(&self) -> &str {
+            "scripted"
+        }
+        fn run_id(&self) -> &str {
+            "run-endless"
+        }
+        fn actor_id(&self) -> &str {
+            "alm:endless"
+        }
+        async fn resolve_source(
+            &self,
+            _kind: &ContextSourceKind,
+            _source_ref: &str,
+            _max_tokens: Option<i64>,
+        ) -> Option<String> {
+            None
+        }
+        async fn dispatch_tool(
+            &self,
+            _tool_name: &str,
+            _tool_args: &HashMap<String, String>,
+            _corr_id: &str,
+        ) {
+        }
+        async fn execute_tool(
+            &self,
+            tool_name: &str,
+            tool_args: &HashMap<String, String>,
+        ) -> AlmToolExecution {
+            AlmToolExecution {
+                turn: 0,
+                tool_name: tool_name.to_string(),
+                tool_args: tool_args.clone(),
+                success: true,
+                output: String::new(),
+                error: None,
+                elapsed_ms: 0,
+            }
+        }
+        async fn call_llm(
+            &self,
+            _prompt: &str,
+            _system_prompt: Option<&str>,
+            _model_hint: Option<&str>,
+        ) -> LlmCallResult {
+            LlmCallResult {
+                output: String::new(),
+                success: true,
+                error: None,
+                elapsed_ms: 0,
+            }
+        }
+        async fn emit_message(&self, _message: &str) {}
+        async fn write_checkpoint(&self, checkpoint: &shared_types::HarnessCheckpoint) {
+            self.checkpoints.lock().unwrap().push(checkpoint.clone());
+        }
+        async fn spawn_harness(
+            &self,
+            _objective: &str,
+            _context: serde_json::Value,
+            _corr_id: &str,
+        ) {
+        }
+        async fn compose_turn(
+            &self,
+            turn_ctx: &AlmTurnContext,
+            _capabilities: &str,
+        ) -> Option<Result<AlmTurn, String>> {
+            *self.turns_composed.lock().unwrap() += 1;
+            Some(Ok(AlmTurn {
+                sources: vec![],
+                working_memory: format!("still working at turn {}", turn_ctx.turn_number),
+                next_action: crate::baml_client::types::NextAction {
+                    kind: NextActionKind::ToolCalls,
+                    reason: None,
+                    tool_calls: Some(vec![]),
+                    program: None,
+                    branches: None,
+                    recurse: None,
+                },
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_turn_budget_checkpoints_and_finalizes_blocked() {
+        let harness = AlmHarness::new(
+            EndlessPort::default(),
+            ModelRegistry::new(),
+            AlmConfig {
+                max_turns: 3,
+                ..AlmConfig::default()
+            },
+        );
+
+        let result = harness.run("never finishes".to_string()).await.unwrap();
+
+        assert_eq!(result.objective_status, ObjectiveStatus::Blocked);
+        assert_eq!(result.completion_reason, TURN_BUDGET_EXHAUSTED);
+        assert_eq!(result.turns_taken, 3);
+        assert_eq!(result.final_working_memory, "still working at turn 3");
+        assert_eq!(*harness.port.turns_composed.lock().unwrap(), 3);
+
+        let checkpoints = harness.port.checkpoints.lock().unwrap();
+        assert_eq!(checkpoints.len(), 1);
+        assert_eq!(checkpoints[0].turn_number, 3);
+        assert_eq!(checkpoints[0].run_id, "run-endless");
+        assert_eq!(checkpoints[0].working_memory, "still working at turn 3");
+        assert_eq!(checkpoints[0].turn_summaries.len(), 3);
+    }
 }
@@ -101,6 +101,8 @@ impl ActorAlmPort {
             writer_actor: None,
             run_id: Some(self.run_id.clone()),
             call_id: Some(corr_id.to_string()),
+            dry_run: false,
+            approved_plan: None,
         });
     }
 }
@@ -175,12 +177,15 @@ ContextSourceKind::ToolOutput with that corr_id on the next turn to read the res
                 // Poll EventStore for result events keyed by corr_id.
                 // 2s timeout on the DB call: if EventStore is stuck we treat
                 // the result as not-ready and the model retries next turn.
-                for event_prefix in &["harness.result", "tool.result"] {
+                for topic in [
+                    shared_types::EventTopic::HarnessResult,
+                    shared_types::EventTopic::ToolResult,
+                ] {
                     let result = ractor::call_t!(
                         self.event_store,
                         |reply| EventStoreMsg::GetEventsByCorrId {
                             corr_id: source_ref.to_string(),
-                            event_type_prefix: Some(event_prefix.to_string()),
+                            event_type_prefix: Some(topic.to_string()),
                             reply,
                         },
                         2000
@@ -407,6 +412,7 @@ ContextSourceKind::ToolOutput with that corr_id on the next turn to read the res
                 }),
                 actor_id: self.actor_id.clone(),
                 user_id: "system".to_string(),
+                idempotency_key: None,
             },
         });
     }
@@ -421,10 +427,11 @@ ContextSourceKind::ToolOutput with that corr_id on the next turn to read the res
         };
         let _ = self.event_store.send_message(EventStoreMsg::AppendAsync {
             event: AppendEvent {
-                event_type: "harness.checkpoint".to_string(),
+                event_type: shared_types::EventTopic::HarnessCheckpoint.to_string(),
                 payload,
                 actor_id: self.actor_id.clone(),
                 user_id: "system".to_string(),
+                idempotency_key: None,
             },
         });
         info!(
@@ -42,6 +42,7 @@ use tokio::sync::mpsc;
 use tracing::{debug, error, info};
 
 use crate::actors::event_store::{AppendEvent, EventStoreMsg};
+use crate::actors::llm_scheduler::{is_rate_limit_error, llm_scheduler};
 use crate::actors::model_config::{ModelConfigError, ModelRegistry, ModelResolutionContext};
 use crate::baml_client::types::{
     AgentDecision, Message as BamlMessage,
@@ -49,7 +50,7 @@ use crate::baml_client::types::{
 };
 use crate::baml_client::{new_collector, ClientRegistry, B};
 use crate::observability::llm_trace::{
-    token_usage_from_collector, LlmCallScope, LlmTokenUsage, LlmTraceEmitter,
+    estimate_tokens, token_usage_from_collector, LlmCallScope, LlmTokenUsage, LlmTraceEmitter,
 };
 
 // Re-export shared types for convenience
@@ -133,6 +134,10 @@ fn tool_call_args_json(tool_call: &AgentToolCall) -> serde_json::Value {
     }
 }
 
+/// Label of the output line in a tool result message whose output was cut
+/// to the per-step echo budget.
+const TRUNCATED_OUTPUT_LABEL: &str = "Output (truncated)";
+
 fn truncate_for_next_turn(value: &str, max_chars: usize) -> (String, bool) {
     let mut iter = value.chars();
     let truncated: String = iter.by_ref().take(max_chars).collect();
@@ -253,7 +258,7 @@ fn tool_execution_message(
     let (output_excerpt, output_truncated) =
         truncate_for_next_turn(&execution.output, max_echo_chars);
     let output_label = if output_truncated {
-        "Output (truncated)"
+        TRUNCATED_OUTPUT_LABEL
     } else {
         "Output"
     };
@@ -277,6 +282,57 @@ fn tool_execution_message(
     )
 }
 
+/// Prompt composition of one Decide call, for `model.context.trace`.
+fn context_trace_items(
+    system_context: &str,
+    tools_description: &str,
+    messages: &[BamlMessage],
+) -> Vec<shared_types::ContextTraceItem> {
+    let truncated_marker = format!("\n{TRUNCATED_OUTPUT_LABEL}: ");
+    let mut items = vec![
+        shared_types::ContextTraceItem {
+            kind: shared_types::ContextTraceItemKind::System,
+            role: None,
+            label: "System context".to_string(),
+            id: Some("system".to_string()),
+            est_tokens: estimate_tokens(system_context),
+            trimmed: false,
+            trim_reason: None,
+        },
+        shared_types::ContextTraceItem {
+            kind: shared_types::ContextTraceItemKind::Tools,
+            role: None,
+            label: "Tool descriptions".to_string(),
+            id: Some("tools".to_string()),
+            est_tokens: estimate_tokens(tools_description),
+            trimmed: false,
+            trim_reason: None,
+        },
+    ];
+    items.extend(messages.iter().enumerate().map(|(index, message)| {
+        let first_line = message.content.lines().next().unwrap_or_default();
+        let label = if index == 0 {
+            "Objective".to_string()
+        } else if let Some(tool_name) = first_line.strip_prefix("Executed ") {
+            format!("{tool_name} result")
+        } else {
+            first_line.chars().take(60).collect()
+        };
+        let trimmed = message.content.contains(&truncated_marker);
+        shared_types::ContextTraceItem {
+            kind: shared_types::ContextTraceItemKind::Message,
+            role: Some(message.role.clone()),
+            label,
+            id: Some(format!("message:{index}")),
+            est_tokens: estimate_tokens(&message.content),
+            trimmed,
+            trim_reason: trimmed
+                .then(|| "tool output cut to the per-step echo budget".to_string()),
+        }
+    }));
+    items
+}
+
 /// State machine states for the agentic loop (simplified)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AgentLoopState {
@@ -569,6 +625,12 @@ pub trait WorkerPort: Send + Sync {
         }
     }
 
+    /// How the objective carries a document, for adapters that put one in
+    /// the prompt. Recorded in each call's context trace.
+    fn document_context(&self) -> Option<shared_types::DocumentContextMode> {
+        None
+    }
+
     /// Validate whether a terminal model completion decision is allowed.
     ///
     /// Adapters can enforce capability-specific completion invariants.
@@ -673,8 +735,32 @@ impl<W: WorkerPort> AgentHarness<W> {
         run_id: Option<String>,
         call_id: Option<String>,
     ) -> Result<AgentResult, HarnessError> {
-        let loop_id = ulid::Ulid::new().to_string();
+        self.run_with_loop_id(
+            ulid::Ulid::new().to_string(),
+            worker_id,
+            user_id,
+            objective,
+            model_override,
+            progress_tx,
+            run_id,
+            call_id,
+        )
+        .await
+    }
 
+    /// Same as [`Self::run`] with a caller-chosen loop id, so the caller can
+    /// correlate the loop's `worker.task.*` events before it finishes.
+    pub async fn run_with_loop_id(
+        &self,
+        loop_id: String,
+        worker_id: String,
+        user_id: String,
+        objective: String,
+        model_override: Option<String>,
+        progress_tx: Option<mpsc::UnboundedSender<AgentProgress>>,
+        run_id: Option<String>,
+        call_id: Option<String>,
+    ) -> Result<AgentResult, HarnessError> {
         // Resolve model
         let resolved_model = self
             .model_registry
@@ -991,6 +1077,7 @@ impl<W: WorkerPort> AgentHarness<W> {
                         call_id: ctx.call_id.clone(),
                         session_id: None,
                         thread_id: None,
+                        queue_wait_ms: None,
                     };
                     let tool_ctx = self.trace_emitter.start_tool_call(
                         self.worker_port.get_model_role(),
@@ -1046,6 +1133,7 @@ impl<W: WorkerPort> AgentHarness<W> {
                     call_id: ctx.call_id.clone(),
                     session_id: None,
                     thread_id: None,
+                    queue_wait_ms: None,
                 };
                 let tool_ctx = self.trace_emitter.start_tool_call(
                     self.worker_port.get_model_role(),
@@ -1241,6 +1329,12 @@ impl<W: WorkerPort> AgentHarness<W> {
         let model_used = &ctx.model_used;
         let provider: Option<&str> = None;
 
+        let permit = llm_scheduler()
+            .acquire(
+                &self.model_registry.call_target(model_used),
+                ctx.run_id.as_deref(),
+            )
+            .await;
         let trace_ctx = self.trace_emitter.start_call(
             self.worker_port.get_model_role(),
             "Decide",
@@ -1256,8 +1350,16 @@ impl<W: WorkerPort> AgentHarness<W> {
                 call_id: ctx.call_id.clone(),
                 session_id: None,
                 thread_id: None,
+                queue_wait_ms: Some(permit.queue_wait_ms()),
             }),
         );
+        self.trace_emitter.record_context(
+            &trace_ctx,
+            model_used,
+            context_trace_items(system_context, &tools_description, messages),
+            self.model_registry.context_window(model_used),
+            self.worker_port.document_context(),
+        );
 
         let collector = new_collector("agent_harness.decide");
         let result = B
@@ -1301,13 +1403,18 @@ impl<W: WorkerPort> AgentHarness<W> {
                 );
             }
             Err(e) => {
+                let message = e.to_string();
+                let rate_limited = is_rate_limit_error(&message);
+                if rate_limited {
+                    permit.report_rate_limited();
+                }
                 self.trace_emitter.fail_call_with_usage(
                     &trace_ctx,
                     model_used,
                     provider,
                     None,
-                    &e.to_string(),
-                    None,
+                    &message,
+                    rate_limited.then_some(shared_types::FailureKind::RateLimit),
                     usage.clone(),
                 );
             }
@@ -1453,12 +1560,13 @@ impl EventStoreEmitter {
         }
     }
 
-    pub fn emit_event(&self, event_type: &str, payload: serde_json::Value) {
+    pub fn emit_event(&self, topic: shared_types::EventTopic, payload: serde_json::Value) {
         let event = AppendEvent {
-            event_type: event_type.to_string(),
+            event_type: topic.to_string(),
             payload,
             actor_id: self.worker_id.clone(),
             user_id: self.user_id.clone(),
+            idempotency_key: None,
         };
         let _ = self
             .event_store
@@ -1485,7 +1593,7 @@ impl EventStoreEmitter {
             timestamp: chrono::Utc::now().to_rfc3339(),
         })
         .unwrap_or(serde_json::Value::Null);
-        self.emit_event("worker.task.progress", payload);
+        self.emit_event(shared_types::EventTopic::WorkerTaskProgress, payload);
     }
 
     pub fn emit_worker_started(
@@ -1508,7 +1616,7 @@ impl EventStoreEmitter {
             timestamp: chrono::Utc::now().to_rfc3339(),
         })
         .unwrap_or(serde_json::Value::Null);
-        self.emit_event("worker.task.started", payload);
+        self.emit_event(shared_types::EventTopic::WorkerTaskStarted, payload);
     }
 
     pub fn emit_worker_completed(
@@ -1529,7 +1637,7 @@ impl EventStoreEmitter {
             timestamp: chrono::Utc::now().to_rfc3339(),
         })
         .unwrap_or(serde_json::Value::Null);
-        self.emit_event("worker.task.completed", payload);
+        self.emit_event(shared_types::EventTopic::WorkerTaskCompleted, payload);
     }
 
     pub fn emit_worker_failed(
@@ -1550,7 +1658,7 @@ impl EventStoreEmitter {
             timestamp: chrono::Utc::now().to_rfc3339(),
         })
         .unwrap_or(serde_json::Value::Null);
-        self.emit_event("worker.task.failed", payload);
+        self.emit_event(shared_types::EventTopic::WorkerTaskFailed, payload);
     }
 
     pub fn emit_worker_finding(
@@ -1576,7 +1684,7 @@ impl EventStoreEmitter {
             timestamp: chrono::Utc::now().to_rfc3339(),
         })
         .unwrap_or(serde_json::Value::Null);
-        self.emit_event("worker.task.finding", payload);
+        self.emit_event(shared_types::EventTopic::WorkerTaskFinding, payload);
     }
 
     pub fn emit_worker_learning(
@@ -1600,7 +1708,7 @@ impl EventStoreEmitter {
             timestamp: chrono::Utc::now().to_rfc3339(),
         })
         .unwrap_or(serde_json::Value::Null);
-        self.emit_event("worker.task.learning", payload);
+        self.emit_event(shared_types::EventTopic::WorkerTaskLearning, payload);
     }
 }
 
@@ -1683,7 +1791,7 @@ impl WorkerPort for DefaultAdapter {
                 timestamp: chrono::Utc::now().to_rfc3339(),
             })
             .unwrap_or(serde_json::Value::Null);
-            emitter.emit_event("worker.report.received", payload);
+            emitter.emit_event(shared_types::EventTopic::WorkerReportReceived, payload);
         }
         Ok(())
     }
@@ -1772,4 +1880,34 @@ mod tests {
             MAX_TOOL_OUTPUT_ECHO_CHARS
         );
     }
+
+    #[test]
+    fn test_context_trace_items_flag_truncated_tool_output() {
+        let messages = vec![
+            BamlMessage {
+                role: "user".to_string(),
+                content: "Objective:\nfind the release date".to_string(),
+            },
+            BamlMessage {
+                role: "user".to_string(),
+                content: format!("Executed bash\nSuccess: true\n{TRUNCATED_OUTPUT_LABEL}: abcd"),
+            },
+        ];
+        let items = context_trace_items("12345678", "", &messages);
+        assert_eq!(items.len(), 4);
+        assert_eq!(items[0].kind, shared_types::ContextTraceItemKind::System);
+        assert_eq!(items[0].est_tokens, 2);
+        assert_eq!(items[2].label, "Objective");
+        assert!(!items[2].trimmed);
+        assert_eq!(items[3].label, "bash result");
+        assert!(items[3].trimmed);
+        assert!(items[3].trim_reason.is_some());
+
+        let trace = shared_types::ModelContextTracePayload::new("trace-1", "test-model", items);
+        assert!(trace.trimmed);
+        assert_eq!(
+            trace.included_item_ids(),
+            vec!["system", "tools", "message:0", "message:1"]
+        );
+    }
 }
@@ -5,17 +5,20 @@
 
 use async_trait::async_trait;
 use ractor::{Actor, ActorProcessingErr, ActorRef};
+use shared_types::ConductorExecuteRequest;
 use std::sync::Arc;
 
 use crate::actors::conductor::{
+    capability::CapabilityRegistry,
     model_gateway::{BamlConductorModelGateway, SharedConductorModelGateway},
     protocol::ConductorMsg,
     registry,
+    runtime::refine::refine_objective,
     state::ConductorState as RunStateStore,
 };
 use crate::actors::event_store::EventStoreMsg;
 use crate::actors::writer::{WriterError, WriterMsg};
-use crate::supervisor::writer::WriterSupervisorMsg;
+use crate::supervisor::writer::{WriterDocumentHolder, WriterSupervisorMsg};
 
 /// ConductorActor - main orchestration actor.
 #[derive(Debug, Default)]
@@ -30,6 +33,8 @@ pub struct ConductorArguments {
     pub writer_supervisor: Option<ActorRef<WriterSupervisorMsg>>,
     /// Optional memory actor for context retrieval (Phase 5.4).
     pub memory_actor: Option<ActorRef<crate::actors::memory::MemoryMsg>>,
+    /// Capabilities the conductor may dispatch to directly.
+    pub capabilities: CapabilityRegistry,
 }
 
 /// Internal state for ConductorActor.
@@ -39,6 +44,10 @@ pub struct ConductorState {
     pub(crate) writer_supervisor: Option<ActorRef<WriterSupervisorMsg>>,
     pub(crate) memory_actor: Option<ActorRef<crate::actors::memory::MemoryMsg>>,
     pub(crate) model_gateway: SharedConductorModelGateway,
+    pub(crate) capabilities: CapabilityRegistry,
+    pub(crate) provider_gateway_reachable: bool,
+    /// Runs accepted while the provider gateway was unreachable, oldest first.
+    pub(crate) waiting_for_provider: Vec<(String, ConductorExecuteRequest)>,
 }
 
 #[async_trait]
@@ -55,11 +64,14 @@ impl Actor for ConductorActor {
         tracing::info!(actor_id = %myself.get_id(), "ConductorActor starting");
         let model_gateway = Arc::new(BamlConductorModelGateway::new(args.event_store.clone()));
         Ok(ConductorState {
-            tasks: RunStateStore::new(),
+            tasks: RunStateStore::new().with_event_store(args.event_store.clone()),
             event_store: args.event_store,
             writer_supervisor: args.writer_supervisor,
             memory_actor: args.memory_actor,
             model_gateway,
+            capabilities: args.capabilities,
+            provider_gateway_reachable: true,
+            waiting_for_provider: Vec::new(),
         })
     }
 
@@ -84,10 +96,21 @@ impl Actor for ConductorActor {
             ConductorMsg::ExecuteTask { request, reply } => {
                 let _ = reply.send(self.handle_execute_task(myself, state, request).await);
             }
+            ConductorMsg::RefineObjective { request, reply } => {
+                // The model call can take seconds; keep the mailbox moving.
+                let model_gateway = state.model_gateway.clone();
+                tokio::spawn(async move {
+                    let _ = reply.send(refine_objective(model_gateway.as_ref(), request).await);
+                });
+            }
             ConductorMsg::StartRun { run_id, request } => {
                 self.handle_start_run(&myself, state, run_id, request)
                     .await?;
             }
+            ConductorMsg::ProviderGatewayChanged { reachable } => {
+                self.handle_provider_gateway_changed(&myself, state, reachable)
+                    .await;
+            }
             ConductorMsg::GetRunState { run_id, reply } => {
                 let _ = reply.send(state.tasks.get_run(&run_id).cloned());
             }
@@ -104,6 +127,7 @@ impl Actor for ConductorActor {
                 result,
             } => {
                 self.handle_capability_call_finished(
+                    &myself,
                     state,
                     run_id,
                     call_id,
@@ -180,15 +204,24 @@ impl ConductorActor {
         state: &ConductorState,
         run_id: &str,
     ) -> Result<ActorRef<WriterMsg>, crate::actors::conductor::ConductorError> {
-        if let Some(writer_actor) = registry::lookup_writer_actor_for_run(run_id) {
-            return Ok(writer_actor);
-        }
+        self.run_writer(state, run_id, None).await
+    }
+
+    /// Writer of the run's document, from the writer supervisor when there is
+    /// one so runs and windows on the same document share a writer. `holder`
+    /// keeps it alive until released.
+    pub(crate) async fn run_writer(
+        &self,
+        state: &ConductorState,
+        run_id: &str,
+        holder: Option<WriterDocumentHolder>,
+    ) -> Result<ActorRef<WriterMsg>, crate::actors::conductor::ConductorError> {
         if let Some(writer_supervisor) = state.writer_supervisor.as_ref() {
-            let writer_id = registry::run_writer_id(run_id);
             let result = ractor::call!(writer_supervisor, |reply| {
-                WriterSupervisorMsg::GetOrCreateWriter {
-                    writer_id,
+                WriterSupervisorMsg::GetOrCreateDocumentWriter {
+                    document_path: format!("conductor/runs/{run_id}/draft.md"),
                     user_id: "system".to_string(),
+                    holder,
                     reply,
                 }
             })
@@ -196,6 +229,9 @@ impl ConductorActor {
             .map_err(crate::actors::conductor::ConductorError::ActorUnavailable)?;
             return Ok(result);
         }
+        if let Some(writer_actor) = registry::lookup_writer_actor_for_run(run_id) {
+            return Ok(writer_actor);
+        }
         if let Some(writer_actor) = registry::lookup_writer_actor() {
             return Ok(writer_actor);
         }
@@ -204,6 +240,15 @@ impl ConductorActor {
         ))
     }
 
+    /// Let the run's writer stop once no window shows its document.
+    pub(crate) fn release_run_writer(&self, state: &ConductorState, run_id: &str) {
+        if let Some(writer_supervisor) = state.writer_supervisor.as_ref() {
+            let _ = writer_supervisor.cast(WriterSupervisorMsg::ReleaseHolder {
+                holder: WriterDocumentHolder::Run(run_id.to_string()),
+            });
+        }
+    }
+
     fn map_writer_error(error: WriterError) -> crate::actors::conductor::ConductorError {
         match error {
             WriterError::Validation(message) => {
@@ -306,15 +351,15 @@ mod tests {
     use crate::actors::conductor::model_gateway::{
         ConductorModelGateway, SharedConductorModelGateway,
     };
-    use crate::actors::conductor::protocol::ConductorError;
+    use crate::actors::conductor::protocol::{ConductorError, ConductorMsg};
     use crate::actors::conductor::state::ConductorState as RunStateStore;
     use crate::actors::event_store::{EventStoreActor, EventStoreArguments, EventStoreMsg};
-    use crate::baml_client::types::ConductorBootstrapOutput;
+    use crate::baml_client::types::{ConductorBootstrapOutput, ConductorObjectiveRefineOutput};
     use async_trait::async_trait;
-    use ractor::Actor;
+    use ractor::{Actor, ActorProcessingErr, ActorRef};
     use shared_types::{
-        ConductorOutputMode, ConductorRunState, ConductorRunStatus, EventImportance, EventLane,
-        EventMetadata,
+        ConductorExecuteRequest, ConductorOutputMode, ConductorRunState, ConductorRunStatus,
+        EventImportance, EventLane, EventMetadata,
     };
     use std::sync::Arc;
 
@@ -344,6 +389,16 @@ mod tests {
                 "immediate_response should not be called in handle_process_event tests".to_string(),
             ))
         }
+
+        async fn refine_objective(
+            &self,
+            _raw_objective: &str,
+            _desktop_context: &[String],
+        ) -> Result<ConductorObjectiveRefineOutput, ConductorError> {
+            Err(ConductorError::ModelGatewayError(
+                "refine_objective should not be called in handle_process_event tests".to_string(),
+            ))
+        }
     }
 
     fn test_run(run_id: &str) -> ConductorRunState {
@@ -362,6 +417,8 @@ mod tests {
             document_path: "/tmp/test-draft.md".to_string(),
             output_mode: ConductorOutputMode::Auto,
             desktop_id: "desktop-test".to_string(),
+            revision: 0,
+            contract: None,
         }
     }
 
@@ -380,6 +437,9 @@ mod tests {
                 writer_supervisor: None,
                 memory_actor: None,
                 model_gateway: gateway,
+                capabilities: Default::default(),
+                provider_gateway_reachable: true,
+                waiting_for_provider: Vec::new(),
             },
             event_store,
         )
@@ -459,4 +519,94 @@ mod tests {
 
         event_store.stop(None);
     }
+
+    /// Stands in for the conductor's own mailbox and records the runs it is
+    /// asked to start.
+    struct StartRunRecorder;
+
+    #[async_trait]
+    impl Actor for StartRunRecorder {
+        type Msg = ConductorMsg;
+        type State = Arc<std::sync::Mutex<Vec<String>>>;
+        type Arguments = Arc<std::sync::Mutex<Vec<String>>>;
+
+        async fn pre_start(
+            &self,
+            _myself: ActorRef<Self::Msg>,
+            started: Self::Arguments,
+        ) -> Result<Self::State, ActorProcessingErr> {
+            Ok(started)
+        }
+
+        async fn handle(
+            &self,
+            _myself: ActorRef<Self::Msg>,
+            message: Self::Msg,
+            started: &mut Self::State,
+        ) -> Result<(), ActorProcessingErr> {
+            if let ConductorMsg::StartRun { run_id, .. } = message {
+                started.lock().unwrap().push(run_id);
+            }
+            Ok(())
+        }
+    }
+
+    fn queued_request(objective: &str) -> ConductorExecuteRequest {
+        ConductorExecuteRequest {
+            objective: objective.to_string(),
+            desktop_id: "desktop-test".to_string(),
+            output_mode: ConductorOutputMode::Auto,
+            hints: None,
+            contract: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_runs_waiting_for_provider_resume_in_order_on_recovery() {
+        let gateway = Arc::new(CountingGateway);
+        let (mut state, event_store) = test_state_with_gateway(gateway).await;
+        let started = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let (recorder, _handle) = Actor::spawn(None, StartRunRecorder, started.clone())
+            .await
+            .unwrap();
+
+        let actor = ConductorActor;
+        actor
+            .handle_provider_gateway_changed(&recorder, &mut state, false)
+            .await;
+        assert!(!state.provider_gateway_reachable);
+        for run_id in ["run_queued_a", "run_queued_b"] {
+            let mut run = test_run(run_id);
+            run.status = ConductorRunStatus::WaitingForProvider;
+            state.tasks.insert_run(run);
+            state
+                .waiting_for_provider
+                .push((run_id.to_string(), queued_request(run_id)));
+        }
+
+        // Repeated outage reports keep the queue intact.
+        actor
+            .handle_provider_gateway_changed(&recorder, &mut state, false)
+            .await;
+        assert_eq!(state.waiting_for_provider.len(), 2);
+
+        actor
+            .handle_provider_gateway_changed(&recorder, &mut state, true)
+            .await;
+        assert!(state.provider_gateway_reachable);
+        assert!(state.waiting_for_provider.is_empty());
+        for run_id in ["run_queued_a", "run_queued_b"] {
+            let run = state.tasks.get_run(run_id).unwrap();
+            assert_eq!(run.status, ConductorRunStatus::Running);
+        }
+
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert_eq!(
+            started.lock().unwrap().as_slice(),
+            ["run_queued_a", "run_queued_b"]
+        );
+
+        recorder.stop(None);
+        event_store.stop(None);
+    }
 }
@@ -0,0 +1,502 @@
+//! Typed capability dispatch for the conductor.
+//!
+//! A `Capability` turns an objective plus dispatch constraints into a
+//! `WorkerResult`. The conductor looks capabilities up by name in a
+//! `CapabilityRegistry`, so new workers plug in by registering an
+//! implementation instead of adding another branch to the call runtime.
+//! Results flow through the same completion path as the built-ins and are
+//! folded into the run's agenda, artifacts and event log.
+//!
+//! The registry also caps how many calls of one capability run at once, so
+//! parallel dispatch cannot overload a single subsystem. Dispatches beyond a
+//! capability's limit queue in arrival order until a running call finishes.
+//! It can also give a capability its own call timeout, used when the run's
+//! contract sets none.
+
+use futures_util::future::BoxFuture;
+use ractor::ActorRef;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+use crate::actors::conductor::protocol::{CapabilityWorkerOutput, ConductorError, WriterRunOutput};
+use crate::actors::conductor::{registry, workers};
+use crate::actors::researcher::ResearcherMsg;
+use crate::actors::terminal::TerminalMsg;
+use crate::actors::writer::WriterMsg;
+
+/// Step budget for writer orchestration when the call sets none.
+const DEFAULT_WRITER_MAX_STEPS: u8 = 100;
+
+/// Terminal calls allowed in flight at once by the built-in registry.
+pub const DEFAULT_TERMINAL_CONCURRENCY: usize = 2;
+
+/// Researcher calls allowed in flight at once by the built-in registry.
+pub const DEFAULT_RESEARCHER_CONCURRENCY: usize = 4;
+
+/// Result of dispatching a capability.
+pub type WorkerResult = Result<CapabilityWorkerOutput, ConductorError>;
+
+/// Per-call limits and correlation handed to a capability.
+#[derive(Debug, Clone, Default)]
+pub struct CapabilityConstraints {
+    pub run_id: Option<String>,
+    pub call_id: Option<String>,
+    pub timeout_ms: Option<u64>,
+    pub max_steps: Option<u8>,
+    /// Exact shell command for terminal dispatch; agentic mode when `None`.
+    pub terminal_command: Option<String>,
+    /// Plan the terminal objective without executing any command.
+    pub terminal_dry_run: bool,
+    /// Commands of an approved terminal dry-run plan, run without re-planning.
+    pub terminal_plan: Option<Vec<String>>,
+    pub writer_actor: Option<ActorRef<WriterMsg>>,
+    /// Run context handed to a `writer` call; `None` for other capabilities.
+    pub writer_handoff: Option<WriterHandoff>,
+    /// Objective contract the call answers to, scoped to its agenda item.
+    pub contract: Option<shared_types::ObjectiveContract>,
+}
+
+/// What a nested writer run inherits from its parent conductor run.
+#[derive(Debug, Clone, Default)]
+pub struct WriterHandoff {
+    /// Parent run document the writer run is scoped to.
+    pub document_path: String,
+    /// Artifacts produced by the run's completed capability calls.
+    pub artifacts: Vec<shared_types::ConductorArtifact>,
+    /// Citations from completed research, deduplicated by URL.
+    pub citations: Vec<shared_types::ResearchCitation>,
+}
+
+impl WriterHandoff {
+    /// Collect the handoff from a run's state so far.
+    pub fn from_run(run: &shared_types::ConductorRunState) -> Self {
+        let mut artifacts = Vec::new();
+        let mut citations: Vec<shared_types::ResearchCitation> = Vec::new();
+        for artifact in &run.artifacts {
+            let Some(metadata) = artifact.metadata.as_ref() else {
+                continue;
+            };
+            match metadata.get("capability").and_then(|value| value.as_str()) {
+                Some("writer") | None => continue,
+                Some(_) => {}
+            }
+            for citation in artifact_citations(artifact) {
+                if !citations.iter().any(|known| known.url == citation.url) {
+                    citations.push(citation);
+                }
+            }
+            artifacts.push(artifact.clone());
+        }
+        Self {
+            document_path: run.document_path.clone(),
+            artifacts,
+            citations,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.artifacts.is_empty()
+    }
+
+    /// `objective` followed by what earlier calls produced, so the writer's
+    /// planner can use it. Unchanged when there is nothing to hand off.
+    pub fn objective_with_context(&self, objective: String) -> String {
+        if self.is_empty() {
+            return objective;
+        }
+        let mut context = format!(
+            "{objective}\n\nContext from earlier steps of this run (document: {}):\n",
+            self.document_path
+        );
+        for artifact in &self.artifacts {
+            let summary = artifact
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.get("summary"))
+                .and_then(|value| value.as_str())
+                .unwrap_or(&artifact.reference);
+            context.push_str(&format!("- {summary}\n"));
+        }
+        if !self.citations.is_empty() {
+            context.push_str("\nSources to cite:\n");
+            for (index, citation) in self.citations.iter().enumerate() {
+                context.push_str(&format!(
+                    "{}. [{}]({})\n",
+                    index + 1,
+                    citation.title,
+                    citation.url
+                ));
+            }
+        }
+        context
+    }
+}
+
+fn artifact_citations(
+    artifact: &shared_types::ConductorArtifact,
+) -> Vec<shared_types::ResearchCitation> {
+    artifact
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.get("citations"))
+        .and_then(|value| serde_json::from_value(value.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// A worker capability the conductor can dispatch an objective to.
+pub trait Capability: Send + Sync {
+    /// Registry key, matched against lowercase agenda item capabilities.
+    fn name(&self) -> &str;
+
+    fn dispatch(
+        &self,
+        objective: String,
+        constraints: CapabilityConstraints,
+    ) -> BoxFuture<'static, WorkerResult>;
+}
+
+/// Name-indexed set of capabilities available to a conductor.
+///
+/// Clones share concurrency limits, so every call actor dispatching through a
+/// clone of the conductor's registry counts against the same limit.
+#[derive(Clone, Default)]
+pub struct CapabilityRegistry {
+    capabilities: HashMap<String, Arc<dyn Capability>>,
+    limits: HashMap<String, ConcurrencyLimit>,
+    timeouts: HashMap<String, u64>,
+}
+
+#[derive(Clone)]
+struct ConcurrencyLimit {
+    max: usize,
+    permits: Arc<Semaphore>,
+}
+
+impl CapabilityRegistry {
+    /// Registry with the built-in terminal and researcher capabilities.
+    ///
+    /// The conductor never dispatches these itself; it routes terminal and
+    /// research work through the writer.
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::default();
+        registry.register(TerminalCapability::default());
+        registry.register(ResearcherCapability::default());
+        registry.set_concurrency_limit("terminal", DEFAULT_TERMINAL_CONCURRENCY);
+        registry.set_concurrency_limit("researcher", DEFAULT_RESEARCHER_CONCURRENCY);
+        registry
+    }
+
+    /// Cap concurrent calls of `name` at `max` (at least one). Capabilities
+    /// without a limit run unbounded. Limits are kept when the capability
+    /// itself is re-registered.
+    pub fn set_concurrency_limit(&mut self, name: &str, max: usize) {
+        let max = max.max(1);
+        self.limits.insert(
+            name.to_ascii_lowercase(),
+            ConcurrencyLimit {
+                max,
+                permits: Arc::new(Semaphore::new(max)),
+            },
+        );
+    }
+
+    /// Concurrency limit of `name`, if one is set.
+    pub fn concurrency_limit(&self, name: &str) -> Option<usize> {
+        self.limits
+            .get(&name.to_ascii_lowercase())
+            .map(|limit| limit.max)
+    }
+
+    /// Time calls of `name` out after `timeout_ms` when the run's contract
+    /// sets no timeout. Like limits, this applies to any name, registered or
+    /// not.
+    pub fn set_call_timeout(&mut self, name: &str, timeout_ms: u64) {
+        self.timeouts.insert(name.to_ascii_lowercase(), timeout_ms);
+    }
+
+    /// Call timeout of `name`, if one is set.
+    pub fn call_timeout_ms(&self, name: &str) -> Option<u64> {
+        self.timeouts.get(&name.to_ascii_lowercase()).copied()
+    }
+
+    /// Dispatch to the capability registered as `name`, waiting for a free
+    /// slot under its concurrency limit first. `None` when nothing is
+    /// registered under `name`.
+    pub fn dispatch(
+        &self,
+        name: &str,
+        objective: String,
+        constraints: CapabilityConstraints,
+    ) -> Option<BoxFuture<'static, WorkerResult>> {
+        let capability = self.get(name)?;
+        let permits = self
+            .limits
+            .get(&name.to_ascii_lowercase())
+            .map(|limit| limit.permits.clone());
+        Some(Box::pin(async move {
+            let _permit = match permits {
+                Some(permits) => Some(permits.acquire_owned().await.map_err(|_| {
+                    ConductorError::WorkerFailed(format!(
+                        "capability '{}' concurrency limit closed",
+                        capability.name()
+                    ))
+                })?),
+                None => None,
+            };
+            capability.dispatch(objective, constraints).await
+        }))
+    }
+
+    /// Register a capability, replacing any existing one with the same name.
+    pub fn register(&mut self, capability: impl Capability + 'static) {
+        self.capabilities
+            .insert(capability.name().to_ascii_lowercase(), Arc::new(capability));
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn Capability>> {
+        self.capabilities.get(&name.to_ascii_lowercase()).cloned()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.capabilities.contains_key(&name.to_ascii_lowercase())
+    }
+
+    /// Registered capability names, sorted.
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.capabilities.keys().cloned().collect();
+        names.sort();
+        names
+    }
+}
+
+/// Terminal capability backed by a TerminalActor.
+///
+/// Without an explicit actor the default conductor terminal is resolved from
+/// the actor registry at dispatch time.
+#[derive(Clone, Default)]
+pub struct TerminalCapability {
+    terminal: Option<ActorRef<TerminalMsg>>,
+}
+
+impl TerminalCapability {
+    pub fn new(terminal: ActorRef<TerminalMsg>) -> Self {
+        Self {
+            terminal: Some(terminal),
+        }
+    }
+}
+
+impl Capability for TerminalCapability {
+    fn name(&self) -> &str {
+        "terminal"
+    }
+
+    fn dispatch(
+        &self,
+        objective: String,
+        constraints: CapabilityConstraints,
+    ) -> BoxFuture<'static, WorkerResult> {
+        let terminal = self.terminal.clone();
+        Box::pin(async move {
+            let terminal = terminal
+                .or_else(registry::lookup_terminal_actor)
+                .ok_or_else(|| {
+                    ConductorError::ActorUnavailable("terminal actor unavailable".to_string())
+                })?;
+            let result = workers::call_terminal(
+                &terminal,
+                objective,
+                workers::TerminalDispatch {
+                    command: constraints.terminal_command,
+                    dry_run: constraints.terminal_dry_run,
+                    approved_plan: constraints.terminal_plan,
+                },
+                constraints.timeout_ms,
+                constraints.max_steps,
+                None,
+                constraints.run_id,
+                constraints.call_id,
+            )
+            .await?;
+            Ok(CapabilityWorkerOutput::Terminal(result))
+        })
+    }
+}
+
+/// Researcher capability backed by a ResearcherActor.
+///
+/// Without an explicit actor the default conductor researcher is resolved
+/// from the actor registry at dispatch time.
+#[derive(Clone, Default)]
+pub struct ResearcherCapability {
+    researcher: Option<ActorRef<ResearcherMsg>>,
+}
+
+impl ResearcherCapability {
+    pub fn new(researcher: ActorRef<ResearcherMsg>) -> Self {
+        Self {
+            researcher: Some(researcher),
+        }
+    }
+}
+
+impl Capability for ResearcherCapability {
+    fn name(&self) -> &str {
+        "researcher"
+    }
+
+    fn dispatch(
+        &self,
+        objective: String,
+        constraints: CapabilityConstraints,
+    ) -> BoxFuture<'static, WorkerResult> {
+        let researcher = self.researcher.clone();
+        Box::pin(async move {
+            let researcher = researcher
+                .or_else(registry::lookup_researcher_actor)
+                .ok_or_else(|| {
+                    ConductorError::ActorUnavailable("researcher actor unavailable".to_string())
+                })?;
+            let result = workers::call_researcher(
+                &researcher,
+                objective,
+                constraints.timeout_ms,
+                None,
+                constraints.max_steps,
+                None,
+                constraints.writer_actor,
+                constraints.run_id,
+                constraints.call_id,
+            )
+            .await?;
+            Ok(CapabilityWorkerOutput::Researcher(result))
+        })
+    }
+}
+
+/// Writer capability backed by a WriterActor.
+///
+/// Not a built-in: the conductor's own writer path handles `writer` calls.
+/// Register it to pin the writer actor. With a non-empty handoff the call is
+/// a nested writer run over the parent run's document, with the handed-off
+/// artifacts and citations given to the writer's planner as context. Either
+/// way the work stays inside the call's timeout and step budget. Without an
+/// explicit actor the run's writer, then the run-scoped registry entry, is
+/// used.
+#[derive(Clone, Default)]
+pub struct WriterCapability {
+    writer: Option<ActorRef<WriterMsg>>,
+}
+
+impl WriterCapability {
+    pub fn new(writer: ActorRef<WriterMsg>) -> Self {
+        Self {
+            writer: Some(writer),
+        }
+    }
+}
+
+impl Capability for WriterCapability {
+    fn name(&self) -> &str {
+        "writer"
+    }
+
+    fn dispatch(
+        &self,
+        objective: String,
+        constraints: CapabilityConstraints,
+    ) -> BoxFuture<'static, WorkerResult> {
+        let writer = self.writer.clone();
+        Box::pin(async move {
+            let writer = writer
+                .or_else(|| constraints.writer_actor.clone())
+                .or_else(|| {
+                    constraints
+                        .run_id
+                        .as_deref()
+                        .and_then(registry::lookup_writer_actor_for_run)
+                })
+                .ok_or_else(|| {
+                    ConductorError::ActorUnavailable("writer actor unavailable".to_string())
+                })?;
+
+            let handoff = constraints
+                .writer_handoff
+                .clone()
+                .filter(|handoff| !handoff.is_empty());
+            let Some(handoff) = handoff else {
+                let result = workers::call_writer(
+                    &writer,
+                    objective,
+                    constraints.timeout_ms,
+                    Some(constraints.max_steps.unwrap_or(DEFAULT_WRITER_MAX_STEPS)),
+                    constraints.run_id,
+                    constraints.call_id,
+                )
+                .await?;
+                return Ok(CapabilityWorkerOutput::Writer(result));
+            };
+
+            let (Some(run_id), Some(call_id)) =
+                (constraints.run_id.clone(), constraints.call_id.clone())
+            else {
+                return Err(ConductorError::InvalidRequest(
+                    "nested writer run requires run_id and call_id".to_string(),
+                ));
+            };
+            Ok(CapabilityWorkerOutput::WriterRun(
+                run_nested_writer(&writer, run_id, call_id, objective, handoff, &constraints).await,
+            ))
+        })
+    }
+}
+
+/// Run the nested writer inside the parent call's timeout and step budget.
+async fn run_nested_writer(
+    writer: &ActorRef<WriterMsg>,
+    run_id: String,
+    call_id: String,
+    objective: String,
+    handoff: WriterHandoff,
+    constraints: &CapabilityConstraints,
+) -> WriterRunOutput {
+    let mut output = WriterRunOutput {
+        writer_run_id: registry::nested_writer_run_id(&run_id, &call_id),
+        status: shared_types::WriterRunStatusKind::Failed,
+        summary: String::new(),
+        document_path: handoff.document_path.clone(),
+        revision: None,
+        cited_urls: handoff
+            .citations
+            .iter()
+            .map(|citation| citation.url.clone())
+            .collect(),
+    };
+
+    if constraints.max_steps == Some(0) {
+        output.status = shared_types::WriterRunStatusKind::Blocked;
+        output.summary = "nested writer run has no step budget left".to_string();
+        return output;
+    }
+
+    match workers::call_writer(
+        writer,
+        handoff.objective_with_context(objective),
+        constraints.timeout_ms,
+        Some(constraints.max_steps.unwrap_or(DEFAULT_WRITER_MAX_STEPS)),
+        Some(run_id),
+        Some(call_id),
+    )
+    .await
+    {
+        Ok(result) => {
+            if result.success {
+                output.status = shared_types::WriterRunStatusKind::Completed;
+            }
+            output.summary = result.summary;
+        }
+        Err(error) => output.summary = error.to_string(),
+    }
+    output
+}
@@ -12,10 +12,10 @@ use chrono::Utc;
 use ractor::ActorRef;
 use serde::Serialize;
 use shared_types::{
-    ConductorOutputMode, ConductorTaskCompletedPayload, ConductorTaskFailedPayload,
-    ConductorTaskProgressPayload, ConductorTaskStartedPayload, ConductorToastPayload,
-    ConductorWorkerCallPayload, ConductorWorkerResultPayload, EventImportance, EventLane,
-    EventMetadata, FailureKind,
+    ConductorOutputMode, ConductorRunStateDelta, ConductorTaskCompletedPayload,
+    ConductorTaskFailedPayload, ConductorTaskProgressPayload, ConductorTaskStartedPayload,
+    ConductorToastPayload, ConductorWorkerCallPayload, ConductorWorkerResultPayload,
+    EventImportance, EventLane, EventMetadata, FailureKind, ObjectiveContract,
 };
 
 fn to_payload<T: Serialize>(payload: T) -> serde_json::Value {
@@ -41,6 +41,7 @@ pub async fn emit_prompt_received(
         payload,
         actor_id: format!("conductor:{run_id}"),
         user_id: "system".to_string(),
+        idempotency_key: None,
     };
 
     let _ = event_store
@@ -54,6 +55,7 @@ pub async fn emit_task_started(
     run_id: &str,
     objective: &str,
     desktop_id: &str,
+    contract: Option<&ObjectiveContract>,
 ) {
     let payload = to_payload(ConductorTaskStartedPayload {
         run_id: run_id.to_string(),
@@ -62,6 +64,7 @@ pub async fn emit_task_started(
         status: "started".to_string(),
         phase: "initialization".to_string(),
         timestamp: Utc::now().to_rfc3339(),
+        contract: contract.cloned(),
     });
 
     let event = AppendEvent {
@@ -69,6 +72,7 @@ pub async fn emit_task_started(
         payload,
         actor_id: format!("conductor:{run_id}"),
         user_id: "system".to_string(),
+        idempotency_key: None,
     };
 
     let _ = event_store
@@ -97,6 +101,7 @@ pub async fn emit_task_progress(
         payload,
         actor_id: format!("conductor:{run_id}"),
         user_id: "system".to_string(),
+        idempotency_key: None,
     };
 
     let _ = event_store
@@ -123,6 +128,7 @@ pub async fn emit_worker_call(
         payload,
         actor_id: format!("conductor:{run_id}"),
         user_id: "system".to_string(),
+        idempotency_key: None,
     };
 
     let _ = event_store
@@ -151,6 +157,7 @@ pub async fn emit_worker_result(
         payload,
         actor_id: format!("conductor:{run_id}"),
         user_id: "system".to_string(),
+        idempotency_key: None,
     };
 
     let _ = event_store
@@ -182,6 +189,24 @@ pub async fn emit_task_completed(
         payload,
         actor_id: format!("conductor:{run_id}"),
         user_id: "system".to_string(),
+        idempotency_key: None,
+    };
+
+    let _ = event_store
+        .send_message(EventStoreMsg::AppendAsync { event })
+        .ok();
+}
+
+/// Emit the delta of a run state change.
+///
+/// Synchronous so deltas reach the EventStore mailbox in revision order.
+pub fn emit_run_state_delta(event_store: &ActorRef<EventStoreMsg>, delta: &ConductorRunStateDelta) {
+    let event = AppendEvent {
+        event_type: shared_types::EVENT_TOPIC_CONDUCTOR_RUN_STATE_DELTA.to_string(),
+        payload: to_payload(delta),
+        actor_id: format!("conductor:{}", delta.run_id),
+        user_id: "system".to_string(),
+        idempotency_key: None,
     };
 
     let _ = event_store
@@ -211,6 +236,7 @@ pub async fn emit_task_failed(
         payload,
         actor_id: format!("conductor:{run_id}"),
         user_id: "system".to_string(),
+        idempotency_key: None,
     };
 
     let _ = event_store
@@ -237,6 +263,7 @@ pub async fn emit_document_update(
         payload,
         actor_id: format!("conductor:{run_id}"),
         user_id: "system".to_string(),
+        idempotency_key: None,
     };
 
     let _ = event_store
@@ -283,6 +310,7 @@ pub async fn emit_control_event(
         payload: full_payload,
         actor_id: format!("conductor:{run_id}"),
         user_id: "system".to_string(),
+        idempotency_key: None,
     };
 
     let _ = event_store
@@ -323,6 +351,7 @@ pub async fn emit_telemetry_event(
         payload: full_payload,
         actor_id: format!("conductor:{run_id}"),
         user_id: "system".to_string(),
+        idempotency_key: None,
     };
 
     let _ = event_store
@@ -342,7 +371,7 @@ pub async fn emit_capability_completed(
 ) {
     emit_control_event(
         event_store,
-        "conductor.capability.completed",
+        shared_types::EVENT_TOPIC_CONDUCTOR_CAPABILITY_COMPLETED,
         run_id,
         capability,
         "completion",
@@ -365,7 +394,7 @@ pub async fn emit_capability_failed(
 ) {
     emit_control_event(
         event_store,
-        "conductor.capability.failed",
+        shared_types::EVENT_TOPIC_CONDUCTOR_CAPABILITY_FAILED,
         run_id,
         capability,
         "failure",
@@ -388,7 +417,7 @@ pub async fn emit_capability_blocked(
 ) {
     emit_control_event(
         event_store,
-        "conductor.capability.blocked",
+        shared_types::EVENT_TOPIC_CONDUCTOR_CAPABILITY_BLOCKED,
         run_id,
         capability,
         "blocked",
@@ -798,7 +827,14 @@ mod tests {
                 .await
                 .unwrap();
 
-        emit_task_started(&store_ref, "task-123", "Test objective", "desktop-789").await;
+        emit_task_started(
+            &store_ref,
+            "task-123",
+            "Test objective",
+            "desktop-789",
+            None,
+        )
+        .await;
 
         // Give async event time to process
         tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
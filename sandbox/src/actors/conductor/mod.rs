@@ -24,18 +24,23 @@
 //!
 //! let args = ConductorArguments {
 //!     event_store: event_store_ref,
+//!     writer_supervisor: None,
+//!     memory_actor: None,
+//!     capabilities: CapabilityRegistry::default(),
 //! };
 //!
 //! let (conductor_ref, _handle) = Actor::spawn(None, ConductorActor, args).await?;
 //! ```
 
 pub mod actor;
+pub mod capability;
 pub mod events;
 pub mod model_gateway;
 pub mod output;
 pub mod protocol;
 pub mod registry;
 mod runtime;
+pub mod schedule;
 pub mod state;
 pub mod workers;
 
@@ -43,4 +48,6 @@ pub mod workers;
 mod tests;
 
 pub use actor::{ConductorActor, ConductorArguments, ConductorState};
-pub use protocol::{ConductorError, ConductorMsg, WorkerOutput};
+pub use capability::{Capability, CapabilityConstraints, CapabilityRegistry, WorkerResult};
+pub use protocol::{CapabilityOutput, ConductorError, ConductorMsg, WorkerOutput};
+pub use schedule::{schedule, ScheduleError};
@@ -8,10 +8,14 @@ use std::sync::Arc;
 
 use crate::actors::conductor::protocol::ConductorError;
 use crate::actors::event_store::EventStoreMsg;
+use crate::actors::llm_scheduler::{is_rate_limit_error, llm_scheduler};
 use crate::actors::model_config::{
     ModelRegistry, ModelResolutionContext, ProviderConfig, ResolvedModel,
 };
-use crate::baml_client::types::{ConductorBootstrapInput, ConductorBootstrapOutput};
+use crate::baml_client::types::{
+    ConductorBootstrapInput, ConductorBootstrapOutput, ConductorObjectiveRefineInput,
+    ConductorObjectiveRefineOutput,
+};
 use crate::baml_client::{new_collector, ClientRegistry, B};
 use crate::observability::llm_trace::{token_usage_from_collector, LlmCallScope, LlmTraceEmitter};
 
@@ -33,6 +37,14 @@ pub trait ConductorModelGateway: Send + Sync {
         run_id: Option<&str>,
         objective: &str,
     ) -> Result<String, ConductorError>;
+
+    /// Restate a raw objective with success criteria and clarifying
+    /// questions before any run exists.
+    async fn refine_objective(
+        &self,
+        raw_objective: &str,
+        desktop_context: &[String],
+    ) -> Result<ConductorObjectiveRefineOutput, ConductorError>;
 }
 
 #[derive(Debug)]
@@ -106,6 +118,9 @@ impl ConductorModelGateway for BamlConductorModelGateway {
             available_capabilities.len()
         );
 
+        let permit = llm_scheduler()
+            .acquire(&resolved.config.call_target(), run_id)
+            .await;
         let ctx = self.trace_emitter.start_call(
             "conductor",
             "ConductorBootstrapAgenda",
@@ -121,6 +136,7 @@ impl ConductorModelGateway for BamlConductorModelGateway {
                 call_id: None,
                 session_id: None,
                 thread_id: None,
+                queue_wait_ms: Some(permit.queue_wait_ms()),
             }),
         );
 
@@ -206,13 +222,18 @@ impl ConductorModelGateway for BamlConductorModelGateway {
                 );
             }
             Err(e) => {
+                let message = e.to_string();
+                let rate_limited = is_rate_limit_error(&message);
+                if rate_limited {
+                    permit.report_rate_limited();
+                }
                 self.trace_emitter.fail_call_with_usage(
                     &ctx,
                     model_used,
                     provider,
                     None,
-                    &e.to_string(),
-                    None,
+                    &message,
+                    rate_limited.then_some(shared_types::FailureKind::RateLimit),
                     usage,
                 );
             }
@@ -245,6 +266,9 @@ impl ConductorModelGateway for BamlConductorModelGateway {
             "conversation_history": "",
         });
         let input_summary = "Generate immediate conductor response";
+        let permit = llm_scheduler()
+            .acquire(&resolved.config.call_target(), run_id)
+            .await;
         let ctx = self.trace_emitter.start_call(
             "conductor",
             "QuickResponse",
@@ -260,6 +284,7 @@ impl ConductorModelGateway for BamlConductorModelGateway {
                 call_id: None,
                 session_id: None,
                 thread_id: None,
+                queue_wait_ms: Some(permit.queue_wait_ms()),
             }),
         );
 
@@ -300,13 +325,18 @@ impl ConductorModelGateway for BamlConductorModelGateway {
                 Ok(trimmed)
             }
             Err(e) => {
+                let message = e.to_string();
+                let rate_limited = is_rate_limit_error(&message);
+                if rate_limited {
+                    permit.report_rate_limited();
+                }
                 self.trace_emitter.fail_call_with_usage(
                     &ctx,
                     model_used,
                     provider,
                     None,
-                    &e.to_string(),
-                    None,
+                    &message,
+                    rate_limited.then_some(shared_types::FailureKind::RateLimit),
                     usage,
                 );
                 Err(ConductorError::ModelGatewayError(format!(
@@ -315,6 +345,99 @@ impl ConductorModelGateway for BamlConductorModelGateway {
             }
         }
     }
+
+    async fn refine_objective(
+        &self,
+        raw_objective: &str,
+        desktop_context: &[String],
+    ) -> Result<ConductorObjectiveRefineOutput, ConductorError> {
+        if raw_objective.trim().is_empty() {
+            return Err(ConductorError::ModelGatewayError(
+                "Objective to refine is empty".to_string(),
+            ));
+        }
+
+        let (client_registry, resolved) = self.resolve_for_callsite("conductor")?;
+        let model_used = resolved.config.id.as_str();
+        let provider = Some(Self::provider_string(&resolved.config.provider));
+        let input = ConductorObjectiveRefineInput {
+            raw_objective: raw_objective.to_string(),
+            context: desktop_context.to_vec(),
+            target_capability: "conductor".to_string(),
+        };
+        let input_json = serde_json::json!({
+            "raw_objective": raw_objective,
+            "context": desktop_context,
+        });
+        let permit = llm_scheduler()
+            .acquire(&resolved.config.call_target(), None)
+            .await;
+        let ctx = self.trace_emitter.start_call(
+            "conductor",
+            "ConductorRefineObjective",
+            "conductor-model-gateway",
+            model_used,
+            provider,
+            raw_objective,
+            &input_json,
+            "Refine objective into a contract",
+            Some(LlmCallScope {
+                run_id: None,
+                task_id: None,
+                call_id: None,
+                session_id: None,
+                thread_id: None,
+                queue_wait_ms: Some(permit.queue_wait_ms()),
+            }),
+        );
+
+        let collector = new_collector("conductor.refine_objective");
+        let result = B
+            .ConductorRefineObjective
+            .with_client_registry(&client_registry)
+            .with_collector(&collector)
+            .call(&input)
+            .await;
+        let usage = token_usage_from_collector(&collector);
+
+        match result {
+            Ok(output) => {
+                self.trace_emitter.complete_call_with_usage(
+                    &ctx,
+                    model_used,
+                    provider,
+                    &serde_json::json!({
+                        "refined_objective": output.refined_objective,
+                        "success_criteria": output.success_criteria,
+                        "clarifying_questions": output.clarifying_questions,
+                        "estimated_steps": output.estimated_steps,
+                    }),
+                    "Objective refined",
+                    usage,
+                );
+                Ok(output)
+            }
+            Err(e) => {
+                let message = e.to_string();
+                let rate_limited = is_rate_limit_error(&message);
+                if rate_limited {
+                    permit.report_rate_limited();
+                }
+                self.trace_emitter.fail_call_with_usage(
+                    &ctx,
+                    model_used,
+                    provider,
+                    None,
+                    &message,
+                    rate_limited.then_some(shared_types::FailureKind::RateLimit),
+                    usage,
+                );
+                Err(ConductorError::ModelGatewayError(format!(
+                    "Refine objective model-gateway call failed: {e}"
+                )))
+            }
+        }
+    }
 }
 
 #[cfg(test)]
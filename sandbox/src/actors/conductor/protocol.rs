@@ -7,7 +7,10 @@ use crate::actors::researcher::ResearcherResult;
 use crate::actors::terminal::TerminalAgentResult;
 use crate::actors::writer::{WriterOrchestrationResult, WriterQueueAck};
 use ractor::{ActorRef, RpcReplyPort};
-use shared_types::{CitationRecord, ConductorExecuteRequest, ConductorRunState, EventMetadata};
+use shared_types::{
+    CitationRecord, ConductorExecuteRequest, ConductorRefineRequest, ConductorRefineResponse,
+    ConductorRunState, ErrorCode, EventMetadata,
+};
 
 /// Messages handled by ConductorActor
 #[derive(Debug)]
@@ -17,11 +20,19 @@ pub enum ConductorMsg {
         request: ConductorExecuteRequest,
         reply: RpcReplyPort<Result<ConductorRunState, ConductorError>>,
     },
+    /// Propose an objective contract and clarifying questions; starts no run.
+    RefineObjective {
+        request: ConductorRefineRequest,
+        reply: RpcReplyPort<Result<ConductorRefineResponse, ConductorError>>,
+    },
     /// Perform initial conduct + worker dispatch asynchronously after run acceptance.
     StartRun {
         run_id: String,
         request: ConductorExecuteRequest,
     },
+    /// Provider gateway reachability changed. While unreachable, new runs are
+    /// accepted as `WaitingForProvider`; recovery starts them in order.
+    ProviderGatewayChanged { reachable: bool },
     /// Get the current state of a run.
     GetRunState {
         run_id: String,
@@ -105,6 +116,33 @@ pub enum CapabilityWorkerOutput {
     Writer(WriterOrchestrationResult),
     ImmediateResponse(String),
     Harness(HarnessResult),
+    /// Nested writer run started by the `writer` capability with a handoff.
+    WriterRun(WriterRunOutput),
+    /// Output from a registered `Capability` without a dedicated variant.
+    Capability(CapabilityOutput),
+}
+
+/// Outcome of a nested writer run over the parent run's document.
+#[derive(Debug, Clone)]
+pub struct WriterRunOutput {
+    pub writer_run_id: String,
+    /// Terminal status the writer reported; `Completed`, `Failed` or `Blocked`.
+    pub status: shared_types::WriterRunStatusKind,
+    pub summary: String,
+    pub document_path: String,
+    /// Document revision after the writer's patch, when one was applied.
+    pub revision: Option<u64>,
+    /// URLs of the handed-off citations referenced by the draft.
+    pub cited_urls: Vec<String>,
+}
+
+/// Generic capability result folded into run state like the built-ins.
+#[derive(Debug, Clone)]
+pub struct CapabilityOutput {
+    pub success: bool,
+    pub summary: String,
+    /// Structured details recorded on the call artifact.
+    pub metadata: serde_json::Value,
 }
 
 // ---------------------------------------------------------------------------
@@ -241,17 +279,16 @@ impl From<ConductorError> for shared_types::ConductorError {
     fn from(err: ConductorError) -> Self {
         shared_types::ConductorError {
             code: match &err {
-                ConductorError::NotFound(_) => "NOT_FOUND",
-                ConductorError::ActorUnavailable(_) => "ACTOR_NOT_AVAILABLE",
-                ConductorError::InvalidRequest(_) => "INVALID_REQUEST",
-                ConductorError::WorkerFailed(_) => "WORKER_FAILED",
-                ConductorError::WorkerBlocked(_) => "WORKER_BLOCKED",
-                ConductorError::ReportWriteFailed(_) => "REPORT_WRITE_FAILED",
-                ConductorError::DuplicateRun(_) => "DUPLICATE_RUN",
-                ConductorError::ModelGatewayError(_) => "MODEL_GATEWAY_ERROR",
-                ConductorError::FileError(_) => "FILE_ERROR",
-            }
-            .to_string(),
+                ConductorError::NotFound(_) => ErrorCode::NotFound,
+                ConductorError::ActorUnavailable(_) => ErrorCode::ActorUnavailable,
+                ConductorError::InvalidRequest(_) => ErrorCode::InvalidRequest,
+                ConductorError::WorkerFailed(_) => ErrorCode::RunFailed,
+                ConductorError::WorkerBlocked(_) => ErrorCode::RunBlocked,
+                ConductorError::ReportWriteFailed(_) => ErrorCode::InternalError,
+                ConductorError::DuplicateRun(_) => ErrorCode::AlreadyExists,
+                ConductorError::ModelGatewayError(_) => ErrorCode::ProviderUnavailable,
+                ConductorError::FileError(_) => ErrorCode::InternalError,
+            },
             message: err.to_string(),
             failure_kind: Some(match err {
                 ConductorError::NotFound(_) => shared_types::FailureKind::Unknown,
@@ -3,12 +3,14 @@ use ractor::ActorRef;
 use crate::actors::researcher::ResearcherMsg;
 use crate::actors::terminal::TerminalMsg;
 use crate::actors::writer::WriterMsg;
+use crate::paths::normalize_document_path;
 
 pub const DEFAULT_CONDUCTOR_RESEARCHER_ID: &str = "conductor-researcher";
 pub const DEFAULT_CONDUCTOR_TERMINAL_ID: &str = "conductor-terminal";
 pub const DEFAULT_CONDUCTOR_WRITER_ID: &str = "conductor-writer";
 pub const RUN_WRITER_ID_PREFIX: &str = "conductor-run-writer";
 pub const CALL_RESEARCHER_ID_PREFIX: &str = "conductor-call-researcher";
+pub const NESTED_WRITER_RUN_ID_PREFIX: &str = "conductor-writer-run";
 
 fn lookup_actor<T>(registry_name: String) -> Option<ActorRef<T>> {
     ractor::registry::where_is(registry_name).map(Into::into)
@@ -30,10 +32,34 @@ pub fn run_writer_id(run_id: &str) -> String {
     format!("{RUN_WRITER_ID_PREFIX}-{run_id}")
 }
 
+/// Run id of a run document path (`conductor/runs/{run_id}/draft.md`),
+/// matched after [`normalize_document_path`].
+pub fn extract_run_id_from_document_path(path: &str) -> Option<String> {
+    let path = normalize_document_path(path).ok()?;
+    let mut parts = path.split('/');
+    match (
+        parts.next(),
+        parts.next(),
+        parts.next(),
+        parts.next(),
+        parts.next(),
+    ) {
+        (Some("conductor"), Some("runs"), Some(run_id), Some("draft.md"), None) => {
+            Some(run_id.to_string())
+        }
+        _ => None,
+    }
+}
+
 pub fn call_researcher_id(run_id: &str, call_id: &str) -> String {
     format!("{CALL_RESEARCHER_ID_PREFIX}-{run_id}-{call_id}")
 }
 
+/// Id of the nested writer run started by a `writer` capability call.
+pub fn nested_writer_run_id(run_id: &str, call_id: &str) -> String {
+    format!("{NESTED_WRITER_RUN_ID_PREFIX}-{run_id}-{call_id}")
+}
+
 pub fn lookup_writer_actor_for_run(run_id: &str) -> Option<ActorRef<WriterMsg>> {
     let writer_id = run_writer_id(run_id);
     lookup_actor(format!("writer:{writer_id}"))
@@ -1,8 +1,12 @@
 use async_trait::async_trait;
 use ractor::{Actor, ActorProcessingErr, ActorRef};
 
+use crate::actors::conductor::capability::{
+    CapabilityConstraints, CapabilityRegistry, WriterHandoff,
+};
 use crate::actors::conductor::model_gateway::SharedConductorModelGateway;
 use crate::actors::conductor::protocol::{CapabilityWorkerOutput, ConductorError, ConductorMsg};
+use crate::actors::conductor::workers;
 use crate::actors::writer::{SectionState, WriterMsg, WriterSource};
 
 #[derive(Debug, Default)]
@@ -12,12 +16,17 @@ pub(crate) struct CapabilityCallActor;
 pub(crate) struct CapabilityCallArguments {
     pub conductor_ref: ActorRef<ConductorMsg>,
     pub model_gateway: SharedConductorModelGateway,
+    pub capabilities: CapabilityRegistry,
     pub writer_actor: Option<ActorRef<WriterMsg>>,
     pub run_id: String,
     pub call_id: String,
     pub agenda_item_id: String,
     pub capability: String,
     pub objective: String,
+    pub writer_handoff: Option<WriterHandoff>,
+    /// Child contract for the agenda item, derived from the run's contract;
+    /// its constraints replace the call defaults.
+    pub contract: Option<shared_types::ObjectiveContract>,
 }
 
 #[derive(Debug)]
@@ -29,12 +38,17 @@ pub(crate) enum CapabilityCallMsg {
 pub(crate) struct CapabilityCallState {
     pub conductor_ref: ActorRef<ConductorMsg>,
     pub model_gateway: SharedConductorModelGateway,
+    pub capabilities: CapabilityRegistry,
     pub writer_actor: Option<ActorRef<WriterMsg>>,
     pub run_id: String,
     pub call_id: String,
     pub agenda_item_id: String,
     pub capability: String,
     pub objective: String,
+    pub writer_handoff: Option<WriterHandoff>,
+    /// Child contract for the agenda item, derived from the run's contract;
+    /// its constraints replace the call defaults.
+    pub contract: Option<shared_types::ObjectiveContract>,
 }
 
 #[async_trait]
@@ -51,12 +65,15 @@ impl Actor for CapabilityCallActor {
         let state = CapabilityCallState {
             conductor_ref: args.conductor_ref,
             model_gateway: args.model_gateway,
+            capabilities: args.capabilities,
             writer_actor: args.writer_actor,
             run_id: args.run_id,
             call_id: args.call_id,
             agenda_item_id: args.agenda_item_id,
             capability: args.capability,
             objective: args.objective,
+            writer_handoff: args.writer_handoff,
+            contract: args.contract,
         };
         let _ = myself.send_message(CapabilityCallMsg::Run);
         Ok(state)
@@ -92,10 +109,46 @@ impl Actor for CapabilityCallActor {
     }
 }
 
-async fn run_capability_call(
+/// Timeout for a capability call when neither the run's contract nor the
+/// registry sets one.
+const DEFAULT_CALL_TIMEOUT_MS: u64 = 60_000;
+
+/// Default step budget for a writer call when the run has no contract.
+const DEFAULT_CALL_MAX_STEPS: u8 = 100;
+
+/// Workers the writer delegates to. The conductor never dispatches them
+/// itself, even when they are registered.
+const WRITER_DELEGATED_CAPABILITIES: &[&str] = &["researcher", "terminal"];
+
+/// Timeout and step budget for a call: the run contract's when agreed,
+/// `default_timeout_ms` and the default step budget otherwise.
+pub(crate) fn call_budget(
+    budget: Option<&shared_types::ObjectiveConstraints>,
+    default_timeout_ms: u64,
+) -> (u64, u8) {
+    match budget {
+        Some(budget) => (
+            budget.timeout_ms,
+            u8::try_from(budget.max_tool_calls).unwrap_or(u8::MAX),
+        ),
+        None => (default_timeout_ms, DEFAULT_CALL_MAX_STEPS),
+    }
+}
+
+pub(crate) async fn run_capability_call(
     state: CapabilityCallState,
 ) -> Result<CapabilityWorkerOutput, ConductorError> {
     let capability = state.capability.to_ascii_lowercase();
+    let (timeout_ms, max_steps) = call_budget(
+        state
+            .contract
+            .as_ref()
+            .map(|contract| &contract.constraints),
+        state
+            .capabilities
+            .call_timeout_ms(&capability)
+            .unwrap_or(DEFAULT_CALL_TIMEOUT_MS),
+    );
     if capability == "immediate_response" {
         let message = tokio::time::timeout(
             std::time::Duration::from_secs(45),
@@ -112,6 +165,32 @@ async fn run_capability_call(
         return Ok(CapabilityWorkerOutput::ImmediateResponse(message));
     }
 
+    if WRITER_DELEGATED_CAPABILITIES.contains(&capability.as_str()) {
+        return Err(route_through_writer(&capability));
+    }
+
+    if state.capabilities.contains(&capability) {
+        let constraints = CapabilityConstraints {
+            run_id: Some(state.run_id),
+            call_id: Some(state.call_id),
+            timeout_ms: Some(timeout_ms),
+            max_steps: state.contract.is_some().then_some(max_steps),
+            writer_actor: state.writer_actor,
+            writer_handoff: state.writer_handoff,
+            contract: state.contract,
+            ..Default::default()
+        };
+        return match state
+            .capabilities
+            .dispatch(&capability, state.objective, constraints)
+        {
+            Some(dispatch) => dispatch.await,
+            None => Err(ConductorError::WorkerFailed(format!(
+                "capability '{capability}' is not registered"
+            ))),
+        };
+    }
+
     let writer_actor = state.writer_actor.ok_or_else(|| {
         ConductorError::WorkerFailed(
             "Writer actor unavailable for capability delegation".to_string(),
@@ -119,24 +198,30 @@ async fn run_capability_call(
     })?;
 
     if capability != "writer" {
-        return Err(ConductorError::WorkerFailed(format!(
-            "Conductor cannot dispatch worker capability '{capability}' directly; route through writer"
-        )));
+        return Err(route_through_writer(&capability));
     }
-    let orchestration_result =
-        ractor::call!(writer_actor, |reply| WriterMsg::OrchestrateObjective {
-            objective: state.objective,
-            timeout_ms: Some(60_000),
-            max_steps: Some(100),
-            run_id: Some(state.run_id),
-            call_id: Some(state.call_id),
-            reply,
-        })
-        .map_err(|e| ConductorError::WorkerFailed(e.to_string()))?
-        .map_err(|e| ConductorError::WorkerFailed(e.to_string()))?;
+    let objective = match state.writer_handoff {
+        Some(handoff) => handoff.objective_with_context(state.objective),
+        None => state.objective,
+    };
+    let orchestration_result = workers::call_writer(
+        &writer_actor,
+        objective,
+        Some(timeout_ms),
+        Some(max_steps),
+        Some(state.run_id),
+        Some(state.call_id),
+    )
+    .await?;
     Ok(CapabilityWorkerOutput::Writer(orchestration_result))
 }
 
+fn route_through_writer(capability: &str) -> ConductorError {
+    ConductorError::WorkerFailed(format!(
+        "Conductor cannot dispatch worker capability '{capability}' directly; route through writer"
+    ))
+}
+
 fn writer_section_for_capability(capability: &str) -> String {
     match capability {
         "researcher" | "terminal" | "writer" => capability.to_string(),
@@ -243,9 +328,50 @@ async fn emit_result_to_writer(
                 (SectionState::Failed, "capability_failed", writer_content)
             }
         }
+        Ok(CapabilityWorkerOutput::WriterRun(output)) => {
+            let writer_content = format!(
+                "Writer run {} {}.\nSummary: {}",
+                output.writer_run_id,
+                match output.status {
+                    shared_types::WriterRunStatusKind::Completed => "completed",
+                    shared_types::WriterRunStatusKind::Blocked => "blocked",
+                    _ => "failed",
+                },
+                output.summary
+            );
+            if output.status == shared_types::WriterRunStatusKind::Completed {
+                (
+                    SectionState::Complete,
+                    "capability_completed",
+                    writer_content,
+                )
+            } else {
+                (SectionState::Failed, "capability_failed", writer_content)
+            }
+        }
         Ok(CapabilityWorkerOutput::ImmediateResponse(_)) => {
             return;
         }
+        Ok(CapabilityWorkerOutput::Capability(output)) => {
+            let writer_content = format!(
+                "Capability {}.\nCapability: {capability}\nSummary: {}",
+                if output.success {
+                    "completed"
+                } else {
+                    "failed"
+                },
+                output.summary
+            );
+            if output.success {
+                (
+                    SectionState::Complete,
+                    "capability_completed",
+                    writer_content,
+                )
+            } else {
+                (SectionState::Failed, "capability_failed", writer_content)
+            }
+        }
         Ok(CapabilityWorkerOutput::Harness(_)) => {
             let writer_content = format!(
                 "Capability failed.\nCapability: {capability}\nError: unsupported capability output"
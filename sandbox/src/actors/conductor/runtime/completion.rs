@@ -1,14 +1,16 @@
-use ractor::ActorProcessingErr;
+use ractor::{ActorProcessingErr, ActorRef};
 
 use crate::actors::conductor::actor::{ConductorActor, ConductorState};
 use crate::actors::conductor::{
     events,
-    protocol::{CapabilityWorkerOutput, ConductorError},
+    protocol::{CapabilityWorkerOutput, ConductorError, ConductorMsg},
+    state::run_progress_pct,
 };
 
 impl ConductorActor {
     pub(crate) async fn handle_capability_call_finished(
         &self,
+        myself: &ActorRef<ConductorMsg>,
         state: &mut ConductorState,
         run_id: String,
         call_id: String,
@@ -348,6 +350,193 @@ impl ConductorActor {
                 )
                 .await;
             }
+            Ok(CapabilityWorkerOutput::WriterRun(output)) => {
+                let (call_status, agenda_status) = match output.status {
+                    shared_types::WriterRunStatusKind::Completed => (
+                        shared_types::CapabilityCallStatus::Completed,
+                        shared_types::AgendaItemStatus::Completed,
+                    ),
+                    shared_types::WriterRunStatusKind::Blocked => (
+                        shared_types::CapabilityCallStatus::Blocked,
+                        shared_types::AgendaItemStatus::Blocked,
+                    ),
+                    _ => (
+                        shared_types::CapabilityCallStatus::Failed,
+                        shared_types::AgendaItemStatus::Failed,
+                    ),
+                };
+                let completed = output.status == shared_types::WriterRunStatusKind::Completed;
+                state
+                    .tasks
+                    .update_capability_call(
+                        &run_id,
+                        &call_id,
+                        call_status,
+                        (!completed).then(|| output.summary.clone()),
+                    )
+                    .map_err(|e| ActorProcessingErr::from(e.to_string()))?;
+                state
+                    .tasks
+                    .update_agenda_item(&run_id, &agenda_item_id, agenda_status)
+                    .map_err(|e| ActorProcessingErr::from(e.to_string()))?;
+
+                // Keyed by the writer run id so the call's artifact_ids name it.
+                let artifact = shared_types::ConductorArtifact {
+                    artifact_id: output.writer_run_id.clone(),
+                    kind: shared_types::ArtifactKind::Report,
+                    reference: output.document_path.clone(),
+                    mime_type: Some("text/markdown".to_string()),
+                    created_at: chrono::Utc::now(),
+                    source_call_id: call_id.clone(),
+                    metadata: Some(serde_json::json!({
+                        "capability": "writer",
+                        "writer_run_id": output.writer_run_id,
+                        "status": output.status,
+                        "summary": output.summary,
+                        "revision": output.revision,
+                        "cited_urls": output.cited_urls,
+                    })),
+                };
+                state
+                    .tasks
+                    .add_artifact(&run_id, artifact)
+                    .map_err(|e| ActorProcessingErr::from(e.to_string()))?;
+
+                match output.status {
+                    shared_types::WriterRunStatusKind::Completed => {
+                        events::emit_capability_completed(
+                            &state.event_store,
+                            &run_id,
+                            &call_id,
+                            &capability,
+                            &output.summary,
+                        )
+                        .await;
+                    }
+                    shared_types::WriterRunStatusKind::Blocked => {
+                        events::emit_capability_blocked(
+                            &state.event_store,
+                            &run_id,
+                            &call_id,
+                            &capability,
+                            &output.summary,
+                        )
+                        .await;
+                    }
+                    _ => {
+                        events::emit_capability_failed(
+                            &state.event_store,
+                            &run_id,
+                            &call_id,
+                            &capability,
+                            &output.summary,
+                            Some(shared_types::FailureKind::Unknown),
+                        )
+                        .await;
+                    }
+                }
+                events::emit_worker_result(
+                    &state.event_store,
+                    &run_id,
+                    "writer",
+                    completed,
+                    &output.summary,
+                )
+                .await;
+            }
+            Ok(CapabilityWorkerOutput::Capability(output)) => {
+                if output.success {
+                    state
+                        .tasks
+                        .update_capability_call(
+                            &run_id,
+                            &call_id,
+                            shared_types::CapabilityCallStatus::Completed,
+                            None,
+                        )
+                        .map_err(|e| ActorProcessingErr::from(e.to_string()))?;
+                    state
+                        .tasks
+                        .update_agenda_item(
+                            &run_id,
+                            &agenda_item_id,
+                            shared_types::AgendaItemStatus::Completed,
+                        )
+                        .map_err(|e| ActorProcessingErr::from(e.to_string()))?;
+
+                    let artifact = shared_types::ConductorArtifact {
+                        artifact_id: ulid::Ulid::new().to_string(),
+                        kind: shared_types::ArtifactKind::JsonData,
+                        reference: format!("call://{call_id}"),
+                        mime_type: Some("application/json".to_string()),
+                        created_at: chrono::Utc::now(),
+                        source_call_id: call_id.clone(),
+                        metadata: Some(serde_json::json!({
+                            "capability": capability,
+                            "summary": output.summary,
+                            "details": output.metadata,
+                        })),
+                    };
+                    state
+                        .tasks
+                        .add_artifact(&run_id, artifact)
+                        .map_err(|e| ActorProcessingErr::from(e.to_string()))?;
+
+                    events::emit_capability_completed(
+                        &state.event_store,
+                        &run_id,
+                        &call_id,
+                        &capability,
+                        &output.summary,
+                    )
+                    .await;
+                    events::emit_worker_result(
+                        &state.event_store,
+                        &run_id,
+                        &capability,
+                        true,
+                        &output.summary,
+                    )
+                    .await;
+                } else {
+                    let err = output.summary.clone();
+                    state
+                        .tasks
+                        .update_capability_call(
+                            &run_id,
+                            &call_id,
+                            shared_types::CapabilityCallStatus::Failed,
+                            Some(err.clone()),
+                        )
+                        .map_err(|e| ActorProcessingErr::from(e.to_string()))?;
+                    state
+                        .tasks
+                        .update_agenda_item(
+                            &run_id,
+                            &agenda_item_id,
+                            shared_types::AgendaItemStatus::Failed,
+                        )
+                        .map_err(|e| ActorProcessingErr::from(e.to_string()))?;
+
+                    events::emit_capability_failed(
+                        &state.event_store,
+                        &run_id,
+                        &call_id,
+                        &capability,
+                        &err,
+                        Some(shared_types::FailureKind::Unknown),
+                    )
+                    .await;
+                    events::emit_worker_result(
+                        &state.event_store,
+                        &run_id,
+                        &capability,
+                        false,
+                        &err,
+                    )
+                    .await;
+                }
+            }
             Ok(CapabilityWorkerOutput::Harness(_)) => {
                 let err = "Harness capability output is not implemented yet".to_string();
                 state
@@ -436,6 +625,8 @@ impl ConductorActor {
             }
         }
 
+        // Items that depended on this call may be ready now.
+        self.dispatch_seed_agenda(myself, state, &run_id).await?;
         self.finalize_run_if_quiescent(state, &run_id).await?;
         Ok(())
     }
@@ -450,6 +641,16 @@ impl ConductorActor {
             let _ = state
                 .tasks
                 .transition_run_status(run_id, shared_types::ConductorRunStatus::WaitingForCalls);
+            if let Some(percent) = state.tasks.get_run(run_id).map(run_progress_pct) {
+                events::emit_progress(
+                    &state.event_store,
+                    run_id,
+                    "conductor",
+                    "agenda item settled",
+                    Some(percent),
+                )
+                .await;
+            }
             return Ok(());
         }
 
@@ -68,6 +68,7 @@ pub struct ConductorHarnessAdapter {
     objective: String,
     available_capabilities: Vec<String>,
     memory_context: Option<String>,
+    preference_context: Option<String>,
 }
 
 impl ConductorHarnessAdapter {
@@ -75,11 +76,13 @@ impl ConductorHarnessAdapter {
         objective: String,
         available_capabilities: Vec<String>,
         memory_context: Option<String>,
+        preference_context: Option<String>,
     ) -> Self {
         Self {
             objective,
             available_capabilities,
             memory_context,
+            preference_context,
         }
     }
 }
@@ -113,12 +116,19 @@ impl WorkerPort for ConductorHarnessAdapter {
             .filter(|s| !s.trim().is_empty())
             .map(|ctx| format!("\n\nRetrieved memory context (relevance-ranked):\n{ctx}"))
             .unwrap_or_default();
+        let preference_section = self
+            .preference_context
+            .as_deref()
+            .filter(|s| !s.trim().is_empty())
+            .map(|ctx| format!("\n\n{ctx}"))
+            .unwrap_or_default();
 
         format!(
-            "{routing_guidance}\nAvailable capabilities: {caps_list}{memory_section}\n\nObjective:\n{objective}",
+            "{routing_guidance}\nAvailable capabilities: {caps_list}{memory_section}{preference_section}\n\nObjective:\n{objective}",
             routing_guidance = CONDUCTOR_ROUTING_GUIDANCE,
             caps_list = caps_list,
             memory_section = memory_section,
+            preference_section = preference_section,
             objective = self.objective,
         )
     }
@@ -244,6 +254,7 @@ mod tests {
             "test objective".to_string(),
             vec!["writer".to_string()],
             None,
+            None,
         );
         let allowed = adapter.allowed_tool_names();
         assert_eq!(allowed, Some(["finished"].as_ref()));
@@ -251,7 +262,7 @@ mod tests {
 
     #[test]
     fn test_conductor_adapter_model_role() {
-        let adapter = ConductorHarnessAdapter::new("obj".to_string(), vec![], None);
+        let adapter = ConductorHarnessAdapter::new("obj".to_string(), vec![], None, None);
         assert_eq!(adapter.get_model_role(), "conductor");
     }
 
@@ -261,6 +272,7 @@ mod tests {
             "write a report".to_string(),
             vec!["writer".to_string()],
             None,
+            None,
         );
         let ctx = ExecutionContext {
             loop_id: "loop1".to_string(),
@@ -284,6 +296,7 @@ mod tests {
             "obj".to_string(),
             vec!["writer".to_string()],
             Some("prior run summary".to_string()),
+            None,
         );
         let ctx = ExecutionContext {
             loop_id: "l".to_string(),
@@ -299,4 +312,30 @@ mod tests {
         let sys = adapter.get_system_context(&ctx);
         assert!(sys.contains("prior run summary"));
     }
+
+    #[test]
+    fn test_conductor_adapter_system_context_includes_preferences_before_objective() {
+        let adapter = ConductorHarnessAdapter::new(
+            "obj".to_string(),
+            vec!["writer".to_string()],
+            None,
+            Some("Learned user preferences:\n- package_manager: Uses pnpm".to_string()),
+        );
+        let ctx = ExecutionContext {
+            loop_id: "l".to_string(),
+            worker_id: "c".to_string(),
+            user_id: "s".to_string(),
+            step_number: 1,
+            max_steps: 10,
+            model_used: "m".to_string(),
+            objective: "obj".to_string(),
+            run_id: None,
+            call_id: None,
+        };
+        let sys = adapter.get_system_context(&ctx);
+        let prefs_at = sys
+            .find("- package_manager: Uses pnpm")
+            .expect("preferences");
+        assert!(prefs_at < sys.find("Objective:").expect("objective"));
+    }
 }
@@ -2,9 +2,11 @@ use ractor::{Actor, ActorProcessingErr, ActorRef};
 
 use crate::actors::conductor::actor::{ConductorActor, ConductorState};
 use crate::actors::conductor::{
+    capability::WriterHandoff,
     events,
     protocol::{ConductorError, ConductorMsg},
     runtime::capability_call::{CapabilityCallActor, CapabilityCallArguments},
+    state::run_progress_pct,
 };
 use crate::actors::harness_actor::{HarnessActor, HarnessArguments, HarnessMsg};
 use crate::actors::writer::SectionState;
@@ -117,7 +119,7 @@ impl ConductorActor {
                 ))
             })?;
 
-        if state.tasks.get_run(run_id).is_some() {
+        if let Some(percent) = state.tasks.get_run(run_id).map(run_progress_pct) {
             events::emit_worker_call(
                 &state.event_store,
                 run_id,
@@ -130,7 +132,7 @@ impl ConductorActor {
                 run_id,
                 &item.capability,
                 "capability dispatched",
-                None,
+                Some(percent),
             )
             .await;
         }
@@ -146,6 +148,16 @@ impl ConductorActor {
             .await
         {
             Ok(actor) => Some(actor),
+            // Registered capabilities run without a writer; progress mirroring is skipped.
+            Err(error) if state.capabilities.contains(&capability) => {
+                tracing::warn!(
+                    run_id = %run_id_owned,
+                    capability = %capability,
+                    error = %error,
+                    "Writer unavailable; capability call runs without progress mirroring"
+                );
+                None
+            }
             Err(error) => {
                 let _ = myself.send_message(ConductorMsg::CapabilityCallFinished {
                     run_id: run_id_owned,
@@ -171,15 +183,37 @@ impl ConductorActor {
             });
         }
 
+        // A writer call inherits what the run's earlier calls produced.
+        let writer_handoff = (capability == "writer")
+            .then(|| {
+                state
+                    .tasks
+                    .get_run(&run_id_owned)
+                    .map(WriterHandoff::from_run)
+            })
+            .flatten();
+
         let args = CapabilityCallArguments {
             conductor_ref,
             model_gateway: state.model_gateway.clone(),
+            capabilities: state.capabilities.clone(),
             writer_actor: writer,
             run_id: run_id_owned.clone(),
             call_id: call_id_owned.clone(),
             agenda_item_id: agenda_item_id.clone(),
             capability: capability.clone(),
             objective,
+            writer_handoff,
+            contract: state.tasks.get_run(&run_id_owned).and_then(|run| {
+                run.contract.as_ref().map(|contract| {
+                    contract.child_for_agenda_item(
+                        &item.item_id,
+                        &item.capability,
+                        &item.objective,
+                        run.agenda.len(),
+                    )
+                })
+            }),
         };
 
         match Actor::spawn(
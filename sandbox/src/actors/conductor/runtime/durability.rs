@@ -6,7 +6,10 @@
 
 use crate::actors::conductor::actor::{ConductorActor, ConductorState};
 use crate::actors::event_store::get_recent_events;
-use shared_types::{ConductorOutputMode, ConductorRunState, ConductorRunStatus};
+use crate::paths::normalize_document_path;
+use shared_types::{
+    ConductorOutputMode, ConductorRunState, ConductorRunStatus, EventTopic, ObjectiveContract,
+};
 use std::collections::HashMap;
 
 /// Maximum number of conductor lifecycle events to load for recovery.
@@ -28,20 +31,27 @@ fn payload_output_mode(payload: &serde_json::Value) -> Option<ConductorOutputMod
         .and_then(|value| serde_json::from_value::<ConductorOutputMode>(value).ok())
 }
 
+fn payload_contract(payload: &serde_json::Value) -> Option<ObjectiveContract> {
+    payload_field(payload, "contract")
+        .cloned()
+        .and_then(|value| serde_json::from_value::<ObjectiveContract>(value).ok())
+}
+
 fn map_status_from_event(
     event_type: &str,
     payload: &serde_json::Value,
 ) -> Option<ConductorRunStatus> {
-    match event_type {
-        "conductor.run.started" => Some(ConductorRunStatus::Running),
-        "conductor.task.started" => Some(ConductorRunStatus::Running),
-        "conductor.task.progress" => {
+    match EventTopic::from(event_type) {
+        EventTopic::ConductorRunStarted => Some(ConductorRunStatus::Running),
+        EventTopic::ConductorTaskStarted => Some(ConductorRunStatus::Running),
+        EventTopic::ConductorTaskProgress => {
             let raw = payload_string(payload, "status")?;
             match raw.as_str() {
                 "running" => Some(ConductorRunStatus::Running),
                 "waiting_worker" | "waiting_calls" | "waiting_for_calls" => {
                     Some(ConductorRunStatus::WaitingForCalls)
                 }
+                "waiting_for_provider" => Some(ConductorRunStatus::WaitingForProvider),
                 "completing" => Some(ConductorRunStatus::Completing),
                 "completed" => Some(ConductorRunStatus::Completed),
                 "failed" => Some(ConductorRunStatus::Failed),
@@ -49,10 +59,10 @@ fn map_status_from_event(
                 _ => Some(ConductorRunStatus::Running),
             }
         }
-        "conductor.task.completed" => Some(ConductorRunStatus::Completed),
-        "conductor.task.failed" => {
+        EventTopic::ConductorTaskCompleted => Some(ConductorRunStatus::Completed),
+        EventTopic::ConductorTaskFailed => {
             let error_code = payload_string(payload, "error_code").unwrap_or_default();
-            if error_code == "RUN_BLOCKED" {
+            if error_code == shared_types::ErrorCode::RunBlocked.as_str() {
                 Some(ConductorRunStatus::Blocked)
             } else {
                 Some(ConductorRunStatus::Failed)
@@ -96,7 +106,7 @@ impl ConductorActor {
 
         for event in events {
             if !event.event_type.starts_with("conductor.task.")
-                && event.event_type != "conductor.run.started"
+                && event.event_type != shared_types::EVENT_TOPIC_CONDUCTOR_RUN_STARTED
             {
                 continue;
             }
@@ -126,6 +136,8 @@ impl ConductorActor {
                     document_path: format!("conductor/runs/{run_id}/draft.md"),
                     output_mode: ConductorOutputMode::Auto,
                     desktop_id: "unknown".to_string(),
+                    revision: 0,
+                    contract: None,
                 });
 
             if let Some(objective) = payload_string(&event.payload, "objective") {
@@ -141,8 +153,11 @@ impl ConductorActor {
             if let Some(output_mode) = payload_output_mode(&event.payload) {
                 run.output_mode = output_mode;
             }
+            if let Some(contract) = payload_contract(&event.payload) {
+                run.contract = Some(contract);
+            }
             if let Some(document_path) = payload_string(&event.payload, "document_path") {
-                if !document_path.trim().is_empty() {
+                if let Ok(document_path) = normalize_document_path(&document_path) {
                     run.document_path = document_path;
                 }
             }
@@ -169,6 +184,7 @@ impl ConductorActor {
                 ConductorRunStatus::Initializing
                     | ConductorRunStatus::Running
                     | ConductorRunStatus::WaitingForCalls
+                    | ConductorRunStatus::WaitingForProvider
                     | ConductorRunStatus::Completing
             ) {
                 run.status = ConductorRunStatus::Blocked;
@@ -210,6 +226,7 @@ mod tests {
                 payload,
                 actor_id: "conductor:test".to_string(),
                 user_id: "system".to_string(),
+                idempotency_key: None,
             },
             reply,
         })
@@ -252,6 +269,7 @@ mod tests {
                 event_store: event_store.clone(),
                 writer_supervisor: None,
                 memory_actor: None,
+                capabilities: Default::default(),
             },
         )
         .await
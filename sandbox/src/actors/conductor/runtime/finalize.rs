@@ -31,10 +31,7 @@ impl ConductorActor {
         let selected_mode = resolve_output_mode(run.output_mode, &output);
         let toast = build_completion_toast(selected_mode, &output, &report_path);
 
-        if let Some(run_state) = state.tasks.get_run_mut(run_id) {
-            run_state.output_mode = selected_mode;
-            run_state.updated_at = chrono::Utc::now();
-        }
+        state.tasks.set_output_mode(run_id, selected_mode)?;
 
         let writer_props =
             if selected_mode == shared_types::ConductorOutputMode::MarkdownReportToWriter {
@@ -66,6 +63,7 @@ impl ConductorActor {
                 reply,
             });
         }
+        self.release_run_writer(state, run_id);
 
         Ok(())
     }
@@ -84,14 +82,14 @@ impl ConductorActor {
         let message =
             reason.unwrap_or_else(|| "Run blocked by conductor model gateway".to_string());
         let shared_error = shared_types::ConductorError {
-            code: "RUN_BLOCKED".to_string(),
+            code: shared_types::ErrorCode::RunBlocked,
             message: message.clone(),
             failure_kind: Some(shared_types::FailureKind::Unknown),
         };
         events::emit_task_failed(
             &state.event_store,
             &run.run_id,
-            &shared_error.code,
+            shared_error.code.as_str(),
             &shared_error.message,
             shared_error.failure_kind,
         )
@@ -104,6 +102,7 @@ impl ConductorActor {
                 reply,
             });
         }
+        self.release_run_writer(state, run_id);
         Ok(())
     }
 
@@ -190,6 +190,7 @@ impl ConductorActor {
                 }),
                 actor_id: format!("conductor:{run_id}"),
                 user_id: "system".to_string(),
+                idempotency_key: None,
             },
         });
     }
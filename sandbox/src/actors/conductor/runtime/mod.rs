@@ -5,4 +5,5 @@ pub(crate) mod decision;
 pub(crate) mod durability;
 pub(crate) mod finalize;
 pub(crate) mod harness;
+pub(crate) mod refine;
 pub(crate) mod start_run;
@@ -0,0 +1,186 @@
+//! Objective refinement ahead of a run.
+//!
+//! A vague objective ("make the report better") is restated by the model
+//! gateway with success criteria, evidence expectations and a few clarifying
+//! questions. The proposal comes back as an `ObjectiveContract` the user can
+//! answer and edit before submitting it with the execute request; the run is
+//! then held to the contract's budget and capability whitelist.
+
+use shared_types::{
+    ConductorRefineRequest, ConductorRefineResponse, EvidenceRequirements, ObjectiveConstraints,
+    ObjectiveContract, OBJECTIVE_CONTRACT_MAX_CRITERIA, OBJECTIVE_CONTRACT_MAX_TIMEOUT_MS,
+    OBJECTIVE_CONTRACT_MAX_TOOL_CALLS, OBJECTIVE_CONTRACT_MIN_TIMEOUT_MS,
+};
+
+use crate::actors::conductor::model_gateway::ConductorModelGateway;
+use crate::actors::conductor::protocol::ConductorError;
+use crate::baml_client::types::ConductorObjectiveRefineOutput;
+
+/// Upper bound on the refinement model call.
+const REFINE_TIMEOUT_SECS: u64 = 30;
+
+/// Questions shown to the user at most; the prompt asks for up to three.
+const MAX_QUESTIONS: usize = 3;
+
+/// Tool calls granted per estimated step.
+const TOOL_CALLS_PER_STEP: u32 = 10;
+
+/// Wall-clock budget granted per estimated step.
+const TIMEOUT_MS_PER_STEP: u64 = 60_000;
+
+/// Confidence evidence must reach unless the user raises it.
+const DEFAULT_MIN_CONFIDENCE: f64 = 0.6;
+
+/// Ask the model gateway for a contract and questions for `request`.
+pub(crate) async fn refine_objective(
+    model_gateway: &dyn ConductorModelGateway,
+    request: ConductorRefineRequest,
+) -> Result<ConductorRefineResponse, ConductorError> {
+    if request.objective.trim().is_empty() {
+        return Err(ConductorError::InvalidRequest(
+            "Objective cannot be empty".to_string(),
+        ));
+    }
+
+    let output = tokio::time::timeout(
+        std::time::Duration::from_secs(REFINE_TIMEOUT_SECS),
+        model_gateway.refine_objective(&request.objective, &request.desktop_context),
+    )
+    .await
+    .map_err(|_| {
+        ConductorError::ModelGatewayError(format!(
+            "Objective refinement timed out after {REFINE_TIMEOUT_SECS}s"
+        ))
+    })??;
+
+    let contract =
+        contract_from_refinement(ulid::Ulid::new().to_string(), &request.objective, &output);
+    contract.validate().map_err(|reason| {
+        ConductorError::ModelGatewayError(format!(
+            "Refinement produced an invalid contract: {reason}"
+        ))
+    })?;
+
+    let questions = output
+        .clarifying_questions
+        .iter()
+        .map(|question| question.trim())
+        .filter(|question| !question.is_empty())
+        .take(MAX_QUESTIONS)
+        .map(ToString::to_string)
+        .collect();
+
+    Ok(ConductorRefineResponse {
+        questions,
+        contract,
+    })
+}
+
+/// Turn a refinement into a contract, sizing the budget from the step
+/// estimate and falling back to the raw objective where the model left
+/// fields blank.
+pub(crate) fn contract_from_refinement(
+    objective_id: String,
+    raw_objective: &str,
+    output: &ConductorObjectiveRefineOutput,
+) -> ObjectiveContract {
+    let primary_objective = match output.refined_objective.trim() {
+        "" => raw_objective.trim().to_string(),
+        refined => refined.to_string(),
+    };
+    let mut success_criteria: Vec<String> = output
+        .success_criteria
+        .iter()
+        .map(|criterion| criterion.trim())
+        .filter(|criterion| !criterion.is_empty())
+        .take(OBJECTIVE_CONTRACT_MAX_CRITERIA)
+        .map(ToString::to_string)
+        .collect();
+    if success_criteria.is_empty() {
+        success_criteria.push(format!("The result addresses: {}", raw_objective.trim()));
+    }
+
+    let steps = output.estimated_steps.clamp(1, 10) as u32;
+    ObjectiveContract {
+        objective_id,
+        parent_objective_id: None,
+        primary_objective,
+        success_criteria,
+        constraints: ObjectiveConstraints {
+            max_tool_calls: (steps * TOOL_CALLS_PER_STEP).min(OBJECTIVE_CONTRACT_MAX_TOOL_CALLS),
+            timeout_ms: (u64::from(steps) * TIMEOUT_MS_PER_STEP).clamp(
+                OBJECTIVE_CONTRACT_MIN_TIMEOUT_MS,
+                OBJECTIVE_CONTRACT_MAX_TIMEOUT_MS,
+            ),
+            max_subframe_depth: 2,
+            allowed_capabilities: Vec::new(),
+        },
+        attempts_budget: 2,
+        evidence_requirements: EvidenceRequirements {
+            requires_citations: output.requires_citations,
+            min_confidence: DEFAULT_MIN_CONFIDENCE,
+            required_source_types: output
+                .required_source_types
+                .iter()
+                .map(|source| source.trim().to_string())
+                .filter(|source| !source.is_empty())
+                .collect(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn refinement(estimated_steps: i64) -> ConductorObjectiveRefineOutput {
+        ConductorObjectiveRefineOutput {
+            refined_objective: "Tighten the Q3 report's executive summary".to_string(),
+            success_criteria: vec![
+                "Summary fits in 150 words".to_string(),
+                "  ".to_string(),
+                "Every figure matches the appendix".to_string(),
+            ],
+            estimated_steps,
+            confidence: 0.7,
+            clarifying_questions: vec!["Which report?".to_string()],
+            requires_citations: true,
+            required_source_types: vec!["documentation".to_string(), String::new()],
+        }
+    }
+
+    #[test]
+    fn test_contract_from_refinement_sizes_budget_from_steps() {
+        let contract = contract_from_refinement(
+            "obj-1".to_string(),
+            "make the report better",
+            &refinement(3),
+        );
+
+        assert_eq!(contract.success_criteria.len(), 2);
+        assert_eq!(contract.constraints.max_tool_calls, 30);
+        assert_eq!(contract.constraints.timeout_ms, 180_000);
+        assert_eq!(
+            contract.evidence_requirements.required_source_types,
+            vec!["documentation"]
+        );
+        assert!(contract.validate().is_ok());
+    }
+
+    #[test]
+    fn test_contract_from_refinement_falls_back_to_raw_objective() {
+        let mut output = refinement(0);
+        output.refined_objective = " ".to_string();
+        output.success_criteria.clear();
+
+        let contract = contract_from_refinement("obj-2".to_string(), "make it better", &output);
+
+        assert_eq!(contract.primary_objective, "make it better");
+        assert_eq!(
+            contract.success_criteria,
+            vec!["The result addresses: make it better"]
+        );
+        assert_eq!(contract.constraints.max_tool_calls, TOOL_CALLS_PER_STEP);
+        assert!(contract.validate().is_ok());
+    }
+}
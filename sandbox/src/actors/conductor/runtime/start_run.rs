@@ -13,6 +13,7 @@ use crate::actors::conductor::{
 use crate::actors::model_config::ModelRegistry;
 use crate::actors::writer::WriterMsg;
 use crate::observability::llm_trace::LlmTraceEmitter;
+use crate::supervisor::writer::WriterDocumentHolder;
 
 impl ConductorActor {
     fn run_document_path(run_id: &str) -> String {
@@ -26,7 +27,13 @@ impl ConductorActor {
         desktop_id: &str,
         objective: &str,
     ) -> Result<(), ConductorError> {
-        let writer_actor = self.resolve_writer_actor_for_run(state, run_id).await?;
+        let writer_actor = self
+            .run_writer(
+                state,
+                run_id,
+                Some(WriterDocumentHolder::Run(run_id.to_string())),
+            )
+            .await?;
         ractor::call!(writer_actor, |reply| WriterMsg::EnsureRunDocument {
             run_id: run_id.to_string(),
             desktop_id: desktop_id.to_string(),
@@ -64,6 +71,12 @@ impl ConductorActor {
         state: &mut ConductorState,
         request: ConductorExecuteRequest,
     ) -> Result<shared_types::ConductorRunState, ConductorError> {
+        if let Some(contract) = &request.contract {
+            contract.validate().map_err(|reason| {
+                ConductorError::InvalidRequest(format!("Invalid objective contract: {reason}"))
+            })?;
+        }
+
         let run_id = ulid::Ulid::new().to_string();
 
         tracing::info!(
@@ -87,6 +100,7 @@ impl ConductorActor {
             &run_id,
             &request.objective,
             &request.desktop_id,
+            request.contract.as_ref(),
         )
         .await;
 
@@ -94,10 +108,17 @@ impl ConductorActor {
         self.ensure_run_document_for_run(state, &run_id, &request.desktop_id, &request.objective)
             .await?;
 
+        // Starting a run while the provider gateway is down would only burn
+        // its timeout budget; accept it and start it on recovery instead.
+        let wait_for_provider = !state.provider_gateway_reachable;
         let run = shared_types::ConductorRunState {
             run_id: run_id.clone(),
             objective: request.objective.clone(),
-            status: shared_types::ConductorRunStatus::Running,
+            status: if wait_for_provider {
+                shared_types::ConductorRunStatus::WaitingForProvider
+            } else {
+                shared_types::ConductorRunStatus::Running
+            },
             created_at: now,
             updated_at: now,
             completed_at: None,
@@ -108,9 +129,31 @@ impl ConductorActor {
             document_path,
             output_mode: request.output_mode,
             desktop_id: request.desktop_id.clone(),
+            revision: 0,
+            contract: request.contract.clone(),
         };
         state.tasks.insert_run(run.clone());
 
+        if wait_for_provider {
+            events::emit_task_progress(
+                &state.event_store,
+                &run_id,
+                "waiting_for_provider",
+                "provider_gateway",
+                Some(serde_json::json!({
+                    "run_id": &run_id,
+                    "queue_len": state.waiting_for_provider.len() + 1,
+                })),
+            )
+            .await;
+            tracing::warn!(
+                run_id = %run_id,
+                "Provider gateway unreachable; queued run until it recovers"
+            );
+            state.waiting_for_provider.push((run_id, request));
+            return Ok(run);
+        }
+
         events::emit_task_progress(
             &state.event_store,
             &run_id,
@@ -131,6 +174,42 @@ impl ConductorActor {
         Ok(run)
     }
 
+    /// Record provider gateway reachability. On recovery every run queued as
+    /// `WaitingForProvider` is started, oldest first.
+    pub(crate) async fn handle_provider_gateway_changed(
+        &self,
+        myself: &ActorRef<ConductorMsg>,
+        state: &mut ConductorState,
+        reachable: bool,
+    ) {
+        state.provider_gateway_reachable = reachable;
+        if !reachable {
+            return;
+        }
+
+        for (run_id, request) in std::mem::take(&mut state.waiting_for_provider) {
+            if let Err(err) = state
+                .tasks
+                .transition_run_status(&run_id, shared_types::ConductorRunStatus::Running)
+            {
+                tracing::warn!(run_id = %run_id, error = %err, "Dropping queued run");
+                continue;
+            }
+            events::emit_task_progress(
+                &state.event_store,
+                &run_id,
+                "running",
+                "run_start",
+                Some(serde_json::json!({
+                    "run_id": &run_id,
+                    "resumed_after": "provider_gateway_recovered",
+                })),
+            )
+            .await;
+            let _ = myself.send_message(ConductorMsg::StartRun { run_id, request });
+        }
+    }
+
     pub(crate) async fn handle_start_run(
         &self,
         myself: &ActorRef<ConductorMsg>,
@@ -153,7 +232,7 @@ impl ConductorActor {
                     created_at: chrono::Utc::now(),
                     source_call_id: "event".to_string(),
                     metadata: Some(serde_json::json!({
-                        "event_type": "conductor.task.failed",
+                        "event_type": shared_types::EVENT_TOPIC_CONDUCTOR_TASK_FAILED,
                         "event_payload": {
                             "error_code": shared_error.code,
                             "error_message": shared_error.message,
@@ -168,11 +247,12 @@ impl ConductorActor {
                 events::emit_task_failed(
                     &state.event_store,
                     &run_id,
-                    &shared_error.code,
+                    shared_error.code.as_str(),
                     &shared_error.message,
                     shared_error.failure_kind,
                 )
                 .await;
+                self.release_run_writer(state, &run_id);
                 tracing::error!(
                     run_id = %run_id,
                     error = %err,
@@ -200,7 +280,7 @@ impl ConductorActor {
 
         events::emit_control_event(
             &state.event_store,
-            "conductor.run.started",
+            shared_types::EVENT_TOPIC_CONDUCTOR_RUN_STARTED,
             &run_id,
             "conductor",
             "run_start",
@@ -234,6 +314,20 @@ impl ConductorActor {
                 "No app-agent capabilities available for Conductor model gateway".to_string(),
             ));
         }
+        if let Some(contract) = &request.contract {
+            available_capabilities.retain(|capability| contract.allows_capability(capability));
+            if available_capabilities.is_empty() {
+                return Err(ConductorError::InvalidRequest(
+                    "Objective contract allows none of the available capabilities".to_string(),
+                ));
+            }
+        }
+        // Workers see the agreed success criteria, not just the raw prompt.
+        let brief = request
+            .contract
+            .as_ref()
+            .map(|contract| contract.brief())
+            .unwrap_or_else(|| request.objective.clone());
 
         // Phase 5.4 — retrieve context snapshot from MemoryActor.
         // Prepend top context items to the objective so the model has retrieval-grounded context.
@@ -274,6 +368,19 @@ impl ConductorActor {
             None
         };
 
+        // Learned user preferences ride along with memory, same 500ms budget.
+        let preference_context = tokio::time::timeout(
+            std::time::Duration::from_millis(500),
+            crate::preferences::load(
+                &state.event_store,
+                crate::preferences::DEFAULT_PREFERENCE_USER,
+            ),
+        )
+        .await
+        .ok()
+        .and_then(Result::ok)
+        .and_then(|prefs| prefs.context_block());
+
         // Phase 4.3 — Run the conductor harness turn.
         //
         // The conductor runs a brief AgentHarness (HarnessProfile::Conductor,
@@ -285,9 +392,10 @@ impl ConductorActor {
             .run_conductor_harness_turn(
                 state,
                 run_id,
-                &request.objective,
+                &brief,
                 &available_capabilities,
                 memory_context.clone(),
+                preference_context,
             )
             .await
             .ok_or_else(|| {
@@ -336,8 +444,7 @@ impl ConductorActor {
         }
 
         for (idx, capability) in selected_capabilities.into_iter().enumerate() {
-            let objective =
-                self.objective_with_capability_contract(&capability, request.objective.clone());
+            let objective = self.objective_with_capability_contract(&capability, brief.clone());
             items.push(shared_types::ConductorAgendaItem {
                 item_id: format!("{run_id}:seed:{idx}:{capability}"),
                 capability,
@@ -365,6 +472,7 @@ impl ConductorActor {
         objective: &str,
         available_capabilities: &[String],
         memory_context: Option<String>,
+        preference_context: Option<String>,
     ) -> Option<ConductorRoutingDecision> {
         let model_registry = ModelRegistry::new();
         let trace_emitter = LlmTraceEmitter::new(state.event_store.clone());
@@ -374,6 +482,7 @@ impl ConductorActor {
             objective.to_string(),
             available_capabilities.to_vec(),
             memory_context,
+            preference_context,
         );
 
         let harness = AgentHarness::with_config(adapter, model_registry, config, trace_emitter);
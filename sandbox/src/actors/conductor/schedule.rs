@@ -0,0 +1,227 @@
+//! Agenda dependency scheduling.
+//!
+//! [`schedule`] moves `Pending` agenda items to `Ready` once every item they
+//! depend on is `Completed`, and returns the ready items in dispatch order:
+//! lowest `priority` first (0 is highest), then oldest, then agenda order.
+//! A dependency cycle would leave its items pending forever, so it is
+//! reported as an error and nothing is changed.
+//!
+//! A `depends_on` id that is not on the agenda is never satisfied; the item
+//! waits until an item with that id is added and completes.
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use shared_types::{AgendaItemStatus, ConductorAgendaItem};
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ScheduleError {
+    /// The item ids along the cycle, starting and ending with the same id.
+    #[error("agenda dependency cycle: {}", .0.join(" -> "))]
+    Cycle(Vec<String>),
+}
+
+/// Mark items whose dependencies have completed as `Ready` and return the
+/// ids of all `Ready` items in dispatch order.
+pub fn schedule(agenda: &mut [ConductorAgendaItem]) -> Result<Vec<String>, ScheduleError> {
+    if let Some(cycle) = find_cycle(agenda) {
+        return Err(ScheduleError::Cycle(cycle));
+    }
+
+    let completed: HashSet<String> = agenda
+        .iter()
+        .filter(|item| item.status == AgendaItemStatus::Completed)
+        .map(|item| item.item_id.clone())
+        .collect();
+    for item in agenda.iter_mut() {
+        if item.status == AgendaItemStatus::Pending
+            && item.depends_on.iter().all(|dep| completed.contains(dep))
+        {
+            item.status = AgendaItemStatus::Ready;
+        }
+    }
+
+    let mut ready: Vec<&ConductorAgendaItem> = agenda
+        .iter()
+        .filter(|item| item.status == AgendaItemStatus::Ready)
+        .collect();
+    ready.sort_by(|a, b| dispatch_order(a, b));
+    Ok(ready.into_iter().map(|item| item.item_id.clone()).collect())
+}
+
+/// Order in which ready items are dispatched. The sort using it must be
+/// stable so that agenda order breaks the remaining ties.
+pub(crate) fn dispatch_order(a: &ConductorAgendaItem, b: &ConductorAgendaItem) -> Ordering {
+    a.priority
+        .cmp(&b.priority)
+        .then_with(|| a.created_at.cmp(&b.created_at))
+}
+
+/// The first dependency cycle found, walking items in agenda order.
+fn find_cycle(agenda: &[ConductorAgendaItem]) -> Option<Vec<String>> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Mark {
+        Visiting,
+        Done,
+    }
+
+    fn visit<'a>(
+        id: &'a str,
+        deps: &HashMap<&'a str, &'a [String]>,
+        marks: &mut HashMap<&'a str, Mark>,
+        path: &mut Vec<&'a str>,
+    ) -> Option<Vec<String>> {
+        match marks.get(id) {
+            Some(Mark::Done) => return None,
+            Some(Mark::Visiting) => {
+                let start = path.iter().position(|step| *step == id).unwrap_or(0);
+                let mut cycle: Vec<String> =
+                    path[start..].iter().map(|step| step.to_string()).collect();
+                cycle.push(id.to_string());
+                return Some(cycle);
+            }
+            None => {}
+        }
+        marks.insert(id, Mark::Visiting);
+        path.push(id);
+        for dep in deps.get(id).copied().unwrap_or_default() {
+            if deps.contains_key(dep.as_str()) {
+                if let Some(cycle) = visit(dep, deps, marks, path) {
+                    return Some(cycle);
+                }
+            }
+        }
+        path.pop();
+        marks.insert(id, Mark::Done);
+        None
+    }
+
+    let deps: HashMap<&str, &[String]> = agenda
+        .iter()
+        .map(|item| (item.item_id.as_str(), item.depends_on.as_slice()))
+        .collect();
+    let mut marks = HashMap::new();
+    agenda
+        .iter()
+        .find_map(|item| visit(&item.item_id, &deps, &mut marks, &mut Vec::new()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    fn item(item_id: &str, priority: u8, depends_on: &[&str]) -> ConductorAgendaItem {
+        ConductorAgendaItem {
+            item_id: item_id.to_string(),
+            capability: "researcher".to_string(),
+            objective: format!("objective for {item_id}"),
+            priority,
+            depends_on: depends_on.iter().map(|dep| dep.to_string()).collect(),
+            status: AgendaItemStatus::Pending,
+            created_at: Utc::now(),
+            started_at: None,
+            completed_at: None,
+        }
+    }
+
+    fn complete(agenda: &mut [ConductorAgendaItem], item_id: &str) {
+        let item = agenda
+            .iter_mut()
+            .find(|item| item.item_id == item_id)
+            .unwrap();
+        item.status = AgendaItemStatus::Completed;
+    }
+
+    fn status(agenda: &[ConductorAgendaItem], item_id: &str) -> AgendaItemStatus {
+        agenda
+            .iter()
+            .find(|item| item.item_id == item_id)
+            .unwrap()
+            .status
+    }
+
+    #[test]
+    fn test_diamond_waits_for_both_branches() {
+        // a -> (b, c) -> d
+        let mut agenda = vec![
+            item("a", 0, &[]),
+            item("b", 1, &["a"]),
+            item("c", 0, &["a"]),
+            item("d", 0, &["b", "c"]),
+        ];
+
+        assert_eq!(schedule(&mut agenda).unwrap(), vec!["a"]);
+        assert_eq!(status(&agenda, "b"), AgendaItemStatus::Pending);
+
+        complete(&mut agenda, "a");
+        assert_eq!(schedule(&mut agenda).unwrap(), vec!["c", "b"]);
+
+        complete(&mut agenda, "c");
+        assert_eq!(schedule(&mut agenda).unwrap(), vec!["b"]);
+        assert_eq!(status(&agenda, "d"), AgendaItemStatus::Pending);
+
+        complete(&mut agenda, "b");
+        assert_eq!(schedule(&mut agenda).unwrap(), vec!["d"]);
+    }
+
+    #[test]
+    fn test_cycle_is_an_error_and_changes_nothing() {
+        let mut agenda = vec![
+            item("root", 0, &[]),
+            item("a", 0, &["c"]),
+            item("b", 0, &["a"]),
+            item("c", 0, &["b"]),
+        ];
+
+        let err = schedule(&mut agenda).unwrap_err();
+        assert_eq!(
+            err,
+            ScheduleError::Cycle(vec![
+                "a".to_string(),
+                "c".to_string(),
+                "b".to_string(),
+                "a".to_string()
+            ])
+        );
+        assert_eq!(err.to_string(), "agenda dependency cycle: a -> c -> b -> a");
+        assert!(agenda
+            .iter()
+            .all(|item| item.status == AgendaItemStatus::Pending));
+
+        let mut self_loop = vec![item("solo", 0, &["solo"])];
+        assert!(matches!(
+            schedule(&mut self_loop),
+            Err(ScheduleError::Cycle(cycle)) if cycle == ["solo", "solo"]
+        ));
+    }
+
+    #[test]
+    fn test_priority_then_age_then_agenda_order() {
+        let now = Utc::now();
+        let mut agenda = vec![
+            item("late_high", 0, &[]),
+            item("low", 2, &[]),
+            item("early_high", 0, &[]),
+            item("tie_first", 1, &[]),
+            item("tie_second", 1, &[]),
+        ];
+        agenda[0].created_at = now;
+        agenda[1].created_at = now - Duration::seconds(10);
+        agenda[2].created_at = now - Duration::seconds(5);
+        agenda[3].created_at = now;
+        agenda[4].created_at = now;
+
+        assert_eq!(
+            schedule(&mut agenda).unwrap(),
+            vec!["early_high", "late_high", "tie_first", "tie_second", "low"]
+        );
+    }
+
+    #[test]
+    fn test_unknown_dependency_stays_pending() {
+        let mut agenda = vec![item("a", 0, &["not_on_agenda"])];
+        assert!(schedule(&mut agenda).unwrap().is_empty());
+        assert_eq!(status(&agenda, "a"), AgendaItemStatus::Pending);
+    }
+}
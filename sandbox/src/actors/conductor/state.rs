@@ -3,12 +3,16 @@
 //! Manages the lifecycle of runs, agenda items, capability calls, artifacts, and decisions.
 //! Implements the agentic runtime model with wake/display event lane separation.
 
+use ractor::ActorRef;
 use shared_types::{
     AgendaItemStatus, CapabilityCallStatus, ConductorAgendaItem, ConductorArtifact,
-    ConductorCapabilityCall, ConductorDecision, ConductorRunState, ConductorRunStatus,
+    ConductorCapabilityCall, ConductorDecision, ConductorOutputMode, ConductorRunState,
+    ConductorRunStateDelta, ConductorRunStatus,
 };
 use std::collections::HashMap;
 
+use crate::actors::event_store::EventStoreMsg;
+
 /// State container for ConductorActor - new runtime model
 pub struct ConductorState {
     /// Runtime model: runs indexed by run_id
@@ -16,6 +20,21 @@ pub struct ConductorState {
 
     /// Active capability calls by call_id (for quick lookup)
     active_calls: HashMap<String, (String, ConductorCapabilityCall)>, // call_id -> (run_id, call)
+
+    /// Where run state deltas are published; unpublished when `None`
+    event_store: Option<ActorRef<EventStoreMsg>>,
+}
+
+/// Move `run` to the delta's revision and publish the delta.
+fn publish_delta(
+    event_store: Option<&ActorRef<EventStoreMsg>>,
+    run: &mut ConductorRunState,
+    delta: ConductorRunStateDelta,
+) {
+    run.revision = delta.revision;
+    if let Some(event_store) = event_store {
+        super::events::emit_run_state_delta(event_store, &delta);
+    }
 }
 
 impl ConductorState {
@@ -24,9 +43,16 @@ impl ConductorState {
         Self {
             runs: HashMap::new(),
             active_calls: HashMap::new(),
+            event_store: None,
         }
     }
 
+    /// Publish a `conductor.run.state_delta` event for every run change
+    pub fn with_event_store(mut self, event_store: ActorRef<EventStoreMsg>) -> Self {
+        self.event_store = Some(event_store);
+        self
+    }
+
     // =========================================================================
     // New Runtime Model: Run Management
     // =========================================================================
@@ -78,8 +104,12 @@ impl ConductorState {
             .get_mut(run_id)
             .ok_or_else(|| super::protocol::ConductorError::NotFound(run_id.to_string()))?;
 
-        run.agenda.extend(items);
-        run.updated_at = chrono::Utc::now();
+        let now = chrono::Utc::now();
+        run.agenda.extend(items.iter().cloned());
+        run.updated_at = now;
+        let mut delta = ConductorRunStateDelta::next(run, now);
+        delta.agenda = items;
+        publish_delta(self.event_store.as_ref(), run, delta);
         Ok(())
     }
 
@@ -110,7 +140,11 @@ impl ConductorState {
                 }
                 _ => {}
             }
+            let item = item.clone();
             run.updated_at = now;
+            let mut delta = ConductorRunStateDelta::next(run, now);
+            delta.agenda = vec![item];
+            publish_delta(self.event_store.as_ref(), run, delta);
             Ok(())
         } else {
             Err(super::protocol::ConductorError::NotFound(format!(
@@ -119,19 +153,25 @@ impl ConductorState {
         }
     }
 
-    /// Get agenda items that are ready to run (status == Ready)
+    /// Get agenda items that are ready to run (status == Ready), in
+    /// dispatch order
     pub fn get_ready_agenda_items(&self, run_id: &str) -> Vec<&ConductorAgendaItem> {
         let Some(run) = self.runs.get(run_id) else {
             return Vec::new();
         };
 
-        run.agenda
+        let mut ready: Vec<_> = run
+            .agenda
             .iter()
             .filter(|item| item.status == AgendaItemStatus::Ready)
-            .collect()
+            .collect();
+        ready.sort_by(|a, b| super::schedule::dispatch_order(a, b));
+        ready
     }
 
     /// Mark agenda items as ready when dependencies are satisfied
+    ///
+    /// Fails without changing anything if the agenda has a dependency cycle.
     pub fn update_agenda_item_readiness(
         &mut self,
         run_id: &str,
@@ -141,25 +181,29 @@ impl ConductorState {
             .get_mut(run_id)
             .ok_or_else(|| super::protocol::ConductorError::NotFound(run_id.to_string()))?;
 
-        let completed: std::collections::HashSet<_> = run
+        let pending: std::collections::HashSet<_> = run
             .agenda
             .iter()
-            .filter(|i| matches!(i.status, AgendaItemStatus::Completed))
+            .filter(|i| i.status == AgendaItemStatus::Pending)
             .map(|i| i.item_id.clone())
             .collect();
+        super::schedule::schedule(&mut run.agenda).map_err(|e| {
+            super::protocol::ConductorError::InvalidRequest(format!("run {run_id}: {e}"))
+        })?;
+        let ready: Vec<_> = run
+            .agenda
+            .iter()
+            .filter(|i| i.status == AgendaItemStatus::Ready && pending.contains(&i.item_id))
+            .cloned()
+            .collect();
 
-        let mut updated = 0;
-        for item in run.agenda.iter_mut() {
-            if item.status == AgendaItemStatus::Pending
-                && item.depends_on.iter().all(|dep| completed.contains(dep))
-            {
-                item.status = AgendaItemStatus::Ready;
-                updated += 1;
-            }
-        }
-
+        let updated = ready.len();
         if updated > 0 {
-            run.updated_at = chrono::Utc::now();
+            let now = chrono::Utc::now();
+            run.updated_at = now;
+            let mut delta = ConductorRunStateDelta::next(run, now);
+            delta.agenda = ready;
+            publish_delta(self.event_store.as_ref(), run, delta);
         }
 
         Ok(updated)
@@ -185,8 +229,12 @@ impl ConductorState {
             .insert(call.call_id.clone(), (run_id.to_string(), call.clone()));
 
         // Add to run's active calls
-        run.active_calls.push(call);
-        run.updated_at = chrono::Utc::now();
+        let now = chrono::Utc::now();
+        run.active_calls.push(call.clone());
+        run.updated_at = now;
+        let mut delta = ConductorRunStateDelta::next(run, now);
+        delta.calls = vec![call];
+        publish_delta(self.event_store.as_ref(), run, delta);
 
         Ok(())
     }
@@ -225,7 +273,11 @@ impl ConductorState {
                     .insert(call_id.to_string(), (run_id.to_string(), call.clone()));
             }
 
+            let call = call.clone();
             run.updated_at = now;
+            let mut delta = ConductorRunStateDelta::next(run, now);
+            delta.calls = vec![call];
+            publish_delta(self.event_store.as_ref(), run, delta);
             Ok(())
         } else {
             Err(super::protocol::ConductorError::NotFound(format!(
@@ -297,16 +349,22 @@ impl ConductorState {
             .ok_or_else(|| super::protocol::ConductorError::NotFound(run_id.to_string()))?;
 
         // Link artifact to its source call
-        if let Some(call) = run
+        let source_call = run
             .active_calls
             .iter_mut()
             .find(|c| c.call_id == artifact.source_call_id)
-        {
-            call.artifact_ids.push(artifact.artifact_id.clone());
-        }
+            .map(|call| {
+                call.artifact_ids.push(artifact.artifact_id.clone());
+                call.clone()
+            });
 
-        run.artifacts.push(artifact);
-        run.updated_at = chrono::Utc::now();
+        let now = chrono::Utc::now();
+        run.artifacts.push(artifact.clone());
+        run.updated_at = now;
+        let mut delta = ConductorRunStateDelta::next(run, now);
+        delta.calls = source_call.into_iter().collect();
+        delta.artifacts = vec![artifact];
+        publish_delta(self.event_store.as_ref(), run, delta);
         Ok(())
     }
 
@@ -344,8 +402,12 @@ impl ConductorState {
             .get_mut(run_id)
             .ok_or_else(|| super::protocol::ConductorError::NotFound(run_id.to_string()))?;
 
-        run.decision_log.push(decision);
-        run.updated_at = chrono::Utc::now();
+        let now = chrono::Utc::now();
+        run.decision_log.push(decision.clone());
+        run.updated_at = now;
+        let mut delta = ConductorRunStateDelta::next(run, now);
+        delta.decisions = vec![decision];
+        publish_delta(self.event_store.as_ref(), run, delta);
         Ok(())
     }
 
@@ -372,8 +434,11 @@ impl ConductorState {
             .get_mut(run_id)
             .ok_or_else(|| super::protocol::ConductorError::NotFound(run_id.to_string()))?;
 
+        let now = chrono::Utc::now();
         run.status = status;
-        run.updated_at = chrono::Utc::now();
+        run.updated_at = now;
+        let mut delta = ConductorRunStateDelta::next(run, now);
+        delta.status = Some(status);
 
         if matches!(
             status,
@@ -381,9 +446,31 @@ impl ConductorState {
                 | ConductorRunStatus::Failed
                 | ConductorRunStatus::Blocked
         ) {
-            run.completed_at = Some(chrono::Utc::now());
+            run.completed_at = Some(now);
+            delta.completed_at = Some(now);
         }
 
+        publish_delta(self.event_store.as_ref(), run, delta);
+        Ok(())
+    }
+
+    /// Record the output mode selected for final delivery
+    pub fn set_output_mode(
+        &mut self,
+        run_id: &str,
+        output_mode: ConductorOutputMode,
+    ) -> Result<(), super::protocol::ConductorError> {
+        let run = self
+            .runs
+            .get_mut(run_id)
+            .ok_or_else(|| super::protocol::ConductorError::NotFound(run_id.to_string()))?;
+
+        let now = chrono::Utc::now();
+        run.output_mode = output_mode;
+        run.updated_at = now;
+        let mut delta = ConductorRunStateDelta::next(run, now);
+        delta.output_mode = Some(output_mode);
+        publish_delta(self.event_store.as_ref(), run, delta);
         Ok(())
     }
 
@@ -461,6 +548,33 @@ impl Default for ConductorState {
     }
 }
 
+/// Agenda weight by priority: priority 0 items count four times as much as
+/// priority 3+ items, as a stand-in for their share of the run's work.
+fn agenda_item_weight(item: &ConductorAgendaItem) -> u32 {
+    1 + 3u32.saturating_sub(u32::from(item.priority))
+}
+
+/// Overall run progress in percent, from the priority-weighted share of
+/// completed agenda items. Failed and blocked items never count as done; a
+/// completed run is always 100.
+pub fn run_progress_pct(run: &ConductorRunState) -> u8 {
+    if run.status == ConductorRunStatus::Completed {
+        return 100;
+    }
+    let total: u32 = run.agenda.iter().map(agenda_item_weight).sum();
+    if total == 0 {
+        return 0;
+    }
+    let done: u32 = run
+        .agenda
+        .iter()
+        .filter(|item| item.status == AgendaItemStatus::Completed)
+        .map(agenda_item_weight)
+        .sum();
+    // An unfinished run stays below 100 even when every item so far is done.
+    (done * 100 / total).min(99) as u8
+}
+
 /// Summary of a run for observability
 #[derive(Debug, Clone)]
 pub struct RunSummary {
@@ -481,7 +595,7 @@ pub struct RunSummary {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use shared_types::{AgendaItemStatus, ConductorRunStatus};
+    use shared_types::{AgendaItemStatus, ConductorRunStatus, RunDeltaOutcome};
 
     // ============================================================================
     // StartRun and Run State Tests
@@ -505,6 +619,8 @@ mod tests {
             document_path: format!("conductor/runs/{}/draft.md", "run_1"),
             output_mode: shared_types::ConductorOutputMode::Auto,
             desktop_id: "desktop_1".to_string(),
+            revision: 0,
+            contract: None,
         };
 
         state.insert_run(run);
@@ -532,6 +648,8 @@ mod tests {
             document_path: format!("conductor/runs/{}/draft.md", "run_only_id"),
             output_mode: shared_types::ConductorOutputMode::Auto,
             desktop_id: "desktop_1".to_string(),
+            revision: 0,
+            contract: None,
         };
 
         state.insert_run(run);
@@ -559,6 +677,8 @@ mod tests {
             document_path: format!("conductor/runs/{}/draft.md", "run_1"),
             output_mode: shared_types::ConductorOutputMode::Auto,
             desktop_id: "desktop_1".to_string(),
+            revision: 0,
+            contract: None,
         };
 
         state.insert_run(run);
@@ -641,6 +761,8 @@ mod tests {
             document_path: format!("conductor/runs/{}/draft.md", "run_status_test"),
             output_mode: shared_types::ConductorOutputMode::Auto,
             desktop_id: "desktop_1".to_string(),
+            revision: 0,
+            contract: None,
         };
 
         state.insert_run(run);
@@ -686,6 +808,8 @@ mod tests {
             document_path: format!("conductor/runs/{}/draft.md", "run_failed_test"),
             output_mode: shared_types::ConductorOutputMode::Auto,
             desktop_id: "desktop_1".to_string(),
+            revision: 0,
+            contract: None,
         };
 
         state.insert_run(run);
@@ -716,6 +840,8 @@ mod tests {
             document_path: format!("conductor/runs/{}/draft.md", "run_blocked_test"),
             output_mode: shared_types::ConductorOutputMode::Auto,
             desktop_id: "desktop_1".to_string(),
+            revision: 0,
+            contract: None,
         };
 
         state.insert_run(run);
@@ -769,6 +895,8 @@ mod tests {
             document_path: format!("conductor/runs/{}/draft.md", "run_no_ready"),
             output_mode: shared_types::ConductorOutputMode::Auto,
             desktop_id: "desktop_1".to_string(),
+            revision: 0,
+            contract: None,
         };
 
         state.insert_run(run);
@@ -832,6 +960,8 @@ mod tests {
             document_path: format!("conductor/runs/{}/draft.md", "run_dispatch"),
             output_mode: shared_types::ConductorOutputMode::Auto,
             desktop_id: "desktop_1".to_string(),
+            revision: 0,
+            contract: None,
         };
 
         state.insert_run(run);
@@ -901,6 +1031,8 @@ mod tests {
             document_path: format!("conductor/runs/{}/draft.md", "run_transitions"),
             output_mode: shared_types::ConductorOutputMode::Auto,
             desktop_id: "desktop_1".to_string(),
+            revision: 0,
+            contract: None,
         };
 
         state.insert_run(run);
@@ -978,6 +1110,8 @@ mod tests {
             document_path: format!("conductor/runs/{}/draft.md", "run_failed"),
             output_mode: shared_types::ConductorOutputMode::Auto,
             desktop_id: "desktop_1".to_string(),
+            revision: 0,
+            contract: None,
         };
 
         state.insert_run(run);
@@ -1027,6 +1161,8 @@ mod tests {
             document_path: format!("conductor/runs/{}/draft.md", "run_blocked"),
             output_mode: shared_types::ConductorOutputMode::Auto,
             desktop_id: "desktop_1".to_string(),
+            revision: 0,
+            contract: None,
         };
 
         state.insert_run(run);
@@ -1078,6 +1214,8 @@ mod tests {
             document_path: format!("conductor/runs/{}/draft.md", "run_exists"),
             output_mode: shared_types::ConductorOutputMode::Auto,
             desktop_id: "desktop_1".to_string(),
+            revision: 0,
+            contract: None,
         };
 
         state.insert_run(run);
@@ -1122,6 +1260,8 @@ mod tests {
             document_path: format!("conductor/runs/{}/draft.md", "run_active"),
             output_mode: shared_types::ConductorOutputMode::Auto,
             desktop_id: "desktop_1".to_string(),
+            revision: 0,
+            contract: None,
         };
 
         state.insert_run(run);
@@ -1157,6 +1297,8 @@ mod tests {
             document_path: format!("conductor/runs/{}/draft.md", "run_ready"),
             output_mode: shared_types::ConductorOutputMode::Auto,
             desktop_id: "desktop_1".to_string(),
+            revision: 0,
+            contract: None,
         };
 
         state.insert_run(run);
@@ -1203,6 +1345,8 @@ mod tests {
             document_path: format!("conductor/runs/{}/draft.md", "run_idle"),
             output_mode: shared_types::ConductorOutputMode::Auto,
             desktop_id: "desktop_1".to_string(),
+            revision: 0,
+            contract: None,
         };
 
         state.insert_run(run);
@@ -1210,6 +1354,61 @@ mod tests {
         assert!(!state.has_active_work("run_idle"));
     }
 
+    #[test]
+    fn test_run_progress_pct_grows_with_completed_items_and_hits_100_on_completion() {
+        let item = |item_id: &str, priority: u8| ConductorAgendaItem {
+            item_id: item_id.to_string(),
+            capability: "writer".to_string(),
+            objective: item_id.to_string(),
+            priority,
+            depends_on: vec![],
+            status: AgendaItemStatus::Running,
+            created_at: chrono::Utc::now(),
+            started_at: None,
+            completed_at: None,
+        };
+        let mut run = ConductorRunState {
+            run_id: "run_progress".to_string(),
+            objective: "Progress".to_string(),
+            status: ConductorRunStatus::WaitingForCalls,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+            completed_at: None,
+            agenda: vec![item("high", 0), item("mid", 2), item("low", 5)],
+            active_calls: vec![],
+            artifacts: vec![],
+            decision_log: vec![],
+            document_path: "conductor/runs/run_progress/draft.md".to_string(),
+            output_mode: shared_types::ConductorOutputMode::Auto,
+            desktop_id: "desktop_1".to_string(),
+            revision: 0,
+            contract: None,
+        };
+        assert_eq!(run_progress_pct(&run), 0);
+
+        // Weights 4 + 2 + 1: the low-priority item moves progress least.
+        run.agenda[2].status = AgendaItemStatus::Completed;
+        assert_eq!(run_progress_pct(&run), 14);
+        run.agenda[2].status = AgendaItemStatus::Running;
+        run.agenda[0].status = AgendaItemStatus::Completed;
+        assert_eq!(run_progress_pct(&run), 57);
+
+        run.agenda[1].status = AgendaItemStatus::Failed;
+        assert_eq!(run_progress_pct(&run), 57);
+        run.agenda[1].status = AgendaItemStatus::Completed;
+        assert_eq!(run_progress_pct(&run), 85);
+        run.agenda[2].status = AgendaItemStatus::Completed;
+        assert_eq!(run_progress_pct(&run), 99);
+
+        run.status = ConductorRunStatus::Completed;
+        assert_eq!(run_progress_pct(&run), 100);
+
+        run.agenda.clear();
+        assert_eq!(run_progress_pct(&run), 100);
+        run.status = ConductorRunStatus::Running;
+        assert_eq!(run_progress_pct(&run), 0);
+    }
+
     #[test]
     fn test_get_run_summary() {
         let mut state = ConductorState::new();
@@ -1323,6 +1522,8 @@ mod tests {
             document_path: format!("conductor/runs/{}/draft.md", "run_summary"),
             output_mode: shared_types::ConductorOutputMode::Auto,
             desktop_id: "desktop_1".to_string(),
+            revision: 0,
+            contract: None,
         };
 
         state.insert_run(run);
@@ -1360,6 +1561,8 @@ mod tests {
             document_path: format!("conductor/runs/{}/draft.md", "run_1"),
             output_mode: shared_types::ConductorOutputMode::Auto,
             desktop_id: "desktop_1".to_string(),
+            revision: 0,
+            contract: None,
         };
 
         state.insert_run(run);
@@ -1386,4 +1589,153 @@ mod tests {
             .unwrap();
         assert_eq!(state.get_run_active_calls("run_1").len(), 0);
     }
+
+    #[tokio::test]
+    async fn test_state_deltas_replay_to_final_state() {
+        use crate::actors::event_store::{get_recent_events, EventStoreActor, EventStoreArguments};
+
+        let (event_store, _handle) =
+            ractor::Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .unwrap();
+        let mut state = ConductorState::new().with_event_store(event_store.clone());
+        let now = chrono::Utc::now();
+        state.insert_run(ConductorRunState {
+            run_id: "run_delta".to_string(),
+            objective: "Test".to_string(),
+            status: ConductorRunStatus::Initializing,
+            created_at: now,
+            updated_at: now,
+            completed_at: None,
+            agenda: vec![],
+            active_calls: vec![],
+            artifacts: vec![],
+            decision_log: vec![],
+            document_path: "conductor/runs/run_delta/draft.md".to_string(),
+            output_mode: shared_types::ConductorOutputMode::Auto,
+            desktop_id: "desktop_1".to_string(),
+            revision: 0,
+            contract: None,
+        });
+        let initial = state.get_run("run_delta").unwrap().clone();
+
+        let item = |item_id: &str, depends_on: Vec<String>| ConductorAgendaItem {
+            item_id: item_id.to_string(),
+            capability: "terminal".to_string(),
+            objective: "Run command".to_string(),
+            priority: 0,
+            depends_on,
+            status: AgendaItemStatus::Pending,
+            created_at: now,
+            started_at: None,
+            completed_at: None,
+        };
+        state
+            .transition_run_status("run_delta", ConductorRunStatus::Running)
+            .unwrap();
+        state
+            .add_agenda_items(
+                "run_delta",
+                vec![
+                    item("item_1", vec![]),
+                    item("item_2", vec!["item_1".into()]),
+                ],
+            )
+            .unwrap();
+        state.update_agenda_item_readiness("run_delta").unwrap();
+        state
+            .update_agenda_item("run_delta", "item_1", AgendaItemStatus::Running)
+            .unwrap();
+        state
+            .record_decision(
+                "run_delta",
+                ConductorDecision {
+                    decision_id: "dec_1".to_string(),
+                    decision_type: shared_types::DecisionType::Dispatch,
+                    reason: "Selected terminal".to_string(),
+                    timestamp: now,
+                    affected_agenda_items: vec!["item_1".to_string()],
+                    new_agenda_items: vec![],
+                },
+            )
+            .unwrap();
+        state
+            .register_capability_call(
+                "run_delta",
+                ConductorCapabilityCall {
+                    call_id: "call_1".to_string(),
+                    capability: "terminal".to_string(),
+                    objective: "Run ls".to_string(),
+                    status: CapabilityCallStatus::Running,
+                    started_at: now,
+                    completed_at: None,
+                    parent_call_id: None,
+                    agenda_item_id: Some("item_1".to_string()),
+                    artifact_ids: vec![],
+                    error: None,
+                },
+            )
+            .unwrap();
+        state
+            .add_artifact(
+                "run_delta",
+                ConductorArtifact {
+                    artifact_id: "art_1".to_string(),
+                    kind: shared_types::ArtifactKind::TerminalOutput,
+                    reference: "ls output".to_string(),
+                    mime_type: None,
+                    created_at: now,
+                    source_call_id: "call_1".to_string(),
+                    metadata: None,
+                },
+            )
+            .unwrap();
+        state
+            .update_capability_call("run_delta", "call_1", CapabilityCallStatus::Completed, None)
+            .unwrap();
+        state
+            .update_agenda_item("run_delta", "item_1", AgendaItemStatus::Completed)
+            .unwrap();
+        state.update_agenda_item_readiness("run_delta").unwrap();
+        state
+            .set_output_mode(
+                "run_delta",
+                shared_types::ConductorOutputMode::MarkdownReportToWriter,
+            )
+            .unwrap();
+        state
+            .transition_run_status("run_delta", ConductorRunStatus::Completed)
+            .unwrap();
+        let last = state.get_run("run_delta").unwrap().clone();
+        assert_eq!(last.revision, 12);
+
+        let events = get_recent_events(
+            &event_store,
+            0,
+            100,
+            Some(shared_types::EVENT_TOPIC_CONDUCTOR_RUN_STATE_DELTA.to_string()),
+            None,
+            None,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        let mut replayed = initial;
+        for event in &events {
+            let delta: ConductorRunStateDelta =
+                serde_json::from_value(event.payload.clone()).unwrap();
+            assert_eq!(
+                replayed.apply_delta(&delta),
+                RunDeltaOutcome::Applied,
+                "gap at {}",
+                delta.revision
+            );
+        }
+        assert_eq!(
+            serde_json::to_value(&replayed).unwrap(),
+            serde_json::to_value(&last).unwrap()
+        );
+
+        event_store.stop(None);
+    }
 }
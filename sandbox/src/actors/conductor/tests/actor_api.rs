@@ -39,6 +39,7 @@ async fn test_execute_task_message_missing_workers() {
         desktop_id: "test-desktop-001".to_string(),
         output_mode: ConductorOutputMode::MarkdownReportToWriter,
         hints: None,
+        contract: None,
     };
 
     let result: Result<Result<ConductorRunState, ConductorError>, _> =
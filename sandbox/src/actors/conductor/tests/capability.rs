@@ -0,0 +1,729 @@
+use async_trait::async_trait;
+use futures_util::future::BoxFuture;
+use ractor::{Actor, ActorProcessingErr, ActorRef};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+use crate::actors::conductor::actor::{ConductorActor, ConductorState};
+use crate::actors::conductor::capability::{
+    Capability, CapabilityConstraints, CapabilityRegistry, WorkerResult, WriterHandoff,
+};
+use crate::actors::conductor::model_gateway::BamlConductorModelGateway;
+use crate::actors::conductor::protocol::{CapabilityOutput, CapabilityWorkerOutput, ConductorMsg};
+use crate::actors::conductor::runtime::capability_call::{
+    run_capability_call, CapabilityCallState,
+};
+use crate::actors::conductor::state::ConductorState as RunStateStore;
+use crate::actors::event_store::get_recent_events;
+use crate::actors::researcher::{ResearchObjectiveStatus, ResearcherResult};
+use crate::actors::writer::{WriterMsg, WriterOrchestrationResult};
+
+use super::support::setup_test_conductor;
+
+#[derive(Clone, Default)]
+struct MockCapability {
+    dispatched: Arc<Mutex<Vec<(String, Option<String>)>>>,
+}
+
+impl Capability for MockCapability {
+    fn name(&self) -> &str {
+        "mock"
+    }
+
+    fn dispatch(
+        &self,
+        objective: String,
+        constraints: CapabilityConstraints,
+    ) -> BoxFuture<'static, WorkerResult> {
+        let dispatched = self.dispatched.clone();
+        Box::pin(async move {
+            dispatched
+                .lock()
+                .unwrap()
+                .push((objective.clone(), constraints.call_id));
+            Ok(CapabilityWorkerOutput::Capability(CapabilityOutput {
+                success: true,
+                summary: "mock capability done".to_string(),
+                metadata: serde_json::json!({ "echo": objective }),
+            }))
+        })
+    }
+}
+
+fn agenda_item(item_id: &str, capability: &str) -> shared_types::ConductorAgendaItem {
+    shared_types::ConductorAgendaItem {
+        item_id: item_id.to_string(),
+        capability: capability.to_string(),
+        objective: format!("objective for {item_id}"),
+        priority: 0,
+        depends_on: vec![],
+        status: shared_types::AgendaItemStatus::Running,
+        created_at: chrono::Utc::now(),
+        started_at: Some(chrono::Utc::now()),
+        completed_at: None,
+    }
+}
+
+fn capability_call(
+    call_id: &str,
+    item: &shared_types::ConductorAgendaItem,
+) -> shared_types::ConductorCapabilityCall {
+    shared_types::ConductorCapabilityCall {
+        call_id: call_id.to_string(),
+        capability: item.capability.clone(),
+        objective: item.objective.clone(),
+        status: shared_types::CapabilityCallStatus::Running,
+        started_at: chrono::Utc::now(),
+        completed_at: None,
+        parent_call_id: None,
+        agenda_item_id: Some(item.item_id.clone()),
+        artifact_ids: vec![],
+        error: None,
+    }
+}
+
+#[test]
+fn test_registry_includes_builtins_and_is_case_insensitive() {
+    let mut registry = CapabilityRegistry::with_builtins();
+    registry.register(MockCapability::default());
+    assert_eq!(registry.names(), vec!["mock", "researcher", "terminal"]);
+    assert!(registry.contains("Terminal"));
+    assert!(registry.get("unknown").is_none());
+}
+
+/// Capability whose calls block until the test releases them.
+#[derive(Clone)]
+struct GatedCapability {
+    name: &'static str,
+    started: Arc<AtomicUsize>,
+    release: Arc<tokio::sync::Semaphore>,
+}
+
+impl GatedCapability {
+    fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            started: Arc::new(AtomicUsize::new(0)),
+            release: Arc::new(tokio::sync::Semaphore::new(0)),
+        }
+    }
+}
+
+impl Capability for GatedCapability {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn dispatch(
+        &self,
+        _objective: String,
+        _constraints: CapabilityConstraints,
+    ) -> BoxFuture<'static, WorkerResult> {
+        let started = self.started.clone();
+        let release = self.release.clone();
+        Box::pin(async move {
+            started.fetch_add(1, Ordering::SeqCst);
+            release.acquire().await.expect("gate").forget();
+            Ok(CapabilityWorkerOutput::Capability(CapabilityOutput {
+                success: true,
+                summary: "released".to_string(),
+                metadata: serde_json::Value::Null,
+            }))
+        })
+    }
+}
+
+async fn wait_for_started(capability: &GatedCapability, expected: usize) {
+    for _ in 0..100 {
+        if capability.started.load(Ordering::SeqCst) >= expected {
+            return;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    }
+    panic!("{} never reached {expected} started calls", capability.name);
+}
+
+#[tokio::test]
+async fn test_excess_terminal_dispatch_waits_while_research_proceeds() {
+    let terminal = GatedCapability::new("terminal");
+    let researcher = GatedCapability::new("researcher");
+    let mut registry = CapabilityRegistry::with_builtins();
+    registry.register(terminal.clone());
+    registry.register(researcher.clone());
+    assert_eq!(registry.concurrency_limit("terminal"), Some(2));
+    assert_eq!(registry.concurrency_limit("Researcher"), Some(4));
+    assert_eq!(registry.concurrency_limit("writer"), None);
+
+    let dispatch = |name: &str| {
+        let future = registry
+            .dispatch(
+                name,
+                "objective".to_string(),
+                CapabilityConstraints::default(),
+            )
+            .expect("registered");
+        tokio::spawn(future)
+    };
+    let terminal_calls: Vec<_> = (0..3).map(|_| dispatch("terminal")).collect();
+    wait_for_started(&terminal, 2).await;
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert_eq!(terminal.started.load(Ordering::SeqCst), 2);
+
+    let research_call = dispatch("researcher");
+    wait_for_started(&researcher, 1).await;
+    researcher.release.add_permits(1);
+    assert!(research_call.await.expect("join").is_ok());
+    assert_eq!(terminal.started.load(Ordering::SeqCst), 2);
+
+    terminal.release.add_permits(1);
+    wait_for_started(&terminal, 3).await;
+    terminal.release.add_permits(2);
+    for call in terminal_calls {
+        assert!(call.await.expect("join").is_ok());
+    }
+}
+
+#[tokio::test]
+async fn test_mock_capability_dispatch_is_folded_into_run_events() {
+    let (conductor_ref, store_ref) = setup_test_conductor(None, None).await;
+    let mock = MockCapability::default();
+    let mut capabilities = CapabilityRegistry::default();
+    capabilities.register(mock.clone());
+
+    let run_id = "run_capability_mock";
+    let mut tasks = RunStateStore::new();
+    tasks.insert_run(shared_types::ConductorRunState {
+        run_id: run_id.to_string(),
+        objective: "exercise capability dispatch".to_string(),
+        status: shared_types::ConductorRunStatus::WaitingForCalls,
+        created_at: chrono::Utc::now(),
+        updated_at: chrono::Utc::now(),
+        completed_at: None,
+        agenda: vec![],
+        active_calls: vec![],
+        artifacts: vec![],
+        decision_log: vec![],
+        document_path: format!("conductor/runs/{run_id}/draft.md"),
+        output_mode: shared_types::ConductorOutputMode::Auto,
+        desktop_id: "desktop_1".to_string(),
+        revision: 0,
+        contract: None,
+    });
+    let mock_item = agenda_item("item_mock", "mock");
+    // A second in-flight call keeps the run from finalizing.
+    let pending_item = agenda_item("item_pending", "writer");
+    tasks
+        .add_agenda_items(run_id, vec![mock_item.clone(), pending_item.clone()])
+        .unwrap();
+    tasks
+        .register_capability_call(run_id, capability_call("call_mock", &mock_item))
+        .unwrap();
+    tasks
+        .register_capability_call(run_id, capability_call("call_pending", &pending_item))
+        .unwrap();
+
+    let model_gateway = Arc::new(BamlConductorModelGateway::new(store_ref.clone()));
+    let mut state = ConductorState {
+        tasks,
+        event_store: store_ref.clone(),
+        writer_supervisor: None,
+        memory_actor: None,
+        model_gateway: model_gateway.clone(),
+        capabilities: capabilities.clone(),
+        provider_gateway_reachable: true,
+        waiting_for_provider: Vec::new(),
+    };
+
+    let result = run_capability_call(CapabilityCallState {
+        conductor_ref: conductor_ref.clone(),
+        model_gateway,
+        capabilities,
+        writer_actor: None,
+        run_id: run_id.to_string(),
+        call_id: "call_mock".to_string(),
+        agenda_item_id: mock_item.item_id.clone(),
+        capability: "mock".to_string(),
+        objective: mock_item.objective.clone(),
+        writer_handoff: None,
+        contract: None,
+    })
+    .await;
+    assert!(matches!(result, Ok(CapabilityWorkerOutput::Capability(_))));
+    assert_eq!(
+        mock.dispatched.lock().unwrap().as_slice(),
+        &[(mock_item.objective.clone(), Some("call_mock".to_string()))]
+    );
+
+    ConductorActor
+        .handle_capability_call_finished(
+            &conductor_ref,
+            &mut state,
+            run_id.to_string(),
+            "call_mock".to_string(),
+            mock_item.item_id.clone(),
+            "mock".to_string(),
+            result,
+        )
+        .await
+        .unwrap();
+
+    let run = state.tasks.get_run(run_id).unwrap();
+    let item = run
+        .agenda
+        .iter()
+        .find(|item| item.item_id == "item_mock")
+        .unwrap();
+    assert_eq!(item.status, shared_types::AgendaItemStatus::Completed);
+    let artifacts = state.tasks.get_call_artifacts(run_id, "call_mock");
+    assert_eq!(artifacts.len(), 1);
+    let metadata = artifacts[0].metadata.as_ref().unwrap();
+    assert_eq!(metadata["capability"], "mock");
+    assert_eq!(metadata["details"]["echo"], mock_item.objective);
+    assert_eq!(
+        run.status,
+        shared_types::ConductorRunStatus::WaitingForCalls
+    );
+
+    let events = get_recent_events(&store_ref, 0, 100, None, None, None)
+        .await
+        .unwrap()
+        .unwrap();
+    let completed = events
+        .iter()
+        .find(|event| event.event_type == "conductor.capability.completed")
+        .expect("capability completion event");
+    assert_eq!(completed.payload["data"]["call_id"], "call_mock");
+    let worker_result = events
+        .iter()
+        .find(|event| event.event_type == shared_types::EVENT_TOPIC_CONDUCTOR_WORKER_RESULT)
+        .expect("worker result event");
+    assert_eq!(worker_result.payload["worker_type"], "mock");
+    assert_eq!(worker_result.payload["success"], true);
+
+    conductor_ref.stop(None);
+    store_ref.stop(None);
+}
+
+/// Capability that records the timeout each call was given.
+#[derive(Clone)]
+struct TimeoutProbe {
+    name: &'static str,
+    timeouts: Arc<Mutex<Vec<Option<u64>>>>,
+}
+
+impl Capability for TimeoutProbe {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn dispatch(
+        &self,
+        _objective: String,
+        constraints: CapabilityConstraints,
+    ) -> BoxFuture<'static, WorkerResult> {
+        let timeouts = self.timeouts.clone();
+        Box::pin(async move {
+            timeouts.lock().unwrap().push(constraints.timeout_ms);
+            Ok(CapabilityWorkerOutput::Capability(CapabilityOutput {
+                success: true,
+                summary: "probed".to_string(),
+                metadata: serde_json::Value::Null,
+            }))
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_conductor_routes_workers_through_writer_and_applies_call_timeouts() {
+    let (conductor_ref, store_ref) = setup_test_conductor(None, None).await;
+    let terminal = TimeoutProbe {
+        name: "terminal",
+        timeouts: Arc::default(),
+    };
+    let probe = TimeoutProbe {
+        name: "probe",
+        timeouts: Arc::default(),
+    };
+    let mut capabilities = CapabilityRegistry::with_builtins();
+    capabilities.register(terminal.clone());
+    capabilities.register(probe.clone());
+    capabilities.set_call_timeout("Probe", 5_000);
+    assert_eq!(capabilities.call_timeout_ms("probe"), Some(5_000));
+    assert_eq!(capabilities.call_timeout_ms("terminal"), None);
+
+    let model_gateway = Arc::new(BamlConductorModelGateway::new(store_ref.clone()));
+    let call = |capability: &str| CapabilityCallState {
+        conductor_ref: conductor_ref.clone(),
+        model_gateway: model_gateway.clone(),
+        capabilities: capabilities.clone(),
+        writer_actor: None,
+        run_id: "run_capability_routing".to_string(),
+        call_id: format!("call_{capability}"),
+        agenda_item_id: format!("item_{capability}"),
+        capability: capability.to_string(),
+        objective: "objective".to_string(),
+        writer_handoff: None,
+        contract: None,
+    };
+
+    // Registered or not, terminal work goes through the writer.
+    let err = run_capability_call(call("terminal")).await.unwrap_err();
+    assert!(err.to_string().contains("route through writer"), "{err}");
+    assert!(terminal.timeouts.lock().unwrap().is_empty());
+
+    assert!(run_capability_call(call("probe")).await.is_ok());
+    assert_eq!(probe.timeouts.lock().unwrap().as_slice(), &[Some(5_000)]);
+
+    conductor_ref.stop(None);
+    store_ref.stop(None);
+}
+
+/// Research capability that returns a fixed cited finding.
+struct CitingResearcher;
+
+impl Capability for CitingResearcher {
+    fn name(&self) -> &str {
+        "web_research"
+    }
+
+    fn dispatch(
+        &self,
+        _objective: String,
+        _constraints: CapabilityConstraints,
+    ) -> BoxFuture<'static, WorkerResult> {
+        Box::pin(async move {
+            Ok(CapabilityWorkerOutput::Researcher(ResearcherResult {
+                summary: "WAL readers see a consistent snapshot while a writer commits".to_string(),
+                success: true,
+                objective_status: ResearchObjectiveStatus::Complete,
+                completion_reason: "answered".to_string(),
+                recommended_next_capability: None,
+                recommended_next_objective: None,
+                provider_used: Some("stub".to_string()),
+                model_used: None,
+                citations: vec![shared_types::ResearchCitation {
+                    id: "cite_wal".to_string(),
+                    provider: "stub".to_string(),
+                    title: "Write-Ahead Logging".to_string(),
+                    url: "https://www.sqlite.org/wal.html".to_string(),
+                    snippet: "Readers do not block writers.".to_string(),
+                    published_at: None,
+                    score: None,
+                    age_days: None,
+                }],
+                provider_calls: vec![],
+                raw_results_count: 1,
+                error: None,
+                worker_report: None,
+                proposed_citation_ids: vec![],
+                proposed_citation_stubs: vec![],
+            }))
+        })
+    }
+}
+
+/// Stands in for the conductor mailbox, forwarding what calls send back.
+struct Forwarder;
+
+#[async_trait]
+impl Actor for Forwarder {
+    type Msg = ConductorMsg;
+    type State = mpsc::UnboundedSender<ConductorMsg>;
+    type Arguments = mpsc::UnboundedSender<ConductorMsg>;
+
+    async fn pre_start(
+        &self,
+        _myself: ActorRef<Self::Msg>,
+        tx: Self::Arguments,
+    ) -> Result<Self::State, ActorProcessingErr> {
+        Ok(tx)
+    }
+
+    async fn handle(
+        &self,
+        _myself: ActorRef<Self::Msg>,
+        message: Self::Msg,
+        tx: &mut Self::State,
+    ) -> Result<(), ActorProcessingErr> {
+        let _ = tx.send(message);
+        Ok(())
+    }
+}
+
+/// Writer stand-in that records orchestrated objectives and reports success.
+struct RecordingWriter;
+
+#[async_trait]
+impl Actor for RecordingWriter {
+    type Msg = WriterMsg;
+    type State = Arc<Mutex<Vec<String>>>;
+    type Arguments = Arc<Mutex<Vec<String>>>;
+
+    async fn pre_start(
+        &self,
+        _myself: ActorRef<Self::Msg>,
+        objectives: Self::Arguments,
+    ) -> Result<Self::State, ActorProcessingErr> {
+        Ok(objectives)
+    }
+
+    async fn handle(
+        &self,
+        _myself: ActorRef<Self::Msg>,
+        message: Self::Msg,
+        objectives: &mut Self::State,
+    ) -> Result<(), ActorProcessingErr> {
+        if let WriterMsg::OrchestrateObjective {
+            objective, reply, ..
+        } = message
+        {
+            objectives.lock().unwrap().push(objective);
+            let _ = reply.send(Ok(WriterOrchestrationResult {
+                success: true,
+                summary: "draft updated".to_string(),
+                delegated_capabilities: vec![],
+                pending_delegations: 0,
+            }));
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_writer_call_after_research_gets_citations_as_context() {
+    let (store_ref, _store_handle) = Actor::spawn(
+        None,
+        crate::actors::event_store::EventStoreActor,
+        crate::actors::event_store::EventStoreArguments::InMemory,
+    )
+    .await
+    .unwrap();
+    let objectives = Arc::new(Mutex::new(Vec::new()));
+    let (writer_ref, _writer_handle) = Actor::spawn(None, RecordingWriter, objectives.clone())
+        .await
+        .unwrap();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let (forwarder, _forwarder_handle) = Actor::spawn(None, Forwarder, tx).await.unwrap();
+
+    let run_id = format!("run_writer_handoff_{}", ulid::Ulid::new());
+    let mut capabilities = CapabilityRegistry::default();
+    capabilities.register(CitingResearcher);
+
+    let mut tasks = RunStateStore::new();
+    tasks.insert_run(shared_types::ConductorRunState {
+        run_id: run_id.clone(),
+        objective: "Explain WAL readers".to_string(),
+        status: shared_types::ConductorRunStatus::WaitingForCalls,
+        created_at: chrono::Utc::now(),
+        updated_at: chrono::Utc::now(),
+        completed_at: None,
+        agenda: vec![],
+        active_calls: vec![],
+        artifacts: vec![],
+        decision_log: vec![],
+        document_path: format!("conductor/runs/{run_id}/draft.md"),
+        output_mode: shared_types::ConductorOutputMode::Auto,
+        desktop_id: "desktop_1".to_string(),
+        revision: 0,
+        contract: None,
+    });
+    let research_item = agenda_item("item_research", "web_research");
+    let writer_item = agenda_item("item_writer", "writer");
+    tasks
+        .add_agenda_items(&run_id, vec![research_item.clone(), writer_item.clone()])
+        .unwrap();
+    tasks
+        .register_capability_call(&run_id, capability_call("call_research", &research_item))
+        .unwrap();
+    tasks
+        .register_capability_call(&run_id, capability_call("call_writer", &writer_item))
+        .unwrap();
+
+    let model_gateway = Arc::new(BamlConductorModelGateway::new(store_ref.clone()));
+    let mut state = ConductorState {
+        tasks,
+        event_store: store_ref.clone(),
+        writer_supervisor: None,
+        memory_actor: None,
+        model_gateway: model_gateway.clone(),
+        capabilities: capabilities.clone(),
+        provider_gateway_reachable: true,
+        waiting_for_provider: Vec::new(),
+    };
+    let call_state =
+        |call_id: &str, item: &shared_types::ConductorAgendaItem| CapabilityCallState {
+            conductor_ref: forwarder.clone(),
+            model_gateway: model_gateway.clone(),
+            capabilities: capabilities.clone(),
+            writer_actor: Some(writer_ref.clone()),
+            run_id: run_id.clone(),
+            call_id: call_id.to_string(),
+            agenda_item_id: item.item_id.clone(),
+            capability: item.capability.clone(),
+            objective: item.objective.clone(),
+            writer_handoff: None,
+            contract: None,
+        };
+
+    let research = run_capability_call(call_state("call_research", &research_item)).await;
+    ConductorActor
+        .handle_capability_call_finished(
+            &forwarder,
+            &mut state,
+            run_id.clone(),
+            "call_research".to_string(),
+            research_item.item_id.clone(),
+            "web_research".to_string(),
+            research,
+        )
+        .await
+        .unwrap();
+    assert!(
+        rx.try_recv().is_err(),
+        "the running writer call is not redispatched"
+    );
+
+    // `writer` is not a registered capability, so the conductor's own writer
+    // path runs and hands the research to the writer's planner.
+    let handoff = WriterHandoff::from_run(state.tasks.get_run(&run_id).unwrap());
+    let writer = run_capability_call(CapabilityCallState {
+        writer_handoff: Some(handoff),
+        ..call_state("call_writer", &writer_item)
+    })
+    .await;
+    assert!(matches!(writer, Ok(CapabilityWorkerOutput::Writer(_))));
+    let objective = objectives.lock().unwrap().pop().expect("writer was called");
+    assert!(objective.starts_with(&writer_item.objective));
+    assert!(objective.contains("WAL readers see a consistent snapshot while a writer commits"));
+    assert!(objective.contains("1. [Write-Ahead Logging](https://www.sqlite.org/wal.html)"));
+
+    ConductorActor
+        .handle_capability_call_finished(
+            &forwarder,
+            &mut state,
+            run_id.clone(),
+            "call_writer".to_string(),
+            writer_item.item_id.clone(),
+            "writer".to_string(),
+            writer,
+        )
+        .await
+        .unwrap();
+    let run = state.tasks.get_run(&run_id).unwrap();
+    assert!(run
+        .agenda
+        .iter()
+        .all(|item| item.status == shared_types::AgendaItemStatus::Completed));
+
+    writer_ref.stop(None);
+    forwarder.stop(None);
+    store_ref.stop(None);
+}
+
+#[tokio::test]
+async fn test_finished_call_dispatches_items_that_depended_on_it() {
+    let (store_ref, _store_handle) = Actor::spawn(
+        None,
+        crate::actors::event_store::EventStoreActor,
+        crate::actors::event_store::EventStoreArguments::InMemory,
+    )
+    .await
+    .unwrap();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let (forwarder, _forwarder_handle) = Actor::spawn(None, Forwarder, tx).await.unwrap();
+
+    let run_id = format!("run_depends_on_{}", ulid::Ulid::new());
+    let mock = MockCapability::default();
+    let mut capabilities = CapabilityRegistry::default();
+    capabilities.register(CitingResearcher);
+    capabilities.register(mock.clone());
+
+    let mut tasks = RunStateStore::new();
+    tasks.insert_run(shared_types::ConductorRunState {
+        run_id: run_id.clone(),
+        objective: "Explain WAL readers".to_string(),
+        status: shared_types::ConductorRunStatus::WaitingForCalls,
+        created_at: chrono::Utc::now(),
+        updated_at: chrono::Utc::now(),
+        completed_at: None,
+        agenda: vec![],
+        active_calls: vec![],
+        artifacts: vec![],
+        decision_log: vec![],
+        document_path: format!("conductor/runs/{run_id}/draft.md"),
+        output_mode: shared_types::ConductorOutputMode::Auto,
+        desktop_id: "desktop_1".to_string(),
+        revision: 0,
+        contract: None,
+    });
+    let research_item = agenda_item("item_research", "web_research");
+    let mut follow_up = agenda_item("item_follow_up", "mock");
+    follow_up.status = shared_types::AgendaItemStatus::Pending;
+    follow_up.started_at = None;
+    follow_up.depends_on = vec![research_item.item_id.clone()];
+    tasks
+        .add_agenda_items(&run_id, vec![research_item.clone(), follow_up.clone()])
+        .unwrap();
+    tasks
+        .register_capability_call(&run_id, capability_call("call_research", &research_item))
+        .unwrap();
+
+    let model_gateway = Arc::new(BamlConductorModelGateway::new(store_ref.clone()));
+    let mut state = ConductorState {
+        tasks,
+        event_store: store_ref.clone(),
+        writer_supervisor: None,
+        memory_actor: None,
+        model_gateway: model_gateway.clone(),
+        capabilities: capabilities.clone(),
+        provider_gateway_reachable: true,
+        waiting_for_provider: Vec::new(),
+    };
+
+    let research = run_capability_call(CapabilityCallState {
+        conductor_ref: forwarder.clone(),
+        model_gateway,
+        capabilities,
+        writer_actor: None,
+        run_id: run_id.clone(),
+        call_id: "call_research".to_string(),
+        agenda_item_id: research_item.item_id.clone(),
+        capability: "web_research".to_string(),
+        objective: research_item.objective.clone(),
+        writer_handoff: None,
+        contract: None,
+    })
+    .await;
+    ConductorActor
+        .handle_capability_call_finished(
+            &forwarder,
+            &mut state,
+            run_id.clone(),
+            "call_research".to_string(),
+            research_item.item_id.clone(),
+            "web_research".to_string(),
+            research,
+        )
+        .await
+        .unwrap();
+
+    let message = tokio::time::timeout(std::time::Duration::from_secs(10), rx.recv())
+        .await
+        .expect("follow-up call finished in time")
+        .unwrap();
+    let ConductorMsg::CapabilityCallFinished {
+        agenda_item_id,
+        capability,
+        ..
+    } = message
+    else {
+        panic!("expected CapabilityCallFinished, got {message:?}");
+    };
+    assert_eq!(agenda_item_id, follow_up.item_id);
+    assert_eq!(capability, "mock");
+    assert_eq!(mock.dispatched.lock().unwrap()[0].0, follow_up.objective);
+
+    forwarder.stop(None);
+    store_ref.stop(None);
+}
@@ -1,4 +1,5 @@
 mod actor_api;
+mod capability;
 mod output;
 mod runtime_loop;
 mod support;
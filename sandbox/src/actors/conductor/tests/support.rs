@@ -18,6 +18,7 @@ pub(crate) async fn setup_test_conductor(
         event_store: store_ref.clone(),
         writer_supervisor: None,
         memory_actor: None,
+        capabilities: Default::default(),
     };
 
     let (conductor_ref, _conductor_handle) =
@@ -7,9 +7,9 @@ use crate::actors::conductor::protocol::ConductorError;
 use crate::actors::researcher::{ResearcherMsg, ResearcherProgress, ResearcherResult};
 use crate::actors::terminal::{
     ensure_terminal_started, TerminalAgentProgress, TerminalAgentResult, TerminalBashToolRequest,
-    TerminalError, TerminalMsg,
+    TerminalError, TerminalMsg, BASH_TOOL_MAX_TIMEOUT_MS, BASH_TOOL_MIN_TIMEOUT_MS,
 };
-use crate::actors::writer::WriterMsg;
+use crate::actors::writer::{WriterMsg, WriterOrchestrationResult};
 
 /// Call the ResearcherActor for an agentic task.
 pub async fn call_researcher(
@@ -41,11 +41,21 @@ pub async fn call_researcher(
     .map_err(|e| ConductorError::WorkerFailed(e.to_string()))
 }
 
+/// How a terminal call runs. An exact `command` wins; otherwise the objective
+/// goes to the terminal agent, which plans only when `dry_run` is set and
+/// skips planning when an `approved_plan` is passed back.
+#[derive(Debug, Clone, Default)]
+pub struct TerminalDispatch {
+    pub command: Option<String>,
+    pub dry_run: bool,
+    pub approved_plan: Option<Vec<String>>,
+}
+
 /// Call the TerminalActor for either a command or an agentic objective.
 pub async fn call_terminal(
     terminal: &ActorRef<TerminalMsg>,
     objective: String,
-    terminal_command: Option<String>,
+    dispatch: TerminalDispatch,
     timeout_ms: Option<u64>,
     max_steps: Option<u8>,
     progress_tx: Option<mpsc::UnboundedSender<TerminalAgentProgress>>,
@@ -58,11 +68,13 @@ pub async fn call_terminal(
         .await
         .map_err(ConductorError::WorkerFailed)?;
 
-    if let Some(cmd) = terminal_command {
+    if let Some(cmd) = dispatch.command {
         call!(terminal, |reply| TerminalMsg::RunBashTool {
             request: TerminalBashToolRequest {
                 cmd,
-                timeout_ms,
+                // The call budget is not bounded like a single command's timeout.
+                timeout_ms: timeout_ms
+                    .map(|ms| ms.clamp(BASH_TOOL_MIN_TIMEOUT_MS, BASH_TOOL_MAX_TIMEOUT_MS)),
                 model_override: None,
                 reasoning: Some("conductor capability dispatch terminal command".to_string()),
                 run_id,
@@ -88,6 +100,8 @@ pub async fn call_terminal(
             writer_actor: None,
             run_id,
             call_id,
+            dry_run: dispatch.dry_run,
+            approved_plan: dispatch.approved_plan,
             reply,
         })
         .map_err(|e| {
@@ -99,3 +113,26 @@ pub async fn call_terminal(
         })
     }
 }
+
+/// Call the WriterActor's planner for an objective.
+pub async fn call_writer(
+    writer: &ActorRef<WriterMsg>,
+    objective: String,
+    timeout_ms: Option<u64>,
+    max_steps: Option<u8>,
+    run_id: Option<String>,
+    call_id: Option<String>,
+) -> Result<WriterOrchestrationResult, ConductorError> {
+    use ractor::call;
+
+    call!(writer, |reply| WriterMsg::OrchestrateObjective {
+        objective,
+        timeout_ms,
+        max_steps,
+        run_id,
+        call_id,
+        reply,
+    })
+    .map_err(|e| ConductorError::WorkerFailed(e.to_string()))?
+    .map_err(|e| ConductorError::WorkerFailed(e.to_string()))
+}
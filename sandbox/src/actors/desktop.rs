@@ -13,12 +13,20 @@
 //! - Window state survives page refresh
 //! - Same actor instance for same desktop_id
 //! - Mobile-first: single window view, desktop: floating windows
+//!
+//! RECOVERY: every `snapshot_interval` events (by default
+//! [`DESKTOP_SNAPSHOT_INTERVAL`]) the actor writes a best-effort state
+//! snapshot to the EventStore. A respawned actor restores the snapshot and
+//! replays only the events after it.
 
 use async_trait::async_trait;
 use ractor::{Actor, ActorProcessingErr, ActorRef, RpcReplyPort};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 
-use crate::actors::event_store::{AppendEvent, EventStoreError, EventStoreMsg};
+use crate::actors::event_store::{
+    get_latest_snapshot, save_snapshot, ActorSnapshot, AppendEvent, EventStoreError, EventStoreMsg,
+};
 
 /// Actor that manages desktop window state
 #[derive(Debug, Default)]
@@ -30,6 +38,9 @@ pub struct DesktopArguments {
     pub desktop_id: String,
     pub user_id: String,
     pub event_store: ActorRef<EventStoreMsg>,
+    /// Events between state snapshots; 0 disables snapshots. Usually
+    /// [`DESKTOP_SNAPSHOT_INTERVAL`].
+    pub snapshot_interval: u64,
 }
 
 /// State for DesktopActor
@@ -41,7 +52,48 @@ pub struct DesktopState {
     active_window: Option<String>,
     next_z_index: u32,
     last_seq: i64,
+    /// Area window title bars are kept inside; set from the client viewport.
+    bounds: WindowBounds,
     event_store: ActorRef<EventStoreMsg>,
+    /// Whether state has been rebuilt from the EventStore since spawn.
+    restored: bool,
+    /// Events reflected in state since the last snapshot was written.
+    events_since_snapshot: u64,
+    snapshot_interval: u64,
+    /// Templates already applied, so a retried creation opens nothing twice.
+    applied_templates: HashSet<String>,
+}
+
+impl DesktopState {
+    fn new(args: DesktopArguments) -> Self {
+        Self {
+            desktop_id: args.desktop_id,
+            user_id: args.user_id,
+            windows: HashMap::new(),
+            apps: HashMap::new(),
+            active_window: None,
+            next_z_index: 100,
+            last_seq: 0,
+            bounds: DEFAULT_DESKTOP_BOUNDS,
+            event_store: args.event_store,
+            restored: false,
+            events_since_snapshot: 0,
+            snapshot_interval: args.snapshot_interval,
+            applied_templates: HashSet::new(),
+        }
+    }
+}
+
+/// Snapshotted part of [`DesktopState`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DesktopSnapshot {
+    windows: HashMap<String, shared_types::WindowState>,
+    apps: HashMap<String, shared_types::AppDefinition>,
+    active_window: Option<String>,
+    next_z_index: u32,
+    bounds: WindowBounds,
+    #[serde(default)]
+    applied_templates: HashSet<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -50,7 +102,7 @@ pub struct RestoreResult {
     pub from: String,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct WindowBounds {
     pub x: i32,
     pub y: i32,
@@ -62,6 +114,53 @@ const MIN_WINDOW_WIDTH: i32 = 200;
 const MIN_WINDOW_HEIGHT: i32 = 160;
 const MAXIMIZED_X: i32 = 0;
 const MAXIMIZED_Y: i32 = 0;
+/// Default number of events between state snapshots.
+pub const DESKTOP_SNAPSHOT_INTERVAL: u64 = 25;
+/// Title bar height that must stay inside the desktop bounds.
+const TITLE_BAR_HEIGHT: i32 = 36;
+/// Title bar width that must stay inside the desktop bounds.
+const MIN_VISIBLE_TITLE_BAR_WIDTH: i32 = 80;
+/// Bounds used until a client reports its viewport; two 4K screens side by side.
+pub const DEFAULT_DESKTOP_BOUNDS: WindowBounds = WindowBounds {
+    x: 0,
+    y: 0,
+    width: 7680,
+    height: 2160,
+};
+
+impl WindowBounds {
+    /// Clamp a window's top-left corner so at least part of its title bar
+    /// stays inside these bounds. Computed in i64 so large or negative
+    /// virtual bounds cannot overflow.
+    pub fn clamp_position(&self, x: i32, y: i32, width: i32) -> (i32, i32) {
+        let left = i64::from(self.x);
+        let top = i64::from(self.y);
+        let span_x = i64::from(self.width.max(0));
+        let span_y = i64::from(self.height.max(0));
+        let visible = i64::from(MIN_VISIBLE_TITLE_BAR_WIDTH)
+            .min(i64::from(width.max(1)))
+            .min(span_x.max(1));
+
+        let min_x = left + visible - i64::from(width.max(0));
+        let max_x = (left + span_x - visible).max(min_x);
+        let min_y = top;
+        let max_y = (top + span_y - i64::from(TITLE_BAR_HEIGHT)).max(min_y);
+
+        let clamp_i32 = |value: i64| value.clamp(i64::from(i32::MIN), i64::from(i32::MAX)) as i32;
+        (
+            clamp_i32(i64::from(x).clamp(min_x, max_x)),
+            clamp_i32(i64::from(y).clamp(min_y, max_y)),
+        )
+    }
+
+    /// Cap a window size to these bounds, never below the minimum size.
+    pub fn clamp_size(&self, width: i32, height: i32) -> (i32, i32) {
+        (
+            width.min(self.width).max(MIN_WINDOW_WIDTH),
+            height.min(self.height).max(MIN_WINDOW_HEIGHT),
+        )
+    }
+}
 
 // ============================================================================
 // Messages
@@ -87,14 +186,14 @@ pub enum DesktopActorMsg {
         window_id: String,
         x: i32,
         y: i32,
-        reply: RpcReplyPort<Result<(), DesktopError>>,
+        reply: RpcReplyPort<Result<shared_types::WindowState, DesktopError>>,
     },
     /// Resize a window
     ResizeWindow {
         window_id: String,
         width: i32,
         height: i32,
-        reply: RpcReplyPort<Result<(), DesktopError>>,
+        reply: RpcReplyPort<Result<shared_types::WindowState, DesktopError>>,
     },
     /// Focus a window (bring to front)
     FocusWindow {
@@ -117,6 +216,11 @@ pub enum DesktopActorMsg {
         window_id: String,
         reply: RpcReplyPort<Result<RestoreResult, DesktopError>>,
     },
+    /// Set the desktop bounds and pull existing windows back inside them
+    SetDesktopBounds {
+        bounds: WindowBounds,
+        reply: RpcReplyPort<Result<Vec<shared_types::WindowState>, DesktopError>>,
+    },
     /// Get all windows
     GetWindows {
         reply: RpcReplyPort<Vec<shared_types::WindowState>>,
@@ -130,6 +234,12 @@ pub enum DesktopActorMsg {
         app: shared_types::AppDefinition,
         reply: RpcReplyPort<Result<(), DesktopError>>,
     },
+    /// Open a template's windows unless it was already applied; replies
+    /// with the windows opened by this call
+    ApplyTemplate {
+        template: shared_types::DesktopTemplate,
+        reply: RpcReplyPort<Result<Vec<shared_types::WindowState>, DesktopError>>,
+    },
     /// Get all registered apps
     GetApps {
         reply: RpcReplyPort<Vec<shared_types::AppDefinition>>,
@@ -155,6 +265,8 @@ const EVENT_WINDOW_MINIMIZED: &str = "desktop.window_minimized";
 const EVENT_WINDOW_MAXIMIZED: &str = "desktop.window_maximized";
 const EVENT_WINDOW_RESTORED: &str = "desktop.window_restored";
 const EVENT_APP_REGISTERED: &str = "desktop.app_registered";
+const EVENT_BOUNDS_CHANGED: &str = "desktop.bounds_changed";
+const EVENT_TEMPLATE_APPLIED: &str = "desktop.template_applied";
 
 // ============================================================================
 // Error Types
@@ -212,18 +324,7 @@ impl Actor for DesktopActor {
             "DesktopActor starting"
         );
 
-        let state = DesktopState {
-            desktop_id: args.desktop_id,
-            user_id: args.user_id,
-            windows: HashMap::new(),
-            apps: HashMap::new(),
-            active_window: None,
-            next_z_index: 100,
-            last_seq: 0,
-            event_store: args.event_store,
-        };
-
-        Ok(state)
+        Ok(DesktopState::new(args))
     }
 
     async fn post_start(
@@ -300,6 +401,10 @@ impl Actor for DesktopActor {
                 let result = self.handle_restore_window(window_id, state).await;
                 let _ = reply.send(result);
             }
+            DesktopActorMsg::SetDesktopBounds { bounds, reply } => {
+                let result = self.handle_set_desktop_bounds(bounds, state).await;
+                let _ = reply.send(result);
+            }
             DesktopActorMsg::GetWindows { reply } => {
                 let result = self.handle_get_windows(state);
                 let _ = reply.send(result);
@@ -312,6 +417,10 @@ impl Actor for DesktopActor {
                 let result = self.handle_register_app(app, state).await;
                 let _ = reply.send(result);
             }
+            DesktopActorMsg::ApplyTemplate { template, reply } => {
+                let result = self.handle_apply_template(template, state).await;
+                let _ = reply.send(result);
+            }
             DesktopActorMsg::GetApps { reply } => {
                 let result = self.handle_get_apps(state);
                 let _ = reply.send(result);
@@ -324,6 +433,7 @@ impl Actor for DesktopActor {
                 let _ = reply.send(result);
             }
         }
+        self.maybe_snapshot(state).await;
         Ok(())
     }
 
@@ -462,12 +572,17 @@ impl DesktopActor {
     fn project_events(&self, events: Vec<shared_types::Event>, state: &mut DesktopState) {
         for event in events {
             state.last_seq = event.seq;
+            state.events_since_snapshot += 1;
 
             match event.event_type.as_str() {
                 EVENT_WINDOW_OPENED => {
                     if let Ok(window) =
                         serde_json::from_value::<shared_types::WindowState>(event.payload.clone())
                     {
+                        // Opening took a z-index from the counter; keep later
+                        // windows above this one, as the live open did.
+                        state.next_z_index =
+                            state.next_z_index.max(window.z_index.saturating_add(1));
                         state.active_window = Some(window.id.clone());
                         state.windows.insert(window.id.clone(), window);
                     }
@@ -513,6 +628,12 @@ impl DesktopActor {
                                 {
                                     window.height = height as i32;
                                 }
+                                if let Some(x) = payload.get("x").and_then(|v| v.as_i64()) {
+                                    window.x = x as i32;
+                                }
+                                if let Some(y) = payload.get("y").and_then(|v| v.as_i64()) {
+                                    window.y = y as i32;
+                                }
                             }
                         }
                     }
@@ -647,6 +768,25 @@ impl DesktopActor {
                         }
                     }
                 }
+                EVENT_BOUNDS_CHANGED => {
+                    let field = |name: &str| {
+                        event
+                            .payload
+                            .get(name)
+                            .and_then(|v| v.as_i64())
+                            .map(|v| v as i32)
+                    };
+                    if let (Some(x), Some(y), Some(width), Some(height)) =
+                        (field("x"), field("y"), field("width"), field("height"))
+                    {
+                        state.bounds = WindowBounds {
+                            x,
+                            y,
+                            width,
+                            height,
+                        };
+                    }
+                }
                 EVENT_APP_REGISTERED => {
                     if let Ok(app) =
                         serde_json::from_value::<shared_types::AppDefinition>(event.payload.clone())
@@ -654,6 +794,13 @@ impl DesktopActor {
                         state.apps.insert(app.id.clone(), app);
                     }
                 }
+                EVENT_TEMPLATE_APPLIED => {
+                    if let Some(template_id) =
+                        event.payload.get("template_id").and_then(|v| v.as_str())
+                    {
+                        state.applied_templates.insert(template_id.to_string());
+                    }
+                }
                 _ => {} // Ignore other event types
             }
         }
@@ -681,12 +828,95 @@ impl DesktopActor {
         }
     }
 
+    /// Rebuild state from the latest snapshot plus the events after it, or
+    /// from every event when there is no usable snapshot. Returns how many
+    /// events were replayed, or `None` if the events could not be loaded.
+    async fn restore_from_store(&self, state: &mut DesktopState) -> Option<usize> {
+        let snapshot = match get_latest_snapshot(&state.event_store, state.desktop_id.clone()).await
+        {
+            Ok(Ok(snapshot)) => snapshot,
+            Ok(Err(e)) => {
+                tracing::warn!(desktop_id = %state.desktop_id, error = %e, "Failed to load desktop snapshot");
+                None
+            }
+            Err(e) => {
+                tracing::warn!(desktop_id = %state.desktop_id, error = %e, "Failed to load desktop snapshot");
+                None
+            }
+        };
+        let restored = snapshot.and_then(|snapshot| {
+            match serde_json::from_value::<DesktopSnapshot>(snapshot.state) {
+                Ok(restored) => Some((snapshot.seq, restored)),
+                Err(e) => {
+                    tracing::warn!(desktop_id = %state.desktop_id, error = %e, "Ignoring unreadable desktop snapshot");
+                    None
+                }
+            }
+        });
+        match restored {
+            Some((seq, restored)) => {
+                state.windows = restored.windows;
+                state.apps = restored.apps;
+                state.active_window = restored.active_window;
+                state.next_z_index = restored.next_z_index;
+                state.bounds = restored.bounds;
+                state.applied_templates = restored.applied_templates;
+                state.last_seq = seq;
+            }
+            None => state.last_seq = 0,
+        }
+        state.events_since_snapshot = 0;
+
+        let events = self.sync_with_event_store(state).await?;
+        let replayed = events.len();
+        self.project_events(events, state);
+        state.restored = true;
+        Some(replayed)
+    }
+
+    /// Write a snapshot once enough events have accumulated. Best-effort: a
+    /// failed write only means a longer replay after the next respawn.
+    async fn maybe_snapshot(&self, state: &mut DesktopState) {
+        if !state.restored
+            || state.snapshot_interval == 0
+            || state.events_since_snapshot < state.snapshot_interval
+        {
+            return;
+        }
+        let snapshot = DesktopSnapshot {
+            windows: state.windows.clone(),
+            apps: state.apps.clone(),
+            active_window: state.active_window.clone(),
+            next_z_index: state.next_z_index,
+            bounds: state.bounds,
+            applied_templates: state.applied_templates.clone(),
+        };
+        let snapshot = ActorSnapshot {
+            actor_id: state.desktop_id.clone(),
+            seq: state.last_seq,
+            state: match serde_json::to_value(snapshot) {
+                Ok(value) => value,
+                Err(_) => return,
+            },
+            created_at: chrono::Utc::now().to_rfc3339(),
+        };
+        match save_snapshot(&state.event_store, snapshot).await {
+            Ok(Ok(())) => state.events_since_snapshot = 0,
+            Ok(Err(e)) => {
+                tracing::warn!(desktop_id = %state.desktop_id, error = %e, "Failed to write desktop snapshot");
+            }
+            Err(e) => {
+                tracing::warn!(desktop_id = %state.desktop_id, error = %e, "Failed to write desktop snapshot");
+            }
+        }
+    }
+
     /// Append event to EventStore and return unit result
     async fn append_event_unit(
         &self,
         event_type: &str,
         payload: serde_json::Value,
-        state: &DesktopState,
+        state: &mut DesktopState,
     ) -> Result<(), DesktopError> {
         let result: Result<
             Result<shared_types::Event, EventStoreError>,
@@ -697,12 +927,17 @@ impl DesktopActor {
                 payload,
                 actor_id: state.desktop_id.clone(),
                 user_id: state.user_id.clone(),
+                idempotency_key: None,
             },
             reply,
         });
 
         match result {
-            Ok(Ok(_)) => Ok(()),
+            Ok(Ok(event)) => {
+                state.last_seq = event.seq;
+                state.events_since_snapshot += 1;
+                Ok(())
+            }
             Ok(Err(e)) => Err(DesktopError::EventStore(e.to_string())),
             Err(e) => Err(DesktopError::EventStore(format!("RPC error: {e}"))),
         }
@@ -725,6 +960,7 @@ impl DesktopActor {
                 payload,
                 actor_id: state.desktop_id.clone(),
                 user_id: state.user_id.clone(),
+                idempotency_key: None,
             },
             reply,
         });
@@ -754,6 +990,10 @@ impl DesktopActor {
         // Create window state
         let window_id = ulid::Ulid::new().to_string();
         let (x, y) = self.get_default_position(state, &app_id);
+        let (width, height) = state
+            .bounds
+            .clamp_size(app.default_width, app.default_height);
+        let (x, y) = state.bounds.clamp_position(x, y, width);
 
         let window = shared_types::WindowState {
             id: window_id.clone(),
@@ -761,8 +1001,8 @@ impl DesktopActor {
             title: title.clone(),
             x,
             y,
-            width: app.default_width,
-            height: app.default_height,
+            width,
+            height,
             z_index: self.next_z(state),
             minimized: false,
             maximized: false,
@@ -808,28 +1048,30 @@ impl DesktopActor {
         x: i32,
         y: i32,
         state: &mut DesktopState,
-    ) -> Result<(), DesktopError> {
+    ) -> Result<shared_types::WindowState, DesktopError> {
         // Update memory
-        if let Some(window) = state.windows.get_mut(&window_id) {
+        let bounds = state.bounds;
+        let window = if let Some(window) = state.windows.get_mut(&window_id) {
             if window.maximized {
                 return Err(DesktopError::InvalidOperation(
                     "Cannot move a maximized window".to_string(),
                 ));
             }
-            window.x = x;
-            window.y = y;
+            (window.x, window.y) = bounds.clamp_position(x, y, window.width);
+            window.clone()
         } else {
             return Err(DesktopError::WindowNotFound(window_id));
-        }
+        };
 
-        // Append event
+        // Append event with the clamped position
         let payload = serde_json::json!({
             "window_id": window_id,
-            "x": x,
-            "y": y,
+            "x": window.x,
+            "y": window.y,
         });
         self.append_event_unit(EVENT_WINDOW_MOVED, payload, state)
-            .await
+            .await?;
+        Ok(window)
     }
 
     async fn handle_resize_window(
@@ -838,7 +1080,7 @@ impl DesktopActor {
         width: i32,
         height: i32,
         state: &mut DesktopState,
-    ) -> Result<(), DesktopError> {
+    ) -> Result<shared_types::WindowState, DesktopError> {
         // Update memory
         if width < MIN_WINDOW_WIDTH || height < MIN_WINDOW_HEIGHT {
             return Err(DesktopError::InvalidOperation(format!(
@@ -846,26 +1088,80 @@ impl DesktopActor {
             )));
         }
 
-        if let Some(window) = state.windows.get_mut(&window_id) {
+        let bounds = state.bounds;
+        let window = if let Some(window) = state.windows.get_mut(&window_id) {
             if window.maximized {
                 return Err(DesktopError::InvalidOperation(
                     "Cannot resize a maximized window".to_string(),
                 ));
             }
-            window.width = width;
-            window.height = height;
+            (window.width, window.height) = bounds.clamp_size(width, height);
+            // A narrower window can leave its title bar off the left edge.
+            (window.x, window.y) = bounds.clamp_position(window.x, window.y, window.width);
+            window.clone()
         } else {
             return Err(DesktopError::WindowNotFound(window_id));
-        }
+        };
 
-        // Append event
+        // Append event with the clamped size and position
         let payload = serde_json::json!({
             "window_id": window_id,
-            "width": width,
-            "height": height,
+            "width": window.width,
+            "height": window.height,
+            "x": window.x,
+            "y": window.y,
         });
         self.append_event_unit(EVENT_WINDOW_RESIZED, payload, state)
-            .await
+            .await?;
+        Ok(window)
+    }
+
+    async fn handle_set_desktop_bounds(
+        &self,
+        bounds: WindowBounds,
+        state: &mut DesktopState,
+    ) -> Result<Vec<shared_types::WindowState>, DesktopError> {
+        if bounds.width < MIN_WINDOW_WIDTH || bounds.height < MIN_WINDOW_HEIGHT {
+            return Err(DesktopError::InvalidOperation(format!(
+                "Desktop bounds below minimum: {}x{} (min {MIN_WINDOW_WIDTH}x{MIN_WINDOW_HEIGHT})",
+                bounds.width, bounds.height
+            )));
+        }
+
+        state.bounds = bounds;
+        let payload = serde_json::json!({
+            "x": bounds.x,
+            "y": bounds.y,
+            "width": bounds.width,
+            "height": bounds.height,
+        });
+        self.append_event_unit(EVENT_BOUNDS_CHANGED, payload, state)
+            .await?;
+
+        // Maximized windows follow the work area; minimized ones aren't shown.
+        let mut moved = Vec::new();
+        for window in state.windows.values_mut() {
+            if window.maximized || window.minimized {
+                continue;
+            }
+            let (x, y) = bounds.clamp_position(window.x, window.y, window.width);
+            if (x, y) != (window.x, window.y) {
+                window.x = x;
+                window.y = y;
+                moved.push(window.clone());
+            }
+        }
+        moved.sort_by_key(|window| window.z_index);
+        for window in &moved {
+            let payload = serde_json::json!({
+                "window_id": window.id,
+                "x": window.x,
+                "y": window.y,
+            });
+            self.append_event_unit(EVENT_WINDOW_MOVED, payload, state)
+                .await?;
+        }
+        Ok(moved)
     }
 
     async fn handle_focus_window(
@@ -1126,11 +1422,9 @@ impl DesktopActor {
         &self,
         state: &mut DesktopState,
     ) -> shared_types::DesktopState {
-        // If events haven't been synced yet, sync first
-        if state.last_seq == 0 {
-            if let Some(events) = self.sync_with_event_store(state).await {
-                self.project_events(events, state);
-            }
+        // If state hasn't been rebuilt since spawn, restore it first
+        if !state.restored {
+            self.restore_from_store(state).await;
         }
 
         // Build and return state
@@ -1159,6 +1453,45 @@ impl DesktopActor {
             .await
     }
 
+    async fn handle_apply_template(
+        &self,
+        template: shared_types::DesktopTemplate,
+        state: &mut DesktopState,
+    ) -> Result<Vec<shared_types::WindowState>, DesktopError> {
+        if !state.restored {
+            self.restore_from_store(state).await;
+        }
+        if state.applied_templates.contains(&template.id) {
+            return Ok(Vec::new());
+        }
+        // Check every app up front so a template never opens half its windows.
+        if let Some(missing) = template
+            .windows
+            .iter()
+            .find(|window| !state.apps.contains_key(&window.app_id))
+        {
+            return Err(DesktopError::AppNotFound(missing.app_id.clone()));
+        }
+
+        let mut opened = Vec::with_capacity(template.windows.len());
+        for window in template.windows {
+            let props = (!window.props.is_null()).then_some(window.props);
+            opened.push(
+                self.handle_open_window(window.app_id, window.title, props, state)
+                    .await?,
+            );
+        }
+
+        let payload = serde_json::json!({
+            "template_id": template.id,
+            "window_ids": opened.iter().map(|w| w.id.as_str()).collect::<Vec<_>>(),
+        });
+        self.append_event_unit(EVENT_TEMPLATE_APPLIED, payload, state)
+            .await?;
+        state.applied_templates.insert(template.id);
+        Ok(opened)
+    }
+
     fn handle_get_apps(&self, state: &DesktopState) -> Vec<shared_types::AppDefinition> {
         state.apps.values().cloned().collect()
     }
@@ -1200,7 +1533,7 @@ pub async fn move_window(
     window_id: impl Into<String>,
     x: i32,
     y: i32,
-) -> Result<Result<(), DesktopError>, ractor::RactorErr<DesktopActorMsg>> {
+) -> Result<Result<shared_types::WindowState, DesktopError>, ractor::RactorErr<DesktopActorMsg>> {
     ractor::call!(desktop, |reply| DesktopActorMsg::MoveWindow {
         window_id: window_id.into(),
         x,
@@ -1215,7 +1548,7 @@ pub async fn resize_window(
     window_id: impl Into<String>,
     width: i32,
     height: i32,
-) -> Result<Result<(), DesktopError>, ractor::RactorErr<DesktopActorMsg>> {
+) -> Result<Result<shared_types::WindowState, DesktopError>, ractor::RactorErr<DesktopActorMsg>> {
     ractor::call!(desktop, |reply| DesktopActorMsg::ResizeWindow {
         window_id: window_id.into(),
         width,
@@ -1224,6 +1557,18 @@ pub async fn resize_window(
     })
 }
 
+/// Convenience function to set the desktop bounds
+pub async fn set_desktop_bounds(
+    desktop: &ActorRef<DesktopActorMsg>,
+    bounds: WindowBounds,
+) -> Result<Result<Vec<shared_types::WindowState>, DesktopError>, ractor::RactorErr<DesktopActorMsg>>
+{
+    ractor::call!(desktop, |reply| DesktopActorMsg::SetDesktopBounds {
+        bounds,
+        reply
+    })
+}
+
 /// Convenience function to focus a window
 pub async fn focus_window(
     desktop: &ActorRef<DesktopActorMsg>,
@@ -1291,6 +1636,18 @@ pub async fn register_app(
     ractor::call!(desktop, |reply| DesktopActorMsg::RegisterApp { app, reply })
 }
 
+/// Convenience function to apply a desktop template
+pub async fn apply_template(
+    desktop: &ActorRef<DesktopActorMsg>,
+    template: shared_types::DesktopTemplate,
+) -> Result<Result<Vec<shared_types::WindowState>, DesktopError>, ractor::RactorErr<DesktopActorMsg>>
+{
+    ractor::call!(desktop, |reply| DesktopActorMsg::ApplyTemplate {
+        template,
+        reply
+    })
+}
+
 /// Convenience function to get all apps
 pub async fn get_apps(
     desktop: &ActorRef<DesktopActorMsg>,
@@ -1322,7 +1679,7 @@ pub async fn get_actor_info(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::actors::event_store::{EventStoreActor, EventStoreArguments};
+    use crate::actors::event_store::{get_events_for_actor, EventStoreActor, EventStoreArguments};
     use ractor::Actor;
 
     // ============================================================================
@@ -1343,6 +1700,7 @@ mod tests {
                 desktop_id: "desktop-1".to_string(),
                 user_id: "user-1".to_string(),
                 event_store: event_store.clone(),
+                snapshot_interval: DESKTOP_SNAPSHOT_INTERVAL,
             },
         )
         .await
@@ -1399,6 +1757,7 @@ mod tests {
                 desktop_id: "desktop-1".to_string(),
                 user_id: "user-1".to_string(),
                 event_store: event_store.clone(),
+                snapshot_interval: DESKTOP_SNAPSHOT_INTERVAL,
             },
         )
         .await
@@ -1434,6 +1793,7 @@ mod tests {
                 desktop_id: "desktop-1".to_string(),
                 user_id: "user-1".to_string(),
                 event_store: event_store.clone(),
+                snapshot_interval: DESKTOP_SNAPSHOT_INTERVAL,
             },
         )
         .await
@@ -1492,6 +1852,7 @@ mod tests {
                 desktop_id: "desktop-1".to_string(),
                 user_id: "user-1".to_string(),
                 event_store: event_store.clone(),
+                snapshot_interval: DESKTOP_SNAPSHOT_INTERVAL,
             },
         )
         .await
@@ -1551,6 +1912,7 @@ mod tests {
                 desktop_id: "desktop-1".to_string(),
                 user_id: "user-1".to_string(),
                 event_store: event_store.clone(),
+                snapshot_interval: DESKTOP_SNAPSHOT_INTERVAL,
             },
         )
         .await
@@ -1614,6 +1976,7 @@ mod tests {
                 desktop_id: "desktop-1".to_string(),
                 user_id: "user-1".to_string(),
                 event_store: event_store.clone(),
+                snapshot_interval: DESKTOP_SNAPSHOT_INTERVAL,
             },
         )
         .await
@@ -1669,6 +2032,7 @@ mod tests {
                 desktop_id: "desktop-1".to_string(),
                 user_id: "user-1".to_string(),
                 event_store: event_store.clone(),
+                snapshot_interval: DESKTOP_SNAPSHOT_INTERVAL,
             },
         )
         .await
@@ -1714,6 +2078,7 @@ mod tests {
                 desktop_id: "desktop-1".to_string(),
                 user_id: "user-1".to_string(),
                 event_store: event_store.clone(),
+                snapshot_interval: DESKTOP_SNAPSHOT_INTERVAL,
             },
         )
         .await
@@ -1770,6 +2135,7 @@ mod tests {
                 desktop_id: "desktop-1".to_string(),
                 user_id: "user-1".to_string(),
                 event_store: event_store.clone(),
+                snapshot_interval: DESKTOP_SNAPSHOT_INTERVAL,
             },
         )
         .await
@@ -1839,6 +2205,7 @@ mod tests {
                 desktop_id: "desktop-1".to_string(),
                 user_id: "user-1".to_string(),
                 event_store: event_store.clone(),
+                snapshot_interval: DESKTOP_SNAPSHOT_INTERVAL,
             },
         )
         .await
@@ -1899,6 +2266,7 @@ mod tests {
                 desktop_id: "desktop-1".to_string(),
                 user_id: "user-1".to_string(),
                 event_store: event_store.clone(),
+                snapshot_interval: DESKTOP_SNAPSHOT_INTERVAL,
             },
         )
         .await
@@ -1952,6 +2320,7 @@ mod tests {
                 desktop_id: "desktop-1".to_string(),
                 user_id: "user-1".to_string(),
                 event_store: event_store.clone(),
+                snapshot_interval: DESKTOP_SNAPSHOT_INTERVAL,
             },
         )
         .await
@@ -2020,6 +2389,7 @@ mod tests {
                 desktop_id: "desktop-1".to_string(),
                 user_id: "user-1".to_string(),
                 event_store: event_store.clone(),
+                snapshot_interval: DESKTOP_SNAPSHOT_INTERVAL,
             },
         )
         .await
@@ -2068,6 +2438,7 @@ mod tests {
                 desktop_id: "desktop-1".to_string(),
                 user_id: "user-1".to_string(),
                 event_store: event_store.clone(),
+                snapshot_interval: DESKTOP_SNAPSHOT_INTERVAL,
             },
         )
         .await
@@ -2104,4 +2475,398 @@ mod tests {
 
         desktop.stop(None);
     }
+
+    #[test]
+    fn test_clamp_position_keeps_title_bar_in_bounds() {
+        let bounds = WindowBounds {
+            x: 0,
+            y: 0,
+            width: 1280,
+            height: 800,
+        };
+        assert_eq!(bounds.clamp_position(100, 100, 400), (100, 100));
+        assert_eq!(
+            bounds.clamp_position(-5000, -40, 400),
+            (MIN_VISIBLE_TITLE_BAR_WIDTH - 400, 0)
+        );
+        assert_eq!(
+            bounds.clamp_position(9000, 9000, 400),
+            (1280 - MIN_VISIBLE_TITLE_BAR_WIDTH, 800 - TITLE_BAR_HEIGHT)
+        );
+    }
+
+    #[test]
+    fn test_clamp_position_handles_large_virtual_bounds() {
+        // A monitor to the left of the primary one puts the origin below zero.
+        let bounds = WindowBounds {
+            x: -3840,
+            y: -1080,
+            width: 7680,
+            height: 3240,
+        };
+        assert_eq!(bounds.clamp_position(-3000, -500, 800), (-3000, -500));
+        assert_eq!(
+            bounds.clamp_position(i32::MIN, i32::MAX, 800),
+            (
+                -3840 + MIN_VISIBLE_TITLE_BAR_WIDTH - 800,
+                -1080 + 3240 - TITLE_BAR_HEIGHT
+            )
+        );
+        let huge = WindowBounds {
+            x: i32::MAX - 10,
+            y: 0,
+            width: i32::MAX,
+            height: i32::MAX,
+        };
+        let (x, _) = huge.clamp_position(i32::MAX, 0, 400);
+        assert_eq!(x, i32::MAX);
+    }
+
+    #[tokio::test]
+    async fn test_off_screen_move_is_clamped_into_bounds() {
+        let (event_store, _handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .unwrap();
+
+        let (desktop, _handle) = Actor::spawn(
+            None,
+            DesktopActor,
+            DesktopArguments {
+                desktop_id: "desktop-1".to_string(),
+                user_id: "user-1".to_string(),
+                event_store: event_store.clone(),
+                snapshot_interval: DESKTOP_SNAPSHOT_INTERVAL,
+            },
+        )
+        .await
+        .unwrap();
+
+        let _ = register_app(
+            &desktop,
+            shared_types::AppDefinition {
+                id: "test-app".to_string(),
+                name: "Test App".to_string(),
+                icon: "🧩".to_string(),
+                component_code: "TestApp".to_string(),
+                default_width: 800,
+                default_height: 600,
+            },
+        )
+        .await
+        .unwrap();
+
+        let bounds = WindowBounds {
+            x: 0,
+            y: 0,
+            width: 1280,
+            height: 800,
+        };
+        set_desktop_bounds(&desktop, bounds).await.unwrap().unwrap();
+
+        let window = open_window(&desktop, "test-app", "Test App", None)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let moved = move_window(&desktop, &window.id, -2000, -300)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!((moved.x, moved.y), (MIN_VISIBLE_TITLE_BAR_WIDTH - 800, 0));
+
+        let moved = move_window(&desktop, &window.id, 5000, 5000)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            (moved.x, moved.y),
+            (1280 - MIN_VISIBLE_TITLE_BAR_WIDTH, 800 - TITLE_BAR_HEIGHT)
+        );
+
+        // Shrinking the desktop pulls the window back and reports it.
+        let reclamped = set_desktop_bounds(
+            &desktop,
+            WindowBounds {
+                x: 0,
+                y: 0,
+                width: 1024,
+                height: 600,
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(reclamped.len(), 1);
+        assert_eq!(
+            (reclamped[0].x, reclamped[0].y),
+            (1024 - MIN_VISIBLE_TITLE_BAR_WIDTH, 600 - TITLE_BAR_HEIGHT)
+        );
+
+        // The emitted events carry the clamped values.
+        let events = ractor::call!(event_store, |reply| EventStoreMsg::GetEventsForActor {
+            actor_id: "desktop-1".to_string(),
+            since_seq: 0,
+            reply,
+        })
+        .unwrap()
+        .unwrap();
+        let last_move = events
+            .iter()
+            .rev()
+            .find(|event| event.event_type == EVENT_WINDOW_MOVED)
+            .unwrap();
+        assert_eq!(last_move.payload["x"], 1024 - MIN_VISIBLE_TITLE_BAR_WIDTH);
+        assert_eq!(last_move.payload["y"], 600 - TITLE_BAR_HEIGHT);
+
+        desktop.stop(None);
+        event_store.stop(None);
+    }
+
+    #[tokio::test]
+    async fn test_respawn_with_snapshot_replays_only_recent_events() {
+        let (event_store, _handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .unwrap();
+        let args = DesktopArguments {
+            desktop_id: "desktop-1".to_string(),
+            user_id: "user-1".to_string(),
+            event_store: event_store.clone(),
+            snapshot_interval: DESKTOP_SNAPSHOT_INTERVAL,
+        };
+
+        let (desktop, _handle) = Actor::spawn(None, DesktopActor, args.clone())
+            .await
+            .unwrap();
+        get_desktop_state(&desktop).await.unwrap();
+        register_app(
+            &desktop,
+            shared_types::AppDefinition {
+                id: "test-app".to_string(),
+                name: "Test App".to_string(),
+                icon: "🧩".to_string(),
+                component_code: "TestApp".to_string(),
+                default_width: 800,
+                default_height: 600,
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        let window = open_window(&desktop, "test-app", "Window", None)
+            .await
+            .unwrap()
+            .unwrap();
+        for step in 0..30 {
+            move_window(&desktop, &window.id, 100 + step, 100 + step)
+                .await
+                .unwrap()
+                .unwrap();
+        }
+        let before = get_desktop_state(&desktop).await.unwrap();
+        desktop.stop(None);
+
+        let all_events = ractor::call!(event_store, |reply| EventStoreMsg::GetEventsForActor {
+            actor_id: "desktop-1".to_string(),
+            since_seq: 0,
+            reply,
+        })
+        .unwrap()
+        .unwrap();
+        let total_events = all_events.len();
+        assert_eq!(total_events, 32);
+
+        // Respawned state restores the snapshot and replays what came after.
+        let mut with_snapshot = DesktopState::new(args.clone());
+        let replayed = DesktopActor
+            .restore_from_store(&mut with_snapshot)
+            .await
+            .unwrap();
+        assert!(
+            replayed < total_events,
+            "replayed {replayed} of {total_events} events despite a snapshot"
+        );
+        assert_eq!(
+            replayed as u64,
+            total_events as u64 % DESKTOP_SNAPSHOT_INTERVAL
+        );
+        let restored = &with_snapshot.windows[&window.id];
+        assert_eq!((restored.x, restored.y), (129, 129));
+        assert_eq!(with_snapshot.windows.len(), before.windows.len());
+        assert_eq!(with_snapshot.active_window, before.active_window);
+        assert!(with_snapshot.apps.contains_key("test-app"));
+
+        // Without a snapshot, recovery replays every event to the same state.
+        let mut full_replay = DesktopState::new(args);
+        DesktopActor.project_events(all_events, &mut full_replay);
+        assert_eq!(full_replay.windows, with_snapshot.windows);
+        assert_eq!(full_replay.last_seq, with_snapshot.last_seq);
+
+        event_store.stop(None);
+    }
+
+    #[tokio::test]
+    async fn test_apply_template_is_idempotent_across_respawn() {
+        let (event_store, _handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .unwrap();
+        let args = DesktopArguments {
+            desktop_id: "desktop-1".to_string(),
+            user_id: "user-1".to_string(),
+            event_store: event_store.clone(),
+            snapshot_interval: DESKTOP_SNAPSHOT_INTERVAL,
+        };
+        let template = shared_types::DesktopTemplate {
+            id: "research".to_string(),
+            name: "Research".to_string(),
+            description: String::new(),
+            windows: vec![
+                shared_types::DesktopTemplateWindow {
+                    app_id: "test-app".to_string(),
+                    title: "Notes".to_string(),
+                    props: serde_json::json!({"path": "notes.md"}),
+                },
+                shared_types::DesktopTemplateWindow {
+                    app_id: "test-app".to_string(),
+                    title: "Scratch".to_string(),
+                    props: serde_json::Value::Null,
+                },
+            ],
+            seed_files: Vec::new(),
+        };
+
+        let (desktop, _handle) = Actor::spawn(None, DesktopActor, args.clone())
+            .await
+            .unwrap();
+        let missing_app = apply_template(&desktop, template.clone()).await.unwrap();
+        assert!(matches!(missing_app, Err(DesktopError::AppNotFound(_))));
+        assert!(get_windows(&desktop).await.unwrap().is_empty());
+
+        register_app(
+            &desktop,
+            shared_types::AppDefinition {
+                id: "test-app".to_string(),
+                name: "Test App".to_string(),
+                icon: "🧩".to_string(),
+                component_code: "TestApp".to_string(),
+                default_width: 800,
+                default_height: 600,
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        let opened = apply_template(&desktop, template.clone())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(opened.len(), 2);
+        assert_eq!(opened[0].props["path"], "notes.md");
+        assert_eq!(opened[1].props, serde_json::json!({}));
+
+        let again = apply_template(&desktop, template.clone())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(again.is_empty());
+        desktop.stop(None);
+
+        let (respawned, _handle) = Actor::spawn(None, DesktopActor, args).await.unwrap();
+        let after_respawn = apply_template(&respawned, template).await.unwrap().unwrap();
+        assert!(after_respawn.is_empty());
+        assert_eq!(get_windows(&respawned).await.unwrap().len(), 2);
+
+        respawned.stop(None);
+        event_store.stop(None);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_interval_is_configurable() {
+        let (event_store, _handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .unwrap();
+        let run = |desktop_id: &str, snapshot_interval: u64| {
+            let args = DesktopArguments {
+                desktop_id: desktop_id.to_string(),
+                user_id: "user-1".to_string(),
+                event_store: event_store.clone(),
+                snapshot_interval,
+            };
+            async move {
+                let (desktop, _handle) = Actor::spawn(None, DesktopActor, args.clone())
+                    .await
+                    .unwrap();
+                get_desktop_state(&desktop).await.unwrap();
+                register_app(
+                    &desktop,
+                    shared_types::AppDefinition {
+                        id: "test-app".to_string(),
+                        name: "Test App".to_string(),
+                        icon: "🧩".to_string(),
+                        component_code: "TestApp".to_string(),
+                        default_width: 800,
+                        default_height: 600,
+                    },
+                )
+                .await
+                .unwrap()
+                .unwrap();
+                for title in ["One", "Two"] {
+                    let window = open_window(&desktop, "test-app", title, None)
+                        .await
+                        .unwrap()
+                        .unwrap();
+                    for step in 0..3 {
+                        move_window(&desktop, &window.id, 150 + step, 120 + step)
+                            .await
+                            .unwrap()
+                            .unwrap();
+                    }
+                }
+                desktop.stop(None);
+                args
+            }
+        };
+
+        // 9 events with an interval of 4: snapshots after events 4 and 8.
+        let args = run("desktop-every-4", 4).await;
+        let snapshot = get_latest_snapshot(&event_store, "desktop-every-4".to_string())
+            .await
+            .unwrap()
+            .unwrap()
+            .expect("snapshot written");
+        let all_events = get_events_for_actor(&event_store, "desktop-every-4", 0)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(all_events.len(), 9);
+        assert_eq!(snapshot.seq, all_events[7].seq);
+
+        let mut from_snapshot = DesktopState::new(args.clone());
+        let replayed = DesktopActor
+            .restore_from_store(&mut from_snapshot)
+            .await
+            .unwrap();
+        assert_eq!(replayed, 1);
+        let mut full_replay = DesktopState::new(args);
+        DesktopActor.project_events(all_events, &mut full_replay);
+        assert_eq!(from_snapshot.windows, full_replay.windows);
+        assert_eq!(from_snapshot.apps, full_replay.apps);
+        assert_eq!(from_snapshot.active_window, full_replay.active_window);
+        assert_eq!(from_snapshot.next_z_index, full_replay.next_z_index);
+        assert_eq!(from_snapshot.last_seq, full_replay.last_seq);
+
+        // An interval of 0 never snapshots.
+        run("desktop-no-snapshots", 0).await;
+        let none = get_latest_snapshot(&event_store, "desktop-no-snapshots".to_string())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(none.is_none());
+
+        event_store.stop(None);
+    }
 }
@@ -96,6 +96,15 @@ impl Event {
         }
     }
 
+    /// Whether this is a replay of a recorded event rather than a new one.
+    /// Projections skip replays; they were never appended to the store.
+    pub fn is_replayed(&self) -> bool {
+        self.payload
+            .get("replayed")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false)
+    }
+
     /// Set correlation ID (builder pattern)
     pub fn with_correlation_id(mut self, id: impl Into<String>) -> Self {
         self.correlation_id = Some(id.into());
@@ -364,6 +373,7 @@ impl EventBusActor {
                     payload: event.payload.clone(),
                     actor_id: event.source.clone(),
                     user_id: "system".to_string(), // TODO: Extract from event
+                    idempotency_key: None,
                 };
 
                 // Fire-and-forget persistence (don't block publish on store)
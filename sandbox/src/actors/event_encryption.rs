@@ -0,0 +1,394 @@
+//! Field-level encryption of sensitive event payloads.
+//!
+//! Chat content and document versions would otherwise sit in plaintext in the
+//! sandbox's sqlite file. For the configured event types the EventStoreActor
+//! seals the listed payload fields with AES-256-GCM before the row is written
+//! and opens them again on every read through the actor. Rows sealed this way
+//! carry the id of their key in the `encryption_key_id` column, and the sealed
+//! field paths under `encrypted_fields` in the payload, so a log with both
+//! plaintext and ciphertext rows reads back uniformly.
+//!
+//! The key is derived from a per-sandbox secret the hypervisor passes at spawn
+//! (`CHOIR_EVENT_ENCRYPTION_KEY`); standalone sandboxes may point
+//! `CHOIR_EVENT_ENCRYPTION_KEY_FILE` at a file holding one. Secrets listed in
+//! `CHOIR_EVENT_ENCRYPTION_PREVIOUS_KEYS` still open older rows, and
+//! [`EventStoreMsg::ReencryptBatch`](super::event_store::EventStoreMsg::ReencryptBatch)
+//! re-seals them under the current key. Without a key new rows are written in
+//! plaintext, and sealed rows are still listed with each sealed field replaced
+//! by [`LOCKED_CONTENT`] and `content_locked: true` set on the payload.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::hkdf;
+use ring::rand::{SecureRandom, SystemRandom};
+
+/// Env var carrying the current secret in managed mode.
+pub const EVENT_KEY_ENV: &str = "CHOIR_EVENT_ENCRYPTION_KEY";
+/// Env var naming a file that holds the current secret in standalone mode.
+pub const EVENT_KEY_FILE_ENV: &str = "CHOIR_EVENT_ENCRYPTION_KEY_FILE";
+/// Env var with comma-separated secrets of keys being rotated out.
+pub const PREVIOUS_EVENT_KEYS_ENV: &str = "CHOIR_EVENT_ENCRYPTION_PREVIOUS_KEYS";
+
+/// Payload key listing the field paths sealed in a stored row.
+pub const ENCRYPTED_FIELDS_KEY: &str = "encrypted_fields";
+/// Payload flag set when sealed fields could not be opened.
+pub const CONTENT_LOCKED_KEY: &str = "content_locked";
+/// Stand-in for a sealed field whose key is not available.
+pub const LOCKED_CONTENT: &str = "[encrypted content locked]";
+
+/// Event types sealed by default, with the dotted payload paths of their
+/// content fields. Paths missing from a payload are skipped.
+pub const DEFAULT_ENCRYPTED_FIELDS: &[(&str, &[&str])] = &[
+    ("chat.user_msg", &["text", "content"]),
+    ("chat.assistant_msg", &["text", "content"]),
+    (shared_types::EVENT_TOPIC_USER_INPUT, &["record.content"]),
+    // Document versions are carried as full-body patches.
+    (shared_types::EVENT_TOPIC_WRITER_RUN_PATCH, &["ops"]),
+];
+
+const KDF_SALT: &[u8] = b"choiros-event-store";
+const KDF_KEY_INFO: &[u8] = b"event payload aes-256-gcm v1";
+const KDF_ID_INFO: &[u8] = b"event payload key id v1";
+
+struct OkmLen(usize);
+
+impl hkdf::KeyType for OkmLen {
+    fn len(&self) -> usize {
+        self.0
+    }
+}
+
+/// AES-256-GCM key derived from a sandbox secret.
+pub struct EventKey {
+    id: String,
+    key: LessSafeKey,
+}
+
+impl std::fmt::Debug for EventKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventKey").field("id", &self.id).finish()
+    }
+}
+
+impl EventKey {
+    /// Derive the key and its public id from `secret` with HKDF-SHA256.
+    pub fn derive(secret: &str) -> Self {
+        let prk = hkdf::Salt::new(hkdf::HKDF_SHA256, KDF_SALT).extract(secret.trim().as_bytes());
+        let key = prk
+            .expand(&[KDF_KEY_INFO], &AES_256_GCM)
+            .map(UnboundKey::from)
+            .expect("AES-256-GCM is a valid HKDF output length");
+        let mut id = [0u8; 8];
+        prk.expand(&[KDF_ID_INFO], OkmLen(id.len()))
+            .and_then(|okm| okm.fill(&mut id))
+            .expect("8 bytes is a valid HKDF output length");
+        Self {
+            id: hex::encode(id),
+            key: LessSafeKey::new(key),
+        }
+    }
+
+    /// Stored in `encryption_key_id`; reveals nothing about the key.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn seal(&self, aad: &str, plaintext: &[u8]) -> Result<String, String> {
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| "failed to generate nonce".to_string())?;
+        let mut sealed = plaintext.to_vec();
+        self.key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(aad.as_bytes()),
+                &mut sealed,
+            )
+            .map_err(|_| "failed to seal field".to_string())?;
+        let mut out = nonce.to_vec();
+        out.extend_from_slice(&sealed);
+        Ok(base64::engine::general_purpose::STANDARD.encode(out))
+    }
+
+    fn open(&self, aad: &str, sealed: &str) -> Option<Vec<u8>> {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(sealed)
+            .ok()?;
+        if bytes.len() < NONCE_LEN {
+            return None;
+        }
+        let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce).ok()?;
+        let mut in_out = ciphertext.to_vec();
+        let plaintext = self
+            .key
+            .open_in_place(nonce, Aad::from(aad.as_bytes()), &mut in_out)
+            .ok()?;
+        Some(plaintext.to_vec())
+    }
+}
+
+/// Which event fields are sealed, and the keys to seal and open them with.
+#[derive(Debug, Clone)]
+pub struct EventEncryption {
+    fields: HashMap<String, Vec<String>>,
+    /// Current key first, then keys being rotated out.
+    keys: Vec<Arc<EventKey>>,
+}
+
+impl Default for EventEncryption {
+    /// Default fields without any key: nothing is sealed, sealed rows read
+    /// back locked.
+    fn default() -> Self {
+        Self {
+            fields: DEFAULT_ENCRYPTED_FIELDS
+                .iter()
+                .map(|(event_type, paths)| {
+                    (
+                        event_type.to_string(),
+                        paths.iter().map(|path| path.to_string()).collect(),
+                    )
+                })
+                .collect(),
+            keys: Vec::new(),
+        }
+    }
+}
+
+impl EventEncryption {
+    /// Default fields sealed with `key`.
+    pub fn with_key(key: EventKey) -> Self {
+        Self::default().with_previous_keys([key])
+    }
+
+    /// Add keys that still open older rows. The first key ever added is the
+    /// one new rows are sealed with.
+    pub fn with_previous_keys(mut self, keys: impl IntoIterator<Item = EventKey>) -> Self {
+        for key in keys {
+            if !self.keys.iter().any(|known| known.id == key.id) {
+                self.keys.push(Arc::new(key));
+            }
+        }
+        self
+    }
+
+    /// Seal `paths` of `event_type` payloads instead of the default fields.
+    pub fn with_fields(mut self, event_type: &str, paths: &[&str]) -> Self {
+        self.fields.insert(
+            event_type.to_string(),
+            paths.iter().map(|path| path.to_string()).collect(),
+        );
+        self
+    }
+
+    /// Keys from the environment; see the module docs. Unreadable key files
+    /// are logged and treated as absent.
+    pub fn from_env() -> Self {
+        let current = std::env::var(EVENT_KEY_ENV)
+            .ok()
+            .filter(|secret| !secret.trim().is_empty())
+            .or_else(|| {
+                let path = std::env::var(EVENT_KEY_FILE_ENV).ok()?;
+                match std::fs::read_to_string(&path) {
+                    Ok(secret) if !secret.trim().is_empty() => Some(secret),
+                    Ok(_) => None,
+                    Err(e) => {
+                        tracing::warn!(path = %path, error = %e, "Failed to read event encryption key file");
+                        None
+                    }
+                }
+            });
+        let Some(current) = current else {
+            return Self::default();
+        };
+        let previous = std::env::var(PREVIOUS_EVENT_KEYS_ENV).unwrap_or_default();
+        Self::with_key(EventKey::derive(&current)).with_previous_keys(
+            previous
+                .split(',')
+                .filter(|secret| !secret.trim().is_empty())
+                .map(EventKey::derive),
+        )
+    }
+
+    /// Whether new rows are sealed.
+    pub fn is_enabled(&self) -> bool {
+        !self.keys.is_empty()
+    }
+
+    /// Whether a configured key has id `key_id`.
+    pub fn has_key(&self, key_id: &str) -> bool {
+        self.keys.iter().any(|key| key.id == key_id)
+    }
+
+    /// Id of the key new rows are sealed with.
+    pub fn current_key_id(&self) -> Option<&str> {
+        self.keys.first().map(|key| key.id())
+    }
+
+    /// Seal the configured fields of `payload` in place. Returns the id of
+    /// the key used, or `None` when nothing was sealed.
+    pub fn seal(
+        &self,
+        event_type: &str,
+        event_id: &str,
+        payload: &mut serde_json::Value,
+    ) -> Result<Option<String>, String> {
+        let (Some(key), Some(paths)) = (self.keys.first(), self.fields.get(event_type)) else {
+            return Ok(None);
+        };
+        let mut sealed_paths = Vec::new();
+        for path in paths {
+            let Some(field) = field_mut(payload, path) else {
+                continue;
+            };
+            let plaintext = serde_json::to_vec(field).map_err(|e| e.to_string())?;
+            *field = serde_json::Value::String(key.seal(&aad(event_id, path), &plaintext)?);
+            sealed_paths.push(serde_json::Value::String(path.clone()));
+        }
+        if sealed_paths.is_empty() {
+            return Ok(None);
+        }
+        if let Some(obj) = payload.as_object_mut() {
+            obj.insert(
+                ENCRYPTED_FIELDS_KEY.to_string(),
+                serde_json::Value::Array(sealed_paths),
+            );
+        }
+        Ok(Some(key.id().to_string()))
+    }
+
+    /// Open the fields of a row sealed under `key_id` in place. Without that
+    /// key the fields read as [`LOCKED_CONTENT`] and the payload is flagged
+    /// `content_locked`; returns whether the fields were opened.
+    pub fn open(&self, key_id: &str, event_id: &str, payload: &mut serde_json::Value) -> bool {
+        let paths: Vec<String> = payload
+            .get(ENCRYPTED_FIELDS_KEY)
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+            .unwrap_or_default();
+        let key = self.keys.iter().find(|key| key.id == key_id);
+        let opened: Option<Vec<serde_json::Value>> = key.and_then(|key| {
+            paths
+                .iter()
+                .map(|path| {
+                    let sealed = field_mut(payload, path)?.as_str()?;
+                    let plaintext = key.open(&aad(event_id, path), sealed)?;
+                    serde_json::from_slice(&plaintext).ok()
+                })
+                .collect()
+        });
+
+        let opened_ok = opened.is_some();
+        let values = opened.unwrap_or_else(|| {
+            vec![serde_json::Value::String(LOCKED_CONTENT.to_string()); paths.len()]
+        });
+        for (path, value) in paths.iter().zip(values) {
+            if let Some(field) = field_mut(payload, path) {
+                *field = value;
+            }
+        }
+        if let Some(obj) = payload.as_object_mut() {
+            if opened_ok {
+                obj.remove(ENCRYPTED_FIELDS_KEY);
+            } else {
+                obj.insert(
+                    CONTENT_LOCKED_KEY.to_string(),
+                    serde_json::Value::Bool(true),
+                );
+            }
+        }
+        opened_ok
+    }
+}
+
+/// Binds each sealed value to its event and field so it cannot be moved.
+fn aad(event_id: &str, path: &str) -> String {
+    format!("{event_id}:{path}")
+}
+
+fn field_mut<'a>(
+    payload: &'a mut serde_json::Value,
+    path: &str,
+) -> Option<&'a mut serde_json::Value> {
+    path.split('.')
+        .try_fold(payload, |value, segment| value.get_mut(segment))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sealed_fields_round_trip_and_leave_other_fields_alone() {
+        let encryption = EventEncryption::with_key(EventKey::derive("secret"));
+        let mut payload = serde_json::json!({
+            "record": { "content": "private words", "surface": "chat" },
+            "scope": { "session_id": "s1" },
+        });
+        let key_id = encryption
+            .seal(shared_types::EVENT_TOPIC_USER_INPUT, "evt-1", &mut payload)
+            .expect("seal")
+            .expect("sealed");
+        assert_ne!(payload["record"]["content"], "private words");
+        assert_eq!(payload["record"]["surface"], "chat");
+        assert_eq!(payload["scope"]["session_id"], "s1");
+        assert_eq!(payload[ENCRYPTED_FIELDS_KEY][0], "record.content");
+
+        assert!(encryption.open(&key_id, "evt-1", &mut payload));
+        assert_eq!(payload["record"]["content"], "private words");
+        assert!(payload.get(ENCRYPTED_FIELDS_KEY).is_none());
+    }
+
+    #[test]
+    fn unconfigured_types_and_missing_keys_are_not_sealed() {
+        let encryption = EventEncryption::with_key(EventKey::derive("secret"));
+        let mut payload = serde_json::json!({ "text": "hello" });
+        assert_eq!(
+            encryption.seal("conductor.task.started", "evt-1", &mut payload),
+            Ok(None)
+        );
+        assert_eq!(
+            EventEncryption::default().seal("chat.user_msg", "evt-1", &mut payload),
+            Ok(None)
+        );
+        assert_eq!(payload, serde_json::json!({ "text": "hello" }));
+    }
+
+    #[test]
+    fn fields_read_as_locked_without_the_key_or_when_moved() {
+        let encryption = EventEncryption::with_key(EventKey::derive("secret"));
+        let mut payload = serde_json::json!({ "text": "hello" });
+        let key_id = encryption
+            .seal("chat.user_msg", "evt-1", &mut payload)
+            .unwrap()
+            .unwrap();
+
+        let mut without_key = payload.clone();
+        assert!(!EventEncryption::default().open(&key_id, "evt-1", &mut without_key));
+        assert_eq!(without_key["text"], LOCKED_CONTENT);
+        assert_eq!(without_key[CONTENT_LOCKED_KEY], true);
+
+        let mut other_event = payload.clone();
+        assert!(!encryption.open(&key_id, "evt-2", &mut other_event));
+        assert_eq!(other_event["text"], LOCKED_CONTENT);
+    }
+
+    #[test]
+    fn previous_keys_open_rows_sealed_before_rotation() {
+        let old = EventEncryption::with_key(EventKey::derive("old"));
+        let mut payload = serde_json::json!({ "content": "draft" });
+        let old_id = old
+            .seal("chat.assistant_msg", "evt-1", &mut payload)
+            .unwrap()
+            .unwrap();
+
+        let rotated = EventEncryption::with_key(EventKey::derive("new"))
+            .with_previous_keys([EventKey::derive("old")]);
+        assert_ne!(rotated.current_key_id(), Some(old_id.as_str()));
+        assert!(rotated.open(&old_id, "evt-1", &mut payload));
+        assert_eq!(payload["content"], "draft");
+    }
+}
@@ -87,6 +87,46 @@ impl Actor for EventRelayActor {
     }
 }
 
+/// The EventBus event the relay publishes for a committed EventStore row:
+/// topic and type are the stored event type, and the payload carries the
+/// row's identity under `committed_event`.
+pub fn relayed_event(stored: shared_types::Event) -> Result<Event, serde_json::Error> {
+    let relay_payload = match stored.payload {
+        serde_json::Value::Object(mut obj) => {
+            obj.insert(
+                "committed_event".to_string(),
+                serde_json::json!({
+                    "seq": stored.seq,
+                    "event_id": stored.event_id,
+                    "event_type": stored.event_type,
+                    "timestamp": stored.timestamp.to_rfc3339(),
+                    "actor_id": stored.actor_id.0,
+                    "user_id": stored.user_id,
+                }),
+            );
+            serde_json::Value::Object(obj)
+        }
+        other => serde_json::json!({
+            "value": other,
+            "committed_event": {
+                "seq": stored.seq,
+                "event_id": stored.event_id,
+                "event_type": stored.event_type,
+                "timestamp": stored.timestamp.to_rfc3339(),
+                "actor_id": stored.actor_id.0,
+                "user_id": stored.user_id,
+            }
+        }),
+    };
+
+    Event::new(
+        EventType::Custom(stored.event_type.clone()),
+        stored.event_type.clone(),
+        relay_payload,
+        stored.actor_id.0.clone(),
+    )
+}
+
 impl EventRelayActor {
     async fn relay_once(&self, state: &mut EventRelayState) -> Result<(), String> {
         let events = ractor::call!(state.event_store, |reply| EventStoreMsg::GetRecentEvents {
@@ -105,41 +145,8 @@ impl EventRelayActor {
         }
 
         for stored in events {
-            let relay_payload = match stored.payload {
-                serde_json::Value::Object(mut obj) => {
-                    obj.insert(
-                        "committed_event".to_string(),
-                        serde_json::json!({
-                            "seq": stored.seq,
-                            "event_id": stored.event_id,
-                            "event_type": stored.event_type,
-                            "timestamp": stored.timestamp.to_rfc3339(),
-                            "actor_id": stored.actor_id.0,
-                            "user_id": stored.user_id,
-                        }),
-                    );
-                    serde_json::Value::Object(obj)
-                }
-                other => serde_json::json!({
-                    "value": other,
-                    "committed_event": {
-                        "seq": stored.seq,
-                        "event_id": stored.event_id,
-                        "event_type": stored.event_type,
-                        "timestamp": stored.timestamp.to_rfc3339(),
-                        "actor_id": stored.actor_id.0,
-                        "user_id": stored.user_id,
-                    }
-                }),
-            };
-
-            let event = Event::new(
-                EventType::Custom(stored.event_type.clone()),
-                stored.event_type.clone(),
-                relay_payload,
-                stored.actor_id.0.clone(),
-            )
-            .map_err(|e| e.to_string())?;
+            let seq = stored.seq;
+            let event = relayed_event(stored).map_err(|e| e.to_string())?;
 
             ractor::cast!(
                 state.event_bus,
@@ -151,7 +158,7 @@ impl EventRelayActor {
             .map_err(|e| e.to_string())?;
 
             // Advance cursor only after successful fanout publish.
-            state.since_seq = state.since_seq.max(stored.seq);
+            state.since_seq = state.since_seq.max(seq);
         }
 
         Ok(())
@@ -277,6 +284,7 @@ mod tests {
                 payload: serde_json::json!({"task_id":"t1"}),
                 actor_id: "application_supervisor".to_string(),
                 user_id: "system".to_string(),
+                idempotency_key: None,
             },
             reply
         })
@@ -333,6 +341,7 @@ mod tests {
                 payload: serde_json::json!({"task_id":"t-failover"}),
                 actor_id: "application_supervisor".to_string(),
                 user_id: "system".to_string(),
+                idempotency_key: None,
             },
             reply
         })
@@ -385,6 +394,7 @@ mod tests {
                 payload: serde_json::json!({"task_id":"t-rebind"}),
                 actor_id: "application_supervisor".to_string(),
                 user_id: "system".to_string(),
+                idempotency_key: None,
             },
             reply
         })
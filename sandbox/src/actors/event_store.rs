@@ -9,6 +9,89 @@
 //! - Uses sqlx for SQLite database access with compile-time checked migrations
 //! - Supports append-only event log pattern
 //! - Events are immutable and ordered by sequence number
+//! - The store stamps `stored_at` itself and never lets it decrease along seq,
+//!   so seq order is also the canonical order of
+//!   [`shared_types::Event::canonical_cmp`] and seq cursors stay valid.
+//!   Producers' own clocks only show up as `produced_at`.
+//!
+//! # Read-after-write
+//!
+//! `Append` replies only after its transaction has committed, and the
+//! database runs in WAL mode, so any read transaction started after the reply
+//! sees the event — through this actor or through a separate connection such
+//! as [`EventStoreActor::open_read_pool`]. A client holding an appended seq
+//! can query for it immediately without polling. `AppendAsync` has no reply;
+//! a later message from the same sender is still handled after it, since the
+//! actor drains its mailbox in order.
+//!
+//! `AppendBatch` writes several events in one transaction, so producers of
+//! bursts pay for one commit instead of one per event. The batch keeps its
+//! order, commits whole or not at all, and replies with the seq range it was
+//! given. Telemetry sent with `AppendAsync` (worker progress) is batched the
+//! same way by the store: it is queued for up to 50ms and written together
+//! with the next control-lane event, the next full queue or before any other
+//! message is handled. Separate read connections may see it that much later.
+//!
+//! # Telemetry limits
+//!
+//! A store spawned [`EventStoreArguments::with_telemetry`] samples progress
+//! and caps the telemetry-lane events `AppendAsync` writes per correlation,
+//! summarizing what the cap dropped; see [`crate::actors::telemetry_gate`]. `Append` and
+//! `AppendBatch` are never limited.
+//!
+//! # Snapshots
+//!
+//! Actors that rebuild state from their events can store one state snapshot
+//! each with [`EventStoreMsg::SaveSnapshot`] and, after a respawn, load it
+//! with [`EventStoreMsg::GetLatestSnapshot`] and replay only the events after
+//! its `seq`. Snapshots are not events; losing one only slows recovery.
+//!
+//! # Scope enforcement
+//!
+//! Topics that belong to a run or thread list the scope fields their events
+//! must carry in [`shared_types::EventTopic::required_scope`]. An append
+//! missing one is rejected in debug and test builds; release builds store it,
+//! log a warning and count it in [`scope_violations`]. [`scope_coverage`]
+//! summarizes how well the events already stored meet the table.
+//!
+//! # Encryption
+//!
+//! Once configured with [`EventStoreMsg::ConfigureEncryption`], content
+//! fields of sensitive event types are sealed before they are written and
+//! opened again on every read through this actor; see
+//! [`crate::actors::event_encryption`]. Reads through a separate connection
+//! see the ciphertext.
+//!
+//! # Retention
+//!
+//! [`EventStoreMsg::Compact`] removes telemetry-lane events older than the
+//! store's [`CompactionPolicy`] allows, or beyond its row limit (oldest
+//! first), then runs `VACUUM`. Events declaring the control lane in
+//! `_meta.lane` are never removed. Seqs are never reused, so cursors past a
+//! removed event stay valid; neither are `actor_seq`s, which come from a
+//! per-actor counter rather than the rows still stored. Compaction records
+//! the highest `actor_seq` it removed for each actor
+//! ([`EventStoreMsg::GetCompactedThrough`]), so a consumer can tell a gap
+//! left by retention from a missed event.
+//!
+//! # Event search
+//!
+//! Unless spawned [`EventStoreArguments::without_event_search`], the store
+//! keeps an FTS5 index over every event's type, actor and payload text,
+//! written in the same transaction as the event, and answers
+//! [`EventStoreMsg::Search`] from it. Sealed payload fields are left out of
+//! the index. Events stored while the index was off are indexed the next
+//! time the store starts with it on.
+//!
+//! # Export and import
+//!
+//! [`EventStoreMsg::Export`] streams a seq range as NDJSON, one
+//! [`shared_types::Event`] per line with payloads opened, without holding up
+//! the actor. [`EventStoreMsg::Import`] appends such a stream in one
+//! transaction under new local seqs, keeping each `event_id`; lines whose
+//! `event_id` is already stored are skipped, so importing a file twice is a
+//! no-op. Imported `stored_at` times are kept unless they would move the
+//! log's time backwards.
 //!
 //! # Example
 //!
@@ -29,14 +112,26 @@
 //!         payload: json!({"key": "value"}),
 //!         actor_id: "actor-1".to_string(),
 //!         user_id: "user-1".to_string(),
+//!         idempotency_key: None,
 //!     },
 //!     reply,
 //! })?;
 //! ```
 
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicU64, Ordering};
+
 use async_trait::async_trait;
 use ractor::{Actor, ActorProcessingErr, ActorRef, RpcReplyPort};
-use sqlx::SqlitePool;
+use serde::Serialize;
+use shared_types::EventLane;
+use sqlx::{SqliteConnection, SqlitePool};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::actors::conductor::events::parse_event_metadata;
+use crate::actors::event_encryption::{EventEncryption, ENCRYPTED_FIELDS_KEY};
+use crate::actors::telemetry_gate::{self, TelemetryGate, TelemetryPolicy, TELEMETRY_WINDOW};
 
 /// Actor that manages the append-only event log
 #[derive(Debug, Default)]
@@ -49,13 +144,95 @@ pub enum EventStoreArguments {
     File(String),
     /// In-memory database (for testing)
     InMemory,
+    /// Either database, compacted under a policy other than the default.
+    WithCompaction(Box<EventStoreArguments>, CompactionPolicy),
+    /// Either database, without maintaining the event search index.
+    WithoutEventSearch(Box<EventStoreArguments>),
+    /// Either database, limiting `AppendAsync` telemetry under a policy.
+    WithTelemetry(Box<EventStoreArguments>, TelemetryPolicy),
+}
+
+impl EventStoreArguments {
+    /// Compact the store under `policy` instead of the default.
+    pub fn with_compaction(self, policy: CompactionPolicy) -> Self {
+        match self {
+            Self::WithCompaction(database, _) => Self::WithCompaction(database, policy),
+            database => Self::WithCompaction(Box::new(database), policy),
+        }
+    }
+
+    /// Skip the event search index, for deployments short on disk or CPU.
+    /// [`EventStoreMsg::Search`] then fails with
+    /// [`EventStoreError::SearchDisabled`].
+    pub fn without_event_search(self) -> Self {
+        Self::WithoutEventSearch(Box::new(self))
+    }
+
+    /// Limit telemetry appended with `AppendAsync` under `policy`; without
+    /// it every such event is written.
+    pub fn with_telemetry(self, policy: TelemetryPolicy) -> Self {
+        match self {
+            Self::WithTelemetry(database, _) => Self::WithTelemetry(database, policy),
+            database => Self::WithTelemetry(Box::new(database), policy),
+        }
+    }
+
+    /// The database to open, its compaction policy, whether event search is
+    /// maintained and its telemetry policy. The outermost setting of each
+    /// wins.
+    fn into_parts(self) -> (Self, CompactionPolicy, bool, Option<TelemetryPolicy>) {
+        let mut compaction = None;
+        let mut event_search = true;
+        let mut telemetry = None;
+        let mut args = self;
+        loop {
+            args = match args {
+                Self::WithCompaction(database, policy) => {
+                    compaction.get_or_insert(policy);
+                    *database
+                }
+                Self::WithoutEventSearch(database) => {
+                    event_search = false;
+                    *database
+                }
+                Self::WithTelemetry(database, policy) => {
+                    telemetry.get_or_insert(policy);
+                    *database
+                }
+                database => {
+                    return (
+                        database,
+                        compaction.unwrap_or_default(),
+                        event_search,
+                        telemetry,
+                    )
+                }
+            };
+        }
+    }
 }
 
 /// State for EventStoreActor
 pub struct EventStoreState {
     pool: SqlitePool,
+    encryption: EventEncryption,
+    compaction: CompactionPolicy,
+    event_search: bool,
+    writes: WriteStats,
+    telemetry: Option<TelemetryGate>,
+    /// `AppendAsync` events waiting to be written as one batch.
+    pending_appends: Vec<AppendEvent>,
+    append_flush_scheduled: bool,
 }
 
+/// How long a queued `AppendAsync` telemetry event waits for others before
+/// its batch is written.
+const APPEND_FLUSH_DELAY: std::time::Duration = std::time::Duration::from_millis(50);
+
+/// Queued `AppendAsync` events that are written without waiting for the
+/// delay.
+const APPEND_BATCH_MAX_EVENTS: usize = 256;
+
 // ============================================================================
 // Messages
 // ============================================================================
@@ -68,14 +245,50 @@ pub enum EventStoreMsg {
         event: AppendEvent,
         reply: RpcReplyPort<Result<shared_types::Event, EventStoreError>>,
     },
-    /// Append a new event to the store (fire-and-forget)
+    /// Append a new event to the store (fire-and-forget). Telemetry may be
+    /// dropped under the store's [`TelemetryPolicy`].
     AppendAsync { event: AppendEvent },
+    /// Report telemetry windows that have ended. The store sends this to
+    /// itself after dropping telemetry.
+    FlushTelemetry,
+    /// Write the `AppendAsync` events queued for batching. The store sends
+    /// this to itself.
+    FlushAppends,
+    /// Append events in order in one transaction: all of them commit or none
+    /// do. Replies with the seq range assigned, `None` for an empty batch.
+    AppendBatch {
+        events: Vec<AppendEvent>,
+        reply: RpcReplyPort<Result<Option<RangeInclusive<i64>>, EventStoreError>>,
+    },
+    /// Appends committed since the store started.
+    GetWriteStats { reply: RpcReplyPort<WriteStats> },
+    /// Replace the keys and fields used to seal sensitive payload fields.
+    /// Replies with which sealed rows the new keys can open.
+    ConfigureEncryption {
+        encryption: EventEncryption,
+        reply: RpcReplyPort<Result<EncryptionStatus, EventStoreError>>,
+    },
+    /// Re-seal up to `batch_size` rows after `after_seq` that were sealed
+    /// under a key other than the current one.
+    ReencryptBatch {
+        after_seq: i64,
+        batch_size: i64,
+        reply: RpcReplyPort<Result<ReencryptBatch, EventStoreError>>,
+    },
     /// Get all events for an actor since a specific sequence number
     GetEventsForActor {
         actor_id: String,
         since_seq: i64,
         reply: RpcReplyPort<Result<Vec<shared_types::Event>, EventStoreError>>,
     },
+    /// Events for `query.actor_id` after `query.since_seq`, in seq order,
+    /// limited to `query.lane` if set. The lane is read from `_meta.lane` the
+    /// same way conductor event metadata is parsed, so events without lane
+    /// metadata are telemetry.
+    QueryEvents {
+        query: shared_types::QueryEvents,
+        reply: RpcReplyPort<Result<Vec<shared_types::Event>, EventStoreError>>,
+    },
     /// Get events for an actor scoped to a session/thread pair.
     GetEventsForActorWithScope {
         actor_id: String,
@@ -97,11 +310,24 @@ pub enum EventStoreMsg {
     GetLatestSeq {
         reply: RpcReplyPort<Result<Option<i64>, EventStoreError>>,
     },
+    /// Get the sequence number of the last event stored before `before`, or 0.
+    /// Timestamps never decrease along seq, so this turns a time bound into a
+    /// `since_seq` cursor.
+    GetLastSeqBefore {
+        before: chrono::DateTime<chrono::Utc>,
+        reply: RpcReplyPort<Result<i64, EventStoreError>>,
+    },
     /// Get a single event by its sequence number
     GetEventBySeq {
         seq: i64,
         reply: RpcReplyPort<Result<Option<shared_types::Event>, EventStoreError>>,
     },
+    /// Which of `event_ids` are stored. Used to resolve evidence references
+    /// in worker reports.
+    ExistingEventIds {
+        event_ids: Vec<String>,
+        reply: RpcReplyPort<Result<HashSet<String>, EventStoreError>>,
+    },
     /// Find events by corr_id field inside the payload JSON.
     /// Used by harness recovery to check whether a pending reply has already
     /// arrived (written as `tool.result` or `subharness.result` by the actor
@@ -111,12 +337,144 @@ pub enum EventStoreMsg {
         event_type_prefix: Option<String>,
         reply: RpcReplyPort<Result<Vec<shared_types::Event>, EventStoreError>>,
     },
+    /// Committed events whose payload `correlation_id` or `trace_id` matches,
+    /// in seq order. Both fields are indexed, so this does not scan the
+    /// table. With `lane`, only events in that lane are returned; the lane is read
+    /// from `_meta.lane` the same way conductor event metadata is parsed.
+    QueryByCorrelation {
+        correlation_id: String,
+        lane: Option<EventLane>,
+        reply: RpcReplyPort<Result<Vec<shared_types::Event>, EventStoreError>>,
+    },
+    /// One page of events matching `query`, with a cursor for the next page.
+    QueryFiltered {
+        query: EventQuery,
+        reply: RpcReplyPort<Result<EventPage, EventStoreError>>,
+    },
+    /// Events stored between `from` and `to`, both inclusive and compared
+    /// at whole seconds, newest first. At most `limit` events, clamped to
+    /// `1..=MAX_QUERY_PAGE`.
+    QueryByTime {
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+        actor_id: Option<String>,
+        limit: i64,
+        reply: RpcReplyPort<Result<Vec<shared_types::Event>, EventStoreError>>,
+    },
+    /// The latest `limit` events, newest first, clamped like
+    /// [`EventStoreMsg::QueryByTime`].
+    QueryTail {
+        actor_id: Option<String>,
+        limit: i64,
+        reply: RpcReplyPort<Result<Vec<shared_types::Event>, EventStoreError>>,
+    },
     /// Get the latest harness checkpoint event for a given run_id.
     /// Used by harness recovery to reconstruct in-flight state after a crash.
     GetLatestHarnessCheckpoint {
         run_id: String,
         reply: RpcReplyPort<Result<Option<shared_types::Event>, EventStoreError>>,
     },
+    /// Get the stored cursor for a named projection.
+    GetProjectionCursor {
+        name: String,
+        reply: RpcReplyPort<Result<Option<ProjectionCursor>, EventStoreError>>,
+    },
+    /// Upsert the cursor for a named projection.
+    SetProjectionCursor {
+        name: String,
+        cursor_seq: i64,
+        status: String,
+        reply: RpcReplyPort<Result<(), EventStoreError>>,
+    },
+    /// Replace the state snapshot stored for an actor.
+    SaveSnapshot {
+        snapshot: ActorSnapshot,
+        reply: RpcReplyPort<Result<(), EventStoreError>>,
+    },
+    /// Get the state snapshot stored for an actor, if any.
+    GetLatestSnapshot {
+        actor_id: String,
+        reply: RpcReplyPort<Result<Option<ActorSnapshot>, EventStoreError>>,
+    },
+    /// Insert or replace the search index entry derived from an event.
+    IndexSearchEntry {
+        entry: SearchIndexEntry,
+        reply: RpcReplyPort<Result<(), EventStoreError>>,
+    },
+    /// Remove the search index entry derived from the event at `seq`.
+    /// Returns whether an entry existed.
+    RemoveSearchEntry {
+        seq: i64,
+        reply: RpcReplyPort<Result<bool, EventStoreError>>,
+    },
+    /// Drop every search index entry.
+    ClearSearchIndex {
+        reply: RpcReplyPort<Result<(), EventStoreError>>,
+    },
+    /// Run an FTS5 MATCH expression against the search index, best first.
+    /// An empty `kinds` list matches every kind.
+    SearchIndex {
+        match_expr: String,
+        kinds: Vec<String>,
+        limit: i64,
+        reply: RpcReplyPort<Result<Vec<SearchIndexMatch>, EventStoreError>>,
+    },
+    /// Events whose type, actor or payload text matches `query`, an FTS5
+    /// MATCH expression, best first, each with a snippet of the matched text.
+    Search {
+        query: String,
+        limit: i64,
+        reply: RpcReplyPort<Result<Vec<EventSearchMatch>, EventStoreError>>,
+    },
+    /// Remove telemetry events the compaction policy no longer retains, then
+    /// `VACUUM` the database.
+    Compact {
+        reply: RpcReplyPort<Result<CompactionReport, EventStoreError>>,
+    },
+    /// Highest `actor_seq` of `actor_id` that compaction has removed, or 0.
+    /// Gaps in the actor's sequence at or below it are retention, not loss.
+    GetCompactedThrough {
+        actor_id: String,
+        reply: RpcReplyPort<Result<i64, EventStoreError>>,
+    },
+    /// Write and read back a row inside a transaction that is rolled back,
+    /// to check the database accepts writes; see [`EventStoreHealth`].
+    HealthCheck {
+        reply: RpcReplyPort<EventStoreHealth>,
+    },
+    /// Write events with `since_seq < seq <= until_seq` to `writer` as NDJSON,
+    /// then shut it down. Without `until_seq` the export ends at the last
+    /// event stored when it starts. Replies with the number of events written.
+    Export {
+        since_seq: i64,
+        until_seq: Option<i64>,
+        writer: ExportWriter,
+        reply: RpcReplyPort<Result<u64, EventStoreError>>,
+    },
+    /// Append the NDJSON events read from `reader`; see the module docs.
+    /// Nothing is stored if any line is invalid or out of seq order.
+    Import {
+        reader: ImportReader,
+        reply: RpcReplyPort<Result<ImportReport, EventStoreError>>,
+    },
+}
+
+/// Destination of an [`EventStoreMsg::Export`].
+pub struct ExportWriter(pub Box<dyn AsyncWrite + Send + Unpin>);
+
+impl std::fmt::Debug for ExportWriter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ExportWriter")
+    }
+}
+
+/// Source of an [`EventStoreMsg::Import`].
+pub struct ImportReader(pub Box<dyn AsyncBufRead + Send + Unpin>);
+
+impl std::fmt::Debug for ImportReader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ImportReader")
+    }
 }
 
 impl EventStoreActor {
@@ -137,6 +495,18 @@ impl EventStoreActor {
 
         Ok(pool)
     }
+
+    /// Open a read-only pool on a file-backed store owned by a running actor.
+    ///
+    /// Reads on it see every event whose `Append` has already replied; see the
+    /// module docs.
+    pub async fn open_read_pool(path: &str) -> Result<SqlitePool, sqlx::Error> {
+        use sqlx::sqlite::SqliteConnectOptions;
+        use std::str::FromStr;
+
+        let opts = SqliteConnectOptions::from_str(&format!("sqlite:{path}"))?.read_only(true);
+        SqlitePool::connect_with(opts).await
+    }
 }
 
 #[async_trait]
@@ -155,7 +525,8 @@ impl Actor for EventStoreActor {
             "EventStoreActor starting"
         );
 
-        let pool = match args {
+        let (database, compaction, event_search, telemetry) = args.into_parts();
+        let pool = match database {
             EventStoreArguments::File(path) => {
                 tracing::info!(database_path = %path, "Opening file-based database");
                 // Ensure parent directory exists
@@ -174,9 +545,34 @@ impl Actor for EventStoreActor {
                     ActorProcessingErr::from(format!("Failed to open in-memory database: {e}"))
                 })?
             }
+            EventStoreArguments::WithCompaction(..)
+            | EventStoreArguments::WithoutEventSearch(..)
+            | EventStoreArguments::WithTelemetry(..) => {
+                unreachable!("into_parts unwraps every wrapper")
+            }
         };
+        if event_search {
+            let mut conn = pool.acquire().await.map_err(|e| {
+                ActorProcessingErr::from(format!("Failed to open database connection: {e}"))
+            })?;
+            let indexed = Self::catch_up_event_search(&mut conn).await.map_err(|e| {
+                ActorProcessingErr::from(format!("Failed to build event search index: {e}"))
+            })?;
+            if indexed > 0 {
+                tracing::info!(indexed, "Indexed events stored without event search");
+            }
+        }
 
-        Ok(EventStoreState { pool })
+        Ok(EventStoreState {
+            pool,
+            encryption: EventEncryption::default(),
+            compaction,
+            event_search,
+            writes: WriteStats::default(),
+            telemetry: telemetry.map(TelemetryGate::new),
+            pending_appends: Vec::new(),
+            append_flush_scheduled: false,
+        })
     }
 
     async fn post_start(
@@ -193,17 +589,56 @@ impl Actor for EventStoreActor {
 
     async fn handle(
         &self,
-        _myself: ActorRef<Self::Msg>,
+        myself: ActorRef<Self::Msg>,
         message: Self::Msg,
         state: &mut Self::State,
     ) -> Result<(), ActorProcessingErr> {
+        // Anything else may read queued async appends or expect them to be
+        // written first, so they are flushed before it is handled.
+        if !matches!(
+            message,
+            EventStoreMsg::AppendAsync { .. }
+                | EventStoreMsg::FlushAppends
+                | EventStoreMsg::FlushTelemetry
+        ) {
+            self.flush_appends(state).await;
+        }
         match message {
             EventStoreMsg::Append { event, reply } => {
                 let result = self.handle_append(event, state).await;
                 let _ = reply.send(result);
             }
             EventStoreMsg::AppendAsync { event } => {
-                let _ = self.handle_append(event, state).await;
+                for event in Self::gate_telemetry(&myself, event, state) {
+                    self.queue_append(&myself, event, state).await;
+                }
+            }
+            EventStoreMsg::FlushTelemetry => {
+                self.handle_flush_telemetry(&myself, state).await;
+            }
+            EventStoreMsg::FlushAppends => {
+                self.flush_appends(state).await;
+            }
+            EventStoreMsg::AppendBatch { events, reply } => {
+                let result = self.handle_append_batch(events, state).await;
+                let _ = reply.send(result);
+            }
+            EventStoreMsg::GetWriteStats { reply } => {
+                let _ = reply.send(state.writes);
+            }
+            EventStoreMsg::ConfigureEncryption { encryption, reply } => {
+                let result = self.handle_configure_encryption(encryption, state).await;
+                let _ = reply.send(result);
+            }
+            EventStoreMsg::ReencryptBatch {
+                after_seq,
+                batch_size,
+                reply,
+            } => {
+                let result = self
+                    .handle_reencrypt_batch(after_seq, batch_size, state)
+                    .await;
+                let _ = reply.send(result);
             }
             EventStoreMsg::GetEventsForActor {
                 actor_id,
@@ -215,6 +650,10 @@ impl Actor for EventStoreActor {
                     .await;
                 let _ = reply.send(result);
             }
+            EventStoreMsg::QueryEvents { query, reply } => {
+                let result = self.handle_query_events(query, state).await;
+                let _ = reply.send(result);
+            }
             EventStoreMsg::GetEventsForActorWithScope {
                 actor_id,
                 session_id,
@@ -253,10 +692,18 @@ impl Actor for EventStoreActor {
                 let result = self.handle_get_latest_seq(state).await;
                 let _ = reply.send(result);
             }
+            EventStoreMsg::GetLastSeqBefore { before, reply } => {
+                let result = self.handle_get_last_seq_before(before, state).await;
+                let _ = reply.send(result);
+            }
             EventStoreMsg::GetEventBySeq { seq, reply } => {
                 let result = self.handle_get_event_by_seq(seq, state).await;
                 let _ = reply.send(result);
             }
+            EventStoreMsg::ExistingEventIds { event_ids, reply } => {
+                let result = self.handle_existing_event_ids(&event_ids, state).await;
+                let _ = reply.send(result);
+            }
             EventStoreMsg::GetEventsByCorrId {
                 corr_id,
                 event_type_prefix,
@@ -267,12 +714,130 @@ impl Actor for EventStoreActor {
                     .await;
                 let _ = reply.send(result);
             }
+            EventStoreMsg::QueryByCorrelation {
+                correlation_id,
+                lane,
+                reply,
+            } => {
+                let result = self
+                    .handle_query_by_correlation(&correlation_id, lane, state)
+                    .await;
+                let _ = reply.send(result);
+            }
+            EventStoreMsg::QueryFiltered { query, reply } => {
+                let result = self.handle_query_filtered(query, state).await;
+                let _ = reply.send(result);
+            }
+            EventStoreMsg::QueryByTime {
+                from,
+                to,
+                actor_id,
+                limit,
+                reply,
+            } => {
+                let result = self
+                    .handle_query_by_time(from, to, actor_id, limit, state)
+                    .await;
+                let _ = reply.send(result);
+            }
+            EventStoreMsg::QueryTail {
+                actor_id,
+                limit,
+                reply,
+            } => {
+                let result = self.handle_query_tail(actor_id, limit, state).await;
+                let _ = reply.send(result);
+            }
             EventStoreMsg::GetLatestHarnessCheckpoint { run_id, reply } => {
                 let result = self
                     .handle_get_latest_harness_checkpoint(&run_id, state)
                     .await;
                 let _ = reply.send(result);
             }
+            EventStoreMsg::GetProjectionCursor { name, reply } => {
+                let result = self.handle_get_projection_cursor(&name, state).await;
+                let _ = reply.send(result);
+            }
+            EventStoreMsg::SetProjectionCursor {
+                name,
+                cursor_seq,
+                status,
+                reply,
+            } => {
+                let result = self
+                    .handle_set_projection_cursor(&name, cursor_seq, &status, state)
+                    .await;
+                let _ = reply.send(result);
+            }
+            EventStoreMsg::SaveSnapshot { snapshot, reply } => {
+                let result = self.handle_save_snapshot(snapshot, state).await;
+                let _ = reply.send(result);
+            }
+            EventStoreMsg::GetLatestSnapshot { actor_id, reply } => {
+                let result = self.handle_get_latest_snapshot(&actor_id, state).await;
+                let _ = reply.send(result);
+            }
+            EventStoreMsg::IndexSearchEntry { entry, reply } => {
+                let result = self.handle_index_search_entry(entry, state).await;
+                let _ = reply.send(result);
+            }
+            EventStoreMsg::RemoveSearchEntry { seq, reply } => {
+                let result = self.handle_remove_search_entry(seq, state).await;
+                let _ = reply.send(result);
+            }
+            EventStoreMsg::ClearSearchIndex { reply } => {
+                let result = self.handle_clear_search_index(state).await;
+                let _ = reply.send(result);
+            }
+            EventStoreMsg::SearchIndex {
+                match_expr,
+                kinds,
+                limit,
+                reply,
+            } => {
+                let result = self
+                    .handle_search_index(&match_expr, &kinds, limit, state)
+                    .await;
+                let _ = reply.send(result);
+            }
+            EventStoreMsg::Search {
+                query,
+                limit,
+                reply,
+            } => {
+                let result = self.handle_search(&query, limit, state).await;
+                let _ = reply.send(result);
+            }
+            EventStoreMsg::Compact { reply } => {
+                let result = self.handle_compact(state).await;
+                let _ = reply.send(result);
+            }
+            EventStoreMsg::GetCompactedThrough { actor_id, reply } => {
+                let result = self.handle_get_compacted_through(&actor_id, state).await;
+                let _ = reply.send(result);
+            }
+            EventStoreMsg::HealthCheck { reply } => {
+                let _ = reply.send(self.handle_health_check(state).await);
+            }
+            EventStoreMsg::Export {
+                since_seq,
+                until_seq,
+                writer,
+                reply,
+            } => {
+                // A slow reader on the other end must not hold up the actor.
+                let pool = state.pool.clone();
+                let encryption = state.encryption.clone();
+                tokio::spawn(async move {
+                    let result =
+                        Self::export_ndjson(&pool, &encryption, since_seq, until_seq, writer).await;
+                    let _ = reply.send(result);
+                });
+            }
+            EventStoreMsg::Import { reader, reply } => {
+                let result = self.handle_import(reader, state).await;
+                let _ = reply.send(result);
+            }
         }
         Ok(())
     }
@@ -301,6 +866,10 @@ pub struct AppendEvent {
     pub payload: serde_json::Value,
     pub actor_id: String,
     pub user_id: String,
+    /// Client-chosen key that makes the append safe to retry: appending again
+    /// with a key the same user already stored returns the original event
+    /// unchanged.
+    pub idempotency_key: Option<String>,
 }
 
 impl AppendEvent {
@@ -316,10 +885,260 @@ impl AppendEvent {
             payload: serde_json::to_value(payload)?,
             actor_id: actor_id.into(),
             user_id: user_id.into(),
+            idempotency_key: None,
         })
     }
 }
 
+/// Filters and page position for [`EventStoreMsg::QueryFiltered`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventQuery {
+    pub actor_id: Option<String>,
+    /// Event type prefixes, any of which may match; a trailing `*` is
+    /// accepted (`"writer.run.*"`). Empty matches every type.
+    pub event_type_prefixes: Vec<String>,
+    /// Earliest stored time, inclusive. Compared at whole seconds.
+    pub since: Option<chrono::DateTime<chrono::Utc>>,
+    /// Latest stored time, inclusive. Compared at whole seconds.
+    pub until: Option<chrono::DateTime<chrono::Utc>>,
+    /// Page size, clamped to `1..=MAX_QUERY_PAGE`.
+    pub limit: i64,
+    /// `next_cursor` of the previous page; `None` starts from the beginning
+    /// (or from the end when `newest_first`).
+    pub cursor: Option<i64>,
+    /// Page backwards from the newest event, for lazily loading history.
+    pub newest_first: bool,
+}
+
+/// One page of a filtered query. Events are in seq order, descending when
+/// the query was `newest_first`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EventPage {
+    pub events: Vec<shared_types::Event>,
+    /// Cursor for the following page; `None` when this page is the last.
+    pub next_cursor: Option<i64>,
+}
+
+/// Largest page [`EventStoreMsg::QueryFiltered`] returns.
+pub const MAX_QUERY_PAGE: i64 = 1000;
+
+/// Stored replay position for a named projection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectionCursor {
+    pub name: String,
+    pub cursor_seq: i64,
+    pub status: String,
+    pub updated_at: String,
+}
+
+/// Actor state as of `seq`. Restoring it and replaying the actor's events
+/// after `seq` yields the same state as replaying from the start.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActorSnapshot {
+    pub actor_id: String,
+    pub seq: i64,
+    pub state: serde_json::Value,
+    pub created_at: String,
+}
+
+/// Which sealed rows the configured keys can open.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncryptionStatus {
+    /// Key new rows are sealed with; `None` writes plaintext.
+    pub current_key_id: Option<String>,
+    /// Keys of stored rows the configured keys cannot open; their content
+    /// reads as locked.
+    pub locked_key_ids: Vec<String>,
+}
+
+/// Outcome of one re-encryption batch.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReencryptBatch {
+    /// Rows re-sealed under the current key.
+    pub reencrypted: usize,
+    /// Rows skipped because no configured key opens them.
+    pub locked: usize,
+    /// Highest seq examined; pass it as the next `after_seq`. `None` once
+    /// no rows are left.
+    pub last_seq: Option<i64>,
+}
+
+/// How long telemetry events are retained; see [`EventStoreMsg::Compact`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompactionPolicy {
+    /// Telemetry events stored longer ago than this are removed.
+    pub telemetry_max_age: Option<std::time::Duration>,
+    /// Telemetry events kept at most, newest first.
+    pub telemetry_max_rows: Option<u64>,
+    /// Keep events that declare no lane, which the supervisor routes as
+    /// control. When false they count as telemetry, the lane
+    /// [`parse_event_metadata`] gives them. Events declaring the control lane
+    /// are kept either way.
+    pub control_keep_forever: bool,
+}
+
+/// Default telemetry retention age (30 days).
+pub const DEFAULT_TELEMETRY_MAX_AGE: std::time::Duration =
+    std::time::Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Default cap on retained telemetry events.
+pub const DEFAULT_TELEMETRY_MAX_ROWS: u64 = 250_000;
+
+impl Default for CompactionPolicy {
+    fn default() -> Self {
+        Self {
+            telemetry_max_age: Some(DEFAULT_TELEMETRY_MAX_AGE),
+            telemetry_max_rows: Some(DEFAULT_TELEMETRY_MAX_ROWS),
+            control_keep_forever: true,
+        }
+    }
+}
+
+impl CompactionPolicy {
+    /// The default policy with `CHOIR_EVENT_TELEMETRY_MAX_AGE_DAYS` and
+    /// `CHOIR_EVENT_TELEMETRY_MAX_ROWS` applied; `0` disables either limit.
+    pub fn from_env() -> Self {
+        let mut policy = Self::default();
+        if let Ok(raw) = std::env::var("CHOIR_EVENT_TELEMETRY_MAX_AGE_DAYS") {
+            if let Ok(days) = raw.trim().parse::<u64>() {
+                policy.telemetry_max_age =
+                    (days > 0).then(|| std::time::Duration::from_secs(days * 24 * 60 * 60));
+            }
+        }
+        if let Ok(raw) = std::env::var("CHOIR_EVENT_TELEMETRY_MAX_ROWS") {
+            if let Ok(rows) = raw.trim().parse::<u64>() {
+                policy.telemetry_max_rows = (rows > 0).then_some(rows);
+            }
+        }
+        policy
+    }
+}
+
+/// Rows removed by one compaction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CompactionReport {
+    /// Telemetry events older than `telemetry_max_age`.
+    pub expired: u64,
+    /// Telemetry events beyond `telemetry_max_rows`.
+    pub over_limit: u64,
+    /// Search and event search index entries whose source event was removed.
+    pub search_entries: u64,
+}
+
+impl CompactionReport {
+    /// Events removed in total.
+    pub fn removed(&self) -> u64 {
+        self.expired + self.over_limit
+    }
+}
+
+/// Scope coverage of one topic's stored events; see [`scope_coverage`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TopicScopeCoverage {
+    pub topic: String,
+    pub events: u64,
+    /// Events carrying each [`shared_types::ScopeField`], by field name.
+    pub present: BTreeMap<String, u64>,
+    /// Field names the topic requires today.
+    pub required: Vec<String>,
+    /// Events missing at least one required field.
+    pub non_compliant: u64,
+}
+
+/// Scope coverage of the whole event log, topics in name order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ScopeCoverageReport {
+    pub events: u64,
+    pub non_compliant: u64,
+    pub topics: Vec<TopicScopeCoverage>,
+}
+
+/// How long a health check may wait on the database, e.g. for a write lock
+/// held by another connection, before it reports the store unwritable.
+pub const HEALTH_CHECK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Actor id of the snapshot row a health check writes and rolls back.
+const HEALTH_CHECK_PROBE_ID: &str = "event_store.health_check";
+
+/// Result of [`EventStoreMsg::HealthCheck`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EventStoreHealth {
+    /// Whether the probe write and read back succeeded.
+    pub writable: bool,
+    /// Time the probe took, or waited before giving up.
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+/// Appends committed by one store since it started, from
+/// [`EventStoreMsg::GetWriteStats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct WriteStats {
+    /// Committed append transactions; a batch is one.
+    pub transactions: u64,
+    /// Events those transactions wrote.
+    pub events: u64,
+}
+
+impl WriteStats {
+    fn record(&mut self, events: u64) {
+        self.transactions += 1;
+        self.events += events;
+    }
+}
+
+/// Outcome of an [`EventStoreMsg::Import`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ImportReport {
+    /// Events appended under new local seqs.
+    pub imported: u64,
+    /// Events skipped because their `event_id` was already stored.
+    pub duplicates: u64,
+}
+
+/// Marks the start of a matched term inside a search snippet.
+pub const SEARCH_HIGHLIGHT_START: char = '\u{2}';
+/// Marks the end of a matched term inside a search snippet.
+pub const SEARCH_HIGHLIGHT_END: char = '\u{3}';
+
+/// Entry in the full-text search read model, keyed by its source event seq.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchIndexEntry {
+    pub seq: i64,
+    pub kind: String,
+    pub title: String,
+    pub content: String,
+    /// Serialized deep-link target for the hit.
+    pub target: serde_json::Value,
+    pub created_at: String,
+}
+
+/// Search index match with an excerpt around the matched terms.
+///
+/// Matched terms in `snippet` are wrapped in `SEARCH_HIGHLIGHT_START` and
+/// `SEARCH_HIGHLIGHT_END`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchIndexMatch {
+    pub seq: i64,
+    pub kind: String,
+    pub title: String,
+    pub snippet: String,
+    pub target: serde_json::Value,
+    pub created_at: String,
+    /// Relevance, higher is better.
+    pub score: f64,
+}
+
+/// Event matched by [`EventStoreMsg::Search`].
+///
+/// Matched terms in `snippet` are wrapped in `SEARCH_HIGHLIGHT_START` and
+/// `SEARCH_HIGHLIGHT_END`.
+#[derive(Debug, Clone, Serialize)]
+pub struct EventSearchMatch {
+    pub event: shared_types::Event,
+    pub snippet: String,
+}
+
 // ============================================================================
 // Error Types
 // ============================================================================
@@ -338,6 +1157,18 @@ pub enum EventStoreError {
 
     #[error("Invalid timestamp format: {0}")]
     InvalidTimestamp(String),
+
+    #[error("Invalid event payload: {0}")]
+    InvalidPayload(String),
+
+    #[error("Encryption error: {0}")]
+    Encryption(String),
+
+    #[error("I/O error: {0}")]
+    Io(String),
+
+    #[error("Event search is disabled for this store")]
+    SearchDisabled,
 }
 
 impl From<sqlx::Error> for EventStoreError {
@@ -352,6 +1183,12 @@ impl From<serde_json::Error> for EventStoreError {
     }
 }
 
+impl From<std::io::Error> for EventStoreError {
+    fn from(e: std::io::Error) -> Self {
+        EventStoreError::Io(e.to_string())
+    }
+}
+
 // ============================================================================
 // Row mapping helper
 // ============================================================================
@@ -363,56 +1200,357 @@ struct EventRow {
     event_type: String,
     payload: String,
     actor_id: String,
+    actor_seq: i64,
     user_id: String,
+    encryption_key_id: Option<String>,
 }
 
-fn parse_event_row(row: EventRow) -> Result<shared_types::Event, EventStoreError> {
+fn parse_event_row(
+    row: EventRow,
+    encryption: &EventEncryption,
+) -> Result<shared_types::Event, EventStoreError> {
     // SQLite stores timestamps as TEXT in the format "YYYY-MM-DD HH:MM:SS".
     let naive_dt = chrono::NaiveDateTime::parse_from_str(&row.timestamp, "%Y-%m-%d %H:%M:%S")
         .map_err(|e| EventStoreError::InvalidTimestamp(e.to_string()))?;
 
+    let stored_at = chrono::DateTime::from_naive_utc_and_offset(naive_dt, chrono::Utc);
+    let mut payload: serde_json::Value = serde_json::from_str(&row.payload)?;
+    if let Some(key_id) = row.encryption_key_id.as_deref() {
+        encryption.open(key_id, &row.event_id, &mut payload);
+    }
+
     Ok(shared_types::Event {
         seq: row.seq,
         event_id: row.event_id,
-        timestamp: chrono::DateTime::from_naive_utc_and_offset(naive_dt, chrono::Utc),
+        timestamp: stored_at,
+        stored_at,
+        produced_at: produced_at(&payload),
         event_type: row.event_type,
-        payload: serde_json::from_str(&row.payload)?,
+        payload,
         actor_id: shared_types::ActorId(row.actor_id),
+        actor_seq: row.actor_seq,
         user_id: row.user_id,
     })
 }
 
-// ============================================================================
-// Message Handlers
-// ============================================================================
-
-impl EventStoreActor {
-    async fn handle_append(
-        &self,
-        msg: AppendEvent,
-        state: &mut EventStoreState,
-    ) -> Result<shared_types::Event, EventStoreError> {
-        let event_id = ulid::Ulid::new().to_string();
-        let payload_json = serde_json::to_string(&msg.payload)?;
-        let scope_session_id: Option<String> = msg
-            .payload
-            .get("scope")
-            .and_then(|s| s.get("session_id"))
-            .and_then(|v| v.as_str())
-            .map(ToString::to_string);
-        let scope_thread_id: Option<String> = msg
-            .payload
-            .get("scope")
-            .and_then(|s| s.get("thread_id"))
-            .and_then(|v| v.as_str())
-            .map(ToString::to_string);
+/// The producer's clock reading, taken from the payload's RFC 3339 `timestamp`.
+fn produced_at(payload: &serde_json::Value) -> Option<chrono::DateTime<chrono::Utc>> {
+    let timestamp = payload.get("timestamp")?.as_str()?;
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|dt| dt.with_timezone(&chrono::Utc))
+}
 
+/// Text of a stored payload for the event search index: every string,
+/// number and boolean, one per line. Sealed fields and the list naming them
+/// are skipped.
+fn payload_search_text(payload: &serde_json::Value) -> String {
+    fn collect(
+        value: &serde_json::Value,
+        path: &str,
+        sealed: &HashSet<&str>,
+        out: &mut Vec<String>,
+    ) {
+        if sealed.contains(path) {
+            return;
+        }
+        match value {
+            serde_json::Value::Object(fields) => {
+                for (key, field) in fields {
+                    if path.is_empty() && key == ENCRYPTED_FIELDS_KEY {
+                        continue;
+                    }
+                    let path = if path.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{path}.{key}")
+                    };
+                    collect(field, &path, sealed, out);
+                }
+            }
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    collect(item, path, sealed, out);
+                }
+            }
+            serde_json::Value::String(text) => out.push(text.clone()),
+            serde_json::Value::Number(number) => out.push(number.to_string()),
+            serde_json::Value::Bool(flag) => out.push(flag.to_string()),
+            serde_json::Value::Null => {}
+        }
+    }
+
+    let sealed: HashSet<&str> = payload
+        .get(ENCRYPTED_FIELDS_KEY)
+        .and_then(|paths| paths.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|path| path.as_str())
+        .collect();
+    let mut out = Vec::new();
+    collect(payload, "", &sealed, &mut out);
+    out.join("\n")
+}
+
+/// Reject payloads of topics with a typed shape that do not parse as it.
+fn validate_payload(event: &AppendEvent) -> Result<(), EventStoreError> {
+    if event.event_type == shared_types::EVENT_TOPIC_USER_INPUT {
+        let record = event
+            .payload
+            .get("record")
+            .cloned()
+            .unwrap_or(serde_json::Value::Null);
+        serde_json::from_value::<shared_types::UserInputRecord>(record)
+            .map_err(|e| EventStoreError::InvalidPayload(format!("user_input record: {e}")))?;
+    }
+    enforce_scope(event)
+}
+
+/// Appends since start that lacked a scope field their topic requires.
+static SCOPE_VIOLATIONS: AtomicU64 = AtomicU64::new(0);
+
+/// Number of appends missing required scope since the process started.
+pub fn scope_violations() -> u64 {
+    SCOPE_VIOLATIONS.load(Ordering::Relaxed)
+}
+
+/// Check the scope fields [`shared_types::EventTopic::required_scope`] lists
+/// for the event's topic.
+///
+/// Debug and test builds reject a violation so the emit site gets fixed.
+/// Release builds store the event anyway, logging it and counting it in
+/// [`scope_violations`], since dropping an event is worse than an unscoped one.
+fn enforce_scope(event: &AppendEvent) -> Result<(), EventStoreError> {
+    let missing =
+        shared_types::EventTopic::from(event.event_type.as_str()).missing_scope(&event.payload);
+    if missing.is_empty() {
+        return Ok(());
+    }
+    let violations = SCOPE_VIOLATIONS.fetch_add(1, Ordering::Relaxed) + 1;
+    let fields = missing
+        .iter()
+        .map(|field| field.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    if cfg!(debug_assertions) {
+        return Err(EventStoreError::InvalidPayload(format!(
+            "{} is missing required scope: {fields}",
+            event.event_type
+        )));
+    }
+    tracing::warn!(
+        event_type = %event.event_type,
+        actor_id = %event.actor_id,
+        missing = %fields,
+        scope_violations = violations,
+        "Event appended without required scope"
+    );
+    Ok(())
+}
+
+// ============================================================================
+// Message Handlers
+// ============================================================================
+
+impl EventStoreActor {
+    async fn handle_append(
+        &self,
+        msg: AppendEvent,
+        state: &mut EventStoreState,
+    ) -> Result<shared_types::Event, EventStoreError> {
+        validate_payload(&msg)?;
         let mut tx = state.pool.begin().await?;
+        let event = Self::insert_event(&mut tx, &state.encryption, state.event_search, msg).await?;
+        tx.commit().await?;
+        state.writes.record(1);
+        Ok(event)
+    }
+
+    async fn handle_append_batch(
+        &self,
+        events: Vec<AppendEvent>,
+        state: &mut EventStoreState,
+    ) -> Result<Option<RangeInclusive<i64>>, EventStoreError> {
+        if events.is_empty() {
+            return Ok(None);
+        }
+        for event in &events {
+            validate_payload(event)?;
+        }
+        let count = events.len() as u64;
+        // Dropped without commit on any error, so a failed batch stores nothing.
+        let mut tx = state.pool.begin().await?;
+        let (mut first_seq, mut last_seq) = (None, 0);
+        for event in events {
+            last_seq = Self::insert_event(&mut tx, &state.encryption, state.event_search, event)
+                .await?
+                .seq;
+            first_seq.get_or_insert(last_seq);
+        }
+        tx.commit().await?;
+        state.writes.record(count);
+        Ok(first_seq.map(|first_seq| first_seq..=last_seq))
+    }
+
+    /// Pass an `AppendAsync` event through progress sampling and the
+    /// telemetry limits. Returns the events to write, in order: summaries of
+    /// windows that have ended, then whatever of `event` was admitted.
+    fn gate_telemetry(
+        myself: &ActorRef<EventStoreMsg>,
+        event: AppendEvent,
+        state: &mut EventStoreState,
+    ) -> Vec<AppendEvent> {
+        let Some(gate) = state.telemetry.as_mut() else {
+            return vec![event];
+        };
+        let now = chrono::Utc::now();
+        let mut events = gate.end_expired(now);
+        let sampled = gate.sample(event, now);
+        let sampled_count = sampled.len();
+        let admitted: Vec<_> = sampled
+            .into_iter()
+            .filter(|event| gate.admit(event, now))
+            .collect();
+        if admitted.len() < sampled_count && gate.schedule_flush() {
+            Self::schedule_telemetry_flush(myself);
+        }
+        events.extend(admitted);
+        events
+    }
+
+    async fn handle_flush_telemetry(
+        &self,
+        myself: &ActorRef<EventStoreMsg>,
+        state: &mut EventStoreState,
+    ) {
+        let Some(gate) = state.telemetry.as_mut() else {
+            return;
+        };
+        gate.flushed();
+        let summaries = gate.end_expired(chrono::Utc::now());
+        if gate.has_drops() && gate.schedule_flush() {
+            Self::schedule_telemetry_flush(myself);
+        }
+        for summary in summaries {
+            self.queue_append(myself, summary, state).await;
+        }
+    }
+
+    /// Report the drops of a window once it has ended, even if no more
+    /// telemetry arrives to end it.
+    fn schedule_telemetry_flush(myself: &ActorRef<EventStoreMsg>) {
+        let myself = myself.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(TELEMETRY_WINDOW).await;
+            let _ = myself.cast(EventStoreMsg::FlushTelemetry);
+        });
+    }
+
+    /// Queue an `AppendAsync` event for the next batch. A control-lane event
+    /// or a full queue is written at once, with everything queued before it;
+    /// telemetry otherwise waits up to [`APPEND_FLUSH_DELAY`] for company.
+    async fn queue_append(
+        &self,
+        myself: &ActorRef<EventStoreMsg>,
+        event: AppendEvent,
+        state: &mut EventStoreState,
+    ) {
+        // Checked here so one bad event cannot fail the batch it joins.
+        if let Err(e) = validate_payload(&event) {
+            tracing::warn!(
+                error = %e,
+                event_type = %event.event_type,
+                "Dropped invalid async append"
+            );
+            return;
+        }
+        let control = matches!(
+            telemetry_gate::event_lane(&event.event_type, &event.payload),
+            EventLane::Control
+        );
+        state.pending_appends.push(event);
+        if control || state.pending_appends.len() >= APPEND_BATCH_MAX_EVENTS {
+            self.flush_appends(state).await;
+        } else if !state.append_flush_scheduled {
+            state.append_flush_scheduled = true;
+            let myself = myself.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(APPEND_FLUSH_DELAY).await;
+                let _ = myself.cast(EventStoreMsg::FlushAppends);
+            });
+        }
+    }
+
+    /// Write the queued `AppendAsync` events in one transaction.
+    async fn flush_appends(&self, state: &mut EventStoreState) {
+        state.append_flush_scheduled = false;
+        if state.pending_appends.is_empty() {
+            return;
+        }
+        let events = std::mem::take(&mut state.pending_appends);
+        let count = events.len();
+        if let Err(e) = self.handle_append_batch(events, state).await {
+            tracing::warn!(error = %e, count, "Failed to store queued async appends");
+        }
+    }
+
+    /// Insert one validated event on `conn`, sealing its sensitive fields.
+    ///
+    /// The timestamp is clamped to the previous event's so a clock stepping
+    /// backwards cannot reorder events relative to seq, and `actor_seq` comes
+    /// from the actor's counter in `actor_seq_counters`. An event whose
+    /// idempotency key its user already stored is not inserted again; the
+    /// stored event is returned instead. With `event_search` the event is indexed
+    /// on the same connection.
+    async fn insert_event(
+        conn: &mut SqliteConnection,
+        encryption: &EventEncryption,
+        event_search: bool,
+        msg: AppendEvent,
+    ) -> Result<shared_types::Event, EventStoreError> {
+        if let Some(key) = msg.idempotency_key.as_deref() {
+            let existing = sqlx::query_as!(
+                EventRow,
+                r#"
+                SELECT seq as "seq!", event_id, timestamp, event_type, payload, actor_id, actor_seq, user_id, encryption_key_id
+                FROM events
+                WHERE user_id = ?1 AND idempotency_key = ?2
+                "#,
+                msg.user_id,
+                key,
+            )
+            .fetch_optional(&mut *conn)
+            .await?;
+            if let Some(row) = existing {
+                return parse_event_row(row, encryption);
+            }
+        }
+
+        let event_id = ulid::Ulid::new().to_string();
+        let shared_types::EventScope {
+            session_id: scope_session_id,
+            thread_id: scope_thread_id,
+        } = shared_types::EventScope::from_payload(&msg.payload);
+        let mut stored_payload = msg.payload.clone();
+        let encryption_key_id = encryption
+            .seal(&msg.event_type, &event_id, &mut stored_payload)
+            .map_err(EventStoreError::Encryption)?;
+        let payload_json = serde_json::to_string(&stored_payload)?;
+
         let row = sqlx::query_as!(
             EventRow,
             r#"
-            INSERT INTO events (event_id, event_type, payload, actor_id, user_id, session_id, thread_id)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            INSERT INTO events (event_id, timestamp, event_type, payload, actor_id, actor_seq, user_id, session_id, thread_id, encryption_key_id, idempotency_key)
+            VALUES (
+                ?1,
+                max(
+                    datetime('now'),
+                    COALESCE((SELECT timestamp FROM events ORDER BY seq DESC LIMIT 1), datetime('now'))
+                ),
+                ?2, ?3, ?4,
+                COALESCE((SELECT last_actor_seq FROM actor_seq_counters WHERE actor_id = ?4), 0) + 1,
+                ?5, ?6, ?7, ?8, ?9
+            )
             RETURNING
                 seq as "seq!",
                 event_id,
@@ -420,7 +1558,9 @@ impl EventStoreActor {
                 event_type,
                 payload,
                 actor_id,
-                user_id
+                actor_seq,
+                user_id,
+                encryption_key_id
             "#,
             event_id,
             msg.event_type,
@@ -429,12 +1569,217 @@ impl EventStoreActor {
             msg.user_id,
             scope_session_id,
             scope_thread_id,
+            encryption_key_id,
+            msg.idempotency_key,
         )
-        .fetch_one(&mut *tx)
+        .fetch_one(&mut *conn)
+        .await?;
+        Self::advance_actor_seq(&mut *conn, &row.actor_id).await?;
+        if event_search {
+            Self::index_event(
+                &mut *conn,
+                row.seq,
+                &row.event_type,
+                &row.actor_id,
+                &stored_payload,
+            )
+            .await?;
+        }
+
+        // The caller already holds the plaintext; skip opening what was just sealed.
+        let mut event = parse_event_row(
+            EventRow {
+                encryption_key_id: None,
+                ..row
+            },
+            encryption,
+        )?;
+        event.payload = msg.payload;
+        Ok(event)
+    }
+
+    /// Record that `actor_id`'s next event was just stored. Its `actor_seq`
+    /// was read from the same counter, which only ever moves forward.
+    async fn advance_actor_seq(
+        conn: &mut SqliteConnection,
+        actor_id: &str,
+    ) -> Result<(), EventStoreError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO actor_seq_counters (actor_id, last_actor_seq)
+            VALUES (?1, 1)
+            ON CONFLICT(actor_id) DO UPDATE SET last_actor_seq = last_actor_seq + 1
+            "#,
+            actor_id,
+        )
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Add the event at `seq` to the event search index. `stored_payload` is
+    /// the payload as written, so sealed fields stay out of the index.
+    ///
+    /// An `event.redacted` marker also takes its target out of the index.
+    /// Catch-up indexes in `seq` order, so the target is always in by then.
+    async fn index_event(
+        conn: &mut SqliteConnection,
+        seq: i64,
+        event_type: &str,
+        actor_id: &str,
+        stored_payload: &serde_json::Value,
+    ) -> Result<(), EventStoreError> {
+        let content = payload_search_text(stored_payload);
+        sqlx::query!(
+            "INSERT INTO event_search (rowid, event_type, actor_id, content) VALUES (?1, ?2, ?3, ?4)",
+            seq,
+            event_type,
+            actor_id,
+            content,
+        )
+        .execute(&mut *conn)
+        .await?;
+        if event_type == shared_types::EVENT_TOPIC_EVENT_REDACTED {
+            if let Some(target) = stored_payload.get("seq").and_then(|v| v.as_i64()) {
+                sqlx::query("DELETE FROM event_search WHERE rowid = ?1")
+                    .bind(target)
+                    .execute(&mut *conn)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Index events stored after the last indexed one, in batches. Returns
+    /// how many were indexed.
+    async fn catch_up_event_search(conn: &mut SqliteConnection) -> Result<u64, EventStoreError> {
+        const BATCH: i64 = 500;
+        let mut indexed = 0;
+        loop {
+            let rows = sqlx::query!(
+                r#"
+                SELECT seq as "seq!", event_type, actor_id, payload
+                FROM events
+                WHERE seq > (SELECT COALESCE(MAX(rowid), 0) FROM event_search)
+                ORDER BY seq ASC
+                LIMIT ?1
+                "#,
+                BATCH,
+            )
+            .fetch_all(&mut *conn)
+            .await?;
+            let count = rows.len() as i64;
+            for row in rows {
+                let payload: serde_json::Value = serde_json::from_str(&row.payload)?;
+                Self::index_event(
+                    &mut *conn,
+                    row.seq,
+                    &row.event_type,
+                    &row.actor_id,
+                    &payload,
+                )
+                .await?;
+                indexed += 1;
+            }
+            if count < BATCH {
+                return Ok(indexed);
+            }
+        }
+    }
+
+    async fn handle_configure_encryption(
+        &self,
+        encryption: EventEncryption,
+        state: &mut EventStoreState,
+    ) -> Result<EncryptionStatus, EventStoreError> {
+        let stored_key_ids = sqlx::query_scalar!(
+            r#"
+            SELECT DISTINCT encryption_key_id AS "key_id!"
+            FROM events
+            WHERE encryption_key_id IS NOT NULL
+            "#
+        )
+        .fetch_all(&state.pool)
+        .await?;
+
+        let locked_key_ids: Vec<String> = stored_key_ids
+            .into_iter()
+            .filter(|key_id| !encryption.has_key(key_id))
+            .collect();
+        if !locked_key_ids.is_empty() {
+            tracing::warn!(
+                locked_key_ids = ?locked_key_ids,
+                "Event log holds rows sealed with unavailable keys; their content reads as locked"
+            );
+        }
+        let status = EncryptionStatus {
+            current_key_id: encryption.current_key_id().map(str::to_string),
+            locked_key_ids,
+        };
+        state.encryption = encryption;
+        Ok(status)
+    }
+
+    async fn handle_reencrypt_batch(
+        &self,
+        after_seq: i64,
+        batch_size: i64,
+        state: &mut EventStoreState,
+    ) -> Result<ReencryptBatch, EventStoreError> {
+        let Some(current_key_id) = state.encryption.current_key_id().map(str::to_string) else {
+            return Err(EventStoreError::Encryption(
+                "no current key to re-encrypt with".to_string(),
+            ));
+        };
+        let batch_size = batch_size.clamp(1, 1000);
+        let rows = sqlx::query!(
+            r#"
+            SELECT seq as "seq!", event_id, event_type, payload, encryption_key_id AS "key_id!"
+            FROM events
+            WHERE seq > ?1
+              AND encryption_key_id IS NOT NULL
+              AND encryption_key_id != ?2
+            ORDER BY seq ASC
+            LIMIT ?3
+            "#,
+            after_seq,
+            current_key_id,
+            batch_size,
+        )
+        .fetch_all(&state.pool)
         .await?;
-        tx.commit().await?;
 
-        parse_event_row(row)
+        let mut batch = ReencryptBatch {
+            last_seq: rows.last().map(|row| row.seq),
+            ..Default::default()
+        };
+        let mut tx = state.pool.begin().await?;
+        for row in rows {
+            let mut payload: serde_json::Value = serde_json::from_str(&row.payload)?;
+            if !state
+                .encryption
+                .open(&row.key_id, &row.event_id, &mut payload)
+            {
+                batch.locked += 1;
+                continue;
+            }
+            let key_id = state
+                .encryption
+                .seal(&row.event_type, &row.event_id, &mut payload)
+                .map_err(EventStoreError::Encryption)?;
+            let payload_json = serde_json::to_string(&payload)?;
+            sqlx::query!(
+                "UPDATE events SET payload = ?1, encryption_key_id = ?2 WHERE seq = ?3",
+                payload_json,
+                key_id,
+                row.seq,
+            )
+            .execute(&mut *tx)
+            .await?;
+            batch.reencrypted += 1;
+        }
+        tx.commit().await?;
+        Ok(batch)
     }
 
     async fn handle_get_events_for_actor(
@@ -446,7 +1791,7 @@ impl EventStoreActor {
         let rows = sqlx::query_as!(
             EventRow,
             r#"
-            SELECT seq as "seq!", event_id, timestamp, event_type, payload, actor_id, user_id
+            SELECT seq as "seq!", event_id, timestamp, event_type, payload, actor_id, actor_seq, user_id, encryption_key_id
             FROM events
             WHERE actor_id = ?1 AND seq > ?2
             ORDER BY seq ASC
@@ -457,7 +1802,28 @@ impl EventStoreActor {
         .fetch_all(&state.pool)
         .await?;
 
-        rows.into_iter().map(parse_event_row).collect()
+        rows.into_iter()
+            .map(|row| parse_event_row(row, &state.encryption))
+            .collect()
+    }
+
+    async fn handle_query_events(
+        &self,
+        query: shared_types::QueryEvents,
+        state: &mut EventStoreState,
+    ) -> Result<Vec<shared_types::Event>, EventStoreError> {
+        let shared_types::QueryEvents {
+            actor_id,
+            since_seq,
+            lane,
+        } = query;
+        let mut events = self
+            .handle_get_events_for_actor(actor_id.0, since_seq, state)
+            .await?;
+        if let Some(lane) = lane {
+            events.retain(|event| parse_event_metadata(&event.payload).lane == lane);
+        }
+        Ok(events)
     }
 
     async fn handle_get_events_for_actor_with_scope(
@@ -471,7 +1837,7 @@ impl EventStoreActor {
         let rows = sqlx::query_as!(
             EventRow,
             r#"
-            SELECT seq as "seq!", event_id, timestamp, event_type, payload, actor_id, user_id
+            SELECT seq as "seq!", event_id, timestamp, event_type, payload, actor_id, actor_seq, user_id, encryption_key_id
             FROM events
             WHERE actor_id = ?1
               AND seq > ?2
@@ -494,7 +1860,9 @@ impl EventStoreActor {
         .fetch_all(&state.pool)
         .await?;
 
-        rows.into_iter().map(parse_event_row).collect()
+        rows.into_iter()
+            .map(|row| parse_event_row(row, &state.encryption))
+            .collect()
     }
 
     async fn handle_get_recent_events(
@@ -505,6 +1873,27 @@ impl EventStoreActor {
         actor_id: Option<String>,
         user_id: Option<String>,
         state: &mut EventStoreState,
+    ) -> Result<Vec<shared_types::Event>, EventStoreError> {
+        Self::query_recent_events(
+            &state.pool,
+            &state.encryption,
+            since_seq,
+            limit,
+            event_type_prefix,
+            actor_id,
+            user_id,
+        )
+        .await
+    }
+
+    async fn query_recent_events(
+        pool: &SqlitePool,
+        encryption: &EventEncryption,
+        since_seq: i64,
+        limit: i64,
+        event_type_prefix: Option<String>,
+        actor_id: Option<String>,
+        user_id: Option<String>,
     ) -> Result<Vec<shared_types::Event>, EventStoreError> {
         let safe_limit = limit.clamp(1, 1000);
         // Build LIKE pattern outside the query.
@@ -513,7 +1902,7 @@ impl EventStoreActor {
         let rows = sqlx::query_as!(
             EventRow,
             r#"
-            SELECT seq as "seq!", event_id, timestamp, event_type, payload, actor_id, user_id
+            SELECT seq as "seq!", event_id, timestamp, event_type, payload, actor_id, actor_seq, user_id, encryption_key_id
             FROM events
             WHERE seq > ?1
               AND (?2 IS NULL OR event_type LIKE ?2)
@@ -528,10 +1917,12 @@ impl EventStoreActor {
             user_id,
             safe_limit,
         )
-        .fetch_all(&state.pool)
+        .fetch_all(pool)
         .await?;
 
-        rows.into_iter().map(parse_event_row).collect()
+        rows.into_iter()
+            .map(|row| parse_event_row(row, encryption))
+            .collect()
     }
 
     async fn handle_get_event_by_seq(
@@ -542,7 +1933,7 @@ impl EventStoreActor {
         let maybe_row = sqlx::query_as!(
             EventRow,
             r#"
-            SELECT seq as "seq!", event_id, timestamp, event_type, payload, actor_id, user_id
+            SELECT seq as "seq!", event_id, timestamp, event_type, payload, actor_id, actor_seq, user_id, encryption_key_id
             FROM events
             WHERE seq = ?1
             "#,
@@ -551,7 +1942,31 @@ impl EventStoreActor {
         .fetch_optional(&state.pool)
         .await?;
 
-        maybe_row.map(parse_event_row).transpose()
+        maybe_row
+            .map(|row| parse_event_row(row, &state.encryption))
+            .transpose()
+    }
+
+    async fn handle_existing_event_ids(
+        &self,
+        event_ids: &[String],
+        state: &mut EventStoreState,
+    ) -> Result<HashSet<String>, EventStoreError> {
+        if event_ids.is_empty() {
+            return Ok(HashSet::new());
+        }
+        let ids_json = serde_json::to_string(event_ids)?;
+        let rows = sqlx::query!(
+            r#"
+            SELECT event_id
+            FROM events
+            WHERE event_id IN (SELECT value FROM json_each(?1))
+            "#,
+            ids_json,
+        )
+        .fetch_all(&state.pool)
+        .await?;
+        Ok(rows.into_iter().map(|row| row.event_id).collect())
     }
 
     async fn handle_get_latest_seq(
@@ -564,6 +1979,22 @@ impl EventStoreActor {
         Ok(row.max_seq)
     }
 
+    async fn handle_get_last_seq_before(
+        &self,
+        before: chrono::DateTime<chrono::Utc>,
+        state: &mut EventStoreState,
+    ) -> Result<i64, EventStoreError> {
+        // Same TEXT format the rows are stored in, so the comparison is ordinal.
+        let before = before.format("%Y-%m-%d %H:%M:%S").to_string();
+        let row = sqlx::query!(
+            r#"SELECT COALESCE(MAX(seq), 0) as "seq!: i64" FROM events WHERE timestamp < ?1"#,
+            before,
+        )
+        .fetch_one(&state.pool)
+        .await?;
+        Ok(row.seq)
+    }
+
     /// Find all events whose payload contains `"corr_id": "<corr_id>"`.
     /// Optionally filter by event_type prefix.
     /// Used by harness recovery to check whether a pending reply already landed.
@@ -580,7 +2011,7 @@ impl EventStoreActor {
             sqlx::query_as!(
                 EventRow,
                 r#"
-                SELECT seq as "seq!", event_id, timestamp, event_type, payload, actor_id, user_id
+                SELECT seq as "seq!", event_id, timestamp, event_type, payload, actor_id, actor_seq, user_id, encryption_key_id
                 FROM events
                 WHERE payload LIKE ?1
                   AND event_type LIKE ?2
@@ -595,7 +2026,7 @@ impl EventStoreActor {
             sqlx::query_as!(
                 EventRow,
                 r#"
-                SELECT seq as "seq!", event_id, timestamp, event_type, payload, actor_id, user_id
+                SELECT seq as "seq!", event_id, timestamp, event_type, payload, actor_id, actor_seq, user_id, encryption_key_id
                 FROM events
                 WHERE payload LIKE ?1
                 ORDER BY seq ASC
@@ -606,204 +2037,3001 @@ impl EventStoreActor {
             .await?
         };
 
-        rows.into_iter().map(parse_event_row).collect()
+        rows.into_iter()
+            .map(|row| parse_event_row(row, &state.encryption))
+            .collect()
     }
 
-    /// Get the most recent `harness.checkpoint` event for a given run_id.
-    /// Returns None if no checkpoint exists (run never checkpointed or already
-    /// complete and cleaned up).
-    async fn handle_get_latest_harness_checkpoint(
+    async fn handle_query_filtered(
         &self,
-        run_id: &str,
+        query: EventQuery,
         state: &mut EventStoreState,
-    ) -> Result<Option<shared_types::Event>, EventStoreError> {
-        let run_id_pattern = format!("%\"run_id\":\"{run_id}\"%");
-        let maybe_row = sqlx::query_as!(
-            EventRow,
-            r#"
-            SELECT seq as "seq!", event_id, timestamp, event_type, payload, actor_id, user_id
-            FROM events
-            WHERE event_type = 'harness.checkpoint'
-              AND payload LIKE ?1
-            ORDER BY seq DESC
-            LIMIT 1
-            "#,
-            run_id_pattern,
-        )
-        .fetch_optional(&state.pool)
-        .await?;
-
-        maybe_row.map(parse_event_row).transpose()
-    }
-}
+    ) -> Result<EventPage, EventStoreError> {
+        let limit = query.limit.clamp(1, MAX_QUERY_PAGE);
+        // One extra row tells whether another page follows.
+        let fetch = limit + 1;
+        let prefixes: Vec<&str> = query
+            .event_type_prefixes
+            .iter()
+            .map(|prefix| prefix.trim().trim_end_matches('*'))
+            .collect();
+        // An empty prefix matches everything, so it disables the filter.
+        let prefixes = (!prefixes.is_empty() && !prefixes.contains(&""))
+            .then(|| serde_json::to_string(&prefixes))
+            .transpose()?;
+        // Same TEXT format the rows are stored in, so comparisons are ordinal.
+        let format_bound =
+            |bound: chrono::DateTime<chrono::Utc>| bound.format("%Y-%m-%d %H:%M:%S").to_string();
+        let since = query.since.map(format_bound);
+        let until = query.until.map(format_bound);
 
-// ============================================================================
-// Helper Functions
-// ============================================================================
+        let rows = if query.newest_first {
+            let before_seq = query.cursor.unwrap_or(i64::MAX);
+            sqlx::query_as!(
+                EventRow,
+                r#"
+                SELECT seq as "seq!", event_id, timestamp, event_type, payload, actor_id, actor_seq, user_id, encryption_key_id
+                FROM events
+                WHERE seq < ?1
+                  AND (?2 IS NULL OR actor_id = ?2)
+                  AND (?3 IS NULL OR EXISTS (
+                      SELECT 1 FROM json_each(?3) AS prefix
+                      WHERE substr(events.event_type, 1, length(prefix.value)) = prefix.value
+                  ))
+                  AND (?4 IS NULL OR timestamp >= ?4)
+                  AND (?5 IS NULL OR timestamp <= ?5)
+                ORDER BY seq DESC
+                LIMIT ?6
+                "#,
+                before_seq,
+                query.actor_id,
+                prefixes,
+                since,
+                until,
+                fetch,
+            )
+            .fetch_all(&state.pool)
+            .await?
+        } else {
+            let after_seq = query.cursor.unwrap_or(0);
+            sqlx::query_as!(
+                EventRow,
+                r#"
+                SELECT seq as "seq!", event_id, timestamp, event_type, payload, actor_id, actor_seq, user_id, encryption_key_id
+                FROM events
+                WHERE seq > ?1
+                  AND (?2 IS NULL OR actor_id = ?2)
+                  AND (?3 IS NULL OR EXISTS (
+                      SELECT 1 FROM json_each(?3) AS prefix
+                      WHERE substr(events.event_type, 1, length(prefix.value)) = prefix.value
+                  ))
+                  AND (?4 IS NULL OR timestamp >= ?4)
+                  AND (?5 IS NULL OR timestamp <= ?5)
+                ORDER BY seq ASC
+                LIMIT ?6
+                "#,
+                after_seq,
+                query.actor_id,
+                prefixes,
+                since,
+                until,
+                fetch,
+            )
+            .fetch_all(&state.pool)
+            .await?
+        };
 
-/// Convenience function to append an event
-pub async fn append_event(
-    store: &ActorRef<EventStoreMsg>,
-    event: AppendEvent,
-) -> Result<Result<shared_types::Event, EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
-    ractor::call!(store, |reply| EventStoreMsg::Append { event, reply })
-}
+        let has_more = rows.len() as i64 > limit;
+        let events = rows
+            .into_iter()
+            .take(limit as usize)
+            .map(|row| parse_event_row(row, &state.encryption))
+            .collect::<Result<Vec<_>, _>>()?;
+        let next_cursor = if has_more {
+            events.last().map(|event| event.seq)
+        } else {
+            None
+        };
+        Ok(EventPage {
+            events,
+            next_cursor,
+        })
+    }
 
-/// Convenience function to get events for an actor
-pub async fn get_events_for_actor(
-    store: &ActorRef<EventStoreMsg>,
-    actor_id: impl Into<String>,
-    since_seq: i64,
-) -> Result<Result<Vec<shared_types::Event>, EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
+    async fn handle_query_by_time(
+        &self,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+        actor_id: Option<String>,
+        limit: i64,
+        state: &mut EventStoreState,
+    ) -> Result<Vec<shared_types::Event>, EventStoreError> {
+        let limit = limit.clamp(1, MAX_QUERY_PAGE);
+        // Same TEXT format the rows are stored in, so comparisons are ordinal.
+        let from = from.format("%Y-%m-%d %H:%M:%S").to_string();
+        let to = to.format("%Y-%m-%d %H:%M:%S").to_string();
+        // Separate statements so each can use its index: timestamp alone, or
+        // actor_id then timestamp.
+        let rows = if let Some(actor_id) = actor_id {
+            sqlx::query_as!(
+                EventRow,
+                r#"
+                SELECT seq as "seq!", event_id, timestamp, event_type, payload, actor_id, actor_seq, user_id, encryption_key_id
+                FROM events
+                WHERE actor_id = ?1
+                  AND timestamp >= ?2
+                  AND timestamp <= ?3
+                ORDER BY seq DESC
+                LIMIT ?4
+                "#,
+                actor_id,
+                from,
+                to,
+                limit,
+            )
+            .fetch_all(&state.pool)
+            .await?
+        } else {
+            sqlx::query_as!(
+                EventRow,
+                r#"
+                SELECT seq as "seq!", event_id, timestamp, event_type, payload, actor_id, actor_seq, user_id, encryption_key_id
+                FROM events
+                WHERE timestamp >= ?1
+                  AND timestamp <= ?2
+                ORDER BY seq DESC
+                LIMIT ?3
+                "#,
+                from,
+                to,
+                limit,
+            )
+            .fetch_all(&state.pool)
+            .await?
+        };
+
+        rows.into_iter()
+            .map(|row| parse_event_row(row, &state.encryption))
+            .collect()
+    }
+
+    async fn handle_query_tail(
+        &self,
+        actor_id: Option<String>,
+        limit: i64,
+        state: &mut EventStoreState,
+    ) -> Result<Vec<shared_types::Event>, EventStoreError> {
+        let limit = limit.clamp(1, MAX_QUERY_PAGE);
+        let rows = if let Some(actor_id) = actor_id {
+            sqlx::query_as!(
+                EventRow,
+                r#"
+                SELECT seq as "seq!", event_id, timestamp, event_type, payload, actor_id, actor_seq, user_id, encryption_key_id
+                FROM events
+                WHERE actor_id = ?1
+                ORDER BY seq DESC
+                LIMIT ?2
+                "#,
+                actor_id,
+                limit,
+            )
+            .fetch_all(&state.pool)
+            .await?
+        } else {
+            sqlx::query_as!(
+                EventRow,
+                r#"
+                SELECT seq as "seq!", event_id, timestamp, event_type, payload, actor_id, actor_seq, user_id, encryption_key_id
+                FROM events
+                ORDER BY seq DESC
+                LIMIT ?1
+                "#,
+                limit,
+            )
+            .fetch_all(&state.pool)
+            .await?
+        };
+
+        rows.into_iter()
+            .map(|row| parse_event_row(row, &state.encryption))
+            .collect()
+    }
+
+    async fn handle_query_by_correlation(
+        &self,
+        correlation_id: &str,
+        lane: Option<EventLane>,
+        state: &mut EventStoreState,
+    ) -> Result<Vec<shared_types::Event>, EventStoreError> {
+        let rows = sqlx::query_as!(
+            EventRow,
+            r#"
+            SELECT seq as "seq!", event_id, timestamp, event_type, payload, actor_id, actor_seq, user_id, encryption_key_id
+            FROM events
+            WHERE json_extract(payload, '$.correlation_id') = ?1
+               OR json_extract(payload, '$.trace_id') = ?1
+            ORDER BY seq ASC
+            "#,
+            correlation_id,
+        )
+        .fetch_all(&state.pool)
+        .await?;
+
+        let mut events = Vec::with_capacity(rows.len());
+        for row in rows {
+            let event = parse_event_row(row, &state.encryption)?;
+            if lane.map_or(true, |lane| {
+                parse_event_metadata(&event.payload).lane == lane
+            }) {
+                events.push(event);
+            }
+        }
+        Ok(events)
+    }
+
+    /// Get the most recent `harness.checkpoint` event for a given run_id.
+    /// Returns None if no checkpoint exists (run never checkpointed or already
+    /// complete and cleaned up).
+    async fn handle_get_latest_harness_checkpoint(
+        &self,
+        run_id: &str,
+        state: &mut EventStoreState,
+    ) -> Result<Option<shared_types::Event>, EventStoreError> {
+        let run_id_pattern = format!("%\"run_id\":\"{run_id}\"%");
+        let maybe_row = sqlx::query_as!(
+            EventRow,
+            r#"
+            SELECT seq as "seq!", event_id, timestamp, event_type, payload, actor_id, actor_seq, user_id, encryption_key_id
+            FROM events
+            WHERE event_type = 'harness.checkpoint'
+              AND payload LIKE ?1
+            ORDER BY seq DESC
+            LIMIT 1
+            "#,
+            run_id_pattern,
+        )
+        .fetch_optional(&state.pool)
+        .await?;
+
+        maybe_row
+            .map(|row| parse_event_row(row, &state.encryption))
+            .transpose()
+    }
+
+    async fn handle_get_projection_cursor(
+        &self,
+        name: &str,
+        state: &mut EventStoreState,
+    ) -> Result<Option<ProjectionCursor>, EventStoreError> {
+        let cursor = sqlx::query_as!(
+            ProjectionCursor,
+            r#"
+            SELECT name, cursor_seq, status, updated_at
+            FROM projection_cursors
+            WHERE name = ?1
+            "#,
+            name,
+        )
+        .fetch_optional(&state.pool)
+        .await?;
+
+        Ok(cursor)
+    }
+
+    async fn handle_set_projection_cursor(
+        &self,
+        name: &str,
+        cursor_seq: i64,
+        status: &str,
+        state: &mut EventStoreState,
+    ) -> Result<(), EventStoreError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO projection_cursors (name, cursor_seq, status, updated_at)
+            VALUES (?1, ?2, ?3, datetime('now'))
+            ON CONFLICT(name) DO UPDATE SET
+                cursor_seq = excluded.cursor_seq,
+                status = excluded.status,
+                updated_at = excluded.updated_at
+            "#,
+            name,
+            cursor_seq,
+            status,
+        )
+        .execute(&state.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn handle_save_snapshot(
+        &self,
+        snapshot: ActorSnapshot,
+        state: &mut EventStoreState,
+    ) -> Result<(), EventStoreError> {
+        let snapshot_state = serde_json::to_string(&snapshot.state)?;
+        sqlx::query!(
+            r#"
+            INSERT INTO actor_snapshots (actor_id, seq, state, created_at)
+            VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT(actor_id) DO UPDATE SET
+                seq = excluded.seq,
+                state = excluded.state,
+                created_at = excluded.created_at
+            "#,
+            snapshot.actor_id,
+            snapshot.seq,
+            snapshot_state,
+            snapshot.created_at,
+        )
+        .execute(&state.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn handle_get_latest_snapshot(
+        &self,
+        actor_id: &str,
+        state: &mut EventStoreState,
+    ) -> Result<Option<ActorSnapshot>, EventStoreError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT actor_id, seq, state, created_at
+            FROM actor_snapshots
+            WHERE actor_id = ?1
+            "#,
+            actor_id,
+        )
+        .fetch_optional(&state.pool)
+        .await?;
+
+        row.map(|row| {
+            Ok(ActorSnapshot {
+                actor_id: row.actor_id,
+                seq: row.seq,
+                state: serde_json::from_str(&row.state)?,
+                created_at: row.created_at,
+            })
+        })
+        .transpose()
+    }
+
+    async fn handle_index_search_entry(
+        &self,
+        entry: SearchIndexEntry,
+        state: &mut EventStoreState,
+    ) -> Result<(), EventStoreError> {
+        let target = serde_json::to_string(&entry.target)?;
+        sqlx::query!(
+            r#"
+            INSERT OR REPLACE INTO search_index (rowid, title, content, kind, target, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+            entry.seq,
+            entry.title,
+            entry.content,
+            entry.kind,
+            target,
+            entry.created_at,
+        )
+        .execute(&state.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn handle_remove_search_entry(
+        &self,
+        seq: i64,
+        state: &mut EventStoreState,
+    ) -> Result<bool, EventStoreError> {
+        let result = sqlx::query!("DELETE FROM search_index WHERE rowid = ?1", seq)
+            .execute(&state.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn handle_clear_search_index(
+        &self,
+        state: &mut EventStoreState,
+    ) -> Result<(), EventStoreError> {
+        sqlx::query!("DELETE FROM search_index")
+            .execute(&state.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn handle_health_check(&self, state: &mut EventStoreState) -> EventStoreHealth {
+        let started = std::time::Instant::now();
+        let result = tokio::time::timeout(HEALTH_CHECK_TIMEOUT, Self::probe_write(&state.pool))
+            .await
+            .unwrap_or_else(|_| {
+                Err(EventStoreError::Database(format!(
+                    "no write within {}ms",
+                    HEALTH_CHECK_TIMEOUT.as_millis()
+                )))
+            });
+        EventStoreHealth {
+            writable: result.is_ok(),
+            latency_ms: started.elapsed().as_millis() as u64,
+            error: result.err().map(|e| e.to_string()),
+        }
+    }
+
+    /// Upsert the probe snapshot row and read it back, then roll back so
+    /// nothing is left behind.
+    async fn probe_write(pool: &SqlitePool) -> Result<(), EventStoreError> {
+        let mut tx = pool.begin().await?;
+        sqlx::query(
+            r#"
+            INSERT INTO actor_snapshots (actor_id, seq, state)
+            VALUES (?1, 0, '{}')
+            ON CONFLICT(actor_id) DO UPDATE SET seq = seq + 1
+            "#,
+        )
+        .bind(HEALTH_CHECK_PROBE_ID)
+        .execute(&mut *tx)
+        .await?;
+        let found: Option<(i64,)> =
+            sqlx::query_as("SELECT seq FROM actor_snapshots WHERE actor_id = ?1")
+                .bind(HEALTH_CHECK_PROBE_ID)
+                .fetch_optional(&mut *tx)
+                .await?;
+        tx.rollback().await?;
+        found.map(|_| ()).ok_or_else(|| {
+            EventStoreError::Database("probe row not visible after write".to_string())
+        })
+    }
+
+    async fn export_ndjson(
+        pool: &SqlitePool,
+        encryption: &EventEncryption,
+        since_seq: i64,
+        until_seq: Option<i64>,
+        writer: ExportWriter,
+    ) -> Result<u64, EventStoreError> {
+        let mut writer = writer.0;
+        let until_seq = match until_seq {
+            Some(seq) => seq,
+            None => sqlx::query!("SELECT MAX(seq) as max_seq FROM events")
+                .fetch_one(pool)
+                .await?
+                .max_seq
+                .unwrap_or(0),
+        };
+
+        let mut cursor = since_seq;
+        let mut written = 0;
+        'pages: while cursor < until_seq {
+            let events = Self::query_recent_events(
+                pool,
+                encryption,
+                cursor,
+                MAX_QUERY_PAGE,
+                None,
+                None,
+                None,
+            )
+            .await?;
+            if events.is_empty() {
+                break;
+            }
+            for event in events {
+                if event.seq > until_seq {
+                    break 'pages;
+                }
+                cursor = event.seq;
+                let mut line = serde_json::to_vec(&event)?;
+                line.push(b'\n');
+                writer.write_all(&line).await?;
+                written += 1;
+            }
+        }
+        writer.flush().await?;
+        writer.shutdown().await?;
+        Ok(written)
+    }
+
+    async fn handle_import(
+        &self,
+        reader: ImportReader,
+        state: &mut EventStoreState,
+    ) -> Result<ImportReport, EventStoreError> {
+        let mut lines = reader.0.lines();
+        let mut report = ImportReport::default();
+        let mut last_seq: Option<i64> = None;
+        let mut line_number = 0;
+
+        // Dropped without commit on any error, so a bad file stores nothing.
+        let mut tx = state.pool.begin().await?;
+        while let Some(line) = lines.next_line().await? {
+            line_number += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let event: shared_types::Event = serde_json::from_str(&line)
+                .map_err(|e| EventStoreError::InvalidPayload(format!("line {line_number}: {e}")))?;
+            if let Some(last_seq) = last_seq.filter(|last| event.seq <= *last) {
+                return Err(EventStoreError::InvalidPayload(format!(
+                    "line {line_number}: seq {} does not follow seq {last_seq}",
+                    event.seq
+                )));
+            }
+            last_seq = Some(event.seq);
+
+            let shared_types::EventScope {
+                session_id: scope_session_id,
+                thread_id: scope_thread_id,
+            } = shared_types::EventScope::from_payload(&event.payload);
+            let mut stored_payload = event.payload;
+            let encryption_key_id = state
+                .encryption
+                .seal(&event.event_type, &event.event_id, &mut stored_payload)
+                .map_err(EventStoreError::Encryption)?;
+            let payload_json = serde_json::to_string(&stored_payload)?;
+            let stored_at = event.stored_at.format("%Y-%m-%d %H:%M:%S").to_string();
+
+            let inserted = sqlx::query!(
+                r#"
+                INSERT INTO events (event_id, timestamp, event_type, payload, actor_id, actor_seq, user_id, session_id, thread_id, encryption_key_id)
+                VALUES (
+                    ?1,
+                    max(?2, COALESCE((SELECT timestamp FROM events ORDER BY seq DESC LIMIT 1), ?2)),
+                    ?3, ?4, ?5,
+                    COALESCE((SELECT last_actor_seq FROM actor_seq_counters WHERE actor_id = ?5), 0) + 1,
+                    ?6, ?7, ?8, ?9
+                )
+                ON CONFLICT(event_id) DO NOTHING
+                "#,
+                event.event_id,
+                stored_at,
+                event.event_type,
+                payload_json,
+                event.actor_id.0,
+                event.user_id,
+                scope_session_id,
+                scope_thread_id,
+                encryption_key_id,
+            )
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+            if inserted == 0 {
+                report.duplicates += 1;
+            } else {
+                Self::advance_actor_seq(&mut tx, &event.actor_id.0).await?;
+                report.imported += 1;
+            }
+        }
+        if state.event_search {
+            Self::catch_up_event_search(&mut tx).await?;
+        }
+        tx.commit().await?;
+
+        Ok(report)
+    }
+
+    async fn handle_compact(
+        &self,
+        state: &mut EventStoreState,
+    ) -> Result<CompactionReport, EventStoreError> {
+        let keep_undeclared = state.compaction.control_keep_forever;
+        let mut report = CompactionReport::default();
+        let mut tx = state.pool.begin().await?;
+
+        // Same TEXT format the rows are stored in, so the comparison is ordinal.
+        let cutoff = state
+            .compaction
+            .telemetry_max_age
+            .and_then(|age| chrono::Duration::from_std(age).ok())
+            .and_then(|age| chrono::Utc::now().checked_sub_signed(age))
+            .map(|cutoff| cutoff.format("%Y-%m-%d %H:%M:%S").to_string());
+        // Highest actor_seq removed per actor, recorded as its watermark.
+        let mut compacted_through: HashMap<String, i64> = HashMap::new();
+        let mut record_removed = |rows: Vec<(String, i64)>| {
+            let removed = rows.len() as u64;
+            for (actor_id, actor_seq) in rows {
+                let through = compacted_through.entry(actor_id).or_default();
+                *through = (*through).max(actor_seq);
+            }
+            removed
+        };
+
+        if let Some(cutoff) = cutoff {
+            let rows = sqlx::query!(
+                r#"
+                DELETE FROM events
+                WHERE timestamp < ?1
+                  AND json_extract(payload, '$._meta.lane') IS NOT 'control'
+                  AND (json_type(payload, '$._meta') IS NOT NULL OR ?2 = 0)
+                RETURNING actor_id AS "actor_id!: String", actor_seq AS "actor_seq!: i64"
+                "#,
+                cutoff,
+                keep_undeclared,
+            )
+            .fetch_all(&mut *tx)
+            .await?;
+            report.expired = record_removed(
+                rows.into_iter()
+                    .map(|row| (row.actor_id, row.actor_seq))
+                    .collect(),
+            );
+        }
+
+        if let Some(max_rows) = state.compaction.telemetry_max_rows {
+            let max_rows = i64::try_from(max_rows).unwrap_or(i64::MAX);
+            let rows = sqlx::query!(
+                r#"
+                DELETE FROM events
+                WHERE seq IN (
+                    SELECT seq FROM events
+                    WHERE json_extract(payload, '$._meta.lane') IS NOT 'control'
+                      AND (json_type(payload, '$._meta') IS NOT NULL OR ?1 = 0)
+                    ORDER BY seq DESC
+                    LIMIT -1 OFFSET ?2
+                )
+                RETURNING actor_id AS "actor_id!: String", actor_seq AS "actor_seq!: i64"
+                "#,
+                keep_undeclared,
+                max_rows,
+            )
+            .fetch_all(&mut *tx)
+            .await?;
+            report.over_limit = record_removed(
+                rows.into_iter()
+                    .map(|row| (row.actor_id, row.actor_seq))
+                    .collect(),
+            );
+        }
+
+        for (actor_id, through) in &compacted_through {
+            sqlx::query!(
+                r#"
+                UPDATE actor_seq_counters
+                SET compacted_through = max(compacted_through, ?2)
+                WHERE actor_id = ?1
+                "#,
+                actor_id,
+                through,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        if report.removed() > 0 {
+            report.search_entries = sqlx::query!(
+                "DELETE FROM search_index WHERE rowid NOT IN (SELECT seq FROM events)"
+            )
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+            report.search_entries += sqlx::query!(
+                "DELETE FROM event_search WHERE rowid NOT IN (SELECT seq FROM events)"
+            )
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        }
+        tx.commit().await?;
+
+        // VACUUM cannot run inside a transaction.
+        sqlx::query("VACUUM").execute(&state.pool).await?;
+        Ok(report)
+    }
+
+    async fn handle_get_compacted_through(
+        &self,
+        actor_id: &str,
+        state: &mut EventStoreState,
+    ) -> Result<i64, EventStoreError> {
+        let through = sqlx::query_scalar!(
+            r#"
+            SELECT compacted_through AS "compacted_through!: i64"
+            FROM actor_seq_counters
+            WHERE actor_id = ?1
+            "#,
+            actor_id,
+        )
+        .fetch_optional(&state.pool)
+        .await?;
+        Ok(through.unwrap_or(0))
+    }
+
+    async fn handle_search_index(
+        &self,
+        match_expr: &str,
+        kinds: &[String],
+        limit: i64,
+        state: &mut EventStoreState,
+    ) -> Result<Vec<SearchIndexMatch>, EventStoreError> {
+        // Kinds are matched as ",kind," substrings of a comma-joined list.
+        let kinds = if kinds.is_empty() {
+            String::new()
+        } else {
+            format!(",{},", kinds.join(","))
+        };
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                rowid AS "seq!: i64",
+                kind AS "kind!: String",
+                title AS "title!: String",
+                snippet(search_index, -1, char(2), char(3), '…', 24) AS "snippet!: String",
+                target AS "target!: String",
+                created_at AS "created_at!: String",
+                bm25(search_index) AS "rank!: f64"
+            FROM search_index
+            WHERE search_index MATCH ?1
+              AND (?2 = '' OR instr(?2, ',' || kind || ',') > 0)
+            ORDER BY bm25(search_index), rowid DESC
+            LIMIT ?3
+            "#,
+            match_expr,
+            kinds,
+            limit,
+        )
+        .fetch_all(&state.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(SearchIndexMatch {
+                    seq: row.seq,
+                    kind: row.kind,
+                    title: row.title,
+                    snippet: row.snippet,
+                    target: serde_json::from_str(&row.target)?,
+                    created_at: row.created_at,
+                    score: -row.rank,
+                })
+            })
+            .collect()
+    }
+
+    async fn handle_search(
+        &self,
+        query: &str,
+        limit: i64,
+        state: &mut EventStoreState,
+    ) -> Result<Vec<EventSearchMatch>, EventStoreError> {
+        if !state.event_search {
+            return Err(EventStoreError::SearchDisabled);
+        }
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                e.seq AS "seq!",
+                e.event_id AS "event_id!",
+                e.timestamp AS "timestamp!",
+                e.event_type AS "event_type!",
+                e.payload AS "payload!",
+                e.actor_id AS "actor_id!",
+                e.actor_seq AS "actor_seq!",
+                e.user_id AS "user_id!",
+                e.encryption_key_id,
+                snippet(event_search, -1, char(2), char(3), '…', 24) AS "snippet!: String"
+            FROM event_search
+            JOIN events e ON e.seq = event_search.rowid
+            WHERE event_search MATCH ?1
+            ORDER BY bm25(event_search), e.seq DESC
+            LIMIT ?2
+            "#,
+            query,
+            limit,
+        )
+        .fetch_all(&state.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let event = parse_event_row(
+                    EventRow {
+                        seq: row.seq,
+                        event_id: row.event_id,
+                        timestamp: row.timestamp,
+                        event_type: row.event_type,
+                        payload: row.payload,
+                        actor_id: row.actor_id,
+                        actor_seq: row.actor_seq,
+                        user_id: row.user_id,
+                        encryption_key_id: row.encryption_key_id,
+                    },
+                    &state.encryption,
+                )?;
+                Ok(EventSearchMatch {
+                    event,
+                    snippet: row.snippet,
+                })
+            })
+            .collect()
+    }
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Convenience function to append an event
+pub async fn append_event(
+    store: &ActorRef<EventStoreMsg>,
+    event: AppendEvent,
+) -> Result<Result<shared_types::Event, EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
+    ractor::call!(store, |reply| EventStoreMsg::Append { event, reply })
+}
+
+/// Configure payload encryption; see [`crate::actors::event_encryption`].
+pub async fn configure_encryption(
+    store: &ActorRef<EventStoreMsg>,
+    encryption: EventEncryption,
+) -> Result<Result<EncryptionStatus, EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
+    ractor::call!(store, |reply| EventStoreMsg::ConfigureEncryption {
+        encryption,
+        reply
+    })
+}
+
+/// Re-seal every row sealed under a previous key, `batch_size` rows per
+/// message so appends interleave with the rotation. Returns the totals.
+pub async fn reencrypt_all(
+    store: &ActorRef<EventStoreMsg>,
+    batch_size: i64,
+) -> Result<ReencryptBatch, EventStoreError> {
+    let mut total = ReencryptBatch::default();
+    let mut after_seq = 0;
+    loop {
+        let batch = ractor::call!(store, |reply| EventStoreMsg::ReencryptBatch {
+            after_seq,
+            batch_size,
+            reply,
+        })
+        .map_err(|e| EventStoreError::Database(e.to_string()))??;
+        total.reencrypted += batch.reencrypted;
+        total.locked += batch.locked;
+        let Some(last_seq) = batch.last_seq else {
+            return Ok(total);
+        };
+        total.last_seq = Some(last_seq);
+        after_seq = last_seq;
+    }
+}
+
+/// Scan every stored event, `batch_size` per message, and summarize which
+/// scope fields each topic carries against what it requires today.
+pub async fn scope_coverage(
+    store: &ActorRef<EventStoreMsg>,
+    batch_size: i64,
+) -> Result<ScopeCoverageReport, EventStoreError> {
+    let mut topics: BTreeMap<String, TopicScopeCoverage> = BTreeMap::new();
+    let mut report = ScopeCoverageReport::default();
+    let mut since_seq = 0;
+    loop {
+        let events = get_recent_events(store, since_seq, batch_size, None, None, None)
+            .await
+            .map_err(|e| EventStoreError::Database(e.to_string()))??;
+        let Some(last_seq) = events.last().map(|event| event.seq) else {
+            break;
+        };
+        for event in events {
+            let topic = shared_types::EventTopic::from(event.event_type.as_str());
+            let coverage =
+                topics
+                    .entry(event.event_type.clone())
+                    .or_insert_with(|| TopicScopeCoverage {
+                        topic: event.event_type.clone(),
+                        required: topic
+                            .required_scope()
+                            .iter()
+                            .map(|field| field.as_str().to_string())
+                            .collect(),
+                        ..Default::default()
+                    });
+            coverage.events += 1;
+            report.events += 1;
+            for field in shared_types::ScopeField::ALL {
+                if field.is_present(&event.payload) {
+                    *coverage
+                        .present
+                        .entry(field.as_str().to_string())
+                        .or_default() += 1;
+                }
+            }
+            if !topic.missing_scope(&event.payload).is_empty() {
+                coverage.non_compliant += 1;
+                report.non_compliant += 1;
+            }
+        }
+        since_seq = last_seq;
+    }
+    report.topics = topics.into_values().collect();
+    Ok(report)
+}
+
+/// Convenience function to get events for an actor
+pub async fn get_events_for_actor(
+    store: &ActorRef<EventStoreMsg>,
+    actor_id: impl Into<String>,
+    since_seq: i64,
+) -> Result<Result<Vec<shared_types::Event>, EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
     ractor::call!(store, |reply| EventStoreMsg::GetEventsForActor {
         actor_id: actor_id.into(),
-        since_seq,
+        since_seq,
+        reply,
+    })
+}
+
+/// Convenience function to get events for an actor scoped by session/thread.
+pub async fn get_events_for_actor_with_scope(
+    store: &ActorRef<EventStoreMsg>,
+    actor_id: impl Into<String>,
+    session_id: impl Into<String>,
+    thread_id: impl Into<String>,
+    since_seq: i64,
+) -> Result<Result<Vec<shared_types::Event>, EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
+    ractor::call!(store, |reply| EventStoreMsg::GetEventsForActorWithScope {
+        actor_id: actor_id.into(),
+        session_id: session_id.into(),
+        thread_id: thread_id.into(),
+        since_seq,
+        reply,
+    })
+}
+
+/// Convenience function to get recent events with optional filters.
+pub async fn get_recent_events(
+    store: &ActorRef<EventStoreMsg>,
+    since_seq: i64,
+    limit: i64,
+    event_type_prefix: Option<String>,
+    actor_id: Option<String>,
+    user_id: Option<String>,
+) -> Result<Result<Vec<shared_types::Event>, EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
+    ractor::call!(store, |reply| EventStoreMsg::GetRecentEvents {
+        since_seq,
+        limit,
+        event_type_prefix,
+        actor_id,
+        user_id,
+        reply,
+    })
+}
+
+/// Convenience function to get the latest event sequence number.
+pub async fn get_latest_seq(
+    store: &ActorRef<EventStoreMsg>,
+) -> Result<Result<Option<i64>, EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
+    ractor::call!(store, |reply| EventStoreMsg::GetLatestSeq { reply })
+}
+
+/// Convenience function to get the last sequence number stored before a time.
+pub async fn get_last_seq_before(
+    store: &ActorRef<EventStoreMsg>,
+    before: chrono::DateTime<chrono::Utc>,
+) -> Result<Result<i64, EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
+    ractor::call!(store, |reply| EventStoreMsg::GetLastSeqBefore {
+        before,
+        reply
+    })
+}
+
+/// Convenience function to get an event by sequence number
+pub async fn get_event_by_seq(
+    store: &ActorRef<EventStoreMsg>,
+    seq: i64,
+) -> Result<Result<Option<shared_types::Event>, EventStoreError>, ractor::RactorErr<EventStoreMsg>>
+{
+    ractor::call!(store, |reply| EventStoreMsg::GetEventBySeq { seq, reply })
+}
+
+/// Append `events` in one transaction; see [`EventStoreMsg::AppendBatch`].
+pub async fn append_batch(
+    store: &ActorRef<EventStoreMsg>,
+    events: Vec<AppendEvent>,
+) -> Result<Result<Option<RangeInclusive<i64>>, EventStoreError>, ractor::RactorErr<EventStoreMsg>>
+{
+    ractor::call!(store, |reply| EventStoreMsg::AppendBatch { events, reply })
+}
+
+/// Appends committed since the store started.
+pub async fn write_stats(
+    store: &ActorRef<EventStoreMsg>,
+) -> Result<WriteStats, ractor::RactorErr<EventStoreMsg>> {
+    ractor::call!(store, |reply| EventStoreMsg::GetWriteStats { reply })
+}
+
+/// The subset of `event_ids` that are stored.
+pub async fn existing_event_ids(
+    store: &ActorRef<EventStoreMsg>,
+    event_ids: Vec<String>,
+) -> Result<Result<HashSet<String>, EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
+    ractor::call!(store, |reply| EventStoreMsg::ExistingEventIds {
+        event_ids,
+        reply
+    })
+}
+
+/// Find events matching a corr_id in their payload.
+/// Optionally filter by event_type prefix (e.g. "tool.result" or "harness.result").
+pub async fn get_events_by_corr_id(
+    store: &ActorRef<EventStoreMsg>,
+    corr_id: impl Into<String>,
+    event_type_prefix: Option<String>,
+) -> Result<Result<Vec<shared_types::Event>, EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
+    ractor::call!(store, |reply| EventStoreMsg::GetEventsByCorrId {
+        corr_id: corr_id.into(),
+        event_type_prefix,
+        reply,
+    })
+}
+
+/// Events for an actor, optionally limited to one lane; see
+/// [`EventStoreMsg::QueryEvents`].
+pub async fn query_events(
+    store: &ActorRef<EventStoreMsg>,
+    query: shared_types::QueryEvents,
+) -> Result<Result<Vec<shared_types::Event>, EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
+    ractor::call!(store, |reply| EventStoreMsg::QueryEvents { query, reply })
+}
+
+/// Committed events for a correlation_id (or trace_id), optionally limited to
+/// one lane.
+pub async fn query_by_correlation(
+    store: &ActorRef<EventStoreMsg>,
+    correlation_id: impl Into<String>,
+    lane: Option<EventLane>,
+) -> Result<Result<Vec<shared_types::Event>, EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
+    ractor::call!(store, |reply| EventStoreMsg::QueryByCorrelation {
+        correlation_id: correlation_id.into(),
+        lane,
+        reply,
+    })
+}
+
+/// Compact the store under its retention policy.
+pub async fn compact(
+    store: &ActorRef<EventStoreMsg>,
+) -> Result<Result<CompactionReport, EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
+    ractor::call!(store, |reply| EventStoreMsg::Compact { reply })
+}
+
+/// Highest `actor_seq` of `actor_id` that compaction has removed; see
+/// [`EventStoreMsg::GetCompactedThrough`].
+pub async fn get_compacted_through(
+    store: &ActorRef<EventStoreMsg>,
+    actor_id: impl Into<String>,
+) -> Result<Result<i64, EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
+    ractor::call!(store, |reply| EventStoreMsg::GetCompactedThrough {
+        actor_id: actor_id.into(),
+        reply,
+    })
+}
+
+/// Check that the store accepts writes.
+pub async fn health_check(
+    store: &ActorRef<EventStoreMsg>,
+) -> Result<EventStoreHealth, ractor::RactorErr<EventStoreMsg>> {
+    ractor::call!(store, |reply| EventStoreMsg::HealthCheck { reply })
+}
+
+/// Append the NDJSON events read from `reader`.
+pub async fn import_events(
+    store: &ActorRef<EventStoreMsg>,
+    reader: impl AsyncBufRead + Send + Unpin + 'static,
+) -> Result<Result<ImportReport, EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
+    ractor::call!(store, |reply| EventStoreMsg::Import {
+        reader: ImportReader(Box::new(reader)),
+        reply,
+    })
+}
+
+/// One page of events matching `query`.
+pub async fn query_filtered(
+    store: &ActorRef<EventStoreMsg>,
+    query: EventQuery,
+) -> Result<Result<EventPage, EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
+    ractor::call!(store, |reply| EventStoreMsg::QueryFiltered { query, reply })
+}
+
+/// Events stored between `from` and `to`, newest first; see
+/// [`EventStoreMsg::QueryByTime`].
+pub async fn query_by_time(
+    store: &ActorRef<EventStoreMsg>,
+    from: chrono::DateTime<chrono::Utc>,
+    to: chrono::DateTime<chrono::Utc>,
+    actor_id: Option<String>,
+    limit: i64,
+) -> Result<Result<Vec<shared_types::Event>, EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
+    ractor::call!(store, |reply| EventStoreMsg::QueryByTime {
+        from,
+        to,
+        actor_id,
+        limit,
+        reply,
+    })
+}
+
+/// The latest `limit` events, newest first; see [`EventStoreMsg::QueryTail`].
+pub async fn query_tail(
+    store: &ActorRef<EventStoreMsg>,
+    actor_id: Option<String>,
+    limit: i64,
+) -> Result<Result<Vec<shared_types::Event>, EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
+    ractor::call!(store, |reply| EventStoreMsg::QueryTail {
+        actor_id,
+        limit,
+        reply,
+    })
+}
+
+/// Get the latest harness.checkpoint event for a run_id.
+/// Returns None if the run has no checkpoint yet (not started or already cleaned up).
+pub async fn get_latest_harness_checkpoint(
+    store: &ActorRef<EventStoreMsg>,
+    run_id: impl Into<String>,
+) -> Result<Result<Option<shared_types::Event>, EventStoreError>, ractor::RactorErr<EventStoreMsg>>
+{
+    ractor::call!(store, |reply| EventStoreMsg::GetLatestHarnessCheckpoint {
+        run_id: run_id.into(),
+        reply,
+    })
+}
+
+/// Get the stored cursor for a named projection.
+pub async fn get_projection_cursor(
+    store: &ActorRef<EventStoreMsg>,
+    name: impl Into<String>,
+) -> Result<Result<Option<ProjectionCursor>, EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
+    ractor::call!(store, |reply| EventStoreMsg::GetProjectionCursor {
+        name: name.into(),
+        reply,
+    })
+}
+
+/// Upsert the cursor for a named projection.
+pub async fn set_projection_cursor(
+    store: &ActorRef<EventStoreMsg>,
+    name: impl Into<String>,
+    cursor_seq: i64,
+    status: impl Into<String>,
+) -> Result<Result<(), EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
+    ractor::call!(store, |reply| EventStoreMsg::SetProjectionCursor {
+        name: name.into(),
+        cursor_seq,
+        status: status.into(),
+        reply,
+    })
+}
+
+/// Replace the state snapshot stored for an actor.
+pub async fn save_snapshot(
+    store: &ActorRef<EventStoreMsg>,
+    snapshot: ActorSnapshot,
+) -> Result<Result<(), EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
+    ractor::call!(store, |reply| EventStoreMsg::SaveSnapshot {
+        snapshot,
+        reply
+    })
+}
+
+/// Get the state snapshot stored for an actor, if any.
+pub async fn get_latest_snapshot(
+    store: &ActorRef<EventStoreMsg>,
+    actor_id: impl Into<String>,
+) -> Result<Result<Option<ActorSnapshot>, EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
+    ractor::call!(store, |reply| EventStoreMsg::GetLatestSnapshot {
+        actor_id: actor_id.into(),
+        reply,
+    })
+}
+
+/// Insert or replace the search index entry derived from an event.
+pub async fn index_search_entry(
+    store: &ActorRef<EventStoreMsg>,
+    entry: SearchIndexEntry,
+) -> Result<Result<(), EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
+    ractor::call!(store, |reply| EventStoreMsg::IndexSearchEntry {
+        entry,
+        reply
+    })
+}
+
+/// Remove the search index entry derived from the event at `seq`.
+pub async fn remove_search_entry(
+    store: &ActorRef<EventStoreMsg>,
+    seq: i64,
+) -> Result<Result<bool, EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
+    ractor::call!(store, |reply| EventStoreMsg::RemoveSearchEntry {
+        seq,
+        reply
+    })
+}
+
+/// Drop every search index entry.
+pub async fn clear_search_index(
+    store: &ActorRef<EventStoreMsg>,
+) -> Result<Result<(), EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
+    ractor::call!(store, |reply| EventStoreMsg::ClearSearchIndex { reply })
+}
+
+/// Run an FTS5 MATCH expression against the search index.
+pub async fn search_index(
+    store: &ActorRef<EventStoreMsg>,
+    match_expr: impl Into<String>,
+    kinds: Vec<String>,
+    limit: i64,
+) -> Result<Result<Vec<SearchIndexMatch>, EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
+    ractor::call!(store, |reply| EventStoreMsg::SearchIndex {
+        match_expr: match_expr.into(),
+        kinds,
+        limit,
+        reply,
+    })
+}
+
+/// Run an FTS5 MATCH expression against the event search index.
+pub async fn search_events(
+    store: &ActorRef<EventStoreMsg>,
+    query: impl Into<String>,
+    limit: i64,
+) -> Result<Result<Vec<EventSearchMatch>, EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
+    ractor::call!(store, |reply| EventStoreMsg::Search {
+        query: query.into(),
+        limit,
         reply,
     })
 }
 
-/// Convenience function to get events for an actor scoped by session/thread.
-pub async fn get_events_for_actor_with_scope(
-    store: &ActorRef<EventStoreMsg>,
-    actor_id: impl Into<String>,
-    session_id: impl Into<String>,
-    thread_id: impl Into<String>,
-    since_seq: i64,
-) -> Result<Result<Vec<shared_types::Event>, EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
-    ractor::call!(store, |reply| EventStoreMsg::GetEventsForActorWithScope {
-        actor_id: actor_id.into(),
-        session_id: session_id.into(),
-        thread_id: thread_id.into(),
-        since_seq,
-        reply,
-    })
-}
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ractor::Actor;
+
+    #[tokio::test]
+    async fn test_append_and_retrieve_event() {
+        let (store_ref, _handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .unwrap();
+
+        // Append an event
+        let event = append_event(
+            &store_ref,
+            AppendEvent {
+                event_type: "test.event".to_string(),
+                payload: serde_json::json!({"foo": "bar"}),
+                actor_id: "actor-1".to_string(),
+                user_id: "user-1".to_string(),
+                idempotency_key: None,
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        assert!(event.seq > 0);
+        assert_eq!(event.event_type, "test.event");
+        assert_eq!(event.actor_id.0, "actor-1");
+
+        // Retrieve events for actor
+        let events = get_events_for_actor(&store_ref, "actor-1", 0)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].seq, event.seq);
+
+        // Cleanup
+        store_ref.stop(None);
+    }
+
+    #[tokio::test]
+    async fn test_get_events_since_seq() {
+        let (store_ref, _handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .unwrap();
+
+        // Append multiple events
+        for i in 0..5 {
+            append_event(
+                &store_ref,
+                AppendEvent {
+                    event_type: "test.event".to_string(),
+                    payload: serde_json::json!({"index": i}),
+                    actor_id: "actor-1".to_string(),
+                    user_id: "user-1".to_string(),
+                    idempotency_key: None,
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        }
+
+        // Get events after seq 2
+        let events = get_events_for_actor(&store_ref, "actor-1", 2)
+            .await
+            .unwrap()
+            .unwrap();
+
+        // Should get events with seq > 2
+        assert_eq!(events.len(), 3);
+        for event in &events {
+            assert!(event.seq > 2);
+        }
+
+        // Cleanup
+        store_ref.stop(None);
+    }
+
+    #[tokio::test]
+    async fn test_events_isolated_by_actor() {
+        let (store_ref, _handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .unwrap();
+
+        // Events for different actors
+        append_event(
+            &store_ref,
+            AppendEvent {
+                event_type: "interaction.msg".to_string(),
+                payload: serde_json::json!({"text": "hello"}),
+                actor_id: "session-1".to_string(),
+                user_id: "user-1".to_string(),
+                idempotency_key: None,
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        append_event(
+            &store_ref,
+            AppendEvent {
+                event_type: "file.write".to_string(),
+                payload: serde_json::json!({"path": "test.txt"}),
+                actor_id: "writer-1".to_string(),
+                user_id: "user-1".to_string(),
+                idempotency_key: None,
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        // Get events for a single actor only
+        let session_events = get_events_for_actor(&store_ref, "session-1", 0)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(session_events.len(), 1);
+        assert_eq!(session_events[0].event_type, "interaction.msg");
+
+        // Cleanup
+        store_ref.stop(None);
+    }
+
+    #[tokio::test]
+    async fn test_actor_seq_counts_per_actor() {
+        let (store_ref, _handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .unwrap();
+
+        let mut appended = Vec::new();
+        for actor_id in [
+            "session-1",
+            "writer-1",
+            "session-1",
+            "session-1",
+            "writer-1",
+        ] {
+            let event = append_event(
+                &store_ref,
+                AppendEvent {
+                    event_type: "test.event".to_string(),
+                    payload: serde_json::json!({}),
+                    actor_id: actor_id.to_string(),
+                    user_id: "user-1".to_string(),
+                    idempotency_key: None,
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+            appended.push((event.seq, event.actor_seq));
+        }
+        let actor_seqs: Vec<i64> = appended.iter().map(|(_, actor_seq)| *actor_seq).collect();
+        assert_eq!(actor_seqs, [1, 1, 2, 3, 2]);
+        assert!(appended.windows(2).all(|pair| pair[0].0 < pair[1].0));
+
+        // Reads return the number assigned at append time.
+        let session_events = get_events_for_actor(&store_ref, "session-1", 0)
+            .await
+            .unwrap()
+            .unwrap();
+        let actor_seqs: Vec<i64> = session_events.iter().map(|e| e.actor_seq).collect();
+        assert_eq!(actor_seqs, [1, 2, 3]);
+
+        store_ref.stop(None);
+    }
+
+    #[tokio::test]
+    async fn test_get_events_for_actor_with_scope() {
+        let (store_ref, _handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .unwrap();
+
+        append_event(
+            &store_ref,
+            AppendEvent {
+                event_type: "interaction.user_msg".to_string(),
+                payload: serde_json::json!({
+                    "text": "msg-in-scope",
+                    "scope": {
+                        "session_id": "session-1",
+                        "thread_id": "thread-1"
+                    }
+                }),
+                actor_id: "session-1".to_string(),
+                user_id: "user-1".to_string(),
+                idempotency_key: None,
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        append_event(
+            &store_ref,
+            AppendEvent {
+                event_type: "interaction.user_msg".to_string(),
+                payload: serde_json::json!({
+                    "text": "msg-other-thread",
+                    "scope": {
+                        "session_id": "session-1",
+                        "thread_id": "thread-2"
+                    }
+                }),
+                actor_id: "session-1".to_string(),
+                user_id: "user-1".to_string(),
+                idempotency_key: None,
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        let scoped =
+            get_events_for_actor_with_scope(&store_ref, "session-1", "session-1", "thread-1", 0)
+                .await
+                .unwrap()
+                .unwrap();
+
+        assert_eq!(scoped.len(), 1);
+        assert_eq!(
+            scoped[0].payload.get("text").and_then(|v| v.as_str()),
+            Some("msg-in-scope")
+        );
+
+        store_ref.stop(None);
+    }
+
+    #[tokio::test]
+    async fn test_append_never_moves_timestamp_backwards() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_path = temp_dir.path().join("events.db");
+        let (store_ref, _handle) = Actor::spawn(
+            None,
+            EventStoreActor,
+            EventStoreArguments::File(db_path.to_string_lossy().to_string()),
+        )
+        .await
+        .unwrap();
+
+        // Simulate an event written while the clock was ahead.
+        let pool = SqlitePool::connect(&format!("sqlite:{}", db_path.display()))
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO events (event_id, timestamp, event_type, payload, actor_id, actor_seq)
+             VALUES ('evt-future', '2999-01-01 00:00:00', 'test.event', '{}', 'actor-1', 1)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO actor_seq_counters (actor_id, last_actor_seq) VALUES ('actor-1', 1)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let appended = append_event(
+            &store_ref,
+            AppendEvent {
+                event_type: "test.event".to_string(),
+                payload: serde_json::json!({}),
+                actor_id: "actor-1".to_string(),
+                user_id: "user-1".to_string(),
+                idempotency_key: None,
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(appended.stored_at.to_rfc3339(), "2999-01-01T00:00:00+00:00");
+        assert_eq!(appended.timestamp, appended.stored_at);
+
+        let events = get_recent_events(&store_ref, 0, 10, None, None, None)
+            .await
+            .unwrap()
+            .unwrap();
+        let mut canonical = events.clone();
+        canonical.sort_by(shared_types::Event::canonical_cmp);
+        let seqs: Vec<i64> = events.iter().map(|e| e.seq).collect();
+        let canonical_seqs: Vec<i64> = canonical.iter().map(|e| e.seq).collect();
+        assert_eq!(seqs, canonical_seqs);
+
+        pool.close().await;
+        store_ref.stop(None);
+    }
+
+    #[tokio::test]
+    async fn test_producer_clock_jump_is_kept_as_produced_at() {
+        let (store_ref, _handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .unwrap();
+
+        let mut appended = Vec::new();
+        for produced_at in ["2026-03-01T12:00:00Z", "2026-03-01T11:00:00Z", "not a time"] {
+            let event = append_event(
+                &store_ref,
+                AppendEvent {
+                    event_type: "test.event".to_string(),
+                    payload: serde_json::json!({ "timestamp": produced_at }),
+                    actor_id: "actor-1".to_string(),
+                    user_id: "user-1".to_string(),
+                    idempotency_key: None,
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+            appended.push(event);
+        }
+
+        assert_eq!(
+            appended[0].produced_at.map(|dt| dt.to_rfc3339()).as_deref(),
+            Some("2026-03-01T12:00:00+00:00")
+        );
+        assert_eq!(
+            appended[1].produced_at.map(|dt| dt.to_rfc3339()).as_deref(),
+            Some("2026-03-01T11:00:00+00:00")
+        );
+        assert_eq!(appended[2].produced_at, None);
+        assert!(appended
+            .windows(2)
+            .all(|pair| pair[0].seq < pair[1].seq && pair[0].stored_at <= pair[1].stored_at));
+
+        store_ref.stop(None);
+    }
+
+    #[tokio::test]
+    async fn test_get_recent_events_with_filters() {
+        let (store_ref, _handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .unwrap();
+
+        append_event(
+            &store_ref,
+            AppendEvent {
+                event_type: "worker.task.started".to_string(),
+                payload: serde_json::json!({"task_id": "t1"}),
+                actor_id: "supervisor-1".to_string(),
+                user_id: "user-1".to_string(),
+                idempotency_key: None,
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        append_event(
+            &store_ref,
+            AppendEvent {
+                event_type: "interaction.user_msg".to_string(),
+                payload: serde_json::json!({"text": "hello"}),
+                actor_id: "session-1".to_string(),
+                user_id: "user-1".to_string(),
+                idempotency_key: None,
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        append_event(
+            &store_ref,
+            AppendEvent {
+                event_type: "worker.task.completed".to_string(),
+                payload: serde_json::json!({"task_id": "t1"}),
+                actor_id: "supervisor-1".to_string(),
+                user_id: "user-2".to_string(),
+                idempotency_key: None,
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        let filtered = get_recent_events(
+            &store_ref,
+            0,
+            10,
+            Some("worker.task".to_string()),
+            Some("supervisor-1".to_string()),
+            Some("user-1".to_string()),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].event_type, "worker.task.started");
+        assert_eq!(filtered[0].actor_id.0, "supervisor-1");
+        assert_eq!(filtered[0].user_id, "user-1");
+
+        store_ref.stop(None);
+    }
+
+    #[tokio::test]
+    async fn test_query_filtered_pages_through_combined_filters() {
+        let (store_ref, _handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .unwrap();
+
+        let seeded = [
+            ("writer.run.started", "writer-1"),
+            ("chat.message", "writer-1"),
+            ("writer.run.patch", "writer-1"),
+            ("writer.run.patch", "writer-2"),
+            ("writer.runtime.tick", "writer-1"),
+            ("conductor.run.started", "writer-1"),
+            ("writer.run.status", "writer-1"),
+        ];
+        for (event_type, actor_id) in seeded {
+            append_event(
+                &store_ref,
+                AppendEvent {
+                    event_type: event_type.to_string(),
+                    payload: serde_json::json!({
+                        "run_id": "run-1",
+                        "scope": { "session_id": "session-1", "thread_id": "thread-1" },
+                    }),
+                    actor_id: actor_id.to_string(),
+                    user_id: "user-1".to_string(),
+                    idempotency_key: None,
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        }
+
+        let query = EventQuery {
+            actor_id: Some("writer-1".to_string()),
+            event_type_prefixes: vec!["writer.run.*".to_string(), "conductor.".to_string()],
+            limit: 2,
+            ..EventQuery::default()
+        };
+        let mut pages = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = query_filtered(
+                &store_ref,
+                EventQuery {
+                    cursor,
+                    ..query.clone()
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+            pages.push(
+                page.events
+                    .iter()
+                    .map(|event| event.event_type.clone())
+                    .collect::<Vec<_>>(),
+            );
+            cursor = page.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        assert_eq!(
+            pages,
+            vec![
+                vec!["writer.run.started", "writer.run.patch"],
+                vec!["conductor.run.started", "writer.run.status"],
+            ]
+        );
+
+        let newest = query_filtered(
+            &store_ref,
+            EventQuery {
+                newest_first: true,
+                limit: 3,
+                ..query.clone()
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert!(newest
+            .events
+            .windows(2)
+            .all(|pair| pair[0].seq > pair[1].seq));
+        assert_eq!(newest.events[0].event_type, "writer.run.status");
+        assert_eq!(newest.next_cursor, Some(newest.events[2].seq));
+
+        // A cursor past the last match is valid and yields an empty last page.
+        let latest_seq = get_latest_seq(&store_ref).await.unwrap().unwrap().unwrap();
+        let empty = query_filtered(
+            &store_ref,
+            EventQuery {
+                cursor: Some(latest_seq),
+                ..query.clone()
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert!(empty.events.is_empty());
+        assert_eq!(empty.next_cursor, None);
+
+        let clamped = query_filtered(
+            &store_ref,
+            EventQuery {
+                limit: 0,
+                ..EventQuery::default()
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(clamped.events.len(), 1);
+        assert!(clamped.next_cursor.is_some());
+
+        let future = query_filtered(
+            &store_ref,
+            EventQuery {
+                since: Some(chrono::Utc::now() + chrono::Duration::hours(1)),
+                limit: 10,
+                ..EventQuery::default()
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert!(future.events.is_empty());
+
+        store_ref.stop(None);
+    }
+
+    #[tokio::test]
+    async fn test_query_by_time_and_tail_return_newest_first() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_path = temp_dir.path().join("events.db");
+        let (store_ref, _handle) = Actor::spawn(
+            None,
+            EventStoreActor,
+            EventStoreArguments::File(db_path.to_string_lossy().to_string()),
+        )
+        .await
+        .unwrap();
+
+        let pool = SqlitePool::connect(&format!("sqlite:{}", db_path.display()))
+            .await
+            .unwrap();
+        sqlx::query(
+            r#"INSERT INTO events (event_id, timestamp, event_type, payload, actor_id, actor_seq) VALUES
+               ('evt-1', '2026-01-01 00:00:00', 'test.event', '{}', 'actor-a', 1),
+               ('evt-2', '2026-01-01 00:05:00', 'test.event', '{}', 'actor-b', 1),
+               ('evt-3', '2026-01-01 00:08:00', 'test.event', '{}', 'actor-a', 2),
+               ('evt-4', '2026-01-01 00:10:00', 'test.event', '{}', 'actor-b', 2),
+               ('evt-5', '2026-01-01 00:15:00', 'test.event', '{}', 'actor-a', 3)"#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool.close().await;
+
+        let at = |minute: u32| {
+            chrono::NaiveDate::from_ymd_opt(2026, 1, 1)
+                .unwrap()
+                .and_hms_opt(0, minute, 0)
+                .unwrap()
+                .and_utc()
+        };
+        let ids = |events: Vec<shared_types::Event>| -> Vec<String> {
+            events.into_iter().map(|event| event.event_id).collect()
+        };
+
+        // Both bounds are inclusive.
+        let range = query_by_time(&store_ref, at(5), at(10), None, 100)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(ids(range), ["evt-4", "evt-3", "evt-2"]);
+
+        let range = query_by_time(&store_ref, at(0), at(20), Some("actor-a".to_string()), 2)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(ids(range), ["evt-5", "evt-3"]);
+
+        let tail = query_tail(&store_ref, None, 2).await.unwrap().unwrap();
+        assert_eq!(ids(tail), ["evt-5", "evt-4"]);
+
+        let tail = query_tail(&store_ref, Some("actor-b".to_string()), 100)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(ids(tail), ["evt-4", "evt-2"]);
+
+        let clamped = query_tail(&store_ref, None, 0).await.unwrap().unwrap();
+        assert_eq!(ids(clamped), ["evt-5"]);
+
+        store_ref.stop(None);
+    }
+
+    #[tokio::test]
+    async fn test_query_by_correlation_filters_by_lane() {
+        let (store_ref, _handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .unwrap();
+
+        let seeded = [
+            ("conductor.run.started", "corr-1", Some("control")),
+            ("conductor.worker.progress", "corr-1", Some("telemetry")),
+            ("conductor.worker.call", "corr-2", Some("control")),
+            ("conductor.worker.output", "corr-1", None),
+            ("conductor.run.completed", "corr-1", Some("control")),
+        ];
+        for (event_type, correlation_id, lane) in seeded {
+            let mut payload =
+                serde_json::json!({ "correlation_id": correlation_id, "run_id": correlation_id });
+            if let Some(lane) = lane {
+                payload["_meta"] = serde_json::json!({ "lane": lane });
+            }
+            append_event(
+                &store_ref,
+                AppendEvent {
+                    event_type: event_type.to_string(),
+                    payload,
+                    actor_id: "conductor-1".to_string(),
+                    user_id: "user-1".to_string(),
+                    idempotency_key: None,
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        }
+
+        let topics = |events: Vec<shared_types::Event>| {
+            events
+                .into_iter()
+                .map(|event| event.event_type)
+                .collect::<Vec<_>>()
+        };
+
+        let control = query_by_correlation(&store_ref, "corr-1", Some(EventLane::Control))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(control.windows(2).all(|pair| pair[0].seq < pair[1].seq));
+        assert_eq!(
+            topics(control),
+            vec!["conductor.run.started", "conductor.run.completed"]
+        );
+
+        let telemetry = query_by_correlation(&store_ref, "corr-1", Some(EventLane::Telemetry))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            topics(telemetry),
+            vec!["conductor.worker.progress", "conductor.worker.output"]
+        );
+
+        let all = query_by_correlation(&store_ref, "corr-1", None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(all.len(), 4);
+
+        store_ref.stop(None);
+    }
+
+    #[tokio::test]
+    async fn test_query_events_filters_by_lane() {
+        let (store_ref, _handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .unwrap();
+
+        let seeded = [
+            ("conductor.run.started", Some("control")),
+            ("conductor.worker.progress", Some("telemetry")),
+            ("conductor.worker.output", None),
+        ];
+        for (event_type, lane) in seeded {
+            let mut payload = serde_json::json!({ "run_id": "run-1" });
+            if let Some(lane) = lane {
+                payload["_meta"] = serde_json::json!({ "lane": lane });
+            }
+            append_event(
+                &store_ref,
+                AppendEvent {
+                    event_type: event_type.to_string(),
+                    payload,
+                    actor_id: "conductor-1".to_string(),
+                    user_id: "user-1".to_string(),
+                    idempotency_key: None,
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        }
+
+        let query = |lane| shared_types::QueryEvents {
+            actor_id: shared_types::ActorId("conductor-1".to_string()),
+            since_seq: 0,
+            lane,
+        };
+        let topics = |events: Vec<shared_types::Event>| {
+            events
+                .into_iter()
+                .map(|event| event.event_type)
+                .collect::<Vec<_>>()
+        };
+
+        let all = query_events(&store_ref, query(None))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(all.len(), 3);
+
+        let control = query_events(&store_ref, query(Some(EventLane::Control)))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(topics(control), vec!["conductor.run.started"]);
+
+        let telemetry = query_events(&store_ref, query(Some(EventLane::Telemetry)))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            topics(telemetry),
+            vec!["conductor.worker.progress", "conductor.worker.output"]
+        );
+
+        store_ref.stop(None);
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_key_returns_the_stored_event() {
+        let (store_ref, _handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .unwrap();
+
+        let message = |user_id: &str, idempotency_key: &str| AppendEvent {
+            event_type: "chat.user_msg".to_string(),
+            payload: serde_json::json!({ "text": "hello" }),
+            actor_id: "chat-1".to_string(),
+            user_id: user_id.to_string(),
+            idempotency_key: Some(idempotency_key.to_string()),
+        };
+
+        let first = append_event(&store_ref, message("user-1", "k1"))
+            .await
+            .unwrap()
+            .unwrap();
+        let retry = append_event(&store_ref, message("user-1", "k1"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(retry.seq, first.seq);
+        assert_eq!(retry.event_id, first.event_id);
+        assert_eq!(retry.payload, first.payload);
+
+        let other = append_event(&store_ref, message("user-1", "k2"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_ne!(other.seq, first.seq);
+
+        // Keys are scoped to their user.
+        let other_user = append_event(&store_ref, message("user-2", "k1"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_ne!(other_user.seq, first.seq);
+        assert_eq!(other_user.user_id, "user-2");
+
+        let events = ractor::call!(store_ref, |reply| EventStoreMsg::GetEventsForActor {
+            actor_id: "chat-1".to_string(),
+            since_seq: 0,
+            reply,
+        })
+        .unwrap()
+        .unwrap();
+        assert_eq!(events.len(), 3);
+
+        store_ref.stop(None);
+    }
+
+    #[tokio::test]
+    async fn test_query_by_correlation_matches_trace_id_and_uses_index() {
+        let (store_ref, _handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .unwrap();
+
+        let seeded = [
+            serde_json::json!({ "correlation_id": "corr-1", "trace_id": "corr-1" }),
+            serde_json::json!({ "correlation_id": "corr-2" }),
+            serde_json::json!({ "trace_id": "corr-1" }),
+            serde_json::json!({ "note": "corr-1" }),
+            serde_json::json!({ "correlation_id": "corr-1" }),
+            serde_json::json!({ "nested": { "correlation_id": "corr-1" } }),
+        ];
+        let mut expected = Vec::new();
+        for (n, payload) in seeded.into_iter().enumerate() {
+            let correlated =
+                payload["correlation_id"] == "corr-1" || payload["trace_id"] == "corr-1";
+            let event = append_event(
+                &store_ref,
+                AppendEvent {
+                    event_type: format!("supervisor.step.{n}"),
+                    payload,
+                    actor_id: "supervisor-1".to_string(),
+                    user_id: "user-1".to_string(),
+                    idempotency_key: None,
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+            if correlated {
+                expected.push(event.event_id);
+            }
+        }
+        assert_eq!(expected.len(), 3);
+
+        let chain = query_by_correlation(&store_ref, "corr-1", None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            chain
+                .into_iter()
+                .map(|event| event.event_id)
+                .collect::<Vec<_>>(),
+            expected
+        );
+        store_ref.stop(None);
+
+        let pool = EventStoreActor::open_pool("sqlite::memory:").await.unwrap();
+        let plan: Vec<String> = sqlx::query_as::<_, (i64, i64, i64, String)>(
+            "EXPLAIN QUERY PLAN SELECT seq FROM events \
+             WHERE json_extract(payload, '$.correlation_id') = ?1 \
+                OR json_extract(payload, '$.trace_id') = ?1",
+        )
+        .bind("corr-1")
+        .fetch_all(&pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|(_, _, _, detail)| detail)
+        .collect();
+        assert!(
+            plan.iter()
+                .any(|step| step.contains("idx_events_correlation_id"))
+                && plan.iter().any(|step| step.contains("idx_events_trace_id")),
+            "query plan does not use the correlation indexes: {plan:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_compact_removes_expired_and_excess_telemetry_only() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_path = temp_dir.path().join("events.db");
+        let policy = CompactionPolicy {
+            telemetry_max_age: Some(std::time::Duration::from_secs(24 * 60 * 60)),
+            telemetry_max_rows: Some(2),
+            control_keep_forever: true,
+        };
+        let (store_ref, _handle) = Actor::spawn(
+            None,
+            EventStoreActor,
+            EventStoreArguments::File(db_path.to_string_lossy().to_string())
+                .with_compaction(policy),
+        )
+        .await
+        .unwrap();
+
+        // Rows stored long before the retention window.
+        let pool = SqlitePool::connect(&format!("sqlite:{}", db_path.display()))
+            .await
+            .unwrap();
+        sqlx::query(
+            r#"INSERT INTO events (event_id, timestamp, event_type, payload, actor_id, actor_seq) VALUES
+               ('evt-old-control', '2020-01-01 00:00:00', 'old.control', '{"_meta":{"lane":"control"}}', 'actor-1', 1),
+               ('evt-old-telemetry', '2020-01-01 00:00:00', 'old.telemetry', '{"_meta":{"lane":"telemetry"}}', 'actor-1', 2),
+               ('evt-old-undeclared', '2020-01-01 00:00:00', 'old.undeclared', '{}', 'actor-1', 3)"#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO actor_seq_counters (actor_id, last_actor_seq) VALUES ('actor-1', 3)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool.close().await;
+
+        for index in 0..3 {
+            append_event(
+                &store_ref,
+                AppendEvent {
+                    event_type: format!("fresh.telemetry.{index}"),
+                    payload: serde_json::json!({ "_meta": { "lane": "telemetry" } }),
+                    actor_id: "actor-1".to_string(),
+                    user_id: "user-1".to_string(),
+                    idempotency_key: None,
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        }
+
+        let report = compact(&store_ref).await.unwrap().unwrap();
+        assert_eq!(report.expired, 1);
+        assert_eq!(report.over_limit, 1);
+        assert_eq!(report.removed(), 2);
+
+        let remaining: Vec<String> = get_recent_events(&store_ref, 0, 10, None, None, None)
+            .await
+            .unwrap()
+            .unwrap()
+            .into_iter()
+            .map(|event| event.event_type)
+            .collect();
+        assert_eq!(
+            remaining,
+            vec![
+                "old.control",
+                "old.undeclared",
+                "fresh.telemetry.1",
+                "fresh.telemetry.2",
+            ]
+        );
+
+        assert_eq!(
+            get_compacted_through(&store_ref, "actor-1")
+                .await
+                .unwrap()
+                .unwrap(),
+            4
+        );
+
+        // Nothing left to remove; a second pass only vacuums.
+        let again = compact(&store_ref).await.unwrap().unwrap();
+        assert_eq!(again, CompactionReport::default());
+
+        store_ref.stop(None);
+    }
+
+    #[tokio::test]
+    async fn test_actor_seq_is_not_reused_after_compaction_removes_the_newest_events() {
+        let policy = CompactionPolicy {
+            telemetry_max_age: None,
+            telemetry_max_rows: Some(0),
+            control_keep_forever: true,
+        };
+        let (store_ref, _handle) = Actor::spawn(
+            None,
+            EventStoreActor,
+            EventStoreArguments::InMemory.with_compaction(policy),
+        )
+        .await
+        .unwrap();
+        let append = |event_type: &str| {
+            let store_ref = store_ref.clone();
+            let event = AppendEvent {
+                event_type: event_type.to_string(),
+                payload: serde_json::json!({ "_meta": { "lane": "telemetry" } }),
+                actor_id: "worker-1".to_string(),
+                user_id: "user-1".to_string(),
+                idempotency_key: None,
+            };
+            async move { append_event(&store_ref, event).await.unwrap().unwrap() }
+        };
+        let compacted_through = |actor_id: &'static str| {
+            let store_ref = store_ref.clone();
+            async move {
+                get_compacted_through(&store_ref, actor_id)
+                    .await
+                    .unwrap()
+                    .unwrap()
+            }
+        };
+
+        for index in 0..3 {
+            append(&format!("worker.progress.{index}")).await;
+        }
+        assert_eq!(compacted_through("worker-1").await, 0);
+        let report = compact(&store_ref).await.unwrap().unwrap();
+        assert_eq!(report.over_limit, 3);
+        assert_eq!(compacted_through("worker-1").await, 3);
+
+        let next = append("worker.progress.3").await;
+        assert_eq!(next.actor_seq, 4);
+        assert_eq!(compacted_through("unknown").await, 0);
+
+        store_ref.stop(None);
+    }
+
+    #[tokio::test]
+    async fn test_user_input_with_unknown_surface_is_rejected() {
+        let (store_ref, _handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .unwrap();
+        let user_input = |surface: &str| AppendEvent {
+            event_type: shared_types::EVENT_TOPIC_USER_INPUT.to_string(),
+            payload: serde_json::json!({
+                "record": {
+                    "input_id": "in_1",
+                    "content": "hello",
+                    "surface": surface,
+                    "desktop_id": "desktop_1",
+                    "session_id": "desktop_1",
+                    "thread_id": "",
+                    "run_id": null,
+                    "document_path": null,
+                    "base_version_id": null,
+                    "created_at": "2026-03-01T00:00:00Z",
+                }
+            }),
+            actor_id: "api.conductor".to_string(),
+            user_id: "user-1".to_string(),
+            idempotency_key: None,
+        };
+
+        for surface in ["conductor", "writer", "prompt_bar"] {
+            let event = append_event(&store_ref, user_input(surface))
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(event.payload["record"]["surface"], surface);
+        }
+
+        let rejected = append_event(&store_ref, user_input("telepathy"))
+            .await
+            .unwrap();
+        assert!(
+            matches!(rejected, Err(EventStoreError::InvalidPayload(_))),
+            "{rejected:?}"
+        );
+        let events = get_events_for_actor(&store_ref, "api.conductor", 0)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(events.len(), 3);
+
+        store_ref.stop(None);
+    }
+
+    #[cfg(debug_assertions)]
+    #[tokio::test]
+    async fn test_append_missing_required_scope_is_rejected() {
+        let (store_ref, _handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .unwrap();
+        let patch = |payload: serde_json::Value| AppendEvent {
+            event_type: shared_types::EVENT_TOPIC_WRITER_RUN_PATCH.to_string(),
+            payload,
+            actor_id: "writer:run-1".to_string(),
+            user_id: "system".to_string(),
+            idempotency_key: None,
+        };
+
+        let before = scope_violations();
+        let rejected = append_event(&store_ref, patch(serde_json::json!({ "run_id": "run-1" })))
+            .await
+            .unwrap();
+        match rejected {
+            Err(EventStoreError::InvalidPayload(message)) => {
+                assert!(message.contains("session_id, thread_id"), "{message}");
+            }
+            other => panic!("expected a scope rejection, got {other:?}"),
+        }
+        assert!(scope_violations() > before);
+
+        let scoped = shared_types::with_scope(
+            serde_json::json!({ "run_id": "run-1" }),
+            Some("session-1".to_string()),
+            Some("thread-1".to_string()),
+        );
+        let event = append_event(&store_ref, patch(scoped))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.payload["scope"]["thread_id"], "thread-1");
+
+        store_ref.stop(None);
+    }
+
+    #[tokio::test]
+    async fn test_scope_coverage_counts_fields_per_topic() {
+        let (store_ref, _handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .unwrap();
+        let payloads = [
+            (
+                shared_types::EVENT_TOPIC_CONDUCTOR_TASK_PROGRESS,
+                serde_json::json!({ "run_id": "run-1" }),
+            ),
+            (
+                shared_types::EVENT_TOPIC_CONDUCTOR_TASK_PROGRESS,
+                shared_types::with_scope(
+                    serde_json::json!({ "run_id": "run-1" }),
+                    Some("session-1".to_string()),
+                    None,
+                ),
+            ),
+            ("test.event", serde_json::json!({})),
+        ];
+        for (event_type, payload) in payloads {
+            append_event(
+                &store_ref,
+                AppendEvent {
+                    event_type: event_type.to_string(),
+                    payload,
+                    actor_id: "actor-1".to_string(),
+                    user_id: "user-1".to_string(),
+                    idempotency_key: None,
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        }
+
+        let report = scope_coverage(&store_ref, 2).await.unwrap();
+        assert_eq!(report.events, 3);
+        assert_eq!(report.non_compliant, 0);
+        assert_eq!(report.topics.len(), 2);
+        let progress = &report.topics[0];
+        assert_eq!(
+            progress.topic,
+            shared_types::EVENT_TOPIC_CONDUCTOR_TASK_PROGRESS
+        );
+        assert_eq!(progress.events, 2);
+        assert_eq!(progress.required, vec!["run_id".to_string()]);
+        assert_eq!(progress.present.get("run_id"), Some(&2));
+        assert_eq!(progress.present.get("session_id"), Some(&1));
+        assert_eq!(progress.present.get("thread_id"), None);
+        assert_eq!(report.topics[1].topic, "test.event");
+        assert!(report.topics[1].required.is_empty());
+
+        store_ref.stop(None);
+    }
+
+    #[tokio::test]
+    async fn test_append_batch_is_ordered_and_all_or_nothing() {
+        let (store_ref, _handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .unwrap();
+        let event = |event_type: &str, payload: serde_json::Value| AppendEvent {
+            event_type: event_type.to_string(),
+            payload,
+            actor_id: "actor-1".to_string(),
+            user_id: "user-1".to_string(),
+            idempotency_key: None,
+        };
+
+        let seqs = append_batch(
+            &store_ref,
+            (0..3)
+                .map(|n| event(&format!("test.batch.{n}"), serde_json::json!({ "n": n })))
+                .collect(),
+        )
+        .await
+        .unwrap()
+        .unwrap()
+        .expect("seq range");
+        let stored = get_events_for_actor(&store_ref, "actor-1", 0)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            stored.iter().map(|e| e.seq).collect::<Vec<_>>(),
+            seqs.clone().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            stored
+                .iter()
+                .map(|e| e.event_type.as_str())
+                .collect::<Vec<_>>(),
+            vec!["test.batch.0", "test.batch.1", "test.batch.2"]
+        );
+
+        // One bad event fails the whole batch.
+        let rejected = append_batch(
+            &store_ref,
+            vec![
+                event("test.batch.3", serde_json::json!({})),
+                event(
+                    shared_types::EVENT_TOPIC_USER_INPUT,
+                    serde_json::json!({ "record": {} }),
+                ),
+            ],
+        )
+        .await
+        .unwrap();
+        assert!(
+            matches!(rejected, Err(EventStoreError::InvalidPayload(_))),
+            "{rejected:?}"
+        );
+        assert_eq!(
+            get_latest_seq(&store_ref).await.unwrap().unwrap(),
+            Some(*seqs.end())
+        );
+        assert_eq!(
+            append_batch(&store_ref, Vec::new()).await.unwrap().unwrap(),
+            None
+        );
+
+        assert_eq!(
+            write_stats(&store_ref).await.unwrap(),
+            WriteStats {
+                transactions: 1,
+                events: 3
+            }
+        );
+
+        store_ref.stop(None);
+    }
 
-/// Convenience function to get recent events with optional filters.
-pub async fn get_recent_events(
-    store: &ActorRef<EventStoreMsg>,
-    since_seq: i64,
-    limit: i64,
-    event_type_prefix: Option<String>,
-    actor_id: Option<String>,
-    user_id: Option<String>,
-) -> Result<Result<Vec<shared_types::Event>, EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
-    ractor::call!(store, |reply| EventStoreMsg::GetRecentEvents {
-        since_seq,
-        limit,
-        event_type_prefix,
-        actor_id,
-        user_id,
-        reply,
-    })
-}
+    #[tokio::test]
+    async fn test_append_is_visible_to_separate_read_connection() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_path = temp_dir.path().join("events.db");
+        let db_path = db_path.to_str().unwrap().to_string();
+        let (store_ref, _handle) = Actor::spawn(
+            None,
+            EventStoreActor,
+            EventStoreArguments::File(db_path.clone()),
+        )
+        .await
+        .unwrap();
+        let read_pool = EventStoreActor::open_read_pool(&db_path).await.unwrap();
 
-/// Convenience function to get the latest event sequence number.
-pub async fn get_latest_seq(
-    store: &ActorRef<EventStoreMsg>,
-) -> Result<Result<Option<i64>, EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
-    ractor::call!(store, |reply| EventStoreMsg::GetLatestSeq { reply })
-}
+        for i in 0..20 {
+            let event = append_event(
+                &store_ref,
+                AppendEvent {
+                    event_type: "test.event".to_string(),
+                    payload: serde_json::json!({ "index": i }),
+                    actor_id: "actor-1".to_string(),
+                    user_id: "user-1".to_string(),
+                    idempotency_key: None,
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
 
-/// Convenience function to get an event by sequence number
-pub async fn get_event_by_seq(
-    store: &ActorRef<EventStoreMsg>,
-    seq: i64,
-) -> Result<Result<Option<shared_types::Event>, EventStoreError>, ractor::RactorErr<EventStoreMsg>>
-{
-    ractor::call!(store, |reply| EventStoreMsg::GetEventBySeq { seq, reply })
-}
+            let payload: Option<String> =
+                sqlx::query_scalar("SELECT payload FROM events WHERE seq = ?1")
+                    .bind(event.seq)
+                    .fetch_optional(&read_pool)
+                    .await
+                    .unwrap();
+            let payload: serde_json::Value =
+                serde_json::from_str(&payload.expect("appended event not visible")).unwrap();
+            assert_eq!(payload["index"], i);
+        }
 
-/// Find events matching a corr_id in their payload.
-/// Optionally filter by event_type prefix (e.g. "tool.result" or "harness.result").
-pub async fn get_events_by_corr_id(
-    store: &ActorRef<EventStoreMsg>,
-    corr_id: impl Into<String>,
-    event_type_prefix: Option<String>,
-) -> Result<Result<Vec<shared_types::Event>, EventStoreError>, ractor::RactorErr<EventStoreMsg>> {
-    ractor::call!(store, |reply| EventStoreMsg::GetEventsByCorrId {
-        corr_id: corr_id.into(),
-        event_type_prefix,
-        reply,
-    })
-}
+        // A fire-and-forget append is still handled before the sender's next query.
+        store_ref
+            .cast(EventStoreMsg::AppendAsync {
+                event: AppendEvent {
+                    event_type: "test.event".to_string(),
+                    payload: serde_json::json!({ "index": 20 }),
+                    actor_id: "actor-1".to_string(),
+                    user_id: "user-1".to_string(),
+                    idempotency_key: None,
+                },
+            })
+            .unwrap();
+        let latest = get_latest_seq(&store_ref)
+            .await
+            .unwrap()
+            .unwrap()
+            .expect("events were appended");
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM events WHERE seq <= ?1")
+            .bind(latest)
+            .fetch_one(&read_pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 21);
 
-/// Get the latest harness.checkpoint event for a run_id.
-/// Returns None if the run has no checkpoint yet (not started or already cleaned up).
-pub async fn get_latest_harness_checkpoint(
-    store: &ActorRef<EventStoreMsg>,
-    run_id: impl Into<String>,
-) -> Result<Result<Option<shared_types::Event>, EventStoreError>, ractor::RactorErr<EventStoreMsg>>
-{
-    ractor::call!(store, |reply| EventStoreMsg::GetLatestHarnessCheckpoint {
-        run_id: run_id.into(),
-        reply,
-    })
-}
+        read_pool.close().await;
+        store_ref.stop(None);
+    }
 
-// ============================================================================
-// Tests
-// ============================================================================
+    fn chat_message(text: &str) -> AppendEvent {
+        AppendEvent {
+            event_type: "chat.user_msg".to_string(),
+            payload: serde_json::json!({
+                "text": text,
+                "scope": { "session_id": "session-1", "thread_id": "thread-1" },
+            }),
+            actor_id: "actor-1".to_string(),
+            user_id: "user-1".to_string(),
+            idempotency_key: None,
+        }
+    }
+
+    async fn stored_row(read_pool: &SqlitePool, seq: i64) -> (serde_json::Value, Option<String>) {
+        let (payload, key_id): (String, Option<String>) =
+            sqlx::query_as("SELECT payload, encryption_key_id FROM events WHERE seq = ?1")
+                .bind(seq)
+                .fetch_one(read_pool)
+                .await
+                .unwrap();
+        (serde_json::from_str(&payload).unwrap(), key_id)
+    }
+
+    #[tokio::test]
+    async fn test_sensitive_fields_are_sealed_at_rest_and_opened_on_read() {
+        use crate::actors::event_encryption::{EventEncryption, EventKey};
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_path = temp_dir
+            .path()
+            .join("events.db")
+            .to_string_lossy()
+            .to_string();
+        let (store_ref, _handle) = Actor::spawn(
+            None,
+            EventStoreActor,
+            EventStoreArguments::File(db_path.clone()),
+        )
+        .await
+        .unwrap();
+        let read_pool = EventStoreActor::open_read_pool(&db_path).await.unwrap();
+
+        let plaintext_before = append_event(&store_ref, chat_message("before the key"))
+            .await
+            .unwrap()
+            .unwrap();
+        let status = configure_encryption(
+            &store_ref,
+            EventEncryption::with_key(EventKey::derive("sandbox-secret")),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert!(status.current_key_id.is_some());
+        assert!(status.locked_key_ids.is_empty());
+
+        let sealed = append_event(&store_ref, chat_message("for your eyes only"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(sealed.payload["text"], "for your eyes only");
+        let other = append_event(
+            &store_ref,
+            AppendEvent {
+                event_type: "test.event".to_string(),
+                payload: serde_json::json!({ "text": "not sensitive" }),
+                actor_id: "actor-1".to_string(),
+                user_id: "user-1".to_string(),
+                idempotency_key: None,
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        let (raw, key_id) = stored_row(&read_pool, sealed.seq).await;
+        assert_eq!(key_id, status.current_key_id);
+        assert!(!raw.to_string().contains("for your eyes only"));
+        assert_eq!(raw["scope"]["thread_id"], "thread-1");
+        assert_eq!(stored_row(&read_pool, plaintext_before.seq).await.1, None);
+        assert_eq!(stored_row(&read_pool, other.seq).await.1, None);
+
+        // Mixed plaintext and sealed rows read back uniformly; the scope
+        // columns still work for sealed rows.
+        let events =
+            get_events_for_actor_with_scope(&store_ref, "actor-1", "session-1", "thread-1", 0)
+                .await
+                .unwrap()
+                .unwrap();
+        let texts: Vec<_> = events.iter().map(|e| e.payload["text"].clone()).collect();
+        assert_eq!(texts, vec!["before the key", "for your eyes only"]);
+        assert!(events[1]
+            .payload
+            .get(crate::actors::event_encryption::ENCRYPTED_FIELDS_KEY)
+            .is_none());
+
+        read_pool.close().await;
+        store_ref.stop(None);
+    }
+
+    #[tokio::test]
+    async fn test_search_matches_payload_text_but_not_sealed_fields() {
+        use crate::actors::event_encryption::{EventEncryption, EventKey};
+
+        let (store_ref, _handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .unwrap();
+        configure_encryption(
+            &store_ref,
+            EventEncryption::with_key(EventKey::derive("sandbox-secret")),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        append_event(&store_ref, chat_message("for your eyes only"))
+            .await
+            .unwrap()
+            .unwrap();
+        let stalled = append_event(
+            &store_ref,
+            AppendEvent {
+                event_type: "conductor.run.status".to_string(),
+                payload: serde_json::json!({
+                    "run_id": "run-1",
+                    "detail": { "notes": ["worker stalled on tool call"] },
+                }),
+                actor_id: "conductor-1".to_string(),
+                user_id: "user-1".to_string(),
+                idempotency_key: None,
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        let matches = search_events(&store_ref, "\"stalled\"", 10)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].event.seq, stalled.seq);
+        assert_eq!(matches[0].event.payload, stalled.payload);
+        assert!(matches[0].snippet.contains(&format!(
+            "{SEARCH_HIGHLIGHT_START}stalled{SEARCH_HIGHLIGHT_END}"
+        )));
+
+        // Type and actor are indexed too.
+        let by_actor = search_events(&store_ref, "actor_id:\"conductor\"", 10)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(by_actor.len(), 1);
+
+        // Sealed content stays out of the index.
+        let sealed = search_events(&store_ref, "\"eyes\"", 10)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(sealed.is_empty());
+
+        store_ref.stop(None);
+    }
+
+    #[tokio::test]
+    async fn test_event_search_can_be_skipped_and_catches_up_when_enabled() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_path = temp_dir
+            .path()
+            .join("events.db")
+            .to_string_lossy()
+            .to_string();
+        let needle = |text: &str| AppendEvent {
+            event_type: "test.event".to_string(),
+            payload: serde_json::json!({ "text": text }),
+            actor_id: "actor-1".to_string(),
+            user_id: "user-1".to_string(),
+            idempotency_key: None,
+        };
+
+        let (store_ref, handle) = Actor::spawn(
+            None,
+            EventStoreActor,
+            EventStoreArguments::File(db_path.clone()).without_event_search(),
+        )
+        .await
+        .unwrap();
+        append_event(&store_ref, needle("haystack needle"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(
+            search_events(&store_ref, "\"needle\"", 10).await.unwrap(),
+            Err(EventStoreError::SearchDisabled)
+        ));
+        store_ref.stop(None);
+        handle.await.unwrap();
+
+        let (store_ref, _handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::File(db_path))
+                .await
+                .unwrap();
+        append_event(&store_ref, needle("second needle"))
+            .await
+            .unwrap()
+            .unwrap();
+        let texts: Vec<_> = search_events(&store_ref, "\"needle\"", 10)
+            .await
+            .unwrap()
+            .unwrap()
+            .into_iter()
+            .map(|m| m.event.payload["text"].clone())
+            .collect();
+        assert_eq!(texts.len(), 2);
+        assert!(texts.contains(&serde_json::json!("haystack needle")));
+        assert!(texts.contains(&serde_json::json!("second needle")));
+
+        store_ref.stop(None);
+    }
+
+    #[tokio::test]
+    async fn test_redacted_events_drop_out_of_event_search() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_path = temp_dir
+            .path()
+            .join("events.db")
+            .to_string_lossy()
+            .to_string();
+        let needle = |text: &str| AppendEvent {
+            event_type: "test.event".to_string(),
+            payload: serde_json::json!({ "text": text }),
+            actor_id: "actor-1".to_string(),
+            user_id: "user-1".to_string(),
+            idempotency_key: None,
+        };
+        let redaction = |seq: i64| AppendEvent {
+            event_type: shared_types::EVENT_TOPIC_EVENT_REDACTED.to_string(),
+            payload: serde_json::json!({ "seq": seq, "reason": "leaked" }),
+            actor_id: "api.admin".to_string(),
+            user_id: "system".to_string(),
+            idempotency_key: None,
+        };
+        let texts = |matches: Vec<EventSearchMatch>| {
+            matches
+                .into_iter()
+                .map(|m| m.event.payload["text"].clone())
+                .collect::<Vec<_>>()
+        };
+
+        let (store_ref, handle) = Actor::spawn(
+            None,
+            EventStoreActor,
+            EventStoreArguments::File(db_path.clone()),
+        )
+        .await
+        .unwrap();
+        let secret = append_event(&store_ref, needle("secret needle"))
+            .await
+            .unwrap()
+            .unwrap();
+        append_event(&store_ref, redaction(secret.seq))
+            .await
+            .unwrap()
+            .unwrap();
+        let matches = search_events(&store_ref, "\"needle\"", 10)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches.is_empty());
+        store_ref.stop(None);
+        handle.await.unwrap();
+
+        // Redacted while search was off: catch-up indexes and then drops it.
+        let (store_ref, handle) = Actor::spawn(
+            None,
+            EventStoreActor,
+            EventStoreArguments::File(db_path.clone()).without_event_search(),
+        )
+        .await
+        .unwrap();
+        let later = append_event(&store_ref, needle("later needle"))
+            .await
+            .unwrap()
+            .unwrap();
+        append_event(&store_ref, needle("kept needle"))
+            .await
+            .unwrap()
+            .unwrap();
+        append_event(&store_ref, redaction(later.seq))
+            .await
+            .unwrap()
+            .unwrap();
+        store_ref.stop(None);
+        handle.await.unwrap();
+
+        let (store_ref, _handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::File(db_path))
+                .await
+                .unwrap();
+        let matches = search_events(&store_ref, "\"needle\"", 10)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(texts(matches), [serde_json::json!("kept needle")]);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use ractor::Actor;
+        store_ref.stop(None);
+    }
 
     #[tokio::test]
-    async fn test_append_and_retrieve_event() {
-        let (store_ref, _handle) =
-            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+    async fn test_missing_key_locks_content_and_rotation_reseals_rows() {
+        use crate::actors::event_encryption::{
+            EventEncryption, EventKey, CONTENT_LOCKED_KEY, LOCKED_CONTENT,
+        };
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_path = temp_dir
+            .path()
+            .join("events.db")
+            .to_string_lossy()
+            .to_string();
+        let (store_ref, _handle) = Actor::spawn(
+            None,
+            EventStoreActor,
+            EventStoreArguments::File(db_path.clone()),
+        )
+        .await
+        .unwrap();
+        let read_pool = EventStoreActor::open_read_pool(&db_path).await.unwrap();
+
+        let old_key_id = configure_encryption(
+            &store_ref,
+            EventEncryption::with_key(EventKey::derive("old")),
+        )
+        .await
+        .unwrap()
+        .unwrap()
+        .current_key_id
+        .unwrap();
+        let mut seqs = Vec::new();
+        for i in 0..5 {
+            let event = append_event(&store_ref, chat_message(&format!("message {i}")))
                 .await
+                .unwrap()
                 .unwrap();
+            seqs.push(event.seq);
+        }
 
-        // Append an event
-        let event = append_event(
+        // Without the key events are still listed, with their content locked.
+        let status = configure_encryption(&store_ref, EventEncryption::default())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(status.current_key_id, None);
+        assert_eq!(status.locked_key_ids, vec![old_key_id.clone()]);
+        let locked = get_event_by_seq(&store_ref, seqs[0])
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(locked.payload["text"], LOCKED_CONTENT);
+        assert_eq!(locked.payload[CONTENT_LOCKED_KEY], true);
+        assert_eq!(locked.event_type, "chat.user_msg");
+        assert!(matches!(
+            reencrypt_all(&store_ref, 2).await,
+            Err(EventStoreError::Encryption(_))
+        ));
+
+        // Rotate: the new key seals, the old one opens rows until re-sealed.
+        let new_key_id = configure_encryption(
             &store_ref,
-            AppendEvent {
-                event_type: "test.event".to_string(),
-                payload: serde_json::json!({"foo": "bar"}),
-                actor_id: "actor-1".to_string(),
-                user_id: "user-1".to_string(),
-            },
+            EventEncryption::with_key(EventKey::derive("new"))
+                .with_previous_keys([EventKey::derive("old")]),
         )
         .await
         .unwrap()
+        .unwrap()
+        .current_key_id
         .unwrap();
+        let rotated = reencrypt_all(&store_ref, 2).await.unwrap();
+        assert_eq!(rotated.reencrypted, 5);
+        assert_eq!(rotated.locked, 0);
+        for seq in &seqs {
+            assert_eq!(
+                stored_row(&read_pool, *seq).await.1.as_deref(),
+                Some(new_key_id.as_str())
+            );
+        }
 
-        assert!(event.seq > 0);
-        assert_eq!(event.event_type, "test.event");
-        assert_eq!(event.actor_id.0, "actor-1");
-
-        // Retrieve events for actor
-        let events = get_events_for_actor(&store_ref, "actor-1", 0)
+        configure_encryption(
+            &store_ref,
+            EventEncryption::with_key(EventKey::derive("new")),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        let events = get_recent_events(&store_ref, 0, 10, Some("chat.".to_string()), None, None)
             .await
             .unwrap()
             .unwrap();
+        let texts: Vec<_> = events.iter().map(|e| e.payload["text"].clone()).collect();
+        assert_eq!(
+            texts,
+            (0..5)
+                .map(|i| serde_json::json!(format!("message {i}")))
+                .collect::<Vec<_>>()
+        );
 
-        assert_eq!(events.len(), 1);
-        assert_eq!(events[0].seq, event.seq);
-
-        // Cleanup
+        read_pool.close().await;
         store_ref.stop(None);
     }
 
     #[tokio::test]
-    async fn test_get_events_since_seq() {
+    async fn test_save_snapshot_replaces_previous_snapshot() {
         let (store_ref, _handle) =
             Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
                 .await
                 .unwrap();
 
-        // Append multiple events
-        for i in 0..5 {
-            append_event(
+        assert_eq!(
+            get_latest_snapshot(&store_ref, "actor-1")
+                .await
+                .unwrap()
+                .unwrap(),
+            None
+        );
+
+        for seq in [10, 20] {
+            save_snapshot(
                 &store_ref,
-                AppendEvent {
-                    event_type: "test.event".to_string(),
-                    payload: serde_json::json!({"index": i}),
+                ActorSnapshot {
                     actor_id: "actor-1".to_string(),
-                    user_id: "user-1".to_string(),
+                    seq,
+                    state: serde_json::json!({ "count": seq }),
+                    created_at: "2026-03-04T12:00:00Z".to_string(),
                 },
             )
             .await
@@ -811,191 +5039,200 @@ mod tests {
             .unwrap();
         }
 
-        // Get events after seq 2
-        let events = get_events_for_actor(&store_ref, "actor-1", 2)
+        let snapshot = get_latest_snapshot(&store_ref, "actor-1")
             .await
             .unwrap()
+            .unwrap()
             .unwrap();
+        assert_eq!(snapshot.seq, 20);
+        assert_eq!(snapshot.state["count"], 20);
 
-        // Should get events with seq > 2
-        assert_eq!(events.len(), 3);
-        for event in &events {
-            assert!(event.seq > 2);
-        }
-
-        // Cleanup
         store_ref.stop(None);
     }
 
     #[tokio::test]
-    async fn test_events_isolated_by_actor() {
-        let (store_ref, _handle) =
-            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
-                .await
-                .unwrap();
-
-        // Events for different actors
-        append_event(
-            &store_ref,
-            AppendEvent {
-                event_type: "interaction.msg".to_string(),
-                payload: serde_json::json!({"text": "hello"}),
-                actor_id: "session-1".to_string(),
-                user_id: "user-1".to_string(),
-            },
+    async fn test_health_check_reports_locked_database_unwritable() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_path = temp_dir.path().join("events.db");
+        let (store_ref, _handle) = Actor::spawn(
+            None,
+            EventStoreActor,
+            EventStoreArguments::File(db_path.to_string_lossy().to_string()),
         )
         .await
-        .unwrap()
         .unwrap();
 
-        append_event(
-            &store_ref,
-            AppendEvent {
-                event_type: "file.write".to_string(),
-                payload: serde_json::json!({"path": "test.txt"}),
-                actor_id: "writer-1".to_string(),
-                user_id: "user-1".to_string(),
-            },
-        )
-        .await
+        let health = health_check(&store_ref).await.unwrap();
+        assert!(
+            health.writable,
+            "fresh store should be writable: {health:?}"
+        );
+        assert_eq!(health.error, None);
+        // The probe row is rolled back.
+        let probe = ractor::call!(store_ref, |reply| EventStoreMsg::GetLatestSnapshot {
+            actor_id: HEALTH_CHECK_PROBE_ID.to_string(),
+            reply,
+        })
         .unwrap()
         .unwrap();
+        assert!(probe.is_none());
 
-        // Get events for a single actor only
-        let session_events = get_events_for_actor(&store_ref, "session-1", 0)
+        // Another connection holding the write lock leaves the store readable
+        // but unwritable.
+        let pool = SqlitePool::connect(&format!("sqlite:{}", db_path.display()))
+            .await
+            .unwrap();
+        let mut locker = pool.acquire().await.unwrap();
+        sqlx::query("BEGIN IMMEDIATE")
+            .execute(&mut *locker)
             .await
-            .unwrap()
             .unwrap();
 
-        assert_eq!(session_events.len(), 1);
-        assert_eq!(session_events[0].event_type, "interaction.msg");
+        let health = health_check(&store_ref).await.unwrap();
+        assert!(!health.writable, "locked store reported writable");
+        assert!(health.error.is_some());
+        assert!(get_latest_seq(&store_ref).await.unwrap().is_ok());
+
+        sqlx::query("COMMIT").execute(&mut *locker).await.unwrap();
+        let health = health_check(&store_ref).await.unwrap();
+        assert!(health.writable, "store stayed unwritable: {health:?}");
 
-        // Cleanup
         store_ref.stop(None);
     }
 
     #[tokio::test]
-    async fn test_get_events_for_actor_with_scope() {
-        let (store_ref, _handle) =
+    async fn test_export_import_round_trip_is_idempotent() {
+        let (source, _source_handle) =
             Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
                 .await
                 .unwrap();
+        for n in 0..3 {
+            append_event(
+                &source,
+                AppendEvent {
+                    event_type: "test.event".to_string(),
+                    payload: serde_json::json!({ "n": n }),
+                    actor_id: "actor-1".to_string(),
+                    user_id: "user-1".to_string(),
+                    idempotency_key: None,
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        }
 
-        append_event(
-            &store_ref,
-            AppendEvent {
-                event_type: "interaction.user_msg".to_string(),
-                payload: serde_json::json!({
-                    "text": "msg-in-scope",
-                    "scope": {
-                        "session_id": "session-1",
-                        "thread_id": "thread-1"
-                    }
-                }),
-                actor_id: "session-1".to_string(),
-                user_id: "user-1".to_string(),
-            },
-        )
-        .await
-        .unwrap()
-        .unwrap();
+        let (writer, mut reader) = tokio::io::duplex(64);
+        let export = tokio::spawn({
+            let source = source.clone();
+            async move {
+                ractor::call!(source, |reply| EventStoreMsg::Export {
+                    since_seq: 0,
+                    until_seq: None,
+                    writer: ExportWriter(Box::new(writer)),
+                    reply,
+                })
+            }
+        });
+        let mut ndjson = Vec::new();
+        tokio::io::AsyncReadExt::read_to_end(&mut reader, &mut ndjson)
+            .await
+            .unwrap();
+        assert_eq!(export.await.unwrap().unwrap().unwrap(), 3);
+        assert_eq!(ndjson.iter().filter(|b| **b == b'\n').count(), 3);
 
-        append_event(
-            &store_ref,
+        let (target, _target_handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .unwrap();
+        // Local seqs already in use; imported events are renumbered after them.
+        let local = append_event(
+            &target,
             AppendEvent {
-                event_type: "interaction.user_msg".to_string(),
-                payload: serde_json::json!({
-                    "text": "msg-other-thread",
-                    "scope": {
-                        "session_id": "session-1",
-                        "thread_id": "thread-2"
-                    }
-                }),
-                actor_id: "session-1".to_string(),
+                event_type: "local.event".to_string(),
+                payload: serde_json::json!({}),
+                actor_id: "actor-2".to_string(),
                 user_id: "user-1".to_string(),
+                idempotency_key: None,
             },
         )
         .await
         .unwrap()
         .unwrap();
 
-        let scoped =
-            get_events_for_actor_with_scope(&store_ref, "session-1", "session-1", "thread-1", 0)
-                .await
-                .unwrap()
-                .unwrap();
-
-        assert_eq!(scoped.len(), 1);
+        let report = import_events(&target, std::io::Cursor::new(ndjson.clone()))
+            .await
+            .unwrap()
+            .unwrap();
         assert_eq!(
-            scoped[0].payload.get("text").and_then(|v| v.as_str()),
-            Some("msg-in-scope")
+            report,
+            ImportReport {
+                imported: 3,
+                duplicates: 0
+            }
+        );
+        let again = import_events(&target, std::io::Cursor::new(ndjson))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            again,
+            ImportReport {
+                imported: 0,
+                duplicates: 3
+            }
         );
 
-        store_ref.stop(None);
+        let exported = get_events_for_actor(&source, "actor-1", 0)
+            .await
+            .unwrap()
+            .unwrap();
+        let imported = get_events_for_actor(&target, "actor-1", 0)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(imported.len(), 3);
+        for (original, copy) in exported.iter().zip(&imported) {
+            assert_eq!(copy.event_id, original.event_id);
+            assert_eq!(copy.payload, original.payload);
+            assert_eq!(copy.actor_seq, original.actor_seq);
+            assert!(copy.seq > local.seq);
+        }
+
+        source.stop(None);
+        target.stop(None);
     }
 
     #[tokio::test]
-    async fn test_get_recent_events_with_filters() {
+    async fn test_import_rejects_out_of_order_seq_and_stores_nothing() {
         let (store_ref, _handle) =
             Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
                 .await
                 .unwrap();
+        let line = |seq: i64, event_id: &str| {
+            serde_json::json!({
+                "seq": seq,
+                "event_id": event_id,
+                "timestamp": "2026-01-01T00:00:00Z",
+                "stored_at": "2026-01-01T00:00:00Z",
+                "actor_id": "actor-1",
+                "event_type": "test.event",
+                "payload": {},
+                "user_id": "user-1",
+            })
+            .to_string()
+        };
+        let ndjson = format!("{}\n{}\n", line(5, "evt-a"), line(5, "evt-b"));
 
-        append_event(
-            &store_ref,
-            AppendEvent {
-                event_type: "worker.task.started".to_string(),
-                payload: serde_json::json!({"task_id": "t1"}),
-                actor_id: "supervisor-1".to_string(),
-                user_id: "user-1".to_string(),
-            },
-        )
-        .await
-        .unwrap()
-        .unwrap();
-
-        append_event(
-            &store_ref,
-            AppendEvent {
-                event_type: "interaction.user_msg".to_string(),
-                payload: serde_json::json!({"text": "hello"}),
-                actor_id: "session-1".to_string(),
-                user_id: "user-1".to_string(),
-            },
-        )
-        .await
-        .unwrap()
-        .unwrap();
-
-        append_event(
-            &store_ref,
-            AppendEvent {
-                event_type: "worker.task.completed".to_string(),
-                payload: serde_json::json!({"task_id": "t1"}),
-                actor_id: "supervisor-1".to_string(),
-                user_id: "user-2".to_string(),
-            },
-        )
-        .await
-        .unwrap()
-        .unwrap();
-
-        let filtered = get_recent_events(
-            &store_ref,
-            0,
-            10,
-            Some("worker.task".to_string()),
-            Some("supervisor-1".to_string()),
-            Some("user-1".to_string()),
-        )
-        .await
-        .unwrap()
-        .unwrap();
-
-        assert_eq!(filtered.len(), 1);
-        assert_eq!(filtered[0].event_type, "worker.task.started");
-        assert_eq!(filtered[0].actor_id.0, "supervisor-1");
-        assert_eq!(filtered[0].user_id, "user-1");
+        let err = import_events(&store_ref, std::io::Cursor::new(ndjson.into_bytes()))
+            .await
+            .unwrap()
+            .unwrap_err();
+        assert!(
+            matches!(&err, EventStoreError::InvalidPayload(msg) if msg.starts_with("line 2:")),
+            "unexpected error: {err}"
+        );
+        assert_eq!(get_latest_seq(&store_ref).await.unwrap().unwrap(), None);
 
         store_ref.stop(None);
     }
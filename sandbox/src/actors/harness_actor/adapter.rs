@@ -19,7 +19,7 @@ use crate::actors::agent_harness::{
 };
 use crate::actors::conductor::protocol::ConductorMsg;
 use crate::actors::event_store::{AppendEvent, EventStoreMsg};
-use crate::actors::researcher::providers;
+use crate::actors::researcher::{freshness, providers};
 use crate::actors::researcher::{ResearcherFetchUrlRequest, ResearcherWebSearchRequest};
 use crate::baml_client::types::Union8BashToolCallOrFetchUrlToolCallOrFileEditToolCallOrFileReadToolCallOrFileWriteToolCallOrFinishedToolCallOrMessageWriterToolCallOrWebSearchToolCall as AgentToolCall;
 
@@ -83,6 +83,7 @@ impl HarnessAdapter {
                 payload,
                 actor_id: format!("harness:{}", self.correlation_id),
                 user_id: "system".to_string(),
+                idempotency_key: None,
             },
         });
     }
@@ -332,7 +333,11 @@ Guidelines:
                     None,
                 )
                 .await;
-                let citations = providers::merge_citations(&outputs);
+                let citations = freshness::apply_freshness(
+                    providers::merge_citations(&outputs),
+                    None,
+                    chrono::Utc::now(),
+                );
                 let success = !citations.is_empty();
                 let output = serde_json::json!({
                     "citations": citations,
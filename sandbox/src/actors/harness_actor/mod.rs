@@ -176,10 +176,11 @@ async fn emit_harness_execute(
     });
     let _ = event_store.send_message(EventStoreMsg::AppendAsync {
         event: AppendEvent {
-            event_type: "harness.execute".to_string(),
+            event_type: shared_types::EventTopic::HarnessExecute.to_string(),
             payload,
             actor_id: format!("harness:{correlation_id}"),
             user_id: "system".to_string(),
+            idempotency_key: None,
         },
     });
 }
@@ -201,10 +202,11 @@ async fn emit_harness_result(
     });
     let _ = event_store.send_message(EventStoreMsg::AppendAsync {
         event: AppendEvent {
-            event_type: "harness.result".to_string(),
+            event_type: shared_types::EventTopic::HarnessResult.to_string(),
             payload,
             actor_id: format!("harness:{correlation_id}"),
             user_id: "system".to_string(),
+            idempotency_key: None,
         },
     });
 }
@@ -0,0 +1,519 @@
+//! Shared LLM call scheduler
+//!
+//! Every harness and gateway call to a model provider goes through one
+//! process-wide scheduler, so concurrent runs share a provider's budget instead
+//! of each discovering the limit with its own 429s and backing off in lockstep.
+//!
+//! Budgets are per provider endpoint (`ProviderConfig::endpoint_key`) and come
+//! from the model catalog: `max_concurrency` caps in-flight calls and
+//! `requests_per_minute` caps call starts over a sliding window.
+//!
+//! Waiting callers are queued per run_id and served round-robin, so one busy
+//! run cannot starve another. A 429 from the provider halves the endpoint's
+//! effective budget until a cool-down passes; further 429s during the
+//! cool-down halve it again and restart the cool-down.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::time::Instant;
+
+pub const DEFAULT_MAX_CONCURRENCY: usize = 4;
+pub const DEFAULT_REQUESTS_PER_MINUTE: u32 = 60;
+
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+const RATE_LIMIT_COOLDOWN: Duration = Duration::from_secs(30);
+/// Budget never shrinks below 1/16th of the configured value.
+const MAX_THROTTLE_LEVEL: u32 = 4;
+
+/// Call budget for one provider endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LlmCallBudget {
+    pub max_concurrency: usize,
+    pub requests_per_minute: u32,
+}
+
+impl Default for LlmCallBudget {
+    fn default() -> Self {
+        Self {
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
+            requests_per_minute: DEFAULT_REQUESTS_PER_MINUTE,
+        }
+    }
+}
+
+impl LlmCallBudget {
+    fn throttled(self, level: u32) -> Self {
+        Self {
+            max_concurrency: (self.max_concurrency >> level).max(1),
+            requests_per_minute: (self.requests_per_minute >> level).max(1),
+        }
+    }
+}
+
+/// Provider endpoint a call is scheduled against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LlmCallTarget {
+    pub endpoint: String,
+    pub budget: LlmCallBudget,
+}
+
+/// Whether a provider error is a 429 / rate-limit response.
+pub fn is_rate_limit_error(message: &str) -> bool {
+    let lower = message.to_ascii_lowercase();
+    lower.contains("429")
+        || lower.contains("rate limit")
+        || lower.contains("rate_limit")
+        || lower.contains("too many requests")
+        || lower.contains("throttlingexception")
+}
+
+/// Process-wide scheduler shared by all LLM call sites.
+pub fn llm_scheduler() -> &'static LlmCallScheduler {
+    static SCHEDULER: OnceLock<LlmCallScheduler> = OnceLock::new();
+    SCHEDULER.get_or_init(LlmCallScheduler::new)
+}
+
+#[derive(Debug, Clone)]
+pub struct LlmCallScheduler {
+    inner: Arc<Mutex<SchedulerInner>>,
+}
+
+#[derive(Debug)]
+struct SchedulerInner {
+    rate_window: Duration,
+    cooldown: Duration,
+    providers: HashMap<String, ProviderState>,
+}
+
+#[derive(Debug)]
+struct ProviderState {
+    budget: LlmCallBudget,
+    in_flight: usize,
+    recent_starts: VecDeque<Instant>,
+    throttle_level: u32,
+    cooldown_until: Option<Instant>,
+    /// Waiters grouped by run, served round-robin from the front.
+    lanes: VecDeque<RunLane>,
+    wake_scheduled: bool,
+}
+
+#[derive(Debug)]
+struct RunLane {
+    run_key: String,
+    waiters: VecDeque<Waiter>,
+}
+
+#[derive(Debug)]
+struct Waiter {
+    requested_at: Instant,
+    grant: oneshot::Sender<LlmCallPermit>,
+}
+
+impl ProviderState {
+    fn new(budget: LlmCallBudget) -> Self {
+        Self {
+            budget,
+            in_flight: 0,
+            recent_starts: VecDeque::new(),
+            throttle_level: 0,
+            cooldown_until: None,
+            lanes: VecDeque::new(),
+            wake_scheduled: false,
+        }
+    }
+
+    fn effective_budget(&self) -> LlmCallBudget {
+        self.budget.throttled(self.throttle_level)
+    }
+
+    fn refresh(&mut self, now: Instant, rate_window: Duration) {
+        while let Some(started) = self.recent_starts.front() {
+            if now.duration_since(*started) >= rate_window {
+                self.recent_starts.pop_front();
+            } else {
+                break;
+            }
+        }
+        if self.cooldown_until.is_some_and(|until| now >= until) {
+            self.cooldown_until = None;
+            self.throttle_level = 0;
+        }
+    }
+
+    fn enqueue(&mut self, run_key: String, waiter: Waiter) {
+        if let Some(lane) = self.lanes.iter_mut().find(|lane| lane.run_key == run_key) {
+            lane.waiters.push_back(waiter);
+        } else {
+            self.lanes.push_back(RunLane {
+                run_key,
+                waiters: VecDeque::from([waiter]),
+            });
+        }
+    }
+
+    fn next_waiter(&mut self) -> Option<Waiter> {
+        let mut lane = self.lanes.pop_front()?;
+        let waiter = lane.waiters.pop_front();
+        if !lane.waiters.is_empty() {
+            self.lanes.push_back(lane);
+        }
+        waiter
+    }
+}
+
+impl Default for LlmCallScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LlmCallScheduler {
+    pub fn new() -> Self {
+        Self::with_timing(RATE_WINDOW, RATE_LIMIT_COOLDOWN)
+    }
+
+    /// Scheduler with a custom rate window and 429 cool-down. The
+    /// `requests_per_minute` budget applies per `rate_window`.
+    pub fn with_timing(rate_window: Duration, cooldown: Duration) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(SchedulerInner {
+                rate_window,
+                cooldown,
+                providers: HashMap::new(),
+            })),
+        }
+    }
+
+    /// Wait for a call slot on `target`'s endpoint.
+    ///
+    /// The slot is held until the returned permit is dropped.
+    pub async fn acquire(&self, target: &LlmCallTarget, run_id: Option<&str>) -> LlmCallPermit {
+        let (grant, granted) = oneshot::channel();
+        {
+            let mut inner = self.lock();
+            let provider = inner
+                .providers
+                .entry(target.endpoint.clone())
+                .or_insert_with(|| ProviderState::new(target.budget));
+            // Models sharing an endpoint share its budget; the latest
+            // catalog value wins.
+            provider.budget = target.budget;
+            provider.enqueue(
+                run_id.unwrap_or_default().to_string(),
+                Waiter {
+                    requested_at: Instant::now(),
+                    grant,
+                },
+            );
+            self.dispatch(&mut inner, &target.endpoint);
+        }
+        granted
+            .await
+            .expect("scheduler keeps waiters until they are granted")
+    }
+
+    /// Current budget for an endpoint after any 429 throttling.
+    pub fn effective_budget(&self, endpoint: &str) -> Option<LlmCallBudget> {
+        let mut inner = self.lock();
+        let rate_window = inner.rate_window;
+        let provider = inner.providers.get_mut(endpoint)?;
+        provider.refresh(Instant::now(), rate_window);
+        Some(provider.effective_budget())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, SchedulerInner> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Grant queued waiters while the endpoint has budget left.
+    fn dispatch(&self, inner: &mut SchedulerInner, endpoint: &str) {
+        let rate_window = inner.rate_window;
+        let Some(provider) = inner.providers.get_mut(endpoint) else {
+            return;
+        };
+        loop {
+            let now = Instant::now();
+            provider.refresh(now, rate_window);
+            if provider.lanes.is_empty() {
+                return;
+            }
+            let budget = provider.effective_budget();
+            if provider.in_flight >= budget.max_concurrency {
+                return;
+            }
+            if provider.recent_starts.len() >= budget.requests_per_minute as usize {
+                let window_frees_at = provider
+                    .recent_starts
+                    .get(provider.recent_starts.len() - budget.requests_per_minute as usize)
+                    .map(|started| *started + rate_window)
+                    .unwrap_or(now);
+                // A throttled budget recovers when the cool-down ends, which
+                // may be sooner.
+                let wake_at = provider
+                    .cooldown_until
+                    .map_or(window_frees_at, |until| until.min(window_frees_at));
+                if !provider.wake_scheduled {
+                    provider.wake_scheduled = true;
+                    self.schedule_wake(endpoint.to_string(), wake_at);
+                }
+                return;
+            }
+            let Some(waiter) = provider.next_waiter() else {
+                continue;
+            };
+
+            provider.in_flight += 1;
+            provider.recent_starts.push_back(now);
+            let permit = LlmCallPermit {
+                scheduler: self.clone(),
+                endpoint: endpoint.to_string(),
+                queue_wait: now.duration_since(waiter.requested_at),
+                released: false,
+            };
+            if let Err(mut permit) = waiter.grant.send(permit) {
+                // Caller gave up waiting; hand the slot to the next one.
+                permit.released = true;
+                provider.in_flight -= 1;
+                provider.recent_starts.pop_back();
+            }
+        }
+    }
+
+    fn schedule_wake(&self, endpoint: String, at: Instant) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let scheduler = self.clone();
+        runtime.spawn(async move {
+            tokio::time::sleep_until(at).await;
+            let mut inner = scheduler.lock();
+            if let Some(provider) = inner.providers.get_mut(&endpoint) {
+                provider.wake_scheduled = false;
+            }
+            scheduler.dispatch(&mut inner, &endpoint);
+        });
+    }
+
+    fn release(&self, endpoint: &str) {
+        let mut inner = self.lock();
+        if let Some(provider) = inner.providers.get_mut(endpoint) {
+            provider.in_flight = provider.in_flight.saturating_sub(1);
+        }
+        self.dispatch(&mut inner, endpoint);
+    }
+
+    fn report_rate_limited(&self, endpoint: &str) {
+        let mut inner = self.lock();
+        let cooldown = inner.cooldown;
+        if let Some(provider) = inner.providers.get_mut(endpoint) {
+            provider.throttle_level = (provider.throttle_level + 1).min(MAX_THROTTLE_LEVEL);
+            provider.cooldown_until = Some(Instant::now() + cooldown);
+            let budget = provider.effective_budget();
+            tracing::warn!(
+                endpoint,
+                max_concurrency = budget.max_concurrency,
+                requests_per_minute = budget.requests_per_minute,
+                cooldown_ms = cooldown.as_millis() as u64,
+                "Provider rate limited; shrinking LLM call budget"
+            );
+        }
+    }
+}
+
+/// A granted call slot. Dropping it frees the slot for the next waiter.
+#[derive(Debug)]
+pub struct LlmCallPermit {
+    scheduler: LlmCallScheduler,
+    endpoint: String,
+    queue_wait: Duration,
+    released: bool,
+}
+
+impl LlmCallPermit {
+    /// Time spent queued before the slot was granted.
+    pub fn queue_wait_ms(&self) -> u64 {
+        self.queue_wait.as_millis() as u64
+    }
+
+    /// Record a 429 from the provider so the endpoint's budget shrinks.
+    pub fn report_rate_limited(&self) {
+        self.scheduler.report_rate_limited(&self.endpoint);
+    }
+}
+
+impl Drop for LlmCallPermit {
+    fn drop(&mut self) {
+        if !self.released {
+            self.released = true;
+            self.scheduler.release(&self.endpoint);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Provider stub that answers 429 once more than `rpm` calls start
+    /// within `window`.
+    #[derive(Clone)]
+    struct StrictRpmProvider {
+        rpm: usize,
+        window: Duration,
+        starts: Arc<Mutex<VecDeque<Instant>>>,
+        rejected: Arc<Mutex<usize>>,
+    }
+
+    impl StrictRpmProvider {
+        async fn call(&self) -> Result<(), String> {
+            {
+                let now = Instant::now();
+                let mut starts = self.starts.lock().unwrap();
+                while starts
+                    .front()
+                    .is_some_and(|started| now.duration_since(*started) >= self.window)
+                {
+                    starts.pop_front();
+                }
+                if starts.len() >= self.rpm {
+                    *self.rejected.lock().unwrap() += 1;
+                    return Err("429 Too Many Requests".to_string());
+                }
+                starts.push_back(now);
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_strict_rpm_provider_sees_no_429s_and_runs_interleave() {
+        let window = Duration::from_millis(250);
+        let scheduler = LlmCallScheduler::with_timing(window, Duration::from_secs(5));
+        let target = LlmCallTarget {
+            endpoint: "stub".to_string(),
+            budget: LlmCallBudget {
+                max_concurrency: 2,
+                requests_per_minute: 3,
+            },
+        };
+        let provider = StrictRpmProvider {
+            rpm: 3,
+            window,
+            starts: Arc::new(Mutex::new(VecDeque::new())),
+            rejected: Arc::new(Mutex::new(0)),
+        };
+        let grants = Arc::new(Mutex::new(Vec::new()));
+
+        let mut handles = Vec::new();
+        for run_id in ["run-a", "run-b"] {
+            for _ in 0..6 {
+                let scheduler = scheduler.clone();
+                let target = target.clone();
+                let provider = provider.clone();
+                let grants = grants.clone();
+                handles.push(tokio::spawn(async move {
+                    let permit = scheduler.acquire(&target, Some(run_id)).await;
+                    grants.lock().unwrap().push(run_id);
+                    let result = provider.call().await;
+                    if result.as_ref().is_err_and(|e| is_rate_limit_error(e)) {
+                        permit.report_rate_limited();
+                    }
+                    (result, permit.queue_wait_ms())
+                }));
+            }
+        }
+
+        let mut max_wait_ms = 0;
+        for handle in handles {
+            let (result, wait_ms) = handle.await.expect("join");
+            assert!(result.is_ok());
+            max_wait_ms = max_wait_ms.max(wait_ms);
+        }
+
+        assert_eq!(*provider.rejected.lock().unwrap(), 0);
+        // 12 calls at 3 per window need at least three extra windows.
+        assert!(max_wait_ms >= 3 * window.as_millis() as u64 - 50);
+
+        let grants = grants.lock().unwrap().clone();
+        assert_eq!(grants.len(), 12);
+        // Once both runs are queued they alternate until run-a runs dry.
+        let contended = &grants[target.budget.max_concurrency..10];
+        assert!(
+            contended.windows(2).all(|pair| pair[0] != pair[1]),
+            "grants were not interleaved: {grants:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_report_shrinks_budget_until_cooldown() {
+        let cooldown = Duration::from_millis(100);
+        let scheduler = LlmCallScheduler::with_timing(Duration::from_secs(60), cooldown);
+        let target = LlmCallTarget {
+            endpoint: "stub".to_string(),
+            budget: LlmCallBudget {
+                max_concurrency: 4,
+                requests_per_minute: 40,
+            },
+        };
+
+        let permit = scheduler.acquire(&target, None).await;
+        permit.report_rate_limited();
+        assert_eq!(
+            scheduler.effective_budget("stub"),
+            Some(LlmCallBudget {
+                max_concurrency: 2,
+                requests_per_minute: 20,
+            })
+        );
+        permit.report_rate_limited();
+        assert_eq!(
+            scheduler.effective_budget("stub").unwrap().max_concurrency,
+            1
+        );
+        drop(permit);
+
+        tokio::time::sleep(cooldown + Duration::from_millis(20)).await;
+        assert_eq!(scheduler.effective_budget("stub"), Some(target.budget));
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_waiter_does_not_leak_slot() {
+        let scheduler = LlmCallScheduler::new();
+        let target = LlmCallTarget {
+            endpoint: "stub".to_string(),
+            budget: LlmCallBudget {
+                max_concurrency: 1,
+                requests_per_minute: 100,
+            },
+        };
+
+        let held = scheduler.acquire(&target, Some("run-a")).await;
+        let waiting = tokio::time::timeout(
+            Duration::from_millis(20),
+            scheduler.acquire(&target, Some("run-b")),
+        )
+        .await;
+        assert!(waiting.is_err());
+        drop(held);
+
+        let next = tokio::time::timeout(
+            Duration::from_millis(200),
+            scheduler.acquire(&target, Some("run-c")),
+        )
+        .await;
+        assert!(next.is_ok());
+    }
+
+    #[test]
+    fn test_is_rate_limit_error() {
+        assert!(is_rate_limit_error("Request failed: 429 Too Many Requests"));
+        assert!(is_rate_limit_error("ThrottlingException: Rate exceeded"));
+        assert!(is_rate_limit_error("provider rate limit reached"));
+        assert!(!is_rate_limit_error("500 Internal Server Error"));
+    }
+}
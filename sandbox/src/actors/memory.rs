@@ -24,9 +24,10 @@ use async_trait::async_trait;
 use ractor::{Actor, ActorProcessingErr, ActorRef, RpcReplyPort};
 use sha2::{Digest, Sha256};
 
-use shared_types::{CitationRef, ContextItem, ContextSnapshot};
+use shared_types::{CitationRef, ContextItem, ContextSnapshot, QwyDocument};
 
 use crate::actors::event_store::EventStoreMsg;
+use crate::paths::{normalize_document_path, DocumentPathError};
 
 const LEGACY_EMBEDDING_DIM: usize = 384;
 
@@ -298,6 +299,34 @@ pub struct IngestRequest {
     pub content: String,
 }
 
+impl IngestRequest {
+    /// One `version_snapshots` request per embeddable block of a `.qwy`
+    /// document. Private blocks (and anything under them) are left out; see
+    /// [`QwyDocument::embeddable_blocks`].
+    pub fn for_qwy_blocks(document: &QwyDocument) -> Vec<Self> {
+        document
+            .embeddable_blocks()
+            .into_iter()
+            .map(|block| Self {
+                item_id: block.block_id.0.clone(),
+                collection: CollectionKind::VersionSnapshots,
+                source_ref: document.header.document_id.clone(),
+                content: block.content.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Stored form of a `source_ref`: URLs are kept as given, anything else is
+/// a document path or an id and is normalized so one document has one key.
+fn source_ref_key(source_ref: &str) -> Result<String, DocumentPathError> {
+    if source_ref.contains("://") {
+        Ok(source_ref.to_string())
+    } else {
+        normalize_document_path(source_ref)
+    }
+}
+
 #[derive(Debug)]
 pub struct ArtifactSearchResult {
     pub items: Vec<ContextItem>,
@@ -398,7 +427,17 @@ impl Actor for MemoryActor {
     ) -> Result<(), ActorProcessingErr> {
         match message {
             // ── Ingest ────────────────────────────────────────────────────────
-            MemoryMsg::Ingest { req, reply } => {
+            MemoryMsg::Ingest { mut req, reply } => {
+                match source_ref_key(&req.source_ref) {
+                    Ok(source_ref) => req.source_ref = source_ref,
+                    Err(e) => {
+                        tracing::warn!("MemoryActor ingest rejected: {e}");
+                        if let Some(r) = reply {
+                            let _ = r.send(false);
+                        }
+                        return Ok(());
+                    }
+                }
                 let inner = Arc::clone(&state.inner);
                 let inserted = tokio::task::spawn_blocking(move || {
                     let guard = inner.lock().expect("MemoryInner lock poisoned");
@@ -4,12 +4,15 @@ pub mod desktop;
 pub mod event_bus;
 #[cfg(test)]
 mod event_bus_test;
+pub mod event_encryption;
 pub mod event_relay;
 pub mod event_store;
 pub mod harness_actor;
+pub mod llm_scheduler;
 pub mod memory;
 pub mod model_config;
 pub mod researcher;
+pub mod telemetry_gate;
 pub mod terminal;
 pub mod writer;
 
@@ -1,3 +1,4 @@
+use crate::actors::llm_scheduler::{LlmCallBudget, LlmCallTarget};
 use crate::baml_client::ClientRegistry;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -41,11 +42,36 @@ pub enum ProviderConfig {
     },
 }
 
+impl ProviderConfig {
+    /// Endpoint whose rate limits this provider shares with other models.
+    pub fn endpoint_key(&self) -> String {
+        match self {
+            Self::AwsBedrock { region, .. } => format!("aws-bedrock/{region}"),
+            Self::AnthropicCompatible { base_url, .. } | Self::OpenAiGeneric { base_url, .. } => {
+                base_url.trim_end_matches('/').to_string()
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ModelConfig {
     pub id: String,
     pub name: String,
     pub provider: ProviderConfig,
+    pub call_budget: LlmCallBudget,
+    /// Input token limit, when the catalog declares one.
+    pub context_window: Option<u32>,
+}
+
+impl ModelConfig {
+    /// Scheduler target for calls to this model.
+    pub fn call_target(&self) -> LlmCallTarget {
+        LlmCallTarget {
+            endpoint: self.provider.endpoint_key(),
+            budget: self.call_budget,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -108,6 +134,12 @@ pub struct ModelCatalogEntry {
     pub api_key_env: Option<String>,
     pub headers: Option<HashMap<String, String>>,
     pub aliases: Option<Vec<String>>,
+    /// Max in-flight calls to this model's provider endpoint.
+    pub max_concurrency: Option<usize>,
+    /// Max call starts per minute to this model's provider endpoint.
+    pub requests_per_minute: Option<u32>,
+    /// Input token limit for this model.
+    pub context_window: Option<u32>,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
@@ -147,6 +179,22 @@ impl ModelRegistry {
         self.configs.get(model_id)
     }
 
+    /// Scheduler target for a model id, falling back to a default budget
+    /// keyed by the id itself for models outside the catalog.
+    pub fn call_target(&self, model_id: &str) -> LlmCallTarget {
+        self.get(model_id)
+            .map(ModelConfig::call_target)
+            .unwrap_or_else(|| LlmCallTarget {
+                endpoint: model_id.to_string(),
+                budget: LlmCallBudget::default(),
+            })
+    }
+
+    /// Input token limit for a model id, if the catalog declares one.
+    pub fn context_window(&self, model_id: &str) -> Option<u32> {
+        self.get(model_id).and_then(|config| config.context_window)
+    }
+
     pub fn available_model_ids(&self) -> Vec<String> {
         let mut ids: Vec<String> = self.configs.keys().cloned().collect();
         ids.sort();
@@ -398,7 +446,7 @@ fn built_in_model_catalog() -> ModelCatalog {
     })
 }
 
-fn find_default_config_path(relative_path: &str) -> Option<PathBuf> {
+pub(crate) fn find_default_config_path(relative_path: &str) -> Option<PathBuf> {
     let mut current = std::env::current_dir().ok()?;
     loop {
         let candidate = current.join(relative_path);
@@ -725,10 +773,22 @@ fn model_config_from_catalog_entry(id: &str, entry: &ModelCatalogEntry) -> Optio
         }
     };
 
+    let defaults = LlmCallBudget::default();
     Some(ModelConfig {
         id: id.to_string(),
         name: entry.name.clone().unwrap_or_else(|| id.to_string()),
         provider,
+        call_budget: LlmCallBudget {
+            max_concurrency: entry
+                .max_concurrency
+                .unwrap_or(defaults.max_concurrency)
+                .max(1),
+            requests_per_minute: entry
+                .requests_per_minute
+                .unwrap_or(defaults.requests_per_minute)
+                .max(1),
+        },
+        context_window: entry.context_window,
     })
 }
 
@@ -916,6 +976,8 @@ aliases = ["GLM5", "ZaiGLM5"]
                 model: "test-model".to_string(),
                 headers: HashMap::new(),
             },
+            call_budget: LlmCallBudget::default(),
+            context_window: None,
         };
         let missing = create_client_registry_for_config(&config, &["ClaudeBedrock"]);
         assert!(matches!(missing, Err(ModelConfigError::MissingApiKey(_))));
@@ -932,6 +994,8 @@ aliases = ["GLM5", "ZaiGLM5"]
                 model: "test-model".to_string(),
                 headers: HashMap::new(),
             },
+            call_budget: LlmCallBudget::default(),
+            context_window: None,
         };
         let result = create_client_registry_for_config(&config, &["ClaudeBedrock"]);
         assert!(result.is_ok());
@@ -1302,6 +1366,8 @@ aliases = ["ZaiGLM47Flash"]
                 model: "mercury-2".to_string(),
                 headers: HashMap::new(),
             },
+            call_budget: LlmCallBudget::default(),
+            context_window: None,
         };
 
         let result = create_client_registry_for_config(&config, &["ManagedNoGateway"]);
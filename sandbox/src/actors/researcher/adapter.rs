@@ -18,6 +18,7 @@
 use async_trait::async_trait;
 use ractor::ActorRef;
 use serde::Deserialize;
+use shared_types::EventTopic;
 use std::collections::HashSet;
 use std::path::PathBuf;
 use tokio::sync::mpsc;
@@ -38,11 +39,11 @@ use crate::baml_client::types::{
 };
 
 use super::{
-    providers, ResearcherFetchUrlRequest, ResearcherProgress, ResearcherState,
+    freshness, providers, ResearcherFetchUrlRequest, ResearcherProgress, ResearcherState,
     ResearcherWebSearchRequest,
 };
 
-use crate::paths::sandbox_root;
+use crate::paths::{normalize_document_path, same_document_path, sandbox_root};
 
 /// Validate path is within sandbox
 fn validate_sandbox_path(user_path: &str) -> Result<PathBuf, String> {
@@ -73,7 +74,8 @@ fn validate_sandbox_path(user_path: &str) -> Result<PathBuf, String> {
 const RUN_DOC_PATTERN: &str = "conductor/runs/";
 
 fn is_run_document_path(path: &str) -> bool {
-    path.starts_with(RUN_DOC_PATTERN) && path.ends_with("/draft.md")
+    normalize_document_path(path)
+        .is_ok_and(|path| path.starts_with(RUN_DOC_PATTERN) && path.ends_with("/draft.md"))
 }
 
 fn tool_call_name(tool_call: &AgentToolCall) -> &str {
@@ -175,12 +177,13 @@ impl ResearcherAdapter {
     }
 
     /// Emit event to EventStore
-    fn emit_event(&self, event_type: &str, payload: serde_json::Value) {
+    fn emit_event(&self, topic: EventTopic, payload: serde_json::Value) {
         let event = AppendEvent {
-            event_type: event_type.to_string(),
+            event_type: topic.to_string(),
             payload,
             actor_id: self.state.researcher_id.clone(),
             user_id: self.state.user_id.clone(),
+            idempotency_key: None,
         };
         let _ = self
             .state
@@ -198,7 +201,10 @@ impl ResearcherAdapter {
             "content_excerpt": content_excerpt.chars().take(500).collect::<String>(),
             "timestamp": chrono::Utc::now().to_rfc3339(),
         });
-        self.emit_event("worker.task.document_update", payload);
+        self.emit_event(
+            EventTopic::Custom("worker.task.document_update".to_string()),
+            payload,
+        );
     }
 
     fn resolve_writer_section(section_hint: Option<&str>) -> String {
@@ -1015,7 +1021,11 @@ Guidelines:
                 .await;
 
                 let elapsed = start_time.elapsed().as_millis() as u64;
-                let citations = providers::merge_citations(&outputs);
+                let citations = freshness::apply_freshness(
+                    providers::merge_citations(&outputs),
+                    request.time_range.as_deref(),
+                    chrono::Utc::now(),
+                );
                 let mut seen = HashSet::new();
                 let observed_source_refs: Vec<String> = citations
                     .iter()
@@ -1029,7 +1039,7 @@ Guidelines:
                 if !citations.is_empty() {
                     if let Some(content) = Self::summarize_research_scan(&query, &citations) {
                         self.emit_event(
-                            "researcher.auto_pulse.search",
+                            EventTopic::Custom("researcher.auto_pulse.search".to_string()),
                             serde_json::json!({
                                 "query": &query,
                                 "citation_count": citations.len(),
@@ -1133,10 +1143,11 @@ Guidelines:
                                 concepts
                             );
                             self.emit_event(
-                                "researcher.auto_pulse.fetch",
+                                EventTopic::Custom("researcher.auto_pulse.fetch".to_string()),
                                 serde_json::json!({
                                     "url": &result.final_url,
                                     "source_id": &source_id,
+                                    "snapshot_ref": &result.snapshot_ref,
                                     "content": content,
                                 }),
                             );
@@ -1149,6 +1160,7 @@ Guidelines:
                             "content_type": result.content_type,
                             "content_excerpt": result.content_excerpt,
                             "content_length": result.content_length,
+                            "snapshot_ref": result.snapshot_ref,
                         });
 
                         Ok(ToolExecution {
@@ -1259,7 +1271,7 @@ Guidelines:
                     || self
                         .run_document_path()
                         .as_ref()
-                        .map(|p| p == path)
+                        .map(|p| same_document_path(p, path))
                         .unwrap_or(false);
                 if is_run_doc_path && self.has_writer_document_context() {
                     let elapsed = start_time.elapsed().as_millis() as u64;
@@ -1366,7 +1378,7 @@ Guidelines:
                     || self
                         .run_document_path()
                         .as_ref()
-                        .map(|p| p == path)
+                        .map(|p| same_document_path(p, path))
                         .unwrap_or(false);
                 if is_run_doc_path && self.has_writer_document_context() {
                     let elapsed = start_time.elapsed().as_millis() as u64;
@@ -1501,7 +1513,7 @@ Guidelines:
             "report": &report,
             "timestamp": chrono::Utc::now().to_rfc3339(),
         });
-        self.emit_event("worker.report.received", payload);
+        self.emit_event(EventTopic::WorkerReportReceived, payload);
 
         if let Some(tx) = &self.progress_tx {
             let _ = tx.send(ResearcherProgress {
@@ -1547,7 +1559,7 @@ Guidelines:
             "model_used": &progress.model_used,
             "timestamp": &progress.timestamp,
         });
-        self.emit_event("worker.task.progress", payload);
+        self.emit_event(EventTopic::WorkerTaskProgress, payload);
 
         match progress.phase.as_str() {
             "started" => self.writer_set_state(SectionState::Running).await,
@@ -1,9 +1,12 @@
 use chrono::Utc;
+use shared_types::{
+    EventTopic, ResearchTaskCompletedPayload, ResearchTaskFailedPayload, ResearchTaskStartedPayload,
+};
 use tokio::sync::mpsc;
 
 use crate::actors::event_store::{AppendEvent, EventStoreMsg};
 
-use super::{ResearcherProgress, ResearcherState};
+use super::{ResearchTask, ResearcherError, ResearcherProgress, ResearcherResult, ResearcherState};
 
 pub(crate) fn emit_progress(
     state: &ResearcherState,
@@ -40,13 +43,92 @@ pub(crate) fn emit_progress(
         "timestamp": timestamp,
     });
 
+    append(state, EventTopic::WorkerTaskProgress, payload);
+}
+
+fn append(state: &ResearcherState, topic: EventTopic, payload: serde_json::Value) {
     let event = AppendEvent {
-        event_type: "worker.task.progress".to_string(),
+        event_type: topic.to_string(),
         payload,
         actor_id: state.researcher_id.clone(),
         user_id: state.user_id.clone(),
+        idempotency_key: None,
     };
     let _ = state
         .event_store
         .send_message(EventStoreMsg::AppendAsync { event });
 }
+
+pub(crate) fn emit_task_started(
+    state: &ResearcherState,
+    task: &ResearchTask,
+    objective: &str,
+    run_id: Option<&str>,
+    call_id: Option<&str>,
+) {
+    let payload = serde_json::to_value(ResearchTaskStartedPayload {
+        task_id: task.task_id.clone(),
+        researcher_id: state.researcher_id.clone(),
+        objective: objective.to_string(),
+        run_id: run_id.map(ToString::to_string),
+        call_id: call_id.map(ToString::to_string),
+        rerun_of: task.rerun_of.clone(),
+        batch_id: task.batch_id.clone(),
+        desktop_id: task.desktop_id.clone(),
+        timestamp: Utc::now().to_rfc3339(),
+    })
+    .unwrap_or(serde_json::Value::Null);
+    append(state, EventTopic::ResearchTaskStarted, payload);
+}
+
+fn emit_task_completed(
+    state: &ResearcherState,
+    task: &ResearchTask,
+    run_id: Option<String>,
+    result: &ResearcherResult,
+) {
+    let payload = serde_json::to_value(ResearchTaskCompletedPayload {
+        task_id: task.task_id.clone(),
+        researcher_id: state.researcher_id.clone(),
+        run_id,
+        success: result.success,
+        summary: result.summary.clone(),
+        provider_used: result.provider_used.clone(),
+        model_used: result.model_used.clone(),
+        citations: result.citations.clone(),
+        provider_calls: result.provider_calls.clone(),
+        timestamp: Utc::now().to_rfc3339(),
+    })
+    .unwrap_or(serde_json::Value::Null);
+    append(state, EventTopic::ResearchTaskCompleted, payload);
+}
+
+fn emit_task_failed(
+    state: &ResearcherState,
+    task: &ResearchTask,
+    run_id: Option<String>,
+    error: &ResearcherError,
+) {
+    let payload = serde_json::to_value(ResearchTaskFailedPayload {
+        task_id: task.task_id.clone(),
+        researcher_id: state.researcher_id.clone(),
+        run_id,
+        error: error.to_string(),
+        timestamp: Utc::now().to_rfc3339(),
+    })
+    .unwrap_or(serde_json::Value::Null);
+    append(state, EventTopic::ResearchTaskFailed, payload);
+}
+
+/// Record the outcome of a task as `research.task.completed` or `.failed`.
+pub(crate) fn emit_task_finished(
+    state: &ResearcherState,
+    task: &ResearchTask,
+    run_id: Option<String>,
+    result: &Result<ResearcherResult, ResearcherError>,
+) {
+    match result {
+        Ok(research) => emit_task_completed(state, task, run_id, research),
+        Err(error) => emit_task_failed(state, task, run_id, error),
+    }
+}
@@ -0,0 +1,200 @@
+//! Freshness filtering for search citations.
+//!
+//! Providers honour `time_range` loosely, and each reports dates its own way:
+//! Exa and Tavily send ISO or RFC 2822 timestamps, Brave a human "age" such
+//! as "3 days ago" or "August 12, 2024". Every citation gets `age_days` when
+//! its date can be read. With a `time_range`, dated citations older than the
+//! range are dropped, and undated ones are kept after the dated ones.
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
+use shared_types::ResearchCitation;
+
+/// Oldest a citation may be for a `time_range` ("day", "week", "month",
+/// "year", or Brave's "pd"/"pw"/"pm"/"py").
+pub(crate) fn max_age_for_time_range(time_range: Option<&str>) -> Option<Duration> {
+    match time_range?.trim().to_ascii_lowercase().as_str() {
+        "day" | "d" | "pd" => Some(Duration::days(1)),
+        "week" | "w" | "pw" => Some(Duration::days(7)),
+        "month" | "m" | "pm" => Some(Duration::days(31)),
+        "year" | "y" | "py" => Some(Duration::days(366)),
+        _ => None,
+    }
+}
+
+/// Read a provider date: a timestamp, a calendar date, or a relative age.
+pub(crate) fn parse_published_at(raw: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return None;
+    }
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(raw) {
+        return Some(parsed.with_timezone(&Utc));
+    }
+    if let Ok(parsed) = DateTime::parse_from_rfc2822(raw) {
+        return Some(parsed.with_timezone(&Utc));
+    }
+    for format in ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S"] {
+        if let Ok(parsed) = NaiveDateTime::parse_from_str(raw, format) {
+            return Some(parsed.and_utc());
+        }
+    }
+    for format in ["%Y-%m-%d", "%B %d, %Y", "%b %d, %Y", "%d %B %Y", "%d %b %Y"] {
+        if let Ok(parsed) = NaiveDate::parse_from_str(raw, format) {
+            return parsed.and_hms_opt(0, 0, 0).map(|date| date.and_utc());
+        }
+    }
+    parse_relative_age(raw).map(|age| now - age)
+}
+
+/// "3 days ago", "1 hour ago", "yesterday".
+fn parse_relative_age(raw: &str) -> Option<Duration> {
+    let lower = raw.to_ascii_lowercase();
+    match lower.as_str() {
+        "today" | "just now" => return Some(Duration::zero()),
+        "yesterday" => return Some(Duration::days(1)),
+        _ => {}
+    }
+    let mut words = lower.strip_suffix(" ago")?.split_whitespace();
+    let count = match words.next()? {
+        "a" | "an" => 1,
+        number => number.parse::<i64>().ok()?,
+    };
+    let unit = words.next()?;
+    if words.next().is_some() {
+        return None;
+    }
+    let unit_duration = match unit.trim_end_matches('s') {
+        "second" | "sec" => Duration::seconds(1),
+        "minute" | "min" => Duration::minutes(1),
+        "hour" | "hr" => Duration::hours(1),
+        "day" => Duration::days(1),
+        "week" => Duration::weeks(1),
+        "month" => Duration::days(30),
+        "year" => Duration::days(365),
+        _ => return None,
+    };
+    unit_duration.checked_mul(count.try_into().ok()?)
+}
+
+/// Annotate `age_days` and apply the `time_range` cutoff.
+pub(crate) fn apply_freshness(
+    citations: Vec<ResearchCitation>,
+    time_range: Option<&str>,
+    now: DateTime<Utc>,
+) -> Vec<ResearchCitation> {
+    let max_age = max_age_for_time_range(time_range);
+    let mut dated = Vec::new();
+    let mut undated = Vec::new();
+    for mut citation in citations {
+        let age = citation
+            .published_at
+            .as_deref()
+            .and_then(|raw| parse_published_at(raw, now))
+            .map(|published| (now - published).max(Duration::zero()));
+        citation.age_days = age.map(|age| u32::try_from(age.num_days()).unwrap_or(u32::MAX));
+        match (age, max_age) {
+            (Some(age), Some(max_age)) if age > max_age => {}
+            (None, Some(_)) => undated.push(citation),
+            _ => dated.push(citation),
+        }
+    }
+    dated.extend(undated);
+    dated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap()
+    }
+
+    fn citation(url: &str, published_at: Option<&str>) -> ResearchCitation {
+        ResearchCitation {
+            id: url.to_string(),
+            provider: "test".to_string(),
+            title: url.to_string(),
+            url: url.to_string(),
+            snippet: String::new(),
+            published_at: published_at.map(str::to_string),
+            score: None,
+            age_days: None,
+        }
+    }
+
+    #[test]
+    fn parses_provider_date_formats() {
+        let now = now();
+        let day = |y, m, d| Utc.with_ymd_and_hms(y, m, d, 0, 0, 0).unwrap();
+        let cases = [
+            (
+                "2026-03-08T09:30:00Z",
+                Utc.with_ymd_and_hms(2026, 3, 8, 9, 30, 0).unwrap(),
+            ),
+            (
+                "Sun, 08 Mar 2026 09:30:00 GMT",
+                Utc.with_ymd_and_hms(2026, 3, 8, 9, 30, 0).unwrap(),
+            ),
+            (
+                "2026-03-08T09:30:00.000",
+                Utc.with_ymd_and_hms(2026, 3, 8, 9, 30, 0).unwrap(),
+            ),
+            ("2026-03-08", day(2026, 3, 8)),
+            ("August 12, 2024", day(2024, 8, 12)),
+            ("3 days ago", now - Duration::days(3)),
+            ("an hour ago", now - Duration::hours(1)),
+            ("yesterday", now - Duration::days(1)),
+        ];
+        for (raw, expected) in cases {
+            assert_eq!(parse_published_at(raw, now), Some(expected), "{raw}");
+        }
+        assert_eq!(parse_published_at("recently", now), None);
+        assert_eq!(parse_published_at("", now), None);
+    }
+
+    #[test]
+    fn recent_time_range_drops_old_results_and_keeps_undated_last() {
+        let citations = vec![
+            citation("https://example.com/undated", None),
+            citation("https://example.com/old", Some("2024-01-15")),
+            citation("https://example.com/fresh", Some("2 days ago")),
+            citation("https://example.com/garbled", Some("recently")),
+            citation(
+                "https://example.com/this-week",
+                Some("2026-03-04T08:00:00Z"),
+            ),
+        ];
+
+        let filtered = apply_freshness(citations.clone(), Some("week"), now());
+        let urls: Vec<&str> = filtered.iter().map(|c| c.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://example.com/fresh",
+                "https://example.com/this-week",
+                "https://example.com/undated",
+                "https://example.com/garbled",
+            ]
+        );
+        assert_eq!(filtered[0].age_days, Some(2));
+        assert_eq!(filtered[1].age_days, Some(6));
+        assert_eq!(filtered[2].age_days, None);
+
+        let day = apply_freshness(citations.clone(), Some("day"), now());
+        assert!(day.iter().all(|c| c.age_days.is_none()));
+    }
+
+    #[test]
+    fn without_time_range_only_annotates_age() {
+        let citations = vec![
+            citation("https://example.com/undated", None),
+            citation("https://example.com/old", Some("2024-01-15")),
+        ];
+        let annotated = apply_freshness(citations, None, now());
+        assert_eq!(annotated[0].url, "https://example.com/undated");
+        assert_eq!(annotated[1].age_days, Some(785));
+        assert_eq!(max_age_for_time_range(Some("decade")), None);
+    }
+}
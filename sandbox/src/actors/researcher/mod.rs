@@ -8,7 +8,9 @@
 
 mod adapter;
 mod events;
+pub(crate) mod freshness;
 pub(crate) mod providers;
+mod snapshots;
 
 // Policy module kept for backward compatibility with BAML types
 // The researcher now uses the unified agent harness instead
@@ -28,6 +30,7 @@ use crate::actors::writer::{
 use crate::observability::llm_trace::LlmTraceEmitter;
 
 pub use adapter::ResearcherAdapter;
+pub use shared_types::{ResearchCitation, ResearchProviderCall};
 
 #[derive(Debug, Default)]
 pub struct ResearcherActor;
@@ -72,6 +75,25 @@ pub enum ResearcherMsg {
         run_id: Option<String>,
         call_id: Option<String>,
     },
+    /// Research task started from the Research app; `task_id` identifies its
+    /// `research.task.*` events.
+    RunResearchTask {
+        task_id: String,
+        objective: String,
+        rerun_of: Option<String>,
+        max_rounds: Option<u8>,
+        run_id: Option<String>,
+        desktop_id: Option<String>,
+    },
+    /// Batch of Research app tasks run concurrently, as `(task_id, objective)`
+    /// pairs. `max_rounds` is the round budget of the whole batch; each task
+    /// gets an even share of it.
+    RunResearchBatch {
+        batch_id: String,
+        tasks: Vec<(String, String)>,
+        max_rounds: u16,
+        desktop_id: Option<String>,
+    },
     RunWebSearchTool {
         request: ResearcherWebSearchRequest,
         progress_tx: Option<mpsc::UnboundedSender<ResearcherProgress>>,
@@ -125,26 +147,66 @@ pub struct ResearcherFetchUrlResult {
     pub content_excerpt: String,
     pub content_length: usize,
     pub success: bool,
+    /// Content-addressed reference to the stored raw body, if it was saved.
+    #[serde(default)]
+    pub snapshot_ref: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ResearchCitation {
-    pub id: String,
-    pub provider: String,
-    pub title: String,
-    pub url: String,
-    pub snippet: String,
-    pub published_at: Option<String>,
-    pub score: Option<f64>,
+impl ResearcherFetchUrlResult {
+    /// Local record of this fetch, or `None` if the fetch did not succeed.
+    pub fn external_content_record(
+        &self,
+        fetched_by: &str,
+        run_id: Option<&str>,
+    ) -> Option<shared_types::ExternalContentRecord> {
+        use sha2::{Digest, Sha256};
+
+        if !self.success {
+            return None;
+        }
+        let url = if self.final_url.trim().is_empty() {
+            self.url.clone()
+        } else {
+            self.final_url.clone()
+        };
+        Some(shared_types::ExternalContentRecord {
+            content_id: ulid::Ulid::new().to_string(),
+            domain: reqwest::Url::parse(&url)
+                .ok()
+                .and_then(|parsed| parsed.host_str().map(ToString::to_string)),
+            url,
+            content_hash: hex::encode(Sha256::digest(self.content_excerpt.as_bytes())),
+            fetched_at: chrono::Utc::now(),
+            fetched_by: fetched_by.to_string(),
+            run_id: run_id.map(ToString::to_string),
+            title: None,
+            content_text: self.content_excerpt.clone(),
+            chunk_strategy: crate::chunking::ChunkStrategy::Full.as_str().to_string(),
+            snapshot_ref: self.snapshot_ref.clone(),
+            csl_metadata: None,
+            private: false,
+        })
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ResearchProviderCall {
-    pub provider: String,
-    pub latency_ms: u64,
-    pub result_count: usize,
-    pub succeeded: bool,
-    pub error: Option<String>,
+/// Identity of a research task recorded as `research.task.*` events.
+#[derive(Debug, Clone)]
+pub(crate) struct ResearchTask {
+    pub(crate) task_id: String,
+    pub(crate) rerun_of: Option<String>,
+    pub(crate) batch_id: Option<String>,
+    pub(crate) desktop_id: Option<String>,
+}
+
+impl ResearchTask {
+    fn new() -> Self {
+        Self {
+            task_id: ulid::Ulid::new().to_string(),
+            rerun_of: None,
+            batch_id: None,
+            desktop_id: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -292,6 +354,7 @@ impl Actor for ResearcherActor {
                         writer_actor_for_run,
                         run_id_for_run,
                         call_id_for_run,
+                        Some(ResearchTask::new()),
                     )
                     .await;
                 Self::emit_writer_completion(
@@ -324,10 +387,82 @@ impl Actor for ResearcherActor {
                         writer_actor.clone(),
                         run_id.clone(),
                         call_id.clone(),
+                        Some(ResearchTask::new()),
                     )
                     .await;
                 Self::emit_writer_completion(writer_actor, run_id, call_id, result);
             }
+            ResearcherMsg::RunResearchTask {
+                task_id,
+                objective,
+                rerun_of,
+                max_rounds,
+                run_id,
+                desktop_id,
+            } => {
+                let _ = self
+                    .run_with_harness(
+                        state,
+                        objective,
+                        None,
+                        max_rounds,
+                        None,
+                        None,
+                        None,
+                        run_id,
+                        None,
+                        Some(ResearchTask {
+                            task_id,
+                            rerun_of,
+                            batch_id: None,
+                            desktop_id,
+                        }),
+                    )
+                    .await;
+            }
+            ResearcherMsg::RunResearchBatch {
+                batch_id,
+                tasks,
+                max_rounds,
+                desktop_id,
+            } => {
+                let share = batch_round_share(max_rounds, tasks.len());
+                let tasks: Vec<(ResearchTask, String)> = tasks
+                    .into_iter()
+                    .map(|(task_id, objective)| {
+                        let task = ResearchTask {
+                            task_id,
+                            rerun_of: None,
+                            batch_id: Some(batch_id.clone()),
+                            desktop_id: desktop_id.clone(),
+                        };
+                        (task, objective)
+                    })
+                    .collect();
+                // The whole batch is visible before any of its loops starts.
+                let state: &ResearcherState = state;
+                for (task, objective) in &tasks {
+                    events::emit_task_started(state, task, objective, None, None);
+                }
+                let runs = tasks.into_iter().map(|(task, objective)| async move {
+                    let result = self
+                        .run_harness_loop(
+                            state,
+                            task.task_id.clone(),
+                            objective,
+                            None,
+                            Some(share),
+                            None,
+                            None,
+                            None,
+                            None,
+                            None,
+                        )
+                        .await;
+                    events::emit_task_finished(state, &task, None, &result);
+                });
+                futures_util::future::join_all(runs).await;
+            }
             ResearcherMsg::RunWebSearchTool {
                 request,
                 progress_tx,
@@ -344,6 +479,7 @@ impl Actor for ResearcherActor {
                         None,
                         None,
                         None,
+                        None,
                     )
                     .await;
                 let _ = reply.send(result);
@@ -389,6 +525,12 @@ impl Actor for ResearcherActor {
     }
 }
 
+/// Rounds each of `tasks` batch tasks gets from a shared `budget`.
+fn batch_round_share(budget: u16, tasks: usize) -> u8 {
+    let share = budget as usize / tasks.max(1);
+    share.clamp(1, u8::MAX as usize) as u8
+}
+
 impl ResearcherActor {
     fn emit_writer_completion(
         writer_actor: Option<ractor::ActorRef<WriterMsg>>,
@@ -431,6 +573,53 @@ impl ResearcherActor {
         writer_actor: Option<ractor::ActorRef<crate::actors::writer::WriterMsg>>,
         run_id: Option<String>,
         call_id: Option<String>,
+        task: Option<ResearchTask>,
+    ) -> Result<ResearcherResult, ResearcherError> {
+        let loop_id = task
+            .as_ref()
+            .map(|task| task.task_id.clone())
+            .unwrap_or_else(|| ulid::Ulid::new().to_string());
+        if let Some(task) = &task {
+            events::emit_task_started(
+                state,
+                task,
+                &objective,
+                run_id.as_deref(),
+                call_id.as_deref(),
+            );
+        }
+        let result = self
+            .run_harness_loop(
+                state,
+                loop_id,
+                objective,
+                timeout_ms,
+                max_rounds,
+                model_override,
+                progress_tx,
+                writer_actor,
+                run_id.clone(),
+                call_id,
+            )
+            .await;
+        if let Some(task) = &task {
+            events::emit_task_finished(state, task, run_id, &result);
+        }
+        result
+    }
+
+    async fn run_harness_loop(
+        &self,
+        state: &ResearcherState,
+        loop_id: String,
+        objective: String,
+        timeout_ms: Option<u64>,
+        max_rounds: Option<u8>,
+        model_override: Option<String>,
+        progress_tx: Option<mpsc::UnboundedSender<ResearcherProgress>>,
+        writer_actor: Option<ractor::ActorRef<crate::actors::writer::WriterMsg>>,
+        run_id: Option<String>,
+        call_id: Option<String>,
     ) -> Result<ResearcherResult, ResearcherError> {
         let timeout = timeout_ms.unwrap_or(30_000).clamp(3_000, 120_000);
         // Keep delegated loops bounded by default so simple objectives converge
@@ -468,7 +657,8 @@ impl ResearcherActor {
         );
 
         let agent_result: AgentResult = harness
-            .run(
+            .run_with_loop_id(
+                loop_id,
                 state.researcher_id.clone(),
                 state.user_id.clone(),
                 objective,
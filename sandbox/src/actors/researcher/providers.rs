@@ -222,6 +222,24 @@ pub(crate) async fn fetch_url(
         .await
         .map_err(|e| ResearcherError::ProviderRequest("fetch_url".to_string(), e.to_string()))?;
 
+    let snapshot_ref = if status.is_success() {
+        match super::snapshots::store_snapshot(
+            &crate::paths::sandbox_root(),
+            &body,
+            content_type.as_deref(),
+        )
+        .await
+        {
+            Ok(reference) => Some(reference),
+            Err(e) => {
+                tracing::warn!(url = %url, error = %e, "Failed to store fetch_url snapshot");
+                None
+            }
+        }
+    } else {
+        None
+    };
+
     Ok(ResearcherFetchUrlResult {
         url: url.to_string(),
         final_url,
@@ -230,6 +248,7 @@ pub(crate) async fn fetch_url(
         content_excerpt: extract_text_excerpt(&body, content_type.as_deref(), max_chars),
         content_length: body.len(),
         success: status.is_success(),
+        snapshot_ref,
     })
 }
 
@@ -561,6 +580,7 @@ async fn search_tavily(
                 .and_then(|v| v.as_str())
                 .map(ToString::to_string),
             score: row.get("score").and_then(|v| v.as_f64()),
+            age_days: None,
         });
     }
 
@@ -662,6 +682,7 @@ async fn search_brave(
                 .and_then(|v| v.as_str())
                 .map(ToString::to_string),
             score: None,
+            age_days: None,
         });
     }
 
@@ -786,6 +807,7 @@ async fn search_exa(
                 .and_then(|v| v.as_str())
                 .map(ToString::to_string),
             score,
+            age_days: None,
         });
     }
 
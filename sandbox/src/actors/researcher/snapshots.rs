@@ -0,0 +1,116 @@
+//! Content-addressed snapshots of fetched pages.
+//!
+//! The raw body of every successful `fetch_url` is written once under
+//! `artifacts/snapshots/` in the sandbox root, named by the SHA-256 of its
+//! bytes. The relative path is the snapshot reference carried on
+//! `ExternalContentRecord.snapshot_ref`, so a citation can still be checked
+//! against what was read after the source changes or goes offline.
+
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+pub(crate) const SNAPSHOT_DIR: &str = "artifacts/snapshots";
+
+/// Snapshot reference for `body`: `artifacts/snapshots/{sha256}.{html|txt}`.
+pub(crate) fn snapshot_reference(body: &str, content_type: Option<&str>) -> String {
+    let hash = hex::encode(Sha256::digest(body.as_bytes()));
+    let looks_html = content_type
+        .map(|ct| ct.to_ascii_lowercase().contains("html"))
+        .unwrap_or_else(|| body.contains("<html") || body.contains("<body"));
+    let extension = if looks_html { "html" } else { "txt" };
+    format!("{SNAPSHOT_DIR}/{hash}.{extension}")
+}
+
+/// Absolute path of a snapshot reference inside `root`.
+pub(crate) fn snapshot_path(root: &Path, reference: &str) -> PathBuf {
+    root.join(reference)
+}
+
+/// Store `body` under `root` and return its reference. Identical bodies share
+/// one file, so an existing snapshot is left untouched.
+pub(crate) async fn store_snapshot(
+    root: &Path,
+    body: &str,
+    content_type: Option<&str>,
+) -> Result<String, std::io::Error> {
+    let reference = snapshot_reference(body, content_type);
+    let path = snapshot_path(root, &reference);
+    if tokio::fs::try_exists(&path).await.unwrap_or(false) {
+        return Ok(reference);
+    }
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    // Write-then-rename so a concurrent reader never sees a partial snapshot.
+    let tmp = path.with_extension(format!("tmp-{}", ulid::Ulid::new()));
+    tokio::fs::write(&tmp, body).await?;
+    tokio::fs::rename(&tmp, &path).await?;
+    Ok(reference)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actors::researcher::{providers, ResearcherFetchUrlRequest};
+    use axum::{response::Html, routing::get, Router};
+
+    #[tokio::test]
+    async fn fetched_content_is_snapshotted_and_referenced() {
+        let marker = ulid::Ulid::new().to_string();
+        let page = format!(
+            "<html><head><title>Snapshot {marker}</title></head><body><p>Cited text {marker}.</p></body></html>"
+        );
+        let served = page.clone();
+        let app = Router::new().route("/page", get(move || async move { Html(served) }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        let result = providers::fetch_url(&ResearcherFetchUrlRequest {
+            url: format!("http://{addr}/page"),
+            timeout_ms: None,
+            max_chars: None,
+        })
+        .await
+        .expect("fetch");
+
+        let reference = result.snapshot_ref.clone().expect("snapshot_ref");
+        assert_eq!(reference, snapshot_reference(&page, Some("text/html")));
+        let path = snapshot_path(&crate::paths::sandbox_root(), &reference);
+        let stored = tokio::fs::read_to_string(&path).await.expect("snapshot");
+        assert_eq!(stored, page);
+
+        let record = result
+            .external_content_record("loop-snapshot", Some("run-snapshot"))
+            .expect("record for successful fetch");
+        assert_eq!(record.snapshot_ref.as_deref(), Some(reference.as_str()));
+        assert_eq!(record.domain.as_deref(), Some("127.0.0.1"));
+        assert!(record.content_text.contains(&marker));
+
+        let _ = tokio::fs::remove_file(&path).await;
+    }
+
+    #[tokio::test]
+    async fn identical_bodies_share_one_snapshot() {
+        let root = std::env::temp_dir().join(format!("snapshots-{}", ulid::Ulid::new()));
+        let first = store_snapshot(&root, "plain body", Some("text/plain"))
+            .await
+            .expect("store");
+        let second = store_snapshot(&root, "plain body", Some("text/plain"))
+            .await
+            .expect("store again");
+        assert_eq!(first, second);
+        assert!(first.ends_with(".txt"));
+
+        let entries = std::fs::read_dir(root.join(SNAPSHOT_DIR))
+            .expect("snapshot dir")
+            .count();
+        assert_eq!(entries, 1);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
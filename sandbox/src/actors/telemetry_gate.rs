@@ -0,0 +1,442 @@
+//! Per-correlation limits on worker telemetry written to the EventStore.
+//!
+//! Workers report progress with fire-and-forget
+//! [`EventStoreMsg::AppendAsync`](crate::actors::event_store::EventStoreMsg::AppendAsync),
+//! and a busy loop can send hundreds of updates a second for one call. A
+//! store spawned [`with_telemetry`](crate::actors::event_store::EventStoreArguments::with_telemetry)
+//! passes each such event through a [`TelemetryGate`] before writing it:
+//!
+//! - With [`TelemetryPolicy::sample_every`] above 1, progress events are
+//!   sampled per correlation; see [`ProgressSample`].
+//! - At most [`TelemetryPolicy::max_per_second`] telemetry-lane events per
+//!   correlation are written in each one-second window, and the drops are
+//!   reported once per window as a `telemetry.throttled` event.
+//!
+//! Control-lane events are never held back, and neither are `Append`
+//! requests, whose senders wait for the stored event.
+
+use std::collections::{BTreeMap, HashMap};
+
+use shared_types::EventLane;
+
+use crate::actors::conductor::events::parse_event_metadata;
+use crate::actors::event_store::AppendEvent;
+
+/// Length of one telemetry rate-limit window.
+pub const TELEMETRY_WINDOW: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Sampling state of a correlation with no progress for this long is
+/// discarded, held event included.
+const PROGRESS_SAMPLE_IDLE: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+
+/// Limits applied to telemetry appended with `AppendAsync`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TelemetryPolicy {
+    /// Telemetry-lane events written per correlation per second.
+    pub max_per_second: u32,
+    /// Keep 1 in N progress events per correlation. 1 keeps every event.
+    pub sample_every: u32,
+}
+
+impl Default for TelemetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_per_second: 20,
+            sample_every: 1,
+        }
+    }
+}
+
+impl TelemetryPolicy {
+    /// The default policy with `CHOIR_TELEMETRY_MAX_PER_SEC` and
+    /// `CHOIR_TELEMETRY_SAMPLE_EVERY` applied.
+    pub fn from_env() -> Self {
+        let mut policy = Self::default();
+        if let Ok(raw) = std::env::var("CHOIR_TELEMETRY_MAX_PER_SEC") {
+            if let Ok(parsed) = raw.trim().parse::<u32>() {
+                policy.max_per_second = parsed.clamp(1, 1_000);
+            }
+        }
+        if let Ok(raw) = std::env::var("CHOIR_TELEMETRY_SAMPLE_EVERY") {
+            if let Ok(parsed) = raw.trim().parse::<u32>() {
+                policy.sample_every = parsed.clamp(1, 1_000);
+            }
+        }
+        policy
+    }
+}
+
+/// Lane of an event. Events that carry `_meta` declare their lane; otherwise
+/// progress updates are telemetry and everything else is control.
+pub(crate) fn event_lane(topic: &str, payload: &serde_json::Value) -> EventLane {
+    if payload.get("_meta").is_some() {
+        return parse_event_metadata(payload).lane;
+    }
+    if topic.ends_with(".progress") {
+        EventLane::Telemetry
+    } else {
+        EventLane::Control
+    }
+}
+
+/// What an event's telemetry is counted under: its `correlation_id`, else
+/// its `call_id`, `run_id` or `task_id`. Events carrying none are not
+/// limited.
+pub(crate) fn correlation_key(payload: &serde_json::Value) -> Option<&str> {
+    ["correlation_id", "call_id", "run_id", "task_id"]
+        .into_iter()
+        .find_map(|field| payload.get(field).and_then(|value| value.as_str()))
+        .filter(|key| !key.is_empty())
+}
+
+/// Telemetry written for one correlation in the current window.
+#[derive(Debug, Clone)]
+struct TelemetryWindow {
+    started_at: chrono::DateTime<chrono::Utc>,
+    emitted: u32,
+    /// Events dropped this window, by topic.
+    dropped: BTreeMap<String, u64>,
+    actor_id: String,
+    user_id: String,
+    scope: Option<serde_json::Value>,
+}
+
+impl TelemetryWindow {
+    fn is_expired(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        now.signed_duration_since(self.started_at)
+            .to_std()
+            .is_ok_and(|elapsed| elapsed >= TELEMETRY_WINDOW)
+    }
+}
+
+/// Progress sampling state of one correlation.
+///
+/// With sampling at 1 in N, a progress event is kept when it is the first,
+/// when its `phase` differs from the previous one, or when it is the Nth since
+/// the last kept one. The latest dropped event is held and written ahead of
+/// the correlation's terminal event, so the last progress is never lost.
+#[derive(Debug, Clone)]
+struct ProgressSample {
+    seen: u64,
+    /// Progress events dropped since the last kept one.
+    skipped: u32,
+    phase: Option<String>,
+    held: Option<AppendEvent>,
+    updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl ProgressSample {
+    fn new(now: chrono::DateTime<chrono::Utc>) -> Self {
+        Self {
+            seen: 0,
+            skipped: 0,
+            phase: None,
+            held: None,
+            updated_at: now,
+        }
+    }
+
+    /// `event` if it is kept, otherwise `None` and it is held.
+    fn sample(
+        &mut self,
+        every: u32,
+        event: AppendEvent,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Option<AppendEvent> {
+        let phase = event
+            .payload
+            .get("phase")
+            .and_then(|value| value.as_str())
+            .map(ToString::to_string);
+        let first = self.seen == 0;
+        let transition = phase.is_some() && phase != self.phase;
+        self.seen += 1;
+        self.updated_at = now;
+        if phase.is_some() {
+            self.phase = phase;
+        }
+        if first || transition || self.skipped + 1 >= every {
+            self.skipped = 0;
+            self.held = None;
+            return Some(event);
+        }
+        self.skipped += 1;
+        self.held = Some(event);
+        None
+    }
+}
+
+/// Per-correlation sampling and windows of one EventStore.
+#[derive(Debug)]
+pub(crate) struct TelemetryGate {
+    policy: TelemetryPolicy,
+    samples: HashMap<String, ProgressSample>,
+    windows: HashMap<String, TelemetryWindow>,
+    flush_scheduled: bool,
+}
+
+impl TelemetryGate {
+    pub(crate) fn new(policy: TelemetryPolicy) -> Self {
+        Self {
+            policy,
+            samples: HashMap::new(),
+            windows: HashMap::new(),
+            flush_scheduled: false,
+        }
+    }
+
+    /// Apply progress sampling to `event`; returns the events to write, in
+    /// order.
+    ///
+    /// Only telemetry-lane `*.progress` events are sampled. A `*.completed`
+    /// or `*.failed` event ends its correlation's sampling and is preceded by
+    /// the last progress event sampling dropped.
+    pub(crate) fn sample(
+        &mut self,
+        event: AppendEvent,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Vec<AppendEvent> {
+        let every = self.policy.sample_every;
+        if every <= 1 {
+            return vec![event];
+        }
+        let Some(key) = correlation_key(&event.payload).map(ToString::to_string) else {
+            return vec![event];
+        };
+        self.samples.retain(|_, sample| {
+            now.signed_duration_since(sample.updated_at)
+                .to_std()
+                .is_ok_and(|idle| idle < PROGRESS_SAMPLE_IDLE)
+        });
+
+        let topic = event.event_type.as_str();
+        let is_progress = topic.ends_with(".progress")
+            && matches!(event_lane(topic, &event.payload), EventLane::Telemetry);
+        if is_progress {
+            return self
+                .samples
+                .entry(key)
+                .or_insert_with(|| ProgressSample::new(now))
+                .sample(every, event, now)
+                .into_iter()
+                .collect();
+        }
+        if topic.ends_with(".completed") || topic.ends_with(".failed") {
+            if let Some(sample) = self.samples.remove(&key) {
+                return sample.held.into_iter().chain([event]).collect();
+            }
+        }
+        vec![event]
+    }
+
+    /// Count `event` against its correlation's window; false if the window
+    /// is full and the event should be dropped.
+    pub(crate) fn admit(
+        &mut self,
+        event: &AppendEvent,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> bool {
+        if !matches!(
+            event_lane(&event.event_type, &event.payload),
+            EventLane::Telemetry
+        ) {
+            return true;
+        }
+        let Some(key) = correlation_key(&event.payload) else {
+            return true;
+        };
+        let window = self
+            .windows
+            .entry(key.to_string())
+            .or_insert_with(|| TelemetryWindow {
+                started_at: now,
+                emitted: 0,
+                dropped: BTreeMap::new(),
+                actor_id: event.actor_id.clone(),
+                user_id: event.user_id.clone(),
+                scope: event.payload.get("scope").cloned(),
+            });
+        if window.emitted < self.policy.max_per_second {
+            window.emitted += 1;
+            return true;
+        }
+        *window.dropped.entry(event.event_type.clone()).or_default() += 1;
+        false
+    }
+
+    /// End every window started a full window before `now`, returning a
+    /// `telemetry.throttled` summary for each that dropped events.
+    pub(crate) fn end_expired(&mut self, now: chrono::DateTime<chrono::Utc>) -> Vec<AppendEvent> {
+        let mut expired: Vec<(chrono::DateTime<chrono::Utc>, String)> = self
+            .windows
+            .iter()
+            .filter(|(_, window)| window.is_expired(now))
+            .map(|(key, window)| (window.started_at, key.clone()))
+            .collect();
+        expired.sort();
+        expired
+            .into_iter()
+            .filter_map(|(_, key)| {
+                let window = self.windows.remove(&key)?;
+                self.throttled_summary(&key, window)
+            })
+            .collect()
+    }
+
+    /// Whether a window has drops not yet reported.
+    pub(crate) fn has_drops(&self) -> bool {
+        self.windows
+            .values()
+            .any(|window| !window.dropped.is_empty())
+    }
+
+    /// Mark a flush of ended windows as scheduled; false if one already is.
+    pub(crate) fn schedule_flush(&mut self) -> bool {
+        !std::mem::replace(&mut self.flush_scheduled, true)
+    }
+
+    /// Mark the scheduled flush as done.
+    pub(crate) fn flushed(&mut self) {
+        self.flush_scheduled = false;
+    }
+
+    fn throttled_summary(&self, key: &str, window: TelemetryWindow) -> Option<AppendEvent> {
+        if window.dropped.is_empty() {
+            return None;
+        }
+        let dropped: u64 = window.dropped.values().sum();
+        tracing::warn!(
+            correlation_id = key,
+            dropped,
+            actor_id = %window.actor_id,
+            "Throttled telemetry events"
+        );
+        let mut payload = serde_json::json!({
+            "correlation_id": key,
+            "dropped": dropped,
+            "dropped_by_topic": window.dropped,
+            "emitted": window.emitted,
+            "max_per_second": self.policy.max_per_second,
+            "window_started_at": window.started_at.to_rfc3339(),
+            "_meta": {
+                "lane": "telemetry",
+                "importance": "low",
+            },
+        });
+        if let Some(scope) = window.scope {
+            payload["scope"] = scope;
+        }
+        Some(AppendEvent {
+            event_type: shared_types::EVENT_TOPIC_TELEMETRY_THROTTLED.to_string(),
+            payload,
+            actor_id: window.actor_id,
+            user_id: window.user_id,
+            idempotency_key: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(call_id: &str, step: u32) -> AppendEvent {
+        AppendEvent {
+            event_type: shared_types::EVENT_TOPIC_WORKER_TASK_PROGRESS.to_string(),
+            payload: serde_json::json!({
+                "task_id": "task-1",
+                "call_id": call_id,
+                "message": format!("step {step}"),
+            }),
+            actor_id: "worker-1".to_string(),
+            user_id: "user-1".to_string(),
+            idempotency_key: None,
+        }
+    }
+
+    #[test]
+    fn windows_are_per_correlation_and_report_their_drops() {
+        let mut gate = TelemetryGate::new(TelemetryPolicy {
+            max_per_second: 2,
+            ..TelemetryPolicy::default()
+        });
+        let now = chrono::Utc::now();
+
+        let admitted: Vec<bool> = (0..4)
+            .map(|step| gate.admit(&progress("call-a", step), now))
+            .collect();
+        assert_eq!(admitted, vec![true, true, false, false]);
+        assert!(gate.admit(&progress("call-b", 0), now));
+        let completed = AppendEvent {
+            event_type: shared_types::EVENT_TOPIC_WORKER_TASK_COMPLETED.to_string(),
+            ..progress("call-a", 4)
+        };
+        assert!(
+            gate.admit(&completed, now),
+            "control events are never dropped"
+        );
+
+        assert!(gate.end_expired(now).is_empty());
+        let later = now + chrono::Duration::milliseconds(1_000);
+        let summaries = gate.end_expired(later);
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].payload["correlation_id"], "call-a");
+        assert_eq!(summaries[0].payload["dropped"], 2);
+        assert_eq!(summaries[0].actor_id, "worker-1");
+        assert!(!gate.has_drops());
+        assert!(gate.admit(&progress("call-a", 5), later));
+    }
+
+    #[test]
+    fn sampling_keeps_phase_transitions_and_terminal_events() {
+        let mut gate = TelemetryGate::new(TelemetryPolicy {
+            max_per_second: 1_000,
+            sample_every: 3,
+        });
+        let now = chrono::Utc::now();
+        let progress = |step: u32, phase: &str| AppendEvent {
+            payload: serde_json::json!({
+                "task_id": "task-1",
+                "call_id": "call-a",
+                "phase": phase,
+                "message": format!("step {step}"),
+            }),
+            ..progress("call-a", step)
+        };
+        let steps = [
+            (1, "search"),
+            (2, "search"),
+            (3, "search"),
+            (4, "search"),
+            (5, "write"),
+            (6, "write"),
+        ];
+        let mut written = Vec::new();
+        for (step, phase) in steps {
+            written.extend(gate.sample(progress(step, phase), now));
+        }
+        written.extend(gate.sample(
+            AppendEvent {
+                event_type: shared_types::EVENT_TOPIC_WORKER_TASK_COMPLETED.to_string(),
+                payload: serde_json::json!({ "task_id": "task-1", "call_id": "call-a" }),
+                ..progress(7, "write")
+            },
+            now,
+        ));
+
+        let messages: Vec<&str> = written
+            .iter()
+            .map(|event| event.payload["message"].as_str().unwrap_or("terminal"))
+            .collect();
+        // First, every third, the switch to "write", the last progress, then
+        // the terminal event; steps 2 and 3 are dropped.
+        assert_eq!(
+            messages,
+            vec!["step 1", "step 4", "step 5", "step 6", "terminal"]
+        );
+        assert_eq!(
+            written.last().map(|event| event.event_type.as_str()),
+            Some(shared_types::EVENT_TOPIC_WORKER_TASK_COMPLETED)
+        );
+    }
+}
@@ -23,11 +23,12 @@ use serde::Deserialize;
 use std::collections::HashSet;
 use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use tokio::sync::{broadcast, mpsc};
 
 use crate::actors::agent_harness::{
-    AgentHarness, AgentProgress, ExecutionContext, HarnessConfig, HarnessError, ToolExecution,
-    WorkerPort,
+    AgentHarness, AgentProgress, ExecutionContext, HarnessConfig, HarnessError, ObjectiveStatus,
+    ToolExecution, WorkerPort,
 };
 use crate::actors::event_store::EventStoreMsg;
 use crate::actors::model_config::ModelRegistry;
@@ -42,7 +43,7 @@ use crate::baml_client::types::{
 use crate::observability::llm_trace::LlmTraceEmitter;
 
 use shared_types::{
-    WorkerEscalation, WorkerEscalationKind, WorkerEscalationUrgency, WorkerTurnReport,
+    escalation_urgency_for, FailureKind, WorkerEscalation, WorkerEscalationKind, WorkerTurnReport,
     WorkerTurnStatus,
 };
 
@@ -59,6 +60,9 @@ pub struct TerminalAdapter {
     progress_tx: Option<mpsc::UnboundedSender<TerminalAgentProgress>>,
     writer_actor: Option<ActorRef<WriterMsg>>,
     run_id: Option<String>,
+    /// Plan bash calls instead of running them.
+    dry_run: bool,
+    planned_steps: Arc<Mutex<Vec<TerminalPlanStep>>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -106,14 +110,33 @@ impl TerminalAdapter {
             progress_tx,
             writer_actor,
             run_id,
+            dry_run: false,
+            planned_steps: Arc::default(),
         }
     }
 
+    /// Record bash calls as planned steps without executing them.
+    ///
+    /// A dry run never writes to the run document, so writer mode is off.
+    pub fn with_dry_run(mut self) -> Self {
+        self.dry_run = true;
+        self
+    }
+
+    /// Steps recorded by a dry run. The handle stays valid after the adapter
+    /// is moved into a harness.
+    pub fn planned_steps(&self) -> Arc<Mutex<Vec<TerminalPlanStep>>> {
+        self.planned_steps.clone()
+    }
+
     fn has_writer_document_context(&self) -> bool {
-        self.writer_actor.is_some() && self.run_id.is_some()
+        !self.dry_run && self.writer_actor.is_some() && self.run_id.is_some()
     }
 
     fn writer_context(&self) -> Option<(ActorRef<WriterMsg>, String)> {
+        if self.dry_run {
+            return None;
+        }
         Some((self.writer_actor.clone()?, self.run_id.clone()?))
     }
 
@@ -429,6 +452,97 @@ impl TerminalAdapter {
         Ok((combined, output.status.code().unwrap_or(1)))
     }
 
+    /// Record a bash call as a planned step instead of running it.
+    fn plan_bash_call(
+        &self,
+        ctx: &ExecutionContext,
+        command: &str,
+        reasoning: Option<String>,
+    ) -> ToolExecution {
+        let blocked_reason = Self::validate_command_policy(command)
+            .err()
+            .map(|e| e.to_string());
+        let step_index = {
+            let mut steps = self
+                .planned_steps
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            steps.push(TerminalPlanStep {
+                command: command.to_string(),
+                reasoning: reasoning.clone(),
+                blocked_reason: blocked_reason.clone(),
+            });
+            steps.len()
+        };
+
+        self.emit_terminal_progress(
+            "terminal_plan_step",
+            if blocked_reason.is_some() {
+                "terminal agent planned a command the policy would block"
+            } else {
+                "terminal agent planned a command (dry run, not executed)"
+            },
+            reasoning.clone(),
+            Some(command.to_string()),
+            Some(ctx.model_used.clone()),
+            None,
+            None,
+            Some(step_index),
+            Some(ctx.max_steps),
+        );
+        self.emit_agent_step(
+            &ctx.user_id,
+            serde_json::json!({
+                "step_index": step_index,
+                "command": command,
+                "reasoning": reasoning,
+                "dry_run": true,
+                "executed": false,
+                "blocked": blocked_reason.is_some(),
+                "blocked_reason": blocked_reason,
+            }),
+        );
+
+        let output = match &blocked_reason {
+            Some(reason) => format!(
+                "Dry run: not executed. The terminal policy would block this command ({reason}). Plan an allowed alternative or finish."
+            ),
+            None => "Dry run: not executed. Assume it succeeds, plan any remaining steps, then finish.".to_string(),
+        };
+        ToolExecution {
+            tool_name: "bash".to_string(),
+            success: true,
+            output,
+            error: None,
+            execution_time_ms: 0,
+        }
+    }
+
+    /// Append a `terminal.agent.step` event for this terminal and run.
+    fn emit_agent_step(&self, user_id: &str, payload: serde_json::Value) {
+        let Some(event_store) = &self.event_store else {
+            return;
+        };
+        let mut payload = payload;
+        if let Some(obj) = payload.as_object_mut() {
+            obj.insert("terminal_id".to_string(), self.terminal_id.clone().into());
+            obj.insert("run_id".to_string(), self.run_id.clone().into());
+            obj.insert(
+                "timestamp".to_string(),
+                chrono::Utc::now().to_rfc3339().into(),
+            );
+        }
+        let _ = event_store.send_message(EventStoreMsg::AppendAsync {
+            event: crate::actors::event_store::AppendEvent {
+                event_type: shared_types::EventTopic::TerminalAgentStep.to_string(),
+                payload,
+                actor_id: self.terminal_id.clone(),
+                user_id: user_id.to_string(),
+                idempotency_key: None,
+            },
+        });
+    }
+
     fn validate_command_policy(command: &str) -> Result<(), TerminalError> {
         let allowed_prefixes = std::env::var("CHOIR_TERMINAL_ALLOWED_COMMAND_PREFIXES")
             .ok()
@@ -597,6 +711,9 @@ Writer mode contract:
                 let command = bash_call.tool_args.command.as_str();
                 let timeout_ms = 30_000;
                 let start_time = std::time::Instant::now();
+                if self.dry_run {
+                    return Ok(self.plan_bash_call(ctx, command, bash_call.reasoning.clone()));
+                }
 
                 self.emit_terminal_progress(
                     "terminal_tool_call",
@@ -699,10 +816,11 @@ Writer mode contract:
                 "timestamp": chrono::Utc::now().to_rfc3339(),
             });
             let event = crate::actors::event_store::AppendEvent {
-                event_type: "worker.report.received".to_string(),
+                event_type: shared_types::EventTopic::WorkerReportReceived.to_string(),
                 payload,
                 actor_id: ctx.worker_id.clone(),
                 user_id: ctx.user_id.clone(),
+                idempotency_key: None,
             };
             let _ = event_store
                 .send_message(crate::actors::event_store::EventStoreMsg::AppendAsync { event });
@@ -739,11 +857,16 @@ Writer mode contract:
         // Build escalations for blocked commands
         let mut escalations = Vec::new();
         if !success {
+            let failure_kind = if crate::actors::llm_scheduler::is_rate_limit_error(summary) {
+                FailureKind::RateLimit
+            } else {
+                FailureKind::Unknown
+            };
             escalations.push(WorkerEscalation {
                 escalation_id: ulid::Ulid::new().to_string(),
                 kind: WorkerEscalationKind::Blocker,
                 reason: format!("Terminal task failed or blocked: {summary}"),
-                urgency: WorkerEscalationUrgency::Medium,
+                urgency: escalation_urgency_for(failure_kind),
                 options: vec![
                     "retry".to_string(),
                     "escalate".to_string(),
@@ -956,6 +1079,10 @@ pub enum TerminalMsg {
         writer_actor: Option<ActorRef<WriterMsg>>,
         run_id: Option<String>,
         call_id: Option<String>,
+        /// Plan without executing; the result carries the plan.
+        dry_run: bool,
+        /// Commands from an approved dry-run plan, run verbatim without re-planning.
+        approved_plan: Option<Vec<String>>,
         reply: RpcReplyPort<Result<TerminalAgentResult, TerminalError>>,
     },
     RunAgenticTaskDetached {
@@ -967,6 +1094,8 @@ pub enum TerminalMsg {
         writer_actor: Option<ActorRef<WriterMsg>>,
         run_id: Option<String>,
         call_id: Option<String>,
+        dry_run: bool,
+        approved_plan: Option<Vec<String>>,
     },
     /// Execute one typed bash command for appactor->toolactor delegation.
     RunBashTool {
@@ -1004,6 +1133,63 @@ pub struct TerminalAgentResult {
     pub exit_code: Option<i32>,
     pub executed_commands: Vec<String>,
     pub steps: Vec<TerminalExecutionStep>,
+    /// Planned commands when the task ran as a dry run; none were executed.
+    #[serde(default)]
+    pub plan: Vec<TerminalPlanStep>,
+    #[serde(default)]
+    pub objective_status: Option<ObjectiveStatus>,
+}
+
+impl TerminalAgentResult {
+    /// Result of a dry run: blocked when the policy would reject any planned
+    /// command, otherwise still in progress until the plan is approved.
+    fn from_plan(plan: Vec<TerminalPlanStep>, summary: &str, model_used: Option<String>) -> Self {
+        let blocked = plan
+            .iter()
+            .filter(|step| step.blocked_reason.is_some())
+            .count();
+        let objective_status = if blocked > 0 {
+            ObjectiveStatus::Blocked
+        } else {
+            ObjectiveStatus::Incomplete
+        };
+        let mut header = format!("Dry run: planned {} command(s), none executed.", plan.len());
+        if blocked > 0 {
+            header.push_str(&format!(
+                " {blocked} would be blocked by the terminal policy."
+            ));
+        }
+        let summary = summary.trim();
+        Self {
+            summary: if summary.is_empty() {
+                header
+            } else {
+                format!("{header}\n{summary}")
+            },
+            reasoning: None,
+            success: objective_status != ObjectiveStatus::Blocked,
+            model_used,
+            exit_code: None,
+            executed_commands: Vec::new(),
+            steps: Vec::new(),
+            plan,
+            objective_status: Some(objective_status),
+        }
+    }
+
+    /// Commands of a dry-run plan, ready to pass back as `approved_plan`.
+    pub fn plan_commands(&self) -> Vec<String> {
+        self.plan.iter().map(|step| step.command.clone()).collect()
+    }
+}
+
+/// A command the terminal agent would run, recorded by a dry run.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct TerminalPlanStep {
+    pub command: String,
+    pub reasoning: Option<String>,
+    /// Why the command policy would reject this command, if it would.
+    pub blocked_reason: Option<String>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -1027,6 +1213,14 @@ pub struct TerminalAgentProgress {
     pub timestamp: String,
 }
 
+/// Shortest `timeout_ms` a bash tool request may ask for.
+pub const BASH_TOOL_MIN_TIMEOUT_MS: u64 = 1_000;
+/// Longest `timeout_ms` a bash tool request may ask for.
+pub const BASH_TOOL_MAX_TIMEOUT_MS: u64 = 120_000;
+/// Longest `reasoning` a bash tool request may carry, in characters.
+pub const BASH_TOOL_MAX_REASONING_CHARS: usize = 2_000;
+const BASH_TOOL_DEFAULT_TIMEOUT_MS: u64 = 30_000;
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct TerminalBashToolRequest {
     pub cmd: String,
@@ -1037,6 +1231,37 @@ pub struct TerminalBashToolRequest {
     pub call_id: Option<String>,
 }
 
+impl TerminalBashToolRequest {
+    /// Reject a request before anything runs: blank `cmd`, `timeout_ms`
+    /// outside [`BASH_TOOL_MIN_TIMEOUT_MS`]..=[`BASH_TOOL_MAX_TIMEOUT_MS`], or
+    /// `reasoning` over [`BASH_TOOL_MAX_REASONING_CHARS`].
+    pub fn validate(&self) -> Result<(), TerminalError> {
+        if self.cmd.trim().is_empty() {
+            return Err(TerminalError::InvalidInput(
+                "bash tool cmd must not be empty".to_string(),
+            ));
+        }
+        if let Some(timeout_ms) = self.timeout_ms {
+            if !(BASH_TOOL_MIN_TIMEOUT_MS..=BASH_TOOL_MAX_TIMEOUT_MS).contains(&timeout_ms) {
+                return Err(TerminalError::InvalidInput(format!(
+                    "bash tool timeout_ms must be between {BASH_TOOL_MIN_TIMEOUT_MS} and \
+                     {BASH_TOOL_MAX_TIMEOUT_MS}, got {timeout_ms}"
+                )));
+            }
+        }
+        if let Some(reasoning) = &self.reasoning {
+            let chars = reasoning.chars().count();
+            if chars > BASH_TOOL_MAX_REASONING_CHARS {
+                return Err(TerminalError::InvalidInput(format!(
+                    "bash tool reasoning is {chars} characters; the limit is \
+                     {BASH_TOOL_MAX_REASONING_CHARS}"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone)]
 struct TerminalExecutionContext {
     terminal_id: String,
@@ -1277,6 +1502,8 @@ impl Actor for TerminalActor {
                 writer_actor,
                 run_id,
                 call_id,
+                dry_run,
+                approved_plan,
                 reply,
             } => {
                 let result = match (
@@ -1306,6 +1533,8 @@ impl Actor for TerminalActor {
                             max_steps,
                             model_override,
                             progress_tx,
+                            dry_run,
+                            approved_plan,
                         )
                         .await
                     }
@@ -1328,6 +1557,8 @@ impl Actor for TerminalActor {
                 writer_actor,
                 run_id,
                 call_id,
+                dry_run,
+                approved_plan,
             } => {
                 let result = match (
                     state.is_running,
@@ -1356,6 +1587,8 @@ impl Actor for TerminalActor {
                             max_steps,
                             model_override,
                             progress_tx,
+                            dry_run,
+                            approved_plan,
                         )
                         .await
                     }
@@ -1381,10 +1614,11 @@ impl Actor for TerminalActor {
                     let _ = state.event_store.send_message(
                         crate::actors::event_store::EventStoreMsg::AppendAsync {
                             event: crate::actors::event_store::AppendEvent {
-                                event_type: "tool.result".to_string(),
+                                event_type: shared_types::EventTopic::ToolResult.to_string(),
                                 payload,
                                 actor_id: state.terminal_id.clone(),
                                 user_id: state.user_id.clone(),
+                                idempotency_key: None,
                             },
                         },
                     );
@@ -1397,11 +1631,13 @@ impl Actor for TerminalActor {
                 reply,
             } => {
                 let result = match (
+                    request.validate(),
                     state.is_running,
                     state.input_tx.clone(),
                     state.output_tx.clone(),
                 ) {
-                    (true, Some(input_tx), Some(output_tx)) => {
+                    (Err(e), ..) => Err(e),
+                    (Ok(()), true, Some(input_tx), Some(output_tx)) => {
                         let exec = TerminalExecutionContext {
                             terminal_id: state.terminal_id.clone(),
                             user_id: state.user_id.clone(),
@@ -1492,6 +1728,7 @@ impl TerminalActor {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn run_agentic_task(
         &self,
         ctx: TerminalExecutionContext,
@@ -1500,9 +1737,17 @@ impl TerminalActor {
         max_steps: Option<u8>,
         model_override: Option<String>,
         progress_tx: Option<mpsc::UnboundedSender<TerminalAgentProgress>>,
+        dry_run: bool,
+        approved_plan: Option<Vec<String>>,
     ) -> Result<TerminalAgentResult, TerminalError> {
+        if let Some(commands) = approved_plan {
+            return self
+                .run_approved_plan(ctx, commands, timeout_ms, progress_tx)
+                .await;
+        }
+
         // Create the terminal adapter for the harness
-        let adapter = TerminalAdapter::new(
+        let mut adapter = TerminalAdapter::new(
             ctx.terminal_id.clone(),
             ctx.working_dir.clone(),
             ctx.shell.clone(),
@@ -1511,6 +1756,10 @@ impl TerminalActor {
             ctx.writer_actor.clone(),
             ctx.run_id.clone(),
         );
+        if dry_run {
+            adapter = adapter.with_dry_run();
+        }
+        let planned_steps = adapter.planned_steps();
 
         // Configure the harness with provided parameters
         let config = HarnessConfig {
@@ -1544,6 +1793,17 @@ impl TerminalActor {
             .unwrap_or(Err(HarnessError::Timeout(timeout_budget_ms)));
 
         match result {
+            Ok(agent_result) if dry_run => {
+                let plan = planned_steps
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .clone();
+                Ok(TerminalAgentResult::from_plan(
+                    plan,
+                    &agent_result.summary,
+                    agent_result.model_used,
+                ))
+            }
             Ok(agent_result) => {
                 // Convert harness result to TerminalAgentResult
                 let steps: Vec<TerminalExecutionStep> = agent_result
@@ -1577,6 +1837,8 @@ impl TerminalActor {
                     }),
                     executed_commands,
                     steps,
+                    plan: Vec::new(),
+                    objective_status: Some(agent_result.objective_status),
                 })
             }
             Err(e) => {
@@ -1589,6 +1851,130 @@ impl TerminalActor {
         }
     }
 
+    /// Run the commands of an approved dry-run plan in order, stopping at the
+    /// first one that fails or is rejected by the command policy.
+    async fn run_approved_plan(
+        &self,
+        ctx: TerminalExecutionContext,
+        commands: Vec<String>,
+        timeout_ms: Option<u64>,
+        progress_tx: Option<mpsc::UnboundedSender<TerminalAgentProgress>>,
+    ) -> Result<TerminalAgentResult, TerminalError> {
+        if commands.iter().all(|command| command.trim().is_empty()) {
+            return Err(TerminalError::InvalidInput(
+                "approved plan has no commands".to_string(),
+            ));
+        }
+        let adapter = TerminalAdapter::new(
+            ctx.terminal_id.clone(),
+            ctx.working_dir.clone(),
+            ctx.shell.clone(),
+            Some(ctx.event_store.clone()),
+            progress_tx,
+            None,
+            ctx.run_id.clone(),
+        );
+        let timeout_budget_ms = timeout_ms.unwrap_or(30_000).clamp(1_000, 120_000);
+        let total = commands.len();
+
+        let run = async {
+            let mut steps = Vec::new();
+            let mut halted = None;
+            for (index, command) in commands.iter().enumerate() {
+                let step_index = index + 1;
+                adapter.emit_terminal_progress(
+                    "terminal_plan_execute",
+                    "terminal executing approved plan step",
+                    None,
+                    Some(command.clone()),
+                    None,
+                    None,
+                    None,
+                    Some(step_index),
+                    Some(total),
+                );
+                match adapter.execute_bash(command, timeout_budget_ms).await {
+                    Ok((output, exit_code)) => {
+                        let output_excerpt = TerminalAdapter::truncate_excerpt(&output);
+                        adapter.emit_terminal_progress(
+                            "terminal_tool_result",
+                            "terminal received approved plan step result",
+                            None,
+                            Some(command.clone()),
+                            None,
+                            Some(output_excerpt.clone()),
+                            Some(exit_code),
+                            Some(step_index),
+                            Some(total),
+                        );
+                        adapter.emit_agent_step(
+                            &ctx.user_id,
+                            serde_json::json!({
+                                "step_index": step_index,
+                                "command": command,
+                                "dry_run": false,
+                                "executed": true,
+                                "blocked": false,
+                                "exit_code": exit_code,
+                            }),
+                        );
+                        steps.push(TerminalExecutionStep {
+                            command: command.clone(),
+                            exit_code,
+                            output_excerpt,
+                        });
+                        if exit_code != 0 {
+                            halted =
+                                Some(format!("step {step_index} exited with status {exit_code}"));
+                            break;
+                        }
+                    }
+                    Err(TerminalError::InvalidInput(reason)) => {
+                        adapter.emit_agent_step(
+                            &ctx.user_id,
+                            serde_json::json!({
+                                "step_index": step_index,
+                                "command": command,
+                                "dry_run": false,
+                                "executed": false,
+                                "blocked": true,
+                                "blocked_reason": reason,
+                            }),
+                        );
+                        halted = Some(format!("step {step_index} was blocked: {reason}"));
+                        break;
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            Ok((steps, halted))
+        };
+        let (steps, halted) =
+            tokio::time::timeout(std::time::Duration::from_millis(timeout_budget_ms), run)
+                .await
+                .map_err(|_| TerminalError::Timeout(timeout_budget_ms))??;
+
+        let mut summary = format!("Executed {} of {total} approved command(s).", steps.len());
+        if let Some(reason) = &halted {
+            summary.push_str(&format!(" Stopped: {reason}."));
+        }
+        Ok(TerminalAgentResult {
+            summary,
+            reasoning: None,
+            success: halted.is_none(),
+            model_used: None,
+            exit_code: steps.last().map(|step| step.exit_code),
+            executed_commands: steps.iter().map(|step| step.command.clone()).collect(),
+            steps,
+            plan: Vec::new(),
+            objective_status: Some(if halted.is_none() {
+                ObjectiveStatus::Complete
+            } else {
+                ObjectiveStatus::Blocked
+            }),
+        })
+    }
+
     async fn run_bash_tool_request(
         &self,
         ctx: TerminalExecutionContext,
@@ -1596,7 +1982,7 @@ impl TerminalActor {
         progress_tx: Option<mpsc::UnboundedSender<TerminalAgentProgress>>,
     ) -> Result<TerminalAgentResult, TerminalError> {
         // For RunBashTool, we use the harness with a single-step objective
-        let timeout_ms = request.timeout_ms.unwrap_or(30_000).clamp(1_000, 120_000);
+        let timeout_ms = request.timeout_ms.unwrap_or(BASH_TOOL_DEFAULT_TIMEOUT_MS);
 
         // Emit initial progress
         if let Some(ref tx) = progress_tx {
@@ -1640,6 +2026,7 @@ impl TerminalActor {
                 call_id: ctx.call_id.clone(),
                 session_id: None,
                 thread_id: None,
+                queue_wait_ms: None,
             }),
         );
 
@@ -1687,6 +2074,8 @@ impl TerminalActor {
                         exit_code,
                         output_excerpt: TerminalAdapter::truncate_excerpt(&output),
                     }],
+                    plan: Vec::new(),
+                    objective_status: None,
                 })
             }
             Err(e) => {
@@ -2257,6 +2646,8 @@ mod tests {
             writer_actor: None,
             run_id: None,
             call_id: None,
+            dry_run: false,
+            approved_plan: None,
             reply,
         })
         .expect("run agentic task call failed")
@@ -2337,6 +2728,8 @@ mod tests {
             writer_actor: None,
             run_id: None,
             call_id: None,
+            dry_run: false,
+            approved_plan: None,
             reply,
         })
         .expect("run agentic task call failed");
@@ -2357,4 +2750,274 @@ mod tests {
         terminal.stop(None);
         event_store.stop(None);
     }
+
+    fn plan_step(command: &str, blocked_reason: Option<&str>) -> TerminalPlanStep {
+        TerminalPlanStep {
+            command: command.to_string(),
+            reasoning: None,
+            blocked_reason: blocked_reason.map(ToString::to_string),
+        }
+    }
+
+    #[test]
+    fn dry_run_result_is_in_progress_until_approved() {
+        let result = TerminalAgentResult::from_plan(
+            vec![
+                plan_step("cargo build", None),
+                plan_step("cargo test", None),
+            ],
+            "Build, then test.",
+            Some("planner".to_string()),
+        );
+        assert!(result.success);
+        assert_eq!(result.objective_status, Some(ObjectiveStatus::Incomplete));
+        assert!(result.executed_commands.is_empty());
+        assert!(result.steps.is_empty());
+        assert_eq!(result.plan_commands(), vec!["cargo build", "cargo test"]);
+        assert!(result
+            .summary
+            .starts_with("Dry run: planned 2 command(s), none executed."));
+        assert!(result.summary.ends_with("Build, then test."));
+    }
+
+    #[test]
+    fn dry_run_result_marks_policy_blocked_commands() {
+        let result = TerminalAgentResult::from_plan(
+            vec![
+                plan_step("ls", None),
+                plan_step("rm -rf build", Some("Command denied by terminal policy")),
+            ],
+            "",
+            None,
+        );
+        assert!(!result.success);
+        assert_eq!(result.objective_status, Some(ObjectiveStatus::Blocked));
+        assert!(result
+            .summary
+            .contains("1 would be blocked by the terminal policy"));
+        assert_eq!(
+            result.plan[1].blocked_reason.as_deref(),
+            Some("Command denied by terminal policy")
+        );
+    }
+
+    #[tokio::test]
+    async fn dry_run_plans_bash_calls_without_running_them() {
+        let (event_store, _event_store_handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .expect("failed to start event store");
+        let marker = std::env::temp_dir().join(format!("terminal-dry-run-{}", ulid::Ulid::new()));
+        let adapter = TerminalAdapter::new(
+            "test-terminal-dry-run".to_string(),
+            test_working_dir(),
+            test_shell(),
+            Some(event_store.clone()),
+            None,
+            None,
+            Some("run-dry".to_string()),
+        )
+        .with_dry_run();
+        let planned_steps = adapter.planned_steps();
+        let ctx = ExecutionContext {
+            loop_id: "loop-dry".to_string(),
+            worker_id: "test-terminal-dry-run".to_string(),
+            user_id: "test-user".to_string(),
+            step_number: 1,
+            max_steps: 5,
+            model_used: "planner".to_string(),
+            objective: "create a marker file".to_string(),
+            run_id: Some("run-dry".to_string()),
+            call_id: None,
+        };
+        let command = format!("touch {}", marker.display());
+
+        let execution =
+            adapter.plan_bash_call(&ctx, &command, Some("create the marker".to_string()));
+
+        assert!(execution.success);
+        assert!(execution.output.starts_with("Dry run: not executed."));
+        assert!(!marker.exists(), "dry run must not execute the command");
+        let steps = planned_steps.lock().unwrap().clone();
+        assert_eq!(steps.len(), 1);
+        assert_eq!(steps[0].command, command);
+        assert_eq!(steps[0].reasoning.as_deref(), Some("create the marker"));
+
+        let mut events = Vec::new();
+        for _ in 0..50 {
+            events = ractor::call!(event_store, |reply| EventStoreMsg::GetRecentEvents {
+                since_seq: 0,
+                limit: 10,
+                event_type_prefix: Some("terminal.agent.step".to_string()),
+                actor_id: None,
+                user_id: None,
+                reply,
+            })
+            .expect("event store call")
+            .expect("events");
+            if !events.is_empty() {
+                break;
+            }
+            sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(events.len(), 1);
+        let payload = &events[0].payload;
+        assert_eq!(payload["command"], command.as_str());
+        assert_eq!(payload["dry_run"], true);
+        assert_eq!(payload["executed"], false);
+        assert_eq!(payload["run_id"], "run-dry");
+        assert_eq!(payload["step_index"], 1);
+
+        event_store.stop(None);
+    }
+
+    #[tokio::test]
+    async fn approved_plan_runs_verbatim_and_stops_at_first_failure() {
+        let (event_store, _event_store_handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .expect("failed to start event store");
+        let (terminal, _terminal_handle) = Actor::spawn(
+            None,
+            TerminalActor,
+            TerminalArguments {
+                terminal_id: "test-terminal-approved-plan".to_string(),
+                user_id: "test-user".to_string(),
+                shell: test_shell(),
+                working_dir: test_working_dir(),
+                event_store: event_store.clone(),
+            },
+        )
+        .await
+        .expect("failed to start terminal actor");
+        ractor::call!(terminal, |reply| TerminalMsg::Start { reply })
+            .expect("start call failed")
+            .expect("terminal failed to start");
+
+        let result = ractor::call!(terminal, |reply| TerminalMsg::RunAgenticTask {
+            objective: "approved plan".to_string(),
+            timeout_ms: Some(10_000),
+            max_steps: None,
+            model_override: None,
+            progress_tx: None,
+            writer_actor: None,
+            run_id: None,
+            call_id: None,
+            dry_run: false,
+            approved_plan: Some(vec![
+                "echo approved-one".to_string(),
+                "exit 3".to_string(),
+                "echo never-run".to_string(),
+            ]),
+            reply,
+        })
+        .expect("run agentic task call failed")
+        .expect("approved plan returned error");
+
+        assert!(!result.success);
+        assert_eq!(result.objective_status, Some(ObjectiveStatus::Blocked));
+        assert_eq!(
+            result.executed_commands,
+            vec!["echo approved-one".to_string(), "exit 3".to_string()]
+        );
+        assert_eq!(result.steps[0].exit_code, 0);
+        assert!(result.steps[0].output_excerpt.contains("approved-one"));
+        assert_eq!(result.steps[1].exit_code, 3);
+        assert_eq!(result.exit_code, Some(3));
+        assert!(result
+            .summary
+            .contains("Executed 2 of 3 approved command(s)."));
+
+        terminal.stop(None);
+        event_store.stop(None);
+    }
+
+    fn bash_request(cmd: &str, timeout_ms: Option<u64>) -> TerminalBashToolRequest {
+        TerminalBashToolRequest {
+            cmd: cmd.to_string(),
+            timeout_ms,
+            model_override: None,
+            reasoning: None,
+            run_id: None,
+            call_id: None,
+        }
+    }
+
+    #[test]
+    fn test_bash_tool_request_validation() {
+        assert!(bash_request("echo ok", None).validate().is_ok());
+        assert!(bash_request("echo ok", Some(BASH_TOOL_MIN_TIMEOUT_MS))
+            .validate()
+            .is_ok());
+        assert!(bash_request("echo ok", Some(BASH_TOOL_MAX_TIMEOUT_MS))
+            .validate()
+            .is_ok());
+
+        for cmd in ["", "  \n\t"] {
+            assert!(matches!(
+                bash_request(cmd, None).validate(),
+                Err(TerminalError::InvalidInput(msg)) if msg.contains("cmd")
+            ));
+        }
+        for timeout_ms in [
+            0,
+            BASH_TOOL_MIN_TIMEOUT_MS - 1,
+            BASH_TOOL_MAX_TIMEOUT_MS + 1,
+        ] {
+            assert!(matches!(
+                bash_request("echo ok", Some(timeout_ms)).validate(),
+                Err(TerminalError::InvalidInput(msg)) if msg.contains("timeout_ms")
+            ));
+        }
+
+        let mut request = bash_request("echo ok", None);
+        request.reasoning = Some("x".repeat(BASH_TOOL_MAX_REASONING_CHARS));
+        assert!(request.validate().is_ok());
+        request.reasoning = Some("x".repeat(BASH_TOOL_MAX_REASONING_CHARS + 1));
+        assert!(matches!(
+            request.validate(),
+            Err(TerminalError::InvalidInput(msg)) if msg.contains("reasoning")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_run_bash_tool_rejects_invalid_request_before_running() {
+        let (event_store, _event_store_handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .expect("failed to start event store");
+        let (terminal, _terminal_handle) = Actor::spawn(
+            None,
+            TerminalActor,
+            TerminalArguments {
+                terminal_id: "test-terminal-bash-validation".to_string(),
+                user_id: "test-user".to_string(),
+                shell: test_shell(),
+                working_dir: test_working_dir(),
+                event_store: event_store.clone(),
+            },
+        )
+        .await
+        .expect("failed to start terminal actor");
+
+        // The terminal is never started: validation must fail first.
+        for request in [
+            bash_request("   ", None),
+            bash_request("echo ok", Some(10 * BASH_TOOL_MAX_TIMEOUT_MS)),
+        ] {
+            let result = ractor::call!(terminal, |reply| TerminalMsg::RunBashTool {
+                request,
+                progress_tx: None,
+                reply,
+            })
+            .expect("run bash tool call failed");
+            assert!(
+                matches!(result, Err(TerminalError::InvalidInput(_))),
+                "expected invalid input, got: {result:?}"
+            );
+        }
+
+        terminal.stop(None);
+        event_store.stop(None);
+    }
 }
@@ -6,13 +6,17 @@
 
 use async_trait::async_trait;
 use ractor::ActorRef;
+use shared_types::DocumentContextMode;
+use std::sync::Mutex;
 
 use crate::actors::agent_harness::{
     AgentProgress, ExecutionContext, HarnessError, ToolExecution, WorkerPort, WorkerTurnReport,
 };
 use crate::actors::event_store::{AppendEvent, EventStoreMsg};
+use crate::actors::writer::context::{apply_section_edits, section_text};
+use crate::actors::writer::document_runtime::changed_block_ids;
 use crate::actors::writer::{
-    dispatch_delegate_capability, VersionSource, WriterDelegateCapability, WriterMsg,
+    dispatch_delegate_capability, WriterDelegateCapability, WriterMsg, WriterRevisionOutcome,
 };
 use crate::baml_client::types::{
     MessageWriterToolCall,
@@ -34,6 +38,29 @@ fn tool_call_name(tool_call: &AgentToolCall) -> &str {
     }
 }
 
+/// Block lock a writer loop holds while it works from `base`, its parent
+/// version's content.
+pub(crate) struct LoopLock {
+    pub(crate) lock_id: String,
+    pub(crate) base: String,
+}
+
+impl LoopLock {
+    /// Declare the blocks `revision` rewrites before it is submitted, so user
+    /// edits to them are warned about or refused until the loop ends.
+    fn declare_targets(&self, writer_actor: &ActorRef<WriterMsg>, run_id: &str, revision: &str) {
+        let block_ids = changed_block_ids(&self.base, revision);
+        if block_ids.is_empty() {
+            return;
+        }
+        let _ = writer_actor.send_message(WriterMsg::DeclareWriterLockTargets {
+            run_id: run_id.to_string(),
+            lock_id: self.lock_id.clone(),
+            block_ids,
+        });
+    }
+}
+
 pub(crate) struct WriterDelegationAdapter {
     writer_id: String,
     user_id: String,
@@ -43,6 +70,7 @@ pub(crate) struct WriterDelegationAdapter {
     terminal_supervisor: Option<ActorRef<TerminalSupervisorMsg>>,
     run_id: Option<String>,
     parent_version_id: Option<u64>,
+    lock: Option<LoopLock>,
 }
 
 impl WriterDelegationAdapter {
@@ -56,6 +84,7 @@ impl WriterDelegationAdapter {
         terminal_supervisor: Option<ActorRef<TerminalSupervisorMsg>>,
         run_id: Option<String>,
         parent_version_id: Option<u64>,
+        lock: Option<LoopLock>,
     ) -> Self {
         Self {
             writer_id,
@@ -66,6 +95,7 @@ impl WriterDelegationAdapter {
             terminal_supervisor,
             run_id,
             parent_version_id,
+            lock,
         }
     }
 
@@ -75,6 +105,7 @@ impl WriterDelegationAdapter {
             payload,
             actor_id: self.writer_id.clone(),
             user_id: self.user_id.clone(),
+            idempotency_key: None,
         };
         let _ = self
             .event_store
@@ -140,21 +171,27 @@ impl WriterDelegationAdapter {
                 }),
             );
 
+            if let Some(lock) = &self.lock {
+                lock.declare_targets(&self.writer_actor, run_id, &content);
+            }
+
             // Fire-and-forget: the WriterActor is blocked awaiting this harness, so
             // ractor::call! would deadlock. Queue the message and return success now;
             // the actor processes CreateWriterDocumentVersion after the harness finishes.
             let (tx, rx) = tokio::sync::oneshot::channel::<
-                Result<crate::actors::writer::DocumentVersion, crate::actors::writer::WriterError>,
+                Result<
+                    crate::actors::writer::WriterRevisionOutcome,
+                    crate::actors::writer::WriterError,
+                >,
             >();
-            let send_result =
-                self.writer_actor
-                    .send_message(WriterMsg::CreateWriterDocumentVersion {
-                        run_id: run_id.clone(),
-                        parent_version_id: Some(parent_version_id),
-                        content,
-                        source: VersionSource::Writer,
-                        reply: ractor::RpcReplyPort::from(tx),
-                    });
+            let send_result = self
+                .writer_actor
+                .send_message(WriterMsg::ProposeWriterRevision {
+                    run_id: run_id.clone(),
+                    parent_version_id: Some(parent_version_id),
+                    content,
+                    reply: ractor::RpcReplyPort::from(tx),
+                });
             drop(rx); // we don't await the reply
 
             return match send_result {
@@ -411,6 +448,12 @@ pub(crate) struct WriterUserPromptAdapter {
     terminal_supervisor: Option<ActorRef<TerminalSupervisorMsg>>,
     run_id: String,
     parent_version_id: u64,
+    /// Parent version content when the objective shows the document as
+    /// sections; `write_section` ids refer to it.
+    sectioned_parent: Option<String>,
+    /// Section edits of this loop, re-applied together on every write.
+    section_edits: Mutex<Vec<(String, String)>>,
+    lock: Option<LoopLock>,
 }
 
 impl WriterUserPromptAdapter {
@@ -424,6 +467,8 @@ impl WriterUserPromptAdapter {
         terminal_supervisor: Option<ActorRef<TerminalSupervisorMsg>>,
         run_id: String,
         parent_version_id: u64,
+        sectioned_parent: Option<String>,
+        lock: Option<LoopLock>,
     ) -> Self {
         Self {
             writer_id,
@@ -434,6 +479,9 @@ impl WriterUserPromptAdapter {
             terminal_supervisor,
             run_id,
             parent_version_id,
+            sectioned_parent,
+            section_edits: Mutex::new(Vec::new()),
+            lock,
         }
     }
 
@@ -443,12 +491,102 @@ impl WriterUserPromptAdapter {
             payload,
             actor_id: self.writer_id.clone(),
             user_id: self.user_id.clone(),
+            idempotency_key: None,
         };
         let _ = self
             .event_store
             .send_message(EventStoreMsg::AppendAsync { event });
     }
 
+    /// Submit `content` as the loop's revision of its parent version.
+    async fn propose_revision(
+        &self,
+        ctx: &ExecutionContext,
+        mode: &str,
+        content: String,
+        start: tokio::time::Instant,
+    ) -> Result<ToolExecution, HarnessError> {
+        self.emit_event(
+            "writer.user_prompt.write_revision",
+            serde_json::json!({
+                "run_id": &self.run_id,
+                "call_id": ctx.call_id,
+                "parent_version_id": self.parent_version_id,
+                "mode": mode,
+                "content_len": content.len(),
+            }),
+        );
+
+        if let Some(lock) = &self.lock {
+            lock.declare_targets(&self.writer_actor, &self.run_id, &content);
+        }
+        let result: Result<_, ractor::RactorErr<WriterMsg>> =
+            ractor::call!(self.writer_actor, |reply| {
+                WriterMsg::ProposeWriterRevision {
+                    run_id: self.run_id.clone(),
+                    parent_version_id: Some(self.parent_version_id),
+                    content,
+                    reply,
+                }
+            });
+
+        match result {
+            Ok(Ok(WriterRevisionOutcome::PendingReview { overlay, impact })) => {
+                Ok(ToolExecution {
+                    tool_name: "message_writer".to_string(),
+                    success: true,
+                    output: serde_json::json!({
+                        "mode": mode,
+                        "overlay_id": overlay.overlay_id,
+                        "impact": impact,
+                        "status": "revision_pending_review",
+                        "next_step": "This revision rewrites much of the document and is waiting for the user to review it. Call finished now; do not resubmit it.",
+                    })
+                    .to_string(),
+                    error: None,
+                    execution_time_ms: start.elapsed().as_millis() as u64,
+                })
+            }
+            Ok(Ok(WriterRevisionOutcome::Applied(version))) => Ok(ToolExecution {
+                tool_name: "message_writer".to_string(),
+                success: true,
+                output: serde_json::json!({
+                    "mode": mode,
+                    "version_id": version.version_id,
+                    "status": "revision_applied",
+                    "next_step": "If this revision satisfies the objective and you have no pending delegations, call finished now. Do not emit another write_revision unless new worker results require a materially different document.",
+                })
+                .to_string(),
+                error: None,
+                execution_time_ms: start.elapsed().as_millis() as u64,
+            }),
+            Ok(Err(e)) => Ok(ToolExecution {
+                tool_name: "message_writer".to_string(),
+                success: false,
+                output: String::new(),
+                error: Some(format!("Failed to create revision: {e}")),
+                execution_time_ms: start.elapsed().as_millis() as u64,
+            }),
+            Err(e) => Ok(ToolExecution {
+                tool_name: "message_writer".to_string(),
+                success: false,
+                output: String::new(),
+                error: Some(format!("Writer actor call failed: {e}")),
+                execution_time_ms: start.elapsed().as_millis() as u64,
+            }),
+        }
+    }
+
+    fn failed(start: tokio::time::Instant, error: String) -> ToolExecution {
+        ToolExecution {
+            tool_name: "message_writer".to_string(),
+            success: false,
+            output: String::new(),
+            error: Some(error),
+            execution_time_ms: start.elapsed().as_millis() as u64,
+        }
+    }
+
     async fn execute_message_writer(
         &self,
         ctx: &ExecutionContext,
@@ -474,55 +612,81 @@ impl WriterUserPromptAdapter {
                     });
                 }
 
-                self.emit_event(
-                    "writer.user_prompt.write_revision",
-                    serde_json::json!({
-                        "run_id": &self.run_id,
-                        "call_id": ctx.call_id,
-                        "parent_version_id": self.parent_version_id,
-                        "content_len": content.len(),
-                    }),
-                );
-
-                let result: Result<_, ractor::RactorErr<WriterMsg>> =
-                    ractor::call!(self.writer_actor, |reply| {
-                        WriterMsg::CreateWriterDocumentVersion {
-                            run_id: self.run_id.clone(),
-                            parent_version_id: Some(self.parent_version_id),
-                            content,
-                            source: VersionSource::Writer,
-                            reply,
-                        }
-                    });
+                // The model has not seen the whole document, so a complete
+                // rewrite from it would drop the sections it was not shown.
+                if self.sectioned_parent.is_some() {
+                    return Ok(Self::failed(
+                        start,
+                        "the document is shown as sections; use write_section per section"
+                            .to_string(),
+                    ));
+                }
+
+                self.propose_revision(ctx, "write_revision", content, start)
+                    .await
+            }
+
+            // Replace one section of a document shown as sections.
+            "write_section" => {
+                let Some(parent) = self.sectioned_parent.as_deref() else {
+                    return Ok(Self::failed(
+                        start,
+                        "write_section is only available when the document is shown as \
+                         sections; use write_revision with the complete document"
+                            .to_string(),
+                    ));
+                };
+                let section_id = call
+                    .tool_args
+                    .mode_arg
+                    .as_deref()
+                    .map(str::trim)
+                    .unwrap_or_default()
+                    .to_string();
+                let revised = {
+                    let mut edits = self
+                        .section_edits
+                        .lock()
+                        .unwrap_or_else(|poisoned| poisoned.into_inner());
+                    edits.push((section_id, content));
+                    let revised = apply_section_edits(parent, &edits);
+                    if revised.is_err() {
+                        edits.pop();
+                    }
+                    revised
+                };
+                match revised {
+                    Ok(revised) => {
+                        self.propose_revision(ctx, "write_section", revised, start)
+                            .await
+                    }
+                    Err(e) => Ok(Self::failed(
+                        start,
+                        format!("write_section needs mode_arg set to an outline id: {e}"),
+                    )),
+                }
+            }
 
-                match result {
-                    Ok(Ok(version)) => Ok(ToolExecution {
+            // Quote a section the objective left out.
+            "read_section" => {
+                let section_id = call.tool_args.mode_arg.as_deref().map(str::trim);
+                let text = self
+                    .sectioned_parent
+                    .as_deref()
+                    .zip(section_id)
+                    .and_then(|(parent, section_id)| section_text(parent, section_id));
+                match text {
+                    Some(text) => Ok(ToolExecution {
                         tool_name: "message_writer".to_string(),
                         success: true,
-                        output: serde_json::json!({
-                            "mode": "write_revision",
-                            "version_id": version.version_id,
-                            "status": "revision_applied",
-                            "next_step": "If this revision satisfies the objective and you have no pending delegations, call finished now. Do not emit another write_revision unless new worker results require a materially different document.",
-                        })
-                        .to_string(),
+                        output: text.to_string(),
                         error: None,
                         execution_time_ms: start.elapsed().as_millis() as u64,
                     }),
-                    Ok(Err(e)) => Ok(ToolExecution {
-                        tool_name: "message_writer".to_string(),
-                        success: false,
-                        output: String::new(),
-                        error: Some(format!("Failed to create revision: {e}")),
-                        execution_time_ms: start.elapsed().as_millis() as u64,
-                    }),
-                    Err(e) => Ok(ToolExecution {
-                        tool_name: "message_writer".to_string(),
-                        success: false,
-                        output: String::new(),
-                        error: Some(format!("Writer actor call failed: {e}")),
-                        execution_time_ms: start.elapsed().as_millis() as u64,
-                    }),
+                    None => Ok(Self::failed(
+                        start,
+                        "read_section needs mode_arg set to an outline id".to_string(),
+                    )),
                 }
             }
 
@@ -613,7 +777,7 @@ impl WriterUserPromptAdapter {
                 output: String::new(),
                 error: Some(format!(
                     "Unsupported message_writer mode '{}'. Use: write_revision, \
-                     delegate_researcher, delegate_terminal",
+                     write_section, read_section, delegate_researcher, delegate_terminal",
                     call.tool_args.mode
                 )),
                 execution_time_ms: start.elapsed().as_millis() as u64,
@@ -629,7 +793,7 @@ impl WorkerPort for WriterUserPromptAdapter {
     }
 
     fn get_tool_description(&self) -> String {
-        r#"Tool: message_writer
+        let description = r#"Tool: message_writer
 Description: Writer document revision and worker delegation.
 Required args:
 - mode: "write_revision" | "delegate_researcher" | "delegate_terminal"
@@ -644,8 +808,22 @@ Instructions:
 - If the user's changes are purely editorial (typo fixes, reformatting, direct content changes), apply them directly via write_revision.
 - Always call write_revision before calling `finished` — the revision is the primary output.
 - After write_revision succeeds, call `finished` immediately unless you still need unresolved worker delegation.
-- Do not use markdown footnote syntax like [^s1] or [1] unless the document already contains a resolved citation system. Prefer inline source mentions or plain prose; source URLs are rendered separately in the UI."#
-            .to_string()
+- Do not use markdown footnote syntax like [^s1] or [1] unless the document already contains a resolved citation system. Prefer inline source mentions or plain prose; source URLs are rendered separately in the UI."#;
+        if self.sectioned_parent.is_none() {
+            return description.to_string();
+        }
+        format!(
+            "{description}\n\
+             Sectioned document:\n\
+             - The objective shows the document as an outline plus selected sections, so \
+             write_revision is not available; revise with \"write_section\" instead.\n\
+             - write_section: mode_arg is a section id from the outline (e.g. \"s3\"); content is \
+             the complete new text of that section, heading included. Empty content deletes \
+             the section. Call it once per section you change; each call keeps your earlier \
+             section edits.\n\
+             - read_section: mode_arg is a section id; returns that section's current text. Use \
+             it before rewriting a section the objective did not quote."
+        )
     }
 
     fn allowed_tool_names(&self) -> Option<&'static [&'static str]> {
@@ -663,7 +841,8 @@ Instructions:
                 produce the revision directly via write_revision.\n\
              3. If the changes include instructions that require research or code inspection, \
                 delegate to workers first, then revise.\n\
-             4. The write_revision content must be the COMPLETE revised document, not a partial diff.\n\
+             4. The write_revision content must be the COMPLETE revised document, not a partial diff. \
+                When the document is shown as sections, use write_section per section instead.\n\
              5. Always call write_revision with your final output before calling finished.\n\
              6. After write_revision succeeds, call finished immediately unless unresolved \
                 worker delegation still needs to change the document.\n\
@@ -678,6 +857,14 @@ Instructions:
         )
     }
 
+    fn document_context(&self) -> Option<DocumentContextMode> {
+        Some(if self.sectioned_parent.is_some() {
+            DocumentContextMode::Sectioned
+        } else {
+            DocumentContextMode::FullText
+        })
+    }
+
     async fn execute_tool_call(
         &self,
         ctx: &ExecutionContext,
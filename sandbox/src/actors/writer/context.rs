@@ -0,0 +1,486 @@
+//! Document context for writer loops.
+//!
+//! A run's first loop, and any loop on a small document, sees the whole
+//! document. Follow-up loops on a large document see its outline, the
+//! sections relevant to the objective or changed since the loop's base
+//! version, and a summary of those changes. They revise it section by
+//! section through `write_section`, addressing sections by outline id.
+
+use std::ops::Range;
+
+use shared_types::{DocumentContextMode, WriterRunChangesetPayload};
+
+use crate::observability::llm_trace::estimate_tokens;
+
+/// Documents up to this many estimated tokens are always sent in full.
+pub(crate) const FULL_TEXT_MAX_TOKENS: u32 = 1_500;
+/// Sections quoted for lexical relevance, on top of the changed ones.
+const MAX_RELEVANT_SECTIONS: usize = 3;
+/// Query words too common to say anything about relevance.
+const STOP_WORDS: &[&str] = &[
+    "about", "after", "also", "document", "from", "have", "into", "make", "more", "section",
+    "should", "that", "their", "them", "then", "there", "these", "this", "what", "when", "which",
+    "with", "would", "your",
+];
+
+/// A heading and the text under it, up to the next heading.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DocumentSection {
+    /// `s1`, `s2`, … in document order; `s0` is text before the first heading.
+    pub id: String,
+    /// The heading line, empty for `s0`.
+    pub heading: String,
+    /// Byte range of the section, including its trailing blank lines.
+    pub range: Range<usize>,
+}
+
+/// The document part of a writer loop's objective.
+#[derive(Debug, Clone)]
+pub(crate) struct LoopContext {
+    pub mode: DocumentContextMode,
+    /// The document itself in full-text mode, the rendered outline and
+    /// sections otherwise.
+    pub text: String,
+}
+
+/// What a loop's document context is assembled from.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct LoopContextInput<'a> {
+    /// The document the loop revises.
+    pub content: &'a str,
+    /// What the loop is asked to do; its words select relevant sections.
+    pub query: &'a str,
+    /// The loop's base version, `None` on a run's first loop.
+    pub base: Option<(u64, &'a str)>,
+    /// Changesets of versions after the base version.
+    pub changesets: &'a [WriterRunChangesetPayload],
+}
+
+/// Split markdown into sections at ATX headings outside code fences.
+pub(crate) fn document_sections(content: &str) -> Vec<DocumentSection> {
+    let mut starts: Vec<(usize, String)> = Vec::new();
+    let mut in_fence = false;
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        } else if !in_fence && line.len() - trimmed.len() <= 3 && is_heading(trimmed) {
+            starts.push((offset, line.trim().to_string()));
+        }
+        offset += line.len();
+    }
+
+    let mut sections = Vec::new();
+    let first_heading = starts.first().map_or(content.len(), |(start, _)| *start);
+    if !content[..first_heading].trim().is_empty() {
+        sections.push(DocumentSection {
+            id: "s0".to_string(),
+            heading: String::new(),
+            range: 0..first_heading,
+        });
+    }
+    for (index, (start, heading)) in starts.iter().enumerate() {
+        let end = starts
+            .get(index + 1)
+            .map_or(content.len(), |(next, _)| *next);
+        sections.push(DocumentSection {
+            id: format!("s{}", index + 1),
+            heading: heading.clone(),
+            range: *start..end,
+        });
+    }
+    sections
+}
+
+fn is_heading(line: &str) -> bool {
+    let hashes = line.bytes().take_while(|b| *b == b'#').count();
+    (1..=6).contains(&hashes)
+        && line[hashes..]
+            .chars()
+            .next()
+            .map_or(true, char::is_whitespace)
+}
+
+/// Text of section `section_id` of `content`.
+pub(crate) fn section_text<'a>(content: &'a str, section_id: &str) -> Option<&'a str> {
+    document_sections(content)
+        .into_iter()
+        .find(|section| section.id == section_id)
+        .map(|section| content[section.range].trim_end())
+}
+
+/// Replace sections of `content`, addressed by the ids `document_sections`
+/// gives `content`. The text of a section includes its heading; empty text
+/// deletes the section. A later edit of the same section wins.
+pub(crate) fn apply_section_edits(
+    content: &str,
+    edits: &[(String, String)],
+) -> Result<String, String> {
+    let sections = document_sections(content);
+    let mut resolved: Vec<(Range<usize>, &str)> = Vec::new();
+    for (section_id, text) in edits {
+        let section = sections
+            .iter()
+            .find(|section| &section.id == section_id)
+            .ok_or_else(|| format!("unknown section '{section_id}'"))?;
+        let range = section.range.clone();
+        let text = text.trim_end();
+        let body_end = range.start + content[range.clone()].trim_end().len();
+        let target = if text.is_empty() {
+            range
+        } else {
+            range.start..body_end
+        };
+        resolved.retain(|(existing, _)| existing.start != target.start);
+        resolved.push((target, text));
+    }
+
+    resolved.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+    let mut next = content.to_string();
+    for (range, text) in resolved {
+        next.replace_range(range, text);
+    }
+    Ok(next)
+}
+
+/// Assemble the document context of a loop: full text for a run's first
+/// loop or a small document, sections otherwise.
+pub(crate) fn assemble(input: LoopContextInput<'_>) -> LoopContext {
+    let Some((base_version_id, base_content)) = input.base else {
+        return full_text(input.content);
+    };
+    if estimate_tokens(input.content) <= FULL_TEXT_MAX_TOKENS {
+        return full_text(input.content);
+    }
+    let sections = document_sections(input.content);
+    if sections.len() < 2 {
+        return full_text(input.content);
+    }
+
+    let changes = section_changes(base_content, input.content);
+    let changed_ids: Vec<&str> = changes
+        .iter()
+        .filter_map(|change| change.section_id.as_deref())
+        .collect();
+    let relevant_ids = relevant_sections(&sections, input.content, input.query);
+
+    let mut text = String::from("## Document Outline\n\n");
+    for section in &sections {
+        text.push_str(&format!(
+            "- {} {} (~{} tokens)\n",
+            section.id,
+            heading_label(&section.heading),
+            estimate_tokens(&input.content[section.range.clone()])
+        ));
+    }
+
+    text.push_str(&format!("\n## Changes Since Version {base_version_id}\n\n"));
+    if changes.is_empty() && input.changesets.is_empty() {
+        text.push_str("No changes.\n");
+    }
+    for change in &changes {
+        let heading = heading_label(&change.heading);
+        match &change.section_id {
+            Some(id) => text.push_str(&format!("- {id} {heading}: {}\n", change.kind)),
+            None => text.push_str(&format!("- {heading}: {}\n", change.kind)),
+        }
+    }
+    for changeset in input.changesets {
+        let version = changeset
+            .target_version_id
+            .map(|id| format!("v{id}"))
+            .unwrap_or_else(|| "pending".to_string());
+        let impact = format!("{:?}", changeset.impact).to_ascii_lowercase();
+        text.push_str(&format!(
+            "- {version} ({impact} impact): {}\n",
+            changeset.summary.trim()
+        ));
+    }
+
+    text.push_str("\n## Relevant Sections\n");
+    for section in &sections {
+        let id = section.id.as_str();
+        if changed_ids.contains(&id) || relevant_ids.contains(&id) {
+            text.push_str(&format!(
+                "\n### {id}\n\n{}\n",
+                input.content[section.range.clone()].trim_end()
+            ));
+        }
+    }
+
+    LoopContext {
+        mode: DocumentContextMode::Sectioned,
+        text,
+    }
+}
+
+fn heading_label(heading: &str) -> &str {
+    if heading.is_empty() {
+        "(text before the first heading)"
+    } else {
+        heading
+    }
+}
+
+fn full_text(content: &str) -> LoopContext {
+    LoopContext {
+        mode: DocumentContextMode::FullText,
+        text: content.to_string(),
+    }
+}
+
+/// Up to `MAX_RELEVANT_SECTIONS` ids, best lexical match first. Query words
+/// count three times in a heading and once in the body.
+fn relevant_sections<'a>(
+    sections: &'a [DocumentSection],
+    content: &str,
+    query: &str,
+) -> Vec<&'a str> {
+    let mut terms: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|word| word.chars().count() >= 4 && !STOP_WORDS.contains(&word.as_str()))
+        .collect();
+    terms.sort();
+    terms.dedup();
+
+    let mut scored: Vec<(usize, &str)> = sections
+        .iter()
+        .map(|section| {
+            let heading = section.heading.to_lowercase();
+            let body = content[section.range.clone()].to_lowercase();
+            let score = terms
+                .iter()
+                .map(|term| {
+                    if heading.contains(term.as_str()) {
+                        3
+                    } else if body.contains(term.as_str()) {
+                        1
+                    } else {
+                        0
+                    }
+                })
+                .sum();
+            (score, section.id.as_str())
+        })
+        .filter(|(score, _)| *score > 0)
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored
+        .into_iter()
+        .take(MAX_RELEVANT_SECTIONS)
+        .map(|(_, id)| id)
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SectionChange {
+    /// Id in the current document, `None` when the section was removed.
+    section_id: Option<String>,
+    heading: String,
+    kind: &'static str,
+}
+
+/// Sections added, modified or removed between `base` and `content`,
+/// matched by heading.
+fn section_changes(base: &str, content: &str) -> Vec<SectionChange> {
+    let mut base_sections: Vec<Option<DocumentSection>> =
+        document_sections(base).into_iter().map(Some).collect();
+    let mut changes = Vec::new();
+    for section in document_sections(content) {
+        let matched = base_sections
+            .iter_mut()
+            .find(|candidate| {
+                candidate
+                    .as_ref()
+                    .is_some_and(|candidate| candidate.heading == section.heading)
+            })
+            .and_then(Option::take);
+        let kind = match matched {
+            None => "added",
+            Some(previous)
+                if base[previous.range.clone()].trim() != content[section.range.clone()].trim() =>
+            {
+                "modified"
+            }
+            Some(_) => continue,
+        };
+        changes.push(SectionChange {
+            section_id: Some(section.id),
+            heading: section.heading,
+            kind,
+        });
+    }
+    changes.extend(
+        base_sections
+            .into_iter()
+            .flatten()
+            .map(|removed| SectionChange {
+                section_id: None,
+                heading: removed.heading,
+                kind: "removed",
+            }),
+    );
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A report long enough to be sent as sections.
+    fn large_document() -> String {
+        let topics = [
+            "Overview",
+            "Market Size",
+            "Pricing",
+            "Competitors",
+            "Distribution",
+            "Hiring Plan",
+            "Risks",
+            "Timeline",
+            "Budget",
+            "Legal Review",
+            "Support",
+            "Metrics",
+        ];
+        let mut doc = String::from("# Launch Report\n\nDraft for review.\n\n");
+        for topic in topics {
+            doc.push_str(&format!("## {topic}\n\n"));
+            for paragraph in 0..6 {
+                doc.push_str(&format!(
+                    "{topic} paragraph {paragraph}: the team compared several options, \
+                     noted open questions and recorded the figures gathered so far, \
+                     with more detail to follow once the next review is complete.\n\n"
+                ));
+            }
+        }
+        doc
+    }
+
+    fn section_id(content: &str, heading: &str) -> String {
+        document_sections(content)
+            .into_iter()
+            .find(|section| section.heading == heading)
+            .map(|section| section.id)
+            .expect("heading should exist")
+    }
+
+    #[test]
+    fn sections_split_at_headings_outside_code_fences() {
+        let doc = "Intro line.\n\n# Title\n\n```sh\n# not a heading\n```\n\n## Next\nBody.";
+        let sections = document_sections(doc);
+        let ids: Vec<&str> = sections.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["s0", "s1", "s2"]);
+        assert_eq!(sections[1].heading, "# Title");
+        assert_eq!(section_text(doc, "s2"), Some("## Next\nBody."));
+        assert_eq!(
+            sections.last().map(|s| s.range.end),
+            Some(doc.len()),
+            "sections cover the whole document"
+        );
+        assert_eq!(document_sections("#hashtag only").len(), 1);
+    }
+
+    #[test]
+    fn first_loop_and_small_documents_get_full_text() {
+        let doc = large_document();
+        let first = assemble(LoopContextInput {
+            content: &doc,
+            query: "pricing",
+            base: None,
+            changesets: &[],
+        });
+        assert_eq!(first.mode, DocumentContextMode::FullText);
+        assert_eq!(first.text, doc);
+
+        let small = "# Note\n\nShort.";
+        let follow_up = assemble(LoopContextInput {
+            content: small,
+            query: "pricing",
+            base: Some((1, small)),
+            changesets: &[],
+        });
+        assert_eq!(follow_up.mode, DocumentContextMode::FullText);
+    }
+
+    #[test]
+    fn follow_up_context_is_smaller_and_section_edits_land_on_target() {
+        let base = large_document();
+        let doc = base.replace(
+            "Risks paragraph 1: the team",
+            "Risks paragraph 1: after the audit the team",
+        );
+        let changesets = vec![WriterRunChangesetPayload {
+            patch_id: "patch-1".to_string(),
+            loop_id: None,
+            target_version_id: Some(3),
+            source: Some("user".to_string()),
+            summary: "Noted the audit in the risks section.".to_string(),
+            impact: shared_types::ChangesetImpact::Low,
+            op_taxonomy: vec!["insert".to_string()],
+            confirmed_by: None,
+        }];
+        let query = "Incorporate the researcher's competitor pricing findings.";
+
+        let full_prompt = format!("## Current Document\n\n{doc}");
+        let context = assemble(LoopContextInput {
+            content: &doc,
+            query,
+            base: Some((2, &base)),
+            changesets: &changesets,
+        });
+        assert_eq!(context.mode, DocumentContextMode::Sectioned);
+        let full_tokens = estimate_tokens(&full_prompt);
+        let sectioned_tokens = estimate_tokens(&context.text);
+        assert!(
+            sectioned_tokens * 3 < full_tokens,
+            "sectioned {sectioned_tokens} tokens vs full {full_tokens}"
+        );
+
+        let pricing = section_id(&doc, "## Pricing");
+        let competitors = section_id(&doc, "## Competitors");
+        let risks = section_id(&doc, "## Risks");
+        let timeline = section_id(&doc, "## Timeline");
+        assert!(context.text.contains(&format!("- {pricing} ## Pricing")));
+        assert!(context
+            .text
+            .contains(&format!("### {pricing}\n\n## Pricing\n")));
+        assert!(context.text.contains(&format!("### {competitors}\n")));
+        assert!(context
+            .text
+            .contains(&format!("- {risks} ## Risks: modified")));
+        assert!(context.text.contains(&format!("### {risks}\n")));
+        assert!(!context.text.contains(&format!("### {timeline}\n")));
+        assert!(context.text.contains("- v3 (low impact): Noted the audit"));
+
+        let new_pricing = "## Pricing\n\nCompetitors charge $20 to $40 a seat.";
+        let edited = apply_section_edits(&doc, &[(pricing.clone(), new_pricing.to_string())])
+            .expect("section edit applies");
+        let old_pricing = section_text(&doc, &pricing).unwrap();
+        assert_eq!(edited, doc.replacen(old_pricing, new_pricing, 1));
+        assert_eq!(section_text(&edited, &pricing), Some(new_pricing));
+        assert_eq!(
+            section_text(&edited, &competitors),
+            section_text(&doc, &competitors)
+        );
+    }
+
+    #[test]
+    fn section_edits_combine_and_reject_unknown_ids() {
+        let doc = "# A\n\nOne.\n\n# B\n\nTwo.\n\n# C\n\nThree.\n";
+        let edited = apply_section_edits(
+            doc,
+            &[
+                ("s1".to_string(), "# A\n\nFirst draft.".to_string()),
+                ("s3".to_string(), "# C\n\nTres.".to_string()),
+                ("s1".to_string(), "# A\n\nUno.".to_string()),
+                ("s2".to_string(), String::new()),
+            ],
+        )
+        .unwrap();
+        assert_eq!(edited, "# A\n\nUno.\n\n# C\n\nTres.\n");
+        assert!(apply_section_edits(doc, &[("s9".to_string(), String::new())]).is_err());
+    }
+}
@@ -0,0 +1,199 @@
+//! Soft block locks held by writer loops on a run document.
+//!
+//! Locks live only in memory; the `writer.run.lock` events are the durable
+//! record. Every lock carries an expiry, so a writer restart or a lost
+//! release can never leave a block locked for longer than its TTL.
+
+use chrono::{DateTime, Utc};
+use shared_types::{writer_blocks, WriterRunLockPayload};
+use std::collections::HashSet;
+
+#[derive(Debug, Default)]
+pub struct BlockLocks {
+    locks: Vec<WriterRunLockPayload>,
+}
+
+impl BlockLocks {
+    /// Record a lock on `block_ids` until `now + ttl_ms`.
+    pub fn declare(
+        &mut self,
+        lock_id: String,
+        block_ids: Vec<String>,
+        ttl_ms: u64,
+        now: DateTime<Utc>,
+    ) -> WriterRunLockPayload {
+        let lock = WriterRunLockPayload {
+            lock_id,
+            block_ids,
+            ttl_ms,
+            expires_at: now + chrono::Duration::milliseconds(ttl_ms as i64),
+            released: false,
+            reason: None,
+        };
+        self.locks.retain(|held| held.lock_id != lock.lock_id);
+        self.locks.push(lock.clone());
+        lock
+    }
+
+    /// Add `block_ids` to a held lock. Returns the updated lock, or `None` if
+    /// it is no longer held or already covered every block.
+    pub fn extend(
+        &mut self,
+        lock_id: &str,
+        block_ids: Vec<String>,
+        now: DateTime<Utc>,
+    ) -> Option<WriterRunLockPayload> {
+        let lock = self
+            .locks
+            .iter_mut()
+            .find(|lock| lock.lock_id == lock_id && lock.is_active(now))?;
+        let before = lock.block_ids.len();
+        for block_id in block_ids {
+            if !lock.block_ids.contains(&block_id) {
+                lock.block_ids.push(block_id);
+            }
+        }
+        (lock.block_ids.len() > before).then(|| lock.clone())
+    }
+
+    /// End a lock early. Returns it marked released, or `None` if it was not
+    /// held (already released, or expired and pruned).
+    pub fn release(&mut self, lock_id: &str, reason: &str) -> Option<WriterRunLockPayload> {
+        let index = self.locks.iter().position(|lock| lock.lock_id == lock_id)?;
+        let mut lock = self.locks.remove(index);
+        lock.released = true;
+        lock.reason = Some(reason.to_string());
+        Some(lock)
+    }
+
+    /// End every lock, e.g. when the run completes or fails.
+    pub fn release_all(&mut self, reason: &str) -> Vec<WriterRunLockPayload> {
+        self.locks
+            .drain(..)
+            .map(|mut lock| {
+                lock.released = true;
+                lock.reason = Some(reason.to_string());
+                lock
+            })
+            .collect()
+    }
+
+    /// Drop locks whose TTL has run out.
+    pub fn prune(&mut self, now: DateTime<Utc>) {
+        self.locks.retain(|lock| lock.is_active(now));
+    }
+
+    pub fn active(&self, now: DateTime<Utc>) -> impl Iterator<Item = &WriterRunLockPayload> {
+        self.locks.iter().filter(move |lock| lock.is_active(now))
+    }
+
+    /// Locked blocks of `base` that an edit producing `next` changes or
+    /// removes. Blocks are matched by content id, so edits to other blocks,
+    /// and new blocks inserted between locked ones, touch nothing.
+    pub fn touched_by_edit(&self, base: &str, next: &str, now: DateTime<Utc>) -> Vec<String> {
+        let locked: HashSet<&str> = self
+            .active(now)
+            .flat_map(|lock| lock.block_ids.iter().map(String::as_str))
+            .collect();
+        if locked.is_empty() {
+            return Vec::new();
+        }
+        changed_block_ids(base, next)
+            .into_iter()
+            .filter(|block_id| locked.contains(block_id.as_str()))
+            .collect()
+    }
+}
+
+/// Ids of the non-empty blocks of `base` that `next` changes or removes, the
+/// targets a writer loop declares before submitting `next` as a revision.
+pub fn changed_block_ids(base: &str, next: &str) -> Vec<String> {
+    let kept: HashSet<String> = writer_blocks(next)
+        .into_iter()
+        .map(|block| block.block_id)
+        .collect();
+    writer_blocks(base)
+        .into_iter()
+        .filter(|block| !base[block.range.clone()].trim().is_empty())
+        .map(|block| block.block_id)
+        .filter(|block_id| !kept.contains(block_id))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "# Plan\n\nIntro paragraph.\n\nPricing details.";
+
+    fn locks_on_intro(now: DateTime<Utc>, ttl_ms: u64) -> BlockLocks {
+        let intro = writer_blocks(BASE)[1].block_id.clone();
+        let mut locks = BlockLocks::default();
+        locks.declare("lock-1".to_string(), vec![intro], ttl_ms, now);
+        locks
+    }
+
+    #[test]
+    fn touched_by_edit_reports_only_changed_locked_blocks() {
+        let now = Utc::now();
+        let locks = locks_on_intro(now, 60_000);
+        let intro = writer_blocks(BASE)[1].block_id.clone();
+
+        let edits_intro = BASE.replace("Intro paragraph.", "Intro, by the user.");
+        assert_eq!(locks.touched_by_edit(BASE, &edits_intro, now), vec![intro]);
+
+        let edits_pricing = BASE.replace("Pricing details.", "Pricing, by the user.");
+        assert!(locks.touched_by_edit(BASE, &edits_pricing, now).is_empty());
+
+        let inserts_block = BASE.replace("\n\nPricing", "\n\nA new note.\n\nPricing");
+        assert!(locks.touched_by_edit(BASE, &inserts_block, now).is_empty());
+
+        let deletes_intro = BASE.replace("Intro paragraph.\n\n", "");
+        assert_eq!(locks.touched_by_edit(BASE, &deletes_intro, now).len(), 1);
+    }
+
+    #[test]
+    fn locks_expire_on_ttl_and_release() {
+        let now = Utc::now();
+        let edited = BASE.replace("Intro paragraph.", "Intro, by the user.");
+
+        let mut locks = locks_on_intro(now, 1_000);
+        let later = now + chrono::Duration::seconds(2);
+        assert!(locks.touched_by_edit(BASE, &edited, later).is_empty());
+        locks.prune(later);
+        assert_eq!(locks.active(now).count(), 0);
+
+        let mut locks = locks_on_intro(now, 60_000);
+        let released = locks.release("lock-1", "completed").unwrap();
+        assert!(released.released);
+        assert_eq!(released.reason.as_deref(), Some("completed"));
+        assert!(locks.touched_by_edit(BASE, &edited, now).is_empty());
+        assert!(locks.release("lock-1", "completed").is_none());
+
+        let mut locks = locks_on_intro(now, 60_000);
+        assert_eq!(locks.release_all("failed").len(), 1);
+        assert!(locks.touched_by_edit(BASE, &edited, now).is_empty());
+    }
+
+    #[test]
+    fn locks_extend_to_declared_targets() {
+        let now = Utc::now();
+        let mut locks = BlockLocks::default();
+        locks.declare("lock-1".to_string(), Vec::new(), 60_000, now);
+        let edited = BASE.replace("Pricing details.", "Pricing, by the agent.");
+        assert!(locks.touched_by_edit(BASE, &edited, now).is_empty());
+
+        let targets = changed_block_ids(BASE, &edited);
+        assert_eq!(targets, vec![writer_blocks(BASE)[2].block_id.clone()]);
+        let lock = locks.extend("lock-1", targets.clone(), now).unwrap();
+        assert_eq!(lock.block_ids, targets);
+        assert!(locks.extend("lock-1", targets, now).is_none());
+        assert_eq!(locks.touched_by_edit(BASE, &edited, now).len(), 1);
+
+        assert!(changed_block_ids("", "Anything.").is_empty());
+        locks.release_all("completed");
+        assert!(locks
+            .extend("lock-1", vec!["blk-x".to_string()], now)
+            .is_none());
+    }
+}
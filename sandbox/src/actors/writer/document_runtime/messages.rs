@@ -55,6 +55,9 @@ pub enum WriterDocumentError {
     #[error("Document write failed: {0}")]
     WriteFailed(String),
 
+    #[error("Blocks locked by an active writer run: {}", .0.join(", "))]
+    BlocksLocked(Vec<String>),
+
     #[error("Run ID mismatch: expected {expected}, got {actual}")]
     RunIdMismatch { expected: String, actual: String },
 
@@ -3,12 +3,13 @@
 //! This replaces the former per-run actor process with an in-process
 //! runtime object owned by WriterActor.
 
+mod locks;
 mod messages;
 mod state;
 
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tokio::fs;
 
 pub use messages::{ApplyPatchResult, PatchOp, PatchOpKind, SectionState, WriterDocumentError};
@@ -17,6 +18,9 @@ pub use state::{
     VersionSource, WriterDocumentState,
 };
 
+pub use locks::changed_block_ids;
+use locks::BlockLocks;
+
 use crate::actors::event_store::{AppendEvent, EventStoreMsg};
 
 const BASE_RUNS_DIR: &str = "conductor/runs";
@@ -78,6 +82,8 @@ impl WriterDocumentRuntime {
                 document_path_relative: document_path_relative.to_string_lossy().to_string(),
                 revision,
                 document,
+                block_locks: BlockLocks::default(),
+                last_patch_id: None,
             },
         };
 
@@ -91,6 +97,47 @@ impl WriterDocumentRuntime {
         Ok(runtime)
     }
 
+    /// Write a run document to disk without starting a runtime, for runs
+    /// loaded from a run bundle. `load` hydrates it on first use.
+    pub async fn import_document(
+        root_dir: &Path,
+        run_id: &str,
+        document: RunDocument,
+    ) -> Result<(), WriterDocumentError> {
+        let run_dir = root_dir.join(BASE_RUNS_DIR).join(run_id);
+        fs::create_dir_all(&run_dir)
+            .await
+            .map_err(|e| WriterDocumentError::WriteFailed(e.to_string()))?;
+
+        let revision = 1;
+        let content = format!("<!-- revision:{revision} -->\n{}", document.to_markdown());
+        let sidecar = PersistedWriterDocumentSnapshot { revision, document };
+        let sidecar_raw = serde_json::to_string_pretty(&sidecar).map_err(|e| {
+            WriterDocumentError::WriteFailed(format!("Failed to encode sidecar: {e}"))
+        })?;
+
+        fs::write(run_dir.join("draft.md"), content)
+            .await
+            .map_err(|e| WriterDocumentError::WriteFailed(e.to_string()))?;
+        fs::write(run_dir.join("draft.writer-state.json"), sidecar_raw)
+            .await
+            .map_err(|e| WriterDocumentError::WriteFailed(e.to_string()))
+    }
+
+    /// Read a run document from disk without starting a runtime or creating
+    /// anything. A runtime persists every change, so this is its current
+    /// state. `None` when the run has no document.
+    pub async fn read_document(root_dir: &Path, run_id: &str) -> Option<RunDocument> {
+        let run_dir = root_dir.join(BASE_RUNS_DIR).join(run_id);
+        if let Ok(raw) = fs::read_to_string(run_dir.join("draft.writer-state.json")).await {
+            if let Ok(snapshot) = serde_json::from_str::<PersistedWriterDocumentSnapshot>(&raw) {
+                return Some(snapshot.document);
+            }
+        }
+        let content = fs::read_to_string(run_dir.join("draft.md")).await.ok()?;
+        RunDocument::from_legacy_markdown(&content).ok()
+    }
+
     pub fn run_id(&self) -> &str {
         self.state.run_id.as_str()
     }
@@ -115,6 +162,19 @@ impl WriterDocumentRuntime {
         self.state.revision
     }
 
+    /// `patch_id` of the last patch event emitted for this document.
+    pub fn last_patch_id(&self) -> Option<&str> {
+        self.state.last_patch_id.as_deref()
+    }
+
+    pub fn review_mode(&self) -> bool {
+        self.state.document.review_mode
+    }
+
+    pub fn lock_strictness(&self) -> shared_types::WriterLockStrictness {
+        self.state.document.lock_strictness
+    }
+
     pub fn document_markdown(&self) -> String {
         self.state.document.to_markdown()
     }
@@ -137,6 +197,14 @@ impl WriterDocumentRuntime {
             .ok_or(WriterDocumentError::VersionNotFound(version_id))
     }
 
+    pub fn get_overlay(&self, overlay_id: &str) -> Result<Overlay, WriterDocumentError> {
+        self.state
+            .document
+            .get_overlay(overlay_id)
+            .cloned()
+            .ok_or_else(|| WriterDocumentError::OverlayNotFound(overlay_id.to_string()))
+    }
+
     pub fn list_versions(&self) -> Vec<DocumentVersion> {
         let mut versions = self.state.document.versions.clone();
         versions.sort_by_key(|version| version.version_id);
@@ -176,8 +244,25 @@ impl WriterDocumentRuntime {
         source: VersionSource,
     ) -> Result<DocumentVersion, WriterDocumentError> {
         self.ensure_run_id(run_id)?;
+        let event_source = match source {
+            VersionSource::UserSave => "user",
+            _ => "writer",
+        };
+        if source == VersionSource::UserSave {
+            let base =
+                self.get_version(parent_version_id.unwrap_or(self.state.document.head_version_id))?;
+            self.check_user_edit(&base.content, &content).await?;
+        }
         let version = self
-            .create_version_internal(parent_version_id, content, source, "writer", None)
+            .create_version_internal(
+                parent_version_id,
+                content,
+                source,
+                event_source,
+                None,
+                None,
+                None,
+            )
             .await?;
         self.emit_progress_event(
             "version_created",
@@ -188,6 +273,39 @@ impl WriterDocumentRuntime {
         Ok(version)
     }
 
+    /// Record a user undo/redo as a new version whose patch event carries the
+    /// inverse ops and the id of the patch it reverts.
+    pub async fn create_revert_version(
+        &mut self,
+        run_id: &str,
+        parent_version_id: u64,
+        content: String,
+        ops: Vec<shared_types::PatchOp>,
+        undo_of: &str,
+    ) -> Result<DocumentVersion, WriterDocumentError> {
+        self.ensure_run_id(run_id)?;
+        let base = self.get_version(parent_version_id)?;
+        self.check_user_edit(&base.content, &content).await?;
+        let version = self
+            .create_version_internal(
+                Some(parent_version_id),
+                content,
+                VersionSource::UserSave,
+                "user",
+                None,
+                Some((ops, undo_of)),
+                None,
+            )
+            .await?;
+        self.emit_progress_event(
+            "version_reverted",
+            format!("Created version {} reverting {undo_of}", version.version_id),
+            Vec::new(),
+        )
+        .await;
+        Ok(version)
+    }
+
     pub async fn create_overlay(
         &mut self,
         run_id: &str,
@@ -217,6 +335,53 @@ impl WriterDocumentRuntime {
         Ok(overlay)
     }
 
+    /// Park an agent revision as a pending proposal for human review and mark
+    /// the run blocked until the overlay is accepted or dismissed.
+    pub async fn request_revision_review(
+        &mut self,
+        run_id: &str,
+        base_version_id: u64,
+        content: String,
+        impact: shared_types::ChangesetImpact,
+    ) -> Result<Overlay, WriterDocumentError> {
+        let overlay = self
+            .create_overlay(
+                run_id,
+                base_version_id,
+                OverlayAuthor::Writer,
+                OverlayKind::Proposal,
+                vec![shared_types::PatchOp::Replace {
+                    pos: 0,
+                    len: u64::MAX,
+                    text: content,
+                }],
+            )
+            .await?;
+        self.emit_event(
+            shared_types::EventTopic::WriterReviewRequested,
+            serde_json::json!({
+                "run_id": self.state.run_id,
+                "desktop_id": self.state.desktop_id,
+                "session_id": self.state.session_id,
+                "thread_id": self.state.thread_id,
+                "document_path": self.state.document_path_relative,
+                "overlay_id": overlay.overlay_id,
+                "base_version_id": base_version_id,
+                "impact": impact,
+            }),
+        )
+        .await;
+        self.emit_status_event(
+            shared_types::WriterRunStatusKind::Blocked,
+            Some(format!(
+                "High-impact revision awaiting review (overlay {})",
+                overlay.overlay_id
+            )),
+        )
+        .await;
+        Ok(overlay)
+    }
+
     pub async fn dismiss_overlay(
         &mut self,
         run_id: &str,
@@ -239,6 +404,221 @@ impl WriterDocumentRuntime {
         Ok(())
     }
 
+    /// Toggle review mode; while on, agent patches become proposal overlays.
+    pub async fn set_review_mode(
+        &mut self,
+        run_id: &str,
+        enabled: bool,
+    ) -> Result<(), WriterDocumentError> {
+        self.ensure_run_id(run_id)?;
+        if self.state.document.review_mode == enabled {
+            return Ok(());
+        }
+        self.state.document.review_mode = enabled;
+        self.persist_sidecar().await?;
+        self.emit_progress_event(
+            "review_mode_changed",
+            format!(
+                "Review mode {}",
+                if enabled { "enabled" } else { "disabled" }
+            ),
+            Vec::new(),
+        )
+        .await;
+        Ok(())
+    }
+
+    /// Choose whether user edits to locked blocks are warned about or refused.
+    pub async fn set_lock_strictness(
+        &mut self,
+        run_id: &str,
+        strictness: shared_types::WriterLockStrictness,
+    ) -> Result<(), WriterDocumentError> {
+        self.ensure_run_id(run_id)?;
+        if self.state.document.lock_strictness == strictness {
+            return Ok(());
+        }
+        self.state.document.lock_strictness = strictness;
+        self.persist_sidecar().await?;
+        self.emit_progress_event(
+            "lock_strictness_changed",
+            format!("Block lock strictness set to {strictness:?}"),
+            Vec::new(),
+        )
+        .await;
+        Ok(())
+    }
+
+    /// Start a lock for a writer loop on `block_ids`, which may be empty
+    /// until the loop declares its targets. The lock lapses after `ttl_ms`
+    /// unless released earlier.
+    pub async fn lock_blocks(
+        &mut self,
+        run_id: &str,
+        block_ids: Vec<String>,
+        ttl_ms: u64,
+    ) -> Result<shared_types::WriterRunLockPayload, WriterDocumentError> {
+        self.ensure_run_id(run_id)?;
+        let now = Utc::now();
+        self.state.block_locks.prune(now);
+        let lock =
+            self.state
+                .block_locks
+                .declare(ulid::Ulid::new().to_string(), block_ids, ttl_ms, now);
+        self.emit_lock_event(lock.clone()).await;
+        Ok(lock)
+    }
+
+    /// Add blocks a writer loop is about to rewrite to its lock. Returns false
+    /// if the lock is no longer held or already covered them.
+    pub async fn add_lock_targets(
+        &mut self,
+        run_id: &str,
+        lock_id: &str,
+        block_ids: Vec<String>,
+    ) -> Result<bool, WriterDocumentError> {
+        self.ensure_run_id(run_id)?;
+        let Some(lock) = self
+            .state
+            .block_locks
+            .extend(lock_id, block_ids, Utc::now())
+        else {
+            return Ok(false);
+        };
+        self.emit_lock_event(lock).await;
+        Ok(true)
+    }
+
+    /// Release a lock before its TTL. Returns false if it was no longer held.
+    pub async fn release_block_lock(
+        &mut self,
+        run_id: &str,
+        lock_id: &str,
+        reason: &str,
+    ) -> Result<bool, WriterDocumentError> {
+        self.ensure_run_id(run_id)?;
+        let Some(lock) = self.state.block_locks.release(lock_id, reason) else {
+            return Ok(false);
+        };
+        self.emit_lock_event(lock).await;
+        Ok(true)
+    }
+
+    /// Check a user edit from `base` to `next` against held block locks.
+    /// In warn mode the edit goes through with a warning event; in reject
+    /// mode it fails with the touched block ids.
+    async fn check_user_edit(&mut self, base: &str, next: &str) -> Result<(), WriterDocumentError> {
+        let now = Utc::now();
+        self.state.block_locks.prune(now);
+        let touched = self.state.block_locks.touched_by_edit(base, next, now);
+        if touched.is_empty() {
+            return Ok(());
+        }
+        match self.state.document.lock_strictness {
+            shared_types::WriterLockStrictness::Reject => {
+                Err(WriterDocumentError::BlocksLocked(touched))
+            }
+            shared_types::WriterLockStrictness::Warn => {
+                tracing::warn!(
+                    run_id = %self.state.run_id,
+                    blocks = ?touched,
+                    "User edit touches blocks locked by an active writer run"
+                );
+                self.emit_progress_event(
+                    "block_lock_warning",
+                    format!(
+                        "User edit touches {} block(s) the agent is editing",
+                        touched.len()
+                    ),
+                    Vec::new(),
+                )
+                .await;
+                Ok(())
+            }
+        }
+    }
+
+    /// Apply an accepted proposal as a new head version attributed to its
+    /// author, recording `confirmed_by` on the patch event.
+    ///
+    /// `content` is the proposal already rebased onto `parent_version_id`.
+    pub async fn accept_overlay(
+        &mut self,
+        run_id: &str,
+        overlay_id: &str,
+        parent_version_id: u64,
+        content: String,
+        confirmed_by: &str,
+    ) -> Result<DocumentVersion, WriterDocumentError> {
+        self.ensure_run_id(run_id)?;
+        let overlay = self.get_overlay(overlay_id)?;
+        let author = Self::overlay_author_source(&overlay.author);
+        let version = self
+            .create_version_internal(
+                Some(parent_version_id),
+                content,
+                Self::source_to_version_source(author),
+                author,
+                None,
+                None,
+                Some(confirmed_by),
+            )
+            .await?;
+        // Creating the version supersedes pending overlays on the parent,
+        // this one included, so mark it applied afterwards.
+        if let Some(overlay) = self.state.document.get_overlay_mut(overlay_id) {
+            overlay.status = OverlayStatus::Applied;
+        }
+        self.persist_sidecar().await?;
+        self.emit_progress_event(
+            "overlay_accepted",
+            format!(
+                "Accepted overlay {overlay_id} as version {}",
+                version.version_id
+            ),
+            Vec::new(),
+        )
+        .await;
+        Ok(version)
+    }
+
+    /// Replace a stale proposal with a fresh one against the current head.
+    pub async fn repropose_overlay(
+        &mut self,
+        run_id: &str,
+        overlay_id: &str,
+        content: String,
+    ) -> Result<Overlay, WriterDocumentError> {
+        self.ensure_run_id(run_id)?;
+        let stale = self.get_overlay(overlay_id)?;
+        let head = self.head_version()?;
+        let fresh = self
+            .create_overlay_internal(
+                head.version_id,
+                stale.author.clone(),
+                stale.kind.clone(),
+                Self::diff_full_replace(&head.content, &content),
+                Self::overlay_author_source(&stale.author),
+                None,
+                Some(content),
+            )
+            .await?;
+        if let Some(overlay) = self.state.document.get_overlay_mut(overlay_id) {
+            overlay.status = OverlayStatus::Discarded;
+        }
+        self.persist_sidecar().await?;
+        self.emit_progress_event(
+            "overlay_reproposed",
+            format!(
+                "Re-proposed overlay {overlay_id} as {} on version {}",
+                fresh.overlay_id, head.version_id
+            ),
+            Vec::new(),
+        )
+        .await;
+        Ok(fresh)
+    }
+
     pub async fn apply_patch(
         &mut self,
         run_id: &str,
@@ -257,6 +637,12 @@ impl WriterDocumentRuntime {
             .map(|version| version.content.clone())
             .unwrap_or_default();
         let (next_content, lines_modified) = Self::apply_legacy_line_patch_ops(&base_content, &ops);
+        let proposal = proposal
+            || (self.state.document.review_mode
+                && Self::source_to_patch_source(source) == shared_types::PatchSource::Agent);
+        if !proposal && Self::source_to_patch_source(source) == shared_types::PatchSource::User {
+            self.check_user_edit(&base_content, &next_content).await?;
+        }
 
         if proposal {
             let diff_ops = Self::diff_full_replace(&base_content, &next_content);
@@ -293,6 +679,8 @@ impl WriterDocumentRuntime {
                 Self::source_to_version_source(source),
                 source,
                 Some(section_id),
+                None,
+                None,
             )
             .await?;
         self.emit_progress_event(
@@ -393,6 +781,16 @@ impl WriterDocumentRuntime {
         };
 
         self.emit_status_event(status, Some(status_message)).await;
+        if matches!(section_state, SectionState::Complete | SectionState::Failed) {
+            let reason = if section_state == SectionState::Complete {
+                "completed"
+            } else {
+                "failed"
+            };
+            for lock in self.state.block_locks.release_all(reason) {
+                self.emit_lock_event(lock).await;
+            }
+        }
         Ok(())
     }
 
@@ -544,6 +942,15 @@ impl WriterDocumentRuntime {
         }
     }
 
+    pub fn overlay_author_source(author: &OverlayAuthor) -> &'static str {
+        match author {
+            OverlayAuthor::User => "user",
+            OverlayAuthor::Researcher => "researcher",
+            OverlayAuthor::Terminal => "terminal",
+            OverlayAuthor::Writer => "writer",
+        }
+    }
+
     fn full_document_ops(content: &str) -> Vec<shared_types::PatchOp> {
         vec![
             shared_types::PatchOp::Delete {
@@ -637,6 +1044,7 @@ impl WriterDocumentRuntime {
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn create_version_internal(
         &mut self,
         parent_version_id: Option<u64>,
@@ -644,6 +1052,8 @@ impl WriterDocumentRuntime {
         source: VersionSource,
         event_source: &str,
         section_id: Option<&str>,
+        revert: Option<(Vec<shared_types::PatchOp>, &str)>,
+        confirmed_by: Option<&str>,
     ) -> Result<DocumentVersion, WriterDocumentError> {
         let parent = parent_version_id.unwrap_or(self.state.document.head_version_id);
         if self.state.document.get_version(parent).is_none() {
@@ -673,15 +1083,21 @@ impl WriterDocumentRuntime {
         // Emit the canonical version content (body) for live UI patching.
         // The persisted markdown file may include wrapper/title formatting,
         // but writer UI state should track version content directly.
-        let live_content = version.content.clone();
+        // Reverts carry their inverse ops so the undo stack can be replayed.
+        let (ops, undo_of) = match revert {
+            Some((ops, undo_of)) => (ops, Some(undo_of)),
+            None => (Self::full_document_ops(&version.content), None),
+        };
         self.emit_patch_event(
             event_source,
             section_id,
-            Self::full_document_ops(&live_content),
+            ops,
             None,
             Some(parent),
             Some(version.version_id),
             None,
+            undo_of,
+            confirmed_by,
         )
         .await;
 
@@ -727,6 +1143,8 @@ impl WriterDocumentRuntime {
             Some(base_version_id),
             None,
             Some(&overlay.overlay_id),
+            None,
+            None,
         )
         .await;
 
@@ -754,12 +1172,17 @@ impl WriterDocumentRuntime {
         payload
     }
 
-    async fn emit_event(&self, event_type: &str, payload: serde_json::Value) {
+    async fn emit_event(&self, topic: shared_types::EventTopic, payload: serde_json::Value) {
+        let scope = shared_types::EventScope::new(
+            Some(self.state.session_id.clone()),
+            Some(self.state.thread_id.clone()),
+        );
         let event = AppendEvent {
-            event_type: event_type.to_string(),
-            payload,
+            event_type: topic.to_string(),
+            payload: scope.attach(payload),
             actor_id: format!("writer:{}", self.state.run_id),
             user_id: "system".to_string(),
+            idempotency_key: None,
         };
         let _ = self
             .state
@@ -772,11 +1195,13 @@ impl WriterDocumentRuntime {
             base: self.writer_run_event_base(),
             objective: self.state.objective.clone(),
         });
-        self.emit_event("writer.run.started", payload).await;
+        self.emit_event(shared_types::EventTopic::WriterRunStarted, payload)
+            .await;
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn emit_patch_event(
-        &self,
+        &mut self,
         source: &str,
         section_id: Option<&str>,
         ops: Vec<shared_types::PatchOp>,
@@ -784,11 +1209,15 @@ impl WriterDocumentRuntime {
         base_version_id: Option<u64>,
         target_version_id: Option<u64>,
         overlay_id: Option<&str>,
+        undo_of: Option<&str>,
+        confirmed_by: Option<&str>,
     ) {
+        let patch_id = ulid::Ulid::new().to_string();
+        self.state.last_patch_id = Some(patch_id.clone());
         let payload = Self::payload_from_writer_event(shared_types::WriterRunEvent::Patch {
             base: self.writer_run_event_base(),
             payload: shared_types::WriterRunPatchPayload {
-                patch_id: ulid::Ulid::new().to_string(),
+                patch_id,
                 source: Self::source_to_patch_source(source),
                 source_actor: Some(source.to_string()),
                 section_id: section_id.map(ToString::to_string),
@@ -797,9 +1226,12 @@ impl WriterDocumentRuntime {
                 base_version_id,
                 target_version_id,
                 overlay_id: overlay_id.map(ToString::to_string),
+                undo_of: undo_of.map(ToString::to_string),
+                confirmed_by: confirmed_by.map(ToString::to_string),
             },
         });
-        self.emit_event("writer.run.patch", payload).await;
+        self.emit_event(shared_types::EventTopic::WriterRunPatch, payload)
+            .await;
     }
 
     async fn emit_progress_event(
@@ -815,7 +1247,17 @@ impl WriterDocumentRuntime {
             progress_pct: None,
             source_refs,
         });
-        self.emit_event("writer.run.progress", payload).await;
+        self.emit_event(shared_types::EventTopic::WriterRunProgress, payload)
+            .await;
+    }
+
+    async fn emit_lock_event(&self, lock: shared_types::WriterRunLockPayload) {
+        let payload = Self::payload_from_writer_event(shared_types::WriterRunEvent::Lock {
+            base: self.writer_run_event_base(),
+            payload: lock,
+        });
+        self.emit_event(shared_types::EventTopic::WriterRunLock, payload)
+            .await;
     }
 
     async fn emit_status_event(
@@ -828,7 +1270,8 @@ impl WriterDocumentRuntime {
             status,
             message,
         });
-        self.emit_event("writer.run.status", payload).await;
+        self.emit_event(shared_types::EventTopic::WriterRunStatus, payload)
+            .await;
     }
 }
 
@@ -5,6 +5,7 @@ use ractor::ActorRef;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
+use super::locks::BlockLocks;
 use crate::actors::event_store::EventStoreMsg;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
@@ -80,6 +81,13 @@ pub struct RunDocument {
     pub selected_source_refs: Vec<String>,
     #[serde(default)]
     pub observed_source_refs: Vec<String>,
+    /// When set, agent patches are parked as proposal overlays for review.
+    #[serde(default)]
+    pub review_mode: bool,
+    /// Whether user edits to blocks locked by a writer loop are warned about
+    /// or refused.
+    #[serde(default)]
+    pub lock_strictness: shared_types::WriterLockStrictness,
 }
 
 impl Default for RunDocument {
@@ -101,6 +109,8 @@ impl Default for RunDocument {
             head_version_id: 0,
             selected_source_refs: Vec::new(),
             observed_source_refs: Vec::new(),
+            review_mode: false,
+            lock_strictness: shared_types::WriterLockStrictness::default(),
         }
     }
 }
@@ -124,6 +134,8 @@ impl RunDocument {
             head_version_id: 0,
             selected_source_refs: Vec::new(),
             observed_source_refs: Vec::new(),
+            review_mode: false,
+            lock_strictness: shared_types::WriterLockStrictness::default(),
         }
     }
 
@@ -226,6 +238,8 @@ impl RunDocument {
             head_version_id: 1,
             selected_source_refs: Vec::new(),
             observed_source_refs: Vec::new(),
+            review_mode: false,
+            lock_strictness: shared_types::WriterLockStrictness::default(),
         };
 
         let proposal = proposal_lines.join("\n").trim().to_string();
@@ -262,4 +276,8 @@ pub struct WriterDocumentState {
     pub document_path_relative: String,
     pub revision: u64,
     pub document: RunDocument,
+    pub block_locks: BlockLocks,
+    /// `patch_id` of the latest `writer.run.patch` event; the changeset
+    /// summarizing that patch references it.
+    pub last_patch_id: Option<String>,
 }
@@ -6,7 +6,10 @@
 //! delegate to researcher/terminal actors via typed actor messages.
 
 mod adapter;
+mod context;
 pub mod document_runtime;
+pub mod proposals;
+pub mod undo;
 
 use async_trait::async_trait;
 use ractor::{Actor, ActorProcessingErr, ActorRef, RpcReplyPort};
@@ -16,14 +19,15 @@ use std::path::PathBuf;
 use tokio::sync::mpsc;
 
 use crate::actors::agent_harness::{AgentHarness, HarnessConfig, ToolExecution};
-use crate::actors::event_store::{AppendEvent, EventStoreMsg};
+use crate::actors::event_store::{AppendEvent, EventQuery, EventStoreMsg};
 use crate::actors::model_config::ModelRegistry;
 use crate::actors::researcher::{ResearcherMsg, ResearcherProgress};
 use crate::actors::terminal::{ensure_terminal_started, TerminalAgentProgress, TerminalMsg};
 use crate::observability::llm_trace::LlmTraceEmitter;
+use crate::secret_scan::{secret_scanner, ScanReport};
 use crate::supervisor::researcher::ResearcherSupervisorMsg;
 use crate::supervisor::terminal::TerminalSupervisorMsg;
-use adapter::{WriterDelegationAdapter, WriterUserPromptAdapter};
+use adapter::{LoopLock, WriterDelegationAdapter, WriterUserPromptAdapter};
 pub use document_runtime::{
     ApplyPatchResult, DocumentVersion, Overlay, OverlayAuthor, OverlayKind, OverlayStatus, PatchOp,
     PatchOpKind, RunDocument, SectionState, VersionSource, WriterDocumentArguments,
@@ -57,6 +61,9 @@ pub struct WriterState {
     /// Confirmed citation stubs per run_id — populated on DelegationWorkerCompleted,
     /// used to populate .qwy citation_registry on version save (Phase 3.5).
     confirmed_citations_by_run_id: HashMap<String, Vec<ProposedCitationStub>>,
+    /// Parent version of each run's latest writer loop: the base the next
+    /// loop's change summary starts from.
+    loop_base_version_by_run_id: HashMap<String, u64>,
 }
 
 #[derive(Debug, Clone)]
@@ -139,6 +146,9 @@ pub enum WriterMsg {
         objective: String,
         reply: RpcReplyPort<Result<(), WriterError>>,
     },
+    /// Hydrate a run document from its persisted state, e.g. after the
+    /// supervisor respawned this writer.
+    RestoreRunDocument { run_id: String },
     /// List run document versions for a registered run.
     ListWriterDocumentVersions {
         run_id: String,
@@ -163,6 +173,55 @@ pub enum WriterMsg {
         overlay_id: String,
         reply: RpcReplyPort<Result<(), WriterError>>,
     },
+    /// List proposal overlays awaiting review, flagging stale ones.
+    ListWriterDocumentProposals {
+        run_id: String,
+        reply: RpcReplyPort<Result<WriterProposalList, WriterError>>,
+    },
+    /// Accept a proposal overlay, rebasing it onto the head if needed.
+    AcceptWriterDocumentProposal {
+        run_id: String,
+        overlay_id: String,
+        reply: RpcReplyPort<Result<WriterProposalAcceptance, WriterError>>,
+    },
+    /// Re-propose a stale proposal overlay against the current head.
+    ReproposeWriterDocumentProposal {
+        run_id: String,
+        overlay_id: String,
+        reply: RpcReplyPort<Result<WriterProposal, WriterError>>,
+    },
+    /// Turn per-document review mode on or off.
+    SetWriterReviewMode {
+        run_id: String,
+        enabled: bool,
+        reply: RpcReplyPort<Result<bool, WriterError>>,
+    },
+    /// Choose whether user edits to locked blocks are warned about or refused.
+    SetWriterLockStrictness {
+        run_id: String,
+        strictness: shared_types::WriterLockStrictness,
+        reply: RpcReplyPort<Result<shared_types::WriterLockStrictness, WriterError>>,
+    },
+    /// Lock `block_ids` for a writer loop until `ttl_ms` elapses or the lock
+    /// is released. Loops start with no blocks and declare their targets.
+    LockWriterBlocks {
+        run_id: String,
+        block_ids: Vec<String>,
+        ttl_ms: u64,
+        reply: RpcReplyPort<Result<shared_types::WriterRunLockPayload, WriterError>>,
+    },
+    /// Add the blocks a writer loop is about to rewrite to its lock.
+    DeclareWriterLockTargets {
+        run_id: String,
+        lock_id: String,
+        block_ids: Vec<String>,
+    },
+    /// Release a block lock held by a finished writer loop.
+    ReleaseWriterBlockLock {
+        run_id: String,
+        lock_id: String,
+        reason: String,
+    },
     /// Create a canonical document version for a registered run.
     CreateWriterDocumentVersion {
         run_id: String,
@@ -171,6 +230,19 @@ pub enum WriterMsg {
         source: VersionSource,
         reply: RpcReplyPort<Result<DocumentVersion, WriterError>>,
     },
+    /// Submit an agent-authored revision; high-impact ones wait for review.
+    ProposeWriterRevision {
+        run_id: String,
+        parent_version_id: Option<u64>,
+        content: String,
+        reply: RpcReplyPort<Result<WriterRevisionOutcome, WriterError>>,
+    },
+    /// Revert the latest user edit (or, with `redo`, the latest undo).
+    RevertUserPatch {
+        run_id: String,
+        redo: bool,
+        reply: RpcReplyPort<Result<WriterRevertResult, WriterError>>,
+    },
     /// Submit a user prompt diff into writer ingress for a run.
     SubmitUserPrompt {
         run_id: String,
@@ -234,6 +306,8 @@ pub enum WriterMsg {
         call_id: String,
         objective: String,
         parent_version_id: u64,
+        /// How `objective` carries the parent version.
+        document_context: shared_types::DocumentContextMode,
     },
 }
 
@@ -304,17 +378,68 @@ struct ChangesetSummarizationCtx {
     event_store: ActorRef<EventStoreMsg>,
     model_registry: ModelRegistry,
     run_id: String,
+    /// Patch event the changeset summarizes.
+    patch_id: String,
     desktop_id: String,
     session_id: String,
     thread_id: String,
     document_path: String,
     revision: u64,
     source: String,
+    confirmed_by: Option<String>,
     before_content: String,
     after_content: String,
     target_version_id: u64,
 }
 
+/// Result of submitting an agent revision through the review policy.
+#[derive(Debug, Clone)]
+pub enum WriterRevisionOutcome {
+    /// Applied as a new canonical version.
+    Applied(DocumentVersion),
+    /// Parked as a pending proposal overlay for human review.
+    PendingReview {
+        overlay: Overlay,
+        impact: shared_types::ChangesetImpact,
+    },
+}
+
+/// A proposal overlay with the content accepting it would produce.
+#[derive(Debug, Clone, Serialize)]
+pub struct WriterProposal {
+    #[serde(flatten)]
+    pub overlay: Overlay,
+    /// The base is no longer the head; accepting rebases or may conflict.
+    pub stale: bool,
+    /// Base version content with the proposal applied.
+    pub proposed_content: String,
+}
+
+/// Reviewable proposals of a run document.
+#[derive(Debug, Clone, Serialize)]
+pub struct WriterProposalList {
+    pub review_mode: bool,
+    pub lock_strictness: shared_types::WriterLockStrictness,
+    pub head_version_id: u64,
+    pub proposals: Vec<WriterProposal>,
+}
+
+/// Version created by accepting a proposal.
+#[derive(Debug, Clone, Serialize)]
+pub struct WriterProposalAcceptance {
+    pub overlay_id: String,
+    pub version: DocumentVersion,
+    /// The proposal was merged onto a newer head than its base.
+    pub rebased: bool,
+}
+
+/// Version created by a user undo/redo and the patch it reverted.
+#[derive(Debug, Clone)]
+pub struct WriterRevertResult {
+    pub version: DocumentVersion,
+    pub undo_of: String,
+}
+
 #[derive(Debug, thiserror::Error, Clone)]
 pub enum WriterError {
     #[error("validation error: {0}")]
@@ -329,6 +454,8 @@ pub enum WriterError {
     ModelResolution(String),
     #[error("writer llm failed: {0}")]
     WriterLlmFailed(String),
+    #[error("conflict: {0}")]
+    Conflict(String),
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -465,6 +592,8 @@ pub(crate) fn dispatch_delegate_capability(
                             writer_actor: Some(writer_actor.clone()),
                             run_id: run_id_for_task.clone(),
                             call_id: call_id_for_task.clone(),
+                            dry_run: false,
+                            approved_plan: None,
                         })
                         .map_err(|e| WriterError::WorkerFailed(e.to_string()))?;
                     Ok::<(), WriterError>(())
@@ -517,6 +646,7 @@ impl Actor for WriterActor {
             inbox_processing: false,
             run_documents_by_run_id: HashMap::new(),
             confirmed_citations_by_run_id: HashMap::new(),
+            loop_base_version_by_run_id: HashMap::new(),
         })
     }
 
@@ -536,6 +666,16 @@ impl Actor for WriterActor {
                 let result = Self::ensure_run_document(state, run_id, desktop_id, objective).await;
                 let _ = reply.send(result);
             }
+            WriterMsg::RestoreRunDocument { run_id } => {
+                if let Err(err) = Self::ensure_run_document_loaded(state, &run_id).await {
+                    tracing::warn!(
+                        writer_id = %state.writer_id,
+                        run_id = %run_id,
+                        error = %err,
+                        "Failed to restore run document"
+                    );
+                }
+            }
             WriterMsg::ListWriterDocumentVersions { run_id, reply } => {
                 let result = Self::list_writer_document_versions(state, run_id).await;
                 let _ = reply.send(result);
@@ -567,6 +707,74 @@ impl Actor for WriterActor {
                 let result = Self::dismiss_writer_document_overlay(state, run_id, overlay_id).await;
                 let _ = reply.send(result);
             }
+            WriterMsg::ListWriterDocumentProposals { run_id, reply } => {
+                let result = Self::list_writer_document_proposals(state, run_id).await;
+                let _ = reply.send(result);
+            }
+            WriterMsg::AcceptWriterDocumentProposal {
+                run_id,
+                overlay_id,
+                reply,
+            } => {
+                let result = Self::accept_writer_document_proposal(state, run_id, overlay_id).await;
+                let _ = reply.send(result);
+            }
+            WriterMsg::ReproposeWriterDocumentProposal {
+                run_id,
+                overlay_id,
+                reply,
+            } => {
+                let result =
+                    Self::repropose_writer_document_proposal(state, run_id, overlay_id).await;
+                let _ = reply.send(result);
+            }
+            WriterMsg::SetWriterReviewMode {
+                run_id,
+                enabled,
+                reply,
+            } => {
+                let result = Self::set_writer_review_mode(state, run_id, enabled).await;
+                let _ = reply.send(result);
+            }
+            WriterMsg::SetWriterLockStrictness {
+                run_id,
+                strictness,
+                reply,
+            } => {
+                let result = Self::set_writer_lock_strictness(state, run_id, strictness).await;
+                let _ = reply.send(result);
+            }
+            WriterMsg::LockWriterBlocks {
+                run_id,
+                block_ids,
+                ttl_ms,
+                reply,
+            } => {
+                let result = Self::lock_writer_blocks(state, &run_id, block_ids, ttl_ms).await;
+                let _ = reply.send(result);
+            }
+            WriterMsg::DeclareWriterLockTargets {
+                run_id,
+                lock_id,
+                block_ids,
+            } => {
+                if let Ok(run_doc) = Self::resolve_run_document_mut(state, &run_id) {
+                    if let Err(e) = run_doc.add_lock_targets(&run_id, &lock_id, block_ids).await {
+                        tracing::warn!(run_id = %run_id, lock_id = %lock_id, error = %e, "Failed to declare block lock targets");
+                    }
+                }
+            }
+            WriterMsg::ReleaseWriterBlockLock {
+                run_id,
+                lock_id,
+                reason,
+            } => {
+                if let Ok(run_doc) = Self::resolve_run_document_mut(state, &run_id) {
+                    if let Err(e) = run_doc.release_block_lock(&run_id, &lock_id, &reason).await {
+                        tracing::warn!(run_id = %run_id, lock_id = %lock_id, error = %e, "Failed to release block lock");
+                    }
+                }
+            }
             WriterMsg::CreateWriterDocumentVersion {
                 run_id,
                 parent_version_id,
@@ -584,6 +792,24 @@ impl Actor for WriterActor {
                 .await;
                 let _ = reply.send(result);
             }
+            WriterMsg::ProposeWriterRevision {
+                run_id,
+                parent_version_id,
+                content,
+                reply,
+            } => {
+                let result =
+                    Self::propose_writer_revision(state, run_id, parent_version_id, content).await;
+                let _ = reply.send(result);
+            }
+            WriterMsg::RevertUserPatch {
+                run_id,
+                redo,
+                reply,
+            } => {
+                let result = Self::revert_user_patch(state, run_id, redo).await;
+                let _ = reply.send(result);
+            }
             WriterMsg::SubmitUserPrompt {
                 run_id,
                 prompt_diff,
@@ -683,11 +909,33 @@ impl Actor for WriterActor {
                 call_id,
                 objective,
                 parent_version_id,
+                document_context,
             } => {
                 // IMPORTANT: Spawn as a background task instead of awaiting.
                 // The orchestration adapter calls back into this actor via
                 // CreateWriterDocumentVersion. Awaiting here would deadlock
                 // because the actor can't process messages while handle() is blocked.
+                // The loop holds a block lock while it runs.
+                let lock = Self::start_loop_lock(
+                    state,
+                    &run_id,
+                    parent_version_id,
+                    Self::USER_PROMPT_TIMEOUT_BUDGET_MS,
+                )
+                .await;
+                state
+                    .loop_base_version_by_run_id
+                    .insert(run_id.clone(), parent_version_id);
+                // Section ids in a sectioned objective refer to the parent version.
+                let sectioned_parent = match document_context {
+                    shared_types::DocumentContextMode::FullText => None,
+                    shared_types::DocumentContextMode::Sectioned => {
+                        Self::resolve_run_document(state, &run_id)
+                            .ok()
+                            .and_then(|doc| doc.get_version(parent_version_id).ok())
+                            .map(|version| version.content)
+                    }
+                };
                 let myself_clone = myself.clone();
                 let writer_id = state.writer_id.clone();
                 let user_id = state.user_id.clone();
@@ -709,6 +957,8 @@ impl Actor for WriterActor {
                         call_id,
                         objective,
                         parent_version_id,
+                        sectioned_parent,
+                        lock,
                     )
                     .await;
                 });
@@ -721,6 +971,17 @@ impl Actor for WriterActor {
 impl WriterActor {
     const MAX_SEEN_IDS: usize = 4096;
     const AUTO_ACCEPT_WORKER_DIFFS: bool = false;
+    /// Agent revisions at or above this impact wait for human review.
+    const REVIEW_IMPACT_THRESHOLD: shared_types::ChangesetImpact =
+        shared_types::ChangesetImpact::High;
+    /// Bases shorter than this (non-blank lines) are drafts, never escalated.
+    const REVIEW_MIN_BASE_LINES: usize = 4;
+    /// `confirmed_by` recorded when a user accepts an agent proposal.
+    const PROPOSAL_CONFIRMER: &'static str = "user";
+    /// Default time budget of a writer loop, and the TTL of its block lock.
+    const USER_PROMPT_TIMEOUT_BUDGET_MS: u64 = 180_000;
+    /// Newest changeset events scanned for a loop's change summary.
+    const LOOP_CONTEXT_CHANGESET_SCAN: i64 = 200;
     const RUN_DOCUMENTS_ROOT: &'static str = "conductor/runs";
     const RUN_DOCUMENT_FILE: &'static str = "draft.md";
     const RUN_DOCUMENT_STATE_FILE: &'static str = "draft.writer-state.json";
@@ -831,6 +1092,7 @@ impl WriterActor {
             payload,
             actor_id: state.writer_id.clone(),
             user_id: state.user_id.clone(),
+            idempotency_key: None,
         };
         let _ = state
             .event_store
@@ -1304,7 +1566,7 @@ impl WriterActor {
             run_doc
                 .apply_patch(&run_id, source.as_str(), &section_id, ops, proposal)
                 .await
-                .map_err(|e| WriterError::WriterDocumentFailed(e.to_string()))?
+                .map_err(Self::user_edit_error)?
         };
         Self::emit_event(
             state,
@@ -1363,6 +1625,267 @@ impl WriterActor {
             .map_err(|e| WriterError::WriterDocumentFailed(e.to_string()))
     }
 
+    /// Proposal overlays still open for review: pending ones, and ones a
+    /// newer head superseded, which are flagged stale.
+    async fn list_writer_document_proposals(
+        state: &mut WriterState,
+        run_id: String,
+    ) -> Result<WriterProposalList, WriterError> {
+        Self::ensure_run_document_loaded(state, &run_id).await?;
+        let run_doc = Self::resolve_run_document(state, &run_id)?;
+        let head_version_id = run_doc
+            .head_version()
+            .map_err(|e| WriterError::WriterDocumentFailed(e.to_string()))?
+            .version_id;
+        let proposals = run_doc
+            .list_overlays(None, None)
+            .into_iter()
+            .filter(Self::is_open_proposal)
+            .map(|overlay| Self::writer_proposal(run_doc, overlay, head_version_id))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(WriterProposalList {
+            review_mode: run_doc.review_mode(),
+            lock_strictness: run_doc.lock_strictness(),
+            head_version_id,
+            proposals,
+        })
+    }
+
+    fn is_open_proposal(overlay: &Overlay) -> bool {
+        overlay.kind == OverlayKind::Proposal
+            && matches!(
+                overlay.status,
+                OverlayStatus::Pending | OverlayStatus::Superseded
+            )
+    }
+
+    fn writer_proposal(
+        run_doc: &WriterDocumentRuntime,
+        overlay: Overlay,
+        head_version_id: u64,
+    ) -> Result<WriterProposal, WriterError> {
+        let base = run_doc
+            .get_version(overlay.base_version_id)
+            .map_err(|e| WriterError::WriterDocumentFailed(e.to_string()))?;
+        let proposed_content = Self::apply_shared_patch_ops(&base.content, &overlay.diff_ops)?;
+        Ok(WriterProposal {
+            stale: overlay.base_version_id != head_version_id,
+            overlay,
+            proposed_content,
+        })
+    }
+
+    /// Resolve an open proposal with its base content and the current head.
+    fn open_proposal(
+        state: &WriterState,
+        run_id: &str,
+        overlay_id: &str,
+    ) -> Result<(WriterProposal, String, DocumentVersion), WriterError> {
+        let run_doc = Self::resolve_run_document(state, run_id)?;
+        let overlay = run_doc
+            .get_overlay(overlay_id)
+            .map_err(|e| WriterError::Validation(e.to_string()))?;
+        if !Self::is_open_proposal(&overlay) {
+            return Err(WriterError::Conflict(format!(
+                "overlay {overlay_id} is not an open proposal"
+            )));
+        }
+        let head = run_doc
+            .head_version()
+            .map_err(|e| WriterError::WriterDocumentFailed(e.to_string()))?;
+        let proposal = Self::writer_proposal(run_doc, overlay, head.version_id)?;
+        let base_content = run_doc
+            .get_version(proposal.overlay.base_version_id)
+            .map_err(|e| WriterError::WriterDocumentFailed(e.to_string()))?
+            .content;
+        Ok((proposal, base_content, head))
+    }
+
+    /// Apply a proposal as the agent's change, confirmed by the user.
+    async fn accept_writer_document_proposal(
+        state: &mut WriterState,
+        run_id: String,
+        overlay_id: String,
+    ) -> Result<WriterProposalAcceptance, WriterError> {
+        Self::ensure_run_document_loaded(state, &run_id).await?;
+        let (proposal, base_content, head) = Self::open_proposal(state, &run_id, &overlay_id)?;
+        let rebased = proposal.stale;
+        let content = if rebased {
+            proposals::rebase_proposal(&base_content, &proposal.proposed_content, &head.content)
+                .ok_or_else(|| {
+                    WriterError::Conflict(format!(
+                        "proposal {overlay_id} conflicts with version {}; re-propose it",
+                        head.version_id
+                    ))
+                })?
+        } else {
+            proposal.proposed_content
+        };
+        let author = WriterDocumentRuntime::overlay_author_source(&proposal.overlay.author);
+
+        let event_store = state.event_store.clone();
+        let model_registry = state.model_registry.clone();
+        let run_doc = Self::resolve_run_document_mut(state, &run_id)?;
+        let version = run_doc
+            .accept_overlay(
+                &run_id,
+                &overlay_id,
+                head.version_id,
+                content.clone(),
+                Self::PROPOSAL_CONFIRMER,
+            )
+            .await
+            .map_err(|e| WriterError::WriterDocumentFailed(e.to_string()))?;
+
+        Self::spawn_changeset_summarization(ChangesetSummarizationCtx {
+            event_store,
+            model_registry,
+            run_id: run_id.clone(),
+            patch_id: run_doc.last_patch_id().unwrap_or_default().to_string(),
+            desktop_id: run_doc.desktop_id().to_string(),
+            session_id: run_doc.session_id().to_string(),
+            thread_id: run_doc.thread_id().to_string(),
+            document_path: run_doc.document_path_relative().to_string(),
+            revision: run_doc.revision(),
+            source: author.to_string(),
+            confirmed_by: Some(Self::PROPOSAL_CONFIRMER.to_string()),
+            before_content: head.content,
+            after_content: content,
+            target_version_id: version.version_id,
+        });
+
+        Ok(WriterProposalAcceptance {
+            overlay_id,
+            version,
+            rebased,
+        })
+    }
+
+    /// Re-record a stale proposal against the head. Hunks that merge cleanly
+    /// are rebased; a conflicting proposal is offered whole for review.
+    async fn repropose_writer_document_proposal(
+        state: &mut WriterState,
+        run_id: String,
+        overlay_id: String,
+    ) -> Result<WriterProposal, WriterError> {
+        Self::ensure_run_document_loaded(state, &run_id).await?;
+        let (proposal, base_content, head) = Self::open_proposal(state, &run_id, &overlay_id)?;
+        if !proposal.stale {
+            return Err(WriterError::Conflict(format!(
+                "proposal {overlay_id} is already based on the head version"
+            )));
+        }
+        let content =
+            proposals::rebase_proposal(&base_content, &proposal.proposed_content, &head.content)
+                .unwrap_or(proposal.proposed_content);
+        let overlay = Self::resolve_run_document_mut(state, &run_id)?
+            .repropose_overlay(&run_id, &overlay_id, content.clone())
+            .await
+            .map_err(|e| WriterError::WriterDocumentFailed(e.to_string()))?;
+        Ok(WriterProposal {
+            overlay,
+            stale: false,
+            proposed_content: content,
+        })
+    }
+
+    async fn set_writer_review_mode(
+        state: &mut WriterState,
+        run_id: String,
+        enabled: bool,
+    ) -> Result<bool, WriterError> {
+        Self::ensure_run_document_loaded(state, &run_id).await?;
+        let run_doc = Self::resolve_run_document_mut(state, &run_id)?;
+        run_doc
+            .set_review_mode(&run_id, enabled)
+            .await
+            .map_err(|e| WriterError::WriterDocumentFailed(e.to_string()))?;
+        Ok(run_doc.review_mode())
+    }
+
+    async fn set_writer_lock_strictness(
+        state: &mut WriterState,
+        run_id: String,
+        strictness: shared_types::WriterLockStrictness,
+    ) -> Result<shared_types::WriterLockStrictness, WriterError> {
+        Self::ensure_run_document_loaded(state, &run_id).await?;
+        Self::resolve_run_document_mut(state, &run_id)?
+            .set_lock_strictness(&run_id, strictness)
+            .await
+            .map_err(|e| WriterError::WriterDocumentFailed(e.to_string()))?;
+        Ok(strictness)
+    }
+
+    async fn lock_writer_blocks(
+        state: &mut WriterState,
+        run_id: &str,
+        block_ids: Vec<String>,
+        ttl_ms: u64,
+    ) -> Result<shared_types::WriterRunLockPayload, WriterError> {
+        Self::ensure_run_document_loaded(state, run_id).await?;
+        Self::resolve_run_document_mut(state, run_id)?
+            .lock_blocks(run_id, block_ids, ttl_ms)
+            .await
+            .map_err(|e| WriterError::WriterDocumentFailed(e.to_string()))
+    }
+
+    /// Start the block lock of a writer loop working from `parent_version_id`.
+    /// The loop declares the blocks it rewrites as it goes; a loop that fails
+    /// to lock runs unlocked.
+    async fn start_loop_lock(
+        state: &mut WriterState,
+        run_id: &str,
+        parent_version_id: u64,
+        ttl_ms: u64,
+    ) -> Option<LoopLock> {
+        let locked = Self::lock_writer_blocks(state, run_id, Vec::new(), ttl_ms)
+            .await
+            .and_then(|lock| {
+                let base = Self::resolve_run_document(state, run_id)?
+                    .get_version(parent_version_id)
+                    .map_err(|e| WriterError::WriterDocumentFailed(e.to_string()))?
+                    .content;
+                Ok(LoopLock {
+                    lock_id: lock.lock_id,
+                    base,
+                })
+            });
+        match locked {
+            Ok(lock) => Some(lock),
+            Err(e) => {
+                tracing::warn!(run_id = %run_id, error = %e, "Failed to lock writer blocks");
+                None
+            }
+        }
+    }
+
+    /// Release a loop's block lock once it ends. Sent as a message so it is
+    /// handled after any revision the loop queued.
+    fn release_loop_lock(
+        myself: &ActorRef<WriterMsg>,
+        run_id: &str,
+        lock_id: Option<String>,
+        succeeded: bool,
+    ) {
+        let Some(lock_id) = lock_id else {
+            return;
+        };
+        let _ = myself.send_message(WriterMsg::ReleaseWriterBlockLock {
+            run_id: run_id.to_string(),
+            lock_id,
+            reason: if succeeded { "completed" } else { "failed" }.to_string(),
+        });
+    }
+
+    /// Map a document error from a user edit; edits refused because they
+    /// touch locked blocks are conflicts.
+    fn user_edit_error(err: WriterDocumentError) -> WriterError {
+        match err {
+            WriterDocumentError::BlocksLocked(_) => WriterError::Conflict(err.to_string()),
+            err => WriterError::WriterDocumentFailed(err.to_string()),
+        }
+    }
+
     async fn create_writer_document_version(
         state: &mut WriterState,
         run_id: String,
@@ -1395,7 +1918,7 @@ impl WriterActor {
         let version = run_doc
             .create_version(&run_id, parent_version_id, content.clone(), source)
             .await
-            .map_err(|e| WriterError::WriterDocumentFailed(e.to_string()))?;
+            .map_err(Self::user_edit_error)?;
 
         let (session_id, thread_id, document_path, revision) = (
             run_doc.session_id().to_string(),
@@ -1409,12 +1932,14 @@ impl WriterActor {
             event_store,
             model_registry,
             run_id: run_id.clone(),
+            patch_id: run_doc.last_patch_id().unwrap_or_default().to_string(),
             desktop_id,
             session_id,
             thread_id,
             document_path,
             revision,
             source: source_str,
+            confirmed_by: None,
             before_content,
             after_content: content,
             target_version_id: version.version_id,
@@ -1445,6 +1970,7 @@ impl WriterActor {
                             payload,
                             actor_id: state.writer_id.clone(),
                             user_id: state.user_id.clone(),
+                            idempotency_key: None,
                         },
                     });
                 }
@@ -1454,51 +1980,225 @@ impl WriterActor {
         Ok(version)
     }
 
-    /// Generate a unified diff string between two texts for LLM context.
-    fn compute_unified_diff(base: &str, edited: &str) -> String {
-        use similar::{ChangeTag, TextDiff};
-        let diff = TextDiff::from_lines(base, edited);
-        let mut out = String::new();
-        for change in diff.iter_all_changes() {
-            let prefix = match change.tag() {
-                ChangeTag::Equal => " ",
-                ChangeTag::Delete => "-",
-                ChangeTag::Insert => "+",
-            };
-            out.push_str(prefix);
-            out.push_str(change.value());
-            if !change.value().ends_with('\n') {
-                out.push('\n');
-            }
-        }
-        out
-    }
-
-    async fn submit_user_prompt(
-        myself: &ActorRef<WriterMsg>,
+    /// Undo (or redo) a user edit by applying its inverse as a new version.
+    ///
+    /// The stack is rebuilt from the run's `writer.run.patch` events on every
+    /// call, so it survives reloads and writer restarts.
+    async fn revert_user_patch(
         state: &mut WriterState,
         run_id: String,
-        prompt_diff: Vec<shared_types::PatchOp>,
-        base_version_id: u64,
-    ) -> Result<WriterQueueAck, WriterError> {
+        redo: bool,
+    ) -> Result<WriterRevertResult, WriterError> {
         Self::ensure_run_document_loaded(state, &run_id).await?;
-        if prompt_diff.is_empty() {
-            return Err(WriterError::Validation(
-                "prompt_diff cannot be empty".to_string(),
-            ));
+        let events = crate::actors::event_store::get_events_for_actor(
+            &state.event_store,
+            format!("writer:{run_id}"),
+            0,
+        )
+        .await
+        .map_err(|e| WriterError::ActorUnavailable(e.to_string()))?
+        .map_err(|e| WriterError::WriterDocumentFailed(e.to_string()))?;
+        let patches = events
+            .into_iter()
+            .filter(|event| event.event_type == shared_types::EVENT_TOPIC_WRITER_RUN_PATCH)
+            .filter_map(|event| {
+                serde_json::from_value::<shared_types::WriterRunPatchPayload>(event.payload).ok()
+            });
+        let stack = undo::UndoStack::from_patches(patches);
+        let target = if redo {
+            stack.redo_target()
+        } else {
+            stack.undo_target()
         }
+        .ok_or_else(|| {
+            WriterError::Conflict(format!("nothing to {}", if redo { "redo" } else { "undo" }))
+        })?
+        .clone();
 
-        let head = Self::resolve_run_document(state, &run_id)?
+        let base_version_id = target.base_version_id.ok_or_else(|| {
+            WriterError::Validation(format!("patch {} has no base version", target.patch_id))
+        })?;
+        let run_doc = Self::resolve_run_document(state, &run_id)?;
+        let head = run_doc
             .head_version()
             .map_err(|e| WriterError::WriterDocumentFailed(e.to_string()))?;
-        if base_version_id != head.version_id {
-            return Err(WriterError::Validation(format!(
-                "stale base_version_id: expected {}, got {}",
-                head.version_id, base_version_id
-            )));
-        }
-
-        // Get the base content and compute the user's prompted version.
+        // Earlier undos restore content under new version ids, so the head is
+        // compared by content: any edit since the patch makes it a conflict.
+        let base_content = run_doc
+            .get_version(base_version_id)
+            .map_err(|e| WriterError::WriterDocumentFailed(e.to_string()))?
+            .content;
+
+        let (content, inverse) = undo::revert_patch(&base_content, &head.content, &target)?;
+        let version = Self::resolve_run_document_mut(state, &run_id)?
+            .create_revert_version(&run_id, head.version_id, content, inverse, &target.patch_id)
+            .await
+            .map_err(Self::user_edit_error)?;
+        Ok(WriterRevertResult {
+            version,
+            undo_of: target.patch_id,
+        })
+    }
+
+    /// Pre-apply estimate of how disruptive a revision is.
+    ///
+    /// The LLM changeset summary only runs once a version exists, so the review
+    /// gate classifies on how much of the base survives and whether its heading
+    /// outline is kept. Growing a document is cheap; rewriting it is not.
+    fn estimate_changeset_impact(before: &str, after: &str) -> shared_types::ChangesetImpact {
+        use shared_types::ChangesetImpact;
+        use similar::{ChangeTag, TextDiff};
+
+        let base_lines = before.lines().filter(|l| !l.trim().is_empty()).count();
+        if base_lines < Self::REVIEW_MIN_BASE_LINES {
+            return ChangesetImpact::Low;
+        }
+
+        let removed = TextDiff::from_lines(before, after)
+            .iter_all_changes()
+            .filter(|c| c.tag() == ChangeTag::Delete && !c.value().trim().is_empty())
+            .count();
+        let removed_ratio = removed as f64 / base_lines as f64;
+
+        let headings = |text: &str| -> Vec<String> {
+            text.lines()
+                .map(str::trim)
+                .filter(|l| l.starts_with('#'))
+                .map(ToString::to_string)
+                .collect()
+        };
+        let before_headings = headings(before);
+        let after_headings = headings(after);
+        // The outline survives if every old heading is still there, in order.
+        let mut remaining = after_headings.iter();
+        let outline_kept = before_headings
+            .iter()
+            .all(|h| remaining.any(|candidate| candidate == h));
+
+        if removed_ratio >= 0.5 || (!outline_kept && before_headings.len() >= 2) {
+            ChangesetImpact::High
+        } else if removed_ratio >= 0.15 || !outline_kept {
+            ChangesetImpact::Medium
+        } else {
+            ChangesetImpact::Low
+        }
+    }
+
+    fn impact_rank(impact: &shared_types::ChangesetImpact) -> u8 {
+        match impact {
+            shared_types::ChangesetImpact::Low => 0,
+            shared_types::ChangesetImpact::Medium => 1,
+            shared_types::ChangesetImpact::High => 2,
+        }
+    }
+
+    /// Apply an agent revision, or park it for review when its impact is high
+    /// or the document is in review mode.
+    async fn propose_writer_revision(
+        state: &mut WriterState,
+        run_id: String,
+        parent_version_id: Option<u64>,
+        content: String,
+    ) -> Result<WriterRevisionOutcome, WriterError> {
+        Self::ensure_run_document_loaded(state, &run_id).await?;
+        let base = {
+            let run_doc = Self::resolve_run_document(state, &run_id)?;
+            match parent_version_id {
+                Some(version_id) => run_doc.get_version(version_id),
+                None => run_doc.head_version(),
+            }
+            .map_err(|e| WriterError::WriterDocumentFailed(e.to_string()))?
+        };
+
+        let impact = Self::estimate_changeset_impact(&base.content, &content);
+        if Self::resolve_run_document(state, &run_id)?.review_mode() {
+            let overlay = Self::resolve_run_document_mut(state, &run_id)?
+                .create_overlay(
+                    &run_id,
+                    base.version_id,
+                    OverlayAuthor::Writer,
+                    OverlayKind::Proposal,
+                    vec![shared_types::PatchOp::Replace {
+                        pos: 0,
+                        len: u64::MAX,
+                        text: content,
+                    }],
+                )
+                .await
+                .map_err(|e| WriterError::WriterDocumentFailed(e.to_string()))?;
+            return Ok(WriterRevisionOutcome::PendingReview { overlay, impact });
+        }
+        if Self::impact_rank(&impact) >= Self::impact_rank(&Self::REVIEW_IMPACT_THRESHOLD) {
+            let run_doc = Self::resolve_run_document_mut(state, &run_id)?;
+            let overlay = run_doc
+                .request_revision_review(&run_id, base.version_id, content, impact.clone())
+                .await
+                .map_err(|e| WriterError::WriterDocumentFailed(e.to_string()))?;
+            tracing::info!(
+                run_id = %run_id,
+                overlay_id = %overlay.overlay_id,
+                base_version_id = base.version_id,
+                "writer revision escalated for human review"
+            );
+            return Ok(WriterRevisionOutcome::PendingReview { overlay, impact });
+        }
+
+        let version = Self::create_writer_document_version(
+            state,
+            run_id,
+            Some(base.version_id),
+            content,
+            VersionSource::Writer,
+        )
+        .await?;
+        Ok(WriterRevisionOutcome::Applied(version))
+    }
+
+    /// Generate a unified diff string between two texts for LLM context.
+    fn compute_unified_diff(base: &str, edited: &str) -> String {
+        use similar::{ChangeTag, TextDiff};
+        let diff = TextDiff::from_lines(base, edited);
+        let mut out = String::new();
+        for change in diff.iter_all_changes() {
+            let prefix = match change.tag() {
+                ChangeTag::Equal => " ",
+                ChangeTag::Delete => "-",
+                ChangeTag::Insert => "+",
+            };
+            out.push_str(prefix);
+            out.push_str(change.value());
+            if !change.value().ends_with('\n') {
+                out.push('\n');
+            }
+        }
+        out
+    }
+
+    async fn submit_user_prompt(
+        myself: &ActorRef<WriterMsg>,
+        state: &mut WriterState,
+        run_id: String,
+        prompt_diff: Vec<shared_types::PatchOp>,
+        base_version_id: u64,
+    ) -> Result<WriterQueueAck, WriterError> {
+        Self::ensure_run_document_loaded(state, &run_id).await?;
+        if prompt_diff.is_empty() {
+            return Err(WriterError::Validation(
+                "prompt_diff cannot be empty".to_string(),
+            ));
+        }
+
+        let head = Self::resolve_run_document(state, &run_id)?
+            .head_version()
+            .map_err(|e| WriterError::WriterDocumentFailed(e.to_string()))?;
+        if base_version_id != head.version_id {
+            return Err(WriterError::Validation(format!(
+                "stale base_version_id: expected {}, got {}",
+                head.version_id, base_version_id
+            )));
+        }
+
+        // Get the base content and compute the user's prompted version.
         let base_content = head.content.clone();
         let prompted_content = Self::apply_shared_patch_ops(&base_content, &prompt_diff)?;
 
@@ -1514,8 +2214,8 @@ impl WriterActor {
             .join(" ");
         let user_input_record = shared_types::UserInputRecord {
             input_id: ulid::Ulid::new().to_string(),
-            content: prompt_text,
-            surface: "writer".to_string(),
+            content: prompt_text.clone(),
+            surface: shared_types::UserInputSurface::Writer,
             desktop_id: String::new(),
             session_id: String::new(),
             thread_id: run_id.clone(),
@@ -1536,6 +2236,7 @@ impl WriterActor {
                 }),
                 actor_id: "writer".to_string(),
                 user_id: state.user_id.clone(),
+                idempotency_key: None,
             },
         });
 
@@ -1573,23 +2274,36 @@ impl WriterActor {
         let user_version_id = user_version.version_id;
 
         // Build the objective that includes document + diff for the writer LLM.
-        let objective = format!(
-            "The user edited the document. Review their changes and produce a revised version \
-             that incorporates the user's intent. The user's changes may include:\n\
+        let instructions = "The user edited the document. Review their changes and produce a \
+             revised version that incorporates the user's intent. The user's changes may include:\n\
              - Direct content edits (apply them)\n\
              - Inline instructions like [make this shorter] (interpret and execute)\n\
              - Deletions (honor them)\n\
              - Replacements (use the new content)\n\n\
              If the changes are purely editorial (typo fixes, reformatting), apply them as-is.\n\
              If the changes require research or code inspection, delegate to workers first.\n\
-             After you submit a satisfactory write_revision, call finished instead of creating \
+             After you submit a satisfactory revision, call finished instead of creating \
              another revision unless fresh worker results still require a change.\n\
              Do not use markdown footnote markers like [^s1] or [1] unless the document already \
-             has a working citation system. Prefer inline source mentions or plain prose.\n\n\
-             ## Current Document (before user edit)\n\n{base_content}\n\n\
-             ## User's Changes (unified diff)\n\n```diff\n{unified_diff}```\n\n\
-             ## User's Edited Version\n\n{prompted_content}"
-        );
+             has a working citation system. Prefer inline source mentions or plain prose.";
+        let loop_context =
+            Self::loop_context(state, &run_id, &prompted_content, &prompt_text).await;
+        let objective = match loop_context.mode {
+            shared_types::DocumentContextMode::FullText => format!(
+                "{instructions}\n\n\
+                 ## Current Document (before user edit)\n\n{base_content}\n\n\
+                 ## User's Changes (unified diff)\n\n```diff\n{unified_diff}```\n\n\
+                 ## User's Edited Version\n\n{prompted_content}"
+            ),
+            shared_types::DocumentContextMode::Sectioned => format!(
+                "{instructions}\n\n\
+                 ## User's Changes (unified diff)\n\n```diff\n{unified_diff}```\n\n\
+                 The edited document is shown below as an outline with the sections that \
+                 changed or relate to this edit. Revise it with write_section, and use \
+                 read_section for any section not shown.\n\n{}",
+                loop_context.text
+            ),
+        };
 
         // Spawn background orchestration — writer LLM processes the diff.
         let _ = writer_ref.send_message(WriterMsg::OrchestrateUserPrompt {
@@ -1597,6 +2311,7 @@ impl WriterActor {
             call_id: orchestration_call_id,
             objective,
             parent_version_id: user_version_id,
+            document_context: loop_context.mode,
         });
 
         Ok(WriterQueueAck {
@@ -1608,6 +2323,79 @@ impl WriterActor {
         })
     }
 
+    /// Document context of a writer loop on `content`. A run's first loop,
+    /// or a loop on a small document, gets the full text; later loops get
+    /// the sections relevant to `query` and the changes since the previous
+    /// loop's base version.
+    async fn loop_context(
+        state: &WriterState,
+        run_id: &str,
+        content: &str,
+        query: &str,
+    ) -> context::LoopContext {
+        let base = state
+            .loop_base_version_by_run_id
+            .get(run_id)
+            .and_then(|version_id| {
+                Self::resolve_run_document(state, run_id)
+                    .ok()?
+                    .get_version(*version_id)
+                    .ok()
+            });
+        let changesets = match &base {
+            Some(base) => Self::changesets_since(state, run_id, base.version_id).await,
+            None => Vec::new(),
+        };
+        context::assemble(context::LoopContextInput {
+            content,
+            query,
+            base: base
+                .as_ref()
+                .map(|base| (base.version_id, base.content.as_str())),
+            changesets: &changesets,
+        })
+    }
+
+    /// Changeset summaries of the run's versions after `base_version_id`,
+    /// oldest first. Summaries are written asynchronously, so the newest
+    /// versions may not have one yet.
+    async fn changesets_since(
+        state: &WriterState,
+        run_id: &str,
+        base_version_id: u64,
+    ) -> Vec<shared_types::WriterRunChangesetPayload> {
+        let query = EventQuery {
+            actor_id: Some("writer".to_string()),
+            event_type_prefixes: vec![shared_types::EVENT_TOPIC_WRITER_RUN_CHANGESET.to_string()],
+            limit: Self::LOOP_CONTEXT_CHANGESET_SCAN,
+            newest_first: true,
+            ..EventQuery::default()
+        };
+        let Ok(Ok(page)) =
+            crate::actors::event_store::query_filtered(&state.event_store, query).await
+        else {
+            return Vec::new();
+        };
+        let mut changesets: Vec<_> = page
+            .events
+            .into_iter()
+            .filter(|event| {
+                event.payload.get("run_id").and_then(|value| value.as_str()) == Some(run_id)
+            })
+            .filter_map(|event| {
+                serde_json::from_value::<shared_types::WriterRunChangesetPayload>(event.payload)
+                    .ok()
+            })
+            .filter(|changeset| {
+                changeset
+                    .target_version_id
+                    .is_some_and(|version_id| version_id > base_version_id)
+            })
+            .collect();
+        changesets.reverse();
+        changesets
+    }
+
     fn extract_capability_from_tool_output(output: &str) -> Option<String> {
         let value: serde_json::Value = serde_json::from_str(output).ok()?;
         value
@@ -1638,7 +2426,7 @@ impl WriterActor {
     #[allow(clippy::too_many_arguments)]
     async fn orchestrate_objective(
         myself: &ActorRef<WriterMsg>,
-        state: &WriterState,
+        state: &mut WriterState,
         objective: String,
         timeout_ms: Option<u64>,
         max_steps: Option<u8>,
@@ -1686,6 +2474,15 @@ impl WriterActor {
             .and_then(|doc| doc.head_version().ok())
             .map(|v| v.version_id);
 
+        let timeout_budget_ms = timeout_ms.unwrap_or(Self::USER_PROMPT_TIMEOUT_BUDGET_MS);
+        let lock = match (run_id.as_deref(), parent_version_id) {
+            (Some(rid), Some(parent_version_id)) => {
+                Self::start_loop_lock(state, rid, parent_version_id, timeout_budget_ms).await
+            }
+            _ => None,
+        };
+        let lock_id = lock.as_ref().map(|lock| lock.lock_id.clone());
+
         let adapter = WriterDelegationAdapter::new(
             state.writer_id.clone(),
             state.user_id.clone(),
@@ -1695,12 +2492,13 @@ impl WriterActor {
             state.terminal_supervisor.clone(),
             run_id.clone(),
             parent_version_id,
+            lock,
         );
         let harness = AgentHarness::with_config(
             adapter,
             state.model_registry.clone(),
             HarnessConfig {
-                timeout_budget_ms: timeout_ms.unwrap_or(180_000),
+                timeout_budget_ms,
                 // Keep writer orchestration shallow so workers are dispatched early.
                 max_steps: usize::from(max_steps.unwrap_or(100)).min(2),
                 emit_progress: true,
@@ -1719,7 +2517,11 @@ impl WriterActor {
                 run_id.clone(),
                 Some(orchestration_call_id.clone()),
             )
-            .await
+            .await;
+        if let Some(rid) = run_id.as_deref() {
+            Self::release_loop_lock(myself, rid, lock_id, result.is_ok());
+        }
+        let result = result
             .map_err(|e| WriterError::WorkerFailed(format!("writer orchestration failed: {e}")))?;
 
         let delegated_capabilities =
@@ -1782,6 +2584,7 @@ impl WriterActor {
                     call_id: rewrite_call_id,
                     objective: objective_text.to_string(),
                     parent_version_id,
+                    document_context: shared_types::DocumentContextMode::FullText,
                 });
             }
         }
@@ -1814,6 +2617,8 @@ impl WriterActor {
         call_id: String,
         objective: String,
         parent_version_id: u64,
+        sectioned_parent: Option<String>,
+        lock: Option<LoopLock>,
     ) {
         let _ = event_store.send_message(EventStoreMsg::AppendAsync {
             event: AppendEvent {
@@ -1829,9 +2634,11 @@ impl WriterActor {
                 }),
                 actor_id: writer_id.clone(),
                 user_id: user_id.clone(),
+                idempotency_key: None,
             },
         });
 
+        let lock_id = lock.as_ref().map(|lock| lock.lock_id.clone());
         let adapter = WriterUserPromptAdapter::new(
             writer_id.clone(),
             user_id.clone(),
@@ -1841,12 +2648,14 @@ impl WriterActor {
             terminal_supervisor,
             run_id.clone(),
             parent_version_id,
+            sectioned_parent,
+            lock,
         );
         let harness = AgentHarness::with_config(
             adapter,
             model_registry,
             HarnessConfig {
-                timeout_budget_ms: 180_000,
+                timeout_budget_ms: Self::USER_PROMPT_TIMEOUT_BUDGET_MS,
                 max_steps: 5,
                 emit_progress: true,
                 emit_worker_report: true,
@@ -1866,6 +2675,8 @@ impl WriterActor {
             )
             .await;
 
+        Self::release_loop_lock(myself, &run_id, lock_id, result.is_ok());
+
         let (event_type, payload) = match result {
             Ok(run_result) => {
                 let summary = run_result.summary;
@@ -1904,6 +2715,7 @@ impl WriterActor {
                 payload,
                 actor_id: writer_id,
                 user_id,
+                idempotency_key: None,
             },
         });
     }
@@ -1920,19 +2732,20 @@ impl WriterActor {
             event_store,
             model_registry,
             run_id,
+            patch_id,
             desktop_id,
             session_id,
             thread_id,
             document_path,
             revision,
             source,
+            confirmed_by,
             before_content,
             after_content,
             target_version_id,
         } = ctx;
 
         tokio::spawn(async move {
-            let patch_id = ulid::Ulid::new().to_string();
             let ops_json = serde_json::json!({
                 "before_len": before_content.len(),
                 "after_len": after_content.len(),
@@ -1994,8 +2807,8 @@ impl WriterActor {
                         serde_json::to_value(shared_types::WriterRunEvent::Changeset {
                             base: shared_types::WriterRunEventBase {
                                 desktop_id,
-                                session_id,
-                                thread_id,
+                                session_id: session_id.clone(),
+                                thread_id: thread_id.clone(),
                                 run_id,
                                 document_path,
                                 revision,
@@ -2010,18 +2823,22 @@ impl WriterActor {
                                 summary: summary.summary,
                                 impact,
                                 op_taxonomy: summary.op_taxonomy,
+                                confirmed_by,
                             },
                         })
                         .unwrap_or(serde_json::Value::Null);
                     if let Some(object) = payload.as_object_mut() {
                         object.remove("event_type");
                     }
+                    let payload =
+                        shared_types::with_scope(payload, Some(session_id), Some(thread_id));
                     let _ = event_store.cast(EventStoreMsg::AppendAsync {
                         event: AppendEvent {
                             event_type: shared_types::EVENT_TOPIC_WRITER_RUN_CHANGESET.to_string(),
                             payload,
                             actor_id: "writer".to_string(),
                             user_id: String::new(),
+                            idempotency_key: None,
                         },
                     });
                 }
@@ -2248,43 +3065,61 @@ impl WriterActor {
         // Without this, the original harness has already finished and the
         // worker results sit as progress events but never become content.
         if success {
-            if let Ok(run_doc) = Self::resolve_run_document(state, &run_id) {
-                if let Ok(head) = run_doc.head_version() {
-                    let parent_version_id = head.version_id;
-                    let current_content = head.content.clone();
-                    let rewrite_call_id =
-                        format!("writer-rewrite-after-worker:{}", ulid::Ulid::new());
-
-                    let objective = format!(
-                        "A delegated {capability:?} worker has completed. \
-                         Incorporate its findings into the document.\n\n\
-                         ## Worker Summary\n\n{summary}\n\n\
-                         ## Current Document\n\n{current_content}\n\n\
-                         Review the worker's results and produce a revised version \
-                         that integrates the findings naturally into the document. \
-                         Always call write_revision with the updated content, \
-                         then call finished."
-                    );
+            let head = Self::resolve_run_document(state, &run_id)
+                .ok()
+                .and_then(|run_doc| run_doc.head_version().ok());
+            if let Some(head) = head {
+                let parent_version_id = head.version_id;
+                let rewrite_call_id = format!("writer-rewrite-after-worker:{}", ulid::Ulid::new());
+                let loop_context =
+                    Self::loop_context(state, &run_id, &head.content, &summary).await;
+                let document = match loop_context.mode {
+                    shared_types::DocumentContextMode::FullText => {
+                        format!("## Current Document\n\n{}", loop_context.text)
+                    }
+                    shared_types::DocumentContextMode::Sectioned => format!(
+                        "The document is shown as an outline with the sections that changed \
+                         or relate to the findings. Revise it with write_section, and use \
+                         read_section for any section not shown.\n\n{}",
+                        loop_context.text
+                    ),
+                };
+                let revise = match loop_context.mode {
+                    shared_types::DocumentContextMode::FullText => "write_revision",
+                    shared_types::DocumentContextMode::Sectioned => "write_section",
+                };
 
-                    Self::emit_event(
-                        state,
-                        "writer.actor.delegation_worker.rewrite_triggered",
-                        serde_json::json!({
-                            "run_id": &run_id,
-                            "dispatch_id": &dispatch_id,
-                            "capability": format!("{capability:?}"),
-                            "parent_version_id": parent_version_id,
-                            "rewrite_call_id": &rewrite_call_id,
-                        }),
-                    );
+                let objective = format!(
+                    "A delegated {capability:?} worker has completed. \
+                     Incorporate its findings into the document.\n\n\
+                     ## Worker Summary\n\n{summary}\n\n\
+                     {document}\n\n\
+                     Review the worker's results and produce a revised version \
+                     that integrates the findings naturally into the document. \
+                     Always call {revise} with the updated content, \
+                     then call finished."
+                );
 
-                    let _ = _myself.send_message(WriterMsg::OrchestrateUserPrompt {
-                        run_id,
-                        call_id: rewrite_call_id,
-                        objective,
-                        parent_version_id,
-                    });
-                }
+                Self::emit_event(
+                    state,
+                    "writer.actor.delegation_worker.rewrite_triggered",
+                    serde_json::json!({
+                        "run_id": &run_id,
+                        "dispatch_id": &dispatch_id,
+                        "capability": format!("{capability:?}"),
+                        "parent_version_id": parent_version_id,
+                        "rewrite_call_id": &rewrite_call_id,
+                        "document_context": loop_context.mode,
+                    }),
+                );
+
+                let _ = _myself.send_message(WriterMsg::OrchestrateUserPrompt {
+                    run_id,
+                    call_id: rewrite_call_id,
+                    objective,
+                    parent_version_id,
+                    document_context: loop_context.mode,
+                });
             }
         }
     }
@@ -2330,6 +3165,7 @@ impl WriterActor {
                     payload,
                     actor_id: state.writer_id.clone(),
                     user_id: state.user_id.clone(),
+                    idempotency_key: None,
                 },
             });
         }
@@ -2355,6 +3191,19 @@ impl WriterActor {
                 // content_id is the URL hash — computed downstream by the store
                 "action": "upsert",
             });
+            // The upsert leaves the sandbox; a cited URL can carry a token
+            // in its query string.
+            let mut scan = ScanReport::default();
+            scan.extend(secret_scanner().scan_json("payload", &payload));
+            if let Err(blocked) = scan.into_result("global_external_content.upsert") {
+                tracing::warn!(
+                    run_id = %run_id,
+                    citation_id = %stub.citation_id,
+                    findings = ?blocked.findings,
+                    "{blocked}"
+                );
+                continue;
+            }
             let _ = state.event_store.cast(EventStoreMsg::AppendAsync {
                 event: AppendEvent {
                     event_type: shared_types::EVENT_TOPIC_GLOBAL_EXTERNAL_CONTENT_UPSERT
@@ -2362,6 +3211,7 @@ impl WriterActor {
                     payload,
                     actor_id: state.writer_id.clone(),
                     user_id: state.user_id.clone(),
+                    idempotency_key: None,
                 },
             });
         }
@@ -2473,4 +3323,482 @@ mod tests {
             tokio::fs::remove_dir_all(&run_dir).await.unwrap();
         }
     }
+
+    #[test]
+    fn estimate_changeset_impact_separates_growth_from_rewrites() {
+        let base = "# Plan\n\nIntro paragraph.\n\n## Pricing\n\nFree and pro tiers.\n\n## Risks\n\nChurn.\n";
+        let grown = format!("{base}\n## Timeline\n\nShip in Q3.\n");
+        assert_eq!(
+            WriterActor::estimate_changeset_impact(base, &grown),
+            shared_types::ChangesetImpact::Low
+        );
+
+        let reordered = "# Plan\n\nIntro paragraph.\n\n## Risks\n\nChurn.\n\n## Pricing\n\nFree and pro tiers.\n";
+        assert_eq!(
+            WriterActor::estimate_changeset_impact(base, reordered),
+            shared_types::ChangesetImpact::High
+        );
+
+        let rewritten = "Completely different memo.\nNothing survives.\n";
+        assert_eq!(
+            WriterActor::estimate_changeset_impact(base, rewritten),
+            shared_types::ChangesetImpact::High
+        );
+
+        // A near-empty base is a first draft, not a rewrite.
+        assert_eq!(
+            WriterActor::estimate_changeset_impact("# Draft\n", rewritten),
+            shared_types::ChangesetImpact::Low
+        );
+    }
+
+    #[tokio::test]
+    async fn high_impact_agent_revision_waits_for_review_while_low_impact_applies() {
+        let run_id = format!("run_writer_review_{}", ulid::Ulid::new());
+        let run_dir = run_dir(&run_id);
+
+        let (event_store, _event_store_handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .unwrap();
+        let (writer, _writer_handle) = Actor::spawn(
+            None,
+            WriterActor,
+            WriterArguments {
+                writer_id: "writer-test".to_string(),
+                user_id: "user-test".to_string(),
+                event_store: event_store.clone(),
+                researcher_supervisor: None,
+                terminal_supervisor: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        ractor::call!(writer, |reply| WriterMsg::EnsureRunDocument {
+            run_id: run_id.clone(),
+            desktop_id: "desktop-test".to_string(),
+            objective: "Review policy test".to_string(),
+            reply,
+        })
+        .unwrap()
+        .unwrap();
+
+        let base_content = "# Plan\n\nIntro paragraph.\n\n## Pricing\n\nFree and pro tiers.\n";
+        let base = ractor::call!(writer, |reply| WriterMsg::CreateWriterDocumentVersion {
+            run_id: run_id.clone(),
+            parent_version_id: None,
+            content: base_content.to_string(),
+            source: VersionSource::UserSave,
+            reply,
+        })
+        .unwrap()
+        .unwrap();
+
+        let low = ractor::call!(writer, |reply| WriterMsg::ProposeWriterRevision {
+            run_id: run_id.clone(),
+            parent_version_id: Some(base.version_id),
+            content: format!("{base_content}\nTeam tier coming later.\n"),
+            reply,
+        })
+        .unwrap()
+        .unwrap();
+        let applied = match low {
+            WriterRevisionOutcome::Applied(version) => version,
+            other => panic!("low-impact revision should apply directly, got {other:?}"),
+        };
+        assert_eq!(applied.parent_version_id, Some(base.version_id));
+
+        let high = ractor::call!(writer, |reply| WriterMsg::ProposeWriterRevision {
+            run_id: run_id.clone(),
+            parent_version_id: Some(applied.version_id),
+            content: "A structural rewrite that replaces everything.\n".to_string(),
+            reply,
+        })
+        .unwrap()
+        .unwrap();
+        let overlay = match high {
+            WriterRevisionOutcome::PendingReview { overlay, impact } => {
+                assert_eq!(impact, shared_types::ChangesetImpact::High);
+                overlay
+            }
+            other => panic!("high-impact revision should wait for review, got {other:?}"),
+        };
+
+        let pending = ractor::call!(writer, |reply| WriterMsg::ListWriterDocumentOverlays {
+            run_id: run_id.clone(),
+            base_version_id: Some(applied.version_id),
+            status: Some(OverlayStatus::Pending),
+            reply,
+        })
+        .unwrap()
+        .unwrap();
+        assert!(pending.iter().any(|o| o.overlay_id == overlay.overlay_id));
+
+        let versions = ractor::call!(writer, |reply| WriterMsg::ListWriterDocumentVersions {
+            run_id: run_id.clone(),
+            reply,
+        })
+        .unwrap()
+        .unwrap();
+        let head = versions.iter().map(|v| v.version_id).max().unwrap();
+        assert_eq!(
+            head, applied.version_id,
+            "escalated revision must not apply"
+        );
+
+        writer.stop(None);
+        event_store.stop(None);
+        if run_dir.exists() {
+            tokio::fs::remove_dir_all(&run_dir).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn review_mode_parks_agent_patches_and_accept_rebases_stale_proposals() {
+        let run_id = format!("run_writer_proposals_{}", ulid::Ulid::new());
+        let run_dir = run_dir(&run_id);
+
+        let (event_store, _event_store_handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .unwrap();
+        let (writer, _writer_handle) = Actor::spawn(
+            None,
+            WriterActor,
+            WriterArguments {
+                writer_id: "writer-test".to_string(),
+                user_id: "user-test".to_string(),
+                event_store: event_store.clone(),
+                researcher_supervisor: None,
+                terminal_supervisor: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        ractor::call!(writer, |reply| WriterMsg::EnsureRunDocument {
+            run_id: run_id.clone(),
+            desktop_id: "desktop-test".to_string(),
+            objective: "Proposal review test".to_string(),
+            reply,
+        })
+        .unwrap()
+        .unwrap();
+        let base_content = "# Plan\n\nIntro paragraph.\n\n## Pricing\n\nFree and pro tiers.\n";
+        let base = ractor::call!(writer, |reply| WriterMsg::CreateWriterDocumentVersion {
+            run_id: run_id.clone(),
+            parent_version_id: None,
+            content: base_content.to_string(),
+            source: VersionSource::UserSave,
+            reply,
+        })
+        .unwrap()
+        .unwrap();
+        let enabled = ractor::call!(writer, |reply| WriterMsg::SetWriterReviewMode {
+            run_id: run_id.clone(),
+            enabled: true,
+            reply,
+        })
+        .unwrap()
+        .unwrap();
+        assert!(enabled);
+
+        // Even a low-impact agent revision waits for review in review mode.
+        let rewrite = ractor::call!(writer, |reply| WriterMsg::ProposeWriterRevision {
+            run_id: run_id.clone(),
+            parent_version_id: None,
+            content: format!("{base_content}\nTeam tier at launch.\n"),
+            reply,
+        })
+        .unwrap()
+        .unwrap();
+        let WriterRevisionOutcome::PendingReview {
+            overlay: rewrite, ..
+        } = rewrite
+        else {
+            panic!("review mode should park agent revisions, got {rewrite:?}");
+        };
+        ractor::call!(writer, |reply| WriterMsg::ApplyText {
+            run_id: run_id.clone(),
+            section_id: "researcher".to_string(),
+            source: WriterSource::Researcher,
+            content: "Team tier coming later.".to_string(),
+            proposal: false,
+            reply,
+        })
+        .unwrap()
+        .unwrap();
+
+        let list = ractor::call!(writer, |reply| WriterMsg::ListWriterDocumentProposals {
+            run_id: run_id.clone(),
+            reply,
+        })
+        .unwrap()
+        .unwrap();
+        assert!(list.review_mode);
+        assert_eq!(list.head_version_id, base.version_id, "nothing applied");
+        assert_eq!(list.proposals.len(), 2);
+        assert!(list.proposals.iter().all(|p| !p.stale));
+        let appended = list
+            .proposals
+            .iter()
+            .find(|p| p.overlay.author == OverlayAuthor::Researcher)
+            .unwrap()
+            .overlay
+            .overlay_id
+            .clone();
+
+        // A user edit moves the head on, leaving both proposals stale.
+        let edited = ractor::call!(writer, |reply| WriterMsg::CreateWriterDocumentVersion {
+            run_id: run_id.clone(),
+            parent_version_id: None,
+            content: base_content.replace("Intro paragraph.", "Intro, revised by the user."),
+            source: VersionSource::UserSave,
+            reply,
+        })
+        .unwrap()
+        .unwrap();
+        let list = ractor::call!(writer, |reply| WriterMsg::ListWriterDocumentProposals {
+            run_id: run_id.clone(),
+            reply,
+        })
+        .unwrap()
+        .unwrap();
+        assert!(list.proposals.iter().all(|p| p.stale));
+
+        let accepted = ractor::call!(writer, |reply| {
+            WriterMsg::AcceptWriterDocumentProposal {
+                run_id: run_id.clone(),
+                overlay_id: appended.clone(),
+                reply,
+            }
+        })
+        .unwrap()
+        .unwrap();
+        assert!(accepted.rebased);
+        assert_eq!(accepted.version.parent_version_id, Some(edited.version_id));
+        assert_eq!(
+            accepted.version.content,
+            "# Plan\n\nIntro, revised by the user.\n\n## Pricing\n\nFree and pro tiers.\nTeam tier coming later."
+        );
+
+        let mut confirmed = None;
+        for _ in 0..20 {
+            let events = crate::actors::event_store::get_events_for_actor(
+                &event_store,
+                format!("writer:{run_id}"),
+                0,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+            confirmed = events
+                .into_iter()
+                .filter(|event| event.event_type == shared_types::EVENT_TOPIC_WRITER_RUN_PATCH)
+                .filter_map(|event| {
+                    serde_json::from_value::<shared_types::WriterRunPatchPayload>(event.payload)
+                        .ok()
+                })
+                .find(|patch| patch.target_version_id == Some(accepted.version.version_id));
+            if confirmed.is_some() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        let confirmed = confirmed.expect("accepted proposal should emit a patch event");
+        assert_eq!(confirmed.source, shared_types::PatchSource::Agent);
+        assert_eq!(confirmed.source_actor.as_deref(), Some("researcher"));
+        assert_eq!(confirmed.confirmed_by.as_deref(), Some("user"));
+
+        // The whole-document rewrite overlaps the user edit: it must be
+        // re-proposed against the head before it can be accepted.
+        let conflict = ractor::call!(writer, |reply| {
+            WriterMsg::AcceptWriterDocumentProposal {
+                run_id: run_id.clone(),
+                overlay_id: rewrite.overlay_id.clone(),
+                reply,
+            }
+        })
+        .unwrap();
+        assert!(matches!(conflict, Err(WriterError::Conflict(_))));
+        let reproposed = ractor::call!(writer, |reply| {
+            WriterMsg::ReproposeWriterDocumentProposal {
+                run_id: run_id.clone(),
+                overlay_id: rewrite.overlay_id.clone(),
+                reply,
+            }
+        })
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            reproposed.overlay.base_version_id,
+            accepted.version.version_id
+        );
+        let list = ractor::call!(writer, |reply| WriterMsg::ListWriterDocumentProposals {
+            run_id: run_id.clone(),
+            reply,
+        })
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            list.proposals
+                .iter()
+                .map(|p| (p.overlay.overlay_id.clone(), p.stale))
+                .collect::<Vec<_>>(),
+            vec![(reproposed.overlay.overlay_id.clone(), false)]
+        );
+
+        writer.stop(None);
+        event_store.stop(None);
+        if run_dir.exists() {
+            tokio::fs::remove_dir_all(&run_dir).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn block_locks_warn_or_reject_user_edits_until_released() {
+        let run_id = format!("run_writer_locks_{}", ulid::Ulid::new());
+        let run_dir = run_dir(&run_id);
+
+        let (event_store, _event_store_handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .unwrap();
+        let (writer, _writer_handle) = Actor::spawn(
+            None,
+            WriterActor,
+            WriterArguments {
+                writer_id: "writer-test".to_string(),
+                user_id: "user-test".to_string(),
+                event_store: event_store.clone(),
+                researcher_supervisor: None,
+                terminal_supervisor: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        ractor::call!(writer, |reply| WriterMsg::EnsureRunDocument {
+            run_id: run_id.clone(),
+            desktop_id: "desktop-test".to_string(),
+            objective: "Block lock test".to_string(),
+            reply,
+        })
+        .unwrap()
+        .unwrap();
+        let base_content = "# Plan\n\nIntro paragraph.\n\nPricing details.";
+        let save = |parent_version_id: u64, content: String| {
+            let run_id = run_id.clone();
+            let writer = writer.clone();
+            async move {
+                ractor::call!(writer, |reply| WriterMsg::CreateWriterDocumentVersion {
+                    run_id,
+                    parent_version_id: Some(parent_version_id),
+                    content,
+                    source: VersionSource::UserSave,
+                    reply,
+                })
+                .unwrap()
+            }
+        };
+        let base = save(0, base_content.to_string()).await.unwrap();
+        let lock = ractor::call!(writer, |reply| WriterMsg::LockWriterBlocks {
+            run_id: run_id.clone(),
+            block_ids: Vec::new(),
+            ttl_ms: 60_000,
+            reply,
+        })
+        .unwrap()
+        .unwrap();
+        assert!(lock.block_ids.is_empty());
+
+        // The loop declares the blocks its revision rewrites; the heading is
+        // left unlocked.
+        let revision = base_content
+            .replace("Intro paragraph.", "Intro, by the agent.")
+            .replace("Pricing details.", "Pricing, by the agent.");
+        writer
+            .send_message(WriterMsg::DeclareWriterLockTargets {
+                run_id: run_id.clone(),
+                lock_id: lock.lock_id.clone(),
+                block_ids: document_runtime::changed_block_ids(base_content, &revision),
+            })
+            .unwrap();
+
+        // Warn mode (the default) lets the edit through.
+        let warned = save(
+            base.version_id,
+            base_content.replace("Intro paragraph.", "Intro, edited."),
+        )
+        .await
+        .unwrap();
+
+        ractor::call!(writer, |reply| WriterMsg::SetWriterLockStrictness {
+            run_id: run_id.clone(),
+            strictness: shared_types::WriterLockStrictness::Reject,
+            reply,
+        })
+        .unwrap()
+        .unwrap();
+        let rejected = save(
+            warned.version_id,
+            base_content.replace("Pricing details.", "Pricing, edited."),
+        )
+        .await;
+        assert!(matches!(rejected, Err(WriterError::Conflict(_))));
+        let retitled = save(
+            warned.version_id,
+            base_content
+                .replace("Intro paragraph.", "Intro, edited.")
+                .replace("# Plan", "# Revised plan"),
+        )
+        .await
+        .unwrap();
+
+        writer
+            .send_message(WriterMsg::ReleaseWriterBlockLock {
+                run_id: run_id.clone(),
+                lock_id: lock.lock_id.clone(),
+                reason: "completed".to_string(),
+            })
+            .unwrap();
+        save(
+            retitled.version_id,
+            base_content
+                .replace("Intro paragraph.", "Intro, edited.")
+                .replace("# Plan", "# Revised plan")
+                .replace("Pricing details.", "Pricing, edited."),
+        )
+        .await
+        .unwrap();
+
+        let events = crate::actors::event_store::get_events_for_actor(
+            &event_store,
+            format!("writer:{run_id}"),
+            0,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        let locks: Vec<shared_types::WriterRunLockPayload> = events
+            .iter()
+            .filter(|event| event.event_type == shared_types::EVENT_TOPIC_WRITER_RUN_LOCK)
+            .map(|event| serde_json::from_value(event.payload.clone()).unwrap())
+            .collect();
+        assert_eq!(locks.len(), 3);
+        assert!(!locks[0].released);
+        assert_eq!(locks[1].block_ids.len(), 2);
+        assert!(locks[2].released);
+        assert_eq!(locks[2].reason.as_deref(), Some("completed"));
+        assert!(events
+            .iter()
+            .any(|event| event.payload["phase"] == "block_lock_warning"));
+
+        writer.stop(None);
+        event_store.stop(None);
+        if run_dir.exists() {
+            tokio::fs::remove_dir_all(&run_dir).await.unwrap();
+        }
+    }
 }
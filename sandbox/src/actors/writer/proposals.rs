@@ -0,0 +1,111 @@
+//! Review of agent proposals parked as overlays.
+//!
+//! A proposal is recorded against the version that was head when the agent
+//! produced it. Once the head moves on the proposal is stale: accepting it
+//! rebases its line hunks onto the new head, and when those hunks touch lines
+//! the head also changed it has to be re-proposed instead.
+
+use similar::{DiffTag, TextDiff};
+
+/// Lines `start..end` of the base replaced by `lines`.
+struct Hunk<'a> {
+    start: usize,
+    end: usize,
+    lines: Vec<&'a str>,
+}
+
+fn hunks<'a>(base: &str, target: &'a str) -> Vec<Hunk<'a>> {
+    let target_lines: Vec<&str> = target.split_inclusive('\n').collect();
+    let mut hunks: Vec<Hunk<'a>> = Vec::new();
+    for op in TextDiff::from_lines(base, target).ops() {
+        if op.tag() == DiffTag::Equal {
+            continue;
+        }
+        let (old, new) = (op.old_range(), op.new_range());
+        match hunks.last_mut() {
+            Some(last) if last.end == old.start => {
+                last.end = old.end;
+                last.lines.extend_from_slice(&target_lines[new]);
+            }
+            _ => hunks.push(Hunk {
+                start: old.start,
+                end: old.end,
+                lines: target_lines[new].to_vec(),
+            }),
+        }
+    }
+    hunks
+}
+
+/// Three-way merge of `proposed` onto `head`, both derived from `base`.
+///
+/// Returns `None` when a proposal hunk overlaps or touches a hunk the head
+/// already changed; adjacent edits are treated as conflicts, like git does.
+pub fn rebase_proposal(base: &str, proposed: &str, head: &str) -> Option<String> {
+    if base == head {
+        return Some(proposed.to_string());
+    }
+    if base == proposed {
+        return Some(head.to_string());
+    }
+
+    let ours = hunks(base, proposed);
+    let theirs = hunks(base, head);
+    let conflicts = ours
+        .iter()
+        .any(|o| theirs.iter().any(|t| o.start <= t.end && t.start <= o.end));
+    if conflicts {
+        return None;
+    }
+
+    let base_lines: Vec<&str> = base.split_inclusive('\n').collect();
+    let mut all: Vec<&Hunk> = ours.iter().chain(theirs.iter()).collect();
+    all.sort_by_key(|hunk| hunk.start);
+
+    let mut merged = String::with_capacity(proposed.len().max(head.len()));
+    let mut cursor = 0usize;
+    for hunk in all {
+        merged.extend(base_lines[cursor..hunk.start].iter().copied());
+        merged.extend(hunk.lines.iter().copied());
+        cursor = hunk.end;
+    }
+    merged.extend(base_lines[cursor..].iter().copied());
+    Some(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rebase_proposal;
+
+    #[test]
+    fn unchanged_head_takes_the_proposal_as_is() {
+        assert_eq!(
+            rebase_proposal("a\nb\n", "a\nB\n", "a\nb\n").as_deref(),
+            Some("a\nB\n")
+        );
+    }
+
+    #[test]
+    fn disjoint_edits_merge_onto_the_new_head() {
+        let base = "# Title\n\nintro\n\nbody\n\noutro\n";
+        let head = "# Title\n\nIntro, edited by the user.\n\nbody\n\noutro\n";
+        let proposed = "# Title\n\nintro\n\nbody\n\nOutro from the agent.\n";
+        assert_eq!(
+            rebase_proposal(base, proposed, head).as_deref(),
+            Some("# Title\n\nIntro, edited by the user.\n\nbody\n\nOutro from the agent.\n")
+        );
+    }
+
+    #[test]
+    fn edits_to_the_same_line_conflict() {
+        let base = "one\ntwo\nthree\n";
+        assert_eq!(
+            rebase_proposal(
+                base,
+                "one\nTWO (agent)\nthree\n",
+                "one\nTwo (user)\nthree\n"
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,268 @@
+//! Event-sourced undo/redo for user document edits.
+//!
+//! The undo stack is never stored: it is rebuilt from the user-sourced
+//! `writer.run.patch` events of a run document. Undoing a patch applies its
+//! inverse as a new patch tagged `undo_of: <patch_id>`; redo is the undo of
+//! an undo. History is only ever appended to.
+
+use shared_types::{PatchOp, PatchSource, WriterRunPatchPayload};
+
+use super::{WriterActor, WriterError};
+
+/// Apply `ops` to `base` and compute the ops that turn the result back into `base`.
+///
+/// Ops use the same char-position semantics as writer prompt diffs. The
+/// inverse is returned in application order (last op inverted first).
+pub fn invert_patch_ops(
+    base: &str,
+    ops: &[PatchOp],
+) -> Result<(String, Vec<PatchOp>), WriterError> {
+    let mut chars: Vec<char> = base.chars().collect();
+    let mut inverse = Vec::with_capacity(ops.len());
+
+    for op in ops {
+        match op {
+            PatchOp::Insert { pos, text } => {
+                let idx = position(*pos, chars.len(), "insert")?;
+                chars.splice(idx..idx, text.chars());
+                inverse.push(PatchOp::Delete {
+                    pos: idx as u64,
+                    len: text.chars().count() as u64,
+                });
+            }
+            PatchOp::Delete { pos, len } => {
+                let idx = position(*pos, chars.len(), "delete")?;
+                let end = span_end(idx, *len, chars.len());
+                let removed: String = chars.drain(idx..end).collect();
+                inverse.push(PatchOp::Insert {
+                    pos: idx as u64,
+                    text: removed,
+                });
+            }
+            PatchOp::Replace { pos, len, text } => {
+                let idx = position(*pos, chars.len(), "replace")?;
+                let end = span_end(idx, *len, chars.len());
+                let removed: String = chars.splice(idx..end, text.chars()).collect();
+                inverse.push(PatchOp::Replace {
+                    pos: idx as u64,
+                    len: text.chars().count() as u64,
+                    text: removed,
+                });
+            }
+            PatchOp::Retain { .. } => {}
+        }
+    }
+
+    inverse.reverse();
+    Ok((chars.into_iter().collect(), inverse))
+}
+
+fn position(pos: u64, len: usize, op: &str) -> Result<usize, WriterError> {
+    let idx = usize::try_from(pos).map_err(|_| {
+        WriterError::Validation(format!("{op} position out of bounds for usize: {pos}"))
+    })?;
+    if idx > len {
+        return Err(WriterError::Validation(format!(
+            "{op} position {idx} exceeds content length {len}"
+        )));
+    }
+    Ok(idx)
+}
+
+fn span_end(idx: usize, len: u64, content_len: usize) -> usize {
+    let max = content_len.saturating_sub(idx);
+    let requested = if len == u64::MAX {
+        max
+    } else {
+        usize::try_from(len).unwrap_or(max)
+    };
+    idx.saturating_add(requested).min(content_len)
+}
+
+/// Undo and redo candidates derived from a document's patch history.
+#[derive(Debug, Default)]
+pub struct UndoStack {
+    done: Vec<WriterRunPatchPayload>,
+    undone: Vec<WriterRunPatchPayload>,
+}
+
+impl UndoStack {
+    /// Replay patches in commit order. Only canonical user edits participate;
+    /// agent patches and overlay proposals are skipped.
+    pub fn from_patches(patches: impl IntoIterator<Item = WriterRunPatchPayload>) -> Self {
+        let mut stack = Self::default();
+        for patch in patches {
+            if patch.source != PatchSource::User
+                || patch.overlay_id.is_some()
+                || patch.target_version_id.is_none()
+            {
+                continue;
+            }
+            match patch.undo_of.as_deref() {
+                None => {
+                    stack.undone.clear();
+                    stack.done.push(patch);
+                }
+                Some(target) if stack.done.last().is_some_and(|p| p.patch_id == target) => {
+                    stack.done.pop();
+                    stack.undone.push(patch);
+                }
+                Some(target) if stack.undone.last().is_some_and(|p| p.patch_id == target) => {
+                    stack.undone.pop();
+                    stack.done.push(patch);
+                }
+                // Reverts of patches that are no longer on top are history only.
+                Some(_) => {}
+            }
+        }
+        stack
+    }
+
+    /// Most recent user patch that has not been undone.
+    pub fn undo_target(&self) -> Option<&WriterRunPatchPayload> {
+        self.done.last()
+    }
+
+    /// Most recent undo that has not been redone or invalidated by a new edit.
+    pub fn redo_target(&self) -> Option<&WriterRunPatchPayload> {
+        self.undone.last()
+    }
+}
+
+/// Ops and content needed to revert `patch`, which produced the current head.
+pub fn revert_patch(
+    base_content: &str,
+    head_content: &str,
+    patch: &WriterRunPatchPayload,
+) -> Result<(String, Vec<PatchOp>), WriterError> {
+    let (patched, inverse) = invert_patch_ops(base_content, &patch.ops)?;
+    if patched != head_content {
+        return Err(WriterError::Conflict(format!(
+            "patch {} no longer matches the document head",
+            patch.patch_id
+        )));
+    }
+    let reverted = WriterActor::apply_shared_patch_ops(head_content, &inverse)?;
+    Ok((reverted, inverse))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Small deterministic generator so property runs are reproducible.
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next(&mut self) -> u64 {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            self.0 >> 33
+        }
+
+        fn below(&mut self, n: usize) -> usize {
+            if n == 0 {
+                0
+            } else {
+                (self.next() % n as u64) as usize
+            }
+        }
+
+        fn text(&mut self) -> String {
+            const ALPHABET: [char; 8] = ['a', 'b', ' ', '\n', '#', 'é', '中', 'z'];
+            (0..self.below(6))
+                .map(|_| ALPHABET[self.below(ALPHABET.len())])
+                .collect()
+        }
+
+        fn op(&mut self, content_len: usize) -> PatchOp {
+            let pos = self.below(content_len + 1) as u64;
+            let len = if self.below(8) == 0 {
+                u64::MAX
+            } else {
+                self.below(content_len + 2) as u64
+            };
+            match self.below(4) {
+                0 => PatchOp::Insert {
+                    pos,
+                    text: self.text(),
+                },
+                1 => PatchOp::Delete { pos, len },
+                2 => PatchOp::Replace {
+                    pos,
+                    len,
+                    text: self.text(),
+                },
+                _ => PatchOp::Retain { len },
+            }
+        }
+    }
+
+    fn patch(patch_id: &str, source: PatchSource, undo_of: Option<&str>) -> WriterRunPatchPayload {
+        WriterRunPatchPayload {
+            patch_id: patch_id.to_string(),
+            source,
+            source_actor: None,
+            section_id: None,
+            ops: Vec::new(),
+            proposal: None,
+            base_version_id: Some(0),
+            target_version_id: Some(1),
+            overlay_id: None,
+            undo_of: undo_of.map(str::to_string),
+            confirmed_by: None,
+        }
+    }
+
+    #[test]
+    fn undo_after_apply_restores_prior_content_for_random_patches() {
+        let mut rng = Lcg(0x5eed);
+        for _ in 0..500 {
+            let mut content = rng.text();
+            for _ in 0..rng.below(5) {
+                let ops: Vec<PatchOp> = (0..1 + rng.below(4))
+                    .map(|_| rng.op(content.chars().count()))
+                    .collect();
+                // Ops are generated against the starting length, so later ones
+                // may be out of range; those patches are rejected by both paths.
+                let Ok(expected) = WriterActor::apply_shared_patch_ops(&content, &ops) else {
+                    assert!(invert_patch_ops(&content, &ops).is_err());
+                    continue;
+                };
+                let (patched, inverse) = invert_patch_ops(&content, &ops).unwrap();
+                assert_eq!(patched, expected);
+                let undone = WriterActor::apply_shared_patch_ops(&patched, &inverse).unwrap();
+                assert_eq!(undone, content, "ops={ops:?}");
+                content = patched;
+            }
+        }
+    }
+
+    #[test]
+    fn undo_stack_tracks_undo_redo_and_skips_agent_patches() {
+        let history = vec![
+            patch("p1", PatchSource::User, None),
+            patch("a1", PatchSource::Agent, None),
+            patch("p2", PatchSource::User, None),
+            patch("u2", PatchSource::User, Some("p2")),
+        ];
+        let stack = UndoStack::from_patches(history.clone());
+        assert_eq!(stack.undo_target().unwrap().patch_id, "p1");
+        assert_eq!(stack.redo_target().unwrap().patch_id, "u2");
+
+        let mut redone = history.clone();
+        redone.push(patch("r2", PatchSource::User, Some("u2")));
+        let stack = UndoStack::from_patches(redone);
+        assert_eq!(stack.undo_target().unwrap().patch_id, "r2");
+        assert!(stack.redo_target().is_none());
+
+        // A fresh edit after an undo clears the redo stack.
+        let mut edited = history;
+        edited.push(patch("p3", PatchSource::User, None));
+        let stack = UndoStack::from_patches(edited);
+        assert_eq!(stack.undo_target().unwrap().patch_id, "p3");
+        assert!(stack.redo_target().is_none());
+    }
+}
@@ -0,0 +1,161 @@
+//! Admin API endpoints
+//!
+//! Operator tooling for maintaining derived state, such as rebuilding
+//! projections from the event log and redacting events, and for reloading
+//! the secret scan rules.
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use serde::Deserialize;
+use serde_json::json;
+use shared_types::ErrorCode;
+
+use super::error::api_error;
+use super::ApiState;
+use crate::actors::event_store::{append_event, get_event_by_seq, AppendEvent};
+use crate::projections::search::SEARCH_PROJECTION;
+use crate::projections::ProjectionError;
+use crate::secret_scan::{reload_secret_scanner, RulesError};
+
+fn projection_error_response(err: ProjectionError) -> axum::response::Response {
+    let code = match err {
+        ProjectionError::NotFound(_) => ErrorCode::NotFound,
+        ProjectionError::AlreadyRegistered(_) => ErrorCode::AlreadyExists,
+        _ => ErrorCode::InternalError,
+    };
+    api_error(code, err.to_string())
+}
+
+/// List registered projections with their stored cursors.
+pub async fn list_projections(State(state): State<ApiState>) -> impl IntoResponse {
+    let manager = state.app_state.projections();
+    let mut projections = Vec::new();
+    for name in manager.names().await {
+        match manager.status(&name).await {
+            Ok(status) => projections.push(status),
+            Err(err) => return projection_error_response(err),
+        }
+    }
+    (StatusCode::OK, Json(json!({ "projections": projections }))).into_response()
+}
+
+/// Get the stored cursor and status for one projection.
+pub async fn get_projection_status(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    match state.app_state.projections().status(&name).await {
+        Ok(status) => (StatusCode::OK, Json(json!(status))).into_response(),
+        Err(err) => projection_error_response(err),
+    }
+}
+
+/// Reset a projection and replay the full event log into it.
+///
+/// Resumes instead of resetting when a previous rebuild was interrupted.
+pub async fn rebuild_projection(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    match state.app_state.projections().rebuild(&name).await {
+        Ok(report) => (StatusCode::OK, Json(json!(report))).into_response(),
+        Err(err) => projection_error_response(err),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RedactEventRequest {
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+fn store_error_response(err: impl std::fmt::Display) -> axum::response::Response {
+    api_error(ErrorCode::InternalError, err.to_string())
+}
+
+/// Mark an event as redacted.
+///
+/// The event log stays append-only; an `event.redacted` event is recorded and
+/// derived read models drop what they built from the target. The search index
+/// is caught up before responding so the content is unsearchable on return.
+pub async fn redact_event(
+    State(state): State<ApiState>,
+    Path(seq): Path<i64>,
+    Json(req): Json<RedactEventRequest>,
+) -> impl IntoResponse {
+    let event_store = state.app_state.event_store();
+    let target = match get_event_by_seq(&event_store, seq).await {
+        Ok(Ok(Some(event))) => event,
+        Ok(Ok(None)) => {
+            return api_error(ErrorCode::NotFound, format!("event not found: {seq}"));
+        }
+        Ok(Err(err)) => return store_error_response(err),
+        Err(err) => return store_error_response(err),
+    };
+
+    let redaction = AppendEvent {
+        event_type: shared_types::EVENT_TOPIC_EVENT_REDACTED.to_string(),
+        payload: json!({
+            "seq": seq,
+            "event_type": target.event_type,
+            "reason": req.reason,
+        }),
+        actor_id: "api.admin".to_string(),
+        user_id: "system".to_string(),
+        idempotency_key: None,
+    };
+    let redaction = match append_event(&event_store, redaction).await {
+        Ok(Ok(event)) => event,
+        Ok(Err(err)) => return store_error_response(err),
+        Err(err) => return store_error_response(err),
+    };
+
+    if let Err(err) = state
+        .app_state
+        .projections()
+        .catch_up(SEARCH_PROJECTION)
+        .await
+    {
+        return projection_error_response(err);
+    }
+
+    (
+        StatusCode::OK,
+        Json(json!({ "seq": seq, "redaction_seq": redaction.seq })),
+    )
+        .into_response()
+}
+
+/// Reload the secret scan rules from their TOML file.
+///
+/// A file that fails to parse is rejected and the rules in force are kept.
+pub async fn reload_secret_scan_rules() -> impl IntoResponse {
+    match reload_secret_scanner() {
+        Ok(scanner) => {
+            let rules = scanner
+                .rule_summaries()
+                .into_iter()
+                .map(|(id, description)| json!({ "id": id, "description": description }))
+                .collect::<Vec<_>>();
+            let source = scanner
+                .source()
+                .map(|path| path.display().to_string())
+                .unwrap_or_else(|| "built-in".to_string());
+            tracing::info!(source = %source, rules = rules.len(), "Reloaded secret scan rules");
+            (
+                StatusCode::OK,
+                Json(json!({ "source": source, "rules": rules })),
+            )
+                .into_response()
+        }
+        Err(err) => {
+            let code = match &err {
+                RulesError::Read { .. } => ErrorCode::InternalError,
+                RulesError::Parse(_) | RulesError::Pattern { .. } => ErrorCode::InvalidContent,
+            };
+            api_error(code, err.to_string())
+        }
+    }
+}
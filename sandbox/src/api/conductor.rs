@@ -8,46 +8,19 @@ use axum::response::IntoResponse;
 use axum::Json;
 use serde::Serialize;
 
+use crate::actors::conductor::state::run_progress_pct;
 use crate::actors::conductor::{ConductorError as ActorConductorError, ConductorMsg};
 use crate::actors::event_store::{AppendEvent, EventStoreMsg};
+use crate::api::error::{api_error, status_for};
 use crate::api::websocket::{broadcast_event, WsMessage};
 use crate::api::ApiState;
 use shared_types::{
     ConductorDocumentUpdatePayload, ConductorError, ConductorExecuteRequest,
-    ConductorExecuteResponse, ConductorRunState, ConductorRunStatus, ConductorRunStatusResponse,
-    ConductorToastPayload, ConductorToastTone, DesktopTelemetryEvent, EventImportance,
-    WriterWindowProps,
+    ConductorExecuteResponse, ConductorRefineRequest, ConductorRunState, ConductorRunStatus,
+    ConductorRunStatusResponse, ConductorToastPayload, ConductorToastTone, DesktopTelemetryEvent,
+    ErrorCode, EventImportance, WriterWindowProps,
 };
 
-/// Conductor error codes for machine-readable error responses
-#[derive(Debug, Clone)]
-pub enum ConductorErrorCode {
-    InvalidRequest,
-    ActorNotAvailable,
-    RunNotFound,
-    InternalError,
-}
-
-impl ConductorErrorCode {
-    fn as_str(&self) -> &'static str {
-        match self {
-            ConductorErrorCode::InvalidRequest => "INVALID_REQUEST",
-            ConductorErrorCode::ActorNotAvailable => "ACTOR_NOT_AVAILABLE",
-            ConductorErrorCode::RunNotFound => "RUN_NOT_FOUND",
-            ConductorErrorCode::InternalError => "INTERNAL_ERROR",
-        }
-    }
-
-    fn status_code(&self) -> StatusCode {
-        match self {
-            ConductorErrorCode::InvalidRequest => StatusCode::BAD_REQUEST,
-            ConductorErrorCode::ActorNotAvailable => StatusCode::SERVICE_UNAVAILABLE,
-            ConductorErrorCode::RunNotFound => StatusCode::NOT_FOUND,
-            ConductorErrorCode::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
-        }
-    }
-}
-
 #[derive(Debug, Serialize)]
 struct RunStatusErrorResponse {
     run_id: String,
@@ -55,12 +28,12 @@ struct RunStatusErrorResponse {
 }
 
 fn conductor_error(
-    code: ConductorErrorCode,
+    code: ErrorCode,
     message: impl Into<String>,
     failure_kind: Option<shared_types::FailureKind>,
 ) -> ConductorError {
     ConductorError {
-        code: code.as_str().to_string(),
+        code,
         message: message.into(),
         failure_kind,
     }
@@ -71,6 +44,7 @@ fn status_code_for_run(status: ConductorRunStatus) -> StatusCode {
         ConductorRunStatus::Initializing
         | ConductorRunStatus::Running
         | ConductorRunStatus::WaitingForCalls
+        | ConductorRunStatus::WaitingForProvider
         | ConductorRunStatus::Completing => StatusCode::ACCEPTED,
         ConductorRunStatus::Completed => StatusCode::OK,
         ConductorRunStatus::Failed | ConductorRunStatus::Blocked => {
@@ -95,18 +69,25 @@ fn run_lifecycle_telemetry_descriptor(
         | ConductorRunStatus::Running
         | ConductorRunStatus::WaitingForCalls
         | ConductorRunStatus::Completing => (
-            "conductor.task.started",
+            shared_types::EVENT_TOPIC_CONDUCTOR_TASK_STARTED,
             "run_start",
             EventImportance::Normal,
         ),
+        ConductorRunStatus::WaitingForProvider => (
+            "conductor.task.waiting_for_provider",
+            "provider_gateway",
+            EventImportance::High,
+        ),
         ConductorRunStatus::Completed => (
-            "conductor.task.completed",
+            shared_types::EVENT_TOPIC_CONDUCTOR_TASK_COMPLETED,
             "completion",
             EventImportance::Normal,
         ),
-        ConductorRunStatus::Failed | ConductorRunStatus::Blocked => {
-            ("conductor.task.failed", "failure", EventImportance::High)
-        }
+        ConductorRunStatus::Failed | ConductorRunStatus::Blocked => (
+            shared_types::EVENT_TOPIC_CONDUCTOR_TASK_FAILED,
+            "failure",
+            EventImportance::High,
+        ),
     }
 }
 
@@ -138,6 +119,19 @@ fn writer_window_props_for_run_document(document_path: &str, run_id: &str) -> Wr
     }
 }
 
+/// Catalog code for a recorded `error_code`, including the per-variant codes
+/// written before the shared catalog existed.
+fn error_code_from_event(code: &str) -> ErrorCode {
+    ErrorCode::parse(code).unwrap_or(match code {
+        "ACTOR_NOT_AVAILABLE" => ErrorCode::ActorUnavailable,
+        "WORKER_BLOCKED" => ErrorCode::RunBlocked,
+        "DUPLICATE_RUN" => ErrorCode::AlreadyExists,
+        "MODEL_GATEWAY_ERROR" => ErrorCode::ProviderUnavailable,
+        "REPORT_WRITE_FAILED" | "FILE_ERROR" => ErrorCode::InternalError,
+        _ => ErrorCode::RunFailed,
+    })
+}
+
 fn run_error_from_artifacts(run: &ConductorRunState) -> Option<ConductorError> {
     for artifact in run.artifacts.iter().rev() {
         let Some(metadata) = artifact.metadata.as_ref() else {
@@ -146,7 +140,7 @@ fn run_error_from_artifacts(run: &ConductorRunState) -> Option<ConductorError> {
         let Some(event_type) = metadata.get("event_type").and_then(|value| value.as_str()) else {
             continue;
         };
-        if event_type != "conductor.task.failed" {
+        if event_type != shared_types::EVENT_TOPIC_CONDUCTOR_TASK_FAILED {
             continue;
         }
 
@@ -156,8 +150,8 @@ fn run_error_from_artifacts(run: &ConductorRunState) -> Option<ConductorError> {
         let code = payload
             .get("error_code")
             .and_then(|value| value.as_str())
-            .unwrap_or("RUN_FAILED")
-            .to_string();
+            .map(error_code_from_event)
+            .unwrap_or(ErrorCode::RunFailed);
         let message = payload
             .get("error_message")
             .and_then(|value| value.as_str())
@@ -185,13 +179,13 @@ fn run_error_for_status(run: &ConductorRunState) -> Option<ConductorError> {
 
     if run.status == ConductorRunStatus::Blocked {
         Some(ConductorError {
-            code: "RUN_BLOCKED".to_string(),
+            code: ErrorCode::RunBlocked,
             message: "Run blocked by conductor model gateway".to_string(),
             failure_kind: Some(shared_types::FailureKind::Unknown),
         })
     } else if run.status == ConductorRunStatus::Failed {
         Some(ConductorError {
-            code: "RUN_FAILED".to_string(),
+            code: ErrorCode::RunFailed,
             message: "Run failed".to_string(),
             failure_kind: Some(shared_types::FailureKind::Unknown),
         })
@@ -231,6 +225,17 @@ fn toast_from_run(
     run: &ConductorRunState,
     report_path: Option<&str>,
 ) -> Option<ConductorToastPayload> {
+    if run.status == ConductorRunStatus::WaitingForProvider {
+        return Some(ConductorToastPayload {
+            title: "Provider unavailable".to_string(),
+            message: format!(
+                "{} This run is queued and will start when it recovers.",
+                crate::app_state::PROVIDER_GATEWAY_OUTAGE_MESSAGE
+            ),
+            tone: ConductorToastTone::Warning,
+            report_path: None,
+        });
+    }
     if run.status != ConductorRunStatus::Completed {
         return None;
     }
@@ -274,6 +279,7 @@ fn run_state_to_execute_response(run: ConductorRunState) -> ConductorExecuteResp
         }
         ConductorRunStatus::Initializing
         | ConductorRunStatus::WaitingForCalls
+        | ConductorRunStatus::WaitingForProvider
         | ConductorRunStatus::Completing => {
             let path = run.document_path.clone();
             let props = writer_window_props_for_run_document(&path, &run_id);
@@ -313,6 +319,7 @@ fn run_state_to_status_response(run: ConductorRunState) -> ConductorRunStatusRes
     };
     let toast = toast_from_run(&run, report_path.as_deref());
     let error = run_error_for_status(&run);
+    let progress_pct = run_progress_pct(&run);
 
     ConductorRunStatusResponse {
         run_id: run.run_id,
@@ -325,33 +332,35 @@ fn run_state_to_status_response(run: ConductorRunState) -> ConductorRunStatusRes
         completed_at: run.completed_at,
         document_path: run.document_path,
         report_path,
+        progress_pct,
         toast,
         error,
+        revision: run.revision,
     }
 }
 
 fn map_actor_error(err: ActorConductorError) -> (StatusCode, ConductorError) {
     match err {
         ActorConductorError::ActorUnavailable(msg) => (
-            ConductorErrorCode::ActorNotAvailable.status_code(),
+            status_for(ErrorCode::ActorUnavailable),
             conductor_error(
-                ConductorErrorCode::ActorNotAvailable,
+                ErrorCode::ActorUnavailable,
                 msg,
                 Some(shared_types::FailureKind::Unknown),
             ),
         ),
         ActorConductorError::InvalidRequest(msg) => (
-            ConductorErrorCode::InvalidRequest.status_code(),
+            status_for(ErrorCode::InvalidRequest),
             conductor_error(
-                ConductorErrorCode::InvalidRequest,
+                ErrorCode::InvalidRequest,
                 msg,
                 Some(shared_types::FailureKind::Validation),
             ),
         ),
         ActorConductorError::NotFound(msg) => (
-            ConductorErrorCode::RunNotFound.status_code(),
+            status_for(ErrorCode::RunNotFound),
             conductor_error(
-                ConductorErrorCode::RunNotFound,
+                ErrorCode::RunNotFound,
                 msg,
                 Some(shared_types::FailureKind::Unknown),
             ),
@@ -362,9 +371,9 @@ fn map_actor_error(err: ActorConductorError) -> (StatusCode, ConductorError) {
         | ActorConductorError::DuplicateRun(msg)
         | ActorConductorError::ModelGatewayError(msg)
         | ActorConductorError::FileError(msg) => (
-            ConductorErrorCode::InternalError.status_code(),
+            status_for(ErrorCode::InternalError),
             conductor_error(
-                ConductorErrorCode::InternalError,
+                ErrorCode::InternalError,
                 msg,
                 Some(shared_types::FailureKind::Unknown),
             ),
@@ -454,7 +463,7 @@ pub async fn execute_task(
 ) -> impl IntoResponse {
     if request.objective.trim().is_empty() {
         let error = conductor_error(
-            ConductorErrorCode::InvalidRequest,
+            ErrorCode::InvalidRequest,
             "Objective cannot be empty",
             Some(shared_types::FailureKind::Validation),
         );
@@ -471,7 +480,7 @@ pub async fn execute_task(
 
     if request.desktop_id.trim().is_empty() {
         let error = conductor_error(
-            ConductorErrorCode::InvalidRequest,
+            ErrorCode::InvalidRequest,
             "Desktop ID cannot be empty",
             Some(shared_types::FailureKind::Validation),
         );
@@ -486,11 +495,35 @@ pub async fn execute_task(
         return (StatusCode::BAD_REQUEST, body).into_response();
     }
 
+    // The run directory starts out holding at least the objective.
+    if let Err(e) = state
+        .app_state
+        .check_disk_write(request.objective.len() as u64)
+        .await
+    {
+        let code = ErrorCode::QuotaExceeded;
+        let status = status_for(code);
+        let body = Json(ConductorExecuteResponse {
+            run_id: String::new(),
+            status: ConductorRunStatus::Failed,
+            document_path: None,
+            writer_window_props: None,
+            toast: None,
+            error: Some(conductor_error(code, e.to_string(), None)),
+        });
+        return (status, body).into_response();
+    }
+
+    state.app_state.record_activity(
+        &request.desktop_id,
+        shared_types::ActivitySource::Delegation,
+    );
+
     let input_id = ulid::Ulid::new().to_string();
     let user_input_record = shared_types::UserInputRecord {
         input_id: input_id.clone(),
         content: request.objective.clone(),
-        surface: "conductor".to_string(),
+        surface: shared_types::UserInputSurface::Conductor,
         desktop_id: request.desktop_id.clone(),
         session_id: request.desktop_id.clone(),
         thread_id: String::new(),
@@ -513,6 +546,7 @@ pub async fn execute_task(
                 }),
                 actor_id: "api.conductor".to_string(),
                 user_id: "system".to_string(),
+                idempotency_key: None,
             },
         });
 
@@ -520,7 +554,7 @@ pub async fn execute_task(
         Ok(actor) => actor,
         Err(e) => {
             let error = conductor_error(
-                ConductorErrorCode::ActorNotAvailable,
+                ErrorCode::SupervisorUnavailable,
                 format!("Failed to ensure conductor actor: {e}"),
                 Some(shared_types::FailureKind::Unknown),
             );
@@ -552,6 +586,7 @@ pub async fn execute_task(
     .await;
 
     let desktop_id = request.desktop_id.clone();
+    let objective = request.objective.clone();
     let ws_sessions = state.ws_sessions.clone();
     let mut result: Result<Result<ConductorRunState, ActorConductorError>, _> =
         ractor::call!(conductor, |reply| ConductorMsg::ExecuteTask {
@@ -573,6 +608,13 @@ pub async fn execute_task(
 
     match result {
         Ok(Ok(run_state)) => {
+            tokio::spawn(crate::preferences::learn_from_input(
+                state.app_state.event_store(),
+                state.app_state.disk_usage().root().to_path_buf(),
+                crate::preferences::DEFAULT_PREFERENCE_USER.to_string(),
+                objective,
+                Some(run_state.run_id.clone()),
+            ));
             let run_state = match run_state.status {
                 ConductorRunStatus::Initializing
                 | ConductorRunStatus::Running
@@ -625,7 +667,7 @@ pub async fn execute_task(
         }
         Err(e) => {
             let error = conductor_error(
-                ConductorErrorCode::ActorNotAvailable,
+                ErrorCode::ActorUnavailable,
                 format!("Conductor RPC failed: {e}"),
                 Some(shared_types::FailureKind::Unknown),
             );
@@ -642,16 +684,51 @@ pub async fn execute_task(
     }
 }
 
+/// POST /api/conductor/refine - Propose an objective contract and clarifying
+/// questions for an objective. Starts no run.
+pub async fn refine_objective(
+    State(state): State<ApiState>,
+    Json(request): Json<ConductorRefineRequest>,
+) -> impl IntoResponse {
+    if request.objective.trim().is_empty() {
+        return api_error(ErrorCode::InvalidRequest, "Objective cannot be empty");
+    }
+
+    let conductor = match state.app_state.ensure_conductor().await {
+        Ok(actor) => actor,
+        Err(e) => {
+            return api_error(
+                ErrorCode::SupervisorUnavailable,
+                format!("Conductor unavailable: {e}"),
+            );
+        }
+    };
+
+    match ractor::call!(conductor, |reply| ConductorMsg::RefineObjective {
+        request,
+        reply,
+    }) {
+        Ok(Ok(response)) => (StatusCode::OK, Json(response)).into_response(),
+        Ok(Err(actor_err)) => {
+            let (_, error) = map_actor_error(actor_err);
+            api_error(error.code, error.message)
+        }
+        Err(e) => api_error(
+            ErrorCode::ActorUnavailable,
+            format!("Conductor RPC failed: {e}"),
+        ),
+    }
+}
+
 /// GET /conductor/runs - List all runs sorted by most recently created
 pub async fn list_runs(State(state): State<ApiState>) -> impl IntoResponse {
     let conductor = match state.app_state.ensure_conductor().await {
         Ok(actor) => actor,
         Err(e) => {
-            return (
-                StatusCode::SERVICE_UNAVAILABLE,
-                Json(serde_json::json!({ "error": format!("Conductor unavailable: {e}") })),
-            )
-                .into_response();
+            return api_error(
+                ErrorCode::SupervisorUnavailable,
+                format!("Conductor unavailable: {e}"),
+            );
         }
     };
 
@@ -661,11 +738,10 @@ pub async fn list_runs(State(state): State<ApiState>) -> impl IntoResponse {
                 runs.into_iter().map(run_state_to_status_response).collect();
             (StatusCode::OK, Json(responses)).into_response()
         }
-        Err(e) => (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(serde_json::json!({ "error": format!("Conductor RPC failed: {e}") })),
-        )
-            .into_response(),
+        Err(e) => api_error(
+            ErrorCode::ActorUnavailable,
+            format!("Conductor RPC failed: {e}"),
+        ),
     }
 }
 
@@ -678,7 +754,7 @@ pub async fn get_run_status(
         let body = Json(RunStatusErrorResponse {
             run_id,
             error: conductor_error(
-                ConductorErrorCode::InvalidRequest,
+                ErrorCode::InvalidRequest,
                 "Run ID cannot be empty",
                 Some(shared_types::FailureKind::Validation),
             ),
@@ -692,7 +768,7 @@ pub async fn get_run_status(
             let body = Json(RunStatusErrorResponse {
                 run_id,
                 error: conductor_error(
-                    ConductorErrorCode::ActorNotAvailable,
+                    ErrorCode::SupervisorUnavailable,
                     format!("Failed to ensure conductor actor: {e}"),
                     Some(shared_types::FailureKind::Unknown),
                 ),
@@ -728,7 +804,7 @@ pub async fn get_run_status(
             let body = Json(RunStatusErrorResponse {
                 run_id,
                 error: conductor_error(
-                    ConductorErrorCode::RunNotFound,
+                    ErrorCode::RunNotFound,
                     "Run not found",
                     Some(shared_types::FailureKind::Unknown),
                 ),
@@ -739,7 +815,7 @@ pub async fn get_run_status(
             let body = Json(RunStatusErrorResponse {
                 run_id,
                 error: conductor_error(
-                    ConductorErrorCode::ActorNotAvailable,
+                    ErrorCode::ActorUnavailable,
                     format!("Conductor RPC failed: {e}"),
                     Some(shared_types::FailureKind::Unknown),
                 ),
@@ -758,7 +834,7 @@ pub async fn get_run_state(
         let body = Json(RunStatusErrorResponse {
             run_id,
             error: conductor_error(
-                ConductorErrorCode::InvalidRequest,
+                ErrorCode::InvalidRequest,
                 "Run ID cannot be empty",
                 Some(shared_types::FailureKind::Validation),
             ),
@@ -772,7 +848,7 @@ pub async fn get_run_state(
             let body = Json(RunStatusErrorResponse {
                 run_id,
                 error: conductor_error(
-                    ConductorErrorCode::ActorNotAvailable,
+                    ErrorCode::SupervisorUnavailable,
                     format!("Failed to ensure conductor actor: {e}"),
                     Some(shared_types::FailureKind::Unknown),
                 ),
@@ -805,7 +881,7 @@ pub async fn get_run_state(
             let body = Json(RunStatusErrorResponse {
                 run_id,
                 error: conductor_error(
-                    ConductorErrorCode::RunNotFound,
+                    ErrorCode::RunNotFound,
                     "Run not found",
                     Some(shared_types::FailureKind::Unknown),
                 ),
@@ -816,7 +892,7 @@ pub async fn get_run_state(
             let body = Json(RunStatusErrorResponse {
                 run_id,
                 error: conductor_error(
-                    ConductorErrorCode::ActorNotAvailable,
+                    ErrorCode::ActorUnavailable,
                     format!("Conductor RPC failed: {e}"),
                     Some(shared_types::FailureKind::Unknown),
                 ),
@@ -846,6 +922,10 @@ mod tests {
             status_code_for_run(ConductorRunStatus::WaitingForCalls),
             StatusCode::ACCEPTED
         );
+        assert_eq!(
+            status_code_for_run(ConductorRunStatus::WaitingForProvider),
+            StatusCode::ACCEPTED
+        );
         assert_eq!(
             status_code_for_run(ConductorRunStatus::Completing),
             StatusCode::ACCEPTED
@@ -879,6 +959,7 @@ mod tests {
             status: ConductorRunStatus::WaitingForCalls,
             objective: "test objective".to_string(),
             desktop_id: "desktop-1".to_string(),
+            revision: 0,
             output_mode: ConductorOutputMode::Auto,
             created_at: now,
             updated_at: now,
@@ -888,6 +969,7 @@ mod tests {
             artifacts: vec![],
             decision_log: vec![],
             document_path: "conductor/runs/run_123/draft.md".to_string(),
+            contract: None,
         };
 
         let response = run_state_to_execute_response(run);
@@ -914,6 +996,7 @@ mod tests {
             status: ConductorRunStatus::Running,
             objective: "hi".to_string(),
             desktop_id: "desktop-1".to_string(),
+            revision: 0,
             output_mode: ConductorOutputMode::Auto,
             created_at: now,
             updated_at: now,
@@ -923,6 +1006,7 @@ mod tests {
             artifacts: vec![],
             decision_log: vec![],
             document_path: "conductor/runs/run_456/draft.md".to_string(),
+            contract: None,
         };
 
         let response = run_state_to_execute_response(run);
@@ -947,6 +1031,7 @@ mod tests {
             status: ConductorRunStatus::Failed,
             objective: "test".to_string(),
             desktop_id: "desktop-1".to_string(),
+            revision: 0,
             output_mode: ConductorOutputMode::Auto,
             created_at: now,
             updated_at: now,
@@ -971,11 +1056,12 @@ mod tests {
             }],
             decision_log: vec![],
             document_path: "conductor/runs/run_failed/draft.md".to_string(),
+            contract: None,
         };
 
         let response = run_state_to_status_response(run);
         let error = response.error.expect("error details");
-        assert_eq!(error.code, "MODEL_GATEWAY_ERROR");
+        assert_eq!(error.code, ErrorCode::ProviderUnavailable);
         assert_eq!(error.message, "Missing API key: OPENAI_API_KEY");
         assert_eq!(
             error.failure_kind,
@@ -989,7 +1075,7 @@ mod tests {
             "workers unavailable".to_string(),
         ));
         assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
-        assert_eq!(error.code, "ACTOR_NOT_AVAILABLE");
+        assert_eq!(error.code, ErrorCode::ActorUnavailable);
         assert!(error.message.contains("workers unavailable"));
     }
 
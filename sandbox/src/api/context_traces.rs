@@ -0,0 +1,292 @@
+//! Context trace API
+//!
+//! Joins `llm.call.started` with `model.context.trace` by `trace_id` so the
+//! UI can show what went into each LLM call of a run or thread. A call whose
+//! trace is gone reports `pruned` instead of disappearing.
+
+use std::collections::HashMap;
+
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use ractor::ActorRef;
+use serde::Deserialize;
+use shared_types::{
+    ContextTraceCall, ContextTraceStatus, ContextTracesResponse, ErrorCode, EventScope,
+    ModelContextTracePayload, EVENT_MODEL_CONTEXT_TRACE, EVENT_TOPIC_LLM_CALL_STARTED,
+};
+
+use super::error::api_error;
+use super::ApiState;
+use crate::actors::event_store::EventStoreMsg;
+use crate::actors::model_config::ModelRegistry;
+
+#[derive(Debug, Deserialize)]
+pub struct ContextTracesQuery {
+    pub run_id: Option<String>,
+    pub thread_id: Option<String>,
+}
+
+/// Which calls a query selects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TraceSelector {
+    Run(String),
+    Thread(String),
+}
+
+impl TraceSelector {
+    fn from_query(query: &ContextTracesQuery) -> Result<Self, &'static str> {
+        match (&query.run_id, &query.thread_id) {
+            (Some(run_id), None) => Ok(Self::Run(run_id.clone())),
+            (None, Some(thread_id)) => Ok(Self::Thread(thread_id.clone())),
+            (Some(_), Some(_)) => Err("provide run_id or thread_id, not both"),
+            (None, None) => Err("provide run_id or thread_id"),
+        }
+    }
+
+    fn matches(&self, payload: &serde_json::Value) -> bool {
+        let field = |name: &str| payload.get(name).and_then(|value| value.as_str());
+        match self {
+            Self::Run(run_id) => field("run_id") == Some(run_id.as_str()),
+            Self::Thread(thread_id) => {
+                field("thread_id") == Some(thread_id.as_str())
+                    || EventScope::from_payload(payload).thread_id.as_deref()
+                        == Some(thread_id.as_str())
+            }
+        }
+    }
+}
+
+/// Context composition per LLM call, oldest first.
+///
+/// Query params (exactly one):
+/// - `run_id`: calls made for a conductor run
+/// - `thread_id`: calls made in a chat thread
+pub async fn get_context_traces(
+    State(state): State<ApiState>,
+    Query(query): Query<ContextTracesQuery>,
+) -> impl IntoResponse {
+    let selector = match TraceSelector::from_query(&query) {
+        Ok(selector) => selector,
+        Err(message) => return api_error(ErrorCode::InvalidRequest, message),
+    };
+    match build_context_traces(state.app_state.event_store(), &selector).await {
+        Ok(response) => (StatusCode::OK, Json(response)).into_response(),
+        Err(err) => api_error(ErrorCode::InternalError, err),
+    }
+}
+
+async fn build_context_traces(
+    event_store: ActorRef<EventStoreMsg>,
+    selector: &TraceSelector,
+) -> Result<ContextTracesResponse, String> {
+    let started = fetch_matching(&event_store, EVENT_TOPIC_LLM_CALL_STARTED, selector).await?;
+    let mut traces: HashMap<String, ModelContextTracePayload> =
+        fetch_matching(&event_store, EVENT_MODEL_CONTEXT_TRACE, selector)
+            .await?
+            .into_iter()
+            .filter_map(|event| {
+                serde_json::from_value::<ModelContextTracePayload>(event.payload).ok()
+            })
+            .map(|trace| (trace.trace_id.clone(), trace))
+            .collect();
+
+    let registry = ModelRegistry::new();
+    let calls = started
+        .into_iter()
+        .filter_map(|event| {
+            let payload = &event.payload;
+            let trace_id = payload.get("trace_id")?.as_str()?.to_string();
+            let text = |name: &str| {
+                payload
+                    .get(name)
+                    .and_then(|value| value.as_str())
+                    .unwrap_or_default()
+                    .to_string()
+            };
+            let started_at = payload
+                .get("started_at")
+                .and_then(|value| value.as_str())
+                .and_then(|raw| chrono::DateTime::parse_from_rfc3339(raw).ok())
+                .map(|at| at.with_timezone(&chrono::Utc))
+                .unwrap_or(event.timestamp);
+            let call = match traces.remove(&trace_id) {
+                Some(trace) => ContextTraceCall {
+                    trace_id,
+                    function_name: text("function_name"),
+                    model_used: trace.model_used,
+                    started_at,
+                    status: ContextTraceStatus::Recorded,
+                    items: trace.items,
+                    total_est_tokens: trace.total_est_tokens,
+                    context_window: trace.context_window,
+                    document_context: trace.document_context,
+                },
+                None => {
+                    let model_used = text("model_used");
+                    ContextTraceCall {
+                        trace_id,
+                        function_name: text("function_name"),
+                        context_window: registry.context_window(&model_used),
+                        model_used,
+                        started_at,
+                        status: ContextTraceStatus::Pruned,
+                        items: Vec::new(),
+                        total_est_tokens: 0,
+                        document_context: None,
+                    }
+                }
+            };
+            Some(call)
+        })
+        .collect();
+
+    Ok(ContextTracesResponse { calls })
+}
+
+/// Every event of `event_type` the selector matches, in seq order.
+async fn fetch_matching(
+    event_store: &ActorRef<EventStoreMsg>,
+    event_type: &str,
+    selector: &TraceSelector,
+) -> Result<Vec<shared_types::Event>, String> {
+    let mut since_seq = 0_i64;
+    let mut collected = Vec::new();
+
+    loop {
+        let page = match ractor::call!(event_store, |reply| EventStoreMsg::GetRecentEvents {
+            since_seq,
+            limit: 1000,
+            event_type_prefix: Some(event_type.to_string()),
+            actor_id: None,
+            user_id: None,
+            reply,
+        }) {
+            Ok(Ok(events)) => events,
+            Ok(Err(err)) => return Err(format!("EventStore error: {err}")),
+            Err(err) => return Err(format!("RPC error: {err}")),
+        };
+
+        let Some(last_seq) = page.last().map(|event| event.seq) else {
+            break;
+        };
+        collected
+            .extend(page.into_iter().filter(|event| {
+                event.event_type == event_type && selector.matches(&event.payload)
+            }));
+        if last_seq <= since_seq {
+            break;
+        }
+        since_seq = last_seq;
+    }
+
+    Ok(collected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actors::event_store::{AppendEvent, EventStoreActor, EventStoreArguments};
+    use ractor::Actor;
+    use serde_json::json;
+    use shared_types::{ContextTraceItem, ContextTraceItemKind, DocumentContextMode};
+
+    async fn append(store: &ActorRef<EventStoreMsg>, event_type: &str, payload: serde_json::Value) {
+        let event = AppendEvent {
+            event_type: event_type.to_string(),
+            payload,
+            actor_id: "harness:test".to_string(),
+            user_id: "system".to_string(),
+            idempotency_key: None,
+        };
+        ractor::call!(store, |reply| EventStoreMsg::Append { event, reply })
+            .unwrap()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_selector_requires_exactly_one_key() {
+        let query = |run_id: Option<&str>, thread_id: Option<&str>| ContextTracesQuery {
+            run_id: run_id.map(ToString::to_string),
+            thread_id: thread_id.map(ToString::to_string),
+        };
+        assert_eq!(
+            TraceSelector::from_query(&query(Some("run-1"), None)),
+            Ok(TraceSelector::Run("run-1".to_string()))
+        );
+        assert!(TraceSelector::from_query(&query(None, None)).is_err());
+        assert!(TraceSelector::from_query(&query(Some("run-1"), Some("t-1"))).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_calls_without_a_trace_report_pruned() {
+        let (store, _handle) = Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+            .await
+            .unwrap();
+
+        for trace_id in ["trace-old", "trace-new"] {
+            append(
+                &store,
+                EVENT_TOPIC_LLM_CALL_STARTED,
+                json!({
+                    "trace_id": trace_id,
+                    "function_name": "Decide",
+                    "model_used": "ClaudeBedrockSonnet46",
+                    "started_at": "2026-03-01T12:00:00Z",
+                    "run_id": "run-1",
+                }),
+            )
+            .await;
+        }
+        append(
+            &store,
+            EVENT_TOPIC_LLM_CALL_STARTED,
+            json!({ "trace_id": "trace-other", "run_id": "run-2" }),
+        )
+        .await;
+        let trace = ModelContextTracePayload {
+            trace_id: "trace-new".to_string(),
+            run_id: Some("run-1".to_string()),
+            thread_id: None,
+            model_used: "ClaudeBedrockSonnet46".to_string(),
+            items: vec![ContextTraceItem {
+                kind: ContextTraceItemKind::System,
+                role: None,
+                label: "System context".to_string(),
+                id: Some("system".to_string()),
+                est_tokens: 12,
+                trimmed: false,
+                trim_reason: None,
+            }],
+            total_est_tokens: 12,
+            trimmed: false,
+            context_window: Some(200_000),
+            document_context: Some(DocumentContextMode::Sectioned),
+        };
+        append(
+            &store,
+            EVENT_MODEL_CONTEXT_TRACE,
+            serde_json::to_value(&trace).unwrap(),
+        )
+        .await;
+
+        let response =
+            build_context_traces(store.clone(), &TraceSelector::Run("run-1".to_string()))
+                .await
+                .unwrap();
+        assert_eq!(response.calls.len(), 2);
+        assert_eq!(response.calls[0].trace_id, "trace-old");
+        assert_eq!(response.calls[0].status, ContextTraceStatus::Pruned);
+        assert!(response.calls[0].items.is_empty());
+        assert_eq!(response.calls[1].status, ContextTraceStatus::Recorded);
+        assert_eq!(response.calls[1].total_est_tokens, 12);
+        assert_eq!(response.calls[1].context_window, Some(200_000));
+        assert_eq!(
+            response.calls[1].document_context,
+            Some(DocumentContextMode::Sectioned)
+        );
+
+        store.stop(None);
+    }
+}
@@ -10,13 +10,30 @@ use axum::Json;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
-use crate::actors::desktop::{DesktopActorMsg, WindowBounds};
+use shared_types::{ErrorCode, WindowState};
+
+use crate::actors::conductor::registry::extract_run_id_from_document_path;
+use crate::actors::desktop::{DesktopActorMsg, DesktopError, WindowBounds};
+use crate::api::desktop_templates::{find_template, seed_template_files};
+use crate::api::error::api_error;
 use crate::api::websocket::{broadcast_event, WsMessage};
 use crate::api::ApiState;
+use crate::app_state::AppState;
+use crate::supervisor::WriterDocumentHolder;
 
 const MIN_WINDOW_WIDTH: i32 = 200;
 const MIN_WINDOW_HEIGHT: i32 = 160;
 
+/// Typed response for an error returned by the desktop actor.
+fn desktop_error(error: DesktopError) -> axum::response::Response {
+    let code = match &error {
+        DesktopError::WindowNotFound(_) | DesktopError::AppNotFound(_) => ErrorCode::NotFound,
+        DesktopError::InvalidOperation(_) => ErrorCode::InvalidRequest,
+        DesktopError::EventStore(_) | DesktopError::Serialization(_) => ErrorCode::InternalError,
+    };
+    api_error(code, error.to_string())
+}
+
 async fn get_desktop_actor(
     app_state: &std::sync::Arc<crate::app_state::AppState>,
     desktop_id: &str,
@@ -25,17 +42,34 @@ async fn get_desktop_actor(
         .get_or_create_desktop(desktop_id.to_string(), "system".to_string())
         .await
         .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "success": false,
-                    "error": format!("Failed to get desktop: {e}")
-                })),
+            api_error(
+                ErrorCode::ActorSpawnFailed,
+                format!("Failed to get desktop: {e}"),
             )
-                .into_response()
         })
 }
 
+/// Request to create a desktop, optionally from a template
+#[derive(Debug, Default, Deserialize)]
+pub struct CreateDesktopRequest {
+    #[serde(default)]
+    pub template_id: Option<String>,
+    /// Apps registered before the template's windows open.
+    #[serde(default)]
+    pub apps: Vec<shared_types::AppDefinition>,
+}
+
+/// Response after creating a desktop
+#[derive(Debug, Serialize)]
+pub struct CreateDesktopResponse {
+    pub success: bool,
+    pub desktop: shared_types::DesktopState,
+    /// Windows opened by this request; empty when the template was applied before.
+    pub opened_windows: Vec<WindowState>,
+    /// Seed files written by this request; existing files are left alone.
+    pub seeded_files: Vec<String>,
+}
+
 /// Request to open a window
 #[derive(Debug, Deserialize)]
 pub struct OpenWindowRequest {
@@ -49,7 +83,6 @@ pub struct OpenWindowRequest {
 pub struct OpenWindowResponse {
     pub success: bool,
     pub window: Option<shared_types::WindowState>,
-    pub error: Option<String>,
 }
 
 /// Request to move a window
@@ -66,6 +99,15 @@ pub struct ResizeWindowRequest {
     pub height: i32,
 }
 
+/// Request to set the area window title bars are kept inside
+#[derive(Debug, Deserialize)]
+pub struct DesktopBoundsRequest {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
 /// Request to maximize a window to a specific work area
 #[derive(Debug, Deserialize)]
 pub struct MaximizeWindowRequest {
@@ -75,6 +117,29 @@ pub struct MaximizeWindowRequest {
     pub height: i32,
 }
 
+/// Register a Writer window on a run document as holding its writer open.
+async fn retain_window_document(app_state: &AppState, window: &WindowState) {
+    if window.app_id != "writer" {
+        return;
+    }
+    let Some(path) = window.props.get("path").and_then(|path| path.as_str()) else {
+        return;
+    };
+    if extract_run_id_from_document_path(path).is_none() {
+        return;
+    }
+    if let Err(e) = app_state
+        .get_or_create_writer(
+            path.to_string(),
+            "system".to_string(),
+            Some(WriterDocumentHolder::Window(window.id.clone())),
+        )
+        .await
+    {
+        tracing::warn!(window_id = %window.id, error = %e, "Writer window could not retain its document");
+    }
+}
+
 /// Open a new window for an app
 pub async fn open_window(
     Path(desktop_id): Path<String>,
@@ -96,6 +161,7 @@ pub async fn open_window(
         reply,
     }) {
         Ok(Ok(window)) => {
+            retain_window_document(&app_state, &window).await;
             broadcast_event(
                 &state.ws_sessions,
                 &desktop_id,
@@ -110,29 +176,12 @@ pub async fn open_window(
                 Json(OpenWindowResponse {
                     success: true,
                     window: Some(window),
-                    error: None,
                 }),
             )
                 .into_response()
         }
-        Ok(Err(e)) => (
-            StatusCode::BAD_REQUEST,
-            Json(OpenWindowResponse {
-                success: false,
-                window: None,
-                error: Some(e.to_string()),
-            }),
-        )
-            .into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(OpenWindowResponse {
-                success: false,
-                window: None,
-                error: Some(format!("Actor error: {e}")),
-            }),
-        )
-            .into_response(),
+        Ok(Err(e)) => desktop_error(e),
+        Err(e) => api_error(ErrorCode::ActorUnavailable, format!("Actor error: {e}")),
     }
 }
 
@@ -158,14 +207,10 @@ pub async fn get_windows(
             })),
         )
             .into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({
-                "success": false,
-                "error": format!("Failed to get windows: {}", e)
-            })),
-        )
-            .into_response(),
+        Err(e) => api_error(
+            ErrorCode::ActorUnavailable,
+            format!("Failed to get windows: {}", e),
+        ),
     }
 }
 
@@ -187,6 +232,9 @@ pub async fn close_window(
         reply,
     }) {
         Ok(Ok(())) => {
+            let _ = app_state
+                .release_writer_holder(WriterDocumentHolder::Window(window_id.clone()))
+                .await;
             broadcast_event(
                 &state.ws_sessions,
                 &desktop_id,
@@ -205,22 +253,8 @@ pub async fn close_window(
             )
                 .into_response()
         }
-        Ok(Err(e)) => (
-            StatusCode::BAD_REQUEST,
-            Json(json!({
-                "success": false,
-                "error": e.to_string()
-            })),
-        )
-            .into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({
-                "success": false,
-                "error": format!("Actor error: {}", e)
-            })),
-        )
-            .into_response(),
+        Ok(Err(e)) => desktop_error(e),
+        Err(e) => api_error(ErrorCode::ActorUnavailable, format!("Actor error: {e}")),
     }
 }
 
@@ -244,14 +278,14 @@ pub async fn move_window(
         y: req.y,
         reply,
     }) {
-        Ok(Ok(())) => {
+        Ok(Ok(window)) => {
             broadcast_event(
                 &state.ws_sessions,
                 &desktop_id,
                 WsMessage::WindowMoved {
                     window_id: window_id.clone(),
-                    x: req.x,
-                    y: req.y,
+                    x: window.x,
+                    y: window.y,
                 },
             )
             .await;
@@ -260,27 +294,14 @@ pub async fn move_window(
                 StatusCode::OK,
                 Json(json!({
                     "success": true,
+                    "window": window,
                     "message": "Window moved"
                 })),
             )
                 .into_response()
         }
-        Ok(Err(e)) => (
-            StatusCode::BAD_REQUEST,
-            Json(json!({
-                "success": false,
-                "error": e.to_string()
-            })),
-        )
-            .into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({
-                "success": false,
-                "error": format!("Actor error: {}", e)
-            })),
-        )
-            .into_response(),
+        Ok(Err(e)) => desktop_error(e),
+        Err(e) => api_error(ErrorCode::ActorUnavailable, format!("Actor error: {e}")),
     }
 }
 
@@ -291,20 +312,13 @@ pub async fn resize_window(
     Json(req): Json<ResizeWindowRequest>,
 ) -> impl IntoResponse {
     if req.width < MIN_WINDOW_WIDTH || req.height < MIN_WINDOW_HEIGHT {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(json!({
-                "success": false,
-                "error": format!(
-                    "Invalid size {}x{} (minimum is {}x{})",
-                    req.width,
-                    req.height,
-                    MIN_WINDOW_WIDTH,
-                    MIN_WINDOW_HEIGHT
-                )
-            })),
-        )
-            .into_response();
+        return api_error(
+            ErrorCode::InvalidRequest,
+            format!(
+                "Invalid size {}x{} (minimum is {}x{})",
+                req.width, req.height, MIN_WINDOW_WIDTH, MIN_WINDOW_HEIGHT
+            ),
+        );
     }
 
     let app_state = state.app_state.clone();
@@ -321,14 +335,25 @@ pub async fn resize_window(
         height: req.height,
         reply,
     }) {
-        Ok(Ok(())) => {
+        Ok(Ok(window)) => {
             broadcast_event(
                 &state.ws_sessions,
                 &desktop_id,
                 WsMessage::WindowResized {
                     window_id: window_id.clone(),
-                    width: req.width,
-                    height: req.height,
+                    width: window.width,
+                    height: window.height,
+                },
+            )
+            .await;
+            // Resizing can pull the window back inside the desktop bounds.
+            broadcast_event(
+                &state.ws_sessions,
+                &desktop_id,
+                WsMessage::WindowMoved {
+                    window_id: window_id.clone(),
+                    x: window.x,
+                    y: window.y,
                 },
             )
             .await;
@@ -337,27 +362,14 @@ pub async fn resize_window(
                 StatusCode::OK,
                 Json(json!({
                     "success": true,
+                    "window": window,
                     "message": "Window resized"
                 })),
             )
                 .into_response()
         }
-        Ok(Err(e)) => (
-            StatusCode::BAD_REQUEST,
-            Json(json!({
-                "success": false,
-                "error": e.to_string()
-            })),
-        )
-            .into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({
-                "success": false,
-                "error": format!("Actor error: {}", e)
-            })),
-        )
-            .into_response(),
+        Ok(Err(e)) => desktop_error(e),
+        Err(e) => api_error(ErrorCode::ActorUnavailable, format!("Actor error: {e}")),
     }
 }
 
@@ -408,22 +420,8 @@ pub async fn focus_window(
             )
                 .into_response()
         }
-        Ok(Err(e)) => (
-            StatusCode::BAD_REQUEST,
-            Json(json!({
-                "success": false,
-                "error": e.to_string()
-            })),
-        )
-            .into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({
-                "success": false,
-                "error": format!("Actor error: {}", e)
-            })),
-        )
-            .into_response(),
+        Ok(Err(e)) => desktop_error(e),
+        Err(e) => api_error(ErrorCode::ActorUnavailable, format!("Actor error: {e}")),
     }
 }
 
@@ -462,22 +460,8 @@ pub async fn minimize_window(
             )
                 .into_response()
         }
-        Ok(Err(e)) => (
-            StatusCode::BAD_REQUEST,
-            Json(json!({
-                "success": false,
-                "error": e.to_string()
-            })),
-        )
-            .into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({
-                "success": false,
-                "error": format!("Actor error: {}", e)
-            })),
-        )
-            .into_response(),
+        Ok(Err(e)) => desktop_error(e),
+        Err(e) => api_error(ErrorCode::ActorUnavailable, format!("Actor error: {e}")),
     }
 }
 
@@ -497,17 +481,13 @@ pub async fn maximize_window(
 
     if let Some(bounds) = work_area.as_ref() {
         if bounds.width < MIN_WINDOW_WIDTH || bounds.height < MIN_WINDOW_HEIGHT {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(json!({
-                    "success": false,
-                    "error": format!(
-                        "Maximize work area below minimum: {}x{} (min {}x{})",
-                        bounds.width, bounds.height, MIN_WINDOW_WIDTH, MIN_WINDOW_HEIGHT
-                    )
-                })),
-            )
-                .into_response();
+            return api_error(
+                ErrorCode::InvalidRequest,
+                format!(
+                    "Maximize work area below minimum: {}x{} (min {}x{})",
+                    bounds.width, bounds.height, MIN_WINDOW_WIDTH, MIN_WINDOW_HEIGHT
+                ),
+            );
         }
     }
 
@@ -545,22 +525,60 @@ pub async fn maximize_window(
             )
                 .into_response()
         }
-        Ok(Err(e)) => (
-            StatusCode::BAD_REQUEST,
-            Json(json!({
-                "success": false,
-                "error": e.to_string()
-            })),
-        )
-            .into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({
-                "success": false,
-                "error": format!("Actor error: {}", e)
-            })),
-        )
-            .into_response(),
+        Ok(Err(e)) => desktop_error(e),
+        Err(e) => api_error(ErrorCode::ActorUnavailable, format!("Actor error: {e}")),
+    }
+}
+
+/// Set the desktop bounds; windows left outside them are moved back in
+pub async fn set_desktop_bounds(
+    Path(desktop_id): Path<String>,
+    axum::extract::State(state): axum::extract::State<ApiState>,
+    Json(req): Json<DesktopBoundsRequest>,
+) -> impl IntoResponse {
+    let app_state = state.app_state.clone();
+
+    let desktop = match get_desktop_actor(&app_state, &desktop_id).await {
+        Ok(actor) => actor,
+        Err(response) => return response,
+    };
+
+    let bounds = WindowBounds {
+        x: req.x,
+        y: req.y,
+        width: req.width,
+        height: req.height,
+    };
+    match ractor::call!(desktop, |reply| DesktopActorMsg::SetDesktopBounds {
+        bounds,
+        reply
+    }) {
+        Ok(Ok(moved)) => {
+            for window in &moved {
+                broadcast_event(
+                    &state.ws_sessions,
+                    &desktop_id,
+                    WsMessage::WindowMoved {
+                        window_id: window.id.clone(),
+                        x: window.x,
+                        y: window.y,
+                    },
+                )
+                .await;
+            }
+
+            (
+                StatusCode::OK,
+                Json(json!({
+                    "success": true,
+                    "moved_windows": moved,
+                    "message": "Desktop bounds updated"
+                })),
+            )
+                .into_response()
+        }
+        Ok(Err(e)) => desktop_error(e),
+        Err(e) => api_error(ErrorCode::ActorUnavailable, format!("Actor error: {e}")),
     }
 }
 
@@ -607,22 +625,8 @@ pub async fn restore_window(
             )
                 .into_response()
         }
-        Ok(Err(e)) => (
-            StatusCode::BAD_REQUEST,
-            Json(json!({
-                "success": false,
-                "error": e.to_string()
-            })),
-        )
-            .into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({
-                "success": false,
-                "error": format!("Actor error: {}", e)
-            })),
-        )
-            .into_response(),
+        Ok(Err(e)) => desktop_error(e),
+        Err(e) => api_error(ErrorCode::ActorUnavailable, format!("Actor error: {e}")),
     }
 }
 
@@ -648,14 +652,97 @@ pub async fn get_desktop_state(
             })),
         )
             .into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({
-                "success": false,
-                "error": format!("Failed to get desktop state: {}", e)
-            })),
+        Err(e) => api_error(
+            ErrorCode::ActorUnavailable,
+            format!("Failed to get desktop state: {}", e),
+        ),
+    }
+}
+
+/// Create a desktop, registering the given apps and applying a template.
+/// Safe to retry: seed files are never overwritten and a template opens its
+/// windows at most once per desktop.
+pub async fn create_desktop(
+    Path(desktop_id): Path<String>,
+    axum::extract::State(state): axum::extract::State<ApiState>,
+    Json(req): Json<CreateDesktopRequest>,
+) -> impl IntoResponse {
+    let app_state = state.app_state.clone();
+
+    let template = match &req.template_id {
+        Some(template_id) => match find_template(&app_state.event_store(), template_id).await {
+            Ok(template) => Some(template),
+            Err(response) => return response,
+        },
+        None => None,
+    };
+
+    let desktop = match get_desktop_actor(&app_state, &desktop_id).await {
+        Ok(actor) => actor,
+        Err(response) => return response,
+    };
+
+    for app in req.apps {
+        match ractor::call!(desktop, |reply| DesktopActorMsg::RegisterApp {
+            app: app.clone(),
+            reply,
+        }) {
+            Ok(Ok(())) => {
+                broadcast_event(
+                    &state.ws_sessions,
+                    &desktop_id,
+                    WsMessage::AppRegistered { app },
+                )
+                .await;
+            }
+            Ok(Err(e)) => return desktop_error(e),
+            Err(e) => return api_error(ErrorCode::ActorUnavailable, format!("Actor error: {e}")),
+        }
+    }
+
+    let mut seeded_files = Vec::new();
+    let mut opened_windows = Vec::new();
+    if let Some(template) = template {
+        seeded_files = match seed_template_files(&template.seed_files).await {
+            Ok(written) => written,
+            Err(response) => return response,
+        };
+        opened_windows = match ractor::call!(desktop, |reply| DesktopActorMsg::ApplyTemplate {
+            template,
+            reply,
+        }) {
+            Ok(Ok(windows)) => windows,
+            Ok(Err(e)) => return desktop_error(e),
+            Err(e) => return api_error(ErrorCode::ActorUnavailable, format!("Actor error: {e}")),
+        };
+        for window in &opened_windows {
+            retain_window_document(&app_state, window).await;
+            broadcast_event(
+                &state.ws_sessions,
+                &desktop_id,
+                WsMessage::WindowOpened {
+                    window: window.clone(),
+                },
+            )
+            .await;
+        }
+    }
+
+    match ractor::call!(desktop, |reply| DesktopActorMsg::GetDesktopState { reply }) {
+        Ok(desktop_state) => (
+            StatusCode::OK,
+            Json(CreateDesktopResponse {
+                success: true,
+                desktop: desktop_state,
+                opened_windows,
+                seeded_files,
+            }),
         )
             .into_response(),
+        Err(e) => api_error(
+            ErrorCode::ActorUnavailable,
+            format!("Failed to get desktop state: {e}"),
+        ),
     }
 }
 
@@ -694,22 +781,8 @@ pub async fn register_app(
             )
                 .into_response()
         }
-        Ok(Err(e)) => (
-            StatusCode::BAD_REQUEST,
-            Json(json!({
-                "success": false,
-                "error": e.to_string()
-            })),
-        )
-            .into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({
-                "success": false,
-                "error": format!("Actor error: {}", e)
-            })),
-        )
-            .into_response(),
+        Ok(Err(e)) => desktop_error(e),
+        Err(e) => api_error(ErrorCode::ActorUnavailable, format!("Actor error: {e}")),
     }
 }
 
@@ -735,13 +808,9 @@ pub async fn get_apps(
             })),
         )
             .into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({
-                "success": false,
-                "error": format!("Failed to get apps: {}", e)
-            })),
-        )
-            .into_response(),
+        Err(e) => api_error(
+            ErrorCode::ActorUnavailable,
+            format!("Failed to get apps: {}", e),
+        ),
     }
 }
@@ -0,0 +1,242 @@
+//! Desktop template endpoints.
+//!
+//! A template names the windows a new desktop opens and the files it seeds
+//! into the workspace. The research and coding templates are built in; saved
+//! templates are EventStore events and the latest save of an id wins.
+
+use std::io::ErrorKind;
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Serialize;
+use serde_json::json;
+use shared_types::{DesktopTemplate, DesktopTemplateFile, DesktopTemplateWindow, ErrorCode};
+use tokio::io::AsyncWriteExt;
+
+use crate::actors::event_store::{get_events_for_actor, AppendEvent, EventStoreMsg};
+use crate::api::error::api_error;
+use crate::api::ApiState;
+use crate::paths::{normalize_document_path, sandbox_root};
+
+/// EventStore actor id saved templates are recorded under.
+const TEMPLATES_ACTOR_ID: &str = "desktop-templates";
+
+#[derive(Debug, Serialize)]
+pub struct DesktopTemplatesResponse {
+    pub success: bool,
+    pub templates: Vec<DesktopTemplate>,
+}
+
+fn window(app_id: &str, title: &str, props: serde_json::Value) -> DesktopTemplateWindow {
+    DesktopTemplateWindow {
+        app_id: app_id.to_string(),
+        title: title.to_string(),
+        props,
+    }
+}
+
+/// Templates every sandbox ships with. Saved templates cannot reuse their ids.
+pub fn builtin_templates() -> Vec<DesktopTemplate> {
+    vec![
+        DesktopTemplate {
+            id: "research".to_string(),
+            name: "Research".to_string(),
+            description: "Research app, a notes document and the file browser.".to_string(),
+            windows: vec![
+                window("research", "Research", json!({})),
+                window("writer", "Notes", json!({ "path": "research/notes.md" })),
+                window("files", "Files", json!({})),
+            ],
+            seed_files: vec![DesktopTemplateFile {
+                path: "research/notes.md".to_string(),
+                content: "# Research notes\n\n## Questions\n\n## Sources\n\n## Findings\n"
+                    .to_string(),
+            }],
+        },
+        DesktopTemplate {
+            id: "coding".to_string(),
+            name: "Coding".to_string(),
+            description: "Terminal, a project README and the file browser.".to_string(),
+            windows: vec![
+                window("terminal", "Terminal", json!({})),
+                window("writer", "README", json!({ "path": "project/README.md" })),
+                window("files", "Files", json!({})),
+            ],
+            seed_files: vec![DesktopTemplateFile {
+                path: "project/README.md".to_string(),
+                content: "# Project\n\n## Goal\n\n## Notes\n".to_string(),
+            }],
+        },
+    ]
+}
+
+/// Built-in templates followed by saved ones, or `None` if the EventStore
+/// could not be read.
+async fn load_templates(
+    event_store: &ractor::ActorRef<EventStoreMsg>,
+) -> Option<Vec<DesktopTemplate>> {
+    let events = match get_events_for_actor(event_store, TEMPLATES_ACTOR_ID, 0).await {
+        Ok(Ok(events)) => events,
+        _ => return None,
+    };
+    let mut templates = builtin_templates();
+    let builtin_count = templates.len();
+    for event in events {
+        if event.event_type != shared_types::EVENT_DESKTOP_TEMPLATE_SAVED {
+            continue;
+        }
+        let Ok(template) = serde_json::from_value::<DesktopTemplate>(event.payload) else {
+            continue;
+        };
+        match templates[builtin_count..]
+            .iter_mut()
+            .find(|saved| saved.id == template.id)
+        {
+            Some(saved) => *saved = template,
+            None => templates.push(template),
+        }
+    }
+    Some(templates)
+}
+
+/// Look up a built-in or saved template.
+pub(crate) async fn find_template(
+    event_store: &ractor::ActorRef<EventStoreMsg>,
+    template_id: &str,
+) -> Result<DesktopTemplate, Response> {
+    let templates = load_templates(event_store)
+        .await
+        .ok_or_else(|| api_error(ErrorCode::InternalError, "Failed to load desktop templates"))?;
+    templates
+        .into_iter()
+        .find(|template| template.id == template_id)
+        .ok_or_else(|| {
+            api_error(
+                ErrorCode::NotFound,
+                format!("Desktop template not found: {template_id}"),
+            )
+        })
+}
+
+fn validate_template(template: &DesktopTemplate) -> Result<(), String> {
+    if template.id.trim().is_empty() || template.name.trim().is_empty() {
+        return Err("template id and name are required".to_string());
+    }
+    if builtin_templates().iter().any(|b| b.id == template.id) {
+        return Err(format!("'{}' is a built-in template", template.id));
+    }
+    if let Some(window) = template.windows.iter().find(|w| w.app_id.is_empty()) {
+        return Err(format!("window '{}' has no app_id", window.title));
+    }
+    for file in &template.seed_files {
+        normalize_document_path(&file.path).map_err(|e| format!("invalid seed file path: {e}"))?;
+    }
+    Ok(())
+}
+
+/// Write a template's seed files under the sandbox root, leaving any file
+/// that already exists untouched. Returns the paths written.
+pub(crate) async fn seed_template_files(
+    files: &[DesktopTemplateFile],
+) -> Result<Vec<String>, Response> {
+    let root = sandbox_root();
+    let mut written = Vec::new();
+    for file in files {
+        let path = normalize_document_path(&file.path).map_err(|e| {
+            api_error(
+                ErrorCode::PathTraversal,
+                format!("Invalid seed file path: {e}"),
+            )
+        })?;
+        let full_path = root.join(&path);
+        if let Some(parent) = full_path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(|e| {
+                api_error(
+                    ErrorCode::InternalError,
+                    format!("Failed to create {}: {e}", parent.display()),
+                )
+            })?;
+        }
+        let mut handle = match tokio::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&full_path)
+            .await
+        {
+            Ok(handle) => handle,
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(e) => {
+                return Err(api_error(
+                    ErrorCode::InternalError,
+                    format!("Failed to create {path}: {e}"),
+                ))
+            }
+        };
+        handle
+            .write_all(file.content.as_bytes())
+            .await
+            .map_err(|e| {
+                api_error(
+                    ErrorCode::InternalError,
+                    format!("Failed to write {path}: {e}"),
+                )
+            })?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// GET /desktop-templates — built-in and saved templates
+pub async fn list_templates(
+    axum::extract::State(state): axum::extract::State<ApiState>,
+) -> impl IntoResponse {
+    match load_templates(&state.app_state.event_store()).await {
+        Some(templates) => (
+            StatusCode::OK,
+            Json(DesktopTemplatesResponse {
+                success: true,
+                templates,
+            }),
+        )
+            .into_response(),
+        None => api_error(ErrorCode::InternalError, "Failed to load desktop templates"),
+    }
+}
+
+/// POST /desktop-templates — save a template, replacing any with its id
+pub async fn save_template(
+    axum::extract::State(state): axum::extract::State<ApiState>,
+    Json(template): Json<DesktopTemplate>,
+) -> impl IntoResponse {
+    if let Err(message) = validate_template(&template) {
+        return api_error(ErrorCode::InvalidRequest, message);
+    }
+    let payload = match serde_json::to_value(&template) {
+        Ok(payload) => payload,
+        Err(e) => return api_error(ErrorCode::InternalError, e.to_string()),
+    };
+
+    let event_store = state.app_state.event_store();
+    match ractor::call!(event_store, |reply| EventStoreMsg::Append {
+        event: AppendEvent {
+            event_type: shared_types::EVENT_DESKTOP_TEMPLATE_SAVED.to_string(),
+            payload,
+            actor_id: TEMPLATES_ACTOR_ID.to_string(),
+            user_id: "system".to_string(),
+            idempotency_key: None,
+        },
+        reply,
+    }) {
+        Ok(Ok(_)) => (
+            StatusCode::OK,
+            Json(json!({
+                "success": true,
+                "template": template
+            })),
+        )
+            .into_response(),
+        Ok(Err(e)) => api_error(ErrorCode::InternalError, e.to_string()),
+        Err(e) => api_error(ErrorCode::ActorUnavailable, format!("Actor error: {e}")),
+    }
+}
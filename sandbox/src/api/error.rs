@@ -0,0 +1,133 @@
+//! Typed API error responses
+//!
+//! Handlers return `{ "error": ApiError, "version": BuildVersion }` with the
+//! HTTP status implied by the error code, so clients can branch on
+//! `error.code` instead of parsing text, and bug reports name the build.
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Serialize;
+use shared_types::{ApiError, BuildVersion, ErrorCode};
+
+#[derive(Debug, Serialize)]
+struct ApiErrorBody {
+    error: ApiError,
+    version: BuildVersion,
+}
+
+/// HTTP status for an error code.
+pub fn status_for(code: ErrorCode) -> StatusCode {
+    match code {
+        ErrorCode::InvalidRequest
+        | ErrorCode::NotAFile
+        | ErrorCode::NotADirectory
+        | ErrorCode::IsDirectory
+        | ErrorCode::InvalidContent
+        | ErrorCode::InvalidRevision => StatusCode::BAD_REQUEST,
+        ErrorCode::PathTraversal | ErrorCode::PermissionDenied | ErrorCode::PolicyBlocked => {
+            StatusCode::FORBIDDEN
+        }
+        ErrorCode::NotFound | ErrorCode::RunNotFound => StatusCode::NOT_FOUND,
+        ErrorCode::AlreadyExists
+        | ErrorCode::Conflict
+        | ErrorCode::DocumentConflict
+        | ErrorCode::RunBlocked => StatusCode::CONFLICT,
+        ErrorCode::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+        ErrorCode::UnsupportedMediaType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        ErrorCode::UnsupportedSchema => StatusCode::UNPROCESSABLE_ENTITY,
+        ErrorCode::QuotaExceeded => StatusCode::INSUFFICIENT_STORAGE,
+        ErrorCode::SupervisorUnavailable
+        | ErrorCode::ActorSpawnFailed
+        | ErrorCode::ActorUnavailable
+        | ErrorCode::ProviderUnavailable
+        | ErrorCode::ServiceUnavailable => StatusCode::SERVICE_UNAVAILABLE,
+        ErrorCode::RunFailed | ErrorCode::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Response for a fully built [`ApiError`].
+pub fn error_response(error: ApiError) -> Response {
+    let status = status_for(error.code);
+    let body = ApiErrorBody {
+        error,
+        version: BuildVersion::current(),
+    };
+    (status, Json(body)).into_response()
+}
+
+/// Shorthand for an error with only a code and message.
+pub fn api_error(code: ErrorCode, message: impl Into<String>) -> Response {
+    error_response(ApiError::new(code, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_body_util::BodyExt;
+
+    #[tokio::test]
+    async fn body_nests_typed_error_under_error_key() {
+        let response = error_response(
+            ApiError::new(ErrorCode::PayloadTooLarge, "too big")
+                .with_details(serde_json::json!({ "limit_bytes": 10 })),
+        );
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "error": {
+                    "code": "PAYLOAD_TOO_LARGE",
+                    "message": "too big",
+                    "details": { "limit_bytes": 10 }
+                },
+                "version": BuildVersion::current(),
+            })
+        );
+    }
+
+    #[test]
+    fn every_code_round_trips_and_has_a_status() {
+        for code in ErrorCode::ALL {
+            let wire = serde_json::to_value(code).unwrap();
+            assert_eq!(wire, serde_json::json!(code.as_str()));
+            assert_eq!(ErrorCode::parse(code.as_str()), Some(code));
+            assert!(status_for(code).is_client_error() || status_for(code).is_server_error());
+        }
+    }
+
+    fn rust_sources(dir: &std::path::Path, out: &mut Vec<std::path::PathBuf>) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                rust_sources(&path, out);
+            } else if path.extension().is_some_and(|ext| ext == "rs") {
+                out.push(path);
+            }
+        }
+    }
+
+    #[test]
+    fn handlers_only_build_errors_from_the_catalog() {
+        let api_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src/api");
+        let mut sources = Vec::new();
+        rust_sources(&api_dir, &mut sources);
+        assert!(!sources.is_empty());
+        for path in sources {
+            if path.ends_with("error.rs") {
+                continue;
+            }
+            let source = std::fs::read_to_string(&path).unwrap();
+            for (index, line) in source.lines().enumerate() {
+                assert!(
+                    !line.contains("\"error\": ") && !line.contains("\"success\": false"),
+                    "{}:{} builds an error body without an ErrorCode; use api_error",
+                    path.display(),
+                    index + 1
+                );
+            }
+        }
+    }
+}
@@ -11,77 +11,15 @@ use serde::{Deserialize, Serialize};
 use std::path::{Component, Path, PathBuf};
 use tokio::fs;
 
+use shared_types::ErrorCode;
+
+use crate::api::error::api_error;
 use crate::api::ApiState;
 use crate::paths::sandbox_root;
 
 /// Maximum file read size (1MB)
 const MAX_READ_SIZE: usize = 1_048_576;
 
-/// File error codes for machine-readable error responses
-#[derive(Debug, Clone)]
-pub enum FileErrorCode {
-    PathTraversal,
-    NotFound,
-    NotAFile,
-    NotADirectory,
-    AlreadyExists,
-    PermissionDenied,
-    InvalidContent,
-    InternalError,
-}
-
-impl FileErrorCode {
-    fn as_str(&self) -> &'static str {
-        match self {
-            FileErrorCode::PathTraversal => "PATH_TRAVERSAL",
-            FileErrorCode::NotFound => "NOT_FOUND",
-            FileErrorCode::NotAFile => "NOT_A_FILE",
-            FileErrorCode::NotADirectory => "NOT_A_DIRECTORY",
-            FileErrorCode::AlreadyExists => "ALREADY_EXISTS",
-            FileErrorCode::PermissionDenied => "PERMISSION_DENIED",
-            FileErrorCode::InvalidContent => "INVALID_CONTENT",
-            FileErrorCode::InternalError => "INTERNAL_ERROR",
-        }
-    }
-
-    fn status_code(&self) -> StatusCode {
-        match self {
-            FileErrorCode::PathTraversal => StatusCode::FORBIDDEN,
-            FileErrorCode::NotFound => StatusCode::NOT_FOUND,
-            FileErrorCode::NotAFile => StatusCode::BAD_REQUEST,
-            FileErrorCode::NotADirectory => StatusCode::BAD_REQUEST,
-            FileErrorCode::AlreadyExists => StatusCode::CONFLICT,
-            FileErrorCode::PermissionDenied => StatusCode::FORBIDDEN,
-            FileErrorCode::InvalidContent => StatusCode::BAD_REQUEST,
-            FileErrorCode::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
-        }
-    }
-}
-
-/// Error response structure
-#[derive(Debug, Serialize)]
-pub struct FileErrorResponse {
-    error: FileErrorDetail,
-}
-
-#[derive(Debug, Serialize)]
-pub struct FileErrorDetail {
-    code: String,
-    message: String,
-}
-
-/// Create an error response
-fn file_error(code: FileErrorCode, message: impl Into<String>) -> impl IntoResponse {
-    let status = code.status_code();
-    let body = Json(FileErrorResponse {
-        error: FileErrorDetail {
-            code: code.as_str().to_string(),
-            message: message.into(),
-        },
-    });
-    (status, body)
-}
-
 /// Validates and normalizes a path relative to sandbox
 ///
 /// Returns the full path within the sandbox if valid, or an error response if invalid.
@@ -89,17 +27,15 @@ fn validate_path(sandbox: &Path, user_path: &str) -> Result<PathBuf, axum::respo
     // Reject null bytes
     if user_path.contains('\0') {
         return Err(
-            file_error(FileErrorCode::PathTraversal, "Path contains null bytes").into_response(),
+            api_error(ErrorCode::PathTraversal, "Path contains null bytes").into_response(),
         );
     }
 
     // Reject absolute paths
     if user_path.starts_with('/') {
-        return Err(file_error(
-            FileErrorCode::PathTraversal,
-            "Absolute paths are not allowed",
-        )
-        .into_response());
+        return Err(
+            api_error(ErrorCode::PathTraversal, "Absolute paths are not allowed").into_response(),
+        );
     }
 
     // Normalize path by processing components manually
@@ -111,8 +47,8 @@ fn validate_path(sandbox: &Path, user_path: &str) -> Result<PathBuf, axum::respo
             Component::ParentDir => {
                 // Pop the last component if we can, otherwise this escapes the sandbox
                 if !normalized.pop() {
-                    return Err(file_error(
-                        FileErrorCode::PathTraversal,
+                    return Err(api_error(
+                        ErrorCode::PathTraversal,
                         "Path escapes sandbox directory",
                     )
                     .into_response());
@@ -121,8 +57,8 @@ fn validate_path(sandbox: &Path, user_path: &str) -> Result<PathBuf, axum::respo
             Component::RootDir | Component::Prefix(_) => {
                 // These shouldn't happen due to the absolute path check above,
                 // but reject them just in case
-                return Err(file_error(
-                    FileErrorCode::PathTraversal,
+                return Err(api_error(
+                    ErrorCode::PathTraversal,
                     "Path contains invalid components",
                 )
                 .into_response());
@@ -139,8 +75,8 @@ fn validate_path(sandbox: &Path, user_path: &str) -> Result<PathBuf, axum::respo
         match full_path.canonicalize() {
             Ok(p) => p,
             Err(e) => {
-                return Err(file_error(
-                    FileErrorCode::InternalError,
+                return Err(api_error(
+                    ErrorCode::InternalError,
                     format!("Failed to canonicalize path: {e}"),
                 )
                 .into_response());
@@ -172,8 +108,8 @@ fn validate_path(sandbox: &Path, user_path: &str) -> Result<PathBuf, axum::respo
     let sandbox_canonical = match sandbox.canonicalize() {
         Ok(p) => p,
         Err(e) => {
-            return Err(file_error(
-                FileErrorCode::InternalError,
+            return Err(api_error(
+                ErrorCode::InternalError,
                 format!("Failed to canonicalize sandbox root: {e}"),
             )
             .into_response());
@@ -181,11 +117,9 @@ fn validate_path(sandbox: &Path, user_path: &str) -> Result<PathBuf, axum::respo
     };
 
     if !path_to_check.starts_with(&sandbox_canonical) {
-        return Err(file_error(
-            FileErrorCode::PathTraversal,
-            "Path escapes sandbox directory",
-        )
-        .into_response());
+        return Err(
+            api_error(ErrorCode::PathTraversal, "Path escapes sandbox directory").into_response(),
+        );
     }
 
     Ok(full_path)
@@ -234,23 +168,23 @@ pub async fn list_directory(
     match fs::metadata(&dir_path).await {
         Ok(metadata) => {
             if !metadata.is_dir() {
-                return file_error(
-                    FileErrorCode::NotADirectory,
+                return api_error(
+                    ErrorCode::NotADirectory,
                     format!("Path is not a directory: {user_path}"),
                 )
                 .into_response();
             }
         }
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-            return file_error(
-                FileErrorCode::NotFound,
+            return api_error(
+                ErrorCode::NotFound,
                 format!("Directory not found: {user_path}"),
             )
             .into_response();
         }
         Err(e) => {
-            return file_error(
-                FileErrorCode::InternalError,
+            return api_error(
+                ErrorCode::InternalError,
                 format!("Failed to read directory metadata: {e}"),
             )
             .into_response();
@@ -294,8 +228,8 @@ async fn collect_entries(
     let mut read_dir = match fs::read_dir(dir_path).await {
         Ok(rd) => rd,
         Err(e) => {
-            return Err(file_error(
-                FileErrorCode::InternalError,
+            return Err(api_error(
+                ErrorCode::InternalError,
                 format!("Failed to read directory: {e}"),
             )
             .into_response());
@@ -401,15 +335,15 @@ pub async fn get_metadata(
     let metadata = match fs::metadata(&file_path).await {
         Ok(m) => m,
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-            return file_error(
-                FileErrorCode::NotFound,
+            return api_error(
+                ErrorCode::NotFound,
                 format!("File or directory not found: {user_path}"),
             )
             .into_response();
         }
         Err(e) => {
-            return file_error(
-                FileErrorCode::InternalError,
+            return api_error(
+                ErrorCode::InternalError,
                 format!("Failed to read metadata: {e}"),
             )
             .into_response();
@@ -496,23 +430,20 @@ pub async fn get_content(
     match fs::metadata(&file_path).await {
         Ok(m) => {
             if m.is_dir() {
-                return file_error(
-                    FileErrorCode::NotAFile,
+                return api_error(
+                    ErrorCode::NotAFile,
                     format!("Path is a directory, not a file: {user_path}"),
                 )
                 .into_response();
             }
         }
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-            return file_error(
-                FileErrorCode::NotFound,
-                format!("File not found: {user_path}"),
-            )
-            .into_response();
+            return api_error(ErrorCode::NotFound, format!("File not found: {user_path}"))
+                .into_response();
         }
         Err(e) => {
-            return file_error(
-                FileErrorCode::InternalError,
+            return api_error(
+                ErrorCode::InternalError,
                 format!("Failed to read file metadata: {e}"),
             )
             .into_response();
@@ -523,8 +454,8 @@ pub async fn get_content(
     let bytes = match fs::read(&file_path).await {
         Ok(b) => b,
         Err(e) => {
-            return file_error(
-                FileErrorCode::InternalError,
+            return api_error(
+                ErrorCode::InternalError,
                 format!("Failed to read file: {e}"),
             )
             .into_response();
@@ -533,17 +464,14 @@ pub async fn get_content(
 
     // Check for binary content (null bytes or invalid UTF-8)
     if bytes.contains(&0) {
-        return file_error(
-            FileErrorCode::InvalidContent,
-            "Binary files are not supported",
-        )
-        .into_response();
+        return api_error(ErrorCode::InvalidContent, "Binary files are not supported")
+            .into_response();
     }
 
     let content = match String::from_utf8(bytes) {
         Ok(s) => s,
         Err(_) => {
-            return file_error(FileErrorCode::InvalidContent, "File contains invalid UTF-8")
+            return api_error(ErrorCode::InvalidContent, "File contains invalid UTF-8")
                 .into_response();
         }
     };
@@ -596,7 +524,7 @@ pub struct CreateFileResponse {
 
 /// Create a new file
 pub async fn create_file(
-    State(_state): State<ApiState>,
+    State(state): State<ApiState>,
     Json(req): Json<CreateFileRequest>,
 ) -> impl IntoResponse {
     let sandbox = sandbox_root();
@@ -611,8 +539,8 @@ pub async fn create_file(
     if file_path.exists() {
         let overwrite = req.overwrite.unwrap_or(false);
         if !overwrite {
-            return file_error(
-                FileErrorCode::AlreadyExists,
+            return api_error(
+                ErrorCode::AlreadyExists,
                 format!("File already exists: {user_path}"),
             )
             .into_response();
@@ -622,8 +550,8 @@ pub async fn create_file(
     // Ensure parent directory exists
     if let Some(parent) = file_path.parent() {
         if !parent.exists() {
-            return file_error(
-                FileErrorCode::NotADirectory,
+            return api_error(
+                ErrorCode::NotADirectory,
                 format!("Parent directory does not exist: {}", parent.display()),
             )
             .into_response();
@@ -633,19 +561,26 @@ pub async fn create_file(
     let content = req.content.unwrap_or_default();
     let size = content.len() as u64;
 
+    if let Err(e) = state.app_state.check_disk_write(size).await {
+        return api_error(ErrorCode::QuotaExceeded, e.to_string()).into_response();
+    }
+
     // Write the file
     match fs::write(&file_path, content).await {
-        Ok(_) => (
-            StatusCode::OK,
-            Json(CreateFileResponse {
-                path: user_path,
-                created: true,
-                size,
-            }),
-        )
-            .into_response(),
-        Err(e) => file_error(
-            FileErrorCode::InternalError,
+        Ok(_) => {
+            state.app_state.record_disk_write(&file_path, size).await;
+            (
+                StatusCode::OK,
+                Json(CreateFileResponse {
+                    path: user_path,
+                    created: true,
+                    size,
+                }),
+            )
+                .into_response()
+        }
+        Err(e) => api_error(
+            ErrorCode::InternalError,
             format!("Failed to create file: {e}"),
         )
         .into_response(),
@@ -671,7 +606,7 @@ pub struct WriteFileResponse {
 
 /// Write or overwrite file content
 pub async fn write_file(
-    State(_state): State<ApiState>,
+    State(state): State<ApiState>,
     Json(req): Json<WriteFileRequest>,
 ) -> impl IntoResponse {
     let sandbox = sandbox_root();
@@ -688,19 +623,16 @@ pub async fn write_file(
     // Check if file exists
     let file_exists = file_path.exists();
     if !file_exists && !create_if_missing {
-        return file_error(
-            FileErrorCode::NotFound,
-            format!("File not found: {user_path}"),
-        )
-        .into_response();
+        return api_error(ErrorCode::NotFound, format!("File not found: {user_path}"))
+            .into_response();
     }
 
     // Check if path is a directory
     if file_exists {
         match fs::metadata(&file_path).await {
             Ok(m) if m.is_dir() => {
-                return file_error(
-                    FileErrorCode::NotAFile,
+                return api_error(
+                    ErrorCode::NotAFile,
                     format!("Path is a directory: {user_path}"),
                 )
                 .into_response();
@@ -711,6 +643,10 @@ pub async fn write_file(
 
     let bytes_written = req.content.len();
 
+    if let Err(e) = state.app_state.check_disk_write(bytes_written as u64).await {
+        return api_error(ErrorCode::QuotaExceeded, e.to_string()).into_response();
+    }
+
     // Write or append to the file
     let result = if append && file_exists {
         match fs::OpenOptions::new().append(true).open(&file_path).await {
@@ -732,6 +668,11 @@ pub async fn write_file(
 
     match result {
         Ok(_) => {
+            state
+                .app_state
+                .record_disk_write(&file_path, bytes_written as u64)
+                .await;
+
             // Get the new file size
             let size = match fs::metadata(&file_path).await {
                 Ok(m) => m.len(),
@@ -748,8 +689,8 @@ pub async fn write_file(
             )
                 .into_response()
         }
-        Err(e) => file_error(
-            FileErrorCode::InternalError,
+        Err(e) => api_error(
+            ErrorCode::InternalError,
             format!("Failed to write file: {e}"),
         )
         .into_response(),
@@ -785,8 +726,8 @@ pub async fn create_directory(
 
     // Check if directory already exists
     if dir_path.exists() {
-        return file_error(
-            FileErrorCode::AlreadyExists,
+        return api_error(
+            ErrorCode::AlreadyExists,
             format!("Directory already exists: {user_path}"),
         )
         .into_response();
@@ -810,8 +751,8 @@ pub async fn create_directory(
             }),
         )
             .into_response(),
-        Err(e) => file_error(
-            FileErrorCode::InternalError,
+        Err(e) => api_error(
+            ErrorCode::InternalError,
             format!("Failed to create directory: {e}"),
         )
         .into_response(),
@@ -853,8 +794,8 @@ pub async fn rename_file(
 
     // Check if source exists
     if !source_path.exists() {
-        return file_error(
-            FileErrorCode::NotFound,
+        return api_error(
+            ErrorCode::NotFound,
             format!("Source not found: {}", req.source),
         )
         .into_response();
@@ -863,8 +804,8 @@ pub async fn rename_file(
     // Check if target exists
     let overwrite = req.overwrite.unwrap_or(false);
     if target_path.exists() && !overwrite {
-        return file_error(
-            FileErrorCode::AlreadyExists,
+        return api_error(
+            ErrorCode::AlreadyExists,
             format!("Target already exists: {}", req.target),
         )
         .into_response();
@@ -873,8 +814,8 @@ pub async fn rename_file(
     // Ensure target parent directory exists
     if let Some(parent) = target_path.parent() {
         if !parent.exists() {
-            return file_error(
-                FileErrorCode::NotADirectory,
+            return api_error(
+                ErrorCode::NotADirectory,
                 format!(
                     "Target parent directory does not exist: {}",
                     parent.display()
@@ -895,11 +836,9 @@ pub async fn rename_file(
             }),
         )
             .into_response(),
-        Err(e) => file_error(
-            FileErrorCode::InternalError,
-            format!("Failed to rename: {e}"),
-        )
-        .into_response(),
+        Err(e) => {
+            api_error(ErrorCode::InternalError, format!("Failed to rename: {e}")).into_response()
+        }
     }
 }
 
@@ -936,15 +875,12 @@ pub async fn delete_file(
     let metadata = match fs::metadata(&file_path).await {
         Ok(m) => m,
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-            return file_error(
-                FileErrorCode::NotFound,
-                format!("Path not found: {user_path}"),
-            )
-            .into_response();
+            return api_error(ErrorCode::NotFound, format!("Path not found: {user_path}"))
+                .into_response();
         }
         Err(e) => {
-            return file_error(
-                FileErrorCode::InternalError,
+            return api_error(
+                ErrorCode::InternalError,
                 format!("Failed to read path metadata: {e}"),
             )
             .into_response();
@@ -976,16 +912,14 @@ pub async fn delete_file(
             }),
         )
             .into_response(),
-        Err(e) if e.kind() == std::io::ErrorKind::DirectoryNotEmpty => file_error(
-            FileErrorCode::NotAFile,
+        Err(e) if e.kind() == std::io::ErrorKind::DirectoryNotEmpty => api_error(
+            ErrorCode::NotAFile,
             "Directory is not empty (use recursive=true to delete)",
         )
         .into_response(),
-        Err(e) => file_error(
-            FileErrorCode::InternalError,
-            format!("Failed to delete: {e}"),
-        )
-        .into_response(),
+        Err(e) => {
+            api_error(ErrorCode::InternalError, format!("Failed to delete: {e}")).into_response()
+        }
     }
 }
 
@@ -1008,7 +942,7 @@ pub struct CopyResponse {
 
 /// Copy a file
 pub async fn copy_file(
-    State(_state): State<ApiState>,
+    State(state): State<ApiState>,
     Json(req): Json<CopyRequest>,
 ) -> impl IntoResponse {
     let sandbox = sandbox_root();
@@ -1027,8 +961,8 @@ pub async fn copy_file(
     let metadata = match fs::metadata(&source_path).await {
         Ok(m) => {
             if m.is_dir() {
-                return file_error(
-                    FileErrorCode::NotAFile,
+                return api_error(
+                    ErrorCode::NotAFile,
                     format!("Source is a directory, not a file: {}", req.source),
                 )
                 .into_response();
@@ -1036,15 +970,15 @@ pub async fn copy_file(
             m
         }
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-            return file_error(
-                FileErrorCode::NotFound,
+            return api_error(
+                ErrorCode::NotFound,
                 format!("Source not found: {}", req.source),
             )
             .into_response();
         }
         Err(e) => {
-            return file_error(
-                FileErrorCode::InternalError,
+            return api_error(
+                ErrorCode::InternalError,
                 format!("Failed to read source metadata: {e}"),
             )
             .into_response();
@@ -1056,27 +990,34 @@ pub async fn copy_file(
     // Check if target exists
     let overwrite = req.overwrite.unwrap_or(false);
     if target_path.exists() && !overwrite {
-        return file_error(
-            FileErrorCode::AlreadyExists,
+        return api_error(
+            ErrorCode::AlreadyExists,
             format!("Target already exists: {}", req.target),
         )
         .into_response();
     }
 
+    if let Err(e) = state.app_state.check_disk_write(size).await {
+        return api_error(ErrorCode::QuotaExceeded, e.to_string()).into_response();
+    }
+
     // Copy the file
     match fs::copy(&source_path, &target_path).await {
-        Ok(_) => (
-            StatusCode::OK,
-            Json(CopyResponse {
-                source: req.source,
-                target: req.target,
-                copied: true,
-                size,
-            }),
-        )
-            .into_response(),
+        Ok(_) => {
+            state.app_state.record_disk_write(&target_path, size).await;
+            (
+                StatusCode::OK,
+                Json(CopyResponse {
+                    source: req.source,
+                    target: req.target,
+                    copied: true,
+                    size,
+                }),
+            )
+                .into_response()
+        }
         Err(e) => {
-            file_error(FileErrorCode::InternalError, format!("Failed to copy: {e}")).into_response()
+            api_error(ErrorCode::InternalError, format!("Failed to copy: {e}")).into_response()
         }
     }
 }
@@ -1,18 +1,35 @@
 //! Logs API endpoints
 //!
-//! Provides filtered event-log access for observability dashboards and watcher tooling.
+//! Provides filtered event-log access for observability dashboards and watcher tooling,
+//! and the generic event append used by REST clients.
 
+use axum::body::Body;
 use axum::extract::{Query, State};
-use axum::http::StatusCode;
+use axum::http::{HeaderMap, StatusCode};
 use axum::response::IntoResponse;
 use axum::Json;
 use ractor::ActorRef;
 use serde::Deserialize;
 use serde_json::json;
+use shared_types::ErrorCode;
 use std::fmt::Write;
 
+use super::error::api_error;
 use super::ApiState;
-use crate::actors::event_store::EventStoreMsg;
+use crate::actors::event_store::{
+    append_event as append_to_store, query_by_time, query_filtered, query_tail,
+    search_events as search_store, AppendEvent, EventQuery, EventStoreError, EventStoreMsg,
+    ExportWriter, MAX_QUERY_PAGE,
+};
+use crate::projections::search::match_expression;
+
+/// Bytes buffered between the EventStore export and the response body.
+const EXPORT_BUFFER_BYTES: usize = 64 * 1024;
+
+const DEFAULT_SEARCH_LIMIT: i64 = 20;
+const MAX_SEARCH_LIMIT: i64 = 100;
+
+const DEFAULT_RECENT_LIMIT: i64 = 200;
 
 #[derive(Debug, Deserialize)]
 pub struct LogsQuery {
@@ -22,6 +39,55 @@ pub struct LogsQuery {
     pub actor_id: Option<String>,
     pub user_id: Option<String>,
     pub run_id: Option<String>,
+    /// Earliest event time: RFC 3339 or a relative offset such as `-15m`.
+    pub since: Option<String>,
+    /// Latest event time, in the same forms as `since`.
+    pub until: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EventPageQuery {
+    pub actor_id: Option<String>,
+    /// Comma-separated event type prefixes, e.g. `writer.run.*,chat.`.
+    pub event_types: Option<String>,
+    /// Earliest event time: RFC 3339 or a relative offset such as `-15m`.
+    pub since: Option<String>,
+    /// Latest event time, in the same forms as `since`.
+    pub until: Option<String>,
+    pub limit: Option<i64>,
+    /// `next_cursor` from the previous page.
+    pub cursor: Option<i64>,
+    /// `asc` (default) pages forward from the oldest event, `desc` backward
+    /// from the newest.
+    pub order: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EventRangeQuery {
+    /// Earliest event time: RFC 3339 or a relative offset such as `-10m`.
+    pub from: Option<String>,
+    /// Latest event time, in the same forms as `from`. Defaults to now.
+    pub to: Option<String>,
+    pub actor_id: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EventTailQuery {
+    pub actor_id: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EventSearchQuery {
+    pub q: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EventExportQuery {
+    pub since_seq: Option<i64>,
+    pub until_seq: Option<i64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -47,7 +113,24 @@ pub(crate) async fn query_events_from_store(
     event_store: ActorRef<EventStoreMsg>,
     query: LogsQuery,
 ) -> Result<Vec<shared_types::Event>, String> {
-    let since_seq = query.since_seq.unwrap_or(0).max(0);
+    let now = chrono::Utc::now();
+    let parse_bound = |raw: &Option<String>| {
+        raw.as_deref()
+            .map(|raw| super::time::parse_time_bound(raw, now))
+            .transpose()
+            .map_err(|e| format!("bad_request:{e}"))
+    };
+    let since = parse_bound(&query.since)?;
+    let until = parse_bound(&query.until)?;
+
+    let mut since_seq = query.since_seq.unwrap_or(0).max(0);
+    if let Some(since) = since {
+        match crate::actors::event_store::get_last_seq_before(&event_store, since).await {
+            Ok(Ok(seq)) => since_seq = since_seq.max(seq),
+            Ok(Err(err)) => return Err(format!("EventStore error: {err}")),
+            Err(err) => return Err(format!("RPC error: {err}")),
+        }
+    }
     let limit = query.limit.unwrap_or(200).clamp(1, 1000);
 
     match ractor::call!(event_store, |reply| {
@@ -61,6 +144,10 @@ pub(crate) async fn query_events_from_store(
         }
     }) {
         Ok(Ok(mut events)) => {
+            events.sort_by(shared_types::Event::canonical_cmp);
+            if let Some(until) = until {
+                events.retain(|event| event.stored_at <= until);
+            }
             if let Some(ref run_id) = query.run_id {
                 events.retain(|event| {
                     event
@@ -92,14 +179,156 @@ pub async fn get_events(
 ) -> impl IntoResponse {
     match query_events(&state, query).await {
         Ok(events) => (StatusCode::OK, Json(json!({ "events": events }))).into_response(),
-        Err(err) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({ "error": err })),
+        Err(err) if err.starts_with("bad_request:") => api_error(
+            ErrorCode::InvalidRequest,
+            err.trim_start_matches("bad_request:"),
+        ),
+        Err(err) => api_error(ErrorCode::InternalError, err),
+    }
+}
+
+/// One page of events matching actor, type, and time filters. Pass the
+/// returned `next_cursor` back as `cursor` to load the following page.
+pub async fn get_events_page(
+    State(state): State<ApiState>,
+    Query(query): Query<EventPageQuery>,
+) -> impl IntoResponse {
+    let query = match event_query_from_params(query, chrono::Utc::now()) {
+        Ok(query) => query,
+        Err(err) => return api_error(ErrorCode::InvalidRequest, err),
+    };
+    match query_filtered(&state.app_state.event_store(), query).await {
+        Ok(Ok(page)) => (StatusCode::OK, Json(json!(page))).into_response(),
+        Ok(Err(err)) => api_error(ErrorCode::InternalError, format!("EventStore error: {err}")),
+        Err(err) => api_error(ErrorCode::InternalError, format!("RPC error: {err}")),
+    }
+}
+
+/// GET /api/events/range — events stored between `from` and `to`, newest
+/// first. `to` defaults to now, so `from=-10m` is the last ten minutes.
+pub async fn get_events_by_time(
+    State(state): State<ApiState>,
+    Query(query): Query<EventRangeQuery>,
+) -> impl IntoResponse {
+    let now = chrono::Utc::now();
+    let Some(from) = query.from.as_deref() else {
+        return api_error(ErrorCode::InvalidRequest, "from is required");
+    };
+    let from = match super::time::parse_time_bound(from, now) {
+        Ok(from) => from,
+        Err(err) => return api_error(ErrorCode::InvalidRequest, err),
+    };
+    let to = match query.to.as_deref() {
+        Some(to) => match super::time::parse_time_bound(to, now) {
+            Ok(to) => to,
+            Err(err) => return api_error(ErrorCode::InvalidRequest, err),
+        },
+        None => now,
+    };
+    if from > to {
+        return api_error(ErrorCode::InvalidRequest, "from must not be after to");
+    }
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_RECENT_LIMIT)
+        .clamp(1, MAX_QUERY_PAGE);
+    let actor_id = query.actor_id.filter(|actor_id| !actor_id.is_empty());
+
+    match query_by_time(&state.app_state.event_store(), from, to, actor_id, limit).await {
+        Ok(Ok(events)) => (StatusCode::OK, Json(json!({ "events": events }))).into_response(),
+        Ok(Err(err)) => api_error(ErrorCode::InternalError, format!("EventStore error: {err}")),
+        Err(err) => api_error(ErrorCode::InternalError, format!("RPC error: {err}")),
+    }
+}
+
+/// GET /api/events/tail — the most recent `limit` events, newest first.
+pub async fn get_events_tail(
+    State(state): State<ApiState>,
+    Query(query): Query<EventTailQuery>,
+) -> impl IntoResponse {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_RECENT_LIMIT)
+        .clamp(1, MAX_QUERY_PAGE);
+    let actor_id = query.actor_id.filter(|actor_id| !actor_id.is_empty());
+
+    match query_tail(&state.app_state.event_store(), actor_id, limit).await {
+        Ok(Ok(events)) => (StatusCode::OK, Json(json!({ "events": events }))).into_response(),
+        Ok(Err(err)) => api_error(ErrorCode::InternalError, format!("EventStore error: {err}")),
+        Err(err) => api_error(ErrorCode::InternalError, format!("RPC error: {err}")),
+    }
+}
+
+/// GET /api/events/search — events whose type, actor or payload text match
+/// `q`, best first, each with a snippet of the matched text. Words are
+/// matched literally and the last one as a prefix.
+pub async fn search_events(
+    State(state): State<ApiState>,
+    Query(query): Query<EventSearchQuery>,
+) -> impl IntoResponse {
+    let q = query.q.unwrap_or_default();
+    if q.trim().is_empty() {
+        return api_error(ErrorCode::InvalidRequest, "q is required");
+    }
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+    let Some(match_expr) = match_expression(&q) else {
+        return (StatusCode::OK, Json(json!({ "query": q, "matches": [] }))).into_response();
+    };
+
+    match search_store(&state.app_state.event_store(), match_expr, limit).await {
+        Ok(Ok(matches)) => (
+            StatusCode::OK,
+            Json(json!({ "query": q, "matches": matches })),
         )
             .into_response(),
+        Ok(Err(EventStoreError::SearchDisabled)) => api_error(
+            ErrorCode::ServiceUnavailable,
+            "Event search is disabled on this sandbox",
+        ),
+        Ok(Err(err)) => api_error(ErrorCode::InternalError, format!("EventStore error: {err}")),
+        Err(err) => api_error(ErrorCode::ActorUnavailable, format!("RPC error: {err}")),
     }
 }
 
+fn event_query_from_params(
+    params: EventPageQuery,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<EventQuery, String> {
+    let parse_bound = |raw: &Option<String>| {
+        raw.as_deref()
+            .map(|raw| super::time::parse_time_bound(raw, now))
+            .transpose()
+    };
+    let newest_first = match params.order.as_deref() {
+        None | Some("asc") => false,
+        Some("desc") => true,
+        Some(other) => return Err(format!("order must be asc or desc, got {other}")),
+    };
+    if params.cursor.is_some_and(|cursor| cursor < 0) {
+        return Err("cursor must not be negative".to_string());
+    }
+    Ok(EventQuery {
+        actor_id: params.actor_id.filter(|actor_id| !actor_id.is_empty()),
+        event_type_prefixes: params
+            .event_types
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|prefix| !prefix.is_empty())
+            .map(str::to_string)
+            .collect(),
+        since: parse_bound(&params.since)?,
+        until: parse_bound(&params.until)?,
+        limit: params.limit.unwrap_or(200),
+        cursor: params.cursor,
+        newest_first,
+    })
+}
+
 #[derive(serde::Serialize)]
 struct LatestSeqResponse {
     latest_seq: i64,
@@ -154,6 +383,85 @@ async fn find_latest_seq(event_store: ActorRef<EventStoreMsg>) -> Result<i64, St
     Ok(low)
 }
 
+/// Header carrying the client's idempotency key for [`append_event`].
+pub(crate) const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Event types clients may append. Everything else is written by the actors
+/// themselves, and a client forging it could steer a conductor or writer.
+const CLIENT_EVENT_TYPES: &[&str] = &["chat.user_msg"];
+
+/// User recorded for appends without an authenticated session.
+const ANONYMOUS_USER: &str = "anonymous";
+
+/// The session user the hypervisor proxy vouches for, or [`ANONYMOUS_USER`].
+/// The proxy overwrites `x-choiros-proxy-authenticated` on every request, so
+/// a `x-choiros-user-id` sent by an unauthenticated client is not trusted.
+fn session_user_id(headers: &HeaderMap) -> String {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    };
+    match (
+        header("x-choiros-proxy-authenticated"),
+        header("x-choiros-user-id"),
+    ) {
+        (Some("true"), Some(user_id)) => user_id.to_string(),
+        _ => ANONYMOUS_USER.to_string(),
+    }
+}
+
+/// Append one client event, recorded for the session user.
+///
+/// Only [`CLIENT_EVENT_TYPES`] are accepted. An `Idempotency-Key` header (or
+/// the body's `idempotency_key`; the header wins) makes the request safe to
+/// retry: a repeat with the same key from the same user returns the event
+/// stored the first time instead of appending it again.
+pub async fn append_event(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Json(request): Json<shared_types::AppendEvent>,
+) -> impl IntoResponse {
+    if !CLIENT_EVENT_TYPES.contains(&request.event_type.as_str()) {
+        return api_error(
+            ErrorCode::InvalidRequest,
+            format!(
+                "event type {} cannot be appended by clients",
+                request.event_type
+            ),
+        );
+    }
+    let header_key = match headers
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .map(|value| value.to_str())
+    {
+        Some(Ok(key)) if !key.trim().is_empty() => Some(key.trim().to_string()),
+        Some(Ok(_)) | None => None,
+        Some(Err(_)) => {
+            return api_error(
+                ErrorCode::InvalidRequest,
+                "Idempotency-Key header must be visible ASCII",
+            )
+        }
+    };
+    let event = AppendEvent {
+        event_type: request.event_type,
+        payload: request.payload,
+        actor_id: request.actor_id.0,
+        user_id: session_user_id(&headers),
+        idempotency_key: header_key.or(request.idempotency_key),
+    };
+
+    match append_to_store(&state.app_state.event_store(), event).await {
+        Ok(Ok(event)) => (StatusCode::OK, Json(event)).into_response(),
+        Ok(Err(EventStoreError::InvalidPayload(e))) => api_error(ErrorCode::InvalidRequest, e),
+        Ok(Err(e)) => api_error(ErrorCode::InternalError, e.to_string()),
+        Err(e) => api_error(ErrorCode::ActorUnavailable, format!("Actor error: {e}")),
+    }
+}
+
 /// Get latest committed event sequence number.
 pub async fn get_latest_seq(State(state): State<ApiState>) -> impl IntoResponse {
     match find_latest_seq(state.app_state.event_store()).await {
@@ -162,11 +470,7 @@ pub async fn get_latest_seq(State(state): State<ApiState>) -> impl IntoResponse
             Json(json!(LatestSeqResponse { latest_seq })),
         )
             .into_response(),
-        Err(err) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({ "error": err })),
-        )
-            .into_response(),
+        Err(err) => api_error(ErrorCode::InternalError, err),
     }
 }
 
@@ -185,18 +489,11 @@ pub async fn export_run_markdown(
             body,
         )
             .into_response(),
-        Err(err) if err.starts_with("bad_request:") => (
-            StatusCode::BAD_REQUEST,
-            [("content-type", "application/json")],
-            json!({ "error": err.trim_start_matches("bad_request:") }).to_string(),
-        )
-            .into_response(),
-        Err(err) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            [("content-type", "application/json")],
-            json!({ "error": err }).to_string(),
-        )
-            .into_response(),
+        Err(err) if err.starts_with("bad_request:") => api_error(
+            ErrorCode::InvalidRequest,
+            err.trim_start_matches("bad_request:"),
+        ),
+        Err(err) => api_error(ErrorCode::InternalError, err),
     }
 }
 
@@ -225,6 +522,8 @@ pub(crate) async fn build_run_markdown_from_store(
         actor_id: query.actor_id.clone(),
         user_id: query.user_id.clone(),
         run_id: query.run_id.clone(),
+        since: None,
+        until: None,
     };
 
     let events = query_events_from_store(event_store, base_query).await?;
@@ -248,18 +547,9 @@ pub(crate) fn validate_scope_pair(
 
 pub(crate) fn event_matches_run_filter(event: &shared_types::Event, query: &RunLogQuery) -> bool {
     if let (Some(session_id), Some(thread_id)) = (&query.session_id, &query.thread_id) {
-        let payload_session = event
-            .payload
-            .get("scope")
-            .and_then(|scope| scope.get("session_id"))
-            .and_then(|v| v.as_str());
-        let payload_thread = event
-            .payload
-            .get("scope")
-            .and_then(|scope| scope.get("thread_id"))
-            .and_then(|v| v.as_str());
-        if payload_session != Some(session_id.as_str())
-            || payload_thread != Some(thread_id.as_str())
+        let scope = shared_types::EventScope::from_payload(&event.payload);
+        if scope.session_id.as_deref() != Some(session_id.as_str())
+            || scope.thread_id.as_deref() != Some(thread_id.as_str())
         {
             return false;
         }
@@ -555,12 +845,10 @@ pub async fn export_events_jsonl(
                         out.push('\n');
                     }
                     Err(e) => {
-                        return (
-                            StatusCode::INTERNAL_SERVER_ERROR,
-                            [("content-type", "application/json")],
-                            json!({ "error": format!("Serialization error: {e}") }).to_string(),
-                        )
-                            .into_response();
+                        return api_error(
+                            ErrorCode::InternalError,
+                            format!("Serialization error: {e}"),
+                        );
                     }
                 }
             }
@@ -571,15 +859,47 @@ pub async fn export_events_jsonl(
             )
                 .into_response()
         }
-        Err(err) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            [("content-type", "application/json")],
-            json!({ "error": err }).to_string(),
-        )
-            .into_response(),
+        Err(err) if err.starts_with("bad_request:") => api_error(
+            ErrorCode::InvalidRequest,
+            err.trim_start_matches("bad_request:"),
+        ),
+        Err(err) => api_error(ErrorCode::InternalError, err),
     }
 }
 
+/// GET /api/events/export — the event log, or a seq range of it, streamed
+/// as NDJSON for moving a sandbox or reading its history offline. The
+/// response is chunked; an export that fails midway ends the stream early.
+pub async fn export_event_log(
+    State(state): State<ApiState>,
+    Query(query): Query<EventExportQuery>,
+) -> impl IntoResponse {
+    let (writer, reader) = tokio::io::duplex(EXPORT_BUFFER_BYTES);
+    let (reply, exported) = tokio::sync::oneshot::channel();
+    if let Err(e) = state.app_state.event_store().cast(EventStoreMsg::Export {
+        since_seq: query.since_seq.unwrap_or(0).max(0),
+        until_seq: query.until_seq,
+        writer: ExportWriter(Box::new(writer)),
+        reply: reply.into(),
+    }) {
+        return api_error(ErrorCode::ActorUnavailable, format!("Actor error: {e}"));
+    }
+    tokio::spawn(async move {
+        match exported.await {
+            Ok(Ok(count)) => tracing::debug!(count, "Event log export finished"),
+            Ok(Err(e)) => tracing::warn!(error = %e, "Event log export failed"),
+            Err(_) => tracing::warn!("Event log export dropped without a result"),
+        }
+    });
+
+    (
+        StatusCode::OK,
+        [("content-type", "application/x-ndjson; charset=utf-8")],
+        Body::from_stream(tokio_util::io::ReaderStream::new(reader)),
+    )
+        .into_response()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -612,6 +932,7 @@ mod tests {
                 payload: serde_json::json!({ "idx": idx }),
                 actor_id: "session:test".to_string(),
                 user_id: "user:test".to_string(),
+                idempotency_key: None,
             };
             let appended = ractor::call!(store_ref, |reply| EventStoreMsg::Append { event, reply })
                 .unwrap()
@@ -624,4 +945,53 @@ mod tests {
 
         store_ref.stop(None);
     }
+
+    #[tokio::test]
+    async fn test_query_events_filters_by_time_bounds() {
+        let (store_ref, _handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .unwrap();
+        for idx in 0..3 {
+            let event = AppendEvent {
+                event_type: "interaction.user_msg".to_string(),
+                payload: serde_json::json!({ "idx": idx }),
+                actor_id: "session:test".to_string(),
+                user_id: "user:test".to_string(),
+                idempotency_key: None,
+            };
+            ractor::call!(store_ref, |reply| EventStoreMsg::Append { event, reply })
+                .unwrap()
+                .unwrap();
+        }
+
+        let query = |since: Option<&str>, until: Option<&str>| LogsQuery {
+            since_seq: None,
+            limit: None,
+            event_type_prefix: None,
+            actor_id: None,
+            user_id: None,
+            run_id: None,
+            since: since.map(str::to_string),
+            until: until.map(str::to_string),
+        };
+        let count = |query| {
+            let store_ref = store_ref.clone();
+            async move {
+                query_events_from_store(store_ref, query)
+                    .await
+                    .map(|events| events.len())
+            }
+        };
+
+        assert_eq!(count(query(Some("-15m"), None)).await, Ok(3));
+        assert_eq!(count(query(Some("+1h"), None)).await, Ok(0));
+        assert_eq!(count(query(None, Some("-1h"))).await, Ok(0));
+        let until = (chrono::Utc::now() + chrono::TimeDelta::minutes(1)).to_rfc3339();
+        assert_eq!(count(query(Some("-1h"), Some(&until))).await, Ok(3));
+        let invalid = count(query(Some("yesterday"), None)).await.unwrap_err();
+        assert!(invalid.starts_with("bad_request:"), "{invalid}");
+
+        store_ref.stop(None);
+    }
 }
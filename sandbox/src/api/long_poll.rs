@@ -0,0 +1,461 @@
+//! Long-poll event transport, for clients whose network blocks WebSocket
+//! upgrades.
+//!
+//! `POST /api/events/subscriptions` subscribes a poll session to a desktop
+//! the way a WebSocket `subscribe` message does and returns a cursor.
+//! `GET /api/events/poll?cursor=&timeout_ms=` waits until the session has
+//! messages after the cursor, or the timeout elapses, and returns them with
+//! the next cursor. A poll session is registered as a [`WsSubscriber`] like
+//! any WebSocket session, so it is sent the same messages in the same order.
+//!
+//! Cursors are `<session id>.<position>`. A poll acknowledges every message
+//! up to the cursor's position; later ones stay buffered until a poll
+//! acknowledges them, so a poll retried with the same cursor returns the same
+//! messages again. A session that is neither polled nor subscribed for
+//! [`POLL_SESSION_IDLE_TIMEOUT`] is dropped along with its subscriptions.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::extract::ws::Message;
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Deserialize;
+use shared_types::{
+    ActivitySource, DesktopWsMessage, ErrorCode, EventPollResponse, EventSubscriptionRequest,
+    EventSubscriptionResponse, PatchGranularity,
+};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use super::error::api_error;
+use super::websocket::{
+    provider_gateway_status_message, send_json, subscribe_session, subscription_snapshot,
+    unsubscribe_session, WsSessions, WsSubscriber, MAX_SUBSCRIPTIONS_PER_SESSION,
+};
+use super::ApiState;
+
+/// How long a session lives without a poll or subscribe.
+pub const POLL_SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How long a poll waits for messages when `timeout_ms` is not given.
+pub const DEFAULT_POLL_TIMEOUT_MS: u64 = 25_000;
+
+/// Longest a poll may wait; larger `timeout_ms` values are clamped.
+pub const MAX_POLL_TIMEOUT_MS: u64 = 30_000;
+
+/// Unacknowledged messages a session may hold. A session past this is
+/// dropped rather than losing messages, and the client subscribes again.
+pub const MAX_BUFFERED_MESSAGES: usize = 10_000;
+
+struct PollSession {
+    ws_sessions: WsSessions,
+    sender: mpsc::UnboundedSender<Message>,
+    subscription: Mutex<Subscription>,
+    last_seen: Mutex<Instant>,
+    /// Held for the whole of a poll, so polls on a session run one at a time.
+    queue: tokio::sync::Mutex<MessageQueue>,
+}
+
+#[derive(Default)]
+struct Subscription {
+    desktops: Vec<String>,
+    patch_granularity: PatchGranularity,
+}
+
+struct MessageQueue {
+    receiver: mpsc::UnboundedReceiver<Message>,
+    /// Messages not acknowledged yet; the first is at position `acked + 1`.
+    pending: VecDeque<DesktopWsMessage>,
+    acked: u64,
+}
+
+impl PollSession {
+    fn touch(&self) {
+        *self.last_seen.lock().expect("poll session lock poisoned") = Instant::now();
+    }
+
+    fn idle_for(&self) -> Duration {
+        self.last_seen
+            .lock()
+            .expect("poll session lock poisoned")
+            .elapsed()
+    }
+}
+
+impl MessageQueue {
+    /// Drop the messages up to `position`. Earlier positions were dropped by
+    /// a previous poll and cannot be replayed.
+    fn acknowledge(&mut self, position: u64) -> Result<(), String> {
+        let end = self.acked + self.pending.len() as u64;
+        if position < self.acked || position > end {
+            return Err(format!(
+                "cursor position {position} is outside the replayable range {}..={end}; subscribe again",
+                self.acked
+            ));
+        }
+        self.pending.drain(..(position - self.acked) as usize);
+        self.acked = position;
+        Ok(())
+    }
+
+    /// Move everything already sent to the session into `pending`.
+    fn receive_ready(&mut self) {
+        while let Ok(message) = self.receiver.try_recv() {
+            self.push(message);
+        }
+    }
+
+    fn push(&mut self, message: Message) {
+        let Message::Text(text) = message else {
+            return;
+        };
+        match serde_json::from_str(&text) {
+            Ok(message) => self.pending.push_back(message),
+            Err(e) => tracing::error!("Failed to parse long-poll message: {}", e),
+        }
+    }
+}
+
+/// Live poll sessions by id, held in [`ApiState`].
+#[derive(Clone, Default)]
+pub struct PollSessions {
+    inner: Arc<Mutex<HashMap<Uuid, Arc<PollSession>>>>,
+}
+
+impl PollSessions {
+    fn get(&self, session_id: &Uuid) -> Option<Arc<PollSession>> {
+        self.inner
+            .lock()
+            .expect("poll sessions lock poisoned")
+            .get(session_id)
+            .cloned()
+    }
+
+    fn insert(&self, session_id: Uuid, session: Arc<PollSession>) {
+        self.inner
+            .lock()
+            .expect("poll sessions lock poisoned")
+            .insert(session_id, session);
+    }
+
+    fn remove(&self, session_id: &Uuid) -> Option<Arc<PollSession>> {
+        self.inner
+            .lock()
+            .expect("poll sessions lock poisoned")
+            .remove(session_id)
+    }
+}
+
+fn format_cursor(session_id: Uuid, position: u64) -> String {
+    format!("{session_id}.{position}")
+}
+
+fn parse_cursor(cursor: &str) -> Option<(Uuid, u64)> {
+    let (session_id, position) = cursor.split_once('.')?;
+    Some((session_id.parse().ok()?, position.parse().ok()?))
+}
+
+/// The session a cursor belongs to, and the cursor's position.
+fn find_session(
+    sessions: &PollSessions,
+    cursor: &str,
+) -> Result<(Uuid, Arc<PollSession>, u64), Response> {
+    let (session_id, position) = parse_cursor(cursor).ok_or_else(|| {
+        api_error(
+            ErrorCode::InvalidRequest,
+            format!("Malformed cursor '{cursor}'"),
+        )
+    })?;
+    let session = sessions.get(&session_id).ok_or_else(|| {
+        api_error(
+            ErrorCode::NotFound,
+            "Long-poll session not found or expired; subscribe again",
+        )
+    })?;
+    Ok((session_id, session, position))
+}
+
+fn start_session(sessions: &PollSessions, ws_sessions: WsSessions) -> (Uuid, Arc<PollSession>) {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let session_id = Uuid::new_v4();
+    let session = Arc::new(PollSession {
+        ws_sessions,
+        sender,
+        subscription: Mutex::new(Subscription::default()),
+        last_seen: Mutex::new(Instant::now()),
+        queue: tokio::sync::Mutex::new(MessageQueue {
+            receiver,
+            pending: VecDeque::new(),
+            acked: 0,
+        }),
+    });
+    sessions.insert(session_id, session.clone());
+    tokio::spawn(close_when_idle(sessions.clone(), session_id));
+    (session_id, session)
+}
+
+async fn close_when_idle(sessions: PollSessions, session_id: Uuid) {
+    loop {
+        let Some(session) = sessions.get(&session_id) else {
+            return;
+        };
+        let idle_for = session.idle_for();
+        if idle_for >= POLL_SESSION_IDLE_TIMEOUT {
+            tracing::info!(%session_id, "Long-poll session expired");
+            close_session(&sessions, session_id).await;
+            return;
+        }
+        tokio::time::sleep(POLL_SESSION_IDLE_TIMEOUT - idle_for).await;
+    }
+}
+
+async fn close_session(sessions: &PollSessions, session_id: Uuid) {
+    let Some(session) = sessions.remove(&session_id) else {
+        return;
+    };
+    let desktops = std::mem::take(
+        &mut session
+            .subscription
+            .lock()
+            .expect("poll session lock poisoned")
+            .desktops,
+    );
+    for desktop_id in desktops {
+        unsubscribe_session(&session.ws_sessions, &desktop_id, session_id).await;
+    }
+}
+
+fn too_many_subscriptions() -> Response {
+    api_error(
+        ErrorCode::Conflict,
+        format!(
+            "Subscription refused: a session may hold at most {MAX_SUBSCRIPTIONS_PER_SESSION} subscriptions"
+        ),
+    )
+}
+
+/// POST /api/events/subscriptions - subscribe a long-poll session to a
+/// desktop, starting a session unless `cursor` names one.
+pub async fn subscribe(
+    State(state): State<ApiState>,
+    Json(request): Json<EventSubscriptionRequest>,
+) -> impl IntoResponse {
+    if request.desktop_id.trim().is_empty() {
+        return api_error(ErrorCode::InvalidRequest, "desktop_id cannot be empty");
+    }
+    let (session_id, session, cursor) = match request.cursor.as_deref() {
+        Some(cursor) => match find_session(&state.poll_sessions, cursor) {
+            Ok((session_id, session, _)) => (session_id, session, cursor.to_string()),
+            Err(response) => return response,
+        },
+        None => {
+            let (session_id, session) =
+                start_session(&state.poll_sessions, state.ws_sessions.clone());
+            (session_id, session, format_cursor(session_id, 0))
+        }
+    };
+    session.touch();
+    state
+        .app_state
+        .record_activity(&request.desktop_id, ActivitySource::LongPoll);
+
+    let patch_granularity = {
+        let mut subscription = session
+            .subscription
+            .lock()
+            .expect("poll session lock poisoned");
+        if !subscription.desktops.contains(&request.desktop_id)
+            && subscription.desktops.len() >= MAX_SUBSCRIPTIONS_PER_SESSION
+        {
+            return too_many_subscriptions();
+        }
+        if let Some(requested) = request.patch_granularity {
+            subscription.patch_granularity = requested;
+        }
+        subscription.patch_granularity
+    };
+
+    match subscription_snapshot(&state.app_state, &request.desktop_id).await {
+        Ok(snapshot) => {
+            let _ = send_json(&session.sender, &snapshot);
+        }
+        Err(message) => return api_error(ErrorCode::ActorSpawnFailed, message),
+    }
+    let subscribed = subscribe_session(
+        &session.ws_sessions,
+        &request.desktop_id,
+        session_id,
+        WsSubscriber {
+            sender: session.sender.clone(),
+            patch_granularity,
+            topics: request.topics,
+        },
+    )
+    .await;
+    if !subscribed {
+        return too_many_subscriptions();
+    }
+    {
+        let mut subscription = session
+            .subscription
+            .lock()
+            .expect("poll session lock poisoned");
+        if !subscription.desktops.contains(&request.desktop_id) {
+            subscription.desktops.push(request.desktop_id.clone());
+        }
+    }
+    // The outage banner is persistent: a desktop that subscribes mid-outage
+    // must see it too.
+    if !state.app_state.provider_gateway_reachable() {
+        let _ = send_json(&session.sender, &provider_gateway_status_message(false));
+    }
+
+    (
+        StatusCode::OK,
+        Json(EventSubscriptionResponse {
+            cursor,
+            expires_in_ms: POLL_SESSION_IDLE_TIMEOUT.as_millis() as u64,
+        }),
+    )
+        .into_response()
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct PollQuery {
+    pub cursor: Option<String>,
+    /// Defaults to [`DEFAULT_POLL_TIMEOUT_MS`], capped at [`MAX_POLL_TIMEOUT_MS`].
+    pub timeout_ms: Option<u64>,
+}
+
+/// GET /api/events/poll - acknowledge everything up to `cursor` and wait for
+/// the messages after it.
+pub async fn poll(
+    State(state): State<ApiState>,
+    Query(query): Query<PollQuery>,
+) -> impl IntoResponse {
+    let Some(cursor) = query.cursor.as_deref() else {
+        return api_error(ErrorCode::InvalidRequest, "cursor is required");
+    };
+    let (session_id, session, position) = match find_session(&state.poll_sessions, cursor) {
+        Ok(found) => found,
+        Err(response) => return response,
+    };
+    let timeout = Duration::from_millis(
+        query
+            .timeout_ms
+            .unwrap_or(DEFAULT_POLL_TIMEOUT_MS)
+            .min(MAX_POLL_TIMEOUT_MS),
+    );
+    session.touch();
+    let activity_session = session
+        .subscription
+        .lock()
+        .expect("poll session lock poisoned")
+        .desktops
+        .first()
+        .cloned()
+        .unwrap_or_else(|| session_id.to_string());
+    state
+        .app_state
+        .record_activity(&activity_session, ActivitySource::LongPoll);
+
+    let mut queue = session.queue.lock().await;
+    if let Err(message) = queue.acknowledge(position) {
+        return api_error(ErrorCode::Conflict, message);
+    }
+    queue.receive_ready();
+    if queue.pending.is_empty() {
+        if let Ok(Some(message)) = tokio::time::timeout(timeout, queue.receiver.recv()).await {
+            queue.push(message);
+            queue.receive_ready();
+        }
+    }
+    session.touch();
+    if queue.pending.len() > MAX_BUFFERED_MESSAGES {
+        drop(queue);
+        tracing::warn!(%session_id, "Long-poll session fell too far behind, closing");
+        close_session(&state.poll_sessions, session_id).await;
+        return api_error(
+            ErrorCode::Conflict,
+            "Too many unacknowledged messages; subscribe again",
+        );
+    }
+
+    let next_cursor = format_cursor(session_id, queue.acked + queue.pending.len() as u64);
+    (
+        StatusCode::OK,
+        Json(EventPollResponse {
+            messages: queue.pending.iter().cloned().collect(),
+            next_cursor,
+        }),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue_with(messages: &[&str]) -> (mpsc::UnboundedSender<Message>, MessageQueue) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        for window_id in messages {
+            let _ = send_json(
+                &sender,
+                &DesktopWsMessage::WindowClosed {
+                    window_id: window_id.to_string(),
+                },
+            );
+        }
+        let mut queue = MessageQueue {
+            receiver,
+            pending: VecDeque::new(),
+            acked: 0,
+        };
+        queue.receive_ready();
+        (sender, queue)
+    }
+
+    fn window_ids(queue: &MessageQueue) -> Vec<String> {
+        queue
+            .pending
+            .iter()
+            .map(|message| match message {
+                DesktopWsMessage::WindowClosed { window_id } => window_id.clone(),
+                other => panic!("unexpected message: {other:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let session_id = Uuid::new_v4();
+        assert_eq!(
+            parse_cursor(&format_cursor(session_id, 42)),
+            Some((session_id, 42))
+        );
+        assert_eq!(parse_cursor("not-a-cursor"), None);
+        assert_eq!(parse_cursor(&format!("{session_id}.-1")), None);
+    }
+
+    #[test]
+    fn test_acknowledge_drops_only_delivered_messages() {
+        let (_sender, mut queue) = queue_with(&["w1", "w2", "w3"]);
+
+        // Retrying the same cursor keeps everything.
+        queue.acknowledge(0).unwrap();
+        assert_eq!(window_ids(&queue), ["w1", "w2", "w3"]);
+
+        queue.acknowledge(2).unwrap();
+        assert_eq!(window_ids(&queue), ["w3"]);
+        assert_eq!(queue.acked, 2);
+
+        // Already dropped, and never delivered.
+        assert!(queue.acknowledge(1).is_err());
+        assert!(queue.acknowledge(4).is_err());
+        assert_eq!(window_ids(&queue), ["w3"]);
+    }
+}
@@ -11,15 +11,29 @@ use axum::{Json, Router};
 use serde_json::json;
 use std::sync::Arc;
 
+pub mod admin;
 pub mod conductor;
+pub mod context_traces;
 pub mod desktop;
+pub mod desktop_templates;
 pub mod dioxus_compat;
+pub mod error;
 pub mod files;
 pub mod logs;
+pub mod long_poll;
+pub mod preferences;
+pub mod replay;
+pub mod research;
+pub mod run_audit;
+pub mod run_bundle;
 pub mod run_observability;
+pub mod search;
 pub mod terminal;
+pub mod time;
+pub mod usage;
 pub mod user;
 pub mod viewer;
+pub mod viewer_images;
 pub mod websocket;
 pub mod websocket_logs;
 pub mod writer;
@@ -31,20 +45,44 @@ use crate::app_state::AppState;
 pub struct ApiState {
     pub app_state: Arc<AppState>,
     pub ws_sessions: WsSessions,
+    pub research_tasks: research::ResearchTaskIndex,
+    pub poll_sessions: long_poll::PollSessions,
+    pub patch_order: websocket::PatchOrder,
+}
+
+impl ApiState {
+    pub fn new(app_state: Arc<AppState>, ws_sessions: WsSessions) -> Self {
+        Self {
+            app_state,
+            ws_sessions,
+            research_tasks: research::ResearchTaskIndex::default(),
+            poll_sessions: long_poll::PollSessions::default(),
+            patch_order: websocket::new_patch_order(),
+        }
+    }
 }
 
 /// Configure all API routes
 pub fn router() -> Router<ApiState> {
     Router::new()
         .route("/health", get(health_check))
+        .route("/health/ready", get(readiness_check))
         .route("/_dioxus", get(dioxus_compat::hmr_websocket))
         .route("/ws", get(websocket::ws_handler))
         .route("/ws/logs/events", get(websocket_logs::logs_websocket))
         // Note: legacy message backend removed - Prompt Bar routes to Conductor
         // Logs routes
         .route("/logs/events", get(logs::get_events))
+        .route("/logs/events/page", get(logs::get_events_page))
         .route("/logs/latest-seq", get(logs::get_latest_seq))
         .route("/logs/events.jsonl", get(logs::export_events_jsonl))
+        .route("/api/events", post(logs::append_event))
+        .route("/api/events/export", get(logs::export_event_log))
+        .route("/api/events/range", get(logs::get_events_by_time))
+        .route("/api/events/tail", get(logs::get_events_tail))
+        .route("/api/events/search", get(logs::search_events))
+        .route("/api/events/subscriptions", post(long_poll::subscribe))
+        .route("/api/events/poll", get(long_poll::poll))
         .route("/logs/run.md", get(logs::export_run_markdown))
         .route(
             "/api/runs/{run_id}/timeline",
@@ -66,7 +104,18 @@ pub fn router() -> Router<ApiState> {
             get(user::get_model_config).patch(user::update_model_config),
         )
         // Desktop routes
-        .route("/desktop/{desktop_id}", get(desktop::get_desktop_state))
+        .route(
+            "/desktop/{desktop_id}",
+            get(desktop::get_desktop_state).post(desktop::create_desktop),
+        )
+        .route(
+            "/desktop-templates",
+            get(desktop_templates::list_templates).post(desktop_templates::save_template),
+        )
+        .route(
+            "/desktop/{desktop_id}/bounds",
+            patch(desktop::set_desktop_bounds),
+        )
         .route(
             "/desktop/{desktop_id}/windows",
             get(desktop::get_windows).post(desktop::open_window),
@@ -108,6 +157,11 @@ pub fn router() -> Router<ApiState> {
             "/viewer/content",
             get(viewer::get_viewer_content).patch(viewer::patch_viewer_content),
         )
+        .route("/viewer/image-set", get(viewer_images::get_run_image_set))
+        .route(
+            "/viewer/thumbnail",
+            get(viewer_images::get_viewer_thumbnail),
+        )
         // Terminal routes
         .route(
             "/api/terminals/{terminal_id}",
@@ -147,6 +201,38 @@ pub fn router() -> Router<ApiState> {
         .route("/writer/versions", get(writer::list_versions))
         .route("/writer/version", get(writer::get_version))
         .route("/writer/overlay/dismiss", post(writer::dismiss_overlay))
+        .route(
+            "/api/writer/documents/{path}/undo",
+            post(writer::undo_document),
+        )
+        .route(
+            "/api/writer/documents/{path}/redo",
+            post(writer::redo_document),
+        )
+        .route(
+            "/api/writer/documents/{path}/proposals",
+            get(writer::list_proposals),
+        )
+        .route(
+            "/api/writer/documents/{path}/proposals/{overlay_id}/accept",
+            post(writer::accept_proposal),
+        )
+        .route(
+            "/api/writer/documents/{path}/proposals/{overlay_id}/reject",
+            post(writer::reject_proposal),
+        )
+        .route(
+            "/api/writer/documents/{path}/proposals/{overlay_id}/repropose",
+            post(writer::repropose_proposal),
+        )
+        .route(
+            "/api/writer/documents/{path}/review-mode",
+            patch(writer::set_review_mode),
+        )
+        .route(
+            "/api/writer/documents/{path}/lock-strictness",
+            patch(writer::set_lock_strictness),
+        )
         // Conductor API routes
         .route("/conductor/execute", post(conductor::execute_task))
         .route("/conductor/runs", get(conductor::list_runs))
@@ -155,6 +241,75 @@ pub fn router() -> Router<ApiState> {
             "/conductor/runs/{run_id}/state",
             get(conductor::get_run_state),
         )
+        .route(
+            "/conductor/runs/{run_id}/audit",
+            get(run_audit::export_run_audit),
+        )
+        .route("/api/conductor/refine", post(conductor::refine_objective))
+        .route(
+            "/api/conductor/runs/{run_id}/bundle",
+            get(run_bundle::export_run_bundle),
+        )
+        .route(
+            "/api/conductor/runs/import",
+            post(run_bundle::import_run_bundle),
+        )
+        // Admin routes
+        .route("/api/admin/projections", get(admin::list_projections))
+        .route(
+            "/api/admin/projections/{name}",
+            get(admin::get_projection_status),
+        )
+        .route(
+            "/api/admin/projections/{name}/rebuild",
+            post(admin::rebuild_projection),
+        )
+        .route("/api/admin/events/{seq}/redact", post(admin::redact_event))
+        .route(
+            "/api/admin/secret-scan/reload",
+            post(admin::reload_secret_scan_rules),
+        )
+        .route("/api/admin/replay", post(replay::replay_bundle))
+        // LLM context traces
+        .route(
+            "/api/context-traces",
+            get(context_traces::get_context_traces),
+        )
+        // Search
+        .route("/api/search", get(search::search))
+        // Research tasks
+        .route("/api/research/tasks", get(research::list_tasks))
+        .route("/api/research/tasks/{task_id}", get(research::get_task))
+        .route(
+            "/api/research/tasks/{task_id}/rerun",
+            post(research::rerun_task),
+        )
+        .route(
+            "/api/research/tasks/{task_id}/send-to-writer",
+            post(research::send_to_writer),
+        )
+        .route("/api/research/batch", post(research::submit_batch))
+        .route("/api/research/batch/{batch_id}", get(research::get_batch))
+        // Learned preferences
+        .route(
+            "/api/preferences",
+            get(preferences::list_preferences).patch(preferences::update_preference_learning),
+        )
+        .route(
+            "/api/preferences/{preference_id}",
+            delete(preferences::delete_preference),
+        )
+        // Usage
+        .route("/api/usage/disk", get(usage::get_disk_usage))
+        .route("/api/activity", get(usage::get_activity))
+        // Server clock
+        .route("/api/time", get(time::get_time))
+        .route("/api/version", get(get_version))
+}
+
+/// Build of this sandbox; the UI reloads when its schema version differs.
+pub async fn get_version() -> impl IntoResponse {
+    Json(shared_types::BuildVersion::current())
 }
 
 /// Health check endpoint
@@ -179,3 +334,39 @@ pub async fn health_check(State(_state): State<ApiState>) -> impl IntoResponse {
         })),
     )
 }
+
+/// Readiness probe: the sandbox can serve requests once its event store
+/// accepts writes. The body carries the store's health check.
+pub async fn readiness_check(State(state): State<ApiState>) -> impl IntoResponse {
+    let probe = tokio::time::timeout(
+        std::time::Duration::from_secs(2),
+        crate::actors::event_store::health_check(&state.app_state.event_store()),
+    )
+    .await;
+
+    match probe {
+        Ok(Ok(health)) if health.writable => (
+            StatusCode::OK,
+            Json(json!({ "status": "ready", "event_store": health })),
+        ),
+        Ok(Ok(health)) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({
+                "status": "not_ready",
+                "reason": format!(
+                    "EventStore is not writable: {}",
+                    health.error.as_deref().unwrap_or("unknown")
+                ),
+                "event_store": health,
+            })),
+        ),
+        Ok(Err(e)) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "status": "not_ready", "reason": format!("RPC error: {e}") })),
+        ),
+        Err(_) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "status": "not_ready", "reason": "EventStore did not respond" })),
+        ),
+    }
+}
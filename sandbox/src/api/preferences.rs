@@ -0,0 +1,90 @@
+//! Learned preference API endpoints
+//!
+//! Lets users review and delete the preferences learned from their prompts,
+//! and switch learning off. Theme and model settings live in `user.rs`.
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Deserialize;
+use shared_types::{ErrorCode, LearnedPreferencesResponse, UpdatePreferenceLearningRequest};
+
+use super::error::api_error;
+use super::ApiState;
+use crate::preferences::{self, UserPreferences, DEFAULT_PREFERENCE_USER};
+
+#[derive(Debug, Default, Deserialize)]
+pub struct PreferencesQuery {
+    pub user_id: Option<String>,
+}
+
+impl PreferencesQuery {
+    fn user_id(&self) -> &str {
+        self.user_id
+            .as_deref()
+            .filter(|id| !id.trim().is_empty())
+            .unwrap_or(DEFAULT_PREFERENCE_USER)
+    }
+}
+
+fn response(prefs: UserPreferences) -> Response {
+    let mut preferences = prefs.preferences;
+    preferences.reverse();
+    (
+        StatusCode::OK,
+        Json(LearnedPreferencesResponse {
+            learning_enabled: prefs.learning_enabled,
+            preferences,
+        }),
+    )
+        .into_response()
+}
+
+/// Learned preferences for a user, newest first.
+pub async fn list_preferences(
+    State(state): State<ApiState>,
+    Query(query): Query<PreferencesQuery>,
+) -> Response {
+    match preferences::load(&state.app_state.event_store(), query.user_id()).await {
+        Ok(prefs) => response(prefs),
+        Err(e) => api_error(ErrorCode::InternalError, e),
+    }
+}
+
+/// Turn preference learning on or off. Existing preferences are kept.
+pub async fn update_preference_learning(
+    State(state): State<ApiState>,
+    Query(query): Query<PreferencesQuery>,
+    Json(request): Json<UpdatePreferenceLearningRequest>,
+) -> Response {
+    match preferences::set_learning_enabled(
+        &state.app_state.event_store(),
+        state.app_state.disk_usage().root(),
+        query.user_id(),
+        request.learning_enabled,
+    )
+    .await
+    {
+        Ok(prefs) => response(prefs),
+        Err(e) => api_error(ErrorCode::InternalError, e),
+    }
+}
+
+/// Forget one learned preference.
+pub async fn delete_preference(
+    State(state): State<ApiState>,
+    Path(preference_id): Path<String>,
+    Query(query): Query<PreferencesQuery>,
+) -> Response {
+    let event_store = state.app_state.event_store();
+    let root = state.app_state.disk_usage().root();
+    match preferences::delete(&event_store, root, query.user_id(), &preference_id).await {
+        Ok(true) => match preferences::load(&event_store, query.user_id()).await {
+            Ok(prefs) => response(prefs),
+            Err(e) => api_error(ErrorCode::InternalError, e),
+        },
+        Ok(false) => api_error(ErrorCode::NotFound, "Preference not found"),
+        Err(e) => api_error(ErrorCode::InternalError, e),
+    }
+}
@@ -0,0 +1,287 @@
+//! Event replay for frontend development.
+//!
+//! `POST /api/admin/replay?speed=<factor>` takes a run bundle from the export
+//! endpoint and re-publishes its events on the EventBus, keeping the original
+//! gaps between events divided by `speed`. Nothing is appended to the event
+//! store. Bus events carry `"replayed": true` in their payload so projections
+//! skip them, and WebSocket sessions are sent the same messages the live
+//! forwarders send. The desktops and actors a bundle names need not exist
+//! here: delivery is by id string.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::time::Duration;
+
+use axum::body::Body;
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use ractor::ActorRef;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use shared_types::{ErrorCode, Event};
+use zip::result::ZipError;
+use zip::ZipArchive;
+
+use crate::actors::event_bus::{Event as BusEvent, EventBusMsg, EventType};
+use crate::api::error::api_error;
+use crate::api::run_bundle::{read_manifest, spool_body, temp_bundle_path, EVENTS_FILE};
+use crate::api::websocket::{forward_event, PatchOrder, WsSessions};
+use crate::api::ApiState;
+
+pub const DEFAULT_REPLAY_SPEED: f64 = 1.0;
+pub const MAX_REPLAY_SPEED: f64 = 1000.0;
+
+/// Longest pause between two replayed events after scaling, so an idle
+/// stretch in the source run does not stall the replay.
+pub const MAX_REPLAY_GAP: Duration = Duration::from_secs(5);
+
+fn default_speed() -> f64 {
+    DEFAULT_REPLAY_SPEED
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReplayParams {
+    /// Playback speed factor; 2.0 replays twice as fast as recorded.
+    #[serde(default = "default_speed")]
+    pub speed: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayResponse {
+    pub replay_id: String,
+    pub source_run_id: String,
+    pub events: u64,
+    pub speed: f64,
+    /// Expected wall-clock length of the replay.
+    pub duration_ms: u64,
+}
+
+/// Start replaying an exported run bundle. Responds once the bundle is
+/// parsed; the events are published in the background.
+pub async fn replay_bundle(
+    State(state): State<ApiState>,
+    Query(params): Query<ReplayParams>,
+    body: Body,
+) -> impl IntoResponse {
+    if !params.speed.is_finite() || params.speed <= 0.0 || params.speed > MAX_REPLAY_SPEED {
+        return api_error(
+            ErrorCode::InvalidRequest,
+            format!("speed must be greater than 0 and at most {MAX_REPLAY_SPEED}"),
+        );
+    }
+
+    let path = temp_bundle_path();
+    let read = match spool_body(body, &path).await {
+        Ok(()) => {
+            let path = path.clone();
+            tokio::task::spawn_blocking(move || read_bundle_events(&path))
+                .await
+                .unwrap_or_else(|err| Err(api_error(ErrorCode::InternalError, err.to_string())))
+        }
+        Err(response) => Err(response),
+    };
+    let _ = std::fs::remove_file(&path);
+    let (source_run_id, mut events) = match read {
+        Ok(read) => read,
+        Err(response) => return response,
+    };
+
+    let event_bus = match state.app_state.event_bus().await {
+        Ok(event_bus) => event_bus,
+        Err(err) => return api_error(ErrorCode::InternalError, err),
+    };
+
+    events.sort_by(|a, b| a.canonical_cmp(b));
+    let offsets = replay_offsets(&events, params.speed);
+    let response = ReplayResponse {
+        replay_id: ulid::Ulid::new().to_string(),
+        source_run_id,
+        events: events.len() as u64,
+        speed: params.speed,
+        duration_ms: offsets.last().map_or(0, |offset| offset.as_millis() as u64),
+    };
+
+    tracing::info!(
+        replay_id = %response.replay_id,
+        source_run_id = %response.source_run_id,
+        events = response.events,
+        speed = response.speed,
+        "Replaying run bundle"
+    );
+    tokio::spawn(run_replay(
+        event_bus,
+        state.ws_sessions.clone(),
+        state.patch_order.clone(),
+        response.replay_id.clone(),
+        events,
+        offsets,
+    ));
+
+    (StatusCode::ACCEPTED, Json(json!(response))).into_response()
+}
+
+fn read_bundle_events(
+    path: &std::path::Path,
+) -> Result<(String, Vec<Event>), axum::response::Response> {
+    let invalid = |message: String| api_error(ErrorCode::InvalidRequest, message);
+    let file =
+        File::open(path).map_err(|err| api_error(ErrorCode::InternalError, err.to_string()))?;
+    let mut archive = ZipArchive::new(BufReader::new(file))
+        .map_err(|err| invalid(format!("invalid bundle: {err}")))?;
+    let manifest = read_manifest(&mut archive)?;
+
+    let mut events = Vec::new();
+    let reader = match archive.by_name(EVENTS_FILE) {
+        Ok(reader) => reader,
+        Err(ZipError::FileNotFound) => return Ok((manifest.run_id, events)),
+        Err(err) => return Err(invalid(format!("invalid bundle: {err}"))),
+    };
+    for line in BufReader::new(reader).lines() {
+        let line = line.map_err(|err| invalid(format!("invalid {EVENTS_FILE}: {err}")))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let event: Event = serde_json::from_str(&line)
+            .map_err(|err| invalid(format!("invalid {EVENTS_FILE} line: {err}")))?;
+        events.push(event);
+    }
+    Ok((manifest.run_id, events))
+}
+
+/// When each event is due, relative to the start of the replay: the recorded
+/// gap to the previous event divided by `speed`, capped at `MAX_REPLAY_GAP`.
+pub fn replay_offsets(events: &[Event], speed: f64) -> Vec<Duration> {
+    let mut offset = Duration::ZERO;
+    let mut previous: Option<chrono::DateTime<chrono::Utc>> = None;
+    events
+        .iter()
+        .map(|event| {
+            if let Some(previous) = previous {
+                let gap = (event.stored_at - previous)
+                    .to_std()
+                    .unwrap_or(Duration::ZERO);
+                offset += gap.div_f64(speed).min(MAX_REPLAY_GAP);
+            }
+            previous = Some(event.stored_at);
+            offset
+        })
+        .collect()
+}
+
+/// Bus event for a replayed store event, tagged so projections ignore it.
+pub fn replay_bus_event(event: &Event, replay_id: &str) -> Result<BusEvent, serde_json::Error> {
+    let payload = match event.payload.clone() {
+        serde_json::Value::Object(mut obj) => {
+            obj.insert("replayed".to_string(), json!(true));
+            obj.insert("replay_id".to_string(), json!(replay_id));
+            serde_json::Value::Object(obj)
+        }
+        other => json!({ "value": other, "replayed": true, "replay_id": replay_id }),
+    };
+    BusEvent::new(
+        EventType::Custom(event.event_type.clone()),
+        event.event_type.clone(),
+        payload,
+        event.actor_id.0.clone(),
+    )
+}
+
+async fn run_replay(
+    event_bus: ActorRef<EventBusMsg>,
+    sessions: WsSessions,
+    patch_order: PatchOrder,
+    replay_id: String,
+    events: Vec<Event>,
+    offsets: Vec<Duration>,
+) {
+    let started = tokio::time::Instant::now();
+    for (event, offset) in events.iter().zip(offsets) {
+        tokio::time::sleep_until(started + offset).await;
+
+        match replay_bus_event(event, &replay_id) {
+            Ok(bus_event) => {
+                if let Err(err) = ractor::cast!(
+                    event_bus,
+                    EventBusMsg::Publish {
+                        event: bus_event,
+                        persist: false,
+                    }
+                ) {
+                    tracing::warn!(replay_id = %replay_id, error = %err, "Replay publish failed");
+                    return;
+                }
+            }
+            Err(err) => {
+                tracing::warn!(replay_id = %replay_id, seq = event.seq, error = %err, "Skipping unreplayable event");
+            }
+        }
+        forward_event(&sessions, &patch_order, event).await;
+    }
+    tracing::info!(replay_id = %replay_id, events = events.len(), "Replay finished");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use shared_types::ActorId;
+
+    fn event_at(seq: i64, millis: i64, payload: serde_json::Value) -> Event {
+        let stored_at = Utc
+            .timestamp_millis_opt(1_700_000_000_000 + millis)
+            .unwrap();
+        Event {
+            seq,
+            event_id: format!("evt-{seq}"),
+            timestamp: stored_at,
+            stored_at,
+            produced_at: None,
+            actor_id: ActorId("writer:ghost".to_string()),
+            actor_seq: seq,
+            event_type: "writer.run.status".to_string(),
+            payload,
+            user_id: "user-1".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_replay_offsets_scale_and_cap_gaps() {
+        let events = vec![
+            event_at(1, 0, json!({})),
+            event_at(2, 1_000, json!({})),
+            event_at(3, 1_000, json!({})),
+            event_at(4, 601_000, json!({})),
+        ];
+
+        let offsets = replay_offsets(&events, 4.0);
+
+        assert_eq!(
+            offsets,
+            vec![
+                Duration::ZERO,
+                Duration::from_millis(250),
+                Duration::from_millis(250),
+                Duration::from_millis(250) + MAX_REPLAY_GAP,
+            ]
+        );
+        assert!(replay_offsets(&[], 1.0).is_empty());
+    }
+
+    #[test]
+    fn test_replay_bus_event_is_tagged_replayed() {
+        let event = event_at(1, 0, json!({ "run_id": "run-1" }));
+        let bus_event = replay_bus_event(&event, "replay-1").unwrap();
+
+        assert_eq!(bus_event.topic, "writer.run.status");
+        assert_eq!(bus_event.source, "writer:ghost");
+        assert_eq!(bus_event.payload["run_id"], "run-1");
+        assert_eq!(bus_event.payload["replay_id"], "replay-1");
+        assert!(bus_event.is_replayed());
+
+        let scalar = replay_bus_event(&event_at(2, 0, json!("raw")), "replay-1").unwrap();
+        assert_eq!(scalar.payload["value"], "raw");
+        assert!(scalar.is_replayed());
+    }
+}
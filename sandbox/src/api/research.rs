@@ -0,0 +1,627 @@
+//! Research API endpoints
+//!
+//! Research tasks are folded from `research.task.*` events into a
+//! [`ResearchTaskIndex`], which folds only the events appended since the last
+//! request. A task is listed on the desktop it was started from, or on its
+//! conductor run's desktop. Besides listing and inspecting them for the
+//! Research app, a finished task can be re-run with a modified objective or
+//! appended to a Writer run document as its own section. Several objectives
+//! can be submitted at once as a batch; its tasks share one round budget and
+//! carry the batch id in their started events. A rerun or batch gets a
+//! researcher of its own, stopped once its work is done.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use ractor::ActorRef;
+use serde::Deserialize;
+use shared_types::{
+    ErrorCode, ResearchTaskCompletedPayload, ResearchTaskDetail, ResearchTaskFailedPayload,
+    ResearchTaskProgress, ResearchTaskStartedPayload, ResearchTaskStatus, ResearchTaskSummary,
+    EVENT_TOPIC_CONDUCTOR_RUN_STARTED, EVENT_TOPIC_RESEARCH_TASK_COMPLETED,
+    EVENT_TOPIC_RESEARCH_TASK_FAILED, EVENT_TOPIC_RESEARCH_TASK_STARTED,
+    EVENT_TOPIC_WORKER_TASK_PROGRESS,
+};
+use tokio::sync::{Mutex, MutexGuard};
+
+use super::error::api_error;
+use super::writer::{extract_run_id_from_document_path, map_writer_actor_error};
+use super::ApiState;
+use crate::actors::event_store::{
+    get_event_by_seq, get_recent_events, query_filtered, EventQuery, EventStoreMsg, MAX_QUERY_PAGE,
+};
+use crate::actors::researcher::ResearcherMsg;
+use crate::actors::writer::{WriterMsg, WriterSource};
+
+const DEFAULT_LIMIT: usize = 20;
+const MAX_LIMIT: usize = 100;
+const PAGE_SIZE: i64 = 1000;
+/// Upper bound on progress lines returned with a task detail.
+const MAX_PROGRESS_LINES: usize = 200;
+/// Upper bound on queries in one batch.
+const MAX_BATCH_QUERIES: usize = 10;
+/// Round budget of a batch that does not name one.
+const DEFAULT_BATCH_ROUNDS: u16 = 30;
+
+#[derive(Debug, Deserialize)]
+pub struct ResearchTasksQuery {
+    pub limit: Option<usize>,
+    /// Only tasks listed on this desktop.
+    pub desktop_id: Option<String>,
+}
+
+/// Task state folded from its events.
+#[derive(Clone)]
+struct ResearchTaskRecord {
+    summary: ResearchTaskSummary,
+    /// Seq of the `research.task.completed` event, read back for the result.
+    completed_seq: Option<i64>,
+    error: Option<String>,
+}
+
+/// Research tasks folded from the event log. Each request folds only the
+/// events appended since the previous one.
+#[derive(Clone, Default)]
+pub struct ResearchTaskIndex {
+    inner: Arc<Mutex<FoldedTasks>>,
+}
+
+#[derive(Default)]
+struct FoldedTasks {
+    /// Seq of the last event folded.
+    through_seq: i64,
+    /// Oldest first.
+    tasks: Vec<ResearchTaskRecord>,
+    /// Position in `tasks` by task id.
+    positions: HashMap<String, usize>,
+    /// Desktop of each conductor run, from `conductor.run.started`.
+    run_desktops: HashMap<String, String>,
+}
+
+impl ResearchTaskIndex {
+    /// Fold the events appended since the last call and return the tasks.
+    async fn refresh(
+        &self,
+        event_store: &ActorRef<EventStoreMsg>,
+    ) -> Result<MutexGuard<'_, FoldedTasks>, String> {
+        let mut folded = self.inner.lock().await;
+        loop {
+            let page = query_filtered(
+                event_store,
+                EventQuery {
+                    event_type_prefixes: vec![
+                        "research.task.".to_string(),
+                        EVENT_TOPIC_CONDUCTOR_RUN_STARTED.to_string(),
+                    ],
+                    limit: MAX_QUERY_PAGE,
+                    cursor: Some(folded.through_seq),
+                    ..EventQuery::default()
+                },
+            )
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| e.to_string())?;
+            for event in page.events {
+                folded.through_seq = event.seq;
+                folded.apply(event);
+            }
+            if page.next_cursor.is_none() {
+                return Ok(folded);
+            }
+        }
+    }
+}
+
+impl FoldedTasks {
+    fn task(&self, task_id: &str) -> Option<&ResearchTaskRecord> {
+        self.tasks.get(*self.positions.get(task_id)?)
+    }
+
+    fn task_mut(&mut self, task_id: &str) -> Option<&mut ResearchTaskRecord> {
+        let position = *self.positions.get(task_id)?;
+        self.tasks.get_mut(position)
+    }
+
+    fn apply(&mut self, event: shared_types::Event) {
+        match event.event_type.as_str() {
+            EVENT_TOPIC_CONDUCTOR_RUN_STARTED => {
+                let field = |pointer: &str| event.payload.pointer(pointer)?.as_str();
+                if let (Some(run_id), Some(desktop_id)) =
+                    (field("/run_id"), field("/data/desktop_id"))
+                {
+                    self.run_desktops
+                        .insert(run_id.to_string(), desktop_id.to_string());
+                }
+            }
+            EVENT_TOPIC_RESEARCH_TASK_STARTED => {
+                let Ok(started) =
+                    serde_json::from_value::<ResearchTaskStartedPayload>(event.payload)
+                else {
+                    return;
+                };
+                let desktop_id = started.desktop_id.or_else(|| {
+                    let run_id = started.run_id.as_ref()?;
+                    self.run_desktops.get(run_id).cloned()
+                });
+                self.positions
+                    .insert(started.task_id.clone(), self.tasks.len());
+                self.tasks.push(ResearchTaskRecord {
+                    summary: ResearchTaskSummary {
+                        task_id: started.task_id,
+                        objective: started.objective,
+                        status: ResearchTaskStatus::Running,
+                        run_id: started.run_id,
+                        rerun_of: started.rerun_of,
+                        batch_id: started.batch_id,
+                        desktop_id,
+                        started_seq: event.seq,
+                        started_at: event.timestamp,
+                        finished_at: None,
+                        citation_count: 0,
+                        provider_used: None,
+                    },
+                    completed_seq: None,
+                    error: None,
+                });
+            }
+            EVENT_TOPIC_RESEARCH_TASK_COMPLETED => {
+                let Ok(completed) =
+                    serde_json::from_value::<ResearchTaskCompletedPayload>(event.payload)
+                else {
+                    return;
+                };
+                if let Some(task) = self.task_mut(&completed.task_id) {
+                    task.summary.status = ResearchTaskStatus::Completed;
+                    task.summary.finished_at = Some(event.timestamp);
+                    task.summary.citation_count = completed.citations.len();
+                    task.summary.provider_used = completed.provider_used;
+                    task.completed_seq = Some(event.seq);
+                }
+            }
+            EVENT_TOPIC_RESEARCH_TASK_FAILED => {
+                let Ok(failed) = serde_json::from_value::<ResearchTaskFailedPayload>(event.payload)
+                else {
+                    return;
+                };
+                if let Some(task) = self.task_mut(&failed.task_id) {
+                    task.summary.status = ResearchTaskStatus::Failed;
+                    task.summary.finished_at = Some(event.timestamp);
+                    task.error = Some(failed.error);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// All events under `prefix` after `since_seq`, oldest first.
+async fn load_events(
+    event_store: &ActorRef<EventStoreMsg>,
+    prefix: &str,
+    mut since_seq: i64,
+) -> Result<Vec<shared_types::Event>, String> {
+    let mut events = Vec::new();
+    loop {
+        let page = get_recent_events(
+            event_store,
+            since_seq,
+            PAGE_SIZE,
+            Some(prefix.to_string()),
+            None,
+            None,
+        )
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+        let full = page.len() as i64 == PAGE_SIZE;
+        if let Some(last) = page.last() {
+            since_seq = last.seq;
+        }
+        events.extend(page);
+        if !full {
+            return Ok(events);
+        }
+    }
+}
+
+async fn load_tasks(
+    state: &ApiState,
+) -> Result<MutexGuard<'_, FoldedTasks>, axum::response::Response> {
+    state
+        .research_tasks
+        .refresh(&state.app_state.event_store())
+        .await
+        .map_err(|e| api_error(ErrorCode::InternalError, e))
+}
+
+async fn find_task(
+    state: &ApiState,
+    task_id: &str,
+) -> Result<ResearchTaskRecord, axum::response::Response> {
+    load_tasks(state)
+        .await?
+        .task(task_id)
+        .cloned()
+        .ok_or_else(|| {
+            api_error(
+                ErrorCode::NotFound,
+                format!("research task not found: {task_id}"),
+            )
+        })
+}
+
+/// Result of a completed task, read back from its completed event.
+async fn load_result(
+    state: &ApiState,
+    task: &ResearchTaskRecord,
+) -> Result<Option<ResearchTaskCompletedPayload>, String> {
+    let Some(seq) = task.completed_seq else {
+        return Ok(None);
+    };
+    let event = get_event_by_seq(&state.app_state.event_store(), seq)
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+    Ok(event.and_then(|event| serde_json::from_value(event.payload).ok()))
+}
+
+/// Progress lines of a task, read from the events after it started.
+async fn load_progress(
+    state: &ApiState,
+    task: &ResearchTaskSummary,
+) -> Result<Vec<ResearchTaskProgress>, String> {
+    let events = load_events(
+        &state.app_state.event_store(),
+        EVENT_TOPIC_WORKER_TASK_PROGRESS,
+        task.started_seq,
+    )
+    .await?;
+    let mut progress: Vec<ResearchTaskProgress> = events
+        .into_iter()
+        .filter(|event| {
+            event.payload.get("task_id").and_then(|v| v.as_str()) == Some(task.task_id.as_str())
+        })
+        .map(|event| {
+            let field = |key: &str| {
+                event
+                    .payload
+                    .get(key)
+                    .and_then(|v| v.as_str())
+                    .map(ToString::to_string)
+            };
+            ResearchTaskProgress {
+                seq: event.seq,
+                phase: field("phase").unwrap_or_default(),
+                message: field("message").unwrap_or_default(),
+                provider: field("provider"),
+                timestamp: field("timestamp").unwrap_or_else(|| event.timestamp.to_rfc3339()),
+            }
+        })
+        .collect();
+    if progress.len() > MAX_PROGRESS_LINES {
+        progress.drain(..progress.len() - MAX_PROGRESS_LINES);
+    }
+    Ok(progress)
+}
+
+/// GET /api/research/tasks - recent research tasks, newest first
+pub async fn list_tasks(
+    State(state): State<ApiState>,
+    Query(query): Query<ResearchTasksQuery>,
+) -> impl IntoResponse {
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    match load_tasks(&state).await {
+        Ok(folded) => {
+            let tasks: Vec<ResearchTaskSummary> = folded
+                .tasks
+                .iter()
+                .rev()
+                .filter(|task| {
+                    query.desktop_id.is_none() || task.summary.desktop_id == query.desktop_id
+                })
+                .take(limit)
+                .map(|task| task.summary.clone())
+                .collect();
+            (StatusCode::OK, Json(tasks)).into_response()
+        }
+        Err(response) => response,
+    }
+}
+
+/// GET /api/research/tasks/{task_id} - task result, citations and progress
+pub async fn get_task(
+    State(state): State<ApiState>,
+    Path(task_id): Path<String>,
+) -> impl IntoResponse {
+    let task = match find_task(&state, &task_id).await {
+        Ok(task) => task,
+        Err(response) => return response,
+    };
+    let progress = match load_progress(&state, &task.summary).await {
+        Ok(progress) => progress,
+        Err(e) => return api_error(ErrorCode::InternalError, e),
+    };
+    let completed = match load_result(&state, &task).await {
+        Ok(completed) => completed,
+        Err(e) => return api_error(ErrorCode::InternalError, e),
+    };
+    let (summary, model_used, citations, provider_calls) = match completed {
+        Some(completed) => (
+            Some(completed.summary),
+            completed.model_used,
+            completed.citations,
+            completed.provider_calls,
+        ),
+        None => (None, None, Vec::new(), Vec::new()),
+    };
+    (
+        StatusCode::OK,
+        Json(ResearchTaskDetail {
+            task: task.summary,
+            summary,
+            model_used,
+            citations,
+            provider_calls,
+            error: task.error,
+            progress,
+        }),
+    )
+        .into_response()
+}
+
+/// Hand `message` to a researcher of its own and stop the researcher once
+/// the task finishes.
+fn run_on_researcher(
+    researcher: &ActorRef<ResearcherMsg>,
+    message: ResearcherMsg,
+) -> Result<(), axum::response::Response> {
+    researcher
+        .send_message(message)
+        .map_err(|e| api_error(ErrorCode::ServiceUnavailable, e.to_string()))?;
+    if researcher.drain().is_err() {
+        researcher.stop(None);
+    }
+    Ok(())
+}
+
+/// POST /api/research/tasks/{task_id}/rerun - start a new task from an old one
+pub async fn rerun_task(
+    State(state): State<ApiState>,
+    Path(task_id): Path<String>,
+    Json(req): Json<shared_types::ResearchRerunRequest>,
+) -> impl IntoResponse {
+    let original = match find_task(&state, &task_id).await {
+        Ok(task) => task.summary,
+        Err(response) => return response,
+    };
+    let objective = req
+        .objective
+        .map(|objective| objective.trim().to_string())
+        .filter(|objective| !objective.is_empty())
+        .unwrap_or(original.objective);
+
+    let new_task_id = ulid::Ulid::new().to_string();
+    let researcher = match state
+        .app_state
+        .get_or_create_researcher(format!("research-app:{new_task_id}"), "system".to_string())
+        .await
+    {
+        Ok(actor) => actor,
+        Err(e) => {
+            return api_error(
+                ErrorCode::ServiceUnavailable,
+                format!("Researcher unavailable: {e}"),
+            )
+        }
+    };
+    if let Err(response) = run_on_researcher(
+        &researcher,
+        ResearcherMsg::RunResearchTask {
+            task_id: new_task_id.clone(),
+            objective,
+            rerun_of: Some(task_id),
+            max_rounds: req.max_rounds,
+            run_id: original.run_id,
+            desktop_id: req.desktop_id.or(original.desktop_id),
+        },
+    ) {
+        return response;
+    }
+
+    (
+        StatusCode::ACCEPTED,
+        Json(shared_types::ResearchRerunResponse {
+            task_id: new_task_id,
+        }),
+    )
+        .into_response()
+}
+
+/// POST /api/research/batch - start one task per query, run concurrently
+/// under a shared round budget
+pub async fn submit_batch(
+    State(state): State<ApiState>,
+    Json(req): Json<shared_types::ResearchBatchRequest>,
+) -> impl IntoResponse {
+    let queries: Vec<String> = req
+        .queries
+        .iter()
+        .map(|query| query.trim().to_string())
+        .filter(|query| !query.is_empty())
+        .collect();
+    if queries.is_empty() {
+        return api_error(ErrorCode::InvalidRequest, "queries must not be empty");
+    }
+    if queries.len() > MAX_BATCH_QUERIES {
+        return api_error(
+            ErrorCode::InvalidRequest,
+            format!("a batch holds at most {MAX_BATCH_QUERIES} queries"),
+        );
+    }
+
+    let batch_id = ulid::Ulid::new().to_string();
+    let researcher = match state
+        .app_state
+        .get_or_create_researcher(format!("research-batch:{batch_id}"), "system".to_string())
+        .await
+    {
+        Ok(actor) => actor,
+        Err(e) => {
+            return api_error(
+                ErrorCode::ServiceUnavailable,
+                format!("Researcher unavailable: {e}"),
+            )
+        }
+    };
+    let tasks: Vec<(String, String)> = queries
+        .into_iter()
+        .map(|query| (ulid::Ulid::new().to_string(), query))
+        .collect();
+    let task_ids = tasks.iter().map(|(task_id, _)| task_id.clone()).collect();
+    if let Err(response) = run_on_researcher(
+        &researcher,
+        ResearcherMsg::RunResearchBatch {
+            batch_id: batch_id.clone(),
+            tasks,
+            max_rounds: req.max_rounds.unwrap_or(DEFAULT_BATCH_ROUNDS),
+            desktop_id: req.desktop_id,
+        },
+    ) {
+        return response;
+    }
+
+    (
+        StatusCode::ACCEPTED,
+        Json(shared_types::ResearchBatchResponse { batch_id, task_ids }),
+    )
+        .into_response()
+}
+
+/// GET /api/research/batch/{batch_id} - the batch's tasks in submission order
+pub async fn get_batch(
+    State(state): State<ApiState>,
+    Path(batch_id): Path<String>,
+) -> impl IntoResponse {
+    let tasks: Vec<ResearchTaskSummary> = match load_tasks(&state).await {
+        Ok(folded) => folded
+            .tasks
+            .iter()
+            .filter(|task| task.summary.batch_id.as_deref() == Some(batch_id.as_str()))
+            .map(|task| task.summary.clone())
+            .collect(),
+        Err(response) => return response,
+    };
+    if tasks.is_empty() {
+        return api_error(
+            ErrorCode::NotFound,
+            format!("research batch not found: {batch_id}"),
+        );
+    }
+    (
+        StatusCode::OK,
+        Json(shared_types::ResearchBatchDetail { batch_id, tasks }),
+    )
+        .into_response()
+}
+
+/// Markdown section appended to a Writer document for a research result.
+fn writer_section(objective: &str, result: &ResearchTaskCompletedPayload) -> String {
+    let mut section = format!(
+        "## Research: {}\n\n{}\n",
+        objective.trim(),
+        result.summary.trim()
+    );
+    if !result.citations.is_empty() {
+        section.push_str("\n### Sources\n\n");
+        for (index, citation) in result.citations.iter().enumerate() {
+            let title = if citation.title.trim().is_empty() {
+                citation.url.as_str()
+            } else {
+                citation.title.trim()
+            };
+            section.push_str(&format!("{}. [{}]({})\n", index + 1, title, citation.url));
+        }
+    }
+    section
+}
+
+/// POST /api/research/tasks/{task_id}/send-to-writer - append the result to
+/// a run document as a `research:{task_id}` section
+pub async fn send_to_writer(
+    State(state): State<ApiState>,
+    Path(task_id): Path<String>,
+    Json(req): Json<shared_types::ResearchSendToWriterRequest>,
+) -> impl IntoResponse {
+    let task = match find_task(&state, &task_id).await {
+        Ok(task) => task,
+        Err(response) => return response,
+    };
+    let completed = match load_result(&state, &task).await {
+        Ok(completed) => completed,
+        Err(e) => return api_error(ErrorCode::InternalError, e),
+    };
+    let Some(result) = completed.as_ref() else {
+        return api_error(
+            ErrorCode::Conflict,
+            format!("research task {task_id} has no result yet"),
+        );
+    };
+
+    let Some(path) = req
+        .path
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty())
+        .or_else(|| {
+            task.summary
+                .run_id
+                .as_ref()
+                .map(|run_id| format!("conductor/runs/{run_id}/draft.md"))
+        })
+    else {
+        return api_error(
+            ErrorCode::InvalidRequest,
+            "path is required for tasks without a run",
+        );
+    };
+    let Some(run_id) = extract_run_id_from_document_path(&path) else {
+        return api_error(
+            ErrorCode::InvalidRequest,
+            "path must be a run document: conductor/runs/{run_id}/draft.md",
+        );
+    };
+
+    let writer = match state.app_state.ensure_run_writer(&run_id).await {
+        Ok(actor) => actor,
+        Err(e) => {
+            return api_error(
+                ErrorCode::ServiceUnavailable,
+                format!("Writer unavailable: {e}"),
+            )
+        }
+    };
+    let section_id = format!("research:{task_id}");
+    match ractor::call!(writer, |reply| WriterMsg::ApplyText {
+        run_id,
+        section_id: section_id.clone(),
+        source: WriterSource::Researcher,
+        content: writer_section(&task.summary.objective, result),
+        proposal: false,
+        reply,
+    }) {
+        Ok(Ok(revision)) => (
+            StatusCode::OK,
+            Json(shared_types::ResearchSendToWriterResponse {
+                path,
+                section_id,
+                revision,
+            }),
+        )
+            .into_response(),
+        Ok(Err(err)) => map_writer_actor_error(err),
+        Err(e) => api_error(ErrorCode::ServiceUnavailable, e.to_string()),
+    }
+}
@@ -0,0 +1,257 @@
+//! Run audit trail: everything recorded about one conductor run, exported
+//! for compliance review.
+//!
+//! `GET /conductor/runs/{run_id}/audit` returns a [`RunAudit`] as one JSON
+//! document, or with `?format=ndjson` one [`AuditRecord`] per line: the
+//! header, then decisions, capability calls, artifacts, escalations and
+//! events, each section in order. [`AUDIT_SCHEMA_VERSION`] covers both shapes.
+//!
+//! Decisions, calls and artifacts come from the conductor's run state (or the
+//! state saved by a bundle import); escalations and events from the event
+//! log. Event payloads are filtered the way a run bundle's are: events an
+//! admin redacted are blanked and sensitive keys are masked.
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use shared_types::{
+    ConductorArtifact, ConductorCapabilityCall, ConductorDecision, ConductorRunStatus, ErrorCode,
+    Event, EVENT_TOPIC_WORKER_SIGNAL_ESCALATION_REQUESTED,
+};
+
+use super::error::api_error;
+use super::run_bundle::{fetch_run_state, redacted_seqs};
+use super::run_observability::fetch_run_events;
+use super::ApiState;
+use crate::observability::llm_trace::redact_sensitive_keys;
+
+pub const AUDIT_SCHEMA_VERSION: u32 = 1;
+
+/// Escalation topic emitted by the conductor itself.
+const CONDUCTOR_ESCALATION_TOPIC: &str = "conductor.escalation";
+
+#[derive(Debug, Default, Deserialize)]
+pub struct RunAuditQuery {
+    /// `json` (default) or `ndjson`.
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditHeader {
+    pub audit_schema_version: u32,
+    pub run_id: String,
+    pub objective: Option<String>,
+    /// `None` when only events are known about the run.
+    pub status: Option<ConductorRunStatus>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub generated_at: DateTime<Utc>,
+    pub decision_count: usize,
+    pub call_count: usize,
+    pub artifact_count: usize,
+    pub escalation_count: usize,
+    pub event_count: usize,
+}
+
+/// An escalation raised during the run, by the conductor or a worker.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEscalation {
+    pub seq: i64,
+    pub event_type: String,
+    pub raised_at: DateTime<Utc>,
+    pub escalation_id: Option<String>,
+    pub kind: Option<String>,
+    pub reason: Option<String>,
+    pub urgency: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RunAudit {
+    pub header: AuditHeader,
+    /// By decision time.
+    pub decisions: Vec<ConductorDecision>,
+    /// By start time.
+    pub calls: Vec<ConductorCapabilityCall>,
+    /// By creation time.
+    pub artifacts: Vec<ConductorArtifact>,
+    /// By seq.
+    pub escalations: Vec<AuditEscalation>,
+    /// Every event correlated with the run, by seq.
+    pub events: Vec<Event>,
+}
+
+/// One line of the NDJSON export, tagged by `record`.
+#[derive(Debug, Serialize)]
+#[serde(tag = "record", rename_all = "snake_case")]
+pub enum AuditRecord<'a> {
+    Header(&'a AuditHeader),
+    Decision(&'a ConductorDecision),
+    Call(&'a ConductorCapabilityCall),
+    Artifact(&'a ConductorArtifact),
+    Escalation(&'a AuditEscalation),
+    Event(&'a Event),
+}
+
+impl RunAudit {
+    /// The NDJSON lines, in export order.
+    pub fn records(&self) -> impl Iterator<Item = AuditRecord<'_>> {
+        std::iter::once(AuditRecord::Header(&self.header))
+            .chain(self.decisions.iter().map(AuditRecord::Decision))
+            .chain(self.calls.iter().map(AuditRecord::Call))
+            .chain(self.artifacts.iter().map(AuditRecord::Artifact))
+            .chain(self.escalations.iter().map(AuditRecord::Escalation))
+            .chain(self.events.iter().map(AuditRecord::Event))
+    }
+}
+
+/// Export one run's audit trail.
+pub async fn export_run_audit(
+    State(state): State<ApiState>,
+    Path(run_id): Path<String>,
+    Query(query): Query<RunAuditQuery>,
+) -> impl IntoResponse {
+    if run_id.trim().is_empty() {
+        return api_error(ErrorCode::InvalidRequest, "Run ID cannot be empty");
+    }
+    let ndjson = match query.format.as_deref() {
+        None | Some("json") => false,
+        Some("ndjson") => true,
+        Some(other) => {
+            return api_error(
+                ErrorCode::InvalidRequest,
+                format!("unknown format '{other}'; expected json or ndjson"),
+            )
+        }
+    };
+
+    let audit = match build_run_audit(&state, &run_id).await {
+        Ok(Some(audit)) => audit,
+        Ok(None) => return api_error(ErrorCode::NotFound, format!("run not found: {run_id}")),
+        Err(err) => return api_error(ErrorCode::InternalError, err),
+    };
+    if !ndjson {
+        return (StatusCode::OK, Json(audit)).into_response();
+    }
+
+    let mut body = Vec::new();
+    for record in audit.records() {
+        if let Err(err) = serde_json::to_writer(&mut body, &record) {
+            return api_error(ErrorCode::InternalError, err.to_string());
+        }
+        body.push(b'\n');
+    }
+    (
+        StatusCode::OK,
+        [("content-type", "application/x-ndjson; charset=utf-8")],
+        body,
+    )
+        .into_response()
+}
+
+/// Assemble the audit for `run_id`; `None` when nothing is known about it.
+pub(crate) async fn build_run_audit(
+    state: &ApiState,
+    run_id: &str,
+) -> Result<Option<RunAudit>, String> {
+    let event_store = state.app_state.event_store();
+    let redacted = redacted_seqs(&event_store).await?;
+    let mut events = fetch_run_events(event_store, run_id).await?;
+    let run_state = fetch_run_state(state, run_id).await;
+    if events.is_empty() && run_state.is_none() {
+        return Ok(None);
+    }
+
+    for event in &mut events {
+        if redacted.contains(&event.seq) {
+            event.payload = json!({ "redacted": true, "run_id": run_id });
+        } else {
+            redact_sensitive_keys(&mut event.payload);
+        }
+    }
+    let escalations: Vec<AuditEscalation> =
+        events.iter().filter_map(escalation_from_event).collect();
+
+    let (objective, status, created_at, completed_at, mut decisions, mut calls, mut artifacts) =
+        match run_state {
+            Some(run) => (
+                Some(run.objective),
+                Some(run.status),
+                Some(run.created_at),
+                run.completed_at,
+                run.decision_log,
+                run.active_calls,
+                run.artifacts,
+            ),
+            None => (
+                events
+                    .iter()
+                    .find_map(|event| event.payload["objective"].as_str())
+                    .map(str::to_string),
+                None,
+                None,
+                None,
+                Vec::new(),
+                Vec::new(),
+                Vec::new(),
+            ),
+        };
+    // Stable sorts: entries recorded at the same instant keep state order.
+    decisions.sort_by_key(|decision| decision.timestamp);
+    calls.sort_by_key(|call| call.started_at);
+    artifacts.sort_by_key(|artifact| artifact.created_at);
+
+    Ok(Some(RunAudit {
+        header: AuditHeader {
+            audit_schema_version: AUDIT_SCHEMA_VERSION,
+            run_id: run_id.to_string(),
+            objective,
+            status,
+            created_at,
+            completed_at,
+            generated_at: Utc::now(),
+            decision_count: decisions.len(),
+            call_count: calls.len(),
+            artifact_count: artifacts.len(),
+            escalation_count: escalations.len(),
+            event_count: events.len(),
+        },
+        decisions,
+        calls,
+        artifacts,
+        escalations,
+        events,
+    }))
+}
+
+/// Conductor escalations keep their fields under `data`, worker ones under
+/// `escalation`.
+fn escalation_from_event(event: &Event) -> Option<AuditEscalation> {
+    if event.event_type != CONDUCTOR_ESCALATION_TOPIC
+        && event.event_type != EVENT_TOPIC_WORKER_SIGNAL_ESCALATION_REQUESTED
+    {
+        return None;
+    }
+    let payload = &event.payload;
+    let detail = [&payload["escalation"], &payload["data"]]
+        .into_iter()
+        .find(|value| value.is_object())
+        .unwrap_or(payload);
+    let field = |key: &str| match &detail[key] {
+        Value::String(value) => Some(value.clone()),
+        Value::Null => None,
+        other => Some(other.to_string()),
+    };
+    Some(AuditEscalation {
+        seq: event.seq,
+        event_type: event.event_type.clone(),
+        raised_at: event.stored_at,
+        escalation_id: field("escalation_id"),
+        kind: field("kind"),
+        reason: field("reason"),
+        urgency: field("urgency"),
+    })
+}
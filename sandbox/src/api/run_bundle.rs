@@ -0,0 +1,946 @@
+//! Run bundles: one run's event stream, documents, decision log, usage and
+//! captured prompts packed into a zip, for reproducing the run elsewhere.
+//!
+//! `GET /api/conductor/runs/{run_id}/bundle` builds the archive in a temp file
+//! and streams it back, so a long run is never held in memory. Archive I/O
+//! runs on the blocking pool.
+//! `POST /api/conductor/runs/import` loads a bundle under a fresh run id. The
+//! bundle is read and checked in full first, and its events are appended in
+//! one transaction, so a bad bundle leaves nothing behind.
+//!
+//! Every JSON part goes through the LLM trace redaction filter, and events an
+//! admin redacted are exported with their payload blanked. What is left is
+//! checked by the secret scanner; an export with findings is refused unless
+//! `override_secret_scan=true` is passed, which is recorded as a
+//! `secret_scan.overridden` event.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path as FsPath, PathBuf};
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::extract::{Path, Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::IntoResponse;
+use axum::Json;
+use chrono::{DateTime, Utc};
+use futures_util::StreamExt;
+use ractor::ActorRef;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use shared_types::{ApiError, ConductorRunState, ErrorCode, Event};
+use tokio::io::AsyncWriteExt;
+use tokio_util::io::ReaderStream;
+use zip::result::{ZipError, ZipResult};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::actors::conductor::ConductorMsg;
+use crate::actors::event_store::{
+    append_batch, append_event, get_recent_events, AppendEvent, EventStoreMsg,
+};
+use crate::actors::writer::{DocumentVersion, RunDocument, VersionSource, WriterDocumentRuntime};
+use crate::api::error::{api_error, error_response};
+use crate::api::run_observability::event_belongs_to_run;
+use crate::api::ApiState;
+use crate::observability::llm_trace::redact_sensitive_keys;
+use crate::secret_scan::{secret_scanner, ScanBlocked, ScanReport, SecretScanner};
+
+pub const BUNDLE_SCHEMA_VERSION: u32 = 1;
+/// Shape of each `events.jsonl` line: a serialized `shared_types::Event`.
+pub const EVENT_SCHEMA_VERSION: u32 = 1;
+
+pub const MANIFEST_FILE: &str = "manifest.json";
+pub const EVENTS_FILE: &str = "events.jsonl";
+pub const RUN_STATE_FILE: &str = "run_state.json";
+pub const DECISION_LOG_FILE: &str = "decision_log.json";
+pub const INITIAL_DOCUMENT_FILE: &str = "document/initial.md";
+pub const FINAL_DOCUMENT_FILE: &str = "document/final.md";
+pub const USAGE_FILE: &str = "usage.json";
+pub const PROMPTS_FILE: &str = "prompts.jsonl";
+
+/// Run state of an imported run, kept next to its document because the
+/// conductor only rebuilds status, not the decision log, from events.
+const IMPORTED_RUN_STATE_FILE: &str = "bundle-run-state.json";
+const MAX_IMPORT_BYTES: u64 = 512 * 1024 * 1024;
+const EVENT_PAGE_SIZE: i64 = 1000;
+/// Operation name in secret scan errors and audit events.
+const EXPORT_OPERATION: &str = "run_bundle.export";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub bundle_schema_version: u32,
+    pub event_schema_version: u32,
+    pub run_id: String,
+    #[serde(default)]
+    pub objective: Option<String>,
+    pub exported_at: DateTime<Utc>,
+    pub sandbox_version: String,
+    pub event_count: u64,
+    /// Seqs whose payloads were blanked because they had been redacted.
+    #[serde(default)]
+    pub redacted_seqs: Vec<i64>,
+    /// Whether `llm.call.started` prompt captures were found for the run.
+    pub prompts_captured: bool,
+    pub files: Vec<String>,
+}
+
+/// Token and call totals over the run's `llm.call.*` terminal events.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BundleUsage {
+    pub llm_calls: u64,
+    pub failed_calls: u64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cached_input_tokens: i64,
+    pub total_tokens: i64,
+    pub duration_ms: u64,
+    pub by_model: BTreeMap<String, ModelUsage>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelUsage {
+    pub calls: u64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub total_tokens: i64,
+}
+
+impl BundleUsage {
+    fn record(&mut self, event: &Event) {
+        let failed = match event.event_type.as_str() {
+            shared_types::EVENT_TOPIC_LLM_CALL_COMPLETED => false,
+            shared_types::EVENT_TOPIC_LLM_CALL_FAILED => true,
+            _ => return,
+        };
+        let payload = &event.payload;
+        let count = |key: &str| payload["usage"][key].as_i64().unwrap_or(0);
+        let (input, output) = (count("input_tokens"), count("output_tokens"));
+        let total = payload["usage"]["total_tokens"]
+            .as_i64()
+            .unwrap_or(input + output);
+
+        self.llm_calls += 1;
+        self.failed_calls += u64::from(failed);
+        self.input_tokens += input;
+        self.output_tokens += output;
+        self.cached_input_tokens += count("cached_input_tokens");
+        self.total_tokens += total;
+        self.duration_ms += payload["duration_ms"].as_u64().unwrap_or(0);
+
+        let model = payload["model_used"].as_str().unwrap_or("unknown");
+        let by_model = self.by_model.entry(model.to_string()).or_default();
+        by_model.calls += 1;
+        by_model.input_tokens += input;
+        by_model.output_tokens += output;
+        by_model.total_tokens += total;
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ExportRunBundleQuery {
+    /// Export even if the secret scanner finds something.
+    #[serde(default)]
+    pub override_secret_scan: bool,
+    /// Why the override was needed; kept in the audit event.
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+pub(crate) fn temp_bundle_path() -> PathBuf {
+    std::env::temp_dir().join(format!("choir-run-bundle-{}.zip", ulid::Ulid::new()))
+}
+
+/// Export one run as a zip bundle.
+pub async fn export_run_bundle(
+    State(state): State<ApiState>,
+    Path(run_id): Path<String>,
+    Query(query): Query<ExportRunBundleQuery>,
+) -> impl IntoResponse {
+    if run_id.trim().is_empty() {
+        return api_error(ErrorCode::InvalidRequest, "Run ID cannot be empty");
+    }
+
+    let path = temp_bundle_path();
+    let mut scan = BundleScan::new(secret_scanner());
+    let written = write_bundle(&state, &run_id, &path, &mut scan).await;
+    let manifest = match written {
+        Ok(Some(manifest)) => manifest,
+        Ok(None) => {
+            let _ = std::fs::remove_file(&path);
+            return api_error(ErrorCode::NotFound, format!("run not found: {run_id}"));
+        }
+        Err(err) => {
+            let _ = std::fs::remove_file(&path);
+            return api_error(ErrorCode::InternalError, err);
+        }
+    };
+
+    if let Err(blocked) = scan.report.into_result(EXPORT_OPERATION) {
+        if !query.override_secret_scan {
+            let _ = std::fs::remove_file(&path);
+            tracing::warn!(run_id = %run_id, findings = blocked.total, "{blocked}");
+            return scan_blocked_response(&blocked);
+        }
+        record_scan_override(&state, &run_id, query.reason, &blocked).await;
+    }
+
+    let file = match tokio::fs::File::open(&path).await {
+        Ok(file) => file,
+        Err(err) => {
+            let _ = std::fs::remove_file(&path);
+            return api_error(ErrorCode::InternalError, err.to_string());
+        }
+    };
+    // The open handle keeps the archive readable; unlinking now means an
+    // abandoned download leaves nothing behind.
+    let _ = std::fs::remove_file(&path);
+
+    tracing::info!(
+        run_id = %run_id,
+        events = manifest.event_count,
+        "Exported run bundle"
+    );
+    (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"run-{run_id}.zip\""),
+            ),
+        ],
+        Body::from_stream(ReaderStream::new(file)),
+    )
+        .into_response()
+}
+
+/// 403 listing redacted previews of what the scanner found.
+fn scan_blocked_response(blocked: &ScanBlocked) -> axum::response::Response {
+    error_response(
+        ApiError::new(ErrorCode::PolicyBlocked, blocked.to_string()).with_details(json!({
+            "operation": blocked.operation,
+            "findings": blocked.findings,
+            "total_findings": blocked.total,
+        })),
+    )
+}
+
+/// Audit an export forced past scan findings. The findings carry previews
+/// only, so the event does not hold the secrets either.
+async fn record_scan_override(
+    state: &ApiState,
+    run_id: &str,
+    reason: Option<String>,
+    blocked: &ScanBlocked,
+) {
+    let audit = AppendEvent {
+        event_type: shared_types::EVENT_TOPIC_SECRET_SCAN_OVERRIDDEN.to_string(),
+        payload: json!({
+            "operation": blocked.operation,
+            "run_id": run_id,
+            "reason": reason,
+            "findings": blocked.findings,
+            "total_findings": blocked.total,
+        }),
+        actor_id: "api.run_bundle".to_string(),
+        user_id: "system".to_string(),
+        idempotency_key: None,
+    };
+    if let Err(err) = append_event(&state.app_state.event_store(), audit).await {
+        tracing::warn!(run_id = %run_id, error = %err, "Failed to record secret scan override");
+    }
+}
+
+/// Scanner plus the findings for the bundle being written.
+struct BundleScan {
+    scanner: Arc<SecretScanner>,
+    report: ScanReport,
+}
+
+impl BundleScan {
+    fn new(scanner: Arc<SecretScanner>) -> Self {
+        Self {
+            scanner,
+            report: ScanReport::default(),
+        }
+    }
+
+    fn json(&mut self, location: &str, value: &Value) {
+        self.report.extend(self.scanner.scan_json(location, value));
+    }
+
+    fn text(&mut self, location: &str, text: &str) {
+        self.report.extend(self.scanner.scan_text(location, text));
+    }
+}
+
+/// Write the bundle to `path`, scanning each part as it is written. Returns
+/// `None` when nothing is known about the run.
+async fn write_bundle(
+    state: &ApiState,
+    run_id: &str,
+    path: &FsPath,
+    scan: &mut BundleScan,
+) -> Result<Option<BundleManifest>, String> {
+    let event_store = state.app_state.event_store();
+    let redacted = redacted_seqs(&event_store).await?;
+    let mut zip = BundleWriter::create(path.to_path_buf()).await?;
+    let mut files = Vec::new();
+
+    // One pass over the log writes the events and collects the prompt
+    // captures. Those belong to a later entry, so they are spooled to a temp
+    // file until the events are written.
+    zip.start_file(EVENTS_FILE).await?;
+    files.push(EVENTS_FILE.to_string());
+    let mut prompts = None;
+    let mut event_count = 0_u64;
+    let mut redacted_in_run = Vec::new();
+    let mut usage = BundleUsage::default();
+    let mut objective = None;
+    let mut since_seq = 0_i64;
+    loop {
+        let page = fetch_page(&event_store, since_seq, None).await?;
+        let Some(last_seq) = page.last().map(|event| event.seq) else {
+            break;
+        };
+        let mut lines = Vec::new();
+        let mut prompt_lines = Vec::new();
+        for mut event in page {
+            if !event_belongs_to_run(&event, run_id) {
+                continue;
+            }
+            if redacted.contains(&event.seq) {
+                // Keep the run id so the placeholder stays scoped on import.
+                event.payload = json!({ "redacted": true, "run_id": run_id });
+                redacted_in_run.push(event.seq);
+            } else {
+                if event.event_type == shared_types::EVENT_TOPIC_LLM_CALL_STARTED {
+                    let prompt = prompt_capture(&event);
+                    scan.json(&format!("{PROMPTS_FILE} seq {}", event.seq), &prompt);
+                    push_line(&mut prompt_lines, &prompt)?;
+                }
+                redact_sensitive_keys(&mut event.payload);
+            }
+            if objective.is_none() {
+                objective = event.payload["objective"].as_str().map(str::to_string);
+            }
+            usage.record(&event);
+            scan.json(&format!("{EVENTS_FILE} seq {}", event.seq), &event.payload);
+            push_line(&mut lines, &event)?;
+            event_count += 1;
+        }
+        zip.write(lines).await?;
+        if !prompt_lines.is_empty() {
+            prompts = Some(spool_lines(prompts, prompt_lines).await?);
+        }
+        if last_seq <= since_seq {
+            break;
+        }
+        since_seq = last_seq;
+    }
+
+    let run_state = fetch_run_state(state, run_id).await;
+    if event_count == 0 && run_state.is_none() {
+        return Ok(None);
+    }
+
+    let prompts_captured = prompts.is_some();
+    if let Some(prompts) = prompts {
+        zip.copy_file(PROMPTS_FILE, prompts).await?;
+        files.push(PROMPTS_FILE.to_string());
+    }
+
+    if let Some(run_state) = &run_state {
+        objective = Some(run_state.objective.clone());
+        let mut decision_log =
+            serde_json::to_value(&run_state.decision_log).map_err(|e| e.to_string())?;
+        redact_sensitive_keys(&mut decision_log);
+        let mut run_state = serde_json::to_value(run_state).map_err(|e| e.to_string())?;
+        redact_sensitive_keys(&mut run_state);
+        scan.json(RUN_STATE_FILE, &run_state);
+        zip.write_file(RUN_STATE_FILE, json_bytes(&run_state)?)
+            .await?;
+        zip.write_file(DECISION_LOG_FILE, json_bytes(&decision_log)?)
+            .await?;
+        files.push(RUN_STATE_FILE.to_string());
+        files.push(DECISION_LOG_FILE.to_string());
+    }
+
+    let versions = fetch_document_versions(run_id).await;
+    if let (Some(initial), Some(last)) = (versions.first(), versions.last()) {
+        scan.text(INITIAL_DOCUMENT_FILE, &initial.content);
+        scan.text(FINAL_DOCUMENT_FILE, &last.content);
+        zip.write_file(INITIAL_DOCUMENT_FILE, initial.content.clone().into_bytes())
+            .await?;
+        zip.write_file(FINAL_DOCUMENT_FILE, last.content.clone().into_bytes())
+            .await?;
+        files.push(INITIAL_DOCUMENT_FILE.to_string());
+        files.push(FINAL_DOCUMENT_FILE.to_string());
+    }
+
+    zip.write_file(USAGE_FILE, json_bytes(&usage)?).await?;
+    files.push(USAGE_FILE.to_string());
+
+    files.push(MANIFEST_FILE.to_string());
+    let manifest = BundleManifest {
+        bundle_schema_version: BUNDLE_SCHEMA_VERSION,
+        event_schema_version: EVENT_SCHEMA_VERSION,
+        run_id: run_id.to_string(),
+        objective,
+        exported_at: Utc::now(),
+        sandbox_version: env!("CARGO_PKG_VERSION").to_string(),
+        event_count,
+        redacted_seqs: redacted_in_run,
+        prompts_captured,
+        files,
+    };
+    zip.write_file(MANIFEST_FILE, json_bytes(&manifest)?)
+        .await?;
+    zip.finish().await?;
+    Ok(Some(manifest))
+}
+
+type BundleZip = ZipWriter<BufWriter<File>>;
+
+/// Zip writer whose file I/O runs on the blocking pool. Each call hands the
+/// writer to a blocking task and takes it back when the task is done.
+struct BundleWriter {
+    zip: Option<BundleZip>,
+}
+
+impl BundleWriter {
+    async fn create(path: PathBuf) -> Result<Self, String> {
+        let file = blocking(move || File::create(path))
+            .await
+            .map_err(|e| format!("create bundle file: {e}"))?;
+        Ok(Self {
+            zip: Some(ZipWriter::new(BufWriter::new(file))),
+        })
+    }
+
+    async fn with<T: Send + 'static>(
+        &mut self,
+        work: impl FnOnce(&mut BundleZip) -> ZipResult<T> + Send + 'static,
+    ) -> Result<T, String> {
+        let mut zip = self
+            .zip
+            .take()
+            .ok_or_else(|| "write bundle: writer lost after an earlier failure".to_string())?;
+        let (zip, result) = tokio::task::spawn_blocking(move || {
+            let result = work(&mut zip);
+            (zip, result)
+        })
+        .await
+        .map_err(|e| format!("write bundle: {e}"))?;
+        self.zip = Some(zip);
+        result.map_err(|e| format!("write bundle: {e}"))
+    }
+
+    /// Start an entry; following `write`s go to it.
+    async fn start_file(&mut self, name: &'static str) -> Result<(), String> {
+        self.with(move |zip| zip.start_file(name, bundle_file_options()))
+            .await
+    }
+
+    async fn write(&mut self, bytes: Vec<u8>) -> Result<(), String> {
+        self.with(move |zip| Ok(zip.write_all(&bytes)?)).await
+    }
+
+    /// Write a whole entry in one call.
+    async fn write_file(&mut self, name: &'static str, bytes: Vec<u8>) -> Result<(), String> {
+        self.with(move |zip| {
+            zip.start_file(name, bundle_file_options())?;
+            Ok(zip.write_all(&bytes)?)
+        })
+        .await
+    }
+
+    /// Copy a spooled temp file into a new entry.
+    async fn copy_file(&mut self, name: &'static str, mut source: File) -> Result<(), String> {
+        self.with(move |zip| {
+            source.seek(SeekFrom::Start(0))?;
+            zip.start_file(name, bundle_file_options())?;
+            std::io::copy(&mut source, zip)?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Write the central directory and flush the file.
+    async fn finish(mut self) -> Result<(), String> {
+        let Some(zip) = self.zip.take() else {
+            return Err("write bundle: writer lost after an earlier failure".to_string());
+        };
+        blocking(move || -> ZipResult<()> { Ok(zip.finish()?.flush()?) })
+            .await
+            .map_err(|e| format!("write bundle: {e}"))
+    }
+}
+
+fn bundle_file_options() -> SimpleFileOptions {
+    SimpleFileOptions::default().compression_method(CompressionMethod::Deflated)
+}
+
+/// Run blocking file I/O on the blocking pool.
+async fn blocking<T, E>(work: impl FnOnce() -> Result<T, E> + Send + 'static) -> Result<T, String>
+where
+    T: Send + 'static,
+    E: std::fmt::Display + Send + 'static,
+{
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Append `lines` to a temp file, creating it on first use.
+async fn spool_lines(spool: Option<File>, lines: Vec<u8>) -> Result<File, String> {
+    blocking(move || {
+        let mut file = match spool {
+            Some(file) => file,
+            None => tempfile::tempfile()?,
+        };
+        file.write_all(&lines)?;
+        Ok::<_, std::io::Error>(file)
+    })
+    .await
+    .map_err(|e| format!("spool prompts: {e}"))
+}
+
+fn push_line<T: Serialize>(out: &mut Vec<u8>, value: &T) -> Result<(), String> {
+    serde_json::to_writer(&mut *out, value).map_err(|e| e.to_string())?;
+    out.push(b'\n');
+    Ok(())
+}
+
+fn json_bytes<T: Serialize>(value: &T) -> Result<Vec<u8>, String> {
+    serde_json::to_vec_pretty(value).map_err(|e| e.to_string())
+}
+
+async fn fetch_page(
+    event_store: &ActorRef<EventStoreMsg>,
+    since_seq: i64,
+    event_type_prefix: Option<&str>,
+) -> Result<Vec<Event>, String> {
+    match get_recent_events(
+        event_store,
+        since_seq,
+        EVENT_PAGE_SIZE,
+        event_type_prefix.map(str::to_string),
+        None,
+        None,
+    )
+    .await
+    {
+        Ok(Ok(events)) => Ok(events),
+        Ok(Err(err)) => Err(format!("EventStore error: {err}")),
+        Err(err) => Err(format!("RPC error: {err}")),
+    }
+}
+
+/// Seqs targeted by any `event.redacted` marker.
+pub(crate) async fn redacted_seqs(
+    event_store: &ActorRef<EventStoreMsg>,
+) -> Result<BTreeSet<i64>, String> {
+    let mut seqs = BTreeSet::new();
+    let mut since_seq = 0_i64;
+    loop {
+        let page = fetch_page(
+            event_store,
+            since_seq,
+            Some(shared_types::EVENT_TOPIC_EVENT_REDACTED),
+        )
+        .await?;
+        let Some(last_seq) = page.last().map(|event| event.seq) else {
+            break;
+        };
+        seqs.extend(
+            page.iter()
+                .filter_map(|event| event.payload["seq"].as_i64()),
+        );
+        if last_seq <= since_seq {
+            break;
+        }
+        since_seq = last_seq;
+    }
+    Ok(seqs)
+}
+
+/// The `prompts.jsonl` line for an `llm.call.started` event.
+fn prompt_capture(event: &Event) -> Value {
+    let payload = &event.payload;
+    let mut prompt = json!({
+        "seq": event.seq,
+        "trace_id": payload["trace_id"],
+        "role": payload["role"],
+        "function_name": payload["function_name"],
+        "model_used": payload["model_used"],
+        "started_at": payload["started_at"],
+        "system_context": payload["system_context"],
+        "input": payload["input"],
+    });
+    redact_sensitive_keys(&mut prompt);
+    prompt
+}
+
+/// State saved by an import, falling back to the conductor's state for the run.
+///
+/// The saved state wins for imported runs: a conductor started after the
+/// import restores only a skeleton of the run from its `conductor.*` events,
+/// without decisions or calls.
+pub(crate) async fn fetch_run_state(state: &ApiState, run_id: &str) -> Option<ConductorRunState> {
+    if let Ok(raw) = tokio::fs::read_to_string(imported_run_state_path(run_id)).await {
+        if let Ok(run_state) = serde_json::from_str(&raw) {
+            return Some(run_state);
+        }
+    }
+    let conductor = state.app_state.ensure_conductor().await.ok()?;
+    ractor::call!(conductor, |reply| ConductorMsg::GetRunState {
+        run_id: run_id.to_string(),
+        reply,
+    })
+    .ok()
+    .flatten()
+}
+
+/// The run document's versions, oldest first, read from disk so exporting
+/// never starts a writer.
+async fn fetch_document_versions(run_id: &str) -> Vec<DocumentVersion> {
+    let Some(document) =
+        WriterDocumentRuntime::read_document(&crate::paths::writer_root(), run_id).await
+    else {
+        return Vec::new();
+    };
+    let mut versions = document.versions;
+    versions.sort_by_key(|version| version.version_id);
+    versions
+}
+
+fn imported_run_dir(run_id: &str) -> PathBuf {
+    crate::paths::writer_root()
+        .join("conductor/runs")
+        .join(run_id)
+}
+
+fn imported_run_state_path(run_id: &str) -> PathBuf {
+    imported_run_dir(run_id).join(IMPORTED_RUN_STATE_FILE)
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportRunBundleResponse {
+    pub run_id: String,
+    pub source_run_id: String,
+    pub events_imported: u64,
+    pub document_imported: bool,
+}
+
+/// Load a bundle under a new run id.
+///
+/// Events are appended in bundle order with every mention of the source run id
+/// rewritten; they get fresh `seq`s and store timestamps. Either all of them
+/// and the import marker are stored or none are.
+pub async fn import_run_bundle(State(state): State<ApiState>, body: Body) -> impl IntoResponse {
+    let path = temp_bundle_path();
+    let response = match spool_body(body, &path).await {
+        Ok(()) => import_from_file(&state, &path).await,
+        Err(response) => response,
+    };
+    let _ = std::fs::remove_file(&path);
+    response
+}
+
+/// Stream a request body to `path`, refusing bundles over the import limit.
+pub(crate) async fn spool_body(body: Body, path: &FsPath) -> Result<(), axum::response::Response> {
+    let internal = |e: std::io::Error| api_error(ErrorCode::InternalError, e.to_string());
+    let mut file = tokio::fs::File::create(path).await.map_err(internal)?;
+    let mut stream = body.into_data_stream();
+    let mut received = 0_u64;
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| api_error(ErrorCode::InvalidRequest, e.to_string()))?;
+        received += chunk.len() as u64;
+        if received > MAX_IMPORT_BYTES {
+            return Err(error_response(
+                ApiError::new(
+                    ErrorCode::PayloadTooLarge,
+                    format!("bundle exceeds {MAX_IMPORT_BYTES} bytes"),
+                )
+                .with_details(json!({ "limit_bytes": MAX_IMPORT_BYTES })),
+            ));
+        }
+        file.write_all(&chunk).await.map_err(internal)?;
+    }
+    file.flush().await.map_err(internal)
+}
+
+/// Read a whole entry into a string; `None` when the bundle has no such entry.
+pub(crate) fn read_entry<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    name: &str,
+) -> ZipResult<Option<String>> {
+    let mut entry = match archive.by_name(name) {
+        Ok(entry) => entry,
+        Err(ZipError::FileNotFound) => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    let mut contents = String::new();
+    entry.read_to_string(&mut contents)?;
+    Ok(Some(contents))
+}
+
+/// Read and version-check a bundle's manifest.
+pub(crate) fn read_manifest<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+) -> Result<BundleManifest, axum::response::Response> {
+    let invalid = |message: String| api_error(ErrorCode::InvalidRequest, message);
+    let manifest: BundleManifest = match read_entry(archive, MANIFEST_FILE) {
+        Ok(Some(raw)) => serde_json::from_str(&raw)
+            .map_err(|err| invalid(format!("invalid {MANIFEST_FILE}: {err}")))?,
+        Ok(None) => return Err(invalid(format!("bundle has no {MANIFEST_FILE}"))),
+        Err(err) => return Err(invalid(format!("invalid bundle: {err}"))),
+    };
+    if manifest.bundle_schema_version != BUNDLE_SCHEMA_VERSION
+        || manifest.event_schema_version != EVENT_SCHEMA_VERSION
+    {
+        return Err(error_response(
+            ApiError::new(
+                ErrorCode::UnsupportedSchema,
+                format!(
+                    "unsupported bundle schema (bundle v{}, events v{}); expected bundle v{BUNDLE_SCHEMA_VERSION}, events v{EVENT_SCHEMA_VERSION}",
+                    manifest.bundle_schema_version, manifest.event_schema_version
+                ),
+            )
+            .with_details(json!({
+                "bundle_schema_version": manifest.bundle_schema_version,
+                "event_schema_version": manifest.event_schema_version,
+                "supported_bundle_schema_version": BUNDLE_SCHEMA_VERSION,
+                "supported_event_schema_version": EVENT_SCHEMA_VERSION,
+            })),
+        ));
+    }
+    Ok(manifest)
+}
+
+/// A bundle read and checked in full, rekeyed to the new run id.
+struct ImportedBundle {
+    manifest: BundleManifest,
+    events: Vec<AppendEvent>,
+    run_state: Option<Value>,
+    initial: Option<String>,
+    last: Option<String>,
+}
+
+/// Read, validate and rekey a spooled bundle. Blocking; nothing is written.
+fn read_import(path: &FsPath, run_id: &str) -> Result<ImportedBundle, axum::response::Response> {
+    let invalid = |message: String| api_error(ErrorCode::InvalidRequest, message);
+    let file =
+        File::open(path).map_err(|err| api_error(ErrorCode::InternalError, err.to_string()))?;
+    let mut archive = ZipArchive::new(BufReader::new(file))
+        .map_err(|err| invalid(format!("invalid bundle: {err}")))?;
+    let manifest = read_manifest(&mut archive)?;
+    let source_run_id = manifest.run_id.as_str();
+
+    let mut events = Vec::new();
+    match archive.by_name(EVENTS_FILE) {
+        Ok(entry) => {
+            for line in BufReader::new(entry).lines() {
+                let line = line.map_err(|err| invalid(format!("invalid {EVENTS_FILE}: {err}")))?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let mut event: Event = serde_json::from_str(&line)
+                    .map_err(|err| invalid(format!("invalid {EVENTS_FILE} line: {err}")))?;
+                rekey_run_id(&mut event.payload, source_run_id, run_id);
+                events.push(AppendEvent {
+                    event_type: event.event_type,
+                    payload: event.payload,
+                    actor_id: event.actor_id.0,
+                    user_id: event.user_id,
+                    idempotency_key: None,
+                });
+            }
+        }
+        Err(ZipError::FileNotFound) => {}
+        Err(err) => return Err(invalid(format!("invalid bundle: {err}"))),
+    }
+
+    let mut read = |name: &str| {
+        read_entry(&mut archive, name).map_err(|err| invalid(format!("invalid {name}: {err}")))
+    };
+    let run_state = read(RUN_STATE_FILE)?;
+    let initial = read(INITIAL_DOCUMENT_FILE)?;
+    let last = read(FINAL_DOCUMENT_FILE)?;
+
+    let run_state = match run_state {
+        Some(raw) => {
+            let mut run_state: Value = serde_json::from_str(&raw)
+                .map_err(|err| invalid(format!("invalid {RUN_STATE_FILE}: {err}")))?;
+            rekey_run_id(&mut run_state, source_run_id, run_id);
+            serde_json::from_value::<ConductorRunState>(run_state.clone())
+                .map_err(|err| invalid(format!("invalid {RUN_STATE_FILE}: {err}")))?;
+            Some(run_state)
+        }
+        None => None,
+    };
+
+    Ok(ImportedBundle {
+        manifest,
+        events,
+        run_state,
+        initial,
+        last,
+    })
+}
+
+async fn import_from_file(state: &ApiState, path: &FsPath) -> axum::response::Response {
+    let run_id = ulid::Ulid::new().to_string();
+    let read = {
+        let (path, run_id) = (path.to_path_buf(), run_id.clone());
+        tokio::task::spawn_blocking(move || read_import(&path, &run_id)).await
+    };
+    let ImportedBundle {
+        manifest,
+        mut events,
+        run_state,
+        initial,
+        last,
+    } = match read {
+        Ok(Ok(bundle)) => bundle,
+        Ok(Err(response)) => return response,
+        Err(err) => return api_error(ErrorCode::InternalError, err.to_string()),
+    };
+    let source_run_id = manifest.run_id.clone();
+    let events_imported = events.len() as u64;
+
+    // Nothing refers to the new run's directory until its events are
+    // committed, so it is removed if anything below fails.
+    let run_dir = imported_run_dir(&run_id);
+    let written = write_imported_files(
+        &run_id,
+        manifest.objective.clone().unwrap_or_default(),
+        run_state,
+        initial,
+        last,
+    )
+    .await;
+    let document_imported = match written {
+        Ok(document_imported) => document_imported,
+        Err(err) => {
+            let _ = tokio::fs::remove_dir_all(&run_dir).await;
+            return api_error(ErrorCode::InternalError, err);
+        }
+    };
+
+    events.push(AppendEvent {
+        event_type: shared_types::EVENT_TOPIC_RUN_BUNDLE_IMPORTED.to_string(),
+        payload: json!({
+            "imported_run_id": run_id,
+            "source_run_id": source_run_id,
+            "events_imported": events_imported,
+            "exported_at": manifest.exported_at,
+            "sandbox_version": manifest.sandbox_version,
+        }),
+        actor_id: "api.run_bundle".to_string(),
+        user_id: "system".to_string(),
+        idempotency_key: None,
+    });
+    let appended = match append_batch(&state.app_state.event_store(), events).await {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(err)) => Err(err.to_string()),
+        Err(err) => Err(err.to_string()),
+    };
+    if let Err(err) = appended {
+        let _ = tokio::fs::remove_dir_all(&run_dir).await;
+        return api_error(ErrorCode::InternalError, err);
+    }
+
+    tracing::info!(
+        run_id = %run_id,
+        source_run_id = %source_run_id,
+        events = events_imported,
+        "Imported run bundle"
+    );
+    (
+        StatusCode::CREATED,
+        Json(ImportRunBundleResponse {
+            run_id,
+            source_run_id,
+            events_imported,
+            document_imported,
+        }),
+    )
+        .into_response()
+}
+
+/// Save an imported run's state and document. Returns whether a document
+/// was written.
+async fn write_imported_files(
+    run_id: &str,
+    objective: String,
+    run_state: Option<Value>,
+    initial: Option<String>,
+    last: Option<String>,
+) -> Result<bool, String> {
+    if let Some(run_state) = run_state {
+        let raw = json_bytes(&run_state)?;
+        tokio::fs::create_dir_all(imported_run_dir(run_id))
+            .await
+            .map_err(|e| e.to_string())?;
+        tokio::fs::write(imported_run_state_path(run_id), raw)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    if initial.is_none() && last.is_none() {
+        return Ok(false);
+    }
+    let document = imported_document(objective, initial, last);
+    WriterDocumentRuntime::import_document(&crate::paths::writer_root(), run_id, document)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+/// A document whose first version is the bundle's initial revision and whose
+/// head is its final one.
+fn imported_document(
+    objective: String,
+    initial: Option<String>,
+    last: Option<String>,
+) -> RunDocument {
+    let mut document = RunDocument::new(objective);
+    let initial = initial.or_else(|| last.clone()).unwrap_or_default();
+    let last = last.unwrap_or_else(|| initial.clone());
+    document.versions[0].content = initial.clone();
+    if last != initial {
+        let parent = document.head_version_id;
+        let mut head = document.versions[0].clone();
+        head.version_id = document.next_version_id();
+        head.parent_version_id = Some(parent);
+        head.source = VersionSource::Writer;
+        head.content = last;
+        document.head_version_id = head.version_id;
+        document.versions.push(head);
+    }
+    document
+}
+
+/// Replace the source run id inside every string value.
+fn rekey_run_id(value: &mut Value, from: &str, to: &str) {
+    match value {
+        Value::String(text) if text.contains(from) => *text = text.replace(from, to),
+        Value::Array(items) => {
+            for item in items {
+                rekey_run_id(item, from, to);
+            }
+        }
+        Value::Object(map) => {
+            for item in map.values_mut() {
+                rekey_run_id(item, from, to);
+            }
+        }
+        _ => {}
+    }
+}
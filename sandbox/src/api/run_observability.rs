@@ -13,8 +13,17 @@ use axum::Json;
 use ractor::ActorRef;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use shared_types::{
+    ApiError, ErrorCode, EVENT_TOPIC_CONDUCTOR_CAPABILITY_BLOCKED,
+    EVENT_TOPIC_CONDUCTOR_CAPABILITY_COMPLETED, EVENT_TOPIC_CONDUCTOR_CAPABILITY_FAILED,
+    EVENT_TOPIC_CONDUCTOR_RUN_STARTED, EVENT_TOPIC_CONDUCTOR_TASK_COMPLETED,
+    EVENT_TOPIC_CONDUCTOR_TASK_FAILED, EVENT_TOPIC_CONDUCTOR_WORKER_CALL,
+    EVENT_TOPIC_CONDUCTOR_WORKER_RESULT, EVENT_TOPIC_WORKER_TASK_FINDING,
+    EVENT_TOPIC_WORKER_TASK_LEARNING,
+};
 use std::collections::HashSet;
 
+use super::error::{api_error, error_response};
 use super::ApiState;
 use crate::actors::event_store::EventStoreMsg;
 
@@ -81,7 +90,10 @@ pub struct RunTimelineResponse {
 /// Individual timeline event with categorization
 #[derive(Debug, Clone, Serialize)]
 pub struct TimelineEvent {
+    pub seq: i64,
+    /// Store time (`stored_at`), which is what the timeline displays.
     pub timestamp: String,
+    pub produced_at: Option<String>,
     pub category: EventCategory,
     pub event_type: String,
     pub data: Value,
@@ -154,31 +166,23 @@ pub async fn get_run_timeline(
                     .collect();
 
                 if !missing.is_empty() {
-                    return (
-                        StatusCode::UNPROCESSABLE_ENTITY,
-                        Json(json!({
-                            "error": "missing required milestones",
-                            "run_id": run_id,
-                            "missing_milestones": missing,
-                            "timeline": response,
-                        })),
-                    )
-                        .into_response();
+                    return error_response(
+                        ApiError::new(ErrorCode::Conflict, "missing required milestones")
+                            .with_details(json!({
+                                "run_id": run_id,
+                                "missing_milestones": missing,
+                                "timeline": response,
+                            })),
+                    );
                 }
             }
 
             (StatusCode::OK, Json(json!(response))).into_response()
         }
-        Err(err) if err.starts_with("not_found:") => (
-            StatusCode::NOT_FOUND,
-            Json(json!({ "error": err.trim_start_matches("not_found:") })),
-        )
-            .into_response(),
-        Err(err) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({ "error": err })),
-        )
-            .into_response(),
+        Err(err) if err.starts_with("not_found:") => {
+            api_error(ErrorCode::NotFound, err.trim_start_matches("not_found:"))
+        }
+        Err(err) => api_error(ErrorCode::InternalError, err),
     }
 }
 
@@ -199,8 +203,8 @@ async fn build_categorized_timeline(
         .filter_map(|event| categorize_event(event, query.category.as_deref()))
         .collect();
 
-    // Sort by timestamp
-    timeline_events.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    // Order by seq: producer clocks can skew, the log order cannot.
+    timeline_events.sort_by_key(|event| event.seq);
 
     // Build summary
     let summary = build_run_summary(&events, &timeline_events, run_id);
@@ -227,13 +231,15 @@ fn categorize_event(
         }
     }
 
-    let timestamp = event.timestamp.to_rfc3339();
+    let timestamp = event.stored_at.to_rfc3339();
 
     // Build the data payload - include relevant fields based on event type
     let data = build_event_data(&event.event_type, &event.payload, event.seq);
 
     Some(TimelineEvent {
+        seq: event.seq,
         timestamp,
+        produced_at: event.produced_at.map(|dt| dt.to_rfc3339()),
         category,
         event_type: event.event_type.clone(),
         data,
@@ -246,9 +252,9 @@ fn determine_event_category(event_type: &str, _payload: &Value) -> EventCategory
     if event_type.contains("decision")
         || matches!(
             event_type,
-            "conductor.capability.completed"
-                | "conductor.capability.failed"
-                | "conductor.capability.blocked"
+            EVENT_TOPIC_CONDUCTOR_CAPABILITY_COMPLETED
+                | EVENT_TOPIC_CONDUCTOR_CAPABILITY_FAILED
+                | EVENT_TOPIC_CONDUCTOR_CAPABILITY_BLOCKED
                 | "conductor.escalation"
         )
     {
@@ -262,7 +268,7 @@ fn determine_event_category(event_type: &str, _payload: &Value) -> EventCategory
 
     // Agent conduct - run start + assignment formation
     if event_type.contains("agenda")
-        || event_type == "conductor.run.started"
+        || event_type == EVENT_TOPIC_CONDUCTOR_RUN_STARTED
         || event_type == "conductor.writer.enqueue"
         || event_type == "conductor.writer.enqueue.failed"
     {
@@ -307,7 +313,7 @@ fn build_event_data(event_type: &str, payload: &Value, seq: i64) -> Value {
                 data.insert("reason".to_string(), reason.clone());
             }
         }
-        "conductor.worker.call" | "conductor.run.started" => {
+        EVENT_TOPIC_CONDUCTOR_WORKER_CALL | EVENT_TOPIC_CONDUCTOR_RUN_STARTED => {
             if let Some(objective) = payload
                 .get("worker_objective")
                 .or_else(|| payload.get("objective"))
@@ -348,7 +354,7 @@ fn build_event_data(event_type: &str, payload: &Value, seq: i64) -> Value {
                 data.insert("insight".to_string(), insight.clone());
             }
         }
-        "conductor.worker.result" | "conductor.capability.completed" => {
+        EVENT_TOPIC_CONDUCTOR_WORKER_RESULT | EVENT_TOPIC_CONDUCTOR_CAPABILITY_COMPLETED => {
             if let Some(summary) = payload
                 .get("result_summary")
                 .or_else(|| payload.get("summary"))
@@ -380,7 +386,7 @@ fn build_event_data(event_type: &str, payload: &Value, seq: i64) -> Value {
                 data.insert("error".to_string(), error.clone());
             }
         }
-        "conductor.task.completed" | "conductor.task.failed" => {
+        EVENT_TOPIC_CONDUCTOR_TASK_COMPLETED | EVENT_TOPIC_CONDUCTOR_TASK_FAILED => {
             if let Some(status) = payload.get("status") {
                 data.insert("status".to_string(), status.clone());
             }
@@ -449,7 +455,7 @@ fn build_run_summary(
 
             DecisionSummary {
                 decision_type: decision_type.to_string(),
-                timestamp: e.timestamp.to_rfc3339(),
+                timestamp: e.stored_at.to_rfc3339(),
                 reason: reason.to_string(),
             }
         })
@@ -495,12 +501,12 @@ fn extract_artifact_summaries(events: &[shared_types::Event]) -> Vec<ArtifactSum
         // Worker results
         if matches!(
             event.event_type.as_str(),
-            "conductor.worker.result"
-                | "conductor.capability.completed"
+            EVENT_TOPIC_CONDUCTOR_WORKER_RESULT
+                | EVENT_TOPIC_CONDUCTOR_CAPABILITY_COMPLETED
                 | "conductor.writer.enqueue"
                 | "conductor.writer.enqueue.failed"
-                | "worker.task.finding"
-                | "worker.task.learning"
+                | EVENT_TOPIC_WORKER_TASK_FINDING
+                | EVENT_TOPIC_WORKER_TASK_LEARNING
         ) {
             let summary = extract_event_summary(&event.event_type, &event.payload);
             let key = format!("{}:{}", event.event_type, summary);
@@ -517,7 +523,7 @@ fn extract_artifact_summaries(events: &[shared_types::Event]) -> Vec<ArtifactSum
     artifacts
 }
 
-async fn fetch_run_events(
+pub(crate) async fn fetch_run_events(
     event_store: ActorRef<EventStoreMsg>,
     run_id: &str,
 ) -> Result<Vec<shared_types::Event>, String> {
@@ -558,7 +564,7 @@ async fn fetch_run_events(
     Ok(collected)
 }
 
-fn event_belongs_to_run(event: &shared_types::Event, run_id: &str) -> bool {
+pub(crate) fn event_belongs_to_run(event: &shared_types::Event, run_id: &str) -> bool {
     payload_str(&event.payload, &["run_id"]) == Some(run_id)
         || payload_str(&event.payload, &["data", "run_id"]) == Some(run_id)
 }
@@ -583,9 +589,9 @@ fn derive_run_status(events: &[shared_types::Event]) -> String {
 
     for event in events {
         match event.event_type.as_str() {
-            "conductor.task.completed" => return "completed".to_string(),
-            "conductor.task.failed" => return "failed".to_string(),
-            "conductor.run.blocked" | "conductor.capability.blocked" => {
+            EVENT_TOPIC_CONDUCTOR_TASK_COMPLETED => return "completed".to_string(),
+            EVENT_TOPIC_CONDUCTOR_TASK_FAILED => return "failed".to_string(),
+            "conductor.run.blocked" | EVENT_TOPIC_CONDUCTOR_CAPABILITY_BLOCKED => {
                 status = "blocked".to_string();
             }
             _ => {
@@ -837,6 +843,54 @@ mod tests {
         assert_eq!(EventCategory::System.to_string(), "system");
     }
 
+    #[tokio::test]
+    async fn test_timeline_orders_by_seq_across_backwards_clock_jump() {
+        let (store_ref, _handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .unwrap();
+        let run_id = "run_clock_jump";
+
+        // The producer's clock jumps back an hour between the two appends.
+        for (event_type, produced_at) in [
+            ("conductor.run.started", "2026-03-01T12:00:00Z"),
+            ("conductor.worker.call", "2026-03-01T11:00:00Z"),
+        ] {
+            let event = AppendEvent {
+                event_type: event_type.to_string(),
+                payload: json!({ "run_id": run_id, "timestamp": produced_at }),
+                actor_id: "conductor:test".to_string(),
+                user_id: "system".to_string(),
+                idempotency_key: None,
+            };
+            let _ = ractor::call!(store_ref, |reply| EventStoreMsg::Append { event, reply })
+                .unwrap()
+                .unwrap();
+        }
+
+        let query = RunTimelineQuery {
+            category: None,
+            required_milestones: None,
+        };
+        let response = build_categorized_timeline(store_ref.clone(), run_id, &query)
+            .await
+            .unwrap();
+        let types: Vec<&str> = response
+            .events
+            .iter()
+            .map(|e| e.event_type.as_str())
+            .collect();
+        assert_eq!(types, ["conductor.run.started", "conductor.worker.call"]);
+        assert!(response.events[0].seq < response.events[1].seq);
+        assert!(response.events[0].timestamp <= response.events[1].timestamp);
+        assert_eq!(
+            response.events[1].produced_at.as_deref(),
+            Some("2026-03-01T11:00:00+00:00")
+        );
+
+        store_ref.stop(None);
+    }
+
     #[tokio::test]
     async fn test_categorized_timeline_filters_by_category() {
         let (store_ref, _handle) =
@@ -857,6 +911,7 @@ mod tests {
                 }),
                 actor_id: "conductor:test".to_string(),
                 user_id: "system".to_string(),
+                idempotency_key: None,
             },
             // Agent objective
             AppendEvent {
@@ -868,6 +923,7 @@ mod tests {
                 }),
                 actor_id: "conductor:test".to_string(),
                 user_id: "system".to_string(),
+                idempotency_key: None,
             },
             // Decision
             AppendEvent {
@@ -879,6 +935,7 @@ mod tests {
                 }),
                 actor_id: "conductor:test".to_string(),
                 user_id: "system".to_string(),
+                idempotency_key: None,
             },
             // Agent result
             AppendEvent {
@@ -889,6 +946,7 @@ mod tests {
                 }),
                 actor_id: "conductor:test".to_string(),
                 user_id: "system".to_string(),
+                idempotency_key: None,
             },
         ];
 
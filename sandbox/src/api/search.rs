@@ -0,0 +1,89 @@
+//! Search API endpoint
+//!
+//! Full-text search over chat messages, document versions and report
+//! artifacts, served from the `search` projection.
+
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use serde::Deserialize;
+use shared_types::ErrorCode;
+
+use super::error::api_error;
+use super::ApiState;
+use crate::actors::event_store::search_index;
+use crate::projections::search::{match_expression, search_hit, SEARCH_PROJECTION};
+
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    pub q: Option<String>,
+    /// Comma-separated hit kinds: chat, document, artifact. Defaults to all.
+    pub kinds: Option<String>,
+    pub limit: Option<i64>,
+}
+
+fn bad_request(message: impl Into<String>) -> axum::response::Response {
+    api_error(ErrorCode::InvalidRequest, message)
+}
+
+/// Search indexed content, best matches first.
+pub async fn search(
+    State(state): State<ApiState>,
+    Query(query): Query<SearchQuery>,
+) -> impl IntoResponse {
+    let q = query.q.unwrap_or_default();
+    if q.trim().is_empty() {
+        return bad_request("q is required");
+    }
+
+    let mut kinds = Vec::new();
+    for raw in query.kinds.as_deref().unwrap_or("").split(',') {
+        if raw.trim().is_empty() {
+            continue;
+        }
+        match shared_types::SearchHitKind::parse(raw) {
+            Some(kind) => kinds.push(kind.as_str().to_string()),
+            None => return bad_request(format!("unknown search kind: {}", raw.trim())),
+        }
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let Some(match_expr) = match_expression(&q) else {
+        return (
+            StatusCode::OK,
+            Json(shared_types::SearchResponse {
+                query: q,
+                hits: Vec::new(),
+            }),
+        )
+            .into_response();
+    };
+
+    // Fold in anything committed since the last catch-up so a search right
+    // after sending a message finds it.
+    if let Err(e) = state
+        .app_state
+        .projections()
+        .catch_up(SEARCH_PROJECTION)
+        .await
+    {
+        tracing::warn!(error = %e, "Search index catch-up before query failed");
+    }
+
+    match search_index(&state.app_state.event_store(), match_expr, kinds, limit).await {
+        Ok(Ok(matches)) => {
+            let hits = matches.into_iter().filter_map(search_hit).collect();
+            (
+                StatusCode::OK,
+                Json(shared_types::SearchResponse { query: q, hits }),
+            )
+                .into_response()
+        }
+        Ok(Err(err)) => api_error(ErrorCode::InternalError, format!("EventStore error: {err}")),
+        Err(err) => api_error(ErrorCode::ActorUnavailable, format!("RPC error: {err}")),
+    }
+}
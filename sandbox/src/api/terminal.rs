@@ -10,7 +10,10 @@ use serde::{Deserialize, Serialize};
 use tokio::sync::{broadcast, mpsc};
 use uuid::Uuid;
 
+use shared_types::ErrorCode;
+
 use crate::actors::terminal::{TerminalArguments, TerminalError, TerminalMsg};
+use crate::api::error::api_error;
 use crate::api::ApiState;
 use crate::app_state::AppState;
 
@@ -41,6 +44,19 @@ pub struct TerminalWsQuery {
     working_dir: String,
 }
 
+/// Typed response for an error returned by the terminal actor.
+fn terminal_error(error: TerminalError) -> axum::response::Response {
+    let code = match &error {
+        TerminalError::NotRunning | TerminalError::AlreadyRunning => ErrorCode::Conflict,
+        TerminalError::SpawnFailed(_) => ErrorCode::ActorSpawnFailed,
+        TerminalError::InvalidInput(_) => ErrorCode::InvalidRequest,
+        TerminalError::Blocked(_) => ErrorCode::PolicyBlocked,
+        TerminalError::Timeout(_) | TerminalError::PtyNotSupported => ErrorCode::ServiceUnavailable,
+        TerminalError::Io(_) => ErrorCode::InternalError,
+    };
+    api_error(code, error.to_string())
+}
+
 fn default_shell() -> String {
     fn executable_exists(path: &str) -> bool {
         std::path::Path::new(path).exists()
@@ -289,13 +305,10 @@ pub async fn create_terminal(
             })),
         )
             .into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({
-                "error": format!("Failed to create terminal: {e}")
-            })),
-        )
-            .into_response(),
+        Err(e) => api_error(
+            ErrorCode::ActorSpawnFailed,
+            format!("Failed to create terminal: {e}"),
+        ),
     }
 }
 
@@ -315,25 +328,19 @@ pub async fn get_terminal_info(
     {
         Ok(actor) => actor,
         Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({
-                    "error": format!("Failed to get terminal: {e}")
-                })),
-            )
-                .into_response();
+            return api_error(
+                ErrorCode::ActorSpawnFailed,
+                format!("Failed to get terminal: {e}"),
+            );
         }
     };
 
     match ractor::call!(terminal, |reply| TerminalMsg::GetInfo { reply }) {
         Ok(info) => (StatusCode::OK, Json(info)).into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({
-                "error": format!("Failed to get info: {e:?}")
-            })),
-        )
-            .into_response(),
+        Err(e) => api_error(
+            ErrorCode::ActorUnavailable,
+            format!("Failed to get info: {e:?}"),
+        ),
     }
 }
 
@@ -353,13 +360,10 @@ pub async fn stop_terminal(
     {
         Ok(actor) => actor,
         Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({
-                    "error": format!("Failed to get terminal: {e}")
-                })),
-            )
-                .into_response();
+            return api_error(
+                ErrorCode::ActorSpawnFailed,
+                format!("Failed to get terminal: {e}"),
+            );
         }
     };
 
@@ -372,19 +376,10 @@ pub async fn stop_terminal(
             })),
         )
             .into_response(),
-        Ok(Err(e)) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({
-                "error": format!("Failed to stop terminal: {e:?}")
-            })),
-        )
-            .into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({
-                "error": format!("Failed to stop terminal: {e:?}")
-            })),
-        )
-            .into_response(),
+        Ok(Err(e)) => terminal_error(e),
+        Err(e) => api_error(
+            ErrorCode::ActorUnavailable,
+            format!("Failed to stop terminal: {e:?}"),
+        ),
     }
 }
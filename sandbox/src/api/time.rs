@@ -0,0 +1,130 @@
+//! Server time API
+//!
+//! Serves the server clock so the UI can correct relative times for client
+//! clock skew, and parses the time bounds the logs API accepts.
+
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use chrono::{DateTime, TimeDelta, Utc};
+use shared_types::ServerTimeResponse;
+
+fn clock_origin() -> Instant {
+    static ORIGIN: OnceLock<Instant> = OnceLock::new();
+    *ORIGIN.get_or_init(Instant::now)
+}
+
+/// Current server time with a monotonic hint.
+pub async fn get_time() -> impl IntoResponse {
+    let monotonic_ms = clock_origin().elapsed().as_millis() as u64;
+    (
+        StatusCode::OK,
+        Json(ServerTimeResponse {
+            now: Utc::now(),
+            monotonic_ms,
+        }),
+    )
+}
+
+/// Parse a relative offset such as `-15m`, `-2h` or `+30s`.
+///
+/// Units are `s`, `m`, `h`, `d` and `w`. The sign is required so a bare
+/// number is never mistaken for a duration.
+pub fn parse_relative_duration(raw: &str) -> Option<TimeDelta> {
+    let raw = raw.trim();
+    let (negative, rest) = match raw.as_bytes().first()? {
+        b'-' => (true, &raw[1..]),
+        b'+' => (false, &raw[1..]),
+        _ => return None,
+    };
+    let unit = rest.chars().last()?;
+    let amount: i64 = rest[..rest.len() - unit.len_utf8()].parse().ok()?;
+    if amount < 0 {
+        return None;
+    }
+    let unit_secs = match unit {
+        's' => 1,
+        'm' => 60,
+        'h' => 60 * 60,
+        'd' => 24 * 60 * 60,
+        'w' => 7 * 24 * 60 * 60,
+        _ => return None,
+    };
+    let delta = TimeDelta::try_seconds(amount.checked_mul(unit_secs)?)?;
+    Some(if negative { -delta } else { delta })
+}
+
+/// Parse a `since=`/`until=` bound: RFC 3339, `now`, or a relative offset
+/// from `now`.
+pub fn parse_time_bound(raw: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    let trimmed = raw.trim();
+    if trimmed.eq_ignore_ascii_case("now") {
+        return Ok(now);
+    }
+    if let Some(delta) = parse_relative_duration(trimmed) {
+        return now
+            .checked_add_signed(delta)
+            .ok_or_else(|| format!("time bound out of range: {raw}"));
+    }
+    DateTime::parse_from_rfc3339(trimmed)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|_| {
+            format!("invalid time bound '{raw}': expected RFC 3339, 'now', or an offset like -15m")
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn relative_durations_parse_each_unit() {
+        assert_eq!(
+            parse_relative_duration("-15m"),
+            Some(TimeDelta::minutes(-15))
+        );
+        assert_eq!(
+            parse_relative_duration("-30s"),
+            Some(TimeDelta::seconds(-30))
+        );
+        assert_eq!(parse_relative_duration("+2h"), Some(TimeDelta::hours(2)));
+        assert_eq!(parse_relative_duration("-1d"), Some(TimeDelta::days(-1)));
+        assert_eq!(parse_relative_duration(" -1w "), Some(TimeDelta::weeks(-1)));
+    }
+
+    #[test]
+    fn malformed_relative_durations_are_rejected() {
+        for raw in [
+            "15m",
+            "-m",
+            "-15",
+            "-15y",
+            "--15m",
+            "-1.5h",
+            "",
+            "-",
+            "-99999999999999w",
+        ] {
+            assert_eq!(parse_relative_duration(raw), None, "{raw}");
+        }
+    }
+
+    #[test]
+    fn time_bounds_accept_rfc3339_now_and_offsets() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 13, 22, 0, 0).unwrap();
+        assert_eq!(parse_time_bound("now", now), Ok(now));
+        assert_eq!(
+            parse_time_bound("-15m", now),
+            Ok(Utc.with_ymd_and_hms(2026, 3, 13, 21, 45, 0).unwrap())
+        );
+        assert_eq!(
+            parse_time_bound("2026-03-13T23:30:00+01:00", now),
+            Ok(Utc.with_ymd_and_hms(2026, 3, 13, 22, 30, 0).unwrap())
+        );
+        assert!(parse_time_bound("yesterday", now).is_err());
+    }
+}
@@ -0,0 +1,28 @@
+//! Usage API endpoints
+//!
+//! Reports workspace resource usage so users can see what to clean up before
+//! they hit their quota, and session activity so the hypervisor knows when
+//! the sandbox is idle.
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+
+use super::ApiState;
+
+/// Workspace disk usage by category, largest first, against the quota.
+pub async fn get_disk_usage(State(state): State<ApiState>) -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        Json(state.app_state.disk_usage().usage().await),
+    )
+}
+
+/// Latest activity per session; the hypervisor idle watchdog polls this.
+pub async fn get_activity(State(state): State<ApiState>) -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        Json(state.app_state.activity().snapshot(chrono::Utc::now())),
+    )
+}
@@ -10,9 +10,11 @@ use axum::response::IntoResponse;
 use axum::Json;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use shared_types::ErrorCode;
 
 use crate::actors::event_store::{get_events_for_actor, EventStoreMsg};
 use crate::actors::model_config::ModelRegistry;
+use crate::api::error::api_error;
 use crate::api::ApiState;
 
 const DEFAULT_THEME: &str = "dark";
@@ -65,22 +67,11 @@ pub async fn get_user_preferences(
             )
                 .into_response()
         }
-        Ok(Err(_)) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({
-                "success": false,
-                "error": "EventStore error"
-            })),
-        )
-            .into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({
-                "success": false,
-                "error": format!("Failed to get preferences: {}", e)
-            })),
-        )
-            .into_response(),
+        Ok(Err(_)) => api_error(ErrorCode::InternalError, "EventStore error"),
+        Err(e) => api_error(
+            ErrorCode::ActorUnavailable,
+            format!("Failed to get preferences: {}", e),
+        ),
     }
 }
 
@@ -91,14 +82,7 @@ pub async fn update_user_preferences(
     Json(req): Json<UpdateUserPreferencesRequest>,
 ) -> impl IntoResponse {
     if !is_allowed_theme(&req.theme) {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(json!({
-                "success": false,
-                "error": "theme must be 'light' or 'dark'"
-            })),
-        )
-            .into_response();
+        return api_error(ErrorCode::InvalidRequest, "theme must be 'light' or 'dark'");
     }
 
     let event_store = state.app_state.event_store();
@@ -107,6 +91,7 @@ pub async fn update_user_preferences(
         payload: json!({ "theme": req.theme }),
         actor_id: user_actor_id(&user_id),
         user_id,
+        idempotency_key: None,
     };
 
     match ractor::call!(event_store, |reply| EventStoreMsg::Append {
@@ -129,22 +114,8 @@ pub async fn update_user_preferences(
             )
                 .into_response()
         }
-        Ok(Err(e)) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({
-                "success": false,
-                "error": e.to_string()
-            })),
-        )
-            .into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({
-                "success": false,
-                "error": format!("Actor error: {}", e)
-            })),
-        )
-            .into_response(),
+        Ok(Err(e)) => api_error(ErrorCode::InternalError, e.to_string()),
+        Err(e) => api_error(ErrorCode::ActorUnavailable, format!("Actor error: {}", e)),
     }
 }
 
@@ -261,24 +232,16 @@ pub async fn update_model_config(
     // Validate all callsites and model IDs
     for (callsite, model_id) in &req.callsite_models {
         if !CALLSITES.contains(&callsite.as_str()) {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(json!({
-                    "success": false,
-                    "error": format!("unknown callsite: {callsite}")
-                })),
-            )
-                .into_response();
+            return api_error(
+                ErrorCode::InvalidRequest,
+                format!("unknown callsite: {callsite}"),
+            );
         }
         if registry.get(model_id).is_none() {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(json!({
-                    "success": false,
-                    "error": format!("unknown model: {model_id}")
-                })),
-            )
-                .into_response();
+            return api_error(
+                ErrorCode::InvalidRequest,
+                format!("unknown model: {model_id}"),
+            );
         }
     }
 
@@ -288,6 +251,7 @@ pub async fn update_model_config(
         payload: json!({ "callsite_models": req.callsite_models }),
         actor_id: user_actor_id(&user_id),
         user_id,
+        idempotency_key: None,
     };
 
     match ractor::call!(event_store, |reply| EventStoreMsg::Append {
@@ -306,15 +270,7 @@ pub async fn update_model_config(
             })
             .into_response()
         }
-        Ok(Err(e)) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({ "success": false, "error": e.to_string() })),
-        )
-            .into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({ "success": false, "error": format!("Actor error: {e}") })),
-        )
-            .into_response(),
+        Ok(Err(e)) => api_error(ErrorCode::InternalError, e.to_string()),
+        Err(e) => api_error(ErrorCode::ActorUnavailable, format!("Actor error: {e}")),
     }
 }
@@ -9,10 +9,13 @@ use axum::Json;
 use base64::Engine;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use shared_types::{ApiError, ErrorCode, ViewerConflictPayload};
 use std::path::{Path, PathBuf};
 
 use crate::actors::event_store::{AppendEvent, EventStoreMsg};
+use crate::api::error::{api_error, error_response};
 use crate::api::logs::{build_run_markdown_from_store, RunLogQuery};
+use crate::api::viewer_images::jpeg_orientation;
 use crate::api::ApiState;
 
 #[derive(Debug, Deserialize)]
@@ -38,14 +41,15 @@ pub struct ViewerContentResponse {
     pub rendered_html: Option<String>,
     pub revision: shared_types::ViewerRevision,
     pub readonly: bool,
+    /// EXIF orientation (1-8) of a JPEG, so the client can rotate it itself.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub orientation: Option<u8>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct PatchViewerContentResponse {
     pub success: bool,
     pub revision: Option<shared_types::ViewerRevision>,
-    pub error: Option<String>,
-    pub latest: Option<ConflictLatest>,
 }
 
 #[derive(Debug, Serialize)]
@@ -73,6 +77,7 @@ struct ViewerSnapshot {
     content: String,
     revision: shared_types::ViewerRevision,
     readonly: bool,
+    orientation: Option<u8>,
 }
 
 pub async fn get_viewer_content(
@@ -97,29 +102,22 @@ pub async fn get_viewer_content(
                         rendered_html,
                         revision: make_revision(0),
                         readonly: true,
+                        orientation: None,
                     }),
                 )
                     .into_response();
             }
             Err(err) if err.starts_with("bad_request:") => {
-                return (
-                    StatusCode::BAD_REQUEST,
-                    Json(json!({
-                        "success": false,
-                        "error": err.trim_start_matches("bad_request:")
-                    })),
-                )
-                    .into_response();
+                return api_error(
+                    ErrorCode::InvalidRequest,
+                    err.trim_start_matches("bad_request:"),
+                );
             }
             Err(err) => {
-                return (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(json!({
-                        "success": false,
-                        "error": format!("Failed to render run transcript: {err}")
-                    })),
-                )
-                    .into_response();
+                return api_error(
+                    ErrorCode::InternalError,
+                    format!("Failed to render run transcript: {err}"),
+                );
             }
         }
     }
@@ -138,6 +136,7 @@ pub async fn get_viewer_content(
                     rendered_html,
                     revision: snapshot.revision,
                     readonly: snapshot.readonly,
+                    orientation: snapshot.orientation,
                 }),
             )
                 .into_response()
@@ -156,35 +155,18 @@ pub async fn get_viewer_content(
                         rendered_html,
                         revision: snapshot.revision,
                         readonly: snapshot.readonly,
+                        orientation: snapshot.orientation,
                     }),
                 )
                     .into_response()
             }
-            Ok(None) => (
-                StatusCode::NOT_FOUND,
-                Json(json!({
-                    "success": false,
-                    "error": "resource_not_found"
-                })),
-            )
-                .into_response(),
-            Err(e) => (
-                StatusCode::BAD_REQUEST,
-                Json(json!({
-                    "success": false,
-                    "error": e
-                })),
-            )
-                .into_response(),
+            Ok(None) => api_error(ErrorCode::NotFound, "Resource not found"),
+            Err(e) => api_error(ErrorCode::InvalidRequest, e),
         },
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({
-                "success": false,
-                "error": format!("Failed to load viewer content: {e}")
-            })),
-        )
-            .into_response(),
+        Err(e) => api_error(
+            ErrorCode::InternalError,
+            format!("Failed to load viewer content: {e}"),
+        ),
     }
 }
 
@@ -197,31 +179,16 @@ pub async fn patch_viewer_content(
     let mime = infer_mime(&uri);
 
     if is_readonly_mime(&mime) || is_directory_uri(&uri) {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(PatchViewerContentResponse {
-                success: false,
-                revision: None,
-                error: Some("readonly_resource".to_string()),
-                latest: None,
-            }),
-        )
-            .into_response();
+        return api_error(ErrorCode::PermissionDenied, "Resource is read-only");
     }
 
     let latest = match get_latest_snapshot(&event_store, &uri).await {
         Ok(snapshot) => snapshot,
         Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(PatchViewerContentResponse {
-                    success: false,
-                    revision: None,
-                    error: Some(format!("Failed to load revision: {e}")),
-                    latest: None,
-                }),
+            return api_error(
+                ErrorCode::InternalError,
+                format!("Failed to load revision: {e}"),
             )
-                .into_response()
         }
     };
 
@@ -237,41 +204,40 @@ pub async fn patch_viewer_content(
             .map(|s| s.revision.clone())
             .unwrap_or_else(|| make_revision(0));
 
-        let payload = json!({
-            "uri": uri,
-            "mime": mime,
-            "base_rev": req.base_rev,
-            "new_rev": current_rev,
-            "content_hash": hash_content(&current_content),
-            "window_id": req.window_id.clone().unwrap_or_default(),
-            "user_id": req.user_id.clone().unwrap_or_else(|| "user-1".to_string()),
-            "updated_at": chrono::Utc::now().to_rfc3339(),
-        });
+        let conflict = ViewerConflictPayload {
+            path: uri.clone(),
+            base_rev: req.base_rev,
+            current_rev,
+            attempted_content: req.content.clone(),
+            window_id: req.window_id.clone().unwrap_or_default(),
+            user_id: req.user_id.clone().unwrap_or_else(|| "user-1".to_string()),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        };
+        let payload = serde_json::to_value(&conflict).unwrap_or_else(|_| json!({}));
 
         let append = AppendEvent {
             event_type: shared_types::EVENT_VIEWER_CONTENT_CONFLICT.to_string(),
             payload,
             actor_id: viewer_actor_id(&req.uri),
             user_id: req.user_id.clone().unwrap_or_else(|| "user-1".to_string()),
+            idempotency_key: None,
         };
         let _ = ractor::call!(event_store, |reply| EventStoreMsg::Append {
             event: append,
             reply,
         });
 
-        return (
-            StatusCode::CONFLICT,
-            Json(PatchViewerContentResponse {
-                success: false,
-                revision: None,
-                error: Some("revision_conflict".to_string()),
-                latest: Some(ConflictLatest {
-                    content: current_content,
-                    revision: latest_revision,
-                }),
-            }),
-        )
-            .into_response();
+        let latest = ConflictLatest {
+            content: current_content,
+            revision: latest_revision,
+        };
+        return error_response(
+            ApiError::new(
+                ErrorCode::DocumentConflict,
+                "Content was changed by another window",
+            )
+            .with_details(json!({ "latest": latest, "conflict": conflict })),
+        );
     }
 
     let new_rev = current_rev + 1;
@@ -293,6 +259,7 @@ pub async fn patch_viewer_content(
         payload: serde_json::to_value(payload).unwrap_or_else(|_| json!({})),
         actor_id: viewer_actor_id(&req.uri),
         user_id: req.user_id.unwrap_or_else(|| "user-1".to_string()),
+        idempotency_key: None,
     };
 
     match ractor::call!(event_store, |reply| EventStoreMsg::Append {
@@ -307,31 +274,17 @@ pub async fn patch_viewer_content(
                     rev: new_rev,
                     updated_at,
                 }),
-                error: None,
-                latest: None,
-            }),
-        )
-            .into_response(),
-        Ok(Err(e)) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(PatchViewerContentResponse {
-                success: false,
-                revision: None,
-                error: Some(format!("Failed to persist content: {e}")),
-                latest: None,
-            }),
-        )
-            .into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(PatchViewerContentResponse {
-                success: false,
-                revision: None,
-                error: Some(format!("Event store actor error: {e}")),
-                latest: None,
             }),
         )
             .into_response(),
+        Ok(Err(e)) => api_error(
+            ErrorCode::InternalError,
+            format!("Failed to persist content: {e}"),
+        ),
+        Err(e) => api_error(
+            ErrorCode::InternalError,
+            format!("Event store actor error: {e}"),
+        ),
     }
 }
 
@@ -341,7 +294,7 @@ fn viewer_actor_id(uri: &str) -> String {
 
 use crate::paths::sandbox_root;
 
-fn file_path_from_uri(uri: &str) -> Option<String> {
+pub(crate) fn file_path_from_uri(uri: &str) -> Option<String> {
     if let Some(path) = uri.strip_prefix("file://") {
         return Some(path.to_string());
     }
@@ -362,7 +315,7 @@ fn file_path_from_uri(uri: &str) -> Option<String> {
     None
 }
 
-fn infer_mime(uri: &str) -> String {
+pub(crate) fn infer_mime(uri: &str) -> String {
     if uri.starts_with("runlog://") {
         return "text/markdown".to_string();
     }
@@ -465,6 +418,7 @@ fn load_initial_snapshot(uri: &str) -> Result<Option<ViewerSnapshot>, String> {
             content: uri.to_string(),
             revision: make_revision(0),
             readonly: true,
+            orientation: None,
         }));
     }
 
@@ -497,6 +451,7 @@ fn load_initial_snapshot(uri: &str) -> Result<Option<ViewerSnapshot>, String> {
             content: listing,
             revision: make_revision(0),
             readonly: true,
+            orientation: None,
         }));
     }
     let mime = infer_mime(uri);
@@ -507,6 +462,7 @@ fn load_initial_snapshot(uri: &str) -> Result<Option<ViewerSnapshot>, String> {
             Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
             Err(e) => return Err(format!("Failed to read image: {e}")),
         };
+        let orientation = jpeg_orientation(&bytes);
         let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
         let content = format!("data:{mime};base64,{encoded}");
         return Ok(Some(ViewerSnapshot {
@@ -514,6 +470,7 @@ fn load_initial_snapshot(uri: &str) -> Result<Option<ViewerSnapshot>, String> {
             content,
             revision: make_revision(0),
             readonly: true,
+            orientation,
         }));
     }
 
@@ -528,6 +485,7 @@ fn load_initial_snapshot(uri: &str) -> Result<Option<ViewerSnapshot>, String> {
         content,
         revision: make_revision(0),
         readonly: false,
+        orientation: None,
     }))
 }
 
@@ -563,6 +521,7 @@ async fn get_latest_snapshot(
                 updated_at: payload.updated_at.clone(),
             },
             readonly: is_readonly_mime(&payload.mime),
+            orientation: None,
         });
     }
 
@@ -0,0 +1,546 @@
+//! Image-set viewer endpoints
+//!
+//! Collects a run's image artifacts into one `image_set` viewer descriptor and
+//! serves downscaled, cached thumbnails so the desktop strip never has to pull
+//! full-size images. Also hosts the JPEG EXIF reader used for orientation.
+
+use std::collections::HashSet;
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+use axum::body::Bytes;
+use axum::extract::{Query, State};
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::Json;
+use image::metadata::Orientation;
+use image::{DynamicImage, ImageFormat, ImageReader};
+use serde::{Deserialize, Serialize};
+use shared_types::{ErrorCode, ViewerCapabilities, ViewerDescriptor, ViewerKind, ViewerResource};
+
+use crate::actors::event_store::get_recent_events;
+use crate::api::error::api_error;
+use crate::api::run_observability::event_belongs_to_run;
+use crate::api::viewer::{file_path_from_uri, infer_mime};
+use crate::api::ApiState;
+use crate::ttl_cache::TtlCache;
+
+const EVENT_PAGE_SIZE: i64 = 1000;
+/// Longest edge of a raster thumbnail, in pixels.
+const THUMBNAIL_MAX_EDGE: u32 = 256;
+/// SVGs can't be rasterized here and are served as-is up to this size.
+const THUMBNAIL_MAX_SVG_BYTES: usize = 256 * 1024;
+const THUMBNAIL_CACHE_ENTRIES: usize = 128;
+/// Thumbnails not requested for this long are dropped from the cache.
+const THUMBNAIL_CACHE_TTL_MINUTES: i64 = 30;
+
+const EXIF_TAG_ORIENTATION: u16 = 0x0112;
+
+#[derive(Debug, Deserialize)]
+pub struct ImageSetQuery {
+    pub run_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ThumbnailQuery {
+    pub uri: String,
+}
+
+/// An artifact left out of an image set, with the reason shown to the user.
+#[derive(Debug, Serialize)]
+pub struct SkippedResource {
+    pub uri: String,
+    pub mime: String,
+    /// `not_an_image`, `not_found`, or `unsupported_uri`.
+    pub reason: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImageSetResponse {
+    pub success: bool,
+    pub run_id: String,
+    /// `None` when the run produced no viewable images.
+    pub viewer: Option<ViewerDescriptor>,
+    pub skipped: Vec<SkippedResource>,
+}
+
+/// GET /viewer/image-set?run_id=... - every image artifact of a run as one set.
+pub async fn get_run_image_set(
+    State(state): State<ApiState>,
+    Query(query): Query<ImageSetQuery>,
+) -> impl IntoResponse {
+    let run_id = query.run_id.trim().to_string();
+    if run_id.is_empty() {
+        return api_error(ErrorCode::InvalidRequest, "run_id is required");
+    }
+
+    let references = match run_artifact_references(&state, &run_id).await {
+        Ok(references) => references,
+        Err(e) => return api_error(ErrorCode::InternalError, e),
+    };
+
+    let mut seen = HashSet::new();
+    let mut resources = Vec::new();
+    let mut skipped = Vec::new();
+    for reference in references {
+        let uri = artifact_uri(&reference);
+        if !seen.insert(uri.clone()) {
+            continue;
+        }
+        let mime = resource_mime(&uri);
+        let reason = if !mime.starts_with("image/") {
+            Some("not_an_image")
+        } else if uri.starts_with("data:") {
+            None
+        } else {
+            match file_path_from_uri(&uri) {
+                Some(path) if std::path::Path::new(&path).is_file() => None,
+                Some(_) => Some("not_found"),
+                None => Some("unsupported_uri"),
+            }
+        };
+        match reason {
+            Some(reason) => skipped.push(SkippedResource { uri, mime, reason }),
+            None => resources.push(ViewerResource { uri, mime }),
+        }
+    }
+
+    let viewer = resources.first().cloned().map(|first| ViewerDescriptor {
+        kind: ViewerKind::ImageSet,
+        resource: first,
+        capabilities: ViewerCapabilities { readonly: true },
+        resources,
+    });
+
+    Json(ImageSetResponse {
+        success: true,
+        run_id,
+        viewer,
+        skipped,
+    })
+    .into_response()
+}
+
+/// Artifact references published for a run, oldest first.
+async fn run_artifact_references(state: &ApiState, run_id: &str) -> Result<Vec<String>, String> {
+    let event_store = state.app_state.event_store();
+    let mut references = Vec::new();
+    let mut since_seq = 0_i64;
+    loop {
+        let page = match get_recent_events(
+            &event_store,
+            since_seq,
+            EVENT_PAGE_SIZE,
+            Some(shared_types::EVENT_TOPIC_ARTIFACT_CREATED.to_string()),
+            None,
+            None,
+        )
+        .await
+        {
+            Ok(Ok(events)) => events,
+            Ok(Err(e)) => return Err(format!("EventStore error: {e}")),
+            Err(e) => return Err(format!("RPC error: {e}")),
+        };
+        let Some(last) = page.last() else {
+            break;
+        };
+        since_seq = last.seq;
+        let full_page = page.len() as i64 == EVENT_PAGE_SIZE;
+
+        for event in page {
+            if event.event_type != shared_types::EVENT_TOPIC_ARTIFACT_CREATED {
+                continue;
+            }
+            let correlated =
+                event.payload.get("correlation_id").and_then(|v| v.as_str()) == Some(run_id);
+            if !correlated && !event_belongs_to_run(&event, run_id) {
+                continue;
+            }
+            if let Some(reference) = event
+                .payload
+                .get("artifact")
+                .and_then(|artifact| artifact.get("reference"))
+                .and_then(|v| v.as_str())
+                .filter(|reference| !reference.trim().is_empty())
+            {
+                references.push(reference.to_string());
+            }
+        }
+
+        if !full_page {
+            break;
+        }
+    }
+    Ok(references)
+}
+
+/// Viewer URI for an artifact reference; bare paths resolve inside the sandbox.
+fn artifact_uri(reference: &str) -> String {
+    let reference = reference.trim();
+    if reference.contains("://") || reference.starts_with("data:") {
+        reference.to_string()
+    } else if reference.starts_with('/') {
+        format!("file://{reference}")
+    } else {
+        format!("sandbox://{reference}")
+    }
+}
+
+fn resource_mime(uri: &str) -> String {
+    match uri.strip_prefix("data:") {
+        Some(rest) => rest
+            .split([';', ','])
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+        None => infer_mime(uri),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ThumbnailKey {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+#[derive(Debug, Clone)]
+struct Thumbnail {
+    mime: String,
+    bytes: Bytes,
+}
+
+fn thumbnail_cache() -> &'static Mutex<TtlCache<ThumbnailKey, Thumbnail>> {
+    static CACHE: OnceLock<Mutex<TtlCache<ThumbnailKey, Thumbnail>>> = OnceLock::new();
+    CACHE.get_or_init(|| {
+        Mutex::new(TtlCache::new(
+            chrono::Duration::minutes(THUMBNAIL_CACHE_TTL_MINUTES),
+            THUMBNAIL_CACHE_ENTRIES,
+        ))
+    })
+}
+
+/// GET /viewer/thumbnail?uri=... - a small preview of a local image.
+///
+/// Raster images are decoded, turned upright per their EXIF orientation and
+/// scaled to fit [`THUMBNAIL_MAX_EDGE`], then served as PNG. SVGs are served
+/// as-is when under [`THUMBNAIL_MAX_SVG_BYTES`].
+pub async fn get_viewer_thumbnail(Query(query): Query<ThumbnailQuery>) -> impl IntoResponse {
+    let Some(path) = file_path_from_uri(&query.uri) else {
+        return api_error(ErrorCode::InvalidRequest, "Unsupported viewer URI");
+    };
+    let mime = infer_mime(&query.uri);
+    if !mime.starts_with("image/") {
+        return api_error(ErrorCode::UnsupportedMediaType, "Not an image");
+    }
+
+    let path = PathBuf::from(path);
+    let metadata = match tokio::fs::metadata(&path).await {
+        Ok(metadata) if metadata.is_file() => metadata,
+        Ok(_) => return api_error(ErrorCode::NotFound, "Image not found"),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return api_error(ErrorCode::NotFound, "Image not found")
+        }
+        Err(e) => {
+            return api_error(
+                ErrorCode::InternalError,
+                format!("Failed to stat image: {e}"),
+            )
+        }
+    };
+    let key = ThumbnailKey {
+        path: path.clone(),
+        modified: metadata.modified().ok(),
+        len: metadata.len(),
+    };
+
+    let cached = thumbnail_cache()
+        .lock()
+        .ok()
+        .and_then(|mut cache| cache.get(&key, chrono::Utc::now()).cloned());
+    let thumbnail = match cached {
+        Some(thumbnail) => thumbnail,
+        None => {
+            let bytes = match tokio::fs::read(&path).await {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    return api_error(
+                        ErrorCode::InternalError,
+                        format!("Failed to read image: {e}"),
+                    )
+                }
+            };
+            let built = tokio::task::spawn_blocking(move || build_thumbnail(&mime, bytes)).await;
+            let thumbnail = match built {
+                Ok(Ok(thumbnail)) => thumbnail,
+                Ok(Err(ThumbnailError::TooLarge)) => {
+                    return api_error(ErrorCode::PayloadTooLarge, "Image too large to preview")
+                }
+                Ok(Err(ThumbnailError::Undecodable(e))) => {
+                    return api_error(
+                        ErrorCode::UnsupportedMediaType,
+                        format!("Failed to decode image: {e}"),
+                    )
+                }
+                Err(e) => {
+                    return api_error(
+                        ErrorCode::InternalError,
+                        format!("Thumbnail task failed: {e}"),
+                    )
+                }
+            };
+            if let Ok(mut cache) = thumbnail_cache().lock() {
+                let now = chrono::Utc::now();
+                cache.evict_expired(now);
+                cache.insert(key, thumbnail.clone(), now);
+            }
+            thumbnail
+        }
+    };
+
+    (
+        [
+            (header::CONTENT_TYPE, thumbnail.mime),
+            (header::CACHE_CONTROL, "private, max-age=300".to_string()),
+        ],
+        thumbnail.bytes,
+    )
+        .into_response()
+}
+
+#[derive(Debug)]
+enum ThumbnailError {
+    TooLarge,
+    Undecodable(String),
+}
+
+fn build_thumbnail(mime: &str, bytes: Vec<u8>) -> Result<Thumbnail, ThumbnailError> {
+    if mime == "image/svg+xml" {
+        if bytes.len() > THUMBNAIL_MAX_SVG_BYTES {
+            return Err(ThumbnailError::TooLarge);
+        }
+        return Ok(Thumbnail {
+            mime: mime.to_string(),
+            bytes: Bytes::from(bytes),
+        });
+    }
+
+    let orientation = jpeg_orientation(&bytes).and_then(Orientation::from_exif);
+    let image = ImageReader::new(Cursor::new(&bytes))
+        .with_guessed_format()
+        .map_err(|e| ThumbnailError::Undecodable(e.to_string()))?
+        .decode()
+        .map_err(|e| ThumbnailError::Undecodable(e.to_string()))?;
+    let thumbnail = downscale(image, orientation);
+
+    let mut png = Vec::new();
+    thumbnail
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| ThumbnailError::Undecodable(e.to_string()))?;
+    Ok(Thumbnail {
+        mime: "image/png".to_string(),
+        bytes: Bytes::from(png),
+    })
+}
+
+/// `image` turned upright and scaled to fit [`THUMBNAIL_MAX_EDGE`]; images
+/// already small enough keep their size.
+fn downscale(mut image: DynamicImage, orientation: Option<Orientation>) -> DynamicImage {
+    if let Some(orientation) = orientation {
+        image.apply_orientation(orientation);
+    }
+    if image.width() <= THUMBNAIL_MAX_EDGE && image.height() <= THUMBNAIL_MAX_EDGE {
+        return image;
+    }
+    image.thumbnail(THUMBNAIL_MAX_EDGE, THUMBNAIL_MAX_EDGE)
+}
+
+/// EXIF orientation (1-8) of a JPEG, if it declares one.
+pub(crate) fn jpeg_orientation(bytes: &[u8]) -> Option<u8> {
+    let tiff = Tiff::parse(jpeg_exif_block(bytes)?)?;
+    let entry = tiff.find_tag(tiff.first_ifd()?, EXIF_TAG_ORIENTATION)?;
+    let orientation = u8::try_from(tiff.scalar(entry)?).ok()?;
+    (1..=8).contains(&orientation).then_some(orientation)
+}
+
+/// The TIFF payload of a JPEG's APP1 `Exif` segment.
+fn jpeg_exif_block(bytes: &[u8]) -> Option<&[u8]> {
+    if !bytes.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+    let mut pos = 2;
+    loop {
+        if *bytes.get(pos)? != 0xFF {
+            return None;
+        }
+        let marker = *bytes.get(pos + 1)?;
+        // Metadata segments all precede start-of-scan.
+        if marker == 0xDA || marker == 0xD9 {
+            return None;
+        }
+        let len = u16::from_be_bytes([*bytes.get(pos + 2)?, *bytes.get(pos + 3)?]) as usize;
+        if len < 2 {
+            return None;
+        }
+        let segment = bytes.get(pos + 4..pos + 2 + len)?;
+        if marker == 0xE1 {
+            if let Some(tiff) = segment.strip_prefix(b"Exif\0\0") {
+                return Some(tiff);
+            }
+        }
+        pos += 2 + len;
+    }
+}
+
+struct Tiff<'a> {
+    data: &'a [u8],
+    little_endian: bool,
+}
+
+impl<'a> Tiff<'a> {
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        let little_endian = match data.get(..2)? {
+            b"II" => true,
+            b"MM" => false,
+            _ => return None,
+        };
+        let tiff = Self {
+            data,
+            little_endian,
+        };
+        (tiff.u16_at(2)? == 42).then_some(tiff)
+    }
+
+    fn u16_at(&self, offset: usize) -> Option<u16> {
+        let bytes = [*self.data.get(offset)?, *self.data.get(offset + 1)?];
+        Some(if self.little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    }
+
+    fn u32_at(&self, offset: usize) -> Option<u32> {
+        let bytes: [u8; 4] = self.data.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn first_ifd(&self) -> Option<usize> {
+        Some(self.u32_at(4)? as usize)
+    }
+
+    /// Offset of the 12-byte directory entry for `tag`.
+    fn find_tag(&self, ifd: usize, tag: u16) -> Option<usize> {
+        let count = self.u16_at(ifd)? as usize;
+        (0..count)
+            .map(|i| ifd + 2 + i * 12)
+            .find(|&entry| self.u16_at(entry) == Some(tag))
+    }
+
+    /// Inline value of a SHORT or LONG entry.
+    fn scalar(&self, entry: usize) -> Option<u32> {
+        match self.u16_at(entry + 2)? {
+            3 => self.u16_at(entry + 8).map(u32::from),
+            4 => self.u32_at(entry + 8),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A JPEG whose only segment is an EXIF block with the given IFD0
+    /// orientation.
+    fn exif_jpeg(little_endian: bool, orientation: u16) -> Vec<u8> {
+        let u16b = |v: u16| {
+            if little_endian {
+                v.to_le_bytes()
+            } else {
+                v.to_be_bytes()
+            }
+        };
+        let u32b = |v: u32| {
+            if little_endian {
+                v.to_le_bytes()
+            } else {
+                v.to_be_bytes()
+            }
+        };
+        let entry = |tag: u16, kind: u16, value: u32| {
+            let mut out = Vec::new();
+            out.extend(u16b(tag));
+            out.extend(u16b(kind));
+            out.extend(u32b(1));
+            if kind == 3 {
+                out.extend(u16b(value as u16));
+                out.extend([0, 0]);
+            } else {
+                out.extend(u32b(value));
+            }
+            out
+        };
+
+        let mut tiff = Vec::new();
+        tiff.extend(if little_endian { b"II" } else { b"MM" });
+        tiff.extend(u16b(42));
+        tiff.extend(u32b(8));
+        // IFD0 at 8: one entry and no IFD1.
+        tiff.extend(u16b(1));
+        tiff.extend(entry(EXIF_TAG_ORIENTATION, 3, u32::from(orientation)));
+        tiff.extend(u32b(0));
+
+        let mut segment = b"Exif\0\0".to_vec();
+        segment.extend(tiff);
+        let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1];
+        jpeg.extend(((segment.len() + 2) as u16).to_be_bytes());
+        jpeg.extend(segment);
+        jpeg.extend([0xFF, 0xDA, 0x00, 0x02, 0xFF, 0xD9]);
+        jpeg
+    }
+
+    #[test]
+    fn reads_orientation_in_both_byte_orders() {
+        assert_eq!(jpeg_orientation(&exif_jpeg(true, 6)), Some(6));
+        assert_eq!(jpeg_orientation(&exif_jpeg(false, 8)), Some(8));
+    }
+
+    #[test]
+    fn ignores_missing_or_invalid_orientation() {
+        assert_eq!(jpeg_orientation(&exif_jpeg(true, 0)), None);
+        assert_eq!(jpeg_orientation(&exif_jpeg(true, 9)), None);
+        assert_eq!(jpeg_orientation(&[0xFF, 0xD8, 0xFF, 0xD9]), None);
+        assert_eq!(jpeg_orientation(b"\x89PNG\r\n\x1a\n"), None);
+    }
+
+    #[test]
+    fn downscale_fits_the_longest_edge_after_orientation() {
+        let wide = DynamicImage::new_rgb8(1024, 512);
+        let thumbnail = downscale(wide.clone(), None);
+        assert_eq!((thumbnail.width(), thumbnail.height()), (256, 128));
+
+        let upright = downscale(wide, Orientation::from_exif(6));
+        assert_eq!((upright.width(), upright.height()), (128, 256));
+
+        let small = downscale(DynamicImage::new_rgb8(40, 20), None);
+        assert_eq!((small.width(), small.height()), (40, 20));
+    }
+
+    #[test]
+    fn maps_artifact_references_to_viewer_uris() {
+        assert_eq!(
+            artifact_uri("reports/plot.png"),
+            "sandbox://reports/plot.png"
+        );
+        assert_eq!(artifact_uri("/tmp/plot.png"), "file:///tmp/plot.png");
+        assert_eq!(artifact_uri("sandbox://a.png"), "sandbox://a.png");
+        assert_eq!(resource_mime("data:image/gif;base64,R0lG"), "image/gif");
+    }
+}
@@ -2,12 +2,12 @@
 //!
 //! Uses Axum WebSocket support.
 
-use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade};
 use axum::extract::State;
 use axum::response::IntoResponse;
 use futures_util::{SinkExt, StreamExt};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, PoisonError};
 use std::time::Duration;
 use tokio::sync::{mpsc, Mutex};
 use uuid::Uuid;
@@ -15,18 +15,52 @@ use uuid::Uuid;
 use crate::actors::desktop::DesktopActorMsg;
 use crate::actors::event_store::EventStoreMsg;
 use crate::api::ApiState;
-use crate::app_state::AppState;
+use crate::app_state::{AppState, PROVIDER_GATEWAY_OUTAGE_MESSAGE};
 pub use shared_types::DesktopWsMessage as WsMessage;
-use shared_types::WriterRunEvent;
+use shared_types::{
+    ActivitySource, BuildVersion, ConductorRunStateDelta, PatchGranularity, WriterRunEvent,
+    WriterRunPatchOrder, WsErrorCode,
+};
+
+/// Desktops a single WebSocket session may be subscribed to at once.
+pub const MAX_SUBSCRIPTIONS_PER_SESSION: usize = 16;
+
+/// Largest inbound message a session processes. Larger messages are answered
+/// with a `message_too_large` error and dropped; the connection stays open.
+pub const MAX_INBOUND_MESSAGE_BYTES: usize = 64 * 1024;
+
+/// Largest inbound message the socket reassembles at all. Fragments count
+/// toward the whole message, and exceeding this closes the connection.
+pub const MAX_INBOUND_MESSAGE_HARD_BYTES: usize = 1024 * 1024;
+
+/// A session subscribed to a desktop, with its writer-run forwarding filter
+/// and opt-in topics.
+#[derive(Debug, Clone)]
+pub struct WsSubscriber {
+    pub sender: mpsc::UnboundedSender<Message>,
+    pub patch_granularity: PatchGranularity,
+    pub topics: Vec<String>,
+}
 
 /// Shared state for WebSocket sessions
-pub type WsSessions = Arc<Mutex<HashMap<String, HashMap<Uuid, mpsc::UnboundedSender<Message>>>>>;
+pub type WsSessions = Arc<Mutex<HashMap<String, HashMap<Uuid, WsSubscriber>>>>;
+
+/// Patch ids of recent writer runs, for [`flag_orphan_changeset`].
+pub type PatchOrder = Arc<std::sync::Mutex<WriterRunPatchOrder>>;
+
+pub fn new_patch_order() -> PatchOrder {
+    Arc::new(std::sync::Mutex::new(WriterRunPatchOrder::new(
+        PATCH_ORDER_MAX_RUNS,
+    )))
+}
 
 /// WebSocket handler
 pub async fn ws_handler(ws: WebSocketUpgrade, State(state): State<ApiState>) -> impl IntoResponse {
     let app_state = state.app_state.clone();
     let sessions = state.ws_sessions.clone();
-    ws.on_upgrade(move |socket| handle_socket(socket, app_state, sessions))
+    ws.max_message_size(MAX_INBOUND_MESSAGE_HARD_BYTES)
+        .max_frame_size(MAX_INBOUND_MESSAGE_HARD_BYTES)
+        .on_upgrade(move |socket| handle_socket(socket, app_state, sessions))
 }
 
 async fn handle_socket(socket: WebSocket, app_state: Arc<AppState>, sessions: WsSessions) {
@@ -34,7 +68,7 @@ async fn handle_socket(socket: WebSocket, app_state: Arc<AppState>, sessions: Ws
 
     let (mut sender, mut receiver) = socket.split();
     let (tx, mut rx) = mpsc::unbounded_channel::<Message>();
-    let writer = tokio::spawn(async move {
+    let mut writer = tokio::spawn(async move {
         while let Some(msg) = rx.recv().await {
             if sender.send(msg).await.is_err() {
                 break;
@@ -44,10 +78,45 @@ async fn handle_socket(socket: WebSocket, app_state: Arc<AppState>, sessions: Ws
 
     let _ = send_json(&tx, &WsMessage::Pong);
 
-    let mut current_desktop_id: Option<String> = None;
+    let mut subscribed_desktops: Vec<String> = Vec::new();
+    let mut patch_granularity = PatchGranularity::default();
     let session_id = Uuid::new_v4();
 
-    while let Some(Ok(msg)) = receiver.next().await {
+    while let Some(result) = receiver.next().await {
+        let msg = match result {
+            Ok(msg) => msg,
+            Err(e) => {
+                if is_message_too_long(&e) {
+                    tracing::warn!("WebSocket message over hard size limit, closing: {}", e);
+                    let _ = send_json(&tx, &message_too_large_error());
+                    let _ = tx.send(Message::Close(Some(CloseFrame {
+                        code: close_code::SIZE,
+                        reason: "message too large".into(),
+                    })));
+                } else {
+                    tracing::warn!("WebSocket receive failed: {}", e);
+                }
+                break;
+            }
+        };
+        let len = match &msg {
+            Message::Text(text) => text.len(),
+            Message::Binary(data) => data.len(),
+            _ => 0,
+        };
+        if !matches!(msg, Message::Close(_)) {
+            let activity_session = subscribed_desktops
+                .first()
+                .cloned()
+                .unwrap_or_else(|| session_id.to_string());
+            app_state.record_activity(&activity_session, ActivitySource::WebSocket);
+        }
+        if len > MAX_INBOUND_MESSAGE_BYTES {
+            tracing::warn!("Dropping oversized WebSocket message ({} bytes)", len);
+            let _ = send_json(&tx, &message_too_large_error());
+            continue;
+        }
+
         match msg {
             Message::Text(text) => {
                 tracing::debug!("WebSocket received: {}", text);
@@ -56,49 +125,90 @@ async fn handle_socket(socket: WebSocket, app_state: Arc<AppState>, sessions: Ws
                     Ok(WsMessage::Ping) => {
                         let _ = send_json(&tx, &WsMessage::Pong);
                     }
-                    Ok(WsMessage::Subscribe { desktop_id }) => {
+                    Ok(WsMessage::Subscribe {
+                        desktop_id,
+                        patch_granularity: requested_granularity,
+                        topics,
+                    }) => {
+                        if !subscribed_desktops.contains(&desktop_id)
+                            && subscribed_desktops.len() >= MAX_SUBSCRIPTIONS_PER_SESSION
+                        {
+                            tracing::warn!(
+                                desktop_id = %desktop_id,
+                                "WebSocket subscription refused: session limit reached"
+                            );
+                            let _ = send_json(&tx, &too_many_subscriptions_error());
+                            continue;
+                        }
                         tracing::info!("WebSocket subscribed to desktop: {}", desktop_id);
 
-                        if let Some(prev_desktop_id) = current_desktop_id.take() {
-                            unsubscribe_session(&sessions, &prev_desktop_id, session_id).await;
+                        if let Some(requested) = requested_granularity {
+                            patch_granularity = requested;
                         }
 
-                        current_desktop_id = Some(desktop_id.clone());
-
-                        let desktop_actor = match app_state
-                            .get_or_create_desktop(desktop_id.clone(), "anonymous".to_string())
-                            .await
-                        {
-                            Ok(actor) => actor,
-                            Err(e) => {
-                                let _ = send_json(
-                                    &tx,
-                                    &WsMessage::Error {
-                                        message: format!("Failed to get desktop: {e}"),
-                                    },
-                                );
-                                continue;
+                        match subscription_snapshot(&app_state, &desktop_id).await {
+                            Ok(snapshot) => {
+                                let _ = send_json(&tx, &snapshot);
                             }
-                        };
-
-                        match ractor::call!(desktop_actor, |reply| {
-                            DesktopActorMsg::GetDesktopState { reply }
-                        }) {
-                            Ok(desktop) => {
-                                let _ = send_json(&tx, &WsMessage::DesktopState { desktop });
-                            }
-                            Err(e) => {
-                                tracing::error!("Failed to get desktop state: {}", e);
+                            Err(message) => {
                                 let _ = send_json(
                                     &tx,
                                     &WsMessage::Error {
-                                        message: format!("Failed to get desktop state: {e}"),
+                                        message,
+                                        error_code: None,
+                                        version: Some(BuildVersion::current()),
                                     },
                                 );
+                                continue;
                             }
                         }
 
-                        subscribe_session(&sessions, &desktop_id, session_id, tx.clone()).await;
+                        let subscribed = subscribe_session(
+                            &sessions,
+                            &desktop_id,
+                            session_id,
+                            WsSubscriber {
+                                sender: tx.clone(),
+                                patch_granularity,
+                                topics,
+                            },
+                        )
+                        .await;
+                        if !subscribed {
+                            let _ = send_json(&tx, &too_many_subscriptions_error());
+                            continue;
+                        }
+                        if !subscribed_desktops.contains(&desktop_id) {
+                            subscribed_desktops.push(desktop_id);
+                        }
+                        // The outage banner is persistent: a desktop that
+                        // connects mid-outage must see it too.
+                        if !app_state.provider_gateway_reachable() {
+                            let _ = send_json(&tx, &provider_gateway_status_message(false));
+                        }
+                    }
+                    Ok(WsMessage::Unsubscribe { desktop_id }) => {
+                        if let Some(index) =
+                            subscribed_desktops.iter().position(|id| *id == desktop_id)
+                        {
+                            tracing::info!("WebSocket unsubscribed from desktop: {}", desktop_id);
+                            subscribed_desktops.remove(index);
+                            unsubscribe_session(&sessions, &desktop_id, session_id).await;
+                        }
+                    }
+                    Ok(WsMessage::UpdateSubscription {
+                        patch_granularity: requested,
+                    }) => {
+                        patch_granularity = requested;
+                        for desktop_id in &subscribed_desktops {
+                            update_session_granularity(
+                                &sessions,
+                                desktop_id,
+                                session_id,
+                                patch_granularity,
+                            )
+                            .await;
+                        }
                     }
                     _ => {
                         tracing::warn!("Unknown or invalid WebSocket message: {}", text);
@@ -116,15 +226,50 @@ async fn handle_socket(socket: WebSocket, app_state: Arc<AppState>, sessions: Ws
         }
     }
 
-    if let Some(desktop_id) = current_desktop_id {
+    for desktop_id in subscribed_desktops {
         tracing::info!("WebSocket disconnected from desktop: {}", desktop_id);
         unsubscribe_session(&sessions, &desktop_id, session_id).await;
     }
 
-    writer.abort();
+    // Give the writer a moment to flush a final error or close frame.
+    drop(tx);
+    if tokio::time::timeout(Duration::from_secs(1), &mut writer)
+        .await
+        .is_err()
+    {
+        writer.abort();
+    }
 }
 
-/// Broadcast an event to all subscribers of a desktop
+/// The message a new subscription to `desktop_id` starts with: the desktop's
+/// state, or an `error` message if it could not be read. `Err` means the
+/// desktop could not be created and the subscription must be refused.
+pub(crate) async fn subscription_snapshot(
+    app_state: &AppState,
+    desktop_id: &str,
+) -> Result<WsMessage, String> {
+    let desktop_actor = app_state
+        .get_or_create_desktop(desktop_id.to_string(), "anonymous".to_string())
+        .await
+        .map_err(|e| format!("Failed to get desktop: {e}"))?;
+
+    match ractor::call!(desktop_actor, |reply| {
+        DesktopActorMsg::GetDesktopState { reply }
+    }) {
+        Ok(desktop) => Ok(WsMessage::DesktopState { desktop }),
+        Err(e) => {
+            tracing::error!("Failed to get desktop state: {}", e);
+            Ok(WsMessage::Error {
+                message: format!("Failed to get desktop state: {e}"),
+                error_code: None,
+                version: Some(BuildVersion::current()),
+            })
+        }
+    }
+}
+
+/// Broadcast an event to all subscribers of a desktop whose forwarding
+/// filter accepts it.
 pub async fn broadcast_event(sessions: &WsSessions, desktop_id: &str, event: WsMessage) {
     let json = match serde_json::to_string(&event) {
         Ok(j) => j,
@@ -136,21 +281,47 @@ pub async fn broadcast_event(sessions: &WsSessions, desktop_id: &str, event: WsM
 
     let mut sessions = sessions.lock().await;
     if let Some(subscribers) = sessions.get_mut(desktop_id) {
-        subscribers.retain(|_, sender| sender.send(Message::Text(json.clone().into())).is_ok());
+        subscribers.retain(|_, subscriber| {
+            !forwards(subscriber, &event)
+                || subscriber
+                    .sender
+                    .send(Message::Text(json.clone().into()))
+                    .is_ok()
+        });
+    }
+}
+
+/// Whether `subscriber` is sent `message`. Writer-run events are filtered by
+/// patch granularity (status and failure always go through); run state
+/// deltas only reach subscribers that named their topic.
+fn forwards(subscriber: &WsSubscriber, message: &WsMessage) -> bool {
+    let granularity = subscriber.patch_granularity;
+    match message {
+        WsMessage::WriterRunPatch { .. } => granularity == PatchGranularity::All,
+        WsMessage::WriterRunProgress { .. } | WsMessage::WriterRunChangeset { .. } => {
+            granularity != PatchGranularity::StatusOnly
+        }
+        WsMessage::ConductorRunStateDelta { .. } => subscriber
+            .topics
+            .iter()
+            .any(|topic| topic == shared_types::EVENT_TOPIC_CONDUCTOR_RUN_STATE_DELTA),
+        _ => true,
     }
 }
 
-fn writer_ws_message_from_event(
+fn writer_run_event_from_payload(
     event_type: &str,
     payload: &serde_json::Value,
-) -> Option<(String, WsMessage)> {
+) -> Option<WriterRunEvent> {
     let mut writer_event_payload = payload.clone();
     writer_event_payload.as_object_mut()?.insert(
         "event_type".to_string(),
         serde_json::Value::String(event_type.to_string()),
     );
-    let writer_event: WriterRunEvent = serde_json::from_value(writer_event_payload).ok()?;
+    serde_json::from_value(writer_event_payload).ok()
+}
 
+fn writer_ws_message(writer_event: WriterRunEvent) -> Option<(String, WsMessage)> {
     match writer_event {
         WriterRunEvent::Started { base, objective } => Some((
             base.desktop_id.clone(),
@@ -206,27 +377,200 @@ fn writer_ws_message_from_event(
                 failure_kind,
             },
         )),
+        WriterRunEvent::Lock { base, payload } => Some((
+            base.desktop_id.clone(),
+            WsMessage::WriterRunLock { base, payload },
+        )),
+    }
+}
+
+pub(crate) fn provider_gateway_status_message(reachable: bool) -> WsMessage {
+    WsMessage::ProviderGatewayStatus {
+        reachable,
+        message: (!reachable).then(|| PROVIDER_GATEWAY_OUTAGE_MESSAGE.to_string()),
+    }
+}
+
+/// Send an event once to every connected session, whatever desktops it is
+/// subscribed to.
+pub async fn broadcast_to_all_sessions(sessions: &WsSessions, event: WsMessage) {
+    let json = match serde_json::to_string(&event) {
+        Ok(j) => j,
+        Err(e) => {
+            tracing::error!("Failed to serialize WS message: {}", e);
+            return;
+        }
+    };
+
+    let sessions = sessions.lock().await;
+    let mut senders = HashMap::new();
+    for (session_id, subscriber) in sessions.values().flatten() {
+        senders.entry(*session_id).or_insert(&subscriber.sender);
+    }
+    for sender in senders.into_values() {
+        let _ = sender.send(Message::Text(json.clone().into()));
+    }
+}
+
+async fn forward_provider_gateway_event(sessions: &WsSessions, event_type: &str) {
+    let reachable = match event_type {
+        shared_types::EVENT_TOPIC_PROVIDER_GATEWAY_UNREACHABLE => false,
+        shared_types::EVENT_TOPIC_PROVIDER_GATEWAY_RECOVERED => true,
+        _ => return,
+    };
+    broadcast_to_all_sessions(sessions, provider_gateway_status_message(reachable)).await;
+}
+
+async fn forward_writer_run_event(
+    sessions: &WsSessions,
+    patch_order: &PatchOrder,
+    event_type: &str,
+    payload: &serde_json::Value,
+) {
+    let Some(writer_event) = writer_run_event_from_payload(event_type, payload) else {
+        return;
+    };
+    flag_orphan_changeset(patch_order, &writer_event);
+    if let Some((desktop_id, message)) = writer_ws_message(writer_event) {
+        broadcast_event(sessions, &desktop_id, message).await;
+    }
+}
+
+/// Writer runs whose patch ids [`flag_orphan_changeset`] remembers.
+const PATCH_ORDER_MAX_RUNS: usize = 256;
+
+/// Warn about a changeset forwarded before its run's patch. It is still
+/// forwarded; subscribers just have no patch to attach it to.
+fn flag_orphan_changeset(patch_order: &PatchOrder, event: &WriterRunEvent) {
+    let result = patch_order
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .observe(event);
+    if let Err(orphan) = result {
+        tracing::warn!(
+            run_id = %orphan.run_id,
+            patch_id = %orphan.patch_id,
+            "writer changeset references a patch its run has not emitted"
+        );
+    }
+}
+
+async fn forward_conductor_run_event(sessions: &WsSessions, payload: &serde_json::Value) {
+    match serde_json::from_value::<ConductorRunStateDelta>(payload.clone()) {
+        Ok(delta) => {
+            let desktop_id = delta.desktop_id.clone();
+            broadcast_event(
+                sessions,
+                &desktop_id,
+                WsMessage::ConductorRunStateDelta { delta },
+            )
+            .await;
+        }
+        Err(err) => tracing::warn!(error = %err, "malformed conductor run state delta"),
+    }
+}
+
+/// Send `event` to WebSocket sessions the way the live forwarders would.
+/// For events that are delivered without being appended to the event store,
+/// such as replays; sessions are matched by desktop id only, so the desktop
+/// need not exist on this sandbox.
+pub async fn forward_event(
+    sessions: &WsSessions,
+    patch_order: &PatchOrder,
+    event: &shared_types::Event,
+) {
+    let event_type = event.event_type.as_str();
+    if event_type.starts_with("writer.run.") {
+        forward_writer_run_event(sessions, patch_order, event_type, &event.payload).await;
+    } else if event_type == shared_types::EVENT_TOPIC_CONDUCTOR_RUN_STATE_DELTA {
+        forward_conductor_run_event(sessions, &event.payload).await;
+    } else if event_type.starts_with("provider.gateway.") {
+        forward_provider_gateway_event(sessions, event_type).await;
     }
 }
 
 pub fn spawn_writer_run_event_forwarder(
     event_store: ractor::ActorRef<EventStoreMsg>,
     sessions: WsSessions,
+    patch_order: PatchOrder,
+) {
+    spawn_event_forwarder(
+        event_store,
+        sessions,
+        "writer.run.",
+        move |sessions, event| {
+            let patch_order = patch_order.clone();
+            async move {
+                forward_writer_run_event(
+                    &sessions,
+                    &patch_order,
+                    &event.event_type,
+                    &event.payload,
+                )
+                .await;
+            }
+        },
+    );
+}
+
+/// Forward conductor run state deltas to the run's desktop subscribers that
+/// opted into the topic, so the desktop can follow a run without polling.
+pub fn spawn_conductor_run_event_forwarder(
+    event_store: ractor::ActorRef<EventStoreMsg>,
+    sessions: WsSessions,
 ) {
+    spawn_event_forwarder(
+        event_store,
+        sessions,
+        shared_types::EVENT_TOPIC_CONDUCTOR_RUN_STATE_DELTA,
+        |sessions, event| async move {
+            forward_conductor_run_event(&sessions, &event.payload).await;
+        },
+    );
+}
+
+/// Forward provider gateway reachability changes to every session so the
+/// desktop can show or clear its outage banner.
+pub fn spawn_provider_gateway_event_forwarder(
+    event_store: ractor::ActorRef<EventStoreMsg>,
+    sessions: WsSessions,
+) {
+    spawn_event_forwarder(
+        event_store,
+        sessions,
+        "provider.gateway.",
+        |sessions, event| async move {
+            forward_provider_gateway_event(&sessions, &event.event_type).await;
+        },
+    );
+}
+
+/// Tail the event store from its current head and hand every new event with
+/// `event_type_prefix` to `forward`, in seq order.
+fn spawn_event_forwarder<F, Fut>(
+    event_store: ractor::ActorRef<EventStoreMsg>,
+    sessions: WsSessions,
+    event_type_prefix: &'static str,
+    forward: F,
+) where
+    F: Fn(WsSessions, shared_types::Event) -> Fut + Send + 'static,
+    Fut: std::future::Future<Output = ()> + Send,
+{
     tokio::spawn(async move {
-        let mut since_seq =
-            match ractor::call!(event_store, |reply| EventStoreMsg::GetLatestSeq { reply }) {
-                Ok(Ok(Some(seq))) => seq,
-                Ok(Ok(None)) => 0,
-                Ok(Err(err)) => {
-                    tracing::warn!(error = %err, "writer run forwarder failed to read latest seq");
-                    0
-                }
-                Err(err) => {
-                    tracing::warn!(error = %err, "writer run forwarder failed to query latest seq");
-                    0
-                }
-            };
+        let mut since_seq = match ractor::call!(event_store, |reply| EventStoreMsg::GetLatestSeq {
+            reply
+        }) {
+            Ok(Ok(Some(seq))) => seq,
+            Ok(Ok(None)) => 0,
+            Ok(Err(err)) => {
+                tracing::warn!(error = %err, prefix = event_type_prefix, "event forwarder failed to read latest seq");
+                0
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, prefix = event_type_prefix, "event forwarder failed to query latest seq");
+                0
+            }
+        };
 
         let mut ticker = tokio::time::interval(Duration::from_millis(120));
         loop {
@@ -235,46 +579,80 @@ pub fn spawn_writer_run_event_forwarder(
             let events = match ractor::call!(event_store, |reply| EventStoreMsg::GetRecentEvents {
                 since_seq,
                 limit: 250,
-                event_type_prefix: Some("writer.run.".to_string()),
+                event_type_prefix: Some(event_type_prefix.to_string()),
                 actor_id: None,
                 user_id: None,
                 reply
             }) {
                 Ok(Ok(events)) => events,
                 Ok(Err(err)) => {
-                    tracing::warn!(error = %err, "writer run forwarder query failed");
+                    tracing::warn!(error = %err, prefix = event_type_prefix, "event forwarder query failed");
                     continue;
                 }
                 Err(err) => {
-                    tracing::warn!(error = %err, "writer run forwarder rpc failed");
+                    tracing::warn!(error = %err, prefix = event_type_prefix, "event forwarder rpc failed");
                     continue;
                 }
             };
 
             for event in events {
                 since_seq = since_seq.max(event.seq);
-                if let Some((desktop_id, message)) =
-                    writer_ws_message_from_event(&event.event_type, &event.payload)
-                {
-                    broadcast_event(&sessions, &desktop_id, message).await;
-                }
+                forward(sessions.clone(), event).await;
             }
         }
     });
 }
 
-/// Subscribe a session to a desktop
+/// Subscribe a session to a desktop. Re-subscribing to the same desktop
+/// replaces the subscriber; a new desktop is refused (returns false) once the
+/// session holds [`MAX_SUBSCRIPTIONS_PER_SESSION`] subscriptions. Clients
+/// switching desktops send `unsubscribe` for the old one to free its slot.
 pub async fn subscribe_session(
     sessions: &WsSessions,
     desktop_id: &str,
     session_id: Uuid,
-    sender: mpsc::UnboundedSender<Message>,
-) {
+    subscriber: WsSubscriber,
+) -> bool {
     let mut sessions = sessions.lock().await;
+    let already_subscribed = sessions
+        .get(desktop_id)
+        .is_some_and(|subscribers| subscribers.contains_key(&session_id));
+    if !already_subscribed
+        && session_subscription_count(&sessions, session_id) >= MAX_SUBSCRIPTIONS_PER_SESSION
+    {
+        return false;
+    }
     sessions
         .entry(desktop_id.to_string())
         .or_default()
-        .insert(session_id, sender);
+        .insert(session_id, subscriber);
+    true
+}
+
+fn session_subscription_count(
+    sessions: &HashMap<String, HashMap<Uuid, WsSubscriber>>,
+    session_id: Uuid,
+) -> usize {
+    sessions
+        .values()
+        .filter(|subscribers| subscribers.contains_key(&session_id))
+        .count()
+}
+
+/// Change the forwarding filter of an existing subscription
+pub async fn update_session_granularity(
+    sessions: &WsSessions,
+    desktop_id: &str,
+    session_id: Uuid,
+    patch_granularity: PatchGranularity,
+) {
+    let mut sessions = sessions.lock().await;
+    if let Some(subscriber) = sessions
+        .get_mut(desktop_id)
+        .and_then(|subscribers| subscribers.get_mut(&session_id))
+    {
+        subscriber.patch_granularity = patch_granularity;
+    }
 }
 
 /// Remove a session from a desktop
@@ -288,7 +666,33 @@ pub async fn unsubscribe_session(sessions: &WsSessions, desktop_id: &str, sessio
     }
 }
 
-fn send_json(tx: &mpsc::UnboundedSender<Message>, msg: &WsMessage) -> bool {
+fn too_many_subscriptions_error() -> WsMessage {
+    WsMessage::Error {
+        message: format!(
+            "Subscription refused: a session may hold at most {MAX_SUBSCRIPTIONS_PER_SESSION} subscriptions"
+        ),
+        error_code: Some(WsErrorCode::TooManySubscriptions),
+        version: Some(BuildVersion::current()),
+    }
+}
+
+fn message_too_large_error() -> WsMessage {
+    WsMessage::Error {
+        message: format!(
+            "Message dropped: inbound messages may be at most {MAX_INBOUND_MESSAGE_BYTES} bytes"
+        ),
+        error_code: Some(WsErrorCode::MessageTooLarge),
+        version: Some(BuildVersion::current()),
+    }
+}
+
+/// Whether a receive error is tungstenite refusing a message over the
+/// configured `max_message_size`.
+fn is_message_too_long(error: &axum::Error) -> bool {
+    error.to_string().contains("Message too long")
+}
+
+pub(crate) fn send_json(tx: &mpsc::UnboundedSender<Message>, msg: &WsMessage) -> bool {
     match serde_json::to_string(msg) {
         Ok(text) => tx.send(Message::Text(text.into())).is_ok(),
         Err(e) => {
@@ -300,8 +704,359 @@ fn send_json(tx: &mpsc::UnboundedSender<Message>, msg: &WsMessage) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use super::{writer_ws_message_from_event, WsMessage};
+    use super::{
+        forward_conductor_run_event, forward_event, forward_provider_gateway_event,
+        forward_writer_run_event, message_too_large_error, new_patch_order, subscribe_session,
+        too_many_subscriptions_error, update_session_granularity, writer_run_event_from_payload,
+        writer_ws_message, WsMessage, WsSessions, WsSubscriber, MAX_SUBSCRIPTIONS_PER_SESSION,
+    };
+    use axum::extract::ws::Message;
     use serde_json::json;
+    use shared_types::PatchGranularity;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use tokio::sync::mpsc;
+    use uuid::Uuid;
+
+    fn run_event(event_type: &str, fields: serde_json::Value) -> (String, serde_json::Value) {
+        let mut payload = json!({
+            "desktop_id": "desktop-1",
+            "session_id": "session-1",
+            "thread_id": "thread-1",
+            "run_id": "run-1",
+            "document_path": "conductor/runs/run-1/draft.md",
+            "revision": 1,
+            "head_version_id": null,
+            "timestamp": "2026-03-13T22:00:00Z",
+        });
+        payload
+            .as_object_mut()
+            .unwrap()
+            .extend(fields.as_object().unwrap().clone());
+        (event_type.to_string(), payload)
+    }
+
+    fn synthetic_run() -> Vec<(String, serde_json::Value)> {
+        let mut events = vec![
+            run_event("writer.run.started", json!({ "objective": "Draft" })),
+            run_event(
+                "writer.run.status",
+                json!({ "status": "running", "message": null }),
+            ),
+        ];
+        for n in 0..5 {
+            events.push(run_event(
+                "writer.run.patch",
+                json!({
+                    "patch_id": format!("patch-{n}"),
+                    "source": "agent",
+                    "source_actor": null,
+                    "section_id": null,
+                    "ops": [{ "op": "insert", "pos": n, "text": "x" }],
+                    "proposal": null,
+                    "base_version_id": null,
+                    "target_version_id": null,
+                    "overlay_id": null,
+                }),
+            ));
+        }
+        events.push(run_event(
+            "writer.run.changeset",
+            json!({
+                "patch_id": "patch-4",
+                "loop_id": null,
+                "summary": "Typed five characters.",
+                "impact": "low",
+                "op_taxonomy": ["insert"],
+            }),
+        ));
+        events.push(run_event(
+            "writer.run.status",
+            json!({ "status": "completed", "message": null }),
+        ));
+        events
+    }
+
+    async fn subscribe(
+        sessions: &WsSessions,
+        patch_granularity: PatchGranularity,
+    ) -> (Uuid, mpsc::UnboundedReceiver<Message>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let session_id = Uuid::new_v4();
+        subscribe_session(
+            sessions,
+            "desktop-1",
+            session_id,
+            WsSubscriber {
+                sender,
+                patch_granularity,
+                topics: Vec::new(),
+            },
+        )
+        .await;
+        (session_id, receiver)
+    }
+
+    fn received_types(receiver: &mut mpsc::UnboundedReceiver<Message>) -> Vec<String> {
+        let mut types = Vec::new();
+        while let Ok(Message::Text(text)) = receiver.try_recv() {
+            let value: serde_json::Value = serde_json::from_str(&text).unwrap();
+            types.push(value["type"].as_str().unwrap().to_string());
+        }
+        types
+    }
+
+    #[tokio::test]
+    async fn changesets_only_session_receives_no_patches() {
+        let sessions: WsSessions = Arc::new(tokio::sync::Mutex::new(HashMap::new()));
+        let patch_order = new_patch_order();
+        let (_, mut all) = subscribe(&sessions, PatchGranularity::All).await;
+        let (_, mut changesets) = subscribe(&sessions, PatchGranularity::ChangesetsOnly).await;
+        let (_, mut status) = subscribe(&sessions, PatchGranularity::StatusOnly).await;
+
+        for (event_type, payload) in synthetic_run() {
+            forward_writer_run_event(&sessions, &patch_order, &event_type, &payload).await;
+        }
+
+        let all = received_types(&mut all);
+        assert_eq!(all.iter().filter(|t| *t == "writer.run.patch").count(), 5);
+
+        let changesets = received_types(&mut changesets);
+        assert_eq!(
+            changesets,
+            [
+                "writer.run.started",
+                "writer.run.status",
+                "writer.run.changeset",
+                "writer.run.status",
+            ]
+        );
+
+        let status = received_types(&mut status);
+        assert_eq!(
+            status,
+            [
+                "writer.run.started",
+                "writer.run.status",
+                "writer.run.status"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn forward_event_delivers_by_desktop_id_without_a_desktop_actor() {
+        let sessions: WsSessions = Arc::new(tokio::sync::Mutex::new(HashMap::new()));
+        let patch_order = new_patch_order();
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        subscribe_session(
+            &sessions,
+            "ghost-desktop",
+            Uuid::new_v4(),
+            WsSubscriber {
+                sender,
+                patch_granularity: PatchGranularity::StatusOnly,
+                topics: Vec::new(),
+            },
+        )
+        .await;
+
+        for (event_type, mut payload) in synthetic_run() {
+            payload["desktop_id"] = json!("ghost-desktop");
+            let now = chrono::Utc::now();
+            let event = shared_types::Event {
+                seq: 1,
+                event_id: "evt-1".to_string(),
+                timestamp: now,
+                stored_at: now,
+                produced_at: None,
+                actor_id: shared_types::ActorId("writer:ghost".to_string()),
+                actor_seq: 1,
+                event_type,
+                payload,
+                user_id: "user-1".to_string(),
+            };
+            forward_event(&sessions, &patch_order, &event).await;
+        }
+
+        assert_eq!(
+            received_types(&mut receiver),
+            [
+                "writer.run.started",
+                "writer.run.status",
+                "writer.run.status"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn granularity_can_change_mid_session() {
+        let sessions: WsSessions = Arc::new(tokio::sync::Mutex::new(HashMap::new()));
+        let patch_order = new_patch_order();
+        let (session_id, mut receiver) = subscribe(&sessions, PatchGranularity::StatusOnly).await;
+        let events = synthetic_run();
+        let (first, rest) = events.split_at(3);
+
+        for (event_type, payload) in first {
+            forward_writer_run_event(&sessions, &patch_order, event_type, payload).await;
+        }
+        update_session_granularity(&sessions, "desktop-1", session_id, PatchGranularity::All).await;
+        for (event_type, payload) in rest {
+            forward_writer_run_event(&sessions, &patch_order, event_type, payload).await;
+        }
+
+        let received = received_types(&mut receiver);
+        assert_eq!(
+            received.iter().filter(|t| *t == "writer.run.patch").count(),
+            4
+        );
+        assert_eq!(
+            received.last().map(String::as_str),
+            Some("writer.run.status")
+        );
+    }
+
+    #[tokio::test]
+    async fn subscription_beyond_session_limit_is_refused() {
+        let sessions: WsSessions = Arc::new(tokio::sync::Mutex::new(HashMap::new()));
+        let (sender, _receiver) = mpsc::unbounded_channel();
+        let session_id = Uuid::new_v4();
+        let subscriber = WsSubscriber {
+            sender,
+            patch_granularity: PatchGranularity::All,
+            topics: Vec::new(),
+        };
+
+        for n in 0..MAX_SUBSCRIPTIONS_PER_SESSION {
+            let desktop_id = format!("desktop-{n}");
+            assert!(
+                subscribe_session(&sessions, &desktop_id, session_id, subscriber.clone()).await
+            );
+        }
+        assert!(
+            !subscribe_session(&sessions, "desktop-extra", session_id, subscriber.clone()).await
+        );
+        assert!(!sessions.lock().await.contains_key("desktop-extra"));
+
+        // Re-subscribing an existing desktop and other sessions are unaffected.
+        assert!(subscribe_session(&sessions, "desktop-0", session_id, subscriber.clone()).await);
+        assert!(subscribe_session(&sessions, "desktop-extra", Uuid::new_v4(), subscriber).await);
+
+        let error = serde_json::to_value(too_many_subscriptions_error()).unwrap();
+        assert_eq!(error["type"], "error");
+        assert_eq!(error["error_code"], "too_many_subscriptions");
+    }
+
+    #[test]
+    fn message_too_large_error_carries_its_code() {
+        let error = serde_json::to_value(message_too_large_error()).unwrap();
+        assert_eq!(error["type"], "error");
+        assert_eq!(error["error_code"], "message_too_large");
+    }
+
+    #[tokio::test]
+    async fn provider_gateway_status_reaches_each_session_once() {
+        let sessions: WsSessions = Arc::new(tokio::sync::Mutex::new(HashMap::new()));
+        let (_, mut first) = subscribe(&sessions, PatchGranularity::StatusOnly).await;
+        // A second session subscribed to two desktops still gets one copy.
+        let (sender, mut second) = mpsc::unbounded_channel();
+        let second_id = Uuid::new_v4();
+        for desktop_id in ["desktop-1", "desktop-2"] {
+            let subscriber = WsSubscriber {
+                sender: sender.clone(),
+                patch_granularity: PatchGranularity::All,
+                topics: Vec::new(),
+            };
+            subscribe_session(&sessions, desktop_id, second_id, subscriber).await;
+        }
+
+        forward_provider_gateway_event(&sessions, "provider.gateway.unreachable").await;
+        forward_provider_gateway_event(&sessions, "provider.gateway.probe").await;
+        forward_provider_gateway_event(&sessions, "provider.gateway.recovered").await;
+
+        let mut statuses = Vec::new();
+        while let Ok(Message::Text(text)) = first.try_recv() {
+            let value: serde_json::Value = serde_json::from_str(&text).unwrap();
+            assert_eq!(value["type"], "provider.gateway.status");
+            statuses.push((value["reachable"].clone(), value["message"].is_string()));
+        }
+        assert_eq!(statuses, [(json!(false), true), (json!(true), false)]);
+        assert_eq!(
+            received_types(&mut second),
+            ["provider.gateway.status", "provider.gateway.status"]
+        );
+    }
+
+    #[tokio::test]
+    async fn run_state_deltas_reach_only_topic_subscribers() {
+        let sessions: WsSessions = Arc::new(tokio::sync::Mutex::new(HashMap::new()));
+        let (_, mut unscoped) = subscribe(&sessions, PatchGranularity::All).await;
+        let (sender, mut scoped) = mpsc::unbounded_channel();
+        subscribe_session(
+            &sessions,
+            "desktop-1",
+            Uuid::new_v4(),
+            WsSubscriber {
+                sender,
+                patch_granularity: PatchGranularity::StatusOnly,
+                topics: vec![shared_types::EVENT_TOPIC_CONDUCTOR_RUN_STATE_DELTA.to_string()],
+            },
+        )
+        .await;
+
+        let delta = json!({
+            "run_id": "run-1",
+            "desktop_id": "desktop-1",
+            "revision": 3,
+            "updated_at": "2026-03-13T22:00:00Z",
+            "status": "completed",
+        });
+        forward_conductor_run_event(&sessions, &delta).await;
+        let mut other_desktop = delta.clone();
+        other_desktop["desktop_id"] = json!("desktop-2");
+        forward_conductor_run_event(&sessions, &other_desktop).await;
+
+        assert!(received_types(&mut unscoped).is_empty());
+        let Ok(Message::Text(text)) = scoped.try_recv() else {
+            panic!("expected a forwarded delta");
+        };
+        let message: WsMessage = serde_json::from_str(&text).unwrap();
+        match message {
+            WsMessage::ConductorRunStateDelta { delta } => {
+                assert_eq!(delta.run_id, "run-1");
+                assert_eq!(delta.revision, 3);
+                assert_eq!(
+                    delta.status,
+                    Some(shared_types::ConductorRunStatus::Completed)
+                );
+            }
+            other => panic!("unexpected websocket message: {other:?}"),
+        }
+        assert!(scoped.try_recv().is_err());
+    }
+
+    #[test]
+    fn update_subscription_message_round_trips() {
+        let message: WsMessage = serde_json::from_str(
+            r#"{"type":"update_subscription","patch_granularity":"changesets_only"}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            message,
+            WsMessage::UpdateSubscription {
+                patch_granularity: PatchGranularity::ChangesetsOnly
+            }
+        ));
+
+        let message: WsMessage =
+            serde_json::from_str(r#"{"type":"subscribe","desktop_id":"desktop-1"}"#).unwrap();
+        assert!(matches!(
+            message,
+            WsMessage::Subscribe {
+                patch_granularity: None,
+                ..
+            }
+        ));
+    }
 
     #[test]
     fn changeset_ws_message_preserves_writer_run_base_fields() {
@@ -323,7 +1078,8 @@ mod tests {
             "op_taxonomy": ["insert", "clarification"]
         });
 
-        let (_, message) = writer_ws_message_from_event("writer.run.changeset", &payload)
+        let (_, message) = writer_run_event_from_payload("writer.run.changeset", &payload)
+            .and_then(writer_ws_message)
             .expect("changeset should map to websocket message");
 
         match message {
@@ -1,19 +1,23 @@
 //! WebSocket handler for live event-log streaming.
 //!
 //! Streams committed EventStore rows with optional filter query params.
+//! When the stream carries every event of an actor (no type or user filter),
+//! a jump in that actor's `actor_seq` is re-synced from the store: missing
+//! events still stored are sent, gaps left by compaction are skipped, and
+//! only what is lost beyond that is reported as a `gap` message.
 
 use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
 use axum::extract::{Query, State};
-use axum::http::StatusCode;
 use axum::response::IntoResponse;
-use axum::Json;
 use futures_util::{SinkExt, StreamExt};
 use serde_json::json;
+use shared_types::ErrorCode;
 use std::collections::HashMap;
 use tokio::sync::mpsc;
 use tokio::time::{sleep, Duration};
 
-use crate::actors::event_store::EventStoreMsg;
+use crate::actors::event_store::{get_compacted_through, get_events_for_actor, EventStoreMsg};
+use crate::api::error::api_error;
 use crate::api::logs::{event_matches_run_filter, validate_scope_pair, RunLogQuery};
 use crate::api::ApiState;
 
@@ -46,7 +50,7 @@ pub async fn logs_websocket(
         .clamp(50, 5_000);
 
     if let Err(error) = validate_scope_pair(&session_id, &thread_id) {
-        return (StatusCode::BAD_REQUEST, Json(json!({ "error": error }))).into_response();
+        return api_error(ErrorCode::InvalidRequest, error);
     }
 
     let run_filter = RunLogQuery {
@@ -87,6 +91,8 @@ async fn handle_logs_socket(
     poll_ms: u64,
     run_filter: RunLogQuery,
 ) {
+    // Type and user filters drop some of an actor's events on purpose.
+    let mut last_seen = (event_type_prefix.is_none() && user_id.is_none()).then(HashMap::new);
     let (mut sender, mut receiver) = socket.split();
     let (tx, mut rx) = mpsc::unbounded_channel::<Message>();
     let writer = tokio::spawn(async move {
@@ -158,23 +164,36 @@ async fn handle_logs_socket(
 
                 for event in recent {
                     since_seq = since_seq.max(event.seq);
+                    let gap = last_seen
+                        .as_mut()
+                        .and_then(|last| actor_seq_gap(last, &event));
+                    if let Some(gap) = gap {
+                        let (recovered, lost) = resync_gap(&event_store, &event.actor_id.0, gap).await;
+                        for recovered in recovered
+                            .iter()
+                            .filter(|recovered| event_matches_run_filter(recovered, &run_filter))
+                        {
+                            let _ = tx.send(event_message(recovered));
+                        }
+                        if let Some((from, to)) = lost {
+                            tracing::warn!(actor_id = %event.actor_id.0, from, to, "logs websocket detected actor_seq gap");
+                            let _ = tx.send(Message::Text(
+                                json!({
+                                    "type": "gap",
+                                    "actor_id": event.actor_id.0,
+                                    "missing_from_actor_seq": from,
+                                    "missing_to_actor_seq": to,
+                                    "seq": event.seq,
+                                })
+                                .to_string()
+                                .into(),
+                            ));
+                        }
+                    }
                     if !event_matches_run_filter(&event, &run_filter) {
                         continue;
                     }
-                    let _ = tx.send(Message::Text(
-                        json!({
-                            "type": "event",
-                            "seq": event.seq,
-                            "event_id": event.event_id,
-                            "timestamp": event.timestamp.to_rfc3339(),
-                            "event_type": event.event_type,
-                            "actor_id": event.actor_id.0,
-                            "user_id": event.user_id,
-                            "payload": event.payload,
-                        })
-                        .to_string()
-                        .into(),
-                    ));
+                    let _ = tx.send(event_message(&event));
                 }
             }
         }
@@ -182,3 +201,86 @@ async fn handle_logs_socket(
 
     writer.abort();
 }
+
+fn event_message(event: &shared_types::Event) -> Message {
+    Message::Text(
+        json!({
+            "type": "event",
+            "seq": event.seq,
+            "event_id": event.event_id,
+            "timestamp": event.timestamp.to_rfc3339(),
+            "stored_at": event.stored_at.to_rfc3339(),
+            "produced_at": event.produced_at.map(|dt| dt.to_rfc3339()),
+            "event_type": event.event_type,
+            "actor_id": event.actor_id.0,
+            "actor_seq": event.actor_seq,
+            "user_id": event.user_id,
+            "payload": event.payload,
+        })
+        .to_string()
+        .into(),
+    )
+}
+
+/// `actor_seq` values skipped between two consecutive events of an actor.
+#[derive(Debug, Clone, Copy)]
+struct ActorSeqGap {
+    from: i64,
+    to: i64,
+    /// Seq of the actor's previous event; the missing events come after it.
+    after_seq: i64,
+    /// Seq of the event that revealed the gap.
+    before_seq: i64,
+}
+
+/// Record `event` as the latest seen for its actor, as `(actor_seq, seq)`,
+/// and return the `actor_seq` values skipped since the previous one, if any.
+fn actor_seq_gap(
+    last_seen: &mut HashMap<String, (i64, i64)>,
+    event: &shared_types::Event,
+) -> Option<ActorSeqGap> {
+    let (last, after_seq) =
+        last_seen.insert(event.actor_id.0.clone(), (event.actor_seq, event.seq))?;
+    (event.actor_seq > last + 1).then_some(ActorSeqGap {
+        from: last + 1,
+        to: event.actor_seq - 1,
+        after_seq,
+        before_seq: event.seq,
+    })
+}
+
+/// Re-read a gap from the store. Returns the missing events still stored,
+/// and the range to report as lost when compaction does not account for all
+/// of the rest.
+async fn resync_gap(
+    event_store: &ractor::ActorRef<EventStoreMsg>,
+    actor_id: &str,
+    gap: ActorSeqGap,
+) -> (Vec<shared_types::Event>, Option<(i64, i64)>) {
+    let compacted_through = match get_compacted_through(event_store, actor_id).await {
+        Ok(Ok(through)) => through,
+        Ok(Err(e)) => {
+            tracing::warn!(error = %e, "logs websocket compaction watermark query failed");
+            0
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "logs websocket event store RPC failed");
+            0
+        }
+    };
+    let from = gap.from.max(compacted_through + 1);
+    if from > gap.to {
+        return (Vec::new(), None);
+    }
+    let recovered: Vec<_> = match get_events_for_actor(event_store, actor_id, gap.after_seq).await {
+        Ok(Ok(events)) => events
+            .into_iter()
+            .filter(|event| {
+                event.seq < gap.before_seq && (from..=gap.to).contains(&event.actor_seq)
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    let complete = recovered.len() as i64 == gap.to - from + 1;
+    (recovered, (!complete).then_some((from, gap.to)))
+}
@@ -3,7 +3,7 @@
 //! Provides document editing with optimistic concurrency control via revision tracking.
 //! All paths are constrained to the sandbox directory.
 
-use axum::extract::{Query, State};
+use axum::extract::{Path as UrlPath, Query, State};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use axum::Json;
@@ -12,96 +12,33 @@ use serde::{Deserialize, Serialize};
 use std::path::{Component, Path, PathBuf};
 use tokio::fs;
 
-use crate::actors::conductor::registry::lookup_writer_actor_for_run;
+pub(crate) use crate::actors::conductor::registry::extract_run_id_from_document_path;
 use crate::actors::event_store::{AppendEvent, EventStoreMsg};
 use crate::actors::writer::{
-    DocumentVersion, Overlay, OverlayStatus, VersionSource, WriterError, WriterMsg,
+    DocumentVersion, Overlay, OverlayStatus, VersionSource, WriterError, WriterMsg, WriterProposal,
+    WriterProposalAcceptance, WriterProposalList, WriterRevertResult,
 };
-use crate::api::ApiState;
-use crate::paths::{sandbox_root, writer_root};
-
-/// Writer error codes for machine-readable error responses
-#[derive(Debug, Clone)]
-pub enum WriterErrorCode {
-    PathTraversal,
-    NotFound,
-    RunNotFound,
-    IsDirectory,
-    InvalidRevision,
-    Conflict,
-    ReadError,
-    WriteError,
-}
-
-impl WriterErrorCode {
-    fn as_str(&self) -> &'static str {
-        match self {
-            WriterErrorCode::PathTraversal => "PATH_TRAVERSAL",
-            WriterErrorCode::NotFound => "NOT_FOUND",
-            WriterErrorCode::RunNotFound => "RUN_NOT_FOUND",
-            WriterErrorCode::IsDirectory => "IS_DIRECTORY",
-            WriterErrorCode::InvalidRevision => "INVALID_REVISION",
-            WriterErrorCode::Conflict => "CONFLICT",
-            WriterErrorCode::ReadError => "READ_ERROR",
-            WriterErrorCode::WriteError => "WRITE_ERROR",
-        }
-    }
-
-    fn status_code(&self) -> StatusCode {
-        match self {
-            WriterErrorCode::PathTraversal => StatusCode::FORBIDDEN,
-            WriterErrorCode::NotFound => StatusCode::NOT_FOUND,
-            WriterErrorCode::RunNotFound => StatusCode::NOT_FOUND,
-            WriterErrorCode::IsDirectory => StatusCode::BAD_REQUEST,
-            WriterErrorCode::InvalidRevision => StatusCode::BAD_REQUEST,
-            WriterErrorCode::Conflict => StatusCode::CONFLICT,
-            WriterErrorCode::ReadError => StatusCode::INTERNAL_SERVER_ERROR,
-            WriterErrorCode::WriteError => StatusCode::INTERNAL_SERVER_ERROR,
-        }
-    }
-}
-
-/// Error response structures
-#[derive(Debug, Serialize)]
-pub struct WriterErrorDetail {
-    code: String,
-    message: String,
-}
-
-#[derive(Debug, Serialize)]
-pub struct WriterErrorResponse {
-    error: WriterErrorDetail,
-}
+use shared_types::{ApiError, ErrorCode};
 
-/// Create an error response
-fn writer_error(code: WriterErrorCode, message: impl Into<String>) -> impl IntoResponse {
-    let status = code.status_code();
-    let body = Json(WriterErrorResponse {
-        error: WriterErrorDetail {
-            code: code.as_str().to_string(),
-            message: message.into(),
-        },
-    });
-    (status, body)
-}
+use crate::api::error::api_error;
+use crate::api::ApiState;
+use crate::app_state::PROVIDER_GATEWAY_OUTAGE_MESSAGE;
+use crate::paths::{normalize_document_path, sandbox_root, writer_root};
 
 /// Validates and normalizes a path relative to sandbox
 fn validate_path(sandbox: &Path, user_path: &str) -> Result<PathBuf, axum::response::Response> {
     // Reject null bytes
     if user_path.contains('\0') {
         return Err(
-            writer_error(WriterErrorCode::PathTraversal, "Path contains null bytes")
-                .into_response(),
+            api_error(ErrorCode::PathTraversal, "Path contains null bytes").into_response(),
         );
     }
 
     // Reject absolute paths
     if user_path.starts_with('/') {
-        return Err(writer_error(
-            WriterErrorCode::PathTraversal,
-            "Absolute paths are not allowed",
-        )
-        .into_response());
+        return Err(
+            api_error(ErrorCode::PathTraversal, "Absolute paths are not allowed").into_response(),
+        );
     }
 
     // Normalize path by processing components manually
@@ -113,16 +50,16 @@ fn validate_path(sandbox: &Path, user_path: &str) -> Result<PathBuf, axum::respo
             Component::ParentDir => {
                 // Pop the last component if we can, otherwise this escapes the sandbox
                 if !normalized.pop() {
-                    return Err(writer_error(
-                        WriterErrorCode::PathTraversal,
+                    return Err(api_error(
+                        ErrorCode::PathTraversal,
                         "Path escapes sandbox directory",
                     )
                     .into_response());
                 }
             }
             Component::RootDir | Component::Prefix(_) => {
-                return Err(writer_error(
-                    WriterErrorCode::PathTraversal,
+                return Err(api_error(
+                    ErrorCode::PathTraversal,
                     "Path contains invalid components",
                 )
                 .into_response());
@@ -138,8 +75,8 @@ fn validate_path(sandbox: &Path, user_path: &str) -> Result<PathBuf, axum::respo
         match full_path.canonicalize() {
             Ok(p) => p,
             Err(e) => {
-                return Err(writer_error(
-                    WriterErrorCode::ReadError,
+                return Err(api_error(
+                    ErrorCode::InternalError,
                     format!("Failed to canonicalize path: {e}"),
                 )
                 .into_response());
@@ -168,8 +105,8 @@ fn validate_path(sandbox: &Path, user_path: &str) -> Result<PathBuf, axum::respo
     let sandbox_canonical = match sandbox.canonicalize() {
         Ok(p) => p,
         Err(e) => {
-            return Err(writer_error(
-                WriterErrorCode::ReadError,
+            return Err(api_error(
+                ErrorCode::InternalError,
                 format!("Failed to canonicalize sandbox root: {e}"),
             )
             .into_response());
@@ -177,11 +114,9 @@ fn validate_path(sandbox: &Path, user_path: &str) -> Result<PathBuf, axum::respo
     };
 
     if !path_to_check.starts_with(&sandbox_canonical) {
-        return Err(writer_error(
-            WriterErrorCode::PathTraversal,
-            "Path escapes sandbox directory",
-        )
-        .into_response());
+        return Err(
+            api_error(ErrorCode::PathTraversal, "Path escapes sandbox directory").into_response(),
+        );
     }
 
     Ok(full_path)
@@ -304,23 +239,20 @@ pub async fn open_document(
     match fs::metadata(&file_path).await {
         Ok(m) => {
             if m.is_dir() {
-                return writer_error(
-                    WriterErrorCode::IsDirectory,
+                return api_error(
+                    ErrorCode::IsDirectory,
                     format!("Path is a directory, not a file: {user_path}"),
                 )
                 .into_response();
             }
         }
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-            return writer_error(
-                WriterErrorCode::NotFound,
-                format!("File not found: {user_path}"),
-            )
-            .into_response();
+            return api_error(ErrorCode::NotFound, format!("File not found: {user_path}"))
+                .into_response();
         }
         Err(e) => {
-            return writer_error(
-                WriterErrorCode::ReadError,
+            return api_error(
+                ErrorCode::InternalError,
                 format!("Failed to read file metadata: {e}"),
             )
             .into_response();
@@ -331,8 +263,8 @@ pub async fn open_document(
     let bytes = match fs::read(&file_path).await {
         Ok(b) => b,
         Err(e) => {
-            return writer_error(
-                WriterErrorCode::ReadError,
+            return api_error(
+                ErrorCode::InternalError,
                 format!("Failed to read file: {e}"),
             )
             .into_response();
@@ -341,14 +273,14 @@ pub async fn open_document(
 
     // Check for binary content
     if bytes.contains(&0) {
-        return writer_error(WriterErrorCode::ReadError, "Binary files are not supported")
+        return api_error(ErrorCode::InternalError, "Binary files are not supported")
             .into_response();
     }
 
     let content = match String::from_utf8(bytes) {
         Ok(s) => s,
         Err(_) => {
-            return writer_error(WriterErrorCode::ReadError, "File contains invalid UTF-8")
+            return api_error(ErrorCode::InternalError, "File contains invalid UTF-8")
                 .into_response();
         }
     };
@@ -357,7 +289,7 @@ pub async fn open_document(
     let revision = match get_or_create_revision(&state, &user_path).await {
         Ok(rev) => rev,
         Err(e) => {
-            return writer_error(WriterErrorCode::ReadError, e).into_response();
+            return api_error(ErrorCode::InternalError, e).into_response();
         }
     };
 
@@ -410,8 +342,7 @@ pub struct SaveDocumentResponse {
 /// Conflict response includes current server state
 #[derive(Debug, Serialize)]
 pub struct ConflictResponse {
-    #[serde(flatten)]
-    error: WriterErrorResponse,
+    error: ApiError,
     pub path: String,
     pub current_revision: u64,
     pub current_content: String,
@@ -440,16 +371,16 @@ pub async fn save_document(
         match fs::metadata(&file_path).await {
             Ok(m) => {
                 if m.is_dir() {
-                    return writer_error(
-                        WriterErrorCode::IsDirectory,
+                    return api_error(
+                        ErrorCode::IsDirectory,
                         format!("Path is a directory: {user_path}"),
                     )
                     .into_response();
                 }
             }
             Err(e) => {
-                return writer_error(
-                    WriterErrorCode::ReadError,
+                return api_error(
+                    ErrorCode::InternalError,
                     format!("Failed to read file metadata: {e}"),
                 )
                 .into_response();
@@ -457,19 +388,28 @@ pub async fn save_document(
         };
     }
 
+    let content_bytes = req.content.len() as u64;
+    if let Err(e) = state.app_state.check_disk_write(content_bytes).await {
+        return api_error(ErrorCode::QuotaExceeded, e.to_string()).into_response();
+    }
+
     // "Save As" on a new file may use base_rev=0 from the client.
     // Accept this explicitly and initialize revision to 1 on first write.
     if !file_exists && (req.base_rev == 0 || req.base_rev == 1) {
         if let Err(e) = fs::write(&file_path, &req.content).await {
-            return writer_error(
-                WriterErrorCode::WriteError,
+            return api_error(
+                ErrorCode::InternalError,
                 format!("Failed to write file: {e}"),
             )
             .into_response();
         }
+        state
+            .app_state
+            .record_disk_write(&file_path, content_bytes)
+            .await;
 
         if let Err(e) = set_revision(&state, &user_path, 1).await {
-            return writer_error(WriterErrorCode::WriteError, e).into_response();
+            return api_error(ErrorCode::InternalError, e).into_response();
         }
 
         return (
@@ -487,7 +427,7 @@ pub async fn save_document(
     let current_revision = match get_or_create_revision(&state, &user_path).await {
         Ok(rev) => rev,
         Err(e) => {
-            return writer_error(WriterErrorCode::ReadError, e).into_response();
+            return api_error(ErrorCode::InternalError, e).into_response();
         }
     };
 
@@ -498,8 +438,8 @@ pub async fn save_document(
             match fs::read_to_string(&file_path).await {
                 Ok(c) => c,
                 Err(e) => {
-                    return writer_error(
-                        WriterErrorCode::ReadError,
+                    return api_error(
+                        ErrorCode::InternalError,
                         format!("Failed to read current file content: {e}"),
                     )
                     .into_response();
@@ -510,12 +450,10 @@ pub async fn save_document(
         };
 
         let response = Json(ConflictResponse {
-            error: WriterErrorResponse {
-                error: WriterErrorDetail {
-                    code: WriterErrorCode::Conflict.as_str().to_string(),
-                    message: "Document was modified by another client".to_string(),
-                },
-            },
+            error: ApiError::new(
+                ErrorCode::DocumentConflict,
+                "Document was modified by another client",
+            ),
             path: user_path,
             current_revision,
             current_content,
@@ -525,18 +463,22 @@ pub async fn save_document(
 
     // Write the file
     if let Err(e) = fs::write(&file_path, &req.content).await {
-        return writer_error(
-            WriterErrorCode::WriteError,
+        return api_error(
+            ErrorCode::InternalError,
             format!("Failed to write file: {e}"),
         )
         .into_response();
     }
+    state
+        .app_state
+        .record_disk_write(&file_path, content_bytes)
+        .await;
 
     // Increment revision
     let new_revision = match increment_revision(&state, &user_path).await {
         Ok(rev) => rev,
         Err(e) => {
-            return writer_error(WriterErrorCode::WriteError, e).into_response();
+            return api_error(ErrorCode::InternalError, e).into_response();
         }
     };
 
@@ -632,6 +574,62 @@ pub struct SaveVersionResponse {
     pub saved: bool,
 }
 
+#[derive(Debug, Serialize)]
+pub struct RevertPatchResponse {
+    pub run_id: String,
+    pub version: DocumentVersion,
+    pub undo_of: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListProposalsResponse {
+    pub run_id: String,
+    #[serde(flatten)]
+    pub list: WriterProposalList,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AcceptProposalResponse {
+    pub run_id: String,
+    #[serde(flatten)]
+    pub acceptance: WriterProposalAcceptance,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RejectProposalResponse {
+    pub run_id: String,
+    pub overlay_id: String,
+    pub rejected: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReproposeProposalResponse {
+    pub run_id: String,
+    pub proposal: WriterProposal,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewModeRequest {
+    pub enabled: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReviewModeResponse {
+    pub run_id: String,
+    pub review_mode: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LockStrictnessRequest {
+    pub strictness: shared_types::WriterLockStrictness,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LockStrictnessResponse {
+    pub run_id: String,
+    pub lock_strictness: shared_types::WriterLockStrictness,
+}
+
 /// Preview markdown content
 pub async fn preview_markdown(
     State(_state): State<ApiState>,
@@ -655,23 +653,20 @@ pub async fn preview_markdown(
             match fs::metadata(&file_path).await {
                 Ok(m) => {
                     if m.is_dir() {
-                        return writer_error(
-                            WriterErrorCode::IsDirectory,
+                        return api_error(
+                            ErrorCode::IsDirectory,
                             format!("Path is a directory: {path}"),
                         )
                         .into_response();
                     }
                 }
                 Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-                    return writer_error(
-                        WriterErrorCode::NotFound,
-                        format!("File not found: {path}"),
-                    )
-                    .into_response();
+                    return api_error(ErrorCode::NotFound, format!("File not found: {path}"))
+                        .into_response();
                 }
                 Err(e) => {
-                    return writer_error(
-                        WriterErrorCode::ReadError,
+                    return api_error(
+                        ErrorCode::InternalError,
                         format!("Failed to read file metadata: {e}"),
                     )
                     .into_response();
@@ -682,8 +677,8 @@ pub async fn preview_markdown(
             match fs::read(&file_path).await {
                 Ok(b) => {
                     if b.contains(&0) {
-                        return writer_error(
-                            WriterErrorCode::ReadError,
+                        return api_error(
+                            ErrorCode::InternalError,
                             "Binary files cannot be previewed",
                         )
                         .into_response();
@@ -691,8 +686,8 @@ pub async fn preview_markdown(
                     match String::from_utf8(b) {
                         Ok(s) => s,
                         Err(_) => {
-                            return writer_error(
-                                WriterErrorCode::ReadError,
+                            return api_error(
+                                ErrorCode::InternalError,
                                 "File contains invalid UTF-8",
                             )
                             .into_response();
@@ -700,8 +695,8 @@ pub async fn preview_markdown(
                     }
                 }
                 Err(e) => {
-                    return writer_error(
-                        WriterErrorCode::ReadError,
+                    return api_error(
+                        ErrorCode::InternalError,
                         format!("Failed to read file: {e}"),
                     )
                     .into_response();
@@ -709,8 +704,8 @@ pub async fn preview_markdown(
             }
         }
         (None, None) => {
-            return writer_error(
-                WriterErrorCode::InvalidRevision,
+            return api_error(
+                ErrorCode::InvalidRevision,
                 "Either path or content must be provided",
             )
             .into_response();
@@ -722,35 +717,22 @@ pub async fn preview_markdown(
     (StatusCode::OK, Json(PreviewResponse { html })).into_response()
 }
 
-fn extract_run_id_from_document_path(path: &str) -> Option<String> {
-    let mut parts = path.split('/');
-    match (
-        parts.next(),
-        parts.next(),
-        parts.next(),
-        parts.next(),
-        parts.next(),
-    ) {
-        (Some("conductor"), Some("runs"), Some(run_id), Some("draft.md"), None) => {
-            Some(run_id.to_string())
-        }
-        _ => None,
-    }
-}
-
-fn map_writer_actor_error(error: WriterError) -> axum::response::Response {
+pub(crate) fn map_writer_actor_error(error: WriterError) -> axum::response::Response {
     match error {
         WriterError::Validation(message) => {
             if message.contains("not found") {
-                writer_error(WriterErrorCode::NotFound, message).into_response()
+                api_error(ErrorCode::NotFound, message).into_response()
             } else {
-                writer_error(WriterErrorCode::InvalidRevision, message).into_response()
+                api_error(ErrorCode::InvalidRevision, message).into_response()
             }
         }
         WriterError::ActorUnavailable(message) => {
-            writer_error(WriterErrorCode::WriteError, message).into_response()
+            api_error(ErrorCode::InternalError, message).into_response()
+        }
+        WriterError::Conflict(message) => {
+            api_error(ErrorCode::DocumentConflict, message).into_response()
         }
-        other => writer_error(WriterErrorCode::WriteError, other.to_string()).into_response(),
+        other => api_error(ErrorCode::InternalError, other.to_string()).into_response(),
     }
 }
 
@@ -763,8 +745,8 @@ async fn ensure_conductor_writer_actor(
         .ensure_run_writer(run_id)
         .await
         .map_err(|err| {
-            writer_error(
-                WriterErrorCode::WriteError,
+            api_error(
+                ErrorCode::InternalError,
                 format!("Writer unavailable: {err}"),
             )
             .into_response()
@@ -777,18 +759,15 @@ pub async fn prompt_document(
     Json(req): Json<PromptDocumentRequest>,
 ) -> impl IntoResponse {
     if req.prompt_diff.is_empty() {
-        return writer_error(
-            WriterErrorCode::InvalidRevision,
-            "prompt_diff cannot be empty",
-        )
-        .into_response();
+        return api_error(ErrorCode::InvalidRevision, "prompt_diff cannot be empty")
+            .into_response();
     }
 
     let run_id = match extract_run_id_from_document_path(req.path.trim()) {
         Some(run_id) => run_id,
         None => {
-            return writer_error(
-                WriterErrorCode::InvalidRevision,
+            return api_error(
+                ErrorCode::InvalidRevision,
                 "Prompting requires a run document path: conductor/runs/{run_id}/draft.md",
             )
             .into_response();
@@ -809,12 +788,12 @@ pub async fn prompt_document(
     let user_input_record = shared_types::UserInputRecord {
         input_id: ulid::Ulid::new().to_string(),
         content: prompt_text,
-        surface: "writer".to_string(),
+        surface: shared_types::UserInputSurface::Writer,
         desktop_id: String::new(),
         session_id: String::new(),
         thread_id: run_id.clone(),
         run_id: Some(run_id.clone()),
-        document_path: Some(req.path.clone()),
+        document_path: normalize_document_path(&req.path).ok(),
         base_version_id: Some(req.base_version_id),
         created_at: chrono::Utc::now(),
     };
@@ -834,9 +813,22 @@ pub async fn prompt_document(
                 }),
                 actor_id: "api.writer".to_string(),
                 user_id: "system".to_string(),
+                idempotency_key: None,
             },
         });
 
+    // Degraded mode: answer at once instead of letting the prompt time out
+    // against an unreachable provider gateway.
+    if !state.app_state.provider_gateway_reachable() {
+        return api_error(
+            ErrorCode::ProviderUnavailable,
+            format!(
+                "{PROVIDER_GATEWAY_OUTAGE_MESSAGE} Your prompt was not sent; try again once the outage banner clears."
+            ),
+        )
+        .into_response();
+    }
+
     let writer_actor = match ensure_conductor_writer_actor(&state, &run_id).await {
         Ok(actor) => actor,
         Err(response) => return response,
@@ -851,7 +843,7 @@ pub async fn prompt_document(
         Ok(Ok(ack)) => ack,
         Ok(Err(err)) => return map_writer_actor_error(err),
         Err(err) => {
-            return writer_error(WriterErrorCode::WriteError, err.to_string()).into_response();
+            return api_error(ErrorCode::InternalError, err.to_string()).into_response();
         }
     };
 
@@ -876,8 +868,8 @@ pub async fn list_versions(
     let run_id = match extract_run_id_from_document_path(query.path.trim()) {
         Some(run_id) => run_id,
         None => {
-            return writer_error(
-                WriterErrorCode::InvalidRevision,
+            return api_error(
+                ErrorCode::InvalidRevision,
                 "Version listing requires run document path: conductor/runs/{run_id}/draft.md",
             )
             .into_response();
@@ -898,7 +890,7 @@ pub async fn list_versions(
         Ok(Ok(versions)) => versions,
         Ok(Err(err)) => return map_writer_actor_error(err),
         Err(err) => {
-            return writer_error(WriterErrorCode::WriteError, err.to_string()).into_response();
+            return api_error(ErrorCode::InternalError, err.to_string()).into_response();
         }
     };
 
@@ -927,8 +919,8 @@ pub async fn get_version(
     let run_id = match extract_run_id_from_document_path(query.path.trim()) {
         Some(run_id) => run_id,
         None => {
-            return writer_error(
-                WriterErrorCode::InvalidRevision,
+            return api_error(
+                ErrorCode::InvalidRevision,
                 "Version fetch requires run document path: conductor/runs/{run_id}/draft.md",
             )
             .into_response();
@@ -950,7 +942,7 @@ pub async fn get_version(
         Ok(Ok(version)) => version,
         Ok(Err(err)) => return map_writer_actor_error(err),
         Err(err) => {
-            return writer_error(WriterErrorCode::WriteError, err.to_string()).into_response();
+            return api_error(ErrorCode::InternalError, err.to_string()).into_response();
         }
     };
 
@@ -965,7 +957,7 @@ pub async fn get_version(
         Ok(Ok(overlays)) => overlays,
         Ok(Err(err)) => return map_writer_actor_error(err),
         Err(err) => {
-            return writer_error(WriterErrorCode::WriteError, err.to_string()).into_response();
+            return api_error(ErrorCode::InternalError, err.to_string()).into_response();
         }
     };
 
@@ -982,33 +974,26 @@ pub async fn get_version(
 
 /// Dismiss a pending overlay on a run document.
 pub async fn dismiss_overlay(
-    State(_state): State<ApiState>,
+    State(state): State<ApiState>,
     Json(req): Json<DismissOverlayRequest>,
 ) -> impl IntoResponse {
     let run_id = match extract_run_id_from_document_path(req.path.trim()) {
         Some(run_id) => run_id,
         None => {
-            return writer_error(
-                WriterErrorCode::InvalidRevision,
+            return api_error(
+                ErrorCode::InvalidRevision,
                 "Dismiss requires a run document path: conductor/runs/{run_id}/draft.md",
             )
             .into_response();
         }
     };
     if req.overlay_id.trim().is_empty() {
-        return writer_error(
-            WriterErrorCode::InvalidRevision,
-            "overlay_id cannot be empty",
-        )
-        .into_response();
+        return api_error(ErrorCode::InvalidRevision, "overlay_id cannot be empty").into_response();
     }
 
-    let Some(writer_actor) = lookup_writer_actor_for_run(&run_id) else {
-        return writer_error(
-            WriterErrorCode::RunNotFound,
-            format!("No live writer run actor for run_id: {run_id}"),
-        )
-        .into_response();
+    let writer_actor = match ensure_conductor_writer_actor(&state, &run_id).await {
+        Ok(actor) => actor,
+        Err(response) => return response,
     };
 
     let result = ractor::call!(writer_actor, |reply| {
@@ -1025,7 +1010,233 @@ pub async fn dismiss_overlay(
         )
             .into_response(),
         Ok(Err(err)) => map_writer_actor_error(err),
-        Err(err) => writer_error(WriterErrorCode::WriteError, err.to_string()).into_response(),
+        Err(err) => api_error(ErrorCode::InternalError, err.to_string()).into_response(),
+    }
+}
+
+/// Undo the latest user edit on a run document.
+pub async fn undo_document(
+    State(state): State<ApiState>,
+    UrlPath(path): UrlPath<String>,
+) -> impl IntoResponse {
+    revert_document(&state, &path, false).await
+}
+
+/// Redo the latest undone user edit on a run document.
+pub async fn redo_document(
+    State(state): State<ApiState>,
+    UrlPath(path): UrlPath<String>,
+) -> impl IntoResponse {
+    revert_document(&state, &path, true).await
+}
+
+async fn revert_document(state: &ApiState, path: &str, redo: bool) -> axum::response::Response {
+    let Some(run_id) = extract_run_id_from_document_path(path.trim()) else {
+        return api_error(
+            ErrorCode::InvalidRevision,
+            "Undo requires run document path: conductor/runs/{run_id}/draft.md",
+        )
+        .into_response();
+    };
+
+    let writer_actor = match ensure_conductor_writer_actor(state, &run_id).await {
+        Ok(actor) => actor,
+        Err(response) => return response,
+    };
+
+    match ractor::call!(writer_actor, |reply| WriterMsg::RevertUserPatch {
+        run_id: run_id.clone(),
+        redo,
+        reply,
+    }) {
+        Ok(Ok(WriterRevertResult { version, undo_of })) => (
+            StatusCode::OK,
+            Json(RevertPatchResponse {
+                run_id,
+                version,
+                undo_of,
+            }),
+        )
+            .into_response(),
+        Ok(Err(err)) => map_writer_actor_error(err),
+        Err(err) => api_error(ErrorCode::InternalError, err.to_string()).into_response(),
+    }
+}
+
+async fn run_document_writer(
+    state: &ApiState,
+    path: &str,
+) -> Result<(String, ractor::ActorRef<WriterMsg>), axum::response::Response> {
+    let Some(run_id) = extract_run_id_from_document_path(path.trim()) else {
+        return Err(api_error(
+            ErrorCode::InvalidRevision,
+            "Proposal review requires run document path: conductor/runs/{run_id}/draft.md",
+        )
+        .into_response());
+    };
+    let writer_actor = ensure_conductor_writer_actor(state, &run_id).await?;
+    Ok((run_id, writer_actor))
+}
+
+/// List proposals awaiting review on a run document; stale ones are flagged.
+pub async fn list_proposals(
+    State(state): State<ApiState>,
+    UrlPath(path): UrlPath<String>,
+) -> impl IntoResponse {
+    let (run_id, writer_actor) = match run_document_writer(&state, &path).await {
+        Ok(resolved) => resolved,
+        Err(response) => return response,
+    };
+    match ractor::call!(writer_actor, |reply| {
+        WriterMsg::ListWriterDocumentProposals {
+            run_id: run_id.clone(),
+            reply,
+        }
+    }) {
+        Ok(Ok(list)) => {
+            (StatusCode::OK, Json(ListProposalsResponse { run_id, list })).into_response()
+        }
+        Ok(Err(err)) => map_writer_actor_error(err),
+        Err(err) => api_error(ErrorCode::InternalError, err.to_string()).into_response(),
+    }
+}
+
+/// Accept a proposal, rebasing it onto the head when its base is stale.
+pub async fn accept_proposal(
+    State(state): State<ApiState>,
+    UrlPath((path, overlay_id)): UrlPath<(String, String)>,
+) -> impl IntoResponse {
+    let (run_id, writer_actor) = match run_document_writer(&state, &path).await {
+        Ok(resolved) => resolved,
+        Err(response) => return response,
+    };
+    match ractor::call!(writer_actor, |reply| {
+        WriterMsg::AcceptWriterDocumentProposal {
+            run_id: run_id.clone(),
+            overlay_id: overlay_id.clone(),
+            reply,
+        }
+    }) {
+        Ok(Ok(acceptance)) => (
+            StatusCode::OK,
+            Json(AcceptProposalResponse { run_id, acceptance }),
+        )
+            .into_response(),
+        Ok(Err(err)) => map_writer_actor_error(err),
+        Err(err) => api_error(ErrorCode::InternalError, err.to_string()).into_response(),
+    }
+}
+
+/// Reject a proposal without applying it.
+pub async fn reject_proposal(
+    State(state): State<ApiState>,
+    UrlPath((path, overlay_id)): UrlPath<(String, String)>,
+) -> impl IntoResponse {
+    let (run_id, writer_actor) = match run_document_writer(&state, &path).await {
+        Ok(resolved) => resolved,
+        Err(response) => return response,
+    };
+    match ractor::call!(writer_actor, |reply| {
+        WriterMsg::DismissWriterDocumentOverlay {
+            run_id: run_id.clone(),
+            overlay_id: overlay_id.clone(),
+            reply,
+        }
+    }) {
+        Ok(Ok(())) => (
+            StatusCode::OK,
+            Json(RejectProposalResponse {
+                run_id,
+                overlay_id,
+                rejected: true,
+            }),
+        )
+            .into_response(),
+        Ok(Err(err)) => map_writer_actor_error(err),
+        Err(err) => api_error(ErrorCode::InternalError, err.to_string()).into_response(),
+    }
+}
+
+/// Re-propose a stale proposal against the current head version.
+pub async fn repropose_proposal(
+    State(state): State<ApiState>,
+    UrlPath((path, overlay_id)): UrlPath<(String, String)>,
+) -> impl IntoResponse {
+    let (run_id, writer_actor) = match run_document_writer(&state, &path).await {
+        Ok(resolved) => resolved,
+        Err(response) => return response,
+    };
+    match ractor::call!(writer_actor, |reply| {
+        WriterMsg::ReproposeWriterDocumentProposal {
+            run_id: run_id.clone(),
+            overlay_id: overlay_id.clone(),
+            reply,
+        }
+    }) {
+        Ok(Ok(proposal)) => (
+            StatusCode::OK,
+            Json(ReproposeProposalResponse { run_id, proposal }),
+        )
+            .into_response(),
+        Ok(Err(err)) => map_writer_actor_error(err),
+        Err(err) => api_error(ErrorCode::InternalError, err.to_string()).into_response(),
+    }
+}
+
+/// Turn review mode on or off for a run document.
+pub async fn set_review_mode(
+    State(state): State<ApiState>,
+    UrlPath(path): UrlPath<String>,
+    Json(req): Json<ReviewModeRequest>,
+) -> impl IntoResponse {
+    let (run_id, writer_actor) = match run_document_writer(&state, &path).await {
+        Ok(resolved) => resolved,
+        Err(response) => return response,
+    };
+    match ractor::call!(writer_actor, |reply| WriterMsg::SetWriterReviewMode {
+        run_id: run_id.clone(),
+        enabled: req.enabled,
+        reply,
+    }) {
+        Ok(Ok(review_mode)) => (
+            StatusCode::OK,
+            Json(ReviewModeResponse {
+                run_id,
+                review_mode,
+            }),
+        )
+            .into_response(),
+        Ok(Err(err)) => map_writer_actor_error(err),
+        Err(err) => api_error(ErrorCode::InternalError, err.to_string()).into_response(),
+    }
+}
+
+/// Choose whether user edits to blocks locked by a writer run are warned
+/// about or refused.
+pub async fn set_lock_strictness(
+    State(state): State<ApiState>,
+    UrlPath(path): UrlPath<String>,
+    Json(req): Json<LockStrictnessRequest>,
+) -> impl IntoResponse {
+    let (run_id, writer_actor) = match run_document_writer(&state, &path).await {
+        Ok(resolved) => resolved,
+        Err(response) => return response,
+    };
+    match ractor::call!(writer_actor, |reply| WriterMsg::SetWriterLockStrictness {
+        run_id: run_id.clone(),
+        strictness: req.strictness,
+        reply,
+    }) {
+        Ok(Ok(lock_strictness)) => (
+            StatusCode::OK,
+            Json(LockStrictnessResponse {
+                run_id,
+                lock_strictness,
+            }),
+        )
+            .into_response(),
+        Ok(Err(err)) => map_writer_actor_error(err),
+        Err(err) => api_error(ErrorCode::InternalError, err.to_string()).into_response(),
     }
 }
 
@@ -1035,15 +1246,14 @@ pub async fn save_version(
     Json(req): Json<SaveVersionRequest>,
 ) -> impl IntoResponse {
     if req.content.trim().is_empty() {
-        return writer_error(WriterErrorCode::InvalidRevision, "content cannot be empty")
-            .into_response();
+        return api_error(ErrorCode::InvalidRevision, "content cannot be empty").into_response();
     }
 
     let run_id = match extract_run_id_from_document_path(req.path.trim()) {
         Some(run_id) => run_id,
         None => {
-            return writer_error(
-                WriterErrorCode::InvalidRevision,
+            return api_error(
+                ErrorCode::InvalidRevision,
                 "Save version requires run document path: conductor/runs/{run_id}/draft.md",
             )
             .into_response();
@@ -1067,12 +1277,17 @@ pub async fn save_version(
             Ok(Ok(versions)) => versions,
             Ok(Err(err)) => return map_writer_actor_error(err),
             Err(err) => {
-                return writer_error(WriterErrorCode::WriteError, err.to_string()).into_response();
+                return api_error(ErrorCode::InternalError, err.to_string()).into_response();
             }
         };
         versions.iter().map(|v| v.version_id).max()
     };
 
+    let content_bytes = req.content.len() as u64;
+    if let Err(e) = state.app_state.check_disk_write(content_bytes).await {
+        return api_error(ErrorCode::QuotaExceeded, e.to_string()).into_response();
+    }
+
     let version = match ractor::call!(writer_actor, |reply| {
         WriterMsg::CreateWriterDocumentVersion {
             run_id: run_id.clone(),
@@ -1085,9 +1300,13 @@ pub async fn save_version(
         Ok(Ok(version)) => version,
         Ok(Err(err)) => return map_writer_actor_error(err),
         Err(err) => {
-            return writer_error(WriterErrorCode::WriteError, err.to_string()).into_response();
+            return api_error(ErrorCode::InternalError, err.to_string()).into_response();
         }
     };
+    state
+        .app_state
+        .record_disk_write(&writer_root().join(req.path.trim()), content_bytes)
+        .await;
 
     (
         StatusCode::OK,
@@ -1136,14 +1355,20 @@ pub async fn ensure_run_document(
     let run_id = match extract_run_id_from_document_path(req.path.trim()) {
         Some(run_id) => run_id,
         None => {
-            return writer_error(
-                WriterErrorCode::InvalidRevision,
+            return api_error(
+                ErrorCode::InvalidRevision,
                 "ensure requires a run document path: conductor/runs/{run_id}/draft.md",
             )
             .into_response();
         }
     };
 
+    // A new run directory starts out holding at least the objective.
+    let objective_bytes = req.objective.len() as u64;
+    if let Err(e) = state.app_state.check_disk_write(objective_bytes).await {
+        return api_error(ErrorCode::QuotaExceeded, e.to_string()).into_response();
+    }
+
     let writer_actor = match ensure_conductor_writer_actor(&state, &run_id).await {
         Ok(actor) => actor,
         Err(response) => return response,
@@ -1170,7 +1395,7 @@ pub async fn ensure_run_document(
         )
             .into_response(),
         Ok(Err(err)) => map_writer_actor_error(err),
-        Err(err) => writer_error(WriterErrorCode::WriteError, err.to_string()).into_response(),
+        Err(err) => api_error(ErrorCode::InternalError, err.to_string()).into_response(),
     }
 }
 
@@ -1,14 +1,29 @@
 use ractor::{Actor, ActorRef};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex;
 
-use crate::actors::conductor::registry::run_writer_id;
+use crate::activity::{ActivityTracker, ACTIVITY_ACTOR_ID, RESTORE_WINDOW};
 use crate::actors::conductor::ConductorMsg;
-use crate::actors::desktop::{DesktopActorMsg, DesktopArguments};
-use crate::actors::event_store::EventStoreMsg;
+use crate::actors::desktop::{DesktopActorMsg, DesktopArguments, DESKTOP_SNAPSHOT_INTERVAL};
+use crate::actors::event_bus::{Event, EventBusMsg};
+use crate::actors::event_store::{
+    get_events_for_actor, get_last_seq_before, AppendEvent, EventStoreMsg,
+};
+use crate::actors::researcher::ResearcherMsg;
 use crate::actors::terminal::TerminalMsg;
 use crate::actors::writer::WriterMsg;
-use crate::supervisor::{ApplicationSupervisor, ApplicationSupervisorMsg};
+use crate::disk_usage::{DiskUsageTracker, QuotaExceeded};
+use crate::projections::search::{search_projection, SearchIndexerActor, SearchIndexerArguments};
+use crate::projections::ProjectionManager;
+use crate::supervisor::{ApplicationSupervisor, ApplicationSupervisorMsg, WriterDocumentHolder};
+
+/// Returned in place of model output while the provider gateway is unreachable.
+pub const PROVIDER_GATEWAY_OUTAGE_MESSAGE: &str =
+    "The provider gateway is unreachable, so AI models are temporarily unavailable.";
+
+const PROVIDER_GATEWAY_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
 
 #[derive(Clone)]
 pub struct AppState {
@@ -19,15 +34,37 @@ struct AppStateInner {
     event_store: ActorRef<EventStoreMsg>,
     application_supervisor: Mutex<Option<ActorRef<ApplicationSupervisorMsg>>>,
     conductor_actor: Mutex<Option<ActorRef<ConductorMsg>>>,
+    projections: ProjectionManager,
+    provider_gateway_reachable: AtomicBool,
+    disk_usage: DiskUsageTracker,
+    activity: ActivityTracker,
 }
 
 impl AppState {
     pub fn new(event_store: ActorRef<EventStoreMsg>) -> Self {
+        Self::with_disk_usage(
+            event_store,
+            DiskUsageTracker::from_env(crate::paths::sandbox_root()),
+        )
+    }
+
+    pub fn with_disk_usage(
+        event_store: ActorRef<EventStoreMsg>,
+        disk_usage: DiskUsageTracker,
+    ) -> Self {
         Self {
             inner: Arc::new(AppStateInner {
+                projections: ProjectionManager::with_projections(
+                    event_store.clone(),
+                    [search_projection(event_store.clone())],
+                )
+                .expect("built-in projection names are unique"),
                 event_store,
                 application_supervisor: Mutex::new(None),
                 conductor_actor: Mutex::new(None),
+                provider_gateway_reachable: AtomicBool::new(true),
+                disk_usage,
+                activity: ActivityTracker::default(),
             }),
         }
     }
@@ -36,6 +73,10 @@ impl AppState {
         self.inner.event_store.clone()
     }
 
+    pub fn projections(&self) -> ProjectionManager {
+        self.inner.projections.clone()
+    }
+
     pub async fn ensure_supervisor(&self) -> Result<ActorRef<ApplicationSupervisorMsg>, String> {
         let mut guard = self.inner.application_supervisor.lock().await;
         if let Some(supervisor) = guard.as_ref() {
@@ -45,7 +86,7 @@ impl AppState {
         let (supervisor, _) = Actor::spawn(
             Some(format!("application_supervisor:{}", ulid::Ulid::new())),
             ApplicationSupervisor,
-            self.inner.event_store.clone(),
+            self.inner.event_store.clone().into(),
         )
         .await
         .map_err(|e| e.to_string())?;
@@ -54,6 +95,34 @@ impl AppState {
         Ok(supervisor)
     }
 
+    /// The supervised EventBus.
+    pub async fn event_bus(&self) -> Result<ActorRef<EventBusMsg>, String> {
+        let supervisor = self.ensure_supervisor().await?;
+        ractor::call!(supervisor, |reply| {
+            ApplicationSupervisorMsg::GetEventBus { reply }
+        })
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "event bus is not running".to_string())
+    }
+
+    /// Subscribe the search projection to the supervised EventBus so new
+    /// content and redactions are indexed as soon as they are committed.
+    pub async fn start_search_indexer(&self) -> Result<ActorRef<Event>, String> {
+        let event_bus = self.event_bus().await?;
+
+        let (indexer, _) = Actor::spawn(
+            None,
+            SearchIndexerActor,
+            SearchIndexerArguments {
+                projections: self.projections(),
+                event_bus,
+            },
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(indexer)
+    }
+
     pub async fn get_or_create_desktop(
         &self,
         desktop_id: String,
@@ -70,15 +139,43 @@ impl AppState {
         .map_err(|e| e.to_string())
     }
 
+    /// Writer of a run document; `holder` keeps it alive until released.
     pub async fn get_or_create_writer(
         &self,
-        writer_id: String,
+        document_path: String,
         user_id: String,
+        holder: Option<WriterDocumentHolder>,
     ) -> Result<ActorRef<WriterMsg>, String> {
         let supervisor = self.ensure_supervisor().await?;
         ractor::call!(supervisor, |reply| {
             ApplicationSupervisorMsg::GetOrCreateWriter {
-                writer_id,
+                document_path,
+                user_id,
+                holder,
+                reply,
+            }
+        })
+        .map_err(|e| e.to_string())?
+    }
+
+    /// Release a window or run holding run documents; writers left without
+    /// holders stop.
+    pub async fn release_writer_holder(&self, holder: WriterDocumentHolder) -> Result<(), String> {
+        let supervisor = self.ensure_supervisor().await?;
+        supervisor
+            .cast(ApplicationSupervisorMsg::ReleaseWriterHolder { holder })
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn get_or_create_researcher(
+        &self,
+        researcher_id: String,
+        user_id: String,
+    ) -> Result<ActorRef<ResearcherMsg>, String> {
+        let supervisor = self.ensure_supervisor().await?;
+        ractor::call!(supervisor, |reply| {
+            ApplicationSupervisorMsg::GetOrCreateResearcher {
+                researcher_id,
                 user_id,
                 reply,
             }
@@ -119,12 +216,17 @@ impl AppState {
             desktop_id,
             user_id,
             event_store: self.event_store(),
+            snapshot_interval: DESKTOP_SNAPSHOT_INTERVAL,
         }
     }
 
     pub async fn ensure_run_writer(&self, run_id: &str) -> Result<ActorRef<WriterMsg>, String> {
-        self.get_or_create_writer(run_writer_id(run_id), "system".to_string())
-            .await
+        self.get_or_create_writer(
+            format!("conductor/runs/{run_id}/draft.md"),
+            "system".to_string(),
+            None,
+        )
+        .await
     }
 
     pub async fn ensure_conductor(&self) -> Result<ActorRef<ConductorMsg>, String> {
@@ -162,7 +264,208 @@ impl AppState {
         .map_err(|e| e.to_string())?
         .map_err(|e| e.to_string())?;
 
+        // A freshly spawned conductor assumes the gateway is reachable.
+        if !self.provider_gateway_reachable() {
+            let _ =
+                conductor.send_message(ConductorMsg::ProviderGatewayChanged { reachable: false });
+        }
         *guard = Some(conductor.clone());
         Ok(conductor)
     }
+
+    pub fn disk_usage(&self) -> &DiskUsageTracker {
+        &self.inner.disk_usage
+    }
+
+    /// Refuse a write of `bytes` that would take the workspace over its quota.
+    pub async fn check_disk_write(&self, bytes: u64) -> Result<(), QuotaExceeded> {
+        self.inner.disk_usage.check_write(bytes).await
+    }
+
+    /// Count a completed write against the workspace quota.
+    pub async fn record_disk_write(&self, path: &std::path::Path, bytes: u64) {
+        self.inner.disk_usage.record_write(path, bytes);
+        self.warn_on_disk_usage_thresholds().await;
+    }
+
+    /// Emit `workspace.quota.warning` when usage newly crosses 80% or 95% of
+    /// the quota. A no-op without a quota.
+    pub async fn warn_on_disk_usage_thresholds(&self) {
+        let Some(quota_bytes) = self.inner.disk_usage.quota_bytes() else {
+            return;
+        };
+        let usage = self.inner.disk_usage.usage().await;
+        let Some(threshold_pct) = self.inner.disk_usage.take_crossed_threshold(&usage) else {
+            return;
+        };
+
+        tracing::warn!(
+            threshold_pct,
+            used_bytes = usage.used_bytes,
+            quota_bytes,
+            "Workspace disk usage crossed quota warning threshold"
+        );
+        let payload = shared_types::WorkspaceQuotaWarningPayload {
+            threshold_pct,
+            used_bytes: usage.used_bytes,
+            quota_bytes,
+            largest_category: usage.categories.first().map(|usage| usage.category),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        };
+        let event = AppendEvent {
+            event_type: shared_types::EVENT_TOPIC_WORKSPACE_QUOTA_WARNING.to_string(),
+            payload: serde_json::to_value(payload).unwrap_or_default(),
+            actor_id: "disk_usage".to_string(),
+            user_id: "system".to_string(),
+            idempotency_key: None,
+        };
+        match ractor::call!(self.inner.event_store, |reply| EventStoreMsg::Append {
+            event,
+            reply
+        }) {
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => tracing::error!("Failed to persist workspace quota warning: {e}"),
+            Err(e) => tracing::error!("RPC error persisting workspace quota warning: {e}"),
+        }
+    }
+
+    /// Rescan workspace disk usage every `interval` for the life of the process,
+    /// catching writes that bypass the API.
+    pub fn spawn_disk_usage_monitor(&self, interval: Duration) {
+        let app_state = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                app_state.inner.disk_usage.refresh().await;
+                app_state.warn_on_disk_usage_thresholds().await;
+            }
+        });
+    }
+
+    pub fn activity(&self) -> &ActivityTracker {
+        &self.inner.activity
+    }
+
+    /// Note user activity in a session, recording a `session.activity` event
+    /// when the session's last one is due for a refresh.
+    pub fn record_activity(&self, session_id: &str, source: shared_types::ActivitySource) {
+        let Some(activity) = self
+            .inner
+            .activity
+            .record(session_id, source, chrono::Utc::now())
+        else {
+            return;
+        };
+        let _ = self.inner.event_store.cast(EventStoreMsg::AppendAsync {
+            event: AppendEvent {
+                event_type: shared_types::EVENT_TOPIC_SESSION_ACTIVITY.to_string(),
+                payload: serde_json::to_value(activity).unwrap_or_default(),
+                actor_id: ACTIVITY_ACTOR_ID.to_string(),
+                user_id: "system".to_string(),
+                idempotency_key: None,
+            },
+        });
+    }
+
+    /// Rebuild session activity from the last `RESTORE_WINDOW` of the log.
+    pub async fn restore_activity(&self) -> Result<(), String> {
+        let since = chrono::Utc::now() - RESTORE_WINDOW;
+        let since_seq = get_last_seq_before(&self.inner.event_store, since)
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| e.to_string())?;
+        let events = get_events_for_actor(&self.inner.event_store, ACTIVITY_ACTOR_ID, since_seq)
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| e.to_string())?;
+        self.inner.activity.replay(&events);
+        Ok(())
+    }
+
+    /// Whether the provider gateway answered its last probe. Starts true, so
+    /// standalone sandboxes without a gateway are never degraded.
+    pub fn provider_gateway_reachable(&self) -> bool {
+        self.inner.provider_gateway_reachable.load(Ordering::SeqCst)
+    }
+
+    /// Record a provider gateway probe result.
+    ///
+    /// A change emits a control-lane `provider.gateway.unreachable` or
+    /// `provider.gateway.recovered` event and tells the conductor, which queues
+    /// new runs while degraded and resumes them on recovery.
+    pub async fn set_provider_gateway_reachable(&self, reachable: bool) {
+        let previous = self
+            .inner
+            .provider_gateway_reachable
+            .swap(reachable, Ordering::SeqCst);
+        if previous == reachable {
+            return;
+        }
+
+        let (event_type, message) = if reachable {
+            (shared_types::EVENT_TOPIC_PROVIDER_GATEWAY_RECOVERED, None)
+        } else {
+            (
+                shared_types::EVENT_TOPIC_PROVIDER_GATEWAY_UNREACHABLE,
+                Some(PROVIDER_GATEWAY_OUTAGE_MESSAGE),
+            )
+        };
+        if reachable {
+            tracing::info!("Provider gateway recovered");
+        } else {
+            tracing::warn!("Provider gateway unreachable; entering degraded mode");
+        }
+        let event = AppendEvent {
+            event_type: event_type.to_string(),
+            payload: serde_json::json!({
+                "reachable": reachable,
+                "message": message,
+                "timestamp": chrono::Utc::now().to_rfc3339(),
+                "_meta": {
+                    "lane": "control",
+                    "importance": "high",
+                }
+            }),
+            actor_id: "provider_gateway".to_string(),
+            user_id: "system".to_string(),
+            idempotency_key: None,
+        };
+        match ractor::call!(self.inner.event_store, |reply| EventStoreMsg::Append {
+            event,
+            reply
+        }) {
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => tracing::error!("Failed to persist {event_type}: {e}"),
+            Err(e) => tracing::error!("RPC error persisting {event_type}: {e}"),
+        }
+
+        match self.ensure_conductor().await {
+            Ok(conductor) => {
+                let _ = conductor.send_message(ConductorMsg::ProviderGatewayChanged { reachable });
+            }
+            Err(e) => tracing::warn!("Conductor unavailable for gateway status update: {e}"),
+        }
+    }
+
+    /// Probe the provider gateway once and record the result.
+    pub async fn check_provider_gateway(&self, base_url: &str) -> bool {
+        let reachable =
+            crate::runtime_env::probe_provider_gateway(base_url, PROVIDER_GATEWAY_PROBE_TIMEOUT)
+                .await;
+        self.set_provider_gateway_reachable(reachable).await;
+        reachable
+    }
+
+    /// Probe the provider gateway every `interval` for the life of the process.
+    pub fn spawn_provider_gateway_monitor(&self, base_url: String, interval: Duration) {
+        let app_state = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                app_state.check_provider_gateway(&base_url).await;
+            }
+        });
+    }
 }
@@ -21,7 +21,7 @@ pub fn get_baml_files() -> &'static HashMap<String, String> {
 
         m.insert("clients.baml".to_string(), "// ChoirOS Chat Agent Clients\n// Semantic client names for role-based model routing.\n// Actual providers are configured at runtime via ClientRegistry.\n\n// Orchestrator: High-quality model for complex reasoning and orchestration\nclient<llm> Orchestrator {\n  provider aws-bedrock\n  retry_policy Exponential\n  options {\n    model \"us.anthropic.claude-opus-4-5-20251101-v1:0\"\n    region \"us-east-1\"\n    // No explicit auth needed - provider auto-detects AWS_BEARER_TOKEN_BEDROCK\n  }\n}\n\n// FastResponse: Fast/cheap model for quick responses and high-volume tasks\nclient<llm> FastResponse {\n  provider anthropic\n  retry_policy Exponential\n  options {\n    api_key env.ZAI_API_KEY\n    base_url \"https://api.z.ai/api/anthropic\"\n    model \"glm-4.7\"\n  }\n}\n\n// Retry policies\nretry_policy Exponential {\n  max_retries 2\n  strategy {\n    type exponential_backoff\n    delay_ms 300\n    multiplier 1.5\n    max_delay_ms 10000\n  }\n}\n".to_string());
 
-        m.insert("conductor.baml".to_string(), "// ChoirOS Conductor BAML Contracts\n// Orchestration functions for the Conductor actor to manage multi-step agent workflows\n\n// ============================================================================\n// Simplified Decision Types\n// ============================================================================\n\nenum ConductorAction {\n  SpawnWorker\n  AwaitWorker\n  MergeCanon\n  Complete\n  Block\n  /// Phase 4: Spawn a focused sub-agent for a bounded scoped task.\n  SpawnSubharness\n  /// Phase 4: Delegate a task to a named app-level worker.\n  Delegate\n}\n\n// ---------------------------------------------------------------------------\n// Phase 4 — WorkerKind\n// ---------------------------------------------------------------------------\n\nenum WorkerKind {\n  Researcher\n  Writer\n  Terminal\n  Subharness\n}\n\n// ---------------------------------------------------------------------------\n// Phase 4 — Extended ConductorDecision\n// ---------------------------------------------------------------------------\n\nclass ConductorDecision {\n  action ConductorAction\n  args map<string, string>?\n  reason string\n  /// Only set when action == SpawnSubharness.\n  subharness_task string?\n  /// Only set when action == Delegate.\n  delegate_worker WorkerKind?\n  delegate_task string?\n}\n\nclass ConductorDecisionInput {\n  run_id string\n  objective string\n  document_path string\n  last_error string?\n}\n\nfunction ConductorDecide(input: ConductorDecisionInput) -> ConductorDecision {\n  client Orchestrator\n  prompt #\"\n    You are the ChoirOS Conductor, an orchestration AI.\n\n    Current Run:\n    - Run ID: {{ input.run_id }}\n    - Objective: {{ input.objective }}\n    - Document: {{ input.document_path }}\n    {% if input.last_error %}\n    - Last Error: {{ input.last_error }}\n    {% endif %}\n\n    Read the living document at {{ input.document_path }} to understand the current state.\n\n    Decide the next action:\n    - **SpawnWorker**: Dispatch a worker (researcher, terminal) with an objective\n    - **AwaitWorker**: Wait for pending worker calls to complete\n    - **MergeCanon**: Merge completed worker proposals into canon\n    - **Complete**: The objective is achieved\n    - **Block**: Cannot proceed (error or needs human help)\n\n    {{ ctx.output_format }}\n  \"#\n}\n\n// ============================================================================\n// Function: ConductorRefineObjective\n// ============================================================================\n\nclass ConductorObjectiveRefineInput {\n  raw_objective string\n  context string[]\n  target_capability string\n}\n\nclass ConductorObjectiveRefineOutput {\n  refined_objective string\n  success_criteria string[]\n  estimated_steps int\n  confidence float\n}\n\nfunction ConductorRefineObjective(input: ConductorObjectiveRefineInput) -> ConductorObjectiveRefineOutput {\n  client Orchestrator\n  prompt #\"\n    You are the ChoirOS Conductor, refining user objectives into clear, actionable tasks for capability workers.\n\n    Raw Objective: {{ input.raw_objective }}\n\n    Target Capability: {{ input.target_capability }}\n\n    Context from Previous Worker Outputs:\n    {{ input.context }}\n\n    Your task is to transform the raw objective into a refined, actionable objective specifically tailored for the target capability.\n\n    Refinement Guidelines:\n    1. Make the objective specific and unambiguous\n    2. Include relevant context from previous outputs\n    3. Frame it in terms the target capability understands\n    4. Define clear success criteria (3-5 specific, measurable outcomes)\n    5. Estimate the number of steps required (1-10)\n    6. Set confidence based on clarity of the refinement (0.0-1.0)\n\n    Success Criteria Guidelines:\n    - Each criterion should be verifiable\n    - Use specific metrics where possible\n    - Include both positive outcomes and negative constraints\n    - Consider edge cases and error conditions\n\n    Example Transformations:\n    - Raw: \"Research this topic\" -> Refined: \"Search for recent academic papers on X published in 2024, extract key findings, and summarize methodology\"\n    - Raw: \"Fix the bug\" -> Refined: \"Analyze the error logs in /var/log/app.log, identify the root cause of the timeout issue, and implement a fix with test coverage\"\n\n    {{ ctx.output_format }}\n  \"#\n}\n\n// ============================================================================\n// Function: ConductorBootstrapAgenda\n// ============================================================================\n\nclass ConductorBootstrapInput {\n  raw_objective string\n  available_capabilities string[]\n}\n\nclass ConductorBootstrapOutput {\n  dispatch_capabilities string[]\n  block_reason string?\n  rationale string\n  confidence float\n}\n\nfunction ConductorBootstrapAgenda(input: ConductorBootstrapInput) -> ConductorBootstrapOutput {\n  client Orchestrator\n  prompt #\"\n    You are the ChoirOS Conductor bootstrap policy.\n\n    Choose which capabilities should be dispatched first for a new run.\n\n    Raw Objective: {{ input.raw_objective }}\n    Available Capabilities: {{ input.available_capabilities }}\n\n    Output contract:\n    - dispatch_capabilities may include zero, one, or many capability names.\n    - Only return capability names from available_capabilities.\n    - Conductor routes app-level capabilities (for example: writer, immediate_response), not worker roles.\n    - Do not return worker names such as researcher or terminal unless explicitly present in available_capabilities.\n    - Use immediate_response only for short conversational acknowledgements (for example: hi, ping, quick status checks).\n    - Return zero capabilities when the run should be blocked immediately.\n    - If dispatch_capabilities is empty, block_reason is required.\n    - rationale must explain why these capabilities were selected.\n    - confidence is 0.0-1.0.\n    - Avoid deterministic threshold logic and use semantic task fit.\n\n    {{ ctx.output_format }}\n  \"#\n}\n".to_string());
+        m.insert("conductor.baml".to_string(), "// ChoirOS Conductor BAML Contracts\n// Orchestration functions for the Conductor actor to manage multi-step agent workflows\n\n// ============================================================================\n// Simplified Decision Types\n// ============================================================================\n\nenum ConductorAction {\n  SpawnWorker\n  AwaitWorker\n  MergeCanon\n  Complete\n  Block\n  /// Phase 4: Spawn a focused sub-agent for a bounded scoped task.\n  SpawnSubharness\n  /// Phase 4: Delegate a task to a named app-level worker.\n  Delegate\n}\n\n// ---------------------------------------------------------------------------\n// Phase 4 — WorkerKind\n// ---------------------------------------------------------------------------\n\nenum WorkerKind {\n  Researcher\n  Writer\n  Terminal\n  Subharness\n}\n\n// ---------------------------------------------------------------------------\n// Phase 4 — Extended ConductorDecision\n// ---------------------------------------------------------------------------\n\nclass ConductorDecision {\n  action ConductorAction\n  args map<string, string>?\n  reason string\n  /// Only set when action == SpawnSubharness.\n  subharness_task string?\n  /// Only set when action == Delegate.\n  delegate_worker WorkerKind?\n  delegate_task string?\n}\n\nclass ConductorDecisionInput {\n  run_id string\n  objective string\n  document_path string\n  last_error string?\n}\n\nfunction ConductorDecide(input: ConductorDecisionInput) -> ConductorDecision {\n  client Orchestrator\n  prompt #\"\n    You are the ChoirOS Conductor, an orchestration AI.\n\n    Current Run:\n    - Run ID: {{ input.run_id }}\n    - Objective: {{ input.objective }}\n    - Document: {{ input.document_path }}\n    {% if input.last_error %}\n    - Last Error: {{ input.last_error }}\n    {% endif %}\n\n    Read the living document at {{ input.document_path }} to understand the current state.\n\n    Decide the next action:\n    - **SpawnWorker**: Dispatch a worker (researcher, terminal) with an objective\n    - **AwaitWorker**: Wait for pending worker calls to complete\n    - **MergeCanon**: Merge completed worker proposals into canon\n    - **Complete**: The objective is achieved\n    - **Block**: Cannot proceed (error or needs human help)\n\n    {{ ctx.output_format }}\n  \"#\n}\n\n// ============================================================================\n// Function: ConductorRefineObjective\n// ============================================================================\n\nclass ConductorObjectiveRefineInput {\n  raw_objective string\n  context string[]\n  target_capability string\n}\n\nclass ConductorObjectiveRefineOutput {\n  refined_objective string\n  success_criteria string[]\n  estimated_steps int\n  confidence float\n  /// Questions whose answers would remove ambiguity; empty when the objective is already clear.\n  clarifying_questions string[]\n  requires_citations bool\n  required_source_types string[]\n}\n\nfunction ConductorRefineObjective(input: ConductorObjectiveRefineInput) -> ConductorObjectiveRefineOutput {\n  client Orchestrator\n  prompt #\"\n    You are the ChoirOS Conductor, refining user objectives into clear, actionable tasks for capability workers.\n\n    Raw Objective: {{ input.raw_objective }}\n\n    Target Capability: {{ input.target_capability }}\n\n    Context from Previous Worker Outputs or the User's Desktop:\n    {{ input.context }}\n\n    Your task is to transform the raw objective into a refined, actionable objective specifically tailored for the target capability.\n\n    Refinement Guidelines:\n    1. Make the objective specific and unambiguous\n    2. Include relevant context from previous outputs\n    3. Frame it in terms the target capability understands\n    4. Define clear success criteria (3-5 specific, measurable outcomes)\n    5. Estimate the number of steps required (1-10)\n    6. Set confidence based on clarity of the refinement (0.0-1.0)\n    7. Ask up to 3 clarifying_questions only where the answer would change the plan; leave it empty when the objective is already clear\n    8. Set requires_citations when the result makes factual claims that need sources, and list required_source_types (for example: web_page, documentation, code_snippet)\n\n    Success Criteria Guidelines:\n    - Each criterion should be verifiable\n    - Use specific metrics where possible\n    - Include both positive outcomes and negative constraints\n    - Consider edge cases and error conditions\n\n    Example Transformations:\n    - Raw: \"Research this topic\" -> Refined: \"Search for recent academic papers on X published in 2024, extract key findings, and summarize methodology\"\n    - Raw: \"Fix the bug\" -> Refined: \"Analyze the error logs in /var/log/app.log, identify the root cause of the timeout issue, and implement a fix with test coverage\"\n\n    {{ ctx.output_format }}\n  \"#\n}\n\n// ============================================================================\n// Function: ConductorBootstrapAgenda\n// ============================================================================\n\nclass ConductorBootstrapInput {\n  raw_objective string\n  available_capabilities string[]\n}\n\nclass ConductorBootstrapOutput {\n  dispatch_capabilities string[]\n  block_reason string?\n  rationale string\n  confidence float\n}\n\nfunction ConductorBootstrapAgenda(input: ConductorBootstrapInput) -> ConductorBootstrapOutput {\n  client Orchestrator\n  prompt #\"\n    You are the ChoirOS Conductor bootstrap policy.\n\n    Choose which capabilities should be dispatched first for a new run.\n\n    Raw Objective: {{ input.raw_objective }}\n    Available Capabilities: {{ input.available_capabilities }}\n\n    Output contract:\n    - dispatch_capabilities may include zero, one, or many capability names.\n    - Only return capability names from available_capabilities.\n    - Conductor routes app-level capabilities (for example: writer, immediate_response), not worker roles.\n    - Do not return worker names such as researcher or terminal unless explicitly present in available_capabilities.\n    - Use immediate_response only for short conversational acknowledgements (for example: hi, ping, quick status checks).\n    - Return zero capabilities when the run should be blocked immediately.\n    - If dispatch_capabilities is empty, block_reason is required.\n    - rationale must explain why these capabilities were selected.\n    - confidence is 0.0-1.0.\n    - Avoid deterministic threshold logic and use semantic task fit.\n\n    {{ ctx.output_format }}\n  \"#\n}\n".to_string());
 
         m.insert("generators.baml".to_string(), "// BAML Generator for Rust - ChoirOS Chat Agent\n// This generates Rust code for BAML functions\n\ngenerator target {\n    // Valid values: \"python/pydantic\", \"typescript\", \"go\", \"rust\", \"ruby/sorbet\", \"rest/openapi\"\n    output_type \"rust\"\n\n    // Where the generated code will be saved (relative to baml_src/)\n    output_dir \"../sandbox/src\"\n\n    // The version of the BAML package you have installed\n    version \"0.217.0\"\n\n    // Valid values: \"sync\", \"async\"\n    // This controls what `b.FunctionName()` will be (sync or async).\n    default_client_mode async\n}\n".to_string());
 
@@ -286,6 +286,13 @@ pub struct ConductorObjectiveRefineOutput {
     pub estimated_steps: Option<i64>,
 
     pub confidence: Option<f64>,
+
+    /// Questions whose answers would remove ambiguity; empty when the objective is already clear.
+    pub clarifying_questions: Vec<String>,
+
+    pub requires_citations: Option<bool>,
+
+    pub required_source_types: Vec<String>,
 }
 
 impl AsRef<ConductorObjectiveRefineOutput> for ConductorObjectiveRefineOutput {
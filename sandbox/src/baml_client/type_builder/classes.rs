@@ -849,6 +849,24 @@ impl ConductorObjectiveRefineOutputClassBuilder {
         self.inner.get_property("confidence")
             .expect("ConductorObjectiveRefineOutput.confidence is statically defined in .baml and should always be present")
     }
+
+    /// Access the `clarifying_questions` field builder.
+    pub fn property_clarifying_questions(&self) -> baml::ClassPropertyBuilder {
+        self.inner.get_property("clarifying_questions")
+            .expect("ConductorObjectiveRefineOutput.clarifying_questions is statically defined in .baml and should always be present")
+    }
+
+    /// Access the `requires_citations` field builder.
+    pub fn property_requires_citations(&self) -> baml::ClassPropertyBuilder {
+        self.inner.get_property("requires_citations")
+            .expect("ConductorObjectiveRefineOutput.requires_citations is statically defined in .baml and should always be present")
+    }
+
+    /// Access the `required_source_types` field builder.
+    pub fn property_required_source_types(&self) -> baml::ClassPropertyBuilder {
+        self.inner.get_property("required_source_types")
+            .expect("ConductorObjectiveRefineOutput.required_source_types is statically defined in .baml and should always be present")
+    }
 }
 
 /// Wrapper for the `ContextSource` class builder.
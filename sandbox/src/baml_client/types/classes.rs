@@ -282,6 +282,13 @@ pub struct ConductorObjectiveRefineOutput {
     pub estimated_steps: i64,
 
     pub confidence: f64,
+
+    /// Questions whose answers would remove ambiguity; empty when the objective is already clear.
+    pub clarifying_questions: Vec<String>,
+
+    pub requires_citations: bool,
+
+    pub required_source_types: Vec<String>,
 }
 
 impl AsRef<ConductorObjectiveRefineOutput> for ConductorObjectiveRefineOutput {
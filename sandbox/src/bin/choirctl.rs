@@ -0,0 +1,165 @@
+use sandbox::api::replay::{ReplayResponse, DEFAULT_REPLAY_SPEED};
+use std::path::PathBuf;
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Replay { bundle: PathBuf, speed: f64 },
+}
+
+#[derive(Debug)]
+struct Config {
+    url: String,
+    command: Command,
+}
+
+fn usage() -> &'static str {
+    "Usage: choirctl [--url <sandbox url>] <command>\n\n\
+     Commands:\n  \
+     replay <bundle.zip> [--speed <factor>]  Re-publish an exported run bundle's events"
+}
+
+fn default_url() -> String {
+    std::env::var("CHOIR_SANDBOX_URL").unwrap_or_else(|_| "http://localhost:8080".to_string())
+}
+
+fn parse_args_from<I>(args: I) -> Result<Config, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut url = None;
+    let mut subcommand = None;
+    let mut bundle = None;
+    let mut speed = DEFAULT_REPLAY_SPEED;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--url" => {
+                url = Some(
+                    args.next()
+                        .ok_or_else(|| "--url requires a value".to_string())?,
+                );
+            }
+            "--speed" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--speed requires a value".to_string())?;
+                speed = value
+                    .parse()
+                    .map_err(|_| format!("invalid --speed: {value}"))?;
+            }
+            "--help" | "-h" => return Err(usage().to_string()),
+            other if other.starts_with("--") => {
+                return Err(format!("unknown argument: {other}\n{}", usage()))
+            }
+            other if subcommand.is_none() => subcommand = Some(other.to_string()),
+            other if bundle.is_none() => bundle = Some(PathBuf::from(other)),
+            other => return Err(format!("unexpected argument: {other}\n{}", usage())),
+        }
+    }
+
+    let command = match subcommand.as_deref() {
+        Some("replay") => Command::Replay {
+            bundle: bundle.ok_or_else(|| format!("replay requires a bundle\n{}", usage()))?,
+            speed,
+        },
+        Some(other) => return Err(format!("unknown command: {other}\n{}", usage())),
+        None => return Err(usage().to_string()),
+    };
+
+    Ok(Config {
+        url: url.unwrap_or_else(default_url),
+        command,
+    })
+}
+
+async fn replay(url: &str, bundle: &PathBuf, speed: f64) -> Result<ReplayResponse, String> {
+    let body = tokio::fs::read(bundle)
+        .await
+        .map_err(|e| format!("failed to read {}: {e}", bundle.display()))?;
+    let response = reqwest::Client::new()
+        .post(format!("{}/api/admin/replay", url.trim_end_matches('/')))
+        .query(&[("speed", speed)])
+        .header(reqwest::header::CONTENT_TYPE, "application/zip")
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| format!("request failed: {e}"))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!("replay rejected ({status}): {text}"));
+    }
+    response
+        .json::<ReplayResponse>()
+        .await
+        .map_err(|e| format!("failed to parse response: {e}"))
+}
+
+#[tokio::main]
+async fn main() {
+    let config = match parse_args_from(std::env::args().skip(1)) {
+        Ok(config) => config,
+        Err(message) => {
+            eprintln!("{message}");
+            std::process::exit(2);
+        }
+    };
+
+    match config.command {
+        Command::Replay { bundle, speed } => match replay(&config.url, &bundle, speed).await {
+            Ok(replay) => println!(
+                "replay {} started: {} events from run {} over ~{:.1}s at {}x",
+                replay.replay_id,
+                replay.events,
+                replay.source_run_id,
+                replay.duration_ms as f64 / 1000.0,
+                replay.speed
+            ),
+            Err(message) => {
+                eprintln!("{message}");
+                std::process::exit(1);
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_args_from, Command};
+    use std::path::PathBuf;
+
+    fn args(raw: &[&str]) -> Vec<String> {
+        raw.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_replay_with_speed_and_url() {
+        let config = parse_args_from(args(&[
+            "--url",
+            "http://sandbox:8080",
+            "replay",
+            "run.zip",
+            "--speed",
+            "4",
+        ]))
+        .expect("args should parse");
+
+        assert_eq!(config.url, "http://sandbox:8080");
+        assert_eq!(
+            config.command,
+            Command::Replay {
+                bundle: PathBuf::from("run.zip"),
+                speed: 4.0
+            }
+        );
+    }
+
+    #[test]
+    fn replay_requires_a_bundle() {
+        let err = parse_args_from(args(&["replay"])).unwrap_err();
+        assert!(err.starts_with("replay requires a bundle"));
+        assert!(parse_args_from(args(&["rewind", "run.zip"])).is_err());
+    }
+}
@@ -0,0 +1,130 @@
+use sandbox::evals::{self, EvalOptions};
+use std::path::PathBuf;
+
+#[derive(Debug)]
+struct Config {
+    dir: PathBuf,
+    filter: Option<String>,
+    live: bool,
+    report: Option<PathBuf>,
+}
+
+fn usage() -> &'static str {
+    "Usage: evals [--dir <path>] [--filter <name>] [--live] [--report <path>]"
+}
+
+fn parse_args_from<I>(args: I) -> Result<Config, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut dir = evals::default_scenario_dir();
+    let mut filter = None;
+    let mut live = false;
+    let mut report = None;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dir" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--dir requires a path".to_string())?;
+                dir = PathBuf::from(value);
+            }
+            "--filter" => {
+                filter = Some(
+                    args.next()
+                        .ok_or_else(|| "--filter requires a value".to_string())?,
+                );
+            }
+            "--report" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--report requires a path".to_string())?;
+                report = Some(PathBuf::from(value));
+            }
+            "--live" => live = true,
+            "--help" | "-h" => return Err(usage().to_string()),
+            other => return Err(format!("unknown argument: {other}\n{}", usage())),
+        }
+    }
+
+    Ok(Config {
+        dir,
+        filter,
+        live,
+        report,
+    })
+}
+
+#[tokio::main]
+async fn main() {
+    let config = match parse_args_from(std::env::args().skip(1)) {
+        Ok(config) => config,
+        Err(message) => {
+            eprintln!("{message}");
+            std::process::exit(2);
+        }
+    };
+
+    let scenarios = match evals::load_dir(&config.dir) {
+        Ok(scenarios) => scenarios,
+        Err(err) => {
+            eprintln!("{err}");
+            std::process::exit(2);
+        }
+    };
+
+    let options = EvalOptions {
+        filter: config.filter,
+        live: config.live,
+    };
+    let report = evals::run_scenarios(&scenarios, &options).await;
+    let json = serde_json::to_string_pretty(&report).expect("report serialization must succeed");
+    match &config.report {
+        Some(path) => {
+            if let Err(err) = std::fs::write(path, &json) {
+                eprintln!("failed to write {}: {err}", path.display());
+                std::process::exit(2);
+            }
+            eprintln!(
+                "{} passed, {} failed, {} skipped; report at {}",
+                report.passed,
+                report.failed,
+                report.skipped,
+                path.display()
+            );
+        }
+        None => println!("{json}"),
+    }
+
+    if !report.all_passed() {
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_args_from;
+
+    #[test]
+    fn parses_filter_and_live_flags() {
+        let config = parse_args_from(vec![
+            "--filter".to_string(),
+            "writer".to_string(),
+            "--live".to_string(),
+        ])
+        .expect("args should parse");
+
+        assert_eq!(config.filter.as_deref(), Some("writer"));
+        assert!(config.live);
+        assert_eq!(config.dir, sandbox::evals::default_scenario_dir());
+        assert!(config.report.is_none());
+    }
+
+    #[test]
+    fn rejects_unknown_arguments() {
+        let err = parse_args_from(vec!["--verbose".to_string()]).expect_err("should fail");
+        assert!(err.contains("unknown argument: --verbose"));
+    }
+}
@@ -0,0 +1,166 @@
+use ractor::Actor;
+use sandbox::actors::event_store::{
+    scope_coverage, EventStoreActor, EventStoreArguments, ScopeCoverageReport,
+};
+use std::path::PathBuf;
+
+#[derive(Debug)]
+struct Config {
+    db: PathBuf,
+    json: bool,
+}
+
+fn usage() -> &'static str {
+    "Usage: scope_report [--db <path>] [--json]"
+}
+
+fn default_db_path() -> PathBuf {
+    let raw =
+        std::env::var("DATABASE_URL").unwrap_or_else(|_| "/opt/choiros/data/events.db".to_string());
+    PathBuf::from(raw.strip_prefix("sqlite:").unwrap_or(&raw))
+}
+
+fn parse_args_from<I>(args: I) -> Result<Config, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut db = None;
+    let mut json = false;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--db" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--db requires a path".to_string())?;
+                db = Some(PathBuf::from(value));
+            }
+            "--json" => json = true,
+            "--help" | "-h" => return Err(usage().to_string()),
+            other => return Err(format!("unknown argument: {other}\n{}", usage())),
+        }
+    }
+
+    Ok(Config {
+        db: db.unwrap_or_else(default_db_path),
+        json,
+    })
+}
+
+fn render_table(report: &ScopeCoverageReport) -> String {
+    let mut out = format!(
+        "{:<48} {:>8} {:>8} {:>10} {:>9}  required\n",
+        "topic", "events", "run_id", "session_id", "thread_id"
+    );
+    for topic in &report.topics {
+        let present = |field: &str| topic.present.get(field).copied().unwrap_or(0);
+        let required = if topic.required.is_empty() {
+            "-".to_string()
+        } else {
+            format!(
+                "{} ({} missing)",
+                topic.required.join(","),
+                topic.non_compliant
+            )
+        };
+        out.push_str(&format!(
+            "{:<48} {:>8} {:>8} {:>10} {:>9}  {required}\n",
+            topic.topic,
+            topic.events,
+            present("run_id"),
+            present("session_id"),
+            present("thread_id"),
+        ));
+    }
+    out.push_str(&format!(
+        "{} events, {} missing required scope\n",
+        report.events, report.non_compliant
+    ));
+    out
+}
+
+#[tokio::main]
+async fn main() {
+    let config = match parse_args_from(std::env::args().skip(1)) {
+        Ok(config) => config,
+        Err(message) => {
+            eprintln!("{message}");
+            std::process::exit(2);
+        }
+    };
+
+    if !config.db.exists() {
+        eprintln!("no event log at {}", config.db.display());
+        std::process::exit(2);
+    }
+
+    let (event_store, _handle) = match Actor::spawn(
+        None,
+        EventStoreActor,
+        EventStoreArguments::File(config.db.to_string_lossy().to_string()),
+    )
+    .await
+    {
+        Ok(spawned) => spawned,
+        Err(err) => {
+            eprintln!("failed to open {}: {err}", config.db.display());
+            std::process::exit(2);
+        }
+    };
+
+    let report = match scope_coverage(&event_store, 1000).await {
+        Ok(report) => report,
+        Err(err) => {
+            eprintln!("scan failed: {err}");
+            std::process::exit(2);
+        }
+    };
+    event_store.stop(None);
+
+    if config.json {
+        let json =
+            serde_json::to_string_pretty(&report).expect("report serialization must succeed");
+        println!("{json}");
+    } else {
+        print!("{}", render_table(&report));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_args_from, render_table};
+    use sandbox::actors::event_store::{ScopeCoverageReport, TopicScopeCoverage};
+
+    #[test]
+    fn parses_db_and_json_flags() {
+        let config = parse_args_from(vec![
+            "--db".to_string(),
+            "/tmp/events.db".to_string(),
+            "--json".to_string(),
+        ])
+        .expect("args should parse");
+
+        assert_eq!(config.db.to_str(), Some("/tmp/events.db"));
+        assert!(config.json);
+    }
+
+    #[test]
+    fn table_lists_missing_counts_for_required_topics() {
+        let report = ScopeCoverageReport {
+            events: 3,
+            non_compliant: 1,
+            topics: vec![TopicScopeCoverage {
+                topic: "writer.run.patch".to_string(),
+                events: 3,
+                present: [("run_id".to_string(), 3)].into_iter().collect(),
+                required: vec!["run_id".to_string(), "session_id".to_string()],
+                non_compliant: 1,
+            }],
+        };
+
+        let table = render_table(&report);
+        assert!(table.contains("run_id,session_id (1 missing)"));
+        assert!(table.ends_with("3 events, 1 missing required scope\n"));
+    }
+}
@@ -0,0 +1,312 @@
+//! Chunking of fetched content for embedding.
+//!
+//! [`chunk_content`] splits text by the strategy recorded on
+//! `ExternalContentRecord::chunk_strategy`. Every chunk carries its byte
+//! offsets into the original text and a SHA-256 of its text, so re-chunking
+//! unchanged content yields the same chunks and hashes. Chunks are trimmed
+//! of surrounding whitespace and never exceed [`MAX_CHUNK_BYTES`]; a span
+//! longer than that is sub-split at the last paragraph break, line break or
+//! whitespace that fits, or at a char boundary when there is none.
+//!
+//! Fenced code blocks are kept whole by the `sections` and `paragraphs`
+//! strategies: a `#` line inside one is not a heading and a blank line inside
+//! one does not end a paragraph.
+
+use sha2::{Digest, Sha256};
+
+/// Upper bound on the bytes of one chunk.
+pub const MAX_CHUNK_BYTES: usize = 2_000;
+
+/// How content is split before embedding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkStrategy {
+    /// The whole text, sub-split only where it exceeds the bound.
+    Full,
+    /// One chunk per Markdown heading and the text under it; text before the
+    /// first heading is its own section.
+    Sections,
+    /// One chunk per blank-line separated paragraph.
+    Paragraphs,
+}
+
+impl ChunkStrategy {
+    pub const ALL: [ChunkStrategy; 3] = [Self::Full, Self::Sections, Self::Paragraphs];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::Sections => "sections",
+            Self::Paragraphs => "paragraphs",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|strategy| strategy.as_str().eq_ignore_ascii_case(value.trim()))
+    }
+}
+
+/// One piece of chunked content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chunk {
+    /// Position among the chunks of the same text, from 0.
+    pub index: usize,
+    /// Byte offset of `text` in the chunked content.
+    pub start: usize,
+    /// Byte offset just past `text` in the chunked content.
+    pub end: usize,
+    pub text: String,
+    /// SHA-256 hex of `text`.
+    pub hash: String,
+}
+
+/// Split `text` into chunks of at most [`MAX_CHUNK_BYTES`] by `strategy`.
+/// Whitespace-only text has no chunks.
+pub fn chunk_content(text: &str, strategy: ChunkStrategy) -> Vec<Chunk> {
+    chunk_content_bounded(text, strategy, MAX_CHUNK_BYTES)
+}
+
+fn chunk_content_bounded(text: &str, strategy: ChunkStrategy, max_bytes: usize) -> Vec<Chunk> {
+    // Room for any single char, so a hard cut always makes progress.
+    let max_bytes = max_bytes.max(4);
+    let spans = match strategy {
+        ChunkStrategy::Full => vec![(0, text.len())],
+        ChunkStrategy::Sections => section_spans(text),
+        ChunkStrategy::Paragraphs => paragraph_spans(text),
+    };
+    spans
+        .into_iter()
+        .flat_map(|span| split_span(text, span, max_bytes))
+        .enumerate()
+        .map(|(index, (start, end))| {
+            let chunk = &text[start..end];
+            Chunk {
+                index,
+                start,
+                end,
+                text: chunk.to_string(),
+                hash: hex::encode(Sha256::digest(chunk.as_bytes())),
+            }
+        })
+        .collect()
+}
+
+/// A line of `text` with the byte offsets of its start and of the start of
+/// the next line.
+struct Line<'a> {
+    start: usize,
+    end: usize,
+    text: &'a str,
+    /// Inside a fenced code block, fence lines included.
+    fenced: bool,
+}
+
+fn lines(text: &str) -> Vec<Line<'_>> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    let mut in_fence = false;
+    for raw in text.split_inclusive('\n') {
+        let trimmed = raw.trim_start();
+        let is_fence = trimmed.starts_with("```") || trimmed.starts_with("~~~");
+        lines.push(Line {
+            start,
+            end: start + raw.len(),
+            text: raw,
+            fenced: in_fence || is_fence,
+        });
+        if is_fence {
+            in_fence = !in_fence;
+        }
+        start += raw.len();
+    }
+    lines
+}
+
+/// ATX heading: one to six `#` followed by whitespace or the end of the line.
+fn is_heading(line: &str) -> bool {
+    let line = line.trim_start();
+    let hashes = line.chars().take_while(|c| *c == '#').count();
+    (1..=6).contains(&hashes)
+        && line[hashes..]
+            .chars()
+            .next()
+            .map_or(true, char::is_whitespace)
+}
+
+fn section_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = 0;
+    for line in lines(text) {
+        if !line.fenced && is_heading(line.text) && line.start > start {
+            spans.push((start, line.start));
+            start = line.start;
+        }
+    }
+    spans.push((start, text.len()));
+    spans
+}
+
+fn paragraph_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = 0;
+    for line in lines(text) {
+        if !line.fenced && line.text.trim().is_empty() {
+            spans.push((start, line.start));
+            start = line.end;
+        }
+    }
+    spans.push((start, text.len()));
+    spans
+}
+
+/// Trim `span` and cut it into pieces of at most `max_bytes`.
+fn split_span(text: &str, (start, end): (usize, usize), max_bytes: usize) -> Vec<(usize, usize)> {
+    let mut pieces = Vec::new();
+    let mut start = start;
+    loop {
+        let Some((piece_start, piece_end)) = trim_span(text, start, end) else {
+            return pieces;
+        };
+        if piece_end - piece_start <= max_bytes {
+            pieces.push((piece_start, piece_end));
+            return pieces;
+        }
+        let limit = floor_char_boundary(text, piece_start + max_bytes);
+        let window = &text[piece_start..limit];
+        let cut = window
+            .rfind("\n\n")
+            .or_else(|| window.rfind('\n'))
+            .or_else(|| window.rfind(char::is_whitespace))
+            .filter(|offset| *offset > 0)
+            .map_or(limit, |offset| piece_start + offset);
+        if let Some(piece) = trim_span(text, piece_start, cut) {
+            pieces.push(piece);
+        }
+        start = cut;
+    }
+}
+
+fn trim_span(text: &str, start: usize, end: usize) -> Option<(usize, usize)> {
+    let slice = &text[start..end];
+    let trimmed_start = slice.trim_start();
+    if trimmed_start.is_empty() {
+        return None;
+    }
+    let start = start + (slice.len() - trimmed_start.len());
+    Some((start, start + trimmed_start.trim_end().len()))
+}
+
+fn floor_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "Intro line.\n\n\
+# Alpha\n\n\
+First para.\nStill first.\n\n\
+Second para.\n\n\
+```\n# not a heading\n\nstill code\n```\n\n\
+## Beta\n\n\
+Beta para.\n";
+
+    fn texts(chunks: &[Chunk]) -> Vec<&str> {
+        chunks.iter().map(|chunk| chunk.text.as_str()).collect()
+    }
+
+    fn assert_consistent(text: &str, chunks: &[Chunk], max_bytes: usize) {
+        for (index, chunk) in chunks.iter().enumerate() {
+            assert_eq!(chunk.index, index);
+            assert_eq!(&text[chunk.start..chunk.end], chunk.text);
+            assert!(chunk.text.len() <= max_bytes);
+            assert_eq!(
+                chunk.hash,
+                hex::encode(Sha256::digest(chunk.text.as_bytes()))
+            );
+        }
+        assert!(chunks.windows(2).all(|pair| pair[0].end <= pair[1].start));
+    }
+
+    #[test]
+    fn full_strategy_is_one_trimmed_chunk() {
+        let chunks = chunk_content(SAMPLE, ChunkStrategy::Full);
+        assert_eq!(texts(&chunks), [SAMPLE.trim()]);
+        assert_consistent(SAMPLE, &chunks, MAX_CHUNK_BYTES);
+        assert!(chunk_content(" \n\n ", ChunkStrategy::Full).is_empty());
+    }
+
+    #[test]
+    fn sections_strategy_splits_at_headings_outside_code() {
+        let chunks = chunk_content(SAMPLE, ChunkStrategy::Sections);
+        assert_eq!(
+            texts(&chunks),
+            [
+                "Intro line.",
+                "# Alpha\n\nFirst para.\nStill first.\n\nSecond para.\n\n```\n# not a heading\n\nstill code\n```",
+                "## Beta\n\nBeta para.",
+            ]
+        );
+        assert_consistent(SAMPLE, &chunks, MAX_CHUNK_BYTES);
+        assert!(!is_heading("#hashtag"));
+    }
+
+    #[test]
+    fn paragraphs_strategy_splits_at_blank_lines_outside_code() {
+        let chunks = chunk_content(SAMPLE, ChunkStrategy::Paragraphs);
+        assert_eq!(
+            texts(&chunks),
+            [
+                "Intro line.",
+                "# Alpha",
+                "First para.\nStill first.",
+                "Second para.",
+                "```\n# not a heading\n\nstill code\n```",
+                "## Beta",
+                "Beta para.",
+            ]
+        );
+        assert_consistent(SAMPLE, &chunks, MAX_CHUNK_BYTES);
+    }
+
+    #[test]
+    fn long_paragraph_is_sub_split_at_whitespace() {
+        let words: Vec<String> = (0..40).map(|n| format!("word{n}")).collect();
+        let paragraph = words.join(" ");
+        let text = format!("Short.\n\n{paragraph}\n");
+
+        let chunks = chunk_content_bounded(&text, ChunkStrategy::Paragraphs, 40);
+        assert!(chunks.len() > 2);
+        assert_eq!(chunks[0].text, "Short.");
+        assert_consistent(&text, &chunks, 40);
+        let rejoined: Vec<&str> = chunks[1..]
+            .iter()
+            .flat_map(|chunk| chunk.text.split(' '))
+            .collect();
+        assert_eq!(rejoined, words);
+
+        // Unbroken multibyte text is cut at char boundaries.
+        let unbroken = "é".repeat(50);
+        let chunks = chunk_content_bounded(&unbroken, ChunkStrategy::Full, 9);
+        assert_consistent(&unbroken, &chunks, 9);
+        assert_eq!(chunks.iter().map(|c| c.text.len()).sum::<usize>(), 100);
+    }
+
+    #[test]
+    fn chunks_are_stable_and_strategies_round_trip() {
+        assert_eq!(
+            chunk_content(SAMPLE, ChunkStrategy::Sections),
+            chunk_content(SAMPLE, ChunkStrategy::Sections)
+        );
+        for strategy in ChunkStrategy::ALL {
+            assert_eq!(ChunkStrategy::parse(strategy.as_str()), Some(strategy));
+        }
+        assert_eq!(ChunkStrategy::parse("words"), None);
+    }
+}
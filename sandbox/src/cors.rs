@@ -0,0 +1,264 @@
+//! Cross-origin policy for the sandbox HTTP server.
+//!
+//! The allowed origins come from the environment, checked in order:
+//!
+//! - `CHOIR_CORS_DEV` truthy: any origin is allowed; the request's `Origin` is
+//!   reflected back. For local development only.
+//! - `CHOIR_CORS_ORIGINS`: comma-separated origins such as
+//!   `https://choir-ip.com,http://localhost:*`. A `*` port is accepted for
+//!   loopback hosts only and matches any port, or none.
+//! - `CHOIR_HYPERVISOR_ORIGIN`: set by the hypervisor when it spawns a managed
+//!   sandbox; that origin is the only one allowed.
+//! - Otherwise loopback origins on any port.
+//!
+//! A malformed origin is an error, so a typo fails startup instead of
+//! silently locking the UI out.
+
+use std::fmt;
+
+use axum::http::{header, HeaderName, HeaderValue, Method};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+use crate::api::logs::IDEMPOTENCY_KEY_HEADER;
+
+/// Hosts that may use a wildcard port.
+const LOOPBACK_HOSTS: &[&str] = &["localhost", "127.0.0.1", "[::1]"];
+
+/// Origins allowed when nothing is configured.
+const DEFAULT_ORIGINS: &str = "http://localhost:*,http://127.0.0.1:*";
+
+/// An origin in the allowlist could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid CORS origin {origin:?}: {reason}")]
+pub struct InvalidOrigin {
+    pub origin: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PortPattern {
+    /// No port, or exactly this one.
+    Exact(Option<u16>),
+    Any,
+}
+
+/// One allowed origin: scheme and host, with an exact or wildcard port.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OriginPattern {
+    scheme: String,
+    host: String,
+    port: PortPattern,
+}
+
+impl OriginPattern {
+    /// Parse `scheme://host[:port]`, where the port may be `*` for loopback
+    /// hosts.
+    pub fn parse(origin: &str) -> Result<Self, InvalidOrigin> {
+        let invalid = |reason: &str| InvalidOrigin {
+            origin: origin.to_string(),
+            reason: reason.to_string(),
+        };
+        let (scheme, authority) = origin
+            .split_once("://")
+            .ok_or_else(|| invalid("expected scheme://host[:port]"))?;
+        let scheme = scheme.to_ascii_lowercase();
+        if scheme != "http" && scheme != "https" {
+            return Err(invalid("scheme must be http or https"));
+        }
+        if authority.is_empty() {
+            return Err(invalid("missing host"));
+        }
+        if authority.contains(['/', '?', '#', '@']) {
+            return Err(invalid("an origin has no path, query, fragment or user"));
+        }
+
+        // Bracketed IPv6 hosts contain colons of their own.
+        let port_sep = match authority.rfind(']') {
+            Some(end) => authority[end..].find(':').map(|i| end + i),
+            None => authority.rfind(':'),
+        };
+        let (host, port) = match port_sep {
+            Some(i) => (&authority[..i], Some(&authority[i + 1..])),
+            None => (authority, None),
+        };
+        let host = host.to_ascii_lowercase();
+        let valid_host = if let Some(inner) = host.strip_prefix('[') {
+            inner
+                .strip_suffix(']')
+                .is_some_and(|ip| ip.parse::<std::net::Ipv6Addr>().is_ok())
+        } else {
+            !host.is_empty()
+                && host
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+        };
+        if !valid_host {
+            return Err(invalid("invalid host"));
+        }
+
+        let port = match port {
+            None => PortPattern::Exact(None),
+            Some("*") if LOOPBACK_HOSTS.contains(&host.as_str()) => PortPattern::Any,
+            Some("*") => return Err(invalid("wildcard ports are for loopback hosts only")),
+            Some(port) => PortPattern::Exact(Some(
+                port.parse::<u16>().map_err(|_| invalid("invalid port"))?,
+            )),
+        };
+        Ok(Self { scheme, host, port })
+    }
+
+    /// Whether a request's `Origin` header value is allowed by this pattern.
+    fn matches(&self, origin: &str) -> bool {
+        let Ok(origin) = Self::parse(origin) else {
+            return false;
+        };
+        let PortPattern::Exact(port) = origin.port else {
+            return false;
+        };
+        origin.scheme == self.scheme
+            && origin.host == self.host
+            && match self.port {
+                PortPattern::Any => true,
+                PortPattern::Exact(allowed) => allowed == port,
+            }
+    }
+}
+
+impl fmt::Display for OriginPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}://{}", self.scheme, self.host)?;
+        match self.port {
+            PortPattern::Exact(None) => Ok(()),
+            PortPattern::Exact(Some(port)) => write!(f, ":{port}"),
+            PortPattern::Any => write!(f, ":*"),
+        }
+    }
+}
+
+/// The effective cross-origin policy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CorsConfig {
+    /// Reflect any request origin (`CHOIR_CORS_DEV`).
+    Permissive,
+    /// Only these origins.
+    Allowlist(Vec<OriginPattern>),
+}
+
+impl CorsConfig {
+    /// Parse a comma-separated origin list; empty entries are ignored.
+    pub fn allowlist(origins: &str) -> Result<Self, InvalidOrigin> {
+        origins
+            .split(',')
+            .map(str::trim)
+            .filter(|origin| !origin.is_empty())
+            .map(OriginPattern::parse)
+            .collect::<Result<Vec<_>, _>>()
+            .map(Self::Allowlist)
+    }
+
+    /// Policy from the environment; see the module docs for the precedence.
+    pub fn from_env() -> Result<Self, InvalidOrigin> {
+        let var = |key: &str| {
+            std::env::var(key)
+                .ok()
+                .filter(|value| !value.trim().is_empty())
+        };
+        let dev = var("CHOIR_CORS_DEV").is_some_and(|value| {
+            matches!(
+                value.trim().to_ascii_lowercase().as_str(),
+                "1" | "true" | "yes" | "on"
+            )
+        });
+        if dev {
+            return Ok(Self::Permissive);
+        }
+        if let Some(origins) = var("CHOIR_CORS_ORIGINS") {
+            return Self::allowlist(&origins);
+        }
+        if let Some(origin) = var("CHOIR_HYPERVISOR_ORIGIN") {
+            return OriginPattern::parse(origin.trim()).map(|origin| Self::Allowlist(vec![origin]));
+        }
+        Self::allowlist(DEFAULT_ORIGINS)
+    }
+
+    /// The tower-http layer enforcing this policy.
+    pub fn layer(&self) -> CorsLayer {
+        let allow_origin = match self {
+            Self::Permissive => AllowOrigin::mirror_request(),
+            Self::Allowlist(patterns) => {
+                let patterns = patterns.clone();
+                AllowOrigin::predicate(move |origin: &HeaderValue, _| {
+                    origin
+                        .to_str()
+                        .is_ok_and(|origin| patterns.iter().any(|pattern| pattern.matches(origin)))
+                })
+            }
+        };
+        CorsLayer::new()
+            .allow_origin(allow_origin)
+            .allow_methods([
+                Method::GET,
+                Method::POST,
+                Method::DELETE,
+                Method::PATCH,
+                Method::OPTIONS,
+            ])
+            .allow_headers([
+                header::CONTENT_TYPE,
+                header::ACCEPT,
+                header::AUTHORIZATION,
+                HeaderName::from_static(IDEMPOTENCY_KEY_HEADER),
+            ])
+            .max_age(std::time::Duration::from_secs(3600))
+    }
+}
+
+impl fmt::Display for CorsConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Permissive => write!(f, "any origin (dev mode)"),
+            Self::Allowlist(patterns) if patterns.is_empty() => write!(f, "no origins"),
+            Self::Allowlist(patterns) => {
+                let patterns = patterns.iter().map(ToString::to_string).collect::<Vec<_>>();
+                write!(f, "{}", patterns.join(", "))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rejects_malformed_origins() {
+        for origin in [
+            "choir-ip.com",
+            "ftp://choir-ip.com",
+            "https://choir-ip.com/",
+            "https://choir-ip.com:99999",
+            "https://choir-ip.com:*",
+            "http://",
+            "http://bad host",
+        ] {
+            assert!(OriginPattern::parse(origin).is_err(), "{origin} parsed");
+        }
+    }
+
+    #[test]
+    fn test_wildcard_port_matches_any_loopback_port() {
+        let pattern = OriginPattern::parse("http://localhost:*").unwrap();
+        assert!(pattern.matches("http://localhost:3000"));
+        assert!(pattern.matches("http://localhost"));
+        assert!(!pattern.matches("https://localhost:3000"));
+        assert!(!pattern.matches("http://localhost.evil.com:3000"));
+
+        let ipv6 = OriginPattern::parse("http://[::1]:*").unwrap();
+        assert!(ipv6.matches("http://[::1]:8080"));
+        assert_eq!(ipv6.to_string(), "http://[::1]:*");
+
+        let exact = OriginPattern::parse("HTTPS://Choir-IP.com").unwrap();
+        assert!(exact.matches("https://choir-ip.com"));
+        assert!(!exact.matches("https://choir-ip.com:8443"));
+    }
+}
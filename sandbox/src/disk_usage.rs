@@ -0,0 +1,351 @@
+//! Per-workspace disk usage accounting and quota enforcement.
+//!
+//! A full scan of the workspace root is cached for `scan_ttl`; writes made
+//! through the API in between are added to the cached totals, so quota checks
+//! stay cheap. Incremental accounting counts every write at its full size, so
+//! overwrites over-count until the next scan corrects the totals.
+
+use chrono::{DateTime, Utc};
+use shared_types::{DiskCategoryUsage, DiskUsageCategory, DiskUsageResponse};
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long a full scan is trusted before the next read rescans.
+pub const DEFAULT_SCAN_TTL: Duration = Duration::from_secs(60);
+
+/// Usage percentages that emit a `workspace.quota.warning` when crossed.
+pub const QUOTA_WARNING_THRESHOLDS: [u8; 2] = [80, 95];
+
+/// A write was refused because it would take the workspace over its quota.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "Workspace disk quota exceeded: {used_bytes} of {quota_bytes} bytes used, write needs {requested_bytes} more"
+)]
+pub struct QuotaExceeded {
+    pub used_bytes: u64,
+    pub quota_bytes: u64,
+    pub requested_bytes: u64,
+}
+
+/// Category of `path` by its location under `root`. Paths outside the root
+/// count as files.
+pub fn categorize(root: &Path, path: &Path) -> DiskUsageCategory {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    let mut components = relative
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => name.to_str(),
+            _ => None,
+        });
+    match (components.next(), components.next()) {
+        (Some("conductor"), Some("runs")) => DiskUsageCategory::Runs,
+        (Some("reports"), _) => DiskUsageCategory::Reports,
+        (Some(".writer_revisions"), _) => DiskUsageCategory::Revisions,
+        _ => DiskUsageCategory::Files,
+    }
+}
+
+/// Walk `root` and sum file sizes per category. Symlinks are not followed and
+/// unreadable entries are skipped.
+fn scan(root: &Path) -> BTreeMap<DiskUsageCategory, u64> {
+    let mut totals = BTreeMap::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            let path = entry.path();
+            if metadata.is_dir() {
+                pending.push(path);
+            } else if metadata.is_file() {
+                *totals.entry(categorize(root, &path)).or_insert(0) += metadata.len();
+            }
+        }
+    }
+    totals
+}
+
+#[derive(Debug)]
+struct Scan {
+    at: Instant,
+    at_utc: DateTime<Utc>,
+    bytes: BTreeMap<DiskUsageCategory, u64>,
+}
+
+#[derive(Debug, Default)]
+struct TrackerState {
+    scanned: Option<Scan>,
+    /// Bytes recorded at write sites since the last scan.
+    pending: BTreeMap<DiskUsageCategory, u64>,
+    /// Highest warning threshold already reported.
+    warned_pct: u8,
+}
+
+#[derive(Debug)]
+pub struct DiskUsageTracker {
+    root: PathBuf,
+    quota_bytes: Option<u64>,
+    scan_ttl: Duration,
+    state: Mutex<TrackerState>,
+}
+
+impl DiskUsageTracker {
+    pub fn new(root: PathBuf, quota_bytes: Option<u64>) -> Self {
+        Self {
+            root,
+            quota_bytes,
+            scan_ttl: DEFAULT_SCAN_TTL,
+            state: Mutex::new(TrackerState::default()),
+        }
+    }
+
+    /// Tracker for `root` with the quota from `CHOIR_WORKSPACE_QUOTA_BYTES`;
+    /// unset, empty or zero means unlimited.
+    pub fn from_env(root: PathBuf) -> Self {
+        let quota_bytes = std::env::var("CHOIR_WORKSPACE_QUOTA_BYTES")
+            .ok()
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .filter(|quota| *quota > 0);
+        Self::new(root, quota_bytes)
+    }
+
+    pub fn with_scan_ttl(mut self, scan_ttl: Duration) -> Self {
+        self.scan_ttl = scan_ttl;
+        self
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn quota_bytes(&self) -> Option<u64> {
+        self.quota_bytes
+    }
+
+    /// Current usage, rescanning the workspace when the cached scan is stale.
+    pub async fn usage(&self) -> DiskUsageResponse {
+        let stale = self
+            .state
+            .lock()
+            .expect("disk usage state poisoned")
+            .scanned
+            .as_ref()
+            .map_or(true, |scan| scan.at.elapsed() >= self.scan_ttl);
+        if stale {
+            self.refresh().await;
+        }
+        self.snapshot()
+    }
+
+    /// Rescan the workspace now, replacing incremental counts.
+    pub async fn refresh(&self) {
+        let root = self.root.clone();
+        let bytes = tokio::task::spawn_blocking(move || scan(&root))
+            .await
+            .unwrap_or_default();
+        let mut state = self.state.lock().expect("disk usage state poisoned");
+        state.scanned = Some(Scan {
+            at: Instant::now(),
+            at_utc: Utc::now(),
+            bytes,
+        });
+        state.pending.clear();
+    }
+
+    /// Refuse a write of `bytes` that would exceed the quota. Always succeeds,
+    /// without scanning, when no quota is configured.
+    pub async fn check_write(&self, bytes: u64) -> Result<(), QuotaExceeded> {
+        let Some(quota_bytes) = self.quota_bytes else {
+            return Ok(());
+        };
+        let used_bytes = self.usage().await.used_bytes;
+        if used_bytes.saturating_add(bytes) > quota_bytes {
+            return Err(QuotaExceeded {
+                used_bytes,
+                quota_bytes,
+                requested_bytes: bytes,
+            });
+        }
+        Ok(())
+    }
+
+    /// Count a completed write of `bytes` to `path` until the next scan.
+    pub fn record_write(&self, path: &Path, bytes: u64) {
+        let category = categorize(&self.root, path);
+        let mut state = self.state.lock().expect("disk usage state poisoned");
+        *state.pending.entry(category).or_insert(0) += bytes;
+    }
+
+    /// The warning threshold `usage` newly crossed, if any. Each threshold is
+    /// reported once; dropping back below it re-arms the warning.
+    pub fn take_crossed_threshold(&self, usage: &DiskUsageResponse) -> Option<u8> {
+        let used_pct = usage.used_pct?;
+        let reached = QUOTA_WARNING_THRESHOLDS
+            .iter()
+            .copied()
+            .filter(|threshold| used_pct >= *threshold)
+            .max()
+            .unwrap_or(0);
+        let mut state = self.state.lock().expect("disk usage state poisoned");
+        let crossed = reached > state.warned_pct;
+        state.warned_pct = reached;
+        crossed.then_some(reached)
+    }
+
+    fn snapshot(&self) -> DiskUsageResponse {
+        let state = self.state.lock().expect("disk usage state poisoned");
+        let mut totals = state
+            .scanned
+            .as_ref()
+            .map(|scan| scan.bytes.clone())
+            .unwrap_or_default();
+        for (category, bytes) in &state.pending {
+            *totals.entry(*category).or_insert(0) += bytes;
+        }
+
+        let used_bytes = totals.values().sum::<u64>();
+        let mut categories: Vec<DiskCategoryUsage> = totals
+            .into_iter()
+            .map(|(category, bytes)| DiskCategoryUsage { category, bytes })
+            .collect();
+        categories.sort_by_key(|usage| std::cmp::Reverse(usage.bytes));
+
+        DiskUsageResponse {
+            used_bytes,
+            quota_bytes: self.quota_bytes,
+            used_pct: self
+                .quota_bytes
+                .map(|quota| (used_bytes.saturating_mul(100) / quota).min(100) as u8),
+            categories,
+            scanned_at: state
+                .scanned
+                .as_ref()
+                .map(|scan| scan.at_utc)
+                .unwrap_or_else(Utc::now),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, relative: &str, bytes: usize) {
+        let path = root.join(relative);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, vec![b'x'; bytes]).unwrap();
+    }
+
+    #[test]
+    fn categorize_by_top_level_location() {
+        let root = Path::new("/workspace");
+        let cases = [
+            ("conductor/runs/run_1/draft.md", DiskUsageCategory::Runs),
+            ("reports/run_1.md", DiskUsageCategory::Reports),
+            (".writer_revisions/a__b.rev", DiskUsageCategory::Revisions),
+            ("conductor/notes.md", DiskUsageCategory::Files),
+            ("projects/app/main.rs", DiskUsageCategory::Files),
+        ];
+        for (relative, expected) in cases {
+            assert_eq!(
+                categorize(root, &root.join(relative)),
+                expected,
+                "{relative}"
+            );
+        }
+        assert_eq!(
+            categorize(root, Path::new("/elsewhere/reports/x.md")),
+            DiskUsageCategory::Files
+        );
+    }
+
+    #[tokio::test]
+    async fn usage_breaks_down_by_category_largest_first() {
+        let temp = tempfile::tempdir().unwrap();
+        write(temp.path(), "conductor/runs/run_1/draft.md", 300);
+        write(temp.path(), "reports/run_1.md", 50);
+        write(temp.path(), "notes/todo.md", 120);
+
+        let tracker = DiskUsageTracker::new(temp.path().to_path_buf(), Some(1000));
+        let usage = tracker.usage().await;
+        assert_eq!(usage.used_bytes, 470);
+        assert_eq!(usage.used_pct, Some(47));
+        let order: Vec<_> = usage.categories.iter().map(|c| c.category).collect();
+        assert_eq!(
+            order,
+            [
+                DiskUsageCategory::Runs,
+                DiskUsageCategory::Files,
+                DiskUsageCategory::Reports
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn recorded_writes_count_until_the_next_scan() {
+        let temp = tempfile::tempdir().unwrap();
+        write(temp.path(), "notes/todo.md", 100);
+        let tracker = DiskUsageTracker::new(temp.path().to_path_buf(), Some(1000));
+        assert_eq!(tracker.usage().await.used_bytes, 100);
+
+        // Not on disk, so only the incremental count knows about it.
+        tracker.record_write(&temp.path().join("reports/run_2.md"), 40);
+        assert_eq!(tracker.usage().await.used_bytes, 140);
+
+        tracker.refresh().await;
+        assert_eq!(tracker.usage().await.used_bytes, 100);
+    }
+
+    #[tokio::test]
+    async fn check_write_refuses_writes_past_the_quota() {
+        let temp = tempfile::tempdir().unwrap();
+        write(temp.path(), "notes/todo.md", 90);
+        let tracker = DiskUsageTracker::new(temp.path().to_path_buf(), Some(100));
+
+        assert_eq!(tracker.check_write(10).await, Ok(()));
+        assert_eq!(
+            tracker.check_write(11).await,
+            Err(QuotaExceeded {
+                used_bytes: 90,
+                quota_bytes: 100,
+                requested_bytes: 11,
+            })
+        );
+
+        let unlimited = DiskUsageTracker::new(temp.path().to_path_buf(), None);
+        assert_eq!(unlimited.check_write(u64::MAX).await, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn thresholds_warn_once_per_crossing() {
+        let temp = tempfile::tempdir().unwrap();
+        let tracker = DiskUsageTracker::new(temp.path().to_path_buf(), Some(100));
+        let notes = temp.path().join("notes.md");
+
+        let usage = tracker.usage().await;
+        assert_eq!(tracker.take_crossed_threshold(&usage), None);
+
+        tracker.record_write(&notes, 85);
+        let usage = tracker.usage().await;
+        assert_eq!(tracker.take_crossed_threshold(&usage), Some(80));
+        assert_eq!(tracker.take_crossed_threshold(&usage), None);
+
+        tracker.record_write(&notes, 10);
+        let usage = tracker.usage().await;
+        assert_eq!(tracker.take_crossed_threshold(&usage), Some(95));
+
+        // Cleanup below both thresholds re-arms the warnings.
+        tracker.refresh().await;
+        let usage = tracker.usage().await;
+        assert_eq!(tracker.take_crossed_threshold(&usage), None);
+        tracker.record_write(&notes, 82);
+        let usage = tracker.usage().await;
+        assert_eq!(tracker.take_crossed_threshold(&usage), Some(80));
+    }
+}
@@ -0,0 +1,32 @@
+//! Scenario evals for agent behavior.
+//!
+//! A scenario is a YAML file naming an objective, optional fixture files, the
+//! provider responses to replay (`mode: stub`) or `mode: live`, and
+//! assertions on emitted events, the run document and the completion payload.
+//! Each scenario runs against a fresh production actor tree with an
+//! in-memory event store, its sandbox, writer and workspace roots in a temp
+//! dir. Stub scenarios point the provider gateway at a local server replaying
+//! scripted model replies and search results, so the real conductor, workers
+//! and BAML parsing run and only provider HTTP is faked.
+//!
+//! Emitted events with a shared-types payload are decoded into it before any
+//! assertion looks at them, so a schema change fails the eval instead of
+//! silently missing a field.
+//!
+//! ```text
+//! cargo run --bin evals -- --filter writer_patch
+//! ```
+
+pub mod provider;
+pub mod report;
+pub mod runner;
+pub mod scenario;
+
+pub use report::{AssertionFailure, EvalReport, ScenarioOutcome, ScenarioReport};
+pub use runner::{run_scenario, run_scenarios, EvalOptions};
+pub use scenario::{load_dir, Scenario, ScenarioError, ScenarioMode};
+
+/// Directory holding the scenarios shipped with the sandbox.
+pub fn default_scenario_dir() -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("evals")
+}
@@ -0,0 +1,211 @@
+//! Scripted provider gateway for `mode: stub` scenarios.
+//!
+//! With `CHOIR_PROVIDER_GATEWAY_BASE_URL` set, every model call (BAML
+//! clients built by `model_config`) and every web search (researcher
+//! providers) goes over HTTP to the provider gateway. The runner points that
+//! variable at a [`StubProviderServer`], so the conductor, writer, researcher
+//! and terminal run unchanged and only the provider responses are scripted.
+//!
+//! A model request is answered by the first unused reply whose `when` text
+//! occurs in the request body; replies sharing a `when` are used in order.
+//! Search results are answered by `when` every time, since the researcher
+//! queries each search provider in turn. Requests nothing matches get a 500
+//! and are reported by [`StubProviderServer::unmatched`].
+
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::{Json, Router};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+use super::scenario::{StubProviders, StubReply, StubSearch};
+
+/// Bearer token the runner configures for the stub gateway.
+pub const STUB_GATEWAY_TOKEN: &str = "evals-stub-token";
+
+/// Longest request excerpt kept for an unmatched request.
+const UNMATCHED_EXCERPT_CHARS: usize = 400;
+
+#[derive(Default)]
+struct Script {
+    replies: Vec<(StubReply, bool)>,
+    search: Vec<StubSearch>,
+    unmatched: Vec<String>,
+}
+
+impl Script {
+    fn take_reply(&mut self, body: &str) -> Option<Value> {
+        let (reply, used) = self
+            .replies
+            .iter_mut()
+            .find(|(reply, used)| (!*used || reply.repeat) && body.contains(&reply.when))?;
+        *used = true;
+        Some(reply.reply.clone())
+    }
+
+    fn find_search(&self, query: &str) -> Option<StubSearch> {
+        self.search
+            .iter()
+            .find(|search| query.contains(&search.when))
+            .cloned()
+    }
+
+    fn record_unmatched(&mut self, path: &str, body: &str) {
+        let excerpt: String = body.chars().take(UNMATCHED_EXCERPT_CHARS).collect();
+        self.unmatched.push(format!("{path}: {excerpt}"));
+    }
+}
+
+/// Local HTTP server standing in for the provider gateway.
+pub struct StubProviderServer {
+    base_url: String,
+    script: Arc<Mutex<Script>>,
+    server: JoinHandle<()>,
+}
+
+impl StubProviderServer {
+    pub async fn start(stub: &StubProviders) -> std::io::Result<Self> {
+        let script = Arc::new(Mutex::new(Script {
+            replies: stub.replies.iter().cloned().map(|r| (r, false)).collect(),
+            search: stub.search.clone(),
+            unmatched: Vec::new(),
+        }));
+        let app = Router::new().fallback(handle).with_state(script.clone());
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+        Ok(Self {
+            base_url: format!("http://{addr}"),
+            script,
+            server,
+        })
+    }
+
+    /// Value for `CHOIR_PROVIDER_GATEWAY_BASE_URL`.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Requests no scripted reply or search matched, as `path: excerpt`.
+    pub fn unmatched(&self) -> Vec<String> {
+        self.script
+            .lock()
+            .map(|script| script.unmatched.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for StubProviderServer {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+async fn handle(State(script): State<Arc<Mutex<Script>>>, uri: Uri, body: Bytes) -> Response {
+    let path = uri.path();
+    let body = String::from_utf8_lossy(&body);
+    let Ok(mut script) = script.lock() else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+
+    if path.starts_with("/provider/v1/search/") {
+        // Tavily and Exa post the query in the body, Brave sends it in the
+        // query string.
+        let query = format!("{}{body}", uri.query().unwrap_or_default());
+        let Some(search) = script.find_search(&query) else {
+            script.record_unmatched(path, &query);
+            return unmatched_response();
+        };
+        let results: Vec<Value> = search
+            .results
+            .iter()
+            .map(|result| {
+                json!({
+                    "title": result.title,
+                    "url": result.url,
+                    "content": result.content,
+                    "text": result.content,
+                    "description": result.content,
+                })
+            })
+            .collect();
+        return Json(json!({ "results": results, "web": { "results": results } })).into_response();
+    }
+
+    if path.ends_with("/messages") {
+        let Some(reply) = script.take_reply(&body) else {
+            script.record_unmatched(path, &body);
+            return unmatched_response();
+        };
+        let text = match reply {
+            Value::String(text) => text,
+            structured => structured.to_string(),
+        };
+        return Json(json!({
+            "id": format!("msg_{}", ulid::Ulid::new()),
+            "type": "message",
+            "role": "assistant",
+            "model": "evals-stub",
+            "content": [{ "type": "text", "text": text }],
+            "stop_reason": "end_turn",
+            "stop_sequence": null,
+            "usage": { "input_tokens": 0, "output_tokens": 0 },
+        }))
+        .into_response();
+    }
+
+    script.record_unmatched(path, &body);
+    unmatched_response()
+}
+
+fn unmatched_response() -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(json!({
+            "type": "error",
+            "error": { "type": "api_error", "message": "no scripted reply for this request" },
+        })),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reply(when: &str, reply: Value, repeat: bool) -> StubReply {
+        StubReply {
+            when: when.to_string(),
+            reply,
+            repeat,
+        }
+    }
+
+    #[test]
+    fn replies_are_matched_by_body_and_used_once_unless_repeated() {
+        let mut script = Script {
+            replies: vec![
+                (reply("routing", json!("first"), false), false),
+                (reply("routing", json!("second"), false), false),
+                (reply("summarize", json!({"impact": "Low"}), true), false),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(script.take_reply("a routing turn"), Some(json!("first")));
+        assert_eq!(script.take_reply("a routing turn"), Some(json!("second")));
+        assert_eq!(script.take_reply("a routing turn"), None);
+        for _ in 0..3 {
+            assert_eq!(
+                script.take_reply("please summarize"),
+                Some(json!({"impact": "Low"}))
+            );
+        }
+    }
+}
@@ -0,0 +1,120 @@
+//! JSON eval report.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EvalReport {
+    pub started_at: DateTime<Utc>,
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub scenarios: Vec<ScenarioReport>,
+}
+
+impl EvalReport {
+    pub fn new(started_at: DateTime<Utc>, scenarios: Vec<ScenarioReport>) -> Self {
+        let count =
+            |outcome: ScenarioOutcome| scenarios.iter().filter(|s| s.outcome == outcome).count();
+        Self {
+            started_at,
+            passed: count(ScenarioOutcome::Passed),
+            failed: count(ScenarioOutcome::Failed),
+            skipped: count(ScenarioOutcome::Skipped),
+            scenarios,
+        }
+    }
+
+    pub fn all_passed(&self) -> bool {
+        self.failed == 0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScenarioOutcome {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScenarioReport {
+    pub name: String,
+    pub outcome: ScenarioOutcome,
+    pub run_id: Option<String>,
+    pub duration_ms: u64,
+    pub event_count: usize,
+    pub failures: Vec<AssertionFailure>,
+    pub skip_reason: Option<String>,
+}
+
+impl ScenarioReport {
+    pub fn skipped(name: &str, reason: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            outcome: ScenarioOutcome::Skipped,
+            run_id: None,
+            duration_ms: 0,
+            event_count: 0,
+            failures: Vec::new(),
+            skip_reason: Some(reason.into()),
+        }
+    }
+}
+
+/// One failed check, with a unified expected/actual diff when there is
+/// something to compare.
+#[derive(Debug, Clone, Serialize)]
+pub struct AssertionFailure {
+    pub assertion: String,
+    pub message: String,
+    pub diff: Option<String>,
+}
+
+impl AssertionFailure {
+    pub fn new(assertion: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            assertion: assertion.into(),
+            message: message.into(),
+            diff: None,
+        }
+    }
+
+    pub fn with_diff(mut self, expected: &str, actual: &str) -> Self {
+        self.diff = Some(unified_diff(expected, actual));
+        self
+    }
+}
+
+pub fn unified_diff(expected: &str, actual: &str) -> String {
+    similar::TextDiff::from_lines(expected, actual)
+        .unified_diff()
+        .header("expected", "actual")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_counts_outcomes() {
+        let mut failed = ScenarioReport::skipped("b", "");
+        failed.outcome = ScenarioOutcome::Failed;
+        failed.skip_reason = None;
+        let report = EvalReport::new(
+            Utc::now(),
+            vec![ScenarioReport::skipped("a", "live mode disabled"), failed],
+        );
+        assert_eq!((report.passed, report.failed, report.skipped), (0, 1, 1));
+        assert!(!report.all_passed());
+    }
+
+    #[test]
+    fn diff_marks_changed_lines() {
+        let diff = unified_diff("a\nb\n", "a\nc\n");
+        assert!(diff.contains("-b"), "{diff}");
+        assert!(diff.contains("+c"), "{diff}");
+    }
+}
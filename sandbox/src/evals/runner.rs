@@ -0,0 +1,628 @@
+//! Runs scenarios against an in-process actor tree and checks assertions.
+
+use ractor::{Actor, ActorRef};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use shared_types::{
+    ConductorExecuteRequest, ConductorOutputMode, ConductorRunState, ConductorRunStateDelta, Event,
+    RunDeltaOutcome,
+};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+use crate::actors::conductor::ConductorMsg;
+use crate::actors::event_store::{EventStoreActor, EventStoreArguments, EventStoreMsg};
+use crate::app_state::AppState;
+
+use super::provider::{StubProviderServer, STUB_GATEWAY_TOKEN};
+use super::report::{AssertionFailure, EvalReport, ScenarioOutcome, ScenarioReport};
+use super::scenario::{Assertions, EventAssertion, Scenario, ScenarioMode};
+
+const EVAL_DESKTOP_ID: &str = "evals";
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// Events keep landing briefly after a run settles (writer patches,
+/// changesets); wait until the log stops growing for this long.
+const EVENT_SETTLE: Duration = Duration::from_millis(150);
+/// Catalog entry every callsite resolves to in stub mode. Its upstream URL is
+/// never contacted; the gateway override sends calls to the stub server.
+const STUB_MODEL_CATALOG: &str = r#"
+default_model = "EvalStub"
+
+[callsite_defaults]
+conductor = "EvalStub"
+writer = "EvalStub"
+researcher = "EvalStub"
+terminal = "EvalStub"
+summarizer = "EvalStub"
+watcher = "EvalStub"
+
+[models.EvalStub]
+name = "Eval stub"
+provider = "anthropic-compatible"
+model = "evals-stub"
+base_url = "https://provider.invalid/anthropic"
+api_key_env = "CHOIR_EVALS_STUB_API_KEY"
+"#;
+
+/// Env vars an eval run overrides; restored when the run ends.
+const EVAL_ENV_VARS: &[&str] = &[
+    "CHOIR_SANDBOX_ROOT",
+    "CHOIR_WRITER_ROOT_DIR",
+    "CHOIR_WORKSPACE_DIR",
+    "CHOIROS_DATA_DIR",
+    "CHOIR_MODEL_CONFIG_PATH",
+    "CHOIR_PROVIDER_GATEWAY_BASE_URL",
+    "CHOIR_PROVIDER_GATEWAY_TOKEN",
+];
+
+/// Serializes runs: the env overrides and the conductor's registered name
+/// are process-wide.
+static EVAL_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+#[derive(Debug, Clone, Default)]
+pub struct EvalOptions {
+    /// Only run scenarios whose name contains this substring.
+    pub filter: Option<String>,
+    /// Run `mode: live` scenarios; they are skipped otherwise.
+    pub live: bool,
+}
+
+pub async fn run_scenarios(scenarios: &[Scenario], options: &EvalOptions) -> EvalReport {
+    let started_at = chrono::Utc::now();
+    let mut reports = Vec::new();
+    for scenario in scenarios {
+        if let Some(filter) = options.filter.as_deref() {
+            if !scenario.name.contains(filter) {
+                continue;
+            }
+        }
+        if scenario.mode == ScenarioMode::Live && !options.live {
+            reports.push(ScenarioReport::skipped(
+                &scenario.name,
+                "live scenario; pass --live to run it",
+            ));
+            continue;
+        }
+        reports.push(run_scenario(scenario).await);
+    }
+    EvalReport::new(started_at, reports)
+}
+
+/// Process environment for one scenario run: every sandbox, writer and
+/// workspace path points into a temp dir, and in stub mode model and search
+/// calls go to a scripted provider gateway.
+struct EvalEnv {
+    root: TempDir,
+    provider: Option<StubProviderServer>,
+    saved: Vec<(&'static str, Option<String>)>,
+}
+
+impl EvalEnv {
+    async fn enter(scenario: &Scenario) -> Result<Self, String> {
+        let root = tempfile::tempdir().map_err(|e| format!("temp dir: {e}"))?;
+        let mut env = Self {
+            root,
+            provider: None,
+            saved: EVAL_ENV_VARS
+                .iter()
+                .map(|key| (*key, std::env::var(key).ok()))
+                .collect(),
+        };
+
+        let root_path = env.root.path().to_path_buf();
+        std::env::set_var("CHOIR_SANDBOX_ROOT", &root_path);
+        std::env::set_var("CHOIR_WRITER_ROOT_DIR", &root_path);
+        std::env::set_var("CHOIR_WORKSPACE_DIR", &root_path);
+        std::env::set_var("CHOIROS_DATA_DIR", root_path.join("data"));
+
+        if scenario.mode == ScenarioMode::Stub {
+            let catalog = root_path.join("data/model-catalog.toml");
+            std::fs::create_dir_all(root_path.join("data"))
+                .map_err(|e| format!("data dir: {e}"))?;
+            std::fs::write(&catalog, STUB_MODEL_CATALOG)
+                .map_err(|e| format!("model catalog: {e}"))?;
+            let provider = StubProviderServer::start(&scenario.stub)
+                .await
+                .map_err(|e| format!("stub provider gateway: {e}"))?;
+            std::env::set_var("CHOIR_MODEL_CONFIG_PATH", &catalog);
+            std::env::set_var("CHOIR_PROVIDER_GATEWAY_BASE_URL", provider.base_url());
+            std::env::set_var("CHOIR_PROVIDER_GATEWAY_TOKEN", STUB_GATEWAY_TOKEN);
+            env.provider = Some(provider);
+        }
+        Ok(env)
+    }
+
+    /// Sandbox, writer and workspace root; the terminal's working dir.
+    fn root(&self) -> &Path {
+        self.root.path()
+    }
+
+    /// Provider requests no scripted reply matched.
+    fn unmatched_requests(&self) -> Vec<String> {
+        self.provider
+            .as_ref()
+            .map(StubProviderServer::unmatched)
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for EvalEnv {
+    fn drop(&mut self) {
+        for (key, value) in self.saved.drain(..) {
+            match value {
+                Some(value) => std::env::set_var(key, value),
+                None => std::env::remove_var(key),
+            }
+        }
+    }
+}
+
+/// Actors backing one scenario run.
+struct EvalTree {
+    event_store: ActorRef<EventStoreMsg>,
+    conductor: ActorRef<ConductorMsg>,
+    /// Roots stopped (with their linked children) once the run is checked.
+    roots: Vec<ractor::ActorCell>,
+}
+
+impl EvalTree {
+    /// Spawn the production tree. Must run after [`EvalEnv::enter`], since
+    /// actors read their roots and model catalog from env at startup.
+    async fn spawn() -> Result<Self, String> {
+        let (event_store, _) = Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+            .await
+            .map_err(|e| format!("event store spawn failed: {e}"))?;
+        let app_state = AppState::new(event_store.clone());
+        let supervisor = app_state.ensure_supervisor().await?;
+        let conductor = app_state.ensure_conductor().await?;
+        Ok(Self {
+            roots: vec![supervisor.get_cell(), event_store.get_cell()],
+            event_store,
+            conductor,
+        })
+    }
+
+    async fn shutdown(self) {
+        for root in self.roots {
+            let _ = root.stop_and_wait(None, Some(Duration::from_secs(5))).await;
+        }
+    }
+}
+
+pub async fn run_scenario(scenario: &Scenario) -> ScenarioReport {
+    let started = Instant::now();
+    let mut report = ScenarioReport {
+        name: scenario.name.clone(),
+        outcome: ScenarioOutcome::Failed,
+        run_id: None,
+        duration_ms: 0,
+        event_count: 0,
+        failures: Vec::new(),
+        skip_reason: None,
+    };
+
+    let _guard = EVAL_LOCK.lock().await;
+    let env = match EvalEnv::enter(scenario).await {
+        Ok(env) => env,
+        Err(err) => {
+            report.failures.push(AssertionFailure::new("env", err));
+            return report;
+        }
+    };
+    if let Err(err) = write_fixtures(scenario, env.root()).await {
+        report.failures.push(AssertionFailure::new("fixtures", err));
+        return report;
+    }
+
+    match EvalTree::spawn().await {
+        Ok(tree) => {
+            match execute(&tree, scenario).await {
+                Ok((submitted, run, events)) => {
+                    report.run_id = Some(run.run_id.clone());
+                    report.event_count = events.len();
+                    let document = tokio::fs::read_to_string(env.root().join(&run.document_path))
+                        .await
+                        .ok();
+                    report.failures =
+                        check_assertions(&scenario.assert, &run, &events, document.as_deref());
+                    report
+                        .failures
+                        .extend(check_state_deltas(&submitted, &run, &events));
+                }
+                Err(err) => report.failures.push(AssertionFailure::new("run", err)),
+            }
+            tree.shutdown().await;
+        }
+        Err(err) => report
+            .failures
+            .push(AssertionFailure::new("actor_tree", err)),
+    }
+
+    report.failures.extend(
+        env.unmatched_requests().into_iter().map(|request| {
+            AssertionFailure::new("provider", format!("no scripted reply: {request}"))
+        }),
+    );
+    if report.failures.is_empty() {
+        report.outcome = ScenarioOutcome::Passed;
+    }
+    report.duration_ms = started.elapsed().as_millis() as u64;
+    report
+}
+
+async fn write_fixtures(scenario: &Scenario, root: &Path) -> Result<(), String> {
+    for fixture in &scenario.fixtures {
+        let path = root.join(&fixture.path);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("{}: {e}", fixture.path))?;
+        }
+        tokio::fs::write(&path, &fixture.content)
+            .await
+            .map_err(|e| format!("{}: {e}", fixture.path))?;
+    }
+    Ok(())
+}
+
+/// Run the scenario objective; returns the run as submitted, the run once
+/// its events settled, and the event log.
+async fn execute(
+    tree: &EvalTree,
+    scenario: &Scenario,
+) -> Result<(ConductorRunState, ConductorRunState, Vec<Event>), String> {
+    let request = ConductorExecuteRequest {
+        objective: scenario.objective.clone(),
+        desktop_id: EVAL_DESKTOP_ID.to_string(),
+        output_mode: scenario.output_mode.unwrap_or(ConductorOutputMode::Auto),
+        hints: None,
+        contract: None,
+    };
+    let run = ractor::call!(tree.conductor, |reply| ConductorMsg::ExecuteTask {
+        request,
+        reply
+    })
+    .map_err(|e| format!("execute rpc failed: {e}"))?
+    .map_err(|e| format!("execute failed: {e}"))?;
+    let submitted = run.clone();
+
+    let deadline = Instant::now() + Duration::from_millis(scenario.timeout_ms);
+    let run = loop {
+        let state = ractor::call!(tree.conductor, |reply| ConductorMsg::GetRunState {
+            run_id: run.run_id.clone(),
+            reply
+        })
+        .map_err(|e| format!("run state rpc failed: {e}"))?
+        .ok_or_else(|| format!("run {} disappeared", run.run_id))?;
+        if is_settled(&state) {
+            break state;
+        }
+        if Instant::now() >= deadline {
+            return Err(format!(
+                "run {} still {:?} after {}ms",
+                state.run_id, state.status, scenario.timeout_ms
+            ));
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    };
+
+    let mut events = recent_events(&tree.event_store).await?;
+    loop {
+        tokio::time::sleep(EVENT_SETTLE).await;
+        let latest = recent_events(&tree.event_store).await?;
+        if latest.len() == events.len() {
+            break;
+        }
+        events = latest;
+    }
+    let run = ractor::call!(tree.conductor, |reply| ConductorMsg::GetRunState {
+        run_id: run.run_id.clone(),
+        reply
+    })
+    .map_err(|e| format!("run state rpc failed: {e}"))?
+    .unwrap_or(run);
+    Ok((submitted, run, events))
+}
+
+fn is_settled(run: &ConductorRunState) -> bool {
+    use shared_types::ConductorRunStatus::*;
+    matches!(run.status, Completed | Failed | Blocked)
+}
+
+async fn recent_events(event_store: &ActorRef<EventStoreMsg>) -> Result<Vec<Event>, String> {
+    ractor::call!(event_store, |reply| EventStoreMsg::GetRecentEvents {
+        since_seq: 0,
+        limit: 10_000,
+        event_type_prefix: None,
+        actor_id: None,
+        user_id: None,
+        reply,
+    })
+    .map_err(|e| format!("event query rpc failed: {e}"))?
+    .map_err(|e| format!("event query failed: {e}"))
+}
+
+fn check_assertions(
+    assertions: &Assertions,
+    run: &ConductorRunState,
+    events: &[Event],
+    document: Option<&str>,
+) -> Vec<AssertionFailure> {
+    let mut failures = Vec::new();
+
+    // Every typed event must still decode, asserted on or not.
+    let mut decoded = Vec::with_capacity(events.len());
+    for event in events {
+        match decode_typed_payload(&event.event_type, &event.payload) {
+            Some(Ok(payload)) => decoded.push((event.event_type.as_str(), payload)),
+            Some(Err(err)) => failures.push(AssertionFailure::new(
+                format!("schema:{}", event.event_type),
+                format!("seq {} payload no longer decodes: {err}", event.seq),
+            )),
+            None => decoded.push((event.event_type.as_str(), event.payload.clone())),
+        }
+    }
+
+    for assertion in &assertions.events {
+        if let Some(failure) = check_event_assertion(assertion, &decoded) {
+            failures.push(failure);
+        }
+    }
+
+    if let Some(expected) = &assertions.document {
+        match document {
+            None => failures.push(AssertionFailure::new(
+                "document",
+                format!("{} was not written", run.document_path),
+            )),
+            Some(content) => {
+                for needle in &expected.contains {
+                    if !content.contains(needle.as_str()) {
+                        failures.push(
+                            AssertionFailure::new(
+                                "document.contains",
+                                format!("missing {needle:?}"),
+                            )
+                            .with_diff(needle, content),
+                        );
+                    }
+                }
+                for needle in &expected.not_contains {
+                    if content.contains(needle.as_str()) {
+                        failures.push(AssertionFailure::new(
+                            "document.not_contains",
+                            format!("unexpected {needle:?}"),
+                        ));
+                    }
+                }
+                if let Some(equals) = &expected.equals {
+                    if equals.trim_end() != content.trim_end() {
+                        failures.push(
+                            AssertionFailure::new("document.equals", "content differs")
+                                .with_diff(equals, content),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(expected) = &assertions.completion {
+        if run.status != expected.status {
+            failures.push(AssertionFailure::new(
+                "completion.status",
+                format!("expected {:?}, got {:?}", expected.status, run.status),
+            ));
+        }
+        if !expected.fields.is_null() {
+            let topic = match run.status {
+                shared_types::ConductorRunStatus::Completed => {
+                    shared_types::EVENT_TOPIC_CONDUCTOR_TASK_COMPLETED
+                }
+                _ => shared_types::EVENT_TOPIC_CONDUCTOR_TASK_FAILED,
+            };
+            match decoded
+                .iter()
+                .rev()
+                .find(|(event_type, _)| *event_type == topic)
+            {
+                None => failures.push(AssertionFailure::new(
+                    "completion.fields",
+                    format!("no {topic} event"),
+                )),
+                Some((_, payload)) => {
+                    if !json_contains(payload, &expected.fields) {
+                        failures.push(
+                            AssertionFailure::new(
+                                "completion.fields",
+                                format!("{topic} payload does not match"),
+                            )
+                            .with_diff(&pretty(&expected.fields), &pretty(payload)),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    failures
+}
+
+/// The run's `conductor.run.state_delta` stream, applied to the run as
+/// submitted, must reproduce its final state.
+fn check_state_deltas(
+    submitted: &ConductorRunState,
+    run: &ConductorRunState,
+    events: &[Event],
+) -> Option<AssertionFailure> {
+    let mut replayed = submitted.clone();
+    for event in events
+        .iter()
+        .filter(|event| event.event_type == shared_types::EVENT_TOPIC_CONDUCTOR_RUN_STATE_DELTA)
+    {
+        // Undecodable deltas are already reported as schema failures.
+        let Ok(delta) = serde_json::from_value::<ConductorRunStateDelta>(event.payload.clone())
+        else {
+            continue;
+        };
+        if delta.run_id != run.run_id || delta.revision <= submitted.revision {
+            continue;
+        }
+        if replayed.apply_delta(&delta) != RunDeltaOutcome::Applied {
+            return Some(AssertionFailure::new(
+                "state_deltas",
+                format!(
+                    "expected revision {}, got {}",
+                    replayed.revision + 1,
+                    delta.revision
+                ),
+            ));
+        }
+    }
+
+    let expected = pretty(&serde_json::to_value(run).unwrap_or_default());
+    let actual = pretty(&serde_json::to_value(&replayed).unwrap_or_default());
+    (expected != actual).then(|| {
+        AssertionFailure::new("state_deltas", "replayed run state differs")
+            .with_diff(&expected, &actual)
+    })
+}
+
+fn check_event_assertion(
+    assertion: &EventAssertion,
+    decoded: &[(&str, Value)],
+) -> Option<AssertionFailure> {
+    let label = format!("events:{}", assertion.event_type);
+    let of_type: Vec<&Value> = decoded
+        .iter()
+        .filter(|(event_type, _)| *event_type == assertion.event_type)
+        .map(|(_, payload)| payload)
+        .collect();
+    let matching = of_type
+        .iter()
+        .filter(|payload| assertion.payload.is_null() || json_contains(payload, &assertion.payload))
+        .count();
+
+    let failure = if assertion.absent {
+        (matching > 0).then(|| format!("expected no matching events, found {matching}"))
+    } else if let Some(count) = assertion.count {
+        (matching != count).then(|| format!("expected {count} matching events, found {matching}"))
+    } else {
+        (matching == 0).then(|| {
+            format!(
+                "no matching event among {} {} events",
+                of_type.len(),
+                assertion.event_type
+            )
+        })
+    }?;
+
+    let mut failure = AssertionFailure::new(label, failure);
+    if !assertion.absent && !assertion.payload.is_null() {
+        if let Some(closest) = of_type.last() {
+            failure = failure.with_diff(&pretty(&assertion.payload), &pretty(closest));
+        }
+    }
+    Some(failure)
+}
+
+/// Decode a payload into its shared-types struct and back, so assertions see
+/// exactly the typed fields. `None` for topics without a typed payload.
+fn decode_typed_payload(event_type: &str, payload: &Value) -> Option<Result<Value, String>> {
+    use shared_types as st;
+
+    fn roundtrip<T: DeserializeOwned + Serialize>(payload: &Value) -> Result<Value, String> {
+        let typed: T = serde_json::from_value(payload.clone()).map_err(|e| e.to_string())?;
+        serde_json::to_value(typed).map_err(|e| e.to_string())
+    }
+
+    let decoded: fn(&Value) -> Result<Value, String> = match event_type {
+        st::EVENT_TOPIC_CONDUCTOR_TASK_STARTED => roundtrip::<st::ConductorTaskStartedPayload>,
+        st::EVENT_TOPIC_CONDUCTOR_TASK_PROGRESS => roundtrip::<st::ConductorTaskProgressPayload>,
+        st::EVENT_TOPIC_CONDUCTOR_WORKER_CALL => roundtrip::<st::ConductorWorkerCallPayload>,
+        st::EVENT_TOPIC_CONDUCTOR_WORKER_RESULT => roundtrip::<st::ConductorWorkerResultPayload>,
+        st::EVENT_TOPIC_CONDUCTOR_TASK_COMPLETED => roundtrip::<st::ConductorTaskCompletedPayload>,
+        st::EVENT_TOPIC_CONDUCTOR_TASK_FAILED => roundtrip::<st::ConductorTaskFailedPayload>,
+        st::EVENT_TOPIC_CONDUCTOR_RUN_STATE_DELTA => roundtrip::<st::ConductorRunStateDelta>,
+        st::EVENT_TOPIC_RESEARCH_TASK_STARTED => roundtrip::<st::ResearchTaskStartedPayload>,
+        st::EVENT_TOPIC_RESEARCH_TASK_COMPLETED => roundtrip::<st::ResearchTaskCompletedPayload>,
+        st::EVENT_TOPIC_RESEARCH_TASK_FAILED => roundtrip::<st::ResearchTaskFailedPayload>,
+        st::EVENT_TOPIC_WORKSPACE_QUOTA_WARNING => roundtrip::<st::WorkspaceQuotaWarningPayload>,
+        st::EVENT_TOPIC_WRITER_RUN_STARTED
+        | st::EVENT_TOPIC_WRITER_RUN_PROGRESS
+        | st::EVENT_TOPIC_WRITER_RUN_PATCH
+        | st::EVENT_TOPIC_WRITER_RUN_CHANGESET
+        | st::EVENT_TOPIC_WRITER_RUN_STATUS
+        | st::EVENT_TOPIC_WRITER_RUN_FAILED => {
+            // Writer events are stored as a `WriterRunEvent` minus its tag.
+            let mut tagged = payload.clone();
+            if let Some(object) = tagged.as_object_mut() {
+                object.insert("event_type".to_string(), Value::from(event_type));
+            }
+            return Some(roundtrip::<st::WriterRunEvent>(&tagged).map(|mut value| {
+                if let Some(object) = value.as_object_mut() {
+                    object.remove("event_type");
+                }
+                value
+            }));
+        }
+        _ => return None,
+    };
+    Some(decoded(payload))
+}
+
+/// Whether `actual` contains every field of `expected`; arrays match
+/// element-wise by position.
+fn json_contains(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::Object(actual), Value::Object(expected)) => expected.iter().all(|(key, value)| {
+            actual
+                .get(key)
+                .is_some_and(|actual| json_contains(actual, value))
+        }),
+        (Value::Array(actual), Value::Array(expected)) => {
+            actual.len() >= expected.len()
+                && expected
+                    .iter()
+                    .zip(actual)
+                    .all(|(expected, actual)| json_contains(actual, expected))
+        }
+        (actual, expected) => actual == expected,
+    }
+}
+
+fn pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_default() + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn json_contains_matches_nested_subsets() {
+        let actual = json!({"run_id": "r", "toast": {"tone": "success", "message": "done"}});
+        assert!(json_contains(
+            &actual,
+            &json!({"toast": {"tone": "success"}})
+        ));
+        assert!(!json_contains(
+            &actual,
+            &json!({"toast": {"tone": "error"}})
+        ));
+        assert!(!json_contains(&actual, &json!({"missing": null})));
+    }
+
+    #[test]
+    fn drifted_typed_payload_fails_to_decode() {
+        let payload = json!({"run_id": "r", "worker_type": "writer", "timestamp": "t"});
+        let decoded =
+            decode_typed_payload(shared_types::EVENT_TOPIC_CONDUCTOR_WORKER_CALL, &payload)
+                .expect("typed topic");
+        assert!(decoded.unwrap_err().contains("worker_objective"));
+        assert!(decode_typed_payload("conductor.finding", &payload).is_none());
+    }
+}
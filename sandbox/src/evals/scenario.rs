@@ -0,0 +1,291 @@
+//! YAML scenario definitions.
+
+use serde::Deserialize;
+use std::path::{Component, Path};
+
+use shared_types::{ConductorOutputMode, ConductorRunStatus};
+
+const DEFAULT_TIMEOUT_MS: u64 = 30_000;
+
+/// One eval scenario, loaded from a `*.yaml` file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub objective: String,
+    #[serde(default)]
+    pub mode: ScenarioMode,
+    #[serde(default)]
+    pub output_mode: Option<ConductorOutputMode>,
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Files written under the scenario's workspace dir before the run.
+    #[serde(default)]
+    pub fixtures: Vec<FixtureFile>,
+    #[serde(default)]
+    pub stub: StubProviders,
+    #[serde(default)]
+    pub assert: Assertions,
+}
+
+fn default_timeout_ms() -> u64 {
+    DEFAULT_TIMEOUT_MS
+}
+
+/// Where model and worker responses come from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScenarioMode {
+    /// Provider calls answered by a scripted local gateway.
+    #[default]
+    Stub,
+    /// The production gateways and workers; needs provider credentials.
+    Live,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FixtureFile {
+    /// Path relative to the workspace dir.
+    pub path: String,
+    pub content: String,
+}
+
+/// Scripted provider gateway responses for `mode: stub`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StubProviders {
+    /// Model replies, matched to requests by `when`.
+    #[serde(default)]
+    pub replies: Vec<StubReply>,
+    /// Web search results, matched to queries by `when`.
+    #[serde(default)]
+    pub search: Vec<StubSearch>,
+}
+
+/// One model completion. The real BAML parser reads it, so a reply that no
+/// longer fits the function's output type fails the run.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StubReply {
+    /// Text the request (prompt and messages) must contain.
+    pub when: String,
+    /// Completion text; structured values are sent as JSON.
+    pub reply: serde_json::Value,
+    /// Answer every matching request instead of only the first.
+    #[serde(default)]
+    pub repeat: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StubSearch {
+    /// Text the search query must contain.
+    pub when: String,
+    pub results: Vec<StubSearchResult>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StubSearchResult {
+    pub title: String,
+    pub url: String,
+    #[serde(default)]
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Assertions {
+    #[serde(default)]
+    pub events: Vec<EventAssertion>,
+    pub document: Option<DocumentAssertion>,
+    pub completion: Option<CompletionAssertion>,
+}
+
+/// Matches emitted events by type and a payload subset.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EventAssertion {
+    pub event_type: String,
+    /// Fields every matching event must carry; compared after the payload is
+    /// decoded into its typed struct.
+    #[serde(default)]
+    pub payload: serde_json::Value,
+    /// Exact number of matching events; at least one when unset.
+    pub count: Option<usize>,
+    /// Assert that no event matches.
+    #[serde(default)]
+    pub absent: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DocumentAssertion {
+    #[serde(default)]
+    pub contains: Vec<String>,
+    #[serde(default)]
+    pub not_contains: Vec<String>,
+    pub equals: Option<String>,
+}
+
+/// Final run status plus a subset of the `conductor.task.completed` (or
+/// `conductor.task.failed`) payload.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CompletionAssertion {
+    pub status: ConductorRunStatus,
+    #[serde(default)]
+    pub fields: serde_json::Value,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ScenarioError {
+    #[error("failed to read {0}: {1}")]
+    Io(String, std::io::Error),
+    #[error("invalid scenario {0}: {1}")]
+    Parse(String, serde_yaml::Error),
+    #[error("invalid scenario {0}: {1}")]
+    Invalid(String, String),
+}
+
+impl Scenario {
+    pub fn from_yaml(source: &str, yaml: &str) -> Result<Self, ScenarioError> {
+        let scenario: Scenario = serde_yaml::from_str(yaml)
+            .map_err(|err| ScenarioError::Parse(source.to_string(), err))?;
+        scenario
+            .validate()
+            .map_err(|err| ScenarioError::Invalid(source.to_string(), err))?;
+        Ok(scenario)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("name is empty".to_string());
+        }
+        if self.objective.trim().is_empty() {
+            return Err("objective is empty".to_string());
+        }
+        for fixture in &self.fixtures {
+            let path = Path::new(&fixture.path);
+            if fixture.path.is_empty()
+                || !path
+                    .components()
+                    .all(|component| matches!(component, Component::Normal(_)))
+            {
+                return Err(format!(
+                    "fixture path must be relative to the workspace: {}",
+                    fixture.path
+                ));
+            }
+        }
+        if self.mode == ScenarioMode::Stub && self.stub.replies.is_empty() {
+            return Err("stub scenarios need stub.replies".to_string());
+        }
+        if let Some(reply) = self.stub.replies.iter().find(|r| r.when.trim().is_empty()) {
+            return Err(format!("stub reply has an empty `when`: {}", reply.reply));
+        }
+        Ok(())
+    }
+}
+
+/// Load every `*.yaml` scenario in `dir`, sorted by file name.
+pub fn load_dir(dir: &Path) -> Result<Vec<Scenario>, ScenarioError> {
+    let display = dir.display().to_string();
+    let mut paths = Vec::new();
+    for entry in std::fs::read_dir(dir).map_err(|err| ScenarioError::Io(display.clone(), err))? {
+        let path = entry
+            .map_err(|err| ScenarioError::Io(display.clone(), err))?
+            .path();
+        if matches!(
+            path.extension().and_then(|ext| ext.to_str()),
+            Some("yaml" | "yml")
+        ) {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|path| {
+            let source = path.display().to_string();
+            let yaml = std::fs::read_to_string(path)
+                .map_err(|err| ScenarioError::Io(source.clone(), err))?;
+            Scenario::from_yaml(&source, &yaml)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn structured_replies_parse_as_json_values() {
+        let scenario = Scenario::from_yaml(
+            "inline",
+            r#"
+name: replies
+objective: Check the build
+stub:
+  replies:
+    - when: single routing decision
+      reply:
+        tool_calls:
+          - tool_name: finished
+            tool_args:
+              summary: '{"dispatch_capabilities": ["writer"]}'
+        message: ""
+    - when: Marginalia observer
+      repeat: true
+      reply: plain text
+  search:
+    - when: WAL
+      results:
+        - title: Write-Ahead Logging
+          url: https://www.sqlite.org/wal.html
+"#,
+        )
+        .expect("scenario parses");
+
+        assert_eq!(scenario.mode, ScenarioMode::Stub);
+        assert_eq!(scenario.timeout_ms, DEFAULT_TIMEOUT_MS);
+        let routing = &scenario.stub.replies[0].reply;
+        assert_eq!(routing["tool_calls"][0]["tool_name"], "finished");
+        assert!(scenario.stub.replies[1].repeat);
+        assert_eq!(scenario.stub.search[0].results[0].content, "");
+    }
+
+    #[test]
+    fn stub_scenarios_need_replies() {
+        let err = Scenario::from_yaml(
+            "inline",
+            r#"
+name: silent
+objective: Write it up
+"#,
+        )
+        .expect_err("a stub scenario without replies must fail");
+        assert!(err.to_string().contains("stub.replies"), "{err}");
+    }
+
+    #[test]
+    fn fixture_paths_must_stay_in_the_workspace() {
+        let err = Scenario::from_yaml(
+            "inline",
+            r#"
+name: escape
+objective: Read it
+mode: live
+fixtures:
+  - path: ../outside.txt
+    content: nope
+"#,
+        )
+        .expect_err("parent components are rejected");
+        assert!(matches!(err, ScenarioError::Invalid(_, _)));
+    }
+}
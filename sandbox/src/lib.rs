@@ -8,16 +8,27 @@
 #![allow(clippy::result_large_err)]
 #![allow(clippy::type_complexity)]
 
+pub mod activity;
 pub mod actors;
 pub mod api;
 pub mod app_state;
 #[allow(clippy::all)]
 pub mod baml_client;
+pub mod chunking;
+pub mod cors;
+pub mod disk_usage;
+pub mod evals;
 pub mod markdown;
 pub mod observability;
 pub mod paths;
+pub mod preferences;
+pub mod projections;
 pub mod runtime_env;
+pub mod secret_scan;
 pub mod self_directed_dispatch;
+#[cfg(feature = "testkit")]
+pub mod testkit;
 pub mod tools;
+pub mod ttl_cache;
 
 pub mod supervisor;
@@ -1,15 +1,16 @@
-use axum::http::{header, HeaderValue, Method};
 use ractor::Actor;
 use sandbox::actors::event_store::{
-    AppendEvent, EventStoreActor, EventStoreArguments, EventStoreMsg,
+    compact, configure_encryption, import_events, reencrypt_all, AppendEvent, CompactionPolicy,
+    EventStoreActor, EventStoreArguments, EventStoreMsg,
 };
+use sandbox::actors::telemetry_gate::TelemetryPolicy;
 use sandbox::api;
 use sandbox::app_state::AppState;
+use sandbox::cors::CorsConfig;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::net::TcpListener;
-use tower_http::cors::{AllowOrigin, CorsLayer};
 use tower_http::services::{ServeDir, ServeFile};
 
 const FORBIDDEN_PROVIDER_KEY_ENVS: &[&str] = &[
@@ -114,6 +115,94 @@ fn should_enforce_keyless_policy() -> bool {
         || std::env::var("CHOIR_SANDBOX_USER_ID").is_ok()
 }
 
+/// How often the event store drops expired telemetry (default 6 hours).
+fn compaction_interval_from_env() -> std::time::Duration {
+    let secs = std::env::var("CHOIR_EVENT_COMPACTION_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .unwrap_or(6 * 60 * 60);
+    std::time::Duration::from_secs(secs)
+}
+
+/// Import the NDJSON event log named by `CHOIR_IMPORT_EVENTS`, if set, e.g.
+/// one exported from another sandbox by `/api/events/export`. Events already
+/// stored are skipped, so the variable can stay set across restarts.
+async fn import_events_from_env(
+    event_store: &ractor::ActorRef<EventStoreMsg>,
+) -> std::io::Result<()> {
+    let Some(path) = std::env::var("CHOIR_IMPORT_EVENTS")
+        .ok()
+        .filter(|path| !path.trim().is_empty())
+    else {
+        return Ok(());
+    };
+    let file = tokio::fs::File::open(&path).await?;
+    match import_events(event_store, tokio::io::BufReader::new(file)).await {
+        Ok(Ok(report)) => {
+            tracing::info!(
+                path = %path,
+                imported = report.imported,
+                duplicates = report.duplicates,
+                "Imported event log"
+            );
+            Ok(())
+        }
+        Ok(Err(e)) => Err(std::io::Error::other(format!(
+            "Failed to import event log {path}: {e}"
+        ))),
+        Err(e) => Err(std::io::Error::other(format!(
+            "Event store RPC failed importing {path}: {e}"
+        ))),
+    }
+}
+
+/// Compact the event store every `interval`, logging each pass as a
+/// `system.compaction.completed` telemetry event.
+fn spawn_event_compaction(
+    event_store: ractor::ActorRef<EventStoreMsg>,
+    interval: std::time::Duration,
+) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        // The first tick fires immediately; leave startup alone.
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            let report = match compact(&event_store).await {
+                Ok(Ok(report)) => report,
+                Ok(Err(e)) => {
+                    tracing::warn!(error = %e, "Event store compaction failed");
+                    continue;
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "Event store compaction RPC failed");
+                    continue;
+                }
+            };
+            tracing::info!(
+                expired = report.expired,
+                over_limit = report.over_limit,
+                "Event store compaction finished"
+            );
+            let event = AppendEvent {
+                event_type: "system.compaction.completed".to_string(),
+                payload: serde_json::json!({
+                    "removed": report.removed(),
+                    "expired": report.expired,
+                    "over_limit": report.over_limit,
+                    "search_entries": report.search_entries,
+                    "_meta": { "lane": "telemetry" },
+                }),
+                actor_id: "system".to_string(),
+                user_id: "system".to_string(),
+                idempotency_key: None,
+            };
+            let _ = event_store.send_message(EventStoreMsg::AppendAsync { event });
+        }
+    });
+}
+
 fn frontend_dist_from_env() -> String {
     if let Ok(path) = std::env::var("FRONTEND_DIST") {
         return path;
@@ -194,22 +283,58 @@ async fn main() -> std::io::Result<()> {
     // Create EventStoreActor (foundation of the system).
     let db_path_str = db_path.to_str().expect("Invalid database path");
     tracing::info!("Connecting to database: {}", db_path_str);
-    let (event_store, _handle) = Actor::spawn(
-        None,
-        EventStoreActor,
-        EventStoreArguments::File(db_path_str.to_string()),
-    )
-    .await
-    .expect("Failed to create event store");
+    let mut event_store_args = EventStoreArguments::File(db_path_str.to_string())
+        .with_compaction(CompactionPolicy::from_env())
+        .with_telemetry(TelemetryPolicy::from_env());
+    // Low-resource deployments can skip the event search index.
+    if env_var_truthy("CHOIR_EVENT_SEARCH") == Some(false) {
+        tracing::info!("Event search index disabled");
+        event_store_args = event_store_args.without_event_search();
+    }
+    let (event_store, _handle) = Actor::spawn(None, EventStoreActor, event_store_args)
+        .await
+        .expect("Failed to create event store");
 
     tracing::info!("EventStoreActor started");
 
+    // Configure payload encryption before anything else appends.
+    let encryption = sandbox::actors::event_encryption::EventEncryption::from_env();
+    match configure_encryption(&event_store, encryption).await {
+        Ok(Ok(status)) => match status.current_key_id {
+            Some(key_id) => {
+                tracing::info!(key_id = %key_id, "Event payload encryption enabled");
+                // Rows sealed under a previous key are re-sealed in the background.
+                let event_store = event_store.clone();
+                tokio::spawn(async move {
+                    match reencrypt_all(&event_store, 200).await {
+                        Ok(done) if done.reencrypted > 0 || done.locked > 0 => tracing::info!(
+                            reencrypted = done.reencrypted,
+                            locked = done.locked,
+                            "Event payload key rotation finished"
+                        ),
+                        Ok(_) => {}
+                        Err(e) => tracing::warn!(error = %e, "Event payload key rotation failed"),
+                    }
+                });
+            }
+            None => tracing::info!("Event payload encryption disabled (no key configured)"),
+        },
+        Ok(Err(e)) => tracing::error!(error = %e, "Failed to configure event payload encryption"),
+        Err(e) => tracing::error!(error = %e, "Failed to configure event payload encryption"),
+    }
+
+    // After encryption is configured, so imported payloads are sealed.
+    import_events_from_env(&event_store).await?;
+
+    spawn_event_compaction(event_store.clone(), compaction_interval_from_env());
+
     // Log startup event
     let startup_event = AppendEvent {
         event_type: "system.startup".to_string(),
         payload: serde_json::json!({"version": "0.1.0"}),
         actor_id: "system".to_string(),
         user_id: "system".to_string(),
+        idempotency_key: None,
     };
 
     let event_result = ractor::call!(event_store, |reply| EventStoreMsg::Append {
@@ -225,13 +350,44 @@ async fn main() -> std::io::Result<()> {
 
     // Create WebSocket sessions state
     let ws_sessions: api::websocket::WsSessions = Arc::new(tokio::sync::Mutex::new(HashMap::new()));
-    api::websocket::spawn_writer_run_event_forwarder(event_store.clone(), ws_sessions.clone());
+    let patch_order = api::websocket::new_patch_order();
+    api::websocket::spawn_writer_run_event_forwarder(
+        event_store.clone(),
+        ws_sessions.clone(),
+        patch_order.clone(),
+    );
+    api::websocket::spawn_conductor_run_event_forwarder(event_store.clone(), ws_sessions.clone());
+    api::websocket::spawn_provider_gateway_event_forwarder(
+        event_store.clone(),
+        ws_sessions.clone(),
+    );
 
     let app_state = Arc::new(AppState::new(event_store.clone()));
     let _ = app_state
         .ensure_supervisor()
         .await
         .expect("Failed to spawn ApplicationSupervisor");
+    app_state
+        .projections()
+        .spawn_catch_up_loop(std::time::Duration::from_secs(2));
+    if let Err(e) = app_state.start_search_indexer().await {
+        tracing::error!("Failed to start search indexer: {}", e);
+    }
+    if let Err(e) = app_state.restore_activity().await {
+        tracing::error!("Failed to restore session activity: {}", e);
+    }
+    if let Some(base_url) = sandbox::runtime_env::provider_gateway_base_url() {
+        tracing::info!(base_url = %base_url, "Monitoring provider gateway health");
+        app_state.spawn_provider_gateway_monitor(base_url, std::time::Duration::from_secs(10));
+    }
+    if let Some(quota_bytes) = app_state.disk_usage().quota_bytes() {
+        tracing::info!(
+            quota_bytes,
+            root = %app_state.disk_usage().root().display(),
+            "Enforcing workspace disk quota"
+        );
+        app_state.spawn_disk_usage_monitor(sandbox::disk_usage::DEFAULT_SCAN_TTL);
+    }
 
     // Watcher runtime is intentionally disabled during harness simplification.
     // Keep watcher code available for future reintroduction after control-flow refactor.
@@ -244,37 +400,16 @@ async fn main() -> std::io::Result<()> {
 
     tracing::info!("Starting HTTP server on http://0.0.0.0:{port}");
 
-    // Configure CORS to allow known UI origins
-    let allowed_origins = [
-        "http://13.218.213.227",
-        "http://choir-ip.com",
-        "https://choir-ip.com",
-        "http://localhost:3000",
-        "http://127.0.0.1:3000",
-        "http://100.91.73.16:3000",
-        // Hypervisor reverse-proxy origin
-        "http://localhost:9090",
-        "http://127.0.0.1:9090",
-    ]
-    .iter()
-    .map(|origin| HeaderValue::from_str(origin).expect("Invalid CORS origin"))
-    .collect::<Vec<_>>();
-
-    let cors = CorsLayer::new()
-        .allow_origin(AllowOrigin::list(allowed_origins))
-        .allow_methods([
-            Method::GET,
-            Method::POST,
-            Method::DELETE,
-            Method::PATCH,
-            Method::OPTIONS,
-        ])
-        .allow_headers([header::CONTENT_TYPE, header::ACCEPT, header::AUTHORIZATION])
-        .max_age(std::time::Duration::from_secs(3600));
+    let cors_config = CorsConfig::from_env().map_err(|e| {
+        tracing::error!(error = %e, "Invalid CORS configuration");
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, e)
+    })?;
+    tracing::info!(origins = %cors_config, "CORS allowed origins");
+    let cors = cors_config.layer();
 
     let api_state = api::ApiState {
-        app_state,
-        ws_sessions,
+        patch_order,
+        ..api::ApiState::new(app_state, ws_sessions)
     };
     let frontend_dist = frontend_dist_from_env();
     let frontend_index = format!("{frontend_dist}/index.html");
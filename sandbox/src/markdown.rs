@@ -13,9 +13,13 @@
 //! - Blockquotes
 //! - Headers
 //! - HTML sanitization for security
+//! - Rendering `.qwy` block trees to Markdown ([`render_qwy`])
+//! - Applying writer patch ops to plain text ([`apply_patch_ops`])
 
 use pulldown_cmark::{html, Options, Parser};
 use regex::Regex;
+use shared_types::{BlockId, BlockNode, BlockType, CitationRecord, PatchOp, QwyDocument};
+use std::collections::HashSet;
 
 /// Error type for markdown operations
 #[derive(Debug, thiserror::Error)]
@@ -287,6 +291,352 @@ pub fn char_count(markdown: &str) -> usize {
     extract_plain_text(markdown).chars().count()
 }
 
+/// Render a `.qwy` document to Markdown.
+///
+/// Blocks are emitted depth-first in `root_block_ids` and `children` order.
+/// Heading level follows heading nesting. A paragraph starting with a list
+/// marker (`- `, `* `, `+ `, `1. `) is a list item and one starting with
+/// `> ` a blockquote; their children render indented under them, and the
+/// children of any other block follow it at the same depth. Citation anchors
+/// become footnote references numbered by first use, with their definitions
+/// drawn from `citation_registry` at the end.
+pub fn render_qwy(doc: &QwyDocument) -> String {
+    let mut renderer = QwyRenderer {
+        doc,
+        visited: HashSet::new(),
+        footnotes: Vec::new(),
+    };
+    let mut out = join_qwy_chunks(&renderer.render_blocks(&doc.root_block_ids, 0));
+
+    if !renderer.footnotes.is_empty() {
+        let definitions: Vec<String> = renderer
+            .footnotes
+            .iter()
+            .enumerate()
+            .map(|(index, citation_id)| {
+                format!(
+                    "[^{}]: {}",
+                    index + 1,
+                    footnote_text(doc.citation_registry.get(citation_id), citation_id)
+                )
+            })
+            .collect();
+        if !out.is_empty() {
+            out.push_str("\n\n");
+        }
+        out.push_str(&definitions.join("\n"));
+    }
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out
+}
+
+/// A rendered block and whether it is a list item, which joins its list
+/// item siblings without a blank line.
+struct QwyChunk {
+    text: String,
+    list_item: bool,
+}
+
+struct QwyRenderer<'a> {
+    doc: &'a QwyDocument,
+    /// Guards against a malformed tree visiting a block twice.
+    visited: HashSet<&'a str>,
+    /// Citation ids in order of first reference.
+    footnotes: Vec<String>,
+}
+
+impl<'a> QwyRenderer<'a> {
+    fn render_blocks(&mut self, ids: &'a [BlockId], heading_depth: usize) -> Vec<QwyChunk> {
+        let mut chunks = Vec::new();
+        for id in ids {
+            let Some(block) = self.doc.blocks.get(&id.0) else {
+                continue;
+            };
+            if !self.visited.insert(id.0.as_str()) {
+                continue;
+            }
+            self.render_block(block, heading_depth, &mut chunks);
+        }
+        chunks
+    }
+
+    fn render_block(
+        &mut self,
+        block: &'a BlockNode,
+        heading_depth: usize,
+        out: &mut Vec<QwyChunk>,
+    ) {
+        let content = block.content.trim_end();
+        match block.block_type {
+            BlockType::Heading => {
+                let level = (heading_depth + 1).min(6);
+                out.push(QwyChunk {
+                    text: format!("{} {}", "#".repeat(level), content.trim()),
+                    list_item: false,
+                });
+                out.extend(self.render_blocks(&block.children, heading_depth + 1));
+            }
+            BlockType::Paragraph => {
+                if let Some(marker) = list_marker(content) {
+                    let children =
+                        join_qwy_chunks(&self.render_blocks(&block.children, heading_depth));
+                    let mut text = content.to_string();
+                    if !children.is_empty() {
+                        text.push('\n');
+                        text.push_str(&prefix_lines(
+                            &children,
+                            &" ".repeat(marker),
+                            &" ".repeat(marker),
+                        ));
+                    }
+                    out.push(QwyChunk {
+                        text,
+                        list_item: true,
+                    });
+                } else if content.starts_with('>') {
+                    let children =
+                        join_qwy_chunks(&self.render_blocks(&block.children, heading_depth));
+                    let mut text = content.to_string();
+                    if !children.is_empty() {
+                        text.push_str("\n>\n");
+                        text.push_str(&prefix_lines(&children, "> ", ">"));
+                    }
+                    out.push(QwyChunk {
+                        text,
+                        list_item: false,
+                    });
+                } else {
+                    out.push(QwyChunk {
+                        text: content.to_string(),
+                        list_item: false,
+                    });
+                    out.extend(self.render_blocks(&block.children, heading_depth));
+                }
+            }
+            BlockType::Code => {
+                let longest_run = longest_backtick_run(content);
+                let fence = "`".repeat(longest_run.max(2) + 1);
+                out.push(QwyChunk {
+                    text: format!("{fence}\n{content}\n{fence}"),
+                    list_item: false,
+                });
+                out.extend(self.render_blocks(&block.children, heading_depth));
+            }
+            BlockType::Embed => {
+                // The source is the target when set; otherwise content is the URL.
+                let label = content.trim();
+                let target = block
+                    .provenance
+                    .had_primary_source
+                    .as_deref()
+                    .map_or(label, str::trim);
+                let text = if is_image_url(target) {
+                    format!("![{label}]({target})")
+                } else {
+                    format!("[{label}]({target})")
+                };
+                out.push(QwyChunk {
+                    text,
+                    list_item: false,
+                });
+                out.extend(self.render_blocks(&block.children, heading_depth));
+            }
+            BlockType::CitationAnchor => {
+                let citation_id = content.trim().to_string();
+                let number = match self.footnotes.iter().position(|id| *id == citation_id) {
+                    Some(index) => index + 1,
+                    None => {
+                        self.footnotes.push(citation_id);
+                        self.footnotes.len()
+                    }
+                };
+                out.push(QwyChunk {
+                    text: format!("[^{number}]"),
+                    list_item: false,
+                });
+                out.extend(self.render_blocks(&block.children, heading_depth));
+            }
+        }
+    }
+}
+
+fn join_qwy_chunks(chunks: &[QwyChunk]) -> String {
+    let mut out = String::new();
+    for (index, chunk) in chunks.iter().enumerate() {
+        if index > 0 {
+            let tight = chunk.list_item && chunks[index - 1].list_item;
+            out.push_str(if tight { "\n" } else { "\n\n" });
+        }
+        out.push_str(&chunk.text);
+    }
+    out
+}
+
+/// Width of the list marker and its following space, if `content` is a
+/// list item.
+fn list_marker(content: &str) -> Option<usize> {
+    if ["- ", "* ", "+ "].iter().any(|m| content.starts_with(m)) {
+        return Some(2);
+    }
+    let digits = content.chars().take_while(char::is_ascii_digit).count();
+    (digits > 0 && content[digits..].starts_with(". ")).then_some(digits + 2)
+}
+
+/// Prefix every line, using `blank_prefix` (without trailing spaces) on
+/// empty lines.
+fn prefix_lines(text: &str, prefix: &str, blank_prefix: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                blank_prefix.trim_end().to_string()
+            } else {
+                format!("{prefix}{line}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn longest_backtick_run(text: &str) -> usize {
+    let mut longest = 0;
+    let mut run = 0;
+    for c in text.chars() {
+        run = if c == '`' { run + 1 } else { 0 };
+        longest = longest.max(run);
+    }
+    longest
+}
+
+fn is_image_url(url: &str) -> bool {
+    let path = url
+        .split(['?', '#'])
+        .next()
+        .unwrap_or(url)
+        .to_ascii_lowercase();
+    [".png", ".jpg", ".jpeg", ".gif", ".webp", ".svg"]
+        .iter()
+        .any(|ext| path.ends_with(ext))
+}
+
+fn footnote_text(record: Option<&CitationRecord>, citation_id: &str) -> String {
+    let Some(record) = record else {
+        return format!("{citation_id} (not in citation registry)");
+    };
+    match record.excerpt.as_deref().map(str::trim) {
+        Some(excerpt) if !excerpt.is_empty() => format!("{} — \"{excerpt}\"", record.cited_id),
+        _ => record.cited_id.clone(),
+    }
+}
+
+/// Error applying a [`PatchOp`] sequence with [`apply_patch_ops`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PatchApplyError {
+    #[error("{op} of bytes {start}..{end} is out of bounds for a {len}-byte document")]
+    OutOfBounds {
+        op: &'static str,
+        start: u64,
+        end: u64,
+        len: usize,
+    },
+
+    #[error("{op} at byte {pos} splits a multibyte character")]
+    NotCharBoundary { op: &'static str, pos: usize },
+
+    #[error("{op} at byte {pos} is behind the cursor at byte {cursor}")]
+    OutOfOrder {
+        op: &'static str,
+        pos: usize,
+        cursor: usize,
+    },
+
+    #[error("ops cover bytes 0..{cursor} of a {len}-byte document")]
+    IncompleteCoverage { cursor: usize, len: usize },
+}
+
+/// Apply `ops` to `base` in order and return the patched text.
+///
+/// Positions are UTF-8 byte offsets into the document as it stands when the
+/// op applies; each op leaves a cursor at the end of the text it touched,
+/// and `Retain` advances that cursor. Ops that use `Retain` describe a walk
+/// over the whole document, so they must not move behind the cursor and must
+/// end with it at the end of the document. Unlike the writer's char-based
+/// application, spans running past the end are rejected rather than
+/// clamped.
+pub fn apply_patch_ops(
+    base: &str,
+    ops: &[PatchOp],
+) -> std::result::Result<String, PatchApplyError> {
+    let mut doc = base.to_string();
+    let mut cursor = 0usize;
+    let sequential = ops.iter().any(|op| matches!(op, PatchOp::Retain { .. }));
+
+    for op in ops {
+        let (name, pos, len) = match op {
+            PatchOp::Insert { pos, .. } => ("insert", *pos, 0),
+            PatchOp::Delete { pos, len } => ("delete", *pos, *len),
+            PatchOp::Replace { pos, len, .. } => ("replace", *pos, *len),
+            PatchOp::Retain { len } => ("retain", cursor as u64, *len),
+        };
+        let (start, end) = byte_span(&doc, name, pos, len)?;
+        if sequential && start < cursor {
+            return Err(PatchApplyError::OutOfOrder {
+                op: name,
+                pos: start,
+                cursor,
+            });
+        }
+        cursor = match op {
+            PatchOp::Insert { text, .. } | PatchOp::Replace { text, .. } => {
+                doc.replace_range(start..end, text);
+                start + text.len()
+            }
+            PatchOp::Delete { .. } => {
+                doc.replace_range(start..end, "");
+                start
+            }
+            PatchOp::Retain { .. } => end,
+        };
+    }
+
+    if sequential && cursor != doc.len() {
+        return Err(PatchApplyError::IncompleteCoverage {
+            cursor,
+            len: doc.len(),
+        });
+    }
+    Ok(doc)
+}
+
+/// Resolve the byte span `pos..pos + len` of `doc`, checking that it is in
+/// bounds and starts and ends on char boundaries.
+fn byte_span(
+    doc: &str,
+    op: &'static str,
+    pos: u64,
+    len: u64,
+) -> std::result::Result<(usize, usize), PatchApplyError> {
+    let out_of_bounds = || PatchApplyError::OutOfBounds {
+        op,
+        start: pos,
+        end: pos.saturating_add(len),
+        len: doc.len(),
+    };
+    let start = usize::try_from(pos).map_err(|_| out_of_bounds())?;
+    let end = usize::try_from(len)
+        .ok()
+        .and_then(|len| start.checked_add(len))
+        .filter(|end| *end <= doc.len())
+        .ok_or_else(out_of_bounds)?;
+    for boundary in [start, end] {
+        if !doc.is_char_boundary(boundary) {
+            return Err(PatchApplyError::NotCharBoundary { op, pos: boundary });
+        }
+    }
+    Ok((start, end))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -317,4 +667,197 @@ mod tests {
         let markdown = "Hello **world**! This is `code`.";
         assert_eq!(word_count(markdown), 5);
     }
+
+    fn block(id: &str, block_type: BlockType, content: &str, children: &[&str]) -> BlockNode {
+        BlockNode {
+            block_id: BlockId(id.to_string()),
+            block_type,
+            parent_id: None,
+            children: children.iter().map(|c| BlockId(c.to_string())).collect(),
+            content: content.to_string(),
+            chunk_hash: None,
+            provenance: shared_types::ProvenanceEnvelope {
+                was_generated_by: None,
+                was_attributed_to: None,
+                was_revision_of: None,
+                had_primary_source: None,
+                conductor_run_id: None,
+                loop_id: None,
+                private: false,
+            },
+            annotations: Vec::new(),
+        }
+    }
+
+    fn citation(id: &str, cited_id: &str, excerpt: Option<&str>) -> CitationRecord {
+        CitationRecord {
+            citation_id: id.to_string(),
+            cited_id: cited_id.to_string(),
+            cited_kind: "external_url".to_string(),
+            citing_run_id: "run-1".to_string(),
+            citing_loop_id: "loop-1".to_string(),
+            citing_actor: "researcher".to_string(),
+            cite_kind: shared_types::CitationKind::InlineReference,
+            confidence: 0.9,
+            excerpt: excerpt.map(str::to_string),
+            rationale: String::new(),
+            status: shared_types::CitationStatus::Confirmed,
+            proposed_by: "researcher".to_string(),
+            confirmed_by: None,
+            confirmed_at: None,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_render_qwy_follows_tree_order() {
+        let mut chart = block("img", BlockType::Embed, "Chart", &[]);
+        chart.provenance.had_primary_source = Some("https://example.com/chart.png".to_string());
+        let blocks = vec![
+            block("h1", BlockType::Heading, "Report", &["intro", "h2", "tail"]),
+            block("intro", BlockType::Paragraph, "Opening text.", &["cite-a"]),
+            block("cite-a", BlockType::CitationAnchor, "c1", &[]),
+            block("h2", BlockType::Heading, "Findings", &["li-1", "li-2"]),
+            block("li-1", BlockType::Paragraph, "- first", &["li-1a"]),
+            block("li-1a", BlockType::Paragraph, "1. nested", &[]),
+            block("li-2", BlockType::Paragraph, "- second", &[]),
+            block(
+                "tail",
+                BlockType::Paragraph,
+                "> quoted",
+                &["code", "cite-b"],
+            ),
+            block("code", BlockType::Code, "let x = `a`;\n```", &[]),
+            block("cite-b", BlockType::CitationAnchor, "c2", &[]),
+            block("link", BlockType::Embed, "https://example.com", &[]),
+            chart,
+            block(
+                "cite-c",
+                BlockType::CitationAnchor,
+                "c1",
+                &["missing-child"],
+            ),
+        ];
+        let doc = QwyDocument {
+            header: shared_types::QwyDocumentHeader {
+                document_id: "doc-1".to_string(),
+                schema_version: 1,
+                created_at: chrono::Utc::now(),
+                created_by: "user".to_string(),
+                conductor_run_id: None,
+            },
+            root_block_ids: ["h1", "link", "img", "cite-c"]
+                .iter()
+                .map(|id| BlockId(id.to_string()))
+                .collect(),
+            blocks: blocks
+                .into_iter()
+                .map(|block| (block.block_id.0.clone(), block))
+                .collect(),
+            patch_log: Vec::new(),
+            citation_registry: [citation("c1", "https://example.com/a", Some("key quote"))]
+                .into_iter()
+                .map(|record| (record.citation_id.clone(), record))
+                .collect(),
+            version_index: Vec::new(),
+        };
+
+        let expected = "\
+# Report
+
+Opening text.
+
+[^1]
+
+## Findings
+
+- first
+  1. nested
+- second
+
+> quoted
+>
+> ````
+> let x = `a`;
+> ```
+> ````
+>
+> [^2]
+
+[https://example.com](https://example.com)
+
+![Chart](https://example.com/chart.png)
+
+[^1]
+
+[^1]: https://example.com/a — \"key quote\"
+[^2]: c2 (not in citation registry)
+";
+        assert_eq!(render_qwy(&doc), expected);
+        assert_eq!(render_qwy(&doc), render_qwy(&doc));
+    }
+
+    #[test]
+    fn test_apply_patch_ops_insert_at_end() {
+        let ops = [PatchOp::Insert {
+            pos: 5,
+            text: " world".to_string(),
+        }];
+        assert_eq!(apply_patch_ops("hello", &ops).unwrap(), "hello world");
+    }
+
+    #[test]
+    fn test_apply_patch_ops_delete_past_end_is_an_error() {
+        let ops = [PatchOp::Delete { pos: 3, len: 5 }];
+        assert_eq!(
+            apply_patch_ops("hello", &ops),
+            Err(PatchApplyError::OutOfBounds {
+                op: "delete",
+                start: 3,
+                end: 8,
+                len: 5,
+            })
+        );
+    }
+
+    #[test]
+    fn test_apply_patch_ops_replace_changes_length() {
+        // "é" is two bytes; the replaced span and the cursor are byte-based.
+        let ops = [
+            PatchOp::Retain { len: 5 },
+            PatchOp::Replace {
+                pos: 5,
+                len: 5,
+                text: "everyone".to_string(),
+            },
+            PatchOp::Retain { len: 1 },
+        ];
+        assert_eq!(
+            apply_patch_ops("hé, world!", &ops).unwrap(),
+            "hé, everyone!"
+        );
+    }
+
+    #[test]
+    fn test_apply_patch_ops_rejects_split_chars_and_partial_coverage() {
+        assert_eq!(
+            apply_patch_ops("héllo", &[PatchOp::Delete { pos: 2, len: 1 }]),
+            Err(PatchApplyError::NotCharBoundary {
+                op: "delete",
+                pos: 2
+            })
+        );
+        assert_eq!(
+            apply_patch_ops("hello", &[PatchOp::Retain { len: 3 }]),
+            Err(PatchApplyError::IncompleteCoverage { cursor: 3, len: 5 })
+        );
+        let backwards = [
+            PatchOp::Retain { len: 3 },
+            PatchOp::Delete { pos: 1, len: 1 },
+        ];
+        assert!(matches!(
+            apply_patch_ops("hello", &backwards),
+            Err(PatchApplyError::OutOfOrder { cursor: 3, .. })
+        ));
+    }
 }
@@ -9,6 +9,7 @@
 //! - `llm.call.started` - Call initiated
 //! - `llm.call.completed` - Call succeeded
 //! - `llm.call.failed` - Call errored
+//! - `model.context.trace` - Prompt composition of a started call
 //!
 //! # Bounded Payload Policy
 //!
@@ -29,6 +30,7 @@ pub const EVENT_TOPIC_LLM_CALL_COMPLETED: &str = shared_types::EVENT_TOPIC_LLM_C
 pub const EVENT_TOPIC_LLM_CALL_FAILED: &str = shared_types::EVENT_TOPIC_LLM_CALL_FAILED;
 pub const EVENT_TOPIC_WORKER_TOOL_CALL: &str = shared_types::EVENT_TOPIC_WORKER_TOOL_CALL;
 pub const EVENT_TOPIC_WORKER_TOOL_RESULT: &str = shared_types::EVENT_TOPIC_WORKER_TOOL_RESULT;
+pub const EVENT_MODEL_CONTEXT_TRACE: &str = shared_types::EVENT_MODEL_CONTEXT_TRACE;
 
 pub const MAX_SYSTEM_CONTEXT_BYTES: usize = 4 * 1024;
 pub const MAX_INPUT_BYTES: usize = 16 * 1024;
@@ -43,6 +45,15 @@ pub const SENSITIVE_KEYS: &[&str] = &[
     "credential",
 ];
 
+/// Usage counters that match `token` but carry no secret.
+pub const TOKEN_COUNT_KEYS: &[&str] = &[
+    "input_tokens",
+    "output_tokens",
+    "cached_input_tokens",
+    "total_tokens",
+    "max_tokens",
+];
+
 #[derive(Debug, Clone, Default)]
 pub struct LlmCallScope {
     pub run_id: Option<String>,
@@ -50,6 +61,8 @@ pub struct LlmCallScope {
     pub call_id: Option<String>,
     pub session_id: Option<String>,
     pub thread_id: Option<String>,
+    /// Time the call spent queued in the LLM call scheduler.
+    pub queue_wait_ms: Option<u64>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -204,16 +217,14 @@ impl LlmTraceEmitter {
             if let Some(ref call_id) = scope.call_id {
                 obj.insert("call_id".to_string(), serde_json::json!(call_id));
             }
-            if scope.session_id.is_some() || scope.thread_id.is_some() {
-                let mut scope_obj = serde_json::Map::new();
-                if let Some(ref session_id) = scope.session_id {
-                    scope_obj.insert("session_id".to_string(), serde_json::json!(session_id));
-                }
-                if let Some(ref thread_id) = scope.thread_id {
-                    scope_obj.insert("thread_id".to_string(), serde_json::json!(thread_id));
-                }
-                obj.insert("scope".to_string(), serde_json::Value::Object(scope_obj));
+            if let Some(queue_wait_ms) = scope.queue_wait_ms {
+                obj.insert(
+                    "queue_wait_ms".to_string(),
+                    serde_json::json!(queue_wait_ms),
+                );
             }
+            shared_types::EventScope::new(scope.session_id.clone(), scope.thread_id.clone())
+                .insert_into(obj);
         }
 
         let event = AppendEvent {
@@ -221,6 +232,7 @@ impl LlmTraceEmitter {
             payload,
             actor_id: actor_id.to_string(),
             user_id: "system".to_string(),
+            idempotency_key: None,
         };
 
         let _ = self
@@ -309,16 +321,8 @@ impl LlmTraceEmitter {
             if let Some(ref call_id) = ctx.call_id {
                 obj.insert("call_id".to_string(), serde_json::json!(call_id));
             }
-            if ctx.session_id.is_some() || ctx.thread_id.is_some() {
-                let mut scope_obj = serde_json::Map::new();
-                if let Some(ref session_id) = ctx.session_id {
-                    scope_obj.insert("session_id".to_string(), serde_json::json!(session_id));
-                }
-                if let Some(ref thread_id) = ctx.thread_id {
-                    scope_obj.insert("thread_id".to_string(), serde_json::json!(thread_id));
-                }
-                obj.insert("scope".to_string(), serde_json::Value::Object(scope_obj));
-            }
+            shared_types::EventScope::new(ctx.session_id.clone(), ctx.thread_id.clone())
+                .insert_into(obj);
         }
 
         let event = AppendEvent {
@@ -326,6 +330,7 @@ impl LlmTraceEmitter {
             payload,
             actor_id: ctx.actor_id.clone(),
             user_id: "system".to_string(),
+            idempotency_key: None,
         };
 
         let _ = self
@@ -400,16 +405,8 @@ impl LlmTraceEmitter {
             if let Some(ref call_id) = ctx.call_id {
                 obj.insert("call_id".to_string(), serde_json::json!(call_id));
             }
-            if ctx.session_id.is_some() || ctx.thread_id.is_some() {
-                let mut scope_obj = serde_json::Map::new();
-                if let Some(ref session_id) = ctx.session_id {
-                    scope_obj.insert("session_id".to_string(), serde_json::json!(session_id));
-                }
-                if let Some(ref thread_id) = ctx.thread_id {
-                    scope_obj.insert("thread_id".to_string(), serde_json::json!(thread_id));
-                }
-                obj.insert("scope".to_string(), serde_json::Value::Object(scope_obj));
-            }
+            shared_types::EventScope::new(ctx.session_id.clone(), ctx.thread_id.clone())
+                .insert_into(obj);
         }
 
         let event = AppendEvent {
@@ -417,6 +414,42 @@ impl LlmTraceEmitter {
             payload,
             actor_id: ctx.actor_id.clone(),
             user_id: "system".to_string(),
+            idempotency_key: None,
+        };
+
+        let _ = self
+            .event_store
+            .send_message(EventStoreMsg::AppendAsync { event });
+    }
+
+    /// Record what went into the prompt of the call started as `ctx`.
+    pub fn record_context(
+        &self,
+        ctx: &LlmCallContext,
+        model_used: &str,
+        items: Vec<shared_types::ContextTraceItem>,
+        context_window: Option<u32>,
+        document_context: Option<shared_types::DocumentContextMode>,
+    ) {
+        let trace = shared_types::ModelContextTracePayload {
+            run_id: ctx.run_id.clone(),
+            thread_id: ctx.thread_id.clone(),
+            context_window,
+            document_context,
+            ..shared_types::ModelContextTracePayload::new(ctx.trace_id.clone(), model_used, items)
+        };
+        let mut payload = serde_json::to_value(&trace).unwrap_or_default();
+        if let Some(obj) = payload.as_object_mut() {
+            shared_types::EventScope::new(ctx.session_id.clone(), ctx.thread_id.clone())
+                .insert_into(obj);
+        }
+
+        let event = AppendEvent {
+            event_type: EVENT_MODEL_CONTEXT_TRACE.to_string(),
+            payload,
+            actor_id: ctx.actor_id.clone(),
+            user_id: "system".to_string(),
+            idempotency_key: None,
         };
 
         let _ = self
@@ -548,6 +581,7 @@ impl LlmTraceEmitter {
             payload,
             actor_id: actor_id.to_string(),
             user_id: "system".to_string(),
+            idempotency_key: None,
         };
         let _ = self
             .event_store
@@ -589,16 +623,13 @@ fn inject_scope_fields(
     if let Some(call_id) = call_id {
         obj.insert("call_id".to_string(), serde_json::json!(call_id));
     }
-    if session_id.is_some() || thread_id.is_some() {
-        let mut scope_obj = serde_json::Map::new();
-        if let Some(session_id) = session_id {
-            scope_obj.insert("session_id".to_string(), serde_json::json!(session_id));
-        }
-        if let Some(thread_id) = thread_id {
-            scope_obj.insert("thread_id".to_string(), serde_json::json!(thread_id));
-        }
-        obj.insert("scope".to_string(), serde_json::Value::Object(scope_obj));
-    }
+    shared_types::EventScope::new(session_id.clone(), thread_id.clone()).insert_into(obj);
+}
+
+/// Rough token count for prompt text: one token per four characters.
+pub fn estimate_tokens(text: &str) -> u32 {
+    let chars = text.chars().count();
+    u32::try_from(chars.div_ceil(4)).unwrap_or(u32::MAX)
 }
 
 pub fn truncate_to_bytes(text: &str, max_bytes: usize) -> (String, bool, usize) {
@@ -625,7 +656,9 @@ pub fn redact_sensitive_keys(json: &mut serde_json::Value) {
         serde_json::Value::Object(map) => {
             for (key, value) in map.iter_mut() {
                 let key_lower = key.to_lowercase();
-                if SENSITIVE_KEYS.iter().any(|k| key_lower.contains(k)) {
+                if SENSITIVE_KEYS.iter().any(|k| key_lower.contains(k))
+                    && !TOKEN_COUNT_KEYS.contains(&key_lower.as_str())
+                {
                     *value = serde_json::Value::String("[REDACTED]".to_string());
                 } else {
                     redact_sensitive_keys(value);
@@ -766,6 +799,7 @@ mod tests {
         assert_eq!(json["model"], "gpt-4");
         assert_eq!(json["temperature"], 0.7);
         assert_eq!(json["messages"][0]["content"], "Hello");
+        assert_eq!(json["max_tokens"], 100);
     }
 
     #[test]
@@ -42,3 +42,123 @@ pub fn workspace_dir() -> PathBuf {
         .map(PathBuf::from)
         .unwrap_or_else(|_| sandbox_root())
 }
+
+/// Why a document path was refused by [`normalize_document_path`].
+#[derive(Debug, thiserror::Error, Clone, PartialEq, Eq)]
+pub enum DocumentPathError {
+    #[error("document path is empty")]
+    Empty,
+    #[error("document path must be relative: {0}")]
+    Absolute(String),
+    #[error("document path escapes its root: {0}")]
+    Traversal(String),
+    #[error("document path contains a null byte")]
+    NullByte,
+}
+
+/// Canonical key for a document path relative to the writer root.
+///
+/// Document paths are compared as strings across run state, writer
+/// supervision, events and memory records, so `./draft.md`, `draft.md` and
+/// `.\draft.md` must map to one key. Backslashes become `/`, empty and `.`
+/// segments are dropped, and absolute paths or any `..` segment are refused.
+pub fn normalize_document_path(raw: &str) -> Result<String, DocumentPathError> {
+    let raw = raw.trim();
+    if raw.contains('\0') {
+        return Err(DocumentPathError::NullByte);
+    }
+    let unified = raw.replace('\\', "/");
+    let has_drive = unified
+        .split('/')
+        .next()
+        .is_some_and(|first| first.len() == 2 && first.ends_with(':'));
+    if unified.starts_with('/') || has_drive {
+        return Err(DocumentPathError::Absolute(raw.to_string()));
+    }
+
+    let mut segments = Vec::new();
+    for segment in unified.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => return Err(DocumentPathError::Traversal(raw.to_string())),
+            segment => segments.push(segment),
+        }
+    }
+    if segments.is_empty() {
+        return Err(DocumentPathError::Empty);
+    }
+    Ok(segments.join("/"))
+}
+
+/// Whether two document paths name the same document. Paths that do not
+/// normalize never match.
+pub fn same_document_path(a: &str, b: &str) -> bool {
+    match (normalize_document_path(a), normalize_document_path(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equivalent_document_paths_normalize_to_one_key() {
+        for raw in [
+            "conductor/runs/run_1/draft.md",
+            "./conductor/runs/run_1/draft.md",
+            "conductor//runs/./run_1/draft.md",
+            "conductor\\runs\\run_1\\draft.md",
+            " .\\conductor/runs/run_1/draft.md/ ",
+        ] {
+            assert_eq!(
+                normalize_document_path(raw).as_deref(),
+                Ok("conductor/runs/run_1/draft.md"),
+                "{raw:?}"
+            );
+        }
+        assert_eq!(
+            normalize_document_path("./draft.md").as_deref(),
+            Ok("draft.md")
+        );
+        assert!(same_document_path("./draft.md", "draft.md"));
+        assert!(!same_document_path("draft.md", "drafts.md"));
+    }
+
+    #[test]
+    fn test_traversal_and_absolute_document_paths_are_rejected() {
+        for raw in [
+            "../draft.md",
+            "conductor/runs/../../etc/passwd",
+            "conductor\\..\\..\\secret.md",
+            "runs/run_1/..",
+        ] {
+            assert!(
+                matches!(
+                    normalize_document_path(raw),
+                    Err(DocumentPathError::Traversal(_))
+                ),
+                "{raw:?}"
+            );
+        }
+        for raw in ["/etc/passwd", "\\\\server\\share\\draft.md", "C:\\draft.md"] {
+            assert!(
+                matches!(
+                    normalize_document_path(raw),
+                    Err(DocumentPathError::Absolute(_))
+                ),
+                "{raw:?}"
+            );
+        }
+        assert_eq!(
+            normalize_document_path(" ./ "),
+            Err(DocumentPathError::Empty)
+        );
+        assert_eq!(
+            normalize_document_path("draft\0.md"),
+            Err(DocumentPathError::NullByte)
+        );
+        assert!(!same_document_path("../draft.md", "../draft.md"));
+    }
+}
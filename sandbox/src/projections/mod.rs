@@ -0,0 +1,514 @@
+//! ProjectionManager - rebuildable read models derived from the event log.
+//!
+//! ADR-0001: EventStore is the canonical source of truth. Projections are
+//! disposable read models (feeds, usage, run listings, thread registry) that
+//! can always be reconstructed by replaying committed events in seq order.
+//!
+//! Each registered projection has a stored cursor (`projection_cursors` table)
+//! so normal operation only applies events newer than the cursor. A rebuild
+//! resets the projection, rewinds the cursor to zero and replays the full log,
+//! emitting `projection.rebuild.*` progress events. The cursor is persisted
+//! after every batch, so an interrupted rebuild resumes where it stopped
+//! instead of resetting again.
+//!
+//! Delivery is at-least-once: after a crash mid-batch the events since the
+//! last persisted cursor are applied again, so apply functions should be
+//! idempotent or tolerate replays.
+
+use futures_util::future::BoxFuture;
+use ractor::ActorRef;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, RwLock};
+
+pub mod search;
+
+use crate::actors::event_store::{
+    get_latest_seq, get_projection_cursor, get_recent_events, set_projection_cursor, AppendEvent,
+    EventStoreMsg, ProjectionCursor,
+};
+
+pub const PROJECTION_STATUS_ACTIVE: &str = "active";
+pub const PROJECTION_STATUS_REBUILDING: &str = "rebuilding";
+
+const DEFAULT_BATCH_SIZE: i64 = 500;
+
+type ApplyFn =
+    Arc<dyn Fn(shared_types::Event) -> BoxFuture<'static, Result<(), String>> + Send + Sync>;
+type ResetFn = Arc<dyn Fn() -> BoxFuture<'static, Result<(), String>> + Send + Sync>;
+
+/// A named read model fed from the event log.
+#[derive(Clone)]
+pub struct Projection {
+    name: String,
+    topics: Vec<String>,
+    apply: ApplyFn,
+    reset: ResetFn,
+}
+
+impl Projection {
+    /// Create a projection.
+    ///
+    /// `topics` are exact event types or `prefix.*` patterns; an empty list
+    /// handles every event. `apply` is called for each matching event in seq
+    /// order and `reset` clears all projected state before a rebuild.
+    pub fn new<A, AFut, R, RFut>(
+        name: impl Into<String>,
+        topics: impl IntoIterator<Item = impl Into<String>>,
+        apply: A,
+        reset: R,
+    ) -> Self
+    where
+        A: Fn(shared_types::Event) -> AFut + Send + Sync + 'static,
+        AFut: Future<Output = Result<(), String>> + Send + 'static,
+        R: Fn() -> RFut + Send + Sync + 'static,
+        RFut: Future<Output = Result<(), String>> + Send + 'static,
+    {
+        Self {
+            name: name.into(),
+            topics: topics.into_iter().map(Into::into).collect(),
+            apply: Arc::new(move |event| Box::pin(apply(event))),
+            reset: Arc::new(move || Box::pin(reset())),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn topics(&self) -> &[String] {
+        &self.topics
+    }
+
+    /// Whether this projection consumes events of `event_type`.
+    pub fn handles(&self, event_type: &str) -> bool {
+        self.topics.is_empty()
+            || self
+                .topics
+                .iter()
+                .any(|topic| match topic.strip_suffix('*') {
+                    Some(prefix) => event_type.starts_with(prefix),
+                    None => topic == event_type,
+                })
+    }
+}
+
+/// Errors surfaced by ProjectionManager operations.
+#[derive(Debug, thiserror::Error, Clone)]
+pub enum ProjectionError {
+    #[error("projection not found: {0}")]
+    NotFound(String),
+
+    #[error("projection already registered: {0}")]
+    AlreadyRegistered(String),
+
+    #[error("event store error: {0}")]
+    Store(String),
+
+    #[error("projection '{name}' failed at seq {seq}: {message}")]
+    Apply {
+        name: String,
+        seq: i64,
+        message: String,
+    },
+
+    #[error("projection '{name}' reset failed: {message}")]
+    Reset { name: String, message: String },
+}
+
+/// Outcome of a rebuild request.
+#[derive(Debug, Clone, Serialize)]
+pub struct RebuildReport {
+    pub name: String,
+    /// True when the rebuild continued an interrupted one instead of resetting.
+    pub resumed: bool,
+    pub target_seq: i64,
+    pub cursor_seq: i64,
+    pub events_applied: u64,
+}
+
+/// Current replay position of a projection.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectionStatus {
+    pub name: String,
+    pub topics: Vec<String>,
+    pub cursor_seq: i64,
+    pub status: String,
+    pub updated_at: Option<String>,
+}
+
+struct RegisteredProjection {
+    projection: Projection,
+    /// Serializes catch-up and rebuild for a single projection.
+    run_lock: Mutex<()>,
+}
+
+struct ProjectionManagerInner {
+    event_store: ActorRef<EventStoreMsg>,
+    projections: RwLock<HashMap<String, Arc<RegisteredProjection>>>,
+    batch_size: i64,
+}
+
+/// Registry of projections plus the replay machinery that keeps them current.
+#[derive(Clone)]
+pub struct ProjectionManager {
+    inner: Arc<ProjectionManagerInner>,
+}
+
+impl ProjectionManager {
+    pub fn new(event_store: ActorRef<EventStoreMsg>) -> Self {
+        Self::with_batch_size(event_store, DEFAULT_BATCH_SIZE)
+    }
+
+    /// Create a manager that replays `batch_size` events per store round-trip.
+    pub fn with_batch_size(event_store: ActorRef<EventStoreMsg>, batch_size: i64) -> Self {
+        Self::build(event_store, batch_size, HashMap::new())
+    }
+
+    /// Create a manager with `projections` already registered.
+    pub fn with_projections(
+        event_store: ActorRef<EventStoreMsg>,
+        projections: impl IntoIterator<Item = Projection>,
+    ) -> Result<Self, ProjectionError> {
+        let mut registered = HashMap::new();
+        for projection in projections {
+            let name = projection.name().to_string();
+            if registered.contains_key(&name) {
+                return Err(ProjectionError::AlreadyRegistered(name));
+            }
+            registered.insert(
+                name,
+                Arc::new(RegisteredProjection {
+                    projection,
+                    run_lock: Mutex::new(()),
+                }),
+            );
+        }
+        Ok(Self::build(event_store, DEFAULT_BATCH_SIZE, registered))
+    }
+
+    fn build(
+        event_store: ActorRef<EventStoreMsg>,
+        batch_size: i64,
+        projections: HashMap<String, Arc<RegisteredProjection>>,
+    ) -> Self {
+        Self {
+            inner: Arc::new(ProjectionManagerInner {
+                event_store,
+                projections: RwLock::new(projections),
+                batch_size: batch_size.clamp(1, 1000),
+            }),
+        }
+    }
+
+    /// Register a projection. Names must be unique.
+    pub async fn register(&self, projection: Projection) -> Result<(), ProjectionError> {
+        let mut projections = self.inner.projections.write().await;
+        if projections.contains_key(projection.name()) {
+            return Err(ProjectionError::AlreadyRegistered(
+                projection.name().to_string(),
+            ));
+        }
+        projections.insert(
+            projection.name().to_string(),
+            Arc::new(RegisteredProjection {
+                projection,
+                run_lock: Mutex::new(()),
+            }),
+        );
+        Ok(())
+    }
+
+    /// Names of all registered projections, sorted.
+    pub async fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .inner
+            .projections
+            .read()
+            .await
+            .keys()
+            .cloned()
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Stored cursor and status for a projection.
+    pub async fn status(&self, name: &str) -> Result<ProjectionStatus, ProjectionError> {
+        let registered = self.lookup(name).await?;
+        let cursor = self.load_cursor(name).await?;
+        Ok(ProjectionStatus {
+            name: name.to_string(),
+            topics: registered.projection.topics().to_vec(),
+            cursor_seq: cursor.as_ref().map(|c| c.cursor_seq).unwrap_or(0),
+            status: cursor
+                .as_ref()
+                .map(|c| c.status.clone())
+                .unwrap_or_else(|| PROJECTION_STATUS_ACTIVE.to_string()),
+            updated_at: cursor.map(|c| c.updated_at),
+        })
+    }
+
+    /// Apply events committed since the stored cursor. Returns the number of
+    /// events applied.
+    pub async fn catch_up(&self, name: &str) -> Result<u64, ProjectionError> {
+        let registered = self.lookup(name).await?;
+        let _guard = registered.run_lock.lock().await;
+
+        let cursor = self.load_cursor(name).await?;
+        let since_seq = cursor.as_ref().map(|c| c.cursor_seq).unwrap_or(0);
+        // Leave an interrupted rebuild marked as such; catching up from its
+        // cursor is still correct because the reset already happened.
+        let status = cursor
+            .map(|c| c.status)
+            .unwrap_or_else(|| PROJECTION_STATUS_ACTIVE.to_string());
+
+        let (_, applied) = self
+            .replay(&registered.projection, since_seq, None, &status, |_, _| {})
+            .await?;
+        Ok(applied)
+    }
+
+    /// Catch up every registered projection, logging failures.
+    pub async fn catch_up_all(&self) {
+        for name in self.names().await {
+            if let Err(e) = self.catch_up(&name).await {
+                tracing::warn!(projection = %name, error = %e, "Projection catch-up failed");
+            }
+        }
+    }
+
+    /// Reset the projection and replay the full log in seq order.
+    ///
+    /// If a previous rebuild was interrupted, the reset is skipped and replay
+    /// resumes from the stored cursor.
+    pub async fn rebuild(&self, name: &str) -> Result<RebuildReport, ProjectionError> {
+        let registered = self.lookup(name).await?;
+        let _guard = registered.run_lock.lock().await;
+        let projection = &registered.projection;
+
+        let cursor = self.load_cursor(name).await?;
+        let resumed = cursor
+            .as_ref()
+            .map(|c| c.status == PROJECTION_STATUS_REBUILDING)
+            .unwrap_or(false);
+        let target_seq = get_latest_seq(&self.inner.event_store)
+            .await
+            .map_err(|e| ProjectionError::Store(e.to_string()))?
+            .map_err(|e| ProjectionError::Store(e.to_string()))?
+            .unwrap_or(0);
+
+        let start_seq = if resumed {
+            cursor.map(|c| c.cursor_seq).unwrap_or(0)
+        } else {
+            self.save_cursor(name, 0, PROJECTION_STATUS_REBUILDING)
+                .await?;
+            (projection.reset)()
+                .await
+                .map_err(|message| ProjectionError::Reset {
+                    name: name.to_string(),
+                    message,
+                })?;
+            0
+        };
+
+        emit_projection_event(
+            &self.inner.event_store,
+            shared_types::EVENT_TOPIC_PROJECTION_REBUILD_STARTED,
+            serde_json::json!({
+                "projection": name,
+                "resumed": resumed,
+                "start_seq": start_seq,
+                "target_seq": target_seq,
+            }),
+        );
+
+        let event_store = self.inner.event_store.clone();
+        let progress_name = name.to_string();
+        let result = self
+            .replay(
+                projection,
+                start_seq,
+                Some(target_seq),
+                PROJECTION_STATUS_REBUILDING,
+                move |cursor_seq, applied| {
+                    emit_projection_event(
+                        &event_store,
+                        shared_types::EVENT_TOPIC_PROJECTION_REBUILD_PROGRESS,
+                        serde_json::json!({
+                            "projection": progress_name,
+                            "cursor_seq": cursor_seq,
+                            "target_seq": target_seq,
+                            "events_applied": applied,
+                        }),
+                    );
+                },
+            )
+            .await;
+
+        let (cursor_seq, events_applied) = match result {
+            Ok(done) => done,
+            Err(e) => {
+                emit_projection_event(
+                    &self.inner.event_store,
+                    shared_types::EVENT_TOPIC_PROJECTION_REBUILD_FAILED,
+                    serde_json::json!({
+                        "projection": name,
+                        "target_seq": target_seq,
+                        "error": e.to_string(),
+                    }),
+                );
+                return Err(e);
+            }
+        };
+
+        self.save_cursor(name, cursor_seq, PROJECTION_STATUS_ACTIVE)
+            .await?;
+        emit_projection_event(
+            &self.inner.event_store,
+            shared_types::EVENT_TOPIC_PROJECTION_REBUILD_COMPLETED,
+            serde_json::json!({
+                "projection": name,
+                "resumed": resumed,
+                "cursor_seq": cursor_seq,
+                "target_seq": target_seq,
+                "events_applied": events_applied,
+            }),
+        );
+
+        Ok(RebuildReport {
+            name: name.to_string(),
+            resumed,
+            target_seq,
+            cursor_seq,
+            events_applied,
+        })
+    }
+
+    /// Periodically catch up all projections in the background.
+    pub fn spawn_catch_up_loop(&self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let manager = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                manager.catch_up_all().await;
+            }
+        })
+    }
+
+    /// Replay events after `since_seq` (up to `until_seq` when set), persisting
+    /// the cursor after each batch. Returns the final cursor and applied count.
+    async fn replay(
+        &self,
+        projection: &Projection,
+        since_seq: i64,
+        until_seq: Option<i64>,
+        status: &str,
+        mut on_batch: impl FnMut(i64, u64),
+    ) -> Result<(i64, u64), ProjectionError> {
+        let name = projection.name();
+        let mut cursor_seq = since_seq;
+        let mut applied = 0_u64;
+
+        loop {
+            if until_seq.is_some_and(|until| cursor_seq >= until) {
+                break;
+            }
+            let events = get_recent_events(
+                &self.inner.event_store,
+                cursor_seq,
+                self.inner.batch_size,
+                None,
+                None,
+                None,
+            )
+            .await
+            .map_err(|e| ProjectionError::Store(e.to_string()))?
+            .map_err(|e| ProjectionError::Store(e.to_string()))?;
+
+            if events.is_empty() {
+                break;
+            }
+
+            let start_of_batch = cursor_seq;
+            for event in events {
+                if until_seq.is_some_and(|until| event.seq > until) {
+                    break;
+                }
+                let seq = event.seq;
+                if projection.handles(&event.event_type) {
+                    if let Err(message) = (projection.apply)(event).await {
+                        // Persist progress up to the last good event so a
+                        // retry resumes exactly at the failing one.
+                        self.save_cursor(name, cursor_seq, status).await?;
+                        return Err(ProjectionError::Apply {
+                            name: name.to_string(),
+                            seq,
+                            message,
+                        });
+                    }
+                    applied += 1;
+                }
+                cursor_seq = seq;
+            }
+
+            if cursor_seq == start_of_batch {
+                break;
+            }
+            self.save_cursor(name, cursor_seq, status).await?;
+            on_batch(cursor_seq, applied);
+        }
+
+        Ok((cursor_seq, applied))
+    }
+
+    async fn lookup(&self, name: &str) -> Result<Arc<RegisteredProjection>, ProjectionError> {
+        self.inner
+            .projections
+            .read()
+            .await
+            .get(name)
+            .cloned()
+            .ok_or_else(|| ProjectionError::NotFound(name.to_string()))
+    }
+
+    async fn load_cursor(&self, name: &str) -> Result<Option<ProjectionCursor>, ProjectionError> {
+        get_projection_cursor(&self.inner.event_store, name)
+            .await
+            .map_err(|e| ProjectionError::Store(e.to_string()))?
+            .map_err(|e| ProjectionError::Store(e.to_string()))
+    }
+
+    async fn save_cursor(
+        &self,
+        name: &str,
+        cursor_seq: i64,
+        status: &str,
+    ) -> Result<(), ProjectionError> {
+        set_projection_cursor(&self.inner.event_store, name, cursor_seq, status)
+            .await
+            .map_err(|e| ProjectionError::Store(e.to_string()))?
+            .map_err(|e| ProjectionError::Store(e.to_string()))
+    }
+}
+
+fn emit_projection_event(
+    event_store: &ActorRef<EventStoreMsg>,
+    event_type: &str,
+    payload: serde_json::Value,
+) {
+    let event = AppendEvent {
+        event_type: event_type.to_string(),
+        payload,
+        actor_id: "projection_manager".to_string(),
+        user_id: "system".to_string(),
+        idempotency_key: None,
+    };
+    if let Err(e) = event_store.cast(EventStoreMsg::AppendAsync { event }) {
+        tracing::warn!(event_type, error = %e, "Failed to emit projection event");
+    }
+}
@@ -0,0 +1,364 @@
+//! Search projection - full-text index over chat, documents and reports.
+//!
+//! Indexes user chat input (`user_input`), saved document versions
+//! (`writer.run.patch` events that carry a full version body) and report
+//! artifacts (`artifact.created`, `conductor.task.completed`) into the
+//! `search_index` FTS5 table. Each entry is keyed by the seq of the event it
+//! came from, so replays overwrite in place and an `event.redacted` event
+//! removes exactly the entry its target produced.
+//!
+//! The index is a projection: `ProjectionManager::rebuild("search")` drops
+//! and replays it. `SearchIndexerActor` subscribes to the same topics on the
+//! EventBus and catches the projection up as soon as a relevant event is
+//! committed, so new content and redactions show up without waiting for the
+//! periodic catch-up loop.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use ractor::{Actor, ActorProcessingErr, ActorRef};
+use shared_types::{SearchExcerptSegment, SearchHit, SearchHitKind, SearchTarget};
+
+use super::{Projection, ProjectionManager};
+use crate::actors::event_bus::{self, Event, EventBusMsg};
+use crate::actors::event_store::{
+    clear_search_index, index_search_entry, remove_search_entry, EventStoreMsg, SearchIndexEntry,
+    SearchIndexMatch, SEARCH_HIGHLIGHT_END, SEARCH_HIGHLIGHT_START,
+};
+use crate::paths::normalize_document_path;
+
+/// Projection name registered with the ProjectionManager.
+pub const SEARCH_PROJECTION: &str = "search";
+
+/// Event types the search projection consumes.
+pub const SEARCH_TOPICS: [&str; 5] = [
+    shared_types::EVENT_TOPIC_USER_INPUT,
+    shared_types::EVENT_TOPIC_WRITER_RUN_PATCH,
+    shared_types::EVENT_TOPIC_ARTIFACT_CREATED,
+    shared_types::EVENT_TOPIC_CONDUCTOR_TASK_COMPLETED,
+    shared_types::EVENT_TOPIC_EVENT_REDACTED,
+];
+
+/// Build the search projection backed by the event store's FTS5 index.
+pub fn search_projection(event_store: ActorRef<EventStoreMsg>) -> Projection {
+    let reset_store = event_store.clone();
+    Projection::new(
+        SEARCH_PROJECTION,
+        SEARCH_TOPICS,
+        move |event| {
+            let event_store = event_store.clone();
+            async move { apply_event(&event_store, event).await }
+        },
+        move || {
+            let event_store = reset_store.clone();
+            async move {
+                clear_search_index(&event_store)
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .map_err(|e| e.to_string())
+            }
+        },
+    )
+}
+
+async fn apply_event(
+    event_store: &ActorRef<EventStoreMsg>,
+    event: shared_types::Event,
+) -> Result<(), String> {
+    if event.event_type == shared_types::EVENT_TOPIC_EVENT_REDACTED {
+        let Some(seq) = event.payload.get("seq").and_then(|v| v.as_i64()) else {
+            return Ok(());
+        };
+        remove_search_entry(event_store, seq)
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| e.to_string())?;
+        return Ok(());
+    }
+
+    let entry = if event.event_type == shared_types::EVENT_TOPIC_CONDUCTOR_TASK_COMPLETED {
+        report_entry(&event).await
+    } else {
+        entry_for_event(&event)
+    };
+    let Some(entry) = entry else {
+        return Ok(());
+    };
+    index_search_entry(event_store, entry)
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Derive the index entry for a chat, document or artifact event.
+fn entry_for_event(event: &shared_types::Event) -> Option<SearchIndexEntry> {
+    let (kind, title, content, target) = match event.event_type.as_str() {
+        shared_types::EVENT_TOPIC_USER_INPUT => {
+            let record: shared_types::UserInputRecord =
+                serde_json::from_value(event.payload.get("record")?.clone()).ok()?;
+            let target = SearchTarget::Chat {
+                actor_id: event.actor_id.as_str().to_string(),
+                thread_id: record.thread_id,
+                session_id: record.session_id,
+                document_path: record
+                    .document_path
+                    .or_else(|| event.payload.get("path")?.as_str().map(str::to_string))
+                    .and_then(|path| normalize_document_path(&path).ok()),
+            };
+            (
+                SearchHitKind::Chat,
+                record.surface.as_str().to_string(),
+                record.content,
+                target,
+            )
+        }
+        shared_types::EVENT_TOPIC_WRITER_RUN_PATCH => {
+            let payload = &event.payload;
+            // Overlays are proposals, not saved versions.
+            if payload.get("overlay_id").is_some_and(|v| !v.is_null()) {
+                return None;
+            }
+            let revision = payload.get("target_version_id")?.as_u64()?;
+            let ops: Vec<shared_types::PatchOp> =
+                serde_json::from_value(payload.get("ops")?.clone()).ok()?;
+            let content = ops
+                .into_iter()
+                .filter_map(|op| match op {
+                    shared_types::PatchOp::Insert { text, .. }
+                    | shared_types::PatchOp::Replace { text, .. } => Some(text),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            let path = normalize_document_path(payload.get("document_path")?.as_str()?).ok()?;
+            let target = SearchTarget::Document {
+                path: path.clone(),
+                run_id: payload
+                    .get("run_id")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string),
+                revision,
+            };
+            (SearchHitKind::Document, path, content, target)
+        }
+        shared_types::EVENT_TOPIC_ARTIFACT_CREATED => {
+            let artifact: shared_types::WorkerArtifact =
+                serde_json::from_value(event.payload.get("artifact")?.clone()).ok()?;
+            let target = SearchTarget::Artifact {
+                artifact_id: Some(artifact.artifact_id),
+                artifact_kind: artifact.kind.clone(),
+                run_id: None,
+                path: None,
+            };
+            (
+                SearchHitKind::Artifact,
+                artifact.kind,
+                artifact.reference,
+                target,
+            )
+        }
+        _ => return None,
+    };
+
+    if content.trim().is_empty() {
+        return None;
+    }
+    Some(SearchIndexEntry {
+        seq: event.seq,
+        kind: kind.as_str().to_string(),
+        title,
+        content,
+        target: serde_json::to_value(target).ok()?,
+        created_at: event.timestamp.to_rfc3339(),
+    })
+}
+
+/// Index the markdown report written for a completed conductor run.
+async fn report_entry(event: &shared_types::Event) -> Option<SearchIndexEntry> {
+    let payload: shared_types::ConductorTaskCompletedPayload =
+        serde_json::from_value(event.payload.clone()).ok()?;
+    let file = crate::paths::sandbox_root().join(&payload.report_path);
+    let content = match tokio::fs::read_to_string(&file).await {
+        Ok(content) => content,
+        Err(e) => {
+            tracing::debug!(
+                report_path = %payload.report_path,
+                error = %e,
+                "Skipping search indexing for unreadable report"
+            );
+            return None;
+        }
+    };
+    if content.trim().is_empty() {
+        return None;
+    }
+    let title = content
+        .lines()
+        .find_map(|line| {
+            line.trim()
+                .strip_prefix('#')
+                .map(|h| h.trim_start_matches('#').trim())
+        })
+        .filter(|heading| !heading.is_empty())
+        .unwrap_or(payload.report_path.as_str())
+        .to_string();
+    let target = SearchTarget::Artifact {
+        artifact_id: None,
+        artifact_kind: "report".to_string(),
+        run_id: Some(payload.run_id),
+        path: Some(payload.report_path),
+    };
+    Some(SearchIndexEntry {
+        seq: event.seq,
+        kind: SearchHitKind::Artifact.as_str().to_string(),
+        title,
+        content,
+        target: serde_json::to_value(target).ok()?,
+        created_at: event.timestamp.to_rfc3339(),
+    })
+}
+
+/// Turn free-form user input into an FTS5 MATCH expression.
+///
+/// Every word becomes a quoted term (so FTS5 operators and punctuation in the
+/// query are taken literally) and the last one is prefix-matched to support
+/// search-as-you-type. Returns `None` when the query has no searchable words.
+pub fn match_expression(query: &str) -> Option<String> {
+    let terms: Vec<&str> = query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .collect();
+    let (last, rest) = terms.split_last()?;
+    let mut expr: Vec<String> = rest.iter().map(|term| format!("\"{term}\"")).collect();
+    expr.push(format!("\"{last}\"*"));
+    Some(expr.join(" "))
+}
+
+/// Split an FTS5 snippet into plain and highlighted segments.
+pub fn excerpt_segments(snippet: &str) -> Vec<SearchExcerptSegment> {
+    let mut segments = Vec::new();
+    let mut highlight = false;
+    for (idx, part) in snippet
+        .split([SEARCH_HIGHLIGHT_START, SEARCH_HIGHLIGHT_END])
+        .enumerate()
+    {
+        // Markers alternate start/end, so every other part is a match.
+        if idx > 0 {
+            highlight = !highlight;
+        }
+        if !part.is_empty() {
+            segments.push(SearchExcerptSegment {
+                text: part.to_string(),
+                highlight,
+            });
+        }
+    }
+    segments
+}
+
+/// Convert a stored index match into an API search hit.
+pub fn search_hit(found: SearchIndexMatch) -> Option<SearchHit> {
+    Some(SearchHit {
+        kind: SearchHitKind::parse(&found.kind)?,
+        seq: found.seq,
+        title: found.title,
+        excerpt: excerpt_segments(&found.snippet),
+        timestamp: DateTime::parse_from_rfc3339(&found.created_at)
+            .ok()?
+            .with_timezone(&Utc),
+        score: found.score,
+        target: serde_json::from_value(found.target).ok()?,
+    })
+}
+
+// ============================================================================
+// SearchIndexerActor
+// ============================================================================
+
+/// EventBus subscriber that keeps the search projection current.
+#[derive(Debug, Default)]
+pub struct SearchIndexerActor;
+
+pub struct SearchIndexerArguments {
+    pub projections: ProjectionManager,
+    pub event_bus: ActorRef<EventBusMsg>,
+}
+
+pub struct SearchIndexerState {
+    projections: ProjectionManager,
+}
+
+#[async_trait]
+impl Actor for SearchIndexerActor {
+    type Msg = Event;
+    type State = SearchIndexerState;
+    type Arguments = SearchIndexerArguments;
+
+    async fn pre_start(
+        &self,
+        myself: ActorRef<Self::Msg>,
+        args: Self::Arguments,
+    ) -> Result<Self::State, ActorProcessingErr> {
+        for topic in SEARCH_TOPICS {
+            event_bus::subscribe(&args.event_bus, topic, myself.clone()).await?;
+        }
+        Ok(SearchIndexerState {
+            projections: args.projections,
+        })
+    }
+
+    async fn handle(
+        &self,
+        _myself: ActorRef<Self::Msg>,
+        event: Self::Msg,
+        state: &mut Self::State,
+    ) -> Result<(), ActorProcessingErr> {
+        // The bus event only signals that something new was committed; the
+        // projection reads the canonical rows from the store in seq order.
+        if event.is_replayed() {
+            return Ok(());
+        }
+        if let Err(e) = state.projections.catch_up(SEARCH_PROJECTION).await {
+            tracing::warn!(topic = %event.topic, error = %e, "Search index catch-up failed");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_expression_quotes_terms_and_prefixes_last() {
+        assert_eq!(
+            match_expression("pricing mod").as_deref(),
+            Some("\"pricing\" \"mod\"*")
+        );
+        assert_eq!(
+            match_expression("\"rate\" OR NEAR(limit)").as_deref(),
+            Some("\"rate\" \"OR\" \"NEAR\" \"limit\"*")
+        );
+        assert_eq!(match_expression("  -- ** "), None);
+    }
+
+    #[test]
+    fn test_excerpt_segments_split_on_highlight_markers() {
+        let snippet = format!(
+            "the {SEARCH_HIGHLIGHT_START}pricing{SEARCH_HIGHLIGHT_END} and {SEARCH_HIGHLIGHT_START}model{SEARCH_HIGHLIGHT_END}"
+        );
+        let segments = excerpt_segments(&snippet);
+        let parts: Vec<(&str, bool)> = segments
+            .iter()
+            .map(|s| (s.text.as_str(), s.highlight))
+            .collect();
+        assert_eq!(
+            parts,
+            vec![
+                ("the ", false),
+                ("pricing", true),
+                (" and ", false),
+                ("model", true)
+            ]
+        );
+    }
+}
@@ -2,6 +2,7 @@
 
 use std::path::Path;
 use std::sync::OnceLock;
+use std::time::Duration;
 
 static TLS_CERT_PATH: OnceLock<Option<String>> = OnceLock::new();
 
@@ -43,3 +44,38 @@ pub fn ensure_tls_cert_env() -> Option<String> {
         })
         .clone()
 }
+
+/// Gateway path answered without credentials; used as the reachability probe.
+pub const PROVIDER_GATEWAY_HEALTH_PATH: &str = "/provider/v1/health";
+
+/// Base URL of the provider gateway, when provider calls are routed through one.
+pub fn provider_gateway_base_url() -> Option<String> {
+    std::env::var("CHOIR_PROVIDER_GATEWAY_BASE_URL")
+        .ok()
+        .map(|value| value.trim().trim_end_matches('/').to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Probe the provider gateway health endpoint.
+///
+/// Connection errors, timeouts and non-2xx responses all count as unreachable.
+pub async fn probe_provider_gateway(base_url: &str, timeout: Duration) -> bool {
+    let client = match reqwest::Client::builder().timeout(timeout).build() {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::warn!(error = %err, "Failed to build provider gateway probe client");
+            return false;
+        }
+    };
+    let url = format!(
+        "{}{PROVIDER_GATEWAY_HEALTH_PATH}",
+        base_url.trim_end_matches('/')
+    );
+    match client.get(&url).send().await {
+        Ok(response) => response.status().is_success(),
+        Err(err) => {
+            tracing::debug!(url = %url, error = %err, "Provider gateway probe failed");
+            false
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! Bounded RPCs from a supervisor to its children.
+//!
+//! `ractor::call!` waits for a reply forever, so one wedged child would stall
+//! its supervisor and, through it, every caller above. [`call_child`] waits at
+//! most [`CHILD_CALL_TIMEOUT`] and reports a slow child as
+//! [`ChildCallError::Timeout`], which callers may retry; a child that is gone
+//! or drops the reply port is [`ChildCallError::Failed`].
+
+use std::time::Duration;
+
+use ractor::rpc::CallResult;
+use ractor::{ActorRef, Message, RpcReplyPort};
+
+/// How long a supervisor waits on a child before giving up.
+pub const CHILD_CALL_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, thiserror::Error)]
+pub enum ChildCallError {
+    #[error("{child} did not reply within {timeout:?}")]
+    Timeout { child: String, timeout: Duration },
+
+    #[error("call to {child} failed: {message}")]
+    Failed { child: String, message: String },
+}
+
+impl ChildCallError {
+    /// Whether the same call may succeed if sent again. Only a timeout is;
+    /// the child was alive but busy.
+    pub fn is_retriable(&self) -> bool {
+        matches!(self, Self::Timeout { .. })
+    }
+}
+
+/// Call `child` and wait up to [`CHILD_CALL_TIMEOUT`] for its reply.
+pub async fn call_child<M, R, F>(child: &ActorRef<M>, msg_builder: F) -> Result<R, ChildCallError>
+where
+    M: Message,
+    R: Send + 'static,
+    F: FnOnce(RpcReplyPort<R>) -> M,
+{
+    call_child_with_timeout(child, msg_builder, CHILD_CALL_TIMEOUT).await
+}
+
+/// Call `child` and wait up to `timeout` for its reply.
+pub async fn call_child_with_timeout<M, R, F>(
+    child: &ActorRef<M>,
+    msg_builder: F,
+    timeout: Duration,
+) -> Result<R, ChildCallError>
+where
+    M: Message,
+    R: Send + 'static,
+    F: FnOnce(RpcReplyPort<R>) -> M,
+{
+    let child_name = || {
+        child
+            .get_name()
+            .unwrap_or_else(|| child.get_id().to_string())
+    };
+    match child.call(msg_builder, Some(timeout)).await {
+        Ok(CallResult::Success(reply)) => Ok(reply),
+        Ok(CallResult::Timeout) => Err(ChildCallError::Timeout {
+            child: child_name(),
+            timeout,
+        }),
+        Ok(CallResult::SenderError) => Err(ChildCallError::Failed {
+            child: child_name(),
+            message: "reply port dropped".to_string(),
+        }),
+        Err(e) => Err(ChildCallError::Failed {
+            child: child_name(),
+            message: e.to_string(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ractor::{Actor, ActorProcessingErr};
+
+    /// Holds `Stall` reply ports without answering and drops `Discard` ones.
+    struct StallingChild;
+
+    enum StallingMsg {
+        Stall(RpcReplyPort<()>),
+        Discard(RpcReplyPort<()>),
+        Ping(RpcReplyPort<u32>),
+    }
+
+    #[ractor::async_trait]
+    impl Actor for StallingChild {
+        type Msg = StallingMsg;
+        type State = Vec<RpcReplyPort<()>>;
+        type Arguments = ();
+
+        async fn pre_start(
+            &self,
+            _myself: ActorRef<Self::Msg>,
+            _args: Self::Arguments,
+        ) -> Result<Self::State, ActorProcessingErr> {
+            Ok(Vec::new())
+        }
+
+        async fn handle(
+            &self,
+            _myself: ActorRef<Self::Msg>,
+            message: Self::Msg,
+            state: &mut Self::State,
+        ) -> Result<(), ActorProcessingErr> {
+            match message {
+                StallingMsg::Stall(reply) => state.push(reply),
+                StallingMsg::Discard(reply) => drop(reply),
+                StallingMsg::Ping(reply) => {
+                    let _ = reply.send(7);
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_non_responding_child_times_out_as_retriable() {
+        let (child, handle) = Actor::spawn(Some("stalling-child".to_string()), StallingChild, ())
+            .await
+            .unwrap();
+        let timeout = Duration::from_millis(50);
+
+        let err = call_child_with_timeout(&child, StallingMsg::Stall, timeout)
+            .await
+            .unwrap_err();
+        assert!(matches!(&err, ChildCallError::Timeout { child, .. } if child == "stalling-child"));
+        assert!(err.is_retriable());
+
+        let err = call_child_with_timeout(&child, StallingMsg::Discard, timeout)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ChildCallError::Failed { .. }));
+        assert!(!err.is_retriable());
+
+        // The child kept running; a message it answers still gets through.
+        assert_eq!(call_child(&child, StallingMsg::Ping).await.unwrap(), 7);
+
+        child.stop(None);
+        handle.await.unwrap();
+        let err = call_child(&child, StallingMsg::Ping).await.unwrap_err();
+        assert!(matches!(err, ChildCallError::Failed { .. }));
+    }
+}
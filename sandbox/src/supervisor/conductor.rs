@@ -4,7 +4,9 @@ use ractor::{Actor, ActorProcessingErr, ActorRef, RpcReplyPort, SupervisionEvent
 use std::collections::HashMap;
 use tracing::{debug, error, info};
 
-use crate::actors::conductor::{ConductorActor, ConductorArguments, ConductorMsg};
+use crate::actors::conductor::{
+    CapabilityRegistry, ConductorActor, ConductorArguments, ConductorMsg,
+};
 use crate::actors::event_store::EventStoreMsg;
 use crate::actors::memory::MemoryMsg;
 use crate::supervisor::writer::WriterSupervisorMsg;
@@ -122,6 +124,7 @@ impl Actor for ConductorSupervisor {
                     event_store: state.event_store.clone(),
                     writer_supervisor: state.writer_supervisor.clone(),
                     memory_actor: state.memory_actor.clone(),
+                    capabilities: CapabilityRegistry::default(),
                 };
 
                 match Actor::spawn_linked(
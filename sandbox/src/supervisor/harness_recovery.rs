@@ -0,0 +1,296 @@
+//! Harness crash recovery.
+//!
+//! Implements the invariant documented on [`HarnessCheckpoint`]: the latest
+//! `harness.checkpoint` event for a run says which replies the harness was
+//! waiting on, and a `tool.result` event carrying a pending `corr_id` means
+//! that reply already arrived and must be loaded rather than waited for.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use ractor::ActorRef;
+use shared_types::{Event, HarnessCheckpoint, PendingReply, ToolResult, EVENT_TOPIC_TOOL_RESULT};
+
+use crate::actors::event_store::{
+    get_events_by_corr_id, get_latest_harness_checkpoint, EventStoreMsg,
+};
+
+/// What a restarted harness resumes from.
+#[derive(Debug, Clone)]
+pub struct RecoveredHarness {
+    pub checkpoint: HarnessCheckpoint,
+    /// Seq of the checkpoint event.
+    pub checkpoint_seq: i64,
+    /// Replies that came in, in `pending_replies` order.
+    pub already_resolved: Vec<ToolResult>,
+    /// Replies still to wait for.
+    pub still_pending: Vec<PendingReply>,
+    /// Replies with no result whose `timeout_at` has passed; the harness
+    /// treats these as failed.
+    pub timed_out: Vec<PendingReply>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RecoveryError {
+    #[error("no harness checkpoint for run {0}")]
+    NoCheckpoint(String),
+
+    #[error("invalid harness checkpoint at seq {seq}: {message}")]
+    InvalidCheckpoint { seq: i64, message: String },
+
+    #[error("EventStore error: {0}")]
+    EventStore(String),
+}
+
+/// Load the latest checkpoint for `run_id` and sort its pending replies by
+/// whether a `tool.result` for them is already stored.
+pub async fn recover_harness(
+    event_store: &ActorRef<EventStoreMsg>,
+    run_id: &str,
+) -> Result<RecoveredHarness, RecoveryError> {
+    let event = get_latest_harness_checkpoint(event_store, run_id)
+        .await
+        .map_err(|e| RecoveryError::EventStore(e.to_string()))?
+        .map_err(|e| RecoveryError::EventStore(e.to_string()))?
+        .ok_or_else(|| RecoveryError::NoCheckpoint(run_id.to_string()))?;
+    let checkpoint: HarnessCheckpoint =
+        serde_json::from_value(event.payload).map_err(|e| RecoveryError::InvalidCheckpoint {
+            seq: event.seq,
+            message: e.to_string(),
+        })?;
+
+    let mut results = HashMap::new();
+    for pending in &checkpoint.pending_replies {
+        let events = get_events_by_corr_id(
+            event_store,
+            pending.corr_id.clone(),
+            Some(EVENT_TOPIC_TOOL_RESULT.to_string()),
+        )
+        .await
+        .map_err(|e| RecoveryError::EventStore(e.to_string()))?
+        .map_err(|e| RecoveryError::EventStore(e.to_string()))?;
+        // The payload match is a substring test; keep exact corr_id matches.
+        if let Some(result) = events
+            .iter()
+            .rev()
+            .find(|event| event.payload["corr_id"].as_str() == Some(pending.corr_id.as_str()))
+        {
+            results.insert(pending.corr_id.clone(), tool_result(result, pending));
+        }
+    }
+
+    let (already_resolved, still_pending, timed_out) =
+        partition_pending(&checkpoint.pending_replies, results, Utc::now());
+    Ok(RecoveredHarness {
+        checkpoint,
+        checkpoint_seq: event.seq,
+        already_resolved,
+        still_pending,
+        timed_out,
+    })
+}
+
+/// Split pending replies into resolved, still pending and timed out as of
+/// `now`. A stored result wins over an expired deadline.
+fn partition_pending(
+    pending_replies: &[PendingReply],
+    mut results: HashMap<String, ToolResult>,
+    now: DateTime<Utc>,
+) -> (Vec<ToolResult>, Vec<PendingReply>, Vec<PendingReply>) {
+    let mut already_resolved = Vec::new();
+    let mut still_pending = Vec::new();
+    let mut timed_out = Vec::new();
+    for pending in pending_replies {
+        if let Some(result) = results.remove(&pending.corr_id) {
+            already_resolved.push(result);
+        } else if pending
+            .timeout_at
+            .is_some_and(|timeout_at| timeout_at <= now)
+        {
+            timed_out.push(pending.clone());
+        } else {
+            still_pending.push(pending.clone());
+        }
+    }
+    (already_resolved, still_pending, timed_out)
+}
+
+/// Read a `tool.result` event. Producers that predate [`ToolResult`] write a
+/// subset of its fields; the rest come from the pending reply and the event.
+fn tool_result(event: &Event, pending: &PendingReply) -> ToolResult {
+    if let Ok(result) = serde_json::from_value::<ToolResult>(event.payload.clone()) {
+        return result;
+    }
+    let payload = &event.payload;
+    ToolResult {
+        corr_id: pending.corr_id.clone(),
+        actor_kind: payload["actor_kind"]
+            .as_str()
+            .unwrap_or(&pending.actor_kind)
+            .to_string(),
+        success: payload["success"].as_bool().unwrap_or(false),
+        output: payload["output"]
+            .as_str()
+            .or_else(|| payload["output_excerpt"].as_str())
+            .unwrap_or_default()
+            .to_string(),
+        error: payload["error"].as_str().map(str::to_string),
+        elapsed_ms: payload["elapsed_ms"].as_u64().unwrap_or(0),
+        completed_at: event.stored_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actors::event_store::{
+        append_event, AppendEvent, EventStoreActor, EventStoreArguments,
+    };
+    use chrono::Duration;
+    use ractor::Actor;
+    use shared_types::EVENT_TOPIC_HARNESS_CHECKPOINT;
+
+    fn pending(corr_id: &str, timeout_at: Option<DateTime<Utc>>) -> PendingReply {
+        PendingReply {
+            corr_id: corr_id.to_string(),
+            actor_kind: "terminal".to_string(),
+            objective_summary: format!("work for {corr_id}"),
+            sent_at: Utc::now() - Duration::minutes(5),
+            timeout_at,
+        }
+    }
+
+    async fn append(store: &ActorRef<EventStoreMsg>, event_type: &str, payload: serde_json::Value) {
+        append_event(
+            store,
+            AppendEvent {
+                event_type: event_type.to_string(),
+                payload,
+                actor_id: "harness-1".to_string(),
+                user_id: "system".to_string(),
+                idempotency_key: None,
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_recover_partitions_pending_replies() {
+        let (store, _handle) = Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+            .await
+            .unwrap();
+        let now = Utc::now();
+        let checkpoint = HarnessCheckpoint {
+            run_id: "run-1".to_string(),
+            actor_id: "harness-1".to_string(),
+            turn_number: 3,
+            working_memory: "waiting on tools".to_string(),
+            objective: "survey the repo".to_string(),
+            pending_replies: vec![
+                pending("corr-done", Some(now + Duration::minutes(5))),
+                pending("corr-waiting", Some(now + Duration::minutes(5))),
+                pending("corr-expired", Some(now - Duration::minutes(1))),
+                pending("corr-expired-but-done", Some(now - Duration::minutes(1))),
+                pending("corr-no-deadline", None),
+            ],
+            turn_summaries: Vec::new(),
+            checkpointed_at: now,
+        };
+        // An older checkpoint for the same run must be ignored.
+        let mut stale = checkpoint.clone();
+        stale.turn_number = 1;
+        stale.pending_replies = vec![pending("corr-stale", None)];
+        append(
+            &store,
+            EVENT_TOPIC_HARNESS_CHECKPOINT,
+            serde_json::to_value(&stale).unwrap(),
+        )
+        .await;
+        append(
+            &store,
+            EVENT_TOPIC_HARNESS_CHECKPOINT,
+            serde_json::to_value(&checkpoint).unwrap(),
+        )
+        .await;
+
+        let typed = ToolResult {
+            corr_id: "corr-done".to_string(),
+            actor_kind: "researcher".to_string(),
+            success: true,
+            output: "found it".to_string(),
+            error: None,
+            elapsed_ms: 1200,
+            completed_at: now,
+        };
+        append(
+            &store,
+            EVENT_TOPIC_TOOL_RESULT,
+            serde_json::to_value(&typed).unwrap(),
+        )
+        .await;
+        // Terminal-style result: only some ToolResult fields.
+        append(
+            &store,
+            EVENT_TOPIC_TOOL_RESULT,
+            serde_json::json!({
+                "corr_id": "corr-expired-but-done",
+                "run_id": "run-1",
+                "success": false,
+                "output": "",
+                "error": "exit 1",
+            }),
+        )
+        .await;
+        // A corr_id that only shares a prefix is not a match.
+        append(
+            &store,
+            EVENT_TOPIC_TOOL_RESULT,
+            serde_json::json!({ "corr_id": "corr-waiting-2", "success": true }),
+        )
+        .await;
+
+        let recovered = recover_harness(&store, "run-1").await.unwrap();
+        assert_eq!(recovered.checkpoint.turn_number, 3);
+
+        let resolved: Vec<_> = recovered
+            .already_resolved
+            .iter()
+            .map(|result| result.corr_id.as_str())
+            .collect();
+        assert_eq!(resolved, ["corr-done", "corr-expired-but-done"]);
+        assert_eq!(recovered.already_resolved[0].actor_kind, "researcher");
+        assert_eq!(recovered.already_resolved[1].actor_kind, "terminal");
+        assert_eq!(
+            recovered.already_resolved[1].error.as_deref(),
+            Some("exit 1")
+        );
+
+        let corr_ids = |replies: &[PendingReply]| {
+            replies
+                .iter()
+                .map(|reply| reply.corr_id.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            corr_ids(&recovered.still_pending),
+            ["corr-waiting", "corr-no-deadline"]
+        );
+        assert_eq!(corr_ids(&recovered.timed_out), ["corr-expired"]);
+
+        store.stop(None);
+    }
+
+    #[tokio::test]
+    async fn test_recover_without_checkpoint_is_an_error() {
+        let (store, _handle) = Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+            .await
+            .unwrap();
+        assert!(matches!(
+            recover_harness(&store, "run-missing").await,
+            Err(RecoveryError::NoCheckpoint(run_id)) if run_id == "run-missing"
+        ));
+        store.stop(None);
+    }
+}
@@ -5,7 +5,9 @@
 //!
 //! ## Architecture
 //!
-//! ApplicationSupervisor (one_for_one strategy)
+//! ApplicationSupervisor (configurable strategy, one_for_one by default)
+//! ├── EventBusActor
+//! ├── EventRelayActor (depends on EventBus)
 //! └── SessionSupervisor (one_for_one strategy)
 //!     ├── ConductorSupervisor
 //!     ├── DesktopSupervisor
@@ -20,22 +22,42 @@
 //! - `ActorFailed`: Child actor crashed/failed
 //! - `ActorTerminated`: Child actor terminated normally
 //!
+//! ## Restart Strategies
+//!
+//! The application supervisor restarts its event delivery children according
+//! to [`SupervisionStrategy`]:
+//! - `one_for_one`: only the exited child restarts; a restarted EventBus is
+//!   handed to the running relay
+//! - `one_for_all`: both children restart
+//! - `rest_for_one`: the exited child and every child started after it
+//!   restart, so an EventBus failure also restarts the EventRelay
+//!
+//! The per-instance supervisors below SessionSupervisor manage independent
+//! children keyed by id and always restart one_for_one.
+//!
 //! ## Feature Flag
 //!
 //! This module is gated by the `supervision_refactor` feature flag.
 
+pub mod child_call;
 pub mod conductor;
 pub mod desktop;
+pub mod harness_recovery;
 pub mod researcher;
 pub mod session;
 pub mod terminal;
+pub mod worker_topics;
 pub mod writer;
 
+mod worker_events;
+
 // Re-export from session module
 pub use session::{
     SessionSupervisor, SessionSupervisorArgs, SessionSupervisorMsg, SessionSupervisorState,
 };
 
+pub use child_call::{call_child, call_child_with_timeout, ChildCallError, CHILD_CALL_TIMEOUT};
+
 // Re-export from conductor module
 pub use conductor::{
     ConductorSupervisor, ConductorSupervisorArgs, ConductorSupervisorMsg, ConductorSupervisorState,
@@ -47,6 +69,8 @@ pub use desktop::{
     DesktopSupervisorArgs, DesktopSupervisorMsg, DesktopSupervisorState,
 };
 
+pub use harness_recovery::{recover_harness, RecoveredHarness, RecoveryError};
+
 // Re-export from researcher module
 pub use researcher::{
     ResearcherSupervisor, ResearcherSupervisorArgs, ResearcherSupervisorMsg,
@@ -59,26 +83,85 @@ pub use terminal::{
     TerminalSupervisorArgs, TerminalSupervisorMsg, TerminalSupervisorState,
 };
 pub use writer::{
-    WriterSupervisor, WriterSupervisorArgs, WriterSupervisorMsg, WriterSupervisorState,
+    WriterDocument, WriterDocumentHolder, WriterSupervisor, WriterSupervisorArgs,
+    WriterSupervisorMsg, WriterSupervisorState,
 };
 
 use ractor::{Actor, ActorProcessingErr, ActorRef, RpcReplyPort, SupervisionEvent};
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use tracing::{error, info};
 
 use crate::actors::event_bus::{
     Event, EventBusActor, EventBusArguments, EventBusConfig, EventBusMsg, EventType,
 };
 use crate::actors::event_relay::{EventRelayActor, EventRelayArguments, EventRelayMsg};
-use crate::actors::event_store::EventStoreMsg;
+use crate::actors::event_store::{existing_event_ids, EventStoreMsg};
+use worker_events::WorkerEventEmitter;
 
 /// Application supervisor - root of the supervision tree
 #[derive(Debug, Default)]
 pub struct ApplicationSupervisor;
 
+/// How a supervisor restarts its static children when one of them exits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SupervisionStrategy {
+    /// Restart only the child that exited.
+    #[default]
+    OneForOne,
+    /// Restart every child.
+    OneForAll,
+    /// Restart the child that exited and every child started after it.
+    RestForOne,
+}
+
+impl SupervisionStrategy {
+    /// Indices of the children to restart, in start order, when
+    /// `children[exited]` exits.
+    pub fn restart_range(self, exited: usize, children: usize) -> std::ops::Range<usize> {
+        match self {
+            Self::OneForOne => exited..exited + 1,
+            Self::OneForAll => 0..children,
+            Self::RestForOne => exited..children,
+        }
+    }
+}
+
+/// Arguments for spawning ApplicationSupervisor
+#[derive(Debug, Clone)]
+pub struct ApplicationSupervisorArgs {
+    pub event_store: ActorRef<EventStoreMsg>,
+    /// Restart strategy for the event delivery children (EventBus, EventRelay).
+    pub strategy: SupervisionStrategy,
+    /// Worker signal policy; `None` reads it from the environment.
+    pub worker_signal_policy: Option<WorkerSignalPolicy>,
+}
+
+impl From<ActorRef<EventStoreMsg>> for ApplicationSupervisorArgs {
+    fn from(event_store: ActorRef<EventStoreMsg>) -> Self {
+        Self {
+            event_store,
+            strategy: SupervisionStrategy::default(),
+            worker_signal_policy: None,
+        }
+    }
+}
+
+/// Event delivery children in start order. The relay publishes into the
+/// bus, so it comes after it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DeliveryChild {
+    EventBus,
+    EventRelay,
+}
+
+const DELIVERY_CHILDREN: [DeliveryChild; 2] = [DeliveryChild::EventBus, DeliveryChild::EventRelay];
+
+const EVENT_RELAY_POLL_INTERVAL_MS: u64 = 120;
+
 /// Application supervisor state
 pub struct ApplicationState {
     pub event_store: ActorRef<EventStoreMsg>,
+    pub strategy: SupervisionStrategy,
     pub event_bus: Option<ActorRef<EventBusMsg>>,
     pub event_relay: Option<ActorRef<EventRelayMsg>>,
     pub session_supervisor: Option<ActorRef<SessionSupervisorMsg>>,
@@ -86,9 +169,15 @@ pub struct ApplicationState {
     pub last_supervision_failure: Option<String>,
     pub worker_signal_policy: WorkerSignalPolicy,
     pub recent_signal_keys: VecDeque<(String, chrono::DateTime<chrono::Utc>)>,
+    /// Ids of recently accepted worker artifacts, oldest first, capped at
+    /// `KNOWN_ARTIFACT_IDS_CAP`. Findings may cite them as evidence.
+    pub known_artifact_ids: VecDeque<String>,
     pub escalation_cooldowns: HashMap<String, chrono::DateTime<chrono::Utc>>,
 }
 
+/// Accepted artifact ids remembered for resolving evidence refs.
+const KNOWN_ARTIFACT_IDS_CAP: usize = 1_024;
+
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct SupervisionEventCounts {
     pub actor_started: u64,
@@ -114,6 +203,18 @@ pub struct WorkerSignalPolicy {
     pub min_confidence: f64,
     pub duplicate_window_seconds: i64,
     pub escalation_cooldown_seconds: i64,
+    pub evidence_resolution: EvidenceResolution,
+}
+
+/// What happens to a finding whose evidence refs name neither a known
+/// artifact id nor a stored event id.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EvidenceResolution {
+    /// Accept it; the accepted event lists the `unresolved_evidence_refs`.
+    #[default]
+    Lenient,
+    /// Reject it with `MissingEvidence`.
+    Strict,
 }
 
 impl WorkerSignalPolicy {
@@ -154,6 +255,13 @@ impl WorkerSignalPolicy {
                 policy.escalation_cooldown_seconds = parsed.clamp(5, 86_400);
             }
         }
+        if let Ok(raw) = std::env::var("CHOIR_SIGNAL_EVIDENCE_MODE") {
+            match raw.trim().to_ascii_lowercase().as_str() {
+                "strict" => policy.evidence_resolution = EvidenceResolution::Strict,
+                "lenient" => policy.evidence_resolution = EvidenceResolution::Lenient,
+                _ => {}
+            }
+        }
         policy
     }
 }
@@ -168,6 +276,7 @@ impl Default for WorkerSignalPolicy {
             min_confidence: 0.55,
             duplicate_window_seconds: 900,
             escalation_cooldown_seconds: 90,
+            evidence_resolution: EvidenceResolution::default(),
         }
     }
 }
@@ -199,12 +308,15 @@ pub enum ApplicationSupervisorMsg {
             Result<ractor::ActorRef<crate::actors::researcher::ResearcherMsg>, String>,
         >,
     },
-    /// Get or create a writer actor
+    /// Get or create the writer actor of a run document
     GetOrCreateWriter {
-        writer_id: String,
+        document_path: String,
         user_id: String,
+        holder: Option<WriterDocumentHolder>,
         reply: RpcReplyPort<Result<ractor::ActorRef<crate::actors::writer::WriterMsg>, String>>,
     },
+    /// Release a window or run holding run documents open
+    ReleaseWriterHolder { holder: WriterDocumentHolder },
     /// Get or create a conductor actor
     GetOrCreateConductor {
         conductor_id: String,
@@ -225,13 +337,30 @@ pub enum ApplicationSupervisorMsg {
     GetHealth {
         reply: RpcReplyPort<ApplicationSupervisorHealth>,
     },
+    /// Return the supervised EventBus, if it is running.
+    GetEventBus {
+        reply: RpcReplyPort<Option<ActorRef<EventBusMsg>>>,
+    },
+    /// Return the supervised EventRelay, if it is running.
+    GetEventRelay {
+        reply: RpcReplyPort<Option<ActorRef<EventRelayMsg>>>,
+    },
+    /// Publish a worker lifecycle or progress event under a correlation id.
+    PublishWorkerEvent {
+        actor_id: String,
+        topic: String,
+        payload: serde_json::Value,
+        correlation_id: String,
+        session_id: Option<String>,
+        thread_id: Option<String>,
+    },
 }
 
 #[ractor::async_trait]
 impl Actor for ApplicationSupervisor {
     type Msg = ApplicationSupervisorMsg;
     type State = ApplicationState;
-    type Arguments = ActorRef<EventStoreMsg>;
+    type Arguments = ApplicationSupervisorArgs;
 
     async fn handle_supervisor_evt(
         &self,
@@ -252,90 +381,19 @@ impl Actor for ApplicationSupervisor {
                 state.supervision_event_counts.actor_failed += 1;
                 state.last_supervision_failure =
                     Some(format!("actor_id={} error={failure}", actor_cell.get_id()));
+                self.restart_delivery_children(&myself, state, actor_cell.get_id())
+                    .await;
             }
             SupervisionEvent::ActorTerminated(actor_cell, _, _) => {
                 state.supervision_event_counts.actor_terminated += 1;
-                let mut event_bus_terminated = false;
-                let mut event_relay_terminated = false;
 
                 if let Some(session_supervisor) = &state.session_supervisor {
                     if session_supervisor.get_id() == actor_cell.get_id() {
                         state.session_supervisor = None;
                     }
                 }
-                if let Some(event_bus) = &state.event_bus {
-                    if event_bus.get_id() == actor_cell.get_id() {
-                        state.event_bus = None;
-                        event_bus_terminated = true;
-                    }
-                }
-                if let Some(event_relay) = &state.event_relay {
-                    if event_relay.get_id() == actor_cell.get_id() {
-                        state.event_relay = None;
-                        event_relay_terminated = true;
-                    }
-                }
-
-                if event_bus_terminated {
-                    match Actor::spawn_linked(
-                        None,
-                        EventBusActor,
-                        EventBusArguments {
-                            event_store: None,
-                            config: EventBusConfig::default(),
-                        },
-                        myself.get_cell(),
-                    )
-                    .await
-                    {
-                        Ok((event_bus, _)) => {
-                            tracing::info!(
-                                event_bus_id = %event_bus.get_id(),
-                                "respawned EventBusActor after termination"
-                            );
-                            state.event_bus = Some(event_bus.clone());
-                            if let Some(event_relay) = state.event_relay.clone() {
-                                let _ = ractor::cast!(
-                                    event_relay,
-                                    EventRelayMsg::SetEventBus {
-                                        event_bus: event_bus.clone(),
-                                    }
-                                );
-                            }
-                        }
-                        Err(e) => {
-                            tracing::warn!(error = %e, "failed to respawn EventBusActor");
-                        }
-                    }
-                }
-
-                if event_relay_terminated {
-                    if let Some(event_bus) = state.event_bus.clone() {
-                        match Actor::spawn_linked(
-                            None,
-                            EventRelayActor,
-                            EventRelayArguments {
-                                event_store: state.event_store.clone(),
-                                event_bus,
-                                poll_interval_ms: 120,
-                            },
-                            myself.get_cell(),
-                        )
-                        .await
-                        {
-                            Ok((event_relay, _)) => {
-                                tracing::info!(
-                                    event_relay_id = %event_relay.get_id(),
-                                    "respawned EventRelayActor after termination"
-                                );
-                                state.event_relay = Some(event_relay);
-                            }
-                            Err(e) => {
-                                tracing::warn!(error = %e, "failed to respawn EventRelayActor");
-                            }
-                        }
-                    }
-                }
+                self.restart_delivery_children(&myself, state, actor_cell.get_id())
+                    .await;
             }
             _ => {}
         }
@@ -373,29 +431,24 @@ impl Actor for ApplicationSupervisor {
     async fn pre_start(
         &self,
         myself: ActorRef<Self::Msg>,
-        event_store: Self::Arguments,
+        args: Self::Arguments,
     ) -> Result<Self::State, ActorProcessingErr> {
         info!(
             supervisor = %myself.get_id(),
+            strategy = ?args.strategy,
             "ApplicationSupervisor starting"
         );
 
-        // Spawn EventBusActor as a supervised child for pub/sub and correlation-aware tracing.
-        let event_bus_args = EventBusArguments {
-            event_store: None,
-            config: EventBusConfig::default(),
-        };
+        let ApplicationSupervisorArgs {
+            event_store,
+            strategy,
+            worker_signal_policy,
+        } = args;
 
-        let (event_bus, _handle) = Actor::spawn_linked(
-            None, // No fixed name - allows multiple supervisors in tests
-            EventBusActor,
-            event_bus_args,
-            myself.get_cell(),
-        )
-        .await
-        .map_err(|e| {
+        // Spawn EventBusActor as a supervised child for pub/sub and correlation-aware tracing.
+        let event_bus = Self::spawn_event_bus(&myself).await.map_err(|e| {
             tracing::error!("Failed to spawn EventBusActor: {}", e);
-            ActorProcessingErr::from(e)
+            e
         })?;
 
         // Spawn SessionSupervisor as a supervised child
@@ -424,28 +477,24 @@ impl Actor for ApplicationSupervisor {
 
         // Spawn EventRelayActor as a supervised child to relay committed EventStore events
         // to EventBus (ADR-0001).
-        let relay_args = EventRelayArguments {
-            event_store: event_store.clone(),
-            event_bus: event_bus.clone(),
-            poll_interval_ms: 120,
-        };
-        let (event_relay, _handle) =
-            Actor::spawn_linked(None, EventRelayActor, relay_args, myself.get_cell())
-                .await
-                .map_err(|e| {
-                    tracing::error!("Failed to spawn EventRelayActor: {}", e);
-                    ActorProcessingErr::from(e)
-                })?;
+        let event_relay = Self::spawn_event_relay(&myself, &event_store, event_bus.clone())
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to spawn EventRelayActor: {}", e);
+                e
+            })?;
 
         Ok(ApplicationState {
             event_store,
+            strategy,
             event_bus: Some(event_bus),
             event_relay: Some(event_relay),
             session_supervisor: Some(session_supervisor),
             supervision_event_counts: SupervisionEventCounts::default(),
             last_supervision_failure: None,
-            worker_signal_policy: WorkerSignalPolicy::from_env(),
+            worker_signal_policy: worker_signal_policy.unwrap_or_else(WorkerSignalPolicy::from_env),
             recent_signal_keys: VecDeque::new(),
+            known_artifact_ids: VecDeque::new(),
             escalation_cooldowns: HashMap::new(),
         })
     }
@@ -484,16 +533,19 @@ impl Actor for ApplicationSupervisor {
                         desktop_id: desktop_id.clone(),
                         user_id: user_id.clone(),
                         event_store: state.event_store.clone(),
+                        snapshot_interval: crate::actors::desktop::DESKTOP_SNAPSHOT_INTERVAL,
                     };
 
-                    match ractor::call!(session_supervisor, |ss_reply| {
+                    match call_child(session_supervisor, |ss_reply| {
                         SessionSupervisorMsg::GetOrCreateDesktop {
                             desktop_id: desktop_id.clone(),
                             user_id: user_id.clone(),
                             args: desktop_args,
                             reply: ss_reply,
                         }
-                    }) {
+                    })
+                    .await
+                    {
                         Ok(actor_ref) => {
                             self.emit_request_event(
                                 state,
@@ -523,6 +575,7 @@ impl Actor for ApplicationSupervisor {
                                     "desktop_id": desktop_id,
                                     "user_id": user_id,
                                     "error": e.to_string(),
+                                    "retriable": e.is_retriable(),
                                     "supervisor_id": myself.get_id().to_string(),
                                 }),
                                 correlation_id,
@@ -533,6 +586,11 @@ impl Actor for ApplicationSupervisor {
                                 error = %e,
                                 "Failed to get or create desktop via SessionSupervisor"
                             );
+                            // A slow child is not a supervisor fault; dropping
+                            // the reply fails only this caller.
+                            if e.is_retriable() {
+                                return Ok(());
+                            }
                             return Err(ActorProcessingErr::from(e));
                         }
                     }
@@ -567,7 +625,7 @@ impl Actor for ApplicationSupervisor {
                 .await;
 
                 if let Some(ref session_supervisor) = state.session_supervisor {
-                    match ractor::call!(session_supervisor, |ss_reply| {
+                    match call_child(session_supervisor, |ss_reply| {
                         SessionSupervisorMsg::GetOrCreateTerminal {
                             terminal_id: terminal_id.clone(),
                             user_id: user_id.clone(),
@@ -575,7 +633,9 @@ impl Actor for ApplicationSupervisor {
                             working_dir: working_dir.clone(),
                             reply: ss_reply,
                         }
-                    }) {
+                    })
+                    .await
+                    {
                         Ok(result) => match result {
                             Ok(actor_ref) => {
                                 self.emit_request_event(
@@ -630,6 +690,7 @@ impl Actor for ApplicationSupervisor {
                                     "terminal_id": terminal_id,
                                     "user_id": user_id,
                                     "error": e.to_string(),
+                                    "retriable": e.is_retriable(),
                                     "supervisor_id": myself.get_id().to_string(),
                                 }),
                                 correlation_id,
@@ -670,13 +731,15 @@ impl Actor for ApplicationSupervisor {
                 .await;
 
                 if let Some(ref session_supervisor) = state.session_supervisor {
-                    match ractor::call!(session_supervisor, |ss_reply| {
+                    match call_child(session_supervisor, |ss_reply| {
                         SessionSupervisorMsg::GetOrCreateResearcher {
                             researcher_id: researcher_id.clone(),
                             user_id: user_id.clone(),
                             reply: ss_reply,
                         }
-                    }) {
+                    })
+                    .await
+                    {
                         Ok(result) => match result {
                             Ok(actor_ref) => {
                                 self.emit_request_event(
@@ -727,6 +790,7 @@ impl Actor for ApplicationSupervisor {
                                     "researcher_id": researcher_id,
                                     "user_id": user_id,
                                     "error": e.to_string(),
+                                    "retriable": e.is_retriable(),
                                     "supervisor_id": myself.get_id().to_string(),
                                 }),
                                 correlation_id,
@@ -742,8 +806,9 @@ impl Actor for ApplicationSupervisor {
                 }
             }
             ApplicationSupervisorMsg::GetOrCreateWriter {
-                writer_id,
+                document_path,
                 user_id,
+                holder,
                 reply,
             } => {
                 let correlation_id = ulid::Ulid::new().to_string();
@@ -752,7 +817,7 @@ impl Actor for ApplicationSupervisor {
                     "supervisor.writer.get_or_create.started",
                     EventType::Custom("supervisor.writer.get_or_create.started".to_string()),
                     serde_json::json!({
-                        "writer_id": writer_id,
+                        "document_path": document_path,
                         "user_id": user_id,
                         "supervisor_id": myself.get_id().to_string(),
                     }),
@@ -761,13 +826,16 @@ impl Actor for ApplicationSupervisor {
                 .await;
 
                 if let Some(ref session_supervisor) = state.session_supervisor {
-                    match ractor::call!(session_supervisor, |ss_reply| {
+                    match call_child(session_supervisor, |ss_reply| {
                         SessionSupervisorMsg::GetOrCreateWriter {
-                            writer_id: writer_id.clone(),
+                            document_path: document_path.clone(),
                             user_id: user_id.clone(),
+                            holder: holder.clone(),
                             reply: ss_reply,
                         }
-                    }) {
+                    })
+                    .await
+                    {
                         Ok(result) => match result {
                             Ok(actor_ref) => {
                                 self.emit_request_event(
@@ -777,7 +845,7 @@ impl Actor for ApplicationSupervisor {
                                         "supervisor.writer.get_or_create.completed".to_string(),
                                     ),
                                     serde_json::json!({
-                                        "writer_id": writer_id,
+                                        "document_path": document_path,
                                         "user_id": user_id,
                                         "writer_ref": actor_ref.get_id().to_string(),
                                         "supervisor_id": myself.get_id().to_string(),
@@ -795,7 +863,7 @@ impl Actor for ApplicationSupervisor {
                                         "supervisor.writer.get_or_create.failed".to_string(),
                                     ),
                                     serde_json::json!({
-                                        "writer_id": writer_id,
+                                        "document_path": document_path,
                                         "user_id": user_id,
                                         "error": e,
                                         "supervisor_id": myself.get_id().to_string(),
@@ -815,9 +883,10 @@ impl Actor for ApplicationSupervisor {
                                     "supervisor.writer.get_or_create.failed".to_string(),
                                 ),
                                 serde_json::json!({
-                                    "writer_id": writer_id,
+                                    "document_path": document_path,
                                     "user_id": user_id,
                                     "error": e.to_string(),
+                                    "retriable": e.is_retriable(),
                                     "supervisor_id": myself.get_id().to_string(),
                                 }),
                                 correlation_id,
@@ -832,6 +901,12 @@ impl Actor for ApplicationSupervisor {
                     return Ok(());
                 }
             }
+            ApplicationSupervisorMsg::ReleaseWriterHolder { holder } => {
+                if let Some(ref session_supervisor) = state.session_supervisor {
+                    let _ = session_supervisor
+                        .cast(SessionSupervisorMsg::ReleaseWriterHolder { holder });
+                }
+            }
             ApplicationSupervisorMsg::GetOrCreateConductor {
                 conductor_id,
                 user_id,
@@ -852,13 +927,15 @@ impl Actor for ApplicationSupervisor {
                 .await;
 
                 if let Some(ref session_supervisor) = state.session_supervisor {
-                    match ractor::call!(session_supervisor, |ss_reply| {
+                    match call_child(session_supervisor, |ss_reply| {
                         SessionSupervisorMsg::GetOrCreateConductor {
                             conductor_id: conductor_id.clone(),
                             user_id: user_id.clone(),
                             reply: ss_reply,
                         }
-                    }) {
+                    })
+                    .await
+                    {
                         Ok(result) => match result {
                             Ok(actor_ref) => {
                                 self.emit_request_event(
@@ -909,6 +986,7 @@ impl Actor for ApplicationSupervisor {
                                     "conductor_id": conductor_id,
                                     "user_id": user_id,
                                     "error": e.to_string(),
+                                    "retriable": e.is_retriable(),
                                     "supervisor_id": myself.get_id().to_string(),
                                 }),
                                 correlation_id,
@@ -931,12 +1009,15 @@ impl Actor for ApplicationSupervisor {
                 report,
                 reply,
             } => {
-                let correlation_id = ulid::Ulid::new().to_string();
-                Self::publish_worker_event(
-                    state.event_store.clone(),
-                    state.event_bus.clone(),
+                let emitter = WorkerEventEmitter::new(
+                    ulid::Ulid::new().to_string(),
+                    actor_id,
+                    session_id,
+                    thread_id,
+                );
+                emitter.publish(
+                    state,
                     shared_types::EVENT_TOPIC_WORKER_REPORT_RECEIVED,
-                    EventType::Custom(shared_types::EVENT_TOPIC_WORKER_REPORT_RECEIVED.to_string()),
                     serde_json::json!({
                         "turn_id": report.turn_id.clone(),
                         "task_id": report.task_id.clone(),
@@ -949,19 +1030,9 @@ impl Actor for ApplicationSupervisor {
                         "ingested_at": chrono::Utc::now().to_rfc3339(),
                         "requested_by": user_id,
                     }),
-                    correlation_id.clone(),
-                    actor_id.clone(),
-                    session_id.clone(),
-                    thread_id.clone(),
-                );
-                let ingest = Self::ingest_worker_turn_report(
-                    state,
-                    &actor_id,
-                    report,
-                    correlation_id,
-                    session_id,
-                    thread_id,
                 );
+                let unresolved = Self::unresolved_evidence_refs(state, &report).await;
+                let ingest = Self::ingest_worker_turn_report(state, report, &emitter, &unresolved);
                 let _ = reply.send(Ok(ingest));
             }
             ApplicationSupervisorMsg::GetHealth { reply } => {
@@ -973,6 +1044,23 @@ impl Actor for ApplicationSupervisor {
                     last_supervision_failure: state.last_supervision_failure.clone(),
                 });
             }
+            ApplicationSupervisorMsg::GetEventBus { reply } => {
+                let _ = reply.send(state.event_bus.clone());
+            }
+            ApplicationSupervisorMsg::GetEventRelay { reply } => {
+                let _ = reply.send(state.event_relay.clone());
+            }
+            ApplicationSupervisorMsg::PublishWorkerEvent {
+                actor_id,
+                topic,
+                payload,
+                correlation_id,
+                session_id,
+                thread_id,
+            } => {
+                WorkerEventEmitter::new(correlation_id, actor_id, session_id, thread_id)
+                    .publish(state, &topic, payload);
+            }
         }
         Ok(())
     }
@@ -989,6 +1077,139 @@ impl Actor for ApplicationSupervisor {
 }
 
 impl ApplicationSupervisor {
+    async fn spawn_event_bus(
+        myself: &ActorRef<ApplicationSupervisorMsg>,
+    ) -> Result<ActorRef<EventBusMsg>, ActorProcessingErr> {
+        let (event_bus, _handle) = Actor::spawn_linked(
+            None, // No fixed name - allows multiple supervisors in tests
+            EventBusActor,
+            EventBusArguments {
+                event_store: None,
+                config: EventBusConfig::default(),
+            },
+            myself.get_cell(),
+        )
+        .await?;
+        Ok(event_bus)
+    }
+
+    async fn spawn_event_relay(
+        myself: &ActorRef<ApplicationSupervisorMsg>,
+        event_store: &ActorRef<EventStoreMsg>,
+        event_bus: ActorRef<EventBusMsg>,
+    ) -> Result<ActorRef<EventRelayMsg>, ActorProcessingErr> {
+        let (event_relay, _handle) = Actor::spawn_linked(
+            None,
+            EventRelayActor,
+            EventRelayArguments {
+                event_store: event_store.clone(),
+                event_bus,
+                poll_interval_ms: EVENT_RELAY_POLL_INTERVAL_MS,
+            },
+            myself.get_cell(),
+        )
+        .await?;
+        Ok(event_relay)
+    }
+
+    fn delivery_child_id(
+        state: &ApplicationState,
+        child: DeliveryChild,
+    ) -> Option<ractor::ActorId> {
+        match child {
+            DeliveryChild::EventBus => state.event_bus.as_ref().map(|actor| actor.get_id()),
+            DeliveryChild::EventRelay => state.event_relay.as_ref().map(|actor| actor.get_id()),
+        }
+    }
+
+    /// Apply the configured strategy after a delivery child failed or
+    /// terminated. Exits of other children are ignored.
+    async fn restart_delivery_children(
+        &self,
+        myself: &ActorRef<ApplicationSupervisorMsg>,
+        state: &mut ApplicationState,
+        exited: ractor::ActorId,
+    ) {
+        let Some(index) = DELIVERY_CHILDREN
+            .iter()
+            .position(|child| Self::delivery_child_id(state, *child) == Some(exited))
+        else {
+            return;
+        };
+        let restart =
+            &DELIVERY_CHILDREN[state.strategy.restart_range(index, DELIVERY_CHILDREN.len())];
+        tracing::info!(
+            strategy = ?state.strategy,
+            exited = ?DELIVERY_CHILDREN[index],
+            restart = ?restart,
+            "restarting event delivery children"
+        );
+
+        // Siblings are detached from state before they are stopped, so their
+        // own termination events no longer match and are not restarted again.
+        for child in restart {
+            match child {
+                DeliveryChild::EventBus => {
+                    if let Some(event_bus) = state.event_bus.take() {
+                        if event_bus.get_id() != exited {
+                            event_bus.stop(Some("sibling restart".to_string()));
+                        }
+                    }
+                }
+                DeliveryChild::EventRelay => {
+                    if let Some(event_relay) = state.event_relay.take() {
+                        if event_relay.get_id() != exited {
+                            event_relay.stop(Some("sibling restart".to_string()));
+                        }
+                    }
+                }
+            }
+        }
+
+        for child in restart {
+            match child {
+                DeliveryChild::EventBus => match Self::spawn_event_bus(myself).await {
+                    Ok(event_bus) => {
+                        tracing::info!(
+                            event_bus_id = %event_bus.get_id(),
+                            "respawned EventBusActor"
+                        );
+                        // A relay outside the restart set keeps running on the new bus.
+                        if let Some(event_relay) = state.event_relay.clone() {
+                            let _ = ractor::cast!(
+                                event_relay,
+                                EventRelayMsg::SetEventBus {
+                                    event_bus: event_bus.clone(),
+                                }
+                            );
+                        }
+                        state.event_bus = Some(event_bus);
+                    }
+                    Err(e) => {
+                        tracing::warn!(error = %e, "failed to respawn EventBusActor");
+                    }
+                },
+                DeliveryChild::EventRelay => {
+                    let Some(event_bus) = state.event_bus.clone() else {
+                        continue;
+                    };
+                    match Self::spawn_event_relay(myself, &state.event_store, event_bus).await {
+                        Ok(event_relay) => {
+                            tracing::info!(
+                                event_relay_id = %event_relay.get_id(),
+                                "respawned EventRelayActor"
+                            );
+                            state.event_relay = Some(event_relay);
+                        }
+                        Err(e) => {
+                            tracing::warn!(error = %e, "failed to respawn EventRelayActor");
+                        }
+                    }
+                }
+            }
+        }
+    }
+
     fn normalize_signal_key(value: &str) -> String {
         value
             .trim()
@@ -1034,17 +1255,12 @@ impl ApplicationSupervisor {
 
     fn emit_worker_signal_rejection(
         state: &ApplicationState,
-        source_actor_id: &str,
-        correlation_id: &str,
-        session_id: Option<String>,
-        thread_id: Option<String>,
+        emitter: &WorkerEventEmitter,
         rejection: &shared_types::WorkerSignalRejection,
     ) {
-        Self::publish_worker_event(
-            state.event_store.clone(),
-            state.event_bus.clone(),
+        emitter.publish(
+            state,
             shared_types::EVENT_TOPIC_WORKER_SIGNAL_REJECTED,
-            EventType::Custom(shared_types::EVENT_TOPIC_WORKER_SIGNAL_REJECTED.to_string()),
             serde_json::json!({
                 "signal_type": rejection.signal_type,
                 "signal_id": rejection.signal_id,
@@ -1052,20 +1268,64 @@ impl ApplicationSupervisor {
                 "detail": rejection.detail,
                 "rejected_at": chrono::Utc::now().to_rfc3339(),
             }),
-            correlation_id.to_string(),
-            source_actor_id.to_string(),
-            session_id,
-            thread_id,
         );
     }
 
+    /// Finding evidence refs in `report` that name neither an artifact (in
+    /// the report or accepted earlier) nor a stored event. If the EventStore
+    /// cannot be asked, every ref not matching an artifact is unresolved.
+    async fn unresolved_evidence_refs(
+        state: &ApplicationState,
+        report: &shared_types::WorkerTurnReport,
+    ) -> HashSet<String> {
+        let known_artifact = |evidence_ref: &str| {
+            report
+                .artifacts
+                .iter()
+                .any(|artifact| artifact.artifact_id == evidence_ref)
+                || state
+                    .known_artifact_ids
+                    .iter()
+                    .any(|artifact_id| artifact_id == evidence_ref)
+        };
+        let candidates = report
+            .findings
+            .iter()
+            .flat_map(|finding| &finding.evidence_refs)
+            .filter(|evidence_ref| !known_artifact(evidence_ref))
+            .cloned()
+            .collect::<HashSet<_>>();
+        if candidates.is_empty() {
+            return candidates;
+        }
+
+        let stored = match existing_event_ids(
+            &state.event_store,
+            candidates.iter().cloned().collect(),
+        )
+        .await
+        {
+            Ok(Ok(stored)) => stored,
+            Ok(Err(e)) => {
+                tracing::warn!(error = %e, "failed to resolve evidence refs against the EventStore");
+                HashSet::new()
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "EventStore unavailable to resolve evidence refs");
+                HashSet::new()
+            }
+        };
+        candidates
+            .into_iter()
+            .filter(|evidence_ref| !stored.contains(evidence_ref))
+            .collect()
+    }
+
     fn ingest_worker_turn_report(
         state: &mut ApplicationState,
-        source_actor_id: &str,
         report: shared_types::WorkerTurnReport,
-        correlation_id: String,
-        session_id: Option<String>,
-        thread_id: Option<String>,
+        emitter: &WorkerEventEmitter,
+        unresolved_evidence: &HashSet<String>,
     ) -> shared_types::WorkerTurnReportIngestResult {
         let policy = state.worker_signal_policy.clone();
         let now = chrono::Utc::now();
@@ -1074,6 +1334,7 @@ impl ApplicationSupervisor {
         let task_id = report.task_id.clone();
         let worker_id = report.worker_id.clone();
         let worker_role = report.worker_role.clone();
+        let role = worker_topics::WorkerRole::from_report(worker_role.as_deref());
         let status = report.status.clone();
 
         let mut ingest = shared_types::WorkerTurnReportIngestResult {
@@ -1086,6 +1347,12 @@ impl ApplicationSupervisor {
         };
 
         for (idx, finding) in report.findings.iter().enumerate() {
+            let unresolved_refs = finding
+                .evidence_refs
+                .iter()
+                .filter(|evidence_ref| unresolved_evidence.contains(*evidence_ref))
+                .cloned()
+                .collect::<Vec<_>>();
             let reject = if idx >= policy.max_findings_per_turn {
                 Some((
                     shared_types::WorkerSignalRejectReason::MaxPerTurnExceeded,
@@ -1101,6 +1368,13 @@ impl ApplicationSupervisor {
                     shared_types::WorkerSignalRejectReason::MissingEvidence,
                     "finding requires at least one evidence reference".to_string(),
                 ))
+            } else if policy.evidence_resolution == EvidenceResolution::Strict
+                && !unresolved_refs.is_empty()
+            {
+                Some((
+                    shared_types::WorkerSignalRejectReason::MissingEvidence,
+                    format!("unresolved evidence refs: {}", unresolved_refs.join(", ")),
+                ))
             } else if finding.confidence < policy.min_confidence {
                 Some((
                     shared_types::WorkerSignalRejectReason::LowConfidence,
@@ -1133,30 +1407,20 @@ impl ApplicationSupervisor {
             }
 
             ingest.accepted_findings += 1;
-            let topic = if report.worker_role.as_deref() == Some("researcher") {
-                shared_types::EVENT_TOPIC_RESEARCH_FINDING_CREATED
-            } else {
-                shared_types::EVENT_TOPIC_WORKER_FINDING_CREATED
-            };
-            Self::publish_worker_event(
-                state.event_store.clone(),
-                state.event_bus.clone(),
-                topic,
-                EventType::Custom(topic.to_string()),
-                serde_json::json!({
-                    "turn_id": turn_id.clone(),
-                    "task_id": task_id.clone(),
-                    "worker_id": worker_id.clone(),
-                    "worker_role": worker_role.clone(),
-                    "status": status.clone(),
-                    "finding": finding,
-                    "accepted_at": chrono::Utc::now().to_rfc3339(),
-                }),
-                correlation_id.clone(),
-                source_actor_id.to_string(),
-                session_id.clone(),
-                thread_id.clone(),
-            );
+            let topic = worker_topics::topic_for(role, shared_types::WorkerSignalType::Finding);
+            let mut payload = serde_json::json!({
+                "turn_id": turn_id.clone(),
+                "task_id": task_id.clone(),
+                "worker_id": worker_id.clone(),
+                "worker_role": worker_role.clone(),
+                "status": status.clone(),
+                "finding": finding,
+                "accepted_at": chrono::Utc::now().to_rfc3339(),
+            });
+            if !unresolved_refs.is_empty() {
+                payload["unresolved_evidence_refs"] = serde_json::json!(unresolved_refs);
+            }
+            emitter.publish(state, topic, payload);
         }
 
         for (idx, learning) in report.learnings.iter().enumerate() {
@@ -1206,16 +1470,10 @@ impl ApplicationSupervisor {
             }
 
             ingest.accepted_learnings += 1;
-            let topic = if report.worker_role.as_deref() == Some("researcher") {
-                shared_types::EVENT_TOPIC_RESEARCH_LEARNING_CREATED
-            } else {
-                shared_types::EVENT_TOPIC_WORKER_LEARNING_CREATED
-            };
-            Self::publish_worker_event(
-                state.event_store.clone(),
-                state.event_bus.clone(),
+            let topic = worker_topics::topic_for(role, shared_types::WorkerSignalType::Learning);
+            emitter.publish(
+                state,
                 topic,
-                EventType::Custom(topic.to_string()),
                 serde_json::json!({
                     "turn_id": turn_id.clone(),
                     "task_id": task_id.clone(),
@@ -1225,10 +1483,6 @@ impl ApplicationSupervisor {
                     "learning": learning,
                     "accepted_at": chrono::Utc::now().to_rfc3339(),
                 }),
-                correlation_id.clone(),
-                source_actor_id.to_string(),
-                session_id.clone(),
-                thread_id.clone(),
             );
         }
 
@@ -1284,13 +1538,10 @@ impl ApplicationSupervisor {
 
             ingest.accepted_escalations += 1;
             ingest.escalation_notified = true;
-            Self::publish_worker_event(
-                state.event_store.clone(),
-                state.event_bus.clone(),
-                shared_types::EVENT_TOPIC_WORKER_SIGNAL_ESCALATION_REQUESTED,
-                EventType::Custom(
-                    shared_types::EVENT_TOPIC_WORKER_SIGNAL_ESCALATION_REQUESTED.to_string(),
-                ),
+            let topic = worker_topics::topic_for(role, shared_types::WorkerSignalType::Escalation);
+            emitter.publish(
+                state,
+                topic,
                 serde_json::json!({
                     "turn_id": turn_id.clone(),
                     "task_id": task_id.clone(),
@@ -1301,10 +1552,6 @@ impl ApplicationSupervisor {
                     "notified_target": "conductor",
                     "accepted_at": chrono::Utc::now().to_rfc3339(),
                 }),
-                correlation_id.clone(),
-                source_actor_id.to_string(),
-                session_id.clone(),
-                thread_id.clone(),
             );
         }
 
@@ -1337,11 +1584,16 @@ impl ApplicationSupervisor {
             }
 
             ingest.accepted_artifacts += 1;
-            Self::publish_worker_event(
-                state.event_store.clone(),
-                state.event_bus.clone(),
-                shared_types::EVENT_TOPIC_ARTIFACT_CREATED,
-                EventType::Custom(shared_types::EVENT_TOPIC_ARTIFACT_CREATED.to_string()),
+            if state.known_artifact_ids.len() >= KNOWN_ARTIFACT_IDS_CAP {
+                state.known_artifact_ids.pop_front();
+            }
+            state
+                .known_artifact_ids
+                .push_back(artifact.artifact_id.clone());
+            let topic = worker_topics::topic_for(role, shared_types::WorkerSignalType::Artifact);
+            emitter.publish(
+                state,
+                topic,
                 serde_json::json!({
                     "turn_id": turn_id.clone(),
                     "task_id": task_id.clone(),
@@ -1350,22 +1602,11 @@ impl ApplicationSupervisor {
                     "artifact": artifact,
                     "accepted_at": chrono::Utc::now().to_rfc3339(),
                 }),
-                correlation_id.clone(),
-                source_actor_id.to_string(),
-                session_id.clone(),
-                thread_id.clone(),
             );
         }
 
         for rejection in &ingest.rejections {
-            Self::emit_worker_signal_rejection(
-                state,
-                source_actor_id,
-                &correlation_id,
-                session_id.clone(),
-                thread_id.clone(),
-                rejection,
-            );
+            Self::emit_worker_signal_rejection(state, emitter, rejection);
         }
 
         ingest
@@ -1474,6 +1715,7 @@ impl ApplicationSupervisor {
                 payload: event.payload.clone(),
                 actor_id: event.source.clone(),
                 user_id: "system".to_string(),
+                idempotency_key: None,
             },
             reply
         });
@@ -1493,77 +1735,4 @@ impl ApplicationSupervisor {
         // Fanout happens via EventRelayActor from committed EventStore rows (ADR-0001).
         let _ = event;
     }
-
-    fn publish_worker_event(
-        event_store: ActorRef<EventStoreMsg>,
-        _event_bus: Option<ActorRef<EventBusMsg>>,
-        topic: &str,
-        event_type: EventType,
-        payload: serde_json::Value,
-        correlation_id: String,
-        source_actor_id: String,
-        session_id: Option<String>,
-        thread_id: Option<String>,
-    ) {
-        let payload_with_correlation = match payload {
-            serde_json::Value::Object(mut obj) => {
-                obj.insert(
-                    "correlation_id".to_string(),
-                    serde_json::Value::String(correlation_id.clone()),
-                );
-                serde_json::Value::Object(obj)
-            }
-            other => serde_json::json!({
-                "value": other,
-                "correlation_id": correlation_id,
-            }),
-        };
-        let task_id = Self::extract_task_id(&payload_with_correlation);
-        let payload_with_observability = Self::with_observability_metadata(
-            payload_with_correlation,
-            &correlation_id,
-            "appactor_toolactor",
-            task_id.as_deref(),
-        );
-        let event_payload =
-            shared_types::with_scope(payload_with_observability, session_id, thread_id);
-        let event = match Event::new(event_type, topic, event_payload, source_actor_id)
-            .map(|evt| evt.with_correlation_id(correlation_id))
-        {
-            Ok(event) => event,
-            Err(e) => {
-                tracing::warn!(error = %e, topic, "Failed to build worker event");
-                return;
-            }
-        };
-
-        let topic = topic.to_string();
-        tokio::spawn(async move {
-            // Canonical write path: EventStore first.
-            let append_result = ractor::call!(event_store, |reply| EventStoreMsg::Append {
-                event: crate::actors::AppendEvent {
-                    event_type: topic.clone(),
-                    payload: event.payload.clone(),
-                    actor_id: event.source.clone(),
-                    user_id: "system".to_string(),
-                },
-                reply
-            });
-
-            match append_result {
-                Ok(Ok(_)) => {}
-                Ok(Err(e)) => {
-                    tracing::warn!(error = %e, topic, "Failed to persist worker event");
-                    return;
-                }
-                Err(e) => {
-                    tracing::warn!(error = %e, topic, "EventStore RPC failed for worker event");
-                    return;
-                }
-            }
-
-            // Fanout happens via EventRelayActor from committed EventStore rows (ADR-0001).
-            let _ = event;
-        });
-    }
 }
@@ -20,8 +20,10 @@ use crate::supervisor::researcher::{
 use crate::supervisor::terminal::{
     TerminalSupervisor, TerminalSupervisorArgs, TerminalSupervisorMsg,
 };
-use crate::supervisor::writer::{WriterSupervisor, WriterSupervisorArgs, WriterSupervisorMsg};
-use crate::supervisor::ApplicationSupervisorMsg;
+use crate::supervisor::writer::{
+    WriterDocumentHolder, WriterSupervisor, WriterSupervisorArgs, WriterSupervisorMsg,
+};
+use crate::supervisor::{call_child, ApplicationSupervisorMsg};
 
 #[derive(Debug, Default)]
 pub struct SessionSupervisor;
@@ -66,11 +68,16 @@ pub enum SessionSupervisorMsg {
         user_id: String,
         reply: RpcReplyPort<Result<ActorRef<ResearcherMsg>, String>>,
     },
+    /// Writer of a run document, see `WriterSupervisorMsg::GetOrCreateDocumentWriter`.
     GetOrCreateWriter {
-        writer_id: String,
+        document_path: String,
         user_id: String,
+        holder: Option<WriterDocumentHolder>,
         reply: RpcReplyPort<Result<ActorRef<WriterMsg>, String>>,
     },
+    ReleaseWriterHolder {
+        holder: WriterDocumentHolder,
+    },
     GetOrCreateConductor {
         conductor_id: String,
         user_id: String,
@@ -206,17 +213,23 @@ impl Actor for SessionSupervisor {
                 reply,
             } => {
                 if let Some(desktop_supervisor) = &state.desktop_supervisor {
-                    match ractor::call!(desktop_supervisor, |ds_reply| {
+                    match call_child(desktop_supervisor, |ds_reply| {
                         DesktopSupervisorMsg::GetOrCreateDesktop {
                             desktop_id: desktop_id.clone(),
                             user_id: user_id.clone(),
                             args: args.clone(),
                             reply: ds_reply,
                         }
-                    }) {
+                    })
+                    .await
+                    {
                         Ok(actor_ref) => {
                             let _ = reply.send(actor_ref);
                         }
+                        Err(e) if e.is_retriable() => {
+                            // Dropping the reply fails only this caller.
+                            error!(error = %e, "Desktop supervisor RPC timed out");
+                        }
                         Err(e) => return Err(ActorProcessingErr::from(e)),
                     }
                 } else {
@@ -233,7 +246,7 @@ impl Actor for SessionSupervisor {
                 reply,
             } => {
                 if let Some(terminal_supervisor) = &state.terminal_supervisor {
-                    match ractor::call!(terminal_supervisor, |ts_reply| {
+                    match call_child(terminal_supervisor, |ts_reply| {
                         TerminalSupervisorMsg::GetOrCreateTerminal {
                             terminal_id: terminal_id.clone(),
                             user_id: user_id.clone(),
@@ -241,7 +254,9 @@ impl Actor for SessionSupervisor {
                             working_dir: working_dir.clone(),
                             reply: ts_reply,
                         }
-                    }) {
+                    })
+                    .await
+                    {
                         Ok(result) => {
                             let _ = reply.send(result);
                         }
@@ -260,13 +275,15 @@ impl Actor for SessionSupervisor {
                 reply,
             } => {
                 if let Some(researcher_supervisor) = &state.researcher_supervisor {
-                    match ractor::call!(researcher_supervisor, |rs_reply| {
+                    match call_child(researcher_supervisor, |rs_reply| {
                         ResearcherSupervisorMsg::GetOrCreateResearcher {
                             researcher_id: researcher_id.clone(),
                             user_id: user_id.clone(),
                             reply: rs_reply,
                         }
-                    }) {
+                    })
+                    .await
+                    {
                         Ok(result) => {
                             let _ = reply.send(result);
                         }
@@ -280,18 +297,22 @@ impl Actor for SessionSupervisor {
                 }
             }
             SessionSupervisorMsg::GetOrCreateWriter {
-                writer_id,
+                document_path,
                 user_id,
+                holder,
                 reply,
             } => {
                 if let Some(writer_supervisor) = &state.writer_supervisor {
-                    match ractor::call!(writer_supervisor, |ws_reply| {
-                        WriterSupervisorMsg::GetOrCreateWriter {
-                            writer_id: writer_id.clone(),
+                    match call_child(writer_supervisor, |ws_reply| {
+                        WriterSupervisorMsg::GetOrCreateDocumentWriter {
+                            document_path: document_path.clone(),
                             user_id: user_id.clone(),
+                            holder: holder.clone(),
                             reply: ws_reply,
                         }
-                    }) {
+                    })
+                    .await
+                    {
                         Ok(result) => {
                             let _ = reply.send(result);
                         }
@@ -304,19 +325,26 @@ impl Actor for SessionSupervisor {
                     let _ = reply.send(Err("WriterSupervisor not available".to_string()));
                 }
             }
+            SessionSupervisorMsg::ReleaseWriterHolder { holder } => {
+                if let Some(writer_supervisor) = &state.writer_supervisor {
+                    let _ = writer_supervisor.cast(WriterSupervisorMsg::ReleaseHolder { holder });
+                }
+            }
             SessionSupervisorMsg::GetOrCreateConductor {
                 conductor_id,
                 user_id,
                 reply,
             } => {
                 if let Some(conductor_supervisor) = &state.conductor_supervisor {
-                    match ractor::call!(conductor_supervisor, |cs_reply| {
+                    match call_child(conductor_supervisor, |cs_reply| {
                         ConductorSupervisorMsg::GetOrCreateConductor {
                             conductor_id: conductor_id.clone(),
                             user_id: user_id.clone(),
                             reply: cs_reply,
                         }
-                    }) {
+                    })
+                    .await
+                    {
                         Ok(result) => {
                             let _ = reply.send(result);
                         }
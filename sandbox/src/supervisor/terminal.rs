@@ -6,6 +6,7 @@ use tracing::{error, info};
 
 use crate::actors::event_store::EventStoreMsg;
 use crate::actors::terminal::{TerminalActor, TerminalArguments, TerminalInfo, TerminalMsg};
+use crate::supervisor::call_child;
 
 #[derive(Debug, Default)]
 pub struct TerminalSupervisor;
@@ -136,7 +137,9 @@ impl Actor for TerminalSupervisor {
             }
             TerminalSupervisorMsg::GetTerminalInfo { terminal_id, reply } => {
                 let info = if let Some(terminal) = state.terminals.get(&terminal_id) {
-                    ractor::call!(terminal, |port| TerminalMsg::GetInfo { reply: port }).ok()
+                    call_child(terminal, |port| TerminalMsg::GetInfo { reply: port })
+                        .await
+                        .ok()
                 } else {
                     None
                 };
@@ -149,7 +152,7 @@ impl Actor for TerminalSupervisor {
                 let mut infos = Vec::new();
                 for terminal in state.terminals.values() {
                     if let Ok(info) =
-                        ractor::call!(terminal, |port| TerminalMsg::GetInfo { reply: port })
+                        call_child(terminal, |port| TerminalMsg::GetInfo { reply: port }).await
                     {
                         infos.push(info);
                     }
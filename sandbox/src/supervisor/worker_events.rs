@@ -0,0 +1,232 @@
+//! Scoped publishing of worker events from the application supervisor.
+//!
+//! Every worker event the supervisor writes carries the same correlation,
+//! source actor and session/thread scope as the report or request that caused
+//! it. [`WorkerEventEmitter`] captures that context once so each call site
+//! only names a topic and a payload, and every event is enriched the same way.
+//!
+//! Published events go to the EventStore with `AppendAsync`, which samples,
+//! limits and batches telemetry the same way for every producer.
+
+use shared_types::EventScope;
+
+use crate::actors::event_bus::{Event, EventType};
+use crate::actors::event_store::{AppendEvent, EventStoreMsg};
+
+use super::{ApplicationState, ApplicationSupervisor};
+
+/// Correlation and scope shared by the worker events of one supervisor turn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct WorkerEventEmitter {
+    pub(crate) correlation_id: String,
+    pub(crate) source_actor_id: String,
+    pub(crate) scope: EventScope,
+}
+
+impl WorkerEventEmitter {
+    pub(crate) fn new(
+        correlation_id: impl Into<String>,
+        source_actor_id: impl Into<String>,
+        session_id: Option<String>,
+        thread_id: Option<String>,
+    ) -> Self {
+        Self {
+            correlation_id: correlation_id.into(),
+            source_actor_id: source_actor_id.into(),
+            scope: EventScope::new(session_id, thread_id),
+        }
+    }
+
+    /// Append the event to the EventStore without waiting for it.
+    pub(crate) fn publish(
+        &self,
+        state: &ApplicationState,
+        topic: &str,
+        payload: serde_json::Value,
+    ) {
+        let Some(event) = self.append_event(topic, payload) else {
+            return;
+        };
+        if let Err(e) = state.event_store.cast(EventStoreMsg::AppendAsync { event }) {
+            tracing::warn!(error = %e, topic, "EventStore unavailable for worker event");
+        }
+    }
+
+    /// The EventStore record of a worker event, or `None` if it cannot be
+    /// built.
+    fn append_event(&self, topic: &str, payload: serde_json::Value) -> Option<AppendEvent> {
+        match Event::new(
+            EventType::Custom(topic.to_string()),
+            topic,
+            self.event_payload(payload),
+            self.source_actor_id.clone(),
+        )
+        .map(|evt| evt.with_correlation_id(self.correlation_id.clone()))
+        {
+            Ok(event) => Some(AppendEvent {
+                event_type: topic.to_string(),
+                payload: event.payload,
+                actor_id: event.source,
+                user_id: "system".to_string(),
+                idempotency_key: None,
+            }),
+            Err(e) => {
+                tracing::warn!(error = %e, topic, "Failed to build worker event");
+                None
+            }
+        }
+    }
+
+    /// `payload` with the correlation id, observability metadata, normalized
+    /// model fields and session/thread scope every worker event carries.
+    pub(crate) fn event_payload(&self, payload: serde_json::Value) -> serde_json::Value {
+        let payload_with_correlation = match payload {
+            serde_json::Value::Object(mut obj) => {
+                obj.insert(
+                    "correlation_id".to_string(),
+                    serde_json::Value::String(self.correlation_id.clone()),
+                );
+                serde_json::Value::Object(obj)
+            }
+            other => serde_json::json!({
+                "value": other,
+                "correlation_id": self.correlation_id,
+            }),
+        };
+        let task_id = ApplicationSupervisor::extract_task_id(&payload_with_correlation);
+        let payload_with_observability = ApplicationSupervisor::with_observability_metadata(
+            payload_with_correlation,
+            &self.correlation_id,
+            "appactor_toolactor",
+            task_id.as_deref(),
+        );
+        self.scope.attach(payload_with_observability)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn emitter() -> WorkerEventEmitter {
+        WorkerEventEmitter::new(
+            "corr-1",
+            "actor-1",
+            Some("session-1".to_string()),
+            Some("thread-1".to_string()),
+        )
+    }
+
+    fn assert_common_fields(payload: &serde_json::Value) {
+        assert_eq!(payload["correlation_id"], "corr-1");
+        assert_eq!(payload["trace_id"], "corr-1");
+        assert_eq!(payload["interface_kind"], "appactor_toolactor");
+        assert!(payload["span_id"].as_str().is_some_and(|id| !id.is_empty()));
+        assert_eq!(payload["scope"]["session_id"], "session-1");
+        assert_eq!(payload["scope"]["thread_id"], "thread-1");
+    }
+
+    #[test]
+    fn object_payloads_gain_correlation_scope_and_observability() {
+        let payload = emitter().event_payload(serde_json::json!({
+            "task_id": "task-1",
+            "summary": "done",
+        }));
+        assert_common_fields(&payload);
+        assert_eq!(payload["task_id"], "task-1");
+        assert_eq!(payload["summary"], "done");
+    }
+
+    #[test]
+    fn nested_task_ids_are_hoisted() {
+        let payload = emitter().event_payload(serde_json::json!({
+            "task": { "task_id": "task-nested" },
+        }));
+        assert_common_fields(&payload);
+        assert_eq!(payload["task_id"], "task-nested");
+    }
+
+    #[test]
+    fn model_fields_are_normalized() {
+        let direct = emitter().event_payload(serde_json::json!({ "model_requested": "  " }));
+        assert_eq!(direct["model_requested"], "none");
+        assert_eq!(direct["model_used"], "direct_command");
+
+        let requested = emitter().event_payload(serde_json::json!({
+            "model_requested": "claude",
+        }));
+        assert_eq!(requested["model_requested"], "claude");
+        assert_eq!(requested["model_used"], "claude");
+
+        let used = emitter().event_payload(serde_json::json!({
+            "model_requested": "claude",
+            "model_used": "fallback",
+        }));
+        assert_eq!(used["model_used"], "fallback");
+    }
+
+    #[test]
+    fn non_object_payloads_are_wrapped() {
+        let payload = emitter().event_payload(serde_json::json!("raw"));
+        assert_common_fields(&payload);
+        assert_eq!(payload["value"], "raw");
+    }
+
+    #[test]
+    fn unscoped_emitters_add_no_scope() {
+        let payload = WorkerEventEmitter::new("corr-1", "actor-1", None, None)
+            .event_payload(serde_json::json!({}));
+        assert!(payload.get("scope").is_none());
+    }
+
+    #[tokio::test]
+    async fn scope_round_trips_through_the_event_store() {
+        use crate::actors::event_store::{EventStoreActor, EventStoreArguments};
+        use ractor::Actor;
+
+        let (event_store, _handle) =
+            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+                .await
+                .expect("event store");
+        let emitter = emitter();
+        for (topic, payload) in [
+            (
+                "worker.scope.object",
+                serde_json::json!({ "task_id": "task-1" }),
+            ),
+            ("worker.scope.value", serde_json::json!(42)),
+        ] {
+            let event = emitter.append_event(topic, payload).expect("worker event");
+            ractor::call!(event_store, |reply| EventStoreMsg::Append { event, reply })
+                .expect("event store call")
+                .expect("append");
+        }
+
+        let events = ractor::call!(event_store, |reply| EventStoreMsg::GetRecentEvents {
+            since_seq: 0,
+            limit: 10,
+            event_type_prefix: Some("worker.scope.".to_string()),
+            actor_id: None,
+            user_id: None,
+            reply,
+        })
+        .expect("event store call")
+        .expect("events");
+        assert_eq!(events.len(), 2);
+        for event in &events {
+            assert_eq!(
+                EventScope::from_payload(&event.payload),
+                emitter.scope,
+                "{}",
+                event.event_type
+            );
+        }
+        let value = events
+            .iter()
+            .find(|event| event.event_type == "worker.scope.value")
+            .expect("wrapped event");
+        assert_eq!(value.payload["value"], 42);
+
+        event_store.stop(None);
+    }
+}
@@ -0,0 +1,137 @@
+//! Event topic selection for accepted worker turn-report signals.
+
+use shared_types::WorkerSignalType;
+
+/// The worker kind behind a turn report, parsed from `WorkerTurnReport::worker_role`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkerRole {
+    Researcher,
+    Writer,
+    Terminal,
+    Conductor,
+    Harness,
+    /// Missing or unrecognised role; routed like any generic worker.
+    Other,
+}
+
+impl WorkerRole {
+    pub fn from_report(role: Option<&str>) -> Self {
+        match role {
+            Some("researcher") => Self::Researcher,
+            Some("writer") => Self::Writer,
+            Some("terminal") => Self::Terminal,
+            Some("conductor") => Self::Conductor,
+            Some("harness") => Self::Harness,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// Topic an accepted signal is published under.
+///
+/// Researcher findings and learnings go to `research.*`; every other role
+/// uses `worker.*`. Escalations and artifacts share one topic regardless of
+/// role.
+pub fn topic_for(role: WorkerRole, signal_type: WorkerSignalType) -> &'static str {
+    match (role, signal_type) {
+        (WorkerRole::Researcher, WorkerSignalType::Finding) => {
+            shared_types::EVENT_TOPIC_RESEARCH_FINDING_CREATED
+        }
+        (WorkerRole::Researcher, WorkerSignalType::Learning) => {
+            shared_types::EVENT_TOPIC_RESEARCH_LEARNING_CREATED
+        }
+        (_, WorkerSignalType::Finding) => shared_types::EVENT_TOPIC_WORKER_FINDING_CREATED,
+        (_, WorkerSignalType::Learning) => shared_types::EVENT_TOPIC_WORKER_LEARNING_CREATED,
+        (_, WorkerSignalType::Escalation) => {
+            shared_types::EVENT_TOPIC_WORKER_SIGNAL_ESCALATION_REQUESTED
+        }
+        (_, WorkerSignalType::Artifact) => shared_types::EVENT_TOPIC_ARTIFACT_CREATED,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_ROLES: [WorkerRole; 6] = [
+        WorkerRole::Researcher,
+        WorkerRole::Writer,
+        WorkerRole::Terminal,
+        WorkerRole::Conductor,
+        WorkerRole::Harness,
+        WorkerRole::Other,
+    ];
+
+    #[test]
+    fn parses_harness_model_roles() {
+        assert_eq!(
+            WorkerRole::from_report(Some("researcher")),
+            WorkerRole::Researcher
+        );
+        assert_eq!(WorkerRole::from_report(Some("writer")), WorkerRole::Writer);
+        assert_eq!(
+            WorkerRole::from_report(Some("terminal")),
+            WorkerRole::Terminal
+        );
+        assert_eq!(
+            WorkerRole::from_report(Some("conductor")),
+            WorkerRole::Conductor
+        );
+        assert_eq!(
+            WorkerRole::from_report(Some("harness")),
+            WorkerRole::Harness
+        );
+        assert_eq!(
+            WorkerRole::from_report(Some("Researcher")),
+            WorkerRole::Other
+        );
+        assert_eq!(WorkerRole::from_report(None), WorkerRole::Other);
+    }
+
+    #[test]
+    fn researcher_findings_and_learnings_use_research_topics() {
+        assert_eq!(
+            topic_for(WorkerRole::Researcher, WorkerSignalType::Finding),
+            "research.finding.created"
+        );
+        assert_eq!(
+            topic_for(WorkerRole::Researcher, WorkerSignalType::Learning),
+            "research.learning.created"
+        );
+    }
+
+    #[test]
+    fn other_roles_findings_and_learnings_use_worker_topics() {
+        for role in ALL_ROLES
+            .into_iter()
+            .filter(|role| *role != WorkerRole::Researcher)
+        {
+            assert_eq!(
+                topic_for(role, WorkerSignalType::Finding),
+                "worker.finding.created",
+                "{role:?}"
+            );
+            assert_eq!(
+                topic_for(role, WorkerSignalType::Learning),
+                "worker.learning.created",
+                "{role:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn escalations_and_artifacts_ignore_role() {
+        for role in ALL_ROLES {
+            assert_eq!(
+                topic_for(role, WorkerSignalType::Escalation),
+                "worker.signal.escalation_requested",
+                "{role:?}"
+            );
+            assert_eq!(
+                topic_for(role, WorkerSignalType::Artifact),
+                "artifact.created",
+                "{role:?}"
+            );
+        }
+    }
+}
@@ -1,19 +1,58 @@
 //! Writer Supervisor - manages WriterActor instances
+//!
+//! The WriterSupervisor is responsible for:
+//! - One WriterActor per run document, so concurrent runs and windows on the
+//!   same path share a single writer (`writer:{writer_id}` in the registry)
+//! - Restarting failed writers (max 3 restarts per 60 seconds) and restoring
+//!   their run documents from persisted state
+//! - Tearing a writer down once the last window and run holding its
+//!   document are released
 
 use ractor::{Actor, ActorProcessingErr, ActorRef, RpcReplyPort, SupervisionEvent};
-use std::collections::HashMap;
-use tracing::{error, info};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use tracing::{error, info, warn};
 
+use crate::actors::conductor::registry::{extract_run_id_from_document_path, run_writer_id};
 use crate::actors::event_store::EventStoreMsg;
 use crate::actors::writer::{WriterActor, WriterArguments, WriterMsg};
+use crate::paths::normalize_document_path;
 use crate::supervisor::researcher::ResearcherSupervisorMsg;
 use crate::supervisor::terminal::TerminalSupervisorMsg;
 
+/// Maximum restarts allowed within the period
+const MAX_RESTARTS: u32 = 3;
+/// Time window for restart intensity tracking (60 seconds)
+const RESTART_PERIOD: Duration = Duration::from_secs(60);
+
 #[derive(Debug, Default)]
 pub struct WriterSupervisor;
 
+/// What keeps a run document's writer alive.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum WriterDocumentHolder {
+    /// Desktop window showing the document, by window id.
+    Window(String),
+    /// Conductor run writing the document, by run id.
+    Run(String),
+}
+
+/// Run document served by a writer.
+#[derive(Debug, Clone)]
+pub struct WriterDocument {
+    pub writer_id: String,
+    pub run_id: String,
+    pub holders: HashSet<WriterDocumentHolder>,
+}
+
 pub struct WriterSupervisorState {
     pub writers: HashMap<String, ActorRef<WriterMsg>>,
+    /// User each writer was spawned for, so a restart respawns it as-is.
+    pub writer_users: HashMap<String, String>,
+    /// Run documents by document path.
+    pub documents: HashMap<String, WriterDocument>,
+    /// Restart tracking: writer_id -> (restart_count, window_start)
+    pub restart_counts: HashMap<String, (u32, Instant)>,
     pub event_store: ActorRef<EventStoreMsg>,
     pub researcher_supervisor: Option<ActorRef<ResearcherSupervisorMsg>>,
     pub terminal_supervisor: Option<ActorRef<TerminalSupervisorMsg>>,
@@ -33,6 +72,19 @@ pub enum WriterSupervisorMsg {
         user_id: String,
         reply: RpcReplyPort<Result<ActorRef<WriterMsg>, String>>,
     },
+    /// Get or create the writer of a run document, optionally registering
+    /// `holder` as keeping it alive.
+    GetOrCreateDocumentWriter {
+        document_path: String,
+        user_id: String,
+        holder: Option<WriterDocumentHolder>,
+        reply: RpcReplyPort<Result<ActorRef<WriterMsg>, String>>,
+    },
+    /// Drop `holder` from every document; writers left without holders stop
+    /// once their queued messages are processed.
+    ReleaseHolder {
+        holder: WriterDocumentHolder,
+    },
     GetWriter {
         writer_id: String,
         reply: RpcReplyPort<Option<ActorRef<WriterMsg>>>,
@@ -43,6 +95,178 @@ pub enum WriterSupervisorMsg {
     Supervision(SupervisionEvent),
 }
 
+impl WriterSupervisor {
+    /// Check if a writer should be restarted based on intensity
+    fn should_restart(&self, writer_id: &str, state: &mut WriterSupervisorState) -> bool {
+        let now = Instant::now();
+        match state.restart_counts.get_mut(writer_id) {
+            Some((count, window_start)) => {
+                if now.duration_since(*window_start) > RESTART_PERIOD {
+                    *count = 1;
+                    *window_start = now;
+                    true
+                } else if *count < MAX_RESTARTS {
+                    *count += 1;
+                    true
+                } else {
+                    warn!(
+                        writer_id = %writer_id,
+                        restarts = *count,
+                        "Max restart intensity exceeded - will not restart writer"
+                    );
+                    false
+                }
+            }
+            None => {
+                state.restart_counts.insert(writer_id.to_string(), (1, now));
+                true
+            }
+        }
+    }
+
+    fn find_writer_id_by_actor(
+        &self,
+        actor_id: ractor::ActorId,
+        state: &WriterSupervisorState,
+    ) -> Option<String> {
+        state
+            .writers
+            .iter()
+            .find(|(_, writer)| writer.get_id() == actor_id)
+            .map(|(writer_id, _)| writer_id.clone())
+    }
+
+    /// Forget a writer and every document it served.
+    fn forget_writer(&self, writer_id: &str, state: &mut WriterSupervisorState) {
+        state.writers.remove(writer_id);
+        state.writer_users.remove(writer_id);
+        state.restart_counts.remove(writer_id);
+        state
+            .documents
+            .retain(|_, document| document.writer_id != writer_id);
+    }
+
+    async fn spawn_writer(
+        &self,
+        myself: &ActorRef<WriterSupervisorMsg>,
+        state: &mut WriterSupervisorState,
+        writer_id: &str,
+        user_id: String,
+    ) -> Result<ActorRef<WriterMsg>, String> {
+        let args = WriterArguments {
+            writer_id: writer_id.to_string(),
+            user_id: user_id.clone(),
+            event_store: state.event_store.clone(),
+            researcher_supervisor: state.researcher_supervisor.clone(),
+            terminal_supervisor: state.terminal_supervisor.clone(),
+        };
+        let actor_name = format!("writer:{writer_id}");
+        match Actor::spawn_linked(Some(actor_name), WriterActor, args, myself.get_cell()).await {
+            Ok((actor_ref, _)) => {
+                state
+                    .writers
+                    .insert(writer_id.to_string(), actor_ref.clone());
+                state.writer_users.insert(writer_id.to_string(), user_id);
+                Ok(actor_ref)
+            }
+            Err(e) => {
+                error!(error = %e, "Failed to spawn WriterActor");
+                Err(e.to_string())
+            }
+        }
+    }
+
+    async fn get_or_create(
+        &self,
+        myself: &ActorRef<WriterSupervisorMsg>,
+        state: &mut WriterSupervisorState,
+        writer_id: &str,
+        user_id: String,
+    ) -> Result<ActorRef<WriterMsg>, String> {
+        if let Some(writer) = state.writers.get(writer_id) {
+            return Ok(writer.clone());
+        }
+
+        let actor_name = format!("writer:{writer_id}");
+        if let Some(cell) = ractor::registry::where_is(actor_name) {
+            let actor_ref: ActorRef<WriterMsg> = cell.into();
+            if actor_ref.get_status() == ractor::ActorStatus::Running {
+                state
+                    .writers
+                    .insert(writer_id.to_string(), actor_ref.clone());
+                state.writer_users.insert(writer_id.to_string(), user_id);
+                return Ok(actor_ref);
+            }
+        }
+
+        self.spawn_writer(myself, state, writer_id, user_id).await
+    }
+
+    /// Respawn a failed writer and restore the run documents it served.
+    async fn restart_writer(
+        &self,
+        myself: &ActorRef<WriterSupervisorMsg>,
+        state: &mut WriterSupervisorState,
+        writer_id: &str,
+    ) {
+        let user_id = state
+            .writer_users
+            .get(writer_id)
+            .cloned()
+            .unwrap_or_else(|| "system".to_string());
+        state.writers.remove(writer_id);
+        info!(writer_id = %writer_id, "Restarting WriterActor");
+        match self.spawn_writer(myself, state, writer_id, user_id).await {
+            Ok(writer) => {
+                for document in state.documents.values() {
+                    if document.writer_id == writer_id {
+                        let _ = writer.send_message(WriterMsg::RestoreRunDocument {
+                            run_id: document.run_id.clone(),
+                        });
+                    }
+                }
+                info!(
+                    writer_id = %writer_id,
+                    new_actor_id = %writer.get_id(),
+                    "WriterActor restarted successfully"
+                );
+            }
+            Err(e) => {
+                error!(writer_id = %writer_id, error = %e, "Failed to restart WriterActor");
+                self.forget_writer(writer_id, state);
+            }
+        }
+    }
+
+    fn release_holder(&self, holder: &WriterDocumentHolder, state: &mut WriterSupervisorState) {
+        let mut released = Vec::new();
+        state.documents.retain(|_, document| {
+            if document.holders.remove(holder) && document.holders.is_empty() {
+                released.push(document.writer_id.clone());
+                return false;
+            }
+            true
+        });
+        for writer_id in released {
+            let still_used = state
+                .documents
+                .values()
+                .any(|document| document.writer_id == writer_id);
+            if still_used {
+                continue;
+            }
+            if let Some(writer) = state.writers.remove(&writer_id) {
+                info!(writer_id = %writer_id, "Last holder released - stopping WriterActor");
+                if writer.drain().is_err() {
+                    writer.stop(None);
+                }
+            }
+            state.writer_users.remove(&writer_id);
+            state.restart_counts.remove(&writer_id);
+        }
+    }
+}
+
 #[ractor::async_trait]
 impl Actor for WriterSupervisor {
     type Msg = WriterSupervisorMsg;
@@ -57,6 +281,9 @@ impl Actor for WriterSupervisor {
         info!(supervisor = %myself.get_id(), "WriterSupervisor starting");
         Ok(WriterSupervisorState {
             writers: HashMap::new(),
+            writer_users: HashMap::new(),
+            documents: HashMap::new(),
+            restart_counts: HashMap::new(),
             event_store: args.event_store,
             researcher_supervisor: args.researcher_supervisor,
             terminal_supervisor: args.terminal_supervisor,
@@ -69,13 +296,26 @@ impl Actor for WriterSupervisor {
         event: SupervisionEvent,
         state: &mut Self::State,
     ) -> Result<(), ActorProcessingErr> {
-        if let SupervisionEvent::ActorTerminated(actor_cell, _, _)
-        | SupervisionEvent::ActorFailed(actor_cell, _) = &event
-        {
-            let actor_id = actor_cell.get_id();
-            state
-                .writers
-                .retain(|_, writer| writer.get_id() != actor_id);
+        match &event {
+            SupervisionEvent::ActorFailed(actor_cell, _) => {
+                if let Some(writer_id) = self.find_writer_id_by_actor(actor_cell.get_id(), state) {
+                    let serves_documents = state
+                        .documents
+                        .values()
+                        .any(|document| document.writer_id == writer_id);
+                    if serves_documents && self.should_restart(&writer_id, state) {
+                        self.restart_writer(&myself, state, &writer_id).await;
+                    } else {
+                        self.forget_writer(&writer_id, state);
+                    }
+                }
+            }
+            SupervisionEvent::ActorTerminated(actor_cell, _, _) => {
+                if let Some(writer_id) = self.find_writer_id_by_actor(actor_cell.get_id(), state) {
+                    self.forget_writer(&writer_id, state);
+                }
+            }
+            _ => {}
         }
         info!(
             supervisor = %myself.get_id(),
@@ -97,39 +337,48 @@ impl Actor for WriterSupervisor {
                 user_id,
                 reply,
             } => {
-                if let Some(writer) = state.writers.get(&writer_id) {
-                    let _ = reply.send(Ok(writer.clone()));
-                    return Ok(());
-                }
-
-                let actor_name = format!("writer:{writer_id}");
-                if let Some(cell) = ractor::registry::where_is(actor_name.clone()) {
-                    let actor_ref: ActorRef<WriterMsg> = cell.into();
-                    state.writers.insert(writer_id, actor_ref.clone());
-                    let _ = reply.send(Ok(actor_ref));
+                let result = self
+                    .get_or_create(&myself, state, &writer_id, user_id)
+                    .await;
+                let _ = reply.send(result);
+            }
+            WriterSupervisorMsg::GetOrCreateDocumentWriter {
+                document_path,
+                user_id,
+                holder,
+                reply,
+            } => {
+                // Key documents by normalized path so `./x` and `x` share a writer.
+                let normalized = normalize_document_path(&document_path).ok();
+                let Some((document_path, run_id)) = normalized.and_then(|path| {
+                    let run_id = extract_run_id_from_document_path(&path)?;
+                    Some((path, run_id))
+                }) else {
+                    let _ = reply.send(Err(format!("not a run document path: {document_path}")));
                     return Ok(());
-                }
-
-                let args = WriterArguments {
-                    writer_id: writer_id.clone(),
-                    user_id,
-                    event_store: state.event_store.clone(),
-                    researcher_supervisor: state.researcher_supervisor.clone(),
-                    terminal_supervisor: state.terminal_supervisor.clone(),
                 };
-
-                match Actor::spawn_linked(Some(actor_name), WriterActor, args, myself.get_cell())
-                    .await
-                {
-                    Ok((actor_ref, _)) => {
-                        state.writers.insert(writer_id, actor_ref.clone());
-                        let _ = reply.send(Ok(actor_ref));
-                    }
-                    Err(e) => {
-                        error!(error = %e, "Failed to spawn WriterActor");
-                        let _ = reply.send(Err(e.to_string()));
+                let writer_id = run_writer_id(&run_id);
+                let result = self
+                    .get_or_create(&myself, state, &writer_id, user_id)
+                    .await;
+                if result.is_ok() {
+                    let document =
+                        state
+                            .documents
+                            .entry(document_path)
+                            .or_insert_with(|| WriterDocument {
+                                writer_id,
+                                run_id,
+                                holders: HashSet::new(),
+                            });
+                    if let Some(holder) = holder {
+                        document.holders.insert(holder);
                     }
                 }
+                let _ = reply.send(result);
+            }
+            WriterSupervisorMsg::ReleaseHolder { holder } => {
+                self.release_holder(&holder, state);
             }
             WriterSupervisorMsg::GetWriter { writer_id, reply } => {
                 let _ = reply.send(state.writers.get(&writer_id).cloned());
@@ -138,6 +387,7 @@ impl Actor for WriterSupervisor {
                 if let Some(actor_ref) = state.writers.remove(&writer_id) {
                     actor_ref.stop(None);
                 }
+                self.forget_writer(&writer_id, state);
             }
             WriterSupervisorMsg::Supervision(event) => {
                 self.handle_supervisor_evt(myself, event, state).await?;
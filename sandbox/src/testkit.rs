@@ -0,0 +1,256 @@
+//! Harness for integration tests that drive the actor tree.
+//!
+//! `TestSandbox::start()` boots an in-memory EventStore and the
+//! ApplicationSupervisor behind an `AppState`; `start_with_server()` also
+//! serves the API router on a random local port. Tests wait on events with
+//! `wait_for_event` instead of sleeping.
+//!
+//! Built with the `testkit` feature, which the sandbox's own tests enable
+//! through a dev-dependency on the crate itself.
+//!
+//! ```rust,ignore
+//! let sandbox = TestSandbox::start().await;
+//! sandbox
+//!     .app_state
+//!     .get_or_create_desktop("d1".to_string(), "u1".to_string())
+//!     .await?;
+//! let completed = sandbox
+//!     .wait_for_event(
+//!         "supervisor.desktop.get_or_create.completed",
+//!         |event| event.payload["desktop_id"] == "d1",
+//!         Duration::from_secs(2),
+//!     )
+//!     .await
+//!     .expect("desktop creation recorded");
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use ractor::{Actor, ActorProcessingErr, ActorRef};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::actors::event_bus::{Event, EventBusMsg};
+use crate::actors::event_relay::relayed_event;
+use crate::actors::event_store::{
+    get_events_for_actor, EventStoreActor, EventStoreArguments, EventStoreMsg,
+};
+use crate::api::{self, ApiState};
+use crate::app_state::AppState;
+use crate::supervisor::ApplicationSupervisorMsg;
+
+/// Committed events scanned when a wait starts, before live delivery.
+const COMMITTED_SCAN_LIMIT: i64 = 10_000;
+
+/// An in-process sandbox: EventStore, supervision tree and, optionally, the
+/// HTTP API. Everything it started is stopped when it is dropped.
+pub struct TestSandbox {
+    pub app_state: Arc<AppState>,
+    pub event_store: ActorRef<EventStoreMsg>,
+    pub supervisor: ActorRef<ApplicationSupervisorMsg>,
+    base_url: Option<String>,
+    server: Option<JoinHandle<()>>,
+    /// Last drained seq per actor id.
+    drained: Mutex<HashMap<String, i64>>,
+}
+
+impl TestSandbox {
+    /// In-memory EventStore plus the ApplicationSupervisor and its children.
+    pub async fn start() -> Self {
+        let (event_store, _) = Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+            .await
+            .expect("spawn in-memory EventStore");
+        let app_state = Arc::new(AppState::new(event_store.clone()));
+        let supervisor = app_state
+            .ensure_supervisor()
+            .await
+            .expect("spawn ApplicationSupervisor");
+        Self {
+            app_state,
+            event_store,
+            supervisor,
+            base_url: None,
+            server: None,
+            drained: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// `start()` plus the API router served on `127.0.0.1` at a random port.
+    pub async fn start_with_server() -> Self {
+        let mut sandbox = Self::start().await;
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind test server");
+        let addr = listener.local_addr().expect("test server address");
+        let app = api::router().with_state(ApiState::new(
+            sandbox.app_state.clone(),
+            Arc::new(tokio::sync::Mutex::new(HashMap::new())),
+        ));
+        sandbox.server = Some(tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        }));
+        sandbox.base_url = Some(format!("http://{addr}"));
+        sandbox
+    }
+
+    /// Absolute URL of `path` on the test server.
+    ///
+    /// Panics if the sandbox was started without one.
+    pub fn url(&self, path: &str) -> String {
+        let base = self
+            .base_url
+            .as_deref()
+            .expect("TestSandbox was started without a server");
+        format!("{base}{path}")
+    }
+
+    /// The supervised EventBus as of now; it changes when it is restarted.
+    pub async fn event_bus(&self) -> ActorRef<EventBusMsg> {
+        self.app_state
+            .event_bus()
+            .await
+            .expect("EventBus is running")
+    }
+
+    /// First event on `topic` (wildcards as in `Event::matches_topic`) that
+    /// satisfies `predicate`, or `None` once `timeout` passes.
+    ///
+    /// Committed events are checked before live ones, so an event appended
+    /// before the wait began is still found. Events published straight to
+    /// the bus without being stored are only seen if they arrive during the
+    /// wait.
+    pub async fn wait_for_event<F>(
+        &self,
+        topic: &str,
+        predicate: F,
+        timeout: Duration,
+    ) -> Option<Event>
+    where
+        F: Fn(&Event) -> bool,
+    {
+        let event_bus = self.event_bus().await;
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let (forwarder, _) = Actor::spawn(None, EventForwarder, tx)
+            .await
+            .expect("spawn event forwarder");
+        // Subscribe before reading the store: anything committed after the
+        // read is relayed after the subscription and arrives on `rx`.
+        event_bus
+            .cast(EventBusMsg::Subscribe {
+                topic: topic.to_string(),
+                subscriber: forwarder.clone(),
+            })
+            .expect("subscribe to EventBus");
+
+        let committed = self
+            .committed_events(topic)
+            .await
+            .into_iter()
+            .find(|event| predicate(event));
+        let found = match committed {
+            Some(event) => Some(event),
+            None => tokio::time::timeout(timeout, async {
+                while let Some(event) = rx.recv().await {
+                    if predicate(&event) {
+                        return Some(event);
+                    }
+                }
+                None
+            })
+            .await
+            .ok()
+            .flatten(),
+        };
+
+        let _ = event_bus.cast(EventBusMsg::Unsubscribe {
+            topic: topic.to_string(),
+            subscriber: forwarder.clone(),
+        });
+        forwarder.stop(None);
+        found
+    }
+
+    /// Committed events matching `topic`, in the form the relay publishes.
+    async fn committed_events(&self, topic: &str) -> Vec<Event> {
+        let prefix = topic.trim_end_matches('*').trim_end_matches('.');
+        let stored = ractor::call!(self.event_store, |reply| EventStoreMsg::GetRecentEvents {
+            since_seq: 0,
+            limit: COMMITTED_SCAN_LIMIT,
+            event_type_prefix: (!prefix.is_empty()).then(|| prefix.to_string()),
+            actor_id: None,
+            user_id: None,
+            reply,
+        })
+        .expect("EventStore RPC")
+        .expect("EventStore query");
+        stored
+            .into_iter()
+            .filter_map(|stored| relayed_event(stored).ok())
+            .filter(|event| event.matches_topic(topic))
+            .collect()
+    }
+
+    /// Events committed for `actor_id` since the previous drain of it.
+    pub async fn drain_events(&self, actor_id: &str) -> Vec<shared_types::Event> {
+        let since_seq = self
+            .drained
+            .lock()
+            .expect("drain cursors")
+            .get(actor_id)
+            .copied()
+            .unwrap_or(0);
+        let events = get_events_for_actor(&self.event_store, actor_id, since_seq)
+            .await
+            .expect("EventStore RPC")
+            .expect("EventStore query");
+        if let Some(last) = events.last() {
+            self.drained
+                .lock()
+                .expect("drain cursors")
+                .insert(actor_id.to_string(), last.seq);
+        }
+        events
+    }
+}
+
+impl Drop for TestSandbox {
+    fn drop(&mut self) {
+        if let Some(server) = self.server.take() {
+            server.abort();
+        }
+        self.supervisor.stop(None);
+        self.event_store.stop(None);
+    }
+}
+
+/// EventBus subscriber that hands events to a channel.
+struct EventForwarder;
+
+#[async_trait]
+impl Actor for EventForwarder {
+    type Msg = Event;
+    type State = mpsc::UnboundedSender<Event>;
+    type Arguments = mpsc::UnboundedSender<Event>;
+
+    async fn pre_start(
+        &self,
+        _myself: ActorRef<Self::Msg>,
+        tx: Self::Arguments,
+    ) -> Result<Self::State, ActorProcessingErr> {
+        Ok(tx)
+    }
+
+    async fn handle(
+        &self,
+        _myself: ActorRef<Self::Msg>,
+        event: Self::Msg,
+        tx: &mut Self::State,
+    ) -> Result<(), ActorProcessingErr> {
+        let _ = tx.send(event);
+        Ok(())
+    }
+}
@@ -0,0 +1,173 @@
+//! Size-bounded in-memory cache with sliding TTL expiry.
+//!
+//! Time is passed in by the caller, normally the timestamp of the event being
+//! handled, so expiry is deterministic under replay and in tests. Reads
+//! refresh both the TTL and the LRU position of an entry; once the cache is
+//! full, inserts evict the least recently used entry.
+
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+#[derive(Debug)]
+struct Entry<V> {
+    value: V,
+    touched_at: DateTime<Utc>,
+    tick: u64,
+}
+
+#[derive(Debug)]
+pub struct TtlCache<K, V> {
+    ttl: Duration,
+    max_entries: usize,
+    entries: HashMap<K, Entry<V>>,
+    /// Access tick -> key, oldest first.
+    recency: BTreeMap<u64, K>,
+    next_tick: u64,
+}
+
+impl<K: Eq + Hash + Clone, V> TtlCache<K, V> {
+    /// `max_entries` is clamped to at least one entry.
+    pub fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            ttl,
+            max_entries: max_entries.max(1),
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            next_tick: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Value for `key` if it was touched within the TTL. A hit counts as an
+    /// access; an expired entry is dropped.
+    pub fn get(&mut self, key: &K, now: DateTime<Utc>) -> Option<&V> {
+        let expired = self.is_expired(self.entries.get(key)?, now);
+        if expired {
+            self.remove(key);
+            return None;
+        }
+        let tick = self.bump_tick();
+        let entry = self.entries.get_mut(key)?;
+        self.recency.remove(&entry.tick);
+        self.recency.insert(tick, key.clone());
+        entry.tick = tick;
+        entry.touched_at = now;
+        Some(&entry.value)
+    }
+
+    /// Insert or replace `key`, returning the previous value. Evicts the least
+    /// recently used entry when the cache is over capacity.
+    pub fn insert(&mut self, key: K, value: V, now: DateTime<Utc>) -> Option<V> {
+        let previous = self.remove(&key);
+        let tick = self.bump_tick();
+        self.recency.insert(tick, key.clone());
+        self.entries.insert(
+            key,
+            Entry {
+                value,
+                touched_at: now,
+                tick,
+            },
+        );
+        while self.entries.len() > self.max_entries {
+            let Some((_, oldest)) = self.recency.pop_first() else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+        previous
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let entry = self.entries.remove(key)?;
+        self.recency.remove(&entry.tick);
+        Some(entry.value)
+    }
+
+    /// Drop every entry whose TTL has elapsed at `now`. Returns how many were
+    /// dropped.
+    pub fn evict_expired(&mut self, now: DateTime<Utc>) -> usize {
+        let expired: Vec<K> = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| self.is_expired(entry, now))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in &expired {
+            self.remove(key);
+        }
+        expired.len()
+    }
+
+    fn is_expired(&self, entry: &Entry<V>, now: DateTime<Utc>) -> bool {
+        now.signed_duration_since(entry.touched_at) >= self.ttl
+    }
+
+    fn bump_tick(&mut self) -> u64 {
+        let tick = self.next_tick;
+        self.next_tick += 1;
+        tick
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap()
+    }
+
+    #[test]
+    fn entries_expire_after_ttl() {
+        let mut cache = TtlCache::new(Duration::seconds(10), 8);
+        cache.insert("a", 1, at(0));
+        cache.insert("b", 2, at(5));
+
+        assert_eq!(cache.get(&"a", at(9)), Some(&1));
+        assert_eq!(cache.evict_expired(at(15)), 1);
+        assert_eq!(cache.get(&"b", at(15)), None);
+        assert_eq!(cache.get(&"a", at(15)), Some(&1));
+        assert_eq!(cache.get(&"a", at(25)), None);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn insert_evicts_least_recently_used_when_full() {
+        let mut cache = TtlCache::new(Duration::seconds(60), 2);
+        cache.insert("a", 1, at(0));
+        cache.insert("b", 2, at(1));
+        assert_eq!(cache.get(&"a", at(2)), Some(&1));
+
+        cache.insert("c", 3, at(3));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(&"b", at(4)), None);
+        assert_eq!(cache.get(&"a", at(4)), Some(&1));
+        assert_eq!(cache.get(&"c", at(4)), Some(&3));
+
+        // Replacing a key keeps the size and returns the old value.
+        assert_eq!(cache.insert("c", 30, at(5)), Some(3));
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn access_refreshes_ttl() {
+        let mut cache = TtlCache::new(Duration::seconds(10), 8);
+        cache.insert("a", 1, at(0));
+        cache.insert("b", 2, at(0));
+        for t in [8, 16, 24] {
+            assert_eq!(cache.get(&"a", at(t)), Some(&1));
+        }
+        assert_eq!(cache.evict_expired(at(30)), 1);
+        assert_eq!(cache.get(&"a", at(30)), Some(&1));
+        assert_eq!(cache.get(&"b", at(30)), None);
+    }
+}
@@ -10,6 +10,7 @@
 use sandbox::actors::agent_harness::alm::{
     AlmConfig, AlmHarness, AlmPort, AlmRunResult, AlmToolExecution, LlmCallResult,
 };
+use sandbox::actors::agent_harness::ObjectiveStatus;
 use sandbox::actors::model_config::{ModelRegistry, ProviderConfig};
 use sandbox::baml_client::types::ContextSourceKind;
 use sandbox::runtime_env::ensure_tls_cert_env;
@@ -421,8 +422,7 @@ async fn alm_harness_basic_scenarios() {
 
             match result {
                 Ok(Ok(run_result)) => {
-                    let is_complete = !run_result.completion_reason.starts_with("BLOCKED")
-                        && !run_result.completion_reason.starts_with("budget exhausted");
+                    let is_complete = run_result.objective_status == ObjectiveStatus::Complete;
 
                     print_alm_result(model_id, name, &run_result, elapsed_ms);
 
@@ -273,6 +273,7 @@ async fn test_resolve_source_returns_result_after_tool_result_event() {
             payload: result_payload,
             actor_id: format!("terminal:{corr_id}"),
             user_id: "system".to_string(),
+            idempotency_key: None,
         },
     });
 
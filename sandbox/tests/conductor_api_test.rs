@@ -31,10 +31,7 @@ async fn setup_test_app() -> (axum::Router, tempfile::TempDir) {
     let app_state = Arc::new(AppState::new(event_store));
     let ws_sessions: sandbox::api::websocket::WsSessions =
         Arc::new(tokio::sync::Mutex::new(HashMap::new()));
-    let api_state = api::ApiState {
-        app_state,
-        ws_sessions,
-    };
+    let api_state = api::ApiState::new(app_state, ws_sessions);
 
     let app = api::router().with_state(api_state);
     (app, temp_dir)
@@ -380,7 +377,7 @@ async fn test_conductor_execute_no_workers_returns_service_unavailable() {
     std::env::remove_var("CHOIR_DISABLE_CONDUCTOR_WORKERS");
 
     assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
-    assert_eq!(body["error"]["code"], "ACTOR_NOT_AVAILABLE");
+    assert_eq!(body["error"]["code"], "ACTOR_UNAVAILABLE");
 }
 
 #[tokio::test]
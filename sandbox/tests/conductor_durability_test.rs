@@ -43,6 +43,28 @@ async fn make_event_store() -> (ractor::ActorRef<EventStoreMsg>, tempfile::TempD
     (store, tmp)
 }
 
+/// Write a row straight into the event log, bypassing append validation,
+/// like one stored before the EventStore enforced required scope.
+async fn insert_legacy_event(
+    tmp: &tempfile::TempDir,
+    event_type: &str,
+    payload: serde_json::Value,
+) {
+    let db = tmp.path().join("conductor_durability_test.db");
+    let pool = sqlx::SqlitePool::connect(&format!("sqlite:{}", db.display()))
+        .await
+        .expect("open event log");
+    sqlx::query("INSERT INTO events (event_id, event_type, payload, actor_id) VALUES (?, ?, ?, ?)")
+        .bind(Uuid::new_v4().to_string())
+        .bind(event_type)
+        .bind(payload.to_string())
+        .bind("conductor")
+        .execute(&pool)
+        .await
+        .expect("insert legacy event");
+    pool.close().await;
+}
+
 /// Write a `conductor.run.started` event for the given run_id.
 /// This is the event that `restore_run_states` scans for on restart.
 async fn write_run_started_event(
@@ -61,6 +83,7 @@ async fn write_run_started_event(
             }),
             actor_id: "conductor".to_string(),
             user_id: "system".to_string(),
+            idempotency_key: None,
         },
     });
 }
@@ -77,6 +100,7 @@ async fn spawn_conductor(
             event_store,
             writer_supervisor: None,
             memory_actor: None,
+            capabilities: Default::default(),
         },
     )
     .await
@@ -260,25 +284,25 @@ async fn test_conductor_does_not_duplicate_on_duplicate_run_started_events() {
 ///
 /// The recovery loop does `payload.get("run_id").and_then(|v| v.as_str())` with
 /// a `continue` on `None`. This test ensures malformed events don't crash recovery
-/// or prevent other valid runs from being restored.
+/// or prevent other valid runs from being restored. The EventStore now refuses
+/// such appends in test builds, so the malformed row is written directly, as
+/// one stored before that check existed.
 #[tokio::test]
 async fn test_conductor_skips_malformed_run_started_events() {
-    let (event_store, _tmp) = make_event_store().await;
+    let (event_store, tmp) = make_event_store().await;
     let good_run_id = format!("run-good-{}", Uuid::new_v4().as_simple());
 
     // Write a malformed event (missing run_id) first
-    let _ = event_store.send_message(EventStoreMsg::AppendAsync {
-        event: AppendEvent {
-            event_type: "conductor.run.started".to_string(),
-            payload: serde_json::json!({
-                // NO run_id field — this is malformed
-                "objective": "malformed event",
-                "desktop_id": "desktop-bad",
-            }),
-            actor_id: "conductor".to_string(),
-            user_id: "system".to_string(),
-        },
-    });
+    insert_legacy_event(
+        &tmp,
+        "conductor.run.started",
+        serde_json::json!({
+            // NO run_id field — this is malformed
+            "objective": "malformed event",
+            "desktop_id": "desktop-bad",
+        }),
+    )
+    .await;
 
     // Write a valid event after the malformed one
     write_run_started_event(&event_store, &good_run_id, "valid run", "desktop-good").await;
@@ -375,6 +399,7 @@ async fn test_conductor_accepts_new_run_after_recovery() {
         desktop_id: "desktop-2".to_string(),
         output_mode: shared_types::ConductorOutputMode::Auto,
         hints: None,
+        contract: None,
     };
 
     let _result = ractor::call_t!(
@@ -0,0 +1,127 @@
+//! CORS policy tests against the API router.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::http::{header, HeaderMap, Method, Request, StatusCode};
+use ractor::Actor;
+use tower::ServiceExt;
+
+use sandbox::actors::event_store::{EventStoreActor, EventStoreArguments};
+use sandbox::api;
+use sandbox::app_state::AppState;
+use sandbox::cors::CorsConfig;
+
+async fn app(cors: CorsConfig) -> axum::Router {
+    let (event_store, _handle) = Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+        .await
+        .expect("Failed to create event store");
+    api::router()
+        .with_state(api::ApiState::new(
+            Arc::new(AppState::new(event_store)),
+            Arc::new(tokio::sync::Mutex::new(HashMap::new())),
+        ))
+        .layer(cors.layer())
+}
+
+async fn get_from(app: &axum::Router, origin: &str) -> (StatusCode, HeaderMap) {
+    let request = Request::builder()
+        .uri("/health")
+        .header(header::ORIGIN, origin)
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    (response.status(), response.headers().clone())
+}
+
+fn allowed_origin(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+        .and_then(|value| value.to_str().ok())
+}
+
+#[tokio::test]
+async fn test_allowed_origins_are_echoed() {
+    let app = app(CorsConfig::allowlist("https://choir-ip.com, http://localhost:*").unwrap()).await;
+
+    for origin in [
+        "https://choir-ip.com",
+        "http://localhost:3000",
+        "http://localhost:9090",
+    ] {
+        let (status, headers) = get_from(&app, origin).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(allowed_origin(&headers), Some(origin));
+    }
+
+    let preflight = Request::builder()
+        .method(Method::OPTIONS)
+        .uri("/api/events/export")
+        .header(header::ORIGIN, "http://localhost:3000")
+        .header(header::ACCESS_CONTROL_REQUEST_METHOD, "GET")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(preflight).await.unwrap();
+    assert_eq!(
+        allowed_origin(response.headers()),
+        Some("http://localhost:3000")
+    );
+    assert!(response
+        .headers()
+        .contains_key(header::ACCESS_CONTROL_ALLOW_METHODS));
+}
+
+#[tokio::test]
+async fn test_preflight_allows_idempotency_key() {
+    let app = app(CorsConfig::allowlist("http://localhost:*").unwrap()).await;
+
+    let preflight = Request::builder()
+        .method(Method::OPTIONS)
+        .uri("/api/events")
+        .header(header::ORIGIN, "http://localhost:3000")
+        .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+        .header(
+            header::ACCESS_CONTROL_REQUEST_HEADERS,
+            "content-type,idempotency-key",
+        )
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(preflight).await.unwrap();
+    let allowed = response.headers()[header::ACCESS_CONTROL_ALLOW_HEADERS]
+        .to_str()
+        .unwrap()
+        .to_ascii_lowercase();
+    assert!(allowed.contains("idempotency-key"), "{allowed}");
+}
+
+#[tokio::test]
+async fn test_disallowed_origins_get_no_cors_headers() {
+    let app = app(CorsConfig::allowlist("https://choir-ip.com").unwrap()).await;
+
+    for origin in [
+        "https://evil.example",
+        "http://choir-ip.com",
+        "https://choir-ip.com:8443",
+        "http://localhost:3000",
+    ] {
+        let (status, headers) = get_from(&app, origin).await;
+        // The request is still served; the browser withholds the response.
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(allowed_origin(&headers), None, "{origin} was allowed");
+    }
+}
+
+#[tokio::test]
+async fn test_dev_mode_reflects_any_origin() {
+    let app = app(CorsConfig::Permissive).await;
+
+    let (_, headers) = get_from(&app, "http://192.168.1.20:5173").await;
+    assert_eq!(allowed_origin(&headers), Some("http://192.168.1.20:5173"));
+}
+
+#[test]
+fn test_invalid_origin_list_is_an_error() {
+    let err = CorsConfig::allowlist("https://choir-ip.com,choir-ip.com").unwrap_err();
+    assert_eq!(err.origin, "choir-ip.com");
+}
@@ -18,6 +18,7 @@
 use sandbox::actors::agent_harness::alm::{
     AlmConfig, AlmHarness, AlmPort, AlmRunResult, AlmToolExecution, LlmCallResult,
 };
+use sandbox::actors::agent_harness::ObjectiveStatus;
 use sandbox::actors::model_config::{ModelRegistry, ProviderConfig};
 use sandbox::baml_client::types::ContextSourceKind;
 use sandbox::baml_client::B;
@@ -698,8 +699,7 @@ fn scenarios() -> Vec<Scenario> {
             objective: "Run the command `echo DAG_EVAL_OK` using bash and report the output.",
             expected_mode: "ToolCalls",
             validate: |r| {
-                let completed = !r.completion_reason.starts_with("BLOCKED")
-                    && !r.completion_reason.starts_with("budget");
+                let completed = r.objective_status == ObjectiveStatus::Complete;
                 let has_tool = r.tool_executions.iter().any(|t| t.tool_name == "bash");
                 (
                     completed && has_tool,
@@ -712,8 +712,7 @@ fn scenarios() -> Vec<Scenario> {
             objective: "Read the file at Cargo.toml and tell me what workspace members are defined.",
             expected_mode: "ToolCalls",
             validate: |r| {
-                let completed = !r.completion_reason.starts_with("BLOCKED")
-                    && !r.completion_reason.starts_with("budget");
+                let completed = r.objective_status == ObjectiveStatus::Complete;
                 (completed, format!("completed={completed}"))
             },
         },
@@ -725,8 +724,7 @@ fn scenarios() -> Vec<Scenario> {
                         This requires a Program with steps that depend on each other's outputs.",
             expected_mode: "Program",
             validate: |r| {
-                let completed = !r.completion_reason.starts_with("BLOCKED")
-                    && !r.completion_reason.starts_with("budget");
+                let completed = r.objective_status == ObjectiveStatus::Complete;
                 let used_program = r.turn_log.iter().any(|t| t.action_kind == "Program");
                 let has_dag = !r.dag_traces.is_empty();
                 (
@@ -744,8 +742,7 @@ fn scenarios() -> Vec<Scenario> {
                         Use a Program with a Gate step to conditionally branch based on the output.",
             expected_mode: "Program",
             validate: |r| {
-                let completed = !r.completion_reason.starts_with("BLOCKED")
-                    && !r.completion_reason.starts_with("budget");
+                let completed = r.objective_status == ObjectiveStatus::Complete;
                 let used_program = r.turn_log.iter().any(|t| t.action_kind == "Program");
                 (
                     completed,
@@ -765,8 +762,7 @@ fn scenarios() -> Vec<Scenario> {
                         You MUST use kind=Program with op=LlmCall steps, not just ToolCalls.",
             expected_mode: "Program",
             validate: |r| {
-                let completed = !r.completion_reason.starts_with("BLOCKED")
-                    && !r.completion_reason.starts_with("budget");
+                let completed = r.objective_status == ObjectiveStatus::Complete;
                 let used_program = r.turn_log.iter().any(|t| t.action_kind == "Program");
                 let has_llm_in_dag = r.dag_traces.iter().any(|d| {
                     d.steps.iter().any(|s| s.op == "LlmCall" && s.success)
@@ -791,8 +787,7 @@ fn scenarios() -> Vec<Scenario> {
                         You MUST use kind=Program for this.",
             expected_mode: "Program",
             validate: |r| {
-                let completed = !r.completion_reason.starts_with("BLOCKED")
-                    && !r.completion_reason.starts_with("budget");
+                let completed = r.objective_status == ObjectiveStatus::Complete;
                 let used_program = r.turn_log.iter().any(|t| t.action_kind == "Program");
                 let has_transform = r.dag_traces.iter().any(|d| {
                     d.steps.iter().any(|s| s.op == "Transform" && s.success)
@@ -55,10 +55,7 @@ async fn setup_test_app() -> TestApp {
     let app_state = Arc::new(AppState::new(event_store));
     let ws_sessions: sandbox::api::websocket::WsSessions =
         Arc::new(tokio::sync::Mutex::new(HashMap::new()));
-    let api_state = api::ApiState {
-        app_state,
-        ws_sessions,
-    };
+    let api_state = api::ApiState::new(app_state, ws_sessions);
 
     let app = api::router().with_state(api_state);
     TestApp {
@@ -98,6 +95,22 @@ async fn test_health_check() {
     assert!(body["hostname"].is_string());
 }
 
+#[tokio::test]
+async fn test_readiness_check() {
+    let app = setup_test_app().await;
+
+    let req = Request::builder()
+        .method("GET")
+        .uri("/health/ready")
+        .body(Body::empty())
+        .unwrap();
+
+    let (status, body) = json_response(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "ready");
+    assert_eq!(body["event_store"]["writable"], true);
+}
+
 #[tokio::test]
 async fn test_get_desktop_state_empty() {
     let app = setup_test_app().await;
@@ -262,9 +275,12 @@ async fn test_open_window_unknown_app_fails() {
         .unwrap();
 
     let (status, body) = json_response(&app, req).await;
-    assert_eq!(status, StatusCode::BAD_REQUEST);
-    assert!(!body["success"].as_bool().unwrap());
-    assert!(body["error"].as_str().unwrap().contains("not found"));
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"]["code"], "NOT_FOUND");
+    assert!(body["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("not found"));
 }
 
 #[tokio::test]
@@ -553,7 +569,72 @@ async fn test_resize_window_invalid_bounds_rejected() {
 
     let (status, body) = json_response(&app, req).await;
     assert_eq!(status, StatusCode::BAD_REQUEST);
-    assert!(!body["success"].as_bool().unwrap());
+    assert_eq!(body["error"]["code"], "INVALID_REQUEST");
+}
+
+#[tokio::test]
+async fn test_move_window_off_screen_is_clamped_to_desktop_bounds() {
+    let app = setup_test_app().await;
+    let desktop_id = test_desktop_id();
+
+    let app_def = json!({
+        "id": "test-app",
+        "name": "Test App",
+        "icon": "🧩",
+        "component_code": "TestAppView",
+        "default_width": 400,
+        "default_height": 600
+    });
+
+    let req = Request::builder()
+        .method("POST")
+        .uri(format!("/desktop/{desktop_id}/apps"))
+        .header("content-type", "application/json")
+        .body(Body::from(app_def.to_string()))
+        .unwrap();
+    let _ = json_response(&app, req).await;
+
+    let bounds_req = json!({ "x": 0, "y": 0, "width": 1280, "height": 800 });
+    let req = Request::builder()
+        .method("PATCH")
+        .uri(format!("/desktop/{desktop_id}/bounds"))
+        .header("content-type", "application/json")
+        .body(Body::from(bounds_req.to_string()))
+        .unwrap();
+    let (status, _body) = json_response(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let open_req = json!({
+        "app_id": "test-app",
+        "title": "Test App Window",
+        "props": null
+    });
+
+    let req = Request::builder()
+        .method("POST")
+        .uri(format!("/desktop/{desktop_id}/windows"))
+        .header("content-type", "application/json")
+        .body(Body::from(open_req.to_string()))
+        .unwrap();
+    let (_status, body) = json_response(&app, req).await;
+    let window_id = body["window"]["id"].as_str().unwrap();
+
+    let move_req = json!({ "x": 4000, "y": -500 });
+    let req = Request::builder()
+        .method("PATCH")
+        .uri(format!(
+            "/desktop/{desktop_id}/windows/{window_id}/position"
+        ))
+        .header("content-type", "application/json")
+        .body(Body::from(move_req.to_string()))
+        .unwrap();
+
+    let (status, body) = json_response(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+    let x = body["window"]["x"].as_i64().unwrap();
+    let y = body["window"]["y"].as_i64().unwrap();
+    assert!(x < 1280, "title bar should stay on screen, got x={x}");
+    assert_eq!(y, 0);
 }
 
 #[tokio::test]
@@ -675,7 +756,7 @@ async fn test_minimize_maximize_restore_endpoints() {
         .unwrap();
     let (status, body) = json_response(&app, req).await;
     assert_eq!(status, StatusCode::BAD_REQUEST);
-    assert!(!body["success"].as_bool().unwrap());
+    assert_eq!(body["error"]["code"], "INVALID_REQUEST");
 
     let req = Request::builder()
         .method("POST")
@@ -868,8 +949,8 @@ async fn test_new_window_endpoints_reject_unknown_window() {
             .unwrap();
 
         let (status, body) = json_response(&app, req).await;
-        assert_eq!(status, StatusCode::BAD_REQUEST);
-        assert!(!body["success"].as_bool().unwrap());
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["code"], "NOT_FOUND");
     }
 }
 
@@ -930,6 +1011,112 @@ async fn test_get_apps_after_register() {
     assert!(app_ids.contains(&"test-app".to_string()));
 }
 
+#[tokio::test]
+async fn test_desktop_templates_list_builtins_and_reject_their_ids() {
+    let app = setup_test_app().await;
+
+    let req = Request::builder()
+        .method("GET")
+        .uri("/desktop-templates")
+        .body(Body::empty())
+        .unwrap();
+    let (status, body) = json_response(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+    let ids: Vec<&str> = body["templates"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["id"].as_str().unwrap())
+        .collect();
+    assert!(ids.contains(&"research"));
+    assert!(ids.contains(&"coding"));
+
+    let req = Request::builder()
+        .method("POST")
+        .uri("/desktop-templates")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            json!({ "id": "coding", "name": "Mine" }).to_string(),
+        ))
+        .unwrap();
+    let (status, body) = json_response(&app, req).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "INVALID_REQUEST");
+}
+
+#[tokio::test]
+async fn test_create_desktop_from_template_is_idempotent() {
+    let app = setup_test_app().await;
+    let desktop_id = test_desktop_id();
+    let seed_dir = format!("template-test-{}", uuid::Uuid::new_v4());
+    let seed_path = format!("{seed_dir}/notes.md");
+
+    let template = json!({
+        "id": format!("notes-{desktop_id}"),
+        "name": "Notes",
+        "windows": [
+            { "app_id": "test-app", "title": "Notes", "props": { "path": seed_path } },
+            { "app_id": "test-app", "title": "Scratch" }
+        ],
+        "seed_files": [{ "path": seed_path, "content": "# Notes\n" }]
+    });
+    let req = Request::builder()
+        .method("POST")
+        .uri("/desktop-templates")
+        .header("content-type", "application/json")
+        .body(Body::from(template.to_string()))
+        .unwrap();
+    let (status, _) = json_response(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let create = json!({
+        "template_id": template["id"],
+        "apps": [{
+            "id": "test-app",
+            "name": "Test App",
+            "icon": "🧩",
+            "component_code": "TestAppView",
+            "default_width": 400,
+            "default_height": 600
+        }]
+    });
+    let create_request = || {
+        Request::builder()
+            .method("POST")
+            .uri(format!("/desktop/{desktop_id}"))
+            .header("content-type", "application/json")
+            .body(Body::from(create.to_string()))
+            .unwrap()
+    };
+
+    let (status, body) = json_response(&app, create_request()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["opened_windows"].as_array().unwrap().len(), 2);
+    assert_eq!(body["seeded_files"], json!([seed_path]));
+    assert_eq!(body["desktop"]["windows"].as_array().unwrap().len(), 2);
+
+    let seed_file = sandbox::paths::sandbox_root().join(&seed_path);
+    std::fs::write(&seed_file, "# Edited\n").unwrap();
+
+    let (status, body) = json_response(&app, create_request()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["opened_windows"].as_array().unwrap().is_empty());
+    assert!(body["seeded_files"].as_array().unwrap().is_empty());
+    assert_eq!(body["desktop"]["windows"].as_array().unwrap().len(), 2);
+    assert_eq!(std::fs::read_to_string(&seed_file).unwrap(), "# Edited\n");
+
+    let _ = std::fs::remove_dir_all(sandbox::paths::sandbox_root().join(&seed_dir));
+
+    let req = Request::builder()
+        .method("POST")
+        .uri(format!("/desktop/{desktop_id}"))
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "template_id": "missing" }).to_string()))
+        .unwrap();
+    let (status, _) = json_response(&app, req).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
 #[tokio::test]
 async fn test_desktop_state_persists_events() {
     let app = setup_test_app().await;
@@ -1055,6 +1242,6 @@ async fn test_update_user_preferences_rejects_invalid_theme() {
 
     let (status, body) = json_response(&app, req).await;
     assert_eq!(status, StatusCode::BAD_REQUEST);
-    assert!(!body["success"].as_bool().unwrap());
-    assert_eq!(body["error"], "theme must be 'light' or 'dark'");
+    assert_eq!(body["error"]["code"], "INVALID_REQUEST");
+    assert_eq!(body["error"]["message"], "theme must be 'light' or 'dark'");
 }
@@ -10,33 +10,25 @@
 #[cfg(feature = "supervision_refactor")]
 mod desktop_supervision_tests {
     use ractor::Actor;
-    use sandbox::actors::desktop::{get_desktop_state, DesktopActorMsg, DesktopArguments};
+    use sandbox::actors::desktop::{
+        get_desktop_state, DesktopActorMsg, DesktopArguments, DESKTOP_SNAPSHOT_INTERVAL,
+    };
     use sandbox::actors::event_store::{EventStoreActor, EventStoreArguments};
-    use sandbox::supervisor::{ApplicationSupervisor, ApplicationSupervisorMsg};
+    use sandbox::supervisor::ApplicationSupervisorMsg;
+    use sandbox::testkit::TestSandbox;
     use tokio::time::Duration;
 
+    const WAIT: Duration = Duration::from_secs(2);
+
     /// Test that DesktopSupervisor creates desktop actors
     #[tokio::test]
     async fn test_desktop_supervisor_creates_desktop() {
         tracing::info!("Testing DesktopSupervisor creates desktop actors...");
 
-        // Create EventStore first
-        let (event_store, _event_handle) =
-            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
-                .await
-                .expect("Failed to spawn EventStoreActor");
-
-        // Spawn ApplicationSupervisor (which will spawn SessionSupervisor and DesktopSupervisor)
-        let (app_supervisor, _app_handle) = Actor::spawn(
-            Some("test_app_supervisor_creates".to_string()),
-            ApplicationSupervisor,
-            event_store.clone(),
-        )
-        .await
-        .expect("Failed to spawn ApplicationSupervisor");
-
-        // Wait for the supervision tree to initialize
-        tokio::time::sleep(Duration::from_millis(200)).await;
+        // In-memory EventStore plus ApplicationSupervisor, which spawns
+        // SessionSupervisor and DesktopSupervisor before it returns
+        let sandbox = TestSandbox::start().await;
+        let app_supervisor = sandbox.supervisor.clone();
 
         // Get or create a desktop via ApplicationSupervisor
         let desktop_result = ractor::call!(&app_supervisor, |reply| {
@@ -68,6 +60,20 @@ mod desktop_supervision_tests {
         assert_eq!(desktop_id, "test-desktop-1");
         assert_eq!(user_id, "user-1");
 
+        // The delegation is recorded against the actor it returned
+        let completed = sandbox
+            .wait_for_event(
+                "supervisor.desktop.get_or_create.completed",
+                |event| event.payload["desktop_id"] == "test-desktop-1",
+                WAIT,
+            )
+            .await
+            .expect("get_or_create.completed should be committed");
+        assert_eq!(
+            completed.payload["actor_id"],
+            desktop_ref.get_id().to_string()
+        );
+
         // Get desktop state to verify it's working
         let state = get_desktop_state(&desktop_ref).await;
         assert!(state.is_ok(), "Should be able to get desktop state");
@@ -80,23 +86,10 @@ mod desktop_supervision_tests {
     async fn test_desktop_supervisor_uses_registry() {
         tracing::info!("Testing DesktopSupervisor registry discovery...");
 
-        // Create EventStore
-        let (event_store, _event_handle) =
-            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
-                .await
-                .expect("Failed to spawn EventStoreActor");
-
-        // Spawn ApplicationSupervisor
-        let (app_supervisor, _app_handle) = Actor::spawn(
-            Some("test_app_supervisor_registry".to_string()),
-            ApplicationSupervisor,
-            event_store.clone(),
-        )
-        .await
-        .expect("Failed to spawn ApplicationSupervisor");
-
-        // Wait for initialization
-        tokio::time::sleep(Duration::from_millis(200)).await;
+        // In-memory EventStore plus ApplicationSupervisor, which spawns
+        // SessionSupervisor and DesktopSupervisor before it returns
+        let sandbox = TestSandbox::start().await;
+        let app_supervisor = sandbox.supervisor.clone();
 
         // Create a desktop
         let desktop_result = ractor::call!(&app_supervisor, |reply| {
@@ -150,23 +143,10 @@ mod desktop_supervision_tests {
     async fn test_desktop_supervisor_creates_new_after_termination() {
         tracing::info!("Testing DesktopSupervisor creates new actor after termination...");
 
-        // Create EventStore
-        let (event_store, _event_handle) =
-            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
-                .await
-                .expect("Failed to spawn EventStoreActor");
-
-        // Spawn ApplicationSupervisor
-        let (app_supervisor, _app_handle) = Actor::spawn(
-            Some("test_app_supervisor_termination".to_string()),
-            ApplicationSupervisor,
-            event_store.clone(),
-        )
-        .await
-        .expect("Failed to spawn ApplicationSupervisor");
-
-        // Wait for initialization
-        tokio::time::sleep(Duration::from_millis(200)).await;
+        // In-memory EventStore plus ApplicationSupervisor, which spawns
+        // SessionSupervisor and DesktopSupervisor before it returns
+        let sandbox = TestSandbox::start().await;
+        let app_supervisor = sandbox.supervisor.clone();
 
         // Create a desktop
         let desktop_result = ractor::call!(&app_supervisor, |reply| {
@@ -197,10 +177,12 @@ mod desktop_supervision_tests {
         assert!(open_result.is_ok());
 
         // Stop the actor (normal termination - this removes it from tracking)
-        desktop_ref_1.stop(Some("Test stop".to_string()));
-
-        // Wait for the supervision chain to process the termination
-        tokio::time::sleep(Duration::from_millis(500)).await;
+        // Stop it and wait until it has terminated; the supervisors see the
+        // termination before they handle the next request
+        desktop_ref_1
+            .stop_and_wait(Some("Test stop".to_string()), Some(WAIT))
+            .await
+            .expect("DesktopActor should stop");
 
         // Get the desktop again - should create a new actor with same identity
         let desktop_result_2 = ractor::call!(&app_supervisor, |reply| {
@@ -240,23 +222,10 @@ mod desktop_supervision_tests {
     async fn test_desktop_supervisor_preserves_identity() {
         tracing::info!("Testing DesktopSupervisor identity preservation...");
 
-        // Create EventStore
-        let (event_store, _event_handle) =
-            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
-                .await
-                .expect("Failed to spawn EventStoreActor");
-
-        // Spawn ApplicationSupervisor
-        let (app_supervisor, _app_handle) = Actor::spawn(
-            Some("test_app_supervisor_identity".to_string()),
-            ApplicationSupervisor,
-            event_store.clone(),
-        )
-        .await
-        .expect("Failed to spawn ApplicationSupervisor");
-
-        // Wait for initialization
-        tokio::time::sleep(Duration::from_millis(200)).await;
+        // In-memory EventStore plus ApplicationSupervisor, which spawns
+        // SessionSupervisor and DesktopSupervisor before it returns
+        let sandbox = TestSandbox::start().await;
+        let app_supervisor = sandbox.supervisor.clone();
 
         let desktop_id = "test-identity-desktop";
         let user_id = "identity-user";
@@ -284,10 +253,12 @@ mod desktop_supervision_tests {
         assert_eq!(retrieved_user_id, user_id);
 
         // Stop the actor (normal termination)
-        desktop_ref.stop(Some("Test identity".to_string()));
-
-        // Wait for the supervision chain to process the termination
-        tokio::time::sleep(Duration::from_millis(500)).await;
+        // Stop it and wait until it has terminated; the supervisors see the
+        // termination before they handle the next request
+        desktop_ref
+            .stop_and_wait(Some("Test identity".to_string()), Some(WAIT))
+            .await
+            .expect("DesktopActor should stop");
 
         // Get the desktop again - should create a new actor with same identity
         let desktop_result_2 = ractor::call!(&app_supervisor, |reply| {
@@ -347,9 +318,6 @@ mod desktop_supervision_tests {
         .await
         .expect("Failed to spawn DesktopSupervisor");
 
-        // Wait for initialization
-        tokio::time::sleep(Duration::from_millis(100)).await;
-
         let desktop_id = "intensity-test-desktop";
         let user_id = "intensity-user";
 
@@ -358,6 +326,7 @@ mod desktop_supervision_tests {
             desktop_id: desktop_id.to_string(),
             user_id: user_id.to_string(),
             event_store: event_store.clone(),
+            snapshot_interval: DESKTOP_SNAPSHOT_INTERVAL,
         };
 
         // Create a desktop
@@ -381,8 +350,10 @@ mod desktop_supervision_tests {
         let _actor_id = desktop_ref.get_id();
 
         // Stop the actor (first restart trigger)
-        desktop_ref.stop(Some("Test 1".to_string()));
-        tokio::time::sleep(Duration::from_millis(300)).await;
+        desktop_ref
+            .stop_and_wait(Some("Test 1".to_string()), Some(WAIT))
+            .await
+            .expect("DesktopActor should stop");
 
         // Verify it was restarted (request should still work)
         let desktop_result_2 = ractor::call!(&desktop_supervisor, |reply| {
@@ -402,8 +373,10 @@ mod desktop_supervision_tests {
         let desktop_ref_2 = desktop_result_2.unwrap();
 
         // Stop again (second restart trigger)
-        desktop_ref_2.stop(Some("Test 2".to_string()));
-        tokio::time::sleep(Duration::from_millis(300)).await;
+        desktop_ref_2
+            .stop_and_wait(Some("Test 2".to_string()), Some(WAIT))
+            .await
+            .expect("DesktopActor should stop");
 
         // Verify second restart worked
         let desktop_result_3 = ractor::call!(&desktop_supervisor, |reply| {
@@ -428,23 +401,10 @@ mod desktop_supervision_tests {
     async fn test_multiple_desktops() {
         tracing::info!("Testing multiple desktop creation...");
 
-        // Create EventStore
-        let (event_store, _event_handle) =
-            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
-                .await
-                .expect("Failed to spawn EventStoreActor");
-
-        // Spawn ApplicationSupervisor
-        let (app_supervisor, _app_handle) = Actor::spawn(
-            Some("test_app_supervisor_multi".to_string()),
-            ApplicationSupervisor,
-            event_store.clone(),
-        )
-        .await
-        .expect("Failed to spawn ApplicationSupervisor");
-
-        // Wait for initialization
-        tokio::time::sleep(Duration::from_millis(200)).await;
+        // In-memory EventStore plus ApplicationSupervisor, which spawns
+        // SessionSupervisor and DesktopSupervisor before it returns
+        let sandbox = TestSandbox::start().await;
+        let app_supervisor = sandbox.supervisor.clone();
 
         // Create multiple desktops
         let desktops = vec![
@@ -10,10 +10,13 @@ use std::sync::Arc;
 use std::time::Duration;
 use tokio::net::TcpListener;
 use tokio::time::timeout;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::{CloseCode, Data, OpCode};
+use tokio_tungstenite::tungstenite::protocol::frame::Frame;
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 
 use sandbox::actors::event_store::{EventStoreActor, EventStoreArguments};
 use sandbox::api;
+use sandbox::api::websocket::{MAX_INBOUND_MESSAGE_BYTES, MAX_INBOUND_MESSAGE_HARD_BYTES};
 use sandbox::app_state::AppState;
 
 fn test_desktop_id() -> String {
@@ -48,10 +51,7 @@ async fn start_test_server() -> TestServer {
     let app_state = Arc::new(AppState::new(event_store));
     let ws_sessions: sandbox::api::websocket::WsSessions =
         Arc::new(tokio::sync::Mutex::new(HashMap::new()));
-    let api_state = api::ApiState {
-        app_state,
-        ws_sessions,
-    };
+    let api_state = api::ApiState::new(app_state, ws_sessions);
 
     let app: Router = api::router().with_state(api_state);
     let listener = TcpListener::bind("127.0.0.1:0")
@@ -258,6 +258,70 @@ async fn test_desktop_ws_emits_window_delta_after_mutation() {
     assert_eq!(moved["y"], 123);
 }
 
+#[tokio::test]
+async fn test_desktop_ws_unsubscribe_stops_forwarding_a_desktop() {
+    let server = start_test_server().await;
+    let (left, right) = (test_desktop_id(), test_desktop_id());
+    let client = reqwest::Client::new();
+    let register = json!({
+        "id": "test-app",
+        "name": "Test App",
+        "icon": "🧩",
+        "component_code": "TestAppView",
+        "default_width": 400,
+        "default_height": 600
+    });
+    for desktop_id in [&left, &right] {
+        let _ = post_json(
+            &client,
+            server.addr,
+            &format!("/desktop/{desktop_id}/apps"),
+            register.clone(),
+        )
+        .await;
+    }
+
+    let (mut ws, _) = connect_async(ws_url(server.addr, "/ws"))
+        .await
+        .expect("ws connect failed");
+    let _ = recv_json(&mut ws).await;
+
+    // Switch desktops the way the UI does: drop the old one, add the new one.
+    send_json(&mut ws, json!({ "type": "subscribe", "desktop_id": left })).await;
+    let _ = wait_for_type(&mut ws, "desktop_state").await;
+    send_json(
+        &mut ws,
+        json!({ "type": "unsubscribe", "desktop_id": left }),
+    )
+    .await;
+    send_json(&mut ws, json!({ "type": "subscribe", "desktop_id": right })).await;
+    let _ = wait_for_type(&mut ws, "desktop_state").await;
+
+    let addr = server.addr;
+    let open_window = |desktop_id: String| {
+        let client = client.clone();
+        async move {
+            post_json(
+                &client,
+                addr,
+                &format!("/desktop/{desktop_id}/windows"),
+                json!({ "app_id": "test-app", "title": "Test App", "props": null }),
+            )
+            .await
+            .json::<Value>()
+            .await
+            .unwrap()["window"]["id"]
+                .clone()
+        }
+    };
+    let _ = open_window(left.clone()).await;
+    let right_window = open_window(right.clone()).await;
+
+    // Had the left desktop stayed subscribed, its window would arrive first.
+    let opened = wait_for_type(&mut ws, "window_opened").await;
+    assert_eq!(opened["window"]["id"], right_window);
+}
+
 #[tokio::test]
 async fn test_desktop_ws_delta_order_matches_mutation_order() {
     let server = start_test_server().await;
@@ -389,3 +453,89 @@ async fn test_desktop_ws_emits_app_registered_after_subscription() {
     assert_eq!(registered["app"]["id"], "inspector");
     assert_eq!(registered["app"]["name"], "Inspector");
 }
+
+#[tokio::test]
+async fn test_desktop_ws_rejects_oversized_message_and_stays_open() {
+    let server = start_test_server().await;
+    let (mut ws, _) = connect_async(ws_url(server.addr, "/ws"))
+        .await
+        .expect("Failed to connect");
+    let _ = wait_for_type(&mut ws, "pong").await;
+
+    let padding = "x".repeat(MAX_INBOUND_MESSAGE_BYTES);
+    send_json(
+        &mut ws,
+        json!({ "type": "subscribe", "desktop_id": padding }),
+    )
+    .await;
+
+    let error = wait_for_type(&mut ws, "error").await;
+    assert_eq!(error["error_code"], "message_too_large");
+
+    send_json(&mut ws, json!({ "type": "ping" })).await;
+    let _ = wait_for_type(&mut ws, "pong").await;
+}
+
+#[tokio::test]
+async fn test_desktop_ws_counts_fragments_toward_message_size() {
+    let server = start_test_server().await;
+    let (mut ws, _) = connect_async(ws_url(server.addr, "/ws"))
+        .await
+        .expect("Failed to connect");
+    let _ = wait_for_type(&mut ws, "pong").await;
+
+    // Every fragment is well under the limit, but the reassembled message is not.
+    let fragment = vec![b' '; MAX_INBOUND_MESSAGE_BYTES / 4];
+    let frames = [
+        Frame::message(
+            b"{\"type\":\"ping\"}".to_vec(),
+            OpCode::Data(Data::Text),
+            false,
+        ),
+        Frame::message(fragment.clone(), OpCode::Data(Data::Continue), false),
+        Frame::message(fragment.clone(), OpCode::Data(Data::Continue), false),
+        Frame::message(fragment.clone(), OpCode::Data(Data::Continue), false),
+        Frame::message(fragment, OpCode::Data(Data::Continue), true),
+    ];
+    for frame in frames {
+        ws.send(Message::Frame(frame)).await.expect("Send error");
+    }
+
+    let error = wait_for_type(&mut ws, "error").await;
+    assert_eq!(error["error_code"], "message_too_large");
+
+    send_json(&mut ws, json!({ "type": "ping" })).await;
+    let _ = wait_for_type(&mut ws, "pong").await;
+}
+
+#[tokio::test]
+async fn test_desktop_ws_closes_connection_for_egregious_message() {
+    let server = start_test_server().await;
+    let (mut ws, _) = connect_async(ws_url(server.addr, "/ws"))
+        .await
+        .expect("Failed to connect");
+    let _ = wait_for_type(&mut ws, "pong").await;
+
+    // The server may stop reading part-way through, so the send can fail.
+    let _ = ws
+        .send(Message::Text(
+            "x".repeat(MAX_INBOUND_MESSAGE_HARD_BYTES + 1),
+        ))
+        .await;
+
+    let mut close_code = None;
+    loop {
+        match timeout(Duration::from_secs(5), ws.next()).await {
+            Ok(Some(Ok(Message::Close(frame)))) => {
+                close_code = frame.map(|frame| frame.code);
+                break;
+            }
+            Ok(Some(Ok(_))) => continue,
+            Ok(Some(Err(_))) | Ok(None) => break,
+            Err(_) => panic!("Timeout waiting for the connection to close"),
+        }
+    }
+    // Unread request bytes usually make the close a TCP reset, so the close
+    // frame itself may not arrive.
+    assert!(matches!(close_code, None | Some(CloseCode::Size)));
+}
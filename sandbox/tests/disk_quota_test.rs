@@ -0,0 +1,173 @@
+//! Workspace disk quota enforcement and usage reporting.
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use http_body_util::BodyExt;
+use ractor::Actor;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+use sandbox::actors::event_store::EventStoreMsg;
+use sandbox::actors::{EventStoreActor, EventStoreArguments};
+use sandbox::api;
+use sandbox::app_state::AppState;
+use sandbox::disk_usage::DiskUsageTracker;
+
+async fn setup_test_app(
+    quota_bytes: u64,
+) -> (
+    axum::Router,
+    tempfile::TempDir,
+    ractor::ActorRef<EventStoreMsg>,
+) {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp directory");
+    let db_path = temp_dir.path().join("test_events.db");
+    let db_path_str = db_path.to_str().expect("Invalid database path");
+
+    let (event_store, _handle) = Actor::spawn(
+        None,
+        EventStoreActor,
+        EventStoreArguments::File(db_path_str.to_string()),
+    )
+    .await
+    .expect("Failed to create event store");
+
+    let workspace = temp_dir.path().join("workspace");
+    std::fs::create_dir_all(workspace.join("conductor/runs/old_run")).unwrap();
+    std::fs::write(
+        workspace.join("conductor/runs/old_run/draft.md"),
+        vec![b'x'; 700],
+    )
+    .unwrap();
+
+    let app_state = Arc::new(AppState::with_disk_usage(
+        event_store.clone(),
+        DiskUsageTracker::new(workspace, Some(quota_bytes)),
+    ));
+    let ws_sessions: sandbox::api::websocket::WsSessions =
+        Arc::new(tokio::sync::Mutex::new(HashMap::new()));
+    let api_state = api::ApiState::new(app_state, ws_sessions);
+
+    let app = api::router().with_state(api_state);
+    (app, temp_dir, event_store)
+}
+
+async fn json_response(app: &axum::Router, req: Request<Body>) -> (StatusCode, Value) {
+    let response = app.clone().oneshot(req).await.expect("Request failed");
+    let status = response.status();
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("Failed to read body")
+        .to_bytes();
+    let value: Value = serde_json::from_slice(&body).expect("Invalid JSON response");
+    (status, value)
+}
+
+async fn get(app: &axum::Router, uri: &str) -> (StatusCode, Value) {
+    let req = Request::builder().uri(uri).body(Body::empty()).unwrap();
+    json_response(app, req).await
+}
+
+async fn post(app: &axum::Router, uri: &str, body: Value) -> (StatusCode, Value) {
+    let req = Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    json_response(app, req).await
+}
+
+async fn quota_warnings(event_store: &ractor::ActorRef<EventStoreMsg>) -> Vec<u64> {
+    ractor::call!(event_store, |reply| EventStoreMsg::GetRecentEvents {
+        since_seq: 0,
+        limit: 100,
+        event_type_prefix: Some(shared_types::EVENT_TOPIC_WORKSPACE_QUOTA_WARNING.to_string()),
+        actor_id: None,
+        user_id: None,
+        reply,
+    })
+    .expect("rpc")
+    .expect("query")
+    .iter()
+    .map(|event| event.payload["threshold_pct"].as_u64().unwrap())
+    .collect()
+}
+
+#[tokio::test]
+async fn test_tiny_quota_warns_then_blocks_writes() {
+    let (app, _temp_dir, event_store) = setup_test_app(1000).await;
+    let file_path = format!("disk_quota_test_{}.txt", ulid::Ulid::new());
+
+    let (status, usage) = get(&app, "/api/usage/disk").await;
+    assert_eq!(status, StatusCode::OK, "{usage}");
+    assert_eq!(usage["used_bytes"], 700);
+    assert_eq!(usage["quota_bytes"], 1000);
+    assert_eq!(usage["used_pct"], 70);
+    assert_eq!(usage["categories"][0]["category"], "runs");
+
+    let (status, body) = post(
+        &app,
+        "/files/write",
+        json!({ "path": &file_path, "content": "y".repeat(150) }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(quota_warnings(&event_store).await, [80]);
+
+    let (status, body) = post(
+        &app,
+        "/files/write",
+        json!({ "path": &file_path, "content": "y".repeat(200), "append": true }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::INSUFFICIENT_STORAGE, "{body}");
+    assert_eq!(body["error"]["code"], "QUOTA_EXCEEDED");
+    assert!(body["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("850 of 1000 bytes used"));
+
+    let (status, body) = post(
+        &app,
+        "/writer/save",
+        json!({ "path": &file_path, "base_rev": 0, "content": "z".repeat(200) }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::INSUFFICIENT_STORAGE, "{body}");
+    assert_eq!(body["error"]["code"], "QUOTA_EXCEEDED");
+
+    let (status, body) = post(
+        &app,
+        "/files/write",
+        json!({ "path": &file_path, "content": "y".repeat(110), "append": true }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(quota_warnings(&event_store).await, [80, 95]);
+
+    let (_, usage) = get(&app, "/api/usage/disk").await;
+    assert_eq!(usage["used_bytes"], 960);
+    assert_eq!(usage["categories"][0]["category"], "runs");
+    assert_eq!(usage["categories"][1]["category"], "files");
+    assert_eq!(usage["categories"][1]["bytes"], 260);
+
+    let (status, body) = post(
+        &app,
+        "/conductor/execute",
+        json!({
+            "objective": "Write a long report that no longer fits in the workspace",
+            "desktop_id": "quota-desktop",
+            "output_mode": "auto",
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::INSUFFICIENT_STORAGE, "{body}");
+    assert_eq!(body["error"]["code"], "QUOTA_EXCEEDED");
+
+    let _ = tokio::fs::remove_file(sandbox::paths::sandbox_root().join(&file_path)).await;
+}
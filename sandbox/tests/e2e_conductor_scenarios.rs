@@ -59,10 +59,7 @@ async fn setup_test_app() -> (axum::Router, tempfile::TempDir) {
     let app_state = Arc::new(AppState::new(event_store));
     let ws_sessions: sandbox::api::websocket::WsSessions =
         Arc::new(tokio::sync::Mutex::new(HashMap::new()));
-    let api_state = api::ApiState {
-        app_state,
-        ws_sessions,
-    };
+    let api_state = api::ApiState::new(app_state, ws_sessions);
     let app = api::router().with_state(api_state);
     (app, temp_dir)
 }
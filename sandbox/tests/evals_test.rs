@@ -0,0 +1,108 @@
+//! Eval harness integration tests.
+//!
+//! Runs the scenarios shipped in `sandbox/evals` in stub mode, and checks that
+//! a failing assertion is reported with a diff.
+//!
+//! Run:
+//!   cargo test -p sandbox --test evals_test -- --nocapture
+
+use sandbox::evals::{self, EvalOptions, Scenario, ScenarioOutcome};
+
+#[tokio::test]
+async fn test_shipped_scenarios_pass_in_stub_mode() {
+    let scenarios = evals::load_dir(&evals::default_scenario_dir()).expect("scenarios load");
+    let names: Vec<&str> = scenarios.iter().map(|s| s.name.as_str()).collect();
+    for expected in ["chat_tool_use", "research_delegation", "writer_patch_run"] {
+        assert!(names.contains(&expected), "missing scenario {expected}");
+    }
+
+    let report = evals::run_scenarios(&scenarios, &EvalOptions::default()).await;
+    assert!(
+        report.all_passed(),
+        "{}",
+        serde_json::to_string_pretty(&report).unwrap()
+    );
+    assert_eq!(report.passed + report.skipped, scenarios.len());
+}
+
+#[tokio::test]
+async fn test_filter_selects_matching_scenarios() {
+    let scenarios = evals::load_dir(&evals::default_scenario_dir()).expect("scenarios load");
+    let options = EvalOptions {
+        filter: Some("writer_patch".to_string()),
+        live: false,
+    };
+    let report = evals::run_scenarios(&scenarios, &options).await;
+    assert_eq!(report.scenarios.len(), 1);
+    assert_eq!(report.scenarios[0].name, "writer_patch_run");
+}
+
+#[tokio::test]
+async fn test_failed_assertions_carry_diffs() {
+    let scenario = Scenario::from_yaml(
+        "inline",
+        r#"
+name: mismatch
+objective: Summarize the quota change
+stub:
+  replies:
+    - when: single routing decision
+      reply:
+        tool_calls:
+          - tool_name: finished
+            tool_args:
+              summary: '{"dispatch_capabilities":["writer"],"rationale":"Writing task.","confidence":0.9,"block_reason":null}'
+        message: ""
+    - when: Determine whether Writer should delegate workers
+      reply:
+        tool_calls:
+          - tool_name: message_writer
+            tool_args:
+              mode: write_revision
+              content: "Quota is enforced per workspace.\n"
+          - tool_name: finished
+            tool_args:
+              summary: done
+        message: ""
+    - when: Marginalia observer
+      repeat: true
+      reply:
+        summary: Stated the quota scope.
+        impact: Low
+        op_taxonomy: [insert]
+assert:
+  events:
+    - event_type: writer.run.patch
+      payload:
+        source_actor: researcher
+  document:
+    contains:
+      - "Quota is enforced per user."
+  completion:
+    status: failed
+"#,
+    )
+    .expect("scenario parses");
+
+    let report = evals::run_scenario(&scenario).await;
+    assert_eq!(report.outcome, ScenarioOutcome::Failed);
+    let assertions: Vec<&str> = report
+        .failures
+        .iter()
+        .map(|failure| failure.assertion.as_str())
+        .collect();
+    assert_eq!(
+        assertions,
+        [
+            "events:writer.run.patch",
+            "document.contains",
+            "completion.status"
+        ]
+    );
+    let diff = report.failures[0].diff.as_deref().expect("payload diff");
+    assert!(
+        diff.contains("-  \"source_actor\": \"researcher\""),
+        "{diff}"
+    );
+    assert!(diff.contains("+  \"source_actor\": \"writer\""), "{diff}");
+}
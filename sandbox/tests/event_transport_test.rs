@@ -0,0 +1,405 @@
+//! Delivery semantics shared by the WebSocket and long-poll transports.
+//!
+//! Each scenario runs against both transports: messages must arrive in the
+//! order they were sent, with no gaps and no duplicates.
+//!
+//! Run:
+//!   cargo test -p sandbox --test event_transport_test -- --nocapture
+
+use axum::Router;
+use futures_util::{SinkExt, StreamExt};
+use ractor::Actor;
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::timeout;
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+use sandbox::actors::event_store::{EventStoreActor, EventStoreArguments};
+use sandbox::api;
+use sandbox::app_state::AppState;
+
+const MOVES: i64 = 25;
+
+struct TestServer {
+    addr: SocketAddr,
+    _temp_dir: tempfile::TempDir,
+    handle: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+async fn start_test_server() -> TestServer {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp directory");
+    let db_path = temp_dir.path().join("test_events.db");
+    let db_path_str = db_path.to_str().expect("Invalid database path");
+
+    let (event_store, _handle) = Actor::spawn(
+        None,
+        EventStoreActor,
+        EventStoreArguments::File(db_path_str.to_string()),
+    )
+    .await
+    .expect("Failed to create event store");
+
+    let api_state = api::ApiState::new(
+        Arc::new(AppState::new(event_store)),
+        Arc::new(tokio::sync::Mutex::new(HashMap::new())),
+    );
+    let app: Router = api::router().with_state(api_state);
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("Failed to bind listener");
+    let addr = listener.local_addr().expect("Failed to get addr");
+    let handle = tokio::spawn(async move {
+        axum::serve(listener, app.into_make_service())
+            .await
+            .expect("Server failed");
+    });
+
+    TestServer {
+        addr,
+        _temp_dir: temp_dir,
+        handle,
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Transport {
+    WebSocket,
+    LongPoll,
+}
+
+/// A desktop event subscription over either transport.
+enum EventClient {
+    WebSocket(Box<WebSocketStream<MaybeTlsStream<TcpStream>>>),
+    LongPoll {
+        http: reqwest::Client,
+        addr: SocketAddr,
+        cursor: String,
+        received: VecDeque<Value>,
+    },
+}
+
+impl EventClient {
+    async fn subscribe(transport: Transport, addr: SocketAddr, desktop_id: &str) -> Self {
+        match transport {
+            Transport::WebSocket => {
+                let (mut ws, _) = connect_async(format!("ws://{addr}/ws"))
+                    .await
+                    .expect("ws connect failed");
+                ws.send(Message::Text(
+                    json!({ "type": "subscribe", "desktop_id": desktop_id }).to_string(),
+                ))
+                .await
+                .expect("Send error");
+                EventClient::WebSocket(Box::new(ws))
+            }
+            Transport::LongPoll => {
+                let http = reqwest::Client::new();
+                let response: Value = http
+                    .post(format!("http://{addr}/api/events/subscriptions"))
+                    .json(&json!({ "desktop_id": desktop_id }))
+                    .send()
+                    .await
+                    .expect("subscribe failed")
+                    .json()
+                    .await
+                    .expect("Invalid JSON");
+                let cursor = response["cursor"].as_str().expect("cursor").to_string();
+                EventClient::LongPoll {
+                    http,
+                    addr,
+                    cursor,
+                    received: VecDeque::new(),
+                }
+            }
+        }
+    }
+
+    /// The next desktop message, skipping keepalive pongs.
+    async fn next(&mut self) -> Value {
+        loop {
+            let message = match self {
+                EventClient::WebSocket(ws) => {
+                    match timeout(Duration::from_secs(5), ws.next()).await {
+                        Ok(Some(Ok(Message::Text(text)))) => {
+                            serde_json::from_str(&text).expect("Invalid JSON")
+                        }
+                        Ok(Some(Ok(Message::Close(_)))) => panic!("Connection closed"),
+                        Ok(Some(Ok(_))) => continue,
+                        Ok(Some(Err(e))) => panic!("Frame error: {e:?}"),
+                        Ok(None) => panic!("Stream ended"),
+                        Err(_) => panic!("Timeout waiting for frame"),
+                    }
+                }
+                EventClient::LongPoll {
+                    http,
+                    addr,
+                    cursor,
+                    received,
+                } => {
+                    while received.is_empty() {
+                        let body = poll(http, *addr, cursor, 5_000).await;
+                        let messages = body["messages"].as_array().expect("messages array");
+                        assert!(!messages.is_empty(), "Timeout waiting for messages");
+                        received.extend(messages.iter().cloned());
+                        *cursor = body["next_cursor"].as_str().unwrap().to_string();
+                    }
+                    received.pop_front().unwrap()
+                }
+            };
+            if message["type"] != "pong" {
+                return message;
+            }
+        }
+    }
+
+    async fn next_of_type(&mut self, expected_type: &str) -> Value {
+        for _ in 0..10 {
+            let message = self.next().await;
+            if message["type"] == expected_type {
+                return message;
+            }
+        }
+        panic!("did not receive message type {expected_type}");
+    }
+}
+
+async fn poll(http: &reqwest::Client, addr: SocketAddr, cursor: &str, timeout_ms: u64) -> Value {
+    let response = http
+        .get(format!("http://{addr}/api/events/poll"))
+        .query(&[("cursor", cursor), ("timeout_ms", &timeout_ms.to_string())])
+        .send()
+        .await
+        .expect("poll failed");
+    assert!(response.status().is_success(), "{}", response.status());
+    response.json().await.expect("Invalid JSON")
+}
+
+/// Register an app on a fresh desktop and open a window of it.
+async fn open_window(client: &reqwest::Client, addr: SocketAddr, desktop_id: &str) -> String {
+    let register = client
+        .post(format!("http://{addr}/desktop/{desktop_id}/apps"))
+        .json(&json!({
+            "id": "test-app",
+            "name": "Test App",
+            "icon": "🧩",
+            "component_code": "TestAppView",
+            "default_width": 400,
+            "default_height": 600
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert!(register.status().is_success());
+
+    let opened: Value = client
+        .post(format!("http://{addr}/desktop/{desktop_id}/windows"))
+        .json(&json!({ "app_id": "test-app", "title": "Test App", "props": null }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    opened["window"]["id"].as_str().unwrap().to_string()
+}
+
+async fn move_window(
+    client: &reqwest::Client,
+    addr: SocketAddr,
+    desktop_id: &str,
+    window_id: &str,
+    x: i64,
+) {
+    let response = client
+        .patch(format!(
+            "http://{addr}/desktop/{desktop_id}/windows/{window_id}/position"
+        ))
+        .json(&json!({ "x": x, "y": 10 }))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+}
+
+/// Every move arrives once, in the order it was made.
+async fn check_moves_arrive_in_order(transport: Transport) {
+    let server = start_test_server().await;
+    let desktop_id = format!("test-desktop-{}", uuid::Uuid::new_v4());
+    let client = reqwest::Client::new();
+    let window_id = open_window(&client, server.addr, &desktop_id).await;
+
+    let mut events = EventClient::subscribe(transport, server.addr, &desktop_id).await;
+    let state = events.next_of_type("desktop_state").await;
+    assert_eq!(state["desktop"]["windows"][0]["id"], window_id.as_str());
+
+    for x in 1..=MOVES {
+        move_window(&client, server.addr, &desktop_id, &window_id, x).await;
+    }
+    let mut xs = Vec::new();
+    while xs.last() != Some(&MOVES) {
+        let message = events.next().await;
+        assert_eq!(message["type"], "window_moved", "{transport:?}: {message}");
+        assert_eq!(message["window_id"], window_id.as_str());
+        xs.push(message["x"].as_i64().unwrap());
+    }
+    assert_eq!(xs, (1..=MOVES).collect::<Vec<_>>(), "{transport:?}");
+}
+
+#[tokio::test]
+async fn test_websocket_delivers_moves_in_order() {
+    check_moves_arrive_in_order(Transport::WebSocket).await;
+}
+
+#[tokio::test]
+async fn test_long_poll_delivers_moves_in_order() {
+    check_moves_arrive_in_order(Transport::LongPoll).await;
+}
+
+/// Both transports subscribed to one desktop see the same message stream.
+#[tokio::test]
+async fn test_transports_deliver_the_same_stream() {
+    let server = start_test_server().await;
+    let desktop_id = format!("test-desktop-{}", uuid::Uuid::new_v4());
+    let client = reqwest::Client::new();
+    let window_id = open_window(&client, server.addr, &desktop_id).await;
+
+    let mut ws = EventClient::subscribe(Transport::WebSocket, server.addr, &desktop_id).await;
+    let mut long_poll = EventClient::subscribe(Transport::LongPoll, server.addr, &desktop_id).await;
+    ws.next_of_type("desktop_state").await;
+    long_poll.next_of_type("desktop_state").await;
+
+    for x in 1..=5 {
+        move_window(&client, server.addr, &desktop_id, &window_id, x).await;
+    }
+    for action in ["minimize", "restore"] {
+        let response = client
+            .post(format!(
+                "http://{}/desktop/{desktop_id}/windows/{window_id}/{action}",
+                server.addr
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+    }
+    let response = client
+        .delete(format!(
+            "http://{}/desktop/{desktop_id}/windows/{window_id}",
+            server.addr
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+
+    let mut streams = Vec::new();
+    for events in [&mut ws, &mut long_poll] {
+        let mut stream = Vec::new();
+        loop {
+            let message = events.next().await;
+            let done = message["type"] == "window_closed";
+            stream.push(message);
+            if done {
+                break;
+            }
+        }
+        streams.push(stream);
+    }
+    assert_eq!(streams[0], streams[1]);
+    assert!(streams[0]
+        .iter()
+        .any(|message| message["type"] == "window_minimized"));
+}
+
+#[tokio::test]
+async fn test_long_poll_retry_replays_unacknowledged_messages() {
+    let server = start_test_server().await;
+    let desktop_id = format!("test-desktop-{}", uuid::Uuid::new_v4());
+    let client = reqwest::Client::new();
+    let window_id = open_window(&client, server.addr, &desktop_id).await;
+
+    let subscribed: Value = client
+        .post(format!("http://{}/api/events/subscriptions", server.addr))
+        .json(&json!({ "desktop_id": desktop_id }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(subscribed["expires_in_ms"], 60_000);
+    let first_cursor = subscribed["cursor"].as_str().unwrap().to_string();
+    for x in 1..=3 {
+        move_window(&client, server.addr, &desktop_id, &window_id, x).await;
+    }
+
+    let types = |body: &Value| {
+        body["messages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|message| message["type"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+    let first = poll(&client, server.addr, &first_cursor, 1_000).await;
+    assert_eq!(
+        types(&first),
+        [
+            "desktop_state",
+            "window_moved",
+            "window_moved",
+            "window_moved"
+        ]
+    );
+
+    // A response lost in transit is fetched again with the same cursor.
+    let retried = poll(&client, server.addr, &first_cursor, 1_000).await;
+    assert_eq!(retried, first);
+
+    // Moving on acknowledges the batch; nothing new has arrived yet.
+    let next_cursor = first["next_cursor"].as_str().unwrap();
+    let empty = poll(&client, server.addr, next_cursor, 100).await;
+    assert!(types(&empty).is_empty());
+    assert_eq!(empty["next_cursor"], next_cursor);
+
+    // An acknowledged batch cannot be replayed.
+    let stale = client
+        .get(format!("http://{}/api/events/poll", server.addr))
+        .query(&[("cursor", first_cursor.as_str()), ("timeout_ms", "100")])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(stale.status(), reqwest::StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn test_long_poll_rejects_unknown_and_malformed_cursors() {
+    let server = start_test_server().await;
+    let client = reqwest::Client::new();
+
+    for (cursor, status) in [
+        (
+            format!("{}.0", uuid::Uuid::new_v4()),
+            reqwest::StatusCode::NOT_FOUND,
+        ),
+        ("garbage".to_string(), reqwest::StatusCode::BAD_REQUEST),
+    ] {
+        let response = client
+            .get(format!("http://{}/api/events/poll", server.addr))
+            .query(&[("cursor", cursor.as_str()), ("timeout_ms", "100")])
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), status, "{cursor}");
+    }
+}
@@ -127,6 +127,7 @@ impl AlmPort for FanOutTrackingPort {
                 payload,
                 actor_id: self.actor_id.clone(),
                 user_id: "system".into(),
+                idempotency_key: None,
             },
         });
     }
@@ -173,6 +174,7 @@ impl AlmPort for FanOutTrackingPort {
                     payload,
                     actor_id: format!("harness:{corr_id_owned}"),
                     user_id: "system".into(),
+                    idempotency_key: None,
                 },
             });
         });
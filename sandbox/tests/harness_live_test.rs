@@ -550,6 +550,7 @@ async fn test_subharness_result_event_queryable_by_corr_id() {
             }),
             actor_id: format!("harness:{corr_id}"),
             user_id: "system".to_string(),
+            idempotency_key: None,
         },
     });
 
@@ -125,6 +125,7 @@ impl AlmPort for RecoveryTestPort {
                 payload,
                 actor_id: self.actor_id.clone(),
                 user_id: "system".into(),
+                idempotency_key: None,
             },
         });
     }
@@ -282,6 +283,7 @@ async fn test_recovery_resolve_source_after_result_lands() {
             payload: result_payload,
             actor_id: format!("harness:{corr_id}"),
             user_id: "system".into(),
+            idempotency_key: None,
         },
     });
 
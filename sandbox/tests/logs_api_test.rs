@@ -36,10 +36,7 @@ async fn setup_test_app() -> (
     let ws_sessions: sandbox::api::websocket::WsSessions =
         Arc::new(tokio::sync::Mutex::new(HashMap::new()));
 
-    let api_state = api::ApiState {
-        app_state: app_state.clone(),
-        ws_sessions,
-    };
+    let api_state = api::ApiState::new(app_state.clone(), ws_sessions);
 
     let app = api::router().with_state(api_state);
     (app, temp_dir, app_state, event_store)
@@ -68,6 +65,7 @@ async fn test_logs_events_returns_filtered_results() {
             payload: serde_json::json!({"task_id":"t1"}),
             actor_id: "supervisor-1".to_string(),
             user_id: "user-1".to_string(),
+            idempotency_key: None,
         },
         reply
     })
@@ -80,6 +78,7 @@ async fn test_logs_events_returns_filtered_results() {
             payload: serde_json::json!({"text":"hello"}),
             actor_id: "session-1".to_string(),
             user_id: "user-1".to_string(),
+            idempotency_key: None,
         },
         reply
     })
@@ -110,6 +109,7 @@ async fn test_logs_events_limit_is_capped() {
                 payload: serde_json::json!({"idx": idx}),
                 actor_id: "supervisor-1".to_string(),
                 user_id: "user-1".to_string(),
+                idempotency_key: None,
             },
             reply
         })
@@ -139,6 +139,7 @@ async fn test_logs_events_jsonl_export() {
             payload: serde_json::json!({"rule":"worker_failure_spike"}),
             actor_id: "watcher:default".to_string(),
             user_id: "system".to_string(),
+            idempotency_key: None,
         },
         reply
     })
@@ -176,6 +177,68 @@ async fn test_logs_events_jsonl_export() {
     assert_eq!(line_json["event_type"], "watcher.alert.failure_spike");
 }
 
+#[tokio::test]
+async fn test_logs_same_timestamp_events_keep_order_across_query_and_export() {
+    let (app, _temp_dir, _app_state, event_store) = setup_test_app().await;
+
+    for idx in 0..20 {
+        let _ = ractor::call!(event_store, |reply| EventStoreMsg::Append {
+            event: AppendEvent {
+                event_type: "replay.order".to_string(),
+                payload: serde_json::json!({ "idx": idx }),
+                actor_id: format!("actor-{}", idx % 3),
+                user_id: "system".to_string(),
+                idempotency_key: None,
+            },
+            reply
+        })
+        .unwrap()
+        .unwrap();
+    }
+
+    let req = Request::builder()
+        .uri("/logs/events?event_type_prefix=replay.order&limit=100")
+        .body(Body::empty())
+        .unwrap();
+    let (status, body) = json_response(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+    let queried: Vec<shared_types::Event> =
+        serde_json::from_value(body["events"].clone()).expect("events parse");
+
+    let req = Request::builder()
+        .uri("/logs/events.jsonl?event_type_prefix=replay.order&limit=100")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(req).await.expect("Request failed");
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let exported: Vec<shared_types::Event> = String::from_utf8(body.to_vec())
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("jsonl line parse"))
+        .collect();
+
+    // The store has second-precision timestamps, so these events share them.
+    assert!(queried
+        .windows(2)
+        .any(|pair| pair[0].timestamp == pair[1].timestamp));
+    let ids = |events: &[shared_types::Event]| {
+        events
+            .iter()
+            .map(|event| event.event_id.clone())
+            .collect::<Vec<_>>()
+    };
+    let mut canonical = queried.clone();
+    canonical.sort_by(shared_types::Event::canonical_cmp);
+    assert_eq!(queried.len(), 20);
+    assert_eq!(ids(&queried), ids(&exported));
+    assert_eq!(ids(&queried), ids(&canonical));
+    let idx: Vec<i64> = queried
+        .iter()
+        .map(|event| event.payload["idx"].as_i64().unwrap())
+        .collect();
+    assert_eq!(idx, (0..20).collect::<Vec<_>>());
+}
+
 #[tokio::test]
 async fn test_logs_run_markdown_export_contains_transcript_sections() {
     let (app, _temp_dir, _app_state, event_store) = setup_test_app().await;
@@ -194,6 +257,7 @@ async fn test_logs_run_markdown_export_contains_transcript_sections() {
             }),
             actor_id: "thread-1".to_string(),
             user_id: "user-1".to_string(),
+            idempotency_key: None,
         },
         reply
     })
@@ -211,6 +275,7 @@ async fn test_logs_run_markdown_export_contains_transcript_sections() {
             }),
             actor_id: "thread-1".to_string(),
             user_id: "user-1".to_string(),
+            idempotency_key: None,
         },
         reply
     })
@@ -228,6 +293,7 @@ async fn test_logs_run_markdown_export_contains_transcript_sections() {
             }),
             actor_id: "thread-1".to_string(),
             user_id: "user-1".to_string(),
+            idempotency_key: None,
         },
         reply
     })
@@ -244,6 +310,7 @@ async fn test_logs_run_markdown_export_contains_transcript_sections() {
             }),
             actor_id: "thread-1".to_string(),
             user_id: "user-1".to_string(),
+            idempotency_key: None,
         },
         reply
     })
@@ -301,6 +368,7 @@ async fn test_logs_run_markdown_export_filters_by_correlation_id() {
                 }),
                 actor_id: "thread-2".to_string(),
                 user_id: "user-1".to_string(),
+                idempotency_key: None,
             },
             reply
         })
@@ -326,3 +394,253 @@ async fn test_logs_run_markdown_export_filters_by_correlation_id() {
     assert!(markdown.contains("hello from a"));
     assert!(!markdown.contains("hello from b"));
 }
+
+#[tokio::test]
+async fn test_logs_events_page_follows_cursor_backwards() {
+    let (app, _temp_dir, _app_state, event_store) = setup_test_app().await;
+
+    for (idx, event_type) in ["writer.run.patch", "chat.message", "writer.run.patch"]
+        .into_iter()
+        .cycle()
+        .take(6)
+        .enumerate()
+    {
+        let _ = ractor::call!(event_store, |reply| EventStoreMsg::Append {
+            event: AppendEvent {
+                event_type: event_type.to_string(),
+                payload: serde_json::json!({
+                    "idx": idx,
+                    "run_id": "run-1",
+                    "scope": { "session_id": "session-1", "thread_id": "thread-1" },
+                }),
+                actor_id: "writer-1".to_string(),
+                user_id: "user-1".to_string(),
+                idempotency_key: None,
+            },
+            reply
+        })
+        .unwrap()
+        .unwrap();
+    }
+
+    let base = "/logs/events/page?actor_id=writer-1&event_types=writer.run.*&limit=3&order=desc";
+    let mut seen = Vec::new();
+    let mut uri = base.to_string();
+    loop {
+        let req = Request::builder()
+            .method("GET")
+            .uri(&uri)
+            .body(Body::empty())
+            .unwrap();
+        let (status, body) = json_response(&app, req).await;
+        assert_eq!(status, StatusCode::OK);
+        for event in body["events"].as_array().expect("events array") {
+            seen.push(event["payload"]["idx"].as_i64().unwrap());
+        }
+        match body["next_cursor"].as_i64() {
+            Some(cursor) => uri = format!("{base}&cursor={cursor}"),
+            None => break,
+        }
+    }
+    assert_eq!(seen, vec![5, 3, 2, 0]);
+
+    let req = Request::builder()
+        .method("GET")
+        .uri("/logs/events/page?order=sideways")
+        .body(Body::empty())
+        .unwrap();
+    let (status, _body) = json_response(&app, req).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+fn append_request(user_id: &str, event_type: &str, idempotency_key: &str) -> Request<Body> {
+    Request::builder()
+        .method("POST")
+        .uri("/api/events")
+        .header("content-type", "application/json")
+        .header("idempotency-key", idempotency_key)
+        .header("x-choiros-proxy-authenticated", "true")
+        .header("x-choiros-user-id", user_id)
+        .body(Body::from(
+            serde_json::json!({
+                "event_type": event_type,
+                "payload": {"text": "hello"},
+                "actor_id": "chat-1",
+            })
+            .to_string(),
+        ))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_append_event_with_same_idempotency_key_is_stored_once() {
+    let (app, _temp_dir, _app_state, event_store) = setup_test_app().await;
+    let append =
+        |user_id, idempotency_key| append_request(user_id, "chat.user_msg", idempotency_key);
+
+    let (status, first) = json_response(&app, append("user-1", "retry-1")).await;
+    assert_eq!(status, StatusCode::OK, "{first}");
+    assert_eq!(first["user_id"], "user-1");
+    let (status, retry) = json_response(&app, append("user-1", "retry-1")).await;
+    assert_eq!(status, StatusCode::OK, "{retry}");
+    assert_eq!(retry["seq"], first["seq"]);
+    assert_eq!(retry["event_id"], first["event_id"]);
+
+    let (status, other) = json_response(&app, append("user-1", "retry-2")).await;
+    assert_eq!(status, StatusCode::OK, "{other}");
+    assert_ne!(other["seq"], first["seq"]);
+
+    // Another user's key never returns this user's event.
+    let (status, other_user) = json_response(&app, append("user-2", "retry-1")).await;
+    assert_eq!(status, StatusCode::OK, "{other_user}");
+    assert_ne!(other_user["seq"], first["seq"]);
+    assert_eq!(other_user["user_id"], "user-2");
+
+    let events = ractor::call!(event_store, |reply| EventStoreMsg::GetEventsForActor {
+        actor_id: "chat-1".to_string(),
+        since_seq: 0,
+        reply,
+    })
+    .unwrap()
+    .unwrap();
+    assert_eq!(events.len(), 3);
+}
+
+#[tokio::test]
+async fn test_append_event_rejects_actor_event_types_and_unauthenticated_users() {
+    let (app, _temp_dir, _app_state, _event_store) = setup_test_app().await;
+
+    let (status, body) = json_response(
+        &app,
+        append_request("user-1", "conductor.run.started", "forged-1"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{body}");
+
+    // A user id the proxy did not authenticate is not trusted.
+    let mut request = append_request("user-1", "chat.user_msg", "anon-1");
+    request.headers_mut().insert(
+        "x-choiros-proxy-authenticated",
+        axum::http::HeaderValue::from_static("false"),
+    );
+    let (status, event) = json_response(&app, request).await;
+    assert_eq!(status, StatusCode::OK, "{event}");
+    assert_eq!(event["user_id"], "anonymous");
+}
+
+#[tokio::test]
+async fn test_events_search_returns_events_with_snippets() {
+    let (app, _temp_dir, _app_state, event_store) = setup_test_app().await;
+
+    for (event_type, payload) in [
+        (
+            "conductor.run.status",
+            serde_json::json!({"run_id": "run-7", "message": "harness stalled on tool call"}),
+        ),
+        (
+            "worker.task.progress",
+            serde_json::json!({"run_id": "run-7", "message": "indexing files"}),
+        ),
+    ] {
+        let _ = ractor::call!(event_store, |reply| EventStoreMsg::Append {
+            event: AppendEvent {
+                event_type: event_type.to_string(),
+                payload: payload.clone(),
+                actor_id: "conductor-1".to_string(),
+                user_id: "user-1".to_string(),
+                idempotency_key: None,
+            },
+            reply
+        })
+        .unwrap()
+        .unwrap();
+    }
+
+    let req = Request::builder()
+        .uri("/api/events/search?q=harness%20stal")
+        .body(Body::empty())
+        .unwrap();
+    let (status, body) = json_response(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+    let matches = body["matches"].as_array().expect("matches array");
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0]["event"]["event_type"], "conductor.run.status");
+    assert_eq!(matches[0]["event"]["payload"]["run_id"], "run-7");
+    assert!(matches[0]["snippet"]
+        .as_str()
+        .unwrap()
+        .contains("\u{2}stalled\u{3}"));
+
+    let req = Request::builder()
+        .uri("/api/events/search?q=%20")
+        .body(Body::empty())
+        .unwrap();
+    let (status, _) = json_response(&app, req).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_events_range_and_tail_return_newest_first() {
+    let (app, _temp_dir, _app_state, event_store) = setup_test_app().await;
+
+    for (event_type, actor_id) in [
+        ("test.first", "actor-a"),
+        ("test.second", "actor-b"),
+        ("test.third", "actor-a"),
+    ] {
+        let _ = ractor::call!(event_store, |reply| EventStoreMsg::Append {
+            event: AppendEvent {
+                event_type: event_type.to_string(),
+                payload: serde_json::json!({}),
+                actor_id: actor_id.to_string(),
+                user_id: "user-1".to_string(),
+                idempotency_key: None,
+            },
+            reply
+        })
+        .unwrap()
+        .unwrap();
+    }
+    let event_types = |body: &Value| -> Vec<String> {
+        body["events"]
+            .as_array()
+            .expect("events array")
+            .iter()
+            .map(|event| event["event_type"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    let req = Request::builder()
+        .uri("/api/events/tail?limit=2")
+        .body(Body::empty())
+        .unwrap();
+    let (status, body) = json_response(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(event_types(&body), ["test.third", "test.second"]);
+
+    let req = Request::builder()
+        .uri("/api/events/range?from=-10m&actor_id=actor-a")
+        .body(Body::empty())
+        .unwrap();
+    let (status, body) = json_response(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(event_types(&body), ["test.third", "test.first"]);
+
+    let req = Request::builder()
+        .uri("/api/events/range?from=2000-01-01T00:00:00Z&to=2000-01-02T00:00:00Z")
+        .body(Body::empty())
+        .unwrap();
+    let (status, body) = json_response(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(event_types(&body).is_empty());
+
+    for uri in [
+        "/api/events/range",
+        "/api/events/range?from=yesterday",
+        "/api/events/range?from=now&to=-1h",
+    ] {
+        let req = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let (status, _) = json_response(&app, req).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{uri}");
+    }
+}
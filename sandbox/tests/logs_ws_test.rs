@@ -47,10 +47,7 @@ async fn start_test_server() -> TestServer {
     let app_state = Arc::new(AppState::new(event_store.clone()));
     let ws_sessions: sandbox::api::websocket::WsSessions =
         Arc::new(tokio::sync::Mutex::new(HashMap::new()));
-    let api_state = api::ApiState {
-        app_state: app_state.clone(),
-        ws_sessions,
-    };
+    let api_state = api::ApiState::new(app_state.clone(), ws_sessions);
 
     let app: Router = api::router().with_state(api_state);
     let listener = TcpListener::bind("127.0.0.1:0")
@@ -107,6 +104,7 @@ async fn append_event(
             payload,
             actor_id: actor_id.to_string(),
             user_id: user_id.to_string(),
+            idempotency_key: None,
         },
         reply
     })
@@ -227,3 +225,64 @@ async fn test_logs_ws_streams_only_events_in_requested_scope() {
         "unexpected extra websocket frame after scoped match"
     );
 }
+
+#[tokio::test]
+async fn test_logs_ws_skips_compacted_actor_seqs_and_reports_lost_ones() {
+    let server = start_test_server().await;
+    for step in 1..=4 {
+        append_event(
+            &server.event_store,
+            "worker.task.progress",
+            serde_json::json!({ "step": step }),
+            "worker:gap",
+            "system",
+        )
+        .await;
+    }
+    let pool = sqlx::SqlitePool::connect(&format!(
+        "sqlite:{}",
+        server._temp_dir.path().join("test_events.db").display()
+    ))
+    .await
+    .expect("Failed to open database");
+    // actor_seq 2 is removed the way compaction removes it, watermark included.
+    sqlx::query("DELETE FROM events WHERE actor_id = 'worker:gap' AND actor_seq = 2")
+        .execute(&pool)
+        .await
+        .expect("Failed to delete event");
+    sqlx::query(
+        "UPDATE actor_seq_counters SET compacted_through = 2 WHERE actor_id = 'worker:gap'",
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to record watermark");
+    // actor_seq 3 is lost outright.
+    sqlx::query("DELETE FROM events WHERE actor_id = 'worker:gap' AND actor_seq = 3")
+        .execute(&pool)
+        .await
+        .expect("Failed to delete event");
+    pool.close().await;
+
+    let (mut ws, _) = connect_async(ws_url(
+        server.addr,
+        "/ws/logs/events?actor_id=worker:gap&poll_ms=50",
+    ))
+    .await
+    .expect("Failed to connect WebSocket");
+    assert_eq!(recv_json(&mut ws).await["type"], "connected");
+
+    let first = recv_json(&mut ws).await;
+    assert_eq!(first["type"], "event");
+    assert_eq!(first["actor_seq"], 1);
+
+    let gap = recv_json(&mut ws).await;
+    assert_eq!(gap["type"], "gap");
+    assert_eq!(gap["actor_id"], "worker:gap");
+    assert_eq!(gap["missing_from_actor_seq"], 3);
+    assert_eq!(gap["missing_to_actor_seq"], 3);
+
+    let fourth = recv_json(&mut ws).await;
+    assert_eq!(fourth["type"], "event");
+    assert_eq!(fourth["actor_seq"], 4);
+    assert_eq!(fourth["payload"]["step"], 4);
+}
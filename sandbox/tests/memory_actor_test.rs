@@ -384,6 +384,66 @@ async fn test_ingest_dedup_via_actor() {
     event_store.stop(None);
 }
 
+/// Document-path source refs are stored under their normalized key, and
+/// traversal paths are refused.
+#[tokio::test]
+async fn test_ingest_normalizes_document_source_refs() {
+    stub_env();
+
+    let (event_store, _) =
+        ractor::Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+            .await
+            .expect("event store spawn");
+
+    let (memory, _) = ractor::Actor::spawn(
+        None,
+        MemoryActor,
+        MemoryArguments {
+            event_store: event_store.clone(),
+            vec_db_path: ":memory:".to_string(),
+        },
+    )
+    .await
+    .expect("memory actor spawn");
+
+    let ingest = |source_ref: &str, content: &str| IngestRequest {
+        item_id: ulid(),
+        collection: CollectionKind::VersionSnapshots,
+        source_ref: source_ref.to_string(),
+        content: content.to_string(),
+    };
+
+    let inserted = ractor::call!(memory, |reply| MemoryMsg::Ingest {
+        req: ingest(
+            "./conductor\\runs/run_1/draft.md",
+            "normalized pelican draft"
+        ),
+        reply: Some(reply),
+    })
+    .expect("ingest");
+    assert!(inserted);
+
+    let rejected = ractor::call!(memory, |reply| MemoryMsg::Ingest {
+        req: ingest("conductor/../../secrets.md", "escaped pelican draft"),
+        reply: Some(reply),
+    })
+    .expect("ingest");
+    assert!(!rejected, "traversal source ref should be refused");
+
+    let result = ractor::call!(memory, |reply| MemoryMsg::ArtifactSearch {
+        collection: CollectionKind::VersionSnapshots,
+        query: "pelican".to_string(),
+        k: 5,
+        reply,
+    })
+    .expect("search rpc");
+    let source_refs: Vec<&str> = result.items.iter().map(|i| i.source_ref.as_str()).collect();
+    assert_eq!(source_refs, ["conductor/runs/run_1/draft.md"]);
+
+    memory.stop(None);
+    event_store.stop(None);
+}
+
 // ─── Phase 5.3 gate tests ─────────────────────────────────────────────────────
 
 /// 5.3-G1: ArtifactExpand returns neighbors of seeded items from adjacent collections.
@@ -691,3 +751,80 @@ async fn test_selective_reembedding_only_changed_blocks() {
     memory.stop(None);
     event_store.stop(None);
 }
+
+fn qwy_block(id: &str, content: &str, private: bool) -> shared_types::BlockNode {
+    shared_types::BlockNode {
+        block_id: shared_types::BlockId(id.to_string()),
+        block_type: shared_types::BlockType::Paragraph,
+        parent_id: None,
+        children: Vec::new(),
+        content: content.to_string(),
+        chunk_hash: None,
+        provenance: shared_types::ProvenanceEnvelope {
+            was_generated_by: None,
+            was_attributed_to: Some("user".to_string()),
+            was_revision_of: None,
+            had_primary_source: None,
+            conductor_run_id: None,
+            loop_id: None,
+            private,
+        },
+        annotations: Vec::new(),
+    }
+}
+
+/// Private blocks never enter the embedding record set.
+#[test]
+fn test_private_qwy_blocks_are_not_ingested() {
+    let blocks = [
+        qwy_block("pub-1", "shared architecture overview", false),
+        qwy_block("priv-1", "personal reminder about salary talk", true),
+    ];
+    let document = shared_types::QwyDocument {
+        header: shared_types::QwyDocumentHeader {
+            document_id: "doc/notes.qwy".to_string(),
+            schema_version: 1,
+            created_at: chrono::Utc::now(),
+            created_by: "user".to_string(),
+            conductor_run_id: None,
+        },
+        root_block_ids: blocks.iter().map(|b| b.block_id.clone()).collect(),
+        blocks: blocks
+            .into_iter()
+            .map(|block| (block.block_id.0.clone(), block))
+            .collect(),
+        patch_log: Vec::new(),
+        citation_registry: std::collections::HashMap::new(),
+        version_index: Vec::new(),
+    };
+
+    let requests = IngestRequest::for_qwy_blocks(&document);
+    let ids: Vec<&str> = requests.iter().map(|r| r.item_id.as_str()).collect();
+    assert_eq!(ids, vec!["pub-1"]);
+    assert!(requests.iter().all(
+        |r| r.collection == CollectionKind::VersionSnapshots && r.source_ref == "doc/notes.qwy"
+    ));
+
+    let inner = make_inner();
+    let guard = inner.lock().unwrap();
+    for req in &requests {
+        guard
+            .store
+            .insert(
+                req.collection.table_name(),
+                &req.item_id,
+                &req.source_ref,
+                &req.content,
+                &chunk_hash(&req.content),
+            )
+            .expect("insert");
+    }
+    let hits = guard
+        .store
+        .search("version_snapshots", "salary reminder", 5)
+        .expect("search");
+    assert!(
+        hits.iter().all(|hit| hit.item_id != "priv-1"),
+        "private block must not be searchable"
+    );
+}
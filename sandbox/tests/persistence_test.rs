@@ -32,11 +32,15 @@ fn test_event_id() -> String {
 
 /// Create a user message event for testing
 fn create_user_message_event(seq: i64, actor_id: &str, text: &str) -> shared_types::Event {
+    let now = chrono::Utc::now();
     shared_types::Event {
         seq,
         event_id: test_event_id(),
-        timestamp: chrono::Utc::now(),
+        timestamp: now,
+        stored_at: now,
+        produced_at: None,
         actor_id: shared_types::ActorId(actor_id.to_string()),
+        actor_seq: seq,
         event_type: "interaction.user_msg".to_string(),
         payload: serde_json::json!(text),
         user_id: test_user_id(),
@@ -45,11 +49,15 @@ fn create_user_message_event(seq: i64, actor_id: &str, text: &str) -> shared_typ
 
 /// Create an assistant message event for testing
 fn create_assistant_message_event(seq: i64, actor_id: &str, text: &str) -> shared_types::Event {
+    let now = chrono::Utc::now();
     shared_types::Event {
         seq,
         event_id: test_event_id(),
-        timestamp: chrono::Utc::now(),
+        timestamp: now,
+        stored_at: now,
+        produced_at: None,
         actor_id: shared_types::ActorId(actor_id.to_string()),
+        actor_seq: seq,
         event_type: "interaction.assistant_msg".to_string(),
         payload: serde_json::json!({"text": text}),
         user_id: "system".to_string(),
@@ -63,11 +71,15 @@ fn create_tool_call_event(
     tool_name: &str,
     tool_args: &str,
 ) -> shared_types::Event {
+    let now = chrono::Utc::now();
     shared_types::Event {
         seq,
         event_id: test_event_id(),
-        timestamp: chrono::Utc::now(),
+        timestamp: now,
+        stored_at: now,
+        produced_at: None,
         actor_id: shared_types::ActorId(actor_id.to_string()),
+        actor_seq: seq,
         event_type: "interaction.tool_call".to_string(),
         payload: serde_json::json!({
             "tool_name": tool_name,
@@ -110,6 +122,7 @@ async fn test_event_store_append_event() {
             payload: serde_json::json!("Hello, world!"),
             actor_id: test_actor_id(),
             user_id: test_user_id(),
+            idempotency_key: None,
         },
         reply,
     })
@@ -140,6 +153,7 @@ async fn test_event_store_get_events() {
                 payload: serde_json::json!(format!("Message {}", i)),
                 actor_id: actor_id.clone(),
                 user_id: test_user_id(),
+                idempotency_key: None,
             },
             reply,
         })
@@ -175,6 +189,7 @@ async fn test_event_store_get_events_for_actor() {
             payload: serde_json::json!("Actor 1 message"),
             actor_id: actor_id_1.clone(),
             user_id: test_user_id(),
+            idempotency_key: None,
         },
         reply,
     })
@@ -188,6 +203,7 @@ async fn test_event_store_get_events_for_actor() {
             payload: serde_json::json!("Actor 2 message"),
             actor_id: actor_id_2.clone(),
             user_id: test_user_id(),
+            idempotency_key: None,
         },
         reply,
     })
@@ -224,6 +240,7 @@ async fn test_event_store_get_events_since_seq() {
                 payload: serde_json::json!(format!("Message {}", i)),
                 actor_id: actor_id.clone(),
                 user_id: test_user_id(),
+                idempotency_key: None,
             },
             reply,
         })
@@ -264,6 +281,7 @@ async fn test_event_store_event_ordering() {
                 payload: serde_json::json!(format!("Message {}", i)),
                 actor_id: actor_id.clone(),
                 user_id: test_user_id(),
+                idempotency_key: None,
             },
             reply,
         })
@@ -306,6 +324,7 @@ async fn test_event_store_multiple_actors() {
                     payload: serde_json::json!(format!("Actor {} Message {}", i, j)),
                     actor_id: actor_id.clone(),
                     user_id: test_user_id(),
+                    idempotency_key: None,
                 },
                 reply,
             })
@@ -358,6 +377,7 @@ async fn test_event_store_persistence_file() {
                     payload: serde_json::json!(format!("Persistent message {}", i)),
                     actor_id: actor_id.clone(),
                     user_id: test_user_id(),
+                    idempotency_key: None,
                 },
                 reply,
             })
@@ -428,6 +448,7 @@ async fn test_event_store_event_types() {
                 payload: payload.clone(),
                 actor_id: actor_id.clone(),
                 user_id: test_user_id(),
+                idempotency_key: None,
             },
             reply,
         })
@@ -481,6 +502,7 @@ async fn test_conversation_history_multiturn() {
                 payload: event.payload.clone(),
                 actor_id: event.actor_id.0.clone(),
                 user_id: event.user_id.clone(),
+                idempotency_key: None,
             },
             reply,
         })
@@ -533,6 +555,7 @@ async fn test_conversation_history_with_tools() {
                 payload: event.payload.clone(),
                 actor_id: event.actor_id.0.clone(),
                 user_id: event.user_id.clone(),
+                idempotency_key: None,
             },
             reply,
         })
@@ -574,6 +597,7 @@ async fn test_conversation_history_pagination() {
                 payload: serde_json::json!(format!("Message {}", i)),
                 actor_id: actor_id.clone(),
                 user_id: user_id.clone(),
+                idempotency_key: None,
             },
             reply,
         })
@@ -665,6 +689,7 @@ async fn test_conversation_history_large_conversation() {
                 } else {
                     "system".to_string()
                 },
+                idempotency_key: None,
             },
             reply,
         })
@@ -719,6 +744,7 @@ async fn test_conversation_history_chronological_order() {
                 payload: serde_json::json!(format!("Message {}", i)),
                 actor_id: actor_id.clone(),
                 user_id: user_id.clone(),
+                idempotency_key: None,
             },
             reply,
         })
@@ -767,6 +793,7 @@ async fn test_event_logs_user_message() {
             payload: serde_json::json!("Hello, agent!"),
             actor_id: actor_id.clone(),
             user_id: user_id.clone(),
+            idempotency_key: None,
         },
         reply,
     })
@@ -805,6 +832,7 @@ async fn test_event_logs_assistant_response() {
             payload: serde_json::json!({"text": "Yes, I can help!"}),
             actor_id: actor_id.clone(),
             user_id: "system".to_string(),
+            idempotency_key: None,
         },
         reply,
     })
@@ -849,6 +877,7 @@ async fn test_event_logs_tool_calls() {
             }),
             actor_id: actor_id.clone(),
             user_id: user_id.clone(),
+            idempotency_key: None,
         },
         reply,
     })
@@ -904,6 +933,7 @@ async fn test_event_logs_multiple_tools() {
                 }),
                 actor_id: actor_id.clone(),
                 user_id: user_id.clone(),
+                idempotency_key: None,
             },
             reply,
         })
@@ -967,6 +997,7 @@ async fn test_recovery_after_crash() {
                 payload: serde_json::json!(format!("Pre-crash message {}", i)),
                 actor_id: actor_id.clone(),
                 user_id: user_id.clone(),
+                idempotency_key: None,
             },
             reply,
         })
@@ -1031,6 +1062,7 @@ async fn test_recovery_partial_write() {
                     payload: serde_json::json!(format!("Message {}", i)),
                     actor_id: actor_id.clone(),
                     user_id: user_id.clone(),
+                    idempotency_key: None,
                 },
                 reply,
             })
@@ -1117,6 +1149,7 @@ async fn test_recovery_corrupted_event() {
                 payload: serde_json::json!(format!("Valid {}", i)),
                 actor_id: actor_id.clone(),
                 user_id: user_id.clone(),
+                idempotency_key: None,
             },
             reply,
         })
@@ -1169,6 +1202,7 @@ async fn test_concurrent_event_appends() {
                     payload: serde_json::json!(format!("Concurrent message {}", i)),
                     actor_id: actor_id_clone,
                     user_id: user_id_clone,
+                    idempotency_key: None,
                 },
                 reply,
             })
@@ -1247,6 +1281,7 @@ async fn test_ulid_event_id_uniqueness() {
                 payload: serde_json::json!({}),
                 actor_id: actor_id.clone(),
                 user_id: user_id.clone(),
+                idempotency_key: None,
             },
             reply,
         })
@@ -1289,6 +1324,7 @@ async fn test_timestamp_format() {
             payload: serde_json::json!({}),
             actor_id: actor_id.clone(),
             user_id: user_id.clone(),
+            idempotency_key: None,
         },
         reply,
     })
@@ -1306,3 +1342,84 @@ async fn test_timestamp_format() {
     let diff = now.signed_duration_since(event.timestamp);
     assert!(diff.num_seconds() < 60, "Timestamp should be recent");
 }
+
+// ============================================================================
+// Encryption Benchmark
+// ============================================================================
+
+/// Append and read back `count` chat messages; returns (append, read) time.
+async fn time_chat_round_trip(
+    encryption: sandbox::actors::event_encryption::EventEncryption,
+    count: usize,
+) -> (Duration, Duration) {
+    let (store_ref, _store_handle) =
+        Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+            .await
+            .unwrap();
+    sandbox::actors::event_store::configure_encryption(&store_ref, encryption)
+        .await
+        .unwrap()
+        .unwrap();
+    let actor_id = test_actor_id();
+    let text =
+        "A chat message of typical length, long enough to carry a sentence or two. ".repeat(4);
+
+    let append_start = std::time::Instant::now();
+    for _ in 0..count {
+        ractor::call!(store_ref, |reply| EventStoreMsg::Append {
+            event: AppendEvent {
+                event_type: "chat.user_msg".to_string(),
+                payload: serde_json::json!({ "text": text }),
+                actor_id: actor_id.clone(),
+                user_id: "bench-user".to_string(),
+                idempotency_key: None,
+            },
+            reply,
+        })
+        .unwrap()
+        .unwrap();
+    }
+    let append_elapsed = append_start.elapsed();
+
+    let read_start = std::time::Instant::now();
+    let events = ractor::call!(store_ref, |reply| EventStoreMsg::GetEventsForActor {
+        actor_id: actor_id.clone(),
+        since_seq: 0,
+        reply,
+    })
+    .unwrap()
+    .unwrap();
+    let read_elapsed = read_start.elapsed();
+
+    assert_eq!(events.len(), count);
+    assert!(events.iter().all(|event| event.payload["text"] == text));
+    store_ref.stop(None);
+    (append_elapsed, read_elapsed)
+}
+
+#[tokio::test]
+async fn test_encryption_overhead_benchmark() {
+    use sandbox::actors::event_encryption::{EventEncryption, EventKey};
+
+    const N: usize = 300;
+    let (plain_append, plain_read) = time_chat_round_trip(EventEncryption::default(), N).await;
+    let (sealed_append, sealed_read) = time_chat_round_trip(
+        EventEncryption::with_key(EventKey::derive("bench-secret")),
+        N,
+    )
+    .await;
+
+    let per_event = |elapsed: Duration| elapsed.as_secs_f64() * 1_000_000.0 / N as f64;
+    println!(
+        "  [BENCH] {N} chat events append: plaintext {:.1}us/event, encrypted {:.1}us/event ({:+.1}%)",
+        per_event(plain_append),
+        per_event(sealed_append),
+        (sealed_append.as_secs_f64() / plain_append.as_secs_f64() - 1.0) * 100.0
+    );
+    println!(
+        "  [BENCH] {N} chat events read: plaintext {:.1}us/event, encrypted {:.1}us/event ({:+.1}%)",
+        per_event(plain_read),
+        per_event(sealed_read),
+        (sealed_read.as_secs_f64() / plain_read.as_secs_f64() - 1.0) * 100.0
+    );
+}
@@ -0,0 +1,133 @@
+//! Learned preference API integration tests
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use http_body_util::BodyExt;
+use ractor::Actor;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+use sandbox::actors::event_store::EventStoreMsg;
+use sandbox::actors::{EventStoreActor, EventStoreArguments};
+use sandbox::api;
+use sandbox::app_state::AppState;
+use sandbox::disk_usage::DiskUsageTracker;
+use sandbox::preferences;
+
+async fn setup_test_app() -> (
+    axum::Router,
+    tempfile::TempDir,
+    ractor::ActorRef<EventStoreMsg>,
+) {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp directory");
+    let (event_store, _handle) = Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+        .await
+        .expect("Failed to create event store");
+
+    let app_state = Arc::new(AppState::with_disk_usage(
+        event_store.clone(),
+        DiskUsageTracker::new(temp_dir.path().to_path_buf(), None),
+    ));
+    let ws_sessions: sandbox::api::websocket::WsSessions =
+        Arc::new(tokio::sync::Mutex::new(HashMap::new()));
+    let api_state = api::ApiState::new(app_state, ws_sessions);
+
+    let app = api::router().with_state(api_state);
+    (app, temp_dir, event_store)
+}
+
+async fn send(
+    app: &axum::Router,
+    method: &str,
+    uri: &str,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let mut builder = Request::builder().method(method).uri(uri);
+    let body = match body {
+        Some(body) => {
+            builder = builder.header("content-type", "application/json");
+            Body::from(body.to_string())
+        }
+        None => Body::empty(),
+    };
+    let response = app
+        .clone()
+        .oneshot(builder.body(body).unwrap())
+        .await
+        .expect("Request failed");
+    let status = response.status();
+    let bytes = response
+        .into_body()
+        .collect()
+        .await
+        .expect("Failed to read body")
+        .to_bytes();
+    let value: Value = serde_json::from_slice(&bytes).expect("Invalid JSON response");
+    (status, value)
+}
+
+#[tokio::test]
+async fn preferences_can_be_listed_deleted_and_learning_disabled() {
+    let (app, temp_dir, event_store) = setup_test_app().await;
+
+    let (status, body) = send(&app, "GET", "/api/preferences", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, json!({ "learning_enabled": true, "preferences": [] }));
+
+    let raw = r#"[
+        {"topic": "package_manager", "statement": "Uses pnpm, not npm", "confidence": 0.9},
+        {"topic": "units", "statement": "Uses metric units", "confidence": 0.85}
+    ]"#;
+    preferences::record_extracted(
+        &event_store,
+        temp_dir.path(),
+        preferences::DEFAULT_PREFERENCE_USER,
+        raw,
+        Some("run-1"),
+    )
+    .await
+    .expect("record");
+
+    let (status, body) = send(&app, "GET", "/api/preferences", None).await;
+    assert_eq!(status, StatusCode::OK);
+    let listed = body["preferences"].as_array().expect("preferences");
+    assert_eq!(listed.len(), 2);
+    // Newest first.
+    assert_eq!(listed[0]["topic"], "units");
+    assert_eq!(listed[1]["source_run_id"], "run-1");
+
+    let id = listed[0]["preference_id"].as_str().unwrap().to_string();
+    let (status, body) = send(&app, "DELETE", &format!("/api/preferences/{id}"), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["preferences"].as_array().unwrap().len(), 1);
+    let (status, body) = send(&app, "DELETE", &format!("/api/preferences/{id}"), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"]["code"], "NOT_FOUND");
+
+    let document = std::fs::read_to_string(preferences::document_path(
+        temp_dir.path(),
+        preferences::DEFAULT_PREFERENCE_USER,
+    ))
+    .expect("preference document");
+    assert!(document.contains("Uses pnpm, not npm"));
+    assert!(!document.contains("Uses metric units"));
+
+    let (status, body) = send(
+        &app,
+        "PATCH",
+        "/api/preferences",
+        Some(json!({ "learning_enabled": false })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["learning_enabled"], false);
+    assert_eq!(body["preferences"].as_array().unwrap().len(), 1);
+
+    // Another user's preferences are separate.
+    let (_, body) = send(&app, "GET", "/api/preferences?user_id=alice", None).await;
+    assert_eq!(body, json!({ "learning_enabled": true, "preferences": [] }));
+
+    event_store.stop(None);
+}
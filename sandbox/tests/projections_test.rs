@@ -0,0 +1,249 @@
+//! Projection rebuild/replay integration tests
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use http_body_util::BodyExt;
+use ractor::Actor;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+use sandbox::actors::event_store::{AppendEvent, EventStoreMsg};
+use sandbox::actors::{EventStoreActor, EventStoreArguments};
+use sandbox::api;
+use sandbox::app_state::AppState;
+use sandbox::projections::{Projection, ProjectionManager, PROJECTION_STATUS_REBUILDING};
+
+async fn setup_test_app() -> (
+    axum::Router,
+    tempfile::TempDir,
+    Arc<AppState>,
+    ractor::ActorRef<EventStoreMsg>,
+) {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp directory");
+    let db_path = temp_dir.path().join("test_events.db");
+    let db_path_str = db_path.to_str().expect("Invalid database path");
+
+    let (event_store, _handle) = Actor::spawn(
+        None,
+        EventStoreActor,
+        EventStoreArguments::File(db_path_str.to_string()),
+    )
+    .await
+    .expect("Failed to create event store");
+
+    let app_state = Arc::new(AppState::new(event_store.clone()));
+    let ws_sessions: sandbox::api::websocket::WsSessions =
+        Arc::new(tokio::sync::Mutex::new(HashMap::new()));
+
+    let api_state = api::ApiState::new(app_state.clone(), ws_sessions);
+
+    let app = api::router().with_state(api_state);
+    (app, temp_dir, app_state, event_store)
+}
+
+async fn json_response(app: &axum::Router, req: Request<Body>) -> (StatusCode, Value) {
+    let response = app.clone().oneshot(req).await.expect("Request failed");
+    let status = response.status();
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("Failed to read body")
+        .to_bytes();
+    let value: Value = serde_json::from_slice(&body).expect("Invalid JSON response");
+    (status, value)
+}
+
+async fn append(event_store: &ractor::ActorRef<EventStoreMsg>, event_type: &str) -> i64 {
+    ractor::call!(event_store, |reply| EventStoreMsg::Append {
+        event: AppendEvent {
+            event_type: event_type.to_string(),
+            payload: serde_json::json!({}),
+            actor_id: "test".to_string(),
+            user_id: "user-1".to_string(),
+            idempotency_key: None,
+        },
+        reply,
+    })
+    .expect("rpc")
+    .expect("append")
+    .seq
+}
+
+/// Toy projection counting `counter.tick` events.
+struct Counter {
+    count: Arc<AtomicU64>,
+    resets: Arc<AtomicU64>,
+    /// Fail `apply` once at this seq (0 disables).
+    fail_at_seq: Arc<AtomicI64>,
+    failed: Arc<AtomicBool>,
+}
+
+impl Counter {
+    fn new() -> Self {
+        Self {
+            count: Arc::new(AtomicU64::new(0)),
+            resets: Arc::new(AtomicU64::new(0)),
+            fail_at_seq: Arc::new(AtomicI64::new(0)),
+            failed: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    fn projection(&self) -> Projection {
+        let count = self.count.clone();
+        let fail_at_seq = self.fail_at_seq.clone();
+        let failed = self.failed.clone();
+        let reset_count = self.count.clone();
+        let resets = self.resets.clone();
+        Projection::new(
+            "counter",
+            ["counter.*"],
+            move |event| {
+                let count = count.clone();
+                let fail_at_seq = fail_at_seq.clone();
+                let failed = failed.clone();
+                async move {
+                    if event.seq == fail_at_seq.load(Ordering::SeqCst)
+                        && !failed.swap(true, Ordering::SeqCst)
+                    {
+                        return Err("simulated crash".to_string());
+                    }
+                    if event.event_type == "counter.tick" {
+                        count.fetch_add(1, Ordering::SeqCst);
+                    }
+                    Ok(())
+                }
+            },
+            move || {
+                let count = reset_count.clone();
+                let resets = resets.clone();
+                async move {
+                    count.store(0, Ordering::SeqCst);
+                    resets.fetch_add(1, Ordering::SeqCst);
+                    Ok(())
+                }
+            },
+        )
+    }
+}
+
+#[tokio::test]
+async fn test_rebuild_counter_projection_mid_stream() {
+    let (app, _temp_dir, app_state, event_store) = setup_test_app().await;
+    let counter = Counter::new();
+    let manager = app_state.projections();
+    manager
+        .register(counter.projection())
+        .await
+        .expect("register");
+
+    for _ in 0..5 {
+        append(&event_store, "counter.tick").await;
+    }
+    append(&event_store, "unrelated.event").await;
+
+    let applied = manager.catch_up("counter").await.expect("catch up");
+    assert_eq!(applied, 5);
+    assert_eq!(counter.count.load(Ordering::SeqCst), 5);
+
+    // Catch-up is incremental: nothing new, nothing applied.
+    assert_eq!(manager.catch_up("counter").await.expect("catch up"), 0);
+
+    // More events land after the projection is live, then we rebuild.
+    for _ in 0..3 {
+        append(&event_store, "counter.tick").await;
+    }
+    let latest = append(&event_store, "counter.noise").await;
+
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/admin/projections/counter/rebuild")
+        .body(Body::empty())
+        .unwrap();
+    let (status, body) = json_response(&app, req).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["resumed"], false);
+    assert_eq!(body["cursor_seq"], latest);
+    assert_eq!(body["target_seq"], latest);
+    assert_eq!(body["events_applied"], 9);
+    assert_eq!(counter.count.load(Ordering::SeqCst), 8);
+    assert_eq!(counter.resets.load(Ordering::SeqCst), 1);
+
+    // After the rebuild, normal operation picks up only new events.
+    append(&event_store, "counter.tick").await;
+    assert_eq!(manager.catch_up("counter").await.expect("catch up"), 1);
+    assert_eq!(counter.count.load(Ordering::SeqCst), 9);
+
+    let events = ractor::call!(event_store, |reply| EventStoreMsg::GetRecentEvents {
+        since_seq: 0,
+        limit: 1000,
+        event_type_prefix: Some("projection.rebuild.".to_string()),
+        actor_id: None,
+        user_id: None,
+        reply,
+    })
+    .expect("rpc")
+    .expect("query");
+    let types: Vec<&str> = events.iter().map(|e| e.event_type.as_str()).collect();
+    assert_eq!(types.first(), Some(&"projection.rebuild.started"));
+    assert!(types.contains(&"projection.rebuild.progress"));
+    assert_eq!(types.last(), Some(&"projection.rebuild.completed"));
+}
+
+#[tokio::test]
+async fn test_interrupted_rebuild_resumes_from_cursor() {
+    let (event_store, _handle) = Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+        .await
+        .expect("Failed to create event store");
+    let manager = ProjectionManager::with_batch_size(event_store.clone(), 2);
+    let counter = Counter::new();
+    manager
+        .register(counter.projection())
+        .await
+        .expect("register");
+
+    let mut seqs = Vec::new();
+    for _ in 0..6 {
+        seqs.push(append(&event_store, "counter.tick").await);
+    }
+    counter.fail_at_seq.store(seqs[3], Ordering::SeqCst);
+
+    let err = manager.rebuild("counter").await.expect_err("interrupted");
+    assert!(err.to_string().contains("simulated crash"), "{err}");
+
+    let status = manager.status("counter").await.expect("status");
+    assert_eq!(status.status, PROJECTION_STATUS_REBUILDING);
+    assert_eq!(status.cursor_seq, seqs[2]);
+    assert_eq!(counter.count.load(Ordering::SeqCst), 3);
+
+    let report = manager.rebuild("counter").await.expect("resume");
+    assert!(report.resumed);
+    assert_eq!(report.cursor_seq, report.target_seq);
+    assert_eq!(report.events_applied, 3);
+    assert_eq!(counter.count.load(Ordering::SeqCst), 6);
+    assert_eq!(counter.resets.load(Ordering::SeqCst), 1);
+
+    let status = manager.status("counter").await.expect("status");
+    assert_eq!(status.status, "active");
+}
+
+#[tokio::test]
+async fn test_rebuild_unknown_projection_returns_404() {
+    let (app, _temp_dir, _app_state, _event_store) = setup_test_app().await;
+
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/admin/projections/missing/rebuild")
+        .body(Body::empty())
+        .unwrap();
+    let (status, body) = json_response(&app, req).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"]["code"], "NOT_FOUND");
+    assert!(body["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("missing"));
+}
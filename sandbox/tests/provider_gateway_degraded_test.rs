@@ -0,0 +1,211 @@
+//! Degraded mode while the provider gateway is unreachable.
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::routing::get;
+use axum::Router;
+use http_body_util::BodyExt;
+use ractor::Actor;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+use sandbox::actors::event_store::EventStoreMsg;
+use sandbox::actors::{EventStoreActor, EventStoreArguments};
+use sandbox::api;
+use sandbox::app_state::AppState;
+
+async fn setup_test_app() -> (
+    axum::Router,
+    Arc<AppState>,
+    tempfile::TempDir,
+    ractor::ActorRef<EventStoreMsg>,
+) {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp directory");
+    let db_path = temp_dir.path().join("test_events.db");
+    let db_path_str = db_path.to_str().expect("Invalid database path");
+
+    let (event_store, _handle) = Actor::spawn(
+        None,
+        EventStoreActor,
+        EventStoreArguments::File(db_path_str.to_string()),
+    )
+    .await
+    .expect("Failed to create event store");
+
+    let app_state = Arc::new(AppState::new(event_store.clone()));
+    let ws_sessions: sandbox::api::websocket::WsSessions =
+        Arc::new(tokio::sync::Mutex::new(HashMap::new()));
+    let api_state = api::ApiState::new(app_state.clone(), ws_sessions);
+
+    let app = api::router().with_state(api_state);
+    (app, app_state, temp_dir, event_store)
+}
+
+/// Serve the gateway health endpoint on a random port; `up` decides whether it
+/// answers 200 or 503.
+async fn spawn_stub_gateway(up: Arc<AtomicBool>) -> String {
+    let app = Router::new().route(
+        sandbox::runtime_env::PROVIDER_GATEWAY_HEALTH_PATH,
+        get(move || {
+            let up = up.clone();
+            async move {
+                if up.load(Ordering::SeqCst) {
+                    StatusCode::OK
+                } else {
+                    StatusCode::SERVICE_UNAVAILABLE
+                }
+            }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind stub gateway");
+    let addr = listener.local_addr().expect("stub gateway addr");
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.expect("stub gateway");
+    });
+    format!("http://{addr}")
+}
+
+async fn json_response(app: &axum::Router, req: Request<Body>) -> (StatusCode, Value) {
+    let response = app.clone().oneshot(req).await.expect("Request failed");
+    let status = response.status();
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("Failed to read body")
+        .to_bytes();
+    let value: Value = serde_json::from_slice(&body).expect("Invalid JSON response");
+    (status, value)
+}
+
+async fn post(app: &axum::Router, uri: &str, body: Value) -> (StatusCode, Value) {
+    let req = Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    json_response(app, req).await
+}
+
+async fn events_with_prefix(
+    event_store: &ractor::ActorRef<EventStoreMsg>,
+    prefix: &str,
+) -> Vec<shared_types::Event> {
+    ractor::call!(event_store, |reply| EventStoreMsg::GetRecentEvents {
+        since_seq: 0,
+        limit: 500,
+        event_type_prefix: Some(prefix.to_string()),
+        actor_id: None,
+        user_id: None,
+        reply,
+    })
+    .expect("rpc")
+    .expect("query")
+}
+
+#[tokio::test]
+async fn test_unreachable_gateway_queues_runs_and_recovery_resumes_them() {
+    let (app, app_state, _temp_dir, event_store) = setup_test_app().await;
+    let up = Arc::new(AtomicBool::new(true));
+    let gateway = spawn_stub_gateway(up.clone()).await;
+
+    assert!(app_state.check_provider_gateway(&gateway).await);
+    assert!(events_with_prefix(&event_store, "provider.gateway.")
+        .await
+        .is_empty());
+
+    up.store(false, Ordering::SeqCst);
+    assert!(!app_state.check_provider_gateway(&gateway).await);
+    assert!(!app_state.provider_gateway_reachable());
+    let gateway_events = events_with_prefix(&event_store, "provider.gateway.").await;
+    assert_eq!(gateway_events.len(), 1);
+    assert_eq!(
+        gateway_events[0].event_type,
+        shared_types::EVENT_TOPIC_PROVIDER_GATEWAY_UNREACHABLE
+    );
+    assert_eq!(gateway_events[0].payload["_meta"]["lane"], "control");
+
+    let (status, body) = post(
+        &app,
+        "/conductor/execute",
+        json!({
+            "objective": "Summarize the release notes",
+            "desktop_id": "degraded-desktop",
+            "output_mode": "auto",
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::ACCEPTED, "{body}");
+    assert_eq!(body["status"], "waiting_for_provider");
+    assert_eq!(body["toast"]["tone"], "warning");
+    let run_id = body["run_id"].as_str().unwrap().to_string();
+    let doc_path = format!("conductor/runs/{run_id}/draft.md");
+
+    let req = Request::builder()
+        .uri(format!("/conductor/runs/{run_id}"))
+        .body(Body::empty())
+        .unwrap();
+    let (status, run) = json_response(&app, req).await;
+    assert_eq!(status, StatusCode::OK, "{run}");
+    assert_eq!(run["status"], "waiting_for_provider");
+
+    let (status, body) = post(
+        &app,
+        "/writer/prompt",
+        json!({
+            "path": &doc_path,
+            "base_version_id": 0,
+            "prompt_diff": [{ "op": "insert", "pos": 0, "text": "hello?" }],
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE, "{body}");
+    assert_eq!(body["error"]["code"], "PROVIDER_UNAVAILABLE");
+    assert!(body["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("provider gateway is unreachable"));
+
+    up.store(true, Ordering::SeqCst);
+    assert!(app_state.check_provider_gateway(&gateway).await);
+    let gateway_events = events_with_prefix(&event_store, "provider.gateway.").await;
+    assert_eq!(
+        gateway_events.last().map(|event| event.event_type.as_str()),
+        Some(shared_types::EVENT_TOPIC_PROVIDER_GATEWAY_RECOVERED)
+    );
+
+    let mut resumed = false;
+    for _ in 0..50 {
+        resumed = events_with_prefix(&event_store, "conductor.task.progress")
+            .await
+            .iter()
+            .any(|event| {
+                event.payload["run_id"] == run_id.as_str()
+                    && event.payload["details"]["resumed_after"] == "provider_gateway_recovered"
+            });
+        if resumed {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    assert!(resumed, "queued run was not resumed after recovery");
+
+    let _ = tokio::fs::remove_dir_all(
+        sandbox::paths::writer_root()
+            .join("conductor/runs")
+            .join(&run_id),
+    )
+    .await;
+    let _ = tokio::fs::remove_file(
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join(".writer_revisions")
+            .join(format!("{}.rev", doc_path.replace('/', "__"))),
+    )
+    .await;
+}
@@ -0,0 +1,463 @@
+//! Research app API integration tests
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use http_body_util::BodyExt;
+use ractor::Actor;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+use sandbox::actors::event_store::{AppendEvent, EventStoreMsg};
+use sandbox::actors::{EventStoreActor, EventStoreArguments};
+use sandbox::api;
+use sandbox::app_state::AppState;
+
+async fn setup_test_app() -> (
+    axum::Router,
+    tempfile::TempDir,
+    ractor::ActorRef<EventStoreMsg>,
+) {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp directory");
+    let db_path = temp_dir.path().join("test_events.db");
+    let db_path_str = db_path.to_str().expect("Invalid database path");
+
+    let (event_store, _handle) = Actor::spawn(
+        None,
+        EventStoreActor,
+        EventStoreArguments::File(db_path_str.to_string()),
+    )
+    .await
+    .expect("Failed to create event store");
+
+    let app_state = Arc::new(AppState::new(event_store.clone()));
+    let ws_sessions: sandbox::api::websocket::WsSessions =
+        Arc::new(tokio::sync::Mutex::new(HashMap::new()));
+    let api_state = api::ApiState::new(app_state, ws_sessions);
+
+    let app = api::router().with_state(api_state);
+    (app, temp_dir, event_store)
+}
+
+async fn json_response(app: &axum::Router, req: Request<Body>) -> (StatusCode, Value) {
+    let response = app.clone().oneshot(req).await.expect("Request failed");
+    let status = response.status();
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("Failed to read body")
+        .to_bytes();
+    let value: Value = serde_json::from_slice(&body).expect("Invalid JSON response");
+    (status, value)
+}
+
+async fn get(app: &axum::Router, uri: &str) -> (StatusCode, Value) {
+    let req = Request::builder().uri(uri).body(Body::empty()).unwrap();
+    json_response(app, req).await
+}
+
+async fn post(app: &axum::Router, uri: &str, body: Value) -> (StatusCode, Value) {
+    let req = Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    json_response(app, req).await
+}
+
+async fn append(event_store: &ractor::ActorRef<EventStoreMsg>, event_type: &str, payload: Value) {
+    ractor::call!(event_store, |reply| EventStoreMsg::Append {
+        event: AppendEvent {
+            event_type: event_type.to_string(),
+            payload,
+            actor_id: "researcher-1".to_string(),
+            user_id: "system".to_string(),
+            idempotency_key: None,
+        },
+        reply,
+    })
+    .expect("rpc")
+    .expect("append");
+}
+
+async fn start_task(
+    event_store: &ractor::ActorRef<EventStoreMsg>,
+    task_id: &str,
+    objective: &str,
+    run_id: Option<&str>,
+) {
+    append(
+        event_store,
+        shared_types::EVENT_TOPIC_RESEARCH_TASK_STARTED,
+        json!({
+            "task_id": task_id,
+            "researcher_id": "researcher-1",
+            "objective": objective,
+            "run_id": run_id,
+            "call_id": null,
+            "rerun_of": null,
+            "timestamp": "2026-03-01T00:00:00Z",
+        }),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_research_tasks_are_listed_with_results_and_progress() {
+    let (app, _temp_dir, event_store) = setup_test_app().await;
+
+    start_task(&event_store, "task-a", "Compare rust web frameworks", None).await;
+    start_task(&event_store, "task-b", "Summarize sqlite WAL mode", None).await;
+    for (task_id, message) in [
+        ("task-a", "searching tavily"),
+        ("task-b", "searching exa"),
+        ("task-a", "fetched 3 results"),
+    ] {
+        append(
+            &event_store,
+            shared_types::EVENT_TOPIC_WORKER_TASK_PROGRESS,
+            json!({
+                "task_id": task_id,
+                "worker_id": "researcher-1",
+                "phase": "research_provider_call",
+                "message": message,
+                "provider": "tavily",
+                "timestamp": "2026-03-01T00:00:01Z",
+            }),
+        )
+        .await;
+    }
+    append(
+        &event_store,
+        shared_types::EVENT_TOPIC_RESEARCH_TASK_COMPLETED,
+        json!({
+            "task_id": "task-a",
+            "researcher_id": "researcher-1",
+            "run_id": null,
+            "success": true,
+            "summary": "Axum is the most used [1].",
+            "provider_used": "tavily",
+            "model_used": "test-model",
+            "citations": [{
+                "id": "c1",
+                "provider": "tavily",
+                "title": "Axum docs",
+                "url": "https://docs.rs/axum",
+                "snippet": "Ergonomic web framework",
+                "published_at": null,
+                "score": 0.9,
+            }],
+            "provider_calls": [{
+                "provider": "tavily",
+                "latency_ms": 420,
+                "result_count": 1,
+                "succeeded": true,
+                "error": null,
+            }],
+            "timestamp": "2026-03-01T00:00:02Z",
+        }),
+    )
+    .await;
+
+    let (status, tasks) = get(&app, "/api/research/tasks").await;
+    assert_eq!(status, StatusCode::OK, "{tasks}");
+    let tasks = tasks.as_array().unwrap();
+    assert_eq!(tasks.len(), 2);
+    assert_eq!(tasks[0]["task_id"], "task-b");
+    assert_eq!(tasks[0]["status"], "running");
+    assert_eq!(tasks[1]["task_id"], "task-a");
+    assert_eq!(tasks[1]["status"], "completed");
+    assert_eq!(tasks[1]["citation_count"], 1);
+
+    let (_, limited) = get(&app, "/api/research/tasks?limit=1").await;
+    assert_eq!(limited.as_array().unwrap().len(), 1);
+
+    let (status, detail) = get(&app, "/api/research/tasks/task-a").await;
+    assert_eq!(status, StatusCode::OK, "{detail}");
+    assert_eq!(detail["summary"], "Axum is the most used [1].");
+    assert_eq!(detail["citations"][0]["url"], "https://docs.rs/axum");
+    assert_eq!(detail["provider_calls"][0]["latency_ms"], 420);
+    let progress: Vec<&str> = detail["progress"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|line| line["message"].as_str().unwrap())
+        .collect();
+    assert_eq!(progress, ["searching tavily", "fetched 3 results"]);
+
+    let (status, body) = get(&app, "/api/research/tasks/missing").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"]["code"], "NOT_FOUND");
+}
+
+#[tokio::test]
+async fn test_research_tasks_are_listed_per_desktop() {
+    let (app, _temp_dir, event_store) = setup_test_app().await;
+
+    // A conductor-dispatched task takes its desktop from the run.
+    append(
+        &event_store,
+        shared_types::EVENT_TOPIC_CONDUCTOR_RUN_STARTED,
+        json!({
+            "run_id": "run-1",
+            "capability": "conductor",
+            "phase": "started",
+            "data": { "objective": "Plan a trip", "desktop_id": "desktop-a" },
+        }),
+    )
+    .await;
+    start_task(&event_store, "task-run", "Find flights", Some("run-1")).await;
+    append(
+        &event_store,
+        shared_types::EVENT_TOPIC_RESEARCH_TASK_STARTED,
+        json!({
+            "task_id": "task-app",
+            "researcher_id": "researcher-1",
+            "objective": "Compare hotels",
+            "run_id": null,
+            "call_id": null,
+            "rerun_of": null,
+            "desktop_id": "desktop-b",
+            "timestamp": "2026-03-01T00:00:00Z",
+        }),
+    )
+    .await;
+
+    let task_ids = |tasks: &Value| -> Vec<String> {
+        tasks
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|task| task["task_id"].as_str().unwrap().to_string())
+            .collect()
+    };
+    let (status, all) = get(&app, "/api/research/tasks").await;
+    assert_eq!(status, StatusCode::OK, "{all}");
+    assert_eq!(task_ids(&all), ["task-app", "task-run"]);
+    let (_, desktop_a) = get(&app, "/api/research/tasks?desktop_id=desktop-a").await;
+    assert_eq!(task_ids(&desktop_a), ["task-run"]);
+    assert_eq!(desktop_a[0]["desktop_id"], "desktop-a");
+
+    // Events appended after the first listing are folded in on the next one.
+    start_task(&event_store, "task-run-2", "Find trains", Some("run-1")).await;
+    let (_, desktop_a) = get(&app, "/api/research/tasks?desktop_id=desktop-a").await;
+    assert_eq!(task_ids(&desktop_a), ["task-run-2", "task-run"]);
+    let (_, desktop_b) = get(&app, "/api/research/tasks?desktop_id=desktop-b").await;
+    assert_eq!(task_ids(&desktop_b), ["task-app"]);
+}
+
+#[tokio::test]
+async fn test_send_to_writer_appends_result_as_section() {
+    let (app, _temp_dir, event_store) = setup_test_app().await;
+    let run_id = format!("test-research-{}", ulid::Ulid::new());
+    let doc_path = format!("conductor/runs/{run_id}/draft.md");
+
+    let (status, body) = post(
+        &app,
+        "/writer/ensure",
+        json!({ "path": &doc_path, "objective": "Research test", "desktop_id": "default-desktop" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+
+    start_task(
+        &event_store,
+        "task-w",
+        "Explain event sourcing",
+        Some(&run_id),
+    )
+    .await;
+    let uri = "/api/research/tasks/task-w/send-to-writer";
+    let (status, body) = post(&app, uri, json!({})).await;
+    assert_eq!(status, StatusCode::CONFLICT, "running tasks have no result");
+    assert_eq!(body["error"]["code"], "CONFLICT");
+
+    append(
+        &event_store,
+        shared_types::EVENT_TOPIC_RESEARCH_TASK_COMPLETED,
+        json!({
+            "task_id": "task-w",
+            "researcher_id": "researcher-1",
+            "run_id": &run_id,
+            "success": true,
+            "summary": "State is derived from an append-only log [1].",
+            "provider_used": "exa",
+            "model_used": null,
+            "citations": [{
+                "id": "c1",
+                "provider": "exa",
+                "title": "Event Sourcing",
+                "url": "https://martinfowler.com/eaaDev/EventSourcing.html",
+                "snippet": "",
+                "published_at": null,
+                "score": null,
+            }],
+            "provider_calls": [],
+            "timestamp": "2026-03-01T00:00:02Z",
+        }),
+    )
+    .await;
+
+    let (status, sent) = post(&app, uri, json!({})).await;
+    assert_eq!(status, StatusCode::OK, "{sent}");
+    assert_eq!(sent["path"], doc_path.as_str());
+    assert_eq!(sent["section_id"], "research:task-w");
+
+    let (status, opened) = post(&app, "/writer/open", json!({ "path": &doc_path })).await;
+    assert_eq!(status, StatusCode::OK, "{opened}");
+    let content = opened["content"].as_str().unwrap();
+    assert!(
+        content.contains("## Research: Explain event sourcing"),
+        "{content}"
+    );
+    assert!(
+        content.contains("1. [Event Sourcing](https://martinfowler.com/eaaDev/EventSourcing.html)")
+    );
+
+    let (status, body) = post(&app, uri, json!({ "path": "notes/not-a-run-document.md" })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "INVALID_REQUEST");
+
+    let _ = tokio::fs::remove_dir_all(
+        sandbox::paths::writer_root()
+            .join("conductor/runs")
+            .join(&run_id),
+    )
+    .await;
+    let _ = tokio::fs::remove_file(
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join(".writer_revisions")
+            .join(format!("{}.rev", doc_path.replace('/', "__"))),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_batch_starts_one_task_per_query_under_one_batch_id() {
+    let (app, _temp_dir, event_store) = setup_test_app().await;
+
+    let (status, body) = post(&app, "/api/research/batch", json!({ "queries": [" "] })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "INVALID_REQUEST");
+
+    let queries = [
+        "Compare rust web frameworks",
+        "Summarize sqlite WAL mode",
+        "Explain event sourcing",
+    ];
+    let (status, submitted) = post(
+        &app,
+        "/api/research/batch",
+        json!({ "queries": queries, "max_rounds": 6 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::ACCEPTED, "{submitted}");
+    let batch_id = submitted["batch_id"].as_str().unwrap().to_string();
+    let task_ids: Vec<String> = submitted["task_ids"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|id| id.as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(task_ids.len(), 3);
+
+    // Started events for the whole batch are written before any loop runs.
+    let uri = format!("/api/research/batch/{batch_id}");
+    let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(10);
+    let batch = loop {
+        let (status, batch) = get(&app, &uri).await;
+        if status == StatusCode::OK && batch["tasks"].as_array().map(Vec::len) == Some(3) {
+            break batch;
+        }
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "batch did not start: {batch}"
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    };
+    let tasks = batch["tasks"].as_array().unwrap();
+    for ((task, task_id), objective) in tasks.iter().zip(&task_ids).zip(queries) {
+        assert_eq!(task["task_id"], task_id.as_str());
+        assert_eq!(task["objective"], objective);
+        assert_eq!(task["batch_id"], batch_id.as_str());
+    }
+
+    // Per-query outcomes fold into the batch from the task events.
+    for (task_id, objective) in [("batch-a", "one"), ("batch-b", "two"), ("batch-c", "three")] {
+        append(
+            &event_store,
+            shared_types::EVENT_TOPIC_RESEARCH_TASK_STARTED,
+            json!({
+                "task_id": task_id,
+                "researcher_id": "researcher-1",
+                "objective": objective,
+                "run_id": null,
+                "call_id": null,
+                "rerun_of": null,
+                "batch_id": "batch-1",
+                "timestamp": "2026-03-01T00:00:00Z",
+            }),
+        )
+        .await;
+    }
+    for task_id in ["batch-a", "batch-c"] {
+        append(
+            &event_store,
+            shared_types::EVENT_TOPIC_RESEARCH_TASK_COMPLETED,
+            json!({
+                "task_id": task_id,
+                "researcher_id": "researcher-1",
+                "run_id": null,
+                "success": true,
+                "summary": "Done.",
+                "provider_used": null,
+                "model_used": null,
+                "citations": [],
+                "provider_calls": [],
+                "timestamp": "2026-03-01T00:00:02Z",
+            }),
+        )
+        .await;
+    }
+    append(
+        &event_store,
+        shared_types::EVENT_TOPIC_RESEARCH_TASK_FAILED,
+        json!({
+            "task_id": "batch-b",
+            "researcher_id": "researcher-1",
+            "run_id": null,
+            "error": "provider timeout",
+            "timestamp": "2026-03-01T00:00:02Z",
+        }),
+    )
+    .await;
+
+    let (status, batch) = get(&app, "/api/research/batch/batch-1").await;
+    assert_eq!(status, StatusCode::OK, "{batch}");
+    let statuses: Vec<(&str, &str)> = batch["tasks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|task| {
+            (
+                task["task_id"].as_str().unwrap(),
+                task["status"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        statuses,
+        [
+            ("batch-a", "completed"),
+            ("batch-b", "failed"),
+            ("batch-c", "completed")
+        ]
+    );
+
+    let (status, body) = get(&app, "/api/research/batch/missing").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"]["code"], "NOT_FOUND");
+}
@@ -0,0 +1,301 @@
+//! Run audit trail export integration tests.
+//!
+//! Run:
+//!   cargo test -p sandbox --test run_audit_test -- --nocapture
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use http_body_util::BodyExt;
+use ractor::Actor;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{Cursor, Write};
+use std::sync::Arc;
+use tower::ServiceExt;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use sandbox::actors::{EventStoreActor, EventStoreArguments};
+use sandbox::api;
+use sandbox::app_state::AppState;
+
+async fn setup_test_app() -> (axum::Router, tempfile::TempDir) {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp directory");
+    let db_path = temp_dir.path().join("test_events.db");
+    let db_path_str = db_path.to_str().expect("Invalid database path");
+
+    let (event_store, _handle) = Actor::spawn(
+        None,
+        EventStoreActor,
+        EventStoreArguments::File(db_path_str.to_string()),
+    )
+    .await
+    .expect("Failed to create event store");
+
+    let api_state = api::ApiState::new(
+        Arc::new(AppState::new(event_store)),
+        Arc::new(tokio::sync::Mutex::new(HashMap::new())),
+    );
+    (api::router().with_state(api_state), temp_dir)
+}
+
+async fn send(router: &axum::Router, req: Request<Body>) -> (StatusCode, Vec<u8>) {
+    let response = router.clone().oneshot(req).await.expect("Request failed");
+    let status = response.status();
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("Failed to read body")
+        .to_bytes();
+    (status, body.to_vec())
+}
+
+async fn get(router: &axum::Router, uri: &str) -> (StatusCode, Vec<u8>) {
+    let req = Request::builder().uri(uri).body(Body::empty()).unwrap();
+    send(router, req).await
+}
+
+fn decision(id: &str, decision_type: &str, at: &str) -> Value {
+    json!({
+        "decision_id": id,
+        "decision_type": decision_type,
+        "reason": format!("{decision_type} step"),
+        "timestamp": at,
+        "affected_agenda_items": [],
+        "new_agenda_items": [],
+    })
+}
+
+fn call(id: &str, capability: &str, started_at: &str, completed_at: &str) -> Value {
+    json!({
+        "call_id": id,
+        "capability": capability,
+        "objective": format!("{capability} work"),
+        "status": "completed",
+        "started_at": started_at,
+        "completed_at": completed_at,
+        "parent_call_id": null,
+        "agenda_item_id": null,
+        "artifact_ids": [],
+        "error": null,
+    })
+}
+
+fn event(seq: i64, event_type: &str, payload: Value) -> Value {
+    json!({
+        "seq": seq,
+        "event_id": format!("01EVENT{seq:020}"),
+        "timestamp": "2026-10-01T12:00:00Z",
+        "stored_at": "2026-10-01T12:00:00Z",
+        "actor_id": "conductor-1",
+        "event_type": event_type,
+        "payload": payload,
+        "user_id": "system",
+    })
+}
+
+/// Import a completed run through the bundle endpoint and return its new id.
+async fn import_completed_run(router: &axum::Router) -> String {
+    let source = "01AUDITSOURCE0000000000000";
+    let run_state = json!({
+        "run_id": source,
+        "objective": "Audit hosting costs",
+        "status": "completed",
+        "created_at": "2026-10-01T12:00:00Z",
+        "updated_at": "2026-10-01T12:05:00Z",
+        "completed_at": "2026-10-01T12:05:00Z",
+        "agenda": [],
+        "active_calls": [
+            call("call-1", "researcher", "2026-10-01T12:00:02Z", "2026-10-01T12:02:00Z"),
+            call("call-2", "writer", "2026-10-01T12:02:01Z", "2026-10-01T12:04:00Z"),
+        ],
+        "artifacts": [{
+            "artifact_id": "artifact-1",
+            "kind": "report",
+            "reference": "conductor/runs/report.md",
+            "mime_type": "text/markdown",
+            "created_at": "2026-10-01T12:04:00Z",
+            "source_call_id": "call-2",
+            "metadata": null,
+        }],
+        "decision_log": [
+            decision("decision-1", "dispatch", "2026-10-01T12:00:01Z"),
+            decision("decision-2", "dispatch", "2026-10-01T12:02:00Z"),
+            decision("decision-3", "complete", "2026-10-01T12:04:30Z"),
+        ],
+        "document_path": format!("conductor/runs/{source}/draft.md"),
+        "output_mode": "auto",
+        "desktop_id": "desktop-1",
+    });
+    let events = [
+        event(
+            1,
+            "conductor.run.started",
+            json!({ "run_id": source, "objective": "Audit hosting costs" }),
+        ),
+        event(
+            2,
+            "conductor.escalation",
+            json!({
+                "run_id": source,
+                "data": {
+                    "escalation_id": "esc-1",
+                    "kind": "approval",
+                    "reason": "budget exceeded",
+                    "urgency": "high",
+                },
+            }),
+        ),
+        event(
+            3,
+            "conductor.task.completed",
+            json!({ "run_id": source, "api_key": "sk-should-not-appear" }),
+        ),
+    ];
+    let manifest = json!({
+        "bundle_schema_version": 1,
+        "event_schema_version": 1,
+        "run_id": source,
+        "objective": "Audit hosting costs",
+        "exported_at": "2026-10-01T12:06:00Z",
+        "sandbox_version": "0.1.0",
+        "event_count": events.len(),
+        "prompts_captured": false,
+        "files": ["events.jsonl", "run_state.json", "manifest.json"],
+    });
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    zip.start_file("events.jsonl", SimpleFileOptions::default())
+        .unwrap();
+    for event in &events {
+        writeln!(zip, "{event}").unwrap();
+    }
+    zip.start_file("run_state.json", SimpleFileOptions::default())
+        .unwrap();
+    zip.write_all(run_state.to_string().as_bytes()).unwrap();
+    zip.start_file("manifest.json", SimpleFileOptions::default())
+        .unwrap();
+    zip.write_all(manifest.to_string().as_bytes()).unwrap();
+    let bundle = zip.finish().unwrap().into_inner();
+
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/conductor/runs/import")
+        .header("content-type", "application/zip")
+        .body(Body::from(bundle))
+        .unwrap();
+    let (status, body) = send(router, req).await;
+    let imported: Value = serde_json::from_slice(&body).expect("Invalid JSON response");
+    assert_eq!(status, StatusCode::CREATED, "{imported}");
+    imported["run_id"].as_str().unwrap().to_string()
+}
+
+async fn cleanup_run(run_id: &str) {
+    let _ = tokio::fs::remove_dir_all(
+        sandbox::paths::writer_root()
+            .join("conductor/runs")
+            .join(run_id),
+    )
+    .await;
+}
+
+fn ids(values: &Value, key: &str) -> Vec<String> {
+    values
+        .as_array()
+        .expect("array")
+        .iter()
+        .map(|value| value[key].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_audit_of_completed_run_lists_decisions_and_calls_in_order() {
+    let (router, _temp_dir) = setup_test_app().await;
+    let run_id = import_completed_run(&router).await;
+
+    let (status, body) = get(&router, &format!("/conductor/runs/{run_id}/audit")).await;
+    let audit: Value = serde_json::from_slice(&body).expect("Invalid JSON response");
+    assert_eq!(status, StatusCode::OK, "{audit}");
+
+    let header = &audit["header"];
+    assert_eq!(header["audit_schema_version"], 1);
+    assert_eq!(header["run_id"], run_id.as_str());
+    assert_eq!(header["status"], "completed");
+    assert_eq!(header["decision_count"], 3);
+    assert_eq!(header["call_count"], 2);
+    assert_eq!(header["event_count"], 3);
+    assert_eq!(
+        ids(&audit["decisions"], "decision_id"),
+        ["decision-1", "decision-2", "decision-3"]
+    );
+    assert_eq!(ids(&audit["calls"], "call_id"), ["call-1", "call-2"]);
+    assert_eq!(ids(&audit["artifacts"], "artifact_id"), ["artifact-1"]);
+    assert_eq!(audit["escalations"][0]["escalation_id"], "esc-1");
+    assert_eq!(audit["escalations"][0]["reason"], "budget exceeded");
+
+    let events = audit["events"].as_array().unwrap();
+    assert_eq!(
+        ids(&audit["events"], "event_type"),
+        [
+            "conductor.run.started",
+            "conductor.escalation",
+            "conductor.task.completed"
+        ]
+    );
+    assert!(events
+        .windows(2)
+        .all(|pair| pair[0]["seq"].as_i64() < pair[1]["seq"].as_i64()));
+    assert!(!body
+        .windows(b"sk-should-not-appear".len())
+        .any(|window| window == b"sk-should-not-appear"));
+
+    let (status, body) = get(
+        &router,
+        &format!("/conductor/runs/{run_id}/audit?format=ndjson"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let records: Vec<Value> = std::str::from_utf8(&body)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("audit line"))
+        .collect();
+    let kinds: Vec<&str> = records
+        .iter()
+        .map(|record| record["record"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            "header",
+            "decision",
+            "decision",
+            "decision",
+            "call",
+            "call",
+            "artifact",
+            "escalation",
+            "event",
+            "event",
+            "event"
+        ]
+    );
+    assert_eq!(records[1]["decision_id"], "decision-1");
+    assert_eq!(records[3]["decision_id"], "decision-3");
+    assert_eq!(records[4]["call_id"], "call-1");
+    assert_eq!(records[5]["call_id"], "call-2");
+
+    cleanup_run(&run_id).await;
+}
+
+#[tokio::test]
+async fn test_audit_rejects_unknown_run_and_format() {
+    let (router, _temp_dir) = setup_test_app().await;
+
+    let (status, _) = get(&router, "/conductor/runs/01NOSUCHRUN000000000000000/audit").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = get(&router, "/conductor/runs/any/audit?format=csv").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,332 @@
+//! Search index and /api/search integration tests
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use http_body_util::BodyExt;
+use ractor::Actor;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+use sandbox::actors::event_store::{search_index, AppendEvent, EventStoreMsg};
+use sandbox::actors::{EventStoreActor, EventStoreArguments};
+use sandbox::api;
+use sandbox::app_state::AppState;
+
+async fn setup_test_app() -> (
+    axum::Router,
+    tempfile::TempDir,
+    Arc<AppState>,
+    ractor::ActorRef<EventStoreMsg>,
+) {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp directory");
+    let db_path = temp_dir.path().join("test_events.db");
+    let db_path_str = db_path.to_str().expect("Invalid database path");
+
+    let (event_store, _handle) = Actor::spawn(
+        None,
+        EventStoreActor,
+        EventStoreArguments::File(db_path_str.to_string()),
+    )
+    .await
+    .expect("Failed to create event store");
+
+    let app_state = Arc::new(AppState::new(event_store.clone()));
+    let ws_sessions: sandbox::api::websocket::WsSessions =
+        Arc::new(tokio::sync::Mutex::new(HashMap::new()));
+
+    let api_state = api::ApiState::new(app_state.clone(), ws_sessions);
+
+    let app = api::router().with_state(api_state);
+    (app, temp_dir, app_state, event_store)
+}
+
+async fn json_response(app: &axum::Router, req: Request<Body>) -> (StatusCode, Value) {
+    let response = app.clone().oneshot(req).await.expect("Request failed");
+    let status = response.status();
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .expect("Failed to read body")
+        .to_bytes();
+    let value: Value = serde_json::from_slice(&body).expect("Invalid JSON response");
+    (status, value)
+}
+
+async fn get(app: &axum::Router, uri: &str) -> (StatusCode, Value) {
+    let req = Request::builder().uri(uri).body(Body::empty()).unwrap();
+    json_response(app, req).await
+}
+
+async fn post(app: &axum::Router, uri: &str, body: Value) -> (StatusCode, Value) {
+    let req = Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    json_response(app, req).await
+}
+
+async fn append(
+    event_store: &ractor::ActorRef<EventStoreMsg>,
+    event_type: &str,
+    actor_id: &str,
+    payload: Value,
+) -> i64 {
+    ractor::call!(event_store, |reply| EventStoreMsg::Append {
+        event: AppendEvent {
+            event_type: event_type.to_string(),
+            payload,
+            actor_id: actor_id.to_string(),
+            user_id: "user-1".to_string(),
+            idempotency_key: None,
+        },
+        reply,
+    })
+    .expect("rpc")
+    .expect("append")
+    .seq
+}
+
+async fn append_chat(event_store: &ractor::ActorRef<EventStoreMsg>, content: &str) -> i64 {
+    let record = shared_types::UserInputRecord {
+        input_id: ulid::Ulid::new().to_string(),
+        content: content.to_string(),
+        surface: shared_types::UserInputSurface::Conductor,
+        desktop_id: "desktop-1".to_string(),
+        session_id: "session-1".to_string(),
+        thread_id: "thread-1".to_string(),
+        run_id: None,
+        document_path: None,
+        base_version_id: None,
+        created_at: chrono::Utc::now(),
+    };
+    append(
+        event_store,
+        shared_types::EVENT_TOPIC_USER_INPUT,
+        "api.conductor",
+        json!({ "surface": "conductor.execute", "record": record }),
+    )
+    .await
+}
+
+async fn append_version(
+    event_store: &ractor::ActorRef<EventStoreMsg>,
+    path: &str,
+    version_id: u64,
+    content: &str,
+    overlay_id: Option<&str>,
+) -> i64 {
+    append(
+        event_store,
+        shared_types::EVENT_TOPIC_WRITER_RUN_PATCH,
+        "writer:run-1",
+        json!({
+            "desktop_id": "desktop-1",
+            "session_id": "session-1",
+            "thread_id": "thread-1",
+            "scope": { "session_id": "session-1", "thread_id": "thread-1" },
+            "run_id": "run-1",
+            "document_path": path,
+            "revision": version_id,
+            "head_version_id": version_id,
+            "timestamp": chrono::Utc::now(),
+            "patch_id": ulid::Ulid::new().to_string(),
+            "source": "agent",
+            "ops": [
+                { "op": "delete", "pos": 0, "len": u64::MAX },
+                { "op": "insert", "pos": 0, "text": content },
+            ],
+            "base_version_id": version_id - 1,
+            "target_version_id": overlay_id.is_none().then_some(version_id),
+            "overlay_id": overlay_id,
+        }),
+    )
+    .await
+}
+
+fn hit_kinds(body: &Value) -> Vec<String> {
+    body["hits"]
+        .as_array()
+        .expect("hits")
+        .iter()
+        .map(|hit| hit["kind"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_search_returns_typed_hits_with_highlights_and_targets() {
+    let (app, _temp_dir, _app_state, event_store) = setup_test_app().await;
+
+    let chat_seq = append_chat(&event_store, "Where did we land on the pricing model?").await;
+    append_version(
+        &event_store,
+        "conductor/runs/run-1/draft.md",
+        2,
+        "# Plan\n\nThe pricing tiers are free, pro and team.",
+        None,
+    )
+    .await;
+    // Overlay proposals are not saved versions and stay out of the index.
+    append_version(
+        &event_store,
+        "conductor/runs/run-1/draft.md",
+        3,
+        "Proposed pricing rewrite",
+        Some("overlay-1"),
+    )
+    .await;
+    append(
+        &event_store,
+        shared_types::EVENT_TOPIC_ARTIFACT_CREATED,
+        "researcher:1",
+        json!({
+            "artifact": {
+                "artifact_id": "artifact-1",
+                "kind": "research_note",
+                "reference": "Competitor pricing survey notes",
+            },
+        }),
+    )
+    .await;
+    append_chat(&event_store, "unrelated message about deployment").await;
+
+    let (status, body) = get(&app, "/api/search?q=pricing").await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let mut kinds = hit_kinds(&body);
+    kinds.sort();
+    assert_eq!(kinds, vec!["artifact", "chat", "document"]);
+
+    let chat = body["hits"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|hit| hit["kind"] == "chat")
+        .unwrap();
+    assert_eq!(chat["seq"], chat_seq);
+    assert_eq!(chat["target"]["type"], "chat");
+    assert_eq!(chat["target"]["actor_id"], "api.conductor");
+    assert_eq!(chat["target"]["thread_id"], "thread-1");
+    assert!(chat["timestamp"].as_str().is_some());
+    let highlighted: Vec<&str> = chat["excerpt"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|segment| segment["highlight"] == true)
+        .map(|segment| segment["text"].as_str().unwrap())
+        .collect();
+    assert_eq!(highlighted, vec!["pricing"]);
+
+    let document = body["hits"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|hit| hit["kind"] == "document")
+        .unwrap();
+    assert_eq!(document["target"]["type"], "document");
+    assert_eq!(document["target"]["path"], "conductor/runs/run-1/draft.md");
+    assert_eq!(document["target"]["revision"], 2);
+
+    // Multi-word queries match all words, the last one as a prefix.
+    let (_, body) = get(&app, "/api/search?q=pricing%20mod").await;
+    assert_eq!(hit_kinds(&body), vec!["chat"]);
+
+    let (_, body) = get(
+        &app,
+        "/api/search?q=pricing&kinds=document,artifact&limit=1",
+    )
+    .await;
+    assert_eq!(body["hits"].as_array().unwrap().len(), 1);
+    assert_ne!(body["hits"][0]["kind"], "chat");
+}
+
+#[tokio::test]
+async fn test_redacted_event_is_removed_from_index_and_rebuild() {
+    let (app, _temp_dir, _app_state, event_store) = setup_test_app().await;
+
+    let secret_seq = append_chat(&event_store, "the vault passphrase is marmalade").await;
+    append_chat(&event_store, "marmalade on toast for breakfast").await;
+
+    let (_, body) = get(&app, "/api/search?q=marmalade").await;
+    assert_eq!(body["hits"].as_array().unwrap().len(), 2);
+
+    let (status, body) = post(
+        &app,
+        &format!("/api/admin/events/{secret_seq}/redact"),
+        json!({ "reason": "credential" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["seq"], secret_seq);
+
+    // Gone from the index immediately, without relying on search-time catch-up.
+    let matches = search_index(&event_store, "\"passphrase\"", Vec::new(), 10)
+        .await
+        .expect("rpc")
+        .expect("search");
+    assert!(matches.is_empty());
+
+    let (_, body) = get(&app, "/api/search?q=marmalade").await;
+    let hits = body["hits"].as_array().unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_ne!(hits[0]["seq"], secret_seq);
+
+    // A rebuild replays the redaction too.
+    let (status, body) = post(&app, "/api/admin/projections/search/rebuild", json!({})).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let (_, body) = get(&app, "/api/search?q=passphrase").await;
+    assert!(body["hits"].as_array().unwrap().is_empty());
+    let (_, body) = get(&app, "/api/search?q=breakfast").await;
+    assert_eq!(body["hits"].as_array().unwrap().len(), 1);
+
+    let (status, _) = post(&app, "/api/admin/events/999999/redact", json!({})).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_search_validates_query_parameters() {
+    let (app, _temp_dir, _app_state, _event_store) = setup_test_app().await;
+
+    let (status, body) = get(&app, "/api/search").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "INVALID_REQUEST");
+    assert!(body["error"]["message"].as_str().unwrap().contains("q"));
+
+    let (status, body) = get(&app, "/api/search?q=x&kinds=chat,email").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "INVALID_REQUEST");
+    assert!(body["error"]["message"].as_str().unwrap().contains("email"));
+
+    // Punctuation-only queries have nothing to match.
+    let (status, body) = get(&app, "/api/search?q=%22%2A%22").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["hits"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_bus_subscriber_indexes_committed_events() {
+    let (_app, _temp_dir, app_state, event_store) = setup_test_app().await;
+    app_state
+        .start_search_indexer()
+        .await
+        .expect("start search indexer");
+
+    append_chat(&event_store, "subscriber should index quokka sightings").await;
+
+    let mut found = false;
+    for _ in 0..50 {
+        let matches = search_index(&event_store, "\"quokka\"", Vec::new(), 10)
+            .await
+            .expect("rpc")
+            .expect("search");
+        if !matches.is_empty() {
+            found = true;
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    assert!(found, "search indexer did not pick up the committed event");
+}
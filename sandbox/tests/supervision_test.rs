@@ -5,8 +5,14 @@
 //! - Failed actors are detected via SupervisionEvent::ActorFailed
 //! - Terminated actors trigger SupervisionEvent::ActorTerminated
 //! - Registry auto-cleanup works (where_is returns None after actor stops)
+//! - ApplicationSupervisor restart strategies restart dependent siblings
 
 use ractor::{Actor, ActorProcessingErr, ActorRef, RpcReplyPort, SupervisionEvent};
+use sandbox::actors::event_relay::EventRelayMsg;
+use sandbox::actors::event_store::{EventStoreActor, EventStoreArguments};
+use sandbox::supervisor::{
+    ApplicationSupervisor, ApplicationSupervisorArgs, ApplicationSupervisorMsg, SupervisionStrategy,
+};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use tokio::time::{timeout, Duration};
@@ -266,12 +272,81 @@ async fn test_actor_registry_auto_cleanup() {
     );
 }
 
+#[tokio::test]
+async fn test_rest_for_one_restarts_event_relay_when_event_bus_fails() {
+    let (event_store, _event_handle) =
+        Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+            .await
+            .expect("Failed to spawn EventStoreActor");
+
+    let (app_supervisor, _app_handle) = Actor::spawn(
+        None,
+        ApplicationSupervisor,
+        ApplicationSupervisorArgs {
+            event_store,
+            strategy: SupervisionStrategy::RestForOne,
+            worker_signal_policy: None,
+        },
+    )
+    .await
+    .expect("Failed to spawn ApplicationSupervisor");
+
+    let event_bus = ractor::call!(app_supervisor, |reply| {
+        ApplicationSupervisorMsg::GetEventBus { reply }
+    })
+    .expect("GetEventBus RPC failed")
+    .expect("EventBus should be running");
+    let event_relay = ractor::call!(app_supervisor, |reply| {
+        ApplicationSupervisorMsg::GetEventRelay { reply }
+    })
+    .expect("GetEventRelay RPC failed")
+    .expect("EventRelay should be running");
+
+    event_bus.kill();
+
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(2);
+    let (new_bus, new_relay) = loop {
+        let bus = ractor::call!(app_supervisor, |reply| {
+            ApplicationSupervisorMsg::GetEventBus { reply }
+        })
+        .expect("GetEventBus RPC failed");
+        let relay = ractor::call!(app_supervisor, |reply| {
+            ApplicationSupervisorMsg::GetEventRelay { reply }
+        })
+        .expect("GetEventRelay RPC failed");
+        if let (Some(bus), Some(relay)) = (bus, relay) {
+            if bus.get_id() != event_bus.get_id() && relay.get_id() != event_relay.get_id() {
+                break (bus, relay);
+            }
+        }
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "EventBus and EventRelay were not both restarted"
+        );
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    };
+
+    // The old relay was stopped rather than left publishing into a dead bus.
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(ractor::call!(event_relay, |reply| EventRelayMsg::GetCursor { reply }).is_err());
+    ractor::call!(new_relay, |reply| EventRelayMsg::GetCursor { reply })
+        .expect("restarted EventRelay should be running");
+    assert_ne!(new_bus.get_status(), ractor::ActorStatus::Stopped);
+
+    let health = ractor::call!(app_supervisor, |reply| {
+        ApplicationSupervisorMsg::GetHealth { reply }
+    })
+    .expect("GetHealth RPC failed");
+    assert!(health.event_bus_healthy && health.event_relay_healthy);
+}
+
 /// Integration test for the real ApplicationSupervisor
 #[cfg(feature = "supervision_refactor")]
 mod integration_tests {
     use super::*;
-    use sandbox::actors::event_store::{EventStoreActor, EventStoreArguments, EventStoreMsg};
+    use sandbox::actors::event_store::{EventStoreActor, EventStoreArguments};
     use sandbox::supervisor::{ApplicationSupervisor, ApplicationSupervisorMsg};
+    use sandbox::testkit::TestSandbox;
     use std::time::Duration;
 
     #[tokio::test]
@@ -288,7 +363,7 @@ mod integration_tests {
         let (_app_supervisor, _app_handle) = Actor::spawn(
             Some("test_app_supervisor".to_string()),
             ApplicationSupervisor,
-            event_store.clone(),
+            event_store.clone().into(),
         )
         .await
         .expect("Failed to spawn ApplicationSupervisor");
@@ -318,17 +393,9 @@ mod integration_tests {
 
     #[tokio::test]
     async fn test_application_supervisor_persists_request_lifecycle_events() {
-        let (event_store, _event_handle) =
-            Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
-                .await
-                .expect("Failed to spawn EventStoreActor");
-
-        let (app_supervisor, _app_handle) =
-            Actor::spawn(None, ApplicationSupervisor, event_store.clone())
-                .await
-                .expect("Failed to spawn ApplicationSupervisor");
+        let sandbox = TestSandbox::start().await;
 
-        let _conductor_ref = ractor::call!(app_supervisor, |reply| {
+        let _conductor_ref = ractor::call!(sandbox.supervisor, |reply| {
             ApplicationSupervisorMsg::GetOrCreateConductor {
                 conductor_id: "conductor-corr-test".to_string(),
                 user_id: "user-corr-test".to_string(),
@@ -341,42 +408,27 @@ mod integration_tests {
         .expect("GetOrCreateConductor RPC failed")
         .expect("GetOrCreateConductor should return actor");
 
-        let deadline = tokio::time::Instant::now() + Duration::from_secs(2);
-        let mut observed = Vec::new();
-
-        while tokio::time::Instant::now() < deadline {
-            observed = ractor::call!(event_store, |reply| EventStoreMsg::GetEventsForActor {
-                actor_id: "application_supervisor".to_string(),
-                since_seq: 0,
-                reply,
-            })
-            .expect("GetEventsForActor RPC failed")
-            .expect("EventStore query failed");
-
-            if observed
-                .iter()
-                .any(|evt| evt.event_type == "supervisor.conductor.get_or_create.started")
-                && observed
-                    .iter()
-                    .any(|evt| evt.event_type == "supervisor.conductor.get_or_create.completed")
-            {
-                break;
-            }
-
-            tokio::time::sleep(Duration::from_millis(20)).await;
+        for topic in [
+            "supervisor.conductor.get_or_create.started",
+            "supervisor.conductor.get_or_create.completed",
+        ] {
+            sandbox
+                .wait_for_event(topic, |_| true, Duration::from_secs(2))
+                .await
+                .unwrap_or_else(|| {
+                    panic!("expected {topic} lifecycle event from ApplicationSupervisor")
+                });
         }
 
+        let recorded: Vec<String> = sandbox
+            .drain_events("application_supervisor")
+            .await
+            .into_iter()
+            .map(|evt| evt.event_type)
+            .collect();
         assert!(
-            observed
-                .iter()
-                .any(|evt| evt.event_type == "supervisor.conductor.get_or_create.started"),
-            "expected started lifecycle event from ApplicationSupervisor"
-        );
-        assert!(
-            observed
-                .iter()
-                .any(|evt| evt.event_type == "supervisor.conductor.get_or_create.completed"),
-            "expected completed lifecycle event from ApplicationSupervisor"
+            recorded.contains(&"supervisor.conductor.get_or_create.completed".to_string()),
+            "lifecycle events should be recorded against application_supervisor, got {recorded:?}"
         );
     }
 }
@@ -56,7 +56,7 @@ async fn test_terminal_supervisor_create_and_reuse_terminal() {
 #[tokio::test]
 async fn test_application_supervisor_routes_terminal() {
     let event_store = create_event_store().await;
-    let (supervisor, _) = Actor::spawn(None, ApplicationSupervisor, event_store)
+    let (supervisor, _) = Actor::spawn(None, ApplicationSupervisor, event_store.into())
         .await
         .expect("failed to spawn application supervisor");
 
@@ -55,10 +55,7 @@ async fn start_test_server() -> TestServer {
     let app_state = Arc::new(AppState::new(event_store));
     let ws_sessions: sandbox::api::websocket::WsSessions =
         Arc::new(tokio::sync::Mutex::new(HashMap::new()));
-    let api_state = api::ApiState {
-        app_state,
-        ws_sessions,
-    };
+    let api_state = api::ApiState::new(app_state, ws_sessions);
 
     let app: Router = api::router().with_state(api_state);
     let listener = TcpListener::bind("127.0.0.1:0")
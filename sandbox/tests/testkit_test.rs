@@ -0,0 +1,91 @@
+//! TestSandbox harness tests, doubling as usage examples.
+
+use std::time::Duration;
+
+use sandbox::actors::event_store::{AppendEvent, EventStoreMsg};
+use sandbox::testkit::TestSandbox;
+
+const WAIT: Duration = Duration::from_secs(2);
+
+#[tokio::test]
+async fn test_served_request_is_observable_as_events() {
+    let sandbox = TestSandbox::start_with_server().await;
+    let desktop_id = format!("testkit-{}", ulid::Ulid::new());
+
+    let client = reqwest::Client::new();
+    let health = client
+        .get(sandbox.url("/health"))
+        .send()
+        .await
+        .expect("health request");
+    assert!(health.status().is_success());
+
+    let state = client
+        .get(sandbox.url(&format!("/desktop/{desktop_id}")))
+        .send()
+        .await
+        .expect("desktop request");
+    assert!(state.status().is_success());
+
+    // Wildcard topics match the committed event types, and events committed
+    // before the wait began are still found.
+    let completed = sandbox
+        .wait_for_event(
+            "supervisor.desktop.*",
+            |event| {
+                event.topic == "supervisor.desktop.get_or_create.completed"
+                    && event.payload["desktop_id"] == desktop_id.as_str()
+            },
+            WAIT,
+        )
+        .await
+        .expect("desktop creation should be recorded");
+    assert_eq!(completed.source, "application_supervisor");
+
+    assert!(sandbox
+        .wait_for_event(
+            "supervisor.desktop.get_or_create.failed",
+            |_| true,
+            Duration::from_millis(200),
+        )
+        .await
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_wait_for_event_sees_events_appended_during_the_wait() {
+    let sandbox = TestSandbox::start().await;
+    let event_store = sandbox.event_store.clone();
+
+    let append = tokio::spawn(async move {
+        tokio::task::yield_now().await;
+        for step in 1..=2 {
+            ractor::call!(event_store, |reply| EventStoreMsg::Append {
+                event: AppendEvent {
+                    event_type: "testkit.step".to_string(),
+                    payload: serde_json::json!({ "step": step }),
+                    actor_id: "testkit-actor".to_string(),
+                    user_id: "test-user".to_string(),
+                    idempotency_key: None,
+                },
+                reply,
+            })
+            .expect("append RPC")
+            .expect("append");
+        }
+    });
+
+    let second = sandbox
+        .wait_for_event("testkit.step", |event| event.payload["step"] == 2, WAIT)
+        .await
+        .expect("second step should be relayed");
+    assert_eq!(
+        second.payload["committed_event"]["actor_id"],
+        "testkit-actor"
+    );
+    append.await.expect("append task");
+
+    // Draining is incremental per actor.
+    assert_eq!(sandbox.drain_events("testkit-actor").await.len(), 2);
+    assert!(sandbox.drain_events("testkit-actor").await.is_empty());
+}
@@ -39,10 +39,7 @@ async fn setup_test_app() -> (
     let app_state = Arc::new(AppState::new(event_store.clone()));
     let ws_sessions: sandbox::api::websocket::WsSessions =
         Arc::new(tokio::sync::Mutex::new(HashMap::new()));
-    let api_state = api::ApiState {
-        app_state: app_state.clone(),
-        ws_sessions,
-    };
+    let api_state = api::ApiState::new(app_state.clone(), ws_sessions);
     let app = api::router().with_state(api_state);
     (app, temp_dir, app_state, event_store)
 }
@@ -59,6 +56,7 @@ async fn append(
             payload,
             actor_id: actor_id.to_string(),
             user_id: "user-1".to_string(),
+            idempotency_key: None,
         },
         reply
     })
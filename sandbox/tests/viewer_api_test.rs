@@ -9,7 +9,9 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tower::ServiceExt;
 
-use sandbox::actors::event_store::{EventStoreActor, EventStoreArguments, EventStoreMsg};
+use sandbox::actors::event_store::{
+    AppendEvent, EventStoreActor, EventStoreArguments, EventStoreMsg,
+};
 use sandbox::api;
 use sandbox::app_state::AppState;
 
@@ -37,10 +39,7 @@ async fn setup_test_app() -> (
     let app_state = Arc::new(AppState::new(event_store.clone()));
     let ws_sessions: sandbox::api::websocket::WsSessions =
         Arc::new(tokio::sync::Mutex::new(HashMap::new()));
-    let api_state = api::ApiState {
-        app_state,
-        ws_sessions,
-    };
+    let api_state = api::ApiState::new(app_state, ws_sessions);
 
     let app = api::router().with_state(api_state);
     (app, event_store, temp_dir)
@@ -162,10 +161,64 @@ async fn test_patch_viewer_content_conflict() {
         .unwrap();
     let (status, body) = json_response(&app, req2).await;
     assert_eq!(status, StatusCode::CONFLICT);
-    assert_eq!(body["success"], false);
-    assert_eq!(body["error"], "revision_conflict");
-    assert_eq!(body["latest"]["content"], "v2");
-    assert_eq!(body["latest"]["revision"]["rev"], 1);
+    assert_eq!(body["error"]["code"], "DOCUMENT_CONFLICT");
+    assert_eq!(body["error"]["details"]["latest"]["content"], "v2");
+    assert_eq!(body["error"]["details"]["latest"]["revision"]["rev"], 1);
+    assert_eq!(body["error"]["details"]["conflict"]["base_rev"], 0);
+    assert_eq!(body["error"]["details"]["conflict"]["current_rev"], 1);
+    assert_eq!(
+        body["error"]["details"]["conflict"]["attempted_content"],
+        "stale save"
+    );
+}
+
+#[tokio::test]
+async fn test_stale_save_emits_typed_conflict_payload() {
+    let (app, event_store, temp_dir) = setup_test_app().await;
+    let file_path = temp_dir.path().join("stale.md");
+    std::fs::write(&file_path, "v1").expect("failed to write file");
+    let uri = file_uri(&file_path);
+
+    for (base_rev, content, window_id) in [
+        (0, "v2", "window-a"),
+        (1, "v3", "window-a"),
+        (1, "stale save", "window-b"),
+    ] {
+        let patch_req = json!({
+            "uri": uri,
+            "base_rev": base_rev,
+            "content": content,
+            "window_id": window_id,
+            "user_id": "user-1"
+        });
+        let req = Request::builder()
+            .method("PATCH")
+            .uri("/viewer/content")
+            .header("content-type", "application/json")
+            .body(Body::from(patch_req.to_string()))
+            .unwrap();
+        let (_status, _body) = json_response(&app, req).await;
+    }
+
+    let events = ractor::call!(event_store, |reply| EventStoreMsg::GetEventsForActor {
+        actor_id: format!("viewer:{uri}"),
+        since_seq: 0,
+        reply,
+    })
+    .expect("rpc failed")
+    .expect("event store failed");
+
+    let conflict = events
+        .into_iter()
+        .find(|evt| evt.event_type == shared_types::EVENT_VIEWER_CONTENT_CONFLICT)
+        .expect("missing viewer.content_conflict event");
+    let payload: shared_types::ViewerConflictPayload =
+        serde_json::from_value(conflict.payload).expect("untyped conflict payload");
+    assert_eq!(payload.path, uri);
+    assert_eq!(payload.base_rev, 1);
+    assert_eq!(payload.current_rev, 2);
+    assert_eq!(payload.attempted_content, "stale save");
+    assert_eq!(payload.window_id, "window-b");
 }
 
 #[tokio::test]
@@ -260,3 +313,165 @@ async fn test_get_viewer_content_directory_listing_is_markdown_and_readonly() {
         "expected markdown directory listing"
     );
 }
+
+async fn append_artifact(
+    event_store: &ractor::ActorRef<EventStoreMsg>,
+    correlation_id: &str,
+    reference: &str,
+) {
+    ractor::call!(event_store, |reply| EventStoreMsg::Append {
+        event: AppendEvent {
+            event_type: shared_types::EVENT_TOPIC_ARTIFACT_CREATED.to_string(),
+            payload: json!({
+                "worker_role": "terminal",
+                "artifact": { "artifact_id": reference, "kind": "file", "reference": reference },
+                "correlation_id": correlation_id,
+            }),
+            actor_id: "supervisor".to_string(),
+            user_id: "system".to_string(),
+            idempotency_key: None,
+        },
+        reply,
+    })
+    .expect("rpc failed")
+    .expect("append failed");
+}
+
+#[tokio::test]
+async fn test_run_image_set_collects_images_and_skips_others() {
+    let (app, event_store, temp_dir) = setup_test_app().await;
+    let first = temp_dir.path().join("first.png");
+    let second = temp_dir.path().join("second.jpg");
+    let notes = temp_dir.path().join("notes.txt");
+    std::fs::write(&first, b"\x89PNG\r\n\x1a\n").unwrap();
+    std::fs::write(&second, [0xFF, 0xD8, 0xFF, 0xD9]).unwrap();
+    std::fs::write(&notes, "text").unwrap();
+    let missing = temp_dir.path().join("missing.png");
+
+    for path in [&first, &notes, &missing, &first, &second] {
+        append_artifact(&event_store, "run-images", path.to_str().unwrap()).await;
+    }
+    append_artifact(&event_store, "run-other", notes.to_str().unwrap()).await;
+
+    let req = Request::builder()
+        .method("GET")
+        .uri("/viewer/image-set?run_id=run-images")
+        .body(Body::empty())
+        .unwrap();
+    let (status, body) = json_response(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["viewer"]["kind"], "image_set");
+    assert_eq!(body["viewer"]["resource"]["uri"], file_uri(&first));
+    assert_eq!(body["viewer"]["capabilities"]["readonly"], true);
+
+    let resources = body["viewer"]["resources"].as_array().unwrap();
+    let uris: Vec<&str> = resources
+        .iter()
+        .map(|r| r["uri"].as_str().unwrap())
+        .collect();
+    assert_eq!(uris, vec![file_uri(&first), file_uri(&second)]);
+    assert_eq!(resources[1]["mime"], "image/jpeg");
+
+    let skipped = body["skipped"].as_array().unwrap();
+    assert_eq!(skipped.len(), 2);
+    assert_eq!(skipped[0]["uri"], file_uri(&notes));
+    assert_eq!(skipped[0]["reason"], "not_an_image");
+    assert_eq!(skipped[1]["uri"], file_uri(&missing));
+    assert_eq!(skipped[1]["reason"], "not_found");
+}
+
+#[tokio::test]
+async fn test_run_image_set_without_images_has_no_viewer() {
+    let (app, _event_store, _temp_dir) = setup_test_app().await;
+
+    let req = Request::builder()
+        .method("GET")
+        .uri("/viewer/image-set?run_id=run-empty")
+        .body(Body::empty())
+        .unwrap();
+    let (status, body) = json_response(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["viewer"].is_null());
+    assert_eq!(body["skipped"], json!([]));
+}
+
+#[tokio::test]
+async fn test_run_image_set_requires_run_id() {
+    let (app, _event_store, _temp_dir) = setup_test_app().await;
+
+    let req = Request::builder()
+        .method("GET")
+        .uri("/viewer/image-set?run_id=%20")
+        .body(Body::empty())
+        .unwrap();
+    let (status, body) = json_response(&app, req).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "INVALID_REQUEST");
+    assert_eq!(body["error"]["message"], "run_id is required");
+}
+
+#[tokio::test]
+async fn test_viewer_thumbnail_downscales_images_and_rejects_text() {
+    let (app, _event_store, temp_dir) = setup_test_app().await;
+    let plot = temp_dir.path().join("plot.png");
+    image::RgbImage::new(1200, 600).save(&plot).unwrap();
+    let notes = temp_dir.path().join("notes.txt");
+    std::fs::write(&notes, "text").unwrap();
+
+    let req = Request::builder()
+        .method("GET")
+        .uri(format!("/viewer/thumbnail?uri={}", file_uri(&plot)))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(req).await.expect("request failed");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "image/png");
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let thumbnail = image::load_from_memory(&bytes).unwrap();
+    assert_eq!((thumbnail.width(), thumbnail.height()), (256, 128));
+
+    let req = Request::builder()
+        .method("GET")
+        .uri(format!("/viewer/thumbnail?uri={}", file_uri(&notes)))
+        .body(Body::empty())
+        .unwrap();
+    let (status, body) = json_response(&app, req).await;
+    assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    assert_eq!(body["error"]["code"], "UNSUPPORTED_MEDIA_TYPE");
+
+    let req = Request::builder()
+        .method("GET")
+        .uri(format!(
+            "/viewer/thumbnail?uri={}",
+            file_uri(&temp_dir.path().join("gone.png"))
+        ))
+        .body(Body::empty())
+        .unwrap();
+    let (status, body) = json_response(&app, req).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"]["code"], "NOT_FOUND");
+}
+
+#[tokio::test]
+async fn test_get_viewer_content_reports_jpeg_orientation() {
+    let (app, _event_store, temp_dir) = setup_test_app().await;
+    let photo = temp_dir.path().join("rotated.jpg");
+    // Big-endian EXIF block whose IFD0 holds only Orientation = 6.
+    let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1, 0x00, 0x22];
+    jpeg.extend(b"Exif\0\0MM\x00\x2a\x00\x00\x00\x08");
+    jpeg.extend([0x00, 0x01, 0x01, 0x12, 0x00, 0x03, 0x00, 0x00, 0x00, 0x01]);
+    jpeg.extend([0x00, 0x06, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    jpeg.extend([0xFF, 0xD9]);
+    std::fs::write(&photo, jpeg).unwrap();
+
+    let req = Request::builder()
+        .method("GET")
+        .uri(format!("/viewer/content?uri={}", file_uri(&photo)))
+        .body(Body::empty())
+        .unwrap();
+    let (status, body) = json_response(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["mime"], "image/jpeg");
+    assert_eq!(body["readonly"], true);
+    assert_eq!(body["orientation"], 6);
+}
@@ -1,6 +1,13 @@
 use ractor::Actor;
-use sandbox::actors::event_store::{get_recent_events, EventStoreActor, EventStoreArguments};
-use sandbox::supervisor::{ApplicationSupervisor, ApplicationSupervisorMsg};
+use sandbox::actors::agent_harness::EventStoreEmitter;
+use sandbox::actors::event_store::{
+    append_event, get_recent_events, write_stats, AppendEvent, EventStoreActor, EventStoreArguments,
+};
+use sandbox::actors::telemetry_gate::TelemetryPolicy;
+use sandbox::supervisor::{
+    ApplicationSupervisor, ApplicationSupervisorArgs, ApplicationSupervisorMsg, EvidenceResolution,
+    SupervisionStrategy, WorkerSignalPolicy,
+};
 
 fn sample_report() -> shared_types::WorkerTurnReport {
     shared_types::WorkerTurnReport {
@@ -74,7 +81,7 @@ async fn test_ingest_worker_turn_report_emits_canonical_events_and_rejections()
             .await
             .expect("spawn event store");
     let (app_supervisor, _app_handle) =
-        Actor::spawn(None, ApplicationSupervisor, event_store.clone())
+        Actor::spawn(None, ApplicationSupervisor, event_store.clone().into())
             .await
             .expect("spawn app supervisor");
 
@@ -145,7 +152,7 @@ async fn test_ingest_worker_turn_report_applies_escalation_cooldown() {
             .await
             .expect("spawn event store");
     let (app_supervisor, _app_handle) =
-        Actor::spawn(None, ApplicationSupervisor, event_store.clone())
+        Actor::spawn(None, ApplicationSupervisor, event_store.clone().into())
             .await
             .expect("spawn app supervisor");
 
@@ -194,3 +201,437 @@ async fn test_ingest_worker_turn_report_applies_escalation_cooldown() {
     .expect("query escalation");
     assert_eq!(escalation_events.len(), 1);
 }
+
+#[tokio::test]
+async fn test_progress_flood_under_one_call_is_throttled() {
+    let (event_store, _event_handle) = Actor::spawn(
+        None,
+        EventStoreActor,
+        EventStoreArguments::InMemory.with_telemetry(TelemetryPolicy {
+            max_per_second: 20,
+            ..TelemetryPolicy::default()
+        }),
+    )
+    .await
+    .expect("spawn event store");
+    let emitter = EventStoreEmitter::new(
+        event_store.clone(),
+        "researcher-flood".to_string(),
+        "user-1".to_string(),
+    );
+
+    let flood = 200;
+    for i in 0..flood {
+        emitter.emit_worker_progress(
+            "task-flood",
+            Some("run-flood"),
+            Some("call-flood"),
+            "searching",
+            &format!("step {i}"),
+            None,
+        );
+    }
+    // Another call's progress has its own window.
+    emitter.emit_worker_progress(
+        "task-other",
+        Some("run-flood"),
+        Some("call-other"),
+        "searching",
+        "step 0",
+        None,
+    );
+    // Control-lane events are never throttled, even mid-flood.
+    emitter.emit_worker_completed("task-flood", Some("run-flood"), Some("call-flood"), "done");
+
+    let mut summaries = Vec::new();
+    for _ in 0..40 {
+        summaries = get_recent_events(
+            &event_store,
+            0,
+            100,
+            Some(shared_types::EVENT_TOPIC_TELEMETRY_THROTTLED.to_string()),
+            Some("researcher-flood".to_string()),
+            None,
+        )
+        .await
+        .expect("query throttled rpc")
+        .expect("query throttled");
+        if !summaries.is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    assert!(
+        !summaries.is_empty(),
+        "expected a telemetry.throttled summary"
+    );
+    assert!(summaries
+        .iter()
+        .all(|summary| summary.payload["correlation_id"] == "call-flood"));
+
+    let progress_events = get_recent_events(
+        &event_store,
+        0,
+        1000,
+        Some(shared_types::EVENT_TOPIC_WORKER_TASK_PROGRESS.to_string()),
+        Some("researcher-flood".to_string()),
+        None,
+    )
+    .await
+    .expect("query progress rpc")
+    .expect("query progress");
+    let flood_progress = progress_events
+        .iter()
+        .filter(|event| event.payload["call_id"] == "call-flood")
+        .count();
+    assert!(flood_progress < flood);
+    assert!(progress_events
+        .iter()
+        .any(|event| event.payload["call_id"] == "call-other"));
+
+    let dropped: u64 = summaries
+        .iter()
+        .map(|event| event.payload["dropped"].as_u64().expect("dropped count"))
+        .sum();
+    assert_eq!(flood_progress as u64 + dropped, flood as u64);
+    assert_eq!(
+        summaries[0].payload["dropped_by_topic"][shared_types::EVENT_TOPIC_WORKER_TASK_PROGRESS],
+        summaries[0].payload["dropped"]
+    );
+
+    let completed_events = get_recent_events(
+        &event_store,
+        0,
+        100,
+        Some(shared_types::EVENT_TOPIC_WORKER_TASK_COMPLETED.to_string()),
+        Some("researcher-flood".to_string()),
+        None,
+    )
+    .await
+    .expect("query completed rpc")
+    .expect("query completed");
+    assert_eq!(completed_events.len(), 1);
+}
+
+#[tokio::test]
+async fn test_sampled_progress_keeps_the_last_update_before_completion() {
+    let (event_store, _event_handle) = Actor::spawn(
+        None,
+        EventStoreActor,
+        EventStoreArguments::InMemory.with_telemetry(TelemetryPolicy {
+            sample_every: 3,
+            ..TelemetryPolicy::default()
+        }),
+    )
+    .await
+    .expect("spawn event store");
+    let emitter = EventStoreEmitter::new(
+        event_store.clone(),
+        "researcher-sampled".to_string(),
+        "user-1".to_string(),
+    );
+
+    for step in 1..=8 {
+        emitter.emit_worker_progress(
+            "task-sampled",
+            Some("run-sampled"),
+            Some("call-sampled"),
+            "searching",
+            &format!("step {step}"),
+            None,
+        );
+    }
+    emitter.emit_worker_completed(
+        "task-sampled",
+        Some("run-sampled"),
+        Some("call-sampled"),
+        "done",
+    );
+
+    // AppendAsync is handled in order, so a reply to a later query sees
+    // every event written before it.
+    let events = get_recent_events(
+        &event_store,
+        0,
+        100,
+        Some("worker.task.".to_string()),
+        Some("researcher-sampled".to_string()),
+        None,
+    )
+    .await
+    .expect("query rpc")
+    .expect("query");
+    let written: Vec<String> = events
+        .iter()
+        .map(|event| match event.payload["message"].as_str() {
+            Some(message) => message.to_string(),
+            None => event.event_type.clone(),
+        })
+        .collect();
+    assert_eq!(
+        written,
+        vec![
+            "step 1".to_string(),
+            "step 4".to_string(),
+            "step 7".to_string(),
+            "step 8".to_string(),
+            shared_types::EVENT_TOPIC_WORKER_TASK_COMPLETED.to_string(),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_progress_events_are_written_in_batches() {
+    let (event_store, _event_handle) =
+        Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+            .await
+            .expect("spawn event store");
+    let emitter = EventStoreEmitter::new(
+        event_store.clone(),
+        "researcher-batch".to_string(),
+        "user-1".to_string(),
+    );
+
+    let total = 1000;
+    for i in 0..total {
+        emitter.emit_worker_progress(
+            "task-batch",
+            Some("run-batch"),
+            Some("call-batch"),
+            "searching",
+            &format!("step {i}"),
+            None,
+        );
+    }
+
+    let mut stats = write_stats(&event_store).await.expect("write stats");
+    for _ in 0..50 {
+        if stats.events >= total {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        stats = write_stats(&event_store).await.expect("write stats");
+    }
+    assert_eq!(stats.events, total);
+    assert!(
+        stats.transactions * 10 <= total,
+        "expected far fewer transactions than events: {stats:?}"
+    );
+
+    let progress = get_recent_events(
+        &event_store,
+        0,
+        2000,
+        Some(shared_types::EVENT_TOPIC_WORKER_TASK_PROGRESS.to_string()),
+        Some("researcher-batch".to_string()),
+        None,
+    )
+    .await
+    .expect("query progress rpc")
+    .expect("query progress");
+    let messages: Vec<String> = progress
+        .iter()
+        .map(|event| {
+            event.payload["message"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string()
+        })
+        .collect();
+    let expected: Vec<String> = (0..total).map(|i| format!("step {i}")).collect();
+    assert_eq!(messages, expected);
+}
+
+fn evidence_report(
+    turn_id: &str,
+    findings: Vec<(&str, &str, Vec<String>)>,
+) -> shared_types::WorkerTurnReport {
+    shared_types::WorkerTurnReport {
+        turn_id: turn_id.to_string(),
+        findings: findings
+            .into_iter()
+            .map(
+                |(finding_id, claim, evidence_refs)| shared_types::WorkerFinding {
+                    finding_id: finding_id.to_string(),
+                    claim: claim.to_string(),
+                    confidence: 0.9,
+                    evidence_refs,
+                    novel: None,
+                },
+            )
+            .collect(),
+        learnings: Vec::new(),
+        escalations: Vec::new(),
+        ..sample_report()
+    }
+}
+
+async fn spawn_with_evidence_mode(
+    mode: EvidenceResolution,
+) -> (
+    ractor::ActorRef<sandbox::actors::event_store::EventStoreMsg>,
+    ractor::ActorRef<ApplicationSupervisorMsg>,
+) {
+    let (event_store, _event_handle) =
+        Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+            .await
+            .expect("spawn event store");
+    let (app_supervisor, _app_handle) = Actor::spawn(
+        None,
+        ApplicationSupervisor,
+        ApplicationSupervisorArgs {
+            event_store: event_store.clone(),
+            strategy: SupervisionStrategy::default(),
+            worker_signal_policy: Some(WorkerSignalPolicy {
+                evidence_resolution: mode,
+                ..WorkerSignalPolicy::default()
+            }),
+        },
+    )
+    .await
+    .expect("spawn app supervisor");
+    (event_store, app_supervisor)
+}
+
+async fn ingest(
+    app_supervisor: &ractor::ActorRef<ApplicationSupervisorMsg>,
+    report: shared_types::WorkerTurnReport,
+) -> shared_types::WorkerTurnReportIngestResult {
+    ractor::call!(app_supervisor, |reply| {
+        ApplicationSupervisorMsg::IngestWorkerTurnReport {
+            actor_id: "researcher-1".to_string(),
+            user_id: "test-user".to_string(),
+            session_id: Some("session-d".to_string()),
+            thread_id: Some("thread-d".to_string()),
+            report,
+            reply,
+        }
+    })
+    .expect("rpc ok")
+    .expect("ingest ok")
+}
+
+#[tokio::test]
+async fn test_strict_evidence_accepts_refs_to_artifacts_and_events() {
+    let (event_store, app_supervisor) = spawn_with_evidence_mode(EvidenceResolution::Strict).await;
+    let source = append_event(
+        &event_store,
+        AppendEvent {
+            event_type: "research.source.fetched".to_string(),
+            payload: serde_json::json!({ "url": "https://example.com/policy" }),
+            actor_id: "researcher-1".to_string(),
+            user_id: "test-user".to_string(),
+            idempotency_key: None,
+        },
+    )
+    .await
+    .expect("append rpc")
+    .expect("append");
+
+    // "a-1" is an artifact of this report; the event id is a stored event.
+    let first = ingest(
+        &app_supervisor,
+        evidence_report(
+            "turn-1",
+            vec![
+                ("f-1", "Policy is documented", vec!["a-1".to_string()]),
+                ("f-2", "Policy source was fetched", vec![source.event_id]),
+            ],
+        ),
+    )
+    .await;
+    assert_eq!(first.accepted_findings, 2);
+    assert!(first.rejections.is_empty(), "{:?}", first.rejections);
+
+    // Artifacts accepted in an earlier turn stay citable.
+    let mut later = evidence_report(
+        "turn-2",
+        vec![(
+            "f-3",
+            "Earlier artifact still applies",
+            vec!["a-1".to_string()],
+        )],
+    );
+    later.artifacts.clear();
+    let second = ingest(&app_supervisor, later).await;
+    assert_eq!(second.accepted_findings, 1);
+}
+
+#[tokio::test]
+async fn test_strict_evidence_rejects_dangling_refs() {
+    let (event_store, app_supervisor) = spawn_with_evidence_mode(EvidenceResolution::Strict).await;
+
+    let result = ingest(
+        &app_supervisor,
+        evidence_report(
+            "turn-1",
+            vec![(
+                "f-1",
+                "Claim backed by nothing",
+                vec!["a-1".to_string(), "evt-missing".to_string()],
+            )],
+        ),
+    )
+    .await;
+    assert_eq!(result.accepted_findings, 0);
+    let rejection = result
+        .rejections
+        .iter()
+        .find(|r| r.signal_id == "f-1")
+        .expect("finding rejected");
+    assert_eq!(
+        rejection.reason,
+        shared_types::WorkerSignalRejectReason::MissingEvidence
+    );
+    assert!(rejection.detail.contains("evt-missing"));
+    assert!(!rejection.detail.contains("a-1"));
+
+    let findings = get_recent_events(
+        &event_store,
+        0,
+        100,
+        Some(shared_types::EVENT_TOPIC_RESEARCH_FINDING_CREATED.to_string()),
+        Some("researcher-1".to_string()),
+        None,
+    )
+    .await
+    .expect("query finding rpc")
+    .expect("query finding");
+    assert!(findings.is_empty());
+}
+
+#[tokio::test]
+async fn test_lenient_evidence_accepts_and_lists_dangling_refs() {
+    let (event_store, app_supervisor) = spawn_with_evidence_mode(EvidenceResolution::Lenient).await;
+
+    let result = ingest(
+        &app_supervisor,
+        evidence_report(
+            "turn-1",
+            vec![(
+                "f-1",
+                "Claim with a stale ref",
+                vec!["evt-missing".to_string()],
+            )],
+        ),
+    )
+    .await;
+    assert_eq!(result.accepted_findings, 1);
+
+    let findings = get_recent_events(
+        &event_store,
+        0,
+        100,
+        Some(shared_types::EVENT_TOPIC_RESEARCH_FINDING_CREATED.to_string()),
+        Some("researcher-1".to_string()),
+        None,
+    )
+    .await
+    .expect("query finding rpc")
+    .expect("query finding");
+    assert_eq!(findings.len(), 1);
+    assert_eq!(
+        findings[0].payload["unresolved_evidence_refs"],
+        serde_json::json!(["evt-missing"])
+    );
+}
@@ -36,10 +36,7 @@ async fn setup_test_app() -> (axum::Router, tempfile::TempDir) {
     let app_state = Arc::new(AppState::new(event_store));
     let ws_sessions: sandbox::api::websocket::WsSessions =
         Arc::new(tokio::sync::Mutex::new(HashMap::new()));
-    let api_state = api::ApiState {
-        app_state,
-        ws_sessions,
-    };
+    let api_state = api::ApiState::new(app_state, ws_sessions);
 
     let app = api::router().with_state(api_state);
     (app, temp_dir)
@@ -358,7 +355,7 @@ async fn test_save_conflict() {
 
     let (status, body) = json_response(&app, req).await;
     assert_eq!(status, StatusCode::CONFLICT);
-    assert_eq!(body["error"]["code"], "CONFLICT");
+    assert_eq!(body["error"]["code"], "DOCUMENT_CONFLICT");
     assert_eq!(body["current_revision"], 2);
     assert_eq!(body["current_content"], "Client A changes");
 
@@ -848,3 +845,213 @@ async fn test_overlay_dismiss_marks_overlay_rejected() {
         "dismissed overlay still present in pending overlays"
     );
 }
+
+async fn post_json(app: &axum::Router, uri: &str, body: Value) -> (StatusCode, Value) {
+    let req = Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    json_response(app, req).await
+}
+
+#[tokio::test]
+async fn test_undo_redo_reverts_user_edits_from_patch_history() {
+    let (app, _temp_dir) = setup_test_app().await;
+    let run_id = format!("test-undo-redo-{}", ulid::Ulid::new());
+    let doc_path = format!("conductor/runs/{run_id}/draft.md");
+    let undo_uri = format!(
+        "/api/writer/documents/{}/undo",
+        doc_path.replace('/', "%2F")
+    );
+    let redo_uri = undo_uri.replace("/undo", "/redo");
+
+    let (status, _) = post_json(
+        &app,
+        "/writer/ensure",
+        json!({ "path": &doc_path, "objective": "Undo test", "desktop_id": "default-desktop" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    for content in ["First draft", "First draft, edited"] {
+        let (status, body) = post_json(
+            &app,
+            "/writer/save-version",
+            json!({ "path": &doc_path, "content": content }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+    }
+
+    // Agent revisions are not part of the user's undo stack.
+    let writer_actor =
+        lookup_writer_actor_for_run(&run_id).expect("writer actor should exist for ensured run");
+    let revision = ractor::call!(writer_actor, |reply| WriterMsg::ApplyText {
+        run_id: run_id.clone(),
+        section_id: "conductor".to_string(),
+        source: WriterSource::Researcher,
+        content: "Agent note.".to_string(),
+        proposal: true,
+        reply,
+    })
+    .expect("writer apply-text rpc should succeed")
+    .expect("writer should create proposal overlay");
+    assert!(revision > 0);
+
+    let (status, undone) = post_json(&app, &undo_uri, json!({})).await;
+    assert_eq!(status, StatusCode::OK, "{undone}");
+    assert_eq!(undone["version"]["content"], "First draft");
+    let undo_of = undone["undo_of"].as_str().unwrap().to_string();
+
+    let (status, redone) = post_json(&app, &redo_uri, json!({})).await;
+    assert_eq!(status, StatusCode::OK, "{redone}");
+    assert_eq!(redone["version"]["content"], "First draft, edited");
+    assert_ne!(redone["undo_of"].as_str().unwrap(), undo_of);
+
+    let (status, body) = post_json(&app, &redo_uri, json!({})).await;
+    assert_eq!(status, StatusCode::CONFLICT, "{body}");
+
+    // The redo is itself undoable, then the first save.
+    for expected in ["First draft", ""] {
+        let (status, body) = post_json(&app, &undo_uri, json!({})).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        assert_eq!(
+            body["version"]["content"].as_str().unwrap().trim(),
+            expected
+        );
+    }
+
+    // History only grows: every undo/redo is a new version.
+    let (status, body) = json_response(
+        &app,
+        Request::builder()
+            .uri(format!(
+                "/writer/versions?path={}",
+                doc_path.replace('/', "%2F")
+            ))
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["versions"].as_array().unwrap().len(), 7);
+
+    cleanup_writer_artifacts(&app, &doc_path).await;
+    let _ = tokio::fs::remove_dir_all(
+        sandbox::paths::writer_root()
+            .join("conductor/runs")
+            .join(&run_id),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_review_mode_proposals_are_listed_accepted_and_rejected() {
+    let (app, _temp_dir) = setup_test_app().await;
+    let run_id = format!("test-proposals-{}", ulid::Ulid::new());
+    let doc_path = format!("conductor/runs/{run_id}/draft.md");
+    let documents_uri = format!("/api/writer/documents/{}", doc_path.replace('/', "%2F"));
+    let list_proposals = || async {
+        json_response(
+            &app,
+            Request::builder()
+                .uri(format!("{documents_uri}/proposals"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+    };
+
+    let (status, _) = post_json(
+        &app,
+        "/writer/ensure",
+        json!({ "path": &doc_path, "objective": "Proposal test", "desktop_id": "default-desktop" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, body) = post_json(
+        &app,
+        "/writer/save-version",
+        json!({ "path": &doc_path, "content": "Base draft.\n" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+
+    let req = Request::builder()
+        .method("PATCH")
+        .uri(format!("{documents_uri}/review-mode"))
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "enabled": true }).to_string()))
+        .unwrap();
+    let (status, body) = json_response(&app, req).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["review_mode"], true);
+
+    let writer_actor =
+        lookup_writer_actor_for_run(&run_id).expect("writer actor should exist for ensured run");
+    for note in ["Agent note.", "Second agent note."] {
+        ractor::call!(writer_actor, |reply| WriterMsg::ApplyText {
+            run_id: run_id.clone(),
+            section_id: "researcher".to_string(),
+            source: WriterSource::Researcher,
+            content: note.to_string(),
+            proposal: false,
+            reply,
+        })
+        .expect("writer apply-text rpc should succeed")
+        .expect("review mode should park the patch as a proposal");
+    }
+
+    let (status, body) = list_proposals().await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["run_id"], run_id.as_str());
+    assert_eq!(body["review_mode"], true);
+    let proposals = body["proposals"].as_array().unwrap();
+    assert_eq!(proposals.len(), 2);
+    assert_eq!(proposals[0]["stale"], false);
+    assert_eq!(proposals[0]["proposed_content"], "Base draft.\nAgent note.");
+    let accept_id = proposals[0]["overlay_id"].as_str().unwrap().to_string();
+    let reject_id = proposals[1]["overlay_id"].as_str().unwrap().to_string();
+
+    let (status, body) = post_json(
+        &app,
+        &format!("{documents_uri}/proposals/{reject_id}/reject"),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["rejected"], true);
+
+    let (status, body) = post_json(
+        &app,
+        &format!("{documents_uri}/proposals/{accept_id}/accept"),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["overlay_id"], accept_id.as_str());
+    assert_eq!(body["rebased"], false);
+    assert_eq!(body["version"]["content"], "Base draft.\nAgent note.");
+
+    let (status, body) = list_proposals().await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert!(body["proposals"].as_array().unwrap().is_empty());
+
+    let (status, body) = post_json(
+        &app,
+        &format!("{documents_uri}/proposals/{accept_id}/accept"),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT, "{body}");
+
+    cleanup_writer_artifacts(&app, &doc_path).await;
+    let _ = tokio::fs::remove_dir_all(
+        sandbox::paths::writer_root()
+            .join("conductor/runs")
+            .join(&run_id),
+    )
+    .await;
+}
@@ -0,0 +1,147 @@
+//! WriterSupervisor per-document registry tests.
+
+use ractor::Actor;
+use sandbox::actors::conductor::registry::run_writer_id;
+use sandbox::actors::event_store::{EventStoreActor, EventStoreArguments};
+use sandbox::actors::writer::WriterMsg;
+use sandbox::supervisor::writer::{
+    WriterDocumentHolder, WriterSupervisor, WriterSupervisorArgs, WriterSupervisorMsg,
+};
+
+async fn spawn_writer_supervisor() -> ractor::ActorRef<WriterSupervisorMsg> {
+    let (event_store, _) = Actor::spawn(None, EventStoreActor, EventStoreArguments::InMemory)
+        .await
+        .expect("spawn event store");
+    Actor::spawn(
+        None,
+        WriterSupervisor,
+        WriterSupervisorArgs {
+            event_store,
+            researcher_supervisor: None,
+            terminal_supervisor: None,
+        },
+    )
+    .await
+    .expect("spawn writer supervisor")
+    .0
+}
+
+async fn document_writer(
+    supervisor: &ractor::ActorRef<WriterSupervisorMsg>,
+    document_path: &str,
+    holder: Option<WriterDocumentHolder>,
+) -> Result<ractor::ActorRef<WriterMsg>, String> {
+    ractor::call!(supervisor, |reply| {
+        WriterSupervisorMsg::GetOrCreateDocumentWriter {
+            document_path: document_path.to_string(),
+            user_id: "test-user".to_string(),
+            holder,
+            reply,
+        }
+    })
+    .expect("rpc ok")
+}
+
+async fn current_writer(
+    supervisor: &ractor::ActorRef<WriterSupervisorMsg>,
+    writer_id: &str,
+) -> Option<ractor::ActorRef<WriterMsg>> {
+    ractor::call!(supervisor, |reply| WriterSupervisorMsg::GetWriter {
+        writer_id: writer_id.to_string(),
+        reply,
+    })
+    .expect("rpc ok")
+}
+
+#[tokio::test]
+async fn test_concurrent_run_starts_share_one_writer_until_released() {
+    let supervisor = spawn_writer_supervisor().await;
+    let run_id = format!("shared-doc-{}", ulid::Ulid::new());
+    let path = format!("conductor/runs/{run_id}/draft.md");
+
+    let (first, second) = tokio::join!(
+        document_writer(
+            &supervisor,
+            &path,
+            Some(WriterDocumentHolder::Run("run-a".to_string()))
+        ),
+        document_writer(
+            &supervisor,
+            &path,
+            Some(WriterDocumentHolder::Run("run-b".to_string()))
+        ),
+    );
+    let first = first.expect("first run start");
+    let second = second.expect("second run start");
+    assert_eq!(first.get_id(), second.get_id());
+
+    let writer_id = run_writer_id(&run_id);
+    let registered = current_writer(&supervisor, &writer_id)
+        .await
+        .expect("writer registered");
+    assert_eq!(registered.get_id(), first.get_id());
+
+    // One run finishing leaves the writer to the other.
+    supervisor
+        .cast(WriterSupervisorMsg::ReleaseHolder {
+            holder: WriterDocumentHolder::Run("run-a".to_string()),
+        })
+        .unwrap();
+    assert!(current_writer(&supervisor, &writer_id).await.is_some());
+
+    // The last holder going away stops it.
+    supervisor
+        .cast(WriterSupervisorMsg::ReleaseHolder {
+            holder: WriterDocumentHolder::Run("run-b".to_string()),
+        })
+        .unwrap();
+    assert!(current_writer(&supervisor, &writer_id).await.is_none());
+    let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(5);
+    while first.get_status() != ractor::ActorStatus::Stopped {
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "released writer did not stop"
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+}
+
+#[tokio::test]
+async fn test_document_writer_requires_run_document_path() {
+    let supervisor = spawn_writer_supervisor().await;
+    let error = document_writer(&supervisor, "notes/plan.md", None)
+        .await
+        .expect_err("not a run document");
+    assert!(error.contains("notes/plan.md"), "{error}");
+}
+
+#[tokio::test]
+async fn test_equivalent_document_paths_share_one_writer() {
+    let supervisor = spawn_writer_supervisor().await;
+    let run_id = format!("normalized-doc-{}", ulid::Ulid::new());
+
+    let first = document_writer(
+        &supervisor,
+        &format!("./conductor/runs/{run_id}/draft.md"),
+        Some(WriterDocumentHolder::Run("run-a".to_string())),
+    )
+    .await
+    .expect("dot-prefixed path");
+    let second = document_writer(
+        &supervisor,
+        &format!("conductor\\runs\\{run_id}\\draft.md"),
+        Some(WriterDocumentHolder::Run("run-b".to_string())),
+    )
+    .await
+    .expect("backslash path");
+    assert_eq!(first.get_id(), second.get_id());
+
+    let error = document_writer(
+        &supervisor,
+        &format!("conductor/runs/{run_id}/../other/draft.md"),
+        None,
+    )
+    .await
+    .expect_err("traversal path");
+    assert!(error.contains("not a run document path"), "{error}");
+}
@@ -0,0 +1,33 @@
+//! Stamps the git revision into `BuildVersion::current`.
+//!
+//! `CHOIR_GIT_HASH` in the build environment wins, for builds without a
+//! checkout (Nix, Docker); otherwise the short hash of `HEAD`, or "unknown".
+
+use std::process::Command;
+
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let out = String::from_utf8(output.stdout).ok()?;
+    let out = out.trim();
+    (!out.is_empty()).then(|| out.to_string())
+}
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=CHOIR_GIT_HASH");
+    let hash = std::env::var("CHOIR_GIT_HASH")
+        .ok()
+        .map(|hash| hash.trim().to_string())
+        .filter(|hash| !hash.is_empty())
+        .or_else(|| git(&["rev-parse", "--short=12", "HEAD"]))
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=CHOIR_GIT_HASH={hash}");
+
+    // The reflog of HEAD changes on every commit and checkout.
+    if let Some(git_dir) = git(&["rev-parse", "--absolute-git-dir"]) {
+        println!("cargo:rerun-if-changed={git_dir}/HEAD");
+        println!("cargo:rerun-if-changed={git_dir}/logs/HEAD");
+    }
+}
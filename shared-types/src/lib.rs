@@ -50,12 +50,30 @@ pub struct Event {
     /// Unique event ID (ULID)
     pub event_id: String,
 
-    /// When the event occurred
+    /// When the event store recorded the event. Same value as `stored_at`,
+    /// kept for existing consumers.
     pub timestamp: DateTime<Utc>,
 
+    /// Authoritative append time, stamped by the event store and never
+    /// decreasing along `seq`. Events serialized before this field existed
+    /// read back with the Unix epoch.
+    #[serde(default)]
+    pub stored_at: DateTime<Utc>,
+
+    /// The producer's own clock reading (the payload's `timestamp`), if any.
+    /// Producer clocks can skew, so this is informational and never used for
+    /// ordering.
+    #[serde(default)]
+    pub produced_at: Option<DateTime<Utc>>,
+
     /// Which actor produced this event
     pub actor_id: ActorId,
 
+    /// Position among `actor_id`'s events, starting at 1. A gap between
+    /// consecutive events of one actor means events in between were missed.
+    #[serde(default)]
+    pub actor_seq: i64,
+
     /// Event type (e.g., "conductor.run_started", "file.write")
     pub event_type: String,
 
@@ -67,7 +85,21 @@ pub struct Event {
     pub user_id: String,
 }
 
-/// Request to append an event
+impl Event {
+    /// Total order over events: timestamp, then seq, then event_id. Query,
+    /// export and replay all use it so reconstructions are reproducible even
+    /// when many events share a (second-precision) timestamp.
+    pub fn canonical_cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.timestamp, self.seq, &self.event_id).cmp(&(
+            other.timestamp,
+            other.seq,
+            &other.event_id,
+        ))
+    }
+}
+
+/// Request to append an event. The event is recorded for the caller's
+/// session user.
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
 pub struct AppendEvent {
@@ -75,7 +107,10 @@ pub struct AppendEvent {
     #[ts(type = "unknown")]
     pub payload: serde_json::Value,
     pub actor_id: ActorId,
-    pub user_id: String,
+    /// Retrying an append with the same key returns the event stored by the
+    /// first attempt instead of appending a duplicate.
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
 }
 
 /// Query events for an actor
@@ -84,6 +119,10 @@ pub struct AppendEvent {
 pub struct QueryEvents {
     pub actor_id: ActorId,
     pub since_seq: i64,
+    /// Only events in this lane. Events without lane metadata count as
+    /// telemetry.
+    #[serde(default)]
+    pub lane: Option<EventLane>,
 }
 
 // ============================================================================
@@ -141,6 +180,41 @@ pub struct AppDefinition {
     pub default_height: i32,
 }
 
+/// Starting layout for a new desktop: windows to open and files to seed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct DesktopTemplate {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub windows: Vec<DesktopTemplateWindow>,
+    /// Written into the workspace only where no file exists yet.
+    #[serde(default)]
+    pub seed_files: Vec<DesktopTemplateFile>,
+}
+
+/// A window a desktop template opens.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct DesktopTemplateWindow {
+    pub app_id: String,
+    pub title: String,
+    #[serde(default)]
+    #[ts(type = "unknown")]
+    pub props: serde_json::Value,
+}
+
+/// A file a desktop template seeds, relative to the workspace root.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct DesktopTemplateFile {
+    pub path: String,
+    #[serde(default)]
+    pub content: String,
+}
+
 // ============================================================================
 // Viewer Types
 // ============================================================================
@@ -151,6 +225,9 @@ pub struct AppDefinition {
 pub enum ViewerKind {
     Text,
     Image,
+    /// Several images browsed in one window; see `ViewerDescriptor::resources`.
+    #[serde(rename = "image_set")]
+    ImageSet,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
@@ -170,8 +247,12 @@ pub struct ViewerCapabilities {
 #[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
 pub struct ViewerDescriptor {
     pub kind: ViewerKind,
+    /// The resource shown first; for an image set, its first entry.
     pub resource: ViewerResource,
     pub capabilities: ViewerCapabilities,
+    /// Every resource of an image set, in display order. Empty otherwise.
+    #[serde(default)]
+    pub resources: Vec<ViewerResource>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
@@ -185,12 +266,213 @@ pub struct ViewerRevision {
 // API Types
 // ============================================================================
 
+/// Version of the wire types in this crate. Bump it whenever a change would
+/// break decoding for a frontend built against the previous version.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Which build produced a frontend or backend. Served by `GET /api/version`
+/// and attached to error bodies so bug reports name the build.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct BuildVersion {
+    /// Short git hash of the build, or "unknown".
+    pub git_hash: String,
+    /// [`SCHEMA_VERSION`] the build was compiled with.
+    pub schema_version: u32,
+}
+
+impl BuildVersion {
+    /// The build this code was compiled into.
+    pub fn current() -> Self {
+        Self {
+            git_hash: env!("CHOIR_GIT_HASH").to_string(),
+            schema_version: SCHEMA_VERSION,
+        }
+    }
+
+    /// Whether a peer on `other` decodes the same wire types. Only the
+    /// schema version matters; builds differing in anything else interoperate.
+    pub fn is_compatible_with(&self, other: &BuildVersion) -> bool {
+        self.schema_version == other.schema_version
+    }
+}
+
 /// Generic API response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiResponse<T> {
     pub success: bool,
     pub data: Option<T>,
-    pub error: Option<String>,
+    pub error: Option<ApiError>,
+}
+
+/// Catalog of user-facing failure codes, shared by every sandbox endpoint and
+/// the UI. The sandbox maps each code to one HTTP status; the UI branches on
+/// the code for guidance and keeps the raw message as detail.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, TS)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub enum ErrorCode {
+    /// Malformed or missing input (400).
+    InvalidRequest,
+    /// A path escapes the sandbox (403).
+    PathTraversal,
+    /// The path names a directory where a file is required (400).
+    NotAFile,
+    /// The path names a file where a directory is required (400).
+    NotADirectory,
+    /// The path names a directory where a document is required (400).
+    IsDirectory,
+    /// Content could not be decoded or fails validation (400).
+    InvalidContent,
+    /// A document revision that does not exist or does not apply (400).
+    InvalidRevision,
+    /// Upload or resource exceeds a size limit (413).
+    PayloadTooLarge,
+    /// The resource type is not supported by this endpoint (415).
+    UnsupportedMediaType,
+    /// Well-formed input with an unsupported schema version (422).
+    UnsupportedSchema,
+    /// The addressed resource does not exist (404).
+    NotFound,
+    /// The addressed conductor run does not exist (404).
+    RunNotFound,
+    /// A conductor run ended in failure (500).
+    RunFailed,
+    /// A conductor run stopped and needs input before it can continue (409).
+    RunBlocked,
+    /// Creating the resource would overwrite an existing one (409).
+    AlreadyExists,
+    /// The request conflicts with current state (409).
+    Conflict,
+    /// A document changed since the revision the edit was based on (409).
+    DocumentConflict,
+    /// The sandbox refuses the operation on this path (403).
+    PermissionDenied,
+    /// A policy blocked the operation (403).
+    PolicyBlocked,
+    /// The workspace disk quota would be exceeded (507).
+    QuotaExceeded,
+    /// A supervisor the request depends on is not running (503).
+    SupervisorUnavailable,
+    /// The actor serving the request could not be started (503).
+    ActorSpawnFailed,
+    /// A running actor did not answer (503).
+    ActorUnavailable,
+    /// The model provider gateway is unreachable (503).
+    ProviderUnavailable,
+    /// Another backing actor or service could not be reached (503).
+    ServiceUnavailable,
+    /// Anything else (500).
+    InternalError,
+}
+
+impl ErrorCode {
+    pub const ALL: [ErrorCode; 26] = [
+        ErrorCode::InvalidRequest,
+        ErrorCode::PathTraversal,
+        ErrorCode::NotAFile,
+        ErrorCode::NotADirectory,
+        ErrorCode::IsDirectory,
+        ErrorCode::InvalidContent,
+        ErrorCode::InvalidRevision,
+        ErrorCode::PayloadTooLarge,
+        ErrorCode::UnsupportedMediaType,
+        ErrorCode::UnsupportedSchema,
+        ErrorCode::NotFound,
+        ErrorCode::RunNotFound,
+        ErrorCode::RunFailed,
+        ErrorCode::RunBlocked,
+        ErrorCode::AlreadyExists,
+        ErrorCode::Conflict,
+        ErrorCode::DocumentConflict,
+        ErrorCode::PermissionDenied,
+        ErrorCode::PolicyBlocked,
+        ErrorCode::QuotaExceeded,
+        ErrorCode::SupervisorUnavailable,
+        ErrorCode::ActorSpawnFailed,
+        ErrorCode::ActorUnavailable,
+        ErrorCode::ProviderUnavailable,
+        ErrorCode::ServiceUnavailable,
+        ErrorCode::InternalError,
+    ];
+
+    /// The wire form, e.g. `"QUOTA_EXCEEDED"`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::InvalidRequest => "INVALID_REQUEST",
+            ErrorCode::PathTraversal => "PATH_TRAVERSAL",
+            ErrorCode::NotAFile => "NOT_A_FILE",
+            ErrorCode::NotADirectory => "NOT_A_DIRECTORY",
+            ErrorCode::IsDirectory => "IS_DIRECTORY",
+            ErrorCode::InvalidContent => "INVALID_CONTENT",
+            ErrorCode::InvalidRevision => "INVALID_REVISION",
+            ErrorCode::PayloadTooLarge => "PAYLOAD_TOO_LARGE",
+            ErrorCode::UnsupportedMediaType => "UNSUPPORTED_MEDIA_TYPE",
+            ErrorCode::UnsupportedSchema => "UNSUPPORTED_SCHEMA",
+            ErrorCode::NotFound => "NOT_FOUND",
+            ErrorCode::RunNotFound => "RUN_NOT_FOUND",
+            ErrorCode::RunFailed => "RUN_FAILED",
+            ErrorCode::RunBlocked => "RUN_BLOCKED",
+            ErrorCode::AlreadyExists => "ALREADY_EXISTS",
+            ErrorCode::Conflict => "CONFLICT",
+            ErrorCode::DocumentConflict => "DOCUMENT_CONFLICT",
+            ErrorCode::PermissionDenied => "PERMISSION_DENIED",
+            ErrorCode::PolicyBlocked => "POLICY_BLOCKED",
+            ErrorCode::QuotaExceeded => "QUOTA_EXCEEDED",
+            ErrorCode::SupervisorUnavailable => "SUPERVISOR_UNAVAILABLE",
+            ErrorCode::ActorSpawnFailed => "ACTOR_SPAWN_FAILED",
+            ErrorCode::ActorUnavailable => "ACTOR_UNAVAILABLE",
+            ErrorCode::ProviderUnavailable => "PROVIDER_UNAVAILABLE",
+            ErrorCode::ServiceUnavailable => "SERVICE_UNAVAILABLE",
+            ErrorCode::InternalError => "INTERNAL_ERROR",
+        }
+    }
+
+    /// Parse the wire form; unknown codes yield `None`.
+    pub fn parse(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.as_str() == code)
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Typed error body, returned as `{ "error": ApiError }`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct ApiError {
+    pub code: ErrorCode,
+    pub message: String,
+    /// Endpoint-specific context, e.g. the offending field or limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(type = "unknown")]
+    pub details: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_kind: Option<FailureKind>,
+}
+
+impl ApiError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            details: None,
+            failure_kind: None,
+        }
+    }
+
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    pub fn with_failure_kind(mut self, failure_kind: FailureKind) -> Self {
+        self.failure_kind = Some(failure_kind);
+        self
+    }
 }
 
 /// WebSocket message protocol
@@ -244,13 +526,59 @@ pub struct ConductorDocumentUpdatePayload {
     pub timestamp: String,
 }
 
+/// Which `writer.run.*` events a desktop WebSocket session is sent.
+/// Status and failure events are delivered at every level.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub enum PatchGranularity {
+    /// Every event, including each patch.
+    #[default]
+    All,
+    /// Changesets stand in for the patches they summarize.
+    ChangesetsOnly,
+    /// Run start, status and failure only.
+    StatusOnly,
+}
+
+/// Machine-readable reason attached to a desktop WebSocket error.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub enum WsErrorCode {
+    /// The session already holds the maximum number of subscriptions; the
+    /// subscription was refused.
+    TooManySubscriptions,
+    /// An inbound message exceeded the per-message size limit; it was dropped
+    /// without being processed.
+    MessageTooLarge,
+}
+
 /// Canonical desktop WebSocket protocol shared by sandbox and UI.
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(tag = "type")]
 #[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
 pub enum DesktopWsMessage {
     #[serde(rename = "subscribe")]
-    Subscribe { desktop_id: String },
+    Subscribe {
+        desktop_id: String,
+        /// Defaults to the session's current setting (`all` for a new session).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        patch_granularity: Option<PatchGranularity>,
+        /// Opt-in event topics, such as `conductor.run.state_delta`, that
+        /// are only forwarded to sessions naming them.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        topics: Vec<String>,
+    },
+
+    /// Stop forwarding a desktop's events to this session, freeing the
+    /// subscription for another desktop.
+    #[serde(rename = "unsubscribe")]
+    Unsubscribe { desktop_id: String },
+
+    /// Change the session's writer-run forwarding filter mid-session.
+    #[serde(rename = "update_subscription")]
+    UpdateSubscription { patch_granularity: PatchGranularity },
 
     #[serde(rename = "ping")]
     Ping,
@@ -318,6 +646,12 @@ pub enum DesktopWsMessage {
         payload: ConductorDocumentUpdatePayload,
     },
 
+    #[serde(rename = "conductor.run.state_delta")]
+    ConductorRunStateDelta {
+        #[serde(flatten)]
+        delta: ConductorRunStateDelta,
+    },
+
     #[serde(rename = "writer.run.started")]
     WriterRunStarted {
         #[serde(flatten)]
@@ -368,8 +702,493 @@ pub enum DesktopWsMessage {
         payload: WriterRunChangesetPayload,
     },
 
+    #[serde(rename = "writer.run.lock")]
+    WriterRunLock {
+        #[serde(flatten)]
+        base: WriterRunEventBase,
+        #[serde(flatten)]
+        payload: WriterRunLockPayload,
+    },
+
+    /// Provider gateway reachability changed. While `reachable` is false the
+    /// desktop shows a persistent outage banner with `message`.
+    #[serde(rename = "provider.gateway.status")]
+    ProviderGatewayStatus {
+        reachable: bool,
+        message: Option<String>,
+    },
+
     #[serde(rename = "error")]
-    Error { message: String },
+    Error {
+        message: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error_code: Option<WsErrorCode>,
+        /// Build of the sandbox that sent the error.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        version: Option<BuildVersion>,
+    },
+}
+
+/// Body of `POST /api/events/subscriptions`: the long-poll counterpart of a
+/// WebSocket `subscribe` message.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct EventSubscriptionRequest {
+    /// Cursor of an existing long-poll session to add the desktop to; a new
+    /// session is started when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    pub desktop_id: String,
+    /// Defaults to the session's current setting (`all` for a new session).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patch_granularity: Option<PatchGranularity>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub topics: Vec<String>,
+}
+
+/// Response of `POST /api/events/subscriptions`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct EventSubscriptionResponse {
+    /// Cursor for the next `GET /api/events/poll`.
+    pub cursor: String,
+    /// The session is dropped after this long without a poll or subscribe.
+    pub expires_in_ms: u64,
+}
+
+/// Response of `GET /api/events/poll`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct EventPollResponse {
+    /// Messages after the polled cursor, in the order a WebSocket session
+    /// would have received them.
+    pub messages: Vec<DesktopWsMessage>,
+    /// Cursor for the next poll; the polled cursor when nothing arrived.
+    pub next_cursor: String,
+}
+
+/// Server clock reading served by `GET /api/time`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct ServerTimeResponse {
+    /// Server wall-clock time when the request was handled.
+    pub now: DateTime<Utc>,
+    /// Milliseconds on the server's monotonic clock. It only goes backwards
+    /// across a server restart, which makes earlier samples stale.
+    pub monotonic_ms: u64,
+}
+
+/// Source of a search hit.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub enum SearchHitKind {
+    /// A chat message typed by the user on any surface.
+    Chat,
+    /// A saved document version.
+    Document,
+    /// A report or worker artifact.
+    Artifact,
+}
+
+impl SearchHitKind {
+    pub const ALL: [SearchHitKind; 3] = [Self::Chat, Self::Document, Self::Artifact];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Chat => "chat",
+            Self::Document => "document",
+            Self::Artifact => "artifact",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.as_str().eq_ignore_ascii_case(value.trim()))
+    }
+}
+
+/// Where a search hit deep-links to.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub enum SearchTarget {
+    Chat {
+        actor_id: String,
+        thread_id: String,
+        session_id: String,
+        /// Document the message was typed against, for writer prompts.
+        document_path: Option<String>,
+    },
+    Document {
+        path: String,
+        run_id: Option<String>,
+        revision: u64,
+    },
+    Artifact {
+        artifact_id: Option<String>,
+        artifact_kind: String,
+        run_id: Option<String>,
+        /// Sandbox-relative path when the artifact is a file.
+        path: Option<String>,
+    },
+}
+
+/// Piece of a search excerpt; `highlight` marks text that matched the query.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct SearchExcerptSegment {
+    pub text: String,
+    pub highlight: bool,
+}
+
+/// Single result from `GET /api/search`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct SearchHit {
+    pub kind: SearchHitKind,
+    /// Seq of the event the hit was indexed from.
+    pub seq: i64,
+    pub title: String,
+    pub excerpt: Vec<SearchExcerptSegment>,
+    pub timestamp: DateTime<Utc>,
+    /// Relevance, higher is better.
+    pub score: f64,
+    pub target: SearchTarget,
+}
+
+/// Response body for `GET /api/search`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct SearchResponse {
+    pub query: String,
+    pub hits: Vec<SearchHit>,
+}
+
+// ============================================================================
+// Research Tasks
+// ============================================================================
+
+/// Source gathered by a research task.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct ResearchCitation {
+    pub id: String,
+    pub provider: String,
+    pub title: String,
+    pub url: String,
+    pub snippet: String,
+    pub published_at: Option<String>,
+    pub score: Option<f64>,
+    /// Days since `published_at`, when the date could be read.
+    #[serde(default)]
+    pub age_days: Option<u32>,
+}
+
+/// One search provider request made by a research task.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct ResearchProviderCall {
+    pub provider: String,
+    pub latency_ms: u64,
+    pub result_count: usize,
+    pub succeeded: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub enum ResearchTaskStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+/// Payload for `research.task.started`. `task_id` is also the `task_id` of
+/// the task's `worker.task.progress` events.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct ResearchTaskStartedPayload {
+    pub task_id: String,
+    pub researcher_id: String,
+    pub objective: String,
+    pub run_id: Option<String>,
+    pub call_id: Option<String>,
+    /// Task this one re-runs, when started from the Research app.
+    pub rerun_of: Option<String>,
+    /// Batch this task was submitted in, see `POST /api/research/batch`.
+    #[serde(default)]
+    pub batch_id: Option<String>,
+    /// Desktop the task was started from in the Research app. Tasks of a
+    /// conductor run belong to the run's desktop instead.
+    #[serde(default)]
+    pub desktop_id: Option<String>,
+    pub timestamp: String,
+}
+
+/// Payload for `research.task.completed`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct ResearchTaskCompletedPayload {
+    pub task_id: String,
+    pub researcher_id: String,
+    pub run_id: Option<String>,
+    pub success: bool,
+    /// Synthesized answer; citation markers like `[1]` index into `citations`.
+    pub summary: String,
+    pub provider_used: Option<String>,
+    pub model_used: Option<String>,
+    pub citations: Vec<ResearchCitation>,
+    pub provider_calls: Vec<ResearchProviderCall>,
+    pub timestamp: String,
+}
+
+/// Payload for `research.task.failed`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct ResearchTaskFailedPayload {
+    pub task_id: String,
+    pub researcher_id: String,
+    pub run_id: Option<String>,
+    pub error: String,
+    pub timestamp: String,
+}
+
+/// Row of `GET /api/research/tasks`, newest first.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct ResearchTaskSummary {
+    pub task_id: String,
+    pub objective: String,
+    pub status: ResearchTaskStatus,
+    pub run_id: Option<String>,
+    pub rerun_of: Option<String>,
+    pub batch_id: Option<String>,
+    /// Desktop the task is listed on, when known.
+    pub desktop_id: Option<String>,
+    /// Seq of the `research.task.started` event; live progress follows it.
+    pub started_seq: i64,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub citation_count: usize,
+    pub provider_used: Option<String>,
+}
+
+/// Progress line from a task's `worker.task.progress` events.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct ResearchTaskProgress {
+    pub seq: i64,
+    pub phase: String,
+    pub message: String,
+    pub provider: Option<String>,
+    pub timestamp: String,
+}
+
+/// Body of `GET /api/research/tasks/{task_id}`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct ResearchTaskDetail {
+    pub task: ResearchTaskSummary,
+    pub summary: Option<String>,
+    pub model_used: Option<String>,
+    pub citations: Vec<ResearchCitation>,
+    pub provider_calls: Vec<ResearchProviderCall>,
+    pub error: Option<String>,
+    pub progress: Vec<ResearchTaskProgress>,
+}
+
+/// Body of `POST /api/research/tasks/{task_id}/rerun`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct ResearchRerunRequest {
+    /// Replacement objective; the original one is reused when absent.
+    #[serde(default)]
+    pub objective: Option<String>,
+    #[serde(default)]
+    pub max_rounds: Option<u8>,
+    /// Desktop to list the new task on; the original task's when absent.
+    #[serde(default)]
+    pub desktop_id: Option<String>,
+}
+
+/// Response of `POST /api/research/tasks/{task_id}/rerun`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct ResearchRerunResponse {
+    pub task_id: String,
+}
+
+/// Body of `POST /api/research/batch`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct ResearchBatchRequest {
+    pub queries: Vec<String>,
+    /// Round budget shared by the whole batch, split evenly across queries.
+    #[serde(default)]
+    pub max_rounds: Option<u16>,
+    /// Desktop to list the batch's tasks on.
+    #[serde(default)]
+    pub desktop_id: Option<String>,
+}
+
+/// Response of `POST /api/research/batch`. `task_ids` follow the order of
+/// the submitted queries.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct ResearchBatchResponse {
+    pub batch_id: String,
+    pub task_ids: Vec<String>,
+}
+
+/// Body of `GET /api/research/batch/{batch_id}`; tasks in submission order.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct ResearchBatchDetail {
+    pub batch_id: String,
+    pub tasks: Vec<ResearchTaskSummary>,
+}
+
+/// Body of `POST /api/research/tasks/{task_id}/send-to-writer`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct ResearchSendToWriterRequest {
+    /// Run document to append to; defaults to the task's own run document.
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+/// Response of `POST /api/research/tasks/{task_id}/send-to-writer`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct ResearchSendToWriterResponse {
+    pub path: String,
+    pub section_id: String,
+    pub revision: u64,
+}
+
+// ============================================================================
+// Workspace Disk Usage
+// ============================================================================
+
+/// What a byte of workspace disk usage belongs to, by top-level location.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+#[serde(rename_all = "snake_case")]
+pub enum DiskUsageCategory {
+    /// Conductor run directories (`conductor/runs/*`).
+    Runs,
+    /// Final run reports (`reports/*`).
+    Reports,
+    /// Writer revision sidecars (`.writer_revisions/*`).
+    Revisions,
+    /// Everything else: user files and uploads.
+    Files,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct DiskCategoryUsage {
+    pub category: DiskUsageCategory,
+    pub bytes: u64,
+}
+
+/// Body of `GET /api/usage/disk`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct DiskUsageResponse {
+    pub used_bytes: u64,
+    /// None when no quota is configured.
+    pub quota_bytes: Option<u64>,
+    pub used_pct: Option<u8>,
+    /// Largest category first, the order cleanup should work through.
+    pub categories: Vec<DiskCategoryUsage>,
+    /// When the last full scan ran; writes since then are counted incrementally.
+    pub scanned_at: DateTime<Utc>,
+}
+
+/// A durable preference learned from what the user said in conversation.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct LearnedPreference {
+    pub preference_id: String,
+    /// Short snake_case subject, e.g. `package_manager`. A newer preference on
+    /// the same topic replaces the older one.
+    pub topic: String,
+    /// The preference in the user's terms, e.g. "Uses pnpm, not npm".
+    pub statement: String,
+    pub confidence: f64,
+    /// Run whose prompt the preference was learned from.
+    pub source_run_id: Option<String>,
+    pub learned_at: DateTime<Utc>,
+}
+
+/// Body of `GET /api/preferences`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct LearnedPreferencesResponse {
+    /// Whether new preferences are learned from conversations.
+    pub learning_enabled: bool,
+    /// Newest first.
+    pub preferences: Vec<LearnedPreference>,
+}
+
+/// Body of `PATCH /api/preferences`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct UpdatePreferenceLearningRequest {
+    pub learning_enabled: bool,
+}
+
+/// What counted as user activity in a session.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+#[serde(rename_all = "snake_case")]
+pub enum ActivitySource {
+    /// Any inbound WebSocket message, including pings.
+    WebSocket,
+    /// A long-poll subscribe or poll request.
+    LongPoll,
+    /// A task delegated to the conductor.
+    Delegation,
+}
+
+/// Payload for `session.activity`: the last activity seen in a session.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct SessionActivity {
+    /// The desktop id, or the connection id of a WebSocket that has not
+    /// subscribed to a desktop yet.
+    pub session_id: String,
+    pub source: ActivitySource,
+    pub at: DateTime<Utc>,
+}
+
+/// Body of `GET /api/activity`. The hypervisor idle watchdog reads this to
+/// decide when the sandbox may hibernate.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct ActivityResponse {
+    /// Latest activity across all sessions; None before any activity.
+    pub last_activity_at: Option<DateTime<Utc>>,
+    /// Seconds since `last_activity_at`, measured by the sandbox clock.
+    pub idle_secs: Option<u64>,
+    /// Most recently active first.
+    pub sessions: Vec<SessionActivity>,
+}
+
+/// Payload for `workspace.quota.warning`, emitted once per threshold crossing.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct WorkspaceQuotaWarningPayload {
+    /// The crossed threshold: 80 or 95.
+    pub threshold_pct: u8,
+    pub used_bytes: u64,
+    pub quota_bytes: u64,
+    pub largest_category: Option<DiskUsageCategory>,
+    pub timestamp: String,
 }
 
 // ============================================================================
@@ -567,8 +1386,24 @@ pub enum FailureKind {
     Unknown,    // Unclassified failure
 }
 
+/// Urgency of an escalation raised automatically for a failure of `kind`.
+///
+/// Auth failures need a human to fix credentials, so they are urgent;
+/// validation failures are the caller's to correct and rarely block anyone.
+pub fn escalation_urgency_for(kind: FailureKind) -> WorkerEscalationUrgency {
+    match kind {
+        FailureKind::Auth => WorkerEscalationUrgency::High,
+        FailureKind::Timeout
+        | FailureKind::Network
+        | FailureKind::RateLimit
+        | FailureKind::Provider
+        | FailureKind::Unknown => WorkerEscalationUrgency::Medium,
+        FailureKind::Validation => WorkerEscalationUrgency::Low,
+    }
+}
+
 /// Contract defining an objective for parent-child delegation
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
 pub struct ObjectiveContract {
     pub objective_id: String,                // Unique objective identifier
@@ -580,7 +1415,7 @@ pub struct ObjectiveContract {
     pub evidence_requirements: EvidenceRequirements, // What evidence to collect
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
 pub struct ObjectiveConstraints {
     pub max_tool_calls: u32,
@@ -589,7 +1424,7 @@ pub struct ObjectiveConstraints {
     pub allowed_capabilities: Vec<String>, // Capability whitelist
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
 pub struct EvidenceRequirements {
     pub requires_citations: bool,
@@ -597,6 +1432,134 @@ pub struct EvidenceRequirements {
     pub required_source_types: Vec<String>,
 }
 
+/// Most tool calls an objective contract may grant a single capability call.
+pub const OBJECTIVE_CONTRACT_MAX_TOOL_CALLS: u32 = 200;
+
+/// Longest per-call timeout an objective contract may grant (30 minutes).
+pub const OBJECTIVE_CONTRACT_MAX_TIMEOUT_MS: u64 = 30 * 60 * 1000;
+
+/// Shortest per-call timeout an objective contract may set.
+pub const OBJECTIVE_CONTRACT_MIN_TIMEOUT_MS: u64 = 5_000;
+
+/// Most success criteria a contract may list; longer lists are not checkable.
+pub const OBJECTIVE_CONTRACT_MAX_CRITERIA: usize = 10;
+
+impl ObjectiveContract {
+    /// Check that the contract can be enforced as a run budget.
+    ///
+    /// Returns the first problem found, phrased for the user who edited it.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.primary_objective.trim().is_empty() {
+            return Err("primary_objective cannot be empty".to_string());
+        }
+        if self.success_criteria.is_empty() {
+            return Err("at least one success criterion is required".to_string());
+        }
+        if self.success_criteria.len() > OBJECTIVE_CONTRACT_MAX_CRITERIA {
+            return Err(format!(
+                "at most {OBJECTIVE_CONTRACT_MAX_CRITERIA} success criteria are allowed"
+            ));
+        }
+        if self.success_criteria.iter().any(|c| c.trim().is_empty()) {
+            return Err("success criteria cannot be blank".to_string());
+        }
+        if self.attempts_budget == 0 {
+            return Err("attempts_budget must be at least 1".to_string());
+        }
+        let constraints = &self.constraints;
+        if constraints.max_tool_calls == 0
+            || constraints.max_tool_calls > OBJECTIVE_CONTRACT_MAX_TOOL_CALLS
+        {
+            return Err(format!(
+                "max_tool_calls must be between 1 and {OBJECTIVE_CONTRACT_MAX_TOOL_CALLS}"
+            ));
+        }
+        if !(OBJECTIVE_CONTRACT_MIN_TIMEOUT_MS..=OBJECTIVE_CONTRACT_MAX_TIMEOUT_MS)
+            .contains(&constraints.timeout_ms)
+        {
+            return Err(format!(
+                "timeout_ms must be between {OBJECTIVE_CONTRACT_MIN_TIMEOUT_MS} and {OBJECTIVE_CONTRACT_MAX_TIMEOUT_MS}"
+            ));
+        }
+        if constraints
+            .allowed_capabilities
+            .iter()
+            .any(|c| c.trim().is_empty())
+        {
+            return Err("allowed_capabilities cannot contain blank names".to_string());
+        }
+        let min_confidence = self.evidence_requirements.min_confidence;
+        if !(0.0..=1.0).contains(&min_confidence) {
+            return Err("min_confidence must be between 0.0 and 1.0".to_string());
+        }
+        Ok(())
+    }
+
+    /// Whether the contract lets the run dispatch `capability`.
+    ///
+    /// An empty whitelist allows every capability.
+    pub fn allows_capability(&self, capability: &str) -> bool {
+        let allowed = &self.constraints.allowed_capabilities;
+        allowed.is_empty()
+            || allowed
+                .iter()
+                .any(|name| name.trim().eq_ignore_ascii_case(capability))
+    }
+
+    /// The objective as handed to workers: the primary objective followed by
+    /// the success criteria and evidence the run is held to.
+    pub fn brief(&self) -> String {
+        let mut brief = self.primary_objective.trim().to_string();
+        brief.push_str("\n\nSuccess criteria:");
+        for criterion in &self.success_criteria {
+            brief.push_str(&format!("\n- {}", criterion.trim()));
+        }
+        let evidence = &self.evidence_requirements;
+        if evidence.requires_citations {
+            brief.push_str("\n\nCite a source for every factual claim.");
+            if !evidence.required_source_types.is_empty() {
+                brief.push_str(&format!(
+                    " Acceptable sources: {}.",
+                    evidence.required_source_types.join(", ")
+                ));
+            }
+        }
+        brief
+    }
+
+    /// Contract for one agenda item of the run this contract governs.
+    ///
+    /// The child answers to this contract: it keeps the success criteria,
+    /// evidence rules, timeout and attempts, splits the tool-call budget
+    /// evenly across the `agenda_len` items (never below one call), gives up
+    /// one level of sub-frame depth, and may only use the item's capability.
+    pub fn child_for_agenda_item(
+        &self,
+        item_id: &str,
+        capability: &str,
+        objective: &str,
+        agenda_len: usize,
+    ) -> ObjectiveContract {
+        let share = u32::try_from(agenda_len.max(1)).unwrap_or(u32::MAX);
+        let parent = &self.constraints;
+        ObjectiveContract {
+            objective_id: format!("{}:{item_id}", self.objective_id),
+            parent_objective_id: Some(self.objective_id.clone()),
+            primary_objective: objective.to_string(),
+            success_criteria: self.success_criteria.clone(),
+            constraints: ObjectiveConstraints {
+                max_tool_calls: (parent.max_tool_calls / share)
+                    .clamp(1, parent.max_tool_calls.max(1)),
+                timeout_ms: parent.timeout_ms,
+                max_subframe_depth: parent.max_subframe_depth.saturating_sub(1),
+                allowed_capabilities: vec![capability.to_string()],
+            },
+            attempts_budget: self.attempts_budget,
+            evidence_requirements: self.evidence_requirements.clone(),
+        }
+    }
+}
+
 /// Payload for child-to-parent completion reporting
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
@@ -899,6 +1862,118 @@ pub struct ConductorRunState {
     pub output_mode: ConductorOutputMode,
     /// Desktop ID for UI coordination
     pub desktop_id: String,
+    /// Bumped by every change published as a [`ConductorRunStateDelta`]
+    #[serde(default)]
+    pub revision: u64,
+    /// Contract agreed in the refinement dialog, if the user refined the objective
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract: Option<ObjectiveContract>,
+}
+
+/// What [`ConductorRunState::apply_delta`] did with a delta.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunDeltaOutcome {
+    /// The delta was the next revision and is applied.
+    Applied,
+    /// The delta belongs to another run or to a revision already held, as
+    /// when it is redelivered; the state is untouched.
+    Ignored,
+    /// Revisions between the state and the delta were missed; the state is
+    /// untouched and the caller should refetch the run.
+    Gap,
+}
+
+impl ConductorRunState {
+    /// Apply the next delta of this run's stream.
+    pub fn apply_delta(&mut self, delta: &ConductorRunStateDelta) -> RunDeltaOutcome {
+        if delta.run_id != self.run_id || delta.revision <= self.revision {
+            return RunDeltaOutcome::Ignored;
+        }
+        if delta.revision != self.revision + 1 {
+            return RunDeltaOutcome::Gap;
+        }
+        self.revision = delta.revision;
+        self.updated_at = delta.updated_at;
+        if let Some(status) = delta.status {
+            self.status = status;
+        }
+        if delta.completed_at.is_some() {
+            self.completed_at = delta.completed_at;
+        }
+        if let Some(output_mode) = delta.output_mode {
+            self.output_mode = output_mode;
+        }
+        for item in &delta.agenda {
+            match self.agenda.iter_mut().find(|i| i.item_id == item.item_id) {
+                Some(existing) => *existing = item.clone(),
+                None => self.agenda.push(item.clone()),
+            }
+        }
+        for call in &delta.calls {
+            match self
+                .active_calls
+                .iter_mut()
+                .find(|c| c.call_id == call.call_id)
+            {
+                Some(existing) => *existing = call.clone(),
+                None => self.active_calls.push(call.clone()),
+            }
+        }
+        self.artifacts.extend(delta.artifacts.iter().cloned());
+        self.decision_log.extend(delta.decisions.iter().cloned());
+        RunDeltaOutcome::Applied
+    }
+}
+
+/// Changed fields of a conductor run, published as
+/// `conductor.run.state_delta` on every agenda, call, artifact, decision or
+/// status transition. Applied in revision order, the stream reproduces the
+/// run state.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct ConductorRunStateDelta {
+    pub run_id: String,
+    pub desktop_id: String,
+    /// Run revision after this change
+    pub revision: u64,
+    pub updated_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<ConductorRunStatus>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_mode: Option<ConductorOutputMode>,
+    /// Added or changed agenda items, replacing those with the same id
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub agenda: Vec<ConductorAgendaItem>,
+    /// Started or updated capability calls, replacing those with the same id
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<ConductorCapabilityCall>,
+    /// Appended artifacts
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub artifacts: Vec<ConductorArtifact>,
+    /// Appended decisions
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub decisions: Vec<ConductorDecision>,
+}
+
+impl ConductorRunStateDelta {
+    /// An empty delta moving `run` to its next revision at `updated_at`.
+    pub fn next(run: &ConductorRunState, updated_at: DateTime<Utc>) -> Self {
+        Self {
+            run_id: run.run_id.clone(),
+            desktop_id: run.desktop_id.clone(),
+            revision: run.revision + 1,
+            updated_at,
+            status: None,
+            completed_at: None,
+            output_mode: None,
+            agenda: Vec::new(),
+            calls: Vec::new(),
+            artifacts: Vec::new(),
+            decisions: Vec::new(),
+        }
+    }
 }
 
 /// Status of a conductor run
@@ -909,6 +1984,8 @@ pub enum ConductorRunStatus {
     Initializing,
     Running,
     WaitingForCalls,
+    /// Accepted while the provider gateway is unreachable; starts on recovery.
+    WaitingForProvider,
     Completing,
     Completed,
     Failed,
@@ -960,13 +2037,36 @@ pub struct ConductorExecuteRequest {
     pub output_mode: ConductorOutputMode,
     #[ts(type = "unknown")]
     pub hints: Option<serde_json::Value>,
+    /// Refined contract the run is held to; `None` when refinement was skipped
+    #[serde(default)]
+    pub contract: Option<ObjectiveContract>,
+}
+
+/// Request to refine an objective into an [`ObjectiveContract`] before execution.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(deny_unknown_fields)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct ConductorRefineRequest {
+    pub objective: String,
+    pub desktop_id: String,
+    /// What the user has open, e.g. window titles, to ground the questions
+    #[serde(default)]
+    pub desktop_context: Vec<String>,
+}
+
+/// Proposed contract for an objective plus questions to put to the user.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct ConductorRefineResponse {
+    pub questions: Vec<String>,
+    pub contract: ObjectiveContract,
 }
 
 /// Typed error for Conductor task failures
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
 pub struct ConductorError {
-    pub code: String,
+    pub code: ErrorCode,
     pub message: String,
     pub failure_kind: Option<FailureKind>,
 }
@@ -1062,6 +2162,12 @@ pub struct WriterRunPatchPayload {
     pub base_version_id: Option<u64>,
     pub target_version_id: Option<u64>,
     pub overlay_id: Option<String>,
+    /// Patch this one reverts (an undo, or a redo when the target is an undo).
+    #[serde(default)]
+    pub undo_of: Option<String>,
+    /// Who confirmed an accepted proposal; `source` stays the proposing agent.
+    #[serde(default)]
+    pub confirmed_by: Option<String>,
 }
 
 /// Impact level for writer.run.changeset events (mirrors BAML ImpactLevel)
@@ -1092,6 +2198,122 @@ pub struct WriterRunChangesetPayload {
     pub impact: ChangesetImpact,
     /// List of change categories present (e.g. "insert", "structural_rewrite")
     pub op_taxonomy: Vec<String>,
+    /// Who confirmed the change when it was an accepted proposal
+    #[serde(default)]
+    pub confirmed_by: Option<String>,
+}
+
+/// Payload for writer.run.lock events.
+///
+/// A writer loop takes a lock when it starts and declares the blocks it is
+/// about to rewrite, so user edits to them can be warned about or rejected.
+/// The lock is re-sent under the same `lock_id` each time it grows. Locks are
+/// soft: each one lapses at `expires_at` even if its release event never
+/// arrives.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct WriterRunLockPayload {
+    pub lock_id: String,
+    /// Ids from [`writer_blocks`] of the version the loop started from.
+    pub block_ids: Vec<String>,
+    pub ttl_ms: u64,
+    pub expires_at: DateTime<Utc>,
+    /// Set on the event that ends the lock before its TTL.
+    #[serde(default)]
+    pub released: bool,
+    /// Why the lock was released: "completed" or "failed".
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+impl WriterRunLockPayload {
+    /// Whether the lock still holds at `now`.
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        !self.released && self.expires_at > now
+    }
+}
+
+/// What happens to a user edit that touches a block an agent has locked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub enum WriterLockStrictness {
+    /// Apply the edit and record a warning.
+    #[default]
+    Warn,
+    /// Refuse the edit until the lock is released or expires.
+    Reject,
+}
+
+/// A paragraph-level block of a writer document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WriterBlock {
+    /// Content-derived id, stable while the block's text is unchanged.
+    pub block_id: String,
+    /// Position among the document's blocks, matching the editor's rendering.
+    pub index: usize,
+    /// Byte range of the block in the markdown.
+    pub range: std::ops::Range<usize>,
+}
+
+/// Split writer markdown into blocks the way the editor renders it: blocks
+/// are separated by a newline, any whitespace, and another newline.
+///
+/// A block's id hashes its trimmed text, with `-<n>` appended to the n-th
+/// repeat of the same text, so server and editor derive the same ids from
+/// the same content.
+pub fn writer_blocks(markdown: &str) -> Vec<WriterBlock> {
+    let bytes = markdown.as_bytes();
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\n' {
+            let mut last_newline = None;
+            let mut j = i + 1;
+            while j < bytes.len() && bytes[j].is_ascii_whitespace() {
+                if bytes[j] == b'\n' {
+                    last_newline = Some(j);
+                }
+                j += 1;
+            }
+            if let Some(end) = last_newline {
+                ranges.push(start..i);
+                start = end + 1;
+                i = end + 1;
+                continue;
+            }
+        }
+        i += 1;
+    }
+    ranges.push(start..bytes.len());
+
+    let mut seen = std::collections::HashMap::<u64, usize>::new();
+    ranges
+        .into_iter()
+        .enumerate()
+        .map(|(index, range)| {
+            let hash = fnv1a64(markdown[range.clone()].trim().replace('\r', "").as_bytes());
+            let repeat = seen.entry(hash).or_insert(0);
+            *repeat += 1;
+            let block_id = if *repeat == 1 {
+                format!("blk-{hash:016x}")
+            } else {
+                format!("blk-{hash:016x}-{repeat}")
+            };
+            WriterBlock {
+                block_id,
+                index,
+                range,
+            }
+        })
+        .collect()
+}
+
+fn fnv1a64(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
 }
 
 /// Full writer run event with base fields and typed payload
@@ -1143,6 +2365,103 @@ pub enum WriterRunEvent {
         error_message: String,
         failure_kind: Option<FailureKind>,
     },
+    #[serde(rename = "writer.run.lock")]
+    Lock {
+        #[serde(flatten)]
+        base: WriterRunEventBase,
+        #[serde(flatten)]
+        payload: WriterRunLockPayload,
+    },
+}
+
+impl WriterRunEvent {
+    /// Fields every writer run event carries.
+    pub fn base(&self) -> &WriterRunEventBase {
+        match self {
+            Self::Started { base, .. }
+            | Self::Progress { base, .. }
+            | Self::Patch { base, .. }
+            | Self::Changeset { base, .. }
+            | Self::Status { base, .. }
+            | Self::Failed { base, .. }
+            | Self::Lock { base, .. } => base,
+        }
+    }
+}
+
+/// A `writer.run.changeset` whose `patch_id` no earlier `writer.run.patch`
+/// of its run emitted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrphanChangeset {
+    pub run_id: String,
+    pub patch_id: String,
+}
+
+impl std::fmt::Display for OrphanChangeset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "changeset for patch {} precedes any such patch in run {}",
+            self.patch_id, self.run_id
+        )
+    }
+}
+
+impl std::error::Error for OrphanChangeset {}
+
+/// Checks a writer run event stream, in order, for changesets that do not
+/// follow their patch.
+///
+/// Patch ids are remembered for the `max_runs` runs seen most recently; a
+/// changeset of a run forgotten since is reported as an orphan.
+#[derive(Debug, Clone)]
+pub struct WriterRunPatchOrder {
+    /// Emitted patch ids by run, the run seen most recently last.
+    runs: std::collections::VecDeque<(String, std::collections::HashSet<String>)>,
+    max_runs: usize,
+}
+
+impl WriterRunPatchOrder {
+    pub fn new(max_runs: usize) -> Self {
+        Self {
+            runs: std::collections::VecDeque::new(),
+            max_runs: max_runs.max(1),
+        }
+    }
+
+    /// Record `event`, failing if it is a changeset for a patch its run has
+    /// not emitted yet.
+    pub fn observe(&mut self, event: &WriterRunEvent) -> Result<(), OrphanChangeset> {
+        let run_id = event.base().run_id.as_str();
+        let position = self.runs.iter().position(|(id, _)| id == run_id);
+        match event {
+            WriterRunEvent::Patch { payload, .. } => {
+                let mut run = match position.and_then(|index| self.runs.remove(index)) {
+                    Some(run) => run,
+                    None => (run_id.to_string(), Default::default()),
+                };
+                run.1.insert(payload.patch_id.clone());
+                self.runs.push_back(run);
+                while self.runs.len() > self.max_runs {
+                    self.runs.pop_front();
+                }
+                Ok(())
+            }
+            WriterRunEvent::Changeset { payload, .. } => {
+                let emitted =
+                    position.is_some_and(|index| self.runs[index].1.contains(&payload.patch_id));
+                if emitted {
+                    Ok(())
+                } else {
+                    Err(OrphanChangeset {
+                        run_id: run_id.to_string(),
+                        patch_id: payload.patch_id.clone(),
+                    })
+                }
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
 /// State tracking for a Conductor run via API
@@ -1159,8 +2478,13 @@ pub struct ConductorRunStatusResponse {
     pub completed_at: Option<DateTime<Utc>>,
     pub document_path: String,
     pub report_path: Option<String>,
+    /// Priority-weighted share of completed agenda items; 100 once completed.
+    pub progress_pct: u8,
     pub toast: Option<ConductorToastPayload>,
     pub error: Option<ConductorError>,
+    /// Run revision; `conductor.run.state_delta` events continue from it
+    #[serde(default)]
+    pub revision: u64,
 }
 
 /// Payload for `conductor.task.started`.
@@ -1173,6 +2497,8 @@ pub struct ConductorTaskStartedPayload {
     pub status: String,
     pub phase: String,
     pub timestamp: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract: Option<ObjectiveContract>,
 }
 
 /// Payload for `conductor.task.progress`.
@@ -1334,6 +2660,153 @@ pub struct WorkerReportReceivedPayload {
     pub timestamp: String,
 }
 
+/// Payload for `viewer.content_conflict`: a save whose `base_rev` no longer
+/// matched the stored revision.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct ViewerConflictPayload {
+    /// Viewer URI of the document.
+    pub path: String,
+    /// Revision the stale save was based on.
+    pub base_rev: i64,
+    /// Revision stored when the save arrived.
+    pub current_rev: i64,
+    pub attempted_content: String,
+    pub window_id: String,
+    pub user_id: String,
+    pub timestamp: String,
+}
+
+/// What a piece of an LLM call's prompt is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub enum ContextTraceItemKind {
+    System,
+    Tools,
+    Message,
+}
+
+/// One piece of an LLM call's prompt.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct ContextTraceItem {
+    pub kind: ContextTraceItemKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    pub label: String,
+    /// Stable id of the item within its prompt: `system`, `tools`, or
+    /// `message:<index>`. Absent in traces recorded before ids existed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Rough estimate: one token per four characters.
+    pub est_tokens: u32,
+    #[serde(default)]
+    pub trimmed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trim_reason: Option<String>,
+}
+
+/// How a writer loop's prompt carried the document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub enum DocumentContextMode {
+    /// The whole document text.
+    FullText,
+    /// An outline, the sections relevant to the objective, and a summary of
+    /// changes since the loop's base version.
+    Sectioned,
+}
+
+/// Payload for `model.context.trace`: what went into the prompt of the LLM
+/// call whose `llm.call.started` event carries the same `trace_id`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct ModelContextTracePayload {
+    pub trace_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<String>,
+    pub model_used: String,
+    pub items: Vec<ContextTraceItem>,
+    pub total_est_tokens: u32,
+    /// Whether any item was cut down to fit the prompt.
+    #[serde(default)]
+    pub trimmed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_window: Option<u32>,
+    /// Set for writer loops: how the document was put into the prompt.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub document_context: Option<DocumentContextMode>,
+}
+
+impl ModelContextTracePayload {
+    /// Trace of a prompt made of `items`; the token total and `trimmed`
+    /// are derived from them.
+    pub fn new(
+        trace_id: impl Into<String>,
+        model_used: impl Into<String>,
+        items: Vec<ContextTraceItem>,
+    ) -> Self {
+        Self {
+            trace_id: trace_id.into(),
+            run_id: None,
+            thread_id: None,
+            model_used: model_used.into(),
+            total_est_tokens: items.iter().map(|item| item.est_tokens).sum(),
+            trimmed: items.iter().any(|item| item.trimmed),
+            items,
+            context_window: None,
+            document_context: None,
+        }
+    }
+
+    /// Ids of the items that went into the prompt, in prompt order.
+    pub fn included_item_ids(&self) -> Vec<&str> {
+        self.items
+            .iter()
+            .filter_map(|item| item.id.as_deref())
+            .collect()
+    }
+}
+
+/// Whether an LLM call's context composition is still available.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub enum ContextTraceStatus {
+    Recorded,
+    /// Older than the retention window, or recorded before context traces
+    /// existed.
+    Pruned,
+}
+
+/// Context composition of one LLM call, as served by
+/// `GET /api/context-traces`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct ContextTraceCall {
+    pub trace_id: String,
+    pub function_name: String,
+    pub model_used: String,
+    pub started_at: DateTime<Utc>,
+    pub status: ContextTraceStatus,
+    pub items: Vec<ContextTraceItem>,
+    pub total_est_tokens: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_window: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub document_context: Option<DocumentContextMode>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct ContextTracesResponse {
+    pub calls: Vec<ContextTraceCall>,
+}
+
 // ============================================================================
 // Constants
 // ============================================================================
@@ -1343,45 +2816,90 @@ pub const EVENT_MODEL_SELECTION: &str = "model.selection";
 pub const EVENT_MODEL_CHANGED: &str = "model.changed";
 pub const EVENT_MODEL_CONTEXT_TRACE: &str = "model.context.trace";
 
-/// Attach optional scope metadata to any payload.
-///
-/// If no scope is provided, returns payload unchanged.
-/// If payload is an object, inserts `scope`.
-/// Otherwise wraps as `{ "value": <payload>, "scope": {...} }`.
-pub fn with_scope(
-    payload: serde_json::Value,
-    session_id: Option<String>,
-    thread_id: Option<String>,
-) -> serde_json::Value {
-    let mut scope = serde_json::Map::new();
-    if let Some(session_id) = session_id {
-        scope.insert(
-            "session_id".to_string(),
-            serde_json::Value::String(session_id),
-        );
+/// Session/thread scope of an event, carried under `scope` in its payload.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub struct EventScope {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<String>,
+}
+
+impl EventScope {
+    pub fn new(session_id: Option<String>, thread_id: Option<String>) -> Self {
+        Self {
+            session_id,
+            thread_id,
+        }
     }
-    if let Some(thread_id) = thread_id {
-        scope.insert(
-            "thread_id".to_string(),
-            serde_json::Value::String(thread_id),
-        );
+
+    pub fn is_empty(&self) -> bool {
+        self.session_id.is_none() && self.thread_id.is_none()
+    }
+
+    /// Scope recorded on a payload; empty when it has none.
+    ///
+    /// Non-object payloads wrapped by [`EventScope::attach`] keep their scope
+    /// at the top level too, so both shapes read the same way.
+    pub fn from_payload(payload: &serde_json::Value) -> Self {
+        let field = |name: &str| {
+            payload
+                .get("scope")
+                .and_then(|scope| scope.get(name))
+                .and_then(|value| value.as_str())
+                .map(ToString::to_string)
+        };
+        Self {
+            session_id: field("session_id"),
+            thread_id: field("thread_id"),
+        }
     }
-    if scope.is_empty() {
-        return payload;
+
+    /// Set `scope` on an object payload. An empty scope leaves it untouched.
+    pub fn insert_into(&self, obj: &mut serde_json::Map<String, serde_json::Value>) {
+        if self.is_empty() {
+            return;
+        }
+        obj.insert(
+            "scope".to_string(),
+            serde_json::to_value(self).unwrap_or_default(),
+        );
     }
 
-    match payload {
-        serde_json::Value::Object(mut obj) => {
-            obj.insert("scope".to_string(), serde_json::Value::Object(scope));
-            serde_json::Value::Object(obj)
+    /// Attach this scope to any payload.
+    ///
+    /// An empty scope returns the payload unchanged. Objects gain `scope`;
+    /// anything else is wrapped as `{ "value": <payload>, "scope": {...} }`.
+    pub fn attach(&self, payload: serde_json::Value) -> serde_json::Value {
+        if self.is_empty() {
+            return payload;
+        }
+        match payload {
+            serde_json::Value::Object(mut obj) => {
+                self.insert_into(&mut obj);
+                serde_json::Value::Object(obj)
+            }
+            other => {
+                let mut obj = serde_json::Map::new();
+                obj.insert("value".to_string(), other);
+                self.insert_into(&mut obj);
+                serde_json::Value::Object(obj)
+            }
         }
-        other => serde_json::json!({
-            "value": other,
-            "scope": scope,
-        }),
     }
 }
+
+/// Attach optional scope metadata to any payload; see [`EventScope::attach`].
+pub fn with_scope(
+    payload: serde_json::Value,
+    session_id: Option<String>,
+    thread_id: Option<String>,
+) -> serde_json::Value {
+    EventScope::new(session_id, thread_id).attach(payload)
+}
 pub const EVENT_USER_THEME_PREFERENCE: &str = "user.theme_preference";
+pub const EVENT_DESKTOP_TEMPLATE_SAVED: &str = "desktop.template_saved";
 pub const EVENT_FILE_WRITE: &str = "file.write";
 pub const EVENT_FILE_EDIT: &str = "file.edit";
 pub const EVENT_ACTOR_SPAWNED: &str = "actor.spawned";
@@ -1391,8 +2909,11 @@ pub const EVENT_TOPIC_WORKER_TASK_STARTED: &str = "worker.task.started";
 pub const EVENT_TOPIC_WORKER_TASK_PROGRESS: &str = "worker.task.progress";
 pub const EVENT_TOPIC_WORKER_TASK_COMPLETED: &str = "worker.task.completed";
 pub const EVENT_TOPIC_WORKER_TASK_FAILED: &str = "worker.task.failed";
+pub const EVENT_TOPIC_WORKER_TASK_FINDING: &str = "worker.task.finding";
+pub const EVENT_TOPIC_WORKER_TASK_LEARNING: &str = "worker.task.learning";
 pub const EVENT_TOPIC_WORKER_REPORT_RECEIVED: &str = "worker.report.received";
 pub const EVENT_TOPIC_WORKER_SIGNAL_REJECTED: &str = "worker.signal.rejected";
+pub const EVENT_TOPIC_TELEMETRY_THROTTLED: &str = "telemetry.throttled";
 pub const EVENT_TOPIC_WORKER_SIGNAL_ESCALATION_REQUESTED: &str =
     "worker.signal.escalation_requested";
 pub const EVENT_TOPIC_WORKER_FINDING_CREATED: &str = "worker.finding.created";
@@ -1414,6 +2935,11 @@ pub const EVENT_TOPIC_CONDUCTOR_WORKER_CALL: &str = "conductor.worker.call";
 pub const EVENT_TOPIC_CONDUCTOR_WORKER_RESULT: &str = "conductor.worker.result";
 pub const EVENT_TOPIC_CONDUCTOR_TASK_COMPLETED: &str = "conductor.task.completed";
 pub const EVENT_TOPIC_CONDUCTOR_TASK_FAILED: &str = "conductor.task.failed";
+pub const EVENT_TOPIC_CONDUCTOR_RUN_STARTED: &str = "conductor.run.started";
+pub const EVENT_TOPIC_CONDUCTOR_RUN_STATE_DELTA: &str = "conductor.run.state_delta";
+pub const EVENT_TOPIC_CONDUCTOR_CAPABILITY_COMPLETED: &str = "conductor.capability.completed";
+pub const EVENT_TOPIC_CONDUCTOR_CAPABILITY_FAILED: &str = "conductor.capability.failed";
+pub const EVENT_TOPIC_CONDUCTOR_CAPABILITY_BLOCKED: &str = "conductor.capability.blocked";
 
 pub const EVENT_TOPIC_WRITER_RUN_STARTED: &str = "writer.run.started";
 pub const EVENT_TOPIC_WRITER_RUN_PROGRESS: &str = "writer.run.progress";
@@ -1421,6 +2947,10 @@ pub const EVENT_TOPIC_WRITER_RUN_PATCH: &str = "writer.run.patch";
 pub const EVENT_TOPIC_WRITER_RUN_CHANGESET: &str = "writer.run.changeset";
 pub const EVENT_TOPIC_WRITER_RUN_STATUS: &str = "writer.run.status";
 pub const EVENT_TOPIC_WRITER_RUN_FAILED: &str = "writer.run.failed";
+pub const EVENT_TOPIC_WRITER_REVIEW_REQUESTED: &str = "writer.review.requested";
+/// The writer declared or released soft locks on document blocks; payload is
+/// a `WriterRunLockPayload`.
+pub const EVENT_TOPIC_WRITER_RUN_LOCK: &str = "writer.run.lock";
 
 pub const EVENT_TOPIC_TRACE_PROMPT_RECEIVED: &str = "trace.prompt.received";
 pub const EVENT_TOPIC_LLM_CALL_STARTED: &str = "llm.call.started";
@@ -1429,6 +2959,41 @@ pub const EVENT_TOPIC_LLM_CALL_FAILED: &str = "llm.call.failed";
 pub const EVENT_TOPIC_WORKER_TOOL_CALL: &str = "worker.tool.call";
 pub const EVENT_TOPIC_WORKER_TOOL_RESULT: &str = "worker.tool.result";
 
+/// A terminal agent step; dry runs record planned, unexecuted commands.
+pub const EVENT_TOPIC_TERMINAL_AGENT_STEP: &str = "terminal.agent.step";
+
+pub const EVENT_TOPIC_PROVIDER_GATEWAY_UNREACHABLE: &str = "provider.gateway.unreachable";
+pub const EVENT_TOPIC_PROVIDER_GATEWAY_RECOVERED: &str = "provider.gateway.recovered";
+
+pub const EVENT_TOPIC_WORKSPACE_QUOTA_WARNING: &str = "workspace.quota.warning";
+
+pub const EVENT_TOPIC_PROJECTION_REBUILD_STARTED: &str = "projection.rebuild.started";
+pub const EVENT_TOPIC_PROJECTION_REBUILD_PROGRESS: &str = "projection.rebuild.progress";
+pub const EVENT_TOPIC_PROJECTION_REBUILD_COMPLETED: &str = "projection.rebuild.completed";
+pub const EVENT_TOPIC_PROJECTION_REBUILD_FAILED: &str = "projection.rebuild.failed";
+
+/// Marks an earlier event as redacted; payload carries its `seq`.
+pub const EVENT_TOPIC_EVENT_REDACTED: &str = "event.redacted";
+
+/// A run bundle was imported; payload maps `imported_run_id` to `source_run_id`.
+pub const EVENT_TOPIC_RUN_BUNDLE_IMPORTED: &str = "run.bundle.imported";
+
+/// An outbound operation went ahead despite secret scan findings; payload
+/// carries the `operation`, `reason` and redacted finding previews.
+pub const EVENT_TOPIC_SECRET_SCAN_OVERRIDDEN: &str = "secret_scan.overridden";
+
+/// A durable user preference was learned; payload is a `LearnedPreference`.
+pub const EVENT_TOPIC_USER_PREFERENCE_LEARNED: &str = "user.preference.learned";
+/// The user deleted a learned preference; payload carries its `preference_id`.
+pub const EVENT_TOPIC_USER_PREFERENCE_DELETED: &str = "user.preference.deleted";
+/// The user switched preference learning on or off; payload carries
+/// `learning_enabled`.
+pub const EVENT_TOPIC_USER_PREFERENCE_LEARNING: &str = "user.preference.learning";
+
+/// User activity in a session; payload is a `SessionActivity`. Recorded at
+/// most once a minute per session.
+pub const EVENT_TOPIC_SESSION_ACTIVITY: &str = "session.activity";
+
 pub const INTERFACE_KIND_UACTOR_ACTOR: &str = "uactor_actor";
 pub const INTERFACE_KIND_APPACTOR_TOOLACTOR: &str = "appactor_toolactor";
 
@@ -1491,7 +3056,7 @@ impl ChunkHash {
 }
 
 /// W3C PROV-O style provenance envelope attached to every `.qwy` block.
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
 pub struct ProvenanceEnvelope {
     /// Activity that produced this block (loop_id or run_id).
@@ -1506,10 +3071,14 @@ pub struct ProvenanceEnvelope {
     pub conductor_run_id: Option<String>,
     /// Loop ID within the conductor run.
     pub loop_id: Option<String>,
+    /// Author-private block (e.g. a user note). Private blocks and their
+    /// descendants are never embedded.
+    #[serde(default)]
+    pub private: bool,
 }
 
 /// An inline annotation on a block (citation anchor, highlight, comment).
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
 pub struct BlockAnnotation {
     /// Annotation category: "citation_anchor" | "highlight" | "comment"
@@ -1523,7 +3092,7 @@ pub struct BlockAnnotation {
 }
 
 /// A single node in the `.qwy` block tree.
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
 pub struct BlockNode {
     /// Stable ULID — never reassigned.
@@ -1543,7 +3112,7 @@ pub struct BlockNode {
 }
 
 /// A single operation in the `.qwy` append-only patch log.
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(tag = "action", rename_all = "snake_case")]
 #[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
 pub enum QwyPatchOp {
@@ -1566,8 +3135,15 @@ pub enum QwyPatchOp {
     },
 }
 
+impl BlockNode {
+    /// Whether this block's own provenance allows embedding it.
+    pub fn is_embeddable(&self) -> bool {
+        !self.provenance.private && !self.content.trim().is_empty()
+    }
+}
+
 /// A timestamped entry in the `.qwy` patch log.
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
 pub struct QwyPatchEntry {
     pub patch_id: String,
@@ -1582,7 +3158,7 @@ pub struct QwyPatchEntry {
 }
 
 /// Version index entry within a `.qwy` document.
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
 pub struct QwyVersionIndexEntry {
     /// SHA-256 of the full document state at this version.
@@ -1594,7 +3170,7 @@ pub struct QwyVersionIndexEntry {
 }
 
 /// Header block for a `.qwy` document.
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
 pub struct QwyDocumentHeader {
     /// Stable document ULID — never changes after creation.
@@ -1610,9 +3186,10 @@ pub struct QwyDocumentHeader {
 
 /// A complete `.qwy` document in memory.
 ///
-/// Canonical format is CBOR; this struct is the typed Rust projection.
-/// JSON is a derived human-readable encoding. Markdown is a render artifact.
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+/// Canonical format is CBOR ([`QwyDocument::to_cbor`]); this struct is the
+/// typed Rust projection. JSON is a derived human-readable encoding
+/// ([`QwyDocument::to_json_pretty`]). Markdown is a render artifact.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
 pub struct QwyDocument {
     pub header: QwyDocumentHeader,
@@ -1630,6 +3207,276 @@ pub struct QwyDocument {
     pub version_index: Vec<QwyVersionIndexEntry>,
 }
 
+impl QwyDocument {
+    /// Blocks that may enter an embedding collection, in document order.
+    ///
+    /// A private block hides its whole subtree, so children of a private note
+    /// stay out even when they are not flagged themselves.
+    pub fn embeddable_blocks(&self) -> Vec<&BlockNode> {
+        let mut out = Vec::new();
+        let mut stack: Vec<&BlockId> = self.root_block_ids.iter().rev().collect();
+        while let Some(block_id) = stack.pop() {
+            let Some(block) = self.blocks.get(&block_id.0) else {
+                continue;
+            };
+            if block.provenance.private {
+                continue;
+            }
+            if block.is_embeddable() {
+                out.push(block);
+            }
+            stack.extend(block.children.iter().rev());
+        }
+        out
+    }
+
+    /// Apply a patch entry to the block tree and append it to `patch_log`.
+    ///
+    /// Each op's `path` lists block ids from a root-level block down to the
+    /// block it addresses. `Insert` and `Reorder` address the parent (an
+    /// empty path means the root level); `Remove` and `Replace` address the
+    /// block itself. Inserted blocks are appended after their siblings as
+    /// leaves; `Replace` keeps the block's place and children. The entry's
+    /// ops apply as one transaction: on error the document is unchanged.
+    pub fn apply_patch(&mut self, entry: &QwyPatchEntry) -> Result<(), QwyPatchError> {
+        let mut blocks = self.blocks.clone();
+        let mut root_block_ids = self.root_block_ids.clone();
+        for op in &entry.ops {
+            apply_qwy_op(&mut blocks, &mut root_block_ids, op)?;
+        }
+        self.blocks = blocks;
+        self.root_block_ids = root_block_ids;
+        self.patch_log.push(entry.clone());
+        Ok(())
+    }
+}
+
+/// Why a `QwyPatchEntry` could not be applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QwyPatchError {
+    /// An op references a block the document does not have.
+    UnknownBlock(BlockId),
+    /// `Insert` of a block id the document already has.
+    DuplicateBlock(BlockId),
+    /// `Insert` of a block that lists children; blocks are inserted as leaves.
+    NonLeafInsert(BlockId),
+    /// `Reorder.new_order` is not a permutation of the parent's children.
+    BadReorder { parent: Option<BlockId> },
+    /// The path does not lead from a root-level block down to its target,
+    /// or disagrees with the block the op carries.
+    RootMismatch { path: Vec<BlockId> },
+}
+
+impl std::fmt::Display for QwyPatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownBlock(id) => write!(f, "unknown block {}", id.0),
+            Self::DuplicateBlock(id) => write!(f, "block {} already exists", id.0),
+            Self::NonLeafInsert(id) => write!(f, "inserted block {} lists children", id.0),
+            Self::BadReorder { parent: Some(id) } => {
+                write!(
+                    f,
+                    "new order is not a permutation of the children of {}",
+                    id.0
+                )
+            }
+            Self::BadReorder { parent: None } => {
+                f.write_str("new order is not a permutation of the root blocks")
+            }
+            Self::RootMismatch { path } => {
+                let path: Vec<&str> = path.iter().map(|id| id.0.as_str()).collect();
+                write!(
+                    f,
+                    "path [{}] does not lead from a root block",
+                    path.join(", ")
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for QwyPatchError {}
+
+/// Newest `.qwy` schema version this crate reads and writes.
+pub const QWY_SCHEMA_VERSION: u32 = 1;
+
+/// Why a `.qwy` document could not be encoded or decoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QwyCodecError {
+    Encode(String),
+    Decode(String),
+    /// The document was written by a newer schema than this crate knows.
+    UnsupportedSchemaVersion {
+        found: u32,
+        supported: u32,
+    },
+}
+
+impl std::fmt::Display for QwyCodecError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Encode(e) => write!(f, "failed to encode qwy document: {e}"),
+            Self::Decode(e) => write!(f, "failed to decode qwy document: {e}"),
+            Self::UnsupportedSchemaVersion { found, supported } => write!(
+                f,
+                "qwy schema version {found} is newer than supported version {supported}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for QwyCodecError {}
+
+impl QwyDocument {
+    /// Encode to CBOR, the canonical `.qwy` format.
+    pub fn to_cbor(&self) -> Result<Vec<u8>, QwyCodecError> {
+        let mut out = Vec::new();
+        ciborium::into_writer(self, &mut out).map_err(|e| QwyCodecError::Encode(e.to_string()))?;
+        Ok(out)
+    }
+
+    /// Decode from CBOR. The schema version is checked before the rest of
+    /// the document, so a newer document fails as unsupported rather than
+    /// on whatever field it added.
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self, QwyCodecError> {
+        #[derive(Deserialize)]
+        struct VersionProbe {
+            header: HeaderProbe,
+        }
+        #[derive(Deserialize)]
+        struct HeaderProbe {
+            schema_version: u32,
+        }
+
+        let probe: VersionProbe =
+            ciborium::from_reader(bytes).map_err(|e| QwyCodecError::Decode(e.to_string()))?;
+        if probe.header.schema_version > QWY_SCHEMA_VERSION {
+            return Err(QwyCodecError::UnsupportedSchemaVersion {
+                found: probe.header.schema_version,
+                supported: QWY_SCHEMA_VERSION,
+            });
+        }
+        ciborium::from_reader(bytes).map_err(|e| QwyCodecError::Decode(e.to_string()))
+    }
+
+    /// Pretty-printed JSON, the derived human-readable encoding.
+    pub fn to_json_pretty(&self) -> Result<String, QwyCodecError> {
+        serde_json::to_string_pretty(self).map_err(|e| QwyCodecError::Encode(e.to_string()))
+    }
+}
+
+/// Check that `path` runs from a root-level block through parent/child
+/// links, and return its last block.
+fn resolve_qwy_path<'a>(
+    blocks: &std::collections::HashMap<String, BlockNode>,
+    root_block_ids: &[BlockId],
+    path: &'a [BlockId],
+) -> Result<Option<&'a BlockId>, QwyPatchError> {
+    let mismatch = || QwyPatchError::RootMismatch {
+        path: path.to_vec(),
+    };
+    let mut siblings = root_block_ids;
+    for id in path {
+        let block = blocks
+            .get(&id.0)
+            .ok_or_else(|| QwyPatchError::UnknownBlock(id.clone()))?;
+        if !siblings.contains(id) {
+            return Err(mismatch());
+        }
+        siblings = &block.children;
+    }
+    Ok(path.last())
+}
+
+fn apply_qwy_op(
+    blocks: &mut std::collections::HashMap<String, BlockNode>,
+    root_block_ids: &mut Vec<BlockId>,
+    op: &QwyPatchOp,
+) -> Result<(), QwyPatchError> {
+    let mismatch = |path: &[BlockId]| QwyPatchError::RootMismatch {
+        path: path.to_vec(),
+    };
+    match op {
+        QwyPatchOp::Insert { path, value } => {
+            let parent = resolve_qwy_path(blocks, root_block_ids, path)?.cloned();
+            if blocks.contains_key(&value.block_id.0) {
+                return Err(QwyPatchError::DuplicateBlock(value.block_id.clone()));
+            }
+            if !value.children.is_empty() {
+                return Err(QwyPatchError::NonLeafInsert(value.block_id.clone()));
+            }
+            if value.parent_id != parent {
+                return Err(mismatch(path));
+            }
+            match &parent {
+                Some(parent) => blocks
+                    .get_mut(&parent.0)
+                    .expect("resolved parent exists")
+                    .children
+                    .push(value.block_id.clone()),
+                None => root_block_ids.push(value.block_id.clone()),
+            }
+            blocks.insert(value.block_id.0.clone(), value.clone());
+        }
+        QwyPatchOp::Remove { path } => {
+            let target =
+                resolve_qwy_path(blocks, root_block_ids, path)?.ok_or_else(|| mismatch(path))?;
+            match path.len().checked_sub(2).map(|index| &path[index]) {
+                Some(parent) => blocks
+                    .get_mut(&parent.0)
+                    .expect("resolved parent exists")
+                    .children
+                    .retain(|child| child != target),
+                None => root_block_ids.retain(|root| root != target),
+            }
+            let mut stack = vec![target.clone()];
+            while let Some(id) = stack.pop() {
+                if let Some(removed) = blocks.remove(&id.0) {
+                    stack.extend(removed.children);
+                }
+            }
+        }
+        QwyPatchOp::Replace { path, value } => {
+            let target =
+                resolve_qwy_path(blocks, root_block_ids, path)?.ok_or_else(|| mismatch(path))?;
+            if &value.block_id != target {
+                return Err(mismatch(path));
+            }
+            let block = blocks.get_mut(&target.0).expect("resolved target exists");
+            let parent_id = block.parent_id.take();
+            let children = std::mem::take(&mut block.children);
+            *block = BlockNode {
+                parent_id,
+                children,
+                ..value.clone()
+            };
+        }
+        QwyPatchOp::Reorder { path, new_order } => {
+            let parent = resolve_qwy_path(blocks, root_block_ids, path)?;
+            let children = match parent {
+                Some(parent) => {
+                    &mut blocks
+                        .get_mut(&parent.0)
+                        .expect("resolved parent exists")
+                        .children
+                }
+                None => root_block_ids,
+            };
+            let mut current: Vec<&BlockId> = children.iter().collect();
+            let mut proposed: Vec<&BlockId> = new_order.iter().collect();
+            current.sort_by(|a, b| a.0.cmp(&b.0));
+            proposed.sort_by(|a, b| a.0.cmp(&b.0));
+            if current != proposed {
+                return Err(QwyPatchError::BadReorder {
+                    parent: parent.cloned(),
+                });
+            }
+            *children = new_order.clone();
+        }
+    }
+    Ok(())
+}
+
 // ============================================================================
 // Phase 2.2 — Citation Types
 // ============================================================================
@@ -1672,7 +3519,7 @@ pub enum CitationStatus {
 /// 1. Researcher proposes → `status: Proposed`, `confirmed_by: None`
 /// 2. Writer confirms → `status: Confirmed`, `confirmed_by: "writer"`, `confirmed_at: <ts>`
 /// 3. Writer rejects → `status: Rejected`
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
 pub struct CitationRecord {
     pub citation_id: String,
@@ -1704,6 +3551,34 @@ pub struct CitationRecord {
 // Phase 2.3 — Embedding Collection Record Types
 // ============================================================================
 
+/// Surface that received a user input.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../dioxus-desktop/src/types/generated.ts")]
+pub enum UserInputSurface {
+    Conductor,
+    Writer,
+    PromptBar,
+}
+
+impl UserInputSurface {
+    pub const ALL: [UserInputSurface; 3] = [Self::Conductor, Self::Writer, Self::PromptBar];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Conductor => "conductor",
+            Self::Writer => "writer",
+            Self::PromptBar => "prompt_bar",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|surface| surface.as_str() == value)
+    }
+}
+
 /// Record type for the `user_inputs` embedding collection.
 ///
 /// One record per `EventType::UserInput` on any surface.
@@ -1714,8 +3589,8 @@ pub struct UserInputRecord {
     pub input_id: String,
     /// Plain text of the user directive.
     pub content: String,
-    /// Surface that received the input: "conductor" | "writer" | "prompt_bar"
-    pub surface: String,
+    /// Surface that received the input; unknown surfaces fail to deserialize.
+    pub surface: UserInputSurface,
     pub desktop_id: String,
     pub session_id: String,
     pub thread_id: String,
@@ -1799,12 +3674,44 @@ pub struct ExternalContentRecord {
     pub content_text: String,
     /// "full" | "sections" | "paragraphs"
     pub chunk_strategy: String,
-    /// Local filesystem path to the raw snapshot, if stored.
+    /// Content-addressed path of the raw snapshot, relative to the sandbox
+    /// root (`artifacts/snapshots/{sha256}.{ext}`), if stored.
     pub snapshot_ref: Option<String>,
     pub domain: Option<String>,
     /// CSL-JSON bibliographic metadata if extractable.
     #[ts(type = "unknown")]
     pub csl_metadata: Option<serde_json::Value>,
+    /// Fetched for a private context; never published or embedded.
+    #[serde(default)]
+    pub private: bool,
+}
+
+impl ExternalContentRecord {
+    /// The publishable projection of this record, or `None` if it is private.
+    ///
+    /// `fetched_by`, `run_id` and `snapshot_ref` are dropped here and have no
+    /// counterpart on the global record.
+    pub fn to_global(
+        &self,
+        first_cited_at: DateTime<Utc>,
+        citation_count: u32,
+    ) -> Option<GlobalExternalContentRecord> {
+        if self.private {
+            return None;
+        }
+        Some(GlobalExternalContentRecord {
+            content_id: self.content_hash.clone(),
+            url: self.url.clone(),
+            title: self.title.clone(),
+            content_text: self.content_text.clone(),
+            chunk_strategy: self.chunk_strategy.clone(),
+            csl_metadata: self.csl_metadata.clone(),
+            first_cited_at,
+            citation_count,
+            domain: self.domain.clone(),
+            record_kind: "external_content".to_string(),
+        })
+    }
 }
 
 /// Public record in the global hypervisor external content store.
@@ -1831,6 +3738,16 @@ pub struct GlobalExternalContentRecord {
     pub record_kind: String,
 }
 
+impl GlobalExternalContentRecord {
+    /// Text handed to the embedder: title and cleaned content only.
+    pub fn embedding_text(&self) -> String {
+        match self.title.as_deref().map(str::trim) {
+            Some(title) if !title.is_empty() => format!("{title}\n\n{}", self.content_text),
+            _ => self.content_text.clone(),
+        }
+    }
+}
+
 // ============================================================================
 // Phase 4.5 — ContextSnapshot (Memory service stub types)
 // ============================================================================
@@ -1902,6 +3819,246 @@ pub const EVENT_TOPIC_HARNESS_RESULT: &str = "harness.result";
 pub const EVENT_TOPIC_HARNESS_CHECKPOINT: &str = "harness.checkpoint";
 pub const EVENT_TOPIC_TOOL_RESULT: &str = "tool.result";
 
+// ============================================================================
+// Event topic registry
+// ============================================================================
+
+macro_rules! event_topics {
+    ($($variant:ident => $constant:ident,)*) => {
+        /// Typed view of the `EVENT_TOPIC_*` constants.
+        ///
+        /// Serializes as the plain topic string so the wire format is unchanged.
+        /// Topics not in the registry parse as [`EventTopic::Custom`].
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum EventTopic {
+            $($variant,)*
+            Custom(String),
+        }
+
+        impl EventTopic {
+            /// Every registered topic, in declaration order.
+            pub const ALL: &'static [EventTopic] = &[$(EventTopic::$variant,)*];
+
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(EventTopic::$variant => $constant,)*
+                    EventTopic::Custom(topic) => topic,
+                }
+            }
+
+            pub fn is_custom(&self) -> bool {
+                matches!(self, EventTopic::Custom(_))
+            }
+        }
+
+        impl From<&str> for EventTopic {
+            fn from(topic: &str) -> Self {
+                match topic {
+                    $($constant => EventTopic::$variant,)*
+                    other => EventTopic::Custom(other.to_string()),
+                }
+            }
+        }
+    };
+}
+
+event_topics! {
+    WorkerTaskStarted => EVENT_TOPIC_WORKER_TASK_STARTED,
+    WorkerTaskProgress => EVENT_TOPIC_WORKER_TASK_PROGRESS,
+    WorkerTaskCompleted => EVENT_TOPIC_WORKER_TASK_COMPLETED,
+    WorkerTaskFailed => EVENT_TOPIC_WORKER_TASK_FAILED,
+    WorkerTaskFinding => EVENT_TOPIC_WORKER_TASK_FINDING,
+    WorkerTaskLearning => EVENT_TOPIC_WORKER_TASK_LEARNING,
+    WorkerReportReceived => EVENT_TOPIC_WORKER_REPORT_RECEIVED,
+    WorkerSignalRejected => EVENT_TOPIC_WORKER_SIGNAL_REJECTED,
+    TelemetryThrottled => EVENT_TOPIC_TELEMETRY_THROTTLED,
+    WorkerSignalEscalationRequested => EVENT_TOPIC_WORKER_SIGNAL_ESCALATION_REQUESTED,
+    WorkerFindingCreated => EVENT_TOPIC_WORKER_FINDING_CREATED,
+    WorkerLearningCreated => EVENT_TOPIC_WORKER_LEARNING_CREATED,
+    ResearchFindingCreated => EVENT_TOPIC_RESEARCH_FINDING_CREATED,
+    ResearchLearningCreated => EVENT_TOPIC_RESEARCH_LEARNING_CREATED,
+    ResearchTaskStarted => EVENT_TOPIC_RESEARCH_TASK_STARTED,
+    ResearchTaskProgress => EVENT_TOPIC_RESEARCH_TASK_PROGRESS,
+    ResearchTaskCompleted => EVENT_TOPIC_RESEARCH_TASK_COMPLETED,
+    ResearchTaskFailed => EVENT_TOPIC_RESEARCH_TASK_FAILED,
+    ResearchProviderCall => EVENT_TOPIC_RESEARCH_PROVIDER_CALL,
+    ResearchProviderResult => EVENT_TOPIC_RESEARCH_PROVIDER_RESULT,
+    ResearchProviderError => EVENT_TOPIC_RESEARCH_PROVIDER_ERROR,
+    ArtifactCreated => EVENT_TOPIC_ARTIFACT_CREATED,
+    ConductorTaskStarted => EVENT_TOPIC_CONDUCTOR_TASK_STARTED,
+    ConductorTaskProgress => EVENT_TOPIC_CONDUCTOR_TASK_PROGRESS,
+    ConductorWorkerCall => EVENT_TOPIC_CONDUCTOR_WORKER_CALL,
+    ConductorWorkerResult => EVENT_TOPIC_CONDUCTOR_WORKER_RESULT,
+    ConductorTaskCompleted => EVENT_TOPIC_CONDUCTOR_TASK_COMPLETED,
+    ConductorTaskFailed => EVENT_TOPIC_CONDUCTOR_TASK_FAILED,
+    ConductorRunStarted => EVENT_TOPIC_CONDUCTOR_RUN_STARTED,
+    ConductorRunStateDelta => EVENT_TOPIC_CONDUCTOR_RUN_STATE_DELTA,
+    ConductorCapabilityCompleted => EVENT_TOPIC_CONDUCTOR_CAPABILITY_COMPLETED,
+    ConductorCapabilityFailed => EVENT_TOPIC_CONDUCTOR_CAPABILITY_FAILED,
+    ConductorCapabilityBlocked => EVENT_TOPIC_CONDUCTOR_CAPABILITY_BLOCKED,
+    WriterRunStarted => EVENT_TOPIC_WRITER_RUN_STARTED,
+    WriterRunProgress => EVENT_TOPIC_WRITER_RUN_PROGRESS,
+    WriterRunPatch => EVENT_TOPIC_WRITER_RUN_PATCH,
+    WriterRunChangeset => EVENT_TOPIC_WRITER_RUN_CHANGESET,
+    WriterRunStatus => EVENT_TOPIC_WRITER_RUN_STATUS,
+    WriterRunFailed => EVENT_TOPIC_WRITER_RUN_FAILED,
+    WriterReviewRequested => EVENT_TOPIC_WRITER_REVIEW_REQUESTED,
+    WriterRunLock => EVENT_TOPIC_WRITER_RUN_LOCK,
+    TracePromptReceived => EVENT_TOPIC_TRACE_PROMPT_RECEIVED,
+    LlmCallStarted => EVENT_TOPIC_LLM_CALL_STARTED,
+    LlmCallCompleted => EVENT_TOPIC_LLM_CALL_COMPLETED,
+    LlmCallFailed => EVENT_TOPIC_LLM_CALL_FAILED,
+    WorkerToolCall => EVENT_TOPIC_WORKER_TOOL_CALL,
+    WorkerToolResult => EVENT_TOPIC_WORKER_TOOL_RESULT,
+    TerminalAgentStep => EVENT_TOPIC_TERMINAL_AGENT_STEP,
+    ProviderGatewayUnreachable => EVENT_TOPIC_PROVIDER_GATEWAY_UNREACHABLE,
+    ProviderGatewayRecovered => EVENT_TOPIC_PROVIDER_GATEWAY_RECOVERED,
+    WorkspaceQuotaWarning => EVENT_TOPIC_WORKSPACE_QUOTA_WARNING,
+    ProjectionRebuildStarted => EVENT_TOPIC_PROJECTION_REBUILD_STARTED,
+    ProjectionRebuildProgress => EVENT_TOPIC_PROJECTION_REBUILD_PROGRESS,
+    ProjectionRebuildCompleted => EVENT_TOPIC_PROJECTION_REBUILD_COMPLETED,
+    ProjectionRebuildFailed => EVENT_TOPIC_PROJECTION_REBUILD_FAILED,
+    EventRedacted => EVENT_TOPIC_EVENT_REDACTED,
+    RunBundleImported => EVENT_TOPIC_RUN_BUNDLE_IMPORTED,
+    SecretScanOverridden => EVENT_TOPIC_SECRET_SCAN_OVERRIDDEN,
+    UserPreferenceLearned => EVENT_TOPIC_USER_PREFERENCE_LEARNED,
+    UserPreferenceDeleted => EVENT_TOPIC_USER_PREFERENCE_DELETED,
+    UserPreferenceLearning => EVENT_TOPIC_USER_PREFERENCE_LEARNING,
+    SessionActivity => EVENT_TOPIC_SESSION_ACTIVITY,
+    CitationProposed => EVENT_TOPIC_CITATION_PROPOSED,
+    CitationConfirmed => EVENT_TOPIC_CITATION_CONFIRMED,
+    CitationRejected => EVENT_TOPIC_CITATION_REJECTED,
+    UserInput => EVENT_TOPIC_USER_INPUT,
+    GlobalExternalContentUpsert => EVENT_TOPIC_GLOBAL_EXTERNAL_CONTENT_UPSERT,
+    QwyCitationRegistry => EVENT_TOPIC_QWY_CITATION_REGISTRY,
+    HarnessExecute => EVENT_TOPIC_HARNESS_EXECUTE,
+    HarnessResult => EVENT_TOPIC_HARNESS_RESULT,
+    HarnessCheckpoint => EVENT_TOPIC_HARNESS_CHECKPOINT,
+    ToolResult => EVENT_TOPIC_TOOL_RESULT,
+}
+
+/// A scope field a topic can require on every event it carries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ScopeField {
+    /// Top-level `run_id`.
+    RunId,
+    /// `scope.session_id`, the field the EventStore indexes.
+    SessionId,
+    /// `scope.thread_id`, the field the EventStore indexes.
+    ThreadId,
+}
+
+impl ScopeField {
+    pub const ALL: &'static [ScopeField] = &[
+        ScopeField::RunId,
+        ScopeField::SessionId,
+        ScopeField::ThreadId,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ScopeField::RunId => "run_id",
+            ScopeField::SessionId => "session_id",
+            ScopeField::ThreadId => "thread_id",
+        }
+    }
+
+    /// Whether `payload` carries this field as a non-empty string.
+    pub fn is_present(self, payload: &serde_json::Value) -> bool {
+        let value = match self {
+            ScopeField::RunId => payload.get("run_id"),
+            ScopeField::SessionId | ScopeField::ThreadId => payload
+                .get("scope")
+                .and_then(|scope| scope.get(self.as_str())),
+        };
+        value
+            .and_then(|value| value.as_str())
+            .is_some_and(|value| !value.is_empty())
+    }
+}
+
+impl EventTopic {
+    /// Scope fields every event of this topic must carry.
+    ///
+    /// The EventStore checks this table on append; keep it in step with the
+    /// registry above when adding a topic that belongs to a run or thread.
+    pub fn required_scope(&self) -> &'static [ScopeField] {
+        use ScopeField::{RunId, SessionId, ThreadId};
+        match self {
+            EventTopic::ConductorTaskStarted
+            | EventTopic::ConductorTaskProgress
+            | EventTopic::ConductorWorkerCall
+            | EventTopic::ConductorWorkerResult
+            | EventTopic::ConductorTaskCompleted
+            | EventTopic::ConductorTaskFailed
+            | EventTopic::ConductorRunStarted
+            | EventTopic::ConductorRunStateDelta
+            | EventTopic::ConductorCapabilityCompleted
+            | EventTopic::ConductorCapabilityFailed
+            | EventTopic::ConductorCapabilityBlocked
+            | EventTopic::TracePromptReceived
+            | EventTopic::HarnessCheckpoint => &[RunId],
+            EventTopic::WriterRunStarted
+            | EventTopic::WriterRunProgress
+            | EventTopic::WriterRunPatch
+            | EventTopic::WriterRunChangeset
+            | EventTopic::WriterRunStatus
+            | EventTopic::WriterRunFailed
+            | EventTopic::WriterReviewRequested
+            | EventTopic::WriterRunLock => &[RunId, SessionId, ThreadId],
+            _ => &[],
+        }
+    }
+
+    /// Required scope fields `payload` does not carry.
+    pub fn missing_scope(&self, payload: &serde_json::Value) -> Vec<ScopeField> {
+        self.required_scope()
+            .iter()
+            .copied()
+            .filter(|field| !field.is_present(payload))
+            .collect()
+    }
+}
+
+impl std::str::FromStr for EventTopic {
+    type Err = std::convert::Infallible;
+
+    fn from_str(topic: &str) -> Result<Self, Self::Err> {
+        Ok(EventTopic::from(topic))
+    }
+}
+
+impl std::fmt::Display for EventTopic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl PartialEq<str> for EventTopic {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for EventTopic {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl Serialize for EventTopic {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for EventTopic {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let topic = String::deserialize(deserializer)?;
+        Ok(EventTopic::from(topic.as_str()))
+    }
+}
+
 // ============================================================================
 // Phase 4.5 — Harness durability types
 // ============================================================================
@@ -2116,11 +4273,15 @@ mod tests {
 
     #[test]
     fn test_event_serialization() {
+        let now = Utc::now();
         let event = Event {
             seq: 1,
             event_id: "evt_123".to_string(),
-            timestamp: Utc::now(),
+            timestamp: now,
+            stored_at: now,
+            produced_at: None,
             actor_id: ActorId::new(),
+            actor_seq: 1,
             event_type: EVENT_MODEL_SELECTION.to_string(),
             payload: serde_json::json!({"text": "Hello"}),
             user_id: "user_1".to_string(),
@@ -2133,6 +4294,52 @@ mod tests {
         assert_eq!(event.event_type, deserialized.event_type);
     }
 
+    #[test]
+    fn test_event_without_stored_at_deserializes() {
+        let event: Event = serde_json::from_value(serde_json::json!({
+            "seq": 7,
+            "event_id": "evt_old",
+            "timestamp": "2026-01-02T03:04:05Z",
+            "actor_id": "actor-1",
+            "event_type": "test.event",
+            "payload": {},
+            "user_id": "user_1",
+        }))
+        .unwrap();
+
+        assert_eq!(event.stored_at, DateTime::<Utc>::default());
+        assert_eq!(event.actor_seq, 0);
+        assert!(event.produced_at.is_none());
+    }
+
+    #[test]
+    fn test_event_canonical_order() {
+        let at = |secs: i64| DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap();
+        let event = |seq: i64, event_id: &str, secs: i64| Event {
+            seq,
+            event_id: event_id.to_string(),
+            timestamp: at(secs),
+            stored_at: at(secs),
+            produced_at: None,
+            actor_id: ActorId("actor-1".to_string()),
+            actor_seq: seq,
+            event_type: "test.event".to_string(),
+            payload: serde_json::Value::Null,
+            user_id: "user_1".to_string(),
+        };
+
+        let mut events = [
+            event(4, "evt_d", 1),
+            event(3, "evt_c", 0),
+            event(1, "evt_b", 0),
+            event(1, "evt_a", 0),
+            event(2, "evt_e", 0),
+        ];
+        events.sort_by(Event::canonical_cmp);
+        let order: Vec<&str> = events.iter().map(|e| e.event_id.as_str()).collect();
+        assert_eq!(order, ["evt_a", "evt_b", "evt_e", "evt_c", "evt_d"]);
+    }
+
     #[test]
     fn test_ws_msg_protocol() {
         let msg = WsMsg::Subscribe {
@@ -2143,6 +4350,39 @@ mod tests {
         assert!(json.contains("Subscribe"));
     }
 
+    #[test]
+    fn test_user_input_surface_parsing() {
+        for surface in UserInputSurface::ALL {
+            let json = serde_json::to_string(&surface).unwrap();
+            assert_eq!(json, format!("\"{}\"", surface.as_str()));
+            assert_eq!(
+                serde_json::from_str::<UserInputSurface>(&json).unwrap(),
+                surface
+            );
+            assert_eq!(UserInputSurface::parse(surface.as_str()), Some(surface));
+        }
+        assert_eq!(
+            UserInputSurface::parse("prompt_bar"),
+            Some(UserInputSurface::PromptBar)
+        );
+        assert_eq!(UserInputSurface::parse("chat"), None);
+        assert!(serde_json::from_str::<UserInputSurface>("\"chat\"").is_err());
+
+        let record = serde_json::json!({
+            "input_id": "in_1",
+            "content": "hello",
+            "surface": "terminal",
+            "desktop_id": "desktop_1",
+            "session_id": "desktop_1",
+            "thread_id": "",
+            "run_id": null,
+            "document_path": null,
+            "base_version_id": null,
+            "created_at": "2026-03-01T00:00:00Z",
+        });
+        assert!(serde_json::from_value::<UserInputRecord>(record).is_err());
+    }
+
     #[test]
     fn test_viewer_kind_serialization() {
         let kind = ViewerKind::Text;
@@ -2150,6 +4390,707 @@ mod tests {
         assert_eq!(json, "\"text\"");
     }
 
+    #[test]
+    fn test_viewer_descriptor_resources_default_to_empty() {
+        let descriptor: ViewerDescriptor = serde_json::from_value(serde_json::json!({
+            "kind": "image_set",
+            "resource": { "uri": "sandbox://plot.png", "mime": "image/png" },
+            "capabilities": { "readonly": true }
+        }))
+        .unwrap();
+        assert_eq!(descriptor.kind, ViewerKind::ImageSet);
+        assert!(descriptor.resources.is_empty());
+    }
+
+    #[test]
+    fn test_model_context_trace_records_trimming_and_item_ids() {
+        let item = |id: &str, kind, est_tokens, trimmed: bool| ContextTraceItem {
+            kind,
+            role: None,
+            label: id.to_string(),
+            id: Some(id.to_string()),
+            est_tokens,
+            trimmed,
+            trim_reason: trimmed.then(|| "tool output cut".to_string()),
+        };
+        let trace = ModelContextTracePayload::new(
+            "trace-1",
+            "test-model",
+            vec![
+                item("system", ContextTraceItemKind::System, 100, false),
+                item("message:0", ContextTraceItemKind::Message, 40, false),
+                item("message:1", ContextTraceItemKind::Message, 60, true),
+            ],
+        );
+        assert_eq!(trace.total_est_tokens, 200);
+        assert!(trace.trimmed);
+        assert_eq!(
+            trace.included_item_ids(),
+            vec!["system", "message:0", "message:1"]
+        );
+
+        let json = serde_json::to_value(&trace).unwrap();
+        assert_eq!(json["trimmed"], true);
+        assert_eq!(json["items"][2]["id"], "message:1");
+        let parsed: ModelContextTracePayload = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, trace);
+
+        let untrimmed = ModelContextTracePayload::new(
+            "trace-2",
+            "test-model",
+            vec![item("system", ContextTraceItemKind::System, 10, false)],
+        );
+        assert!(!untrimmed.trimmed);
+
+        // Traces recorded before ids and the trimming flag still parse.
+        let legacy: ModelContextTracePayload = serde_json::from_value(serde_json::json!({
+            "trace_id": "trace-0",
+            "model_used": "test-model",
+            "items": [{ "kind": "system", "label": "System context", "est_tokens": 5 }],
+            "total_est_tokens": 5
+        }))
+        .unwrap();
+        assert!(!legacy.trimmed);
+        assert!(legacy.included_item_ids().is_empty());
+    }
+
+    fn qwy_block(id: &str, content: &str, private: bool, children: &[&str]) -> BlockNode {
+        BlockNode {
+            block_id: BlockId(id.to_string()),
+            block_type: BlockType::Paragraph,
+            parent_id: None,
+            children: children.iter().map(|c| BlockId(c.to_string())).collect(),
+            content: content.to_string(),
+            chunk_hash: None,
+            provenance: ProvenanceEnvelope {
+                was_generated_by: None,
+                was_attributed_to: Some("user".to_string()),
+                was_revision_of: None,
+                had_primary_source: None,
+                conductor_run_id: None,
+                loop_id: None,
+                private,
+            },
+            annotations: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_embeddable_blocks_skip_private_subtrees() {
+        let blocks = [
+            qwy_block("a", "public intro", false, &["b"]),
+            qwy_block("b", "public child", false, &[]),
+            qwy_block("c", "private note", true, &["d"]),
+            qwy_block("d", "child of private note", false, &[]),
+            qwy_block("e", "   ", false, &[]),
+            qwy_block("f", "public outro", false, &[]),
+        ];
+        let document = QwyDocument {
+            header: QwyDocumentHeader {
+                document_id: "doc-1".to_string(),
+                schema_version: 1,
+                created_at: Utc::now(),
+                created_by: "user".to_string(),
+                conductor_run_id: None,
+            },
+            root_block_ids: ["a", "c", "e", "f"]
+                .iter()
+                .map(|id| BlockId(id.to_string()))
+                .collect(),
+            blocks: blocks
+                .into_iter()
+                .map(|block| (block.block_id.0.clone(), block))
+                .collect(),
+            patch_log: Vec::new(),
+            citation_registry: std::collections::HashMap::new(),
+            version_index: Vec::new(),
+        };
+
+        let ids: Vec<&str> = document
+            .embeddable_blocks()
+            .iter()
+            .map(|block| block.block_id.0.as_str())
+            .collect();
+        assert_eq!(ids, vec!["a", "b", "f"]);
+    }
+
+    /// Roots `a` (children `b`, `c`) and `d`.
+    fn qwy_tree() -> QwyDocument {
+        let mut blocks = vec![
+            qwy_block("a", "section", false, &["b", "c"]),
+            qwy_block("b", "first", false, &[]),
+            qwy_block("c", "second", false, &[]),
+            qwy_block("d", "outro", false, &[]),
+        ];
+        blocks[1].parent_id = Some(BlockId("a".to_string()));
+        blocks[2].parent_id = Some(BlockId("a".to_string()));
+        QwyDocument {
+            header: QwyDocumentHeader {
+                document_id: "doc-1".to_string(),
+                schema_version: 1,
+                created_at: Utc::now(),
+                created_by: "user".to_string(),
+                conductor_run_id: None,
+            },
+            root_block_ids: vec![BlockId("a".to_string()), BlockId("d".to_string())],
+            blocks: blocks
+                .into_iter()
+                .map(|block| (block.block_id.0.clone(), block))
+                .collect(),
+            patch_log: Vec::new(),
+            citation_registry: std::collections::HashMap::new(),
+            version_index: Vec::new(),
+        }
+    }
+
+    fn qwy_entry(ops: Vec<QwyPatchOp>) -> QwyPatchEntry {
+        QwyPatchEntry {
+            patch_id: "patch-1".to_string(),
+            tx_id: "tx-1".to_string(),
+            timestamp: Utc::now(),
+            author: "writer".to_string(),
+            run_id: None,
+            loop_id: None,
+            ops,
+        }
+    }
+
+    #[test]
+    fn test_qwy_cbor_round_trip_and_schema_check() {
+        let mut doc = qwy_tree();
+        doc.blocks
+            .get_mut("b")
+            .unwrap()
+            .annotations
+            .push(BlockAnnotation {
+                annotation_type: "citation_anchor".to_string(),
+                start: 0,
+                end: 5,
+                attrs: serde_json::json!({ "citation_id": "c1", "weight": 0.25, "n": 3 }),
+            });
+        doc.apply_patch(&qwy_entry(vec![QwyPatchOp::Insert {
+            path: vec![BlockId("a".to_string())],
+            value: {
+                let mut block = qwy_block("e", "appended", false, &[]);
+                block.parent_id = Some(BlockId("a".to_string()));
+                block
+            },
+        }]))
+        .unwrap();
+        doc.citation_registry.insert(
+            "c1".to_string(),
+            CitationRecord {
+                citation_id: "c1".to_string(),
+                cited_id: "https://example.com".to_string(),
+                cited_kind: "external_url".to_string(),
+                citing_run_id: "run-1".to_string(),
+                citing_loop_id: "loop-1".to_string(),
+                citing_actor: "researcher".to_string(),
+                cite_kind: CitationKind::InlineReference,
+                confidence: 0.9,
+                excerpt: Some("quoted".to_string()),
+                rationale: "supports the claim".to_string(),
+                status: CitationStatus::Confirmed,
+                proposed_by: "researcher".to_string(),
+                confirmed_by: Some("user".to_string()),
+                confirmed_at: Some(Utc::now()),
+                created_at: Utc::now(),
+            },
+        );
+        doc.version_index.push(QwyVersionIndexEntry {
+            snapshot_hash: "hash-1".to_string(),
+            tx_id: "tx-1".to_string(),
+            timestamp: Utc::now(),
+            author: "writer".to_string(),
+        });
+
+        let bytes = doc.to_cbor().unwrap();
+        assert_eq!(QwyDocument::from_cbor(&bytes).unwrap(), doc);
+
+        let json = doc.to_json_pretty().unwrap();
+        assert!(json.contains("\n  \"header\": {"));
+        assert_eq!(serde_json::from_str::<QwyDocument>(&json).unwrap(), doc);
+
+        let mut newer = doc.clone();
+        newer.header.schema_version = QWY_SCHEMA_VERSION + 1;
+        assert_eq!(
+            QwyDocument::from_cbor(&newer.to_cbor().unwrap()),
+            Err(QwyCodecError::UnsupportedSchemaVersion {
+                found: QWY_SCHEMA_VERSION + 1,
+                supported: QWY_SCHEMA_VERSION,
+            })
+        );
+        assert!(matches!(
+            QwyDocument::from_cbor(&bytes[..bytes.len() / 2]),
+            Err(QwyCodecError::Decode(_))
+        ));
+    }
+
+    fn ids(raw: &[&str]) -> Vec<BlockId> {
+        raw.iter().map(|id| BlockId(id.to_string())).collect()
+    }
+
+    fn child_ids(document: &QwyDocument, id: &str) -> Vec<BlockId> {
+        document.blocks[id].children.clone()
+    }
+
+    #[test]
+    fn test_qwy_apply_patch_updates_tree_and_log() {
+        let mut document = qwy_tree();
+        let mut inserted = qwy_block("e", "third", false, &[]);
+        inserted.parent_id = Some(BlockId("a".to_string()));
+        let replacement = qwy_block("b", "first, revised", false, &[]);
+
+        document
+            .apply_patch(&qwy_entry(vec![
+                QwyPatchOp::Insert {
+                    path: ids(&["a"]),
+                    value: inserted,
+                },
+                QwyPatchOp::Replace {
+                    path: ids(&["a", "b"]),
+                    value: replacement,
+                },
+                QwyPatchOp::Reorder {
+                    path: ids(&["a"]),
+                    new_order: ids(&["e", "c", "b"]),
+                },
+                QwyPatchOp::Remove { path: ids(&["d"]) },
+                QwyPatchOp::Insert {
+                    path: Vec::new(),
+                    value: qwy_block("f", "new outro", false, &[]),
+                },
+            ]))
+            .expect("patch applies");
+
+        assert_eq!(document.root_block_ids, ids(&["a", "f"]));
+        assert_eq!(child_ids(&document, "a"), ids(&["e", "c", "b"]));
+        assert_eq!(document.blocks["b"].content, "first, revised");
+        assert_eq!(
+            document.blocks["b"].parent_id,
+            Some(BlockId("a".to_string()))
+        );
+        assert!(!document.blocks.contains_key("d"));
+        assert_eq!(document.patch_log.len(), 1);
+
+        document
+            .apply_patch(&qwy_entry(vec![QwyPatchOp::Remove { path: ids(&["a"]) }]))
+            .expect("removing a subtree applies");
+        assert_eq!(document.root_block_ids, ids(&["f"]));
+        assert_eq!(document.blocks.len(), 1);
+        assert_eq!(document.patch_log.len(), 2);
+    }
+
+    #[test]
+    fn test_qwy_apply_patch_rejects_unknown_block_atomically() {
+        let mut document = qwy_tree();
+        let mut inserted = qwy_block("e", "third", false, &[]);
+        inserted.parent_id = Some(BlockId("a".to_string()));
+        let err = document
+            .apply_patch(&qwy_entry(vec![
+                QwyPatchOp::Insert {
+                    path: ids(&["a"]),
+                    value: inserted,
+                },
+                QwyPatchOp::Remove {
+                    path: ids(&["a", "zz"]),
+                },
+            ]))
+            .unwrap_err();
+        assert_eq!(err, QwyPatchError::UnknownBlock(BlockId("zz".to_string())));
+        assert_eq!(child_ids(&document, "a"), ids(&["b", "c"]));
+        assert!(!document.blocks.contains_key("e"));
+        assert!(document.patch_log.is_empty());
+    }
+
+    #[test]
+    fn test_qwy_apply_patch_rejects_duplicate_and_non_leaf_inserts() {
+        let mut document = qwy_tree();
+        let err = document
+            .apply_patch(&qwy_entry(vec![QwyPatchOp::Insert {
+                path: Vec::new(),
+                value: qwy_block("d", "again", false, &[]),
+            }]))
+            .unwrap_err();
+        assert_eq!(err, QwyPatchError::DuplicateBlock(BlockId("d".to_string())));
+
+        let err = document
+            .apply_patch(&qwy_entry(vec![QwyPatchOp::Insert {
+                path: Vec::new(),
+                value: qwy_block("e", "parent", false, &["b"]),
+            }]))
+            .unwrap_err();
+        assert_eq!(err, QwyPatchError::NonLeafInsert(BlockId("e".to_string())));
+        assert!(document.patch_log.is_empty());
+    }
+
+    #[test]
+    fn test_qwy_apply_patch_rejects_reorder_that_is_not_a_permutation() {
+        let mut document = qwy_tree();
+        for new_order in [ids(&["b"]), ids(&["b", "b"]), ids(&["c", "b", "d"])] {
+            let err = document
+                .apply_patch(&qwy_entry(vec![QwyPatchOp::Reorder {
+                    path: ids(&["a"]),
+                    new_order,
+                }]))
+                .unwrap_err();
+            assert_eq!(
+                err,
+                QwyPatchError::BadReorder {
+                    parent: Some(BlockId("a".to_string()))
+                }
+            );
+        }
+        let err = document
+            .apply_patch(&qwy_entry(vec![QwyPatchOp::Reorder {
+                path: Vec::new(),
+                new_order: ids(&["d", "b"]),
+            }]))
+            .unwrap_err();
+        assert_eq!(err, QwyPatchError::BadReorder { parent: None });
+        assert_eq!(child_ids(&document, "a"), ids(&["b", "c"]));
+    }
+
+    #[test]
+    fn test_qwy_apply_patch_rejects_paths_not_rooted_at_target() {
+        let mut document = qwy_tree();
+        let cases = [
+            // `b` is not a root-level block.
+            QwyPatchOp::Remove { path: ids(&["b"]) },
+            // `d` is not a child of `a`.
+            QwyPatchOp::Remove {
+                path: ids(&["a", "d"]),
+            },
+            QwyPatchOp::Remove { path: Vec::new() },
+            // The path addresses `b`, the value is `c`.
+            QwyPatchOp::Replace {
+                path: ids(&["a", "b"]),
+                value: qwy_block("c", "swapped", false, &[]),
+            },
+            // The value's parent disagrees with the path.
+            QwyPatchOp::Insert {
+                path: ids(&["a"]),
+                value: qwy_block("e", "orphan", false, &[]),
+            },
+        ];
+        for op in cases {
+            let path = match &op {
+                QwyPatchOp::Insert { path, .. }
+                | QwyPatchOp::Remove { path }
+                | QwyPatchOp::Replace { path, .. }
+                | QwyPatchOp::Reorder { path, .. } => path.clone(),
+            };
+            let err = document.apply_patch(&qwy_entry(vec![op])).unwrap_err();
+            assert_eq!(err, QwyPatchError::RootMismatch { path });
+        }
+        assert!(document.patch_log.is_empty());
+        assert_eq!(document.blocks.len(), 4);
+    }
+
+    #[test]
+    fn test_provenance_private_defaults_to_false() {
+        let provenance: ProvenanceEnvelope = serde_json::from_value(serde_json::json!({
+            "was_generated_by": null,
+            "was_attributed_to": null,
+            "was_revision_of": null,
+            "had_primary_source": null,
+            "conductor_run_id": null,
+            "loop_id": null
+        }))
+        .unwrap();
+        assert!(!provenance.private);
+    }
+
+    fn external_record(private: bool) -> ExternalContentRecord {
+        ExternalContentRecord {
+            content_id: "local-1".to_string(),
+            url: "https://example.com/post".to_string(),
+            content_hash: "hash-1".to_string(),
+            fetched_at: Utc::now(),
+            fetched_by: "loop-secret".to_string(),
+            run_id: Some("run-secret".to_string()),
+            title: Some("Post".to_string()),
+            content_text: "public body".to_string(),
+            chunk_strategy: "full".to_string(),
+            snapshot_ref: Some("/home/user/private/snapshot.html".to_string()),
+            domain: Some("example.com".to_string()),
+            csl_metadata: None,
+            private,
+        }
+    }
+
+    #[test]
+    fn test_external_content_to_global_strips_private_fields() {
+        let global = external_record(false)
+            .to_global(Utc::now(), 2)
+            .expect("public record publishes");
+        assert_eq!(global.content_id, "hash-1");
+        assert_eq!(global.record_kind, "external_content");
+
+        let published = serde_json::to_string(&global).unwrap();
+        let embedded = global.embedding_text();
+        for secret in ["loop-secret", "run-secret", "/home/user/private"] {
+            assert!(!published.contains(secret), "{secret} leaked into record");
+            assert!(!embedded.contains(secret), "{secret} leaked into embedding");
+        }
+        assert_eq!(embedded, "Post\n\npublic body");
+    }
+
+    #[test]
+    fn test_private_external_content_is_never_published() {
+        assert!(external_record(true).to_global(Utc::now(), 0).is_none());
+    }
+
+    #[test]
+    fn test_every_event_topic_constant_is_registered() {
+        let source = include_str!("lib.rs");
+        let mut constants = Vec::new();
+        for decl in source.split("pub const EVENT_TOPIC_").skip(1) {
+            let Some((name, rest)) = decl.split_once(": &str =") else {
+                continue;
+            };
+            if !name.chars().all(|c| c.is_ascii_uppercase() || c == '_') {
+                continue;
+            }
+            let Some((_, rest)) = rest.split_once('"') else {
+                continue;
+            };
+            let Some((value, _)) = rest.split_once('"') else {
+                continue;
+            };
+            constants.push(value.to_string());
+        }
+        assert_eq!(constants.len(), EventTopic::ALL.len());
+        for value in constants {
+            let topic: EventTopic = value.parse().unwrap();
+            assert!(!topic.is_custom(), "{value} is missing from EventTopic");
+        }
+    }
+
+    #[test]
+    fn test_event_topic_round_trips_through_string() {
+        for topic in EventTopic::ALL {
+            let parsed: EventTopic = topic.as_str().parse().unwrap();
+            assert_eq!(&parsed, topic);
+            let json = serde_json::to_value(topic).unwrap();
+            assert_eq!(json, serde_json::json!(topic.as_str()));
+            assert_eq!(&serde_json::from_value::<EventTopic>(json).unwrap(), topic);
+        }
+
+        let custom = EventTopic::from("worker.task.complete");
+        assert_eq!(
+            custom,
+            EventTopic::Custom("worker.task.complete".to_string())
+        );
+        assert_eq!(custom.as_str(), "worker.task.complete");
+        assert_eq!(
+            EventTopic::WorkerTaskCompleted.as_str(),
+            EVENT_TOPIC_WORKER_TASK_COMPLETED
+        );
+    }
+
+    #[test]
+    fn test_missing_scope_reads_run_id_and_indexed_scope() {
+        let topic = EventTopic::WriterRunPatch;
+        let flat = serde_json::json!({
+            "run_id": "run-1",
+            "session_id": "session-1",
+            "thread_id": "thread-1",
+        });
+        assert_eq!(
+            topic.missing_scope(&flat),
+            vec![ScopeField::SessionId, ScopeField::ThreadId]
+        );
+
+        let scoped = with_scope(
+            serde_json::json!({ "run_id": "run-1" }),
+            Some("session-1".to_string()),
+            Some("thread-1".to_string()),
+        );
+        assert!(topic.missing_scope(&scoped).is_empty());
+
+        let empty_run = serde_json::json!({ "run_id": "" });
+        assert_eq!(
+            EventTopic::ConductorTaskProgress.missing_scope(&empty_run),
+            vec![ScopeField::RunId]
+        );
+        assert!(EventTopic::from("custom.topic")
+            .missing_scope(&serde_json::json!({}))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_build_version_compatibility_follows_schema_version() {
+        let current = BuildVersion::current();
+        assert_eq!(current.schema_version, SCHEMA_VERSION);
+        assert!(!current.git_hash.is_empty());
+
+        let other_build = BuildVersion {
+            git_hash: "0123456789ab".to_string(),
+            ..current.clone()
+        };
+        assert!(current.is_compatible_with(&other_build));
+
+        let newer_schema = BuildVersion {
+            schema_version: SCHEMA_VERSION + 1,
+            ..current.clone()
+        };
+        assert!(!current.is_compatible_with(&newer_schema));
+        assert!(!newer_schema.is_compatible_with(&current));
+
+        let wire: BuildVersion = serde_json::from_value(serde_json::json!({
+            "git_hash": "0123456789ab",
+            "schema_version": SCHEMA_VERSION,
+        }))
+        .unwrap();
+        assert_eq!(wire, other_build);
+    }
+
+    fn fixture_contract() -> ObjectiveContract {
+        ObjectiveContract {
+            objective_id: "obj-1".to_string(),
+            parent_objective_id: None,
+            primary_objective: "Summarize the Q3 incident reports".to_string(),
+            success_criteria: vec![
+                "Every incident is listed with its root cause".to_string(),
+                "Summary fits on one page".to_string(),
+            ],
+            constraints: ObjectiveConstraints {
+                max_tool_calls: 40,
+                timeout_ms: 300_000,
+                max_subframe_depth: 2,
+                allowed_capabilities: vec!["writer".to_string()],
+            },
+            attempts_budget: 2,
+            evidence_requirements: EvidenceRequirements {
+                requires_citations: true,
+                min_confidence: 0.6,
+                required_source_types: vec!["documentation".to_string()],
+            },
+        }
+    }
+
+    #[test]
+    fn test_objective_contract_fixture_is_valid() {
+        assert_eq!(fixture_contract().validate(), Ok(()));
+    }
+
+    type ContractMutation = fn(&mut ObjectiveContract);
+
+    #[test]
+    fn test_objective_contract_validation_rejects_unenforceable_fixtures() {
+        let cases: Vec<(&str, ContractMutation)> = vec![
+            ("primary_objective", |c| c.primary_objective = "  ".into()),
+            ("at least one success criterion", |c| {
+                c.success_criteria.clear()
+            }),
+            ("cannot be blank", |c| c.success_criteria.push(" ".into())),
+            ("at most 10 success criteria", |c| {
+                c.success_criteria = vec!["done".to_string(); 11]
+            }),
+            ("attempts_budget", |c| c.attempts_budget = 0),
+            ("max_tool_calls", |c| c.constraints.max_tool_calls = 0),
+            ("max_tool_calls", |c| {
+                c.constraints.max_tool_calls = OBJECTIVE_CONTRACT_MAX_TOOL_CALLS + 1
+            }),
+            ("timeout_ms", |c| c.constraints.timeout_ms = 1_000),
+            ("timeout_ms", |c| {
+                c.constraints.timeout_ms = OBJECTIVE_CONTRACT_MAX_TIMEOUT_MS + 1
+            }),
+            ("allowed_capabilities", |c| {
+                c.constraints.allowed_capabilities.push(String::new())
+            }),
+            ("min_confidence", |c| {
+                c.evidence_requirements.min_confidence = 1.5
+            }),
+        ];
+        for (expected, mutate) in cases {
+            let mut contract = fixture_contract();
+            mutate(&mut contract);
+            let err = contract.validate().expect_err(expected);
+            assert!(err.contains(expected), "{err:?} should mention {expected}");
+        }
+    }
+
+    #[test]
+    fn test_child_contract_splits_and_caps_parent_budget() {
+        let parent = fixture_contract();
+        let child =
+            parent.child_for_agenda_item("item-2", "researcher", "Collect incident timelines", 3);
+
+        assert_eq!(child.objective_id, "obj-1:item-2");
+        assert_eq!(child.parent_objective_id.as_deref(), Some("obj-1"));
+        assert_eq!(child.primary_objective, "Collect incident timelines");
+        assert_eq!(child.success_criteria, parent.success_criteria);
+        assert_eq!(child.evidence_requirements, parent.evidence_requirements);
+        assert_eq!(child.constraints.max_tool_calls, 13);
+        assert_eq!(child.constraints.timeout_ms, parent.constraints.timeout_ms);
+        assert_eq!(child.constraints.max_subframe_depth, 1);
+        assert_eq!(child.constraints.allowed_capabilities, vec!["researcher"]);
+        assert_eq!(child.attempts_budget, parent.attempts_budget);
+        assert_eq!(child.validate(), Ok(()));
+
+        let only = parent.child_for_agenda_item("item-1", "writer", "Write it", 0);
+        assert_eq!(only.constraints.max_tool_calls, 40);
+    }
+
+    #[test]
+    fn test_child_contract_budget_never_drops_below_one_call_or_depth_zero() {
+        let mut parent = fixture_contract();
+        parent.constraints.max_tool_calls = 2;
+        parent.constraints.max_subframe_depth = 0;
+
+        let child = parent.child_for_agenda_item("item-9", "writer", "Write it", 5);
+        assert_eq!(child.constraints.max_tool_calls, 1);
+        assert_eq!(child.constraints.max_subframe_depth, 0);
+        assert_eq!(child.validate(), Ok(()));
+
+        let grandchild = child.child_for_agenda_item("step", "writer", "Write it", 1);
+        assert_eq!(grandchild.objective_id, "obj-1:item-9:step");
+        assert_eq!(
+            grandchild.parent_objective_id.as_deref(),
+            Some("obj-1:item-9")
+        );
+        assert!(grandchild.constraints.max_tool_calls <= child.constraints.max_tool_calls);
+    }
+
+    #[test]
+    fn test_objective_contract_round_trips_on_execute_request() {
+        let request: ConductorExecuteRequest = serde_json::from_value(serde_json::json!({
+            "objective": "make the report better",
+            "desktop_id": "desktop-1",
+            "output_mode": "auto",
+            "hints": null,
+            "contract": fixture_contract(),
+        }))
+        .unwrap();
+        assert_eq!(request.contract, Some(fixture_contract()));
+
+        let legacy: ConductorExecuteRequest = serde_json::from_value(serde_json::json!({
+            "objective": "make the report better",
+            "desktop_id": "desktop-1",
+            "output_mode": "auto",
+            "hints": null,
+        }))
+        .unwrap();
+        assert!(legacy.contract.is_none());
+    }
+
+    #[test]
+    fn test_objective_contract_brief_and_capability_whitelist() {
+        let contract = fixture_contract();
+        let brief = contract.brief();
+        assert!(brief.starts_with("Summarize the Q3 incident reports\n\nSuccess criteria:"));
+        assert!(brief.contains("\n- Summary fits on one page"));
+        assert!(brief.contains("Acceptable sources: documentation."));
+
+        assert!(contract.allows_capability("writer"));
+        assert!(!contract.allows_capability("immediate_response"));
+        let mut open = contract;
+        open.constraints.allowed_capabilities.clear();
+        assert!(open.allows_capability("immediate_response"));
+    }
+
     #[test]
     fn export_types() {
         // Export all types to TypeScript
@@ -2162,6 +5103,9 @@ mod tests {
         DesktopState::export(&config).unwrap();
         WindowState::export(&config).unwrap();
         AppDefinition::export(&config).unwrap();
+        DesktopTemplate::export(&config).unwrap();
+        DesktopTemplateWindow::export(&config).unwrap();
+        DesktopTemplateFile::export(&config).unwrap();
         ViewerKind::export(&config).unwrap();
         ViewerResource::export(&config).unwrap();
         ViewerCapabilities::export(&config).unwrap();
@@ -2170,7 +5114,45 @@ mod tests {
         WsMsg::export(&config).unwrap();
         DesktopTelemetryEvent::export(&config).unwrap();
         ConductorDocumentUpdatePayload::export(&config).unwrap();
+        PatchGranularity::export(&config).unwrap();
+        WsErrorCode::export(&config).unwrap();
         DesktopWsMessage::export(&config).unwrap();
+        EventSubscriptionRequest::export(&config).unwrap();
+        EventSubscriptionResponse::export(&config).unwrap();
+        EventPollResponse::export(&config).unwrap();
+        ServerTimeResponse::export(&config).unwrap();
+        BuildVersion::export(&config).unwrap();
+        SearchHitKind::export(&config).unwrap();
+        SearchTarget::export(&config).unwrap();
+        SearchExcerptSegment::export(&config).unwrap();
+        SearchHit::export(&config).unwrap();
+        SearchResponse::export(&config).unwrap();
+        ResearchCitation::export(&config).unwrap();
+        ResearchProviderCall::export(&config).unwrap();
+        ResearchTaskStatus::export(&config).unwrap();
+        ResearchTaskStartedPayload::export(&config).unwrap();
+        ResearchTaskCompletedPayload::export(&config).unwrap();
+        ResearchTaskFailedPayload::export(&config).unwrap();
+        ResearchTaskSummary::export(&config).unwrap();
+        ResearchTaskProgress::export(&config).unwrap();
+        ResearchTaskDetail::export(&config).unwrap();
+        ResearchRerunRequest::export(&config).unwrap();
+        ResearchRerunResponse::export(&config).unwrap();
+        ResearchBatchRequest::export(&config).unwrap();
+        ResearchBatchResponse::export(&config).unwrap();
+        ResearchBatchDetail::export(&config).unwrap();
+        ResearchSendToWriterRequest::export(&config).unwrap();
+        ResearchSendToWriterResponse::export(&config).unwrap();
+        DiskUsageCategory::export(&config).unwrap();
+        DiskCategoryUsage::export(&config).unwrap();
+        DiskUsageResponse::export(&config).unwrap();
+        WorkspaceQuotaWarningPayload::export(&config).unwrap();
+        LearnedPreference::export(&config).unwrap();
+        LearnedPreferencesResponse::export(&config).unwrap();
+        UpdatePreferenceLearningRequest::export(&config).unwrap();
+        ActivitySource::export(&config).unwrap();
+        SessionActivity::export(&config).unwrap();
+        ActivityResponse::export(&config).unwrap();
         ToolDef::export(&config).unwrap();
         ToolCall::export(&config).unwrap();
         WorkerTurnStatus::export(&config).unwrap();
@@ -2191,6 +5173,8 @@ mod tests {
         ConductorExecuteRequest::export(&config).unwrap();
         ConductorExecuteResponse::export(&config).unwrap();
         ConductorError::export(&config).unwrap();
+        ErrorCode::export(&config).unwrap();
+        ApiError::export(&config).unwrap();
         ConductorRunStatusResponse::export(&config).unwrap();
         ConductorTaskStartedPayload::export(&config).unwrap();
         ConductorTaskProgressPayload::export(&config).unwrap();
@@ -2209,6 +5193,7 @@ mod tests {
         EventLane::export(&config).unwrap();
         EventImportance::export(&config).unwrap();
         EventMetadata::export(&config).unwrap();
+        EventScope::export(&config).unwrap();
         CapabilityCallStatus::export(&config).unwrap();
         ConductorAgendaItem::export(&config).unwrap();
         AgendaItemStatus::export(&config).unwrap();
@@ -2218,6 +5203,7 @@ mod tests {
         ConductorDecision::export(&config).unwrap();
         DecisionType::export(&config).unwrap();
         ConductorRunState::export(&config).unwrap();
+        ConductorRunStateDelta::export(&config).unwrap();
         ConductorRunStatus::export(&config).unwrap();
         // WorkerMsg lateral protocol
         WorkerRequestKind::export(&config).unwrap();
@@ -2225,5 +5211,258 @@ mod tests {
         WorkerResponse::export(&config).unwrap();
         WorkerSignalKind::export(&config).unwrap();
         WorkerSignal::export(&config).unwrap();
+        ViewerConflictPayload::export(&config).unwrap();
+        ContextTraceItemKind::export(&config).unwrap();
+        ContextTraceItem::export(&config).unwrap();
+        ModelContextTracePayload::export(&config).unwrap();
+        ContextTraceStatus::export(&config).unwrap();
+        ContextTraceCall::export(&config).unwrap();
+        ContextTracesResponse::export(&config).unwrap();
+        UserInputSurface::export(&config).unwrap();
+    }
+
+    #[test]
+    fn event_scope_round_trips_through_object_payloads() {
+        let scope = EventScope::new(Some("session-1".to_string()), Some("thread-1".to_string()));
+        let payload = scope.attach(serde_json::json!({ "text": "hi" }));
+        assert_eq!(payload["text"], "hi");
+        assert_eq!(
+            payload["scope"],
+            serde_json::json!({ "session_id": "session-1", "thread_id": "thread-1" })
+        );
+        assert_eq!(EventScope::from_payload(&payload), scope);
+    }
+
+    #[test]
+    fn event_scope_wraps_non_object_payloads() {
+        let scope = EventScope::new(Some("session-1".to_string()), None);
+        let payload = scope.attach(serde_json::json!([1, 2]));
+        assert_eq!(payload["value"], serde_json::json!([1, 2]));
+        assert_eq!(
+            payload["scope"],
+            serde_json::json!({ "session_id": "session-1" })
+        );
+        assert_eq!(EventScope::from_payload(&payload), scope);
+    }
+
+    #[test]
+    fn empty_event_scope_leaves_payloads_untouched() {
+        let payload = serde_json::json!("plain");
+        assert_eq!(EventScope::default().attach(payload.clone()), payload);
+        assert!(EventScope::from_payload(&payload).is_empty());
+        assert!(EventScope::from_payload(&serde_json::json!({ "scope": 3 })).is_empty());
+    }
+
+    #[test]
+    fn run_state_deltas_apply_in_revision_order_only() {
+        let now = Utc::now();
+        let mut run = ConductorRunState {
+            run_id: "run-1".to_string(),
+            objective: "Test".to_string(),
+            status: ConductorRunStatus::Running,
+            created_at: now,
+            updated_at: now,
+            completed_at: None,
+            agenda: vec![],
+            active_calls: vec![],
+            artifacts: vec![],
+            decision_log: vec![],
+            document_path: "conductor/runs/run-1/draft.md".to_string(),
+            output_mode: ConductorOutputMode::Auto,
+            desktop_id: "desktop-1".to_string(),
+            revision: 4,
+            contract: None,
+        };
+        let item = ConductorAgendaItem {
+            item_id: "item-1".to_string(),
+            capability: "writer".to_string(),
+            objective: "Draft".to_string(),
+            priority: 0,
+            depends_on: vec![],
+            status: AgendaItemStatus::Ready,
+            created_at: now,
+            started_at: None,
+            completed_at: None,
+        };
+
+        let mut added = ConductorRunStateDelta::next(&run, now);
+        added.agenda = vec![item.clone()];
+        let mut gap = added.clone();
+        gap.revision = 6;
+        assert_eq!(run.apply_delta(&gap), RunDeltaOutcome::Gap);
+        assert_eq!(run.revision, 4);
+        assert_eq!(run.apply_delta(&added), RunDeltaOutcome::Applied);
+        assert_eq!(
+            run.apply_delta(&added),
+            RunDeltaOutcome::Ignored,
+            "a redelivered delta is not a gap"
+        );
+        assert_eq!(run.agenda.len(), 1);
+
+        let mut started = ConductorRunStateDelta::next(&run, now);
+        started.agenda = vec![ConductorAgendaItem {
+            status: AgendaItemStatus::Running,
+            started_at: Some(now),
+            ..item
+        }];
+        started.status = Some(ConductorRunStatus::WaitingForCalls);
+        assert_eq!(run.apply_delta(&started), RunDeltaOutcome::Applied);
+        assert_eq!(run.revision, 6);
+        assert_eq!(run.agenda.len(), 1);
+        assert_eq!(run.agenda[0].status, AgendaItemStatus::Running);
+        assert_eq!(run.status, ConductorRunStatus::WaitingForCalls);
+
+        let json = serde_json::to_value(&started).unwrap();
+        assert!(
+            json.get("artifacts").is_none(),
+            "unchanged fields are omitted"
+        );
+        let parsed: ConductorRunStateDelta = serde_json::from_value(json).unwrap();
+        assert!(parsed.artifacts.is_empty());
+    }
+
+    #[test]
+    fn escalation_urgency_follows_failure_kind() {
+        use WorkerEscalationUrgency::{High, Low, Medium};
+        let cases = [
+            (FailureKind::Auth, High),
+            (FailureKind::RateLimit, Medium),
+            (FailureKind::Timeout, Medium),
+            (FailureKind::Network, Medium),
+            (FailureKind::Provider, Medium),
+            (FailureKind::Unknown, Medium),
+            (FailureKind::Validation, Low),
+        ];
+        for (kind, urgency) in cases {
+            assert_eq!(escalation_urgency_for(kind), urgency, "{kind:?}");
+        }
+    }
+
+    #[test]
+    fn writer_blocks_split_like_the_editor_and_keep_ids_across_edits() {
+        let doc = "# Plan\n\nIntro paragraph.\n \n\nSame.\n\nSame.";
+        let blocks = writer_blocks(doc);
+        assert_eq!(
+            blocks
+                .iter()
+                .map(|block| &doc[block.range.clone()])
+                .collect::<Vec<_>>(),
+            vec!["# Plan", "Intro paragraph.", "Same.", "Same."]
+        );
+        assert_eq!(blocks[3].block_id, format!("{}-2", blocks[2].block_id));
+        assert_eq!(
+            blocks.iter().map(|block| block.index).collect::<Vec<_>>(),
+            vec![0, 1, 2, 3]
+        );
+
+        let edited = writer_blocks("# Plan\n\nIntro, revised.\n\nSame.\n\nSame.");
+        assert_eq!(edited[0].block_id, blocks[0].block_id);
+        assert_ne!(edited[1].block_id, blocks[1].block_id);
+        assert_eq!(edited[3].block_id, blocks[3].block_id);
+        assert_eq!(writer_blocks("").len(), 1);
+    }
+
+    #[test]
+    fn writer_run_lock_lapses_at_expiry_or_release() {
+        let now = Utc::now();
+        let mut lock = WriterRunLockPayload {
+            lock_id: "lock-1".to_string(),
+            block_ids: vec!["blk-1".to_string()],
+            ttl_ms: 1_000,
+            expires_at: now + chrono::Duration::seconds(1),
+            released: false,
+            reason: None,
+        };
+        assert!(lock.is_active(now));
+        assert!(!lock.is_active(now + chrono::Duration::seconds(2)));
+        lock.released = true;
+        assert!(!lock.is_active(now));
+    }
+
+    fn writer_run_base(run_id: &str) -> WriterRunEventBase {
+        WriterRunEventBase {
+            desktop_id: "desktop-1".to_string(),
+            session_id: "session-1".to_string(),
+            thread_id: "thread-1".to_string(),
+            run_id: run_id.to_string(),
+            document_path: format!("conductor/runs/{run_id}/draft.md"),
+            revision: 1,
+            head_version_id: Some(1),
+            timestamp: Utc::now(),
+        }
+    }
+
+    fn writer_patch(run_id: &str, patch_id: &str) -> WriterRunEvent {
+        WriterRunEvent::Patch {
+            base: writer_run_base(run_id),
+            payload: WriterRunPatchPayload {
+                patch_id: patch_id.to_string(),
+                source: PatchSource::Agent,
+                source_actor: Some("writer".to_string()),
+                section_id: None,
+                ops: Vec::new(),
+                proposal: None,
+                base_version_id: Some(0),
+                target_version_id: Some(1),
+                overlay_id: None,
+                undo_of: None,
+                confirmed_by: None,
+            },
+        }
+    }
+
+    fn writer_changeset(run_id: &str, patch_id: &str) -> WriterRunEvent {
+        WriterRunEvent::Changeset {
+            base: writer_run_base(run_id),
+            payload: WriterRunChangesetPayload {
+                patch_id: patch_id.to_string(),
+                loop_id: None,
+                target_version_id: Some(1),
+                source: Some("writer".to_string()),
+                summary: "Added an introduction.".to_string(),
+                impact: ChangesetImpact::Low,
+                op_taxonomy: vec!["insert".to_string()],
+                confirmed_by: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_changeset_after_its_patch_is_accepted() {
+        let mut order = WriterRunPatchOrder::new(8);
+        assert_eq!(order.observe(&writer_patch("run-1", "patch-a")), Ok(()));
+        assert_eq!(order.observe(&writer_changeset("run-1", "patch-a")), Ok(()));
+        // A patch may be summarized more than once.
+        assert_eq!(order.observe(&writer_changeset("run-1", "patch-a")), Ok(()));
+    }
+
+    #[test]
+    fn test_orphan_changesets_are_flagged() {
+        let mut order = WriterRunPatchOrder::new(1);
+        let orphan = |run_id: &str, patch_id: &str| {
+            Err(OrphanChangeset {
+                run_id: run_id.to_string(),
+                patch_id: patch_id.to_string(),
+            })
+        };
+
+        // Before its patch.
+        assert_eq!(
+            order.observe(&writer_changeset("run-1", "patch-a")),
+            orphan("run-1", "patch-a")
+        );
+        order.observe(&writer_patch("run-1", "patch-a")).unwrap();
+        // The patch belongs to another run.
+        assert_eq!(
+            order.observe(&writer_changeset("run-2", "patch-a")),
+            orphan("run-2", "patch-a")
+        );
+        // The run was forgotten to stay within `max_runs`.
+        order.observe(&writer_patch("run-2", "patch-b")).unwrap();
+        assert_eq!(
+            order.observe(&writer_changeset("run-1", "patch-a")),
+            orphan("run-1", "patch-a")
+        );
+        assert_eq!(order.observe(&writer_changeset("run-2", "patch-b")), Ok(()));
     }
 }